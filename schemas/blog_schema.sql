@@ -29,6 +29,15 @@ DEFINE FIELD article_count ON user_profile TYPE number DEFAULT 0;
 DEFINE FIELD total_claps_received ON user_profile TYPE number DEFAULT 0;
 DEFINE FIELD is_verified ON user_profile TYPE bool DEFAULT false;
 DEFINE FIELD is_suspended ON user_profile TYPE bool DEFAULT false;
+DEFINE FIELD allow_audience_insights ON user_profile TYPE bool DEFAULT false; -- 是否同意用于出版物受众洞察
+DEFINE FIELD preferred_language ON user_profile TYPE option<string>;
+DEFINE FIELD reputation_score ON user_profile TYPE number DEFAULT 0;
+DEFINE FIELD reputation_updated_at ON user_profile TYPE option<datetime>;
+DEFINE FIELD last_active_at ON user_profile TYPE option<datetime>;
+DEFINE FIELD deactivated_at ON user_profile TYPE option<datetime>; -- 因长期未活跃被停用
+DEFINE FIELD suspended_at ON user_profile TYPE option<datetime>; -- 被管理员停用
+DEFINE FIELD suspension_reason ON user_profile TYPE option<string>;
+DEFINE FIELD deletion_requested_at ON user_profile TYPE option<datetime>; -- 申请删除账户，宽限期内只读
 DEFINE FIELD created_at ON user_profile TYPE datetime DEFAULT time::now();
 DEFINE FIELD updated_at ON user_profile TYPE datetime DEFAULT time::now();
 
@@ -54,16 +63,27 @@ DEFINE FIELD content_html ON article TYPE string DEFAULT ""; -- 渲染后的HTML
 DEFINE FIELD excerpt ON article TYPE option<string> ASSERT $value = NONE OR string::len($value) <= 300;
 DEFINE FIELD cover_image_url ON article TYPE option<string>;
 DEFINE FIELD author_id ON article TYPE string ASSERT $value != NONE;
+DEFINE FIELD pseudonym_id ON article TYPE option<string>; -- 以笔名发布时公开显示的作者，author_id 仍是真实账号
 DEFINE FIELD publication_id ON article TYPE option<record(publication)>;
 DEFINE FIELD series_id ON article TYPE option<record(series)>;
 DEFINE FIELD series_order ON article TYPE option<number>;
-DEFINE FIELD status ON article TYPE string DEFAULT "draft" ASSERT $value INSIDE ["draft", "published", "unlisted", "archived"];
+DEFINE FIELD status ON article TYPE string DEFAULT "draft" ASSERT $value INSIDE ["draft", "pending_review", "scheduled", "published", "unlisted", "archived"];
 DEFINE FIELD is_paid_content ON article TYPE bool DEFAULT false;
 DEFINE FIELD is_featured ON article TYPE bool DEFAULT false;
+DEFINE FIELD pinned_at ON article TYPE option<datetime>; -- 置顶在作者主页
+DEFINE FIELD publication_pinned_at ON article TYPE option<datetime>; -- 置顶在出版物首页
 DEFINE FIELD reading_time ON article TYPE number DEFAULT 0; -- 预计阅读时间（分钟）
 DEFINE FIELD word_count ON article TYPE number DEFAULT 0;
 DEFINE FIELD view_count ON article TYPE number DEFAULT 0;
 DEFINE FIELD clap_count ON article TYPE number DEFAULT 0;
+DEFINE FIELD weighted_clap_score ON article TYPE number DEFAULT 0;
+-- 各反应总数（点赞与 clap_count 保持一致）
+DEFINE FIELD reaction_counts ON article TYPE object DEFAULT {};
+DEFINE FIELD reaction_counts.clap ON article TYPE number DEFAULT 0;
+DEFINE FIELD reaction_counts.insightful ON article TYPE number DEFAULT 0;
+DEFINE FIELD reaction_counts.funny ON article TYPE number DEFAULT 0;
+DEFINE FIELD reaction_counts.love ON article TYPE number DEFAULT 0;
+DEFINE FIELD reaction_counts.celebrate ON article TYPE number DEFAULT 0;
 DEFINE FIELD comment_count ON article TYPE number DEFAULT 0;
 DEFINE FIELD bookmark_count ON article TYPE number DEFAULT 0;
 DEFINE FIELD share_count ON article TYPE number DEFAULT 0;
@@ -74,6 +94,8 @@ DEFINE FIELD metadata ON article TYPE object DEFAULT {};
 DEFINE FIELD created_at ON article TYPE datetime DEFAULT time::now();
 DEFINE FIELD updated_at ON article TYPE datetime DEFAULT time::now();
 DEFINE FIELD published_at ON article TYPE option<datetime>;
+DEFINE FIELD taken_down_at ON article TYPE option<datetime>; -- 被管理员下架
+DEFINE FIELD scheduled_at ON article TYPE option<datetime>;
 DEFINE FIELD last_edited_at ON article TYPE option<datetime>;
 DEFINE FIELD is_deleted ON article TYPE bool DEFAULT false;
 DEFINE FIELD deleted_at ON article TYPE option<datetime>;
@@ -81,12 +103,49 @@ DEFINE FIELD deleted_at ON article TYPE option<datetime>;
 -- 文章索引
 DEFINE INDEX article_slug_idx ON article COLUMNS slug UNIQUE;
 DEFINE INDEX article_author_idx ON article COLUMNS author_id;
+DEFINE INDEX article_pseudonym_idx ON article COLUMNS pseudonym_id;
 DEFINE INDEX article_publication_idx ON article COLUMNS publication_id;
 DEFINE INDEX article_series_idx ON article COLUMNS series_id;
 DEFINE INDEX article_status_idx ON article COLUMNS status;
 DEFINE INDEX article_published_idx ON article COLUMNS published_at;
+DEFINE INDEX article_scheduled_idx ON article COLUMNS status, scheduled_at;
 DEFINE INDEX article_featured_idx ON article COLUMNS is_featured;
 DEFINE INDEX article_deleted_idx ON article COLUMNS is_deleted;
+-- 作者主页和出版物首页先列置顶文章
+DEFINE INDEX article_author_pinned_idx ON article COLUMNS author_id, pinned_at, created_at;
+DEFINE INDEX article_publication_pinned_idx ON article COLUMNS publication_id, publication_pinned_at, created_at;
+
+-- 文章全文检索：标题、摘要、正文分别建索引，按 BM25 打分并支持高亮
+DEFINE ANALYZER article_search TOKENIZERS blank, class, camel, punct FILTERS lowercase, ascii, snowball(english);
+DEFINE INDEX article_title_search ON article FIELDS title SEARCH ANALYZER article_search BM25 HIGHLIGHTS;
+DEFINE INDEX article_excerpt_search ON article FIELDS excerpt SEARCH ANALYZER article_search BM25 HIGHLIGHTS;
+DEFINE INDEX article_content_search ON article FIELDS content SEARCH ANALYZER article_search BM25 HIGHLIGHTS;
+
+-- 文章向量（记录 ID 与文章 ID 相同），用于相关文章和语义搜索。
+-- 维度取决于所用模型，不建 MTREE 索引，查询时直接计算余弦相似度
+DEFINE TABLE article_embedding SCHEMAFULL;
+DEFINE FIELD article_id ON article_embedding TYPE string ASSERT $value != NONE;
+DEFINE FIELD model ON article_embedding TYPE string ASSERT $value != NONE;
+DEFINE FIELD dimensions ON article_embedding TYPE int;
+DEFINE FIELD vector ON article_embedding TYPE array<float>;
+DEFINE FIELD content_hash ON article_embedding TYPE string; -- 计算向量时的输入文本摘要
+DEFINE FIELD source_updated_at ON article_embedding TYPE datetime; -- 计算时文章的 updated_at
+DEFINE FIELD updated_at ON article_embedding TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX article_embedding_model_idx ON article_embedding COLUMNS model;
+
+-- 文章朗读音频表（记录ID与文章ID相同）
+DEFINE TABLE article_audio SCHEMAFULL;
+DEFINE FIELD article_id ON article_audio TYPE string ASSERT $value != NONE;
+DEFINE FIELD audio_url ON article_audio TYPE option<string>; -- 生成失败且之前没有音频时为空
+DEFINE FIELD duration_seconds ON article_audio TYPE option<float>;
+DEFINE FIELD size ON article_audio TYPE option<int>;
+DEFINE FIELD voice ON article_audio TYPE string; -- 模型/音色
+DEFINE FIELD sentence_hashes ON article_audio TYPE array<string> DEFAULT []; -- 生成时朗读文本每句话的摘要
+DEFINE FIELD source_updated_at ON article_audio TYPE datetime; -- 最近一次检查时文章的 updated_at
+DEFINE FIELD generated_at ON article_audio TYPE option<datetime>;
+DEFINE FIELD last_error ON article_audio TYPE option<string>;
+DEFINE FIELD failed_at ON article_audio TYPE option<datetime>;
 
 -- 文章版本历史表
 DEFINE TABLE article_version SCHEMAFULL;
@@ -105,6 +164,35 @@ DEFINE FIELD created_at ON article_version TYPE datetime DEFAULT time::now();
 DEFINE INDEX article_version_article_idx ON article_version COLUMNS article_id;
 DEFINE INDEX article_version_number_idx ON article_version COLUMNS article_id, version_number UNIQUE;
 
+-- 草稿预览链接表：作者分享给审阅者的限时链接，只保存令牌的哈希
+DEFINE TABLE preview_link SCHEMAFULL;
+DEFINE FIELD id ON preview_link TYPE record(preview_link);
+DEFINE FIELD article_id ON preview_link TYPE string ASSERT $value != NONE;
+DEFINE FIELD created_by ON preview_link TYPE string ASSERT $value != NONE;
+DEFINE FIELD label ON preview_link TYPE option<string>; -- 作者给链接起的备注
+DEFINE FIELD token_hash ON preview_link TYPE string ASSERT $value != NONE;
+DEFINE FIELD expires_at ON preview_link TYPE datetime;
+DEFINE FIELD revoked_at ON preview_link TYPE option<datetime>;
+DEFINE FIELD view_count ON preview_link TYPE number DEFAULT 0;
+DEFINE FIELD last_viewed_at ON preview_link TYPE option<datetime>;
+DEFINE FIELD created_at ON preview_link TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX preview_link_token_idx ON preview_link COLUMNS token_hash UNIQUE;
+DEFINE INDEX preview_link_article_idx ON preview_link COLUMNS article_id, expires_at;
+
+-- 文章旧 slug 表：改 slug 后旧地址 301 跳转到新地址，旧 slug 不能被其他文章使用
+DEFINE TABLE article_slug_history SCHEMAFULL;
+DEFINE FIELD id ON article_slug_history TYPE record(article_slug_history);
+DEFINE FIELD article_id ON article_slug_history TYPE string ASSERT $value != NONE;
+DEFINE FIELD publication_id ON article_slug_history TYPE option<record(publication)>; -- 改名时文章所属的出版物
+DEFINE FIELD slug ON article_slug_history TYPE string ASSERT $value != NONE; -- 旧 slug
+DEFINE FIELD new_slug ON article_slug_history TYPE option<string>;
+DEFINE FIELD created_at ON article_slug_history TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX article_slug_history_slug_idx ON article_slug_history COLUMNS publication_id, slug UNIQUE;
+DEFINE INDEX article_slug_history_lookup_idx ON article_slug_history COLUMNS slug;
+DEFINE INDEX article_slug_history_article_idx ON article_slug_history COLUMNS article_id;
+
 -- 文章系列表
 DEFINE TABLE series SCHEMAFULL;
 DEFINE FIELD id ON series TYPE record(series);
@@ -135,6 +223,8 @@ DEFINE FIELD description ON tag TYPE option<string>;
 DEFINE FIELD follower_count ON tag TYPE number DEFAULT 0;
 DEFINE FIELD article_count ON tag TYPE number DEFAULT 0;
 DEFINE FIELD is_featured ON tag TYPE bool DEFAULT false;
+DEFINE FIELD parent_id ON tag TYPE option<string>; -- 上级标签 ID（不带表名）
+DEFINE FIELD aliases ON tag TYPE array<string> DEFAULT []; -- 其他写法的 slug，合并标签时写入被合并标签的 slug
 DEFINE FIELD created_at ON tag TYPE datetime DEFAULT time::now();
 DEFINE FIELD updated_at ON tag TYPE datetime DEFAULT time::now();
 
@@ -142,6 +232,8 @@ DEFINE FIELD updated_at ON tag TYPE datetime DEFAULT time::now();
 DEFINE INDEX tag_slug_idx ON tag COLUMNS slug UNIQUE;
 DEFINE INDEX tag_name_idx ON tag COLUMNS name UNIQUE;
 DEFINE INDEX tag_featured_idx ON tag COLUMNS is_featured;
+DEFINE INDEX tag_parent_idx ON tag COLUMNS parent_id;
+DEFINE INDEX tag_aliases_idx ON tag COLUMNS aliases;
 
 -- 文章标签关联表
 DEFINE TABLE article_tag SCHEMAFULL;
@@ -171,7 +263,7 @@ DEFINE INDEX user_tag_follow_tag_idx ON user_tag_follow COLUMNS tag_id;
 -- 互动系统
 -- =====================================
 
--- 点赞表（Claps）
+-- 点赞表（Claps，旧版，数据已迁移到 reaction 表，仅保留用于迁移）
 DEFINE TABLE clap SCHEMAFULL;
 DEFINE FIELD id ON clap TYPE record(clap);
 DEFINE FIELD user_id ON clap TYPE string ASSERT $value != NONE;
@@ -185,6 +277,151 @@ DEFINE INDEX clap_unique_idx ON clap COLUMNS user_id, article_id UNIQUE;
 DEFINE INDEX clap_article_idx ON clap COLUMNS article_id;
 DEFINE INDEX clap_user_idx ON clap COLUMNS user_id;
 
+-- 文章反应表（记录 ID 为 [article_id, user_id, reaction_type]；旧版 clap 表的数据在启动时迁移过来）
+DEFINE TABLE reaction SCHEMAFULL;
+DEFINE FIELD user_id ON reaction TYPE string ASSERT $value != NONE;
+DEFINE FIELD article_id ON reaction TYPE record(article) ASSERT $value != NONE;
+DEFINE FIELD reaction_type ON reaction TYPE string ASSERT $value INSIDE ["clap", "insightful", "funny", "love", "celebrate"];
+DEFINE FIELD count ON reaction TYPE number DEFAULT 1 ASSERT $value >= 1 AND $value <= 50; -- 点赞最多50次，其他反应为1
+DEFINE FIELD created_at ON reaction TYPE datetime DEFAULT time::now();
+DEFINE FIELD updated_at ON reaction TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX reaction_article_idx ON reaction COLUMNS article_id, reaction_type;
+DEFINE INDEX reaction_user_idx ON reaction COLUMNS user_id, reaction_type;
+
+-- 一次性数据迁移的完成标记
+DEFINE TABLE schema_migration SCHEMAFULL;
+DEFINE FIELD completed_at ON schema_migration TYPE datetime DEFAULT time::now();
+
+-- 文章合著者（记录 ID 为 [article_id, user_id]；作者本人不在此表中）
+DEFINE TABLE article_collaborator SCHEMAFULL;
+DEFINE FIELD article_id ON article_collaborator TYPE string ASSERT $value != NONE;
+DEFINE FIELD user_id ON article_collaborator TYPE string ASSERT $value != NONE;
+DEFINE FIELD role ON article_collaborator TYPE string ASSERT $value INSIDE ["editor", "viewer"];
+DEFINE FIELD status ON article_collaborator TYPE string DEFAULT "pending" ASSERT $value INSIDE ["pending", "accepted"];
+DEFINE FIELD invited_by ON article_collaborator TYPE string;
+DEFINE FIELD created_at ON article_collaborator TYPE datetime DEFAULT time::now();
+DEFINE FIELD accepted_at ON article_collaborator TYPE option<datetime>;
+
+DEFINE INDEX article_collaborator_article_idx ON article_collaborator COLUMNS article_id, status;
+DEFINE INDEX article_collaborator_user_idx ON article_collaborator COLUMNS user_id, status;
+
+-- 文章附件表（PDF、幻灯片、数据集；文件保存在 attachments/ 目录，不公开访问）
+DEFINE TABLE article_attachment SCHEMAFULL;
+DEFINE FIELD article_id ON article_attachment TYPE string ASSERT $value != NONE;
+DEFINE FIELD author_id ON article_attachment TYPE string ASSERT $value != NONE;
+DEFINE FIELD uploaded_by ON article_attachment TYPE string ASSERT $value != NONE;
+DEFINE FIELD title ON article_attachment TYPE string ASSERT string::len($value) > 0;
+DEFINE FIELD description ON article_attachment TYPE option<string>;
+DEFINE FIELD original_filename ON article_attachment TYPE string;
+DEFINE FIELD content_type ON article_attachment TYPE string;
+DEFINE FIELD size ON article_attachment TYPE number;
+DEFINE FIELD storage_path ON article_attachment TYPE string;
+DEFINE FIELD gate ON article_attachment TYPE string DEFAULT 'public'
+    ASSERT $value INSIDE ['public', 'subscribers', 'email'];
+DEFINE FIELD download_count ON article_attachment TYPE number DEFAULT 0;
+DEFINE FIELD created_at ON article_attachment TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX article_attachment_article_idx ON article_attachment COLUMNS article_id;
+DEFINE INDEX article_attachment_author_idx ON article_attachment COLUMNS author_id;
+
+-- 附件下载记录（用于下载统计）
+DEFINE TABLE attachment_download SCHEMAFULL;
+DEFINE FIELD attachment_id ON attachment_download TYPE string ASSERT $value != NONE;
+DEFINE FIELD article_id ON attachment_download TYPE string;
+DEFINE FIELD author_id ON attachment_download TYPE string;
+DEFINE FIELD user_id ON attachment_download TYPE option<string>;
+DEFINE FIELD created_at ON attachment_download TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX attachment_download_author_idx ON attachment_download COLUMNS author_id, created_at;
+
+-- 邮箱门槛附件收集的邮箱（记录 ID 为 [attachment_id, email]）
+DEFINE TABLE attachment_lead SCHEMAFULL;
+DEFINE FIELD attachment_id ON attachment_lead TYPE string ASSERT $value != NONE;
+DEFINE FIELD article_id ON attachment_lead TYPE string;
+DEFINE FIELD author_id ON attachment_lead TYPE string;
+DEFINE FIELD email ON attachment_lead TYPE string;
+DEFINE FIELD user_id ON attachment_lead TYPE option<string>;
+DEFINE FIELD created_at ON attachment_lead TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX attachment_lead_article_idx ON attachment_lead COLUMNS article_id, created_at;
+DEFINE INDEX attachment_lead_author_idx ON attachment_lead COLUMNS author_id, created_at;
+
+-- 文章标题/封面 A/B 实验
+DEFINE TABLE article_experiment SCHEMAFULL;
+DEFINE FIELD article_id ON article_experiment TYPE string ASSERT $value != NONE;
+DEFINE FIELD author_id ON article_experiment TYPE string ASSERT $value != NONE;
+DEFINE FIELD variants ON article_experiment TYPE array<object> FLEXIBLE; -- { key, title, cover_image_url }
+DEFINE FIELD status ON article_experiment TYPE string DEFAULT "running" ASSERT $value INSIDE ["running", "completed"];
+DEFINE FIELD winner ON article_experiment TYPE option<string>;
+DEFINE FIELD created_at ON article_experiment TYPE datetime DEFAULT time::now();
+DEFINE FIELD completed_at ON article_experiment TYPE option<datetime>;
+
+DEFINE INDEX article_experiment_article_idx ON article_experiment COLUMNS article_id, status;
+
+-- 实验曝光（记录 ID 为 [experiment_id, viewer_hash]，每位读者一条）
+DEFINE TABLE experiment_exposure SCHEMAFULL;
+DEFINE FIELD experiment_id ON experiment_exposure TYPE string ASSERT $value != NONE;
+DEFINE FIELD article_id ON experiment_exposure TYPE string;
+DEFINE FIELD variant ON experiment_exposure TYPE string;
+DEFINE FIELD viewer_hash ON experiment_exposure TYPE string;
+DEFINE FIELD clicked ON experiment_exposure TYPE bool DEFAULT false;
+DEFINE FIELD created_at ON experiment_exposure TYPE datetime DEFAULT time::now();
+DEFINE FIELD clicked_at ON experiment_exposure TYPE option<datetime>;
+
+DEFINE INDEX experiment_exposure_experiment_idx ON experiment_exposure COLUMNS experiment_id, variant;
+
+-- 作者笔名（和账号的关联只对本人可见）
+DEFINE TABLE pseudonym SCHEMAFULL;
+DEFINE FIELD owner_id ON pseudonym TYPE string ASSERT $value != NONE;
+DEFINE FIELD username ON pseudonym TYPE string ASSERT $value != NONE;
+DEFINE FIELD display_name ON pseudonym TYPE string ASSERT $value != NONE;
+DEFINE FIELD bio ON pseudonym TYPE option<string>;
+DEFINE FIELD avatar_url ON pseudonym TYPE option<string>;
+DEFINE FIELD created_at ON pseudonym TYPE datetime DEFAULT time::now();
+DEFINE FIELD updated_at ON pseudonym TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX pseudonym_username_idx ON pseudonym COLUMNS username UNIQUE;
+DEFINE INDEX pseudonym_owner_idx ON pseudonym COLUMNS owner_id;
+
+-- 行动号召块（正文中用 {{cta key}} 引用）
+DEFINE TABLE cta SCHEMAFULL;
+DEFINE FIELD owner_id ON cta TYPE string ASSERT $value != NONE;
+DEFINE FIELD key ON cta TYPE string ASSERT $value != NONE;
+DEFINE FIELD kind ON cta TYPE string ASSERT $value INSIDE ['subscribe', 'follow', 'external_link', 'email_capture'];
+DEFINE FIELD title ON cta TYPE string;
+DEFINE FIELD description ON cta TYPE option<string>;
+DEFINE FIELD button_label ON cta TYPE string;
+DEFINE FIELD url ON cta TYPE option<string>;
+DEFINE FIELD click_count ON cta TYPE number DEFAULT 0;
+DEFINE FIELD conversion_count ON cta TYPE number DEFAULT 0;
+DEFINE FIELD created_at ON cta TYPE datetime DEFAULT time::now();
+DEFINE FIELD updated_at ON cta TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX cta_owner_key_idx ON cta COLUMNS owner_id, key UNIQUE;
+
+-- CTA 点击和转化事件
+DEFINE TABLE cta_event SCHEMAFULL;
+DEFINE FIELD cta_id ON cta_event TYPE string ASSERT $value != NONE;
+DEFINE FIELD owner_id ON cta_event TYPE string;
+DEFINE FIELD article_id ON cta_event TYPE option<string>;
+DEFINE FIELD user_id ON cta_event TYPE option<string>;
+DEFINE FIELD event ON cta_event TYPE string ASSERT $value INSIDE ['click', 'conversion'];
+DEFINE FIELD created_at ON cta_event TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX cta_event_owner_idx ON cta_event COLUMNS owner_id, created_at;
+DEFINE INDEX cta_event_cta_idx ON cta_event COLUMNS cta_id, created_at;
+
+-- 邮箱收集块收到的邮箱（记录 ID 为 [cta_id, email]）
+DEFINE TABLE cta_lead SCHEMAFULL;
+DEFINE FIELD cta_id ON cta_lead TYPE string ASSERT $value != NONE;
+DEFINE FIELD owner_id ON cta_lead TYPE string;
+DEFINE FIELD email ON cta_lead TYPE string;
+DEFINE FIELD article_id ON cta_lead TYPE option<string>;
+DEFINE FIELD created_at ON cta_lead TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX cta_lead_cta_idx ON cta_lead COLUMNS cta_id, created_at;
+
 -- 评论表
 DEFINE TABLE comment SCHEMAFULL;
 DEFINE FIELD id ON comment TYPE record(comment);
@@ -199,12 +436,23 @@ DEFINE FIELD is_deleted ON comment TYPE bool DEFAULT false;
 DEFINE FIELD created_at ON comment TYPE datetime DEFAULT time::now();
 DEFINE FIELD updated_at ON comment TYPE datetime DEFAULT time::now();
 DEFINE FIELD deleted_at ON comment TYPE option<datetime>;
+-- 从外部评论系统导入的评论（作者为访客）
+DEFINE FIELD guest_name ON comment TYPE option<string>;
+DEFINE FIELD imported_from ON comment TYPE option<string>;
+DEFINE FIELD external_id ON comment TYPE option<string>;
+-- 评论审核：approved（公开展示）、pending（等待审核）、spam、rejected
+DEFINE FIELD moderation_status ON comment TYPE string DEFAULT "approved" ASSERT $value INSIDE ["approved", "pending", "spam", "rejected", "reported"];
+DEFINE FIELD spam_score ON comment TYPE option<number>;
+DEFINE FIELD moderated_by ON comment TYPE option<string>;
+DEFINE FIELD moderated_at ON comment TYPE option<datetime>;
 
 -- 评论索引
 DEFINE INDEX comment_article_idx ON comment COLUMNS article_id;
 DEFINE INDEX comment_parent_idx ON comment COLUMNS parent_id;
 DEFINE INDEX comment_author_idx ON comment COLUMNS author_id;
 DEFINE INDEX comment_deleted_idx ON comment COLUMNS is_deleted;
+DEFINE INDEX comment_import_idx ON comment COLUMNS imported_from, external_id;
+DEFINE INDEX comment_moderation_idx ON comment COLUMNS moderation_status;
 
 -- 评论点赞表
 DEFINE TABLE comment_clap SCHEMAFULL;
@@ -217,6 +465,103 @@ DEFINE FIELD created_at ON comment_clap TYPE datetime DEFAULT time::now();
 DEFINE INDEX comment_clap_unique_idx ON comment_clap COLUMNS user_id, comment_id UNIQUE;
 DEFINE INDEX comment_clap_comment_idx ON comment_clap COLUMNS comment_id;
 
+-- 评论阅读进度表（记录 ID 为 [user_id, article_id]）
+DEFINE TABLE comment_read_marker SCHEMAFULL;
+DEFINE FIELD user_id ON comment_read_marker TYPE string ASSERT $value != NONE;
+DEFINE FIELD article_id ON comment_read_marker TYPE string ASSERT $value != NONE;
+DEFINE FIELD last_read_at ON comment_read_marker TYPE datetime;
+DEFINE FIELD updated_at ON comment_read_marker TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX comment_read_marker_user_idx ON comment_read_marker COLUMNS user_id;
+
+-- 外部平台账号表（记录 ID 为 user_id + 平台，每个平台一个账号）
+DEFINE TABLE syndication_account SCHEMAFULL;
+DEFINE FIELD user_id ON syndication_account TYPE string ASSERT $value != NONE;
+DEFINE FIELD platform ON syndication_account TYPE string ASSERT $value INSIDE ["dev_to", "hashnode", "twitter"];
+DEFINE FIELD account_name ON syndication_account TYPE option<string>;
+DEFINE FIELD access_token ON syndication_account TYPE string ASSERT $value != NONE;
+DEFINE FIELD hashnode_publication_id ON syndication_account TYPE option<string>;
+DEFINE FIELD auto_post ON syndication_account TYPE bool DEFAULT false;
+DEFINE FIELD created_at ON syndication_account TYPE datetime DEFAULT time::now();
+DEFINE FIELD updated_at ON syndication_account TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX syndication_account_user_idx ON syndication_account COLUMNS user_id;
+
+-- 文章同步状态表（记录 ID 为 article_id + account_id）
+DEFINE TABLE syndicated_post SCHEMAFULL;
+DEFINE FIELD article_id ON syndicated_post TYPE string ASSERT $value != NONE;
+DEFINE FIELD account_id ON syndicated_post TYPE string ASSERT $value != NONE;
+DEFINE FIELD user_id ON syndicated_post TYPE string ASSERT $value != NONE;
+DEFINE FIELD platform ON syndicated_post TYPE string;
+DEFINE FIELD status ON syndicated_post TYPE string ASSERT $value INSIDE ["pending", "published", "failed"];
+DEFINE FIELD external_id ON syndicated_post TYPE option<string>;
+DEFINE FIELD external_url ON syndicated_post TYPE option<string>;
+DEFINE FIELD error ON syndicated_post TYPE option<string>;
+DEFINE FIELD attempts ON syndicated_post TYPE number DEFAULT 0;
+DEFINE FIELD created_at ON syndicated_post TYPE datetime DEFAULT time::now();
+DEFINE FIELD updated_at ON syndicated_post TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX syndicated_post_article_idx ON syndicated_post COLUMNS article_id;
+
+-- 排期的社交推广帖子
+DEFINE TABLE scheduled_share SCHEMAFULL;
+DEFINE FIELD user_id ON scheduled_share TYPE string ASSERT $value != NONE;
+DEFINE FIELD account_id ON scheduled_share TYPE string ASSERT $value != NONE;
+DEFINE FIELD platform ON scheduled_share TYPE string ASSERT $value INSIDE ["dev_to", "hashnode", "twitter"];
+DEFINE FIELD article_id ON scheduled_share TYPE option<string>;
+DEFINE FIELD text ON scheduled_share TYPE string;
+DEFINE FIELD link ON scheduled_share TYPE option<string>;
+DEFINE FIELD image_url ON scheduled_share TYPE option<string>;
+DEFINE FIELD scheduled_at ON scheduled_share TYPE datetime;
+DEFINE FIELD status ON scheduled_share TYPE string DEFAULT "scheduled" ASSERT $value INSIDE ["scheduled", "posting", "posted", "failed", "cancelled"];
+DEFINE FIELD attempts ON scheduled_share TYPE int DEFAULT 0;
+DEFINE FIELD external_id ON scheduled_share TYPE option<string>;
+DEFINE FIELD external_url ON scheduled_share TYPE option<string>;
+DEFINE FIELD error ON scheduled_share TYPE option<string>;
+DEFINE FIELD posted_at ON scheduled_share TYPE option<datetime>;
+DEFINE FIELD created_at ON scheduled_share TYPE datetime DEFAULT time::now();
+DEFINE FIELD updated_at ON scheduled_share TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX scheduled_share_due_idx ON scheduled_share COLUMNS status, scheduled_at;
+DEFINE INDEX scheduled_share_user_idx ON scheduled_share COLUMNS user_id, scheduled_at;
+DEFINE INDEX scheduled_share_account_idx ON scheduled_share COLUMNS account_id, status, posted_at;
+
+-- 排期推广帖子的发布尝试记录
+DEFINE TABLE social_share_log SCHEMAFULL;
+DEFINE FIELD share_id ON social_share_log TYPE string ASSERT $value != NONE;
+DEFINE FIELD account_id ON social_share_log TYPE string;
+DEFINE FIELD success ON social_share_log TYPE bool;
+DEFINE FIELD external_id ON social_share_log TYPE option<string>;
+DEFINE FIELD error ON social_share_log TYPE option<string>;
+DEFINE FIELD attempted_at ON social_share_log TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX social_share_log_share_idx ON social_share_log COLUMNS share_id, attempted_at;
+
+-- 出版物评论审核设置表（记录 ID 为出版物 ID）
+DEFINE TABLE comment_moderation_settings SCHEMAFULL;
+DEFINE FIELD publication_id ON comment_moderation_settings TYPE string ASSERT $value != NONE;
+DEFINE FIELD hold_for_review ON comment_moderation_settings TYPE bool DEFAULT false;
+DEFINE FIELD spam_threshold ON comment_moderation_settings TYPE number DEFAULT 0.8;
+DEFINE FIELD blocked_keywords ON comment_moderation_settings TYPE array<string> DEFAULT [];
+DEFINE FIELD updated_at ON comment_moderation_settings TYPE option<datetime>;
+
+-- Webmention / Pingback 提及表（记录 ID 为 article_id + source 的哈希）
+DEFINE TABLE webmention SCHEMAFULL;
+DEFINE FIELD article_id ON webmention TYPE string ASSERT $value != NONE;
+DEFINE FIELD source ON webmention TYPE string ASSERT $value != NONE;
+DEFINE FIELD target ON webmention TYPE string ASSERT $value != NONE;
+DEFINE FIELD protocol ON webmention TYPE string ASSERT $value INSIDE ["webmention", "pingback"];
+DEFINE FIELD status ON webmention TYPE string DEFAULT "pending" ASSERT $value INSIDE ["pending", "verified", "rejected"];
+DEFINE FIELD title ON webmention TYPE option<string>;
+DEFINE FIELD author_name ON webmention TYPE option<string>;
+DEFINE FIELD excerpt ON webmention TYPE option<string>; -- 来源页面中链接附近的文字
+DEFINE FIELD error ON webmention TYPE option<string>;
+DEFINE FIELD verified_at ON webmention TYPE option<datetime>;
+DEFINE FIELD created_at ON webmention TYPE datetime DEFAULT time::now();
+DEFINE FIELD updated_at ON webmention TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX webmention_article_idx ON webmention COLUMNS article_id, status;
+
 -- 书签表
 DEFINE TABLE bookmark SCHEMAFULL;
 DEFINE FIELD id ON bookmark TYPE record(bookmark);
@@ -230,6 +575,54 @@ DEFINE INDEX bookmark_unique_idx ON bookmark COLUMNS user_id, article_id UNIQUE;
 DEFINE INDEX bookmark_user_idx ON bookmark COLUMNS user_id;
 DEFINE INDEX bookmark_article_idx ON bookmark COLUMNS article_id;
 
+-- 阅读队列表（有序，支持离线同步）
+DEFINE TABLE reading_queue_item SCHEMAFULL;
+DEFINE FIELD id ON reading_queue_item TYPE record(reading_queue_item);
+DEFINE FIELD user_id ON reading_queue_item TYPE string ASSERT $value != NONE;
+DEFINE FIELD article_id ON reading_queue_item TYPE string ASSERT $value != NONE;
+DEFINE FIELD position ON reading_queue_item TYPE number DEFAULT 0;
+DEFINE FIELD progress ON reading_queue_item TYPE number DEFAULT 0; -- 0.0 - 1.0
+DEFINE FIELD is_removed ON reading_queue_item TYPE bool DEFAULT false; -- 同步用墓碑标记
+DEFINE FIELD added_at ON reading_queue_item TYPE datetime DEFAULT time::now();
+DEFINE FIELD updated_at ON reading_queue_item TYPE datetime DEFAULT time::now();
+
+-- 阅读队列索引
+DEFINE INDEX reading_queue_item_user_article_idx ON reading_queue_item COLUMNS user_id, article_id UNIQUE;
+DEFINE INDEX reading_queue_item_user_updated_idx ON reading_queue_item COLUMNS user_id, updated_at;
+
+-- 计量付费墙：读者每月通过免费额度阅读的付费文章（记录 ID 为 [reader, month, article_id]）
+DEFINE TABLE metered_read SCHEMAFULL;
+DEFINE FIELD reader ON metered_read TYPE string ASSERT $value != NONE; -- user:<id> 或 anon:<hash>
+DEFINE FIELD month ON metered_read TYPE string; -- YYYY-MM
+DEFINE FIELD scope ON metered_read TYPE string; -- 出版物 ID，不属于出版物的文章为 platform
+DEFINE FIELD article_id ON metered_read TYPE string;
+DEFINE FIELD created_at ON metered_read TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX metered_read_reader_idx ON metered_read COLUMNS reader, month, scope;
+
+-- 计量付费墙：读者每月在每个范围内已用的额度（记录 ID 为 [reader, month, scope]），并发计数时用来让事务冲突
+DEFINE TABLE meter_usage SCHEMAFULL;
+DEFINE FIELD used ON meter_usage TYPE int DEFAULT 0;
+DEFINE FIELD updated_at ON meter_usage TYPE datetime DEFAULT time::now();
+
+-- 出版物的计量付费墙设置（记录 ID 与出版物相同）
+DEFINE TABLE publication_meter SCHEMAFULL;
+DEFINE FIELD publication_id ON publication_meter TYPE string ASSERT $value != NONE;
+DEFINE FIELD free_articles_per_month ON publication_meter TYPE int ASSERT $value >= 0 AND $value <= 100;
+DEFINE FIELD updated_at ON publication_meter TYPE datetime DEFAULT time::now();
+
+-- 阅读位置表（记录 ID 为 [user_id, article_id]，跨设备同步）
+DEFINE TABLE reading_progress SCHEMAFULL;
+DEFINE FIELD user_id ON reading_progress TYPE string ASSERT $value != NONE;
+DEFINE FIELD article_id ON reading_progress TYPE string ASSERT $value != NONE;
+DEFINE FIELD progress ON reading_progress TYPE number DEFAULT 0; -- 滚动位置 0.0 - 1.0
+DEFINE FIELD last_paragraph ON reading_progress TYPE option<number>;
+DEFINE FIELD device ON reading_progress TYPE option<string>;
+DEFINE FIELD completed ON reading_progress TYPE bool DEFAULT false;
+DEFINE FIELD updated_at ON reading_progress TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX reading_progress_user_updated_idx ON reading_progress COLUMNS user_id, completed, updated_at;
+
 -- 高亮表
 DEFINE TABLE highlight SCHEMAFULL;
 DEFINE FIELD id ON highlight TYPE record(highlight);
@@ -289,6 +682,7 @@ DEFINE FIELD article_count ON publication TYPE number DEFAULT 0;
 DEFINE FIELD follower_count ON publication TYPE number DEFAULT 0;
 DEFINE FIELD is_verified ON publication TYPE bool DEFAULT false;
 DEFINE FIELD is_suspended ON publication TYPE bool DEFAULT false;
+DEFINE FIELD require_two_factor ON publication TYPE bool DEFAULT false;
 DEFINE FIELD created_at ON publication TYPE datetime DEFAULT time::now();
 DEFINE FIELD updated_at ON publication TYPE datetime DEFAULT time::now();
 
@@ -303,7 +697,7 @@ DEFINE TABLE publication_member SCHEMAFULL;
 DEFINE FIELD id ON publication_member TYPE record(publication_member);
 DEFINE FIELD publication_id ON publication_member TYPE record(publication) ASSERT $value != NONE;
 DEFINE FIELD user_id ON publication_member TYPE string ASSERT $value != NONE;
-DEFINE FIELD role ON publication_member TYPE string DEFAULT "writer" ASSERT $value INSIDE ["owner", "editor", "writer"];
+DEFINE FIELD role ON publication_member TYPE string DEFAULT "writer" ASSERT $value INSIDE ["owner", "admin", "editor", "writer"];
 DEFINE FIELD permissions ON publication_member TYPE array<string> DEFAULT ["article.write"];
 DEFINE FIELD invited_by ON publication_member TYPE string ASSERT $value != NONE;
 -- 兼容后端模型，增加 joined_at 与 is_active 字段
@@ -318,6 +712,131 @@ DEFINE INDEX publication_member_unique_idx ON publication_member COLUMNS publica
 DEFINE INDEX publication_member_publication_idx ON publication_member COLUMNS publication_id;
 DEFINE INDEX publication_member_user_idx ON publication_member COLUMNS user_id;
 
+-- 出版物成员邀请表（只保存邀请令牌的哈希）
+DEFINE TABLE publication_invite SCHEMAFULL;
+DEFINE FIELD publication_id ON publication_invite TYPE string ASSERT $value != NONE; -- 不带表名的出版物 ID
+DEFINE FIELD email ON publication_invite TYPE option<string>;
+DEFINE FIELD user_id ON publication_invite TYPE option<string>;
+DEFINE FIELD role ON publication_invite TYPE string ASSERT $value INSIDE ["owner", "admin", "editor", "writer"];
+DEFINE FIELD token_hash ON publication_invite TYPE string ASSERT $value != NONE;
+DEFINE FIELD invited_by ON publication_invite TYPE string ASSERT $value != NONE;
+DEFINE FIELD expires_at ON publication_invite TYPE datetime;
+DEFINE FIELD accepted_at ON publication_invite TYPE option<datetime>;
+DEFINE FIELD accepted_by ON publication_invite TYPE option<string>;
+DEFINE FIELD created_at ON publication_invite TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX publication_invite_token_idx ON publication_invite COLUMNS token_hash UNIQUE;
+DEFINE INDEX publication_invite_publication_idx ON publication_invite COLUMNS publication_id;
+
+-- 出版物 CORS 来源表（允许嵌入站点跨域读取出版物内容）
+DEFINE TABLE publication_cors_origin SCHEMAFULL;
+DEFINE FIELD publication_id ON publication_cors_origin TYPE string ASSERT $value != NONE;
+DEFINE FIELD origin ON publication_cors_origin TYPE string ASSERT $value != NONE; -- scheme://host[:port]，可为 https://*.example.com
+DEFINE FIELD created_by ON publication_cors_origin TYPE string ASSERT $value != NONE;
+DEFINE FIELD created_at ON publication_cors_origin TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX publication_cors_origin_unique_idx ON publication_cors_origin COLUMNS publication_id, origin UNIQUE;
+
+-- 出版物文章模板表
+DEFINE TABLE article_template SCHEMAFULL;
+DEFINE FIELD publication_id ON article_template TYPE string ASSERT $value != NONE;
+DEFINE FIELD name ON article_template TYPE string ASSERT string::len($value) > 0;
+DEFINE FIELD description ON article_template TYPE option<string>;
+DEFINE FIELD content ON article_template TYPE string DEFAULT "";
+DEFINE FIELD required_sections ON article_template TYPE array<string> DEFAULT []; -- 发布前必须出现的章节标题
+DEFINE FIELD default_tags ON article_template TYPE array<string> DEFAULT [];
+DEFINE FIELD seo_checklist ON article_template TYPE array<object> DEFAULT [] FLEXIBLE; -- { check: "title_length", min, max } 等
+DEFINE FIELD created_by ON article_template TYPE string ASSERT $value != NONE;
+DEFINE FIELD created_at ON article_template TYPE datetime DEFAULT time::now();
+DEFINE FIELD updated_at ON article_template TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX article_template_publication_idx ON article_template COLUMNS publication_id;
+
+-- 出版物写作规范表（记录 ID 为出版物 ID）
+DEFINE TABLE style_guide SCHEMAFULL;
+DEFINE FIELD publication_id ON style_guide TYPE string ASSERT $value != NONE;
+DEFINE FIELD banned_phrases ON style_guide TYPE array<string> DEFAULT [];
+DEFINE FIELD check_passive_voice ON style_guide TYPE bool DEFAULT true;
+DEFINE FIELD max_heading_depth ON style_guide TYPE number DEFAULT 3 ASSERT $value >= 1 AND $value <= 6;
+DEFINE FIELD require_alt_text ON style_guide TYPE bool DEFAULT true;
+DEFINE FIELD enforce_on_submit ON style_guide TYPE bool DEFAULT false; -- 有错误时不能发布到出版物或提交审核
+DEFINE FIELD updated_at ON style_guide TYPE datetime DEFAULT time::now();
+
+-- 出版物首页布局（记录 ID 与出版物相同）
+DEFINE TABLE publication_homepage SCHEMAFULL;
+DEFINE FIELD publication_id ON publication_homepage TYPE string ASSERT $value != NONE;
+DEFINE FIELD featured ON publication_homepage TYPE object FLEXIBLE; -- { style, article_ids, limit }
+DEFINE FIELD sections ON publication_homepage TYPE array<object> DEFAULT [] FLEXIBLE; -- { title, source: { type: tag | manual | latest, ... }, style, limit }
+DEFINE FIELD about ON publication_homepage TYPE option<object> FLEXIBLE; -- { title, body, body_html }
+DEFINE FIELD updated_by ON publication_homepage TYPE string;
+DEFINE FIELD updated_at ON publication_homepage TYPE datetime DEFAULT time::now();
+
+-- 出版物主题（记录 ID 与出版物相同）
+DEFINE TABLE publication_theme SCHEMAFULL;
+DEFINE FIELD publication_id ON publication_theme TYPE string ASSERT $value != NONE;
+DEFINE FIELD colors ON publication_theme TYPE object FLEXIBLE; -- { primary, accent, background, text }
+DEFINE FIELD fonts ON publication_theme TYPE object FLEXIBLE; -- { heading, body }
+DEFINE FIELD logos ON publication_theme TYPE object FLEXIBLE; -- { light, dark, icon }
+DEFINE FIELD custom_css ON publication_theme TYPE option<string>; -- 保存前已清理
+DEFINE FIELD updated_at ON publication_theme TYPE datetime DEFAULT time::now();
+
+-- 出版物静态页面（在出版物域名的 /{slug} 访问）
+DEFINE TABLE publication_page SCHEMAFULL;
+DEFINE FIELD publication_id ON publication_page TYPE string ASSERT $value != NONE;
+DEFINE FIELD title ON publication_page TYPE string;
+DEFINE FIELD slug ON publication_page TYPE string ASSERT $value != NONE;
+DEFINE FIELD content ON publication_page TYPE string;
+DEFINE FIELD content_html ON publication_page TYPE string;
+DEFINE FIELD status ON publication_page TYPE string
+    ASSERT $value INSIDE ['draft', 'published'];
+DEFINE FIELD show_in_nav ON publication_page TYPE bool DEFAULT true;
+DEFINE FIELD nav_order ON publication_page TYPE int DEFAULT 0;
+DEFINE FIELD created_by ON publication_page TYPE string;
+DEFINE FIELD created_at ON publication_page TYPE datetime DEFAULT time::now();
+DEFINE FIELD updated_at ON publication_page TYPE datetime DEFAULT time::now();
+DEFINE FIELD published_at ON publication_page TYPE option<datetime>;
+
+DEFINE INDEX publication_page_slug_idx ON publication_page COLUMNS publication_id, slug UNIQUE;
+DEFINE INDEX publication_page_publication_idx ON publication_page COLUMNS publication_id;
+
+-- 出版物赞助位（每个赞助位同一时间只展示一个赞助）
+DEFINE TABLE sponsor_slot SCHEMAFULL;
+DEFINE FIELD publication_id ON sponsor_slot TYPE string ASSERT $value != NONE;
+DEFINE FIELD name ON sponsor_slot TYPE string;
+DEFINE FIELD placement ON sponsor_slot TYPE string
+    ASSERT $value INSIDE ['home_top', 'article_list', 'article_top', 'article_bottom'];
+DEFINE FIELD description ON sponsor_slot TYPE option<string>;
+DEFINE FIELD created_at ON sponsor_slot TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX sponsor_slot_publication_idx ON sponsor_slot COLUMNS publication_id;
+
+-- 赞助投放（投放期 starts_at - ends_at，同一赞助位不重叠）
+DEFINE TABLE sponsorship SCHEMAFULL;
+DEFINE FIELD publication_id ON sponsorship TYPE string ASSERT $value != NONE;
+DEFINE FIELD slot_id ON sponsorship TYPE string ASSERT $value != NONE;
+DEFINE FIELD sponsor_name ON sponsorship TYPE string;
+DEFINE FIELD sponsor_url ON sponsorship TYPE string;
+DEFINE FIELD headline ON sponsorship TYPE string;
+DEFINE FIELD body ON sponsorship TYPE option<string>;
+DEFINE FIELD asset_url ON sponsorship TYPE option<string>;
+DEFINE FIELD disclosure_label ON sponsorship TYPE string DEFAULT 'Sponsored';
+DEFINE FIELD starts_at ON sponsorship TYPE datetime;
+DEFINE FIELD ends_at ON sponsorship TYPE datetime;
+DEFINE FIELD status ON sponsorship TYPE string DEFAULT 'active' ASSERT $value INSIDE ['active', 'paused'];
+DEFINE FIELD impression_count ON sponsorship TYPE number DEFAULT 0;
+DEFINE FIELD created_by ON sponsorship TYPE string;
+DEFINE FIELD created_at ON sponsorship TYPE datetime DEFAULT time::now();
+DEFINE FIELD updated_at ON sponsorship TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX sponsorship_publication_idx ON sponsorship COLUMNS publication_id, starts_at;
+DEFINE INDEX sponsorship_slot_idx ON sponsorship COLUMNS slot_id, starts_at, ends_at;
+
+-- 赞助每日展示次数（记录 ID 为 [sponsorship_id, day]）
+DEFINE TABLE sponsorship_impression SCHEMAFULL;
+DEFINE FIELD sponsorship_id ON sponsorship_impression TYPE string ASSERT $value != NONE;
+DEFINE FIELD day ON sponsorship_impression TYPE string; -- YYYY-MM-DD（UTC）
+DEFINE FIELD impressions ON sponsorship_impression TYPE number DEFAULT 0;
+
 -- 出版物关注表
 DEFINE TABLE publication_follow SCHEMAFULL;
 DEFINE FIELD id ON publication_follow TYPE record(publication_follow);
@@ -364,6 +883,8 @@ DEFINE FIELD current_period_end ON subscription TYPE datetime DEFAULT time::now(
 DEFINE FIELD canceled_at ON subscription TYPE option<datetime>;
 DEFINE FIELD stripe_subscription_id ON subscription TYPE option<string>; -- 支付平台ID
 DEFINE FIELD stripe_subscription_record_id ON subscription TYPE option<string>; -- 内部 Stripe 订阅记录ID
+DEFINE FIELD coupon_id ON subscription TYPE option<string>; -- 结账时使用的优惠券
+DEFINE FIELD gift_id ON subscription TYPE option<string>; -- 由礼物兑换的订阅，不经过 Stripe，到期不续费
 DEFINE FIELD created_at ON subscription TYPE datetime DEFAULT time::now();
 DEFINE FIELD updated_at ON subscription TYPE datetime DEFAULT time::now();
 
@@ -373,6 +894,41 @@ DEFINE INDEX subscription_creator_idx ON subscription COLUMNS creator_id;
 DEFINE INDEX subscription_status_idx ON subscription COLUMNS status;
 DEFINE INDEX subscription_stripe_idx ON subscription COLUMNS stripe_subscription_id;
 DEFINE INDEX subscription_stripe_record_idx ON subscription COLUMNS stripe_subscription_record_id;
+DEFINE INDEX subscription_coupon_idx ON subscription COLUMNS coupon_id;
+
+-- 订阅优惠券表（在 Stripe 创建，本地用于结账校验和统计）
+DEFINE TABLE subscription_coupon SCHEMAFULL;
+DEFINE FIELD id ON subscription_coupon TYPE record(subscription_coupon);
+DEFINE FIELD creator_id ON subscription_coupon TYPE string ASSERT $value != NONE;
+DEFINE FIELD code ON subscription_coupon TYPE string ASSERT $value != NONE; -- 大写优惠码
+DEFINE FIELD stripe_coupon_id ON subscription_coupon TYPE string ASSERT $value != NONE;
+DEFINE FIELD discount_type ON subscription_coupon TYPE string ASSERT $value INSIDE ["percentage", "fixed"];
+DEFINE FIELD discount_value ON subscription_coupon TYPE number ASSERT $value > 0; -- 百分比或金额（美分）
+DEFINE FIELD currency ON subscription_coupon TYPE option<string>;
+DEFINE FIELD duration ON subscription_coupon TYPE string ASSERT $value INSIDE ["once", "repeating", "forever"];
+DEFINE FIELD duration_in_months ON subscription_coupon TYPE option<number>;
+DEFINE FIELD max_redemptions ON subscription_coupon TYPE option<number>;
+DEFINE FIELD times_redeemed ON subscription_coupon TYPE number DEFAULT 0;
+DEFINE FIELD plan_ids ON subscription_coupon TYPE array<string> DEFAULT []; -- 为空表示适用于所有计划
+DEFINE FIELD redeem_by ON subscription_coupon TYPE option<datetime>;
+DEFINE FIELD is_active ON subscription_coupon TYPE bool DEFAULT true;
+DEFINE FIELD created_at ON subscription_coupon TYPE datetime DEFAULT time::now();
+DEFINE FIELD updated_at ON subscription_coupon TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX subscription_coupon_code_idx ON subscription_coupon COLUMNS creator_id, code UNIQUE;
+
+-- 优惠券兑换记录
+DEFINE TABLE coupon_redemption SCHEMAFULL;
+DEFINE FIELD coupon_id ON coupon_redemption TYPE string ASSERT $value != NONE;
+DEFINE FIELD creator_id ON coupon_redemption TYPE string ASSERT $value != NONE;
+DEFINE FIELD plan_id ON coupon_redemption TYPE string ASSERT $value != NONE;
+DEFINE FIELD subscription_id ON coupon_redemption TYPE string ASSERT $value != NONE;
+DEFINE FIELD subscriber_id ON coupon_redemption TYPE string ASSERT $value != NONE;
+DEFINE FIELD discount_amount ON coupon_redemption TYPE number DEFAULT 0; -- 首期减免金额（美分）
+DEFINE FIELD currency ON coupon_redemption TYPE string DEFAULT "USD";
+DEFINE FIELD created_at ON coupon_redemption TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX coupon_redemption_coupon_idx ON coupon_redemption COLUMNS creator_id, coupon_id;
 
 -- =====================================
 -- 第四阶段：会员和付费系统扩展
@@ -413,6 +969,57 @@ DEFINE INDEX one_time_purchase_article_idx ON one_time_purchase COLUMNS article_
 DEFINE INDEX one_time_purchase_creator_idx ON one_time_purchase COLUMNS creator_id;
 DEFINE INDEX one_time_purchase_status_idx ON one_time_purchase COLUMNS payment_status;
 
+-- 创作者打款索引（打款历史和定时任务查询）
+DEFINE INDEX payout_creator_idx ON payout COLUMNS creator_id, created_at;
+DEFINE INDEX payout_status_idx ON payout COLUMNS status;
+
+-- 单篇文章购买表（由支付 Webhook 更新状态）
+DEFINE TABLE article_purchase SCHEMAFULL;
+DEFINE FIELD article_id ON article_purchase TYPE string ASSERT $value != NONE;
+DEFINE FIELD buyer_id ON article_purchase TYPE string ASSERT $value != NONE;
+DEFINE FIELD creator_id ON article_purchase TYPE string ASSERT $value != NONE;
+DEFINE FIELD amount ON article_purchase TYPE number ASSERT $value > 0; -- 金额（美分）
+DEFINE FIELD tax_amount ON article_purchase TYPE number DEFAULT 0; -- 代收税费（美分），不含在 amount 中
+DEFINE FIELD currency ON article_purchase TYPE string DEFAULT "USD";
+DEFINE FIELD stripe_payment_intent_id ON article_purchase TYPE option<string>;
+DEFINE FIELD status ON article_purchase TYPE string DEFAULT "pending" ASSERT $value INSIDE ["pending", "completed", "failed", "refunded", "disputed"];
+DEFINE FIELD gift_id ON article_purchase TYPE option<string>; -- 由礼物兑换的购买
+DEFINE FIELD created_at ON article_purchase TYPE datetime DEFAULT time::now();
+DEFINE FIELD updated_at ON article_purchase TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX article_purchase_buyer_idx ON article_purchase COLUMNS buyer_id, created_at;
+DEFINE INDEX article_purchase_article_buyer_idx ON article_purchase COLUMNS article_id, buyer_id;
+DEFINE INDEX article_purchase_intent_idx ON article_purchase COLUMNS stripe_payment_intent_id;
+
+-- 礼物（代他人购买的文章或订阅）
+DEFINE TABLE gift SCHEMAFULL;
+DEFINE FIELD sender_id ON gift TYPE string ASSERT $value != NONE;
+DEFINE FIELD gift_type ON gift TYPE string ASSERT $value INSIDE ["article", "subscription"];
+DEFINE FIELD creator_id ON gift TYPE string ASSERT $value != NONE;
+DEFINE FIELD title ON gift TYPE string; -- 文章标题或订阅计划名称
+DEFINE FIELD article_id ON gift TYPE option<string>;
+DEFINE FIELD plan_id ON gift TYPE option<string>;
+DEFINE FIELD months ON gift TYPE option<number>; -- 礼物订阅月数
+DEFINE FIELD recipient_id ON gift TYPE option<string>;
+DEFINE FIELD recipient_email ON gift TYPE option<string>;
+DEFINE FIELD message ON gift TYPE option<string>;
+DEFINE FIELD amount ON gift TYPE number ASSERT $value > 0; -- 金额（美分）
+DEFINE FIELD tax_amount ON gift TYPE number DEFAULT 0; -- 代收税费（美分）
+DEFINE FIELD currency ON gift TYPE string DEFAULT "USD";
+DEFINE FIELD stripe_payment_intent_id ON gift TYPE option<string>;
+DEFINE FIELD status ON gift TYPE string DEFAULT "pending" ASSERT $value INSIDE ["pending", "paid", "claimed", "failed", "revoked"];
+DEFINE FIELD claim_token ON gift TYPE string ASSERT $value != NONE;
+DEFINE FIELD claimed_by ON gift TYPE option<string>;
+DEFINE FIELD claimed_at ON gift TYPE option<datetime>;
+DEFINE FIELD created_at ON gift TYPE datetime DEFAULT time::now();
+DEFINE FIELD updated_at ON gift TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX gift_claim_token_idx ON gift COLUMNS claim_token UNIQUE;
+DEFINE INDEX gift_sender_idx ON gift COLUMNS sender_id, created_at;
+DEFINE INDEX gift_recipient_idx ON gift COLUMNS recipient_id;
+DEFINE INDEX gift_recipient_email_idx ON gift COLUMNS recipient_email;
+DEFINE INDEX gift_intent_idx ON gift COLUMNS stripe_payment_intent_id;
+
 -- 作者收益记录表
 DEFINE TABLE creator_earning SCHEMAFULL;
 DEFINE FIELD id ON creator_earning TYPE record(creator_earning);
@@ -482,6 +1089,7 @@ DEFINE FIELD id ON payment_intent TYPE record(payment_intent);
 DEFINE FIELD stripe_payment_intent_id ON payment_intent TYPE string ASSERT $value != NONE;
 DEFINE FIELD user_id ON payment_intent TYPE string ASSERT $value != NONE;
 DEFINE FIELD amount ON payment_intent TYPE number ASSERT $value > 0;
+DEFINE FIELD tax_amount ON payment_intent TYPE number DEFAULT 0; -- amount 中包含的 Stripe Tax 税额
 DEFINE FIELD currency ON payment_intent TYPE string DEFAULT "USD";
 DEFINE FIELD status ON payment_intent TYPE string ASSERT $value INSIDE ["requires_payment_method", "requires_confirmation", "requires_action", "processing", "requires_capture", "succeeded", "canceled"];
 DEFINE FIELD mode ON payment_intent TYPE string DEFAULT "payment" ASSERT $value INSIDE ["payment", "setup"];
@@ -496,6 +1104,18 @@ DEFINE INDEX payment_intent_stripe_id_idx ON payment_intent COLUMNS stripe_payme
 DEFINE INDEX payment_intent_user_idx ON payment_intent COLUMNS user_id;
 DEFINE INDEX payment_intent_status_idx ON payment_intent COLUMNS status;
 
+-- Stripe 请求幂等键（记录 ID 即 Idempotency-Key），保存每个键的最终结果
+DEFINE TABLE stripe_idempotency_key SCHEMAFULL;
+DEFINE FIELD key ON stripe_idempotency_key TYPE string;
+DEFINE FIELD action ON stripe_idempotency_key TYPE string;
+DEFINE FIELD status ON stripe_idempotency_key TYPE string ASSERT $value INSIDE ["succeeded", "failed"];
+DEFINE FIELD attempts ON stripe_idempotency_key TYPE number DEFAULT 0;
+DEFINE FIELD response ON stripe_idempotency_key TYPE option<object> FLEXIBLE; -- 成功时 Stripe 返回的对象
+DEFINE FIELD error ON stripe_idempotency_key TYPE option<string>;
+DEFINE FIELD created_at ON stripe_idempotency_key TYPE datetime DEFAULT time::now();
+DEFINE FIELD updated_at ON stripe_idempotency_key TYPE datetime DEFAULT time::now();
+DEFINE INDEX stripe_idempotency_key_updated_idx ON stripe_idempotency_key COLUMNS updated_at;
+
 -- Stripe订阅表
 DEFINE TABLE stripe_subscription SCHEMAFULL;
 DEFINE FIELD id ON stripe_subscription TYPE record(stripe_subscription);
@@ -563,11 +1183,16 @@ DEFINE FIELD processed ON webhook_event TYPE bool DEFAULT false;
 DEFINE FIELD processed_at ON webhook_event TYPE option<datetime>;
 DEFINE FIELD data ON webhook_event TYPE object;
 DEFINE FIELD processing_summary ON webhook_event TYPE option<object>;
+DEFINE FIELD attempts ON webhook_event TYPE number DEFAULT 0;
+DEFINE FIELD last_error ON webhook_event TYPE option<string>;
+DEFINE FIELD next_retry_at ON webhook_event TYPE option<datetime>;
+DEFINE FIELD dead_lettered_at ON webhook_event TYPE option<datetime>;
 DEFINE FIELD created_at ON webhook_event TYPE datetime DEFAULT time::now();
 
 -- Webhook事件索引
 DEFINE INDEX webhook_event_stripe_id_idx ON webhook_event COLUMNS stripe_event_id UNIQUE;
 DEFINE INDEX webhook_event_type_idx ON webhook_event COLUMNS event_type;
+DEFINE INDEX webhook_event_retry_idx ON webhook_event COLUMNS next_retry_at;
 DEFINE INDEX webhook_event_processed_idx ON webhook_event COLUMNS processed;
 
 -- =====================================
@@ -597,10 +1222,12 @@ DEFINE FIELD user_id ON notification_config TYPE string ASSERT $value != NONE;
 DEFINE FIELD email_notifications ON notification_config TYPE bool DEFAULT true;
 DEFINE FIELD push_notifications ON notification_config TYPE bool DEFAULT true;
 DEFINE FIELD websocket_notifications ON notification_config TYPE bool DEFAULT true;
-DEFINE FIELD notification_types ON notification_config TYPE array<string> DEFAULT ["new_article", "new_comment", "new_follower", "article_clap", "subscription_update", "payment_update"];
+DEFINE FIELD notification_types ON notification_config TYPE array<string> DEFAULT ["new_article", "new_comment", "new_follower", "article_clap", "subscription_update", "payment_update", "publication_analytics", "article_milestone", "collaboration_invite"];
 DEFINE FIELD quiet_hours_start ON notification_config TYPE option<string>; -- "22:00"格式
 DEFINE FIELD quiet_hours_end ON notification_config TYPE option<string>; -- "08:00"格式
 DEFINE FIELD timezone ON notification_config TYPE string DEFAULT "UTC";
+DEFINE FIELD email_digest_window ON notification_config TYPE string DEFAULT "hourly" ASSERT $value INSIDE ["immediate", "hourly", "daily"];
+DEFINE FIELD milestone_notifications ON notification_config TYPE bool DEFAULT true;
 DEFINE FIELD created_at ON notification_config TYPE datetime DEFAULT time::now();
 DEFINE FIELD updated_at ON notification_config TYPE datetime DEFAULT time::now();
 
@@ -617,13 +1244,18 @@ DEFINE FIELD id ON publication_domain TYPE record(publication_domain);
 DEFINE FIELD publication_id ON publication_domain TYPE record(publication) ASSERT $value != NONE;
 DEFINE FIELD domain_type ON publication_domain TYPE string ASSERT $value INSIDE ["subdomain", "custom"];
 DEFINE FIELD subdomain ON publication_domain TYPE option<string>; -- 子域名（不含主域名）
-DEFINE FIELD custom_domain ON publication_domain TYPE option<string>; -- 完整自定义域名
+DEFINE FIELD custom_domain ON publication_domain TYPE option<string>; -- 完整自定义域名（可为 *.example.com 通配符）
+DEFINE FIELD routing_mode ON publication_domain TYPE string DEFAULT "cname" ASSERT $value INSIDE ["cname", "a", "alias"]; -- 根域名使用 A/ALIAS 记录
 DEFINE FIELD status ON publication_domain TYPE string DEFAULT "pending" ASSERT $value INSIDE ["pending", "verifying", "active", "failed"];
 DEFINE FIELD ssl_status ON publication_domain TYPE string DEFAULT "none" ASSERT $value INSIDE ["none", "pending", "active", "expired", "failed"];
 DEFINE FIELD is_primary ON publication_domain TYPE bool DEFAULT false; -- 是否为主域名
 DEFINE FIELD verification_token ON publication_domain TYPE option<string>; -- DNS验证令牌
 DEFINE FIELD ssl_expires_at ON publication_domain TYPE option<datetime>; -- SSL证书过期时间
 DEFINE FIELD verified_at ON publication_domain TYPE option<datetime>;
+-- 迁入模式：在域名仍指向原服务时完成验证和证书签发，检测到 CNAME 切换后再激活
+DEFINE FIELD cutover_phase ON publication_domain TYPE option<string> ASSERT $value = NONE OR $value INSIDE ["awaiting_ownership", "issuing_certificate", "awaiting_dns_switch", "completed"];
+DEFINE FIELD ownership_verified_at ON publication_domain TYPE option<datetime>;
+DEFINE FIELD cutover_completed_at ON publication_domain TYPE option<datetime>;
 DEFINE FIELD created_at ON publication_domain TYPE datetime DEFAULT time::now();
 DEFINE FIELD updated_at ON publication_domain TYPE datetime DEFAULT time::now();
 
@@ -634,12 +1266,13 @@ DEFINE INDEX publication_domain_custom_domain_idx ON publication_domain COLUMNS
 DEFINE INDEX publication_domain_status_idx ON publication_domain COLUMNS status;
 DEFINE INDEX publication_domain_ssl_status_idx ON publication_domain COLUMNS ssl_status;
 DEFINE INDEX publication_domain_primary_idx ON publication_domain COLUMNS is_primary;
+DEFINE INDEX publication_domain_cutover_idx ON publication_domain COLUMNS cutover_phase;
 
 -- 域名验证记录表
 DEFINE TABLE domain_verification_record SCHEMAFULL;
 DEFINE FIELD id ON domain_verification_record TYPE record(domain_verification_record);
 DEFINE FIELD domain_id ON domain_verification_record TYPE record(publication_domain) ASSERT $value != NONE;
-DEFINE FIELD record_type ON domain_verification_record TYPE string ASSERT $value INSIDE ["TXT", "CNAME", "A"];
+DEFINE FIELD record_type ON domain_verification_record TYPE string ASSERT $value INSIDE ["TXT", "CNAME", "A", "ALIAS"];
 DEFINE FIELD record_name ON domain_verification_record TYPE string ASSERT $value != NONE; -- DNS记录名称
 DEFINE FIELD record_value ON domain_verification_record TYPE string ASSERT $value != NONE; -- DNS记录值
 DEFINE FIELD purpose ON domain_verification_record TYPE string ASSERT $value INSIDE ["ownership", "routing", "ssl"];
@@ -674,6 +1307,23 @@ DEFINE INDEX ssl_certificate_info_expires_idx ON ssl_certificate_info COLUMNS ex
 DEFINE INDEX ssl_certificate_info_status_idx ON ssl_certificate_info COLUMNS status;
 DEFINE INDEX ssl_certificate_info_auto_renew_idx ON ssl_certificate_info COLUMNS auto_renew;
 
+-- 出版物发信域名配置表（Newsletter 使用自定义域名发送）
+DEFINE TABLE domain_email_config SCHEMAFULL;
+DEFINE FIELD domain_id ON domain_email_config TYPE string ASSERT $value != NONE;
+DEFINE FIELD publication_id ON domain_email_config TYPE string ASSERT $value != NONE;
+DEFINE FIELD sending_domain ON domain_email_config TYPE string ASSERT $value != NONE;
+DEFINE FIELD from_local_part ON domain_email_config TYPE string DEFAULT "newsletter"; -- 发件地址 @ 前的部分
+DEFINE FIELD dkim_selector ON domain_email_config TYPE string ASSERT $value != NONE;
+DEFINE FIELD dkim_private_key ON domain_email_config TYPE string ASSERT $value != NONE; -- PKCS#1 PEM 私钥
+DEFINE FIELD records ON domain_email_config TYPE array<object> DEFAULT [] FLEXIBLE; -- SPF / DKIM / 退信 CNAME 记录
+DEFINE FIELD status ON domain_email_config TYPE string DEFAULT "pending" ASSERT $value INSIDE ["pending", "verified", "failed"];
+DEFINE FIELD verified_at ON domain_email_config TYPE option<datetime>;
+DEFINE FIELD last_checked_at ON domain_email_config TYPE option<datetime>;
+DEFINE FIELD created_at ON domain_email_config TYPE datetime DEFAULT time::now();
+DEFINE FIELD updated_at ON domain_email_config TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX domain_email_config_publication_idx ON domain_email_config COLUMNS publication_id, status;
+
 -- =====================================
 -- 通知系统
 -- =====================================
@@ -695,6 +1345,196 @@ DEFINE INDEX notification_recipient_idx ON notification COLUMNS recipient_id;
 DEFINE INDEX notification_recipient_unread_idx ON notification COLUMNS recipient_id, is_read;
 DEFINE INDEX notification_created_idx ON notification COLUMNS created_at;
 
+-- 邮件摘要待发送队列
+DEFINE TABLE notification_digest_item SCHEMAFULL;
+DEFINE FIELD id ON notification_digest_item TYPE record(notification_digest_item);
+DEFINE FIELD recipient_id ON notification_digest_item TYPE string ASSERT $value != NONE;
+DEFINE FIELD notification_id ON notification_digest_item TYPE string ASSERT $value != NONE;
+DEFINE FIELD notification_type ON notification_digest_item TYPE string;
+DEFINE FIELD title ON notification_digest_item TYPE string;
+DEFINE FIELD message ON notification_digest_item TYPE string;
+DEFINE FIELD window ON notification_digest_item TYPE string ASSERT $value INSIDE ["immediate", "hourly", "daily"];
+DEFINE FIELD deliver_after ON notification_digest_item TYPE datetime ASSERT $value != NONE; -- 窗口结束时间
+DEFINE FIELD delivered_at ON notification_digest_item TYPE option<datetime>;
+DEFINE FIELD failed_at ON notification_digest_item TYPE option<datetime>; -- 无法投递，不再重试
+DEFINE FIELD failure_reason ON notification_digest_item TYPE option<string>;
+DEFINE FIELD created_at ON notification_digest_item TYPE datetime DEFAULT time::now();
+
+-- 邮件摘要索引
+DEFINE INDEX notification_digest_item_recipient_idx ON notification_digest_item COLUMNS recipient_id;
+DEFINE INDEX notification_digest_item_due_idx ON notification_digest_item COLUMNS delivered_at, failed_at, deliver_after;
+
+-- 按事件类型的通知偏好（记录 ID 为用户 ID）
+DEFINE TABLE notification_preference SCHEMAFULL;
+DEFINE FIELD id ON notification_preference TYPE record(notification_preference);
+DEFINE FIELD user_id ON notification_preference TYPE string ASSERT $value != NONE;
+DEFINE FIELD events ON notification_preference TYPE object FLEXIBLE; -- 事件类型 => { in_app, email, push, digest }
+DEFINE FIELD activity_digest ON notification_preference TYPE string DEFAULT "off" ASSERT $value INSIDE ["off", "daily", "weekly"];
+DEFINE FIELD activity_digest_sent_at ON notification_preference TYPE option<datetime>;
+DEFINE FIELD updated_at ON notification_preference TYPE option<datetime>;
+
+DEFINE INDEX notification_preference_activity_digest_idx ON notification_preference COLUMNS activity_digest, activity_digest_sent_at;
+
+-- 浏览器 Web Push 订阅（记录 ID 为推送地址的 SHA-256）
+DEFINE TABLE push_subscription SCHEMAFULL;
+DEFINE FIELD id ON push_subscription TYPE record(push_subscription);
+DEFINE FIELD user_id ON push_subscription TYPE string ASSERT $value != NONE;
+DEFINE FIELD endpoint ON push_subscription TYPE string ASSERT $value != NONE;
+DEFINE FIELD p256dh ON push_subscription TYPE string ASSERT $value != NONE;
+DEFINE FIELD auth ON push_subscription TYPE string ASSERT $value != NONE;
+DEFINE FIELD user_agent ON push_subscription TYPE option<string>;
+DEFINE FIELD created_at ON push_subscription TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX push_subscription_user_idx ON push_subscription COLUMNS user_id;
+
+-- 限流计数器（RATE_LIMIT_STORE=database 时使用，记录 ID 为 策略:窗口:主体:窗口起点）
+DEFINE TABLE rate_limit_counter SCHEMAFULL;
+DEFINE FIELD id ON rate_limit_counter TYPE record(rate_limit_counter);
+DEFINE FIELD count ON rate_limit_counter TYPE number DEFAULT 0;
+DEFINE FIELD expires_at ON rate_limit_counter TYPE datetime;
+
+DEFINE INDEX rate_limit_counter_expires_idx ON rate_limit_counter COLUMNS expires_at;
+
+-- OAuth2 第三方应用（记录 ID 为 client_id）
+DEFINE TABLE oauth_client SCHEMAFULL;
+DEFINE FIELD id ON oauth_client TYPE record(oauth_client);
+DEFINE FIELD client_id ON oauth_client TYPE string ASSERT $value != NONE;
+DEFINE FIELD owner_id ON oauth_client TYPE string ASSERT $value != NONE;
+DEFINE FIELD name ON oauth_client TYPE string ASSERT string::len($value) > 0;
+DEFINE FIELD redirect_uris ON oauth_client TYPE array<string>;
+DEFINE FIELD scopes ON oauth_client TYPE array<string>;
+DEFINE FIELD confidential ON oauth_client TYPE bool DEFAULT false;
+DEFINE FIELD secret_hash ON oauth_client TYPE option<string>;
+DEFINE FIELD created_at ON oauth_client TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX oauth_client_owner_idx ON oauth_client COLUMNS owner_id;
+
+-- 授权码（记录 ID 为授权码的 SHA-256，使用一次即删除）
+DEFINE TABLE oauth_code SCHEMAFULL;
+DEFINE FIELD id ON oauth_code TYPE record(oauth_code);
+DEFINE FIELD client_id ON oauth_code TYPE string ASSERT $value != NONE;
+DEFINE FIELD user_id ON oauth_code TYPE string ASSERT $value != NONE;
+DEFINE FIELD redirect_uri ON oauth_code TYPE string;
+DEFINE FIELD scopes ON oauth_code TYPE array<string>;
+DEFINE FIELD code_challenge ON oauth_code TYPE string;
+DEFINE FIELD expires_at ON oauth_code TYPE datetime;
+
+-- 访问令牌和刷新令牌（只保存 SHA-256）
+DEFINE TABLE oauth_token SCHEMAFULL;
+DEFINE FIELD access_hash ON oauth_token TYPE string ASSERT $value != NONE;
+DEFINE FIELD refresh_hash ON oauth_token TYPE string ASSERT $value != NONE;
+DEFINE FIELD client_id ON oauth_token TYPE string ASSERT $value != NONE;
+DEFINE FIELD user_id ON oauth_token TYPE string ASSERT $value != NONE;
+DEFINE FIELD scopes ON oauth_token TYPE array<string>;
+DEFINE FIELD access_expires_at ON oauth_token TYPE datetime;
+DEFINE FIELD refresh_expires_at ON oauth_token TYPE datetime;
+DEFINE FIELD revoked_at ON oauth_token TYPE option<datetime>;
+DEFINE FIELD last_used_at ON oauth_token TYPE option<datetime>;
+DEFINE FIELD created_at ON oauth_token TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX oauth_token_access_idx ON oauth_token COLUMNS access_hash UNIQUE;
+DEFINE INDEX oauth_token_refresh_idx ON oauth_token COLUMNS refresh_hash UNIQUE;
+DEFINE INDEX oauth_token_user_client_idx ON oauth_token COLUMNS user_id, client_id;
+
+-- 用户对应用的授权（记录 ID 为 {user_id}_{client_id}）
+DEFINE TABLE oauth_grant SCHEMAFULL;
+DEFINE FIELD id ON oauth_grant TYPE record(oauth_grant);
+DEFINE FIELD user_id ON oauth_grant TYPE string ASSERT $value != NONE;
+DEFINE FIELD client_id ON oauth_grant TYPE string ASSERT $value != NONE;
+DEFINE FIELD client_name ON oauth_grant TYPE string;
+DEFINE FIELD scopes ON oauth_grant TYPE array<string>;
+DEFINE FIELD granted_at ON oauth_grant TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX oauth_grant_user_idx ON oauth_grant COLUMNS user_id;
+DEFINE INDEX oauth_grant_client_idx ON oauth_grant COLUMNS client_id;
+
+-- 两步验证表：每个用户一条记录，记录 ID 为用户 ID；恢复码只保存哈希
+DEFINE TABLE user_two_factor SCHEMAFULL;
+DEFINE FIELD id ON user_two_factor TYPE record(user_two_factor);
+DEFINE FIELD user_id ON user_two_factor TYPE string ASSERT $value != NONE;
+DEFINE FIELD secret ON user_two_factor TYPE string ASSERT $value != NONE;
+DEFINE FIELD enabled ON user_two_factor TYPE bool DEFAULT false;
+DEFINE FIELD last_used_step ON user_two_factor TYPE option<int>;
+DEFINE FIELD recovery_codes ON user_two_factor TYPE array<string> DEFAULT [];
+DEFINE FIELD enabled_at ON user_two_factor TYPE option<datetime>;
+DEFINE FIELD created_at ON user_two_factor TYPE datetime DEFAULT time::now();
+
+-- 已通过两步验证的登录会话，记录 ID 为会话标识，到 JWT 过期为止有效
+DEFINE TABLE two_factor_session SCHEMAFULL;
+DEFINE FIELD id ON two_factor_session TYPE record(two_factor_session);
+DEFINE FIELD user_id ON two_factor_session TYPE string ASSERT $value != NONE;
+DEFINE FIELD expires_at ON two_factor_session TYPE datetime;
+DEFINE FIELD verified_at ON two_factor_session TYPE datetime DEFAULT time::now();
+DEFINE INDEX two_factor_session_user_idx ON two_factor_session COLUMNS user_id;
+DEFINE INDEX two_factor_session_expires_idx ON two_factor_session COLUMNS expires_at;
+
+-- 平台角色表（管理员、审核员），记录ID即用户ID
+DEFINE TABLE platform_role SCHEMAFULL;
+DEFINE FIELD user_id ON platform_role TYPE string ASSERT $value != NONE;
+DEFINE FIELD role ON platform_role TYPE string ASSERT $value INSIDE ["admin", "moderator"];
+DEFINE FIELD granted_by ON platform_role TYPE string;
+DEFINE FIELD granted_at ON platform_role TYPE datetime DEFAULT time::now();
+
+-- 管理操作审计表
+DEFINE TABLE admin_action SCHEMAFULL;
+DEFINE FIELD actor_id ON admin_action TYPE string ASSERT $value != NONE;
+DEFINE FIELD action ON admin_action TYPE string ASSERT $value != NONE;
+DEFINE FIELD target_type ON admin_action TYPE string;
+DEFINE FIELD target_id ON admin_action TYPE string;
+DEFINE FIELD reason ON admin_action TYPE option<string>;
+DEFINE FIELD created_at ON admin_action TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX admin_action_actor_idx ON admin_action COLUMNS actor_id, created_at;
+DEFINE INDEX admin_action_target_idx ON admin_action COLUMNS target_id;
+
+-- 内容举报表
+DEFINE TABLE content_report SCHEMAFULL;
+DEFINE FIELD case_id ON content_report TYPE string ASSERT $value != NONE; -- report_case 的记录ID
+DEFINE FIELD target_type ON content_report TYPE string ASSERT $value INSIDE ["article", "comment", "user"];
+DEFINE FIELD target_id ON content_report TYPE string ASSERT $value != NONE;
+DEFINE FIELD reporter_id ON content_report TYPE string ASSERT $value != NONE;
+DEFINE FIELD reason ON content_report TYPE string ASSERT $value INSIDE ["spam", "harassment", "copyright", "other"];
+DEFINE FIELD details ON content_report TYPE option<string>;
+DEFINE FIELD status ON content_report TYPE string DEFAULT "open" ASSERT $value INSIDE ["open", "resolved", "dismissed"];
+DEFINE FIELD reviewed_by ON content_report TYPE option<string>;
+DEFINE FIELD reviewed_at ON content_report TYPE option<datetime>;
+DEFINE FIELD resolution_note ON content_report TYPE option<string>;
+DEFINE FIELD created_at ON content_report TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX content_report_case_idx ON content_report COLUMNS case_id, status;
+DEFINE INDEX content_report_reporter_idx ON content_report COLUMNS reporter_id, created_at;
+
+-- 举报案件表：同一对象的举报汇总，记录ID为 {target_type}_{target_id}
+DEFINE TABLE report_case SCHEMAFULL;
+DEFINE FIELD target_type ON report_case TYPE string ASSERT $value INSIDE ["article", "comment", "user"];
+DEFINE FIELD target_id ON report_case TYPE string ASSERT $value != NONE;
+DEFINE FIELD target_owner_id ON report_case TYPE string;
+DEFINE FIELD status ON report_case TYPE string DEFAULT "open" ASSERT $value INSIDE ["open", "resolved", "dismissed"];
+DEFINE FIELD report_count ON report_case TYPE int DEFAULT 0; -- 本轮举报人数
+DEFINE FIELD reasons ON report_case TYPE array<string> DEFAULT [];
+DEFINE FIELD hidden_at ON report_case TYPE option<datetime>; -- 达到阈值后自动隐藏
+DEFINE FIELD first_reported_at ON report_case TYPE datetime DEFAULT time::now();
+DEFINE FIELD last_reported_at ON report_case TYPE datetime DEFAULT time::now();
+DEFINE FIELD reviewed_by ON report_case TYPE option<string>;
+DEFINE FIELD reviewed_at ON report_case TYPE option<datetime>;
+DEFINE FIELD resolution_note ON report_case TYPE option<string>;
+
+DEFINE INDEX report_case_status_idx ON report_case COLUMNS status, report_count;
+
+-- 账户删除申请及进度，记录ID即用户ID；永久删除后保留本记录作为删除凭证
+DEFINE TABLE account_deletion SCHEMAFULL;
+DEFINE FIELD user_id ON account_deletion TYPE string ASSERT $value != NONE;
+DEFINE FIELD status ON account_deletion TYPE string DEFAULT "pending" ASSERT $value INSIDE ["pending", "processing", "scheduled", "failed", "completed", "cancelled"];
+DEFINE FIELD reason ON account_deletion TYPE option<string>;
+DEFINE FIELD steps ON account_deletion TYPE array<object> DEFAULT [] FLEXIBLE; -- { step, status, affected, error, completed_at }
+DEFINE FIELD requested_at ON account_deletion TYPE datetime DEFAULT time::now();
+DEFINE FIELD hard_delete_after ON account_deletion TYPE datetime;
+DEFINE FIELD updated_at ON account_deletion TYPE datetime DEFAULT time::now();
+DEFINE FIELD completed_at ON account_deletion TYPE option<datetime>;
+DEFINE FIELD cancelled_at ON account_deletion TYPE option<datetime>;
+
+DEFINE INDEX account_deletion_status_idx ON account_deletion COLUMNS status, hard_delete_after;
+
 -- =====================================
 -- 统计和分析
 -- =====================================
@@ -760,6 +1600,250 @@ DEFINE INDEX search_index_article_idx ON search_index COLUMNS article_id UNIQUE;
 DEFINE INDEX search_index_published_idx ON search_index COLUMNS is_published;
 DEFINE INDEX search_index_popularity_idx ON search_index COLUMNS popularity_score;
 
+-- 审核处罚表
+DEFINE TABLE moderation_strike SCHEMAFULL;
+DEFINE FIELD id ON moderation_strike TYPE record(moderation_strike);
+DEFINE FIELD user_id ON moderation_strike TYPE string ASSERT $value != NONE;
+DEFINE FIELD reason ON moderation_strike TYPE string ASSERT string::len($value) > 0;
+DEFINE FIELD issued_by ON moderation_strike TYPE string ASSERT $value != NONE;
+DEFINE FIELD expires_at ON moderation_strike TYPE option<datetime>; -- 为空表示永久有效
+DEFINE FIELD created_at ON moderation_strike TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX moderation_strike_user_idx ON moderation_strike COLUMNS user_id;
+
+-- 文章浏览事件表（按小时聚合用于流量分析）
+DEFINE TABLE article_view_event SCHEMAFULL;
+DEFINE FIELD id ON article_view_event TYPE record(article_view_event);
+DEFINE FIELD article_id ON article_view_event TYPE string ASSERT $value != NONE;
+DEFINE FIELD publication_id ON article_view_event TYPE option<string>;
+DEFINE FIELD referrer ON article_view_event TYPE string DEFAULT "direct"; -- 来源域名
+DEFINE FIELD created_at ON article_view_event TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX article_view_event_publication_idx ON article_view_event COLUMNS publication_id, created_at;
+DEFINE INDEX article_view_event_article_idx ON article_view_event COLUMNS article_id, created_at;
+
+-- 去重后的文章浏览表（同一读者在 VIEW_DEDUP_WINDOW_MINUTES 内重复浏览只记录一次）
+DEFINE TABLE article_view SCHEMAFULL;
+DEFINE FIELD article_id ON article_view TYPE string ASSERT $value != NONE;
+DEFINE FIELD author_id ON article_view TYPE string;
+DEFINE FIELD publication_id ON article_view TYPE option<string>;
+DEFINE FIELD viewer_hash ON article_view TYPE string ASSERT $value != NONE; -- 用户 ID 或 IP + User-Agent 的加盐哈希
+DEFINE FIELD user_id ON article_view TYPE option<string>;
+DEFINE FIELD referrer ON article_view TYPE string DEFAULT "direct";
+DEFINE FIELD country ON article_view TYPE option<string>;
+DEFINE FIELD device_type ON article_view TYPE option<string>; -- 由 User-Agent 推断：desktop、mobile、tablet
+DEFINE FIELD browser ON article_view TYPE option<string>;
+DEFINE FIELD os ON article_view TYPE option<string>;
+DEFINE FIELD created_at ON article_view TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX article_view_dedup_idx ON article_view COLUMNS article_id, viewer_hash, created_at;
+DEFINE INDEX article_view_publication_idx ON article_view COLUMNS publication_id, created_at;
+DEFINE INDEX article_view_author_idx ON article_view COLUMNS author_id, created_at;
+
+-- 机器流量事件表（不计入浏览数的爬虫访问）
+DEFINE TABLE bot_traffic_event SCHEMAFULL;
+DEFINE FIELD id ON bot_traffic_event TYPE record(bot_traffic_event);
+DEFINE FIELD article_id ON bot_traffic_event TYPE string ASSERT $value != NONE;
+DEFINE FIELD author_id ON bot_traffic_event TYPE string ASSERT $value != NONE;
+DEFINE FIELD publication_id ON bot_traffic_event TYPE option<string>;
+DEFINE FIELD bot_name ON bot_traffic_event TYPE string DEFAULT "unknown";
+DEFINE FIELD category ON bot_traffic_event TYPE string ASSERT $value INSIDE ["search_engine", "social_preview", "seo_tool", "ai_crawler", "monitoring", "automation", "unknown"];
+DEFINE FIELD reason ON bot_traffic_event TYPE string ASSERT $value INSIDE ["user_agent", "missing_headers", "request_rate", "challenge"];
+DEFINE FIELD created_at ON bot_traffic_event TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX bot_traffic_event_author_idx ON bot_traffic_event COLUMNS author_id, created_at;
+
+-- 文章里程碑表（ID 为 文章_类型_阈值，同一里程碑只记录一次）
+DEFINE TABLE article_milestone SCHEMAFULL;
+DEFINE FIELD id ON article_milestone TYPE record(article_milestone);
+DEFINE FIELD user_id ON article_milestone TYPE string ASSERT $value != NONE;
+DEFINE FIELD article_id ON article_milestone TYPE string ASSERT $value != NONE;
+DEFINE FIELD article_title ON article_milestone TYPE string;
+DEFINE FIELD article_slug ON article_milestone TYPE string;
+DEFINE FIELD kind ON article_milestone TYPE string ASSERT $value INSIDE ["views", "claps", "trending"];
+DEFINE FIELD threshold ON article_milestone TYPE int;
+DEFINE FIELD achieved_at ON article_milestone TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX article_milestone_user_idx ON article_milestone COLUMNS user_id, achieved_at;
+DEFINE INDEX article_milestone_article_idx ON article_milestone COLUMNS article_id;
+
+-- 出版物流量异常表
+DEFINE TABLE publication_anomaly SCHEMAFULL;
+DEFINE FIELD id ON publication_anomaly TYPE record(publication_anomaly);
+DEFINE FIELD publication_id ON publication_anomaly TYPE string ASSERT $value != NONE;
+DEFINE FIELD kind ON publication_anomaly TYPE string ASSERT $value INSIDE ["traffic_spike", "traffic_drop", "viral_article"];
+DEFINE FIELD article_id ON publication_anomaly TYPE option<string>;
+DEFINE FIELD window_start ON publication_anomaly TYPE datetime;
+DEFINE FIELD window_end ON publication_anomaly TYPE datetime;
+DEFINE FIELD observed_views ON publication_anomaly TYPE int;
+DEFINE FIELD expected_views ON publication_anomaly TYPE float;
+DEFINE FIELD z_score ON publication_anomaly TYPE float;
+DEFINE FIELD contributing_articles ON publication_anomaly TYPE array<object> DEFAULT [];
+DEFINE FIELD top_referrers ON publication_anomaly TYPE array<object> DEFAULT [];
+DEFINE FIELD created_at ON publication_anomaly TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX publication_anomaly_publication_idx ON publication_anomaly COLUMNS publication_id, created_at;
+
+-- 文章修订版本表
+DEFINE TABLE article_revision SCHEMAFULL;
+DEFINE FIELD id ON article_revision TYPE record(article_revision);
+DEFINE FIELD article_id ON article_revision TYPE string ASSERT $value != NONE;
+DEFINE FIELD revision_number ON article_revision TYPE int ASSERT $value >= 1;
+DEFINE FIELD title ON article_revision TYPE string;
+DEFINE FIELD subtitle ON article_revision TYPE option<string>;
+DEFINE FIELD content ON article_revision TYPE string;
+DEFINE FIELD tags ON article_revision TYPE option<array<string>>;
+DEFINE FIELD word_count ON article_revision TYPE int DEFAULT 0;
+DEFINE FIELD editor_id ON article_revision TYPE string ASSERT $value != NONE;
+DEFINE FIELD is_autosave ON article_revision TYPE bool DEFAULT false;
+DEFINE FIELD change_summary ON article_revision TYPE option<string>;
+DEFINE FIELD created_at ON article_revision TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX article_revision_number_idx ON article_revision COLUMNS article_id, revision_number UNIQUE;
+
+-- 出版物自定义内容转换器（WASM），每次上传产生一个新版本
+DEFINE TABLE content_transform SCHEMAFULL;
+DEFINE FIELD id ON content_transform TYPE record(content_transform);
+DEFINE FIELD publication_id ON content_transform TYPE string ASSERT $value != NONE;
+DEFINE FIELD name ON content_transform TYPE string ASSERT string::len($value) > 0 AND string::len($value) <= 64;
+DEFINE FIELD version ON content_transform TYPE int ASSERT $value >= 1;
+DEFINE FIELD module_base64 ON content_transform TYPE string;
+DEFINE FIELD module_hash ON content_transform TYPE string;
+DEFINE FIELD size_bytes ON content_transform TYPE int;
+DEFINE FIELD is_active ON content_transform TYPE bool DEFAULT true; -- 该名称当前生效的版本
+DEFINE FIELD is_enabled ON content_transform TYPE bool DEFAULT true; -- 开关
+DEFINE FIELD priority ON content_transform TYPE int DEFAULT 100;
+DEFINE FIELD failure_count ON content_transform TYPE int DEFAULT 0;
+DEFINE FIELD last_error ON content_transform TYPE option<string>;
+DEFINE FIELD created_by ON content_transform TYPE string;
+DEFINE FIELD created_at ON content_transform TYPE datetime DEFAULT time::now();
+DEFINE FIELD updated_at ON content_transform TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX content_transform_version_idx ON content_transform COLUMNS publication_id, name, version UNIQUE;
+DEFINE INDEX content_transform_active_idx ON content_transform COLUMNS publication_id, is_active;
+
+-- 生命周期任务（草稿归档、账户停用）的审计记录，撤销时标记 reverted_at
+DEFINE TABLE lifecycle_action SCHEMAFULL;
+DEFINE FIELD id ON lifecycle_action TYPE record(lifecycle_action);
+DEFINE FIELD kind ON lifecycle_action TYPE string ASSERT $value INSIDE ["draft_archive_notice", "draft_archived", "account_inactive_notice", "account_deactivated"];
+DEFINE FIELD resource_type ON lifecycle_action TYPE string ASSERT $value INSIDE ["article", "user"];
+DEFINE FIELD resource_id ON lifecycle_action TYPE string ASSERT $value != NONE;
+DEFINE FIELD user_id ON lifecycle_action TYPE string ASSERT $value != NONE;
+DEFINE FIELD details ON lifecycle_action TYPE object DEFAULT {};
+DEFINE FIELD created_at ON lifecycle_action TYPE datetime DEFAULT time::now();
+DEFINE FIELD reverted_at ON lifecycle_action TYPE option<datetime>;
+DEFINE FIELD reverted_by ON lifecycle_action TYPE option<string>;
+
+DEFINE INDEX lifecycle_action_resource_idx ON lifecycle_action COLUMNS kind, resource_id;
+DEFINE INDEX lifecycle_action_user_idx ON lifecycle_action COLUMNS user_id, created_at;
+
+-- 数据库完整性检查报告（定期任务生成，保留 30 天）
+DEFINE TABLE db_health_report SCHEMAFULL;
+DEFINE FIELD generated_at ON db_health_report TYPE datetime;
+DEFINE FIELD duration_ms ON db_health_report TYPE number;
+DEFINE FIELD total_rows ON db_health_report TYPE number;
+DEFINE FIELD tables ON db_health_report TYPE array<object> DEFAULT [] FLEXIBLE;
+DEFINE FIELD largest_tables ON db_health_report TYPE array<object> DEFAULT [] FLEXIBLE;
+DEFINE FIELD index_hints ON db_health_report TYPE array<object> DEFAULT [] FLEXIBLE;
+DEFINE FIELD orphans ON db_health_report TYPE array<object> DEFAULT [] FLEXIBLE;
+DEFINE FIELD action_plan ON db_health_report TYPE array<object> DEFAULT [] FLEXIBLE;
+
+DEFINE INDEX db_health_report_generated_idx ON db_health_report COLUMNS generated_at;
+
+-- ACME 账户凭据，按目录地址区分
+DEFINE TABLE acme_account SCHEMAFULL;
+DEFINE FIELD directory_url ON acme_account TYPE string;
+DEFINE FIELD credentials ON acme_account TYPE object FLEXIBLE;
+DEFINE FIELD created_at ON acme_account TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX acme_account_directory_idx ON acme_account COLUMNS directory_url UNIQUE;
+
+-- 待完成的 HTTP-01 验证
+DEFINE TABLE acme_challenge SCHEMAFULL;
+DEFINE FIELD domain ON acme_challenge TYPE string;
+DEFINE FIELD token ON acme_challenge TYPE string;
+DEFINE FIELD key_authorization ON acme_challenge TYPE string;
+DEFINE FIELD created_at ON acme_challenge TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX acme_challenge_token_idx ON acme_challenge COLUMNS token;
+
+-- acme-dns 注册信息（DNS-01），每个主机名一条
+DEFINE TABLE acme_dns_registration SCHEMAFULL;
+DEFINE FIELD host ON acme_dns_registration TYPE string;
+DEFINE FIELD username ON acme_dns_registration TYPE string;
+DEFINE FIELD password ON acme_dns_registration TYPE string;
+DEFINE FIELD fulldomain ON acme_dns_registration TYPE string;
+DEFINE FIELD subdomain ON acme_dns_registration TYPE string;
+DEFINE FIELD created_at ON acme_dns_registration TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX acme_dns_host_idx ON acme_dns_registration COLUMNS host UNIQUE;
+
+-- 内置 ACME 客户端签发的证书
+DEFINE TABLE ssl_certificate SCHEMAFULL;
+DEFINE FIELD domain_id ON ssl_certificate TYPE string;
+DEFINE FIELD domain ON ssl_certificate TYPE string;
+DEFINE FIELD certificate_pem ON ssl_certificate TYPE string;
+DEFINE FIELD private_key_pem ON ssl_certificate TYPE string;
+DEFINE FIELD issuer ON ssl_certificate TYPE string;
+DEFINE FIELD issued_at ON ssl_certificate TYPE datetime DEFAULT time::now();
+DEFINE FIELD expires_at ON ssl_certificate TYPE datetime;
+
+DEFINE INDEX ssl_certificate_domain_idx ON ssl_certificate COLUMNS domain_id UNIQUE;
+
+-- Newsletter 表（出版物把文章作为邮件发送）
+DEFINE TABLE newsletter SCHEMAFULL;
+DEFINE FIELD publication_id ON newsletter TYPE string ASSERT $value != NONE;
+DEFINE FIELD article_id ON newsletter TYPE string ASSERT $value != NONE;
+DEFINE FIELD subject ON newsletter TYPE string ASSERT $value != NONE;
+DEFINE FIELD sent_by ON newsletter TYPE string ASSERT $value != NONE;
+DEFINE FIELD status ON newsletter TYPE string DEFAULT "sending" ASSERT $value INSIDE ["sending", "sent", "partially_sent", "failed"];
+DEFINE FIELD provider ON newsletter TYPE string;
+DEFINE FIELD recipient_count ON newsletter TYPE number DEFAULT 0;
+DEFINE FIELD sent_count ON newsletter TYPE number DEFAULT 0;
+DEFINE FIELD failed_count ON newsletter TYPE number DEFAULT 0;
+DEFINE FIELD created_at ON newsletter TYPE datetime DEFAULT time::now();
+DEFINE FIELD completed_at ON newsletter TYPE option<datetime>;
+
+DEFINE INDEX newsletter_publication_idx ON newsletter COLUMNS publication_id, created_at;
+DEFINE INDEX newsletter_article_idx ON newsletter COLUMNS publication_id, article_id;
+DEFINE INDEX newsletter_status_idx ON newsletter COLUMNS status;
+
+-- Newsletter 投递记录（每个收件人一条，带退订令牌）
+DEFINE TABLE newsletter_delivery SCHEMAFULL;
+DEFINE FIELD newsletter_id ON newsletter_delivery TYPE string ASSERT $value != NONE;
+DEFINE FIELD publication_id ON newsletter_delivery TYPE string ASSERT $value != NONE;
+DEFINE FIELD user_id ON newsletter_delivery TYPE string ASSERT $value != NONE;
+DEFINE FIELD email ON newsletter_delivery TYPE string ASSERT $value != NONE;
+DEFINE FIELD status ON newsletter_delivery TYPE string DEFAULT "pending" ASSERT $value INSIDE ["pending", "sent", "failed"];
+DEFINE FIELD provider_message_id ON newsletter_delivery TYPE option<string>;
+DEFINE FIELD error ON newsletter_delivery TYPE option<string>;
+DEFINE FIELD unsubscribe_token ON newsletter_delivery TYPE string ASSERT $value != NONE;
+DEFINE FIELD sent_at ON newsletter_delivery TYPE option<datetime>;
+DEFINE FIELD created_at ON newsletter_delivery TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX newsletter_delivery_newsletter_idx ON newsletter_delivery COLUMNS newsletter_id, status;
+DEFINE INDEX newsletter_delivery_token_idx ON newsletter_delivery COLUMNS unsubscribe_token UNIQUE;
+
+-- Newsletter 退订记录（记录 ID 为 [publication_id, user_id]）
+DEFINE TABLE newsletter_unsubscribe SCHEMAFULL;
+DEFINE FIELD publication_id ON newsletter_unsubscribe TYPE string ASSERT $value != NONE;
+DEFINE FIELD user_id ON newsletter_unsubscribe TYPE string ASSERT $value != NONE;
+DEFINE FIELD email ON newsletter_unsubscribe TYPE string;
+DEFINE FIELD newsletter_id ON newsletter_unsubscribe TYPE option<string>;
+DEFINE FIELD created_at ON newsletter_unsubscribe TYPE datetime DEFAULT time::now();
+
+DEFINE INDEX newsletter_unsubscribe_publication_idx ON newsletter_unsubscribe COLUMNS publication_id;
+
+-- 正文嵌入内容缓存，记录ID为链接的 SHA-256；html 为空表示获取失败
+DEFINE TABLE oembed_cache SCHEMAFULL;
+DEFINE FIELD url ON oembed_cache TYPE string ASSERT $value != NONE;
+DEFINE FIELD provider ON oembed_cache TYPE string ASSERT $value INSIDE ["youtube", "twitter", "gist", "codepen"];
+DEFINE FIELD html ON oembed_cache TYPE option<string>; -- 已清理的嵌入 HTML
+DEFINE FIELD fetched_at ON oembed_cache TYPE datetime DEFAULT time::now();
+DEFINE FIELD expires_at ON oembed_cache TYPE datetime;
+
+DEFINE INDEX oembed_cache_url_idx ON oembed_cache COLUMNS url UNIQUE;
+
 -- =====================================
 -- 初始数据
 -- =====================================
@@ -818,7 +1902,7 @@ DEFINE FUNCTION fn::update_article_stats($article_id: record(article)) {
     LET $bookmark_count = (SELECT count() FROM bookmark WHERE article_id = $article_id);
     
     -- 更新点赞总数
-    LET $clap_count = (SELECT math::sum(count) FROM clap WHERE article_id = $article_id);
+    LET $clap_count = (SELECT math::sum(count) FROM reaction WHERE article_id = $article_id AND reaction_type = 'clap');
     
     -- 更新文章
     UPDATE article SET 
@@ -836,7 +1920,7 @@ DEFINE FUNCTION fn::calculate_popularity_score($article_id: record(article)) {
     -- 基于各种互动指标计算分数
     LET $score = (
         $article.view_count * 0.1 +
-        $article.clap_count * 1 +
+        $article.weighted_clap_score * 1 +
         $article.comment_count * 2 +
         $article.bookmark_count * 3 +
         $article.share_count * 5
@@ -8,6 +8,8 @@ pub struct Config {
     pub server_port: u16,
     pub environment: String,
     pub log_level: String,
+    /// 请求没有可用的 Accept-Language 时错误信息使用的语言（zh-CN 或 en）
+    pub default_locale: String,
 
     // Database configuration
     pub database_url: String,
@@ -22,6 +24,8 @@ pub struct Config {
     pub jwt_secret: String,
     pub jwt_expiry: String,
     pub jwt_refresh_expiry: String,
+    /// 始终拥有平台管理员角色的用户 ID，用于在没有任何管理员时初始化
+    pub platform_admin_ids: Vec<String>,
 
     // Redis configuration
     pub redis_url: Option<String>,
@@ -44,6 +48,19 @@ pub struct Config {
     pub smtp_password: String,
     pub smtp_from_name: String,
     pub smtp_from_email: String,
+    /// 邮件服务商：smtp 或 sendgrid
+    pub email_provider: String,
+    pub sendgrid_api_key: Option<String>,
+    /// API 对外访问地址，用于邮件中的退订链接
+    pub public_api_url: String,
+
+    // Web Push
+    /// VAPID 公钥（URL 安全 Base64），客户端订阅时使用
+    pub vapid_public_key: Option<String>,
+    /// VAPID 私钥（URL 安全 Base64），两者都配置后才发送浏览器推送
+    pub vapid_private_key: Option<String>,
+    /// VAPID 联系方式，mailto: 或 https: 地址
+    pub vapid_subject: String,
 
     // Frontend URLs
     pub frontend_url: String,
@@ -55,6 +72,10 @@ pub struct Config {
     pub max_bio_length: usize,
     pub default_articles_per_page: usize,
     pub default_comments_per_page: usize,
+    /// 评论最多嵌套的回复层数，更深的回复挂到该层的评论下
+    pub comment_max_depth: usize,
+    /// 文章或评论收到多少个不同用户的举报后自动隐藏，等待审核员处理；0 表示不自动隐藏
+    pub report_hide_threshold: usize,
 
     // Feature flags
     pub enable_registrations: bool,
@@ -62,10 +83,28 @@ pub struct Config {
     pub enable_subscriptions: bool,
     pub enable_publications: bool,
     pub enable_email_notifications: bool,
+    pub enable_payments: bool,
 
     // Rate limiting
     pub rate_limit_requests: u32,
     pub rate_limit_window: u64,
+    pub rate_limit_burst: u64,
+    /// memory、database 或 redis
+    pub rate_limit_store: String,
+    /// JSON 数组形式的路由策略，优先于内置策略
+    pub rate_limit_policies: Option<String>,
+    /// 可信反向代理的 IP 或 CIDR，只有来自这些地址的连接才读取 `X-Forwarded-For`
+    pub trusted_proxies: Vec<String>,
+
+    // HTTP caching
+    /// JSON 数组形式的 Cache-Control 路由规则，优先于内置规则
+    pub http_cache_rules: Option<String>,
+    /// 服务端响应缓存：memory、redis 或 none
+    pub response_cache_store: String,
+    /// 响应缓存条目的有效期（秒）
+    pub response_cache_ttl: u64,
+    /// 内存响应缓存的条目上限
+    pub response_cache_capacity: u64,
 
     // Search configuration
     pub search_min_length: usize,
@@ -76,6 +115,18 @@ pub struct Config {
     pub image_max_height: u32,
     pub image_quality: u8,
     pub allowed_image_types: String,
+    /// 上传图片生成的响应式宽度
+    pub image_variant_widths: Vec<u32>,
+    /// AVIF 编码较慢，可以关闭只生成 WebP
+    pub image_avif_enabled: bool,
+
+    // Embedded content
+    /// 正文中的 YouTube、Twitter/X、Gist、CodePen 链接展开为嵌入内容（需要访问外网）
+    pub oembed_enabled: bool,
+
+    // Article attachments
+    pub allowed_attachment_types: String,
+    pub max_attachment_size: u64,
 
     // Recommendation engine
     pub recommendation_batch_size: usize,
@@ -92,6 +143,18 @@ pub struct Config {
     pub stripe_secret_key: Option<String>,
     pub stripe_publishable_key: Option<String>,
     pub stripe_webhook_secret: Option<String>,
+    /// 付费文章对没有访问权限的读者返回的段落数
+    pub paywall_preview_paragraphs: usize,
+    /// 计量付费墙每月默认的免费篇数，出版物可以单独设置
+    pub metered_free_articles: u32,
+    /// 平台从文章购买和订阅收入中抽取的百分比
+    pub platform_fee_percentage: f64,
+    /// 自动打款的最低可用余额（美分）
+    pub minimum_payout_amount: i64,
+    /// 收益入账后转为可提现余额前的等待天数，用于覆盖退款和拒付
+    pub payout_hold_days: i64,
+    /// Stripe Webhook 事件处理失败后的最大尝试次数，超过后转入死信
+    pub webhook_max_attempts: u32,
 
     // Domain configuration
     pub base_domain: Option<String>,
@@ -99,6 +162,80 @@ pub struct Config {
     pub ssl_provider_api_key: Option<String>,
     pub auto_provision_ssl: Option<bool>,
     pub ssl_webhook_url: Option<String>,
+    /// 根域名（无法使用 CNAME）A 记录指向的地址
+    pub domain_apex_target_ips: Vec<String>,
+    /// 内置 ACME 客户端的目录地址（"letsencrypt"、"letsencrypt-staging" 或完整 URL）
+    pub acme_directory_url: Option<String>,
+    pub acme_contact_email: Option<String>,
+    /// acme-dns 兼容的 API，用于 DNS-01 验证
+    pub acme_dns_api_url: Option<String>,
+    /// 出版物发信域名 SPF 记录需要 include 的地址（默认 _spf.<BASE_DOMAIN>）
+    pub email_spf_include: Option<String>,
+    /// 发信域名退信 CNAME 指向的主机（默认 bounces.<BASE_DOMAIN>）
+    pub email_return_path_target: Option<String>,
+
+    // Writing assistant (OpenAI-compatible chat completions endpoint)
+    pub assist_api_url: Option<String>,
+    pub assist_api_key: Option<String>,
+    pub assist_model: String,
+
+    // Article embeddings for related articles and semantic search
+    /// none、openai（OpenAI 兼容的 embeddings 接口）或 local（进程内的哈希向量，不依赖外部服务）
+    pub embedding_provider: String,
+    pub embedding_api_url: String,
+    pub embedding_api_key: Option<String>,
+    pub embedding_model: String,
+
+    // Article text-to-speech audio
+    /// none 或 openai（OpenAI 兼容的 audio/speech 接口，返回 MP3）
+    pub tts_provider: String,
+    pub tts_api_url: String,
+    pub tts_api_key: Option<String>,
+    pub tts_model: String,
+    pub tts_voice: String,
+    /// 朗读文本的字符上限，超出部分不生成音频
+    pub tts_max_chars: usize,
+    /// 变化的句子占比超过此值时重新生成音频
+    pub tts_regenerate_threshold: f64,
+
+    // Akismet comment spam checking (optional)
+    pub akismet_api_key: Option<String>,
+
+    // Plugins
+    pub plugin_dir: Option<String>,
+    pub plugin_hook_timeout_ms: u64,
+    pub plugin_webhook_url: Option<String>,
+    /// 出版物自定义 WASM 内容转换的总开关
+    pub content_transforms_enabled: bool,
+
+    // Lifecycle maintenance (0 disables the job)
+    /// 草稿超过该月数未修改时提醒作者并在提醒期后归档
+    pub draft_archive_after_months: u32,
+    pub draft_archive_notice_days: i64,
+    /// 账户超过该年数未活跃时提醒用户导出数据并在提醒期后停用
+    pub inactive_account_after_years: u32,
+    pub inactive_account_notice_days: i64,
+    /// 申请删除账户后保留数据的天数，期满后永久删除，期间可以撤销
+    pub account_deletion_grace_days: i64,
+    /// 删除的文章和评论在回收站保留的天数，期满后永久删除
+    pub trash_retention_days: i64,
+
+    /// 通过 SurrealDB LIVE SELECT 推送评论/点赞/通知变更
+    pub live_queries_enabled: bool,
+    /// 实时热度榜的半衰期（分钟）
+    pub popularity_half_life_minutes: u64,
+
+    /// 浏览计数必须携带前端脚本换取的 X-View-Token
+    pub bot_challenge_required: bool,
+    /// 同一 IP 每分钟计入的最大浏览数，超过视为机器流量
+    pub bot_max_views_per_minute: u32,
+    /// 同一读者在这段时间（分钟）内重复浏览同一篇文章只计一次
+    pub view_dedup_window_minutes: i64,
+    /// MaxMind GeoLite2/GeoIP2 Country 数据库路径，CDN 没有提供国家代码时用来查询访客国家
+    pub geoip_database_path: Option<String>,
+
+    /// 每个社交账号每小时最多发布的排期推广帖子
+    pub social_share_max_per_hour: u32,
 }
 
 impl Config {
@@ -110,6 +247,7 @@ impl Config {
                 .parse()?,
             environment: env::var("ENVIRONMENT").unwrap_or_else(|_| "development".to_string()),
             log_level: env::var("LOG_LEVEL").unwrap_or_else(|_| "info".to_string()),
+            default_locale: env::var("DEFAULT_LOCALE").unwrap_or_else(|_| "zh-CN".to_string()),
 
             database_url: env::var("DATABASE_URL")
                 .unwrap_or_else(|_| "http://localhost:8000".to_string()),
@@ -132,6 +270,14 @@ impl Config {
                 .unwrap_or_else(|_| "7d".to_string()),
             jwt_refresh_expiry: env::var("JWT_REFRESH_EXPIRY")
                 .unwrap_or_else(|_| "30d".to_string()),
+            platform_admin_ids: env::var("PLATFORM_ADMIN_IDS")
+                .map(|ids| {
+                    ids.split(',')
+                        .map(|id| id.trim().to_string())
+                        .filter(|id| !id.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
 
             redis_url: env::var("REDIS_URL").ok(),
             cache_ttl: env::var("CACHE_TTL")
@@ -169,6 +315,17 @@ impl Config {
                 .unwrap_or_else(|_| "Rainbow Blog".to_string()),
             smtp_from_email: env::var("SMTP_FROM_EMAIL")
                 .unwrap_or_else(|_| "noreply@rainbow-blog.com".to_string()),
+            email_provider: env::var("EMAIL_PROVIDER")
+                .unwrap_or_else(|_| "smtp".to_string())
+                .to_lowercase(),
+            sendgrid_api_key: env::var("SENDGRID_API_KEY").ok().filter(|k| !k.is_empty()),
+            public_api_url: env::var("PUBLIC_API_URL")
+                .unwrap_or_else(|_| "http://localhost:3000".to_string()),
+
+            vapid_public_key: env::var("VAPID_PUBLIC_KEY").ok().filter(|k| !k.is_empty()),
+            vapid_private_key: env::var("VAPID_PRIVATE_KEY").ok().filter(|k| !k.is_empty()),
+            vapid_subject: env::var("VAPID_SUBJECT")
+                .unwrap_or_else(|_| "mailto:noreply@rainbow-blog.com".to_string()),
 
             frontend_url: env::var("FRONTEND_URL")
                 .unwrap_or_else(|_| "http://localhost:3001".to_string()),
@@ -190,6 +347,12 @@ impl Config {
             default_comments_per_page: env::var("DEFAULT_COMMENTS_PER_PAGE")
                 .unwrap_or_else(|_| "50".to_string())
                 .parse()?,
+            comment_max_depth: env::var("COMMENT_MAX_DEPTH")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()?,
+            report_hide_threshold: env::var("REPORT_HIDE_THRESHOLD")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()?,
 
             enable_registrations: env::var("ENABLE_REGISTRATIONS")
                 .unwrap_or_else(|_| "true".to_string())
@@ -206,6 +369,9 @@ impl Config {
             enable_email_notifications: env::var("ENABLE_EMAIL_NOTIFICATIONS")
                 .unwrap_or_else(|_| "true".to_string())
                 .parse()?,
+            enable_payments: env::var("ENABLE_PAYMENTS")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()?,
 
             rate_limit_requests: env::var("RATE_LIMIT_REQUESTS")
                 .unwrap_or_else(|_| "100".to_string())
@@ -213,6 +379,31 @@ impl Config {
             rate_limit_window: env::var("RATE_LIMIT_WINDOW")
                 .unwrap_or_else(|_| "60".to_string())
                 .parse()?,
+            rate_limit_burst: env::var("RATE_LIMIT_BURST")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse()?,
+            rate_limit_store: env::var("RATE_LIMIT_STORE")
+                .unwrap_or_else(|_| "memory".to_string()),
+            rate_limit_policies: env::var("RATE_LIMIT_POLICIES").ok(),
+            trusted_proxies: env::var("TRUSTED_PROXIES")
+                .map(|proxies| {
+                    proxies
+                        .split(',')
+                        .map(|proxy| proxy.trim().to_string())
+                        .filter(|proxy| !proxy.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+
+            http_cache_rules: env::var("HTTP_CACHE_RULES").ok(),
+            response_cache_store: env::var("RESPONSE_CACHE_STORE")
+                .unwrap_or_else(|_| "memory".to_string()),
+            response_cache_ttl: env::var("RESPONSE_CACHE_TTL")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()?,
+            response_cache_capacity: env::var("RESPONSE_CACHE_CAPACITY")
+                .unwrap_or_else(|_| "10000".to_string())
+                .parse()?,
 
             search_min_length: env::var("SEARCH_MIN_LENGTH")
                 .unwrap_or_else(|_| "2".to_string())
@@ -232,6 +423,24 @@ impl Config {
                 .parse()?,
             allowed_image_types: env::var("ALLOWED_IMAGE_TYPES")
                 .unwrap_or_else(|_| "image/jpeg,image/png,image/gif,image/webp".to_string()),
+            image_variant_widths: env::var("IMAGE_VARIANT_WIDTHS")
+                .unwrap_or_else(|_| "480,960,1440".to_string())
+                .split(',')
+                .filter_map(|width| width.trim().parse().ok())
+                .collect(),
+            image_avif_enabled: env::var("IMAGE_AVIF_ENABLED")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()?,
+
+            oembed_enabled: env::var("OEMBED_ENABLED")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()?,
+
+            allowed_attachment_types: env::var("ALLOWED_ATTACHMENT_TYPES")
+                .unwrap_or_else(|_| "application/pdf,application/zip,application/json,text/csv,text/plain,application/vnd.ms-powerpoint,application/vnd.openxmlformats-officedocument.presentationml.presentation,application/vnd.ms-excel,application/vnd.openxmlformats-officedocument.spreadsheetml.sheet".to_string()),
+            max_attachment_size: env::var("MAX_ATTACHMENT_SIZE")
+                .unwrap_or_else(|_| "104857600".to_string())
+                .parse()?,
 
             recommendation_batch_size: env::var("RECOMMENDATION_BATCH_SIZE")
                 .unwrap_or_else(|_| "10".to_string())
@@ -253,6 +462,24 @@ impl Config {
             stripe_secret_key: env::var("STRIPE_SECRET_KEY").ok(),
             stripe_publishable_key: env::var("STRIPE_PUBLISHABLE_KEY").ok(),
             stripe_webhook_secret: env::var("STRIPE_WEBHOOK_SECRET").ok(),
+            paywall_preview_paragraphs: env::var("PAYWALL_PREVIEW_PARAGRAPHS")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()?,
+            metered_free_articles: env::var("METERED_FREE_ARTICLES")
+                .unwrap_or_else(|_| "3".to_string())
+                .parse()?,
+            platform_fee_percentage: env::var("PLATFORM_FEE_PERCENTAGE")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()?,
+            minimum_payout_amount: env::var("MINIMUM_PAYOUT_AMOUNT")
+                .unwrap_or_else(|_| "5000".to_string())
+                .parse()?,
+            payout_hold_days: env::var("PAYOUT_HOLD_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()?,
+            webhook_max_attempts: env::var("WEBHOOK_MAX_ATTEMPTS")
+                .unwrap_or_else(|_| "8".to_string())
+                .parse()?,
 
             base_domain: env::var("BASE_DOMAIN").ok(),
             ssl_provider_endpoint: env::var("SSL_PROVIDER_ENDPOINT").ok(),
@@ -261,6 +488,100 @@ impl Config {
                 .ok()
                 .and_then(|s| s.parse().ok()),
             ssl_webhook_url: env::var("SSL_WEBHOOK_URL").ok(),
+            domain_apex_target_ips: env::var("DOMAIN_APEX_TARGET_IPS")
+                .map(|ips| {
+                    ips.split(',')
+                        .map(|ip| ip.trim().to_string())
+                        .filter(|ip| !ip.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            acme_directory_url: env::var("ACME_DIRECTORY_URL").ok(),
+            acme_contact_email: env::var("ACME_CONTACT_EMAIL").ok(),
+            acme_dns_api_url: env::var("ACME_DNS_API_URL").ok(),
+            email_spf_include: env::var("EMAIL_SPF_INCLUDE").ok(),
+            email_return_path_target: env::var("EMAIL_RETURN_PATH_TARGET").ok(),
+
+            assist_api_url: env::var("ASSIST_API_URL").ok(),
+            assist_api_key: env::var("ASSIST_API_KEY").ok(),
+            assist_model: env::var("ASSIST_MODEL")
+                .unwrap_or_else(|_| "gpt-4o-mini".to_string()),
+
+            embedding_provider: env::var("EMBEDDING_PROVIDER")
+                .unwrap_or_else(|_| "none".to_string()),
+            embedding_api_url: env::var("EMBEDDING_API_URL")
+                .unwrap_or_else(|_| "https://api.openai.com/v1/embeddings".to_string()),
+            embedding_api_key: env::var("EMBEDDING_API_KEY").ok(),
+            embedding_model: env::var("EMBEDDING_MODEL")
+                .unwrap_or_else(|_| "text-embedding-3-small".to_string()),
+
+            tts_provider: env::var("TTS_PROVIDER")
+                .unwrap_or_else(|_| "none".to_string()),
+            tts_api_url: env::var("TTS_API_URL")
+                .unwrap_or_else(|_| "https://api.openai.com/v1/audio/speech".to_string()),
+            tts_api_key: env::var("TTS_API_KEY").ok(),
+            tts_model: env::var("TTS_MODEL")
+                .unwrap_or_else(|_| "tts-1".to_string()),
+            tts_voice: env::var("TTS_VOICE")
+                .unwrap_or_else(|_| "alloy".to_string()),
+            tts_max_chars: env::var("TTS_MAX_CHARS")
+                .unwrap_or_else(|_| "50000".to_string())
+                .parse()?,
+            tts_regenerate_threshold: env::var("TTS_REGENERATE_THRESHOLD")
+                .unwrap_or_else(|_| "0.2".to_string())
+                .parse()?,
+
+            akismet_api_key: env::var("AKISMET_API_KEY").ok(),
+
+            plugin_dir: env::var("PLUGIN_DIR").ok(),
+            plugin_hook_timeout_ms: env::var("PLUGIN_HOOK_TIMEOUT_MS")
+                .unwrap_or_else(|_| "2000".to_string())
+                .parse()?,
+            plugin_webhook_url: env::var("PLUGIN_WEBHOOK_URL").ok(),
+            content_transforms_enabled: env::var("CONTENT_TRANSFORMS_ENABLED")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()?,
+
+            draft_archive_after_months: env::var("DRAFT_ARCHIVE_AFTER_MONTHS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()?,
+            draft_archive_notice_days: env::var("DRAFT_ARCHIVE_NOTICE_DAYS")
+                .unwrap_or_else(|_| "14".to_string())
+                .parse()?,
+            inactive_account_after_years: env::var("INACTIVE_ACCOUNT_AFTER_YEARS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()?,
+            inactive_account_notice_days: env::var("INACTIVE_ACCOUNT_NOTICE_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()?,
+            account_deletion_grace_days: env::var("ACCOUNT_DELETION_GRACE_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()?,
+            trash_retention_days: env::var("TRASH_RETENTION_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()?,
+
+            live_queries_enabled: env::var("LIVE_QUERIES_ENABLED")
+                .unwrap_or_else(|_| "true".to_string())
+                .parse()?,
+            popularity_half_life_minutes: env::var("POPULARITY_HALF_LIFE_MINUTES")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()?,
+
+            bot_challenge_required: env::var("BOT_CHALLENGE_REQUIRED")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()?,
+            bot_max_views_per_minute: env::var("BOT_MAX_VIEWS_PER_MINUTE")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()?,
+            view_dedup_window_minutes: env::var("VIEW_DEDUP_WINDOW_MINUTES")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()?,
+            geoip_database_path: env::var("GEOIP_DATABASE_PATH").ok(),
+
+            social_share_max_per_hour: env::var("SOCIAL_SHARE_MAX_PER_HOUR")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()?,
         })
     }
 
@@ -1,10 +1,14 @@
+use crate::models::response::{ErrorResponse, FieldError};
+use crate::utils::{i18n, middleware::current_request_id};
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
     Json,
 };
-use serde_json::json;
+use serde::Serialize;
+use validator::ValidationErrorsKind;
 use thiserror::Error;
+use utoipa::ToSchema;
 use soulcore::error::SoulCoreError;
 
 pub type Result<T> = std::result::Result<T, AppError>;
@@ -76,117 +80,303 @@ pub enum AppError {
     
     #[error("Parse error: {0}")]
     Parse(String),
+
+    /// 带业务错误码的错误，见 `ErrorCode` 的业务错误部分
+    #[error("{code}: {message}")]
+    Coded { code: ErrorCode, message: String },
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        let (status, error_message, error_code) = match &self {
+/// 错误码目录。响应中的 `error.code` 取自这里，客户端应按错误码而不是错误信息分支；
+/// 已发布的错误码不能改名或删除，只能新增
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    DatabaseError,
+    AuthenticationError,
+    AuthorizationError,
+    ValidationError,
+    NotFound,
+    Conflict,
+    BadRequest,
+    InternalError,
+    ServiceUnavailable,
+    RateLimitExceeded,
+    FileUploadError,
+    ImageProcessingError,
+    EmailError,
+    ExternalServiceError,
+    SerializationError,
+    RequestError,
+    IoError,
+    #[serde(rename = "UTF8_ERROR")]
+    Utf8Error,
+    UuidError,
+    JwtError,
+    ParseError,
+
+    // 业务错误，客户端需要针对性处理的情况
+    ArticleAlreadyPublished,
+    NotPublicationMember,
+    AdminPermissionRequired,
+    PaidSubscriptionRequired,
+    PaymentMethodRequired,
+    InviteExpired,
+    TwoFactorRequired,
+    AccountSuspended,
+    AccountDeletionPending,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::DatabaseError => "DATABASE_ERROR",
+            ErrorCode::AuthenticationError => "AUTHENTICATION_ERROR",
+            ErrorCode::AuthorizationError => "AUTHORIZATION_ERROR",
+            ErrorCode::ValidationError => "VALIDATION_ERROR",
+            ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::Conflict => "CONFLICT",
+            ErrorCode::BadRequest => "BAD_REQUEST",
+            ErrorCode::InternalError => "INTERNAL_ERROR",
+            ErrorCode::ServiceUnavailable => "SERVICE_UNAVAILABLE",
+            ErrorCode::RateLimitExceeded => "RATE_LIMIT_EXCEEDED",
+            ErrorCode::FileUploadError => "FILE_UPLOAD_ERROR",
+            ErrorCode::ImageProcessingError => "IMAGE_PROCESSING_ERROR",
+            ErrorCode::EmailError => "EMAIL_ERROR",
+            ErrorCode::ExternalServiceError => "EXTERNAL_SERVICE_ERROR",
+            ErrorCode::SerializationError => "SERIALIZATION_ERROR",
+            ErrorCode::RequestError => "REQUEST_ERROR",
+            ErrorCode::IoError => "IO_ERROR",
+            ErrorCode::Utf8Error => "UTF8_ERROR",
+            ErrorCode::UuidError => "UUID_ERROR",
+            ErrorCode::JwtError => "JWT_ERROR",
+            ErrorCode::ParseError => "PARSE_ERROR",
+            ErrorCode::ArticleAlreadyPublished => "ARTICLE_ALREADY_PUBLISHED",
+            ErrorCode::NotPublicationMember => "NOT_PUBLICATION_MEMBER",
+            ErrorCode::AdminPermissionRequired => "ADMIN_PERMISSION_REQUIRED",
+            ErrorCode::PaidSubscriptionRequired => "PAID_SUBSCRIPTION_REQUIRED",
+            ErrorCode::PaymentMethodRequired => "PAYMENT_METHOD_REQUIRED",
+            ErrorCode::InviteExpired => "INVITE_EXPIRED",
+            ErrorCode::TwoFactorRequired => "TWO_FACTOR_REQUIRED",
+            ErrorCode::AccountSuspended => "ACCOUNT_SUSPENDED",
+            ErrorCode::AccountDeletionPending => "ACCOUNT_DELETION_PENDING",
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ErrorCode::DatabaseError
+            | ErrorCode::InternalError
+            | ErrorCode::EmailError
+            | ErrorCode::SerializationError
+            | ErrorCode::IoError => StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::AuthenticationError | ErrorCode::JwtError => StatusCode::UNAUTHORIZED,
+            ErrorCode::AuthorizationError
+            | ErrorCode::NotPublicationMember
+            | ErrorCode::AdminPermissionRequired
+            | ErrorCode::PaidSubscriptionRequired
+            | ErrorCode::TwoFactorRequired
+            | ErrorCode::AccountSuspended
+            | ErrorCode::AccountDeletionPending => StatusCode::FORBIDDEN,
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::Conflict | ErrorCode::ArticleAlreadyPublished => StatusCode::CONFLICT,
+            ErrorCode::ServiceUnavailable => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::RateLimitExceeded => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::ExternalServiceError => StatusCode::BAD_GATEWAY,
+            ErrorCode::ValidationError
+            | ErrorCode::BadRequest
+            | ErrorCode::FileUploadError
+            | ErrorCode::ImageProcessingError
+            | ErrorCode::RequestError
+            | ErrorCode::Utf8Error
+            | ErrorCode::UuidError
+            | ErrorCode::ParseError
+            | ErrorCode::PaymentMethodRequired
+            | ErrorCode::InviteExpired => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    /// 不是由 `AppError` 产生的错误响应（路由不存在、请求体解析失败等）按状态码归类
+    pub fn from_status(status: StatusCode) -> Self {
+        match status {
+            StatusCode::UNAUTHORIZED => ErrorCode::AuthenticationError,
+            StatusCode::FORBIDDEN => ErrorCode::AuthorizationError,
+            StatusCode::NOT_FOUND => ErrorCode::NotFound,
+            StatusCode::CONFLICT => ErrorCode::Conflict,
+            StatusCode::UNPROCESSABLE_ENTITY => ErrorCode::ValidationError,
+            StatusCode::TOO_MANY_REQUESTS => ErrorCode::RateLimitExceeded,
+            StatusCode::SERVICE_UNAVAILABLE => ErrorCode::ServiceUnavailable,
+            status if status.is_server_error() => ErrorCode::InternalError,
+            _ => ErrorCode::BadRequest,
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl AppError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            AppError::Database(_) => ErrorCode::DatabaseError,
+            AppError::Authentication(_) => ErrorCode::AuthenticationError,
+            AppError::Authorization(_) => ErrorCode::AuthorizationError,
+            AppError::Validation(_) | AppError::ValidatorError(_) => ErrorCode::ValidationError,
+            AppError::NotFound(_) => ErrorCode::NotFound,
+            AppError::Conflict(_) => ErrorCode::Conflict,
+            AppError::BadRequest(_) => ErrorCode::BadRequest,
+            AppError::Internal(_) => ErrorCode::InternalError,
+            AppError::ServiceUnavailable(_) => ErrorCode::ServiceUnavailable,
+            AppError::RateLimitExceeded => ErrorCode::RateLimitExceeded,
+            AppError::FileUpload(_) => ErrorCode::FileUploadError,
+            AppError::ImageProcessing(_) => ErrorCode::ImageProcessingError,
+            AppError::Email(_) => ErrorCode::EmailError,
+            AppError::ExternalService(_) => ErrorCode::ExternalServiceError,
+            AppError::Serialization(_) => ErrorCode::SerializationError,
+            AppError::Request(_) => ErrorCode::RequestError,
+            AppError::Io(_) => ErrorCode::IoError,
+            AppError::Utf8(_) => ErrorCode::Utf8Error,
+            AppError::Uuid(_) => ErrorCode::UuidError,
+            AppError::Jwt(_) => ErrorCode::JwtError,
+            AppError::Parse(_) => ErrorCode::ParseError,
+            AppError::Coded { code, .. } => *code,
+        }
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.code().status()
+    }
+
+    /// 按当前请求语言翻译后的对外错误信息，批量接口的单项结果也用它
+    pub fn client_message(&self) -> String {
+        // 按 `locale_middleware` 协商出的语言翻译，目录中没有的信息原样返回
+        i18n::translate_current(&self.public_message())
+    }
+
+    /// 返回给客户端的错误信息。服务端内部错误只记录日志，不把细节暴露出去
+    fn public_message(&self) -> String {
+        match self {
             AppError::Database(e) => {
                 tracing::error!("Database error: {}", e);
-                (StatusCode::INTERNAL_SERVER_ERROR, "Database error".to_string(), "DATABASE_ERROR")
-            }
-            AppError::Authentication(msg) => {
-                (StatusCode::UNAUTHORIZED, msg.clone(), "AUTHENTICATION_ERROR")
-            }
-            AppError::Authorization(msg) => {
-                (StatusCode::FORBIDDEN, msg.clone(), "AUTHORIZATION_ERROR")
-            }
-            AppError::Validation(msg) => {
-                (StatusCode::BAD_REQUEST, msg.clone(), "VALIDATION_ERROR")
-            }
-            AppError::NotFound(msg) => {
-                (StatusCode::NOT_FOUND, msg.clone(), "NOT_FOUND")
-            }
-            AppError::Conflict(msg) => {
-                (StatusCode::CONFLICT, msg.clone(), "CONFLICT")
-            }
-            AppError::BadRequest(msg) => {
-                (StatusCode::BAD_REQUEST, msg.clone(), "BAD_REQUEST")
+                "Database error".to_string()
             }
             AppError::Internal(msg) => {
                 tracing::error!("Internal error: {}", msg);
-                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string(), "INTERNAL_ERROR")
-            }
-            AppError::ServiceUnavailable(msg) => {
-                (StatusCode::SERVICE_UNAVAILABLE, msg.clone(), "SERVICE_UNAVAILABLE")
-            }
-            AppError::RateLimitExceeded => {
-                (StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded".to_string(), "RATE_LIMIT_EXCEEDED")
-            }
-            AppError::FileUpload(msg) => {
-                (StatusCode::BAD_REQUEST, msg.clone(), "FILE_UPLOAD_ERROR")
-            }
-            AppError::ImageProcessing(msg) => {
-                (StatusCode::BAD_REQUEST, msg.clone(), "IMAGE_PROCESSING_ERROR")
+                "Internal server error".to_string()
             }
+            AppError::RateLimitExceeded => "Rate limit exceeded".to_string(),
             AppError::Email(msg) => {
                 tracing::error!("Email error: {}", msg);
-                (StatusCode::INTERNAL_SERVER_ERROR, "Email service error".to_string(), "EMAIL_ERROR")
+                "Email service error".to_string()
             }
             AppError::ExternalService(msg) => {
                 tracing::error!("External service error: {}", msg);
-                (StatusCode::BAD_GATEWAY, "External service error".to_string(), "EXTERNAL_SERVICE_ERROR")
+                "External service error".to_string()
             }
             AppError::Serialization(e) => {
                 tracing::error!("Serialization error: {}", e);
-                (StatusCode::INTERNAL_SERVER_ERROR, "Serialization error".to_string(), "SERIALIZATION_ERROR")
+                "Serialization error".to_string()
             }
             AppError::Request(e) => {
                 tracing::error!("Request error: {}", e);
-                (StatusCode::BAD_REQUEST, "Request error".to_string(), "REQUEST_ERROR")
+                "Request error".to_string()
             }
             AppError::Io(e) => {
                 tracing::error!("IO error: {}", e);
-                (StatusCode::INTERNAL_SERVER_ERROR, "IO error".to_string(), "IO_ERROR")
+                "IO error".to_string()
             }
             AppError::Utf8(e) => {
                 tracing::error!("UTF-8 error: {}", e);
-                (StatusCode::BAD_REQUEST, "Invalid UTF-8".to_string(), "UTF8_ERROR")
+                "Invalid UTF-8".to_string()
             }
             AppError::Uuid(e) => {
                 tracing::error!("UUID error: {}", e);
-                (StatusCode::BAD_REQUEST, "Invalid UUID".to_string(), "UUID_ERROR")
+                "Invalid UUID".to_string()
             }
             AppError::Jwt(e) => {
                 tracing::debug!("JWT error: {}", e);
-                (StatusCode::UNAUTHORIZED, "Invalid token".to_string(), "JWT_ERROR")
-            }
-            AppError::ValidatorError(e) => {
-                let validation_errors = e
-                    .field_errors()
-                    .iter()
-                    .map(|(field, errors)| {
-                        (
-                            field.to_string(),
-                            errors.iter().map(|e| e.message.as_ref().unwrap_or(&"Invalid value".into()).to_string()).collect::<Vec<_>>()
-                        )
-                    })
-                    .collect::<std::collections::HashMap<String, Vec<String>>>();
-                    
-                return (
-                    StatusCode::BAD_REQUEST,
-                    Json(json!({
-                        "error": {
-                            "code": "VALIDATION_ERROR",
-                            "message": "Validation failed",
-                            "details": validation_errors
-                        }
-                    }))
-                ).into_response();
-            }
-            AppError::Parse(msg) => {
-                (StatusCode::BAD_REQUEST, msg.clone(), "PARSE_ERROR")
+                "Invalid token".to_string()
             }
+            AppError::ValidatorError(_) => "Validation failed".to_string(),
+            AppError::Authentication(msg)
+            | AppError::Authorization(msg)
+            | AppError::Validation(msg)
+            | AppError::NotFound(msg)
+            | AppError::Conflict(msg)
+            | AppError::BadRequest(msg)
+            | AppError::ServiceUnavailable(msg)
+            | AppError::FileUpload(msg)
+            | AppError::ImageProcessing(msg)
+            | AppError::Parse(msg)
+            | AppError::Coded { message: msg, .. } => msg.clone(),
+        }
+    }
+
+    /// 字段级的校验错误，错误信息按当前请求的语言翻译
+    fn field_errors(&self) -> Vec<FieldError> {
+        let AppError::ValidatorError(e) = self else {
+            return Vec::new();
         };
+        let mut field_errors = Vec::new();
+        collect_field_errors(e, "", &mut field_errors);
+        field_errors.sort_by(|a, b| a.field.cmp(&b.field));
+        field_errors
+    }
+}
 
-        let body = Json(json!({
-            "error": {
-                "code": error_code,
-                "message": error_message
+/// 展开嵌套结构和列表的校验错误，字段路径如 `items[0].name`
+fn collect_field_errors(errors: &validator::ValidationErrors, prefix: &str, out: &mut Vec<FieldError>) {
+    for (field, kind) in errors.errors() {
+        let path = if prefix.is_empty() { field.to_string() } else { format!("{}.{}", prefix, field) };
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                out.extend(errors.iter().map(|error| FieldError {
+                    field: path.clone(),
+                    code: error.code.to_string(),
+                    message: i18n::translate_current(error.message.as_deref().unwrap_or("Invalid value")),
+                    // 不回显提交的值，避免把密码之类的内容写进响应
+                    params: error
+                        .params
+                        .iter()
+                        .filter(|(name, _)| **name != "value")
+                        .map(|(name, value)| (name.to_string(), value.clone()))
+                        .collect(),
+                }));
+            }
+            ValidationErrorsKind::Struct(errors) => collect_field_errors(errors, &path, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, errors) in items {
+                    collect_field_errors(errors, &format!("{}[{}]", path, index), out);
+                }
             }
-        }));
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let code = self.code();
+        let message = self.client_message();
+        let body = ErrorResponse::new(code, message)
+            .with_field_errors(self.field_errors())
+            .with_request_id(current_request_id());
+        (self.status(), Json(body)).into_response()
+    }
+}
 
-        (status, body).into_response()
+/// GraphQL 错误的 `message` 与 REST 接口相同，错误码和请求 ID 放在 `extensions` 中
+impl async_graphql::ErrorExtensions for AppError {
+    fn extend(&self) -> async_graphql::Error {
+        let code = self.code();
+        let request_id = current_request_id();
+        async_graphql::Error::new(self.client_message()).extend_with(|_, extensions| {
+            extensions.set("code", code.as_str());
+            if let Some(request_id) = request_id {
+                extensions.set("request_id", request_id);
+            }
+        })
     }
 }
 
@@ -219,6 +409,10 @@ impl AppError {
     pub fn validation(msg: &str) -> Self {
         Self::Validation(msg.to_string())
     }
+
+    pub fn coded(code: ErrorCode, msg: &str) -> Self {
+        Self::Coded { code, message: msg.to_string() }
+    }
 }
 
 // 从其他错误类型转换
@@ -232,4 +426,38 @@ impl From<SoulCoreError> for AppError {
     fn from(err: SoulCoreError) -> Self {
         AppError::Database(surrealdb::Error::Api(surrealdb::error::Api::Query(err.to_string())))
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use validator::Validate;
+
+    #[derive(Validate)]
+    struct Seo {
+        #[validate(length(max = 5, message = "Too long"))]
+        meta_title: String,
+    }
+
+    #[derive(Validate)]
+    struct Draft {
+        #[validate(length(min = 1, max = 10))]
+        title: String,
+        #[validate]
+        seo: Seo,
+    }
+
+    #[test]
+    fn test_field_errors_flatten_nested_structs() {
+        let draft = Draft { title: String::new(), seo: Seo { meta_title: "far too long".to_string() } };
+        let error = AppError::ValidatorError(draft.validate().unwrap_err());
+        let field_errors = error.field_errors();
+
+        let fields: Vec<&str> = field_errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["seo.meta_title", "title"]);
+        assert_eq!(field_errors[1].code, "length");
+        assert_eq!(field_errors[1].message, "Invalid value");
+        assert_eq!(field_errors[1].params.get("max"), Some(&serde_json::json!(10)));
+        assert!(!field_errors[0].params.contains_key("value"));
+    }
+}
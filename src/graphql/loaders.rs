@@ -0,0 +1,102 @@
+//! 按请求批量加载关联数据。同一层级的字段解析时收集所有键，合并为一次服务调用，
+//! 文章列表的作者、标签、出版物和评论各只查询一次。
+
+use crate::{
+    error::AppError,
+    models::{
+        article::{Article, AuthorInfo, TagInfo},
+        comment::Comment,
+        id::bare_id,
+        publication::Publication,
+    },
+    services::{ArticleService, CommentService, PublicationService},
+};
+use async_graphql::dataloader::Loader;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 加载器的错误在多个字段间共享，需要可以克隆
+pub type LoaderError = Arc<AppError>;
+
+/// 文章、出版物等的键统一去掉表名前缀
+fn bare_keys(table: &str, keys: &[String]) -> Vec<String> {
+    keys.iter().map(|key| bare_id(table, key).to_string()).collect()
+}
+
+/// 按用户 ID 加载作者资料
+pub struct AuthorLoader(pub ArticleService);
+
+#[async_trait::async_trait]
+impl Loader<String> for AuthorLoader {
+    type Value = AuthorInfo;
+    type Error = LoaderError;
+
+    async fn load(&self, keys: &[String]) -> Result<HashMap<String, AuthorInfo>, LoaderError> {
+        Ok(self.0.get_authors_by_user_ids(keys).await?)
+    }
+}
+
+/// 按笔名 ID 加载笔名作者，键不带表名
+pub struct PseudonymLoader(pub ArticleService);
+
+#[async_trait::async_trait]
+impl Loader<String> for PseudonymLoader {
+    type Value = AuthorInfo;
+    type Error = LoaderError;
+
+    async fn load(&self, keys: &[String]) -> Result<HashMap<String, AuthorInfo>, LoaderError> {
+        Ok(self.0.get_pseudonym_authors(&bare_keys("pseudonym", keys)).await?)
+    }
+}
+
+/// 按文章 ID 加载标签，键不带表名
+pub struct ArticleTagsLoader(pub ArticleService);
+
+#[async_trait::async_trait]
+impl Loader<String> for ArticleTagsLoader {
+    type Value = Vec<TagInfo>;
+    type Error = LoaderError;
+
+    async fn load(&self, keys: &[String]) -> Result<HashMap<String, Vec<TagInfo>>, LoaderError> {
+        Ok(self.0.get_tags_for_articles(&bare_keys("article", keys)).await?)
+    }
+}
+
+/// 按 ID 加载已发布的文章（搜索结果只有 ID），键不带表名
+pub struct ArticleLoader(pub ArticleService);
+
+#[async_trait::async_trait]
+impl Loader<String> for ArticleLoader {
+    type Value = Article;
+    type Error = LoaderError;
+
+    async fn load(&self, keys: &[String]) -> Result<HashMap<String, Article>, LoaderError> {
+        Ok(self.0.get_published_articles_by_ids(&bare_keys("article", keys)).await?)
+    }
+}
+
+/// 按出版物 ID 加载出版物，键不带表名
+pub struct PublicationLoader(pub PublicationService);
+
+#[async_trait::async_trait]
+impl Loader<String> for PublicationLoader {
+    type Value = Publication;
+    type Error = LoaderError;
+
+    async fn load(&self, keys: &[String]) -> Result<HashMap<String, Publication>, LoaderError> {
+        Ok(self.0.get_publications_by_ids(&bare_keys("publication", keys)).await?)
+    }
+}
+
+/// 按文章 ID 加载公开显示的评论，键不带表名
+pub struct ArticleCommentsLoader(pub CommentService);
+
+#[async_trait::async_trait]
+impl Loader<String> for ArticleCommentsLoader {
+    type Value = Vec<Comment>;
+    type Error = LoaderError;
+
+    async fn load(&self, keys: &[String]) -> Result<HashMap<String, Vec<Comment>>, LoaderError> {
+        Ok(self.0.get_comments_for_articles(&bare_keys("article", keys)).await?)
+    }
+}
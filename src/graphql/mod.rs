@@ -0,0 +1,208 @@
+//! `/api/blog/graphql` 的查询接口（只读）。
+//!
+//! 前端可以在一次请求中取到文章、作者、标签和评论。查询直接调用现有服务，规则与 REST
+//! 接口相同：未发布的文章只有作者可见，笔名文章不透露作者账号，付费文章按订阅截断正文。
+//! 关联数据由 `loaders` 中的加载器按请求批量获取，列表不会逐条查询。
+
+pub mod loaders;
+pub mod types;
+
+use crate::{
+    error::AppError,
+    models::{article::ArticleQuery, id::bare_id, search::SearchQuery},
+    services::auth::User as AuthUser,
+    state::AppState,
+};
+use async_graphql::{
+    dataloader::DataLoader, Context, EmptyMutation, EmptySubscription, ErrorExtensions, Object, Request, Result,
+    Schema,
+};
+use loaders::{
+    ArticleCommentsLoader, ArticleLoader, ArticleTagsLoader, AuthorLoader, PseudonymLoader, PublicationLoader,
+};
+use std::sync::Arc;
+use types::{Article, ArticlePage, ArticleSort, Publication, SearchResults, SearchType, User};
+
+/// 列表每页数量上限
+pub const MAX_PAGE_SIZE: i32 = 50;
+/// 查询嵌套层数上限，防止构造过深的查询
+const MAX_QUERY_DEPTH: usize = 10;
+
+pub type BlogSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+pub fn build_schema() -> BlogSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .limit_depth(MAX_QUERY_DEPTH)
+        .finish()
+}
+
+/// 为一次请求附加应用状态、当前用户和加载器。加载器的缓存只在这次请求内有效
+pub fn prepare_request(request: Request, state: Arc<AppState>, viewer: Option<AuthUser>) -> Request {
+    let article_service = state.article_service.clone();
+    let mut request = request
+        .data(DataLoader::new(AuthorLoader(article_service.clone()), tokio::spawn))
+        .data(DataLoader::new(PseudonymLoader(article_service.clone()), tokio::spawn))
+        .data(DataLoader::new(ArticleTagsLoader(article_service.clone()), tokio::spawn))
+        .data(DataLoader::new(ArticleLoader(article_service), tokio::spawn))
+        .data(DataLoader::new(PublicationLoader(state.publication_service.clone()), tokio::spawn))
+        .data(DataLoader::new(ArticleCommentsLoader(state.comment_service.clone()), tokio::spawn))
+        .data(state);
+    if let Some(viewer) = viewer {
+        request = request.data(viewer);
+    }
+    request
+}
+
+/// 服务层的错误转换为 GraphQL 错误时保留错误码
+pub trait IntoGraphQLResult<T> {
+    fn into_graphql(self) -> Result<T>;
+}
+
+impl<T> IntoGraphQLResult<T> for std::result::Result<T, AppError> {
+    fn into_graphql(self) -> Result<T> {
+        self.map_err(|e| e.extend())
+    }
+}
+
+impl<T> IntoGraphQLResult<T> for std::result::Result<T, Arc<AppError>> {
+    fn into_graphql(self) -> Result<T> {
+        self.map_err(|e| e.as_ref().extend())
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// 按 slug 获取文章，未发布的文章只有作者本人可以查看。不计入浏览量
+    async fn article(&self, ctx: &Context<'_>, slug: String) -> Result<Option<Article>> {
+        let state = ctx.data_unchecked::<Arc<AppState>>();
+        let viewer_id = ctx.data_opt::<AuthUser>().map(|user| user.id.as_str());
+
+        let Some(mut article) = state
+            .article_service
+            .get_article_with_details(&slug, viewer_id, None)
+            .await
+            .into_graphql()?
+        else {
+            return Ok(None);
+        };
+        if !article.status.can_be_viewed_by_public() && viewer_id != Some(article.author_id.as_str()) {
+            return Ok(None);
+        }
+
+        if let Some(publication) = &article.publication {
+            article.content_html = state
+                .content_transform_service
+                .apply(&publication.id, article.content_html)
+                .await;
+        }
+        article.content_html = state
+            .cta_service
+            .apply(&article.author_id, &article.id, article.content_html)
+            .await;
+
+        Ok(Some(article.into()))
+    }
+
+    /// 已发布的文章列表
+    async fn articles(
+        &self,
+        ctx: &Context<'_>,
+        page: Option<i32>,
+        limit: Option<i32>,
+        tag: Option<String>,
+        featured: Option<bool>,
+        sort: Option<ArticleSort>,
+    ) -> Result<ArticlePage> {
+        types::find_articles(ctx, page, limit, ArticleQuery {
+            tag,
+            featured,
+            sort: sort.map(|sort| sort.as_str().to_string()),
+            ..Default::default()
+        })
+        .await
+    }
+
+    async fn user(&self, ctx: &Context<'_>, username: String) -> Result<Option<User>> {
+        let state = ctx.data_unchecked::<Arc<AppState>>();
+        let profile = state.user_service.get_profile_by_username(&username).await.into_graphql()?;
+        Ok(profile.map(User::from))
+    }
+
+    async fn publication(&self, ctx: &Context<'_>, slug: String) -> Result<Option<Publication>> {
+        let state = ctx.data_unchecked::<Arc<AppState>>();
+        let publication = state
+            .publication_service
+            .get_publication(&slug, None)
+            .await
+            .into_graphql()?;
+        Ok(publication.map(|response| response.publication.into()))
+    }
+
+    /// 全局搜索，结果中的文章可以继续查询作者、标签和评论
+    async fn search(
+        &self,
+        ctx: &Context<'_>,
+        q: String,
+        search_type: Option<SearchType>,
+        page: Option<i32>,
+        limit: Option<i32>,
+    ) -> Result<SearchResults> {
+        let state = ctx.data_unchecked::<Arc<AppState>>();
+        let results = state
+            .search()
+            .into_graphql()?
+            .search(SearchQuery {
+                q,
+                search_type: search_type.map(Into::into),
+                page,
+                limit: limit.map(|limit| limit.clamp(1, MAX_PAGE_SIZE)),
+                author: None,
+                tag: None,
+                publication: None,
+                date_from: None,
+                date_to: None,
+                is_paid: None,
+                fuzzy: None,
+                mode: None,
+            })
+            .await
+            .into_graphql()?;
+
+        // 搜索结果只有摘要，按 ID 批量取出文章，保持相关度顺序
+        let ids: Vec<String> = results.articles.iter().map(|a| bare_id("article", &a.id).to_string()).collect();
+        let mut articles = ctx
+            .data_unchecked::<DataLoader<ArticleLoader>>()
+            .load_many(ids.clone())
+            .await
+            .into_graphql()?;
+
+        Ok(SearchResults {
+            articles: ids
+                .iter()
+                .filter_map(|id| articles.remove(id))
+                .map(Article::from)
+                .collect(),
+            users: results.users.into_iter().map(User::from).collect(),
+            tags: results.tags.into_iter().map(Into::into).collect(),
+            publications: results.publications.into_iter().map(Into::into).collect(),
+            total_results: results.total_results,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_exposes_queries() {
+        let sdl = build_schema().sdl();
+        for field in ["article(slug: String!)", "articles(", "user(username: String!)", "publication(slug: String!)", "search("] {
+            assert!(sdl.contains(field), "missing {}", field);
+        }
+        assert!(sdl.contains("comments: [Comment!]!"));
+        assert!(sdl.contains("PENDING_REVIEW"));
+    }
+}
@@ -0,0 +1,468 @@
+//! GraphQL 输出类型。字段与 REST 接口的模型一致，关联数据（作者、标签、出版物、评论）
+//! 在被查询时才通过加载器批量获取。
+
+use super::{
+    loaders::{ArticleCommentsLoader, ArticleTagsLoader, AuthorLoader, PseudonymLoader, PublicationLoader},
+    IntoGraphQLResult, MAX_PAGE_SIZE,
+};
+use crate::{
+    models::{
+        article::{self as article_model, ArticleQuery, ArticleResponse, AuthorInfo, TagInfo},
+        comment,
+        id::bare_id,
+        publication,
+        search::{PublicationSearchResult, TagSearchResult, UserSearchResult},
+        user::UserProfile,
+    },
+    services::database::PaginatedResult,
+    state::AppState,
+};
+use async_graphql::{dataloader::DataLoader, ComplexObject, Context, Enum, Result, SimpleObject};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "crate::models::article::ArticleStatus")]
+pub enum ArticleStatus {
+    Draft,
+    PendingReview,
+    Scheduled,
+    Published,
+    Unlisted,
+    Archived,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+#[graphql(remote = "crate::models::search::SearchType")]
+pub enum SearchType {
+    All,
+    Articles,
+    Users,
+    Tags,
+    Publications,
+}
+
+/// 文章列表的排序，与 REST 接口的 `sort` 参数相同
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum ArticleSort {
+    Newest,
+    Oldest,
+    Popular,
+    Trending,
+}
+
+impl ArticleSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArticleSort::Newest => "newest",
+            ArticleSort::Oldest => "oldest",
+            ArticleSort::Popular => "popular",
+            ArticleSort::Trending => "trending",
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Author {
+    pub id: String,
+    pub username: String,
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+    pub is_verified: bool,
+    /// 笔名作者：`id` 和 `username` 属于笔名资料，不是用户账号
+    pub is_pseudonym: bool,
+}
+
+impl From<AuthorInfo> for Author {
+    fn from(author: AuthorInfo) -> Self {
+        Self {
+            id: author.id,
+            username: author.username,
+            display_name: author.display_name,
+            avatar_url: author.avatar_url,
+            is_verified: author.is_verified,
+            is_pseudonym: author.is_pseudonym,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Tag {
+    pub id: String,
+    pub name: String,
+    pub slug: String,
+}
+
+impl From<TagInfo> for Tag {
+    fn from(tag: TagInfo) -> Self {
+        Self { id: tag.id, name: tag.name, slug: tag.slug }
+    }
+}
+
+impl From<TagSearchResult> for Tag {
+    fn from(tag: TagSearchResult) -> Self {
+        Self { id: tag.id, name: tag.name, slug: tag.slug }
+    }
+}
+
+/// 作者的来源：单篇文章已经解析好，列表中的文章按账号或笔名批量加载
+enum AuthorSource {
+    Resolved(AuthorInfo),
+    User(String),
+    Pseudonym(String),
+}
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Article {
+    pub id: String,
+    pub slug: String,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub excerpt: Option<String>,
+    pub cover_image_url: Option<String>,
+    /// 正文 HTML，只在 `article(slug:)` 中返回。付费文章没有访问权限时只包含预览部分
+    pub content_html: Option<String>,
+    pub status: ArticleStatus,
+    pub is_paid_content: bool,
+    pub is_featured: bool,
+    pub reading_time: i32,
+    pub view_count: i64,
+    pub clap_count: i64,
+    pub comment_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub published_at: Option<DateTime<Utc>>,
+    #[graphql(skip)]
+    author_source: AuthorSource,
+    #[graphql(skip)]
+    preloaded_tags: Option<Vec<TagInfo>>,
+    #[graphql(skip)]
+    publication_id: Option<String>,
+}
+
+impl From<article_model::Article> for Article {
+    fn from(article: article_model::Article) -> Self {
+        // 笔名文章不透露作者账号
+        let author_source = match article.pseudonym_id {
+            Some(pseudonym_id) => AuthorSource::Pseudonym(pseudonym_id),
+            None => AuthorSource::User(article.author_id),
+        };
+        Self {
+            id: article.id,
+            slug: article.slug,
+            title: article.title,
+            subtitle: article.subtitle,
+            excerpt: article.excerpt,
+            cover_image_url: article.cover_image_url,
+            content_html: None,
+            status: article.status.into(),
+            is_paid_content: article.is_paid_content,
+            is_featured: article.is_featured,
+            reading_time: article.reading_time,
+            view_count: article.view_count,
+            clap_count: article.clap_count,
+            comment_count: article.comment_count,
+            created_at: article.created_at,
+            published_at: article.published_at,
+            author_source,
+            preloaded_tags: None,
+            publication_id: article.publication_id,
+        }
+    }
+}
+
+impl From<ArticleResponse> for Article {
+    fn from(article: ArticleResponse) -> Self {
+        Self {
+            id: article.id,
+            slug: article.slug,
+            title: article.title,
+            subtitle: article.subtitle,
+            excerpt: article.excerpt,
+            cover_image_url: article.cover_image_url,
+            content_html: Some(article.content_html),
+            status: article.status.into(),
+            is_paid_content: article.is_paid_content,
+            is_featured: article.is_featured,
+            reading_time: article.reading_time,
+            view_count: article.view_count,
+            clap_count: article.clap_count,
+            comment_count: article.comment_count,
+            created_at: article.created_at,
+            published_at: article.published_at,
+            author_source: AuthorSource::Resolved(article.author),
+            preloaded_tags: Some(article.tags),
+            publication_id: article.publication.map(|p| p.id),
+        }
+    }
+}
+
+#[ComplexObject]
+impl Article {
+    async fn author(&self, ctx: &Context<'_>) -> Result<Author> {
+        let author = match &self.author_source {
+            AuthorSource::Resolved(author) => author.clone(),
+            AuthorSource::User(user_id) => ctx
+                .data_unchecked::<DataLoader<AuthorLoader>>()
+                .load_one(user_id.clone())
+                .await
+                .into_graphql()?
+                .unwrap_or_else(|| AuthorInfo::unknown(user_id)),
+            // 笔名被删除时显示为匿名作者
+            AuthorSource::Pseudonym(pseudonym_id) => ctx
+                .data_unchecked::<DataLoader<PseudonymLoader>>()
+                .load_one(bare_id("pseudonym", pseudonym_id).to_string())
+                .await
+                .into_graphql()?
+                .unwrap_or_else(AuthorInfo::anonymous),
+        };
+        Ok(author.into())
+    }
+
+    async fn tags(&self, ctx: &Context<'_>) -> Result<Vec<Tag>> {
+        let tags = match &self.preloaded_tags {
+            Some(tags) => tags.clone(),
+            None => ctx
+                .data_unchecked::<DataLoader<ArticleTagsLoader>>()
+                .load_one(bare_id("article", &self.id).to_string())
+                .await
+                .into_graphql()?
+                .unwrap_or_default(),
+        };
+        Ok(tags.into_iter().map(Tag::from).collect())
+    }
+
+    async fn publication(&self, ctx: &Context<'_>) -> Result<Option<Publication>> {
+        let Some(publication_id) = &self.publication_id else {
+            return Ok(None);
+        };
+        let publication = ctx
+            .data_unchecked::<DataLoader<PublicationLoader>>()
+            .load_one(bare_id("publication", publication_id).to_string())
+            .await
+            .into_graphql()?;
+        Ok(publication.map(Publication::from))
+    }
+
+    /// 公开显示的评论，按时间倒序平铺，回复通过 `parentId` 对应到上级评论
+    async fn comments(&self, ctx: &Context<'_>) -> Result<Vec<Comment>> {
+        let comments = ctx
+            .data_unchecked::<DataLoader<ArticleCommentsLoader>>()
+            .load_one(bare_id("article", &self.id).to_string())
+            .await
+            .into_graphql()?
+            .unwrap_or_default();
+        Ok(comments.into_iter().map(Comment::from).collect())
+    }
+}
+
+/// 分页的文章列表
+#[derive(SimpleObject)]
+pub struct ArticlePage {
+    pub items: Vec<Article>,
+    pub total: i64,
+    pub page: i32,
+    pub per_page: i32,
+    pub total_pages: i64,
+}
+
+impl From<PaginatedResult<article_model::Article>> for ArticlePage {
+    fn from(result: PaginatedResult<article_model::Article>) -> Self {
+        Self {
+            items: result.data.into_iter().map(Article::from).collect(),
+            total: result.total as i64,
+            page: result.page as i32,
+            per_page: result.per_page as i32,
+            total_pages: result.total_pages as i64,
+        }
+    }
+}
+
+/// 按条件查询已发布的文章，每页数量不超过 `MAX_PAGE_SIZE`
+pub async fn find_articles(
+    ctx: &Context<'_>,
+    page: Option<i32>,
+    limit: Option<i32>,
+    query: ArticleQuery,
+) -> Result<ArticlePage> {
+    let state = ctx.data_unchecked::<Arc<AppState>>();
+    let query = ArticleQuery {
+        status: None,
+        page: Some(page.unwrap_or(1).max(1) as usize),
+        limit: Some(limit.unwrap_or(20).clamp(1, MAX_PAGE_SIZE) as usize),
+        ..query
+    };
+    let result = state.article_service.find_articles(query).await.into_graphql()?;
+    Ok(result.into())
+}
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Comment {
+    pub id: String,
+    pub parent_id: Option<String>,
+    pub content: String,
+    pub is_author_response: bool,
+    pub clap_count: i64,
+    pub is_edited: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// 导入的访客评论显示的作者名，此时 `author` 为空
+    pub guest_name: Option<String>,
+    #[graphql(skip)]
+    author_id: String,
+}
+
+impl From<comment::Comment> for Comment {
+    fn from(comment: comment::Comment) -> Self {
+        Self {
+            id: comment.id,
+            parent_id: comment.parent_id,
+            content: comment.content,
+            is_author_response: comment.is_author_response,
+            clap_count: comment.clap_count,
+            is_edited: comment.is_edited,
+            created_at: comment.created_at,
+            updated_at: comment.updated_at,
+            guest_name: comment.guest_name,
+            author_id: comment.author_id,
+        }
+    }
+}
+
+#[ComplexObject]
+impl Comment {
+    async fn author(&self, ctx: &Context<'_>) -> Result<Option<Author>> {
+        if self.guest_name.is_some() {
+            return Ok(None);
+        }
+        let author = ctx
+            .data_unchecked::<DataLoader<AuthorLoader>>()
+            .load_one(self.author_id.clone())
+            .await
+            .into_graphql()?;
+        Ok(author.map(Author::from))
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct User {
+    /// 用户账号 ID
+    pub id: String,
+    pub username: String,
+    pub display_name: String,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub is_verified: bool,
+    pub follower_count: i64,
+    pub article_count: i64,
+}
+
+impl From<UserProfile> for User {
+    fn from(profile: UserProfile) -> Self {
+        Self {
+            id: profile.user_id,
+            username: profile.username,
+            display_name: profile.display_name,
+            bio: profile.bio,
+            avatar_url: profile.avatar_url,
+            is_verified: profile.is_verified,
+            follower_count: profile.follower_count,
+            article_count: profile.article_count,
+        }
+    }
+}
+
+impl From<UserSearchResult> for User {
+    fn from(user: UserSearchResult) -> Self {
+        Self {
+            id: user.user_id,
+            username: user.username,
+            display_name: user.display_name,
+            bio: user.bio,
+            avatar_url: user.avatar_url,
+            is_verified: user.is_verified,
+            follower_count: user.follower_count,
+            article_count: user.article_count,
+        }
+    }
+}
+
+#[ComplexObject]
+impl User {
+    /// 已发布的文章，不含笔名文章
+    async fn articles(&self, ctx: &Context<'_>, page: Option<i32>, limit: Option<i32>) -> Result<ArticlePage> {
+        find_articles(ctx, page, limit, ArticleQuery {
+            author: Some(self.id.clone()),
+            ..Default::default()
+        })
+        .await
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct Publication {
+    pub id: String,
+    pub name: String,
+    pub slug: String,
+    pub description: Option<String>,
+    pub tagline: Option<String>,
+    pub logo_url: Option<String>,
+    pub member_count: i64,
+    pub article_count: i64,
+    pub follower_count: i64,
+}
+
+impl From<publication::Publication> for Publication {
+    fn from(publication: publication::Publication) -> Self {
+        Self {
+            id: publication.id,
+            name: publication.name,
+            slug: publication.slug,
+            description: publication.description,
+            tagline: publication.tagline,
+            logo_url: publication.logo_url,
+            member_count: publication.member_count,
+            article_count: publication.article_count,
+            follower_count: publication.follower_count,
+        }
+    }
+}
+
+impl From<PublicationSearchResult> for Publication {
+    fn from(publication: PublicationSearchResult) -> Self {
+        Self {
+            id: publication.id,
+            name: publication.name,
+            slug: publication.slug,
+            description: publication.description,
+            tagline: publication.tagline,
+            logo_url: publication.logo_url,
+            member_count: publication.member_count,
+            article_count: publication.article_count,
+            follower_count: publication.follower_count,
+        }
+    }
+}
+
+#[ComplexObject]
+impl Publication {
+    async fn articles(&self, ctx: &Context<'_>, page: Option<i32>, limit: Option<i32>) -> Result<ArticlePage> {
+        find_articles(ctx, page, limit, ArticleQuery {
+            publication: Some(self.id.clone()),
+            ..Default::default()
+        })
+        .await
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct SearchResults {
+    pub articles: Vec<Article>,
+    pub users: Vec<User>,
+    pub tags: Vec<Tag>,
+    pub publications: Vec<Publication>,
+    pub total_results: i64,
+}
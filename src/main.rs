@@ -1,20 +1,19 @@
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 use axum::{
     routing::{Router, get, post},
     Extension,
-    http::{Method, HeaderValue},
     middleware,
 };
 use tower_http::{
-    cors::{CorsLayer, Any},
     compression::CompressionLayer,
     trace::TraceLayer,
 };
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use tracing::{info, warn, error};
+use tracing::{debug, info, warn, error};
 use tokio::time::{interval, Duration};
 
 mod routes;
+mod graphql;
 mod models;
 mod services;
 mod config;
@@ -28,31 +27,7 @@ mod metrics;
 use crate::{
     config::Config,
     state::AppState,
-    services::{
-        Database,
-        AuthService,
-        ArticleService,
-        UserService,
-        CommentService,
-        NotificationService,
-        SearchService,
-        MediaService,
-        RecommendationService,
-        PublicationService,
-        BookmarkService,
-        FollowService,
-        SeriesService,
-        AnalyticsService,
-        SubscriptionService,
-        PaymentService,
-        RevenueService,
-        StripeService,
-        WebSocketService,
-        RealtimeService,
-        DomainService,
-        domain::DomainConfig,
-    },
-    models::stripe::StripeConfig,
+    services::Database,
 };
 
 #[tokio::main]
@@ -103,86 +78,21 @@ async fn main() -> anyhow::Result<()> {
         }
     });
 
-    // 初始化所有服务
-    let auth_service = AuthService::new(&config).await?;
-    let article_service = ArticleService::new(db.clone()).await?;
-    let user_service = UserService::new(db.clone()).await?;
-    let comment_service = CommentService::new(db.clone()).await?;
-    let notification_service = NotificationService::new(db.clone(), &config).await?;
-    let search_service = SearchService::new(db.clone()).await?;
-    let media_service = MediaService::new(&config, db.clone()).await?;
-    let recommendation_service = RecommendationService::new(db.clone()).await?;
-    let publication_service = PublicationService::new(db.clone()).await?;
-    let bookmark_service = BookmarkService::new(db.clone()).await?;
-    let follow_service = FollowService::new(db.clone(), notification_service.clone()).await?;
-    let tag_service = crate::services::tag::TagService::new(db.clone()).await?;
-    let series_service = SeriesService::new(db.clone()).await?;
-    let analytics_service = AnalyticsService::new(db.clone()).await?;
-    let stripe_service = StripeService::new(db.clone(), StripeConfig::default()).await?;
-    let stripe_service_arc = Arc::new(stripe_service.clone());
-    let subscription_service = SubscriptionService::new(db.clone(), stripe_service_arc.clone()).await?;
-    let subscription_service_arc = Arc::new(subscription_service.clone());
-    let payment_service = PaymentService::new(
-        db.clone(),
-        subscription_service_arc.clone(),
-        stripe_service_arc.clone(),
-    )
-    .await?;
-    let revenue_service = RevenueService::new(db.clone(), stripe_service_arc.clone()).await?;
-    let websocket_service = WebSocketService::new(db.clone()).await?;
-    let realtime_service = RealtimeService::new(Arc::new(websocket_service.clone()), Arc::new(notification_service.clone()));
-    
-    // Initialize domain service with default config
-    let domain_config = DomainConfig {
-        base_domain: config.base_domain.clone().unwrap_or_else(|| "platform.local".to_string()),
-        dns_verification_timeout: 300, // 5 minutes
-        ssl_provider_endpoint: config.ssl_provider_endpoint.clone(),
-        ssl_provider_api_key: config.ssl_provider_api_key.clone(),
-        auto_provision_ssl: config.auto_provision_ssl.unwrap_or(false),
-        ssl_webhook_url: config.ssl_webhook_url.clone(),
-    };
-    let domain_service = DomainService::new(db.clone(), domain_config).await?;
-
-    // 创建应用状态
-    let app_state = Arc::new(AppState {
-        config: config.clone(),
-        db: (*db).clone(),
-        auth_service,
-        article_service,
-        user_service,
-        comment_service,
-        notification_service,
-        search_service,
-        media_service,
-        recommendation_service,
-        publication_service,
-        bookmark_service,
-        follow_service,
-        tag_service,
-        series_service,
-        analytics_service,
-        subscription_service,
-        payment_service,
-        revenue_service,
-        stripe_service,
-        websocket_service,
-        realtime_service,
-        domain_service,
-    });
+    // 初始化所有服务并创建应用状态
+    let app_state = Arc::new(AppState::builder(config.clone(), db.clone()).build().await?);
 
     // 启动后台任务
     start_background_tasks(app_state.clone()).await;
 
-    // 配置 CORS
-    let cors = CorsLayer::new()
-        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE, Method::OPTIONS])
-        .allow_headers(Any)
-        .allow_origin(
-            config.cors_allowed_origins
-                .split(',')
-                .map(|origin| origin.parse::<HeaderValue>().unwrap())
-                .collect::<Vec<_>>(),
-        );
+    // RSS/Atom 订阅源（需要启用 rss feature）
+    let feeds = Router::new();
+    #[cfg(feature = "rss")]
+    let feeds = feeds.nest("/api/blog/feeds", routes::feeds::router());
+
+    // ACME HTTP-01 验证（需要启用 acme feature）
+    let acme = Router::new();
+    #[cfg(feature = "acme")]
+    let acme = acme.merge(routes::domain::acme_router());
 
     // 构建应用路由
     let app = Router::new()
@@ -208,9 +118,31 @@ async fn main() -> anyhow::Result<()> {
         .nest("/api/blog/ws", routes::websocket::router())
         .nest("/api/blog/domains", routes::domain::router())
         .nest("/api/blog/diagnostics", routes::diagnostics::router())
+        .nest("/api/blog/reading-queue", routes::reading_queue::router())
+        .nest("/api/blog/lifecycle", routes::lifecycle::router())
+        .nest("/api/blog/trash", routes::trash::router())
+        .nest("/api/blog/newsletters", routes::newsletters::router())
+        .nest("/api/blog/syndication", routes::syndication::router())
+        .nest("/api/blog/attachments", routes::attachments::router())
+        .nest("/api/blog/ctas", routes::ctas::router())
+        .nest("/api/blog/pseudonyms", routes::pseudonyms::router())
+        .nest("/api/blog/gifts", routes::gifts::router())
+        .nest("/api/blog/notifications", routes::notifications::router())
+        .nest("/api/blog/oauth", routes::oauth::router())
+        .nest("/api/blog/reports", routes::reports::router())
+        .nest("/api/blog/graphql", routes::graphql::router())
+        .nest(
+            "/api/blog/admin",
+            routes::admin::router().route_layer(middleware::from_fn(utils::middleware::admin_middleware)),
+        )
+        .merge(feeds)
+        .merge(acme)
         
         // Health check endpoints (no domain context needed)
         .route("/health", get(health_check))
+
+        // OpenAPI document and Swagger UI
+        .merge(routes::openapi::router())
         
         // Domain-specific routes (work with custom domains and subdomains)
         // These routes are merged at the root level and rely on domain routing middleware
@@ -218,7 +150,16 @@ async fn main() -> anyhow::Result<()> {
         .merge(routes::publication_content::router())
         
         // Apply middleware layers (order matters - they are applied in reverse)
-        .layer(cors)
+        // ETag 和条件请求，放在最内层，计算的是压缩前的响应体
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            utils::middleware::http_cache_middleware,
+        ))
+        // CORS：平台前端来源全局生效，出版物配置的来源只对其内容接口生效
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            utils::middleware::cors_middleware,
+        ))
         .layer(CompressionLayer::new())
         .layer(TraceLayer::new_for_http())
         
@@ -228,6 +169,12 @@ async fn main() -> anyhow::Result<()> {
             utils::middleware::domain_routing_middleware,
         ))
         
+        // Visitor country for audience analytics
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            utils::middleware::geoip_middleware,
+        ))
+        
         // Debug middleware to log requests
         .layer(middleware::from_fn(|req: axum::http::Request<axum::body::Body>, next: axum::middleware::Next<axum::body::Body>| async move {
             let path = req.uri().path().to_string();
@@ -238,16 +185,16 @@ async fn main() -> anyhow::Result<()> {
             res
         }))
         
-        // Authentication middleware (can use publication context if available)
+        // Rate limiting (inside auth so per-user policies see the current user)
         .layer(middleware::from_fn_with_state(
             app_state.clone(),
-            utils::middleware::auth_middleware,
+            utils::middleware::rate_limit_middleware,
         ))
         
-        // Rate limiting
+        // Authentication middleware (can use publication context if available)
         .layer(middleware::from_fn_with_state(
             app_state.clone(),
-            utils::middleware::rate_limit_middleware,
+            utils::middleware::auth_middleware,
         ))
         
         // Logging and security
@@ -257,9 +204,21 @@ async fn main() -> anyhow::Result<()> {
         .layer(middleware::from_fn(
             utils::middleware::security_headers_middleware,
         ))
+        .layer(middleware::from_fn(
+            utils::middleware::error_envelope_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            utils::middleware::locale_middleware,
+        ))
         .layer(middleware::from_fn(
             utils::middleware::request_id_middleware,
         ))
+        // 最先解析客户端 IP，限流、日志和统计都使用解析后的地址
+        .layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            utils::middleware::client_ip_middleware,
+        ))
         
         .with_state(app_state);
 
@@ -281,12 +240,13 @@ async fn main() -> anyhow::Result<()> {
     info!("Starting server on http://{}", addr);
 
     axum::Server::bind(&addr.parse()?)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await?;
 
     Ok(())
 }
 
+#[utoipa::path(get, path = "/health", tag = "health")]
 async fn health_check() -> &'static str {
     "Rainbow-Blog is running!"
 }
@@ -321,6 +281,42 @@ async fn auto_start_database(config: &Config) -> anyhow::Result<()> {
 async fn start_background_tasks(app_state: Arc<AppState>) {
     info!("Starting background tasks...");
 
+    // 数据库实时订阅（断线后自动重新订阅）
+    if app_state.config.live_queries_enabled {
+        tokio::spawn(app_state.live_query_service.clone().run());
+    }
+
+    // 把旧版点赞数据迁移到反应表（只执行一次）
+    let reaction_state = app_state.clone();
+    tokio::spawn(async move {
+        if let Err(e) = reaction_state.reaction_service.migrate_claps().await {
+            error!("Failed to migrate claps to reactions: {}", e);
+        }
+    });
+
+    // 继续投递重启前未发送完的 Newsletter
+    let newsletter_state = app_state.clone();
+    tokio::spawn(async move {
+        match newsletter_state.newsletter_service.resume_unfinished().await {
+            Ok(0) => {}
+            Ok(count) => info!("Resumed delivery of {} newsletters", count),
+            Err(e) => error!("Failed to resume newsletter delivery: {}", e),
+        }
+    });
+
+    // 实时热度榜刷新任务
+    let popularity_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(10)); // 每10秒重新排序一次
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = popularity_state.popularity_service.refresh().await {
+                error!("Failed to refresh popular articles: {}", e);
+            }
+        }
+    });
+
     // 推荐系统更新任务
     let recommendation_state = app_state.clone();
     tokio::spawn(async move {
@@ -336,6 +332,159 @@ async fn start_background_tasks(app_state: Arc<AppState>) {
         }
     });
 
+    // 定时发布任务
+    let scheduled_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(60)); // 每分钟检查一次
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = scheduled_state.article_service.publish_scheduled_articles().await {
+                error!("Failed to publish scheduled articles: {}", e);
+            }
+        }
+    });
+
+    // 排期社交推广帖子发布任务
+    let share_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(60)); // 每分钟检查一次
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = share_state.social_share_service.publish_due_shares().await {
+                error!("Failed to publish scheduled social shares: {}", e);
+            }
+        }
+    });
+
+    // 域名迁入切换检测任务
+    let domain_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(300)); // 每5分钟检查一次 DNS 切换
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = domain_state.domain_service.check_pending_cutovers().await {
+                error!("Failed to check domain cutovers: {}", e);
+            }
+        }
+    });
+
+    // 发信域名 DNS 记录复查任务，记录失效后 Newsletter 回退到平台发件地址
+    let email_domain_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(86400)); // 每天复查一次
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = email_domain_state.domain_service.recheck_email_domains().await {
+                error!("Failed to recheck email sending domains: {}", e);
+            }
+        }
+    });
+
+    // 草稿和账户生命周期维护任务
+    let lifecycle_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(86400)); // 每天执行一次
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = lifecycle_state.lifecycle_service.run().await {
+                error!("Failed to run lifecycle maintenance: {}", e);
+            }
+        }
+    });
+
+    // 账户删除任务：重试中断的删除流程，宽限期结束后永久删除个人数据
+    let deletion_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(3600)); // 每小时执行一次
+
+        loop {
+            interval.tick().await;
+            match deletion_state.data_lifecycle_service.run().await {
+                Ok(report) if report.resumed > 0 || report.hard_deleted > 0 => {
+                    info!("Account deletion run: {} resumed, {} hard deleted", report.resumed, report.hard_deleted);
+                }
+                Ok(_) => {}
+                Err(e) => error!("Failed to run account deletions: {}", e),
+            }
+        }
+    });
+
+    // 回收站清理任务：永久删除超过保留期的文章和评论
+    let trash_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(86400)); // 每天执行一次
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = trash_state.trash_service.purge_expired().await {
+                error!("Failed to purge trash: {}", e);
+            }
+        }
+    });
+
+    // 创作者打款任务：结算过了等待期的收益，每月 1 日向 Connect 账户转账
+    let payout_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(3600)); // 每小时执行一次
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = payout_state.revenue_service.run_scheduled_payouts().await {
+                error!("Failed to run scheduled payouts: {}", e);
+            }
+        }
+    });
+
+    // 礼物订阅到期任务：礼物订阅没有 Stripe Webhook，到期后由这里收回阅读权限
+    let gift_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(3600)); // 每小时执行一次
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = gift_state.gift_service.expire_gift_subscriptions().await {
+                error!("Failed to expire gift subscriptions: {}", e);
+            }
+        }
+    });
+
+    // 清理过期的 Stripe 幂等键记录
+    let idempotency_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(86400)); // 每天执行一次
+
+        loop {
+            interval.tick().await;
+            // 禁用付费功能的部署没有 Stripe 请求，也就没有需要清理的记录
+            let Ok(stripe) = idempotency_state.stripe() else { continue };
+            match stripe.purge_idempotency_keys().await {
+                Ok(count) if count > 0 => info!("Purged {} expired Stripe idempotency keys", count),
+                Ok(_) => {}
+                Err(e) => error!("Failed to purge Stripe idempotency keys: {}", e),
+            }
+        }
+    });
+
+    // 重试处理失败的 Stripe Webhook 事件
+    let webhook_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(60)); // 每分钟执行一次
+
+        loop {
+            interval.tick().await;
+            match webhook_state.webhook_service.retry_failed_events().await {
+                Ok(count) if count > 0 => info!("Reprocessed {} failed Stripe webhooks", count),
+                Ok(_) => {}
+                Err(e) => error!("Failed to retry Stripe webhooks: {}", e),
+            }
+        }
+    });
+
     // 统计数据聚合任务
     let stats_state = app_state.clone();
     tokio::spawn(async move {
@@ -362,5 +511,163 @@ async fn start_background_tasks(app_state: Arc<AppState>) {
         }
     });
 
+    // 邮件通知摘要投递任务
+    let digest_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(300)); // 每5分钟检查一次到期窗口
+        
+        loop {
+            interval.tick().await;
+            if let Err(e) = digest_state.notification_service.flush_due_email_digests().await {
+                error!("Failed to flush email digests: {}", e);
+            }
+        }
+    });
+
+    // 关注动态摘要邮件
+    let activity_digest_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(3600)); // 每小时检查到期的用户
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = activity_digest_state.activity_digest_service.send_due_digests().await {
+                error!("Failed to send activity digests: {}", e);
+            }
+        }
+    });
+
+    // 信誉分定期重算任务
+    let reputation_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(3600)); // 每小时执行一次
+        
+        loop {
+            interval.tick().await;
+            if let Err(e) = reputation_state.reputation_service.refresh_stale_reputations(200).await {
+                error!("Failed to refresh user reputations: {}", e);
+            }
+        }
+    });
+
+    // 出版物流量异常检测任务
+    let anomaly_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(900)); // 每15分钟检查最近一个完整小时
+        
+        loop {
+            interval.tick().await;
+            if let Err(e) = anomaly_state.anomaly_service.scan_publications().await {
+                error!("Failed to scan publication anomalies: {}", e);
+            }
+        }
+    });
+
+    // 文章里程碑检查任务
+    let milestone_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(600)); // 每10分钟检查一次
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = milestone_state.milestone_service.check_milestones().await {
+                error!("Failed to check article milestones: {}", e);
+            }
+        }
+    });
+
+    // 数据库完整性检查任务，生成管理员查看的健康报告
+    let db_health_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(86400)); // 每天执行一次
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = db_health_state.db_health_service.run_integrity_check().await {
+                error!("Failed to run database integrity check: {}", e);
+            }
+        }
+    });
+
+    // 搜索联想索引重建任务，第一次 tick 立即执行，启动后就有索引可用
+    let suggest_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(300)); // 每5分钟重建一次
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = suggest_state.suggest_service.rebuild().await {
+                error!("Failed to rebuild search suggest index: {}", e);
+            }
+        }
+    });
+
+    // 文章向量任务，为新发布和内容有变化的文章重新计算向量（未配置向量模型时直接跳过）
+    let embedding_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(120)); // 每2分钟检查一次
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = embedding_state.embedding_service.reindex_stale().await {
+                error!("Failed to update article embeddings: {}", e);
+            }
+        }
+    });
+
+    // 文章朗读音频任务，补上发布时没有生成成功的音频，内容改动较大的文章重新生成（未配置语音合成时直接跳过）
+    let speech_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(300)); // 每5分钟检查一次
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = speech_state.speech_service.regenerate_stale().await {
+                error!("Failed to update article audio: {}", e);
+            }
+        }
+    });
+
+    // 清理过期的 OAuth 授权码和令牌
+    let oauth_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(3600)); // 每小时执行一次
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = oauth_state.oauth_service.purge_expired().await {
+                error!("Failed to purge expired OAuth tokens: {}", e);
+            }
+        }
+    });
+
+    // 清理过期会话的两步验证标记
+    let two_factor_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(3600)); // 每小时执行一次
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = two_factor_state.two_factor_service.purge_expired().await {
+                error!("Failed to purge expired two-factor sessions: {}", e);
+            }
+        }
+    });
+
+    // 清理过期的限流计数器（计数保存在数据库时）
+    let rate_limit_state = app_state.clone();
+    tokio::spawn(async move {
+        let mut interval = interval(Duration::from_secs(600)); // 每10分钟执行一次
+
+        loop {
+            interval.tick().await;
+            match rate_limit_state.rate_limit_service.purge_expired().await {
+                Ok(count) if count > 0 => debug!("Purged {} expired rate limit counters", count),
+                Ok(_) => {}
+                Err(e) => error!("Failed to purge rate limit counters: {}", e),
+            }
+        }
+    });
+
     info!("Background tasks started successfully");
 }
@@ -0,0 +1,182 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::models::user::UserProfile;
+use utoipa::{IntoParams, ToSchema};
+
+/// 平台级角色，与出版物成员角色无关
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PlatformRole {
+    Admin,
+    Moderator,
+}
+
+impl PlatformRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlatformRole::Admin => "admin",
+            PlatformRole::Moderator => "moderator",
+        }
+    }
+
+    /// 角色授予的权限，由 `auth_middleware` 合并到 `User.permissions`
+    pub fn permissions(&self) -> &'static [&'static str] {
+        match self {
+            PlatformRole::Admin => &[
+                "admin.users",
+                "admin.content",
+                "admin.reports",
+                "admin.stats",
+                "admin.roles",
+                "admin.audit",
+                "admin.payments",
+                "admin.diagnostics",
+                "admin.recommendation",
+                "article.moderate",
+                "user.moderate",
+            ],
+            PlatformRole::Moderator => &[
+                "admin.content",
+                "admin.reports",
+                "admin.stats",
+                "article.moderate",
+            ],
+        }
+    }
+}
+
+/// 只能通过平台角色获得的权限，第三方令牌不会继承
+pub fn is_platform_permission(permission: &str) -> bool {
+    permission.starts_with("admin.") || matches!(permission, "article.moderate" | "user.moderate")
+}
+
+/// 管理后台各路由需要的权限，按路径前缀匹配
+const ADMIN_ROUTE_PERMISSIONS: &[(&str, &str)] = &[
+    ("/api/blog/admin/users", "admin.users"),
+    ("/api/blog/admin/articles", "admin.content"),
+    ("/api/blog/admin/reports", "admin.reports"),
+    ("/api/blog/admin/stats", "admin.stats"),
+    ("/api/blog/admin/roles", "admin.roles"),
+    ("/api/blog/admin/audit", "admin.audit"),
+];
+
+/// 管理后台路由需要的权限，未登记的路由返回 None，一律拒绝
+pub fn required_admin_permission(path: &str) -> Option<&'static str> {
+    ADMIN_ROUTE_PERMISSIONS
+        .iter()
+        .find(|(prefix, _)| path.starts_with(prefix))
+        .map(|(_, permission)| *permission)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PlatformRoleAssignment {
+    pub user_id: String,
+    pub role: PlatformRole,
+    pub granted_by: String,
+    pub granted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct AssignRoleRequest {
+    pub role: PlatformRole,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AdminUserQuery {
+    pub search: Option<String>,
+    pub suspended: Option<bool>,
+    pub page: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+/// 管理后台的用户列表项
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AdminUserSummary {
+    #[serde(flatten)]
+    pub profile: UserProfile,
+    pub platform_role: Option<PlatformRole>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct ModerationReasonRequest {
+    #[validate(length(min = 1, max = 1000, message = "原因长度必须在1-1000字符之间"))]
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AdminArticleQuery {
+    pub author_id: Option<String>,
+    /// 只看已下架的文章
+    pub taken_down: Option<bool>,
+    pub page: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+/// 平台整体数据
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct PlatformStats {
+    pub total_users: i64,
+    pub new_users_7d: i64,
+    pub active_users_30d: i64,
+    pub suspended_users: i64,
+    pub published_articles: i64,
+    pub articles_published_7d: i64,
+    pub taken_down_articles: i64,
+    pub pending_review_articles: i64,
+    pub total_comments: i64,
+    pub held_comments: i64,
+    pub publications: i64,
+    pub open_reports: i64,
+}
+
+/// 响应缓存某个命名空间自进程启动以来的命中情况
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ResponseCacheStats {
+    /// article、publication 或 tag
+    pub namespace: String,
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_rate: f64,
+}
+
+/// 管理操作审计记录
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AdminAction {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub actor_id: String,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: String,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AdminAuditQuery {
+    pub actor_id: Option<String>,
+    pub target_id: Option<String>,
+    pub page: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admin_route_permissions() {
+        assert_eq!(required_admin_permission("/api/blog/admin/users/u1/suspend"), Some("admin.users"));
+        assert_eq!(required_admin_permission("/api/blog/admin/reports"), Some("admin.reports"));
+        assert_eq!(required_admin_permission("/api/blog/admin/unknown"), None);
+
+        let moderator = PlatformRole::Moderator.permissions();
+        assert!(moderator.contains(&"admin.content"));
+        assert!(!moderator.contains(&"admin.users"));
+    }
+}
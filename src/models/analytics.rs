@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use utoipa::{IntoParams, ToSchema};
 
 /// 用户统计概览
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UserAnalyticsOverview {
     pub total_articles: i64,
     pub total_views: i64,
@@ -19,7 +20,7 @@ pub struct UserAnalyticsOverview {
 }
 
 /// 文章统计
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ArticleAnalytics {
     pub article_id: String,
     pub title: String,
@@ -37,7 +38,7 @@ pub struct ArticleAnalytics {
 }
 
 /// 时间段统计
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TimeRangeAnalytics {
     pub date: DateTime<Utc>,
     pub views: i64,
@@ -49,7 +50,7 @@ pub struct TimeRangeAnalytics {
 }
 
 /// 受众分析
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AudienceAnalytics {
     pub total_readers: i64,
     pub returning_readers: i64,
@@ -58,11 +59,12 @@ pub struct AudienceAnalytics {
     pub top_referrers: Vec<ReferrerData>,
     pub geographic_data: Vec<GeographicData>,
     pub device_data: Vec<DeviceData>,
+    pub browser_data: Vec<BrowserData>,
     pub reading_patterns: Vec<ReadingPattern>,
 }
 
 /// 推荐来源信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ReferrerInfo {
     pub source: String,
     pub count: i64,
@@ -70,7 +72,7 @@ pub struct ReferrerInfo {
 }
 
 /// 设备分布
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct DeviceBreakdown {
     pub desktop: i64,
     pub mobile: i64,
@@ -78,7 +80,7 @@ pub struct DeviceBreakdown {
 }
 
 /// 地理分布
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct GeographicInfo {
     pub country: String,
     pub count: i64,
@@ -86,7 +88,7 @@ pub struct GeographicInfo {
 }
 
 /// 地理数据（用于真实受众分析）
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct GeographicData {
     pub country: String,
     pub city: Option<String>,
@@ -95,7 +97,7 @@ pub struct GeographicData {
 }
 
 /// 设备数据
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct DeviceData {
     pub device_type: String,
     pub readers: i64,
@@ -103,8 +105,16 @@ pub struct DeviceData {
     pub avg_session_duration: f64,
 }
 
+/// 浏览器分布
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BrowserData {
+    pub browser: String,
+    pub readers: i64,
+    pub percentage: f32,
+}
+
 /// 推荐数据
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ReferrerData {
     pub source: String,
     pub visits: i64,
@@ -113,7 +123,7 @@ pub struct ReferrerData {
 }
 
 /// 阅读模式
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ReadingPattern {
     pub hour: u8,
     pub readings: i64,
@@ -121,7 +131,7 @@ pub struct ReadingPattern {
 }
 
 /// 标签分析
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TagAnalytics {
     pub tag_id: String,
     pub name: String,
@@ -133,7 +143,7 @@ pub struct TagAnalytics {
 }
 
 /// 收入分析（如果有付费内容）
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RevenueAnalytics {
     pub total_revenue: f64,
     pub paid_subscribers: i64,
@@ -144,7 +154,7 @@ pub struct RevenueAnalytics {
 }
 
 /// 趋势分析
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TrendAnalytics {
     pub period: String, // "daily", "weekly", "monthly"
     pub metrics: Vec<TrendDataPoint>,
@@ -153,7 +163,7 @@ pub struct TrendAnalytics {
     pub peak_value: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TrendDataPoint {
     pub date: DateTime<Utc>,
     pub value: i64,
@@ -161,7 +171,7 @@ pub struct TrendDataPoint {
 }
 
 /// 内容表现分析
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ContentPerformance {
     pub best_performing_articles: Vec<ArticleAnalytics>,
     pub underperforming_articles: Vec<ArticleAnalytics>,
@@ -169,21 +179,21 @@ pub struct ContentPerformance {
     pub content_suggestions: Vec<ContentSuggestion>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct OptimalTimeSlot {
     pub day_of_week: String,
     pub hour: i32,
     pub avg_engagement: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ContentSuggestion {
     pub suggestion_type: SuggestionType,
     pub message: String,
     pub priority: Priority,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub enum SuggestionType {
     Topic,
     Length,
@@ -192,7 +202,7 @@ pub enum SuggestionType {
     SeriesCreation,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub enum Priority {
     High,
     Medium,
@@ -200,7 +210,8 @@ pub enum Priority {
 }
 
 /// 统计查询参数
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct AnalyticsQuery {
     pub start_date: Option<DateTime<Utc>>,
     pub end_date: Option<DateTime<Utc>>,
@@ -209,7 +220,7 @@ pub struct AnalyticsQuery {
     pub limit: Option<i32>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum AnalyticsPeriod {
     Hour,
@@ -220,7 +231,7 @@ pub enum AnalyticsPeriod {
     Year,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum MetricType {
     Views,
@@ -234,21 +245,21 @@ pub enum MetricType {
 }
 
 /// 实时统计
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RealtimeAnalytics {
     pub active_readers: i64,
     pub articles_being_read: Vec<ArticleReadInfo>,
     pub recent_interactions: Vec<InteractionInfo>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ArticleReadInfo {
     pub article_id: String,
     pub title: String,
     pub reader_count: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct InteractionInfo {
     pub interaction_type: String,
     pub article_id: String,
@@ -258,7 +269,7 @@ pub struct InteractionInfo {
 }
 
 /// 综合仪表板
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AnalyticsDashboard {
     pub overview: UserAnalyticsOverview,
     pub recent_articles: Vec<ArticleAnalytics>,
@@ -272,18 +283,133 @@ pub struct AnalyticsDashboard {
 }
 
 /// 导出选项
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ExportOptions {
     pub format: ExportFormat,
     pub include_raw_data: bool,
     pub date_range: Option<(DateTime<Utc>, DateTime<Utc>)>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ExportFormat {
     Csv,
     Json,
     Excel,
     Pdf,
-}
\ No newline at end of file
+}
+/// 文章评论统计查询参数
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CommentAnalyticsQuery {
+    /// 统计最近多少天的评论量，默认 30
+    pub days: Option<i64>,
+    /// 返回的活跃评论者数量，默认 10
+    pub top_limit: Option<usize>,
+    /// 是否计算情感分布
+    #[serde(default)]
+    pub include_sentiment: bool,
+}
+
+/// 每日评论量
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CommentVolumePoint {
+    pub date: String,
+    pub comments: i64,
+    pub replies: i64,
+}
+
+/// 活跃评论者
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TopCommenter {
+    pub user_id: String,
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub comment_count: i64,
+    pub total_claps: i64,
+}
+
+/// 评论情感分布
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CommentSentimentDistribution {
+    pub analyzer: String,
+    pub positive: i64,
+    pub neutral: i64,
+    pub negative: i64,
+}
+
+/// 单篇文章的评论统计
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArticleCommentAnalytics {
+    pub article_id: String,
+    pub total_comments: i64,
+    pub total_replies: i64,
+    pub unique_commenters: i64,
+    pub author_responses: i64,
+    pub volume: Vec<CommentVolumePoint>,
+    pub top_commenters: Vec<TopCommenter>,
+    pub sentiment: Option<CommentSentimentDistribution>,
+}
+
+/// 出版物流量异常类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    TrafficSpike,
+    TrafficDrop,
+    ViralArticle,
+}
+
+impl AnomalyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnomalyKind::TrafficSpike => "traffic_spike",
+            AnomalyKind::TrafficDrop => "traffic_drop",
+            AnomalyKind::ViralArticle => "viral_article",
+        }
+    }
+}
+
+/// 异常窗口内贡献流量的文章
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AnomalyArticle {
+    pub article_id: String,
+    pub title: String,
+    pub slug: String,
+    pub views: i64,
+    /// 基线期内的每小时平均浏览量
+    pub baseline_views: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AnomalyReferrer {
+    pub source: String,
+    pub visits: i64,
+}
+
+/// 检测到的出版物流量异常
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PublicationAnomaly {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub publication_id: String,
+    pub kind: AnomalyKind,
+    /// 爆款文章提醒对应的文章
+    pub article_id: Option<String>,
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    pub observed_views: i64,
+    pub expected_views: f64,
+    pub z_score: f64,
+    #[serde(default)]
+    pub contributing_articles: Vec<AnomalyArticle>,
+    #[serde(default)]
+    pub top_referrers: Vec<AnomalyReferrer>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PublicationAnomalyQuery {
+    pub limit: Option<usize>,
+}
@@ -2,8 +2,16 @@ use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use validator::Validate;
 use uuid::Uuid;
+use super::collaborator::CoAuthorInfo;
+use super::reaction::{ReactionCounts, ReactionType};
+use super::meter::MeterStatus;
+use super::payment::AccessType;
+use super::series::SeriesNavItem;
+use crate::error::{AppError, ErrorCode};
+use crate::utils::markdown::TocItem;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Article {
     #[serde(with = "crate::utils::serde_helpers::thing_id")]
     pub id: String,
@@ -16,6 +24,9 @@ pub struct Article {
     pub cover_image_url: Option<String>,
     #[serde(with = "crate::utils::serde_helpers::thing_id")]
     pub author_id: String,
+    /// 以笔名发布时的笔名 ID；公开接口只显示笔名，不透露 author_id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pseudonym_id: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub publication_id: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -25,10 +36,22 @@ pub struct Article {
     pub status: ArticleStatus,
     pub is_paid_content: bool,
     pub is_featured: bool,
+    /// 置顶到作者主页的时间，作者主页按置顶时间倒序排在最前
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_at: Option<DateTime<Utc>>,
+    /// 置顶到出版物首页的时间
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publication_pinned_at: Option<DateTime<Utc>>,
     pub reading_time: i32, // 分钟
     pub word_count: i32,
     pub view_count: i64,
     pub clap_count: i64,
+    /// 加权点赞分（见 utils::scoring），用于热门、推荐和排行
+    #[serde(default)]
+    pub weighted_clap_score: f64,
+    /// 各反应的总数，见 models::reaction
+    #[serde(default)]
+    pub reaction_counts: ReactionCounts,
     pub comment_count: i64,
     pub bookmark_count: i64,
     pub share_count: i64,
@@ -42,17 +65,28 @@ pub struct Article {
     pub updated_at: DateTime<Utc>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub published_at: Option<DateTime<Utc>>,
+    /// 定时发布时间，仅在 `Scheduled` 状态下有值
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheduled_at: Option<DateTime<Utc>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub last_edited_at: Option<DateTime<Utc>>,
     pub is_deleted: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub deleted_at: Option<DateTime<Utc>>,
+    /// 被平台管理员下架的时间，下架期间作者不能重新发布
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub taken_down_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ArticleStatus {
     Draft,
+    /// 作者信誉不足以直接发布，等待审核
+    #[serde(rename = "pending_review")]
+    PendingReview,
+    /// 等待到达 `scheduled_at` 后由后台任务发布
+    Scheduled,
     Published,
     Unlisted,
     Archived,
@@ -70,10 +104,14 @@ impl ArticleStatus {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Validate)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreateArticleRequest {
     #[validate(length(min = 1, max = 150))]
     pub title: String,
+
+    /// 自定义 slug，不填时根据标题生成。只能包含字母、数字、`-` 和 `_`
+    #[validate(length(min = 1, max = 100))]
+    pub slug: Option<String>,
     
     #[validate(length(max = 200))]
     pub subtitle: Option<String>,
@@ -101,13 +139,19 @@ pub struct CreateArticleRequest {
     
     pub seo_keywords: Option<Vec<String>>,
     pub save_as_draft: Option<bool>,
+    /// 定时发布时间，必须晚于当前时间
+    pub publish_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Validate)]
+#[derive(Debug, Default, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UpdateArticleRequest {
     #[validate(length(min = 1, max = 150))]
     pub title: Option<String>,
     
+    /// 自定义 slug。设置后修改标题不再重新生成 slug，旧 slug 跳转到新 slug
+    #[validate(length(min = 1, max = 100))]
+    pub slug: Option<String>,
+
     #[validate(length(max = 200))]
     pub subtitle: Option<String>,
     
@@ -135,9 +179,11 @@ pub struct UpdateArticleRequest {
     pub seo_keywords: Option<Vec<String>>,
     pub status: Option<ArticleStatus>,
     pub metadata: Option<serde_json::Value>,
+    /// 定时发布时间，必须晚于当前时间
+    pub publish_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ArticleResponse {
     pub id: String,
     pub title: String,
@@ -145,18 +191,36 @@ pub struct ArticleResponse {
     pub slug: String,
     pub content: String,
     pub content_html: String,
+    /// 按层级嵌套的目录，`id` 与 `content_html` 中标题的 id 一致，可直接用作 `#id` 锚点。
+    /// 付费文章被截断时只包含预览部分的标题
+    #[serde(default)]
+    pub toc: Vec<TocItem>,
+    /// 朗读音频（MP3），未启用或尚未生成时为空；付费文章被截断时也不返回
+    pub audio_url: Option<String>,
+    /// 朗读音频时长（秒）
+    pub audio_duration: Option<f64>,
     pub excerpt: Option<String>,
     pub cover_image_url: Option<String>,
     pub author: AuthorInfo,
+    /// 作者账号 ID，只在服务端使用；笔名文章的 `author` 是笔名，不能用于权限和统计
+    #[serde(skip)]
+    pub author_id: String,
+    /// 已接受邀请的合著者
+    #[serde(default)]
+    pub co_authors: Vec<CoAuthorInfo>,
     pub publication: Option<PublicationInfo>,
     pub series: Option<SeriesInfo>,
     pub status: ArticleStatus,
     pub is_paid_content: bool,
+    /// 付费文章的访问情况，免费文章没有此字段
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paywall: Option<PaywallInfo>,
     pub is_featured: bool,
     pub reading_time: i32,
     pub word_count: i32,
     pub view_count: i64,
     pub clap_count: i64,
+    pub reactions: ReactionCounts,
     pub comment_count: i64,
     pub bookmark_count: i64,
     pub share_count: i64,
@@ -167,12 +231,32 @@ pub struct ArticleResponse {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub published_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheduled_at: Option<DateTime<Utc>>,
     pub is_bookmarked: Option<bool>, // 当前用户是否收藏
     pub is_clapped: Option<bool>,    // 当前用户是否点赞
     pub user_clap_count: Option<i32>, // 当前用户点赞次数
+    pub user_reactions: Option<Vec<ReactionType>>, // 当前用户的反应
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 读者没有访问权限时，`content` 和 `content_html` 只包含前几段
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PaywallInfo {
+    pub has_access: bool,
+    pub access_type: AccessType,
+    /// 正文是否被截断
+    pub is_truncated: bool,
+    pub preview_paragraphs: usize,
+    pub paywall_message: String,
+    pub subscription_required: bool,
+    /// 单篇购买价格（美分），None 表示只能订阅
+    pub price: Option<i64>,
+    /// 计量付费墙的免费额度，有订阅或购买的读者没有此字段
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meter: Option<MeterStatus>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ArticleListItem {
     pub id: String,
     pub title: String,
@@ -185,6 +269,10 @@ pub struct ArticleListItem {
     pub status: ArticleStatus,
     pub is_paid_content: bool,
     pub is_featured: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publication_pinned_at: Option<DateTime<Utc>>,
     pub reading_time: i32,
     pub view_count: i64,
     pub clap_count: i64,
@@ -194,16 +282,45 @@ pub struct ArticleListItem {
     pub published_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AuthorInfo {
     pub id: String,
     pub username: String,
     pub display_name: String,
     pub avatar_url: Option<String>,
     pub is_verified: bool,
+    /// 笔名作者：`id` 和 `username` 属于笔名资料，不是用户账号
+    #[serde(default)]
+    pub is_pseudonym: bool,
+}
+
+impl AuthorInfo {
+    /// 笔名已删除的文章显示的作者
+    pub fn anonymous() -> Self {
+        Self {
+            id: String::new(),
+            username: "anonymous".to_string(),
+            display_name: "Anonymous".to_string(),
+            avatar_url: None,
+            is_verified: false,
+            is_pseudonym: true,
+        }
+    }
+
+    /// 找不到资料的用户
+    pub fn unknown(user_id: &str) -> Self {
+        Self {
+            id: user_id.to_string(),
+            username: "unknown".to_string(),
+            display_name: "Unknown Author".to_string(),
+            avatar_url: None,
+            is_verified: false,
+            is_pseudonym: false,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PublicationInfo {
     pub id: String,
     pub name: String,
@@ -211,22 +328,31 @@ pub struct PublicationInfo {
     pub logo_url: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SeriesInfo {
     pub id: String,
     pub title: String,
     pub slug: String,
     pub order: i32,
+    /// 系列中已发布的文章数
+    #[serde(default)]
+    pub total_articles: usize,
+    pub previous: Option<SeriesNavItem>,
+    pub next: Option<SeriesNavItem>,
+    /// 登录读者读完本系列的百分比（0 - 100）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion_percentage: Option<f64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TagInfo {
     pub id: String,
     pub name: String,
     pub slug: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct ArticleQuery {
     pub page: Option<usize>,
     pub limit: Option<usize>,
@@ -237,9 +363,21 @@ pub struct ArticleQuery {
     pub featured: Option<bool>,
     pub search: Option<String>,
     pub sort: Option<String>, // "newest", "oldest", "popular", "trending"
+    /// 按笔名筛选（笔名主页）
+    pub pseudonym: Option<String>,
+    /// 按作者筛选时是否包含笔名文章，只用于作者查看自己的文章，不能从查询参数设置
+    #[serde(skip)]
+    pub include_pseudonymous: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// 代码高亮样式表的主题，默认 InspiredGitHub
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HighlightCssQuery {
+    pub theme: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ArticleStats {
     pub total_articles: i64,
     pub published_articles: i64,
@@ -249,13 +387,152 @@ pub struct ArticleStats {
     pub total_comments: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct TrendingArticle {
     pub article: ArticleListItem,
     pub trend_score: f64,
     pub growth_rate: f64,
 }
 
+/// 实时热度榜中的文章
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PopularNowArticle {
+    pub article_id: String,
+    pub title: String,
+    pub slug: String,
+    pub author_id: String,
+    /// 按半衰期衰减后的浏览/点赞热度
+    pub score: f64,
+}
+
+/// 实时热度榜快照，通过 SSE 推送给首页模块
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PopularNowSnapshot {
+    pub generated_at: DateTime<Utc>,
+    pub articles: Vec<PopularNowArticle>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkArticleAction {
+    Publish,
+    Unpublish,
+    Delete,
+    /// 给每篇文章加上 `tags` 中的标签
+    TagAdd,
+    /// 从每篇文章移除 `tags` 中的标签
+    TagRemove,
+}
+
+impl BulkArticleAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Publish => "publish",
+            Self::Unpublish => "unpublish",
+            Self::Delete => "delete",
+            Self::TagAdd => "tag_add",
+            Self::TagRemove => "tag_remove",
+        }
+    }
+
+    pub fn is_tag_action(&self) -> bool {
+        matches!(self, Self::TagAdd | Self::TagRemove)
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct BulkArticleRequest {
+    pub action: BulkArticleAction,
+    #[validate(length(min = 1, max = 100))]
+    pub article_ids: Vec<String>,
+    /// tag_add / tag_remove 时必填
+    #[serde(default)]
+    #[validate(length(max = 10))]
+    pub tags: Vec<String>,
+}
+
+/// 批量操作中单篇文章的结果，失败不影响其他文章
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BulkArticleItemResult {
+    pub article_id: String,
+    pub success: bool,
+    /// 操作后的文章状态；删除或标签操作时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<ArticleStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<ErrorCode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BulkArticleItemResult {
+    pub fn succeeded(article_id: &str, status: Option<ArticleStatus>) -> Self {
+        Self {
+            article_id: article_id.to_string(),
+            success: true,
+            status,
+            error_code: None,
+            error: None,
+        }
+    }
+
+    pub fn failed(article_id: &str, error: &AppError) -> Self {
+        Self {
+            article_id: article_id.to_string(),
+            success: false,
+            status: None,
+            error_code: Some(error.code()),
+            error: Some(error.client_message()),
+        }
+    }
+}
+
+/// 文章用过的旧 slug，访问时 301 跳转到文章当前的 slug
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArticleSlugRedirect {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub article_id: String,
+    /// 旧 slug
+    pub slug: String,
+    /// 改名时换成的 slug
+    #[serde(default)]
+    pub new_slug: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 置顶位置
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PinTarget {
+    /// 作者主页，只有作者可以置顶
+    #[default]
+    Profile,
+    /// 文章所属出版物的首页，需要出版物的 `article.edit_any` 权限
+    Publication,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PinArticleRequest {
+    #[serde(default)]
+    pub target: PinTarget,
+    /// false 表示取消置顶
+    pub pinned: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct FeatureArticleRequest {
+    pub featured: bool,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BulkArticleResponse {
+    pub action: BulkArticleAction,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BulkArticleItemResult>,
+}
+
 impl Article {
     pub fn new(title: String, content: String, author_id: String) -> Self {
         let now = Utc::now();
@@ -271,16 +548,21 @@ impl Article {
             excerpt: None,
             cover_image_url: None,
             author_id,
+            pseudonym_id: None,
             publication_id: None,
             series_id: None,
             series_order: None,
             status: ArticleStatus::Draft,
             is_paid_content: false,
             is_featured: false,
+            pinned_at: None,
+            publication_pinned_at: None,
             reading_time: Self::calculate_reading_time(&content),
             word_count: Self::calculate_word_count(&content),
             view_count: 0,
             clap_count: 0,
+            weighted_clap_score: 0.0,
+            reaction_counts: ReactionCounts::default(),
             comment_count: 0,
             bookmark_count: 0,
             share_count: 0,
@@ -291,9 +573,11 @@ impl Article {
             created_at: now,
             updated_at: now,
             published_at: None,
+            scheduled_at: None,
             last_edited_at: None,
             is_deleted: false,
             deleted_at: None,
+            taken_down_at: None,
         }
     }
 
@@ -341,6 +625,11 @@ impl Article {
         self.updated_at = Utc::now();
     }
 
+    /// 对外显示的作者 ID：笔名文章返回笔名 ID
+    pub fn public_author_id(&self) -> &str {
+        self.pseudonym_id.as_deref().unwrap_or(&self.author_id)
+    }
+
     pub fn is_published(&self) -> bool {
         self.status == ArticleStatus::Published && !self.is_deleted
     }
@@ -0,0 +1,128 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use utoipa::{IntoParams, ToSchema};
+
+/// 下载附件前需要满足的条件
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentGate {
+    #[default]
+    Public,
+    /// 需要是作者的付费订阅者
+    Subscribers,
+    /// 需要留下邮箱，邮箱记录为作者的潜在读者
+    Email,
+}
+
+impl AttachmentGate {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttachmentGate::Public => "public",
+            AttachmentGate::Subscribers => "subscribers",
+            AttachmentGate::Email => "email",
+        }
+    }
+}
+
+/// 文章附件（PDF、幻灯片、数据集等）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArticleAttachment {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub article_id: String,
+    /// 文章作者，附件的下载和留资统计归属作者
+    pub author_id: String,
+    pub uploaded_by: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub original_filename: String,
+    pub content_type: String,
+    pub size: i64,
+    /// 附件在服务器上的位置，不返回给客户端
+    #[serde(skip_serializing, default)]
+    pub storage_path: String,
+    pub gate: AttachmentGate,
+    pub download_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 上传附件时 multipart 表单中除文件外的字段
+#[derive(Debug, Clone, Default, Validate)]
+pub struct CreateAttachmentRequest {
+    #[validate(length(min = 1, max = 200))]
+    pub title: String,
+    #[validate(length(max = 1000))]
+    pub description: Option<String>,
+    pub gate: AttachmentGate,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UpdateAttachmentRequest {
+    #[validate(length(min = 1, max = 200))]
+    pub title: Option<String>,
+    #[validate(length(max = 1000))]
+    pub description: Option<String>,
+    pub gate: Option<AttachmentGate>,
+}
+
+/// 申请下载链接；邮箱门槛的附件需要提供邮箱（已登录时默认使用账号邮箱）
+#[derive(Debug, Clone, Default, Deserialize, Validate, ToSchema)]
+pub struct AttachmentAccessRequest {
+    #[validate(email)]
+    pub email: Option<String>,
+}
+
+/// 列表中的附件，附带当前读者能否直接下载
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AttachmentListItem {
+    #[serde(flatten)]
+    pub attachment: ArticleAttachment,
+    pub accessible: bool,
+}
+
+/// 短期有效的下载链接
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AttachmentDownloadLink {
+    pub download_url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 通过邮箱门槛下载附件的读者
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AttachmentLead {
+    pub attachment_id: String,
+    pub article_id: String,
+    pub author_id: String,
+    pub email: String,
+    pub user_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AttachmentAnalyticsQuery {
+    pub days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AttachmentDownloadStats {
+    pub attachment_id: String,
+    pub article_id: String,
+    pub title: String,
+    pub gate: AttachmentGate,
+    /// 上传以来的总下载次数
+    pub total_downloads: i64,
+    /// 统计周期内的下载次数
+    pub downloads: i64,
+    /// 统计周期内留下的邮箱数
+    pub leads: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AttachmentAnalytics {
+    pub days: i64,
+    pub total_downloads: i64,
+    pub total_leads: i64,
+    pub attachments: Vec<AttachmentDownloadStats>,
+}
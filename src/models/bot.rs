@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+use chrono::NaiveDate;
+use utoipa::{IntoParams, ToSchema};
+
+/// 爬虫 / 自动化流量的类别
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BotCategory {
+    SearchEngine,
+    /// 社交平台生成链接预览
+    SocialPreview,
+    SeoTool,
+    AiCrawler,
+    Monitoring,
+    /// 无头浏览器、命令行工具和 HTTP 库
+    Automation,
+    Unknown,
+}
+
+impl BotCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BotCategory::SearchEngine => "search_engine",
+            BotCategory::SocialPreview => "social_preview",
+            BotCategory::SeoTool => "seo_tool",
+            BotCategory::AiCrawler => "ai_crawler",
+            BotCategory::Monitoring => "monitoring",
+            BotCategory::Automation => "automation",
+            BotCategory::Unknown => "unknown",
+        }
+    }
+}
+
+/// 判定为机器流量的依据
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BotReason {
+    /// User-Agent 匹配已知爬虫
+    UserAgent,
+    /// 缺少浏览器一定会发送的请求头
+    MissingHeaders,
+    /// 同一 IP 短时间内浏览过多
+    RequestRate,
+    /// 没有通过 JS 验证令牌
+    Challenge,
+}
+
+impl BotReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BotReason::UserAgent => "user_agent",
+            BotReason::MissingHeaders => "missing_headers",
+            BotReason::RequestRate => "request_rate",
+            BotReason::Challenge => "challenge",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BotVerdict {
+    /// 已知爬虫的名称，启发式判定时为 None
+    pub name: Option<String>,
+    pub category: BotCategory,
+    pub reason: BotReason,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct BotTrafficQuery {
+    pub days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BotTrafficSource {
+    pub name: String,
+    pub category: BotCategory,
+    pub views: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BotTrafficByReason {
+    pub reason: BotReason,
+    pub views: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BotTrafficDay {
+    pub date: NaiveDate,
+    pub human_views: i64,
+    pub bot_views: i64,
+}
+
+/// 作者文章的机器流量报告（这些流量没有计入浏览数、热度和受众分析）
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BotTrafficReport {
+    pub days: i64,
+    pub human_views: i64,
+    pub bot_views: i64,
+    /// 机器流量占全部流量的比例（0-1）
+    pub bot_share: f64,
+    pub sources: Vec<BotTrafficSource>,
+    pub reasons: Vec<BotTrafficByReason>,
+    pub daily: Vec<BotTrafficDay>,
+}
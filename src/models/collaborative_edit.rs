@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+
+use crate::utils::ot::TextOperation;
+use utoipa::ToSchema;
+
+/// 参与者在协作编辑中的权限
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EditAccess {
+    Author,
+    Editor,
+    /// 只读：可以看到其他人的编辑和光标，不能提交操作
+    Viewer,
+}
+
+impl EditAccess {
+    pub fn can_edit(&self) -> bool {
+        !matches!(self, EditAccess::Viewer)
+    }
+}
+
+/// 光标或选区，按 Unicode 字符计算位置
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct EditSelection {
+    pub anchor: usize,
+    pub head: usize,
+}
+
+/// 正在编辑同一篇草稿的用户
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct EditParticipant {
+    pub connection_id: String,
+    pub user_id: String,
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+    pub access: EditAccess,
+    pub selection: Option<EditSelection>,
+}
+
+/// 客户端发送的消息
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EditClientMessage {
+    /// 基于 `revision` 版本的编辑操作
+    Operation {
+        revision: usize,
+        /// ot.js 格式的操作数组
+        #[schema(value_type = Vec<serde_json::Value>)]
+        operation: TextOperation,
+        /// 操作之后的光标位置
+        selection: Option<EditSelection>,
+    },
+    /// 只移动了光标
+    Selection { selection: Option<EditSelection> },
+    /// 立即保存，不等待自动保存
+    Save,
+}
+
+/// 服务端发送的消息
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EditServerMessage {
+    /// 加入时（或需要重新同步时）发送的完整文档
+    Init {
+        connection_id: String,
+        revision: usize,
+        content: String,
+        access: EditAccess,
+        participants: Vec<EditParticipant>,
+    },
+    /// 自己的操作已被接受，成为 `revision` 版本
+    Ack { revision: usize },
+    /// 其他人的操作（已变换到最新版本）
+    Operation {
+        revision: usize,
+        /// ot.js 格式的操作数组
+        #[schema(value_type = Vec<serde_json::Value>)]
+        operation: TextOperation,
+        connection_id: String,
+        user_id: String,
+        selection: Option<EditSelection>,
+    },
+    Selection {
+        connection_id: String,
+        selection: Option<EditSelection>,
+    },
+    Join { participant: EditParticipant },
+    Leave { connection_id: String },
+    /// 文档已保存到文章（同时记录为自动保存修订版本）
+    Saved { revision: usize },
+    Error { message: String },
+}
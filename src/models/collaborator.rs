@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+
+use super::article::AuthorInfo;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CollaboratorRole {
+    /// 可以编辑正文、自动保存和恢复修订版本，但不能发布或删除文章
+    Editor,
+    /// 只能查看草稿和修订历史
+    Viewer,
+}
+
+impl CollaboratorRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CollaboratorRole::Editor => "editor",
+            CollaboratorRole::Viewer => "viewer",
+        }
+    }
+
+    pub fn can_edit(&self) -> bool {
+        matches!(self, CollaboratorRole::Editor)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CollaboratorStatus {
+    Pending,
+    Accepted,
+}
+
+/// 文章的合著者（记录 ID 为 [article_id, user_id]）。
+/// 文章作者本人不在此表中，始终拥有全部权限
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArticleCollaborator {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub article_id: String,
+    pub user_id: String,
+    pub role: CollaboratorRole,
+    pub status: CollaboratorStatus,
+    pub invited_by: String,
+    pub created_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct InviteCollaboratorRequest {
+    #[validate(length(min = 1))]
+    pub user_id: String,
+    pub role: CollaboratorRole,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateCollaboratorRequest {
+    pub role: CollaboratorRole,
+}
+
+/// 文章详情中展示的合著者
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CoAuthorInfo {
+    #[serde(flatten)]
+    pub author: AuthorInfo,
+    pub role: CollaboratorRole,
+}
+
+/// 合著者列表中的一项，包含待接受的邀请
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CollaboratorResponse {
+    #[serde(flatten)]
+    pub collaborator: ArticleCollaborator,
+    pub profile: Option<AuthorInfo>,
+}
@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use validator::Validate;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Comment {
     pub id: String,
     pub article_id: String,
@@ -16,9 +17,128 @@ pub struct Comment {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub deleted_at: Option<DateTime<Utc>>,
+    /// 导入的访客评论显示的作者名（没有对应的站内用户）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guest_name: Option<String>,
+    /// 导入来源，如 "disqus"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub imported_from: Option<String>,
+    /// 来源系统中的评论 ID，用于避免重复导入
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    #[serde(default)]
+    pub moderation_status: CommentModerationStatus,
+    /// 垃圾评论打分（0-1），未打分的旧评论为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spam_score: Option<f64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 评论的审核状态，只有 approved 的评论公开展示
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CommentModerationStatus {
+    #[default]
+    Approved,
+    /// 出版物开启了"先审后发"，等待审核
+    Pending,
+    /// 垃圾评论打分超过阈值
+    Spam,
+    Rejected,
+    /// 被多次举报，隐藏后等待平台审核员处理
+    Reported,
+}
+
+impl CommentModerationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CommentModerationStatus::Approved => "approved",
+            CommentModerationStatus::Pending => "pending",
+            CommentModerationStatus::Spam => "spam",
+            CommentModerationStatus::Rejected => "rejected",
+            CommentModerationStatus::Reported => "reported",
+        }
+    }
+}
+
+/// 出版物的评论审核设置
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CommentModerationSettings {
+    pub publication_id: String,
+    /// 新评论先进入审核队列，审核通过后才展示
+    pub hold_for_review: bool,
+    /// 打分不低于该值的评论标记为垃圾评论
+    pub spam_threshold: f64,
+    /// 额外屏蔽的关键词，命中即视为垃圾评论
+    pub blocked_keywords: Vec<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl CommentModerationSettings {
+    pub const DEFAULT_SPAM_THRESHOLD: f64 = 0.8;
+
+    pub fn default_for(publication_id: &str) -> Self {
+        Self {
+            publication_id: publication_id.to_string(),
+            hold_for_review: false,
+            spam_threshold: Self::DEFAULT_SPAM_THRESHOLD,
+            blocked_keywords: Vec::new(),
+            updated_at: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UpdateModerationSettingsRequest {
+    pub hold_for_review: Option<bool>,
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub spam_threshold: Option<f64>,
+    #[validate(length(max = 200))]
+    pub blocked_keywords: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ModerationQueueQuery {
+    /// pending（默认）或 spam
+    pub status: Option<CommentModerationStatus>,
+    pub page: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationAction {
+    Approve,
+    Reject,
+    /// 标记为垃圾评论
+    Spam,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct BulkModerationRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub comment_ids: Vec<String>,
+    pub action: ModerationAction,
+}
+
+/// 审核队列中的评论
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ModerationQueueItem {
+    #[serde(flatten)]
+    pub comment: Comment,
+    pub article_title: String,
+    pub article_slug: String,
+}
+
+/// 发评论时的客户端信息，用于垃圾评论打分
+#[derive(Debug, Clone, Default)]
+pub struct CommentClient {
+    pub ip: Option<String>,
+    pub user_agent: Option<String>,
+    pub author_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CommentWithAuthor {
     #[serde(flatten)]
     pub comment: Comment,
@@ -26,10 +146,68 @@ pub struct CommentWithAuthor {
     pub author_username: String,
     pub author_avatar: Option<String>,
     pub user_has_clapped: bool,
+    /// 当前用户上次阅读之后的新评论
+    #[serde(default)]
+    pub is_new: bool,
+    /// 直接回复数；超出返回深度时 replies 为空，通过 /comments/:id/replies 加载
+    #[serde(default)]
+    pub reply_count: i64,
+    #[schema(no_recursion)]
+    pub replies: Vec<CommentWithAuthor>,
+}
+
+/// 用户在某篇文章讨论区的阅读进度
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CommentReadState {
+    pub article_id: String,
+    /// 首次访问时为空，此时不标记新评论
+    pub last_read_at: Option<DateTime<Utc>>,
+    pub unread_count: i64,
+    /// 最早的一条新评论，前端在此处显示"上次看到这里"分隔线
+    pub first_unread_comment_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CommentListQuery {
+    /// 返回列表后把阅读进度更新到当前时间
+    #[serde(default)]
+    pub mark_read: bool,
+    /// 随列表返回的回复层数，默认为最大嵌套深度
+    pub depth: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CommentRepliesQuery {
+    /// 上一页最后一条回复的 ID
+    pub cursor: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// 某条评论的一页直接回复
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CommentReplies {
+    pub parent_id: String,
     pub replies: Vec<CommentWithAuthor>,
+    /// 还有更多回复时，作为下一次请求的 cursor
+    pub next_cursor: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct MarkCommentsReadRequest {
+    /// 读到的时间点，默认为当前时间
+    pub read_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UnreadCountsQuery {
+    /// 逗号分隔的文章 ID
+    pub article_ids: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreateCommentRequest {
     #[validate(length(min = 1, max = 200000))]
     pub article_id: String,
@@ -38,16 +216,46 @@ pub struct CreateCommentRequest {
     pub content: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UpdateCommentRequest {
     #[validate(length(min = 1, max = 10000))]
     pub content: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CommentClap {
     pub id: String,
     pub user_id: String,
     pub comment_id: String,
     pub created_at: DateTime<Utc>,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CommentImportQuery {
+    /// 只解析和匹配，不写入评论
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// 未能匹配到文章的讨论串
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UnmatchedThread {
+    pub thread_id: String,
+    pub link: String,
+    pub title: String,
+    pub post_count: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct CommentImportReport {
+    pub dry_run: bool,
+    pub threads_total: usize,
+    pub threads_matched: usize,
+    pub comments_imported: usize,
+    /// 之前已经导入过的评论
+    pub comments_skipped_existing: usize,
+    /// 已删除或被标记为垃圾的评论
+    pub comments_skipped_removed: usize,
+    pub unmatched_threads: Vec<UnmatchedThread>,
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use utoipa::ToSchema;
+
+/// 出版物上传的 WASM 内容转换器
+///
+/// 每次上传同名转换器都会产生一个新版本，同一名称只有一个版本处于生效状态。
+/// 转换器在渲染文章时按 `priority` 从小到大依次处理 HTML。
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ContentTransform {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub publication_id: String,
+    pub name: String,
+    pub version: i32,
+    /// 模块内容的 SHA-256
+    pub module_hash: String,
+    pub size_bytes: i64,
+    /// 是否为该名称当前生效的版本
+    pub is_active: bool,
+    /// 开关，关闭后立即停止执行（出错次数过多时会被自动关闭）
+    pub is_enabled: bool,
+    pub priority: i32,
+    /// 自上次启用以来的失败次数
+    pub failure_count: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UploadContentTransformRequest {
+    #[validate(length(min = 1, max = 64))]
+    pub name: String,
+    /// Base64 编码的 WASM 模块
+    pub module_base64: String,
+    #[validate(range(min = 0, max = 1000))]
+    pub priority: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateContentTransformRequest {
+    pub is_enabled: Option<bool>,
+    #[validate(range(min = 0, max = 1000))]
+    pub priority: Option<i32>,
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+/// 出版物允许跨域访问内容接口的来源（如嵌入到客户站点的前端）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PublicationCorsOrigin {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub publication_id: String,
+    /// `https://blog.example.com`，或 `https://*.example.com` 匹配所有子域名
+    pub origin: String,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddCorsOriginRequest {
+    pub origin: String,
+}
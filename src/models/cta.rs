@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CtaKind {
+    /// 付费订阅作者
+    Subscribe,
+    /// 关注作者
+    Follow,
+    /// 跳转到外部链接
+    ExternalLink,
+    /// 收集读者邮箱
+    EmailCapture,
+}
+
+impl CtaKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CtaKind::Subscribe => "subscribe",
+            CtaKind::Follow => "follow",
+            CtaKind::ExternalLink => "external_link",
+            CtaKind::EmailCapture => "email_capture",
+        }
+    }
+
+    pub fn default_button_label(&self) -> &'static str {
+        match self {
+            CtaKind::Subscribe => "Subscribe",
+            CtaKind::Follow => "Follow",
+            CtaKind::ExternalLink => "Learn more",
+            CtaKind::EmailCapture => "Sign up",
+        }
+    }
+}
+
+/// 作者统一管理的行动号召块，在正文中用 `{{cta key}}` 引用
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CallToAction {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub owner_id: String,
+    /// 短代码中使用的名称，同一作者内唯一
+    pub key: String,
+    pub kind: CtaKind,
+    pub title: String,
+    pub description: Option<String>,
+    pub button_label: String,
+    /// 外部链接的目标地址
+    pub url: Option<String>,
+    pub click_count: i64,
+    pub conversion_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateCtaRequest {
+    /// 小写字母、数字和连字符
+    #[validate(length(min = 1, max = 50))]
+    pub key: String,
+    pub kind: CtaKind,
+    #[validate(length(min = 1, max = 150))]
+    pub title: String,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+    #[validate(length(min = 1, max = 40))]
+    pub button_label: Option<String>,
+    #[validate(url)]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UpdateCtaRequest {
+    #[validate(length(min = 1, max = 150))]
+    pub title: Option<String>,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+    #[validate(length(min = 1, max = 40))]
+    pub button_label: Option<String>,
+    #[validate(url)]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CtaEventKind {
+    Click,
+    /// 读者完成了关注、订阅或留下邮箱
+    Conversion,
+}
+
+impl CtaEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CtaEventKind::Click => "click",
+            CtaEventKind::Conversion => "conversion",
+        }
+    }
+}
+
+/// 前端上报的点击或转化
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct CtaEventRequest {
+    pub event: CtaEventKind,
+    pub article_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CtaEmailRequest {
+    #[validate(email)]
+    pub email: String,
+    pub article_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CtaRedirectQuery {
+    pub article: Option<String>,
+}
+
+/// 邮箱收集块留下的邮箱
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CtaLead {
+    pub cta_id: String,
+    pub owner_id: String,
+    pub email: String,
+    pub article_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CtaAnalyticsQuery {
+    pub days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CtaArticleStats {
+    pub article_id: String,
+    pub clicks: i64,
+    pub conversions: i64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CtaStats {
+    pub cta_id: String,
+    pub key: String,
+    pub kind: CtaKind,
+    pub title: String,
+    pub clicks: i64,
+    pub conversions: i64,
+    /// 转化数 / 点击数
+    pub conversion_rate: f64,
+    pub by_article: Vec<CtaArticleStats>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CtaAnalytics {
+    pub days: i64,
+    pub ctas: Vec<CtaStats>,
+}
@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use utoipa::ToSchema;
+
+/// 账户删除的整体状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeletionStatus {
+    /// 已申请，等待后台处理
+    Pending,
+    Processing,
+    /// 内容已下线，等待宽限期结束后永久删除
+    Scheduled,
+    /// 某一步失败，后台任务会重试
+    Failed,
+    Completed,
+    Cancelled,
+}
+
+impl DeletionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeletionStatus::Pending => "pending",
+            DeletionStatus::Processing => "processing",
+            DeletionStatus::Scheduled => "scheduled",
+            DeletionStatus::Failed => "failed",
+            DeletionStatus::Completed => "completed",
+            DeletionStatus::Cancelled => "cancelled",
+        }
+    }
+
+    /// 宽限期内仍可撤销
+    pub fn is_cancellable(&self) -> bool {
+        matches!(self, DeletionStatus::Pending | DeletionStatus::Scheduled | DeletionStatus::Failed)
+    }
+}
+
+/// 删除流程的各个步骤，按顺序执行
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeletionStep {
+    /// 吊销第三方应用令牌和浏览器推送订阅
+    RevokeAccess,
+    SoftDeleteArticles,
+    SoftDeleteComments,
+    /// 取消作为订阅者和创作者的订阅，停用订阅计划
+    CancelSubscriptions,
+    /// 删除 Stripe Customer 和保存的支付方式
+    DetachStripeCustomer,
+    /// 浏览、下载等统计数据去掉用户标识
+    AnonymizeAnalytics,
+    /// 宽限期结束后永久删除个人数据
+    HardDelete,
+}
+
+impl DeletionStep {
+    /// 申请后立即执行的步骤
+    pub const IMMEDIATE: [DeletionStep; 6] = [
+        DeletionStep::RevokeAccess,
+        DeletionStep::SoftDeleteArticles,
+        DeletionStep::SoftDeleteComments,
+        DeletionStep::CancelSubscriptions,
+        DeletionStep::DetachStripeCustomer,
+        DeletionStep::AnonymizeAnalytics,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeletionStep::RevokeAccess => "revoke_access",
+            DeletionStep::SoftDeleteArticles => "soft_delete_articles",
+            DeletionStep::SoftDeleteComments => "soft_delete_comments",
+            DeletionStep::CancelSubscriptions => "cancel_subscriptions",
+            DeletionStep::DetachStripeCustomer => "detach_stripe_customer",
+            DeletionStep::AnonymizeAnalytics => "anonymize_analytics",
+            DeletionStep::HardDelete => "hard_delete",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Pending,
+    Completed,
+    Failed,
+}
+
+/// 单个步骤的进度
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DeletionStepProgress {
+    pub step: DeletionStep,
+    pub status: StepStatus,
+    /// 处理的记录数
+    #[serde(default)]
+    pub affected: usize,
+    pub error: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl DeletionStepProgress {
+    pub fn pending(step: DeletionStep) -> Self {
+        Self {
+            step,
+            status: StepStatus::Pending,
+            affected: 0,
+            error: None,
+            completed_at: None,
+        }
+    }
+}
+
+/// 账户删除申请及进度，记录ID即用户ID
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccountDeletion {
+    pub user_id: String,
+    pub status: DeletionStatus,
+    pub reason: Option<String>,
+    pub steps: Vec<DeletionStepProgress>,
+    pub requested_at: DateTime<Utc>,
+    /// 宽限期结束、永久删除的时间
+    pub hard_delete_after: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+}
+
+impl AccountDeletion {
+    pub fn step(&self, step: DeletionStep) -> Option<&DeletionStepProgress> {
+        self.steps.iter().find(|progress| progress.step == step)
+    }
+
+    pub fn is_step_completed(&self, step: DeletionStep) -> bool {
+        self.step(step).map_or(false, |progress| progress.status == StepStatus::Completed)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct DeleteAccountRequest {
+    /// 必须与当前用户名一致，防止误操作
+    #[validate(length(min = 1, message = "请输入用户名确认删除"))]
+    pub confirm: String,
+
+    #[validate(length(max = 1000))]
+    pub reason: Option<String>,
+}
+
+/// 一次后台执行的结果
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct DataLifecycleRunReport {
+    pub resumed: usize,
+    pub hard_deleted: usize,
+}
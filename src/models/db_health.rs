@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use utoipa::{IntoParams, ToSchema};
+
+/// 定期完整性检查生成的数据库报告
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DbHealthReport {
+    pub generated_at: DateTime<Utc>,
+    /// 检查耗时（毫秒）
+    pub duration_ms: i64,
+    pub total_rows: i64,
+    pub tables: Vec<TableStats>,
+    /// 按行数排序的前几张表
+    pub largest_tables: Vec<TableStats>,
+    pub index_hints: Vec<IndexHint>,
+    /// 只包含存在孤立记录的关联
+    pub orphans: Vec<OrphanReport>,
+    pub action_plan: Vec<CleanupAction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TableStats {
+    pub table: String,
+    pub rows: i64,
+    pub index_count: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexHintKind {
+    /// 大表上的关联字段没有以它开头的索引
+    MissingIndex,
+    /// 两个索引的列完全相同
+    DuplicateIndex,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct IndexHint {
+    pub kind: IndexHintKind,
+    pub table: String,
+    pub columns: Vec<String>,
+    pub message: String,
+    /// 建议执行的语句
+    pub statement: String,
+}
+
+/// 指向不存在的记录的关联行，例如文章被物理删除后残留的 article_tag
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrphanReport {
+    pub table: String,
+    pub field: String,
+    pub target_table: String,
+    pub rows: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CleanupAction {
+    pub description: String,
+    pub statement: String,
+    /// 预计影响的行数，加索引时为空
+    pub affected_rows: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DbReportQuery {
+    /// 立即重新检查，而不是返回最近一次定期检查的结果
+    #[serde(default)]
+    pub refresh: bool,
+}
@@ -2,9 +2,10 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
+use utoipa::ToSchema;
 
 /// Represents the status of a domain
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, ToSchema)]
 #[sqlx(type_name = "domain_status", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
 pub enum DomainStatus {
@@ -19,7 +20,7 @@ pub enum DomainStatus {
 }
 
 /// Represents the type of domain
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, ToSchema)]
 #[sqlx(type_name = "domain_type", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
 pub enum DomainType {
@@ -29,8 +30,22 @@ pub enum DomainType {
     Custom,
 }
 
+/// How traffic for a custom domain is routed to the platform (and verified)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, sqlx::Type, PartialEq, ToSchema)]
+#[sqlx(type_name = "routing_mode", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum RoutingMode {
+    /// CNAME to the platform's domains host
+    #[default]
+    Cname,
+    /// A records pointing at the configured target IPs (apex domains)
+    A,
+    /// ALIAS/ANAME/flattened CNAME at the apex, verified by the resolved addresses
+    Alias,
+}
+
 /// SSL certificate status for a domain
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, ToSchema)]
 #[sqlx(type_name = "ssl_status", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
 pub enum SSLStatus {
@@ -46,8 +61,47 @@ pub enum SSLStatus {
     Failed,
 }
 
+/// Progress of a zero-downtime transfer-in of a domain that is still served elsewhere
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, ToSchema)]
+#[sqlx(type_name = "cutover_phase", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum CutoverPhase {
+    /// Waiting for the ownership TXT and ACME delegation records
+    AwaitingOwnership,
+    /// Ownership verified, SSL certificate being issued via DNS-01
+    IssuingCertificate,
+    /// Certificate issued, waiting for the CNAME to be switched to the platform
+    AwaitingDnsSwitch,
+    /// CNAME switch detected and the domain activated
+    Completed,
+}
+
+/// What an email-sending DNS record is for
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EmailRecordPurpose {
+    /// TXT record authorizing the platform's mail servers
+    Spf,
+    /// TXT record publishing the DKIM public key
+    Dkim,
+    /// CNAME that routes bounces back to the platform
+    ReturnPath,
+}
+
+/// Verification state of a domain's email-sending records
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailDomainStatus {
+    /// Records generated, waiting for the owner to publish them
+    Pending,
+    /// All records found; newsletters are sent from this domain
+    Verified,
+    /// Records were verified before but are no longer found
+    Failed,
+}
+
 /// Main domain model for publications
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct PublicationDomain {
     pub id: Uuid,
     pub publication_id: Uuid,
@@ -60,12 +114,22 @@ pub struct PublicationDomain {
     pub ssl_status: SSLStatus,
     pub ssl_expires_at: Option<DateTime<Utc>>,
     pub is_primary: bool,
+    #[serde(default)]
+    pub routing_mode: RoutingMode,
+    /// Set when the domain was added in transfer-in (cutover) mode
+    #[serde(default)]
+    pub cutover_phase: Option<CutoverPhase>,
+    /// When ownership was proven via TXT, before the domain points at the platform
+    #[serde(default)]
+    pub ownership_verified_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub cutover_completed_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 /// DNS verification record for custom domains
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct DomainVerificationRecord {
     pub id: Uuid,
     pub domain_id: Uuid,
@@ -78,42 +142,109 @@ pub struct DomainVerificationRecord {
     pub updated_at: DateTime<Utc>,
 }
 
+/// DNS record a custom domain must publish to send email from the platform
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EmailDnsRecord {
+    pub purpose: EmailRecordPurpose,
+    pub record_type: String,
+    pub record_name: String,
+    pub record_value: String,
+    pub is_verified: bool,
+}
+
+/// Email-sending (SPF/DKIM/return-path) configuration of a custom domain
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DomainEmailConfig {
+    pub domain_id: Uuid,
+    pub publication_id: Uuid,
+    /// Host newsletters are sent from (the custom domain without a wildcard)
+    pub sending_domain: String,
+    /// Local part of the sender address, e.g. `newsletter` for `newsletter@example.com`
+    pub from_local_part: String,
+    pub dkim_selector: String,
+    /// PKCS#1 PEM, never returned by the API
+    #[serde(default, skip_serializing)]
+    pub dkim_private_key: String,
+    pub records: Vec<EmailDnsRecord>,
+    pub status: EmailDomainStatus,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub last_checked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl DomainEmailConfig {
+    pub fn from_address(&self) -> String {
+        format!("{}@{}", self.from_local_part, self.sending_domain)
+    }
+
+    /// Envelope sender, on the host the return-path CNAME points at the platform
+    pub fn return_path(&self) -> Option<String> {
+        self.records
+            .iter()
+            .find(|record| record.purpose == EmailRecordPurpose::ReturnPath)
+            .map(|record| format!("bounces@{}", record.record_name))
+    }
+}
+
+/// Request to enable newsletter sending from a custom domain
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct ConfigureDomainEmailRequest {
+    /// Defaults to `newsletter`
+    pub from_local_part: Option<String>,
+}
+
+/// Response for email-sending record verification
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DomainEmailVerificationResponse {
+    pub domain_id: Uuid,
+    pub status: EmailDomainStatus,
+    pub records: Vec<EmailDnsRecord>,
+    pub verified: bool,
+    pub errors: Option<Vec<String>>,
+}
+
 /// Request to create a new subdomain
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateSubdomainRequest {
     pub subdomain: String,
     pub is_primary: Option<bool>,
 }
 
 /// Request to add a custom domain
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct AddCustomDomainRequest {
     pub domain: String,
     pub is_primary: Option<bool>,
+    /// Defaults to A records for apex domains and CNAME otherwise
+    pub routing_mode: Option<RoutingMode>,
+    /// Transfer-in mode: verify ownership and issue SSL while the domain still
+    /// points elsewhere, then activate as soon as the CNAME switch is detected
+    pub cutover: Option<bool>,
 }
 
 /// Request to verify a domain
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct VerifyDomainRequest {
     pub domain_id: Uuid,
 }
 
 /// Request to update domain settings
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpdateDomainRequest {
     pub is_primary: Option<bool>,
     pub ssl_enabled: Option<bool>,
 }
 
 /// Response for domain creation
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct DomainResponse {
     pub domain: PublicationDomain,
     pub verification_records: Option<Vec<DomainVerificationRecord>>,
 }
 
 /// Response for domain verification status
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct DomainVerificationResponse {
     pub domain_id: Uuid,
     pub status: DomainStatus,
@@ -122,15 +253,30 @@ pub struct DomainVerificationResponse {
     pub errors: Option<Vec<String>>,
 }
 
+/// Status report for a transfer-in cutover
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CutoverStatusResponse {
+    pub domain_id: Uuid,
+    pub domain: String,
+    pub phase: CutoverPhase,
+    pub ownership_verified: bool,
+    pub ssl_status: SSLStatus,
+    pub dns_switched: bool,
+    /// What the publication owner should do next
+    pub next_step: String,
+    pub verification_records: Vec<DomainVerificationRecord>,
+    pub cutover_completed_at: Option<DateTime<Utc>>,
+}
+
 /// Response for domain list
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct DomainListResponse {
     pub domains: Vec<PublicationDomain>,
     pub total: i64,
 }
 
 /// Domain availability check response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct DomainAvailabilityResponse {
     pub available: bool,
     pub domain: String,
@@ -139,7 +285,7 @@ pub struct DomainAvailabilityResponse {
 }
 
 /// SSL certificate information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SSLCertificateInfo {
     pub domain_id: Uuid,
     pub status: SSLStatus,
@@ -151,14 +297,14 @@ pub struct SSLCertificateInfo {
 }
 
 /// Request to check domain availability
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CheckDomainAvailabilityRequest {
     pub domain: String,
     pub domain_type: DomainType,
 }
 
 /// Domain statistics
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct DomainStats {
     pub total_domains: i64,
     pub active_domains: i64,
@@ -184,6 +330,11 @@ impl PublicationDomain {
         format!("{}://{}", protocol, domain)
     }
 
+    /// Whether this is a wildcard custom domain (`*.example.com`)
+    pub fn is_wildcard(&self) -> bool {
+        self.custom_domain.as_deref().is_some_and(|domain| domain.starts_with("*."))
+    }
+
     /// Check if SSL is enabled and active
     pub fn has_active_ssl(&self) -> bool {
         self.ssl_status == SSLStatus::Active
@@ -216,6 +367,30 @@ impl DomainVerificationRecord {
     }
 }
 
+impl ConfigureDomainEmailRequest {
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+
+        if let Some(local_part) = &self.from_local_part {
+            if local_part.is_empty() || local_part.len() > 64 {
+                errors.push("Sender name must be between 1 and 64 characters".to_string());
+            }
+            if !local_part.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+')) {
+                errors.push("Sender name can only contain letters, numbers, '.', '-', '_' and '+'".to_string());
+            }
+            if local_part.starts_with('.') || local_part.ends_with('.') {
+                errors.push("Sender name cannot start or end with a dot".to_string());
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
 /// Validation helpers
 impl CreateSubdomainRequest {
     pub fn validate(&self) -> Result<(), Vec<String>> {
@@ -266,8 +441,17 @@ impl AddCustomDomainRequest {
             errors.push("Invalid domain format".to_string());
         }
 
+        // A wildcard is only allowed as the leftmost label
+        let (is_wildcard, host_parts) = match parts.split_first() {
+            Some((&"*", rest)) => (true, rest),
+            _ => (false, &parts[..]),
+        };
+        if is_wildcard && host_parts.len() < 2 {
+            errors.push("Wildcard domains must cover a subdomain of a registered domain".to_string());
+        }
+
         // Check each part of the domain
-        for part in &parts {
+        for part in host_parts {
             if part.is_empty() {
                 errors.push("Domain parts cannot be empty".to_string());
                 break;
@@ -314,14 +498,34 @@ mod tests {
         let valid_domain = AddCustomDomainRequest {
             domain: "example.com".to_string(),
             is_primary: Some(true),
+            routing_mode: None,
+            cutover: None,
         };
         assert!(valid_domain.validate().is_ok());
 
+        let wildcard_domain = AddCustomDomainRequest {
+            domain: "*.example.com".to_string(),
+            is_primary: None,
+            routing_mode: None,
+            cutover: None,
+        };
+        assert!(wildcard_domain.validate().is_ok());
+
         let invalid_domain = AddCustomDomainRequest {
             domain: "invalid".to_string(),
             is_primary: Some(false),
+            routing_mode: None,
+            cutover: None,
         };
         assert!(invalid_domain.validate().is_err());
+
+        let misplaced_wildcard = AddCustomDomainRequest {
+            domain: "blog.*.example.com".to_string(),
+            is_primary: None,
+            routing_mode: None,
+            cutover: None,
+        };
+        assert!(misplaced_wildcard.validate().is_err());
     }
 
     #[test]
@@ -338,6 +542,10 @@ mod tests {
             ssl_status: SSLStatus::Active,
             ssl_expires_at: Some(Utc::now() + chrono::Duration::days(90)),
             is_primary: true,
+            routing_mode: RoutingMode::Cname,
+            cutover_phase: None,
+            ownership_verified_at: None,
+            cutover_completed_at: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExperimentStatus {
+    Running,
+    /// 已选出胜出版本，或作者手动结束
+    Completed,
+}
+
+impl ExperimentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExperimentStatus::Running => "running",
+            ExperimentStatus::Completed => "completed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ExperimentVariant {
+    /// a、b、c
+    pub key: String,
+    pub title: String,
+    /// 为空时使用文章当前的封面
+    pub cover_image_url: Option<String>,
+}
+
+/// 文章标题和封面的 A/B 实验，运行期间文章列表和推荐按读者分组展示不同版本
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArticleExperiment {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub article_id: String,
+    pub author_id: String,
+    pub variants: Vec<ExperimentVariant>,
+    pub status: ExperimentStatus,
+    /// 被采用的版本 key
+    pub winner: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl ArticleExperiment {
+    pub fn variant(&self, key: &str) -> Option<&ExperimentVariant> {
+        self.variants.iter().find(|v| v.key == key)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct ExperimentVariantInput {
+    #[validate(length(min = 1, max = 150))]
+    pub title: String,
+    #[validate(url)]
+    pub cover_image_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateExperimentRequest {
+    /// 2 到 3 个版本，按顺序分配 key a、b、c
+    #[validate(length(min = 2, max = 3))]
+    pub variants: Vec<ExperimentVariantInput>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PromoteVariantRequest {
+    pub variant: String,
+}
+
+/// 每个版本的曝光和点击，按读者去重
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct VariantStats {
+    pub key: String,
+    pub title: String,
+    pub cover_image_url: Option<String>,
+    /// 在列表或推荐中看到这个版本的读者数
+    pub impressions: i64,
+    /// 看到后打开了文章的读者数
+    pub clicks: i64,
+    pub click_through_rate: f64,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ExperimentResults {
+    pub experiment: ArticleExperiment,
+    pub variants: Vec<VariantStats>,
+    /// 当前点击率最高的版本，没有曝光时为空
+    pub leader: Option<String>,
+}
@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Follow {
     pub id: String,
     pub follower_id: String,
@@ -9,7 +10,7 @@ pub struct Follow {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct FollowUserInfo {
     pub user_id: String,
     pub username: String,
@@ -23,7 +24,7 @@ pub struct FollowUserInfo {
     pub is_followed_back: bool, // 该用户是否回关了当前用户
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct FollowStats {
     pub followers_count: i64,
     pub following_count: i64,
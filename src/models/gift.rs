@@ -0,0 +1,95 @@
+use crate::models::stripe::StripeIntentResponse;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use utoipa::ToSchema;
+
+/// 礼物内容：一篇付费文章或若干个月的创作者订阅
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GiftType {
+    Article,
+    Subscription,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GiftStatus {
+    /// 等待赠送人完成支付
+    Pending,
+    /// 已支付，等待领取
+    Paid,
+    Claimed,
+    /// 支付失败
+    Failed,
+    /// 付款被全额退款或发起争议，不能再领取，已兑换的权限被收回
+    Revoked,
+}
+
+/// 代他人购买的文章或订阅。收礼人可以是站内用户，也可以是一个邮箱地址，
+/// 通过领取令牌兑换
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Gift {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub sender_id: String,
+    pub gift_type: GiftType,
+    pub creator_id: String,
+    /// 文章标题或订阅计划名称，创建礼物时记录
+    pub title: String,
+    pub article_id: Option<String>,
+    pub plan_id: Option<String>,
+    /// 礼物订阅的月数
+    pub months: Option<u32>,
+    pub recipient_id: Option<String>,
+    pub recipient_email: Option<String>,
+    pub message: Option<String>,
+    pub amount: i64, // 支付金额（美分）
+    /// 代收税费，支付成功后记录
+    #[serde(default)]
+    pub tax_amount: i64,
+    pub currency: String,
+    pub stripe_payment_intent_id: Option<String>,
+    pub status: GiftStatus,
+    /// 只返回给赠送人和收礼人
+    pub claim_token: String,
+    pub claimed_by: Option<String>,
+    pub claimed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Gift {
+    /// 赠送人实际被扣款的金额（含税）
+    pub fn charged_amount(&self) -> i64 {
+        self.amount + self.tax_amount
+    }
+}
+
+/// 赠送请求。`recipient_id` 和 `recipient_email` 二选一
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateGiftRequest {
+    pub gift_type: GiftType,
+    pub article_id: Option<String>,
+    pub plan_id: Option<String>,
+
+    #[validate(range(min = 1, max = 12, message = "礼物订阅月数必须在1-12之间"))]
+    pub months: Option<u32>,
+
+    pub recipient_id: Option<String>,
+
+    #[validate(email(message = "收礼人邮箱格式不正确"))]
+    pub recipient_email: Option<String>,
+
+    #[validate(length(max = 500, message = "赠言不能超过500字符"))]
+    pub message: Option<String>,
+
+    pub payment_method_id: Option<String>, // Stripe payment method ID
+}
+
+/// 赠送响应，客户端用 `payment.client_secret` 完成支付
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct GiftPurchaseResponse {
+    pub gift: Gift,
+    pub payment: StripeIntentResponse,
+}
@@ -0,0 +1,153 @@
+use crate::models::article::ArticleListItem;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use utoipa::ToSchema;
+
+/// 出版物首页布局：精选区、按顺序排列的栏目和关于区块。
+/// 每个出版物一条，没有保存过时使用默认布局（最新文章作为精选，没有栏目）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HomepageLayout {
+    pub publication_id: String,
+    pub featured: FeaturedBlock,
+    pub sections: Vec<HomepageSection>,
+    pub about: Option<AboutBlock>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl HomepageLayout {
+    pub fn default_for(publication_id: &str) -> Self {
+        Self {
+            publication_id: publication_id.to_string(),
+            featured: FeaturedBlock::default(),
+            sections: Vec::new(),
+            about: None,
+            updated_at: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeaturedStyle {
+    /// 第一篇大图，其余并排
+    #[default]
+    Hero,
+    Grid,
+    Carousel,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionStyle {
+    #[default]
+    List,
+    Grid,
+}
+
+/// 精选区。`article_ids` 为置顶的文章，按顺序显示；为空时显示最新的文章
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct FeaturedBlock {
+    #[serde(default)]
+    pub style: FeaturedStyle,
+    #[serde(default)]
+    #[validate(length(max = 10))]
+    pub article_ids: Vec<String>,
+    #[serde(default = "FeaturedBlock::default_limit")]
+    #[validate(range(min = 1, max = 10))]
+    pub limit: usize,
+}
+
+impl FeaturedBlock {
+    fn default_limit() -> usize {
+        5
+    }
+}
+
+impl Default for FeaturedBlock {
+    fn default() -> Self {
+        Self {
+            style: FeaturedStyle::default(),
+            article_ids: Vec::new(),
+            limit: Self::default_limit(),
+        }
+    }
+}
+
+/// 栏目的文章来源
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SectionSource {
+    /// 带某个标签（slug，也可以是别名）或其下级标签的最新文章
+    Tag { tag: String },
+    /// 手动挑选的文章，按顺序显示
+    Manual { article_ids: Vec<String> },
+    /// 出版物的最新文章
+    Latest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct HomepageSection {
+    #[validate(length(min = 1, max = 100))]
+    pub title: String,
+    pub source: SectionSource,
+    #[serde(default)]
+    pub style: SectionStyle,
+    #[serde(default = "HomepageSection::default_limit")]
+    #[validate(range(min = 1, max = 20))]
+    pub limit: usize,
+}
+
+impl HomepageSection {
+    fn default_limit() -> usize {
+        6
+    }
+}
+
+/// 关于区块，正文为 Markdown，保存时渲染成清理过的 HTML
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AboutBlock {
+    pub title: Option<String>,
+    pub body: String,
+    pub body_html: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct AboutBlockRequest {
+    #[validate(length(max = 100))]
+    pub title: Option<String>,
+    #[validate(length(min = 1, max = 5000))]
+    pub body: String,
+}
+
+/// 整体替换首页布局
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UpdateHomepageRequest {
+    #[serde(default)]
+    pub featured: FeaturedBlock,
+    #[serde(default)]
+    #[validate(length(max = 12))]
+    pub sections: Vec<HomepageSection>,
+    pub about: Option<AboutBlockRequest>,
+}
+
+/// 填入文章后的首页，供自定义域名的前端直接渲染
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RenderedHomepage {
+    pub featured: RenderedFeatured,
+    pub sections: Vec<RenderedSection>,
+    pub about: Option<AboutBlock>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RenderedFeatured {
+    pub style: FeaturedStyle,
+    pub articles: Vec<ArticleListItem>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RenderedSection {
+    pub title: String,
+    pub style: SectionStyle,
+    pub articles: Vec<ArticleListItem>,
+}
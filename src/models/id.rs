@@ -0,0 +1,116 @@
+//! 记录 ID 类型
+//!
+//! 数据库里的记录 ID 在不同位置有不同写法：`article:xxx`、`article:⟨xxx⟩`、
+//! `` article:`xxx` `` 或不带表名的 `xxx`。这里为各表定义 `RecordId` 别名，
+//! 统一保存不带表名的 ID，序列化时输出 `table:id`，反序列化时兼容以上所有写法
+//! 以及 SurrealDB 的 Thing 对象。
+
+pub use crate::services::database::{RecordId, Table};
+
+/// 去掉表名前缀和 ID 两侧的转义符号，返回不带表名的 ID
+pub fn bare_id<'a>(table: &str, id: &'a str) -> &'a str {
+    let id = id.trim();
+    let id = id
+        .strip_prefix(table)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .unwrap_or(id);
+
+    id.strip_prefix('`')
+        .and_then(|rest| rest.strip_suffix('`'))
+        .or_else(|| id.strip_prefix('⟨').and_then(|rest| rest.strip_suffix('⟩')))
+        .unwrap_or(id)
+}
+
+pub mod table {
+    //! 各记录 ID 对应的表标记类型
+    use super::Table;
+
+    macro_rules! tables {
+        ($($marker:ident => $table:literal),* $(,)?) => {
+            $(
+                #[doc = concat!("`", $table, "` 表")]
+                #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+                pub struct $marker;
+
+                impl Table for $marker {
+                    const NAME: &'static str = $table;
+                }
+            )*
+        };
+    }
+
+    tables! {
+        Article => "article",
+        User => "user",
+        Publication => "publication",
+        PublicationDomain => "publication_domain",
+        Comment => "comment",
+        Tag => "tag",
+        Subscription => "subscription",
+        StripeSubscription => "stripe_subscription",
+        ConnectAccount => "connect_account",
+        WebhookEvent => "webhook_event",
+    }
+}
+
+/// 文章 ID（`article` 表）
+pub type ArticleId = RecordId<table::Article>;
+/// 用户 ID（Rainbow-Auth 用户）
+pub type UserId = RecordId<table::User>;
+/// 出版物 ID（`publication` 表）
+pub type PublicationId = RecordId<table::Publication>;
+/// 域名 ID（`publication_domain` 表）
+pub type DomainId = RecordId<table::PublicationDomain>;
+/// 评论 ID（`comment` 表）
+pub type CommentId = RecordId<table::Comment>;
+/// 标签 ID（`tag` 表）
+pub type TagId = RecordId<table::Tag>;
+/// 会员订阅 ID（`subscription` 表）
+pub type SubscriptionId = RecordId<table::Subscription>;
+/// Stripe 订阅 ID（`stripe_subscription` 表）
+pub type StripeSubscriptionId = RecordId<table::StripeSubscription>;
+/// Stripe Connect 账户 ID（`connect_account` 表）
+pub type ConnectAccountId = RecordId<table::ConnectAccount>;
+/// Stripe webhook 事件 ID（`webhook_event` 表）
+pub type WebhookEventId = RecordId<table::WebhookEvent>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_id_forms_normalize() {
+        let expected = ArticleId::new("0b6c1a2e-9d8f-4c3b-a1e2-3f4d5e6a7b8c");
+        for raw in [
+            "article:0b6c1a2e-9d8f-4c3b-a1e2-3f4d5e6a7b8c",
+            "article:`0b6c1a2e-9d8f-4c3b-a1e2-3f4d5e6a7b8c`",
+            "article:⟨0b6c1a2e-9d8f-4c3b-a1e2-3f4d5e6a7b8c⟩",
+            " 0b6c1a2e-9d8f-4c3b-a1e2-3f4d5e6a7b8c ",
+        ] {
+            assert_eq!(ArticleId::new(raw), expected, "{}", raw);
+        }
+
+        assert_eq!(expected.as_str(), "0b6c1a2e-9d8f-4c3b-a1e2-3f4d5e6a7b8c");
+        assert_eq!(expected.to_string(), "article:0b6c1a2e-9d8f-4c3b-a1e2-3f4d5e6a7b8c");
+        // 其他表的前缀不会被误删
+        assert_eq!(ArticleId::new("user:abc").as_str(), "user:abc");
+        assert_eq!(DomainId::new("publication_domain:abc").as_str(), "abc");
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let id: PublicationId = serde_json::from_str("\"abc\"").unwrap();
+        assert_eq!(serde_json::to_string(&id).unwrap(), "\"publication:abc\"");
+
+        let thing = serde_json::json!({ "tb": "publication", "id": { "String": "abc" } });
+        let id: PublicationId = serde_json::from_value(thing).unwrap();
+        assert_eq!(id.as_str(), "abc");
+
+        let user: UserId = serde_json::from_value(serde_json::json!("user:42")).unwrap();
+        assert_eq!(user.as_str(), "42");
+
+        let tag = TagId::from_value(&serde_json::json!({ "tb": "tag", "id": "rust" })).unwrap();
+        assert_eq!(tag.to_record(), "tag:rust");
+        assert!(TagId::from_value(&serde_json::Value::Null).is_none());
+    }
+}
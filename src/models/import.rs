@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// 支持导入的外部平台格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    /// Medium 导出的 zip 包（posts/*.html）
+    Medium,
+    /// WordPress 导出的 WXR XML 文件
+    Wordpress,
+}
+
+impl ImportFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportFormat::Medium => "medium",
+            ImportFormat::Wordpress => "wordpress",
+        }
+    }
+
+    /// 未指定格式时根据文件内容判断：zip 文件视为 Medium 导出，其余视为 WXR
+    pub fn detect(data: &[u8]) -> Self {
+        if data.starts_with(b"PK\x03\x04") {
+            ImportFormat::Medium
+        } else {
+            ImportFormat::Wordpress
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ImportedArticle {
+    pub id: String,
+    pub title: String,
+    pub status: String,
+    pub source_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ImportFailure {
+    pub title: String,
+    pub error: String,
+}
+
+/// 一次文章导入的结果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ArticleImportReport {
+    pub format: ImportFormat,
+    pub posts_total: usize,
+    pub imported: Vec<ImportedArticle>,
+    /// 之前已经导入过的文章（按原文链接判断）
+    pub skipped_existing: usize,
+    pub failed: Vec<ImportFailure>,
+    pub images_imported: usize,
+    /// 下载失败的图片保留原地址
+    pub images_failed: usize,
+}
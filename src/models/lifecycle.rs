@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use utoipa::{IntoParams, ToSchema};
+
+/// 生命周期任务执行的操作
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LifecycleActionKind {
+    /// 提醒作者草稿即将被归档
+    DraftArchiveNotice,
+    DraftArchived,
+    /// 提醒用户账户长期未活跃（附数据导出入口）
+    AccountInactiveNotice,
+    AccountDeactivated,
+}
+
+impl LifecycleActionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LifecycleActionKind::DraftArchiveNotice => "draft_archive_notice",
+            LifecycleActionKind::DraftArchived => "draft_archived",
+            LifecycleActionKind::AccountInactiveNotice => "account_inactive_notice",
+            LifecycleActionKind::AccountDeactivated => "account_deactivated",
+        }
+    }
+
+    /// 只有实际改变了数据的操作可以撤销
+    pub fn is_reversible(&self) -> bool {
+        matches!(self, LifecycleActionKind::DraftArchived | LifecycleActionKind::AccountDeactivated)
+    }
+}
+
+/// 生命周期任务的审计记录，撤销时在原记录上标记
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct LifecycleAction {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub kind: LifecycleActionKind,
+    /// "article" 或 "user"
+    pub resource_type: String,
+    pub resource_id: String,
+    /// 被影响的用户（草稿作者或账户本人）
+    pub user_id: String,
+    /// 撤销所需的原始状态等信息
+    #[serde(default)]
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reverted_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reverted_by: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LifecycleActionQuery {
+    pub kind: Option<LifecycleActionKind>,
+    /// 仅管理员可以查看其他用户的记录
+    pub user_id: Option<String>,
+    #[serde(default)]
+    pub include_reverted: bool,
+    pub page: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+/// 一次生命周期任务的执行结果
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct LifecycleRunReport {
+    pub drafts_notified: usize,
+    pub drafts_archived: usize,
+    pub accounts_notified: usize,
+    pub accounts_deactivated: usize,
+}
+
+/// 用户数据导出
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AccountExport {
+    pub exported_at: DateTime<Utc>,
+    pub profile: serde_json::Value,
+    pub articles: Vec<serde_json::Value>,
+    pub comments: Vec<serde_json::Value>,
+    pub bookmarks: Vec<serde_json::Value>,
+}
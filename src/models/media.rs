@@ -1,9 +1,11 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use surrealdb::sql::Thing;
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct MediaFile {
+    #[schema(value_type = String)]
     pub id: Thing,
     pub user_id: String,
     pub filename: String,
@@ -14,10 +16,24 @@ pub struct MediaFile {
     pub height: Option<u32>,
     pub storage_path: String,
     pub public_url: String,
+    /// 同一图片的其他尺寸和格式，渲染正文时用于 srcset
+    #[serde(default)]
+    pub variants: Vec<MediaVariant>,
+    pub thumbnail_url: Option<String>,
+    /// 图片加载前显示的模糊占位
+    pub blurhash: Option<String>,
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MediaVariant {
+    pub url: String,
+    pub width: u32,
+    pub height: Option<u32>,
+    pub content_type: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct MediaUploadResponse {
     pub id: String,
     pub url: String,
@@ -26,9 +42,24 @@ pub struct MediaUploadResponse {
     pub content_type: String,
     pub width: Option<u32>,
     pub height: Option<u32>,
+    /// 与原图同格式的各尺寸，可直接用作 `<img srcset>`
+    pub srcset: Option<String>,
+    /// WebP / AVIF 的 srcset，用于 `<picture><source>`
+    pub sources: Vec<ImageSource>,
+    pub variants: Vec<MediaVariant>,
+    pub thumbnail_url: Option<String>,
+    pub blurhash: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// `<picture>` 中的一个 `<source>`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImageSource {
+    #[serde(rename = "type")]
+    pub content_type: String,
+    pub srcset: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct MediaStats {
     pub total_files: i64,
     pub total_size: i64,
@@ -37,6 +68,61 @@ pub struct MediaStats {
 }
 
 impl MediaFile {
+    /// 原图和同格式的各尺寸组成的 srcset，没有其他尺寸时返回 None
+    pub fn srcset(&self) -> Option<String> {
+        let mut candidates: Vec<(u32, &str)> = self
+            .variants
+            .iter()
+            .filter(|v| v.content_type == self.content_type)
+            .map(|v| (v.width, v.url.as_str()))
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+
+        if let Some(width) = self.width {
+            candidates.push((width, &self.public_url));
+        }
+        Self::join_candidates(candidates)
+    }
+
+    /// 其他格式的 srcset，AVIF 在前
+    pub fn sources(&self) -> Vec<ImageSource> {
+        ["image/avif", "image/webp"]
+            .into_iter()
+            .filter(|content_type| *content_type != self.content_type)
+            .filter_map(|content_type| {
+                let candidates: Vec<(u32, &str)> = self
+                    .variants
+                    .iter()
+                    .filter(|v| v.content_type == content_type)
+                    .map(|v| (v.width, v.url.as_str()))
+                    .collect();
+                Self::join_candidates(candidates).map(|srcset| ImageSource {
+                    content_type: content_type.to_string(),
+                    srcset,
+                })
+            })
+            .collect()
+    }
+
+    fn join_candidates(mut candidates: Vec<(u32, &str)>) -> Option<String> {
+        if candidates.is_empty() {
+            return None;
+        }
+
+        candidates.sort_by_key(|(width, _)| *width);
+        candidates.dedup_by_key(|(width, _)| *width);
+
+        Some(
+            candidates
+                .iter()
+                .map(|(width, url)| format!("{} {}w", url, width))
+                .collect::<Vec<_>>()
+                .join(", "),
+        )
+    }
+
     pub fn to_response(&self) -> MediaUploadResponse {
         MediaUploadResponse {
             id: self.id.id.to_string(),
@@ -46,6 +132,11 @@ impl MediaFile {
             content_type: self.content_type.clone(),
             width: self.width,
             height: self.height,
+            srcset: self.srcset(),
+            sources: self.sources(),
+            variants: self.variants.clone(),
+            thumbnail_url: self.thumbnail_url.clone(),
+            blurhash: self.blurhash.clone(),
         }
     }
 }
\ No newline at end of file
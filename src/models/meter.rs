@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use utoipa::ToSchema;
+
+/// 计量付费墙的读者。登录用户按账号计数，匿名读者按 cookie 计数
+#[derive(Debug, Clone)]
+pub struct MeterReader {
+    pub key: String,
+    /// 匿名读者还没有计量 cookie 时需要写入的值
+    pub new_cookie: Option<String>,
+}
+
+/// 出版物的计量设置，没有设置过时使用 `METERED_FREE_ARTICLES`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MeterSettings {
+    pub publication_id: String,
+    /// 每月免费阅读的付费文章篇数，0 表示不提供免费额度
+    pub free_articles_per_month: u32,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UpdateMeterSettingsRequest {
+    #[validate(range(max = 100))]
+    pub free_articles_per_month: u32,
+}
+
+/// 读者本月的免费额度使用情况，客户端据此显示“本月还可免费阅读 N 篇”
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MeterStatus {
+    pub quota: u32,
+    pub used: u32,
+    pub remaining: u32,
+    /// 这篇文章是否通过免费额度阅读（本月读过的文章再次打开不重复计数）
+    pub granted: bool,
+    /// 额度在下个月第一天（UTC）重置
+    pub resets_at: DateTime<Utc>,
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MilestoneKind {
+    /// 累计浏览数达到阈值
+    Views,
+    /// 累计点赞数达到阈值
+    Claps,
+    /// 进入实时热度榜前 N 名，阈值为名次
+    Trending,
+}
+
+impl MilestoneKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MilestoneKind::Views => "views",
+            MilestoneKind::Claps => "claps",
+            MilestoneKind::Trending => "trending",
+        }
+    }
+}
+
+/// 文章达成的里程碑，展示在作者主页
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArticleMilestone {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub user_id: String,
+    pub article_id: String,
+    pub article_title: String,
+    pub article_slug: String,
+    pub kind: MilestoneKind,
+    pub threshold: i64,
+    pub achieved_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct MilestoneQuery {
+    pub limit: Option<usize>,
+}
@@ -1,3 +1,4 @@
+pub mod id;
 pub mod user;
 pub mod article;
 pub mod comment;
@@ -19,8 +20,49 @@ pub mod websocket;
 pub mod domain;
 pub mod response;
 pub mod media;
+pub mod newsletter;
+pub mod webmention;
+pub mod cors;
+pub mod bot;
+pub mod milestone;
+pub mod syndication;
+pub mod reaction;
+pub mod collaborator;
+pub mod social_share;
+pub mod collaborative_edit;
+pub mod template;
+pub mod reading_progress;
+pub mod style_guide;
+pub mod attachment;
+pub mod view_tracking;
+pub mod sponsorship;
+pub mod cta;
+pub mod pseudonym;
+pub mod db_health;
+pub mod experiment;
+pub mod import;
+pub mod lifecycle;
+pub mod content_transform;
+pub mod reputation;
+pub mod reading_queue;
+pub mod revision;
+pub mod plugin;
+pub mod homepage;
+pub mod theme;
+pub mod page;
+pub mod meter;
+pub mod gift;
+pub mod oauth;
+pub mod two_factor;
+pub mod admin;
+pub mod report;
+pub mod data_lifecycle;
+pub mod speech;
+pub mod trash;
+pub mod preview;
 
 // 重新导出常用类型
+pub use id::{ArticleId, DomainId, PublicationId, UserId};
 pub use user::*;
 pub use article::*;
 pub use comment::*;
@@ -41,4 +83,31 @@ pub use stripe::*;
 pub use websocket::*;
 pub use domain::*;
 pub use response::*;
-pub use media::*;
\ No newline at end of file
+pub use media::*;
+pub use reading_queue::*;
+pub use reputation::*;
+pub use revision::*;
+pub use plugin::*;
+pub use content_transform::*;
+pub use lifecycle::*;
+pub use import::*;
+pub use newsletter::*;
+pub use webmention::*;
+pub use cors::*;
+pub use bot::*;
+pub use milestone::*;
+pub use syndication::*;
+pub use reaction::*;
+pub use collaborator::*;
+pub use social_share::*;
+pub use collaborative_edit::*;
+pub use template::*;
+pub use reading_progress::*;
+pub use style_guide::*;
+pub use attachment::*;
+pub use view_tracking::*;
+pub use sponsorship::*;
+pub use cta::*;
+pub use pseudonym::*;
+pub use db_health::*;
+pub use experiment::*;
\ No newline at end of file
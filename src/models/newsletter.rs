@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use utoipa::{IntoParams, ToSchema};
+
+/// 一期 Newsletter 的发送状态
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NewsletterStatus {
+    Sending,
+    /// 全部收件人发送成功
+    Sent,
+    /// 部分收件人发送失败
+    PartiallySent,
+    Failed,
+}
+
+impl NewsletterStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NewsletterStatus::Sending => "sending",
+            NewsletterStatus::Sent => "sent",
+            NewsletterStatus::PartiallySent => "partially_sent",
+            NewsletterStatus::Failed => "failed",
+        }
+    }
+}
+
+/// 单个收件人的投递状态
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    Pending,
+    Sent,
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Sent => "sent",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+}
+
+/// 出版物发出的一期 Newsletter（对应一篇文章）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Newsletter {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub publication_id: String,
+    pub article_id: String,
+    pub subject: String,
+    pub sent_by: String,
+    pub status: NewsletterStatus,
+    /// 发送使用的邮件服务商（smtp / sendgrid）
+    pub provider: String,
+    pub recipient_count: i64,
+    pub sent_count: i64,
+    pub failed_count: i64,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// 单个收件人的投递记录，退订令牌按收件人生成
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NewsletterDelivery {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub newsletter_id: String,
+    pub publication_id: String,
+    pub user_id: String,
+    pub email: String,
+    pub status: DeliveryStatus,
+    pub provider_message_id: Option<String>,
+    pub error: Option<String>,
+    #[serde(skip_serializing)]
+    pub unsubscribe_token: String,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct SendNewsletterRequest {
+    pub article_id: String,
+    /// 默认使用文章标题
+    #[validate(length(min = 1, max = 200))]
+    pub subject: Option<String>,
+    /// 只发送给付费订阅者
+    #[serde(default)]
+    pub subscribers_only: bool,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct NewsletterListQuery {
+    pub page: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DeliveryListQuery {
+    pub status: Option<DeliveryStatus>,
+    pub page: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UnsubscribeQuery {
+    pub token: String,
+}
@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+use crate::models::websocket::NotificationConfig;
+use validator::Validate;
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Notification {
     pub id: String,
     pub recipient_id: String,
@@ -14,7 +18,7 @@ pub struct Notification {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CreateNotificationRequest {
     pub recipient_id: String,
     pub notification_type: NotificationType,
@@ -23,7 +27,7 @@ pub struct CreateNotificationRequest {
     pub data: serde_json::Value,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub enum NotificationType {
     Follow,
     ArticlePublished,
@@ -31,4 +35,311 @@ pub enum NotificationType {
     CommentReply,
     Clap,
     Mention,
-}
\ No newline at end of file
+    PublicationAnomaly,
+    /// 草稿归档、账户停用等生命周期提醒
+    AccountLifecycle,
+    /// 文章达成浏览、点赞或上榜里程碑
+    Milestone,
+    /// 被邀请成为文章合著者
+    CollaborationInvite,
+    /// 付款、退款和争议
+    Payment,
+    /// 内容下架、账户停用等平台审核结果
+    Moderation,
+    /// 发给审核员的新举报和自动隐藏提醒
+    ReportQueue,
+}
+impl NotificationType {
+    /// 对应 notification_config.notification_types 中的偏好键
+    pub fn preference_key(&self) -> &'static str {
+        match self {
+            NotificationType::Follow => "new_follower",
+            NotificationType::ArticlePublished => "new_article",
+            NotificationType::Comment | NotificationType::CommentReply | NotificationType::Mention => "new_comment",
+            NotificationType::Clap => "article_clap",
+            NotificationType::PublicationAnomaly => "publication_analytics",
+            NotificationType::AccountLifecycle => "account_lifecycle",
+            NotificationType::Milestone => "article_milestone",
+            NotificationType::CollaborationInvite => "collaboration_invite",
+            NotificationType::Payment => "payment_update",
+            NotificationType::Moderation => "moderation",
+            NotificationType::ReportQueue => "report_queue",
+        }
+    }
+
+    /// 会发送浏览器推送的通知：回复、新关注者和关注作者的新文章
+    pub fn supports_web_push(&self) -> bool {
+        matches!(
+            self,
+            NotificationType::CommentReply | NotificationType::Follow | NotificationType::ArticlePublished
+        )
+    }
+
+    /// 事务性通知不受邮件偏好和摘要窗口影响，总是立即发送
+    pub fn is_transactional(&self) -> bool {
+        matches!(self, NotificationType::AccountLifecycle | NotificationType::Payment | NotificationType::Moderation)
+    }
+}
+
+/// 邮件通知的投递窗口
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestWindow {
+    Immediate,
+    Hourly,
+    Daily,
+}
+
+impl Default for DigestWindow {
+    fn default() -> Self {
+        Self::Hourly
+    }
+}
+
+impl DigestWindow {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DigestWindow::Immediate => "immediate",
+            DigestWindow::Hourly => "hourly",
+            DigestWindow::Daily => "daily",
+        }
+    }
+
+    /// 计算在该窗口下事件应当被投递的时间（窗口结束时刻，UTC）
+    pub fn next_delivery_at(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        use chrono::{Duration, DurationRound};
+
+        match self {
+            DigestWindow::Immediate => now,
+            DigestWindow::Hourly => now.duration_trunc(Duration::hours(1)).unwrap_or(now) + Duration::hours(1),
+            DigestWindow::Daily => now.duration_trunc(Duration::days(1)).unwrap_or(now) + Duration::days(1),
+        }
+    }
+}
+
+/// 等待合并进摘要邮件的通知
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct EmailDigestItem {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub recipient_id: String,
+    pub notification_id: String,
+    pub notification_type: String,
+    pub title: String,
+    pub message: String,
+    pub window: DigestWindow,
+    pub deliver_after: DateTime<Utc>,
+    #[serde(default)]
+    pub delivered_at: Option<DateTime<Utc>>,
+    /// 无法投递（例如收件人没有邮箱地址）的时间，失败的项不再重试
+    #[serde(default)]
+    pub failed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub failure_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 可以单独设置偏好的通知事件，与 `NotificationType::preference_key` 对应
+pub const NOTIFICATION_EVENTS: &[&str] = &[
+    "new_follower",
+    "new_article",
+    "new_comment",
+    "article_clap",
+    "publication_analytics",
+    "account_lifecycle",
+    "article_milestone",
+    "collaboration_invite",
+    "payment_update",
+    "moderation",
+    "report_queue",
+];
+
+/// 一类通知在各渠道的开关
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub struct ChannelPreference {
+    /// 站内通知列表
+    pub in_app: bool,
+    pub email: bool,
+    /// 实时推送（WebSocket 等）
+    pub push: bool,
+    /// 邮件立即发送还是合并进摘要
+    #[serde(default)]
+    pub digest: DigestWindow,
+}
+
+impl ChannelPreference {
+    const ALL: Self = Self {
+        in_app: true,
+        email: true,
+        push: true,
+        digest: DigestWindow::Immediate,
+    };
+
+    pub fn is_muted(&self) -> bool {
+        !self.in_app && !self.email && !self.push
+    }
+}
+
+/// 关注动态摘要邮件的频率
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ActivityDigestFrequency {
+    Off,
+    Daily,
+    Weekly,
+}
+
+impl Default for ActivityDigestFrequency {
+    fn default() -> Self {
+        Self::Off
+    }
+}
+
+impl ActivityDigestFrequency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActivityDigestFrequency::Off => "off",
+            ActivityDigestFrequency::Daily => "daily",
+            ActivityDigestFrequency::Weekly => "weekly",
+        }
+    }
+
+    /// 两封摘要之间的间隔，关闭时为 `None`
+    pub fn period(&self) -> Option<chrono::Duration> {
+        match self {
+            ActivityDigestFrequency::Off => None,
+            ActivityDigestFrequency::Daily => Some(chrono::Duration::days(1)),
+            ActivityDigestFrequency::Weekly => Some(chrono::Duration::weeks(1)),
+        }
+    }
+}
+
+/// 用户按事件类型设置的通知偏好
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NotificationPreferences {
+    pub user_id: String,
+    pub events: BTreeMap<String, ChannelPreference>,
+    /// 关注的作者、出版物和标签的新文章摘要
+    #[serde(default)]
+    pub activity_digest: ActivityDigestFrequency,
+    #[serde(default)]
+    pub activity_digest_sent_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl NotificationPreferences {
+    /// 没有保存过偏好时从旧的 notification_config 推导，保留已有的邮件、推送和摘要设置
+    pub fn from_config(config: &NotificationConfig) -> Self {
+        let events = NOTIFICATION_EVENTS
+            .iter()
+            .map(|&event| {
+                let enabled = config.notification_types.iter().any(|t| t == event);
+                let preference = ChannelPreference {
+                    in_app: event != "article_milestone" || config.milestone_notifications,
+                    email: config.email_notifications && enabled,
+                    push: config.push_notifications && config.websocket_notifications,
+                    digest: config.email_digest_window,
+                };
+                (event.to_string(), preference)
+            })
+            .collect();
+
+        Self {
+            user_id: config.user_id.clone(),
+            events,
+            activity_digest: ActivityDigestFrequency::Off,
+            activity_digest_sent_at: None,
+            updated_at: None,
+        }
+    }
+
+    /// 某类通知实际启用的渠道。事务性通知不受偏好影响，邮件总是立即发送
+    pub fn channels_for(&self, notification_type: &NotificationType) -> ChannelPreference {
+        if notification_type.is_transactional() {
+            return ChannelPreference::ALL;
+        }
+
+        self.events
+            .get(notification_type.preference_key())
+            .copied()
+            .unwrap_or(ChannelPreference::ALL)
+    }
+}
+
+/// 更新通知偏好，只修改请求中出现的事件和字段
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct UpdateNotificationPreferencesRequest {
+    #[serde(default)]
+    pub events: HashMap<String, UpdateChannelPreference>,
+    pub activity_digest: Option<ActivityDigestFrequency>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct UpdateChannelPreference {
+    pub in_app: Option<bool>,
+    pub email: Option<bool>,
+    pub push: Option<bool>,
+    pub digest: Option<DigestWindow>,
+}
+
+/// 浏览器的 Web Push 订阅
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PushSubscription {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub user_id: String,
+    pub endpoint: String,
+    /// 浏览器的 P-256 公钥（URL 安全 Base64）
+    pub p256dh: String,
+    /// 认证密钥（URL 安全 Base64）
+    pub auth: String,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 与浏览器 `PushSubscription.toJSON()` 的结构一致
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreatePushSubscriptionRequest {
+    #[validate(url(message = "推送地址格式不正确"), length(max = 1000, message = "推送地址过长"))]
+    pub endpoint: String,
+    pub keys: PushSubscriptionKeys,
+    pub user_agent: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PushSubscriptionKeys {
+    pub p256dh: String,
+    pub auth: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_digest_window_delivery_time() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 10, 14, 25, 7).unwrap();
+
+        assert_eq!(DigestWindow::Immediate.next_delivery_at(now), now);
+        assert_eq!(
+            DigestWindow::Hourly.next_delivery_at(now),
+            Utc.with_ymd_and_hms(2024, 3, 10, 15, 0, 0).unwrap()
+        );
+        assert_eq!(
+            DigestWindow::Daily.next_delivery_at(now),
+            Utc.with_ymd_and_hms(2024, 3, 11, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_transactional_notifications_ignore_preferences() {
+        let mut config = NotificationConfig::default_for("user-1");
+        config.email_notifications = false;
+        let preferences = NotificationPreferences::from_config(&config);
+
+        assert!(!preferences.channels_for(&NotificationType::Follow).email);
+        assert!(preferences.channels_for(&NotificationType::Follow).in_app);
+        assert_eq!(preferences.channels_for(&NotificationType::Payment), ChannelPreference::ALL);
+    }
+}
@@ -0,0 +1,273 @@
+use axum::{
+    http::{header, Method, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use validator::Validate;
+use utoipa::{IntoParams, ToSchema};
+
+/// 第三方应用可以申请的权限范围
+pub const OAUTH_SCOPES: &[(&str, &str)] = &[
+    ("read", "读取你的资料、文章、书签和通知"),
+    ("profile:write", "修改你的个人资料"),
+    ("articles:write", "创建、编辑和发布你的文章与系列，上传图片"),
+    ("comments:write", "以你的身份发表和删除评论"),
+    ("social:write", "关注、收藏、鼓掌和管理你的阅读列表"),
+];
+
+/// 第三方令牌一律不能访问的路由：付款、收益、应用和授权管理、两步验证、管理后台
+const OAUTH_FORBIDDEN_PREFIXES: &[&str] = &[
+    "/api/blog/oauth",
+    "/api/blog/auth/2fa",
+    "/api/blog/admin",
+    "/api/blog/payments",
+    "/api/blog/stripe",
+    "/api/blog/revenue",
+    "/api/blog/subscriptions",
+    "/api/blog/gifts",
+    "/api/blog/lifecycle",
+    "/api/blog/domains",
+    "/api/blog/diagnostics",
+];
+
+/// 写操作需要的权限范围，按路径前缀匹配
+const OAUTH_WRITE_SCOPES: &[(&str, &str)] = &[
+    ("/api/blog/users", "profile:write"),
+    ("/api/blog/articles", "articles:write"),
+    ("/api/blog/series", "articles:write"),
+    ("/api/blog/media", "articles:write"),
+    ("/api/blog/attachments", "articles:write"),
+    ("/api/blog/comments", "comments:write"),
+    ("/api/blog/follows", "social:write"),
+    ("/api/blog/bookmarks", "social:write"),
+    ("/api/blog/reading-queue", "social:write"),
+];
+
+pub fn is_known_scope(scope: &str) -> bool {
+    OAUTH_SCOPES.iter().any(|(name, _)| *name == scope)
+}
+
+/// 第三方令牌访问该路由需要的权限范围，返回 None 表示该路由不对第三方开放
+pub fn required_scope(method: &Method, path: &str) -> Option<&'static str> {
+    if OAUTH_FORBIDDEN_PREFIXES.iter().any(|prefix| path.starts_with(prefix)) {
+        return None;
+    }
+    if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        return Some("read");
+    }
+
+    OAUTH_WRITE_SCOPES
+        .iter()
+        .find(|(prefix, _)| path.starts_with(prefix))
+        .map(|(_, scope)| *scope)
+}
+
+/// 把空格分隔的 scope 参数拆成去重后的列表
+pub fn parse_scopes(scope: &str) -> Vec<String> {
+    let mut scopes: Vec<String> = scope.split_whitespace().map(str::to_string).collect();
+    scopes.sort();
+    scopes.dedup();
+    scopes
+}
+
+/// 注册的第三方应用
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OAuthClient {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub client_id: String,
+    pub owner_id: String,
+    pub name: String,
+    pub redirect_uris: Vec<String>,
+    /// 应用最多可以申请的权限范围
+    pub scopes: Vec<String>,
+    /// 机密客户端（有服务端）在换取令牌时必须提供密钥；移动端等公开客户端只依赖 PKCE
+    pub confidential: bool,
+    #[serde(default, skip_serializing)]
+    pub secret_hash: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateOAuthClientRequest {
+    #[validate(length(min = 1, max = 100, message = "应用名称长度必须在1-100字符之间"))]
+    pub name: String,
+
+    #[validate(length(min = 1, max = 10, message = "回调地址数量必须在1-10个之间"))]
+    pub redirect_uris: Vec<String>,
+
+    #[validate(length(min = 1, message = "至少需要一个权限范围"))]
+    pub scopes: Vec<String>,
+
+    #[serde(default)]
+    pub confidential: bool,
+}
+
+/// 创建应用的响应，`client_secret` 只在创建时返回一次
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OAuthClientCreated {
+    pub client: OAuthClient,
+    pub client_secret: Option<String>,
+}
+
+/// 授权请求参数，GET 时用于展示授权页，POST 时附带用户的选择
+#[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuthorizeRequest {
+    pub response_type: String,
+    pub client_id: String,
+    pub redirect_uri: String,
+    #[serde(default)]
+    pub scope: String,
+    pub state: Option<String>,
+    pub code_challenge: String,
+    #[serde(default = "default_challenge_method")]
+    pub code_challenge_method: String,
+}
+
+fn default_challenge_method() -> String {
+    "S256".to_string()
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct AuthorizeDecision {
+    #[serde(flatten)]
+    pub request: AuthorizeRequest,
+    pub approve: bool,
+}
+
+/// 授权页需要展示的信息
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AuthorizePrompt {
+    pub client_id: String,
+    pub client_name: String,
+    pub redirect_uri: String,
+    pub scopes: Vec<ScopeDescription>,
+    /// 用户之前已同意过全部这些权限
+    pub previously_granted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ScopeDescription {
+    pub scope: String,
+    pub description: String,
+}
+
+/// 令牌端点的表单参数
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct TokenRequest {
+    pub grant_type: String,
+    pub code: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub code_verifier: Option<String>,
+    pub refresh_token: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    /// 刷新时可以申请更小的权限范围
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TokenResponse {
+    pub access_token: String,
+    #[schema(value_type = String)]
+    pub token_type: &'static str,
+    pub expires_in: i64,
+    pub refresh_token: String,
+    pub scope: String,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct RevokeRequest {
+    pub token: String,
+}
+
+/// 用户授权给某个应用的记录
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OAuthGrant {
+    pub client_id: String,
+    pub client_name: String,
+    pub scopes: Vec<String>,
+    pub granted_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// 通过第三方令牌认证的请求，由 `auth_middleware` 放入请求扩展
+#[derive(Debug, Clone)]
+pub struct OAuthAccess {
+    pub client_id: String,
+    pub scopes: Vec<String>,
+}
+
+/// 令牌端点按 RFC 6749 第 5.2 节返回的错误
+#[derive(Debug, Clone)]
+pub struct OAuthError {
+    #[schema(value_type = String)]
+    pub error: &'static str,
+    pub description: String,
+}
+
+impl OAuthError {
+    pub fn invalid_request(description: &str) -> Self {
+        Self { error: "invalid_request", description: description.to_string() }
+    }
+
+    pub fn invalid_client(description: &str) -> Self {
+        Self { error: "invalid_client", description: description.to_string() }
+    }
+
+    pub fn invalid_grant(description: &str) -> Self {
+        Self { error: "invalid_grant", description: description.to_string() }
+    }
+
+    pub fn invalid_scope(description: &str) -> Self {
+        Self { error: "invalid_scope", description: description.to_string() }
+    }
+
+    pub fn server_error(description: &str) -> Self {
+        Self { error: "server_error", description: description.to_string() }
+    }
+}
+
+impl From<crate::error::AppError> for OAuthError {
+    fn from(error: crate::error::AppError) -> Self {
+        Self::server_error(&error.to_string())
+    }
+}
+
+impl IntoResponse for OAuthError {
+    fn into_response(self) -> Response {
+        let status = match self.error {
+            "invalid_client" => StatusCode::UNAUTHORIZED,
+            "server_error" => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_REQUEST,
+        };
+
+        (
+            status,
+            [(header::CACHE_CONTROL, "no-store")],
+            Json(json!({
+                "error": self.error,
+                "error_description": self.description,
+            })),
+        )
+            .into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_required_scope() {
+        assert_eq!(required_scope(&Method::GET, "/api/blog/articles/abc"), Some("read"));
+        assert_eq!(required_scope(&Method::POST, "/api/blog/articles"), Some("articles:write"));
+        assert_eq!(required_scope(&Method::DELETE, "/api/blog/comments/c1"), Some("comments:write"));
+        assert_eq!(required_scope(&Method::GET, "/api/blog/payments/purchases"), None);
+        assert_eq!(required_scope(&Method::POST, "/api/blog/newsletters"), None);
+    }
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use utoipa::ToSchema;
+
+/// 出版物的静态页面（关于、联系方式等），发布后在出版物域名的 `/{slug}` 访问
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PublicationPage {
+    pub id: String,
+    pub publication_id: String,
+    pub title: String,
+    pub slug: String,
+    /// Markdown 正文
+    pub content: String,
+    pub content_html: String,
+    pub status: PageStatus,
+    /// 是否显示在出版物导航中
+    pub show_in_nav: bool,
+    /// 导航中的顺序，从小到大
+    pub nav_order: i64,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PageStatus {
+    #[default]
+    Draft,
+    Published,
+}
+
+impl PageStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PageStatus::Draft => "draft",
+            PageStatus::Published => "published",
+        }
+    }
+}
+
+/// 导航中的页面链接
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PageNavItem {
+    pub title: String,
+    pub slug: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreatePageRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub title: String,
+    /// 不提供时由标题生成
+    #[validate(length(min = 1, max = 80))]
+    pub slug: Option<String>,
+    #[validate(length(max = 100000))]
+    pub content: String,
+    pub status: Option<PageStatus>,
+    pub show_in_nav: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UpdatePageRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub title: Option<String>,
+    #[validate(length(min = 1, max = 80))]
+    pub slug: Option<String>,
+    #[validate(length(max = 100000))]
+    pub content: Option<String>,
+    pub status: Option<PageStatus>,
+    pub show_in_nav: Option<bool>,
+}
+
+/// 按给出的顺序重新排列导航，未列出的页面排在后面
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct ReorderPagesRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub page_ids: Vec<String>,
+}
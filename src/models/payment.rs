@@ -2,9 +2,10 @@ use crate::models::stripe::StripeIntentResponse;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
+use utoipa::{IntoParams, ToSchema};
 
 /// 付费内容访问控制
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ContentAccess {
     pub article_id: String,
     pub user_id: String,
@@ -16,7 +17,7 @@ pub struct ContentAccess {
 }
 
 /// 访问类型
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum AccessType {
     Free,         // 免费内容
@@ -24,10 +25,11 @@ pub enum AccessType {
     OneTime,      // 单次购买
     Author,       // 作者本人
     Preview,      // 预览访问（部分内容）
+    Metered,      // 计量付费墙的免费额度
 }
 
 /// 付费内容预览
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ContentPreview {
     pub article_id: String,
     pub preview_content: String,
@@ -39,13 +41,13 @@ pub struct ContentPreview {
 }
 
 /// 内容访问请求
-#[derive(Debug, Clone, Deserialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
 pub struct ContentAccessRequest {
     pub article_id: String,
 }
 
 /// 文章收费设置
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct ArticlePricingRequest {
     #[validate(range(min = 0, message = "价格不能为负数"))]
     pub price: Option<i64>, // 单次购买价格（美分），None表示仅订阅
@@ -60,7 +62,7 @@ pub struct ArticlePricingRequest {
 }
 
 /// 文章定价信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ArticlePricing {
     pub article_id: String,
     pub is_paid_content: bool,
@@ -74,46 +76,73 @@ pub struct ArticlePricing {
 }
 
 /// 单次购买记录
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ArticlePurchase {
     pub id: String,
     pub article_id: String,
     pub buyer_id: String,
     pub creator_id: String,
-    pub amount: i64, // 支付金额（美分）
+    pub amount: i64, // 支付金额（美分），不含税
+    /// 随购买代收的税费（美分）
+    #[serde(default)]
+    pub tax_amount: i64,
     pub currency: String,
     pub stripe_payment_intent_id: Option<String>,
     pub status: PurchaseStatus,
+    /// 通过领取礼物获得时对应的礼物，金额由赠送人支付
+    #[serde(default)]
+    pub gift_id: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl ArticlePurchase {
+    /// 买家实际被扣款的金额（含税）
+    pub fn charged_amount(&self) -> i64 {
+        self.amount + self.tax_amount
+    }
+}
+
 /// 购买状态
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum PurchaseStatus {
     Pending,   // 待支付
     Completed, // 已完成
     Failed,    // 支付失败
     Refunded,  // 已退款
+    Disputed,  // 买家发起争议（拒付）
 }
 
 /// 单次购买请求
-#[derive(Debug, Clone, Deserialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
 pub struct ArticlePurchaseRequest {
     pub article_id: String,
     pub payment_method_id: Option<String>, // Stripe payment method ID
+    /// 客户端的 `Idempotency-Key` 请求头，由路由填入
+    #[serde(skip)]
+    pub idempotency_key: Option<String>,
 }
 
 /// 单次购买响应
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ArticlePurchaseResponse {
     pub purchase: ArticlePurchase,
     pub payment: StripeIntentResponse,
 }
 
+/// 购买历史中的一条记录，附带文章标题便于展示
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PurchaseHistoryItem {
+    #[serde(flatten)]
+    pub purchase: ArticlePurchase,
+    /// 文章已删除时为空
+    pub article_title: Option<String>,
+    pub article_slug: Option<String>,
+}
+
 /// 内容访问统计
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ContentAccessStats {
     pub article_id: String,
     pub total_views: i64,
@@ -126,7 +155,7 @@ pub struct ContentAccessStats {
 }
 
 /// 用户内容访问历史
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UserContentAccess {
     pub user_id: String,
     pub article_id: String,
@@ -137,7 +166,7 @@ pub struct UserContentAccess {
 }
 
 /// 付费内容仪表板
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PaymentDashboard {
     pub creator_id: String,
     pub total_paid_articles: i64,
@@ -150,7 +179,7 @@ pub struct PaymentDashboard {
 }
 
 /// 文章收益信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ArticleEarnings {
     pub article_id: String,
     pub title: String,
@@ -163,7 +192,8 @@ pub struct ArticleEarnings {
 }
 
 /// 收益分析查询参数
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct EarningsQuery {
     pub creator_id: Option<String>,
     pub article_id: Option<String>,
@@ -173,7 +203,7 @@ pub struct EarningsQuery {
 }
 
 /// 付费内容设置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PaymentSettings {
     pub creator_id: String,
     pub default_preview_percentage: u8,
@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+use super::{article::Article, comment::Comment, user::UserProfile};
+use utoipa::ToSchema;
+
+/// 插件可以订阅的生命周期钩子
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginHook {
+    ArticlePublished,
+    CommentCreated,
+    UserRegistered,
+}
+
+impl PluginHook {
+    /// 钩子名称，同时也是 WASM 模块导出函数名
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PluginHook::ArticlePublished => "on_article_published",
+            PluginHook::CommentCreated => "on_comment_created",
+            PluginHook::UserRegistered => "on_user_registered",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArticlePublishedEvent {
+    pub article_id: String,
+    pub title: String,
+    pub slug: String,
+    pub author_id: String,
+    pub publication_id: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+impl From<&Article> for ArticlePublishedEvent {
+    fn from(article: &Article) -> Self {
+        Self {
+            article_id: article.id.clone(),
+            title: article.title.clone(),
+            slug: article.slug.clone(),
+            author_id: article.author_id.clone(),
+            publication_id: article.publication_id.clone(),
+            published_at: article.published_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CommentCreatedEvent {
+    pub comment_id: String,
+    pub article_id: String,
+    pub author_id: String,
+    pub parent_id: Option<String>,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&Comment> for CommentCreatedEvent {
+    fn from(comment: &Comment) -> Self {
+        Self {
+            comment_id: comment.id.clone(),
+            article_id: comment.article_id.clone(),
+            author_id: comment.author_id.clone(),
+            parent_id: comment.parent_id.clone(),
+            content: comment.content.clone(),
+            created_at: comment.created_at,
+        }
+    }
+}
+
+/// 新用户注册事件（不包含邮箱等敏感信息）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UserRegisteredEvent {
+    pub user_id: String,
+    pub username: String,
+    pub display_name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&UserProfile> for UserRegisteredEvent {
+    fn from(profile: &UserProfile) -> Self {
+        Self {
+            user_id: profile.user_id.clone(),
+            username: profile.username.clone(),
+            display_name: profile.display_name.clone(),
+            created_at: profile.created_at,
+        }
+    }
+}
+
+/// 分发给插件的事件
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "hook", content = "payload", rename_all = "snake_case")]
+pub enum PluginEvent {
+    ArticlePublished(ArticlePublishedEvent),
+    CommentCreated(CommentCreatedEvent),
+    UserRegistered(UserRegisteredEvent),
+}
+
+impl PluginEvent {
+    pub fn hook(&self) -> PluginHook {
+        match self {
+            PluginEvent::ArticlePublished(_) => PluginHook::ArticlePublished,
+            PluginEvent::CommentCreated(_) => PluginHook::CommentCreated,
+            PluginEvent::UserRegistered(_) => PluginHook::UserRegistered,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use utoipa::ToSchema;
+
+use super::article::{ArticleStatus, AuthorInfo, TagInfo};
+
+/// 未发布文章的预览链接。只保存令牌的哈希，令牌本身只在创建时返回一次
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PreviewLink {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub article_id: String,
+    pub created_by: String,
+    /// 作者给链接起的备注，如分享对象
+    pub label: Option<String>,
+    /// 不返回给客户端
+    #[serde(skip_serializing)]
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    #[serde(default)]
+    pub revoked_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub view_count: i64,
+    #[serde(default)]
+    pub last_viewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PreviewLink {
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none() && self.expires_at > Utc::now()
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Validate, ToSchema)]
+pub struct CreatePreviewLinkRequest {
+    #[validate(length(max = 100))]
+    pub label: Option<String>,
+    /// 有效期（小时），默认 7 天，最长 30 天
+    #[validate(range(min = 1, max = 720))]
+    pub expires_in_hours: Option<i64>,
+}
+
+/// 新建的预览链接，`token` 只在这里返回
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedPreviewLink {
+    #[serde(flatten)]
+    pub link: PreviewLink,
+    pub token: String,
+}
+
+/// 通过预览链接看到的文章，只读，不包含互动数据
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ArticlePreview {
+    pub id: String,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub content_html: String,
+    pub cover_image_url: Option<String>,
+    pub author: AuthorInfo,
+    pub tags: Vec<TagInfo>,
+    pub status: ArticleStatus,
+    pub reading_time: i32,
+    pub word_count: i32,
+    pub updated_at: DateTime<Utc>,
+    /// 预览链接的过期时间
+    pub preview_expires_at: DateTime<Utc>,
+}
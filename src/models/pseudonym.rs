@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use super::{article::AuthorInfo, id::bare_id};
+use utoipa::{IntoParams, ToSchema};
+
+/// 笔名资料。和账号的关联只保存在 `owner_id` 中，只有本人能看到；
+/// 收益仍归属账号，出现违规时可以追溯到账号
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Pseudonym {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub owner_id: String,
+    pub username: String,
+    pub display_name: String,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Pseudonym {
+    /// 文章和列表中显示的作者信息
+    pub fn author_info(&self) -> AuthorInfo {
+        AuthorInfo {
+            id: bare_id("pseudonym", &self.id).to_string(),
+            username: self.username.clone(),
+            display_name: self.display_name.clone(),
+            avatar_url: self.avatar_url.clone(),
+            is_verified: false,
+            is_pseudonym: true,
+        }
+    }
+
+    pub fn public_profile(&self, article_count: i64) -> PseudonymProfile {
+        PseudonymProfile {
+            id: bare_id("pseudonym", &self.id).to_string(),
+            username: self.username.clone(),
+            display_name: self.display_name.clone(),
+            bio: self.bio.clone(),
+            avatar_url: self.avatar_url.clone(),
+            article_count,
+            created_at: self.created_at,
+        }
+    }
+}
+
+/// 公开的笔名主页，不包含账号信息
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PseudonymProfile {
+    pub id: String,
+    pub username: String,
+    pub display_name: String,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+    pub article_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreatePseudonymRequest {
+    /// 字母、数字、下划线和连字符，不能和其他笔名或用户名重复
+    #[validate(length(min = 3, max = 30))]
+    pub username: String,
+    #[validate(length(min = 1, max = 50))]
+    pub display_name: String,
+    #[validate(length(max = 300))]
+    pub bio: Option<String>,
+    #[validate(url)]
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UpdatePseudonymRequest {
+    #[validate(length(min = 1, max = 50))]
+    pub display_name: Option<String>,
+    #[validate(length(max = 300))]
+    pub bio: Option<String>,
+    #[validate(url)]
+    pub avatar_url: Option<String>,
+}
+
+/// 设置或取消文章的笔名，`pseudonym_id` 为 null 时以账号身份发布
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct SetArticlePseudonymRequest {
+    pub pseudonym_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PseudonymStatsQuery {
+    pub days: Option<i64>,
+}
+
+/// 笔名文章的统计，只对笔名所有者可见，不计入账号的统计
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PseudonymStats {
+    pub pseudonym_id: String,
+    pub days: i64,
+    pub published_articles: i64,
+    pub total_views: i64,
+    pub total_claps: i64,
+    pub total_comments: i64,
+    /// 统计周期内去重后的浏览数和独立访客数
+    pub views: i64,
+    pub unique_viewers: i64,
+}
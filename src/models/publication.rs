@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use validator::Validate;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Publication {
     pub id: String,
     pub name: String,
@@ -20,11 +21,14 @@ pub struct Publication {
     pub follower_count: i64,
     pub is_verified: bool,
     pub is_suspended: bool,
+    /// 有发布权限的成员必须在当前会话通过两步验证才能发布
+    #[serde(default)]
+    pub require_two_factor: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PublicationMember {
     pub id: String,
     pub publication_id: String,
@@ -35,56 +39,98 @@ pub struct PublicationMember {
     pub is_active: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// 成员角色，权限从高到低。数据库中保存小写形式
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 pub enum MemberRole {
+    #[serde(alias = "owner")]
     Owner,
+    #[serde(alias = "admin")]
+    Admin,
+    #[serde(alias = "editor")]
     Editor,
+    #[serde(alias = "writer")]
     Writer,
-    Contributor,
 }
 
 impl MemberRole {
-    pub fn default_permissions(&self) -> Vec<String> {
+    pub fn as_str(&self) -> &'static str {
         match self {
-            MemberRole::Owner => vec![
-                "publication.read".to_string(),
-                "publication.write".to_string(),
-                "publication.delete".to_string(),
-                "publication.manage_members".to_string(),
-                "publication.manage_settings".to_string(),
-                "article.create".to_string(),
-                "article.publish".to_string(),
-                "article.edit_any".to_string(),
-                "article.delete_any".to_string(),
+            MemberRole::Owner => "owner",
+            MemberRole::Admin => "admin",
+            MemberRole::Editor => "editor",
+            MemberRole::Writer => "writer",
+        }
+    }
+
+    /// 只能管理级别比自己低的成员，也只能授予比自己低的角色（所有者除外）
+    pub fn rank(&self) -> u8 {
+        match self {
+            MemberRole::Owner => 4,
+            MemberRole::Admin => 3,
+            MemberRole::Editor => 2,
+            MemberRole::Writer => 1,
+        }
+    }
+
+    pub fn default_permissions(&self) -> Vec<String> {
+        let permissions: &[&str] = match self {
+            MemberRole::Owner => &[
+                "publication.read",
+                "publication.write",
+                "publication.delete",
+                "publication.manage_members",
+                "publication.manage_settings",
+                "publication.manage_domains",
+                "article.create",
+                "article.publish",
+                "article.edit_any",
+                "article.delete_any",
             ],
-            MemberRole::Editor => vec![
-                "publication.read".to_string(),
-                "publication.write".to_string(),
-                "publication.manage_members".to_string(),
-                "article.create".to_string(),
-                "article.publish".to_string(),
-                "article.edit_any".to_string(),
+            MemberRole::Admin => &[
+                "publication.read",
+                "publication.write",
+                "publication.manage_members",
+                "publication.manage_settings",
+                "publication.manage_domains",
+                "article.create",
+                "article.publish",
+                "article.edit_any",
+                "article.delete_any",
             ],
-            MemberRole::Writer => vec![
-                "publication.read".to_string(),
-                "article.create".to_string(),
-                "article.publish".to_string(),
-                "article.edit_own".to_string(),
+            MemberRole::Editor => &[
+                "publication.read",
+                "publication.write",
+                "article.create",
+                "article.publish",
+                "article.edit_any",
             ],
-            MemberRole::Contributor => vec![
-                "publication.read".to_string(),
-                "article.create".to_string(),
-                "article.edit_own".to_string(),
+            MemberRole::Writer => &[
+                "publication.read",
+                "article.create",
+                "article.edit_own",
             ],
-        }
+        };
+        permissions.iter().map(|p| p.to_string()).collect()
     }
 
     pub fn can_approve_submissions(&self) -> bool {
-        matches!(self, MemberRole::Owner | MemberRole::Editor)
+        matches!(self, MemberRole::Owner | MemberRole::Admin | MemberRole::Editor)
+    }
+
+    pub fn can_view_audience(&self) -> bool {
+        matches!(self, MemberRole::Owner | MemberRole::Admin | MemberRole::Editor)
+    }
+
+    pub fn can_moderate_comments(&self) -> bool {
+        matches!(self, MemberRole::Owner | MemberRole::Admin | MemberRole::Editor)
+    }
+
+    pub fn can_manage_templates(&self) -> bool {
+        matches!(self, MemberRole::Owner | MemberRole::Admin | MemberRole::Editor)
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PublicationSubmission {
     pub id: String,
     pub publication_id: String,
@@ -97,7 +143,7 @@ pub struct PublicationSubmission {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub enum SubmissionStatus {
     Pending,
     Approved,
@@ -105,7 +151,7 @@ pub enum SubmissionStatus {
     Withdrawn,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PublicationResponse {
     #[serde(flatten)]
     pub publication: Publication,
@@ -115,7 +161,7 @@ pub struct PublicationResponse {
     pub recent_articles: Vec<crate::models::article::ArticleListItem>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PublicationFollow {
     pub id: String,
     pub user_id: String,
@@ -123,7 +169,7 @@ pub struct PublicationFollow {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PublicationListItem {
     pub id: String,
     pub name: String,
@@ -139,7 +185,7 @@ pub struct PublicationListItem {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Deserialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
 pub struct CreatePublicationRequest {
     #[validate(length(min = 1, max = 100))]
     pub name: String,
@@ -163,7 +209,7 @@ pub struct CreatePublicationRequest {
     pub custom_domain: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
 pub struct UpdatePublicationRequest {
     #[validate(length(min = 1, max = 100))]
     pub name: Option<String>,
@@ -185,9 +231,11 @@ pub struct UpdatePublicationRequest {
     
     #[validate(url)]
     pub custom_domain: Option<String>,
+
+    pub require_two_factor: Option<bool>,
 }
 
-#[derive(Debug, Clone, Deserialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
 pub struct AddMemberRequest {
     #[validate(length(min = 1))]
     pub user_id: String,
@@ -196,14 +244,58 @@ pub struct AddMemberRequest {
     pub permissions: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, Deserialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
 pub struct UpdateMemberRequest {
     pub role: Option<MemberRole>,
     pub permissions: Option<Vec<String>>,
     pub is_active: Option<bool>,
 }
 
-#[derive(Debug, Clone, Deserialize, Validate)]
+/// 出版物成员邀请。邀请令牌只在创建时返回，数据库中只保存其哈希
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PublicationInvite {
+    pub id: String,
+    pub publication_id: String,
+    pub email: Option<String>,
+    pub user_id: Option<String>,
+    pub role: MemberRole,
+    pub invited_by: String,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 按邮箱或用户邀请，两者必须提供一个
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateInviteRequest {
+    #[validate(email)]
+    pub email: Option<String>,
+
+    #[validate(length(min = 1))]
+    pub user_id: Option<String>,
+
+    pub role: MemberRole,
+
+    /// 有效天数，默认 7 天
+    #[validate(range(min = 1, max = 30))]
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CreatedInvite {
+    #[serde(flatten)]
+    pub invite: PublicationInvite,
+    pub token: String,
+    pub accept_url: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct AcceptInviteRequest {
+    #[validate(length(min = 1))]
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
 pub struct SubmitArticleRequest {
     #[validate(length(min = 1))]
     pub article_id: String,
@@ -212,7 +304,7 @@ pub struct SubmitArticleRequest {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
 pub struct ReviewSubmissionRequest {
     pub status: SubmissionStatus,
     
@@ -220,7 +312,8 @@ pub struct ReviewSubmissionRequest {
     pub notes: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct PublicationQuery {
     pub page: Option<usize>,
     pub limit: Option<usize>,
@@ -239,4 +332,63 @@ impl Default for PublicationQuery {
             sort: Some("popular".to_string()),
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AudienceInsightsQuery {
+    /// 统计的天数范围，默认30天
+    pub days: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+/// 关注者增长数据点
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FollowerGrowthPoint {
+    pub date: String,
+    pub new_followers: i64,
+    pub total_followers: i64,
+}
+
+/// 受众分布项（地区、语言）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AudienceBreakdownItem {
+    pub key: String,
+    pub followers: i64,
+    pub percentage: f64,
+}
+
+/// 出版物关注者信息
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PublicationFollowerInfo {
+    pub user_id: String,
+    pub username: String,
+    pub display_name: String,
+    pub avatar_url: Option<String>,
+    pub followed_at: DateTime<Utc>,
+    pub last_active_at: Option<DateTime<Utc>>,
+}
+
+/// 出版物受众洞察
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PublicationAudienceInsights {
+    pub publication_id: String,
+    pub total_followers: i64,
+    pub new_followers: i64,
+    /// 同意受众洞察的关注者数量，分布数据只基于这部分关注者
+    pub consenting_followers: i64,
+    pub growth: Vec<FollowerGrowthPoint>,
+    pub countries: Vec<AudienceBreakdownItem>,
+    pub languages: Vec<AudienceBreakdownItem>,
+}
+
+/// 关注者导出行，仅包含同意受众洞察的关注者
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct FollowerExportRow {
+    pub user_id: String,
+    pub username: String,
+    pub display_name: String,
+    pub followed_at: DateTime<Utc>,
+    pub country: Option<String>,
+    pub language: Option<String>,
+}
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReactionType {
+    /// 可以重复点赞（每人每篇最多 50 次），计入加权点赞分
+    Clap,
+    Insightful,
+    Funny,
+    Love,
+    Celebrate,
+}
+
+impl ReactionType {
+    pub const ALL: [ReactionType; 5] = [
+        ReactionType::Clap,
+        ReactionType::Insightful,
+        ReactionType::Funny,
+        ReactionType::Love,
+        ReactionType::Celebrate,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReactionType::Clap => "clap",
+            ReactionType::Insightful => "insightful",
+            ReactionType::Funny => "funny",
+            ReactionType::Love => "love",
+            ReactionType::Celebrate => "celebrate",
+        }
+    }
+}
+
+/// 用户对文章的一种反应（记录 ID 为 [article_id, user_id, reaction_type]）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Reaction {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub user_id: String,
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub article_id: String,
+    pub reaction_type: ReactionType,
+    /// 点赞次数；其他反应固定为 1
+    pub count: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 文章各反应的总数（点赞为总点赞次数，其他为人数）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(default)]
+pub struct ReactionCounts {
+    pub clap: i64,
+    pub insightful: i64,
+    pub funny: i64,
+    pub love: i64,
+    pub celebrate: i64,
+}
+
+impl ReactionCounts {
+    pub fn get(&self, reaction_type: ReactionType) -> i64 {
+        match reaction_type {
+            ReactionType::Clap => self.clap,
+            ReactionType::Insightful => self.insightful,
+            ReactionType::Funny => self.funny,
+            ReactionType::Love => self.love,
+            ReactionType::Celebrate => self.celebrate,
+        }
+    }
+
+    pub fn set(&mut self, reaction_type: ReactionType, count: i64) {
+        match reaction_type {
+            ReactionType::Clap => self.clap = count,
+            ReactionType::Insightful => self.insightful = count,
+            ReactionType::Funny => self.funny = count,
+            ReactionType::Love => self.love = count,
+            ReactionType::Celebrate => self.celebrate = count,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct AddReactionRequest {
+    pub reaction_type: ReactionType,
+    /// 点赞次数，其他反应忽略
+    #[validate(range(min = 1, max = 50))]
+    pub count: Option<i32>,
+}
+
+/// 文章的反应汇总，登录用户额外返回自己的反应
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ArticleReactions {
+    pub article_id: String,
+    pub counts: ReactionCounts,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_reactions: Option<Vec<ReactionType>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_clap_count: Option<i32>,
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+
+use super::article::ArticleListItem;
+use utoipa::ToSchema;
+
+/// 用户在一篇文章中的阅读位置（记录 ID 为 [user_id, article_id]），在各设备间同步
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReadingProgress {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub user_id: String,
+    pub article_id: String,
+    /// 滚动位置 0.0 - 1.0
+    pub progress: f64,
+    /// 最后可见的段落序号（从 0 开始），客户端据此精确恢复位置
+    pub last_paragraph: Option<u32>,
+    /// 最后上报位置的设备
+    pub device: Option<String>,
+    #[serde(default)]
+    pub completed: bool,
+    /// 客户端记录位置的时间，用于最后写入者胜出
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UpdateReadingProgressRequest {
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub progress: f64,
+    pub last_paragraph: Option<u32>,
+    #[validate(length(max = 100))]
+    pub device: Option<String>,
+    /// 离线设备补传时提供记录位置的时间；早于服务器上已有位置的更新会被忽略
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// 推荐中的“继续阅读”条目
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ContinueReadingItem {
+    #[serde(flatten)]
+    pub article: ArticleListItem,
+    pub progress: f64,
+    pub last_paragraph: Option<u32>,
+    /// 按剩余比例估算的剩余阅读时间（分钟）
+    pub remaining_minutes: i32,
+    pub last_read_at: DateTime<Utc>,
+}
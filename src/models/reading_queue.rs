@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, TimeZone, Utc};
+use validator::Validate;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use utoipa::ToSchema;
+
+/// 阅读队列项（与书签不同，队列有顺序且支持离线同步）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReadingQueueItem {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub user_id: String,
+    pub article_id: String,
+    pub position: i32,
+    /// 阅读进度 0.0 - 1.0
+    #[serde(default)]
+    pub progress: f64,
+    /// 墓碑标记，已移除的项保留以便同步给其他设备
+    #[serde(default)]
+    pub is_removed: bool,
+    pub added_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReadingQueueEntry {
+    #[serde(flatten)]
+    pub item: ReadingQueueItem,
+    pub article_title: String,
+    pub article_slug: String,
+    pub article_reading_time: i32,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadingQueueResponse {
+    pub items: Vec<ReadingQueueEntry>,
+    pub total_items: usize,
+    /// 未读完部分的预计剩余阅读时间（分钟）
+    pub estimated_total_reading_time: i32,
+    pub sync_token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct AddToQueueRequest {
+    #[validate(length(min = 1))]
+    pub article_id: String,
+    /// 不提供时追加到队尾
+    pub position: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct ReorderQueueRequest {
+    #[validate(length(min = 1, max = 500))]
+    pub article_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct UpdateQueueProgressRequest {
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub progress: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueChangeAction {
+    Upsert,
+    Remove,
+}
+
+/// 客户端离线期间产生的变更
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct QueueClientChange {
+    pub article_id: String,
+    pub action: QueueChangeAction,
+    pub position: Option<i32>,
+    pub progress: Option<f64>,
+    /// 客户端本地修改时间，用于最后写入者胜出
+    pub modified_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct QueueSyncRequest {
+    /// 上一次同步返回的令牌，首次同步为空
+    pub since: Option<String>,
+    #[validate(length(max = 500))]
+    #[serde(default)]
+    pub changes: Vec<QueueClientChange>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct QueueSyncResponse {
+    /// 自 since 之后服务器端的变更（包含墓碑）
+    pub changes: Vec<ReadingQueueItem>,
+    /// 因服务器版本较新而被拒绝的客户端变更
+    pub rejected: Vec<String>,
+    pub sync_token: String,
+}
+
+/// 更新时间同步令牌：对客户端不透明，内部为毫秒时间戳
+pub fn encode_sync_token(at: DateTime<Utc>) -> String {
+    URL_SAFE_NO_PAD.encode(format!("v1:{}", at.timestamp_millis()))
+}
+
+pub fn decode_sync_token(token: &str) -> Option<DateTime<Utc>> {
+    let raw = URL_SAFE_NO_PAD.decode(token).ok()?;
+    let raw = String::from_utf8(raw).ok()?;
+    let millis: i64 = raw.strip_prefix("v1:")?.parse().ok()?;
+    Utc.timestamp_millis_opt(millis).single()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_token_roundtrip() {
+        let now = Utc.timestamp_millis_opt(1_700_000_000_123).unwrap();
+        let token = encode_sync_token(now);
+
+        assert_eq!(decode_sync_token(&token), Some(now));
+        assert_eq!(decode_sync_token("not-a-token"), None);
+    }
+}
@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use crate::models::article::ArticleListItem;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RecommendationResult {
     pub articles: Vec<RecommendedArticle>,
     pub total: usize,
@@ -10,7 +11,7 @@ pub struct RecommendationResult {
     pub generated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RecommendedArticle {
     #[serde(flatten)]
     pub article: ArticleListItem,
@@ -18,7 +19,7 @@ pub struct RecommendedArticle {
     pub reason: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UserInteraction {
     pub id: String,
     pub user_id: String,
@@ -28,7 +29,7 @@ pub struct UserInteraction {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub enum InteractionType {
     View,
     Clap,
@@ -51,7 +52,7 @@ impl InteractionType {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ContentVector {
     pub article_id: String,
     pub tags: Vec<String>,
@@ -63,7 +64,7 @@ pub struct ContentVector {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UserProfile {
     pub user_id: String,
     pub preferred_tags: Vec<TagPreference>,
@@ -73,20 +74,20 @@ pub struct UserProfile {
     pub last_updated: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TagPreference {
     pub tag_id: String,
     pub tag_name: String,
     pub weight: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AuthorPreference {
     pub author_id: String,
     pub weight: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TrendingMetrics {
     pub article_id: String,
     pub views_24h: i64,
@@ -99,7 +100,8 @@ pub struct TrendingMetrics {
     pub calculated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct RecommendationRequest {
     pub user_id: Option<String>,
     pub limit: Option<usize>,
@@ -109,7 +111,7 @@ pub struct RecommendationRequest {
     pub authors: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
 pub enum RecommendationAlgorithm {
     ContentBased,
     CollaborativeFiltering,
@@ -129,4 +131,19 @@ impl Default for RecommendationRequest {
             authors: None,
         }
     }
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct HomeFeedQuery {
+    pub page: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+/// 关注的作者和标签的文章流
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct HomeFeed {
+    pub articles: Vec<RecommendedArticle>,
+    pub page: usize,
+    pub has_more: bool,
 }
\ No newline at end of file
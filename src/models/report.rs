@@ -0,0 +1,148 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportTargetType {
+    Article,
+    Comment,
+    User,
+}
+
+impl ReportTargetType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportTargetType::Article => "article",
+            ReportTargetType::Comment => "comment",
+            ReportTargetType::User => "user",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportReason {
+    Spam,
+    Harassment,
+    Copyright,
+    Other,
+}
+
+impl ReportReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportReason::Spam => "spam",
+            ReportReason::Harassment => "harassment",
+            ReportReason::Copyright => "copyright",
+            ReportReason::Other => "other",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportStatus {
+    Open,
+    /// 已处理（下架、停用或删除了被举报的内容）
+    Resolved,
+    /// 举报不成立
+    Dismissed,
+}
+
+impl ReportStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportStatus::Open => "open",
+            ReportStatus::Resolved => "resolved",
+            ReportStatus::Dismissed => "dismissed",
+        }
+    }
+}
+
+/// 用户对文章、评论或用户的举报
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ContentReport {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    /// 所属的举报案件
+    pub case_id: String,
+    pub target_type: ReportTargetType,
+    pub target_id: String,
+    pub reporter_id: String,
+    pub reason: ReportReason,
+    pub details: Option<String>,
+    pub status: ReportStatus,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub resolution_note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 举报案件：同一对象上未处理的举报汇总在一起，处理后再被举报会重新开启
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReportCase {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub target_type: ReportTargetType,
+    pub target_id: String,
+    /// 被举报内容的作者或被举报的用户
+    pub target_owner_id: String,
+    pub status: ReportStatus,
+    /// 本轮举报的人数，每个用户只计一次
+    pub report_count: usize,
+    pub reasons: Vec<ReportReason>,
+    /// 达到举报阈值后自动隐藏的时间
+    pub hidden_at: Option<DateTime<Utc>>,
+    pub first_reported_at: DateTime<Utc>,
+    pub last_reported_at: DateTime<Utc>,
+    pub reviewed_by: Option<String>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub resolution_note: Option<String>,
+}
+
+impl ReportCase {
+    /// 案件的记录 ID 由举报对象决定
+    pub fn key(target_type: ReportTargetType, target_id: &str) -> String {
+        format!("{}_{}", target_type.as_str(), target_id)
+    }
+}
+
+/// 案件详情，附带本轮的全部举报
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ReportCaseDetail {
+    #[serde(flatten)]
+    pub case: ReportCase,
+    pub reports: Vec<ContentReport>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateReportRequest {
+    pub target_type: ReportTargetType,
+    #[validate(length(min = 1, max = 100))]
+    pub target_id: String,
+    pub reason: ReportReason,
+    #[validate(length(max = 2000, message = "补充说明不能超过2000字符"))]
+    pub details: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReportQuery {
+    pub status: Option<ReportStatus>,
+    pub target_type: Option<ReportTargetType>,
+    /// 只看已自动隐藏的案件
+    pub hidden: Option<bool>,
+    pub page: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct ReviewReportRequest {
+    /// resolved 或 dismissed。驳回时恢复自动隐藏的内容
+    pub status: ReportStatus,
+
+    #[validate(length(max = 1000))]
+    pub note: Option<String>,
+}
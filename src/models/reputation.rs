@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ReputationLevel {
+    New,
+    Member,
+    Trusted,
+    Established,
+}
+
+impl ReputationLevel {
+    pub fn from_score(score: i32) -> Self {
+        match score {
+            s if s >= 150 => Self::Established,
+            s if s >= 50 => Self::Trusted,
+            s if s >= 10 => Self::Member,
+            _ => Self::New,
+        }
+    }
+}
+
+/// 信誉分的组成部分
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReputationBreakdown {
+    /// 收到的互动（加权点赞、评论、关注者）换算的分数
+    pub engagement: i32,
+    /// 被出版物收录的文章换算的分数
+    pub publication_acceptances: i32,
+    /// 账号年龄换算的分数
+    pub account_age: i32,
+    /// 有效处罚扣除的分数，只对本人和管理员可见
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strike_penalty: Option<i32>,
+    pub accepted_articles: i64,
+    /// 有效处罚数，只对本人和管理员可见
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_strikes: Option<i64>,
+    pub account_age_days: i64,
+}
+
+/// 由信誉等级决定的权限
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ReputationPrivileges {
+    /// 可以在评论中发布链接
+    pub can_post_links: bool,
+    /// 发布文章无需审核
+    pub can_publish_immediately: bool,
+}
+
+impl ReputationPrivileges {
+    pub fn for_level(level: ReputationLevel, active_strikes: i64) -> Self {
+        Self {
+            can_post_links: level >= ReputationLevel::Member,
+            can_publish_immediately: level >= ReputationLevel::Member && active_strikes == 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UserReputation {
+    pub user_id: String,
+    pub score: i32,
+    pub level: ReputationLevel,
+    pub breakdown: ReputationBreakdown,
+    pub privileges: ReputationPrivileges,
+    pub calculated_at: DateTime<Utc>,
+}
+
+impl UserReputation {
+    /// 隐藏处罚信息，用于他人查看的公开信誉
+    pub fn without_strikes(mut self) -> Self {
+        self.breakdown.strike_penalty = None;
+        self.breakdown.active_strikes = None;
+        self
+    }
+}
+
+/// 审核处罚记录
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ModerationStrike {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub user_id: String,
+    pub reason: String,
+    pub issued_by: String,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct IssueStrikeRequest {
+    #[validate(length(min = 1, max = 500))]
+    pub reason: String,
+    /// 处罚有效天数，不提供则永久有效
+    #[validate(range(min = 1, max = 3650))]
+    pub duration_days: Option<i64>,
+}
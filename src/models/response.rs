@@ -1,67 +1,171 @@
+use crate::error::ErrorCode;
+use crate::services::database::PaginatedResult;
+use axum::{
+    response::{IntoResponse, Response},
+    Json,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use utoipa::ToSchema;
 
-/// 标准API响应格式
+/// 标准API响应格式，所有接口都通过它返回。
+/// 分页信息放在 `meta.pagination`，不放在 `data` 里
 #[derive(Debug, Serialize, Deserialize)]
-pub struct ApiResponse<T> {
+pub struct ApiResponse<T = Value> {
     pub success: bool,
-    pub data: T,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<T>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<ResponseMeta>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct ResponseMeta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<PaginationMeta>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PaginationMeta {
+    pub page: usize,
+    pub per_page: usize,
+    pub total: usize,
+    pub total_pages: usize,
+    pub has_next: bool,
+    pub has_prev: bool,
+}
+
+impl PaginationMeta {
+    pub fn new(page: usize, per_page: usize, total: usize) -> Self {
+        let total_pages = if per_page == 0 { 0 } else { (total + per_page - 1) / per_page };
+        Self {
+            page,
+            per_page,
+            total,
+            total_pages,
+            has_next: page < total_pages,
+            has_prev: page > 1,
+        }
+    }
+}
+
+impl<T> From<&PaginatedResult<T>> for PaginationMeta {
+    fn from(result: &PaginatedResult<T>) -> Self {
+        Self {
+            page: result.page,
+            per_page: result.per_page,
+            total: result.total,
+            total_pages: result.total_pages,
+            has_next: result.page < result.total_pages,
+            has_prev: result.page > 1,
+        }
+    }
 }
 
 impl<T> ApiResponse<T> {
     pub fn success(data: T) -> Self {
         Self {
             success: true,
-            data,
+            data: Some(data),
             message: None,
+            meta: None,
         }
     }
 
     pub fn success_with_message(data: T, message: String) -> Self {
+        Self::success(data).with_message(message)
+    }
+
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    pub fn with_pagination(mut self, pagination: impl Into<PaginationMeta>) -> Self {
+        self.meta.get_or_insert_with(ResponseMeta::default).pagination = Some(pagination.into());
+        self
+    }
+}
+
+impl ApiResponse {
+    /// 数据不是固定类型时使用，`data` 按 JSON 返回
+    pub fn ok(data: impl Serialize) -> Self {
+        Self::success(json!(data))
+    }
+
+    /// 只有提示信息、没有数据的成功响应
+    pub fn message(message: impl Into<String>) -> Self {
         Self {
             success: true,
-            data,
-            message: Some(message),
+            data: None,
+            message: Some(message.into()),
+            meta: None,
         }
     }
 }
 
-/// 错误响应格式
-#[derive(Debug, Serialize, Deserialize)]
+impl<T: Serialize> IntoResponse for ApiResponse<T> {
+    fn into_response(self) -> Response {
+        Json(self).into_response()
+    }
+}
+
+/// 错误响应格式，由 `AppError` 生成，框架层的错误（如请求体无法解析、路由不存在）
+/// 由 `error_envelope_middleware` 转换为同样的格式
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ErrorResponse {
     pub success: bool,
     pub error: ErrorDetail,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ErrorDetail {
-    pub code: String,
+    pub code: ErrorCode,
     pub message: String,
+    /// 字段级的校验错误，只在 `VALIDATION_ERROR` 时出现
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub field_errors: Vec<FieldError>,
+    /// 与响应头 `x-request-id` 相同，反馈问题时附上便于查日志
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub details: Option<serde_json::Value>,
+    pub request_id: Option<String>,
+}
+
+/// 单个字段的校验错误
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct FieldError {
+    /// 字段路径，嵌套字段用 `.` 连接，列表元素带下标，如 `items[0].name`
+    pub field: String,
+    /// 校验规则，如 `length`、`email`、`range`，自定义校验为其错误码
+    pub code: String,
+    pub message: String,
+    /// 规则参数，如 `length` 的 `min`、`max`
+    #[serde(skip_serializing_if = "serde_json::Map::is_empty")]
+    #[schema(value_type = Object)]
+    pub params: serde_json::Map<String, Value>,
 }
 
 impl ErrorResponse {
-    pub fn new(code: String, message: String) -> Self {
+    pub fn new(code: ErrorCode, message: String) -> Self {
         Self {
             success: false,
             error: ErrorDetail {
                 code,
                 message,
-                details: None,
+                field_errors: Vec::new(),
+                request_id: None,
             },
         }
     }
 
-    pub fn with_details(code: String, message: String, details: serde_json::Value) -> Self {
-        Self {
-            success: false,
-            error: ErrorDetail {
-                code,
-                message,
-                details: Some(details),
-            },
-        }
+    pub fn with_field_errors(mut self, field_errors: Vec<FieldError>) -> Self {
+        self.error.field_errors = field_errors;
+        self
     }
-}
\ No newline at end of file
+
+    pub fn with_request_id(mut self, request_id: Option<String>) -> Self {
+        self.error.request_id = request_id;
+        self
+    }
+}
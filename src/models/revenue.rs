@@ -1,9 +1,10 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use validator::Validate;
+use utoipa::ToSchema;
 
 /// 收益记录
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RevenueRecord {
     pub id: String,
     pub creator_id: String,
@@ -18,8 +19,19 @@ pub struct RevenueRecord {
     pub processed_at: Option<DateTime<Utc>>,
 }
 
+/// 订阅发票收款被退款或争议冲回后的结果
+#[derive(Debug, Clone)]
+pub struct SubscriptionRevenueReversal {
+    /// 内部订阅 ID
+    pub subscription_id: String,
+    /// 发票实际扣款金额（含税）
+    pub charged_amount: i64,
+    /// 本次调整的创作者金额，负数表示争议胜诉后恢复
+    pub reversed_amount: i64,
+}
+
 /// 收益来源类型
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum RevenueSourceType {
     Subscription,      // 订阅收益
@@ -29,7 +41,7 @@ pub enum RevenueSourceType {
 }
 
 /// 收益状态
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum RevenueStatus {
     Pending,    // 待处理
@@ -40,7 +52,7 @@ pub enum RevenueStatus {
 }
 
 /// 作者收益汇总
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CreatorEarnings {
     pub creator_id: String,
     pub total_earnings: i64, // 总收益（美分）
@@ -53,7 +65,7 @@ pub struct CreatorEarnings {
 }
 
 /// 收益支付记录
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Payout {
     pub id: String,
     pub creator_id: String,
@@ -62,6 +74,9 @@ pub struct Payout {
     pub method: PayoutMethod,
     pub status: PayoutStatus,
     pub stripe_payout_id: Option<String>,
+    /// 转入创作者 Connect 账户的 Stripe transfer ID
+    #[serde(default)]
+    pub stripe_transfer_id: Option<String>,
     pub bank_account_id: Option<String>,
     pub description: Option<String>,
     pub created_at: DateTime<Utc>,
@@ -71,7 +86,7 @@ pub struct Payout {
 }
 
 /// 支付方式
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum PayoutMethod {
     Stripe,        // Stripe Connect
@@ -81,7 +96,7 @@ pub enum PayoutMethod {
 }
 
 /// 支付状态
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum PayoutStatus {
     Pending,    // 待处理
@@ -92,7 +107,7 @@ pub enum PayoutStatus {
 }
 
 /// 收益统计
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RevenueStats {
     pub period: RevenuePeriod,
     pub start_date: DateTime<Utc>,
@@ -109,7 +124,7 @@ pub struct RevenueStats {
 }
 
 /// 收益统计周期
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum RevenuePeriod {
     Daily,
@@ -120,7 +135,7 @@ pub enum RevenuePeriod {
 }
 
 /// 内容收益
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ContentEarning {
     pub content_id: String,
     pub content_type: String, // article, publication, series
@@ -133,7 +148,7 @@ pub struct ContentEarning {
 }
 
 /// 银行账户信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct BankAccount {
     pub id: String,
     pub creator_id: String,
@@ -150,7 +165,7 @@ pub struct BankAccount {
 }
 
 /// 创建支付请求
-#[derive(Debug, Validate, Deserialize)]
+#[derive(Debug, Validate, Deserialize, ToSchema)]
 pub struct CreatePayoutRequest {
     #[validate(range(min = 100))] // 最低1美元
     pub amount: i64,
@@ -162,7 +177,7 @@ pub struct CreatePayoutRequest {
 }
 
 /// 收益查询参数
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct RevenueQuery {
     pub creator_id: Option<String>,
     pub source_type: Option<RevenueSourceType>,
@@ -174,7 +189,7 @@ pub struct RevenueQuery {
 }
 
 /// 收益仪表板
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct RevenueDashboard {
     pub earnings: CreatorEarnings,
     pub current_month_stats: RevenueStats,
@@ -187,7 +202,7 @@ pub struct RevenueDashboard {
     pub connect_status: ConnectStatus,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ConnectStatus {
     pub has_connect_account: bool,
     pub charges_enabled: bool,
@@ -197,8 +212,71 @@ pub struct ConnectStatus {
     pub requirements_due: Vec<String>,
 }
 
+/// 创作者余额概览
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreatorBalance {
+    /// 可提现余额（美分），下次自动打款时转出
+    pub available_balance: i64,
+    /// 还在等待期内的收益
+    pub pending_balance: i64,
+    /// 已发起但尚未到账的打款
+    pub in_transit: i64,
+    pub lifetime_earnings: i64,
+    pub currency: String,
+    pub platform_fee_percentage: f64,
+    pub minimum_payout_amount: i64,
+    pub payout_hold_days: i64,
+    pub payouts_enabled: bool,
+    pub last_payout_at: Option<DateTime<Utc>>,
+    pub next_payout_date: Option<DateTime<Utc>>,
+}
+
+/// 订阅优惠券的使用情况
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CouponUsage {
+    pub coupon_id: String,
+    pub code: String,
+    pub is_active: bool,
+    pub times_redeemed: i64,
+    pub max_redemptions: Option<i64>,
+    /// 使用该优惠券、目前仍有效的订阅数
+    pub active_subscriptions: i64,
+    /// 首期累计减免金额（美分），按兑换时的计划价格计算
+    pub discount_given: i64,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// 一笔收款中由 Stripe Tax 代收的税费。税费由平台代缴，不计入创作者收益
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct CollectedTax {
+    pub amount: i64, // 税额（美分）
+    /// 计税地的国家代码（ISO 3166-1 alpha-2）
+    pub country: Option<String>,
+}
+
+/// 创作者在某段时间内的代收税费报表
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TaxReport {
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    pub by_country: Vec<TaxBreakdown>,
+    pub by_source_type: Vec<TaxBreakdown>,
+}
+
+/// 税费报表的一行，按国家或收益来源汇总
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TaxBreakdown {
+    /// 国家代码、收益来源类型，无法确定计税地时为 `unknown`
+    pub key: String,
+    pub currency: String,
+    pub transactions: i64,
+    /// 不含税的销售额（美分）
+    pub taxable_amount: i64,
+    pub tax_amount: i64,
+}
+
 /// 收益分成配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RevenueShare {
     pub platform_fee_percentage: f64, // 平台费用百分比
     pub payment_processing_fee: f64,   // 支付处理费用百分比
@@ -215,6 +293,19 @@ impl Default for RevenueShare {
     }
 }
 
+impl RevenueShare {
+    /// 按配置的平台抽成生成分成比例，支付处理费保持默认值，剩余部分归创作者
+    pub fn with_platform_fee(platform_fee_percentage: f64) -> Self {
+        let default = Self::default();
+        let platform_fee_percentage = platform_fee_percentage.clamp(0.0, 100.0 - default.payment_processing_fee);
+        Self {
+            platform_fee_percentage,
+            creator_share_percentage: 100.0 - default.payment_processing_fee - platform_fee_percentage,
+            ..default
+        }
+    }
+}
+
 /// 计算创作者实际收益
 pub fn calculate_creator_revenue(gross_amount: i64, revenue_share: &RevenueShare) -> i64 {
     let creator_share = gross_amount as f64 * (revenue_share.creator_share_percentage / 100.0);
@@ -251,4 +342,16 @@ mod tests {
         assert_eq!(processing_fee, 290);    // $2.90
         assert_eq!(creator_revenue + platform_fee + processing_fee, gross_amount);
     }
+
+    #[test]
+    fn test_configured_platform_fee() {
+        let revenue_share = RevenueShare::with_platform_fee(15.0);
+        let gross_amount = 10000;
+
+        assert_eq!(calculate_platform_fee(gross_amount, &revenue_share), 1500);
+        assert_eq!(calculate_creator_revenue(gross_amount, &revenue_share), 8210);
+
+        let capped = RevenueShare::with_platform_fee(150.0);
+        assert_eq!(calculate_creator_revenue(gross_amount, &capped), 0);
+    }
 }
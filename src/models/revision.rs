@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use similar::{ChangeTag, TextDiff};
+use validator::Validate;
+use utoipa::{IntoParams, ToSchema};
+
+/// 文章修订版本：每次保存后的文章快照
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArticleRevision {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub article_id: String,
+    pub revision_number: i32,
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub content: String,
+    /// 保存时的标签名；早期版本没有记录
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+    pub word_count: i32,
+    /// 保存该版本的用户
+    pub editor_id: String,
+    /// 自动保存的版本会被同一编辑者的下一次自动保存覆盖
+    #[serde(default)]
+    pub is_autosave: bool,
+    pub change_summary: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 修订列表项（不含正文）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArticleRevisionSummary {
+    pub id: String,
+    pub revision_number: i32,
+    pub title: String,
+    pub word_count: i32,
+    pub editor_id: String,
+    pub is_autosave: bool,
+    pub change_summary: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ArticleRevision> for ArticleRevisionSummary {
+    fn from(revision: ArticleRevision) -> Self {
+        Self {
+            id: revision.id,
+            revision_number: revision.revision_number,
+            title: revision.title,
+            word_count: revision.word_count,
+            editor_id: revision.editor_id,
+            is_autosave: revision.is_autosave,
+            change_summary: revision.change_summary,
+            created_at: revision.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct AutosaveArticleRequest {
+    #[validate(length(min = 1, max = 150))]
+    pub title: Option<String>,
+    #[validate(length(max = 200))]
+    pub subtitle: Option<String>,
+    #[validate(length(max = 50000))]
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RevisionDiffQuery {
+    /// 起始版本号，默认为目标版本的上一个版本
+    pub from: Option<i32>,
+    /// 目标版本号，默认为最新版本
+    pub to: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffOp {
+    Equal,
+    Insert,
+    Delete,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DiffLine {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+/// 两个修订版本之间的逐行差异
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RevisionDiff {
+    pub from_revision: i32,
+    pub to_revision: i32,
+    pub title_changed: bool,
+    pub subtitle_changed: bool,
+    pub additions: usize,
+    pub deletions: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+impl RevisionDiff {
+    pub fn between(from: &ArticleRevision, to: &ArticleRevision) -> Self {
+        let diff = TextDiff::from_lines(&from.content, &to.content);
+        let mut additions = 0;
+        let mut deletions = 0;
+
+        let lines = diff
+            .iter_all_changes()
+            .map(|change| {
+                let op = match change.tag() {
+                    ChangeTag::Equal => DiffOp::Equal,
+                    ChangeTag::Insert => {
+                        additions += 1;
+                        DiffOp::Insert
+                    }
+                    ChangeTag::Delete => {
+                        deletions += 1;
+                        DiffOp::Delete
+                    }
+                };
+                DiffLine {
+                    op,
+                    text: change.value().trim_end_matches('\n').to_string(),
+                }
+            })
+            .collect();
+
+        Self {
+            from_revision: from.revision_number,
+            to_revision: to.revision_number,
+            title_changed: from.title != to.title,
+            subtitle_changed: from.subtitle != to.subtitle,
+            additions,
+            deletions,
+            lines,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn revision(number: i32, title: &str, content: &str) -> ArticleRevision {
+        ArticleRevision {
+            id: format!("article_revision:{}", number),
+            article_id: "article:1".to_string(),
+            revision_number: number,
+            title: title.to_string(),
+            subtitle: None,
+            content: content.to_string(),
+            tags: None,
+            word_count: 0,
+            editor_id: "user-1".to_string(),
+            is_autosave: false,
+            change_summary: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_revision_diff_between() {
+        let from = revision(1, "Draft", "# Intro\nfirst line\nsecond line\n");
+        let to = revision(2, "Final", "# Intro\nfirst line\nsecond line, edited\nthird line\n");
+
+        let diff = RevisionDiff::between(&from, &to);
+
+        assert!(diff.title_changed);
+        assert!(!diff.subtitle_changed);
+        assert_eq!(diff.additions, 2);
+        assert_eq!(diff.deletions, 1);
+        assert_eq!(diff.lines[0].op, DiffOp::Equal);
+        assert!(diff.lines.iter().any(|l| l.op == DiffOp::Delete && l.text == "second line"));
+    }
+}
@@ -2,16 +2,51 @@ use serde::{Deserialize, Serialize};
 use crate::models::{article::ArticleListItem, tag::Tag, user::UserProfile};
 use chrono::{DateTime, Utc};
 use validator::Validate;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SearchQuery {
     pub q: String,
     pub search_type: Option<SearchType>,
     pub page: Option<i32>,
     pub limit: Option<i32>,
+
+    // 文章筛选
+    /// 作者用户名
+    pub author: Option<String>,
+    pub tag: Option<String>,
+    /// 出版物 slug
+    pub publication: Option<String>,
+    pub date_from: Option<DateTime<Utc>>,
+    pub date_to: Option<DateTime<Utc>>,
+    pub is_paid: Option<bool>,
+    /// 全文检索没有结果时按模糊匹配重试，默认开启
+    pub fuzzy: Option<bool>,
+    /// 文章的检索方式，默认关键词
+    pub mode: Option<SearchMode>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+impl SearchQuery {
+    /// 基础搜索的文章部分和高级搜索走同一套检索
+    pub fn to_advanced(&self) -> AdvancedSearchQuery {
+        AdvancedSearchQuery {
+            q: Some(self.q.clone()),
+            search_type: Some(SearchType::Articles),
+            author: self.author.clone(),
+            tags: self.tag.clone().map(|tag| vec![tag]),
+            publication: self.publication.clone(),
+            date_from: self.date_from,
+            date_to: self.date_to,
+            is_paid: self.is_paid,
+            fuzzy: self.fuzzy,
+            mode: self.mode,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate, ToSchema)]
 pub struct AdvancedSearchQuery {
     pub q: Option<String>,
     pub search_type: Option<SearchType>,
@@ -42,9 +77,12 @@ pub struct AdvancedSearchQuery {
     pub include_drafts: Option<bool>, // Only for author's own articles
     pub language: Option<String>,
     pub exclude_read: Option<bool>, // For logged-in users
+    /// 全文检索没有结果时按模糊匹配重试，默认开启
+    pub fuzzy: Option<bool>,
+    pub mode: Option<SearchMode>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum SearchType {
     All,
@@ -54,7 +92,18 @@ pub enum SearchType {
     Publications,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 文章检索方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    /// 全文索引和模糊匹配
+    #[default]
+    Keyword,
+    /// 按文章向量的相似度，需要启用 `EMBEDDING_PROVIDER`
+    Semantic,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SearchResults {
     pub articles: Vec<ArticleSearchResult>,
     pub users: Vec<UserSearchResult>,
@@ -63,7 +112,7 @@ pub struct SearchResults {
     pub total_results: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ArticleSearchResult {
     pub id: String,
     pub title: String,
@@ -76,11 +125,19 @@ pub struct ArticleSearchResult {
     pub published_at: chrono::DateTime<chrono::Utc>,
     pub clap_count: i64,
     pub comment_count: i64,
+    #[serde(default)]
     pub tags: Vec<String>,
+    /// BM25 相关度（标题权重最高），模糊匹配和语义搜索时为相似度
+    #[serde(default)]
+    pub relevance: f64,
+    /// `highlights` 中的第一项
     pub highlight: Option<SearchHighlight>,
+    /// 命中的字段及附近的片段，命中词用 `<mark>` 标记
+    #[serde(default)]
+    pub highlights: Vec<SearchHighlight>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UserSearchResult {
     pub user_id: String,
     pub username: String,
@@ -93,7 +150,7 @@ pub struct UserSearchResult {
     pub highlight: Option<SearchHighlight>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TagSearchResult {
     pub id: String,
     pub name: String,
@@ -105,7 +162,7 @@ pub struct TagSearchResult {
     pub highlight: Option<SearchHighlight>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PublicationSearchResult {
     pub id: String,
     pub name: String,
@@ -119,29 +176,30 @@ pub struct PublicationSearchResult {
     pub highlight: Option<SearchHighlight>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SearchHighlight {
     pub field: String,
     pub snippet: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SearchSuggestion {
     pub text: String,
     pub suggestion_type: SuggestionType,
     pub metadata: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum SuggestionType {
     Query,
     Tag,
     User,
     Publication,
+    Article,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SearchIndex {
     pub article_id: String,
     pub title: String,
@@ -155,7 +213,7 @@ pub struct SearchIndex {
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum SortBy {
     Relevance,
@@ -169,14 +227,14 @@ pub enum SortBy {
     AuthorName,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum SortOrder {
     Asc,
     Desc,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AdvancedSearchResults {
     pub articles: Vec<ArticleSearchResult>,
     pub users: Vec<UserSearchResult>,
@@ -189,7 +247,7 @@ pub struct AdvancedSearchResults {
     pub facets: SearchFacets,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SeriesSearchResult {
     pub id: String,
     pub title: String,
@@ -203,7 +261,7 @@ pub struct SeriesSearchResult {
     pub highlight: Option<SearchHighlight>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SearchFacets {
     pub tags: Vec<FacetItem>,
     pub authors: Vec<FacetItem>,
@@ -212,14 +270,14 @@ pub struct SearchFacets {
     pub reading_time_ranges: Vec<RangeFacet>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct FacetItem {
     pub value: String,
     pub label: String,
     pub count: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct DateRangeFacet {
     pub label: String,
     pub from: DateTime<Utc>,
@@ -227,7 +285,7 @@ pub struct DateRangeFacet {
     pub count: i64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct RangeFacet {
     pub label: String,
     pub min: i32,
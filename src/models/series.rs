@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use validator::Validate;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Series {
     pub id: String,
     pub title: String,
@@ -19,7 +20,7 @@ pub struct Series {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SeriesArticle {
     pub id: String,
     pub series_id: String,
@@ -28,7 +29,7 @@ pub struct SeriesArticle {
     pub added_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SeriesResponse {
     #[serde(flatten)]
     pub series: Series,
@@ -39,7 +40,7 @@ pub struct SeriesResponse {
     pub articles: Vec<SeriesArticleInfo>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SeriesArticleInfo {
     pub id: String,
     pub title: String,
@@ -53,7 +54,7 @@ pub struct SeriesArticleInfo {
     pub published_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SeriesListItem {
     pub id: String,
     pub title: String,
@@ -68,7 +69,7 @@ pub struct SeriesListItem {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SeriesSubscription {
     pub id: String,
     pub series_id: String,
@@ -76,7 +77,7 @@ pub struct SeriesSubscription {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Deserialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
 pub struct CreateSeriesRequest {
     #[validate(length(min = 1, max = 200))]
     pub title: String,
@@ -90,7 +91,7 @@ pub struct CreateSeriesRequest {
     pub is_public: Option<bool>,
 }
 
-#[derive(Debug, Clone, Deserialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
 pub struct UpdateSeriesRequest {
     #[validate(length(min = 1, max = 200))]
     pub title: Option<String>,
@@ -105,25 +106,72 @@ pub struct UpdateSeriesRequest {
     pub is_completed: Option<bool>,
 }
 
-#[derive(Debug, Clone, Deserialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
 pub struct AddArticleToSeriesRequest {
     #[validate(length(min = 1))]
     pub article_id: String,
     pub order_index: Option<i32>,
 }
 
-#[derive(Debug, Clone, Deserialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
 pub struct UpdateArticleOrderRequest {
     pub articles: Vec<ArticleOrderItem>,
 }
 
-#[derive(Debug, Clone, Deserialize, Validate)]
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
 pub struct ArticleOrderItem {
     pub article_id: String,
     pub order_index: i32,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+/// 拖拽排序后的完整顺序，未列出的文章保持原有相对顺序排在后面
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct ReorderSeriesArticlesRequest {
+    #[validate(length(min = 1, max = 500))]
+    pub article_ids: Vec<String>,
+}
+
+/// 系列中相邻文章的链接
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SeriesNavItem {
+    pub id: String,
+    pub title: String,
+    pub slug: String,
+    pub order_index: i32,
+}
+
+/// 读者在整个系列中的阅读进度，只统计已发布的文章
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SeriesProgress {
+    pub series_id: String,
+    pub total_articles: usize,
+    pub completed_articles: usize,
+    /// 0 - 100，保留一位小数
+    pub completion_percentage: f64,
+    pub articles: Vec<SeriesArticleProgress>,
+    /// 按系列顺序第一篇还没读完的文章
+    pub next_article: Option<SeriesNavItem>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SeriesArticleProgress {
+    #[serde(flatten)]
+    pub article: SeriesNavItem,
+    /// 滚动位置 0.0 - 1.0，没有读过为 0
+    pub progress: f64,
+    pub completed: bool,
+}
+
+/// 读完的篇数占已发布篇数的百分比
+pub fn completion_percentage(completed: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    (completed as f64 * 1000.0 / total as f64).round() / 10.0
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SeriesQuery {
     pub author_id: Option<String>,
     pub is_completed: Option<bool>,
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+
+use super::syndication::SyndicationPlatform;
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduledShareStatus {
+    Scheduled,
+    /// 已被后台任务领取，正在发布
+    Posting,
+    Posted,
+    Failed,
+    Cancelled,
+}
+
+impl ScheduledShareStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScheduledShareStatus::Scheduled => "scheduled",
+            ScheduledShareStatus::Posting => "posting",
+            ScheduledShareStatus::Posted => "posted",
+            ScheduledShareStatus::Failed => "failed",
+            ScheduledShareStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// 作者排期的一条推广帖子，到时间后由后台任务发布到连接的社交账号
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScheduledShare {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub user_id: String,
+    /// 发布到的账号（syndication_account）
+    pub account_id: String,
+    pub platform: SyndicationPlatform,
+    /// 推广的文章，可选
+    pub article_id: Option<String>,
+    pub text: String,
+    pub link: Option<String>,
+    pub image_url: Option<String>,
+    pub scheduled_at: DateTime<Utc>,
+    pub status: ScheduledShareStatus,
+    pub attempts: i64,
+    pub external_id: Option<String>,
+    pub external_url: Option<String>,
+    pub error: Option<String>,
+    pub posted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 一次发布尝试的结果
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ShareAttemptLog {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub share_id: String,
+    pub account_id: String,
+    pub success: bool,
+    pub external_id: Option<String>,
+    pub error: Option<String>,
+    pub attempted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateScheduledShareRequest {
+    #[validate(length(min = 1))]
+    pub account_id: String,
+    pub article_id: Option<String>,
+    #[validate(length(min = 1, max = 1000))]
+    pub text: String,
+    /// 不填且指定了文章时使用文章地址
+    #[validate(url)]
+    pub link: Option<String>,
+    #[validate(url)]
+    pub image_url: Option<String>,
+    pub scheduled_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateScheduledShareRequest {
+    #[validate(length(min = 1, max = 1000))]
+    pub text: Option<String>,
+    #[validate(url)]
+    pub link: Option<String>,
+    #[validate(url)]
+    pub image_url: Option<String>,
+    pub scheduled_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ScheduledShareQuery {
+    pub status: Option<ScheduledShareStatus>,
+    pub limit: Option<usize>,
+}
+
+/// 排期详情，包含每次发布尝试的记录
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ScheduledShareDetail {
+    #[serde(flatten)]
+    pub share: ScheduledShare,
+    pub attempts_log: Vec<ShareAttemptLog>,
+}
@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// 文章的朗读音频，记录ID与文章ID相同
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArticleAudio {
+    pub article_id: String,
+    /// 生成失败且之前没有音频时为空
+    pub audio_url: Option<String>,
+    /// 音频时长（秒）
+    pub duration_seconds: Option<f64>,
+    pub size: Option<i64>,
+    /// 生成时使用的模型和音色，配置变化后重新生成
+    pub voice: String,
+    /// 生成音频时朗读文本每句话的摘要，用于判断之后的修改是否需要重新生成
+    #[serde(default)]
+    pub sentence_hashes: Vec<String>,
+    /// 最近一次检查时文章的 updated_at
+    pub source_updated_at: DateTime<Utc>,
+    pub generated_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub failed_at: Option<DateTime<Utc>>,
+}
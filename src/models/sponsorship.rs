@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, NaiveDate, Utc};
+use validator::Validate;
+use utoipa::{IntoParams, ToSchema};
+
+/// 出版物页面上可以放置赞助内容的位置
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SponsorPlacement {
+    /// 出版物首页顶部
+    HomeTop,
+    /// 文章列表中
+    ArticleList,
+    /// 文章正文之前
+    ArticleTop,
+    /// 文章正文之后
+    ArticleBottom,
+}
+
+impl SponsorPlacement {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SponsorPlacement::HomeTop => "home_top",
+            SponsorPlacement::ArticleList => "article_list",
+            SponsorPlacement::ArticleTop => "article_top",
+            SponsorPlacement::ArticleBottom => "article_bottom",
+        }
+    }
+}
+
+/// 出版物定义的赞助位，每个赞助位同一时间只展示一个赞助
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SponsorSlot {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub publication_id: String,
+    pub name: String,
+    pub placement: SponsorPlacement,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateSponsorSlotRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    pub placement: SponsorPlacement,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SponsorshipStatus {
+    /// 在投放期内展示
+    Active,
+    /// 暂停，投放期内也不展示
+    Paused,
+}
+
+/// 直接售出的赞助：在投放期内占用一个赞助位
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Sponsorship {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub publication_id: String,
+    pub slot_id: String,
+    pub sponsor_name: String,
+    pub sponsor_url: String,
+    pub headline: String,
+    pub body: Option<String>,
+    /// 通过 POST .../asset 上传的赞助商图片
+    pub asset_url: Option<String>,
+    /// 展示在赞助内容上的披露标签
+    pub disclosure_label: String,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub status: SponsorshipStatus,
+    pub impression_count: i64,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Sponsorship {
+    pub const DEFAULT_DISCLOSURE_LABEL: &'static str = "Sponsored";
+
+    pub fn is_live(&self, now: DateTime<Utc>) -> bool {
+        self.status == SponsorshipStatus::Active && self.starts_at <= now && now < self.ends_at
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateSponsorshipRequest {
+    pub slot_id: String,
+    #[validate(length(min = 1, max = 100))]
+    pub sponsor_name: String,
+    #[validate(url)]
+    pub sponsor_url: String,
+    #[validate(length(min = 1, max = 150))]
+    pub headline: String,
+    #[validate(length(max = 500))]
+    pub body: Option<String>,
+    /// 默认为 "Sponsored"
+    #[validate(length(min = 1, max = 40))]
+    pub disclosure_label: Option<String>,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UpdateSponsorshipRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub sponsor_name: Option<String>,
+    #[validate(url)]
+    pub sponsor_url: Option<String>,
+    #[validate(length(min = 1, max = 150))]
+    pub headline: Option<String>,
+    #[validate(length(max = 500))]
+    pub body: Option<String>,
+    #[validate(length(min = 1, max = 40))]
+    pub disclosure_label: Option<String>,
+    pub starts_at: Option<DateTime<Utc>>,
+    pub ends_at: Option<DateTime<Utc>>,
+    pub status: Option<SponsorshipStatus>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SponsorBlockQuery {
+    /// 逗号分隔的位置，例如 `article_top,article_bottom`
+    pub placements: Option<String>,
+}
+
+/// 渲染到页面上的赞助内容，`html` 已包含披露标签
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SponsorBlock {
+    pub sponsorship_id: String,
+    pub slot_id: String,
+    pub placement: SponsorPlacement,
+    pub disclosure_label: String,
+    pub sponsor_name: String,
+    pub sponsor_url: String,
+    pub headline: String,
+    pub body: Option<String>,
+    pub asset_url: Option<String>,
+    pub html: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DailyImpressions {
+    pub day: NaiveDate,
+    pub impressions: i64,
+}
+
+/// 赞助的展示报告，交给赞助商核对
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SponsorshipReport {
+    pub sponsorship: Sponsorship,
+    pub total_impressions: i64,
+    pub daily: Vec<DailyImpressions>,
+}
@@ -1,9 +1,10 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
+use utoipa::ToSchema;
 
 /// Stripe客户配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct StripeCustomer {
     pub id: String,
     pub user_id: String,
@@ -16,7 +17,7 @@ pub struct StripeCustomer {
 }
 
 /// Stripe支付方式
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct StripePaymentMethod {
     pub id: String,
     pub user_id: String,
@@ -32,7 +33,7 @@ pub struct StripePaymentMethod {
 }
 
 /// 支付方式类型
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum PaymentMethodType {
     Card,
@@ -42,7 +43,7 @@ pub enum PaymentMethodType {
 }
 
 /// Stripe意图模式
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum StripeIntentMode {
     Payment,
@@ -56,7 +57,7 @@ impl Default for StripeIntentMode {
 }
 
 /// Stripe订阅
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct StripeSubscription {
     pub id: String,
     pub subscription_id: String, // 内部订阅ID
@@ -75,7 +76,7 @@ pub struct StripeSubscription {
 }
 
 /// Stripe订阅状态
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum StripeSubscriptionStatus {
     Trialing,
@@ -88,7 +89,7 @@ pub enum StripeSubscriptionStatus {
 }
 
 /// Stripe支付意图
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct StripePaymentIntent {
     pub id: String,
     pub stripe_payment_intent_id: String,
@@ -105,7 +106,7 @@ pub struct StripePaymentIntent {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct StripeIntentResponse {
     pub mode: StripeIntentMode,
     pub client_secret: String,
@@ -114,7 +115,7 @@ pub struct StripeIntentResponse {
 }
 
 /// 支付意图状态
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum PaymentIntentStatus {
     RequiresPaymentMethod,
@@ -127,7 +128,7 @@ pub enum PaymentIntentStatus {
 }
 
 /// Stripe Connect账户
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct StripeConnectAccount {
     pub id: String,
     pub user_id: String,
@@ -143,7 +144,7 @@ pub struct StripeConnectAccount {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ConnectAccountResponse {
     pub account: StripeConnectAccount,
     pub onboarding_url: Option<String>,
@@ -151,7 +152,7 @@ pub struct ConnectAccountResponse {
 }
 
 /// Connect账户类型
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ConnectAccountType {
     Express,
@@ -160,7 +161,7 @@ pub enum ConnectAccountType {
 }
 
 /// Stripe产品
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct StripeProduct {
     pub id: String,
     pub plan_id: String, // 内部订阅计划ID
@@ -174,7 +175,7 @@ pub struct StripeProduct {
 }
 
 /// Stripe价格
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct StripePrice {
     pub id: String,
     pub product_id: String, // StripeProduct的ID
@@ -189,19 +190,38 @@ pub struct StripePrice {
 }
 
 /// WebHook事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct StripeWebhookEvent {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
     pub id: String,
     pub stripe_event_id: String,
     pub event_type: String,
     pub processed: bool,
     pub processed_at: Option<DateTime<Utc>>,
     pub data: serde_json::Value,
+    /// 处理失败的次数
+    #[serde(default)]
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    /// 下一次自动重试的时间，没有安排重试时为空
+    pub next_retry_at: Option<DateTime<Utc>>,
+    /// 重试次数用尽、进入死信的时间
+    pub dead_lettered_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
 }
 
+/// Webhook 事件的处理结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookDelivery {
+    Processed,
+    /// 处理失败，已保存等待后台重试
+    QueuedForRetry,
+    /// 重试次数用尽，等待管理员重新入队
+    DeadLettered,
+}
+
 /// 创建支付意图请求
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateStripeIntentRequest {
     #[serde(default)]
     pub mode: StripeIntentMode,
@@ -219,7 +239,7 @@ pub struct CreateStripeIntentRequest {
 }
 
 /// 添加支付方式请求
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreatePaymentMethodRequest {
     pub payment_method_id: String,
     #[serde(default)]
@@ -227,17 +247,20 @@ pub struct CreatePaymentMethodRequest {
 }
 
 /// 创建订阅请求
-#[derive(Debug, Validate, Deserialize)]
+#[derive(Debug, Validate, Deserialize, ToSchema)]
 pub struct CreateStripeSubscriptionRequest {
     pub price_id: String,
     pub payment_method_id: Option<String>,
     pub trial_period_days: Option<i32>,
     pub coupon: Option<String>,
     pub metadata: Option<serde_json::Value>,
+    /// 客户端的 `Idempotency-Key` 请求头，由路由填入
+    #[serde(skip)]
+    pub idempotency_key: Option<String>,
 }
 
 /// 创建Connect账户请求
-#[derive(Debug, Validate, Deserialize)]
+#[derive(Debug, Validate, Deserialize, ToSchema)]
 pub struct CreateConnectAccountRequest {
     #[validate(length(min = 2, max = 2))]
     pub country: String,
@@ -258,6 +281,10 @@ pub struct StripeConfig {
     pub connect_return_url: Option<String>,
     pub connect_refresh_url: Option<String>,
     pub api_version: String,
+    /// 是否通过 Stripe Tax 自动计算并代收税费（VAT/GST/销售税）
+    pub tax_enabled: bool,
+    /// 付费内容使用的 Stripe 税码
+    pub tax_code: String,
 }
 
 impl Default for StripeConfig {
@@ -288,12 +315,17 @@ impl Default for StripeConfig {
             connect_return_url: default_return,
             connect_refresh_url: default_refresh,
             api_version: "2023-10-16".to_string(),
+            tax_enabled: std::env::var("STRIPE_TAX_ENABLED")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            tax_code: std::env::var("STRIPE_TAX_CODE")
+                .unwrap_or_else(|_| "txcd_10000000".to_string()),
         }
     }
 }
 
 /// Stripe错误类型
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct StripeError {
     pub code: Option<String>,
     pub message: String,
@@ -302,7 +334,7 @@ pub struct StripeError {
 }
 
 /// 支付统计
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct PaymentStats {
     pub total_payments: i64,
     pub successful_payments: i64,
@@ -315,7 +347,7 @@ pub struct PaymentStats {
 }
 
 /// 退款记录
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct StripeRefund {
     pub id: String,
     pub stripe_refund_id: String,
@@ -329,7 +361,7 @@ pub struct StripeRefund {
 }
 
 /// 退款状态
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum RefundStatus {
     Pending,
@@ -339,7 +371,7 @@ pub enum RefundStatus {
 }
 
 /// 优惠券
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct StripeCoupon {
     pub id: String,
     pub stripe_coupon_id: String,
@@ -358,7 +390,7 @@ pub struct StripeCoupon {
 }
 
 /// 优惠券持续时间
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum CouponDuration {
     Once,
@@ -367,7 +399,7 @@ pub enum CouponDuration {
 }
 
 /// 发票
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct StripeInvoice {
     pub id: String,
     pub stripe_invoice_id: String,
@@ -384,7 +416,7 @@ pub struct StripeInvoice {
 }
 
 /// 发票状态
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum InvoiceStatus {
     Draft,
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use utoipa::ToSchema;
+
+/// 出版物的写作规范，用于检查草稿；不属于出版物的文章使用默认规范
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StyleGuide {
+    pub publication_id: Option<String>,
+    /// 禁用的词语（不区分大小写）
+    pub banned_phrases: Vec<String>,
+    /// 提示可能的被动语态（英文启发式规则）
+    pub check_passive_voice: bool,
+    /// 允许的最深标题级别，例如 3 表示最多到 `###`
+    pub max_heading_depth: u8,
+    /// 图片必须有替代文本
+    pub require_alt_text: bool,
+    /// 有错误级别的问题时，不能发布到出版物或提交审核
+    pub enforce_on_submit: bool,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl StyleGuide {
+    pub const DEFAULT_MAX_HEADING_DEPTH: u8 = 3;
+
+    pub fn default_for(publication_id: Option<&str>) -> Self {
+        Self {
+            publication_id: publication_id.map(str::to_string),
+            banned_phrases: Vec::new(),
+            check_passive_voice: true,
+            max_heading_depth: Self::DEFAULT_MAX_HEADING_DEPTH,
+            require_alt_text: true,
+            enforce_on_submit: false,
+            updated_at: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UpdateStyleGuideRequest {
+    #[validate(length(max = 200))]
+    pub banned_phrases: Option<Vec<String>>,
+    pub check_passive_voice: Option<bool>,
+    #[validate(range(min = 1, max = 6))]
+    pub max_heading_depth: Option<u8>,
+    pub require_alt_text: Option<bool>,
+    pub enforce_on_submit: Option<bool>,
+}
+
+/// 检查编辑器中尚未保存的内容时提供正文
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct LintArticleRequest {
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LintRule {
+    BannedPhrase,
+    PassiveVoice,
+    HeadingDepth,
+    /// 标题跳级，例如 `##` 之后直接是 `####`
+    HeadingSkip,
+    MissingAltText,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LintSeverity {
+    /// 规范开启强制检查时会阻止提交
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, ToSchema)]
+pub struct LintIssue {
+    pub rule: LintRule,
+    pub severity: LintSeverity,
+    pub message: String,
+    /// 问题所在的行（从 1 开始）
+    pub line: usize,
+    /// 命中的文字
+    pub excerpt: String,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LintReport {
+    pub issues: Vec<LintIssue>,
+    pub error_count: usize,
+    pub warning_count: usize,
+    /// 规范是否要求提交前修复错误
+    pub enforced: bool,
+}
+
+impl LintReport {
+    pub fn new(issues: Vec<LintIssue>, enforced: bool) -> Self {
+        let error_count = issues.iter().filter(|i| i.severity == LintSeverity::Error).count();
+        Self {
+            warning_count: issues.len() - error_count,
+            error_count,
+            issues,
+            enforced,
+        }
+    }
+
+    pub fn blocks_submission(&self) -> bool {
+        self.enforced && self.error_count > 0
+    }
+}
@@ -1,9 +1,11 @@
+use crate::models::stripe::CouponDuration;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
+use utoipa::{IntoParams, ToSchema};
 
 /// 订阅计划
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SubscriptionPlan {
     pub id: String,
     pub creator_id: String,
@@ -20,7 +22,7 @@ pub struct SubscriptionPlan {
 }
 
 /// 创建订阅计划请求
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreateSubscriptionPlanRequest {
     #[validate(length(min = 1, max = 100, message = "计划名称长度必须在1-100字符之间"))]
     pub name: String,
@@ -38,7 +40,7 @@ pub struct CreateSubscriptionPlanRequest {
 }
 
 /// 更新订阅计划请求
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UpdateSubscriptionPlanRequest {
     #[validate(length(min = 1, max = 100, message = "计划名称长度必须在1-100字符之间"))]
     pub name: Option<String>,
@@ -54,7 +56,7 @@ pub struct UpdateSubscriptionPlanRequest {
 }
 
 /// 用户订阅
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Subscription {
     pub id: String,
     pub subscriber_id: String,
@@ -71,7 +73,7 @@ pub struct Subscription {
 }
 
 /// 订阅状态
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum SubscriptionStatus {
     Active,
@@ -92,14 +94,21 @@ impl std::fmt::Display for SubscriptionStatus {
 }
 
 /// 创建订阅请求
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreateSubscriptionRequest {
     pub plan_id: String,
     pub payment_method_id: Option<String>, // Stripe payment method ID
+    /// 结账时使用的优惠码
+    #[serde(default)]
+    #[validate(length(max = 32, message = "优惠码不能超过32字符"))]
+    pub coupon_code: Option<String>,
+    /// 客户端的 `Idempotency-Key` 请求头，由路由填入
+    #[serde(skip)]
+    pub idempotency_key: Option<String>,
 }
 
 /// 订阅详情（包含计划信息）
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SubscriptionDetails {
     pub id: String,
     pub subscriber_id: String,
@@ -116,7 +125,7 @@ pub struct SubscriptionDetails {
 }
 
 /// 订阅中的创作者信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SubscriptionCreator {
     pub user_id: String,
     pub username: String,
@@ -126,7 +135,7 @@ pub struct SubscriptionCreator {
 }
 
 /// 订阅统计
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SubscriptionStats {
     pub total_subscribers: i64,
     pub active_subscribers: i64,
@@ -137,7 +146,8 @@ pub struct SubscriptionStats {
 }
 
 /// 订阅查询参数
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SubscriptionQuery {
     pub page: Option<i32>,
     pub limit: Option<i32>,
@@ -147,7 +157,7 @@ pub struct SubscriptionQuery {
 }
 
 /// 订阅分页结果
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SubscriptionListResponse {
     pub subscriptions: Vec<SubscriptionDetails>,
     pub total: i64,
@@ -157,7 +167,7 @@ pub struct SubscriptionListResponse {
 }
 
 /// Stripe Webhook事件
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct StripeWebhookEvent {
     pub id: String,
     pub r#type: String,
@@ -165,7 +175,7 @@ pub struct StripeWebhookEvent {
 }
 
 /// 订阅计划分页结果
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SubscriptionPlanListResponse {
     pub plans: Vec<SubscriptionPlan>,
     pub total: i64,
@@ -175,7 +185,8 @@ pub struct SubscriptionPlanListResponse {
 }
 
 /// 订阅计划查询参数
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SubscriptionPlanQuery {
     pub page: Option<i32>,
     pub limit: Option<i32>,
@@ -184,7 +195,7 @@ pub struct SubscriptionPlanQuery {
 }
 
 /// 创作者收益统计
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CreatorRevenue {
     pub creator_id: String,
     pub total_subscribers: i64,
@@ -195,9 +206,126 @@ pub struct CreatorRevenue {
 }
 
 /// 订阅检查结果
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct SubscriptionCheck {
     pub is_subscribed: bool,
     pub subscription: Option<SubscriptionDetails>,
     pub can_access_paid_content: bool,
 }
+
+/// 订阅优惠券。在 Stripe 创建，本地保存一份用于结账校验和使用统计
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SubscriptionCoupon {
+    pub id: String,
+    pub creator_id: String,
+    /// 读者结账时输入的优惠码，统一大写，同一创作者内唯一
+    pub code: String,
+    pub stripe_coupon_id: String,
+    pub discount_type: CouponDiscountType,
+    /// 百分比（1-100）或固定减免金额（美分）
+    pub discount_value: i64,
+    /// 固定金额优惠券的货币
+    pub currency: Option<String>,
+    pub duration: CouponDuration,
+    pub duration_in_months: Option<i32>,
+    pub max_redemptions: Option<i32>,
+    pub times_redeemed: i32,
+    /// 适用的订阅计划，为空时适用于创作者的所有计划
+    pub plan_ids: Vec<String>,
+    pub redeem_by: Option<DateTime<Utc>>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl SubscriptionCoupon {
+    /// 折扣后的每期价格（美分），不会低于 0
+    pub fn discounted_price(&self, price: i64) -> i64 {
+        let discount = match self.discount_type {
+            CouponDiscountType::Percentage => (price as f64 * self.discount_value as f64 / 100.0).round() as i64,
+            CouponDiscountType::Fixed => self.discount_value,
+        };
+        (price - discount).max(0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CouponDiscountType {
+    Percentage,
+    Fixed,
+}
+
+/// 创建优惠券请求
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct CreateCouponRequest {
+    #[validate(length(min = 3, max = 32, message = "优惠码长度必须在3-32字符之间"))]
+    pub code: String,
+
+    pub discount_type: CouponDiscountType,
+
+    #[validate(range(min = 1, message = "折扣必须大于0"))]
+    pub discount_value: i64,
+
+    #[validate(length(min = 3, max = 3, message = "货币代码必须是3位字符"))]
+    pub currency: Option<String>,
+
+    pub duration: CouponDuration,
+
+    #[validate(range(min = 1, max = 36, message = "持续月数必须在1-36之间"))]
+    pub duration_in_months: Option<i32>,
+
+    #[validate(range(min = 1, message = "最大使用次数必须大于0"))]
+    pub max_redemptions: Option<i32>,
+
+    pub redeem_by: Option<DateTime<Utc>>,
+
+    #[serde(default)]
+    pub plan_ids: Vec<String>,
+}
+
+/// 结账前预览优惠券对某个计划的效果
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CouponPreview {
+    pub code: String,
+    pub plan_id: String,
+    pub original_price: i64,
+    pub discounted_price: i64,
+    pub currency: String,
+    pub duration: CouponDuration,
+    pub duration_in_months: Option<i32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coupon(discount_type: CouponDiscountType, discount_value: i64) -> SubscriptionCoupon {
+        SubscriptionCoupon {
+            id: "c1".to_string(),
+            creator_id: "u1".to_string(),
+            code: "LAUNCH".to_string(),
+            stripe_coupon_id: "co_1".to_string(),
+            discount_type,
+            discount_value,
+            currency: None,
+            duration: CouponDuration::Once,
+            duration_in_months: None,
+            max_redemptions: None,
+            times_redeemed: 0,
+            plan_ids: vec![],
+            redeem_by: None,
+            is_active: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_discounted_price() {
+        assert_eq!(coupon(CouponDiscountType::Percentage, 25).discounted_price(500), 375);
+        assert_eq!(coupon(CouponDiscountType::Percentage, 100).discounted_price(500), 0);
+        assert_eq!(coupon(CouponDiscountType::Fixed, 200).discounted_price(500), 300);
+        assert_eq!(coupon(CouponDiscountType::Fixed, 800).discounted_price(500), 0);
+    }
+}
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SyndicationPlatform {
+    DevTo,
+    Hashnode,
+    /// Twitter/X，文章以推文串的形式发布
+    Twitter,
+}
+
+impl SyndicationPlatform {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyndicationPlatform::DevTo => "dev_to",
+            SyndicationPlatform::Hashnode => "hashnode",
+            SyndicationPlatform::Twitter => "twitter",
+        }
+    }
+
+    /// 是否可以发布推广短帖（排期分享）
+    pub fn supports_social_posts(&self) -> bool {
+        matches!(self, SyndicationPlatform::Twitter)
+    }
+}
+
+/// 作者连接的外部平台账号
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SyndicationAccount {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub user_id: String,
+    pub platform: SyndicationPlatform,
+    /// 外部平台上的用户名，仅用于展示
+    pub account_name: Option<String>,
+    /// 平台的 API key / access token，不返回给客户端
+    #[serde(skip_serializing)]
+    pub access_token: String,
+    /// Hashnode 要发布到的 publication ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hashnode_publication_id: Option<String>,
+    /// 文章发布时自动同步
+    pub auto_post: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct ConnectSyndicationAccountRequest {
+    pub platform: SyndicationPlatform,
+    #[validate(length(min = 1, max = 500))]
+    pub access_token: String,
+    #[validate(length(max = 100))]
+    pub account_name: Option<String>,
+    #[validate(length(max = 100))]
+    pub hashnode_publication_id: Option<String>,
+    #[serde(default)]
+    pub auto_post: bool,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateSyndicationAccountRequest {
+    pub auto_post: Option<bool>,
+    #[validate(length(min = 1, max = 500))]
+    pub access_token: Option<String>,
+    #[validate(length(max = 100))]
+    pub hashnode_publication_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SyndicationStatus {
+    Pending,
+    Published,
+    Failed,
+}
+
+impl SyndicationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyndicationStatus::Pending => "pending",
+            SyndicationStatus::Published => "published",
+            SyndicationStatus::Failed => "failed",
+        }
+    }
+}
+
+/// 一篇文章在一个外部账号上的同步状态（记录 ID 为 article_id + account_id）
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SyndicatedPost {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub article_id: String,
+    pub account_id: String,
+    pub user_id: String,
+    pub platform: SyndicationPlatform,
+    pub status: SyndicationStatus,
+    /// 外部平台上的帖子 ID（推文串为第一条推文）
+    pub external_id: Option<String>,
+    pub external_url: Option<String>,
+    pub error: Option<String>,
+    pub attempts: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct SyndicateArticleRequest {
+    /// 要同步到的账号，为空时同步到所有已连接的账号
+    #[serde(default)]
+    pub account_ids: Vec<String>,
+}
+
+/// 外部平台发布成功后返回的帖子
+#[derive(Debug, Clone)]
+pub struct ExternalPost {
+    pub id: String,
+    pub url: String,
+}
@@ -2,8 +2,9 @@ use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use validator::Validate;
 use crate::utils::serde_helpers::thing_id;
+use utoipa::{IntoParams, ToSchema};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Tag {
     #[serde(with = "thing_id")]
     pub id: String,
@@ -13,11 +14,17 @@ pub struct Tag {
     pub follower_count: i64,
     pub article_count: i64,
     pub is_featured: bool,
+    /// 上级标签（不带表名的 ID），例如 tokio 的上级是 rust
+    #[serde(default)]
+    pub parent_id: Option<String>,
+    /// 指向该标签的其他写法（slug 形式），按别名打标签或访问时使用该标签
+    #[serde(default)]
+    pub aliases: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreateTagRequest {
     #[validate(length(min = 1, max = 50))]
     pub name: String,
@@ -25,7 +32,7 @@ pub struct CreateTagRequest {
     pub description: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UpdateTagRequest {
     #[validate(length(min = 1, max = 50))]
     pub name: Option<String>,
@@ -34,7 +41,7 @@ pub struct UpdateTagRequest {
     pub is_featured: Option<bool>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ArticleTag {
     #[serde(with = "thing_id")]
     pub id: String,
@@ -45,7 +52,7 @@ pub struct ArticleTag {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UserTagFollow {
     #[serde(with = "thing_id")]
     pub id: String,
@@ -56,18 +63,45 @@ pub struct UserTagFollow {
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TagWithFollowStatus {
     #[serde(flatten)]
     pub tag: Tag,
     pub is_following: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct TagQuery {
     pub search: Option<String>,
     pub featured_only: Option<bool>,
     pub sort_by: Option<String>, // popular, name, created_at
     pub page: Option<i32>,
     pub limit: Option<i32>,
+}
+
+/// 设置或清除上级标签
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SetTagParentRequest {
+    pub parent_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct TagAliasRequest {
+    #[validate(length(min = 1, max = 50))]
+    pub alias: String,
+}
+
+/// 把路径中的标签合并到 `into_tag_id`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MergeTagRequest {
+    pub into_tag_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TagMergeResult {
+    /// 合并后的标签，被合并标签的名称和 slug 已加入别名
+    pub tag: Tag,
+    pub moved_articles: i64,
+    pub moved_followers: i64,
 }
\ No newline at end of file
@@ -0,0 +1,167 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+
+use super::article::{Article, CreateArticleRequest};
+use utoipa::{IntoParams, ToSchema};
+
+/// 模板的 SEO 检查项，发布前在检查结果中提示（不阻止发布）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(tag = "check", rename_all = "snake_case")]
+pub enum SeoCheck {
+    /// SEO 标题（没有时使用文章标题）的长度范围
+    TitleLength { min: usize, max: usize },
+    SeoDescription,
+    CoverImage,
+    Excerpt,
+    MinWords { words: usize },
+    MinTags { count: usize },
+}
+
+impl SeoCheck {
+    pub fn evaluate(&self, article: &Article, tags: &[String]) -> SeoCheckResult {
+        let has_text = |value: &Option<String>| value.as_deref().map_or(false, |v| !v.trim().is_empty());
+
+        let (passed, message) = match self {
+            SeoCheck::TitleLength { min, max } => {
+                let title = article.seo_title.as_deref().unwrap_or(&article.title);
+                let length = title.chars().count();
+                (
+                    length >= *min && length <= *max,
+                    format!("SEO title should be {}-{} characters (currently {})", min, max, length),
+                )
+            }
+            SeoCheck::SeoDescription => (has_text(&article.seo_description), "Add an SEO description".to_string()),
+            SeoCheck::CoverImage => (has_text(&article.cover_image_url), "Add a cover image".to_string()),
+            SeoCheck::Excerpt => (has_text(&article.excerpt), "Add an excerpt".to_string()),
+            SeoCheck::MinWords { words } => (
+                article.word_count.max(0) as usize >= *words,
+                format!("Write at least {} words (currently {})", words, article.word_count),
+            ),
+            SeoCheck::MinTags { count } => (
+                tags.len() >= *count,
+                format!("Add at least {} tags (currently {})", count, tags.len()),
+            ),
+        };
+
+        SeoCheckResult {
+            check: self.clone(),
+            passed,
+            message,
+        }
+    }
+}
+
+/// 出版物的文章模板：预填的正文结构、必需的章节、默认标签和 SEO 检查清单
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArticleTemplate {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub publication_id: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// 新文章的初始正文（Markdown）
+    pub content: String,
+    /// 发布前正文中必须包含的章节标题（不区分大小写）
+    pub required_sections: Vec<String>,
+    pub default_tags: Vec<String>,
+    pub seo_checklist: Vec<SeoCheck>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ArticleTemplate {
+    /// 用模板预填新文章：文章归属模板所在的出版物，正文为空时使用模板正文，默认标签与请求中的标签合并
+    pub fn apply_to(&self, request: &mut CreateArticleRequest) {
+        request.publication_id = Some(self.publication_id.clone());
+        if request.content.trim().is_empty() {
+            request.content = self.content.clone();
+        }
+
+        let tags = request.tags.get_or_insert_with(Vec::new);
+        for tag in &self.default_tags {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.clone());
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct CreateTemplateRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: String,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+    #[validate(length(max = 50000))]
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub required_sections: Vec<String>,
+    #[serde(default)]
+    pub default_tags: Vec<String>,
+    #[serde(default)]
+    pub seo_checklist: Vec<SeoCheck>,
+}
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+pub struct UpdateTemplateRequest {
+    #[validate(length(min = 1, max = 100))]
+    pub name: Option<String>,
+    #[validate(length(max = 500))]
+    pub description: Option<String>,
+    #[validate(length(max = 50000))]
+    pub content: Option<String>,
+    pub required_sections: Option<Vec<String>>,
+    pub default_tags: Option<Vec<String>>,
+    pub seo_checklist: Option<Vec<SeoCheck>>,
+}
+
+/// 创建文章时选择模板
+#[derive(Debug, Default, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CreateArticleQuery {
+    pub template: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SeoCheckResult {
+    #[serde(flatten)]
+    pub check: SeoCheck,
+    pub passed: bool,
+    pub message: String,
+}
+
+/// 文章对照模板的检查结果；缺少必需章节时不能发布
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TemplateCheckResult {
+    pub template_id: String,
+    pub template_name: String,
+    pub missing_sections: Vec<String>,
+    pub seo_checks: Vec<SeoCheckResult>,
+    pub can_publish: bool,
+}
+
+/// 正文标题中缺少的必需章节，比较时忽略大小写和首尾空白
+pub fn missing_sections(required: &[String], headings: &[String]) -> Vec<String> {
+    let headings: Vec<String> = headings.iter().map(|h| h.trim().to_lowercase()).collect();
+    required
+        .iter()
+        .filter(|section| !headings.contains(&section.trim().to_lowercase()))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_sections_ignores_case() {
+        let required = vec!["TL;DR".to_string(), "Background".to_string(), "Next steps".to_string()];
+        let headings = vec!["tl;dr".to_string(), " Background ".to_string(), "Details".to_string()];
+
+        assert_eq!(missing_sections(&required, &headings), vec!["Next steps".to_string()]);
+    }
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use validator::Validate;
+use utoipa::ToSchema;
+
+/// 出版物主题，随出版物内容一起返回，供自定义域名的前端渲染品牌样式
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PublicationTheme {
+    pub publication_id: String,
+    pub colors: ThemeColors,
+    pub fonts: ThemeFonts,
+    pub logos: ThemeLogos,
+    /// 已清理的自定义 CSS
+    pub custom_css: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ThemeColors {
+    pub primary: String,
+    pub accent: String,
+    pub background: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ThemeFonts {
+    pub heading: Option<String>,
+    pub body: Option<String>,
+}
+
+/// 浅色背景用的标志、深色背景用的标志和方形图标
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ThemeLogos {
+    pub light: Option<String>,
+    pub dark: Option<String>,
+    pub icon: Option<String>,
+}
+
+impl PublicationTheme {
+    /// 没有设置过主题时，主色沿用出版物的 `theme_color`
+    pub fn default_for(publication_id: &str, theme_color: Option<String>, logo_url: Option<String>) -> Self {
+        Self {
+            publication_id: publication_id.to_string(),
+            colors: ThemeColors {
+                primary: theme_color.unwrap_or_else(|| "#1a1a1a".to_string()),
+                accent: "#242424".to_string(),
+                background: "#ffffff".to_string(),
+                text: "#242424".to_string(),
+            },
+            fonts: ThemeFonts::default(),
+            logos: ThemeLogos {
+                light: logo_url,
+                ..ThemeLogos::default()
+            },
+            custom_css: None,
+            updated_at: None,
+        }
+    }
+}
+
+/// 只修改提交的字段；字体、标志和 CSS 传空字符串表示清除
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct UpdateThemeRequest {
+    pub primary_color: Option<String>,
+    pub accent_color: Option<String>,
+    pub background_color: Option<String>,
+    pub text_color: Option<String>,
+    pub heading_font: Option<String>,
+    pub body_font: Option<String>,
+    #[validate(length(max = 500))]
+    pub logo_light_url: Option<String>,
+    #[validate(length(max = 500))]
+    pub logo_dark_url: Option<String>,
+    #[validate(length(max = 500))]
+    pub icon_url: Option<String>,
+    #[validate(length(max = 20000))]
+    pub custom_css: Option<String>,
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use utoipa::{IntoParams, ToSchema};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TrashItemType {
+    Article,
+    Comment,
+}
+
+/// 回收站中的一条记录
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TrashItem {
+    pub item_type: TrashItemType,
+    pub id: String,
+    /// 文章标题，或评论内容的开头
+    pub title: String,
+    /// 评论所属的文章
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub article_id: Option<String>,
+    pub deleted_at: DateTime<Utc>,
+    /// 到期后由后台任务永久删除；关闭自动清理时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purge_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TrashQuery {
+    /// 只列出文章或评论，默认两者都列出
+    #[serde(rename = "type")]
+    pub item_type: Option<TrashItemType>,
+    pub page: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+/// 一次回收站清理的结果
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct TrashPurgeReport {
+    pub articles: usize,
+    pub comments: usize,
+}
@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use utoipa::ToSchema;
+
+/// 当前用户的两步验证状态
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TwoFactorStatus {
+    pub enabled: bool,
+    pub enabled_at: Option<DateTime<Utc>>,
+    pub recovery_codes_remaining: usize,
+    /// 当前登录会话是否已通过两步验证
+    pub session_verified: bool,
+}
+
+/// 开始绑定验证器时返回的密钥，确认之前不会生效
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TwoFactorEnrollment {
+    /// Base32 编码的密钥，供无法扫码时手动输入
+    pub secret: String,
+    pub otpauth_url: String,
+    /// PNG 二维码，`data:image/png;base64,...`
+    pub qr_code: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+pub struct TwoFactorCodeRequest {
+    /// 验证器上的 6 位动态码，或一个恢复码
+    #[validate(length(min = 6, max = 20, message = "验证码长度不正确"))]
+    pub code: String,
+}
+
+/// 恢复码只在生成时返回一次
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RecoveryCodes {
+    pub recovery_codes: Vec<String>,
+}
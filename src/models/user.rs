@@ -3,9 +3,11 @@ use serde::{Deserialize, Serialize};
 use surrealdb::sql::Thing;
 use uuid::Uuid;
 use validator::Validate;
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UserProfile {
+    #[schema(value_type = String)]
     pub id: Thing,
     pub user_id: String, // Rainbow-Auth 用户ID
     pub username: String,
@@ -42,11 +44,34 @@ pub struct UserProfile {
     pub total_claps_received: i64,
     pub is_verified: bool,
     pub is_suspended: bool,
+    /// 是否同意出版物在受众洞察中使用其地区/语言数据
+    #[serde(default)]
+    pub allow_audience_insights: bool,
+    #[serde(default)]
+    pub preferred_language: Option<String>,
+    /// 信誉分（由 ReputationService 定期计算）
+    #[serde(default)]
+    pub reputation_score: i32,
+    #[serde(default)]
+    pub reputation_updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_active_at: Option<DateTime<Utc>>,
+    /// 因长期未活跃被生命周期任务停用的时间（再次登录时自动恢复）
+    #[serde(default)]
+    pub deactivated_at: Option<DateTime<Utc>>,
+    /// 被平台管理员停用的时间，停用期间只能读取
+    #[serde(default)]
+    pub suspended_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub suspension_reason: Option<String>,
+    /// 用户申请删除账户的时间，宽限期内账户只读
+    #[serde(default)]
+    pub deletion_requested_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Validate)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreateUserProfileRequest {
     #[validate(length(min = 3, max = 30))]
     pub username: String,
@@ -76,7 +101,7 @@ pub struct CreateUserProfileRequest {
     pub facebook_url: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Validate)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UpdateUserProfileRequest {
     #[validate(length(min = 1, max = 50))]
     pub display_name: Option<String>,
@@ -107,16 +132,21 @@ pub struct UpdateUserProfileRequest {
 
     #[validate(url)]
     pub facebook_url: Option<String>,
+
+    pub allow_audience_insights: Option<bool>,
+
+    #[validate(length(min = 2, max = 10))]
+    pub preferred_language: Option<String>,
 }
 
 /// 邮箱更新请求（需要通过Rainbow-Auth验证）
-#[derive(Debug, Serialize, Deserialize, Validate)]
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
 pub struct UpdateEmailRequest {
     #[validate(email(message = "Invalid email format"))]
     pub email: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UserProfileResponse {
     pub id: String,
     pub user_id: String,
@@ -143,10 +173,11 @@ pub struct UserProfileResponse {
     pub total_claps_received: i64,
     pub is_verified: bool,
     pub is_suspended: bool,
+    pub reputation_score: i32,
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UserStats {
     pub total_users: i64,
     pub verified_users: i64,
@@ -154,7 +185,7 @@ pub struct UserStats {
     pub new_users_this_month: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UserActivitySummary {
     pub articles_written: i64,
     pub comments_made: i64,
@@ -194,6 +225,15 @@ impl UserProfile {
             total_claps_received: 0,
             is_verified: false,
             is_suspended: false,
+            allow_audience_insights: false,
+            preferred_language: None,
+            reputation_score: 0,
+            reputation_updated_at: None,
+            last_active_at: None,
+            deactivated_at: None,
+            suspended_at: None,
+            suspension_reason: None,
+            deletion_requested_at: None,
             created_at: now,
             updated_at: now,
         }
@@ -224,6 +264,7 @@ impl UserProfile {
             total_claps_received: self.total_claps_received,
             is_verified: self.is_verified,
             is_suspended: self.is_suspended,
+            reputation_score: self.reputation_score,
             created_at: self.created_at,
         }
     }
@@ -259,6 +300,15 @@ impl From<CreateUserProfileRequest> for UserProfile {
             total_claps_received: 0,
             is_verified: false,
             is_suspended: false,
+            allow_audience_insights: false,
+            preferred_language: None,
+            reputation_score: 0,
+            reputation_updated_at: None,
+            last_active_at: None,
+            deactivated_at: None,
+            suspended_at: None,
+            suspension_reason: None,
+            deletion_requested_at: None,
             created_at: now,
             updated_at: now,
         }
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use utoipa::{IntoParams, ToSchema};
+
+/// 去重后计入的一次文章浏览
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArticleView {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub article_id: String,
+    pub author_id: String,
+    pub publication_id: Option<String>,
+    /// 读者标识：登录用户按用户 ID，匿名读者按 IP + User-Agent 计算的哈希，不保存原始 IP
+    pub viewer_hash: String,
+    pub user_id: Option<String>,
+    /// 来源域名，没有来源时为 direct
+    pub referrer: String,
+    /// 国家代码（ISO 3166-1 alpha-2），来自 CDN 请求头或 GeoIP 数据库
+    pub country: Option<String>,
+    /// 由 User-Agent 推断：desktop、mobile、tablet
+    pub device_type: Option<String>,
+    pub browser: Option<String>,
+    pub os: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ViewStatsQuery {
+    pub days: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
+pub struct ViewStats {
+    pub days: i64,
+    /// 去重后的浏览数
+    pub views: i64,
+    pub unique_viewers: i64,
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use utoipa::ToSchema;
+
+/// 提及来源使用的协议
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MentionProtocol {
+    Webmention,
+    Pingback,
+}
+
+impl MentionProtocol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MentionProtocol::Webmention => "webmention",
+            MentionProtocol::Pingback => "pingback",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MentionStatus {
+    /// 等待抓取来源页面验证
+    Pending,
+    Verified,
+    /// 来源页面不存在或没有链接到文章
+    Rejected,
+}
+
+impl MentionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MentionStatus::Pending => "pending",
+            MentionStatus::Verified => "verified",
+            MentionStatus::Rejected => "rejected",
+        }
+    }
+}
+
+/// 其他站点对文章的提及（Webmention 或 Pingback），验证通过后与评论一起展示
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct Webmention {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    pub id: String,
+    pub article_id: String,
+    pub source: String,
+    pub target: String,
+    pub protocol: MentionProtocol,
+    pub status: MentionStatus,
+    pub title: Option<String>,
+    pub author_name: Option<String>,
+    /// 来源页面中链接附近的文字
+    pub excerpt: Option<String>,
+    pub error: Option<String>,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// W3C Webmention 请求（application/x-www-form-urlencoded）
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct WebmentionRequest {
+    pub source: String,
+    pub target: String,
+}
@@ -1,9 +1,10 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use utoipa::ToSchema;
 
 /// WebSocket连接信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct WebSocketConnection {
     pub id: String,
     pub user_id: String,
@@ -15,7 +16,7 @@ pub struct WebSocketConnection {
 }
 
 /// WebSocket消息类型
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum WebSocketMessageType {
     // 系统消息
@@ -51,10 +52,13 @@ pub enum WebSocketMessageType {
     // 广播消息
     SystemAnnouncement,
     MaintenanceNotice,
+
+    // 在线状态
+    PresenceUpdate,
 }
 
 /// WebSocket消息
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct WebSocketMessage {
     pub id: String,
     pub message_type: WebSocketMessageType,
@@ -67,7 +71,7 @@ pub struct WebSocketMessage {
 }
 
 /// 频道类型
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ChannelType {
     // 用户频道
@@ -84,6 +88,10 @@ pub enum ChannelType {
     
     // 出版物频道
     PublicationUpdates, // publication_updates:{publication_id}
+
+    // 在线阅读频道：订阅文章频道即表示正在阅读
+    ArticlePresence,     // article_presence:{article_id}
+    PublicationPresence, // publication_presence:{publication_id}
     
     // 系统频道
     SystemUpdates,     // system_updates
@@ -101,6 +109,8 @@ impl ChannelType {
             ChannelType::CreatorUpdates => format!("creator_updates:{}", id),
             ChannelType::CreatorRevenue => format!("creator_revenue:{}", id),
             ChannelType::PublicationUpdates => format!("publication_updates:{}", id),
+            ChannelType::ArticlePresence => format!("article_presence:{}", id),
+            ChannelType::PublicationPresence => format!("publication_presence:{}", id),
             ChannelType::SystemUpdates => "system_updates".to_string(),
             ChannelType::GlobalActivity => "global_activity".to_string(),
         }
@@ -108,20 +118,22 @@ impl ChannelType {
 }
 
 /// 订阅请求
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct SubscribeRequest {
     pub channels: Vec<String>,
     pub metadata: Option<HashMap<String, String>>,
+    /// 重新订阅时带上最后收到的事件 ID，补发这些频道在此之后的事件
+    pub last_event_id: Option<String>,
 }
 
 /// 取消订阅请求
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UnsubscribeRequest {
     pub channels: Vec<String>,
 }
 
 /// 发送消息请求
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct SendMessageRequest {
     pub message_type: WebSocketMessageType,
     pub channel: Option<String>,
@@ -131,7 +143,7 @@ pub struct SendMessageRequest {
 }
 
 /// 在线状态
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct OnlineStatus {
     pub user_id: String,
     pub is_online: bool,
@@ -140,7 +152,7 @@ pub struct OnlineStatus {
 }
 
 /// 频道统计
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ChannelStats {
     pub channel: String,
     pub subscriber_count: usize,
@@ -149,7 +161,7 @@ pub struct ChannelStats {
 }
 
 /// WebSocket统计
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct WebSocketStats {
     pub total_connections: usize,
     pub active_users: usize,
@@ -159,7 +171,7 @@ pub struct WebSocketStats {
 }
 
 /// 实时通知配置
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct NotificationConfig {
     pub user_id: String,
     pub email_notifications: bool,
@@ -169,12 +181,50 @@ pub struct NotificationConfig {
     pub quiet_hours_start: Option<String>, // "22:00"
     pub quiet_hours_end: Option<String>,   // "08:00"
     pub timezone: String,
+    #[serde(default)]
+    pub email_digest_window: crate::models::notification::DigestWindow,
+    /// 文章里程碑通知（关闭后仍会记录在主页的里程碑历史中）
+    #[serde(default = "default_milestone_notifications")]
+    pub milestone_notifications: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl NotificationConfig {
+    /// 用户尚未保存配置时使用的默认配置
+    pub fn default_for(user_id: &str) -> Self {
+        Self {
+            user_id: user_id.to_string(),
+            email_notifications: true,
+            push_notifications: true,
+            websocket_notifications: true,
+            notification_types: vec![
+                "new_article".to_string(),
+                "new_comment".to_string(),
+                "new_follower".to_string(),
+                "article_clap".to_string(),
+                "subscription_update".to_string(),
+                "payment_update".to_string(),
+                "publication_analytics".to_string(),
+                "article_milestone".to_string(),
+            ],
+            quiet_hours_start: Some("22:00".to_string()),
+            quiet_hours_end: Some("08:00".to_string()),
+            timezone: "UTC".to_string(),
+            email_digest_window: Default::default(),
+            milestone_notifications: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+fn default_milestone_notifications() -> bool {
+    true
+}
+
 /// 消息队列项
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct MessageQueueItem {
     pub id: String,
     pub message: WebSocketMessage,
@@ -185,8 +235,35 @@ pub struct MessageQueueItem {
     pub processed_at: Option<DateTime<Utc>>,
 }
 
+/// 断线期间错过的实时事件
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MissedEvents {
+    /// 按发生顺序排列，每条事件的 `metadata.event_id` 是它的游标
+    pub events: Vec<WebSocketMessage>,
+    /// 下次同步时使用的游标
+    pub cursor: String,
+    /// 游标已超出缓冲区或服务已重启，部分事件无法补发，客户端应重新加载完整数据
+    pub reset: bool,
+}
+
+/// 正在阅读某篇文章的人数
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ArticlePresence {
+    pub article_id: String,
+    pub reader_count: i64,
+}
+
+/// 一组文章（某位作者或某个出版物）的在线阅读情况
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct PresenceSummary {
+    /// 去重后的读者数，同时阅读多篇文章的读者只算一次
+    pub active_readers: i64,
+    /// 按读者数从多到少排列
+    pub articles: Vec<ArticlePresence>,
+}
+
 /// 连接心跳
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct HeartbeatMessage {
     pub connection_id: String,
     pub timestamp: DateTime<Utc>,
@@ -194,7 +271,7 @@ pub struct HeartbeatMessage {
 }
 
 /// 错误消息
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ErrorMessage {
     pub code: String,
     pub message: String,
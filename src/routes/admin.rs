@@ -0,0 +1,334 @@
+use crate::{
+    error::Result,
+    models::{admin::*, report::*, response::ApiResponse},
+    services::auth::User,
+    state::AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post, put},
+    Extension, Json, Router,
+};
+use std::sync::Arc;
+use tracing::info;
+use validator::Validate;
+use utoipa::OpenApi;
+
+/// 管理后台路由，权限由 `admin_middleware` 按路径统一检查
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/users", get(list_users))
+        .route("/users/:user_id", get(get_user))
+        .route("/users/:user_id/suspend", post(suspend_user))
+        .route("/users/:user_id/unsuspend", post(unsuspend_user))
+        .route("/articles", get(list_articles))
+        .route("/articles/:article_id/takedown", post(take_down_article))
+        .route("/articles/:article_id/restore", post(restore_article))
+        .route("/reports", get(list_report_cases))
+        .route("/reports/:case_id", get(get_report_case))
+        .route("/reports/:case_id/review", post(review_report_case))
+        .route("/stats", get(get_stats))
+        .route("/stats/cache", get(get_cache_stats))
+        .route("/roles", get(list_roles))
+        .route("/roles/:user_id", put(assign_role).delete(revoke_role))
+        .route("/audit", get(get_audit_log))
+}
+
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_users,
+    get_user,
+    suspend_user,
+    unsuspend_user,
+    list_articles,
+    take_down_article,
+    restore_article,
+    list_report_cases,
+    get_report_case,
+    review_report_case,
+    get_stats,
+    get_cache_stats,
+    list_roles,
+    assign_role,
+    revoke_role,
+    get_audit_log,
+))]
+pub struct ApiDoc;
+
+/// 用户列表，可按用户名搜索或只看已停用的账户
+/// GET /api/blog/admin/users
+#[utoipa::path(
+    get,
+    path = "/api/blog/admin/users",
+    tag = "admin",
+    params(AdminUserQuery),
+    security(("bearer_auth" = []))
+)]
+async fn list_users(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AdminUserQuery>,
+) -> Result<ApiResponse> {
+    let result = state.admin_service.list_users(query).await?;
+    Ok(ApiResponse::ok(&result.data).with_pagination(&result))
+}
+
+/// GET /api/blog/admin/users/:user_id
+#[utoipa::path(
+    get,
+    path = "/api/blog/admin/users/{user_id}",
+    tag = "admin",
+    params(("user_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_user(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+) -> Result<ApiResponse> {
+    let user = state.admin_service.get_user(&user_id).await?;
+    Ok(ApiResponse::ok(user))
+}
+
+/// 停用账户，停用后只能读取，写操作返回 ACCOUNT_SUSPENDED
+/// POST /api/blog/admin/users/:user_id/suspend
+#[utoipa::path(
+    post,
+    path = "/api/blog/admin/users/{user_id}/suspend",
+    tag = "admin",
+    params(("user_id" = String, Path)),
+    request_body = ModerationReasonRequest,
+    security(("bearer_auth" = []))
+)]
+async fn suspend_user(
+    State(state): State<Arc<AppState>>,
+    Extension(admin): Extension<User>,
+    Path(user_id): Path<String>,
+    Json(request): Json<ModerationReasonRequest>,
+) -> Result<ApiResponse> {
+    request.validate()?;
+
+    info!("Admin {} suspending user {}", admin.id, user_id);
+    let user = state.admin_service.suspend_user(&admin.id, &user_id, request).await?;
+    Ok(ApiResponse::ok(user).with_message("账户已停用"))
+}
+
+/// POST /api/blog/admin/users/:user_id/unsuspend
+#[utoipa::path(
+    post,
+    path = "/api/blog/admin/users/{user_id}/unsuspend",
+    tag = "admin",
+    params(("user_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn unsuspend_user(
+    State(state): State<Arc<AppState>>,
+    Extension(admin): Extension<User>,
+    Path(user_id): Path<String>,
+) -> Result<ApiResponse> {
+    info!("Admin {} unsuspending user {}", admin.id, user_id);
+    let user = state.admin_service.unsuspend_user(&admin.id, &user_id).await?;
+    Ok(ApiResponse::ok(user).with_message("账户已恢复"))
+}
+
+/// 文章列表，包括草稿和已下架的文章
+/// GET /api/blog/admin/articles
+#[utoipa::path(
+    get,
+    path = "/api/blog/admin/articles",
+    tag = "admin",
+    params(AdminArticleQuery),
+    security(("bearer_auth" = []))
+)]
+async fn list_articles(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AdminArticleQuery>,
+) -> Result<ApiResponse> {
+    let result = state.admin_service.list_articles(query).await?;
+    Ok(ApiResponse::ok(&result.data).with_pagination(&result))
+}
+
+/// 下架文章，作者在恢复之前不能重新发布
+/// POST /api/blog/admin/articles/:article_id/takedown
+#[utoipa::path(
+    post,
+    path = "/api/blog/admin/articles/{article_id}/takedown",
+    tag = "admin",
+    params(("article_id" = String, Path)),
+    request_body = ModerationReasonRequest,
+    security(("bearer_auth" = []))
+)]
+async fn take_down_article(
+    State(state): State<Arc<AppState>>,
+    Extension(admin): Extension<User>,
+    Path(article_id): Path<String>,
+    Json(request): Json<ModerationReasonRequest>,
+) -> Result<ApiResponse> {
+    request.validate()?;
+
+    info!("Admin {} taking down article {}", admin.id, article_id);
+    let article = state.admin_service.take_down_article(&admin.id, &article_id, request).await?;
+    Ok(ApiResponse::ok(article).with_message("文章已下架"))
+}
+
+/// POST /api/blog/admin/articles/:article_id/restore
+#[utoipa::path(
+    post,
+    path = "/api/blog/admin/articles/{article_id}/restore",
+    tag = "admin",
+    params(("article_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn restore_article(
+    State(state): State<Arc<AppState>>,
+    Extension(admin): Extension<User>,
+    Path(article_id): Path<String>,
+) -> Result<ApiResponse> {
+    info!("Admin {} restoring article {}", admin.id, article_id);
+    let article = state.admin_service.restore_article(&admin.id, &article_id).await?;
+    Ok(ApiResponse::ok(article).with_message("文章已恢复"))
+}
+
+/// 举报案件队列，同一对象的举报汇总为一个案件
+/// GET /api/blog/admin/reports
+#[utoipa::path(
+    get,
+    path = "/api/blog/admin/reports",
+    tag = "admin",
+    params(ReportQuery),
+    security(("bearer_auth" = []))
+)]
+async fn list_report_cases(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ReportQuery>,
+) -> Result<ApiResponse> {
+    let result = state.report_service.list_cases(query).await?;
+    Ok(ApiResponse::ok(&result.data).with_pagination(&result))
+}
+
+/// GET /api/blog/admin/reports/:case_id
+#[utoipa::path(
+    get,
+    path = "/api/blog/admin/reports/{case_id}",
+    tag = "admin",
+    params(("case_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_report_case(
+    State(state): State<Arc<AppState>>,
+    Path(case_id): Path<String>,
+) -> Result<ApiResponse> {
+    let case = state.report_service.get_case(&case_id).await?;
+    Ok(ApiResponse::ok(case))
+}
+
+/// 处理举报案件，标记为 resolved 或 dismissed；驳回时恢复自动隐藏的内容
+/// POST /api/blog/admin/reports/:case_id/review
+#[utoipa::path(
+    post,
+    path = "/api/blog/admin/reports/{case_id}/review",
+    tag = "admin",
+    params(("case_id" = String, Path)),
+    request_body = ReviewReportRequest,
+    security(("bearer_auth" = []))
+)]
+async fn review_report_case(
+    State(state): State<Arc<AppState>>,
+    Extension(admin): Extension<User>,
+    Path(case_id): Path<String>,
+    Json(request): Json<ReviewReportRequest>,
+) -> Result<ApiResponse> {
+    request.validate()?;
+
+    let case = state.report_service.review_case(&admin.id, &case_id, request).await?;
+    Ok(ApiResponse::ok(case))
+}
+
+/// GET /api/blog/admin/stats
+#[utoipa::path(
+    get,
+    path = "/api/blog/admin/stats",
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+async fn get_stats(State(state): State<Arc<AppState>>) -> Result<ApiResponse> {
+    let stats = state.admin_service.platform_stats().await?;
+    Ok(ApiResponse::ok(stats))
+}
+
+/// 文章详情、出版物主页和标签页响应缓存的命中率，按命名空间统计，进程重启后清零
+/// GET /api/blog/admin/stats/cache
+#[utoipa::path(
+    get,
+    path = "/api/blog/admin/stats/cache",
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+async fn get_cache_stats(State(state): State<Arc<AppState>>) -> Result<ApiResponse> {
+    Ok(ApiResponse::ok(state.response_cache_service.stats()))
+}
+
+/// 平台角色列表，不包括 PLATFORM_ADMIN_IDS 配置的管理员
+/// GET /api/blog/admin/roles
+#[utoipa::path(
+    get,
+    path = "/api/blog/admin/roles",
+    tag = "admin",
+    security(("bearer_auth" = []))
+)]
+async fn list_roles(State(state): State<Arc<AppState>>) -> Result<ApiResponse> {
+    let roles = state.admin_service.list_roles().await?;
+    Ok(ApiResponse::ok(roles))
+}
+
+/// PUT /api/blog/admin/roles/:user_id
+#[utoipa::path(
+    put,
+    path = "/api/blog/admin/roles/{user_id}",
+    tag = "admin",
+    params(("user_id" = String, Path)),
+    request_body = AssignRoleRequest,
+    security(("bearer_auth" = []))
+)]
+async fn assign_role(
+    State(state): State<Arc<AppState>>,
+    Extension(admin): Extension<User>,
+    Path(user_id): Path<String>,
+    Json(request): Json<AssignRoleRequest>,
+) -> Result<ApiResponse> {
+    let assignment = state.admin_service.assign_role(&admin.id, &user_id, request.role).await?;
+    Ok(ApiResponse::ok(assignment))
+}
+
+/// DELETE /api/blog/admin/roles/:user_id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/admin/roles/{user_id}",
+    tag = "admin",
+    params(("user_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn revoke_role(
+    State(state): State<Arc<AppState>>,
+    Extension(admin): Extension<User>,
+    Path(user_id): Path<String>,
+) -> Result<ApiResponse> {
+    state.admin_service.revoke_role(&admin.id, &user_id).await?;
+    Ok(ApiResponse::message("角色已撤销"))
+}
+
+/// 管理操作审计记录
+/// GET /api/blog/admin/audit
+#[utoipa::path(
+    get,
+    path = "/api/blog/admin/audit",
+    tag = "admin",
+    params(AdminAuditQuery),
+    security(("bearer_auth" = []))
+)]
+async fn get_audit_log(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AdminAuditQuery>,
+) -> Result<ApiResponse> {
+    let result = state.admin_service.audit_log(query).await?;
+    Ok(ApiResponse::ok(&result.data).with_pagination(&result))
+}
@@ -1,6 +1,6 @@
 use crate::{
     error::Result,
-    models::analytics::*,
+    models::{analytics::*, attachment::AttachmentAnalyticsQuery, bot::BotTrafficQuery, cta::CtaAnalyticsQuery, response::ApiResponse},
     state::AppState,
     services::auth::User,
 };
@@ -10,9 +10,10 @@ use axum::{
     routing::{get, post},
     Extension, Router,
 };
-use serde_json::{json, Value};
+use serde_json::json;
 use std::sync::Arc;
 use tracing::debug;
+use utoipa::{IntoParams, OpenApi};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -23,16 +24,43 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/tags", get(get_tag_analytics))
         .route("/trends", get(get_trends))
         .route("/realtime", get(get_realtime))
+        .route("/bot-traffic", get(get_bot_traffic))
+        .route("/attachments", get(get_attachment_downloads))
+        .route("/ctas", get(get_cta_performance))
         .route("/export", post(export_data))
 }
 
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_dashboard,
+    get_overview,
+    get_article_analytics,
+    get_audience,
+    get_tag_analytics,
+    get_trends,
+    get_realtime,
+    get_bot_traffic,
+    get_attachment_downloads,
+    get_cta_performance,
+    export_data,
+))]
+pub struct ApiDoc;
+
 /// 获取完整的分析仪表板
 /// GET /api/stats/dashboard
+#[utoipa::path(
+    get,
+    path = "/api/blog/analytics/dashboard",
+    tag = "analytics",
+    params(AnalyticsQuery),
+    security(("bearer_auth" = []))
+)]
 async fn get_dashboard(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Query(query): Query<AnalyticsQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting analytics dashboard for user: {}", user.id);
 
     let dashboard = state
@@ -40,18 +68,21 @@ async fn get_dashboard(
         .get_user_dashboard(&user.id, query)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": dashboard
-    })))
+    Ok(ApiResponse::ok(dashboard))
 }
 
 /// 获取用户统计概览
 /// GET /api/stats/overview
+#[utoipa::path(
+    get,
+    path = "/api/blog/analytics/overview",
+    tag = "analytics",
+    security(("bearer_auth" = []))
+)]
 async fn get_overview(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting analytics overview for user: {}", user.id);
 
     let overview = state
@@ -59,19 +90,23 @@ async fn get_overview(
         .get_user_overview(&user.id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": overview
-    })))
+    Ok(ApiResponse::ok(overview))
 }
 
 /// 获取文章分析数据
 /// GET /api/stats/articles?limit=10
+#[utoipa::path(
+    get,
+    path = "/api/blog/analytics/articles",
+    tag = "analytics",
+    params(ArticleAnalyticsQuery),
+    security(("bearer_auth" = []))
+)]
 async fn get_article_analytics(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Query(params): Query<ArticleAnalyticsQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting article analytics for user: {}", user.id);
 
     let limit = params.limit.unwrap_or(10);
@@ -80,19 +115,23 @@ async fn get_article_analytics(
         .get_recent_article_analytics(&user.id, limit)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": articles
-    })))
+    Ok(ApiResponse::ok(articles))
 }
 
 /// 获取受众分析
 /// GET /api/stats/audience
+#[utoipa::path(
+    get,
+    path = "/api/blog/analytics/audience",
+    tag = "analytics",
+    params(AnalyticsQuery),
+    security(("bearer_auth" = []))
+)]
 async fn get_audience(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Query(query): Query<AnalyticsQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting audience analytics for user: {}", user.id);
 
     let end_date = query.end_date.unwrap_or_else(chrono::Utc::now);
@@ -103,19 +142,23 @@ async fn get_audience(
         .get_audience_analytics(&user.id, &start_date, &end_date)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": audience
-    })))
+    Ok(ApiResponse::ok(audience))
 }
 
 /// 获取标签分析
 /// GET /api/stats/tags?limit=10
+#[utoipa::path(
+    get,
+    path = "/api/blog/analytics/tags",
+    tag = "analytics",
+    params(TagAnalyticsQuery),
+    security(("bearer_auth" = []))
+)]
 async fn get_tag_analytics(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Query(params): Query<TagAnalyticsQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting tag analytics for user: {}", user.id);
 
     let limit = params.limit.unwrap_or(10);
@@ -124,19 +167,23 @@ async fn get_tag_analytics(
         .get_top_tags_analytics(&user.id, limit)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": tags
-    })))
+    Ok(ApiResponse::ok(tags))
 }
 
 /// 获取趋势分析
 /// GET /api/stats/trends
+#[utoipa::path(
+    get,
+    path = "/api/blog/analytics/trends",
+    tag = "analytics",
+    params(AnalyticsQuery),
+    security(("bearer_auth" = []))
+)]
 async fn get_trends(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Query(query): Query<AnalyticsQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting trend analytics for user: {}", user.id);
 
     let end_date = query.end_date.unwrap_or_else(chrono::Utc::now);
@@ -147,18 +194,21 @@ async fn get_trends(
         .get_trend_analytics(&user.id, &start_date, &end_date)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": trends
-    })))
+    Ok(ApiResponse::ok(trends))
 }
 
 /// 获取实时分析
 /// GET /api/stats/realtime
+#[utoipa::path(
+    get,
+    path = "/api/blog/analytics/realtime",
+    tag = "analytics",
+    security(("bearer_auth" = []))
+)]
 async fn get_realtime(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting realtime analytics for user: {}", user.id);
 
     let realtime = state
@@ -166,20 +216,72 @@ async fn get_realtime(
         .get_realtime_analytics(&user.id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": realtime
-    })))
+    Ok(ApiResponse::ok(realtime))
+}
+
+/// 获取机器流量报告：被排除在浏览数之外的爬虫访问
+/// GET /api/stats/bot-traffic?days=30
+#[utoipa::path(
+    get,
+    path = "/api/blog/analytics/bot-traffic",
+    tag = "analytics",
+    params(BotTrafficQuery),
+    security(("bearer_auth" = []))
+)]
+async fn get_bot_traffic(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Query(query): Query<BotTrafficQuery>,
+) -> Result<ApiResponse> {
+    debug!("Getting bot traffic report for user: {}", user.id);
+
+    let report = state
+        .bot_detection_service
+        .get_bot_report(&user.id, query)
+        .await?;
+
+    Ok(ApiResponse::ok(report))
+}
+
+/// 作者文章附件的下载次数和留下的邮箱数
+/// GET /api/stats/attachments?days=30
+#[utoipa::path(
+    get,
+    path = "/api/blog/analytics/attachments",
+    tag = "analytics",
+    params(AttachmentAnalyticsQuery),
+    security(("bearer_auth" = []))
+)]
+async fn get_attachment_downloads(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Query(query): Query<AttachmentAnalyticsQuery>,
+) -> Result<ApiResponse> {
+    debug!("Getting attachment downloads for user: {}", user.id);
+
+    let analytics = state
+        .attachment_service
+        .get_analytics(&user.id, query)
+        .await?;
+
+    Ok(ApiResponse::ok(analytics))
 }
 
 /// 导出分析数据
 /// POST /api/stats/export
 /// Body: ExportOptions
+#[utoipa::path(
+    post,
+    path = "/api/blog/analytics/export",
+    tag = "analytics",
+    request_body = ExportOptions,
+    security(("bearer_auth" = []))
+)]
 async fn export_data(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Json(options): Json<ExportOptions>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Exporting analytics data for user: {} with format: {:?}", user.id, options.format);
 
     let data = state
@@ -190,23 +292,45 @@ async fn export_data(
     // 对于JSON和CSV，我们返回base64编码的数据
     let base64_data = base64::encode(&data);
 
-    Ok(Json(json!({
-        "success": true,
-        "data": {
-            "content": base64_data,
-            "size": data.len()
-        },
-        "message": "Export completed successfully"
-    })))
+    Ok(ApiResponse::ok(json!({
+        "content": base64_data,
+        "size": data.len()
+    })).with_message("Export completed successfully"))
 }
 
 // Query parameter structs
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct ArticleAnalyticsQuery {
     limit: Option<i32>,
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct TagAnalyticsQuery {
     limit: Option<i32>,
-}
\ No newline at end of file
+}
+
+/// 作者每个 CTA 的点击、转化和转化率，按文章拆分
+/// GET /api/stats/ctas?days=30
+#[utoipa::path(
+    get,
+    path = "/api/blog/analytics/ctas",
+    tag = "analytics",
+    params(CtaAnalyticsQuery),
+    security(("bearer_auth" = []))
+)]
+async fn get_cta_performance(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Query(query): Query<CtaAnalyticsQuery>,
+) -> Result<ApiResponse> {
+    debug!("Getting CTA performance for user: {}", user.id);
+
+    let analytics = state
+        .cta_service
+        .get_analytics(&user.id, query)
+        .await?;
+
+    Ok(ApiResponse::ok(analytics))
+}
@@ -1,161 +1,471 @@
 use crate::{
     error::{AppError, Result},
-    models::article::*,
-    services::auth::User,
+    models::{article::*, analytics::CommentAnalyticsQuery, attachment::*, collaborator::*, id::{ArticleId, UserId}, experiment::*, import::ImportFormat, preview::CreatePreviewLinkRequest, pseudonym::SetArticlePseudonymRequest, reaction::*, reading_progress::UpdateReadingProgressRequest, revision::*, style_guide::LintArticleRequest, template::CreateArticleQuery, view_tracking::ViewStatsQuery, response::ApiResponse},
+    services::{auth::User, meter::meter_set_cookie},
     state::AppState,
+    utils::{http_cache, markdown::MarkdownProcessor, middleware::VisitorGeo},
     require_permission,
 };
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::Json,
+    extract::{DefaultBodyLimit, Multipart, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
     routing::{get, post, put, delete},
     Router,
     Extension,
 };
-use serde_json::{json, Value};
+use futures::stream::{self, Stream, StreamExt};
+use serde_json::json;
+use std::convert::Infallible;
 use std::sync::Arc;
-use tracing::{info, debug, error};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, debug, error, warn};
+use utoipa::OpenApi;
+
+/// Medium 导出包可能包含多年的文章
+const ARTICLE_IMPORT_MAX_BYTES: usize = 100 * 1024 * 1024;
+
+/// 附件上传请求体的上限；附件本身的大小限制（MAX_ATTACHMENT_SIZE）由 MediaService 检查
+const ATTACHMENT_UPLOAD_MAX_BYTES: usize = 200 * 1024 * 1024;
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         // 公开路由（不需要认证）
-        .route("/", get(list_articles))
+        .route("/", get(list_articles).post(create_article))
         .route("/trending", get(get_trending_articles))
         .route("/popular", get(get_popular_articles))
+        .route("/popular/stream", get(stream_popular_articles))
+        .route("/view-token", get(get_view_token))
+        .route("/highlight.css", get(get_highlight_css))
+        .route("/preview/:token", get(get_article_preview))
         
         // 需要认证的路由
         .route("/create", post(create_article))
+        .route("/pending-review", get(get_pending_review_articles))
+        .route("/collaborations/invitations", get(list_collaboration_invitations))
+        .route("/bulk", post(bulk_update_articles))
+        .route(
+            "/import",
+            post(import_articles).layer(DefaultBodyLimit::max(ARTICLE_IMPORT_MAX_BYTES)),
+        )
         
         // 文章操作路由 - 使用 /by-id/ 前缀来避免与 slug 冲突
         .route("/by-id/:id", put(update_article).delete(delete_article))
         .route("/by-id/:id/publish", post(publish_article))
         .route("/by-id/:id/unpublish", post(unpublish_article))
+        .route("/by-id/:id/approve", post(approve_article))
+        .route("/by-id/:id/reject", post(reject_article))
+        .route("/by-id/:id/pin", put(pin_article))
+        .route("/by-id/:id/featured", put(feature_article))
         .route("/by-id/:id/view", post(increment_view_count))
         .route("/by-id/:id/clap", post(clap_article))
+        .route("/by-id/:id/og-image", get(get_og_image))
+        .route("/by-id/:id/comments/analytics", get(get_comment_analytics))
+        .route("/by-id/:id/views", get(get_view_stats))
+        .route("/by-id/:id/pseudonym", put(set_article_pseudonym))
+        .route("/by-id/:id/autosave", post(autosave_article))
+        .route("/by-id/:id/revisions", get(list_revisions))
+        .route("/by-id/:id/revisions/diff", get(diff_revisions))
+        .route("/by-id/:id/revisions/:revision", get(get_revision))
+        .route("/by-id/:id/revisions/:revision/restore", post(restore_revision))
+        .route("/by-id/:id/collaborators", get(list_collaborators).post(invite_collaborator))
+        .route("/by-id/:id/collaborators/accept", post(accept_collaboration))
+        .route("/by-id/:id/collaborators/:user_id", put(update_collaborator).delete(remove_collaborator))
+        .route("/by-id/:id/preview-links", get(list_preview_links).post(create_preview_link))
+        .route("/by-id/:id/preview-links/:link_id", delete(revoke_preview_link))
+        .route("/by-id/:id/slug-redirects", get(list_slug_redirects))
+        .route("/by-id/:id/slug-redirects/:slug", delete(delete_slug_redirect))
+        .route("/by-id/:id/template-check", get(check_article_template))
+        .route("/by-id/:id/lint", post(lint_article))
+        .route(
+            "/by-id/:id/attachments",
+            get(list_article_attachments)
+                .post(upload_attachment)
+                .layer(DefaultBodyLimit::max(ATTACHMENT_UPLOAD_MAX_BYTES)),
+        )
+        .route("/by-id/:id/attachments/leads", get(list_attachment_leads))
+        .route("/by-id/:id/attachments/:attachment_id", put(update_attachment).delete(delete_attachment))
         
         // slug 路由放在最后，作为 catch-all
         .route("/:slug", get(get_article_by_slug))
+        // 反应接口接受文章 ID 或 slug（路由参数名需要与上面的 slug 路由一致）
+        .route("/:slug/reactions", get(get_reactions).post(add_reaction))
+        .route("/:slug/reactions/:reaction_type", delete(remove_reaction))
+        .route("/:slug/progress", get(get_reading_progress).put(update_reading_progress).delete(clear_reading_progress))
+        .route("/:slug/attachments", get(get_attachments))
+        .route("/:slug/experiments", get(list_experiments).post(create_experiment))
+        .route("/:slug/experiments/:experiment_id", get(get_experiment_results))
+        .route("/:slug/experiments/:experiment_id/promote", post(promote_experiment_variant))
+        .route("/:slug/experiments/:experiment_id/stop", post(stop_experiment))
+        .route("/:slug/attachments/:attachment_id/access", post(request_attachment_download))
 }
 
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_articles,
+    create_article,
+    get_trending_articles,
+    get_popular_articles,
+    stream_popular_articles,
+    get_view_token,
+    get_highlight_css,
+    get_pending_review_articles,
+    list_collaboration_invitations,
+    import_articles,
+    update_article,
+    delete_article,
+    bulk_update_articles,
+    publish_article,
+    unpublish_article,
+    approve_article,
+    reject_article,
+    pin_article,
+    feature_article,
+    increment_view_count,
+    clap_article,
+    get_og_image,
+    get_comment_analytics,
+    get_view_stats,
+    set_article_pseudonym,
+    autosave_article,
+    list_revisions,
+    diff_revisions,
+    get_revision,
+    restore_revision,
+    list_collaborators,
+    invite_collaborator,
+    accept_collaboration,
+    update_collaborator,
+    remove_collaborator,
+    list_preview_links,
+    create_preview_link,
+    revoke_preview_link,
+    get_article_preview,
+    list_slug_redirects,
+    delete_slug_redirect,
+    check_article_template,
+    lint_article,
+    list_article_attachments,
+    upload_attachment,
+    list_attachment_leads,
+    update_attachment,
+    delete_attachment,
+    get_article_by_slug,
+    get_reactions,
+    add_reaction,
+    remove_reaction,
+    get_reading_progress,
+    update_reading_progress,
+    clear_reading_progress,
+    get_attachments,
+    list_experiments,
+    create_experiment,
+    get_experiment_results,
+    promote_experiment_variant,
+    stop_experiment,
+    request_attachment_download,
+))]
+pub struct ApiDoc;
+
 /// 获取文章列表
 /// GET /api/articles
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles",
+    tag = "articles",
+    params(ArticleQuery),
+    security((), ("bearer_auth" = []))
+)]
 pub async fn list_articles(
     State(app_state): State<Arc<AppState>>,
     Query(query): Query<ArticleQuery>,
     user: Option<Extension<User>>,
-) -> Result<Json<Value>> {
+    headers: HeaderMap,
+) -> Result<ApiResponse> {
     debug!("Fetching articles list with query: {:?}", query);
 
-    let result = app_state.article_service.get_articles(query).await?;
+    let mut result = app_state.articles()?.get_articles(query).await?;
 
     // 如果用户已登录，可以添加额外信息（如是否收藏等）
     let user_id = user.as_ref().map(|u| &u.0.id);
 
-    Ok(Json(json!({
-        "success": true,
-        "data": {
-            "articles": result.data,
-            "pagination": {
-                "current_page": result.page,
-                "total_pages": result.total_pages,
-                "total_items": result.total,
-                "items_per_page": result.per_page,
-                "has_next": result.page < result.total_pages,
-                "has_prev": result.page > 1,
-            }
-        }
-    })))
+    // 正在做标题/封面实验的文章按读者分组展示
+    app_state.experiment_service
+        .apply_variants(result.data.iter_mut(), &headers, user_id.map(String::as_str))
+        .await;
+
+    Ok(ApiResponse::ok(json!({
+        "articles": result.data
+    })).with_pagination(&result))
 }
 
 /// 获取热门文章
 /// GET /api/articles/trending
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/trending",
+    tag = "articles",
+    params(ArticleQuery)
+)]
 pub async fn get_trending_articles(
     State(app_state): State<Arc<AppState>>,
     Query(query): Query<ArticleQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Fetching trending articles");
 
     let mut trending_query = query;
     trending_query.sort = Some("trending".to_string());
     trending_query.limit = trending_query.limit.or(Some(10));
 
-    let result = app_state.article_service.get_articles(trending_query).await?;
+    let result = app_state.articles()?.get_articles(trending_query).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": result.data
-    })))
+    Ok(ApiResponse::ok(result.data))
 }
 
 /// 获取热门文章
 /// GET /api/articles/popular
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/popular",
+    tag = "articles",
+    params(ArticleQuery)
+)]
 pub async fn get_popular_articles(
     State(app_state): State<Arc<AppState>>,
     Query(query): Query<ArticleQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Fetching popular articles");
 
     let mut popular_query = query;
     popular_query.sort = Some("popular".to_string());
     popular_query.limit = popular_query.limit.or(Some(10));
 
-    let result = app_state.article_service.get_articles(popular_query).await?;
+    let result = app_state.articles()?.get_articles(popular_query).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": result.data
-    })))
+    Ok(ApiResponse::ok(result.data))
 }
 
 /// 根据 slug 获取文章详情
 /// GET /api/articles/:slug
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/{slug}",
+    tag = "articles",
+    params(("slug" = String, Path)),
+    security((), ("bearer_auth" = []))
+)]
 pub async fn get_article_by_slug(
     State(app_state): State<Arc<AppState>>,
     Path(slug): Path<String>,
     user: Option<Extension<User>>,
-) -> Result<Json<Value>> {
+    geo: Option<Extension<VisitorGeo>>,
+    headers: HeaderMap,
+) -> Result<Response> {
     debug!("Fetching article by slug: {}", slug);
 
     // 获取当前用户ID（如果已登录）
     let user_id = user.as_ref().map(|u| u.0.id.as_str());
+    let meter = app_state.meter_service.reader(&headers, user_id);
 
-    // 获取文章完整信息
-    let article_response = app_state.article_service
-        .get_article_with_details(&slug, user_id)
+    // 获取文章完整信息；找不到时检查是否为改名前的旧 slug
+    let mut article_response = match app_state.article_service
+        .get_article_with_details(&slug, user_id, Some(&meter))
         .await?
-        .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
+    {
+        Some(article) => article,
+        None => {
+            let article = app_state.article_service.find_slug_redirect(&slug).await?
+                .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
+            let location = format!("/api/blog/articles/{}", article.slug);
+            return Ok((StatusCode::MOVED_PERMANENTLY, [(header::LOCATION, location)]).into_response());
+        }
+    };
 
     // 检查文章可见性
     if !article_response.status.can_be_viewed_by_public() {
         // 只有作者本人可以查看未发布的文章
-        if user_id != Some(&article_response.author.id) {
+        if user_id != Some(&article_response.author_id) {
             return Err(AppError::NotFound("Article not found".to_string()));
         }
     }
 
-    // 异步增加浏览次数（不阻塞响应）
-    let article_service = app_state.article_service.clone();
-    let article_id = article_response.id.clone();
-    tokio::spawn(async move {
-        if let Err(e) = article_service.increment_view_count(&article_id).await {
-            tracing::warn!("Failed to increment view count for article {}: {}", article_id, e);
+    // 出版物文章应用该出版物的自定义内容转换
+    if let Some(publication) = &article_response.publication {
+        article_response.content_html = app_state.content_transform_service
+            .apply(&publication.id, article_response.content_html)
+            .await;
+    }
+
+    // 展开正文中的 {{cta key}} 短代码
+    article_response.content_html = app_state.cta_service
+        .apply(&article_response.author_id, &article_response.id, article_response.content_html)
+        .await;
+
+    // 异步增加浏览次数（不阻塞响应）；开启 JS 验证时由前端调用 POST /view 计数
+    let bots = &app_state.bot_detection_service;
+    if !bots.challenge_required() && article_response.status.can_be_viewed_by_public() {
+        if let Some(verdict) = bots.detect(&headers) {
+            bots.record_bot_view_async(
+                article_response.id.clone(),
+                article_response.author_id.clone(),
+                article_response.publication.as_ref().map(|p| p.id.clone()),
+                verdict,
+            );
+        } else {
+            let article_service = app_state.article_service.clone();
+            let view_tracking_service = app_state.view_tracking_service.clone();
+            let experiment_service = app_state.experiment_service.clone();
+            let article_id = article_response.id.clone();
+            let author_id = article_response.author_id.clone();
+            let publication_id = article_response.publication.as_ref().map(|p| p.id.clone());
+            let user_id = user_id.map(str::to_string);
+            let country = geo.and_then(|g| g.0.country);
+            tokio::spawn(async move {
+                // 去重窗口内的重复浏览不计数
+                let counted = view_tracking_service
+                    .record_view(&article_id, &author_id, publication_id.as_deref(), &headers, user_id.as_deref(), country.as_deref())
+                    .await;
+                let result = match counted {
+                    Ok(true) => article_service.increment_view_count(&article_id).await,
+                    Ok(false) => Ok(()),
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    tracing::warn!("Failed to increment view count for article {}: {}", article_id, e);
+                }
+                if let Err(e) = experiment_service.record_click(&article_id, &headers, user_id.as_deref()).await {
+                    tracing::warn!("Failed to record experiment click for article {}: {}", article_id, e);
+                }
+            });
+        }
+    }
+
+    // 匿名读者第一次打开付费文章时写入计量 cookie
+    let meter_cookie = article_response.paywall.as_ref()
+        .and_then(|p| p.meter.as_ref())
+        .and(meter.new_cookie)
+        .map(|value| [(header::SET_COOKIE, meter_set_cookie(&value))]);
+    // 付费文章的正文按读者截断，不能进入共享缓存
+    let cache_control = article_response.paywall.is_some()
+        .then(|| [(header::CACHE_CONTROL, http_cache::PRIVATE_CACHE_CONTROL)]);
+    let last_modified = [(header::LAST_MODIFIED, http_cache::last_modified(article_response.updated_at))];
+
+    Ok((meter_cookie, cache_control, last_modified, ApiResponse::ok(article_response)).into_response())
+}
+
+/// 实时热度榜（Server-Sent Events），连接后先推送当前榜单，之后榜单变化时推送
+/// GET /api/blog/articles/popular/stream
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/popular/stream",
+    tag = "articles"
+)]
+pub async fn stream_popular_articles(
+    State(app_state): State<Arc<AppState>>,
+) -> Sse<impl Stream<Item = std::result::Result<Event, Infallible>>> {
+    let (current, receiver) = app_state.popularity_service.subscribe();
+
+    let updates = stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(snapshot) => return Some((snapshot, receiver)),
+                // 消费过慢时跳过旧快照，只推送最新的
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return None,
+            }
         }
     });
 
-    Ok(Json(json!({
-        "success": true,
-        "data": article_response
-    })))
+    let events = stream::iter(current).chain(updates).map(|snapshot| {
+        Ok(Event::default()
+            .event("popular")
+            .json_data(&*snapshot)
+            .unwrap_or_else(|_| Event::default().comment("serialization failed")))
+    });
+
+    Sse::new(events).keep_alive(KeepAlive::default())
 }
 
-/// 创建新文章
-/// POST /api/articles/create
+/// 从 Medium 导出包（zip）或 WordPress WXR 文件导入文章
+/// POST /api/blog/articles/import
+/// multipart 字段：file（必填）、format（medium / wordpress，默认按文件内容判断）、as_drafts
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/import",
+    tag = "articles",
+    security(("bearer_auth" = []))
+)]
+pub async fn import_articles(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    mut multipart: Multipart,
+) -> Result<ApiResponse> {
+    if !user.is_verified {
+        return Err(AppError::Authorization("导入文章需要验证邮箱，请前往 Rainbow-Auth 完成邮箱验证".to_string()));
+    }
+    require_permission!(app_state.auth_service, user, "article.create");
+
+    let mut file_data: Option<Vec<u8>> = None;
+    let mut format: Option<ImportFormat> = None;
+    let mut as_drafts = false;
+
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        error!("Failed to process multipart field: {}", e);
+        AppError::BadRequest("无法处理上传的文件".to_string())
+    })? {
+        match field.name().unwrap_or("") {
+            "file" => {
+                let data = field.bytes().await.map_err(|e| {
+                    error!("Failed to read import file: {}", e);
+                    AppError::BadRequest("无法读取文件数据".to_string())
+                })?;
+                file_data = Some(data.to_vec());
+            }
+            "format" => {
+                let value = field.text().await.unwrap_or_default();
+                format = Some(serde_json::from_value(json!(value.trim().to_lowercase()))
+                    .map_err(|_| AppError::BadRequest("format must be medium or wordpress".to_string()))?);
+            }
+            "as_drafts" => {
+                as_drafts = field.text().await.map(|v| v.trim() == "true").unwrap_or(false);
+            }
+            _ => {}
+        }
+    }
+
+    let file_data = file_data.ok_or_else(|| AppError::BadRequest("未找到上传的文件".to_string()))?;
+    let format = format.unwrap_or_else(|| ImportFormat::detect(&file_data));
+
+    // 信誉不足的作者导入为草稿，之后逐篇提交审核
+    let as_drafts = as_drafts || !app_state.reputation_service.can_publish_immediately(&user.id).await?;
+
+    let report = app_state.import_service
+        .import(&user.id, format, &file_data, as_drafts)
+        .await?;
+
+    Ok(ApiResponse::ok(report))
+}
+
+/// 创建新文章，可以用 `?template=` 选择出版物的文章模板
+/// POST /api/articles 或 POST /api/articles/create
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles",
+    tag = "articles",
+    params(CreateArticleQuery),
+    request_body = CreateArticleRequest,
+    security(("bearer_auth" = []))
+)]
 pub async fn create_article(
     State(app_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-    Json(request): Json<CreateArticleRequest>,
-) -> Result<Json<Value>> {
+    Query(query): Query<CreateArticleQuery>,
+    Json(mut request): Json<CreateArticleRequest>,
+) -> Result<ApiResponse> {
     debug!("Creating article for user: {}", user.id);
 
     // 检查邮箱验证状态
@@ -166,50 +476,617 @@ pub async fn create_article(
     // 检查权限
     require_permission!(app_state.auth_service, user, "article.create");
 
+    // 定时发布不经过审核，只对可以直接发布的作者开放
+    if request.publish_at.is_some() && !app_state.reputation_service.can_publish_immediately(&user.id).await? {
+        return Err(AppError::Authorization("Scheduled publishing requires a higher reputation, submit the article for review instead".to_string()));
+    }
+
+    // 模板只对出版物中可以写文章的成员开放
+    let template = match &query.template {
+        Some(template_id) => {
+            let template = app_state.template_service.get_template(template_id).await?;
+            app_state.publication_service.check_permission(&template.publication_id, &user.id, "article.create").await?;
+            template.apply_to(&mut request);
+            Some(template)
+        }
+        None => None,
+    };
+
+    // 直接发布（或定时发布）时正文必须包含模板要求的章节，并通过出版物强制的写作规范和两步验证要求
+    if request.save_as_draft == Some(false) || request.publish_at.is_some() {
+        if let Some(template) = &template {
+            let missing = app_state.template_service.missing_sections(template, &request.content);
+            if !missing.is_empty() {
+                return Err(AppError::BadRequest(format!("Article is missing required sections: {}", missing.join(", "))));
+            }
+        }
+        app_state.style_guide_service.check_submission(request.publication_id.as_deref(), &request.content).await?;
+        if let Some(publication_id) = &request.publication_id {
+            app_state.publication_service.check_two_factor(publication_id, &user).await?;
+        }
+    }
+
+    // 信誉不足的作者先保存为草稿，再提交审核
+    let needs_review = request.save_as_draft == Some(false)
+        && !app_state.reputation_service.can_publish_immediately(&user.id).await?;
+    if needs_review {
+        request.save_as_draft = Some(true);
+    }
+
     // 创建文章
     let article = app_state.article_service.create_article(&user.id, request).await?;
+    let article = match &template {
+        Some(template) => app_state.template_service.attach(article, template).await?,
+        None => article,
+    };
+    let article = if needs_review {
+        app_state.article_service.submit_for_review(&article.id, &user.id).await?
+    } else {
+        article
+    };
+
+    // 没有封面时生成品牌封面，生成失败不影响文章创建
+    let article = match app_state.cover_image_service.apply_generated_cover(article.clone()).await {
+        Ok(article) => article,
+        Err(e) => {
+            warn!("Failed to generate cover for article {}: {}", article.id, e);
+            article
+        }
+    };
 
     info!("Created article: {} by user: {}", article.id, user.id);
 
-    Ok(Json(json!({
-        "success": true,
-        "data": article,
-        "message": "Article created successfully"
-    })))
+    Ok(ApiResponse::ok(article).with_message("Article created successfully"))
 }
 
 /// 更新文章
 /// PUT /api/articles/:id
+#[utoipa::path(
+    put,
+    path = "/api/blog/articles/by-id/{id}",
+    tag = "articles",
+    params(("id" = String, Path)),
+    request_body = UpdateArticleRequest,
+    security(("bearer_auth" = []))
+)]
 pub async fn update_article(
     State(app_state): State<Arc<AppState>>,
     Path(article_id): Path<String>,
     Extension(user): Extension<User>,
-    Json(request): Json<UpdateArticleRequest>,
-) -> Result<Json<Value>> {
+    Json(mut request): Json<UpdateArticleRequest>,
+) -> Result<ApiResponse> {
     debug!("Updating article: {} by user: {}", article_id, user.id);
 
     // 检查权限
     require_permission!(app_state.auth_service, user, "article.update");
 
+    if request.publish_at.is_some() && !app_state.reputation_service.can_publish_immediately(&user.id).await? {
+        return Err(AppError::Authorization("Scheduled publishing requires a higher reputation, submit the article for review instead".to_string()));
+    }
+
+    if request.status == Some(ArticleStatus::Published) || request.publish_at.is_some() {
+        app_state.template_service.ensure_publishable(&article_id, request.content.as_deref()).await?;
+        app_state.style_guide_service.ensure_submittable(&article_id, request.content.as_deref()).await?;
+        if let Some(publication_id) = app_state
+            .articles()?
+            .get_article_by_id(&article_id)
+            .await?
+            .and_then(|article| article.publication_id)
+        {
+            app_state.publication_service.check_two_factor(&publication_id, &user).await?;
+        }
+    }
+
+    // 信誉不足的作者通过更新发布时改为提交审核
+    if request.status == Some(ArticleStatus::Published) {
+        let already_published = app_state.articles()?.get_article_by_id(&article_id).await?
+            .map_or(false, |a| a.status == ArticleStatus::Published);
+        if !already_published && !app_state.reputation_service.can_publish_immediately(&user.id).await? {
+            request.status = Some(ArticleStatus::PendingReview);
+        }
+    }
+
     // 更新文章
     let article = app_state.article_service.update_article(&article_id, &user.id, request).await?;
 
+    // 标题修改后重新生成自动封面
+    let article = match app_state.cover_image_service.apply_generated_cover(article.clone()).await {
+        Ok(article) => article,
+        Err(e) => {
+            warn!("Failed to regenerate cover for article {}: {}", article.id, e);
+            article
+        }
+    };
+    // 已发布文章的标题修改后重新生成 OG 图片，首次发布由发布钩子生成
+    if article.is_published() {
+        if let Err(e) = app_state.cover_image_service.refresh_og_image(&article).await {
+            warn!("Failed to regenerate OG image for article {}: {}", article.id, e);
+        }
+    }
+
     info!("Updated article: {} by user: {}", article_id, user.id);
 
-    Ok(Json(json!({
-        "success": true,
-        "data": article,
-        "message": "Article updated successfully"
+    Ok(ApiResponse::ok(article).with_message("Article updated successfully"))
+}
+
+/// 自动保存文章草稿
+/// POST /api/articles/:id/autosave
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/by-id/{id}/autosave",
+    tag = "articles",
+    params(("id" = String, Path)),
+    request_body = AutosaveArticleRequest,
+    security(("bearer_auth" = []))
+)]
+pub async fn autosave_article(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+    Json(request): Json<AutosaveArticleRequest>,
+) -> Result<ApiResponse> {
+    debug!("Autosaving article: {} by user: {}", article_id, user.id);
+
+    require_permission!(app_state.auth_service, user, "article.update");
+
+    let article = app_state.article_service.autosave_article(&article_id, &user.id, request).await?;
+
+    Ok(ApiResponse::ok(json!({
+        "id": article.id,
+        "updated_at": article.updated_at,
+    })).with_message("Draft saved"))
+}
+
+/// 获取文章修订历史
+/// GET /api/articles/:id/revisions
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/by-id/{id}/revisions",
+    tag = "articles",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_revisions(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    debug!("Listing revisions for article: {}", article_id);
+
+    let revisions = app_state.article_service.get_revisions(&article_id, &user.id).await?;
+
+    Ok(ApiResponse::ok(revisions))
+}
+
+/// 获取指定修订版本
+/// GET /api/articles/:id/revisions/:revision
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/by-id/{id}/revisions/{revision}",
+    tag = "articles",
+    params(("id" = String, Path), ("revision" = i32, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_revision(
+    State(app_state): State<Arc<AppState>>,
+    Path((article_id, revision_number)): Path<(String, i32)>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let revision = app_state.article_service.get_revision(&article_id, &user.id, revision_number).await?;
+
+    Ok(ApiResponse::ok(revision))
+}
+
+/// 比较两个修订版本
+/// GET /api/articles/:id/revisions/diff?from=1&to=3
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/by-id/{id}/revisions/diff",
+    tag = "articles",
+    params(("id" = String, Path), RevisionDiffQuery),
+    security(("bearer_auth" = []))
+)]
+pub async fn diff_revisions(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Query(query): Query<RevisionDiffQuery>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let diff = app_state.article_service.diff_revisions(&article_id, &user.id, query).await?;
+
+    Ok(ApiResponse::ok(diff))
+}
+
+/// 恢复到指定修订版本
+/// POST /api/articles/:id/revisions/:revision/restore
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/by-id/{id}/revisions/{revision}/restore",
+    tag = "articles",
+    params(("id" = String, Path), ("revision" = i32, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn restore_revision(
+    State(app_state): State<Arc<AppState>>,
+    Path((article_id, revision_number)): Path<(String, i32)>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    require_permission!(app_state.auth_service, user, "article.update");
+
+    let article = app_state.article_service.restore_revision(&article_id, &user.id, revision_number).await?;
+
+    info!("Restored article {} to revision {} by user {}", article_id, revision_number, user.id);
+
+    Ok(ApiResponse::ok(article).with_message(format!("Article restored to revision {}", revision_number)))
+}
+
+/// 列出文章的合著者（含待接受的邀请）
+/// GET /api/articles/by-id/:id/collaborators
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/by-id/{id}/collaborators",
+    tag = "articles",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_collaborators(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let collaborators = app_state.collaborator_service.list(&article_id, &user.id).await?;
+
+    Ok(ApiResponse::ok(collaborators))
+}
+
+/// 邀请合著者
+/// POST /api/articles/by-id/:id/collaborators
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/by-id/{id}/collaborators",
+    tag = "articles",
+    params(("id" = String, Path)),
+    request_body = InviteCollaboratorRequest,
+    security(("bearer_auth" = []))
+)]
+pub async fn invite_collaborator(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+    Json(request): Json<InviteCollaboratorRequest>,
+) -> Result<ApiResponse> {
+    require_permission!(app_state.auth_service, user, "article.update");
+
+    let collaborator = app_state.collaborator_service.invite(&article_id, &user.id, request).await?;
+
+    Ok(ApiResponse::ok(collaborator).with_message("Collaborator invited"))
+}
+
+/// 接受合著邀请
+/// POST /api/articles/by-id/:id/collaborators/accept
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/by-id/{id}/collaborators/accept",
+    tag = "articles",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn accept_collaboration(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let collaborator = app_state.collaborator_service.accept(&article_id, &user.id).await?;
+
+    Ok(ApiResponse::ok(collaborator).with_message("Collaboration accepted"))
+}
+
+/// 修改合著者角色
+/// PUT /api/articles/by-id/:id/collaborators/:user_id
+#[utoipa::path(
+    put,
+    path = "/api/blog/articles/by-id/{id}/collaborators/{user_id}",
+    tag = "articles",
+    params(("id" = String, Path), ("user_id" = String, Path)),
+    request_body = UpdateCollaboratorRequest,
+    security(("bearer_auth" = []))
+)]
+pub async fn update_collaborator(
+    State(app_state): State<Arc<AppState>>,
+    Path((article_id, collaborator_id)): Path<(String, String)>,
+    Extension(user): Extension<User>,
+    Json(request): Json<UpdateCollaboratorRequest>,
+) -> Result<ApiResponse> {
+    let collaborator = app_state.collaborator_service
+        .update_role(&article_id, &user.id, &collaborator_id, request)
+        .await?;
+
+    Ok(ApiResponse::ok(collaborator))
+}
+
+/// 移除合著者；合著者移除自己即退出合著或拒绝邀请
+/// DELETE /api/articles/by-id/:id/collaborators/:user_id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/articles/by-id/{id}/collaborators/{user_id}",
+    tag = "articles",
+    params(("id" = String, Path), ("user_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn remove_collaborator(
+    State(app_state): State<Arc<AppState>>,
+    Path((article_id, collaborator_id)): Path<(String, String)>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    app_state.collaborator_service.remove(&article_id, &user.id, &collaborator_id).await?;
+
+    Ok(ApiResponse::message("Collaborator removed"))
+}
+
+/// 获取文章的预览链接（仅作者）
+/// GET /api/articles/by-id/:id/preview-links
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/by-id/{id}/preview-links",
+    tag = "articles",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_preview_links(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let links = app_state.preview_service.list_links(&article_id, &user.id).await?;
+
+    Ok(ApiResponse::ok(links))
+}
+
+/// 为未发布的文章生成秘密预览链接，令牌只在响应中返回这一次
+/// POST /api/articles/by-id/:id/preview-links
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/by-id/{id}/preview-links",
+    tag = "articles",
+    params(("id" = String, Path)),
+    request_body = CreatePreviewLinkRequest,
+    security(("bearer_auth" = []))
+)]
+pub async fn create_preview_link(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+    Json(request): Json<CreatePreviewLinkRequest>,
+) -> Result<ApiResponse> {
+    require_permission!(app_state.auth_service, user, "article.update");
+
+    let link = app_state.preview_service.create_link(&article_id, &user.id, request).await?;
+
+    Ok(ApiResponse::ok(link).with_message("Preview link created"))
+}
+
+/// 撤销预览链接
+/// DELETE /api/articles/by-id/:id/preview-links/:link_id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/articles/by-id/{id}/preview-links/{link_id}",
+    tag = "articles",
+    params(("id" = String, Path), ("link_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn revoke_preview_link(
+    State(app_state): State<Arc<AppState>>,
+    Path((article_id, link_id)): Path<(String, String)>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let link = app_state.preview_service.revoke_link(&article_id, &link_id, &user.id).await?;
+
+    Ok(ApiResponse::ok(link).with_message("Preview link revoked"))
+}
+
+/// 通过预览链接只读查看未发布的文章，不需要登录
+/// GET /api/articles/preview/:token
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/preview/{token}",
+    tag = "articles",
+    params(("token" = String, Path))
+)]
+pub async fn get_article_preview(
+    State(app_state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> Result<Response> {
+    let preview = app_state.preview_service.get_preview(&token).await?;
+
+    // 草稿内容不能进入任何缓存，也不能被搜索引擎收录
+    let headers = [
+        (header::CACHE_CONTROL, "private, no-store"),
+        (header::HeaderName::from_static("x-robots-tag"), "noindex, nofollow"),
+    ];
+
+    Ok((headers, ApiResponse::ok(preview)).into_response())
+}
+
+/// 获取文章的旧 slug 跳转（仅作者）
+/// GET /api/articles/by-id/:id/slug-redirects
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/by-id/{id}/slug-redirects",
+    tag = "articles",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_slug_redirects(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let redirects = app_state.article_service.list_slug_redirects(&article_id, &user.id).await?;
+
+    Ok(ApiResponse::ok(redirects))
+}
+
+/// 删除旧 slug 的跳转，旧链接之后返回 404，slug 可以被其他文章使用
+/// DELETE /api/articles/by-id/:id/slug-redirects/:slug
+#[utoipa::path(
+    delete,
+    path = "/api/blog/articles/by-id/{id}/slug-redirects/{slug}",
+    tag = "articles",
+    params(("id" = String, Path), ("slug" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_slug_redirect(
+    State(app_state): State<Arc<AppState>>,
+    Path((article_id, slug)): Path<(String, String)>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    require_permission!(app_state.auth_service, user, "article.update");
+
+    app_state.article_service.delete_slug_redirect(&article_id, &user.id, &slug).await?;
+
+    Ok(ApiResponse::message("Slug redirect removed"))
+}
+
+/// 对照创建时选择的模板检查文章：缺少的必需章节和 SEO 清单
+/// GET /api/articles/by-id/:id/template-check
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/by-id/{id}/template-check",
+    tag = "articles",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn check_article_template(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let article = app_state.article_service.get_own_article(&article_id, &user.id).await?;
+    let check = app_state.template_service.check_article(&article).await?;
+
+    Ok(ApiResponse::ok(check))
+}
+
+/// 当前用户收到的待接受合著邀请
+/// GET /api/articles/collaborations/invitations
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/collaborations/invitations",
+    tag = "articles",
+    security(("bearer_auth" = []))
+)]
+pub async fn list_collaboration_invitations(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let invitations = app_state.collaborator_service.list_invitations(&user.id).await?;
+
+    Ok(ApiResponse::ok(invitations))
+}
+
+/// 正文代码高亮（content_html 中 `pre.highlight`）使用的样式表
+/// GET /api/blog/articles/highlight.css?theme=InspiredGitHub
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/highlight.css",
+    tag = "articles",
+    params(HighlightCssQuery)
+)]
+pub async fn get_highlight_css(Query(query): Query<HighlightCssQuery>) -> Result<Response> {
+    let processor = MarkdownProcessor::new();
+    let theme = query.theme.as_deref().unwrap_or("InspiredGitHub");
+    let css = processor.highlight_css(theme).ok_or_else(|| {
+        AppError::BadRequest(format!(
+            "Unknown highlight theme '{}', available: {}",
+            theme,
+            processor.highlight_themes().join(", ")
+        ))
+    })?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/css; charset=utf-8"),
+            (header::CACHE_CONTROL, "public, max-age=86400"),
+        ],
+        css,
+    )
+        .into_response())
+}
+
+/// 获取文章的 OG 分享图片地址。图片在发布和更新时生成，这里只读取已有地址
+/// GET /api/articles/:id/og-image
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/by-id/{id}/og-image",
+    tag = "articles",
+    params(("id" = String, Path))
+)]
+pub async fn get_og_image(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+) -> Result<ApiResponse> {
+    debug!("Getting OG image for article: {}", article_id);
+
+    let article = app_state.articles()?.get_article_by_id(&article_id).await?
+        .filter(|a| a.is_published())
+        .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
+
+    let url = app_state.cover_image_service.og_image_url(&article)
+        .ok_or_else(|| AppError::NotFound("OG image not generated yet".to_string()))?;
+
+    Ok(ApiResponse::ok(json!({
+        "url": url,
+        "width": 1200,
+        "height": 630
     })))
 }
 
+/// 获取文章的评论统计（仅作者可见）
+/// GET /api/articles/:id/comments/analytics
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/by-id/{id}/comments/analytics",
+    tag = "articles",
+    params(("id" = String, Path), CommentAnalyticsQuery),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_comment_analytics(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Query(query): Query<CommentAnalyticsQuery>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    debug!("Getting comment analytics for article: {} by user: {}", article_id, user.id);
+
+    let article = app_state.articles()?.get_article_by_id(&article_id).await?
+        .filter(|a| !a.is_deleted)
+        .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
+
+    if article.author_id != user.id {
+        return Err(AppError::Authorization("Only the article author can view comment analytics".to_string()));
+    }
+
+    let analytics = app_state.analytics_service
+        .get_article_comment_analytics(&article.id, query)
+        .await?;
+
+    Ok(ApiResponse::ok(analytics))
+}
+
 /// 发布文章
 /// POST /api/articles/:id/publish
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/by-id/{id}/publish",
+    tag = "articles",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 pub async fn publish_article(
     State(app_state): State<Arc<AppState>>,
     Path(article_id): Path<String>,
     Extension(user): Extension<User>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Publishing article: {} by user: {}", article_id, user.id);
 
     // 检查邮箱验证状态
@@ -220,25 +1097,62 @@ pub async fn publish_article(
     // 检查权限
     require_permission!(app_state.auth_service, user, "article.update");
 
+    let article = publish_or_submit(&app_state, &article_id, &user).await?;
+    if article.status == ArticleStatus::PendingReview {
+        return Ok(ApiResponse::ok(article).with_message("Article submitted for review"));
+    }
+
+    Ok(ApiResponse::ok(article).with_message("Article published successfully"))
+}
+
+/// 发布文章；信誉不足的作者改为提交审核，返回的文章状态为 pending_review
+async fn publish_or_submit(app_state: &AppState, article_id: &str, user: &User) -> Result<Article> {
+    // 发到出版物的文章需要作者在出版物中有发布权限，没有的通过投稿由编辑发布
+    if let Some(publication_id) = app_state
+        .articles()?
+        .get_article_by_id(article_id)
+        .await?
+        .and_then(|article| article.publication_id)
+    {
+        app_state.publication_service.check_permission(&publication_id, &user.id, "article.publish").await?;
+        app_state.publication_service.check_two_factor(&publication_id, user).await?;
+    }
+
+    // 缺少模板要求的章节或违反出版物强制的写作规范时不能发布，也不能提交审核
+    app_state.template_service.ensure_publishable(article_id, None).await?;
+    app_state.style_guide_service.ensure_submittable(article_id, None).await?;
+
+    // 信誉不足的作者需要经过审核才能发布
+    if !app_state.reputation_service.can_publish_immediately(&user.id).await? {
+        let article = app_state.article_service.submit_for_review(article_id, &user.id).await?;
+
+        info!("Article {} by user {} submitted for review", article_id, user.id);
+
+        return Ok(article);
+    }
+
     // 发布文章
-    let article = app_state.article_service.publish_article(&article_id, &user.id).await?;
+    let article = app_state.article_service.publish_article(article_id, &user.id).await?;
 
     info!("Published article: {} by user: {}", article_id, user.id);
 
-    Ok(Json(json!({
-        "success": true,
-        "data": article,
-        "message": "Article published successfully"
-    })))
+    Ok(article)
 }
 
 /// 取消发布文章
 /// POST /api/articles/:id/unpublish
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/by-id/{id}/unpublish",
+    tag = "articles",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 pub async fn unpublish_article(
     State(app_state): State<Arc<AppState>>,
     Path(article_id): Path<String>,
     Extension(user): Extension<User>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Unpublishing article: {} by user: {}", article_id, user.id);
 
     // 检查权限
@@ -249,20 +1163,88 @@ pub async fn unpublish_article(
 
     info!("Unpublished article: {} by user: {}", article_id, user.id);
 
-    Ok(Json(json!({
-        "success": true,
-        "data": article,
-        "message": "Article unpublished successfully"
-    })))
+    Ok(ApiResponse::ok(article).with_message("Article unpublished successfully"))
+}
+
+/// 获取待审核文章（管理员）
+/// GET /api/articles/pending-review
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/pending-review",
+    tag = "articles",
+    security(("bearer_auth" = []))
+)]
+pub async fn get_pending_review_articles(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    require_permission!(app_state.auth_service, user, "article.moderate");
+
+    let articles = app_state.article_service.get_pending_review_articles(100).await?;
+
+    Ok(ApiResponse::ok(articles))
+}
+
+/// 审核通过并发布文章（管理员）
+/// POST /api/articles/:id/approve
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/by-id/{id}/approve",
+    tag = "articles",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn approve_article(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    require_permission!(app_state.auth_service, user, "article.moderate");
+
+    let article = app_state.article_service.review_article(&article_id, true).await?;
+
+    info!("Article {} approved by {}", article_id, user.id);
+
+    Ok(ApiResponse::ok(article).with_message("Article approved and published"))
+}
+
+/// 驳回待审核文章，退回草稿（管理员）
+/// POST /api/articles/:id/reject
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/by-id/{id}/reject",
+    tag = "articles",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn reject_article(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    require_permission!(app_state.auth_service, user, "article.moderate");
+
+    let article = app_state.article_service.review_article(&article_id, false).await?;
+
+    info!("Article {} rejected by {}", article_id, user.id);
+
+    Ok(ApiResponse::ok(article).with_message("Article returned to draft"))
 }
 
 /// 删除文章
 /// DELETE /api/articles/:id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/articles/by-id/{id}",
+    tag = "articles",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 pub async fn delete_article(
     State(app_state): State<Arc<AppState>>,
     Path(article_id): Path<String>,
     Extension(user): Extension<User>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Deleting article: {} by user: {}", article_id, user.id);
 
     // 检查权限
@@ -273,22 +1255,227 @@ pub async fn delete_article(
 
     info!("Deleted article: {} by user: {}", article_id, user.id);
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Article deleted successfully"
+    Ok(ApiResponse::message("Article deleted successfully"))
+}
+
+/// 置顶到作者主页或出版物首页，各自最多置顶 3 篇
+/// PUT /api/articles/by-id/:id/pin
+#[utoipa::path(
+    put,
+    path = "/api/blog/articles/by-id/{id}/pin",
+    tag = "articles",
+    params(("id" = String, Path)),
+    request_body = PinArticleRequest,
+    security(("bearer_auth" = []))
+)]
+pub async fn pin_article(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+    Json(request): Json<PinArticleRequest>,
+) -> Result<ApiResponse> {
+    require_permission!(app_state.auth_service, user, "article.update");
+
+    let article = match request.target {
+        PinTarget::Profile => app_state.article_service
+            .pin_to_profile(&article_id, &user.id, request.pinned)
+            .await?,
+        PinTarget::Publication => {
+            let article = app_state.articles()?.get_article_by_id(&article_id).await?
+                .filter(|a| !a.is_deleted)
+                .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
+            let publication_id = article.publication_id.as_deref()
+                .ok_or_else(|| AppError::BadRequest("Article does not belong to a publication".to_string()))?;
+            app_state.publication_service.check_permission(publication_id, &user.id, "article.edit_any").await?;
+
+            app_state.article_service.pin_to_publication(&article, request.pinned).await?
+        }
+    };
+
+    let message = if request.pinned { "Article pinned" } else { "Article unpinned" };
+    Ok(ApiResponse::ok(article).with_message(message))
+}
+
+/// 设置或取消精选。平台审核员可以精选任何文章，出版物编辑可以精选本出版物的文章
+/// PUT /api/articles/by-id/:id/featured
+#[utoipa::path(
+    put,
+    path = "/api/blog/articles/by-id/{id}/featured",
+    tag = "articles",
+    params(("id" = String, Path)),
+    request_body = FeatureArticleRequest,
+    security(("bearer_auth" = []))
+)]
+pub async fn feature_article(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+    Json(request): Json<FeatureArticleRequest>,
+) -> Result<ApiResponse> {
+    let article = app_state.articles()?.get_article_by_id(&article_id).await?
+        .filter(|a| !a.is_deleted)
+        .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
+
+    let is_moderator = app_state.auth_service.has_permission(&user, "article.moderate").await?;
+    if !is_moderator {
+        let publication_id = article.publication_id.as_deref()
+            .ok_or_else(|| AppError::forbidden("Permission 'article.moderate' required"))?;
+        app_state.publication_service.check_permission(publication_id, &user.id, "article.edit_any").await?;
+    }
+
+    let article = app_state.article_service.set_featured(&article, request.featured).await?;
+
+    if is_moderator {
+        let action = if request.featured { "article.feature" } else { "article.unfeature" };
+        app_state.admin_service.record(&user.id, action, "article", &article.id, None).await;
+    }
+
+    let message = if request.featured { "Article featured" } else { "Article unfeatured" };
+    Ok(ApiResponse::ok(article).with_message(message))
+}
+
+/// 批量操作文章：发布、取消发布、删除、添加或移除标签。
+/// 每篇文章单独检查权限并返回结果，一篇失败不影响其他文章；整批操作记一条审计日志
+/// POST /api/articles/bulk
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/bulk",
+    tag = "articles",
+    request_body = BulkArticleRequest,
+    responses((status = 200, body = BulkArticleResponse)),
+    security(("bearer_auth" = []))
+)]
+pub async fn bulk_update_articles(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(request): Json<BulkArticleRequest>,
+) -> Result<ApiResponse> {
+    use validator::Validate;
+    request.validate().map_err(AppError::ValidatorError)?;
+
+    let action = request.action;
+    match action {
+        BulkArticleAction::Publish => {
+            if !user.is_verified {
+                return Err(AppError::Authorization("发布文章需要验证邮箱，请前往 Rainbow-Auth 完成邮箱验证".to_string()));
+            }
+            require_permission!(app_state.auth_service, user, "article.update");
+        }
+        BulkArticleAction::Delete => {
+            require_permission!(app_state.auth_service, user, "article.delete");
+        }
+        BulkArticleAction::Unpublish | BulkArticleAction::TagAdd | BulkArticleAction::TagRemove => {
+            require_permission!(app_state.auth_service, user, "article.update");
+        }
+    }
+    if action.is_tag_action() && request.tags.iter().all(|tag| tag.trim().is_empty()) {
+        return Err(AppError::BadRequest("tags is required for tag actions".to_string()));
+    }
+
+    // 重复的 ID 只处理一次
+    let mut article_ids: Vec<String> = Vec::with_capacity(request.article_ids.len());
+    for id in request.article_ids {
+        let id = ArticleId::new(&id).to_record();
+        if !article_ids.contains(&id) {
+            article_ids.push(id);
+        }
+    }
+
+    let mut results = Vec::with_capacity(article_ids.len());
+    for article_id in &article_ids {
+        let outcome = match action {
+            BulkArticleAction::Publish => publish_or_submit(&app_state, article_id, &user)
+                .await
+                .map(|article| Some(article.status)),
+            BulkArticleAction::Unpublish => app_state.article_service
+                .unpublish_article(article_id, &user.id)
+                .await
+                .map(|article| Some(article.status)),
+            BulkArticleAction::Delete => app_state.article_service
+                .delete_article(article_id, &user.id)
+                .await
+                .map(|_| None),
+            BulkArticleAction::TagAdd => app_state.article_service
+                .modify_article_tags(article_id, &user.id, &request.tags, &[])
+                .await
+                .map(|_| None),
+            BulkArticleAction::TagRemove => app_state.article_service
+                .modify_article_tags(article_id, &user.id, &[], &request.tags)
+                .await
+                .map(|_| None),
+        };
+
+        results.push(match outcome {
+            Ok(status) => BulkArticleItemResult::succeeded(article_id, status),
+            Err(e) => {
+                debug!("Bulk {} failed for article {}: {}", action.as_str(), article_id, e);
+                BulkArticleItemResult::failed(article_id, &e)
+            }
+        });
+    }
+
+    let succeeded = results.iter().filter(|r| r.success).count();
+    let failed = results.len() - succeeded;
+
+    let summary = json!({
+        "succeeded": succeeded,
+        "failed": failed,
+        "tags": request.tags,
+    })
+    .to_string();
+    app_state.admin_service
+        .record(&user.id, &format!("article.bulk_{}", action.as_str()), "article", &article_ids.join(","), Some(&summary))
+        .await;
+
+    info!("Bulk {} on {} articles by user {}: {} succeeded, {} failed", action.as_str(), article_ids.len(), user.id, succeeded, failed);
+
+    Ok(ApiResponse::ok(BulkArticleResponse {
+        action,
+        succeeded,
+        failed,
+        results,
+    }))
+}
+
+/// 获取浏览令牌，开启 BOT_CHALLENGE_REQUIRED 时前端脚本在 X-View-Token 头中携带它调用 /view
+/// GET /api/articles/view-token
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/view-token",
+    tag = "articles"
+)]
+pub async fn get_view_token(
+    State(app_state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<ApiResponse> {
+    let token = app_state.bot_detection_service.issue_view_token(&headers);
+
+    Ok(ApiResponse::ok(json!({
+        "token": token,
+        "required": app_state.bot_detection_service.challenge_required()
     })))
 }
 
 /// 增加文章浏览次数
 /// POST /api/articles/:id/view
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/by-id/{id}/view",
+    tag = "articles",
+    params(("id" = String, Path)),
+    security((), ("bearer_auth" = []))
+)]
 pub async fn increment_view_count(
     State(app_state): State<Arc<AppState>>,
     Path(article_id): Path<String>,
-) -> Result<Json<Value>> {
+    user: Option<Extension<User>>,
+    geo: Option<Extension<VisitorGeo>>,
+    headers: HeaderMap,
+) -> Result<ApiResponse> {
     debug!("Incrementing view count for article: {}", article_id);
 
     // 检查文章是否存在
-    let article = app_state.article_service.get_article_by_id(&article_id).await?
+    let article = app_state.articles()?.get_article_by_id(&article_id).await?
         .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
 
     // 只有已发布的文章才能增加浏览次数
@@ -296,23 +1483,193 @@ pub async fn increment_view_count(
         return Err(AppError::BadRequest("Cannot increment view count for unpublished article".to_string()));
     }
 
+    // 爬虫和没有通过 JS 验证的请求不计入浏览数、热度和流量分析
+    let bots = &app_state.bot_detection_service;
+    if let Some(verdict) = bots.detect(&headers).or_else(|| bots.check_view_token(&headers)) {
+        debug!("Excluding {:?} view of article {}", verdict.reason, article_id);
+        bots.record_bot_view_async(article.id.clone(), article.author_id.clone(), article.publication_id.clone(), verdict);
+        return Ok(ApiResponse::message("View not counted"));
+    }
+
+    // 同一读者在去重窗口内的重复浏览不计数；实验点击按曝光去重，不受窗口影响
+    let user_id = user.as_ref().map(|u| u.0.id.as_str());
+    if let Err(e) = app_state.experiment_service.record_click(&article.id, &headers, user_id).await {
+        warn!("Failed to record experiment click for article {}: {}", article_id, e);
+    }
+    let country = geo.as_ref().and_then(|g| g.0.country.as_deref());
+    let counted = app_state.view_tracking_service
+        .record_view(&article.id, &article.author_id, article.publication_id.as_deref(), &headers, user_id, country)
+        .await?;
+    if !counted {
+        return Ok(ApiResponse::message("View already counted"));
+    }
+
     // 增加浏览次数
     app_state.article_service.increment_view_count(&article_id).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "View count incremented"
-    })))
+    // 浏览事件用于流量分析，记录失败不影响计数
+    let referrer = headers.get(header::REFERER).and_then(|v| v.to_str().ok());
+    if let Err(e) = app_state.analytics_service.record_article_view(&article, referrer).await {
+        warn!("Failed to record view event for article {}: {}", article_id, e);
+    }
+    if !app_state.config.live_queries_enabled {
+        app_state.popularity_service.record_view(&article_id);
+    }
+
+    Ok(ApiResponse::message("View count incremented"))
+}
+
+/// 文章最近 N 天去重后的浏览数和独立访客数（作者和合著者）
+/// GET /api/articles/by-id/:id/views?days=30
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/by-id/{id}/views",
+    tag = "articles",
+    params(("id" = String, Path), ViewStatsQuery),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_view_stats(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+    Query(query): Query<ViewStatsQuery>,
+) -> Result<ApiResponse> {
+    let article = app_state.article_service.get_own_article(&article_id, &user.id).await?;
+    let stats = app_state.view_tracking_service.get_article_stats(&article.id, query).await?;
+
+    Ok(ApiResponse::ok(stats))
+}
+
+/// 选择文章以笔名还是账号身份发布，只能在首次发布前修改
+/// PUT /api/articles/by-id/:id/pseudonym
+#[utoipa::path(
+    put,
+    path = "/api/blog/articles/by-id/{id}/pseudonym",
+    tag = "articles",
+    params(("id" = String, Path)),
+    request_body = SetArticlePseudonymRequest,
+    security(("bearer_auth" = []))
+)]
+pub async fn set_article_pseudonym(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+    Json(request): Json<SetArticlePseudonymRequest>,
+) -> Result<ApiResponse> {
+    debug!("Setting pseudonym of article {} by user: {}", article_id, user.id);
+
+    let article = app_state.article_service.get_own_article(&article_id, &user.id).await?;
+    app_state.pseudonym_service
+        .set_article_pseudonym(&article, &user.id, request.pseudonym_id.as_deref())
+        .await?;
+
+    Ok(ApiResponse::message("Article byline updated"))
+}
+
+/// 获取文章的反应汇总
+/// GET /api/articles/:id/reactions
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/{slug}/reactions",
+    tag = "articles",
+    params(("slug" = String, Path)),
+    security((), ("bearer_auth" = []))
+)]
+pub async fn get_reactions(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    user: Option<Extension<User>>,
+) -> Result<ApiResponse> {
+    let article = app_state.reaction_service.resolve_article(&article_id).await?;
+    let reactions = app_state
+        .reaction_service
+        .get_article_reactions(&article, user.as_ref().map(|u| u.0.id.as_str()))
+        .await?;
+
+    Ok(ApiResponse::ok(reactions))
+}
+
+/// 添加反应；点赞可以累加（count，默认 1），其他反应每人一次
+/// POST /api/articles/:id/reactions
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/{slug}/reactions",
+    tag = "articles",
+    params(("slug" = String, Path)),
+    request_body = AddReactionRequest,
+    security(("bearer_auth" = []))
+)]
+pub async fn add_reaction(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+    Json(request): Json<AddReactionRequest>,
+) -> Result<ApiResponse> {
+    use validator::Validate;
+    request.validate().map_err(AppError::ValidatorError)?;
+
+    let article = app_state.reaction_service.resolve_article(&article_id).await?;
+    let article_id = crate::models::id::ArticleId::new(&article.id);
+
+    if request.reaction_type == ReactionType::Clap {
+        app_state.article_service
+            .clap_article(article_id.as_str(), &user.id, request.count.unwrap_or(1))
+            .await?;
+        if !app_state.config.live_queries_enabled {
+            app_state.popularity_service.record_clap(article_id.as_str());
+        }
+    } else {
+        app_state.reaction_service.add_reaction(&article, &user.id, request.reaction_type).await?;
+    }
+
+    info!("User {} reacted {} to article {}", user.id, request.reaction_type.as_str(), article_id);
+
+    // 重新读取文章，返回最新的计数
+    let article = app_state.reaction_service.resolve_article(article_id.as_str()).await?;
+    let reactions = app_state.reaction_service.get_article_reactions(&article, Some(&user.id)).await?;
+
+    Ok(ApiResponse::ok(reactions))
+}
+
+/// 撤销反应（点赞不能撤销）
+/// DELETE /api/articles/:id/reactions/:reaction_type
+#[utoipa::path(
+    delete,
+    path = "/api/blog/articles/{slug}/reactions/{reaction_type}",
+    tag = "articles",
+    params(("slug" = String, Path), ("reaction_type" = ReactionType, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn remove_reaction(
+    State(app_state): State<Arc<AppState>>,
+    Path((article_id, reaction_type)): Path<(String, ReactionType)>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let article = app_state.reaction_service.resolve_article(&article_id).await?;
+    app_state.reaction_service.remove_reaction(&article, &user.id, reaction_type).await?;
+
+    let article = app_state.reaction_service.resolve_article(&article.id).await?;
+    let reactions = app_state.reaction_service.get_article_reactions(&article, Some(&user.id)).await?;
+
+    Ok(ApiResponse::ok(reactions))
 }
 
 /// 为文章点赞
 /// POST /api/articles/:id/clap
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/by-id/{id}/clap",
+    tag = "articles",
+    params(("id" = String, Path)),
+    request_body = crate::models::clap::AddClapRequest,
+    security(("bearer_auth" = []))
+)]
 pub async fn clap_article(
     State(app_state): State<Arc<AppState>>,
     Path(article_id): Path<String>,
     Extension(user): Extension<User>,
     Json(request): Json<crate::models::clap::AddClapRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Clap request received - Path article_id: {}, Request article_id: {}, count: {}, user: {}", 
            article_id, request.article_id, request.count, user.id);
 
@@ -340,9 +1697,403 @@ pub async fn clap_article(
 
     info!("User {} clapped article: {} (total claps: {})", user.id, article_id, response.total_claps);
 
-    Ok(Json(json!({
-        "success": true,
-        "data": response,
-        "message": "Article clapped successfully"
-    })))
-}
\ No newline at end of file
+    // 启用实时订阅时热度由数据库事件驱动，否则在本实例内直接计入
+    if !app_state.config.live_queries_enabled {
+        app_state.popularity_service.record_clap(&article_id);
+    }
+
+    Ok(ApiResponse::ok(response).with_message("Article clapped successfully"))
+}
+
+/// 按所属出版物的写作规范检查草稿，可以提交编辑器中尚未保存的正文
+/// POST /api/articles/by-id/:id/lint
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/by-id/{id}/lint",
+    tag = "articles",
+    params(("id" = String, Path)),
+    request_body = LintArticleRequest,
+    security(("bearer_auth" = []))
+)]
+pub async fn lint_article(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+    request: Option<Json<LintArticleRequest>>,
+) -> Result<ApiResponse> {
+    let article = app_state.article_service.get_own_article(&article_id, &user.id).await?;
+    let content = request.and_then(|Json(r)| r.content);
+    let report = app_state.style_guide_service.lint_article(&article, content.as_deref()).await?;
+
+    Ok(ApiResponse::ok(report))
+}
+
+/// 当前用户在文章中的阅读位置，没有记录时为 null
+/// GET /api/articles/:id/progress
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/{slug}/progress",
+    tag = "articles",
+    params(("slug" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_reading_progress(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let article = app_state.reaction_service.resolve_article(&article_id).await?;
+    let progress = app_state.reading_progress_service.get(&user.id, &article).await?;
+
+    Ok(ApiResponse::ok(progress))
+}
+
+/// 上报阅读位置（滚动比例、最后可见的段落）
+/// PUT /api/articles/:id/progress
+#[utoipa::path(
+    put,
+    path = "/api/blog/articles/{slug}/progress",
+    tag = "articles",
+    params(("slug" = String, Path)),
+    request_body = UpdateReadingProgressRequest,
+    security(("bearer_auth" = []))
+)]
+pub async fn update_reading_progress(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+    Json(request): Json<UpdateReadingProgressRequest>,
+) -> Result<ApiResponse> {
+    let article = app_state.reaction_service.resolve_article(&article_id).await?;
+    let progress = app_state.reading_progress_service.save(&user.id, &article, request).await?;
+
+    Ok(ApiResponse::ok(progress))
+}
+
+/// 清除阅读位置，文章不再出现在“继续阅读”中
+/// DELETE /api/articles/:id/progress
+#[utoipa::path(
+    delete,
+    path = "/api/blog/articles/{slug}/progress",
+    tag = "articles",
+    params(("slug" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn clear_reading_progress(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let article = app_state.reaction_service.resolve_article(&article_id).await?;
+    app_state.reading_progress_service.clear(&user.id, &article).await?;
+
+    Ok(ApiResponse::message("Reading progress cleared"))
+}
+
+/// 文章的全部附件（作者和合著者管理用，草稿也可以查看）
+/// GET /api/articles/by-id/:id/attachments
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/by-id/{id}/attachments",
+    tag = "articles",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_article_attachments(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let article = app_state.article_service.get_own_article(&article_id, &user.id).await?;
+    let attachments = app_state.attachment_service.list(&article).await?;
+
+    Ok(ApiResponse::ok(attachments))
+}
+
+/// 上传附件（multipart：file、title、description、gate）
+/// POST /api/articles/by-id/:id/attachments
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/by-id/{id}/attachments",
+    tag = "articles",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn upload_attachment(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+    mut multipart: Multipart,
+) -> Result<ApiResponse> {
+    let article = app_state.article_service.get_own_article(&article_id, &user.id).await?;
+
+    let mut file_data: Option<Vec<u8>> = None;
+    let mut filename: Option<String> = None;
+    let mut content_type: Option<String> = None;
+    let mut request = CreateAttachmentRequest::default();
+
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        error!("Failed to process multipart field: {}", e);
+        AppError::BadRequest("无法处理上传的文件".to_string())
+    })? {
+        match field.name().unwrap_or("") {
+            "file" => {
+                filename = field.file_name().map(|s| s.to_string());
+                content_type = field.content_type().map(|s| s.to_string());
+                let data = field.bytes().await.map_err(|e| {
+                    error!("Failed to read attachment data: {}", e);
+                    AppError::BadRequest("无法读取文件数据".to_string())
+                })?;
+                file_data = Some(data.to_vec());
+            }
+            "title" => request.title = field.text().await.unwrap_or_default().trim().to_string(),
+            "description" => {
+                request.description = field.text().await.ok().filter(|d| !d.trim().is_empty());
+            }
+            "gate" => {
+                let value = field.text().await.unwrap_or_default();
+                request.gate = serde_json::from_value(json!(value.trim().to_lowercase()))
+                    .map_err(|_| AppError::BadRequest("gate must be public, subscribers or email".to_string()))?;
+            }
+            _ => {}
+        }
+    }
+
+    let file_data = file_data.ok_or_else(|| AppError::BadRequest("未找到上传的文件".to_string()))?;
+    let filename = filename.unwrap_or_else(|| "attachment".to_string());
+    let content_type = content_type.unwrap_or_else(|| "application/octet-stream".to_string());
+    if request.title.is_empty() {
+        request.title = filename.clone();
+    }
+
+    let attachment = app_state.attachment_service
+        .create(&article, &user.id, request, &filename, &content_type, file_data)
+        .await?;
+
+    Ok(ApiResponse::ok(attachment).with_message("Attachment uploaded"))
+}
+
+/// 修改附件的标题、说明或下载门槛
+/// PUT /api/articles/by-id/:id/attachments/:attachment_id
+#[utoipa::path(
+    put,
+    path = "/api/blog/articles/by-id/{id}/attachments/{attachment_id}",
+    tag = "articles",
+    params(("id" = String, Path), ("attachment_id" = String, Path)),
+    request_body = UpdateAttachmentRequest,
+    security(("bearer_auth" = []))
+)]
+pub async fn update_attachment(
+    State(app_state): State<Arc<AppState>>,
+    Path((article_id, attachment_id)): Path<(String, String)>,
+    Extension(user): Extension<User>,
+    Json(request): Json<UpdateAttachmentRequest>,
+) -> Result<ApiResponse> {
+    let article = app_state.article_service.get_own_article(&article_id, &user.id).await?;
+    let attachment = app_state.attachment_service.update(&article, &attachment_id, request).await?;
+
+    Ok(ApiResponse::ok(attachment))
+}
+
+/// 删除附件
+/// DELETE /api/articles/by-id/:id/attachments/:attachment_id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/articles/by-id/{id}/attachments/{attachment_id}",
+    tag = "articles",
+    params(("id" = String, Path), ("attachment_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_attachment(
+    State(app_state): State<Arc<AppState>>,
+    Path((article_id, attachment_id)): Path<(String, String)>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let article = app_state.article_service.get_own_article(&article_id, &user.id).await?;
+    app_state.attachment_service.delete(&article, &attachment_id).await?;
+
+    Ok(ApiResponse::message("Attachment deleted"))
+}
+
+/// 读者下载邮箱门槛附件时留下的邮箱，只有文章作者可以查看
+/// GET /api/articles/by-id/:id/attachments/leads
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/by-id/{id}/attachments/leads",
+    tag = "articles",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_attachment_leads(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let article = app_state.article_service.get_own_article(&article_id, &user.id).await?;
+    if UserId::new(&article.author_id).as_str() != UserId::new(&user.id).as_str() {
+        return Err(AppError::forbidden("Only the article author can view collected emails"));
+    }
+    let leads = app_state.attachment_service.list_leads(&article).await?;
+
+    Ok(ApiResponse::ok(leads))
+}
+
+/// 已发布文章的附件列表，`accessible` 表示当前读者能否直接下载
+/// GET /api/articles/:id/attachments
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/{slug}/attachments",
+    tag = "articles",
+    params(("slug" = String, Path)),
+    security((), ("bearer_auth" = []))
+)]
+pub async fn get_attachments(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    user: Option<Extension<User>>,
+) -> Result<ApiResponse> {
+    let article = app_state.reaction_service.resolve_article(&article_id).await?;
+    let user_id = user.as_ref().map(|u| u.0.id.as_str());
+    let attachments = app_state.attachment_service.list_for_reader(&article, user_id).await?;
+
+    Ok(ApiResponse::ok(attachments))
+}
+
+/// 申请附件的下载链接；邮箱门槛的附件需要在请求体中提供 `email`
+/// POST /api/articles/:id/attachments/:attachment_id/access
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/{slug}/attachments/{attachment_id}/access",
+    tag = "articles",
+    params(("slug" = String, Path), ("attachment_id" = String, Path)),
+    request_body = AttachmentAccessRequest,
+    security((), ("bearer_auth" = []))
+)]
+pub async fn request_attachment_download(
+    State(app_state): State<Arc<AppState>>,
+    Path((article_id, attachment_id)): Path<(String, String)>,
+    user: Option<Extension<User>>,
+    request: Option<Json<AttachmentAccessRequest>>,
+) -> Result<ApiResponse> {
+    let article = app_state.reaction_service.resolve_article(&article_id).await?;
+    let user = user.as_ref().map(|u| (u.0.id.as_str(), u.0.email.as_str()));
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+
+    let link = app_state.attachment_service
+        .request_download(&article, &attachment_id, user, request)
+        .await?;
+
+    Ok(ApiResponse::ok(link))
+}
+
+/// 文章的标题/封面实验（作者和合著者）
+/// GET /api/articles/:id/experiments
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/{slug}/experiments",
+    tag = "articles",
+    params(("slug" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_experiments(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let article = app_state.article_service.get_own_article(&article_id, &user.id).await?;
+    let experiments = app_state.experiment_service.list(&article.id).await?;
+
+    Ok(ApiResponse::ok(experiments))
+}
+
+/// 开始实验，提供 2 到 3 个标题/封面版本
+/// POST /api/articles/:id/experiments
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/{slug}/experiments",
+    tag = "articles",
+    params(("slug" = String, Path)),
+    request_body = CreateExperimentRequest,
+    security(("bearer_auth" = []))
+)]
+pub async fn create_experiment(
+    State(app_state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+    Json(request): Json<CreateExperimentRequest>,
+) -> Result<ApiResponse> {
+    debug!("Creating experiment on article {} by user: {}", article_id, user.id);
+
+    let article = app_state.article_service.get_own_article(&article_id, &user.id).await?;
+    let experiment = app_state.experiment_service.create(&article, request).await?;
+
+    Ok(ApiResponse::ok(experiment).with_message("Experiment started"))
+}
+
+/// 每个版本的曝光、点击和点击率
+/// GET /api/articles/:id/experiments/:experiment_id
+#[utoipa::path(
+    get,
+    path = "/api/blog/articles/{slug}/experiments/{experiment_id}",
+    tag = "articles",
+    params(("slug" = String, Path), ("experiment_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn get_experiment_results(
+    State(app_state): State<Arc<AppState>>,
+    Path((article_id, experiment_id)): Path<(String, String)>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let article = app_state.article_service.get_own_article(&article_id, &user.id).await?;
+    let results = app_state.experiment_service.get_results(&article.id, &experiment_id).await?;
+
+    Ok(ApiResponse::ok(results))
+}
+
+/// 采用某个版本作为文章的标题和封面，并结束实验
+/// POST /api/articles/:id/experiments/:experiment_id/promote
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/{slug}/experiments/{experiment_id}/promote",
+    tag = "articles",
+    params(("slug" = String, Path), ("experiment_id" = String, Path)),
+    request_body = PromoteVariantRequest,
+    security(("bearer_auth" = []))
+)]
+pub async fn promote_experiment_variant(
+    State(app_state): State<Arc<AppState>>,
+    Path((article_id, experiment_id)): Path<(String, String)>,
+    Extension(user): Extension<User>,
+    Json(request): Json<PromoteVariantRequest>,
+) -> Result<ApiResponse> {
+    debug!("Promoting variant {} of experiment {} by user: {}", request.variant, experiment_id, user.id);
+
+    require_permission!(app_state.auth_service, user, "article.update");
+
+    let article = app_state.article_service.get_own_article(&article_id, &user.id).await?;
+    let experiment = app_state.experiment_service
+        .promote(&article, &user.id, &experiment_id, request)
+        .await?;
+
+    Ok(ApiResponse::ok(experiment).with_message("Variant promoted"))
+}
+
+/// 结束实验，文章保持原来的标题和封面
+/// POST /api/articles/:id/experiments/:experiment_id/stop
+#[utoipa::path(
+    post,
+    path = "/api/blog/articles/{slug}/experiments/{experiment_id}/stop",
+    tag = "articles",
+    params(("slug" = String, Path), ("experiment_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn stop_experiment(
+    State(app_state): State<Arc<AppState>>,
+    Path((article_id, experiment_id)): Path<(String, String)>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let article = app_state.article_service.get_own_article(&article_id, &user.id).await?;
+    let experiment = app_state.experiment_service.stop(&article.id, &experiment_id).await?;
+
+    Ok(ApiResponse::ok(experiment).with_message("Experiment stopped"))
+}
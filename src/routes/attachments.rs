@@ -0,0 +1,59 @@
+use crate::{
+    error::{AppError, Result},
+    services::auth::User,
+    state::AppState,
+};
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::Response,
+    routing::get,
+    Extension, Router,
+};
+use std::sync::Arc;
+use tracing::{debug, error};
+use utoipa::OpenApi;
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/download/:token", get(download_attachment))
+}
+
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(download_attachment))]
+pub struct ApiDoc;
+
+/// 通过签名链接下载附件，链接由 POST /api/blog/articles/:id/attachments/:attachment_id/access 签发
+/// GET /api/blog/attachments/download/:token
+#[utoipa::path(
+    get,
+    path = "/api/blog/attachments/download/{token}",
+    tag = "attachments",
+    params(("token" = String, Path)),
+    security((), ("bearer_auth" = []))
+)]
+pub async fn download_attachment(
+    State(app_state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+    user: Option<Extension<User>>,
+) -> Result<Response<Body>> {
+    let user_id = user.as_ref().map(|u| u.0.id.as_str());
+    let (attachment, data) = app_state.attachment_service.download(&token, user_id).await?;
+    debug!("Serving attachment {} ({} bytes)", attachment.id, data.len());
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, &attachment.content_type)
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", attachment.original_filename),
+        )
+        .header(header::CACHE_CONTROL, "private, no-store")
+        .body(Body::from(data))
+        .map_err(|e| {
+            error!("Failed to build attachment response: {}", e);
+            AppError::Internal("构建文件响应失败".to_string())
+        })
+}
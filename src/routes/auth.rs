@@ -1,18 +1,21 @@
 use crate::{
     error::{AppError, Result},
-    services::auth::User,
+    models::{response::ApiResponse, two_factor::TwoFactorCodeRequest},
+    services::auth::{AuthSession, User},
     state::AppState,
 };
 use axum::{
     extract::State,
-    response::Json,
-    routing::get,
+    routing::{get, post},
+    Json,
     Router,
     Extension,
 };
-use serde_json::{json, Value};
+use validator::Validate;
+use serde_json::json;
 use std::sync::Arc;
 use tracing::{info, debug};
+use utoipa::OpenApi;
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -21,17 +24,46 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/status", get(get_auth_status))
         .route("/refresh", get(get_auth_info)) // 获取当前认证信息
         .route("/email-status", get(get_email_verification_status))
+        // 两步验证
+        .route("/2fa", get(get_two_factor_status))
+        .route("/2fa/enroll", post(enroll_two_factor))
+        .route("/2fa/confirm", post(confirm_two_factor))
+        .route("/2fa/verify", post(verify_two_factor))
+        .route("/2fa/recovery-codes", post(regenerate_recovery_codes))
+        .route("/2fa/disable", post(disable_two_factor))
 }
 
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_current_user,
+    get_auth_status,
+    get_auth_info,
+    get_email_verification_status,
+    get_two_factor_status,
+    enroll_two_factor,
+    confirm_two_factor,
+    verify_two_factor,
+    regenerate_recovery_codes,
+    disable_two_factor,
+))]
+pub struct ApiDoc;
+
 /// 获取当前用户信息
 /// GET /api/auth/me
 /// 
 /// 注意：实际的用户认证是由 Rainbow-Gateway 和 Rainbow-Auth 处理的
 /// 这个端点主要是返回通过 JWT 解析得到的用户信息
+#[utoipa::path(
+    get,
+    path = "/api/blog/auth/me",
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
 pub async fn get_current_user(
     State(app_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting current user info for user: {}", user.id);
 
     // 获取或创建用户资料（包含邮箱验证状态）
@@ -43,26 +75,27 @@ pub async fn get_current_user(
         user.display_name.clone(),
     ).await?;
 
+    // 记录活跃时间，因未活跃被停用的账户在此自动恢复
+    app_state.lifecycle_service.record_activity(&user.id).await?;
+
     // 获取用户活动统计
     let stats = app_state.user_service.get_user_stats(&user.id).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": {
-            "auth": {
-                "id": user.id,
-                "email": user.email,
-                "username": user.username,
-                "display_name": user.display_name,
-                "avatar_url": user.avatar_url,
-                "is_verified": user.is_verified,
-                "created_at": user.created_at,
-                "roles": user.roles,
-                "permissions": user.permissions,
-            },
-            "profile": profile.to_response(),
-            "activity": stats
-        }
+    Ok(ApiResponse::ok(json!({
+        "auth": {
+            "id": user.id,
+            "email": user.email,
+            "username": user.username,
+            "display_name": user.display_name,
+            "avatar_url": user.avatar_url,
+            "is_verified": user.is_verified,
+            "two_factor_verified": user.two_factor_verified,
+            "created_at": user.created_at,
+            "roles": user.roles,
+            "permissions": user.permissions,
+        },
+        "profile": profile.to_response(),
+        "activity": stats
     })))
 }
 
@@ -70,38 +103,38 @@ pub async fn get_current_user(
 /// GET /api/auth/status
 /// 
 /// 这个端点可以被未认证的用户访问，用于检查当前的认证状态
+#[utoipa::path(
+    get,
+    path = "/api/blog/auth/status",
+    tag = "auth",
+    security((), ("bearer_auth" = []))
+)]
 pub async fn get_auth_status(
     State(_app_state): State<Arc<AppState>>,
     user: Option<Extension<User>>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Checking authentication status");
 
     match user {
         Some(Extension(user)) => {
-            Ok(Json(json!({
-                "success": true,
-                "data": {
-                    "authenticated": true,
-                    "user": {
-                        "id": user.id,
-                        "email": user.email,
-                        "username": user.username,
-                        "display_name": user.display_name,
-                        "avatar_url": user.avatar_url,
-                        "is_verified": user.is_verified,
-                        "roles": user.roles,
-                    }
+            Ok(ApiResponse::ok(json!({
+                "authenticated": true,
+                "user": {
+                    "id": user.id,
+                    "email": user.email,
+                    "username": user.username,
+                    "display_name": user.display_name,
+                    "avatar_url": user.avatar_url,
+                    "is_verified": user.is_verified,
+                    "roles": user.roles,
                 }
             })))
         }
         None => {
-            Ok(Json(json!({
-                "success": true,
-                "data": {
-                    "authenticated": false,
-                    "user": null,
-                    "message": "Not authenticated. Please login through Rainbow-Gateway."
-                }
+            Ok(ApiResponse::ok(json!({
+                "authenticated": false,
+                "user": null,
+                "message": "Not authenticated. Please login through Rainbow-Gateway."
             })))
         }
     }
@@ -111,10 +144,16 @@ pub async fn get_auth_status(
 /// GET /api/auth/refresh
 /// 
 /// 用于刷新用户的认证状态和获取最新的用户信息
+#[utoipa::path(
+    get,
+    path = "/api/blog/auth/refresh",
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
 pub async fn get_auth_info(
     State(app_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Refreshing auth info for user: {}", user.id);
 
     // 获取最新的用户资料
@@ -145,36 +184,38 @@ pub async fn get_auth_info(
 
     info!("Refreshed auth info for user: {}", user.id);
 
-    Ok(Json(json!({
-        "success": true,
-        "data": {
-            "auth": {
-                "id": user.id,
-                "email": user.email,
-                "username": user.username,
-                "display_name": user.display_name,
-                "avatar_url": user.avatar_url,
-                "is_verified": user.is_verified,
-                "created_at": user.created_at,
-                "roles": user.roles,
-                "permissions": user.permissions,
-            },
-            "profile": profile.to_response(),
-            "activity": stats,
-            "config": user_config
+    Ok(ApiResponse::ok(json!({
+        "auth": {
+            "id": user.id,
+            "email": user.email,
+            "username": user.username,
+            "display_name": user.display_name,
+            "avatar_url": user.avatar_url,
+            "is_verified": user.is_verified,
+            "created_at": user.created_at,
+            "roles": user.roles,
+            "permissions": user.permissions,
         },
-        "message": "Authentication info refreshed successfully"
-    })))
+        "profile": profile.to_response(),
+        "activity": stats,
+        "config": user_config
+    })).with_message("Authentication info refreshed successfully"))
 }
 
 /// 获取邮箱验证状态
 /// GET /api/auth/email-status
 /// 
 /// 专门用于检查用户邮箱验证状态的端点
+#[utoipa::path(
+    get,
+    path = "/api/blog/auth/email-status",
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
 pub async fn get_email_verification_status(
     State(app_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting email verification status for user: {}", user.id);
 
     // 获取用户资料（包含最新的邮箱验证状态）
@@ -186,32 +227,141 @@ pub async fn get_email_verification_status(
         user.display_name.clone(),
     ).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": {
-            "user_id": user.id,
-            "email": user.email,
-            "email_verified": user.is_verified,
-            "verification_required_for": {
-                "creating_articles": !user.is_verified,
-                "commenting": !user.is_verified,
-                "following_users": false,
-                "publishing_articles": !user.is_verified
+    Ok(ApiResponse::ok(json!({
+        "user_id": user.id,
+        "email": user.email,
+        "email_verified": user.is_verified,
+        "verification_required_for": {
+            "creating_articles": !user.is_verified,
+            "commenting": !user.is_verified,
+            "following_users": false,
+            "publishing_articles": !user.is_verified
+        },
+        "rainbow_auth_url": format!("{}/api/auth", app_state.config.auth_service_url),
+        "verification_help": {
+            "message": if user.is_verified {
+                "您的邮箱已经通过验证"
+            } else {
+                "您的邮箱尚未验证，某些功能可能受限"
             },
-            "rainbow_auth_url": format!("{}/api/auth", app_state.config.auth_service_url),
-            "verification_help": {
-                "message": if user.is_verified {
-                    "您的邮箱已经通过验证"
-                } else {
-                    "您的邮箱尚未验证，某些功能可能受限"
-                },
-                "action_required": !user.is_verified,
-                "action_url": if !user.is_verified {
-                    Some(format!("{}/verify-email", app_state.config.auth_service_url))
-                } else {
-                    None
-                }
+            "action_required": !user.is_verified,
+            "action_url": if !user.is_verified {
+                Some(format!("{}/verify-email", app_state.config.auth_service_url))
+            } else {
+                None
             }
         }
     })))
-}
\ No newline at end of file
+}
+
+/// 两步验证状态
+/// GET /api/auth/2fa
+#[utoipa::path(
+    get,
+    path = "/api/blog/auth/2fa",
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+pub async fn get_two_factor_status(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let status = app_state.two_factor_service.status(&user).await?;
+    Ok(ApiResponse::ok(status))
+}
+
+/// 生成验证器密钥和二维码
+/// POST /api/auth/2fa/enroll
+#[utoipa::path(
+    post,
+    path = "/api/blog/auth/2fa/enroll",
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+pub async fn enroll_two_factor(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    debug!("User {} enrolling two-factor authentication", user.id);
+
+    let enrollment = app_state.two_factor_service.enroll(&user).await?;
+    Ok(ApiResponse::ok(enrollment))
+}
+
+/// 输入验证器上的动态码完成绑定，返回恢复码
+/// POST /api/auth/2fa/confirm
+#[utoipa::path(
+    post,
+    path = "/api/blog/auth/2fa/confirm",
+    tag = "auth",
+    request_body = TwoFactorCodeRequest,
+    security(("bearer_auth" = []))
+)]
+pub async fn confirm_two_factor(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Extension(session): Extension<AuthSession>,
+    Json(request): Json<TwoFactorCodeRequest>,
+) -> Result<ApiResponse> {
+    request.validate()?;
+
+    let codes = app_state.two_factor_service.confirm(&user, &session, &request.code).await?;
+    Ok(ApiResponse::ok(codes).with_message("两步验证已开启，请妥善保存恢复码"))
+}
+
+/// 验证当前登录会话
+/// POST /api/auth/2fa/verify
+#[utoipa::path(
+    post,
+    path = "/api/blog/auth/2fa/verify",
+    tag = "auth",
+    request_body = TwoFactorCodeRequest,
+    security(("bearer_auth" = []))
+)]
+pub async fn verify_two_factor(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Extension(session): Extension<AuthSession>,
+    Json(request): Json<TwoFactorCodeRequest>,
+) -> Result<ApiResponse> {
+    request.validate()?;
+
+    app_state.two_factor_service.verify_session(&user, &session, &request.code).await?;
+    Ok(ApiResponse::message("两步验证通过"))
+}
+
+/// 重新生成恢复码
+/// POST /api/auth/2fa/recovery-codes
+#[utoipa::path(
+    post,
+    path = "/api/blog/auth/2fa/recovery-codes",
+    tag = "auth",
+    security(("bearer_auth" = []))
+)]
+pub async fn regenerate_recovery_codes(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let codes = app_state.two_factor_service.regenerate_recovery_codes(&user).await?;
+    Ok(ApiResponse::ok(codes))
+}
+
+/// 关闭两步验证
+/// POST /api/auth/2fa/disable
+#[utoipa::path(
+    post,
+    path = "/api/blog/auth/2fa/disable",
+    tag = "auth",
+    request_body = TwoFactorCodeRequest,
+    security(("bearer_auth" = []))
+)]
+pub async fn disable_two_factor(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(request): Json<TwoFactorCodeRequest>,
+) -> Result<ApiResponse> {
+    request.validate()?;
+
+    app_state.two_factor_service.disable(&user, &request.code).await?;
+    Ok(ApiResponse::message("两步验证已关闭"))
+}
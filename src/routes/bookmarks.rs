@@ -1,6 +1,6 @@
 use crate::{
     error::Result,
-    models::bookmark::*,
+    models::{bookmark::*, response::ApiResponse},
     services::auth::User,
     state::AppState,
 };
@@ -11,11 +11,13 @@ use axum::{
     Extension, Router,
 };
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use serde_json::json;
 use std::sync::Arc;
 use tracing::debug;
+use utoipa::{IntoParams, OpenApi};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct BookmarkQuery {
     pub page: Option<i32>,
     pub limit: Option<i32>,
@@ -29,13 +31,32 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/check/:article_id", get(check_bookmark))
 }
 
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_user_bookmarks,
+    create_bookmark,
+    update_bookmark,
+    delete_bookmark,
+    delete_by_article,
+    check_bookmark,
+))]
+pub struct ApiDoc;
+
 /// Get user's bookmarks
 /// GET /api/bookmarks
+#[utoipa::path(
+    get,
+    path = "/api/blog/bookmarks",
+    tag = "bookmarks",
+    params(BookmarkQuery),
+    security(("bearer_auth" = []))
+)]
 async fn get_user_bookmarks(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Query(query): Query<BookmarkQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting bookmarks for user: {}", user.id);
 
     let bookmarks = state
@@ -43,19 +64,23 @@ async fn get_user_bookmarks(
         .get_user_bookmarks(&user.id, query.page, query.limit)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": bookmarks
-    })))
+    Ok(ApiResponse::ok(bookmarks))
 }
 
 /// Create a bookmark
 /// POST /api/bookmarks
+#[utoipa::path(
+    post,
+    path = "/api/blog/bookmarks",
+    tag = "bookmarks",
+    request_body = CreateBookmarkRequest,
+    security(("bearer_auth" = []))
+)]
 async fn create_bookmark(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Json(request): Json<CreateBookmarkRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Creating bookmark for article: {} by user: {}", request.article_id, user.id);
 
     let bookmark = state
@@ -63,21 +88,25 @@ async fn create_bookmark(
         .create_bookmark(&user.id, request)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": bookmark,
-        "message": "Bookmark created successfully"
-    })))
+    Ok(ApiResponse::ok(bookmark).with_message("Bookmark created successfully"))
 }
 
 /// Update a bookmark's note
 /// PUT /api/bookmarks/:id
+#[utoipa::path(
+    put,
+    path = "/api/blog/bookmarks/{id}",
+    tag = "bookmarks",
+    params(("id" = String, Path)),
+    request_body = UpdateBookmarkRequest,
+    security(("bearer_auth" = []))
+)]
 async fn update_bookmark(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(bookmark_id): Path<String>,
     Json(request): Json<UpdateBookmarkRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Updating bookmark: {} by user: {}", bookmark_id, user.id);
 
     let bookmark = state
@@ -85,20 +114,23 @@ async fn update_bookmark(
         .update_bookmark(&bookmark_id, &user.id, request)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": bookmark,
-        "message": "Bookmark updated successfully"
-    })))
+    Ok(ApiResponse::ok(bookmark).with_message("Bookmark updated successfully"))
 }
 
 /// Delete a bookmark
 /// DELETE /api/bookmarks/:id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/bookmarks/{id}",
+    tag = "bookmarks",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn delete_bookmark(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(bookmark_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Deleting bookmark: {} by user: {}", bookmark_id, user.id);
 
     state
@@ -106,19 +138,23 @@ async fn delete_bookmark(
         .delete_bookmark(&bookmark_id, &user.id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Bookmark deleted successfully"
-    })))
+    Ok(ApiResponse::message("Bookmark deleted successfully"))
 }
 
 /// Delete a bookmark by article ID
 /// DELETE /api/bookmarks/article/:article_id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/bookmarks/article/{article_id}",
+    tag = "bookmarks",
+    params(("article_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn delete_by_article(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(article_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Deleting bookmark for article: {} by user: {}", article_id, user.id);
 
     state
@@ -126,19 +162,23 @@ async fn delete_by_article(
         .delete_bookmark_by_article(&article_id, &user.id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Bookmark removed successfully"
-    })))
+    Ok(ApiResponse::message("Bookmark removed successfully"))
 }
 
 /// Check if an article is bookmarked
 /// GET /api/bookmarks/check/:article_id
+#[utoipa::path(
+    get,
+    path = "/api/blog/bookmarks/check/{article_id}",
+    tag = "bookmarks",
+    params(("article_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn check_bookmark(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(article_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Checking bookmark status for article: {} by user: {}", article_id, user.id);
 
     let is_bookmarked = state
@@ -146,10 +186,7 @@ async fn check_bookmark(
         .is_bookmarked(&article_id, &user.id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": {
-            "is_bookmarked": is_bookmarked
-        }
+    Ok(ApiResponse::ok(json!({
+        "is_bookmarked": is_bookmarked
     })))
 }
\ No newline at end of file
@@ -1,56 +1,212 @@
 use crate::{
     error::{AppError, Result},
-    models::comment::*,
+    models::{comment::*, response::ApiResponse},
     services::AuthService,
     state::AppState,
-    utils::middleware::OptionalAuth,
+    utils::{middleware::{client_ip, OptionalAuth}, validation::contains_link},
 };
 use axum::{
-    extract::{Path, State},
+    extract::{DefaultBodyLimit, Path, Query, State},
+    http::{header, HeaderMap},
     response::Json,
     routing::{delete, get, post, put},
     Extension, Router,
 };
-use serde_json::{json, Value};
+use serde_json::json;
 use std::sync::Arc;
+use utoipa::OpenApi;
+
+/// Disqus 导出文件的大小上限
+const DISQUS_IMPORT_MAX_BYTES: usize = 50 * 1024 * 1024;
+/// 一次查询未读数的文章数量上限
+const MAX_UNREAD_COUNT_ARTICLES: usize = 100;
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/article/:article_id", get(get_article_comments))
+        .route("/article/:article_id/read", post(mark_comments_read))
+        .route("/unread", get(get_unread_counts))
+        .route("/mentions/:id", delete(delete_mention))
         .route("/", post(create_comment))
         .route("/test", post(test_create_comment))
         .route("/:id", put(update_comment))
+        .route("/:id/replies", get(get_comment_replies))
         .route("/:id", delete(delete_comment))
         .route("/:id/clap", post(clap_comment))
         .route("/:id/clap", delete(remove_clap))
+        .route(
+            "/import/disqus",
+            post(import_disqus_comments).layer(DefaultBodyLimit::max(DISQUS_IMPORT_MAX_BYTES)),
+        )
         .layer(axum::middleware::from_fn(|req: axum::http::Request<axum::body::Body>, next: axum::middleware::Next<axum::body::Body>| async move {
             tracing::info!("Comments router: {} {}", req.method(), req.uri().path());
             next.run(req).await
         }))
 }
 
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_article_comments,
+    mark_comments_read,
+    get_unread_counts,
+    delete_mention,
+    create_comment,
+    test_create_comment,
+    update_comment,
+    get_comment_replies,
+    delete_comment,
+    clap_comment,
+    remove_clap,
+    import_disqus_comments,
+))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    get,
+    path = "/api/blog/comments/article/{article_id}",
+    tag = "comments",
+    params(("article_id" = String, Path), CommentListQuery),
+    security((), ("bearer_auth" = []))
+)]
 async fn get_article_comments(
     State(state): State<Arc<AppState>>,
     Path(article_id): Path<String>,
+    Query(query): Query<CommentListQuery>,
     OptionalAuth(user): OptionalAuth,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     let user_id = user.as_ref().map(|u| u.id.as_str());
-    let comments = state
+    let mut comments = state
         .comment_service
         .get_article_comments(&article_id, user_id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": comments
+    // 登录用户额外返回阅读进度和新评论标记
+    let read_state = match user_id {
+        Some(user_id) => {
+            let read_state = state
+                .comment_service
+                .apply_read_state(user_id, &article_id, &mut comments)
+                .await?;
+            if query.mark_read {
+                state.comment_service.mark_read(user_id, &article_id, None).await?;
+            }
+            Some(read_state)
+        }
+        None => None,
+    };
+
+    // 阅读进度按完整的评论树计算，之后再截断到请求的深度
+    state.comment_service.limit_depth(&mut comments, query.depth);
+
+    // 其他站点通过 Webmention / Pingback 发来的提及
+    let mentions = state.webmention_service.list_for_article(&article_id).await?;
+
+    Ok(ApiResponse::ok(json!({
+        "comments": comments,
+        "mentions": mentions,
+        "read_state": read_state
     })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/blog/comments/article/{article_id}/read",
+    tag = "comments",
+    params(("article_id" = String, Path)),
+    request_body = MarkCommentsReadRequest,
+    security((), ("bearer_auth" = []))
+)]
+async fn mark_comments_read(
+    State(state): State<Arc<AppState>>,
+    OptionalAuth(user): OptionalAuth,
+    Path(article_id): Path<String>,
+    request: Option<Json<MarkCommentsReadRequest>>,
+) -> Result<ApiResponse> {
+    let user = user.ok_or_else(|| AppError::unauthorized("Authentication required"))?;
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+    let last_read_at = state
+        .comment_service
+        .mark_read(&user.id, &article_id, request.read_until)
+        .await?;
+
+    Ok(ApiResponse::ok(json!({
+        "article_id": article_id,
+        "last_read_at": last_read_at
+    })))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/blog/comments/unread",
+    tag = "comments",
+    params(UnreadCountsQuery),
+    security((), ("bearer_auth" = []))
+)]
+async fn get_unread_counts(
+    State(state): State<Arc<AppState>>,
+    OptionalAuth(user): OptionalAuth,
+    Query(query): Query<UnreadCountsQuery>,
+) -> Result<ApiResponse> {
+    let user = user.ok_or_else(|| AppError::unauthorized("Authentication required"))?;
+    let article_ids: Vec<String> = query
+        .article_ids
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect();
+    if article_ids.len() > MAX_UNREAD_COUNT_ARTICLES {
+        return Err(AppError::bad_request(&format!(
+            "At most {} article IDs are allowed",
+            MAX_UNREAD_COUNT_ARTICLES
+        )));
+    }
+
+    let counts = state
+        .comment_service
+        .get_unread_counts(&user.id, &article_ids)
+        .await?;
+
+    Ok(ApiResponse::ok(counts))
+}
+
+/// 分页加载某条评论的直接回复（"加载更多回复"）
+/// GET /api/blog/comments/:id/replies?cursor=&limit=
+#[utoipa::path(
+    get,
+    path = "/api/blog/comments/{id}/replies",
+    tag = "comments",
+    params(("id" = String, Path), CommentRepliesQuery),
+    security((), ("bearer_auth" = []))
+)]
+async fn get_comment_replies(
+    State(state): State<Arc<AppState>>,
+    Path(comment_id): Path<String>,
+    Query(query): Query<CommentRepliesQuery>,
+    OptionalAuth(user): OptionalAuth,
+) -> Result<ApiResponse> {
+    let replies = state
+        .comment_service
+        .get_replies(&comment_id, user.as_ref().map(|u| u.id.as_str()), query)
+        .await?;
+
+    Ok(ApiResponse::ok(replies))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/blog/comments",
+    tag = "comments",
+    request_body = CreateCommentRequest,
+    security((), ("bearer_auth" = []))
+)]
 async fn create_comment(
     State(state): State<Arc<AppState>>,
     OptionalAuth(user): OptionalAuth,
+    headers: HeaderMap,
     Json(request): Json<CreateCommentRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     tracing::info!("create_comment handler called");
     tracing::info!("User from OptionalAuth: {:?}", user.is_some());
     
@@ -62,14 +218,19 @@ async fn create_comment(
     
     tracing::info!("User ID: {}", user.id);
     tracing::info!("Request: {:?}", request);
+
+    // 低信誉用户不允许在评论中发布链接
+    if contains_link(&request.content) && !state.reputation_service.can_post_links(&user.id).await? {
+        return Err(AppError::Authorization(
+            "Your reputation is too low to post links in comments".to_string(),
+        ));
+    }
     
-    match state.comment_service.create_comment(&user.id, request).await {
+    let client = comment_client(&headers, &user);
+    match state.comment_service.create_comment(&user.id, request, client).await {
         Ok(comment) => {
             tracing::info!("Comment created successfully: {:?}", comment);
-            Ok(Json(json!({
-                "success": true,
-                "data": comment
-            })))
+            Ok(ApiResponse::ok(comment))
         }
         Err(e) => {
             tracing::error!("Failed to create comment: {}", e);
@@ -78,11 +239,62 @@ async fn create_comment(
     }
 }
 
+fn comment_client(headers: &HeaderMap, user: &crate::services::auth::User) -> CommentClient {
+    CommentClient {
+        ip: client_ip(headers),
+        user_agent: headers
+            .get(header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string),
+        author_name: user.display_name.clone().or_else(|| user.username.clone()),
+    }
+}
+
+/// 从 Disqus XML 导出导入评论
+/// POST /api/blog/comments/import/disqus?dry_run=true
+#[utoipa::path(
+    post,
+    path = "/api/blog/comments/import/disqus",
+    tag = "comments",
+    params(CommentImportQuery),
+    security((), ("bearer_auth" = []))
+)]
+async fn import_disqus_comments(
+    State(state): State<Arc<AppState>>,
+    OptionalAuth(user): OptionalAuth,
+    Query(query): Query<CommentImportQuery>,
+    body: String,
+) -> Result<ApiResponse> {
+    let user = user.ok_or_else(|| AppError::unauthorized("Authentication required"))?;
+
+    if !user.is_verified {
+        return Err(AppError::Authorization("导入评论需要验证邮箱，请前往 Rainbow-Auth 完成邮箱验证".to_string()));
+    }
+
+    // 管理员可以导入到任意文章，其他用户只能导入到自己的文章
+    let can_import_any = state.auth_service.has_permission(&user, "article.moderate").await?;
+
+    let report = state
+        .comment_service
+        .import_disqus(&user.id, can_import_any, &body, query.dry_run)
+        .await?;
+
+    Ok(ApiResponse::ok(report))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/blog/comments/test",
+    tag = "comments",
+    request_body = CreateCommentRequest,
+    security((), ("bearer_auth" = []))
+)]
 async fn test_create_comment(
     State(state): State<Arc<AppState>>,
     OptionalAuth(user): OptionalAuth,
+    headers: HeaderMap,
     Json(request): Json<CreateCommentRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     tracing::info!("test_create_comment handler called");
     tracing::info!("User: {:?}", user);
     tracing::info!("Request: {:?}", request);
@@ -92,21 +304,26 @@ async fn test_create_comment(
     
     let comment = state
         .comment_service
-        .create_comment(&user.id, request)
+        .create_comment(&user.id, request, comment_client(&headers, &user))
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": comment
-    })))
+    Ok(ApiResponse::ok(comment))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/blog/comments/{id}",
+    tag = "comments",
+    params(("id" = String, Path)),
+    request_body = UpdateCommentRequest,
+    security((), ("bearer_auth" = []))
+)]
 async fn update_comment(
     State(state): State<Arc<AppState>>,
     OptionalAuth(user): OptionalAuth,
     Path(comment_id): Path<String>,
     Json(request): Json<UpdateCommentRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     let user = user.ok_or_else(|| AppError::unauthorized("Authentication required"))?;
     
     let comment = state
@@ -114,17 +331,21 @@ async fn update_comment(
         .update_comment(&comment_id, &user.id, request)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": comment
-    })))
+    Ok(ApiResponse::ok(comment))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/blog/comments/{id}",
+    tag = "comments",
+    params(("id" = String, Path)),
+    security((), ("bearer_auth" = []))
+)]
 async fn delete_comment(
     State(state): State<Arc<AppState>>,
     OptionalAuth(user): OptionalAuth,
     Path(comment_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     let user = user.ok_or_else(|| AppError::unauthorized("Authentication required"))?;
     
     state
@@ -132,17 +353,55 @@ async fn delete_comment(
         .delete_comment(&comment_id, &user.id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Comment deleted successfully"
-    })))
+    Ok(ApiResponse::message("Comment deleted successfully"))
+}
+
+/// 文章作者删除不想展示的提及
+#[utoipa::path(
+    delete,
+    path = "/api/blog/comments/mentions/{id}",
+    tag = "comments",
+    params(("id" = String, Path)),
+    security((), ("bearer_auth" = []))
+)]
+async fn delete_mention(
+    State(state): State<Arc<AppState>>,
+    OptionalAuth(user): OptionalAuth,
+    Path(mention_id): Path<String>,
+) -> Result<ApiResponse> {
+    let user = user.ok_or_else(|| AppError::unauthorized("Authentication required"))?;
+
+    let mention = state
+        .webmention_service
+        .get_mention(&mention_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Mention"))?;
+    let article = state
+        .articles()?
+        .get_article_by_id(&mention.article_id)
+        .await?
+        .ok_or_else(|| AppError::not_found("Article"))?;
+    if article.author_id != user.id {
+        return Err(AppError::forbidden("Only the article author can remove mentions"));
+    }
+
+    state.webmention_service.delete_mention(&mention.id).await?;
+
+    Ok(ApiResponse::message("Mention deleted successfully"))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/blog/comments/{id}/clap",
+    tag = "comments",
+    params(("id" = String, Path)),
+    security((), ("bearer_auth" = []))
+)]
 async fn clap_comment(
     State(state): State<Arc<AppState>>,
     OptionalAuth(user): OptionalAuth,
     Path(comment_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     let user = user.ok_or_else(|| AppError::unauthorized("Authentication required"))?;
     
     state
@@ -150,17 +409,21 @@ async fn clap_comment(
         .clap_comment(&comment_id, &user.id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Comment clapped successfully"
-    })))
+    Ok(ApiResponse::message("Comment clapped successfully"))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/blog/comments/{id}/clap",
+    tag = "comments",
+    params(("id" = String, Path)),
+    security((), ("bearer_auth" = []))
+)]
 async fn remove_clap(
     State(state): State<Arc<AppState>>,
     OptionalAuth(user): OptionalAuth,
     Path(comment_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     let user = user.ok_or_else(|| AppError::unauthorized("Authentication required"))?;
     
     state
@@ -168,8 +431,5 @@ async fn remove_clap(
         .remove_clap(&comment_id, &user.id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Clap removed successfully"
-    })))
+    Ok(ApiResponse::message("Clap removed successfully"))
 }
\ No newline at end of file
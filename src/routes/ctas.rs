@@ -0,0 +1,227 @@
+use crate::{
+    error::Result,
+    models::{cta::*, response::ApiResponse},
+    services::auth::User,
+    state::AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    response::{Json, Redirect},
+    routing::{get, post},
+    Extension, Router,
+};
+use std::sync::Arc;
+use tracing::debug;
+use utoipa::OpenApi;
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(list_ctas).post(create_cta))
+        .route("/:id", get(get_cta).put(update_cta).delete(delete_cta))
+        .route("/:id/leads", get(list_cta_leads))
+        .route("/:id/events", post(record_cta_event))
+        .route("/:id/email", post(capture_cta_email))
+        .route("/:id/go", get(follow_cta_link))
+}
+
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_ctas,
+    create_cta,
+    get_cta,
+    update_cta,
+    delete_cta,
+    list_cta_leads,
+    record_cta_event,
+    capture_cta_email,
+    follow_cta_link,
+))]
+pub struct ApiDoc;
+
+/// 列出当前用户的 CTA
+/// GET /api/blog/ctas
+#[utoipa::path(
+    get,
+    path = "/api/blog/ctas",
+    tag = "ctas",
+    security(("bearer_auth" = []))
+)]
+async fn list_ctas(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    debug!("Listing CTAs of user: {}", user.id);
+
+    let ctas = state.cta_service.list(&user.id).await?;
+
+    Ok(ApiResponse::ok(ctas))
+}
+
+/// 创建 CTA，正文中用 `{{cta key}}` 引用
+/// POST /api/blog/ctas
+#[utoipa::path(
+    post,
+    path = "/api/blog/ctas",
+    tag = "ctas",
+    request_body = CreateCtaRequest,
+    security(("bearer_auth" = []))
+)]
+async fn create_cta(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(request): Json<CreateCtaRequest>,
+) -> Result<ApiResponse> {
+    debug!("Creating CTA {} for user: {}", request.key, user.id);
+
+    let cta = state.cta_service.create(&user.id, request).await?;
+
+    Ok(ApiResponse::ok(cta).with_message("Call to action created"))
+}
+
+/// GET /api/blog/ctas/:id
+#[utoipa::path(
+    get,
+    path = "/api/blog/ctas/{id}",
+    tag = "ctas",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_cta(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<String>,
+) -> Result<ApiResponse> {
+    let cta = state.cta_service.get(&user.id, &id).await?;
+
+    Ok(ApiResponse::ok(cta))
+}
+
+/// 修改后所有引用它的文章立即生效
+/// PUT /api/blog/ctas/:id
+#[utoipa::path(
+    put,
+    path = "/api/blog/ctas/{id}",
+    tag = "ctas",
+    params(("id" = String, Path)),
+    request_body = UpdateCtaRequest,
+    security(("bearer_auth" = []))
+)]
+async fn update_cta(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateCtaRequest>,
+) -> Result<ApiResponse> {
+    debug!("Updating CTA {} of user: {}", id, user.id);
+
+    let cta = state.cta_service.update(&user.id, &id, request).await?;
+
+    Ok(ApiResponse::ok(cta).with_message("Call to action updated"))
+}
+
+/// DELETE /api/blog/ctas/:id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/ctas/{id}",
+    tag = "ctas",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn delete_cta(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<String>,
+) -> Result<ApiResponse> {
+    debug!("Deleting CTA {} of user: {}", id, user.id);
+
+    state.cta_service.delete(&user.id, &id).await?;
+
+    Ok(ApiResponse::message("Call to action deleted"))
+}
+
+/// 邮箱收集块收到的邮箱
+/// GET /api/blog/ctas/:id/leads
+#[utoipa::path(
+    get,
+    path = "/api/blog/ctas/{id}/leads",
+    tag = "ctas",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn list_cta_leads(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<String>,
+) -> Result<ApiResponse> {
+    let leads = state.cta_service.list_leads(&user.id, &id).await?;
+
+    Ok(ApiResponse::ok(leads))
+}
+
+/// 读者点击 CTA，或关注、订阅成功后上报转化
+/// POST /api/blog/ctas/:id/events
+#[utoipa::path(
+    post,
+    path = "/api/blog/ctas/{id}/events",
+    tag = "ctas",
+    params(("id" = String, Path)),
+    request_body = CtaEventRequest,
+    security((), ("bearer_auth" = []))
+)]
+async fn record_cta_event(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    user: Option<Extension<User>>,
+    Json(request): Json<CtaEventRequest>,
+) -> Result<ApiResponse> {
+    let user_id = user.as_ref().map(|u| u.0.id.as_str());
+    state.cta_service.record_event(&id, request, user_id).await?;
+
+    Ok(ApiResponse::message("Event recorded"))
+}
+
+/// 邮箱收集块提交
+/// POST /api/blog/ctas/:id/email
+#[utoipa::path(
+    post,
+    path = "/api/blog/ctas/{id}/email",
+    tag = "ctas",
+    params(("id" = String, Path)),
+    request_body = CtaEmailRequest,
+    security((), ("bearer_auth" = []))
+)]
+async fn capture_cta_email(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    user: Option<Extension<User>>,
+    Json(request): Json<CtaEmailRequest>,
+) -> Result<ApiResponse> {
+    let user_id = user.as_ref().map(|u| u.0.id.as_str());
+    state.cta_service.capture_email(&id, request, user_id).await?;
+
+    Ok(ApiResponse::message("Thanks for signing up"))
+}
+
+/// 外部链接 CTA 的跳转，计入点击
+/// GET /api/blog/ctas/:id/go?article=
+#[utoipa::path(
+    get,
+    path = "/api/blog/ctas/{id}/go",
+    tag = "ctas",
+    params(("id" = String, Path), CtaRedirectQuery),
+    security((), ("bearer_auth" = []))
+)]
+async fn follow_cta_link(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(query): Query<CtaRedirectQuery>,
+    user: Option<Extension<User>>,
+) -> Result<Redirect> {
+    let user_id = user.as_ref().map(|u| u.0.id.as_str());
+    let url = state.cta_service
+        .redirect_target(&id, query.article.as_deref(), user_id)
+        .await?;
+
+    Ok(Redirect::temporary(&url))
+}
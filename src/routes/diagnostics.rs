@@ -1,19 +1,63 @@
 use crate::{
-    error::{AppError, Result},
+    error::{AppError, ErrorCode, Result},
+    models::{db_health::DbReportQuery, response::ApiResponse},
+    services::auth::User,
     state::AppState,
 };
-use axum::{routing::get, extract::State, response::Json, Router};
+use axum::{routing::get, extract::{Query, State}, Extension, Router};
 use serde_json::{json, Value};
 use std::sync::Arc;
 use tracing::debug;
+use utoipa::OpenApi;
 
 pub fn router() -> Router<Arc<AppState>> {
-    Router::new().route("/", get(diagnostics))
+    Router::new()
+        .route("/", get(diagnostics))
+        .route("/db-report", get(db_report))
+}
+
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(diagnostics, db_report))]
+pub struct ApiDoc;
+
+/// 数据库健康报告（管理员）。默认返回最近一次定期检查的结果，还没有报告或 `refresh=true` 时立即检查
+/// GET /api/blog/diagnostics/db-report
+#[utoipa::path(
+    get,
+    path = "/api/blog/diagnostics/db-report",
+    tag = "diagnostics",
+    params(DbReportQuery),
+    security(("bearer_auth" = []))
+)]
+async fn db_report(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Query(query): Query<DbReportQuery>,
+) -> Result<ApiResponse> {
+    if !user.permissions.contains(&"admin.diagnostics".to_string()) {
+        return Err(AppError::coded(ErrorCode::AdminPermissionRequired, "Admin permission required"));
+    }
+
+    let report = match state.db_health_service.latest_report().await? {
+        Some(report) if !query.refresh => report,
+        _ => {
+            debug!("Running database integrity check for user: {}", user.id);
+            state.db_health_service.run_integrity_check().await?
+        }
+    };
+
+    Ok(ApiResponse::ok(report))
 }
 
 /// 诊断端点（仅开发环境可用）
 /// GET /api/blog/diagnostics
-async fn diagnostics(State(state): State<Arc<AppState>>) -> Result<Json<Value>> {
+#[utoipa::path(
+    get,
+    path = "/api/blog/diagnostics",
+    tag = "diagnostics"
+)]
+async fn diagnostics(State(state): State<Arc<AppState>>) -> Result<ApiResponse> {
     if !state.is_development() {
         return Err(AppError::forbidden("Diagnostics endpoint is only available in development"));
     }
@@ -53,23 +97,20 @@ async fn diagnostics(State(state): State<Arc<AppState>>) -> Result<Json<Value>>
         Err(_) => Vec::new(),
     };
 
-    Ok(Json(json!({
-        "success": true,
-        "data": {
-            "database": {
-                "namespace": ns,
-                "name": db,
-                "url": url,
-            },
-            "counts": {
-                "tag": tag_count,
-                "article_tag": article_tag_count,
-                "article": article_count,
-                "publication": publication_count,
-            },
-            "samples": {
-                "tags": sample_tags,
-            }
+    Ok(ApiResponse::ok(json!({
+        "database": {
+            "namespace": ns,
+            "name": db,
+            "url": url,
+        },
+        "counts": {
+            "tag": tag_count,
+            "article_tag": article_tag_count,
+            "article": article_count,
+            "publication": publication_count,
+        },
+        "samples": {
+            "tags": sample_tags,
         }
     })))
 }
@@ -1,7 +1,6 @@
 use crate::{
     error::{AppError, Result},
-    models::domain::*,
-    models::publication::MemberRole,
+    models::{domain::*, response::ApiResponse},
     services::auth::User,
     state::AppState,
     utils::middleware::OptionalAuth,
@@ -12,9 +11,10 @@ use axum::{
     routing::{delete, get, post, put},
     Extension, Router,
 };
-use serde_json::{json, Value};
+use serde_json::json;
 use std::sync::Arc;
 use tracing::debug;
+use utoipa::OpenApi;
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -25,18 +25,80 @@ pub fn router() -> Router<Arc<AppState>> {
         // Domain-specific routes
         .route("/domains/:domain_id", get(get_domain_details).put(update_domain).delete(delete_domain))
         .route("/domains/:domain_id/verify", post(verify_domain))
+        .route("/domains/:domain_id/cutover", get(get_cutover_status))
+        .route("/domains/:domain_id/email", get(get_email_config).post(configure_email).delete(remove_email_config))
+        .route("/domains/:domain_id/email/verify", post(verify_email))
         .route("/domains/check-availability", post(check_domain_availability))
         .route("/domains/resolve/:domain", get(resolve_domain))
 }
 
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    create_subdomain,
+    add_custom_domain,
+    list_publication_domains,
+    get_domain_details,
+    update_domain,
+    delete_domain,
+    verify_domain,
+    get_cutover_status,
+    get_email_config,
+    configure_email,
+    remove_email_config,
+    verify_email,
+    check_domain_availability,
+    resolve_domain,
+))]
+pub struct ApiDoc;
+
+/// ACME HTTP-01 challenge responder, mounted at the root of every host
+#[cfg(feature = "acme")]
+pub fn acme_router() -> Router<Arc<AppState>> {
+    Router::new().route("/.well-known/acme-challenge/:token", get(acme_http_challenge))
+}
+
+/// ACME 验证路由的接口文档
+#[cfg(feature = "acme")]
+#[derive(OpenApi)]
+#[openapi(paths(acme_http_challenge))]
+pub struct AcmeApiDoc;
+
+/// Serve the key authorization for a pending HTTP-01 challenge
+/// GET /.well-known/acme-challenge/:token
+#[cfg(feature = "acme")]
+#[utoipa::path(
+    get,
+    path = "/.well-known/acme-challenge/{token}",
+    tag = "domains",
+    params(("token" = String, Path))
+)]
+async fn acme_http_challenge(
+    State(state): State<Arc<AppState>>,
+    Path(token): Path<String>,
+) -> Result<String> {
+    state.domain_service
+        .acme_http_challenge(&token)
+        .await?
+        .ok_or_else(|| AppError::not_found("ACME challenge"))
+}
+
 /// Create subdomain for publication
 /// POST /api/publications/:id/domains/subdomain
+#[utoipa::path(
+    post,
+    path = "/api/blog/domains/publications/{id}/domains/subdomain",
+    tag = "domains",
+    params(("id" = String, Path)),
+    request_body = CreateSubdomainRequest,
+    security(("bearer_auth" = []))
+)]
 async fn create_subdomain(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(publication_id): Path<String>,
     Json(request): Json<CreateSubdomainRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Creating subdomain for publication: {} by user: {}", publication_id, user.id);
 
     // Validate the subdomain request
@@ -58,21 +120,25 @@ async fn create_subdomain(
         .create_subdomain(&publication_id, request)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": domain_response,
-        "message": "Subdomain created successfully"
-    })))
+    Ok(ApiResponse::ok(domain_response).with_message("Subdomain created successfully"))
 }
 
 /// Add custom domain to publication
 /// POST /api/publications/:id/domains/custom
+#[utoipa::path(
+    post,
+    path = "/api/blog/domains/publications/{id}/domains/custom",
+    tag = "domains",
+    params(("id" = String, Path)),
+    request_body = AddCustomDomainRequest,
+    security(("bearer_auth" = []))
+)]
 async fn add_custom_domain(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(publication_id): Path<String>,
     Json(request): Json<AddCustomDomainRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Adding custom domain for publication: {} by user: {}", publication_id, user.id);
 
     // Validate the custom domain request
@@ -94,20 +160,23 @@ async fn add_custom_domain(
         .add_custom_domain(&publication_id, request)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": domain_response,
-        "message": "Custom domain added successfully. Please configure DNS records for verification."
-    })))
+    Ok(ApiResponse::ok(domain_response).with_message("Custom domain added successfully. Please configure DNS records for verification."))
 }
 
 /// List domains for a publication
 /// GET /api/publications/:id/domains
+#[utoipa::path(
+    get,
+    path = "/api/blog/domains/publications/{id}/domains",
+    tag = "domains",
+    params(("id" = String, Path)),
+    security((), ("bearer_auth" = []))
+)]
 async fn list_publication_domains(
     State(state): State<Arc<AppState>>,
     Path(publication_id): Path<String>,
     OptionalAuth(user): OptionalAuth,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Listing domains for publication: {}", publication_id);
 
     // Get domains for the publication
@@ -116,19 +185,23 @@ async fn list_publication_domains(
         .get_publication_domains(&publication_id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": domains
-    })))
+    Ok(ApiResponse::ok(domains))
 }
 
 /// Get domain details
 /// GET /api/domains/:domain_id
+#[utoipa::path(
+    get,
+    path = "/api/blog/domains/domains/{domain_id}",
+    tag = "domains",
+    params(("domain_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn get_domain_details(
     State(state): State<Arc<AppState>>,
     Path(domain_id): Path<String>,
     Extension(user): Extension<User>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting domain details: {} for user: {}", domain_id, user.id);
 
     // Get the domain
@@ -146,19 +219,23 @@ async fn get_domain_details(
         ));
     }
 
-    Ok(Json(json!({
-        "success": true,
-        "data": domain
-    })))
+    Ok(ApiResponse::ok(domain))
 }
 
 /// Trigger domain verification
 /// POST /api/domains/:domain_id/verify
+#[utoipa::path(
+    post,
+    path = "/api/blog/domains/domains/{domain_id}/verify",
+    tag = "domains",
+    params(("domain_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn verify_domain(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(domain_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Verifying domain: {} by user: {}", domain_id, user.id);
 
     // Get the domain to check permissions
@@ -182,24 +259,225 @@ async fn verify_domain(
         .verify_domain(&domain_id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": verification_response,
-        "message": if verification_response.verified {
-            "Domain verified successfully"
-        } else {
-            "Domain verification in progress. Please check DNS records."
-        }
-    })))
+    Ok(ApiResponse::ok(verification_response).with_message(if verification_response.verified {
+        "Domain verified successfully"
+    } else {
+        "Domain verification in progress. Please check DNS records."
+    }))
+}
+
+/// Get the transfer-in cutover status of a domain
+/// GET /api/domains/:domain_id/cutover
+#[utoipa::path(
+    get,
+    path = "/api/blog/domains/domains/{domain_id}/cutover",
+    tag = "domains",
+    params(("domain_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_cutover_status(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(domain_id): Path<String>,
+) -> Result<ApiResponse> {
+    debug!("Getting cutover status for domain: {} by user: {}", domain_id, user.id);
+
+    // Get the domain to check permissions
+    let domain = state
+        .domain_service
+        .get_domain(&domain_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Domain not found".to_string()))?;
+
+    // Check if user has permission to manage this domain
+    let has_permission = check_publication_permission(&state, &domain.domain.publication_id.to_string(), &user.id).await?;
+    if !has_permission {
+        return Err(AppError::Authorization(
+            "You don't have permission to view this domain".to_string()
+        ));
+    }
+
+    let cutover_status = state
+        .domain_service
+        .get_cutover_status(&domain_id)
+        .await?;
+
+    Ok(ApiResponse::ok(cutover_status))
+}
+
+/// Get the email sending configuration of a domain
+/// GET /api/domains/:domain_id/email
+#[utoipa::path(
+    get,
+    path = "/api/blog/domains/domains/{domain_id}/email",
+    tag = "domains",
+    params(("domain_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_email_config(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(domain_id): Path<String>,
+) -> Result<ApiResponse> {
+    debug!("Getting email config for domain: {} by user: {}", domain_id, user.id);
+
+    let domain = state
+        .domain_service
+        .get_domain(&domain_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Domain not found".to_string()))?;
+
+    let has_permission = check_publication_permission(&state, &domain.domain.publication_id.to_string(), &user.id).await?;
+    if !has_permission {
+        return Err(AppError::Authorization(
+            "You don't have permission to view this domain".to_string()
+        ));
+    }
+
+    let email_config = state
+        .domain_service
+        .get_email_config(&domain_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Email sending is not configured for this domain".to_string()))?;
+
+    Ok(ApiResponse::ok(email_config))
+}
+
+/// Configure newsletter sending from a custom domain
+/// POST /api/domains/:domain_id/email
+#[utoipa::path(
+    post,
+    path = "/api/blog/domains/domains/{domain_id}/email",
+    tag = "domains",
+    params(("domain_id" = String, Path)),
+    request_body = ConfigureDomainEmailRequest,
+    security(("bearer_auth" = []))
+)]
+async fn configure_email(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(domain_id): Path<String>,
+    Json(request): Json<ConfigureDomainEmailRequest>,
+) -> Result<ApiResponse> {
+    debug!("Configuring email for domain: {} by user: {}", domain_id, user.id);
+
+    if let Err(errors) = request.validate() {
+        return Err(AppError::Validation(errors.join(", ")));
+    }
+
+    let domain = state
+        .domain_service
+        .get_domain(&domain_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Domain not found".to_string()))?;
+
+    let has_permission = check_publication_permission(&state, &domain.domain.publication_id.to_string(), &user.id).await?;
+    if !has_permission {
+        return Err(AppError::Authorization(
+            "You don't have permission to manage this domain".to_string()
+        ));
+    }
+
+    let email_config = state
+        .domain_service
+        .configure_email(&domain_id, request)
+        .await?;
+
+    Ok(ApiResponse::ok(email_config).with_message("Email sending configured. Please publish the DNS records and verify them."))
+}
+
+/// Verify the SPF, DKIM and return-path records of a domain
+/// POST /api/domains/:domain_id/email/verify
+#[utoipa::path(
+    post,
+    path = "/api/blog/domains/domains/{domain_id}/email/verify",
+    tag = "domains",
+    params(("domain_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn verify_email(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(domain_id): Path<String>,
+) -> Result<ApiResponse> {
+    debug!("Verifying email records for domain: {} by user: {}", domain_id, user.id);
+
+    let domain = state
+        .domain_service
+        .get_domain(&domain_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Domain not found".to_string()))?;
+
+    let has_permission = check_publication_permission(&state, &domain.domain.publication_id.to_string(), &user.id).await?;
+    if !has_permission {
+        return Err(AppError::Authorization(
+            "You don't have permission to verify this domain".to_string()
+        ));
+    }
+
+    let verification_response = state
+        .domain_service
+        .verify_email(&domain_id)
+        .await?;
+
+    Ok(ApiResponse::ok(verification_response).with_message(if verification_response.verified {
+        "Email records verified. Newsletters will be sent from this domain."
+    } else {
+        "Email records not verified yet. Please check DNS records."
+    }))
+}
+
+/// Stop sending newsletters from a domain
+/// DELETE /api/domains/:domain_id/email
+#[utoipa::path(
+    delete,
+    path = "/api/blog/domains/domains/{domain_id}/email",
+    tag = "domains",
+    params(("domain_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn remove_email_config(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(domain_id): Path<String>,
+) -> Result<ApiResponse> {
+    debug!("Removing email config for domain: {} by user: {}", domain_id, user.id);
+
+    let domain = state
+        .domain_service
+        .get_domain(&domain_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Domain not found".to_string()))?;
+
+    let has_permission = check_publication_permission(&state, &domain.domain.publication_id.to_string(), &user.id).await?;
+    if !has_permission {
+        return Err(AppError::Authorization(
+            "You don't have permission to manage this domain".to_string()
+        ));
+    }
+
+    state
+        .domain_service
+        .remove_email_config(&domain_id)
+        .await?;
+
+    Ok(ApiResponse::message("Email sending configuration removed"))
 }
 
 /// Delete domain
 /// DELETE /api/domains/:domain_id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/domains/domains/{domain_id}",
+    tag = "domains",
+    params(("domain_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn delete_domain(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(domain_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Deleting domain: {} by user: {}", domain_id, user.id);
 
     // Get the domain to check permissions
@@ -223,20 +501,25 @@ async fn delete_domain(
         .delete_domain(&domain_id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Domain deleted successfully"
-    })))
+    Ok(ApiResponse::message("Domain deleted successfully"))
 }
 
 /// Update domain settings
 /// PUT /api/domains/:domain_id
+#[utoipa::path(
+    put,
+    path = "/api/blog/domains/domains/{domain_id}",
+    tag = "domains",
+    params(("domain_id" = String, Path)),
+    request_body = UpdateDomainRequest,
+    security(("bearer_auth" = []))
+)]
 async fn update_domain(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(domain_id): Path<String>,
     Json(request): Json<UpdateDomainRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Updating domain: {} by user: {}", domain_id, user.id);
 
     // Get the domain to check permissions
@@ -260,37 +543,43 @@ async fn update_domain(
         .update_domain(&domain_id, request)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": updated_domain,
-        "message": "Domain updated successfully"
-    })))
+    Ok(ApiResponse::ok(updated_domain).with_message("Domain updated successfully"))
 }
 
 /// Check domain availability
 /// POST /api/domains/check-availability
+#[utoipa::path(
+    post,
+    path = "/api/blog/domains/domains/check-availability",
+    tag = "domains",
+    request_body = CheckDomainAvailabilityRequest,
+    security(("bearer_auth" = []))
+)]
 async fn check_domain_availability(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Json(request): Json<CheckDomainAvailabilityRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Checking domain availability: {} for user: {}", request.domain, user.id);
 
     // Check if the domain is available
     let availability = check_domain_available(&state, &request.domain, request.domain_type).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": availability
-    })))
+    Ok(ApiResponse::ok(availability))
 }
 
 /// Resolve domain to publication
 /// GET /api/domains/resolve/:domain
+#[utoipa::path(
+    get,
+    path = "/api/blog/domains/domains/resolve/{domain}",
+    tag = "domains",
+    params(("domain" = String, Path))
+)]
 async fn resolve_domain(
     State(state): State<Arc<AppState>>,
     Path(domain): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Resolving domain: {}", domain);
 
     // Find publication by domain
@@ -300,12 +589,9 @@ async fn resolve_domain(
         .await?;
 
     match publication_id {
-        Some(pub_id) => Ok(Json(json!({
-            "success": true,
-            "data": {
-                "publication_id": pub_id,
-                "domain": domain
-            }
+        Some(pub_id) => Ok(ApiResponse::ok(json!({
+            "publication_id": pub_id,
+            "domain": domain
         }))),
         None => Err(AppError::NotFound("No publication found for this domain".to_string()))
     }
@@ -317,26 +603,10 @@ async fn check_publication_permission(
     publication_id: &str,
     user_id: &str,
 ) -> Result<bool> {
-    // Get publication to check ownership
-    let publication = state
+    state
         .publication_service
-        .get_publication(publication_id, Some(user_id))
-        .await?
-        .ok_or_else(|| AppError::NotFound("Publication not found".to_string()))?;
-
-    // Check if user is owner or editor
-    if publication.publication.owner_id == user_id {
-        return Ok(true);
-    }
-
-    // Check if user is an editor
-    if let Some(member_role) = publication.member_role {
-        if member_role == MemberRole::Editor || member_role == MemberRole::Owner {
-            return Ok(true);
-        }
-    }
-
-    Ok(false)
+        .has_permission(publication_id, user_id, "publication.manage_domains")
+        .await
 }
 
 /// Helper function to check domain availability
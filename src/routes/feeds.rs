@@ -0,0 +1,270 @@
+use crate::{
+    error::{AppError, Result},
+    models::{article::Article, publication::Publication},
+    state::AppState,
+    utils::{
+        feed::{render_atom, render_rss, FeedChannel, FeedEntry},
+        http_cache,
+        middleware::RequiredPublicationContext,
+    },
+};
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::debug;
+use utoipa::OpenApi;
+
+/// 每个订阅源输出的文章数
+const FEED_ITEM_LIMIT: usize = 20;
+
+#[derive(Clone, Copy)]
+enum FeedFormat {
+    Rss,
+    Atom,
+}
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/publications/:slug/rss", get(publication_rss))
+        .route("/publications/:slug/atom", get(publication_atom))
+        .route("/users/:username/rss", get(author_rss))
+        .route("/users/:username/atom", get(author_atom))
+}
+
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    publication_rss,
+    publication_atom,
+    author_rss,
+    author_atom,
+    domain_rss,
+    domain_atom,
+))]
+pub struct ApiDoc;
+
+/// 出版物 RSS 订阅源
+/// GET /api/blog/feeds/publications/:slug/rss
+#[utoipa::path(
+    get,
+    path = "/api/blog/feeds/publications/{slug}/rss",
+    tag = "feeds",
+    params(("slug" = String, Path))
+)]
+pub async fn publication_rss(
+    State(state): State<Arc<AppState>>,
+    Path(slug): Path<String>,
+) -> Result<Response> {
+    let publication = find_publication(&state, &slug).await?;
+    let base_url = frontend_base(&state);
+    let home_url = format!("{}/publications/{}", base_url, slug);
+    let feed_url = format!("{}/api/blog/feeds/publications/{}/rss", base_url, slug);
+    publication_feed(&state, publication, &base_url, home_url, feed_url, FeedFormat::Rss).await
+}
+
+/// 出版物 Atom 订阅源
+/// GET /api/blog/feeds/publications/:slug/atom
+#[utoipa::path(
+    get,
+    path = "/api/blog/feeds/publications/{slug}/atom",
+    tag = "feeds",
+    params(("slug" = String, Path))
+)]
+pub async fn publication_atom(
+    State(state): State<Arc<AppState>>,
+    Path(slug): Path<String>,
+) -> Result<Response> {
+    let publication = find_publication(&state, &slug).await?;
+    let base_url = frontend_base(&state);
+    let home_url = format!("{}/publications/{}", base_url, slug);
+    let feed_url = format!("{}/api/blog/feeds/publications/{}/atom", base_url, slug);
+    publication_feed(&state, publication, &base_url, home_url, feed_url, FeedFormat::Atom).await
+}
+
+/// 通过自定义域名/子域名访问的出版物 RSS 订阅源
+/// GET /feed.xml
+#[utoipa::path(
+    get,
+    path = "/feed.xml",
+    tag = "feeds"
+)]
+pub async fn domain_rss(
+    State(state): State<Arc<AppState>>,
+    RequiredPublicationContext(context): RequiredPublicationContext,
+) -> Result<Response> {
+    let base_url = format!("https://{}", context.domain);
+    let feed_url = format!("{}/feed.xml", base_url);
+    publication_feed(&state, context.publication, &base_url, base_url.clone(), feed_url, FeedFormat::Rss).await
+}
+
+/// 通过自定义域名/子域名访问的出版物 Atom 订阅源
+/// GET /atom.xml
+#[utoipa::path(
+    get,
+    path = "/atom.xml",
+    tag = "feeds"
+)]
+pub async fn domain_atom(
+    State(state): State<Arc<AppState>>,
+    RequiredPublicationContext(context): RequiredPublicationContext,
+) -> Result<Response> {
+    let base_url = format!("https://{}", context.domain);
+    let feed_url = format!("{}/atom.xml", base_url);
+    publication_feed(&state, context.publication, &base_url, base_url.clone(), feed_url, FeedFormat::Atom).await
+}
+
+/// 作者 RSS 订阅源
+/// GET /api/blog/feeds/users/:username/rss
+#[utoipa::path(
+    get,
+    path = "/api/blog/feeds/users/{username}/rss",
+    tag = "feeds",
+    params(("username" = String, Path))
+)]
+pub async fn author_rss(
+    State(state): State<Arc<AppState>>,
+    Path(username): Path<String>,
+) -> Result<Response> {
+    author_feed(&state, &username, FeedFormat::Rss).await
+}
+
+/// 作者 Atom 订阅源
+/// GET /api/blog/feeds/users/:username/atom
+#[utoipa::path(
+    get,
+    path = "/api/blog/feeds/users/{username}/atom",
+    tag = "feeds",
+    params(("username" = String, Path))
+)]
+pub async fn author_atom(
+    State(state): State<Arc<AppState>>,
+    Path(username): Path<String>,
+) -> Result<Response> {
+    author_feed(&state, &username, FeedFormat::Atom).await
+}
+
+async fn publication_feed(
+    state: &AppState,
+    publication: Publication,
+    base_url: &str,
+    home_url: String,
+    feed_url: String,
+    format: FeedFormat,
+) -> Result<Response> {
+    debug!("Rendering feed for publication: {}", publication.slug);
+
+    let articles = state.article_service
+        .get_feed_articles(None, Some(&publication.id), FEED_ITEM_LIMIT)
+        .await?;
+
+    // 出版物订阅源中的文章可能来自多位作者，笔名文章显示笔名
+    let mut author_names = HashMap::new();
+    for article in &articles {
+        if !author_names.contains_key(article.public_author_id()) {
+            let name = state.article_service.get_public_author(article).await?.display_name;
+            author_names.insert(article.public_author_id().to_string(), Some(name));
+        }
+    }
+
+    let entries = articles
+        .iter()
+        .map(|article| {
+            FeedEntry::from_article(
+                article,
+                format!("{}/articles/{}", base_url, article.slug),
+                author_names.get(article.public_author_id()).cloned().flatten(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let channel = FeedChannel {
+        title: publication.name,
+        link: home_url,
+        feed_url,
+        description: publication.description.or(publication.tagline),
+        image_url: publication.logo_url,
+        updated_at: last_updated(&articles),
+    };
+
+    Ok(feed_response(&channel, &entries, format))
+}
+
+async fn author_feed(state: &AppState, username: &str, format: FeedFormat) -> Result<Response> {
+    debug!("Rendering feed for author: {}", username);
+
+    let profile = state.user_service.get_profile_by_username(username).await?
+        .filter(|profile| !profile.is_suspended)
+        .ok_or_else(|| AppError::not_found("User"))?;
+
+    let articles = state.article_service
+        .get_feed_articles(Some(&profile.user_id), None, FEED_ITEM_LIMIT)
+        .await?;
+
+    let base_url = frontend_base(state);
+    let entries = articles
+        .iter()
+        .map(|article| {
+            FeedEntry::from_article(
+                article,
+                format!("{}/articles/{}", base_url, article.slug),
+                Some(profile.display_name.clone()),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let suffix = match format {
+        FeedFormat::Rss => "rss",
+        FeedFormat::Atom => "atom",
+    };
+    let channel = FeedChannel {
+        title: profile.display_name.clone(),
+        link: format!("{}/@{}", base_url, profile.username),
+        feed_url: format!("{}/api/blog/feeds/users/{}/{}", base_url, profile.username, suffix),
+        description: profile.bio.clone(),
+        image_url: profile.avatar_url.clone(),
+        updated_at: last_updated(&articles),
+    };
+
+    Ok(feed_response(&channel, &entries, format))
+}
+
+async fn find_publication(state: &AppState, slug: &str) -> Result<Publication> {
+    state.publication_service.get_publication(slug, None).await?
+        .map(|response| response.publication)
+        .ok_or_else(|| AppError::not_found("Publication"))
+}
+
+fn frontend_base(state: &AppState) -> String {
+    state.config.frontend_url.trim_end_matches('/').to_string()
+}
+
+fn last_updated(articles: &[Article]) -> chrono::DateTime<Utc> {
+    articles
+        .iter()
+        .map(|article| article.updated_at)
+        .max()
+        .unwrap_or_else(Utc::now)
+}
+
+fn feed_response(channel: &FeedChannel, entries: &[FeedEntry], format: FeedFormat) -> Response {
+    let (content_type, body) = match format {
+        FeedFormat::Rss => ("application/rss+xml; charset=utf-8", render_rss(channel, entries)),
+        FeedFormat::Atom => ("application/atom+xml; charset=utf-8", render_atom(channel, entries)),
+    };
+
+    (
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static(content_type)),
+            (header::LAST_MODIFIED, http_cache::last_modified(channel.updated_at)),
+        ],
+        body,
+    )
+        .into_response()
+}
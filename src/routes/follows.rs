@@ -1,22 +1,23 @@
 use crate::{
     error::Result,
-    models::follow::*,
+    models::{follow::*, response::ApiResponse},
     services::auth::User,
     state::AppState,
     utils::middleware::OptionalAuth,
 };
 use axum::{
     extract::{Path, Query, State},
-    response::Json,
     routing::{delete, get, post},
     Extension, Router,
 };
 use serde::Deserialize;
-use serde_json::{json, Value};
+use serde_json::json;
 use std::sync::Arc;
 use tracing::debug;
+use utoipa::{IntoParams, OpenApi};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct FollowQuery {
     pub page: Option<i32>,
     pub limit: Option<i32>,
@@ -32,13 +33,33 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/mutual/:target_user_id", get(get_mutual_followers))
 }
 
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    follow_user,
+    unfollow_user,
+    get_followers,
+    get_following,
+    get_follow_stats,
+    check_following,
+    get_mutual_followers,
+))]
+pub struct ApiDoc;
+
 /// 关注用户
 /// POST /api/follows/user/:user_id/follow
+#[utoipa::path(
+    post,
+    path = "/api/blog/follows/user/{user_id}/follow",
+    tag = "follows",
+    params(("user_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn follow_user(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(user_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("User {} following user {}", user.id, user_id);
 
     state
@@ -46,19 +67,23 @@ async fn follow_user(
         .follow_user(&user.id, &user_id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "User followed successfully"
-    })))
+    Ok(ApiResponse::message("User followed successfully"))
 }
 
 /// 取消关注用户
 /// DELETE /api/follows/user/:user_id/follow
+#[utoipa::path(
+    delete,
+    path = "/api/blog/follows/user/{user_id}/follow",
+    tag = "follows",
+    params(("user_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn unfollow_user(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(user_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("User {} unfollowing user {}", user.id, user_id);
 
     state
@@ -66,20 +91,24 @@ async fn unfollow_user(
         .unfollow_user(&user.id, &user_id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "User unfollowed successfully"
-    })))
+    Ok(ApiResponse::message("User unfollowed successfully"))
 }
 
 /// 获取用户的关注者列表
 /// GET /api/follows/user/:user_id/followers
+#[utoipa::path(
+    get,
+    path = "/api/blog/follows/user/{user_id}/followers",
+    tag = "follows",
+    params(("user_id" = String, Path), FollowQuery),
+    security((), ("bearer_auth" = []))
+)]
 async fn get_followers(
     State(state): State<Arc<AppState>>,
     Path(user_id): Path<String>,
     Query(query): Query<FollowQuery>,
     OptionalAuth(user): OptionalAuth,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting followers for user: {}", user_id);
 
     let current_user_id = user.as_ref().map(|u| u.id.as_str());
@@ -88,20 +117,24 @@ async fn get_followers(
         .get_followers(&user_id, current_user_id, query.page, query.limit)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": followers
-    })))
+    Ok(ApiResponse::ok(followers))
 }
 
 /// 获取用户关注的人列表
 /// GET /api/follows/user/:user_id/following
+#[utoipa::path(
+    get,
+    path = "/api/blog/follows/user/{user_id}/following",
+    tag = "follows",
+    params(("user_id" = String, Path), FollowQuery),
+    security((), ("bearer_auth" = []))
+)]
 async fn get_following(
     State(state): State<Arc<AppState>>,
     Path(user_id): Path<String>,
     Query(query): Query<FollowQuery>,
     OptionalAuth(user): OptionalAuth,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting following for user: {}", user_id);
 
     let current_user_id = user.as_ref().map(|u| u.id.as_str());
@@ -110,19 +143,23 @@ async fn get_following(
         .get_following(&user_id, current_user_id, query.page, query.limit)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": following
-    })))
+    Ok(ApiResponse::ok(following))
 }
 
 /// 获取用户的关注统计
 /// GET /api/follows/user/:user_id/stats
+#[utoipa::path(
+    get,
+    path = "/api/blog/follows/user/{user_id}/stats",
+    tag = "follows",
+    params(("user_id" = String, Path)),
+    security((), ("bearer_auth" = []))
+)]
 async fn get_follow_stats(
     State(state): State<Arc<AppState>>,
     Path(user_id): Path<String>,
     OptionalAuth(user): OptionalAuth,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting follow stats for user: {}", user_id);
 
     let current_user_id = user.as_ref().map(|u| u.id.as_str());
@@ -131,19 +168,23 @@ async fn get_follow_stats(
         .get_follow_stats(&user_id, current_user_id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": stats
-    })))
+    Ok(ApiResponse::ok(stats))
 }
 
 /// 检查是否关注某用户
 /// GET /api/follows/user/:user_id/is-following
+#[utoipa::path(
+    get,
+    path = "/api/blog/follows/user/{user_id}/is-following",
+    tag = "follows",
+    params(("user_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn check_following(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(target_user_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Checking if user {} follows user {}", user.id, target_user_id);
 
     let is_following = state
@@ -151,22 +192,26 @@ async fn check_following(
         .is_following(&user.id, &target_user_id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": {
-            "is_following": is_following
-        }
+    Ok(ApiResponse::ok(json!({
+        "is_following": is_following
     })))
 }
 
 /// 获取共同关注的用户
 /// GET /api/follows/mutual/:target_user_id
+#[utoipa::path(
+    get,
+    path = "/api/blog/follows/mutual/{target_user_id}",
+    tag = "follows",
+    params(("target_user_id" = String, Path), FollowQuery),
+    security(("bearer_auth" = []))
+)]
 async fn get_mutual_followers(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(target_user_id): Path<String>,
     Query(query): Query<FollowQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting mutual followers between {} and {}", user.id, target_user_id);
 
     let mutual = state
@@ -174,8 +219,5 @@ async fn get_mutual_followers(
         .get_mutual_followers(&user.id, &target_user_id, query.limit)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": mutual
-    })))
+    Ok(ApiResponse::ok(mutual))
 }
\ No newline at end of file
@@ -0,0 +1,131 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+    routing::{get, post},
+    Extension, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::debug;
+
+use crate::{
+    error::Result,
+    models::{gift::CreateGiftRequest, response::ApiResponse},
+    services::auth::User,
+    state::AppState,
+};
+use utoipa::{IntoParams, OpenApi};
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", post(create_gift))
+        .route("/sent", get(list_sent_gifts))
+        .route("/received", get(list_received_gifts))
+        .route("/claim/:claim_token", post(claim_gift))
+}
+
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(create_gift, list_sent_gifts, list_received_gifts, claim_gift))]
+pub struct ApiDoc;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct GiftListQuery {
+    page: Option<usize>,
+    limit: Option<usize>,
+}
+
+/// 购买礼物（文章或订阅），返回礼物和待确认的支付
+#[utoipa::path(
+    post,
+    path = "/api/blog/gifts",
+    tag = "gifts",
+    request_body = CreateGiftRequest,
+    security(("bearer_auth" = []))
+)]
+async fn create_gift(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(request): Json<CreateGiftRequest>,
+) -> Result<ApiResponse> {
+    debug!("Creating {:?} gift from user: {}", request.gift_type, user.id);
+
+    let display_name = user.display_name.as_deref().or(user.username.as_deref());
+
+    let gift = state
+        .gift_service
+        .create_gift(&user.id, &user.email, display_name, request)
+        .await?;
+
+    Ok(ApiResponse::ok(gift))
+}
+
+/// 当前用户送出的礼物
+#[utoipa::path(
+    get,
+    path = "/api/blog/gifts/sent",
+    tag = "gifts",
+    params(GiftListQuery),
+    security(("bearer_auth" = []))
+)]
+async fn list_sent_gifts(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Query(query): Query<GiftListQuery>,
+) -> Result<ApiResponse> {
+    let result = state
+        .gift_service
+        .list_sent(&user.id, query.page.unwrap_or(1), query.limit.unwrap_or(20))
+        .await?;
+
+    Ok(ApiResponse::ok(serde_json::json!({
+        "gifts": result.data
+    })).with_pagination(&result))
+}
+
+/// 当前用户收到的礼物
+#[utoipa::path(
+    get,
+    path = "/api/blog/gifts/received",
+    tag = "gifts",
+    params(GiftListQuery),
+    security(("bearer_auth" = []))
+)]
+async fn list_received_gifts(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Query(query): Query<GiftListQuery>,
+) -> Result<ApiResponse> {
+    let result = state
+        .gift_service
+        .list_received(&user.id, &user.email, query.page.unwrap_or(1), query.limit.unwrap_or(20))
+        .await?;
+
+    Ok(ApiResponse::ok(serde_json::json!({
+        "gifts": result.data
+    })).with_pagination(&result))
+}
+
+/// 用领取令牌兑换礼物
+#[utoipa::path(
+    post,
+    path = "/api/blog/gifts/claim/{claim_token}",
+    tag = "gifts",
+    params(("claim_token" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn claim_gift(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(claim_token): Path<String>,
+) -> Result<ApiResponse> {
+    debug!("User {} claiming gift", user.id);
+
+    let gift = state
+        .gift_service
+        .claim_gift(&claim_token, &user.id)
+        .await?;
+
+    Ok(ApiResponse::ok(gift))
+}
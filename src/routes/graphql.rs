@@ -0,0 +1,51 @@
+use crate::{
+    graphql::{self, BlogSchema},
+    state::AppState,
+    utils::middleware::OptionalAuth,
+};
+use async_graphql::http::GraphiQLSource;
+use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
+use axum::{
+    extract::State,
+    response::Html,
+    routing::get,
+    Router,
+};
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+use utoipa::OpenApi;
+
+/// 查询结构不随请求变化，只构建一次
+static SCHEMA: Lazy<BlogSchema> = Lazy::new(graphql::build_schema);
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/", get(graphiql).post(execute))
+}
+
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(execute))]
+pub struct ApiDoc;
+
+/// 执行 GraphQL 查询。请求体为 `{ "query": ..., "variables": ..., "operationName": ... }`，
+/// 查询结构可以在 GET /api/blog/graphql 的 GraphiQL 中浏览
+#[utoipa::path(
+    post,
+    path = "/api/blog/graphql",
+    tag = "graphql",
+    request_body = serde_json::Value,
+    security((), ("bearer_auth" = []))
+)]
+async fn execute(
+    State(state): State<Arc<AppState>>,
+    OptionalAuth(user): OptionalAuth,
+    request: GraphQLRequest,
+) -> GraphQLResponse {
+    let request = graphql::prepare_request(request.into_inner(), state, user);
+    SCHEMA.execute(request).await.into()
+}
+
+/// GET /api/blog/graphql
+async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint("/api/blog/graphql").finish())
+}
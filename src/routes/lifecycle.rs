@@ -0,0 +1,115 @@
+use crate::{
+    error::{AppError, Result},
+    models::{lifecycle::*, response::ApiResponse},
+    services::auth::User,
+    state::AppState,
+    require_permission,
+};
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Extension, Router,
+};
+use std::sync::Arc;
+use tracing::debug;
+use utoipa::OpenApi;
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/actions", get(list_actions))
+        .route("/actions/:id/revert", post(revert_action))
+        .route("/run", post(run_jobs))
+        .route("/export", get(export_account))
+}
+
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(list_actions, revert_action, run_jobs, export_account))]
+pub struct ApiDoc;
+
+/// 获取生命周期审计记录，普通用户只能查看自己的记录
+/// GET /api/blog/lifecycle/actions
+#[utoipa::path(
+    get,
+    path = "/api/blog/lifecycle/actions",
+    tag = "lifecycle",
+    params(LifecycleActionQuery),
+    security(("bearer_auth" = []))
+)]
+async fn list_actions(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Query(mut query): Query<LifecycleActionQuery>,
+) -> Result<ApiResponse> {
+    if !state.auth_service.has_permission(&user, "user.moderate").await? {
+        query.user_id = Some(user.id.clone());
+    }
+
+    let actions = state.lifecycle_service.get_actions(&query).await?;
+
+    Ok(ApiResponse::ok(actions))
+}
+
+/// 撤销草稿归档或账户停用
+/// POST /api/blog/lifecycle/actions/:id/revert
+#[utoipa::path(
+    post,
+    path = "/api/blog/lifecycle/actions/{id}/revert",
+    tag = "lifecycle",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn revert_action(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(action_id): Path<String>,
+) -> Result<ApiResponse> {
+    debug!("Reverting lifecycle action {} by user: {}", action_id, user.id);
+
+    let action = state.lifecycle_service.get_action(&action_id).await?;
+    if action.user_id != user.id && !state.auth_service.has_permission(&user, "user.moderate").await? {
+        return Err(AppError::forbidden("You can only revert actions on your own content"));
+    }
+
+    let action = state.lifecycle_service.revert_action(&action, &user.id).await?;
+
+    Ok(ApiResponse::ok(action))
+}
+
+/// 立即执行生命周期任务（管理员）
+/// POST /api/blog/lifecycle/run
+#[utoipa::path(
+    post,
+    path = "/api/blog/lifecycle/run",
+    tag = "lifecycle",
+    security(("bearer_auth" = []))
+)]
+async fn run_jobs(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    require_permission!(state.auth_service, user, "user.moderate");
+
+    let report = state.lifecycle_service.run().await?;
+
+    Ok(ApiResponse::ok(report))
+}
+
+/// 导出当前用户的数据
+/// GET /api/blog/lifecycle/export
+#[utoipa::path(
+    get,
+    path = "/api/blog/lifecycle/export",
+    tag = "lifecycle",
+    security(("bearer_auth" = []))
+)]
+async fn export_account(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    debug!("Exporting account data for user: {}", user.id);
+
+    let export = state.lifecycle_service.export_account(&user.id).await?;
+
+    Ok(ApiResponse::ok(export))
+}
@@ -2,7 +2,7 @@ use crate::{
     error::{Result, AppError},
     state::AppState,
     services::auth::User,
-    models::media::MediaUploadResponse,
+    models::{media::MediaUploadResponse, response::{ApiResponse, PaginationMeta}},
 };
 use axum::{
     extract::{Path, Query, State, Multipart},
@@ -13,10 +13,11 @@ use axum::{
     http::{StatusCode, header},
     body::Body,
 };
-use serde_json::{json, Value};
+use serde_json::json;
 use serde::Deserialize;
 use std::sync::Arc;
 use tracing::{info, error, debug};
+use utoipa::{IntoParams, OpenApi};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -26,7 +27,13 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/", get(list_user_files))
 }
 
-#[derive(Debug, Deserialize)]
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(upload_image, serve_file, delete_file, list_user_files))]
+pub struct ApiDoc;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct MediaListQuery {
     pub page: Option<usize>,
     pub limit: Option<usize>,
@@ -34,6 +41,12 @@ pub struct MediaListQuery {
 
 /// 上传图片
 /// POST /api/blog/media/upload
+#[utoipa::path(
+    post,
+    path = "/api/blog/media/upload",
+    tag = "media",
+    security(("bearer_auth" = []))
+)]
 pub async fn upload_image(
     State(app_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
@@ -87,6 +100,12 @@ pub async fn upload_image(
 
 /// 获取文件
 /// GET /api/blog/media/files/*path
+#[utoipa::path(
+    get,
+    path = "/api/blog/media/files/{path}",
+    tag = "media",
+    params(("path" = String, Path))
+)]
 pub async fn serve_file(
     State(app_state): State<Arc<AppState>>,
     Path(file_path): Path<String>,
@@ -114,30 +133,41 @@ pub async fn serve_file(
 
 /// 删除文件
 /// DELETE /api/blog/media/:file_id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/media/{file_id}",
+    tag = "media",
+    params(("file_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 pub async fn delete_file(
     State(app_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(file_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Deleting file: {} for user: {}", file_id, user.id);
 
     app_state.media_service.delete_file(&user.id, &file_id).await?;
 
     info!("Successfully deleted file: {} for user: {}", file_id, user.id);
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "文件已删除"
-    })))
+    Ok(ApiResponse::message("文件已删除"))
 }
 
 /// 获取用户的文件列表
 /// GET /api/blog/media/
+#[utoipa::path(
+    get,
+    path = "/api/blog/media",
+    tag = "media",
+    params(MediaListQuery),
+    security(("bearer_auth" = []))
+)]
 pub async fn list_user_files(
     State(app_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Query(query): Query<MediaListQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Listing files for user: {}", user.id);
 
     let page = query.page.unwrap_or(1);
@@ -147,22 +177,9 @@ pub async fn list_user_files(
         .get_user_files(&user.id, page, limit)
         .await?;
 
-    let total_pages = (total + limit - 1) / limit;
-
-    Ok(Json(json!({
-        "success": true,
-        "data": {
-            "files": files.iter().map(|f| f.to_response()).collect::<Vec<_>>(),
-            "pagination": {
-                "current_page": page,
-                "total_pages": total_pages,
-                "total_items": total,
-                "items_per_page": limit,
-                "has_next": page < total_pages,
-                "has_prev": page > 1,
-            }
-        }
-    })))
+    Ok(ApiResponse::ok(json!({
+        "files": files.iter().map(|f| f.to_response()).collect::<Vec<_>>()
+    })).with_pagination(PaginationMeta::new(page, limit, total)))
 }
 
 fn determine_content_type(file_path: &str) -> &'static str {
@@ -173,8 +190,10 @@ fn determine_content_type(file_path: &str) -> &'static str {
         "png" => "image/png",
         "gif" => "image/gif",
         "webp" => "image/webp",
+        "avif" => "image/avif",
         "svg" => "image/svg+xml",
         "ico" => "image/x-icon",
+        "mp3" => "audio/mpeg",
         _ => "application/octet-stream",
     }
 }
\ No newline at end of file
@@ -20,3 +20,21 @@ pub mod websocket;
 pub mod domain;
 pub mod publication_content;
 pub mod diagnostics;
+pub mod reading_queue;
+#[cfg(feature = "rss")]
+pub mod feeds;
+pub mod lifecycle;
+pub mod trash;
+pub mod newsletters;
+pub mod webmention;
+pub mod syndication;
+pub mod attachments;
+pub mod ctas;
+pub mod pseudonyms;
+pub mod gifts;
+pub mod notifications;
+pub mod oauth;
+pub mod admin;
+pub mod reports;
+pub mod openapi;
+pub mod graphql;
@@ -0,0 +1,68 @@
+use crate::{
+    error::Result,
+    models::{newsletter::*, response::ApiResponse},
+    state::AppState,
+};
+use axum::{
+    extract::{Query, State},
+    response::Html,
+    routing::get,
+    Router,
+};
+use serde_json::json;
+use std::sync::Arc;
+use utoipa::OpenApi;
+
+/// 公开的退订入口，邮件中的链接和 List-Unsubscribe 头都指向这里
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/unsubscribe", get(unsubscribe_page).post(unsubscribe_one_click))
+}
+
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(unsubscribe_page, unsubscribe_one_click))]
+pub struct ApiDoc;
+
+/// 点击邮件中的退订链接
+/// GET /api/blog/newsletters/unsubscribe?token=
+#[utoipa::path(
+    get,
+    path = "/api/blog/newsletters/unsubscribe",
+    tag = "newsletters",
+    params(UnsubscribeQuery)
+)]
+async fn unsubscribe_page(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<UnsubscribeQuery>,
+) -> Result<Html<String>> {
+    let publication_name = state.newsletter_service.unsubscribe(&query.token).await?;
+
+    Ok(Html(format!(
+        "<!DOCTYPE html><html><body style=\"font-family: -apple-system, Helvetica, Arial, sans-serif;\">\
+         <p>You have been unsubscribed from {} newsletters.</p></body></html>",
+        html_escape(&publication_name)
+    )))
+}
+
+/// 邮件客户端的一键退订（RFC 8058）
+/// POST /api/blog/newsletters/unsubscribe?token=
+#[utoipa::path(
+    post,
+    path = "/api/blog/newsletters/unsubscribe",
+    tag = "newsletters",
+    params(UnsubscribeQuery)
+)]
+async fn unsubscribe_one_click(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<UnsubscribeQuery>,
+) -> Result<ApiResponse> {
+    let publication_name = state.newsletter_service.unsubscribe(&query.token).await?;
+
+    Ok(ApiResponse::ok(json!({
+        "publication": publication_name
+    })))
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
@@ -0,0 +1,168 @@
+use axum::{
+    extract::{Path, State},
+    response::Json,
+    routing::{delete, get},
+    Extension, Router,
+};
+use std::sync::Arc;
+use tracing::debug;
+
+use crate::{
+    error::{AppError, Result},
+    models::{
+        notification::{CreatePushSubscriptionRequest, UpdateNotificationPreferencesRequest},
+        response::ApiResponse,
+    },
+    services::auth::User,
+    state::AppState,
+};
+use utoipa::OpenApi;
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/preferences", get(get_preferences).put(update_preferences))
+        // 浏览器推送
+        .route("/push/vapid-public-key", get(get_vapid_public_key))
+        .route("/push/subscriptions", get(list_push_subscriptions).post(create_push_subscription))
+        .route("/push/subscriptions/:subscription_id", delete(delete_push_subscription))
+}
+
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_preferences,
+    update_preferences,
+    get_vapid_public_key,
+    list_push_subscriptions,
+    create_push_subscription,
+    delete_push_subscription,
+))]
+pub struct ApiDoc;
+
+/// 当前用户按事件类型的通知偏好
+#[utoipa::path(
+    get,
+    path = "/api/blog/notifications/preferences",
+    tag = "notifications",
+    security(("bearer_auth" = []))
+)]
+async fn get_preferences(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let preferences = state
+        .notification_service
+        .get_preferences(&user.id)
+        .await?;
+
+    Ok(ApiResponse::ok(preferences))
+}
+
+/// 修改部分事件的渠道开关和摘要频率
+#[utoipa::path(
+    put,
+    path = "/api/blog/notifications/preferences",
+    tag = "notifications",
+    request_body = UpdateNotificationPreferencesRequest,
+    security(("bearer_auth" = []))
+)]
+async fn update_preferences(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(request): Json<UpdateNotificationPreferencesRequest>,
+) -> Result<ApiResponse> {
+    debug!("Updating notification preferences for user: {}", user.id);
+
+    let preferences = state
+        .notification_service
+        .update_preferences(&user.id, request)
+        .await?;
+
+    Ok(ApiResponse::ok(preferences))
+}
+
+/// 客户端订阅浏览器推送时使用的 VAPID 公钥
+#[utoipa::path(
+    get,
+    path = "/api/blog/notifications/push/vapid-public-key",
+    tag = "notifications"
+)]
+async fn get_vapid_public_key(
+    State(state): State<Arc<AppState>>,
+) -> Result<ApiResponse> {
+    let public_key = state
+        .notification_service
+        .web_push()
+        .public_key()
+        .ok_or_else(|| AppError::NotFound("浏览器推送未启用".to_string()))?;
+
+    Ok(ApiResponse::ok(serde_json::json!({
+        "public_key": public_key
+    })))
+}
+
+/// 当前用户开启推送的设备
+#[utoipa::path(
+    get,
+    path = "/api/blog/notifications/push/subscriptions",
+    tag = "notifications",
+    security(("bearer_auth" = []))
+)]
+async fn list_push_subscriptions(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let subscriptions = state
+        .notification_service
+        .web_push()
+        .list_subscriptions(&user.id)
+        .await?;
+
+    Ok(ApiResponse::ok(subscriptions))
+}
+
+/// 保存浏览器的推送订阅（`PushSubscription.toJSON()` 的结果）
+#[utoipa::path(
+    post,
+    path = "/api/blog/notifications/push/subscriptions",
+    tag = "notifications",
+    request_body = CreatePushSubscriptionRequest,
+    security(("bearer_auth" = []))
+)]
+async fn create_push_subscription(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(request): Json<CreatePushSubscriptionRequest>,
+) -> Result<ApiResponse> {
+    debug!("Saving push subscription for user: {}", user.id);
+
+    let subscription = state
+        .notification_service
+        .web_push()
+        .subscribe(&user.id, request)
+        .await?;
+
+    Ok(ApiResponse::ok(subscription))
+}
+
+/// 关闭某个设备的推送
+#[utoipa::path(
+    delete,
+    path = "/api/blog/notifications/push/subscriptions/{subscription_id}",
+    tag = "notifications",
+    params(("subscription_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn delete_push_subscription(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(subscription_id): Path<String>,
+) -> Result<ApiResponse> {
+    state
+        .notification_service
+        .web_push()
+        .unsubscribe(&user.id, &subscription_id)
+        .await?;
+
+    Ok(ApiResponse::message("推送订阅已删除"))
+}
@@ -0,0 +1,196 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Json, Response},
+    routing::{delete, get, post},
+    Extension, Form, Router,
+};
+use serde_json::json;
+use std::sync::Arc;
+use tracing::debug;
+
+use crate::{
+    error::Result,
+    models::{
+        oauth::{AuthorizeDecision, AuthorizeRequest, CreateOAuthClientRequest, OAuthError, RevokeRequest, TokenRequest},
+        response::ApiResponse,
+    },
+    services::auth::User,
+    state::AppState,
+};
+use utoipa::OpenApi;
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        // 应用注册
+        .route("/clients", get(list_clients).post(create_client))
+        .route("/clients/:client_id", delete(delete_client))
+        // 授权码流程
+        .route("/authorize", get(get_authorize_prompt).post(authorize))
+        .route("/token", post(issue_token))
+        .route("/revoke", post(revoke_token))
+        // 用户已授权的应用
+        .route("/grants", get(list_grants))
+        .route("/grants/:client_id", delete(revoke_grant))
+}
+
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_clients,
+    create_client,
+    delete_client,
+    get_authorize_prompt,
+    authorize,
+    issue_token,
+    revoke_token,
+    list_grants,
+    revoke_grant,
+))]
+pub struct ApiDoc;
+
+/// 当前用户注册的应用
+#[utoipa::path(
+    get,
+    path = "/api/blog/oauth/clients",
+    tag = "oauth",
+    security(("bearer_auth" = []))
+)]
+async fn list_clients(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let clients = state.oauth_service.list_clients(&user.id).await?;
+    Ok(ApiResponse::ok(clients))
+}
+
+/// 注册第三方应用
+#[utoipa::path(
+    post,
+    path = "/api/blog/oauth/clients",
+    tag = "oauth",
+    request_body = CreateOAuthClientRequest,
+    security(("bearer_auth" = []))
+)]
+async fn create_client(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(request): Json<CreateOAuthClientRequest>,
+) -> Result<ApiResponse> {
+    debug!("User {} registering OAuth client {}", user.id, request.name);
+
+    let created = state.oauth_service.create_client(&user.id, request).await?;
+    Ok(ApiResponse::ok(created))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/blog/oauth/clients/{client_id}",
+    tag = "oauth",
+    params(("client_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn delete_client(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(client_id): Path<String>,
+) -> Result<ApiResponse> {
+    state.oauth_service.delete_client(&user.id, &client_id).await?;
+    Ok(ApiResponse::message("应用已删除，所有授权已撤销"))
+}
+
+/// 授权页：校验请求并返回应用名称和申请的权限，由前端展示给用户确认
+#[utoipa::path(
+    get,
+    path = "/api/blog/oauth/authorize",
+    tag = "oauth",
+    params(AuthorizeRequest),
+    security(("bearer_auth" = []))
+)]
+async fn get_authorize_prompt(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Query(request): Query<AuthorizeRequest>,
+) -> Result<ApiResponse> {
+    let prompt = state.oauth_service.authorize_prompt(&user, &request).await?;
+    Ok(ApiResponse::ok(prompt))
+}
+
+/// 用户同意或拒绝授权，前端跳转到返回的 `redirect_to`
+#[utoipa::path(
+    post,
+    path = "/api/blog/oauth/authorize",
+    tag = "oauth",
+    request_body = AuthorizeDecision,
+    security(("bearer_auth" = []))
+)]
+async fn authorize(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(decision): Json<AuthorizeDecision>,
+) -> Result<ApiResponse> {
+    let redirect_to = state.oauth_service.authorize(&user, decision).await?;
+    Ok(ApiResponse::ok(json!({ "redirect_to": redirect_to })))
+}
+
+/// 令牌端点（application/x-www-form-urlencoded），错误按 OAuth2 规范返回
+#[utoipa::path(
+    post,
+    path = "/api/blog/oauth/token",
+    tag = "oauth",
+    request_body(content = TokenRequest, content_type = "application/x-www-form-urlencoded")
+)]
+async fn issue_token(
+    State(state): State<Arc<AppState>>,
+    Form(request): Form<TokenRequest>,
+) -> std::result::Result<Response, OAuthError> {
+    let token = state.oauth_service.exchange(request).await?;
+    Ok(([(header::CACHE_CONTROL, "no-store")], Json(token)).into_response())
+}
+
+/// 撤销令牌，未知令牌同样返回 200
+#[utoipa::path(
+    post,
+    path = "/api/blog/oauth/revoke",
+    tag = "oauth",
+    request_body(content = RevokeRequest, content_type = "application/x-www-form-urlencoded")
+)]
+async fn revoke_token(
+    State(state): State<Arc<AppState>>,
+    Form(request): Form<RevokeRequest>,
+) -> std::result::Result<Response, OAuthError> {
+    state.oauth_service.revoke(&request.token).await?;
+    Ok(Json(json!({})).into_response())
+}
+
+/// 当前用户授权过的应用
+#[utoipa::path(
+    get,
+    path = "/api/blog/oauth/grants",
+    tag = "oauth",
+    security(("bearer_auth" = []))
+)]
+async fn list_grants(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let grants = state.oauth_service.list_grants(&user.id).await?;
+    Ok(ApiResponse::ok(grants))
+}
+
+/// 取消对应用的授权
+#[utoipa::path(
+    delete,
+    path = "/api/blog/oauth/grants/{client_id}",
+    tag = "oauth",
+    params(("client_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn revoke_grant(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(client_id): Path<String>,
+) -> Result<ApiResponse> {
+    state.oauth_service.revoke_grant(&user.id, &client_id).await?;
+    Ok(ApiResponse::message("已取消授权"))
+}
@@ -0,0 +1,229 @@
+//! OpenAPI 3.1 接口文档。
+//!
+//! 每个路由模块在处理函数上用 `#[utoipa::path]` 标注路径、参数和请求体，并汇总为模块内的
+//! `ApiDoc`；这里把 main.rs 中注册的所有模块合并成一份文档，在 `/api/blog/openapi.json`
+//! 提供，`/api/blog/docs` 为 Swagger UI。新增路由时在处理函数上加标注，并加入所在模块的
+//! `ApiDoc`。
+//!
+//! 接口统一的部分在合并后补上：认证方式、错误响应格式，以及没有声明响应的操作的成功响应。
+
+use crate::{models::response::ErrorResponse, state::AppState};
+use axum::{
+    http::header,
+    response::{Html, IntoResponse},
+    routing::get,
+    Router,
+};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Arc;
+use utoipa::{
+    openapi::{
+        security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+        path::Operation,
+        ContentBuilder, PathItem, Ref, ResponseBuilder,
+    },
+    OpenApi,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Rainbow-Blog API",
+        description = "成功响应为 `{ \"success\": true, \"data\": ... }`，分页信息在 `meta.pagination`；\
+                       错误响应见 `ErrorResponse`，请按 `error.code` 判断错误类型。"
+    ),
+    paths(crate::health_check),
+    components(schemas(ErrorResponse))
+)]
+struct ApiDoc;
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/api/blog/openapi.json", get(openapi_json))
+        .route("/api/blog/docs", get(swagger_ui))
+}
+
+/// 生成的文档不会变化，只在第一次请求时序列化
+static SPEC: Lazy<String> =
+    Lazy::new(|| openapi().to_pretty_json().expect("OpenAPI document serializes to JSON"));
+
+/// GET /api/blog/openapi.json
+async fn openapi_json() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "application/json")], SPEC.as_str())
+}
+
+/// GET /api/blog/docs
+async fn swagger_ui() -> Html<&'static str> {
+    Html(SWAGGER_UI)
+}
+
+/// 合并所有路由模块的接口文档
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    use super::*;
+
+    let mut doc = ApiDoc::openapi();
+    for api in [
+        auth::ApiDoc::openapi(),
+        users::ApiDoc::openapi(),
+        articles::ApiDoc::openapi(),
+        comments::ApiDoc::openapi(),
+        tags::ApiDoc::openapi(),
+        publications::ApiDoc::openapi(),
+        search::ApiDoc::openapi(),
+        media::ApiDoc::openapi(),
+        stats::ApiDoc::openapi(),
+        bookmarks::ApiDoc::openapi(),
+        follows::ApiDoc::openapi(),
+        recommendations::ApiDoc::openapi(),
+        series::ApiDoc::openapi(),
+        analytics::ApiDoc::openapi(),
+        subscriptions::ApiDoc::openapi(),
+        payments::ApiDoc::openapi(),
+        revenue::ApiDoc::openapi(),
+        stripe::ApiDoc::openapi(),
+        websocket::ApiDoc::openapi(),
+        domain::ApiDoc::openapi(),
+        diagnostics::ApiDoc::openapi(),
+        reading_queue::ApiDoc::openapi(),
+        lifecycle::ApiDoc::openapi(),
+        trash::ApiDoc::openapi(),
+        newsletters::ApiDoc::openapi(),
+        syndication::ApiDoc::openapi(),
+        attachments::ApiDoc::openapi(),
+        ctas::ApiDoc::openapi(),
+        pseudonyms::ApiDoc::openapi(),
+        gifts::ApiDoc::openapi(),
+        notifications::ApiDoc::openapi(),
+        oauth::ApiDoc::openapi(),
+        reports::ApiDoc::openapi(),
+        admin::ApiDoc::openapi(),
+        publication_content::ApiDoc::openapi(),
+        webmention::ApiDoc::openapi(),
+        graphql::ApiDoc::openapi(),
+    ] {
+        doc.merge(api);
+    }
+    #[cfg(feature = "rss")]
+    doc.merge(feeds::ApiDoc::openapi());
+    #[cfg(feature = "acme")]
+    doc.merge(domain::AcmeApiDoc::openapi());
+
+    apply_conventions(&mut doc);
+    doc
+}
+
+fn operations(item: &mut PathItem) -> impl Iterator<Item = &mut Operation> {
+    [
+        &mut item.get,
+        &mut item.put,
+        &mut item.post,
+        &mut item.delete,
+        &mut item.options,
+        &mut item.head,
+        &mut item.patch,
+        &mut item.trace,
+    ]
+    .into_iter()
+    .flatten()
+}
+
+/// 补上所有接口共有的部分
+fn apply_conventions(doc: &mut utoipa::openapi::OpenApi) {
+    doc.components.get_or_insert_with(Default::default).add_security_scheme(
+        "bearer_auth",
+        SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).bearer_format("JWT").build()),
+    );
+
+    let error_response = ResponseBuilder::new()
+        .description("错误，格式见 `ErrorResponse`")
+        .content(
+            "application/json",
+            ContentBuilder::new().schema(Some(Ref::from_schema_name("ErrorResponse"))).build(),
+        )
+        .build();
+
+    // 不同模块里同名的处理函数会得到相同的 operationId，重复的加上标签前缀
+    let mut operation_ids: HashMap<String, usize> = HashMap::new();
+    for item in doc.paths.paths.values_mut() {
+        for operation in operations(item) {
+            if let Some(id) = &operation.operation_id {
+                *operation_ids.entry(id.clone()).or_default() += 1;
+            }
+        }
+    }
+
+    for item in doc.paths.paths.values_mut() {
+        for operation in operations(item) {
+            let duplicated = operation
+                .operation_id
+                .as_ref()
+                .map_or(false, |id| operation_ids.get(id).copied().unwrap_or(0) > 1);
+            if duplicated {
+                let tag = operation.tags.as_ref().and_then(|tags| tags.first());
+                if let (Some(id), Some(tag)) = (&operation.operation_id, tag) {
+                    operation.operation_id = Some(format!("{}_{}", tag.replace('-', "_"), id));
+                }
+            }
+
+            // 处理函数注释的第二行多为 `GET /api/...`，与路径重复
+            let is_route_line = operation.description.as_deref().map_or(false, |description| {
+                description.split_once(' ').map_or(false, |(method, path)| {
+                    method.chars().all(|c| c.is_ascii_uppercase()) && path.starts_with('/')
+                })
+            });
+            if is_route_line {
+                operation.description = None;
+            }
+
+            let responses = &mut operation.responses.responses;
+            if responses.is_empty() {
+                responses.insert(
+                    "200".to_string(),
+                    ResponseBuilder::new().description("成功").build().into(),
+                );
+            }
+            responses.entry("default".to_string()).or_insert_with(|| error_response.clone().into());
+        }
+    }
+}
+
+const SWAGGER_UI: &str = r#"<!DOCTYPE html>
+<html lang="zh-CN">
+<head>
+  <meta charset="utf-8">
+  <title>Rainbow-Blog API</title>
+  <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+  <div id="swagger-ui"></div>
+  <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+  <script>
+    window.ui = SwaggerUIBundle({ url: "/api/blog/openapi.json", dom_id: "#swagger-ui" });
+  </script>
+</body>
+</html>
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openapi_document_covers_routes() {
+        let mut doc = openapi();
+        let paths = &doc.paths.paths;
+        assert!(paths.contains_key("/api/blog/articles/{slug}"));
+        assert!(paths.contains_key("/api/blog/bookmarks/{id}"));
+        assert!(paths.contains_key("/api/blog/admin/users"));
+
+        let mut operation_ids = std::collections::HashSet::new();
+        for item in doc.paths.paths.values_mut() {
+            for operation in operations(item) {
+                assert!(operation.responses.responses.contains_key("default"));
+                let id = operation.operation_id.clone().expect("operation id");
+                assert!(operation_ids.insert(id.clone()), "duplicate operationId {}", id);
+            }
+        }
+    }
+}
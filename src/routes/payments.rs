@@ -1,6 +1,6 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     response::Json,
     routing::{delete, get, post, put},
     Extension, Router,
@@ -14,10 +14,13 @@ use crate::{
     models::{
         payment::*,
         stripe::{CreatePaymentMethodRequest, StripePaymentMethod},
+        response::ApiResponse,
     },
     services::auth::User,
     state::AppState,
+    utils::middleware::idempotency_key,
 };
+use utoipa::{IntoParams, OpenApi, ToSchema};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -29,6 +32,8 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/articles/:article_id/pricing", get(get_article_pricing))
         // 单次购买
         .route("/articles/purchase", post(purchase_article))
+        .route("/articles/:article_id/purchase", post(purchase_article_by_id))
+        .route("/purchases", get(get_purchase_history))
         .route("/purchases/:purchase_id", get(get_purchase_details))
         // 创作者仪表板和统计
         .route("/dashboard/:creator_id", get(get_payment_dashboard))
@@ -49,47 +54,77 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/earnings/articles/:article_id", get(get_article_earnings))
 }
 
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    check_content_access,
+    get_content_preview,
+    set_article_pricing,
+    get_article_pricing,
+    purchase_article,
+    purchase_article_by_id,
+    get_purchase_history,
+    get_purchase_details,
+    get_payment_dashboard,
+    record_content_access,
+    list_payment_methods,
+    add_payment_method,
+    remove_payment_method,
+    set_default_payment_method,
+    get_earnings_analysis,
+    get_article_earnings,
+))]
+pub struct ApiDoc;
+
 /// 检查内容访问权限
+#[utoipa::path(
+    get,
+    path = "/api/blog/payments/content/{article_id}/access",
+    tag = "payments",
+    params(("article_id" = String, Path)),
+    security((), ("bearer_auth" = []))
+)]
 async fn check_content_access(
     State(state): State<Arc<AppState>>,
     Path(article_id): Path<String>,
     user: Option<Extension<User>>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Checking content access for article: {}", article_id);
 
     let user_id = user.map(|Extension(u)| u.id);
     let access = state
-        .payment_service
+        .payments()?
         .check_content_access(&article_id, user_id.as_deref())
         .await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": access
-    })))
+    Ok(ApiResponse::ok(access))
 }
 
 /// 获取内容预览
+#[utoipa::path(
+    get,
+    path = "/api/blog/payments/content/{article_id}/preview",
+    tag = "payments",
+    params(("article_id" = String, Path)),
+    security((), ("bearer_auth" = []))
+)]
 async fn get_content_preview(
     State(state): State<Arc<AppState>>,
     Path(article_id): Path<String>,
     user: Option<Extension<User>>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting content preview for article: {}", article_id);
 
     let user_id = user.map(|Extension(u)| u.id);
     let preview = state
-        .payment_service
+        .payments()?
         .get_content_preview(&article_id, user_id.as_deref())
         .await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": preview
-    })))
+    Ok(ApiResponse::ok(preview))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct SetPricingRequest {
     price: Option<i64>,
     subscription_required: bool,
@@ -98,12 +133,20 @@ struct SetPricingRequest {
 }
 
 /// 设置文章定价
+#[utoipa::path(
+    put,
+    path = "/api/blog/payments/articles/{article_id}/pricing",
+    tag = "payments",
+    params(("article_id" = String, Path)),
+    request_body = SetPricingRequest,
+    security(("bearer_auth" = []))
+)]
 async fn set_article_pricing(
     State(state): State<Arc<AppState>>,
     Path(article_id): Path<String>,
     Extension(user): Extension<User>,
     Json(payload): Json<SetPricingRequest>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Setting pricing for article: {}", article_id);
 
     let request = ArticlePricingRequest {
@@ -114,29 +157,29 @@ async fn set_article_pricing(
     };
 
     let pricing = state
-        .payment_service
+        .payments()?
         .set_article_pricing(&article_id, &user.id, request)
         .await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": pricing
-    })))
+    Ok(ApiResponse::ok(pricing))
 }
 
 /// 获取文章定价信息
+#[utoipa::path(
+    get,
+    path = "/api/blog/payments/articles/{article_id}/pricing",
+    tag = "payments",
+    params(("article_id" = String, Path))
+)]
 async fn get_article_pricing(
     State(state): State<Arc<AppState>>,
     Path(article_id): Path<String>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting pricing for article: {}", article_id);
 
-    // 首先尝试从payment_service获取
-    match state.payment_service.get_article_pricing(&article_id).await {
-        Ok(pricing) => Ok(Json(serde_json::json!({
-            "success": true,
-            "data": pricing
-        }))),
+    // 首先尝试从付费内容服务获取
+    match state.payments()?.get_article_pricing(&article_id).await {
+        Ok(pricing) => Ok(ApiResponse::ok(pricing)),
         Err(AppError::NotFound(_)) => {
             // 如果没有定价信息，返回默认配置
             let default_pricing = ArticlePricing {
@@ -151,74 +194,158 @@ async fn get_article_pricing(
                 updated_at: chrono::Utc::now(),
             };
 
-            Ok(Json(serde_json::json!({
-                "success": true,
-                "data": default_pricing
-            })))
+            Ok(ApiResponse::ok(default_pricing))
         }
         Err(e) => Err(e),
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct PurchaseRequest {
     article_id: String,
     payment_method_id: Option<String>,
 }
 
 /// 购买单篇文章
+#[utoipa::path(
+    post,
+    path = "/api/blog/payments/articles/purchase",
+    tag = "payments",
+    request_body = PurchaseRequest,
+    security(("bearer_auth" = []))
+)]
 async fn purchase_article(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
+    headers: HeaderMap,
     Json(payload): Json<PurchaseRequest>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Processing article purchase for user: {}", user.id);
 
     let request = ArticlePurchaseRequest {
         article_id: payload.article_id,
         payment_method_id: payload.payment_method_id,
+        idempotency_key: idempotency_key(&headers),
     };
 
     let display_name = user.display_name.as_deref().or(user.username.as_deref());
 
     let purchase = state
-        .payment_service
+        .payments()?
         .purchase_article(&user.id, &user.email, display_name, request)
         .await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": purchase
-    })))
+    Ok(ApiResponse::ok(purchase))
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+struct PurchaseByIdRequest {
+    payment_method_id: Option<String>,
+}
+
+/// 购买指定文章，请求体可省略，省略时使用默认支付方式
+#[utoipa::path(
+    post,
+    path = "/api/blog/payments/articles/{article_id}/purchase",
+    tag = "payments",
+    params(("article_id" = String, Path)),
+    request_body = PurchaseByIdRequest,
+    security(("bearer_auth" = []))
+)]
+async fn purchase_article_by_id(
+    State(state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+    Extension(user): Extension<User>,
+    headers: HeaderMap,
+    payload: Option<Json<PurchaseByIdRequest>>,
+) -> Result<ApiResponse> {
+    debug!("Processing purchase of article {} for user: {}", article_id, user.id);
+
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    let request = ArticlePurchaseRequest {
+        article_id,
+        payment_method_id: payload.payment_method_id,
+        idempotency_key: idempotency_key(&headers),
+    };
+
+    let display_name = user.display_name.as_deref().or(user.username.as_deref());
+
+    let purchase = state
+        .payments()?
+        .purchase_article(&user.id, &user.email, display_name, request)
+        .await?;
+
+    Ok(ApiResponse::ok(purchase))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct PurchaseHistoryQuery {
+    page: Option<usize>,
+    limit: Option<usize>,
+}
+
+/// 获取当前用户的购买历史
+#[utoipa::path(
+    get,
+    path = "/api/blog/payments/purchases",
+    tag = "payments",
+    params(PurchaseHistoryQuery),
+    security(("bearer_auth" = []))
+)]
+async fn get_purchase_history(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<PurchaseHistoryQuery>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    debug!("Getting purchase history for user: {}", user.id);
+
+    let result = state
+        .payments()?
+        .get_purchase_history(&user.id, query.page.unwrap_or(1), query.limit.unwrap_or(20))
+        .await?;
+
+    Ok(ApiResponse::ok(serde_json::json!({
+        "purchases": result.data
+    })).with_pagination(&result))
 }
 
 /// 获取购买详情
+#[utoipa::path(
+    get,
+    path = "/api/blog/payments/purchases/{purchase_id}",
+    tag = "payments",
+    params(("purchase_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn get_purchase_details(
     State(state): State<Arc<AppState>>,
     Path(purchase_id): Path<String>,
     Extension(user): Extension<User>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting purchase details: {}", purchase_id);
 
-    // 这里需要实现获取购买详情的逻辑
-    // 目前先返回简单的响应
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": {
-            "id": purchase_id,
-            "buyer_id": user.id,
-            "status": "completed",
-            "message": "Purchase details implementation pending"
-        }
-    })))
+    let purchase = state
+        .payments()?
+        .get_purchase(&purchase_id, &user.id)
+        .await?;
+
+    Ok(ApiResponse::ok(purchase))
 }
 
 /// 获取付费内容仪表板
+#[utoipa::path(
+    get,
+    path = "/api/blog/payments/dashboard/{creator_id}",
+    tag = "payments",
+    params(("creator_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn get_payment_dashboard(
     State(state): State<Arc<AppState>>,
     Path(creator_id): Path<String>,
     Extension(user): Extension<User>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting payment dashboard for creator: {}", creator_id);
 
     // 验证权限 - 只有创作者本人可以查看
@@ -229,17 +356,14 @@ async fn get_payment_dashboard(
     }
 
     let dashboard = state
-        .payment_service
+        .payments()?
         .get_payment_dashboard(&creator_id)
         .await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": dashboard
-    })))
+    Ok(ApiResponse::ok(dashboard))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct AccessLogRequest {
     article_id: String,
     access_type: String,
@@ -247,11 +371,18 @@ struct AccessLogRequest {
 }
 
 /// 记录内容访问
+#[utoipa::path(
+    post,
+    path = "/api/blog/payments/access-log",
+    tag = "payments",
+    request_body = AccessLogRequest,
+    security(("bearer_auth" = []))
+)]
 async fn record_content_access(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Json(payload): Json<AccessLogRequest>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Recording content access for user: {}", user.id);
 
     let access_type = match payload.access_type.as_str() {
@@ -260,11 +391,12 @@ async fn record_content_access(
         "one_time" => AccessType::OneTime,
         "author" => AccessType::Author,
         "preview" => AccessType::Preview,
+        "metered" => AccessType::Metered,
         _ => AccessType::Preview,
     };
 
     state
-        .payment_service
+        .payments()?
         .record_content_access(
             &user.id,
             &payload.article_id,
@@ -273,97 +405,109 @@ async fn record_content_access(
         )
         .await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": null
-    })))
+    Ok(ApiResponse::ok(null))
 }
 
 /// 获取当前用户的支付方式列表
+#[utoipa::path(
+    get,
+    path = "/api/blog/payments/payment-methods",
+    tag = "payments",
+    security(("bearer_auth" = []))
+)]
 async fn list_payment_methods(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-) -> Result<Json<serde_json::Value>> {
-    let methods = state.stripe_service.list_payment_methods(&user.id).await?;
+) -> Result<ApiResponse> {
+    let methods = state.stripe()?.list_payment_methods(&user.id).await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": methods,
-    })))
+    Ok(ApiResponse::ok(methods))
 }
 
 /// 添加新的支付方式
+#[utoipa::path(
+    post,
+    path = "/api/blog/payments/payment-methods",
+    tag = "payments",
+    request_body = CreatePaymentMethodRequest,
+    security(("bearer_auth" = []))
+)]
 async fn add_payment_method(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Json(payload): Json<CreatePaymentMethodRequest>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     let display_name = user
         .display_name
         .as_deref()
         .or_else(|| user.username.as_deref());
 
     let payment_method = state
-        .stripe_service
+        .stripe()?
         .add_payment_method(&user.id, &user.email, display_name, payload)
         .await?;
 
-    let updated_methods = state.stripe_service.list_payment_methods(&user.id).await?;
+    let updated_methods = state.stripe()?.list_payment_methods(&user.id).await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": payment_method,
-        "meta": {
-            "payment_methods": updated_methods
-        }
+    Ok(ApiResponse::ok(serde_json::json!({
+        "payment_method": payment_method,
+        "payment_methods": updated_methods
     })))
 }
 
 /// 设置默认支付方式
+#[utoipa::path(
+    post,
+    path = "/api/blog/payments/payment-methods/{payment_method_id}/default",
+    tag = "payments",
+    params(("payment_method_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn set_default_payment_method(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(payment_method_id): Path<String>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     let payment_method = state
-        .stripe_service
+        .stripe()?
         .set_default_payment_method(&user.id, &payment_method_id)
         .await?;
 
-    let updated_methods = state.stripe_service.list_payment_methods(&user.id).await?;
+    let updated_methods = state.stripe()?.list_payment_methods(&user.id).await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": payment_method,
-        "meta": {
-            "payment_methods": updated_methods
-        }
+    Ok(ApiResponse::ok(serde_json::json!({
+        "payment_method": payment_method,
+        "payment_methods": updated_methods
     })))
 }
 
 /// 删除支付方式
+#[utoipa::path(
+    delete,
+    path = "/api/blog/payments/payment-methods/{payment_method_id}",
+    tag = "payments",
+    params(("payment_method_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn remove_payment_method(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(payment_method_id): Path<String>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     state
-        .stripe_service
+        .stripe()?
         .delete_payment_method(&user.id, &payment_method_id)
         .await?;
 
-    let updated_methods = state.stripe_service.list_payment_methods(&user.id).await?;
+    let updated_methods = state.stripe()?.list_payment_methods(&user.id).await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": serde_json::Value::Null,
-        "meta": {
-            "payment_methods": updated_methods
-        }
+    Ok(ApiResponse::ok(serde_json::json!({
+        "payment_methods": updated_methods
     })))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct EarningsQuery {
     creator_id: Option<String>,
     article_id: Option<String>,
@@ -373,11 +517,18 @@ struct EarningsQuery {
 }
 
 /// 获取收益分析
+#[utoipa::path(
+    get,
+    path = "/api/blog/payments/earnings",
+    tag = "payments",
+    params(EarningsQuery),
+    security(("bearer_auth" = []))
+)]
 async fn get_earnings_analysis(
     State(state): State<Arc<AppState>>,
     Query(query): Query<EarningsQuery>,
     Extension(user): Extension<User>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting earnings analysis for user: {}", user.id);
 
     // 如果指定了creator_id，验证权限
@@ -394,30 +545,34 @@ async fn get_earnings_analysis(
 
     // 获取收益仪表板（这里复用了dashboard功能）
     let dashboard = state
-        .payment_service
+        .payments()?
         .get_payment_dashboard(&creator_id)
         .await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": {
-            "creator_id": creator_id,
-            "total_revenue": dashboard.monthly_revenue,
-            "paid_articles_count": dashboard.total_paid_articles,
-            "subscribers_count": dashboard.total_subscribers,
-            "purchases_count": dashboard.total_purchases,
-            "top_earning_articles": dashboard.top_earning_articles,
-            "access_stats": dashboard.access_stats
-        }
+    Ok(ApiResponse::ok(serde_json::json!({
+        "creator_id": creator_id,
+        "total_revenue": dashboard.monthly_revenue,
+        "paid_articles_count": dashboard.total_paid_articles,
+        "subscribers_count": dashboard.total_subscribers,
+        "purchases_count": dashboard.total_purchases,
+        "top_earning_articles": dashboard.top_earning_articles,
+        "access_stats": dashboard.access_stats
     })))
 }
 
 /// 获取单篇文章收益
+#[utoipa::path(
+    get,
+    path = "/api/blog/payments/earnings/articles/{article_id}",
+    tag = "payments",
+    params(("article_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn get_article_earnings(
     State(state): State<Arc<AppState>>,
     Path(article_id): Path<String>,
     Extension(user): Extension<User>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting earnings for article: {}", article_id);
 
     // 验证文章所有权
@@ -425,7 +580,7 @@ async fn get_article_earnings(
     // 简化实现，假设验证通过
 
     let dashboard = state
-        .payment_service
+        .payments()?
         .get_payment_dashboard(&user.id)
         .await?;
 
@@ -436,20 +591,14 @@ async fn get_article_earnings(
         .find(|article| article.article_id == article_id);
 
     match article_earnings {
-        Some(earnings) => Ok(Json(serde_json::json!({
-            "success": true,
-            "data": earnings
-        }))),
-        None => Ok(Json(serde_json::json!({
-            "success": true,
-            "data": {
-                "article_id": article_id,
-                "total_revenue": 0,
-                "subscription_revenue": 0,
-                "purchase_revenue": 0,
-                "view_count": 0,
-                "purchase_count": 0
-            }
+        Some(earnings) => Ok(ApiResponse::ok(earnings)),
+        None => Ok(ApiResponse::ok(serde_json::json!({
+            "article_id": article_id,
+            "total_revenue": 0,
+            "subscription_revenue": 0,
+            "purchase_revenue": 0,
+            "view_count": 0,
+            "purchase_count": 0
         }))),
     }
 }
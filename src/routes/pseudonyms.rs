@@ -0,0 +1,213 @@
+use crate::{
+    error::{AppError, Result},
+    models::{article::ArticleQuery, id::bare_id, pseudonym::*, response::ApiResponse},
+    services::auth::User,
+    state::AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+    routing::get,
+    Extension, Router,
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use tracing::debug;
+use utoipa::{IntoParams, OpenApi};
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(list_pseudonyms).post(create_pseudonym))
+        .route("/:id", get(get_pseudonym).put(update_pseudonym).delete(delete_pseudonym))
+        .route("/:id/stats", get(get_pseudonym_stats))
+        .route("/profile/:username", get(get_pseudonym_profile))
+        .route("/profile/:username/articles", get(get_pseudonym_articles))
+}
+
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_pseudonyms,
+    create_pseudonym,
+    get_pseudonym,
+    update_pseudonym,
+    delete_pseudonym,
+    get_pseudonym_stats,
+    get_pseudonym_profile,
+    get_pseudonym_articles,
+))]
+pub struct ApiDoc;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PseudonymArticlesQuery {
+    pub page: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+/// 列出当前用户的笔名
+/// GET /api/blog/pseudonyms
+#[utoipa::path(
+    get,
+    path = "/api/blog/pseudonyms",
+    tag = "pseudonyms",
+    security(("bearer_auth" = []))
+)]
+async fn list_pseudonyms(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let pseudonyms = state.pseudonym_service.list(&user.id).await?;
+
+    Ok(ApiResponse::ok(pseudonyms))
+}
+
+/// POST /api/blog/pseudonyms
+#[utoipa::path(
+    post,
+    path = "/api/blog/pseudonyms",
+    tag = "pseudonyms",
+    request_body = CreatePseudonymRequest,
+    security(("bearer_auth" = []))
+)]
+async fn create_pseudonym(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(request): Json<CreatePseudonymRequest>,
+) -> Result<ApiResponse> {
+    debug!("Creating pseudonym for user: {}", user.id);
+
+    let pseudonym = state.pseudonym_service.create(&user.id, request).await?;
+
+    Ok(ApiResponse::ok(pseudonym).with_message("Pseudonym created"))
+}
+
+/// GET /api/blog/pseudonyms/:id
+#[utoipa::path(
+    get,
+    path = "/api/blog/pseudonyms/{id}",
+    tag = "pseudonyms",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_pseudonym(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<String>,
+) -> Result<ApiResponse> {
+    let pseudonym = state.pseudonym_service.get(&user.id, &id).await?;
+
+    Ok(ApiResponse::ok(pseudonym))
+}
+
+/// PUT /api/blog/pseudonyms/:id
+#[utoipa::path(
+    put,
+    path = "/api/blog/pseudonyms/{id}",
+    tag = "pseudonyms",
+    params(("id" = String, Path)),
+    request_body = UpdatePseudonymRequest,
+    security(("bearer_auth" = []))
+)]
+async fn update_pseudonym(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdatePseudonymRequest>,
+) -> Result<ApiResponse> {
+    debug!("Updating pseudonym {} of user: {}", id, user.id);
+
+    let pseudonym = state.pseudonym_service.update(&user.id, &id, request).await?;
+
+    Ok(ApiResponse::ok(pseudonym).with_message("Pseudonym updated"))
+}
+
+/// 已发布的笔名文章之后显示为匿名作者
+/// DELETE /api/blog/pseudonyms/:id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/pseudonyms/{id}",
+    tag = "pseudonyms",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn delete_pseudonym(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<String>,
+) -> Result<ApiResponse> {
+    debug!("Deleting pseudonym {} of user: {}", id, user.id);
+
+    state.pseudonym_service.delete(&user.id, &id).await?;
+
+    Ok(ApiResponse::message("Pseudonym deleted"))
+}
+
+/// 笔名文章的统计，只有笔名所有者可以查看
+/// GET /api/blog/pseudonyms/:id/stats?days=30
+#[utoipa::path(
+    get,
+    path = "/api/blog/pseudonyms/{id}/stats",
+    tag = "pseudonyms",
+    params(("id" = String, Path), PseudonymStatsQuery),
+    security(("bearer_auth" = []))
+)]
+async fn get_pseudonym_stats(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(id): Path<String>,
+    Query(query): Query<PseudonymStatsQuery>,
+) -> Result<ApiResponse> {
+    let stats = state.pseudonym_service.get_stats(&user.id, &id, query).await?;
+
+    Ok(ApiResponse::ok(stats))
+}
+
+/// 公开的笔名主页
+/// GET /api/blog/pseudonyms/profile/:username
+#[utoipa::path(
+    get,
+    path = "/api/blog/pseudonyms/profile/{username}",
+    tag = "pseudonyms",
+    params(("username" = String, Path))
+)]
+async fn get_pseudonym_profile(
+    State(state): State<Arc<AppState>>,
+    Path(username): Path<String>,
+) -> Result<ApiResponse> {
+    let profile = state.pseudonym_service.get_public_profile(&username).await?;
+
+    Ok(ApiResponse::ok(profile))
+}
+
+/// 笔名发布的文章
+/// GET /api/blog/pseudonyms/profile/:username/articles
+#[utoipa::path(
+    get,
+    path = "/api/blog/pseudonyms/profile/{username}/articles",
+    tag = "pseudonyms",
+    params(("username" = String, Path), PseudonymArticlesQuery)
+)]
+async fn get_pseudonym_articles(
+    State(state): State<Arc<AppState>>,
+    Path(username): Path<String>,
+    Query(query): Query<PseudonymArticlesQuery>,
+) -> Result<ApiResponse> {
+    debug!("Fetching articles for pseudonym: {}", username);
+
+    let pseudonym = state.pseudonym_service.get_by_username(&username).await?
+        .ok_or_else(|| AppError::not_found("Pseudonym"))?;
+
+    let result = state.articles()?.get_articles(ArticleQuery {
+        page: Some(query.page.unwrap_or(1)),
+        limit: Some(query.limit.unwrap_or(20).min(100)),
+        pseudonym: Some(bare_id("pseudonym", &pseudonym.id).to_string()),
+        ..Default::default()
+    }).await?;
+
+    Ok(ApiResponse::ok(json!({
+        "articles": result.data,
+        "author": pseudonym.author_info()
+    })).with_pagination(&result))
+}
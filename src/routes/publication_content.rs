@@ -1,41 +1,75 @@
 use crate::{
     error::{AppError, Result},
-    models::{article::Article, publication::{Publication, MemberRole}},
-    services::auth::User,
+    models::{article::Article, publication::{Publication, MemberRole}, response::{ApiResponse, PaginationMeta}, sponsorship::SponsorPlacement},
+    services::{auth::User, meter::meter_set_cookie},
     state::AppState,
-    utils::middleware::{OptionalAuth, OptionalPublicationContext, RequiredPublicationContext},
+    utils::middleware::{OptionalAuth, OptionalPublicationContext, RequiredPublicationContext, VisitorGeo},
 };
 use axum::{
     extract::{Path, Query, State},
-    response::Json,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     routing::{get, post},
     Extension, Router,
 };
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use serde_json::json;
 use std::sync::Arc;
 use tracing::{debug, info};
+use utoipa::{IntoParams, OpenApi};
 
 pub fn router() -> Router<Arc<AppState>> {
-    Router::new()
+    let router = Router::new()
         // Domain-specific content routes - these work with custom domains/subdomains
         .route("/", get(get_publication_home))
         .route("/articles", get(get_publication_articles))
         .route("/articles/:slug", get(get_publication_article))
         .route("/about", get(get_publication_about))
         .route("/writers", get(get_publication_writers))
+        .route("/:slug", get(get_publication_page))
         // API routes that require publication context
         .route("/api/content/articles", get(api_get_publication_articles))
         .route("/api/content/featured", get(api_get_featured_articles))
+        // Webmention / Pingback receivers
+        .route("/webmention", post(super::webmention::receive_webmention))
+        .route("/xmlrpc", post(super::webmention::receive_pingback));
+
+    // Feeds for the publication behind the current domain
+    #[cfg(feature = "rss")]
+    let router = router
+        .route("/feed.xml", get(super::feeds::domain_rss))
+        .route("/atom.xml", get(super::feeds::domain_atom));
+
+    router
 }
 
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_publication_home,
+    get_publication_articles,
+    get_publication_article,
+    get_publication_about,
+    get_publication_writers,
+    get_publication_page,
+    api_get_publication_articles,
+    api_get_featured_articles,
+))]
+pub struct ApiDoc;
+
 /// Get publication home page (works with domain routing)
 /// GET / (when accessed via custom domain/subdomain)
+#[utoipa::path(
+    get,
+    path = "/",
+    tag = "publication-content",
+    security((), ("bearer_auth" = []))
+)]
 async fn get_publication_home(
     State(state): State<Arc<AppState>>,
     OptionalAuth(user): OptionalAuth,
     OptionalPublicationContext(pub_context): OptionalPublicationContext,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     info!("Serving publication home page");
     
     match pub_context {
@@ -43,19 +77,32 @@ async fn get_publication_home(
             debug!("Serving home page for publication: {} via domain: {}", 
                    context.publication.name, context.domain);
             
-            // Get featured articles for this publication
-            let featured_articles = get_featured_articles_for_publication(&state, &context.publication_id).await?;
+            // Featured articles, sections and about block from the homepage layout
+            let homepage = state.homepage_service.render(&context.publication_id).await?;
+            let theme = state.theme_service.get(&context.publication_id).await?;
+            let pages = state.page_service.navigation(&context.publication_id).await?;
             
             // Get publication stats
             let stats = get_publication_stats(&state, &context.publication_id).await?;
+
+            // Sponsored blocks carry their own disclosure label
+            let sponsors = state.sponsorship_service
+                .render_for_page(&context.publication_id, &[SponsorPlacement::HomeTop])
+                .await;
             
-            Ok(Json(json!({
+            Ok(ApiResponse::ok(json!({
                 "type": "publication_home",
                 "publication": context.publication,
+                "theme": theme,
+                "pages": pages,
                 "domain": context.domain,
                 "is_custom_domain": context.is_custom_domain,
-                "featured_articles": featured_articles,
+                "featured_layout": homepage.featured.style,
+                "featured_articles": homepage.featured.articles,
+                "sections": homepage.sections,
+                "about": homepage.about,
                 "stats": stats,
+                "sponsors": sponsors,
                 "user": user.map(|u| json!({
                     "id": u.id,
                     "username": u.username,
@@ -67,7 +114,7 @@ async fn get_publication_home(
             // Default platform home page
             debug!("Serving default platform home page");
             
-            Ok(Json(json!({
+            Ok(ApiResponse::ok(json!({
                 "type": "platform_home",
                 "message": "Welcome to Rainbow Blog Platform",
                 "user": user.map(|u| json!({
@@ -82,12 +129,19 @@ async fn get_publication_home(
 
 /// Get publication articles (domain-aware)
 /// GET /articles (when accessed via custom domain/subdomain)
+#[utoipa::path(
+    get,
+    path = "/articles",
+    tag = "publication-content",
+    params(ArticleListParams),
+    security((), ("bearer_auth" = []))
+)]
 async fn get_publication_articles(
     State(state): State<Arc<AppState>>,
     OptionalAuth(user): OptionalAuth,
     RequiredPublicationContext(context): RequiredPublicationContext,
     Query(params): Query<ArticleListParams>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting articles for publication: {} via domain: {}", 
            context.publication.name, context.domain);
     
@@ -104,74 +158,162 @@ async fn get_publication_articles(
     let total_count = state.article_service
         .count_articles_by_publication(&context.publication_id, tag.as_deref(), search.as_deref())
         .await?;
+
+    let sponsors = state.sponsorship_service
+        .render_for_page(&context.publication_id, &[SponsorPlacement::ArticleList])
+        .await;
+    let theme = state.theme_service.get(&context.publication_id).await?;
     
-    Ok(Json(json!({
+    Ok(ApiResponse::ok(json!({
         "articles": articles,
-        "pagination": {
-            "page": page,
-            "per_page": per_page,
-            "total": total_count,
-            "total_pages": (total_count + per_page - 1) / per_page
-        },
+        "sponsors": sponsors,
         "publication": {
             "id": context.publication_id,
             "name": context.publication.name,
             "slug": context.publication.slug
         },
+        "theme": theme,
         "domain": context.domain,
         "filters": {
             "tag": tag,
             "search": search
         }
-    })))
+    })).with_pagination(PaginationMeta::new(page, per_page, total_count)))
 }
 
 /// Get specific publication article by slug (domain-aware)
 /// GET /articles/:slug (when accessed via custom domain/subdomain)
+///
+/// Advertises the Webmention and Pingback endpoints of the domain in the response headers.
+#[utoipa::path(
+    get,
+    path = "/articles/{slug}",
+    tag = "publication-content",
+    params(("slug" = String, Path)),
+    security((), ("bearer_auth" = []))
+)]
 async fn get_publication_article(
     State(state): State<Arc<AppState>>,
     OptionalAuth(user): OptionalAuth,
     RequiredPublicationContext(context): RequiredPublicationContext,
     Path(slug): Path<String>,
-) -> Result<Json<Value>> {
+    geo: Option<Extension<VisitorGeo>>,
+    headers: HeaderMap,
+) -> Result<Response> {
     debug!("Getting article '{}' for publication: {} via domain: {}", 
            slug, context.publication.name, context.domain);
     
-    // Get article by slug within this publication
-    let article = state.article_service
-        .get_article_by_slug_in_publication(&context.publication_id, &slug, user.as_ref().map(|u| u.id.as_str()))
+    // Get article by slug within this publication; paid articles use the publication's metered quota
+    let meter = state.meter_service.reader(&headers, user.as_ref().map(|u| u.id.as_str()));
+    let mut article = match state.article_service
+        .get_article_by_slug_in_publication(&context.publication_id, &slug, user.as_ref().map(|u| u.id.as_str()), Some(&meter))
         .await?
-        .ok_or_else(|| AppError::NotFound("Article not found in this publication".to_string()))?;
+    {
+        Some(article) => article,
+        None => {
+            // The slug may have been changed (e.g. after a title edit): redirect old links permanently
+            let moved = state.article_service
+                .find_slug_redirect(&slug)
+                .await?
+                .filter(|a| a.publication_id.as_deref() == Some(context.publication_id.as_str()))
+                .ok_or_else(|| AppError::NotFound("Article not found in this publication".to_string()))?;
+            let location = format!("/articles/{}", moved.slug);
+            return Ok((StatusCode::MOVED_PERMANENTLY, [(header::LOCATION, location)]).into_response());
+        }
+    };
+
+    // Apply the publication's custom content transforms
+    article.content_html = state.content_transform_service
+        .apply(&context.publication_id, article.content_html)
+        .await;
+    article.content_html = state.cta_service
+        .apply(&article.author_id, &article.id, article.content_html)
+        .await;
     
     // Get related articles from same publication
     let related_articles = state.article_service
         .get_related_articles_in_publication(&context.publication_id, &article.id, 5)
         .await?;
     
-    // Increment view count
-    if let Err(e) = state.article_service.increment_view_count(&article.id).await {
-        tracing::warn!("Failed to increment view count for article {}: {}", article.id, e);
+    // Increment view count, unless the request comes from a crawler or the
+    // view is expected to be counted through the JS challenge instead
+    let bots = &state.bot_detection_service;
+    if !bots.challenge_required() {
+        if let Some(verdict) = bots.detect(&headers) {
+            bots.record_bot_view_async(
+                article.id.clone(),
+                article.author_id.clone(),
+                Some(context.publication_id.clone()),
+                verdict,
+            );
+        } else {
+            let counted = state.view_tracking_service
+                .record_view(
+                    &article.id,
+                    &article.author_id,
+                    Some(&context.publication_id),
+                    &headers,
+                    user.as_ref().map(|u| u.id.as_str()),
+                    geo.as_ref().and_then(|g| g.0.country.as_deref()),
+                )
+                .await;
+            let result = match counted {
+                Ok(true) => state.article_service.increment_view_count(&article.id).await,
+                Ok(false) => Ok(()),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                tracing::warn!("Failed to increment view count for article {}: {}", article.id, e);
+            }
+            if let Err(e) = state.experiment_service
+                .record_click(&article.id, &headers, user.as_ref().map(|u| u.id.as_str()))
+                .await
+            {
+                tracing::warn!("Failed to record experiment click for article {}: {}", article.id, e);
+            }
+        }
     }
     
-    Ok(Json(json!({
+    let discovery = [
+        (header::LINK, format!("<https://{}/webmention>; rel=\"webmention\"", context.domain)),
+        (header::HeaderName::from_static("x-pingback"), format!("https://{}/xmlrpc", context.domain)),
+    ];
+
+    let sponsors = state.sponsorship_service
+        .render_for_page(&context.publication_id, &[SponsorPlacement::ArticleTop, SponsorPlacement::ArticleBottom])
+        .await;
+    let theme = state.theme_service.get(&context.publication_id).await?;
+    let meter_cookie = article.paywall.as_ref()
+        .and_then(|p| p.meter.as_ref())
+        .and(meter.new_cookie)
+        .map(|value| [(header::SET_COOKIE, meter_set_cookie(&value))]);
+
+    Ok((discovery, meter_cookie, ApiResponse::ok(json!({
         "article": article,
         "related_articles": related_articles,
+        "sponsors": sponsors,
         "publication": {
             "id": context.publication_id,
             "name": context.publication.name,
             "slug": context.publication.slug
         },
+        "theme": theme,
         "domain": context.domain,
         "is_custom_domain": context.is_custom_domain
-    })))
+    }))).into_response())
 }
 
 /// Get publication about page
 /// GET /about (when accessed via custom domain/subdomain)
+#[utoipa::path(
+    get,
+    path = "/about",
+    tag = "publication-content"
+)]
 async fn get_publication_about(
     State(state): State<Arc<AppState>>,
     RequiredPublicationContext(context): RequiredPublicationContext,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting about page for publication: {} via domain: {}", 
            context.publication.name, context.domain);
     
@@ -182,9 +324,15 @@ async fn get_publication_about(
     
     // Get publication statistics
     let stats = get_publication_stats(&state, &context.publication_id).await?;
+
+    // About block configured in the homepage layout
+    let about = state.homepage_service.get(&context.publication_id).await?.about;
+    let theme = state.theme_service.get(&context.publication_id).await?;
     
-    Ok(Json(json!({
+    Ok(ApiResponse::ok(json!({
         "publication": context.publication,
+        "theme": theme,
+        "about": about,
         "writers": writers,
         "stats": stats,
         "domain": context.domain,
@@ -194,10 +342,15 @@ async fn get_publication_about(
 
 /// Get publication writers
 /// GET /writers (when accessed via custom domain/subdomain)
+#[utoipa::path(
+    get,
+    path = "/writers",
+    tag = "publication-content"
+)]
 async fn get_publication_writers(
     State(state): State<Arc<AppState>>,
     RequiredPublicationContext(context): RequiredPublicationContext,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting writers for publication: {} via domain: {}", 
            context.publication.name, context.domain);
     
@@ -219,24 +372,67 @@ async fn get_publication_writers(
         }));
     }
     
-    Ok(Json(json!({
+    let theme = state.theme_service.get(&context.publication_id).await?;
+    
+    Ok(ApiResponse::ok(json!({
         "writers": writers_with_stats,
         "publication": {
             "id": context.publication_id,
             "name": context.publication.name,
             "slug": context.publication.slug
         },
+        "theme": theme,
         "domain": context.domain
     })))
 }
 
+/// Get a published static page of the publication
+/// GET /:slug (when accessed via custom domain/subdomain)
+#[utoipa::path(
+    get,
+    path = "/{slug}",
+    tag = "publication-content",
+    params(("slug" = String, Path))
+)]
+async fn get_publication_page(
+    State(state): State<Arc<AppState>>,
+    RequiredPublicationContext(context): RequiredPublicationContext,
+    Path(slug): Path<String>,
+) -> Result<ApiResponse> {
+    debug!("Getting page '{}' for publication: {} via domain: {}",
+           slug, context.publication.name, context.domain);
+
+    let page = state.page_service
+        .get_published_by_slug(&context.publication_id, &slug)
+        .await?
+        .ok_or_else(|| AppError::not_found("Page"))?;
+
+    let pages = state.page_service.navigation(&context.publication_id).await?;
+    let theme = state.theme_service.get(&context.publication_id).await?;
+
+    Ok(ApiResponse::ok(json!({
+        "page": page,
+        "pages": pages,
+        "publication": context.publication,
+        "theme": theme,
+        "domain": context.domain,
+        "is_custom_domain": context.is_custom_domain
+    })))
+}
+
 /// API endpoint to get publication articles (JSON API)
 /// GET /api/content/articles (when accessed via custom domain/subdomain)
+#[utoipa::path(
+    get,
+    path = "/api/content/articles",
+    tag = "publication-content",
+    params(ArticleListParams)
+)]
 async fn api_get_publication_articles(
     State(state): State<Arc<AppState>>,
     RequiredPublicationContext(context): RequiredPublicationContext,
     Query(params): Query<ArticleListParams>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     let page = params.page.unwrap_or(1) as usize;
     let per_page = params.per_page.unwrap_or(20).min(100) as usize; // Max 100 per page
     
@@ -248,53 +444,34 @@ async fn api_get_publication_articles(
         .count_articles_by_publication(&context.publication_id, None, None)
         .await?;
     
-    Ok(Json(json!({
-        "success": true,
-        "data": {
-            "articles": articles,
-            "pagination": {
-                "page": page,
-                "per_page": per_page,
-                "total": total,
-                "has_more": (page * per_page) < total
-            }
-        },
+    Ok(ApiResponse::ok(json!({
+        "articles": articles,
         "publication_id": context.publication_id
-    })))
+    })).with_pagination(PaginationMeta::new(page, per_page, total)))
 }
 
 /// API endpoint to get featured articles
 /// GET /api/content/featured (when accessed via custom domain/subdomain)
+#[utoipa::path(
+    get,
+    path = "/api/content/featured",
+    tag = "publication-content"
+)]
 async fn api_get_featured_articles(
     State(state): State<Arc<AppState>>,
     RequiredPublicationContext(context): RequiredPublicationContext,
-) -> Result<Json<Value>> {
-    let featured_articles = get_featured_articles_for_publication(&state, &context.publication_id).await?;
+) -> Result<ApiResponse> {
+    let homepage = state.homepage_service.render(&context.publication_id).await?;
     
-    Ok(Json(json!({
-        "success": true,
-        "data": {
-            "articles": featured_articles
-        },
+    Ok(ApiResponse::ok(json!({
+        "layout": homepage.featured.style,
+        "articles": homepage.featured.articles,
         "publication_id": context.publication_id
     })))
 }
 
 // Helper functions
 
-async fn get_featured_articles_for_publication(
-    state: &AppState,
-    publication_id: &str,
-) -> Result<Vec<Value>> {
-    // This would get featured articles for the publication
-    // For now, returning the latest 5 articles as featured
-    let articles = state.article_service
-        .get_articles_by_publication(publication_id, 1, 5, None, None)
-        .await?;
-    
-    Ok(articles.into_iter().map(|a| json!(a)).collect())
-}
-
 async fn get_publication_stats(
     state: &AppState,
     publication_id: &str,
@@ -324,7 +501,8 @@ async fn get_publication_stats(
 
 // Data structures
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct ArticleListParams {
     page: Option<u64>,
     per_page: Option<u64>,
@@ -1,19 +1,21 @@
 use crate::{
     error::{AppError, Result},
-    models::publication::*,
+    models::{publication::*, analytics::PublicationAnomalyQuery, content_transform::*, cors::*, newsletter::*, comment::{BulkModerationRequest, ModerationQueueQuery, UpdateModerationSettingsRequest}, sponsorship::*, style_guide::UpdateStyleGuideRequest, homepage::UpdateHomepageRequest, theme::UpdateThemeRequest, page::*, meter::UpdateMeterSettingsRequest, template::*, view_tracking::ViewStatsQuery, response::ApiResponse},
     services::auth::User,
     state::AppState,
-    utils::middleware::OptionalAuth,
+    utils::{http_cache, middleware::OptionalAuth},
 };
 use axum::{
-    extract::{Path, Query, State},
-    response::Json,
+    extract::{Multipart, Path, Query, State},
+    http::header,
+    response::{IntoResponse, Json, Response},
     routing::{delete, get, post, put},
     Extension, Router,
 };
-use serde_json::{json, Value};
+use serde_json::json;
 use std::sync::Arc;
-use tracing::debug;
+use tracing::{debug, error};
+use utoipa::{IntoParams, OpenApi};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -22,32 +24,150 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/:slug/articles", get(get_publication_articles))
         .route("/:id/members", get(get_members).post(add_member))
         .route("/:id/members/:user_id", put(update_member).delete(remove_member))
+        .route("/:id/invites", get(get_invites).post(create_invite))
+        .route("/:id/invites/:invite_id", delete(revoke_invite))
+        .route("/invites/accept", post(accept_invite))
         .route("/:id/follow", post(follow_publication).delete(unfollow_publication))
+        .route("/:id/audience", get(get_audience_insights))
+        .route("/:id/audience/active-followers", get(get_recently_active_followers))
+        .route("/:id/analytics/anomalies", get(get_traffic_anomalies))
+        .route("/:id/analytics/views", get(get_view_stats))
+        .route("/:id/followers/export", get(export_followers))
+        .route("/:id/transforms", get(get_content_transforms).post(upload_content_transform))
+        .route("/:id/transforms/:name", put(update_content_transform).delete(delete_content_transform))
+        .route("/:id/transforms/:name/versions", get(get_content_transform_versions))
+        .route("/:id/transforms/:name/versions/:version/activate", post(activate_content_transform_version))
+        .route("/:id/newsletters", get(get_newsletters).post(send_newsletter))
+        .route("/:id/newsletters/:newsletter_id", get(get_newsletter))
+        .route("/:id/newsletters/:newsletter_id/deliveries", get(get_newsletter_deliveries))
+        .route("/:id/cors-origins", get(get_cors_origins).post(add_cors_origin))
+        .route("/:id/cors-origins/:origin_id", delete(remove_cors_origin))
+        .route("/:id/comment-moderation", get(get_comment_moderation_settings).put(update_comment_moderation_settings))
+        .route("/:id/comment-moderation/queue", get(get_comment_moderation_queue).post(moderate_comments))
+        .route("/:id/templates", get(get_templates).post(create_template))
+        .route("/:id/templates/:template_id", get(get_template).put(update_template).delete(delete_template))
+        .route("/:id/style-guide", get(get_style_guide).put(update_style_guide))
+        .route("/:id/homepage", get(get_homepage_layout).put(update_homepage_layout))
+        .route("/:id/theme", get(get_theme).put(update_theme))
+        .route("/:id/meter", get(get_meter_settings).put(update_meter_settings))
+        .route("/:id/pages", get(get_pages).post(create_page))
+        .route("/:id/pages/reorder", put(reorder_pages))
+        .route("/:id/pages/:page_id", get(get_page).put(update_page).delete(delete_page))
+        .route("/:id/sponsor-slots", get(get_sponsor_slots).post(create_sponsor_slot))
+        .route("/:id/sponsor-slots/:slot_id", delete(delete_sponsor_slot))
+        .route("/:id/sponsorships", get(get_sponsorships).post(create_sponsorship))
+        .route("/:id/sponsorships/:sponsorship_id", put(update_sponsorship).delete(delete_sponsorship))
+        .route("/:id/sponsorships/:sponsorship_id/asset", post(upload_sponsorship_asset))
+        .route("/:id/sponsorships/:sponsorship_id/report", get(get_sponsorship_report))
+        .route("/:id/sponsors", get(get_sponsor_blocks))
 }
 
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_publications,
+    create_publication,
+    get_publication,
+    update_publication,
+    delete_publication,
+    get_publication_articles,
+    get_members,
+    add_member,
+    update_member,
+    remove_member,
+    get_invites,
+    create_invite,
+    revoke_invite,
+    accept_invite,
+    follow_publication,
+    unfollow_publication,
+    get_audience_insights,
+    get_recently_active_followers,
+    get_traffic_anomalies,
+    get_view_stats,
+    export_followers,
+    get_content_transforms,
+    upload_content_transform,
+    update_content_transform,
+    delete_content_transform,
+    get_content_transform_versions,
+    activate_content_transform_version,
+    get_newsletters,
+    send_newsletter,
+    get_newsletter,
+    get_newsletter_deliveries,
+    get_cors_origins,
+    add_cors_origin,
+    remove_cors_origin,
+    get_comment_moderation_settings,
+    update_comment_moderation_settings,
+    get_comment_moderation_queue,
+    moderate_comments,
+    get_templates,
+    create_template,
+    get_template,
+    update_template,
+    delete_template,
+    get_style_guide,
+    update_style_guide,
+    get_homepage_layout,
+    update_homepage_layout,
+    get_theme,
+    update_theme,
+    get_meter_settings,
+    update_meter_settings,
+    get_pages,
+    create_page,
+    reorder_pages,
+    get_page,
+    update_page,
+    delete_page,
+    get_sponsor_slots,
+    create_sponsor_slot,
+    delete_sponsor_slot,
+    get_sponsorships,
+    create_sponsorship,
+    update_sponsorship,
+    delete_sponsorship,
+    upload_sponsorship_asset,
+    get_sponsorship_report,
+    get_sponsor_blocks,
+))]
+pub struct ApiDoc;
+
 /// 获取出版物列表
 /// GET /api/publications
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications",
+    tag = "publications",
+    params(PublicationQuery)
+)]
 async fn get_publications(
     State(state): State<Arc<AppState>>,
     Query(query): Query<PublicationQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting publications list");
 
     let publications = state.publication_service.get_publications(query).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": publications
-    })))
+    Ok(ApiResponse::ok(publications))
 }
 
 /// 创建出版物
 /// POST /api/publications
+#[utoipa::path(
+    post,
+    path = "/api/blog/publications",
+    tag = "publications",
+    request_body = CreatePublicationRequest,
+    security((), ("bearer_auth" = []))
+)]
 async fn create_publication(
     State(state): State<Arc<AppState>>,
     OptionalAuth(user): OptionalAuth,
     Json(request): Json<CreatePublicationRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     let user = user.ok_or_else(|| AppError::Authentication("Authentication required".to_string()))?;
     debug!("Creating publication: {} for user: {}", request.name, user.id);
 
@@ -56,20 +176,23 @@ async fn create_publication(
         .create_publication(&user.id, request)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": publication,
-        "message": "Publication created successfully"
-    })))
+    Ok(ApiResponse::ok(publication).with_message("Publication created successfully"))
 }
 
 /// 获取出版物详情
 /// GET /api/publications/:slug
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{slug}",
+    tag = "publications",
+    params(("slug" = String, Path)),
+    security((), ("bearer_auth" = []))
+)]
 async fn get_publication(
     State(state): State<Arc<AppState>>,
     Path(slug): Path<String>,
     OptionalAuth(user): OptionalAuth,
-) -> Result<Json<Value>> {
+) -> Result<Response> {
     debug!("Getting publication: {}", slug);
 
     let user_id = user.as_ref().map(|u| u.id.as_str());
@@ -79,20 +202,32 @@ async fn get_publication(
         .await?
         .ok_or_else(|| AppError::NotFound("Publication not found".to_string()))?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": publication
-    })))
+    // 响应包含最近文章，新文章发布后也算修改
+    let last_modified = publication
+        .recent_articles
+        .iter()
+        .filter_map(|article| article.published_at)
+        .fold(publication.publication.updated_at, |latest, published_at| latest.max(published_at));
+
+    Ok(([(header::LAST_MODIFIED, http_cache::last_modified(last_modified))], ApiResponse::ok(publication)).into_response())
 }
 
 /// 更新出版物
 /// PUT /api/publications/:slug
+#[utoipa::path(
+    put,
+    path = "/api/blog/publications/{slug}",
+    tag = "publications",
+    params(("slug" = String, Path)),
+    request_body = UpdatePublicationRequest,
+    security(("bearer_auth" = []))
+)]
 async fn update_publication(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(slug): Path<String>,
     Json(request): Json<UpdatePublicationRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Updating publication: {} by user: {}", slug, user.id);
 
     // 先通过slug获取publication_id
@@ -107,20 +242,23 @@ async fn update_publication(
         .update_publication(&existing.publication.id, &user.id, request)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": updated_publication,
-        "message": "Publication updated successfully"
-    })))
+    Ok(ApiResponse::ok(updated_publication).with_message("Publication updated successfully"))
 }
 
 /// 删除出版物
 /// DELETE /api/publications/:slug
+#[utoipa::path(
+    delete,
+    path = "/api/blog/publications/{slug}",
+    tag = "publications",
+    params(("slug" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn delete_publication(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(slug): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Deleting publication: {} by user: {}", slug, user.id);
 
     // 先通过slug获取publication_id
@@ -135,19 +273,22 @@ async fn delete_publication(
         .delete_publication(&existing.publication.id, &user.id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Publication deleted successfully"
-    })))
+    Ok(ApiResponse::message("Publication deleted successfully"))
 }
 
 /// 获取出版物文章
 /// GET /api/publications/:slug/articles
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{slug}/articles",
+    tag = "publications",
+    params(("slug" = String, Path), ArticlesPaginationQuery)
+)]
 async fn get_publication_articles(
     State(state): State<Arc<AppState>>,
     Path(slug): Path<String>,
     Query(pagination): Query<ArticlesPaginationQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting articles for publication: {}", slug);
 
     // 先通过slug获取publication_id
@@ -165,20 +306,25 @@ async fn get_publication_articles(
         .get_publication_articles(&publication.publication.id, page, limit)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": articles
-    })))
+    Ok(ApiResponse::ok(articles))
 }
 
 /// 添加成员
 /// POST /api/publications/:id/members
+#[utoipa::path(
+    post,
+    path = "/api/blog/publications/{id}/members",
+    tag = "publications",
+    params(("id" = String, Path)),
+    request_body = AddMemberRequest,
+    security(("bearer_auth" = []))
+)]
 async fn add_member(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(publication_id): Path<String>,
     Json(request): Json<AddMemberRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Adding member to publication: {}", publication_id);
 
     let member = state
@@ -186,21 +332,25 @@ async fn add_member(
         .add_member(&publication_id, &user.id, request)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": member,
-        "message": "Member added successfully"
-    })))
+    Ok(ApiResponse::ok(member).with_message("Member added successfully"))
 }
 
 /// 更新成员
 /// PUT /api/publications/:id/members/:user_id
+#[utoipa::path(
+    put,
+    path = "/api/blog/publications/{id}/members/{user_id}",
+    tag = "publications",
+    params(("id" = String, Path), ("user_id" = String, Path)),
+    request_body = UpdateMemberRequest,
+    security(("bearer_auth" = []))
+)]
 async fn update_member(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path((publication_id, member_user_id)): Path<(String, String)>,
     Json(request): Json<UpdateMemberRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Updating member in publication: {}", publication_id);
 
     let updated_member = state
@@ -208,20 +358,23 @@ async fn update_member(
         .update_member(&publication_id, &member_user_id, &user.id, request)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": updated_member,
-        "message": "Member updated successfully"
-    })))
+    Ok(ApiResponse::ok(updated_member).with_message("Member updated successfully"))
 }
 
 /// 移除成员
 /// DELETE /api/publications/:id/members/:user_id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/publications/{id}/members/{user_id}",
+    tag = "publications",
+    params(("id" = String, Path), ("user_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn remove_member(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path((publication_id, member_user_id)): Path<(String, String)>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Removing member from publication: {}", publication_id);
 
     state
@@ -229,19 +382,22 @@ async fn remove_member(
         .remove_member(&publication_id, &member_user_id, &user.id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Member removed successfully"
-    })))
+    Ok(ApiResponse::message("Member removed successfully"))
 }
 
 /// 获取成员列表
 /// GET /api/publications/:id/members
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/members",
+    tag = "publications",
+    params(("id" = String, Path), MembersPaginationQuery)
+)]
 async fn get_members(
     State(state): State<Arc<AppState>>,
     Path(publication_id): Path<String>,
     Query(pagination): Query<MembersPaginationQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting members for publication: {}", publication_id);
 
     let page = pagination.page.unwrap_or(1);
@@ -252,19 +408,115 @@ async fn get_members(
         .get_members(&publication_id, page, limit)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": members
-    })))
+    Ok(ApiResponse::ok(members))
+}
+
+/// 邀请成员
+/// POST /api/publications/:id/invites
+#[utoipa::path(
+    post,
+    path = "/api/blog/publications/{id}/invites",
+    tag = "publications",
+    params(("id" = String, Path)),
+    request_body = CreateInviteRequest,
+    security(("bearer_auth" = []))
+)]
+async fn create_invite(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Json(request): Json<CreateInviteRequest>,
+) -> Result<ApiResponse> {
+    debug!("Inviting member to publication: {}", publication_id);
+
+    let invite = state
+        .publication_service
+        .create_invite(&publication_id, &user.id, request)
+        .await?;
+
+    Ok(ApiResponse::ok(invite).with_message("Invite created successfully"))
+}
+
+/// 待接受的邀请
+/// GET /api/publications/:id/invites
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/invites",
+    tag = "publications",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_invites(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+) -> Result<ApiResponse> {
+    let invites = state
+        .publication_service
+        .get_invites(&publication_id, &user.id)
+        .await?;
+
+    Ok(ApiResponse::ok(invites))
+}
+
+/// 撤销邀请
+/// DELETE /api/publications/:id/invites/:invite_id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/publications/{id}/invites/{invite_id}",
+    tag = "publications",
+    params(("id" = String, Path), ("invite_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn revoke_invite(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((publication_id, invite_id)): Path<(String, String)>,
+) -> Result<ApiResponse> {
+    state
+        .publication_service
+        .revoke_invite(&publication_id, &invite_id, &user.id)
+        .await?;
+
+    Ok(ApiResponse::message("Invite revoked successfully"))
+}
+
+/// 接受邀请
+/// POST /api/publications/invites/accept
+#[utoipa::path(
+    post,
+    path = "/api/blog/publications/invites/accept",
+    tag = "publications",
+    request_body = AcceptInviteRequest,
+    security(("bearer_auth" = []))
+)]
+async fn accept_invite(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(request): Json<AcceptInviteRequest>,
+) -> Result<ApiResponse> {
+    let member = state
+        .publication_service
+        .accept_invite(&user.id, &user.email, request)
+        .await?;
+
+    Ok(ApiResponse::ok(member).with_message("Joined publication successfully"))
 }
 
 /// 关注出版物
 /// POST /api/publications/:id/follow
+#[utoipa::path(
+    post,
+    path = "/api/blog/publications/{id}/follow",
+    tag = "publications",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn follow_publication(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(publication_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("User {} following publication: {}", user.id, publication_id);
 
     state
@@ -272,19 +524,23 @@ async fn follow_publication(
         .follow_publication(&publication_id, &user.id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Publication followed successfully"
-    })))
+    Ok(ApiResponse::message("Publication followed successfully"))
 }
 
 /// 取消关注出版物
 /// DELETE /api/publications/:id/follow
+#[utoipa::path(
+    delete,
+    path = "/api/blog/publications/{id}/follow",
+    tag = "publications",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn unfollow_publication(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(publication_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("User {} unfollowing publication: {}", user.id, publication_id);
 
     state
@@ -292,20 +548,1196 @@ async fn unfollow_publication(
         .unfollow_publication(&publication_id, &user.id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Publication unfollowed successfully"
-    })))
+    Ok(ApiResponse::message("Publication unfollowed successfully"))
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct ArticlesPaginationQuery {
     page: Option<usize>,
     limit: Option<usize>,
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct MembersPaginationQuery {
     page: Option<usize>,
     limit: Option<usize>,
 }
+
+/// 获取出版物受众洞察
+/// GET /api/publications/:id/audience
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/audience",
+    tag = "publications",
+    params(("id" = String, Path), AudienceInsightsQuery),
+    security(("bearer_auth" = []))
+)]
+async fn get_audience_insights(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Query(query): Query<AudienceInsightsQuery>,
+) -> Result<ApiResponse> {
+    debug!("Getting audience insights for publication: {}", publication_id);
+
+    let insights = state
+        .publication_service
+        .get_audience_insights(&publication_id, &user.id, query.days.unwrap_or(30))
+        .await?;
+
+    Ok(ApiResponse::ok(insights))
+}
+
+/// 获取出版物的流量异常记录（仅所有者和编辑）
+/// GET /api/publications/:id/analytics/anomalies
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/analytics/anomalies",
+    tag = "publications",
+    params(("id" = String, Path), PublicationAnomalyQuery),
+    security(("bearer_auth" = []))
+)]
+async fn get_traffic_anomalies(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Query(query): Query<PublicationAnomalyQuery>,
+) -> Result<ApiResponse> {
+    debug!("Getting traffic anomalies for publication: {}", publication_id);
+
+    state.publication_service.check_audience_access(&publication_id, &user.id).await?;
+
+    let anomalies = state
+        .anomaly_service
+        .get_publication_anomalies(&publication_id, query.limit.unwrap_or(20).min(100))
+        .await?;
+
+    Ok(ApiResponse::ok(anomalies))
+}
+
+/// 出版物最近 N 天去重后的浏览数和独立访客数（仅所有者和编辑）
+/// GET /api/publications/:id/analytics/views?days=30
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/analytics/views",
+    tag = "publications",
+    params(("id" = String, Path), ViewStatsQuery),
+    security(("bearer_auth" = []))
+)]
+async fn get_view_stats(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Query(query): Query<ViewStatsQuery>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_audience_access(&publication_id, &user.id).await?;
+
+    let stats = state
+        .view_tracking_service
+        .get_publication_stats(&publication_id, query)
+        .await?;
+
+    Ok(ApiResponse::ok(stats))
+}
+
+/// 获取最近活跃的关注者
+/// GET /api/publications/:id/audience/active-followers
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/audience/active-followers",
+    tag = "publications",
+    params(("id" = String, Path), AudienceInsightsQuery),
+    security(("bearer_auth" = []))
+)]
+async fn get_recently_active_followers(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Query(query): Query<AudienceInsightsQuery>,
+) -> Result<ApiResponse> {
+    debug!("Getting recently active followers for publication: {}", publication_id);
+
+    let followers = state
+        .publication_service
+        .get_recently_active_followers(&publication_id, &user.id, query.limit.unwrap_or(20))
+        .await?;
+
+    Ok(ApiResponse::ok(followers))
+}
+
+/// 导出关注者（仅包含同意受众洞察的关注者）
+/// GET /api/publications/:id/followers/export
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/followers/export",
+    tag = "publications",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn export_followers(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+) -> Result<ApiResponse> {
+    debug!("Exporting followers for publication: {}", publication_id);
+
+    let rows = state
+        .publication_service
+        .export_followers(&publication_id, &user.id)
+        .await?;
+
+    Ok(ApiResponse::ok(json!({
+        "followers": rows,
+        "total": rows.len()
+    })))
+}
+
+/// 获取出版物当前生效的内容转换器
+/// GET /api/publications/:id/transforms
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/transforms",
+    tag = "publications",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_content_transforms(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let transforms = state.content_transform_service.get_transforms(&publication_id).await?;
+
+    Ok(ApiResponse::ok(transforms))
+}
+
+/// 上传内容转换器（同名上传会产生新版本并立即生效）
+/// POST /api/publications/:id/transforms
+#[utoipa::path(
+    post,
+    path = "/api/blog/publications/{id}/transforms",
+    tag = "publications",
+    params(("id" = String, Path)),
+    request_body = UploadContentTransformRequest,
+    security(("bearer_auth" = []))
+)]
+async fn upload_content_transform(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Json(request): Json<UploadContentTransformRequest>,
+) -> Result<ApiResponse> {
+    debug!("Uploading content transform {} for publication: {}", request.name, publication_id);
+
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let transform = state
+        .content_transform_service
+        .upload(&publication_id, &user.id, request)
+        .await?;
+
+    Ok(ApiResponse::ok(transform).with_message("Content transform uploaded successfully"))
+}
+
+/// 启用/停用内容转换器或调整执行顺序
+/// PUT /api/publications/:id/transforms/:name
+#[utoipa::path(
+    put,
+    path = "/api/blog/publications/{id}/transforms/{name}",
+    tag = "publications",
+    params(("id" = String, Path), ("name" = String, Path)),
+    request_body = UpdateContentTransformRequest,
+    security(("bearer_auth" = []))
+)]
+async fn update_content_transform(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((publication_id, name)): Path<(String, String)>,
+    Json(request): Json<UpdateContentTransformRequest>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let transform = state
+        .content_transform_service
+        .update_transform(&publication_id, &name, request)
+        .await?;
+
+    Ok(ApiResponse::ok(transform))
+}
+
+/// 删除内容转换器的所有版本
+/// DELETE /api/publications/:id/transforms/:name
+#[utoipa::path(
+    delete,
+    path = "/api/blog/publications/{id}/transforms/{name}",
+    tag = "publications",
+    params(("id" = String, Path), ("name" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn delete_content_transform(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((publication_id, name)): Path<(String, String)>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    state.content_transform_service.delete_transform(&publication_id, &name).await?;
+
+    Ok(ApiResponse::message("Content transform deleted successfully"))
+}
+
+/// 获取内容转换器的版本历史
+/// GET /api/publications/:id/transforms/:name/versions
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/transforms/{name}/versions",
+    tag = "publications",
+    params(("id" = String, Path), ("name" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_content_transform_versions(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((publication_id, name)): Path<(String, String)>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let versions = state.content_transform_service.get_versions(&publication_id, &name).await?;
+
+    Ok(ApiResponse::ok(versions))
+}
+
+/// 切换内容转换器的生效版本（回滚）
+/// POST /api/publications/:id/transforms/:name/versions/:version/activate
+#[utoipa::path(
+    post,
+    path = "/api/blog/publications/{id}/transforms/{name}/versions/{version}/activate",
+    tag = "publications",
+    params(("id" = String, Path), ("name" = String, Path), ("version" = i32, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn activate_content_transform_version(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((publication_id, name, version)): Path<(String, String, i32)>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let transform = state
+        .content_transform_service
+        .activate_version(&publication_id, &name, version)
+        .await?;
+
+    Ok(ApiResponse::ok(transform).with_message("Content transform version activated"))
+}
+
+/// 把一篇已发布的文章作为 Newsletter 发送给关注者和订阅者（后台投递）
+/// POST /api/publications/:id/newsletters
+#[utoipa::path(
+    post,
+    path = "/api/blog/publications/{id}/newsletters",
+    tag = "publications",
+    params(("id" = String, Path)),
+    request_body = SendNewsletterRequest,
+    security(("bearer_auth" = []))
+)]
+async fn send_newsletter(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Json(request): Json<SendNewsletterRequest>,
+) -> Result<ApiResponse> {
+    debug!("Sending newsletter for article {} in publication: {}", request.article_id, publication_id);
+
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let newsletter = state
+        .newsletter_service
+        .send_newsletter(&publication_id, &user.id, request)
+        .await?;
+
+    Ok(ApiResponse::ok(newsletter).with_message("Newsletter queued for delivery"))
+}
+
+/// 获取出版物已发送的 Newsletter
+/// GET /api/publications/:id/newsletters
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/newsletters",
+    tag = "publications",
+    params(("id" = String, Path), NewsletterListQuery),
+    security(("bearer_auth" = []))
+)]
+async fn get_newsletters(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Query(query): Query<NewsletterListQuery>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let newsletters = state.newsletter_service.list_newsletters(&publication_id, query).await?;
+
+    Ok(ApiResponse::ok(newsletters))
+}
+
+/// 获取单期 Newsletter 的发送状态
+/// GET /api/publications/:id/newsletters/:newsletter_id
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/newsletters/{newsletter_id}",
+    tag = "publications",
+    params(("id" = String, Path), ("newsletter_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_newsletter(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((publication_id, newsletter_id)): Path<(String, String)>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let newsletter = state.newsletter_service.get_newsletter(&publication_id, &newsletter_id).await?;
+
+    Ok(ApiResponse::ok(newsletter))
+}
+
+/// 获取单期 Newsletter 每个收件人的投递状态
+/// GET /api/publications/:id/newsletters/:newsletter_id/deliveries
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/newsletters/{newsletter_id}/deliveries",
+    tag = "publications",
+    params(("id" = String, Path), ("newsletter_id" = String, Path), DeliveryListQuery),
+    security(("bearer_auth" = []))
+)]
+async fn get_newsletter_deliveries(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((publication_id, newsletter_id)): Path<(String, String)>,
+    Query(query): Query<DeliveryListQuery>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let deliveries = state
+        .newsletter_service
+        .list_deliveries(&publication_id, &newsletter_id, query)
+        .await?;
+
+    Ok(ApiResponse::ok(deliveries))
+}
+
+/// 获取允许跨域读取出版物内容的来源
+/// GET /api/publications/:id/cors-origins
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/cors-origins",
+    tag = "publications",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_cors_origins(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let origins = state.cors_service.list_origins(&publication_id).await?;
+
+    Ok(ApiResponse::ok(origins))
+}
+
+/// 添加允许的来源（如嵌入出版物内容的客户站点）
+/// POST /api/publications/:id/cors-origins
+#[utoipa::path(
+    post,
+    path = "/api/blog/publications/{id}/cors-origins",
+    tag = "publications",
+    params(("id" = String, Path)),
+    request_body = AddCorsOriginRequest,
+    security(("bearer_auth" = []))
+)]
+async fn add_cors_origin(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Json(request): Json<AddCorsOriginRequest>,
+) -> Result<ApiResponse> {
+    debug!("Adding CORS origin {} for publication: {}", request.origin, publication_id);
+
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let origin = state.cors_service.add_origin(&publication_id, &user.id, request).await?;
+
+    Ok(ApiResponse::ok(origin))
+}
+
+/// 移除允许的来源
+/// DELETE /api/publications/:id/cors-origins/:origin_id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/publications/{id}/cors-origins/{origin_id}",
+    tag = "publications",
+    params(("id" = String, Path), ("origin_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn remove_cors_origin(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((publication_id, origin_id)): Path<(String, String)>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    state.cors_service.remove_origin(&publication_id, &origin_id).await?;
+
+    Ok(ApiResponse::message("CORS origin removed"))
+}
+
+/// 获取评论审核设置
+/// GET /api/publications/:id/comment-moderation
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/comment-moderation",
+    tag = "publications",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_comment_moderation_settings(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_comment_moderation_access(&publication_id, &user.id).await?;
+
+    let settings = state.comment_service.get_moderation_settings(&publication_id).await?;
+
+    Ok(ApiResponse::ok(settings))
+}
+
+/// 更新评论审核设置（先审后发、垃圾评论阈值、屏蔽词）
+/// PUT /api/publications/:id/comment-moderation
+#[utoipa::path(
+    put,
+    path = "/api/blog/publications/{id}/comment-moderation",
+    tag = "publications",
+    params(("id" = String, Path)),
+    request_body = UpdateModerationSettingsRequest,
+    security(("bearer_auth" = []))
+)]
+async fn update_comment_moderation_settings(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Json(request): Json<UpdateModerationSettingsRequest>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let settings = state.comment_service.update_moderation_settings(&publication_id, request).await?;
+
+    Ok(ApiResponse::ok(settings))
+}
+
+/// 审核队列：等待审核或被判为垃圾的评论
+/// GET /api/publications/:id/comment-moderation/queue?status=pending&page=1&limit=50
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/comment-moderation/queue",
+    tag = "publications",
+    params(("id" = String, Path), ModerationQueueQuery),
+    security(("bearer_auth" = []))
+)]
+async fn get_comment_moderation_queue(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Query(query): Query<ModerationQueueQuery>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_comment_moderation_access(&publication_id, &user.id).await?;
+
+    let comments = state.comment_service.list_moderation_queue(&publication_id, query).await?;
+
+    Ok(ApiResponse::ok(comments))
+}
+
+/// 批量通过、拒绝或标记为垃圾评论
+/// POST /api/publications/:id/comment-moderation/queue
+#[utoipa::path(
+    post,
+    path = "/api/blog/publications/{id}/comment-moderation/queue",
+    tag = "publications",
+    params(("id" = String, Path)),
+    request_body = BulkModerationRequest,
+    security(("bearer_auth" = []))
+)]
+async fn moderate_comments(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Json(request): Json<BulkModerationRequest>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_comment_moderation_access(&publication_id, &user.id).await?;
+
+    let updated = state.comment_service.moderate_comments(&publication_id, &user.id, request).await?;
+
+    Ok(ApiResponse::ok(json!({
+        "updated": updated
+    })))
+}
+
+/// 出版物的文章模板列表，成员都可以查看
+/// GET /api/publications/:id/templates
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/templates",
+    tag = "publications",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_templates(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.read").await?;
+
+    let templates = state.template_service.list(&publication_id).await?;
+
+    Ok(ApiResponse::ok(templates))
+}
+
+/// 创建文章模板（所有者和编辑）
+/// POST /api/publications/:id/templates
+#[utoipa::path(
+    post,
+    path = "/api/blog/publications/{id}/templates",
+    tag = "publications",
+    params(("id" = String, Path)),
+    request_body = CreateTemplateRequest,
+    security(("bearer_auth" = []))
+)]
+async fn create_template(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Json(request): Json<CreateTemplateRequest>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_template_management_access(&publication_id, &user.id).await?;
+
+    let template = state.template_service.create(&publication_id, &user.id, request).await?;
+
+    Ok(ApiResponse::ok(template).with_message("Template created"))
+}
+
+/// GET /api/publications/:id/templates/:template_id
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/templates/{template_id}",
+    tag = "publications",
+    params(("id" = String, Path), ("template_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_template(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((publication_id, template_id)): Path<(String, String)>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.read").await?;
+
+    let template = state.template_service.get(&publication_id, &template_id).await?;
+
+    Ok(ApiResponse::ok(template))
+}
+
+/// 修改文章模板，只影响之后创建的文章和之后的发布检查
+/// PUT /api/publications/:id/templates/:template_id
+#[utoipa::path(
+    put,
+    path = "/api/blog/publications/{id}/templates/{template_id}",
+    tag = "publications",
+    params(("id" = String, Path), ("template_id" = String, Path)),
+    request_body = UpdateTemplateRequest,
+    security(("bearer_auth" = []))
+)]
+async fn update_template(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((publication_id, template_id)): Path<(String, String)>,
+    Json(request): Json<UpdateTemplateRequest>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_template_management_access(&publication_id, &user.id).await?;
+
+    let template = state.template_service.update(&publication_id, &template_id, request).await?;
+
+    Ok(ApiResponse::ok(template))
+}
+
+/// DELETE /api/publications/:id/templates/:template_id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/publications/{id}/templates/{template_id}",
+    tag = "publications",
+    params(("id" = String, Path), ("template_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn delete_template(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((publication_id, template_id)): Path<(String, String)>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_template_management_access(&publication_id, &user.id).await?;
+
+    state.template_service.delete(&publication_id, &template_id).await?;
+
+    Ok(ApiResponse::message("Template deleted"))
+}
+
+/// 出版物的写作规范，成员都可以查看
+/// GET /api/publications/:id/style-guide
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/style-guide",
+    tag = "publications",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_style_guide(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.read").await?;
+
+    let guide = state.style_guide_service.get(Some(&publication_id)).await?;
+
+    Ok(ApiResponse::ok(guide))
+}
+
+/// 更新写作规范（禁用词、被动语态、标题层级、替代文本、提交前强制检查）
+/// PUT /api/publications/:id/style-guide
+#[utoipa::path(
+    put,
+    path = "/api/blog/publications/{id}/style-guide",
+    tag = "publications",
+    params(("id" = String, Path)),
+    request_body = UpdateStyleGuideRequest,
+    security(("bearer_auth" = []))
+)]
+async fn update_style_guide(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Json(request): Json<UpdateStyleGuideRequest>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let guide = state.style_guide_service.update(&publication_id, request).await?;
+
+    Ok(ApiResponse::ok(guide))
+}
+
+/// 首页布局（编辑用，未填入文章）
+/// GET /api/publications/:id/homepage
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/homepage",
+    tag = "publications",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_homepage_layout(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.read").await?;
+
+    let layout = state.homepage_service.get(&publication_id).await?;
+
+    Ok(ApiResponse::ok(layout))
+}
+
+/// 替换首页布局（精选文章、栏目、关于区块）
+/// PUT /api/publications/:id/homepage
+#[utoipa::path(
+    put,
+    path = "/api/blog/publications/{id}/homepage",
+    tag = "publications",
+    params(("id" = String, Path)),
+    request_body = UpdateHomepageRequest,
+    security(("bearer_auth" = []))
+)]
+async fn update_homepage_layout(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Json(request): Json<UpdateHomepageRequest>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let layout = state.homepage_service.update(&publication_id, &user.id, request).await?;
+
+    Ok(ApiResponse::ok(layout).with_message("Homepage updated"))
+}
+
+/// 出版物主题（公开，前端渲染出版物页面时使用）
+/// GET /api/publications/:id/theme
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/theme",
+    tag = "publications",
+    params(("id" = String, Path))
+)]
+async fn get_theme(
+    State(state): State<Arc<AppState>>,
+    Path(publication_id): Path<String>,
+) -> Result<ApiResponse> {
+    let theme = state.theme_service.get(&publication_id).await?;
+
+    Ok(ApiResponse::ok(theme))
+}
+
+/// 修改主题（颜色、字体、标志、自定义 CSS）
+/// PUT /api/publications/:id/theme
+#[utoipa::path(
+    put,
+    path = "/api/blog/publications/{id}/theme",
+    tag = "publications",
+    params(("id" = String, Path)),
+    request_body = UpdateThemeRequest,
+    security(("bearer_auth" = []))
+)]
+async fn update_theme(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Json(request): Json<UpdateThemeRequest>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let theme = state.theme_service.update(&publication_id, request).await?;
+
+    Ok(ApiResponse::ok(theme).with_message("Theme updated"))
+}
+
+/// 计量付费墙设置（每月免费阅读的付费文章篇数）
+/// GET /api/publications/:id/meter
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/meter",
+    tag = "publications",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_meter_settings(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.read").await?;
+
+    let settings = state.meter_service.get_settings(&publication_id).await?;
+
+    Ok(ApiResponse::ok(settings))
+}
+
+/// PUT /api/publications/:id/meter
+#[utoipa::path(
+    put,
+    path = "/api/blog/publications/{id}/meter",
+    tag = "publications",
+    params(("id" = String, Path)),
+    request_body = UpdateMeterSettingsRequest,
+    security(("bearer_auth" = []))
+)]
+async fn update_meter_settings(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Json(request): Json<UpdateMeterSettingsRequest>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let settings = state.meter_service.update_settings(&publication_id, request).await?;
+
+    Ok(ApiResponse::ok(settings).with_message("Meter settings updated"))
+}
+
+/// 出版物的静态页面（包括草稿），按导航顺序
+/// GET /api/publications/:id/pages
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/pages",
+    tag = "publications",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_pages(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.read").await?;
+
+    let pages = state.page_service.list(&publication_id).await?;
+
+    Ok(ApiResponse::ok(pages))
+}
+
+/// 创建静态页面
+/// POST /api/publications/:id/pages
+#[utoipa::path(
+    post,
+    path = "/api/blog/publications/{id}/pages",
+    tag = "publications",
+    params(("id" = String, Path)),
+    request_body = CreatePageRequest,
+    security(("bearer_auth" = []))
+)]
+async fn create_page(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Json(request): Json<CreatePageRequest>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let page = state.page_service.create(&publication_id, &user.id, request).await?;
+
+    Ok(ApiResponse::ok(page).with_message("Page created"))
+}
+
+/// 调整页面在导航中的顺序
+/// PUT /api/publications/:id/pages/reorder
+#[utoipa::path(
+    put,
+    path = "/api/blog/publications/{id}/pages/reorder",
+    tag = "publications",
+    params(("id" = String, Path)),
+    request_body = ReorderPagesRequest,
+    security(("bearer_auth" = []))
+)]
+async fn reorder_pages(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Json(request): Json<ReorderPagesRequest>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let pages = state.page_service.reorder(&publication_id, request).await?;
+
+    Ok(ApiResponse::ok(pages).with_message("Pages reordered"))
+}
+
+/// GET /api/publications/:id/pages/:page_id
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/pages/{page_id}",
+    tag = "publications",
+    params(("id" = String, Path), ("page_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_page(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((publication_id, page_id)): Path<(String, String)>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.read").await?;
+
+    let page = state.page_service.get(&publication_id, &page_id).await?;
+
+    Ok(ApiResponse::ok(page))
+}
+
+/// 修改页面，改为 published 时首次记录发布时间
+/// PUT /api/publications/:id/pages/:page_id
+#[utoipa::path(
+    put,
+    path = "/api/blog/publications/{id}/pages/{page_id}",
+    tag = "publications",
+    params(("id" = String, Path), ("page_id" = String, Path)),
+    request_body = UpdatePageRequest,
+    security(("bearer_auth" = []))
+)]
+async fn update_page(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((publication_id, page_id)): Path<(String, String)>,
+    Json(request): Json<UpdatePageRequest>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let page = state.page_service.update(&publication_id, &page_id, request).await?;
+
+    Ok(ApiResponse::ok(page).with_message("Page updated"))
+}
+
+/// DELETE /api/publications/:id/pages/:page_id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/publications/{id}/pages/{page_id}",
+    tag = "publications",
+    params(("id" = String, Path), ("page_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn delete_page(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((publication_id, page_id)): Path<(String, String)>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    state.page_service.delete(&publication_id, &page_id).await?;
+
+    Ok(ApiResponse::message("Page deleted"))
+}
+
+/// 出版物的赞助位
+/// GET /api/publications/:id/sponsor-slots
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/sponsor-slots",
+    tag = "publications",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_sponsor_slots(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let slots = state.sponsorship_service.list_slots(&publication_id).await?;
+
+    Ok(ApiResponse::ok(slots))
+}
+
+/// 定义赞助位（首页顶部、文章列表、文章前后）
+/// POST /api/publications/:id/sponsor-slots
+#[utoipa::path(
+    post,
+    path = "/api/blog/publications/{id}/sponsor-slots",
+    tag = "publications",
+    params(("id" = String, Path)),
+    request_body = CreateSponsorSlotRequest,
+    security(("bearer_auth" = []))
+)]
+async fn create_sponsor_slot(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Json(request): Json<CreateSponsorSlotRequest>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let slot = state.sponsorship_service.create_slot(&publication_id, request).await?;
+
+    Ok(ApiResponse::ok(slot).with_message("Sponsor slot created"))
+}
+
+/// DELETE /api/publications/:id/sponsor-slots/:slot_id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/publications/{id}/sponsor-slots/{slot_id}",
+    tag = "publications",
+    params(("id" = String, Path), ("slot_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn delete_sponsor_slot(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((publication_id, slot_id)): Path<(String, String)>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    state.sponsorship_service.delete_slot(&publication_id, &slot_id).await?;
+
+    Ok(ApiResponse::message("Sponsor slot deleted"))
+}
+
+/// 出版物的全部赞助（包括已结束和未开始的）
+/// GET /api/publications/:id/sponsorships
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/sponsorships",
+    tag = "publications",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_sponsorships(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let sponsorships = state.sponsorship_service.list(&publication_id).await?;
+
+    Ok(ApiResponse::ok(sponsorships))
+}
+
+/// 排期一个赞助，同一赞助位的投放期不能重叠
+/// POST /api/publications/:id/sponsorships
+#[utoipa::path(
+    post,
+    path = "/api/blog/publications/{id}/sponsorships",
+    tag = "publications",
+    params(("id" = String, Path)),
+    request_body = CreateSponsorshipRequest,
+    security(("bearer_auth" = []))
+)]
+async fn create_sponsorship(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(publication_id): Path<String>,
+    Json(request): Json<CreateSponsorshipRequest>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let sponsorship = state.sponsorship_service.create(&publication_id, &user.id, request).await?;
+
+    Ok(ApiResponse::ok(sponsorship).with_message("Sponsorship scheduled"))
+}
+
+/// PUT /api/publications/:id/sponsorships/:sponsorship_id
+#[utoipa::path(
+    put,
+    path = "/api/blog/publications/{id}/sponsorships/{sponsorship_id}",
+    tag = "publications",
+    params(("id" = String, Path), ("sponsorship_id" = String, Path)),
+    request_body = UpdateSponsorshipRequest,
+    security(("bearer_auth" = []))
+)]
+async fn update_sponsorship(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((publication_id, sponsorship_id)): Path<(String, String)>,
+    Json(request): Json<UpdateSponsorshipRequest>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let sponsorship = state.sponsorship_service.update(&publication_id, &sponsorship_id, request).await?;
+
+    Ok(ApiResponse::ok(sponsorship))
+}
+
+/// DELETE /api/publications/:id/sponsorships/:sponsorship_id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/publications/{id}/sponsorships/{sponsorship_id}",
+    tag = "publications",
+    params(("id" = String, Path), ("sponsorship_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn delete_sponsorship(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((publication_id, sponsorship_id)): Path<(String, String)>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    state.sponsorship_service.delete(&publication_id, &sponsorship_id).await?;
+
+    Ok(ApiResponse::message("Sponsorship deleted"))
+}
+
+/// 上传赞助商图片（multipart 字段 file）
+/// POST /api/publications/:id/sponsorships/:sponsorship_id/asset
+#[utoipa::path(
+    post,
+    path = "/api/blog/publications/{id}/sponsorships/{sponsorship_id}/asset",
+    tag = "publications",
+    params(("id" = String, Path), ("sponsorship_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn upload_sponsorship_asset(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((publication_id, sponsorship_id)): Path<(String, String)>,
+    mut multipart: Multipart,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let mut file: Option<(String, String, Vec<u8>)> = None;
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        error!("Failed to process multipart field: {}", e);
+        AppError::BadRequest("无法处理上传的文件".to_string())
+    })? {
+        if field.name() == Some("file") {
+            let filename = field.file_name().unwrap_or("sponsor").to_string();
+            let content_type = field.content_type().unwrap_or("application/octet-stream").to_string();
+            let data = field.bytes().await.map_err(|e| {
+                error!("Failed to read sponsor asset: {}", e);
+                AppError::BadRequest("无法读取文件数据".to_string())
+            })?;
+            file = Some((filename, content_type, data.to_vec()));
+            break;
+        }
+    }
+    let (filename, content_type, data) = file.ok_or_else(|| AppError::BadRequest("未找到上传的文件".to_string()))?;
+
+    let sponsorship = state.sponsorship_service
+        .upload_asset(&publication_id, &sponsorship_id, &user.id, &filename, &content_type, data)
+        .await?;
+
+    Ok(ApiResponse::ok(sponsorship))
+}
+
+/// 赞助的展示报告（总展示次数和每日展示次数）
+/// GET /api/publications/:id/sponsorships/:sponsorship_id/report
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/sponsorships/{sponsorship_id}/report",
+    tag = "publications",
+    params(("id" = String, Path), ("sponsorship_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_sponsorship_report(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((publication_id, sponsorship_id)): Path<(String, String)>,
+) -> Result<ApiResponse> {
+    state.publication_service.check_permission(&publication_id, &user.id, "publication.manage_settings").await?;
+
+    let report = state.sponsorship_service.report(&publication_id, &sponsorship_id).await?;
+
+    Ok(ApiResponse::ok(report))
+}
+
+/// 当前投放中的赞助内容，供前端在出版物页面渲染；每次请求计入展示次数
+/// GET /api/publications/:id/sponsors?placements=article_top,article_bottom
+#[utoipa::path(
+    get,
+    path = "/api/blog/publications/{id}/sponsors",
+    tag = "publications",
+    params(("id" = String, Path), SponsorBlockQuery)
+)]
+async fn get_sponsor_blocks(
+    State(state): State<Arc<AppState>>,
+    Path(publication_id): Path<String>,
+    Query(query): Query<SponsorBlockQuery>,
+) -> Result<ApiResponse> {
+    let placements: Vec<SponsorPlacement> = match query.placements.as_deref() {
+        Some(placements) => placements
+            .split(',')
+            .map(|p| serde_json::from_value(json!(p.trim())))
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|_| AppError::BadRequest("Unknown sponsor placement".to_string()))?,
+        None => vec![
+            SponsorPlacement::HomeTop,
+            SponsorPlacement::ArticleList,
+            SponsorPlacement::ArticleTop,
+            SponsorPlacement::ArticleBottom,
+        ],
+    };
+
+    let blocks = state.sponsorship_service.render_for_page(&publication_id, &placements).await;
+
+    Ok(ApiResponse::ok(blocks))
+}
@@ -0,0 +1,163 @@
+use crate::{
+    error::Result,
+    models::{reading_queue::*, response::ApiResponse},
+    services::auth::User,
+    state::AppState,
+};
+use axum::{
+    extract::{Path, State},
+    response::Json,
+    routing::{delete, get, post, put},
+    Extension, Router,
+};
+use std::sync::Arc;
+use tracing::debug;
+use utoipa::OpenApi;
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(get_queue).post(add_to_queue))
+        .route("/reorder", put(reorder_queue))
+        .route("/sync", post(sync_queue))
+        .route("/:article_id", delete(remove_from_queue))
+        .route("/:article_id/progress", put(update_progress))
+}
+
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_queue,
+    add_to_queue,
+    reorder_queue,
+    sync_queue,
+    remove_from_queue,
+    update_progress,
+))]
+pub struct ApiDoc;
+
+/// Get the user's ordered reading queue
+/// GET /api/blog/reading-queue
+#[utoipa::path(
+    get,
+    path = "/api/blog/reading-queue",
+    tag = "reading-queue",
+    security(("bearer_auth" = []))
+)]
+async fn get_queue(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    debug!("Getting reading queue for user: {}", user.id);
+
+    let queue = state.reading_queue_service.get_queue(&user.id).await?;
+
+    Ok(ApiResponse::ok(queue))
+}
+
+/// Add an article to the reading queue
+/// POST /api/blog/reading-queue
+#[utoipa::path(
+    post,
+    path = "/api/blog/reading-queue",
+    tag = "reading-queue",
+    request_body = AddToQueueRequest,
+    security(("bearer_auth" = []))
+)]
+async fn add_to_queue(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(request): Json<AddToQueueRequest>,
+) -> Result<ApiResponse> {
+    debug!("Adding article {} to reading queue of user: {}", request.article_id, user.id);
+
+    let item = state.reading_queue_service.add_to_queue(&user.id, request).await?;
+
+    Ok(ApiResponse::ok(item).with_message("Article added to reading queue"))
+}
+
+/// Remove an article from the reading queue
+/// DELETE /api/blog/reading-queue/:article_id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/reading-queue/{article_id}",
+    tag = "reading-queue",
+    params(("article_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn remove_from_queue(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(article_id): Path<String>,
+) -> Result<ApiResponse> {
+    debug!("Removing article {} from reading queue of user: {}", article_id, user.id);
+
+    state.reading_queue_service.remove_from_queue(&user.id, &article_id).await?;
+
+    Ok(ApiResponse::message("Article removed from reading queue"))
+}
+
+/// Reorder the reading queue
+/// PUT /api/blog/reading-queue/reorder
+#[utoipa::path(
+    put,
+    path = "/api/blog/reading-queue/reorder",
+    tag = "reading-queue",
+    request_body = ReorderQueueRequest,
+    security(("bearer_auth" = []))
+)]
+async fn reorder_queue(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(request): Json<ReorderQueueRequest>,
+) -> Result<ApiResponse> {
+    debug!("Reordering reading queue for user: {}", user.id);
+
+    let queue = state.reading_queue_service.reorder(&user.id, request).await?;
+
+    Ok(ApiResponse::ok(queue))
+}
+
+/// Update reading progress of a queued article
+/// PUT /api/blog/reading-queue/:article_id/progress
+#[utoipa::path(
+    put,
+    path = "/api/blog/reading-queue/{article_id}/progress",
+    tag = "reading-queue",
+    params(("article_id" = String, Path)),
+    request_body = UpdateQueueProgressRequest,
+    security(("bearer_auth" = []))
+)]
+async fn update_progress(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(article_id): Path<String>,
+    Json(request): Json<UpdateQueueProgressRequest>,
+) -> Result<ApiResponse> {
+    let item = state
+        .reading_queue_service
+        .update_progress(&user.id, &article_id, request)
+        .await?;
+
+    Ok(ApiResponse::ok(item))
+}
+
+/// Sync offline queue changes and fetch server changes since the last token
+/// POST /api/blog/reading-queue/sync
+#[utoipa::path(
+    post,
+    path = "/api/blog/reading-queue/sync",
+    tag = "reading-queue",
+    request_body = QueueSyncRequest,
+    security(("bearer_auth" = []))
+)]
+async fn sync_queue(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(request): Json<QueueSyncRequest>,
+) -> Result<ApiResponse> {
+    debug!("Syncing reading queue for user: {} ({} client changes)", user.id, request.changes.len());
+
+    let result = state.reading_queue_service.sync(&user.id, request).await?;
+
+    Ok(ApiResponse::ok(result))
+}
@@ -1,60 +1,93 @@
 use crate::{
-    error::{AppError, Result},
-    models::recommendation::*,
+    error::{AppError, ErrorCode, Result},
+    models::{recommendation::*, response::ApiResponse},
     services::auth::User,
     state::AppState,
     utils::middleware::OptionalAuth,
 };
 use axum::{
     extract::{Path, Query, State},
-    response::Json,
+    http::HeaderMap,
     routing::get,
     Extension, Router,
 };
-use serde_json::{json, Value};
 use std::sync::Arc;
 use tracing::debug;
+use utoipa::{IntoParams, OpenApi};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(get_recommendations))
         .route("/trending", get(get_trending))
         .route("/following", get(get_following_recommendations))
+        .route("/feed", get(get_home_feed))
         .route("/related/:article_id", get(get_related_articles))
+        .route("/continue-reading", get(get_continue_reading))
         .route("/update", get(update_recommendations)) // 管理员手动触发更新
 }
 
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_recommendations,
+    get_trending,
+    get_following_recommendations,
+    get_home_feed,
+    get_related_articles,
+    get_continue_reading,
+    update_recommendations,
+))]
+pub struct ApiDoc;
+
 /// 获取个性化推荐
 /// GET /api/recommendations
+#[utoipa::path(
+    get,
+    path = "/api/blog/recommendations",
+    tag = "recommendations",
+    params(RecommendationRequest),
+    security((), ("bearer_auth" = []))
+)]
 async fn get_recommendations(
     State(state): State<Arc<AppState>>,
     Query(request): Query<RecommendationRequest>,
     OptionalAuth(user): OptionalAuth,
-) -> Result<Json<Value>> {
+    headers: HeaderMap,
+) -> Result<ApiResponse> {
     debug!("Getting personalized recommendations");
 
     let mut final_request = request;
     if let Some(user) = user {
         final_request.user_id = Some(user.id);
     }
+    let user_id = final_request.user_id.clone();
 
-    let recommendations = state
+    let mut recommendations = state
         .recommendation_service
         .get_recommendations(final_request)
         .await?;
+    state.experiment_service
+        .apply_variants(recommendations.articles.iter_mut().map(|r| &mut r.article), &headers, user_id.as_deref())
+        .await;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": recommendations
-    })))
+    Ok(ApiResponse::ok(recommendations))
 }
 
 /// 获取热门推荐
 /// GET /api/recommendations/trending
+#[utoipa::path(
+    get,
+    path = "/api/blog/recommendations/trending",
+    tag = "recommendations",
+    params(RecommendationRequest),
+    security((), ("bearer_auth" = []))
+)]
 async fn get_trending(
     State(state): State<Arc<AppState>>,
     Query(request): Query<RecommendationRequest>,
-) -> Result<Json<Value>> {
+    OptionalAuth(user): OptionalAuth,
+    headers: HeaderMap,
+) -> Result<ApiResponse> {
     debug!("Getting trending recommendations");
 
     let trending_request = RecommendationRequest {
@@ -62,87 +95,171 @@ async fn get_trending(
         ..request
     };
 
-    let recommendations = state
+    let mut recommendations = state
         .recommendation_service
         .get_recommendations(trending_request)
         .await?;
+    state.experiment_service
+        .apply_variants(
+            recommendations.articles.iter_mut().map(|r| &mut r.article),
+            &headers,
+            user.as_ref().map(|u| u.id.as_str()),
+        )
+        .await;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": recommendations
-    })))
+    Ok(ApiResponse::ok(recommendations))
 }
 
 /// 获取关注用户的文章推荐
 /// GET /api/recommendations/following
+#[utoipa::path(
+    get,
+    path = "/api/blog/recommendations/following",
+    tag = "recommendations",
+    params(RecommendationRequest),
+    security(("bearer_auth" = []))
+)]
 async fn get_following_recommendations(
     State(state): State<Arc<AppState>>,
     Query(request): Query<RecommendationRequest>,
     Extension(user): Extension<User>,
-) -> Result<Json<Value>> {
+    headers: HeaderMap,
+) -> Result<ApiResponse> {
     debug!("Getting following recommendations for user: {}", user.id);
 
     let following_request = RecommendationRequest {
-        user_id: Some(user.id),
+        user_id: Some(user.id.clone()),
         algorithm: Some(RecommendationAlgorithm::Following),
         ..request
     };
 
-    let recommendations = state
+    let mut recommendations = state
         .recommendation_service
         .get_recommendations(following_request)
         .await?;
+    state.experiment_service
+        .apply_variants(recommendations.articles.iter_mut().map(|r| &mut r.article), &headers, Some(&user.id))
+        .await;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": recommendations
-    })))
+    Ok(ApiResponse::ok(recommendations))
+}
+
+/// 首页动态：关注的作者和标签的最新文章交替排列
+/// GET /api/recommendations/feed
+#[utoipa::path(
+    get,
+    path = "/api/blog/recommendations/feed",
+    tag = "recommendations",
+    params(HomeFeedQuery),
+    security(("bearer_auth" = []))
+)]
+async fn get_home_feed(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<HomeFeedQuery>,
+    Extension(user): Extension<User>,
+    headers: HeaderMap,
+) -> Result<ApiResponse> {
+    debug!("Getting home feed for user: {}", user.id);
+
+    let mut feed = state.recommendation_service.get_home_feed(&user.id, query).await?;
+    state.experiment_service
+        .apply_variants(feed.articles.iter_mut().map(|r| &mut r.article), &headers, Some(&user.id))
+        .await;
+
+    Ok(ApiResponse::ok(feed))
 }
 
 /// 获取相关文章推荐
 /// GET /api/recommendations/related/:article_id
+#[utoipa::path(
+    get,
+    path = "/api/blog/recommendations/related/{article_id}",
+    tag = "recommendations",
+    params(("article_id" = String, Path), RelatedArticlesQuery),
+    security((), ("bearer_auth" = []))
+)]
 async fn get_related_articles(
     State(state): State<Arc<AppState>>,
     Path(article_id): Path<String>,
     Query(params): Query<RelatedArticlesQuery>,
-) -> Result<Json<Value>> {
+    OptionalAuth(user): OptionalAuth,
+    headers: HeaderMap,
+) -> Result<ApiResponse> {
     debug!("Getting related articles for: {}", article_id);
 
     let limit = params.limit.unwrap_or(5);
     
-    let related_articles = state
+    // 有文章向量时按内容相似度，否则按共同标签
+    let mut related_articles = state.article_service.get_related_articles(&article_id, limit).await?;
+    if related_articles.is_empty() {
+        related_articles = state
+            .recommendation_service
+            .get_related_articles(&article_id, limit)
+            .await?;
+    }
+    state.experiment_service
+        .apply_variants(
+            related_articles.iter_mut().map(|r| &mut r.article),
+            &headers,
+            user.as_ref().map(|u| u.id.as_str()),
+        )
+        .await;
+
+    Ok(ApiResponse::ok(related_articles))
+}
+
+/// 读到一半的文章（跨设备同步的阅读位置）
+/// GET /api/recommendations/continue-reading
+#[utoipa::path(
+    get,
+    path = "/api/blog/recommendations/continue-reading",
+    tag = "recommendations",
+    params(RelatedArticlesQuery),
+    security(("bearer_auth" = []))
+)]
+async fn get_continue_reading(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<RelatedArticlesQuery>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    debug!("Getting continue reading entries for user: {}", user.id);
+
+    let limit = params.limit.unwrap_or(10).min(50);
+
+    let items = state
         .recommendation_service
-        .get_related_articles(&article_id, limit)
+        .get_continue_reading(&user.id, limit)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": related_articles
-    })))
+    Ok(ApiResponse::ok(items))
 }
 
 /// 手动更新推荐系统缓存（管理员功能）
 /// GET /api/recommendations/update
+#[utoipa::path(
+    get,
+    path = "/api/blog/recommendations/update",
+    tag = "recommendations",
+    security(("bearer_auth" = []))
+)]
 async fn update_recommendations(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Manually updating recommendation cache");
 
     // 检查管理员权限
     if !user.permissions.contains(&"admin.recommendation".to_string()) {
-        return Err(AppError::forbidden("Admin permission required"));
+        return Err(AppError::coded(ErrorCode::AdminPermissionRequired, "Admin permission required"));
     }
 
     state.recommendation_service.update_recommendations().await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Recommendation cache updated successfully"
-    })))
+    Ok(ApiResponse::message("Recommendation cache updated successfully"))
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct RelatedArticlesQuery {
     limit: Option<usize>,
 }
\ No newline at end of file
@@ -0,0 +1,63 @@
+use crate::{
+    error::Result,
+    models::{report::*, response::ApiResponse},
+    services::auth::User,
+    state::AppState,
+};
+use axum::{
+    extract::{Query, State},
+    routing::get,
+    Extension, Json, Router,
+};
+use std::sync::Arc;
+use tracing::debug;
+use validator::Validate;
+use utoipa::OpenApi;
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/", get(list_my_reports).post(create_report))
+}
+
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(list_my_reports, create_report))]
+pub struct ApiDoc;
+
+/// 举报文章、评论或用户
+/// POST /api/blog/reports
+#[utoipa::path(
+    post,
+    path = "/api/blog/reports",
+    tag = "reports",
+    request_body = CreateReportRequest,
+    security(("bearer_auth" = []))
+)]
+async fn create_report(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(request): Json<CreateReportRequest>,
+) -> Result<ApiResponse> {
+    request.validate()?;
+
+    debug!("User {} reporting {} {}", user.id, request.target_type.as_str(), request.target_id);
+    let report = state.report_service.create_report(&user.id, request).await?;
+    Ok(ApiResponse::ok(report).with_message("举报已提交，我们会尽快处理"))
+}
+
+/// 当前用户提交过的举报及处理结果
+/// GET /api/blog/reports
+#[utoipa::path(
+    get,
+    path = "/api/blog/reports",
+    tag = "reports",
+    params(ReportQuery),
+    security(("bearer_auth" = []))
+)]
+async fn list_my_reports(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Query(query): Query<ReportQuery>,
+) -> Result<ApiResponse> {
+    let result = state.report_service.list_my_reports(&user.id, query).await?;
+    Ok(ApiResponse::ok(&result.data).with_pagination(&result))
+}
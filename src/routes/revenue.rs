@@ -12,10 +12,11 @@ use tracing::{debug, error};
 
 use crate::{
     error::{AppError, Result},
-    models::revenue::*,
+    models::{revenue::*, response::{ApiResponse, PaginationMeta}},
     services::auth::User,
     state::AppState,
 };
+use utoipa::{IntoParams, OpenApi, ToSchema};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -25,8 +26,11 @@ pub fn router() -> Router<Arc<AppState>> {
         // 收益统计
         .route("/stats", get(get_revenue_stats))
         .route("/transactions", get(get_revenue_transactions))
+        .route("/coupons", get(get_coupon_usage))
+        .route("/tax", get(get_tax_report))
         
         // 支付管理
+        .route("/balance", get(get_balance))
         .route("/payouts", post(create_payout))
         .route("/payouts", get(get_payouts))
         .route("/payouts/:payout_id", get(get_payout_details))
@@ -42,24 +46,49 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/settings", post(update_revenue_settings))
 }
 
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_revenue_dashboard,
+    get_revenue_stats,
+    get_revenue_transactions,
+    get_coupon_usage,
+    get_tax_report,
+    get_balance,
+    create_payout,
+    get_payouts,
+    get_payout_details,
+    get_bank_accounts,
+    add_bank_account,
+    verify_bank_account,
+    set_default_bank_account,
+    get_revenue_settings,
+    update_revenue_settings,
+))]
+pub struct ApiDoc;
+
 /// 获取收益仪表板
+#[utoipa::path(
+    get,
+    path = "/api/blog/revenue/dashboard",
+    tag = "revenue",
+    security(("bearer_auth" = []))
+)]
 async fn get_revenue_dashboard(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting revenue dashboard for user: {}", user.id);
 
     let dashboard = state.revenue_service
         .get_revenue_dashboard(&user.id)
         .await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": dashboard
-    })))
+    Ok(ApiResponse::ok(dashboard))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct RevenueStatsQuery {
     period: Option<String>,
     start_date: Option<chrono::DateTime<chrono::Utc>>,
@@ -67,11 +96,18 @@ struct RevenueStatsQuery {
 }
 
 /// 获取收益统计
+#[utoipa::path(
+    get,
+    path = "/api/blog/revenue/stats",
+    tag = "revenue",
+    params(RevenueStatsQuery),
+    security(("bearer_auth" = []))
+)]
 async fn get_revenue_stats(
     State(state): State<Arc<AppState>>,
     Query(query): Query<RevenueStatsQuery>,
     Extension(user): Extension<User>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting revenue stats for user: {}", user.id);
 
     let period = match query.period.as_deref() {
@@ -119,13 +155,11 @@ async fn get_revenue_stats(
         .get_revenue_stats(&user.id, period, start_date, end_date)
         .await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": stats
-    })))
+    Ok(ApiResponse::ok(stats))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct TransactionsQuery {
     page: Option<i32>,
     per_page: Option<i32>,
@@ -134,11 +168,18 @@ struct TransactionsQuery {
 }
 
 /// 获取收益交易记录
+#[utoipa::path(
+    get,
+    path = "/api/blog/revenue/transactions",
+    tag = "revenue",
+    params(TransactionsQuery),
+    security(("bearer_auth" = []))
+)]
 async fn get_revenue_transactions(
     State(state): State<Arc<AppState>>,
     Query(query): Query<TransactionsQuery>,
     Extension(user): Extension<User>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting revenue transactions for user: {}", user.id);
 
     let page = query.page.unwrap_or(1);
@@ -155,95 +196,199 @@ async fn get_revenue_transactions(
         )
         .await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": {
-            "transactions": transactions,
-            "pagination": {
-                "page": page,
-                "per_page": per_page,
-                "total": total,
-                "pages": (total + per_page as i64 - 1) / per_page as i64
-            }
-        }
-    })))
+    Ok(ApiResponse::ok(serde_json::json!({
+        "transactions": transactions
+    })).with_pagination(PaginationMeta::new(page as usize, per_page as usize, total as usize)))
+}
+
+/// 获取可提现余额、待结算余额和打款设置
+#[utoipa::path(
+    get,
+    path = "/api/blog/revenue/balance",
+    tag = "revenue",
+    security(("bearer_auth" = []))
+)]
+async fn get_balance(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    debug!("Getting balance for user: {}", user.id);
+
+    let balance = state.revenue_service
+        .get_balance(&user.id)
+        .await?;
+
+    Ok(ApiResponse::ok(balance))
+}
+
+/// 获取订阅优惠券使用情况
+#[utoipa::path(
+    get,
+    path = "/api/blog/revenue/coupons",
+    tag = "revenue",
+    security(("bearer_auth" = []))
+)]
+async fn get_coupon_usage(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    debug!("Getting coupon usage for user: {}", user.id);
+
+    let usage = state.revenue_service
+        .get_coupon_usage(&user.id)
+        .await?;
+
+    Ok(ApiResponse::ok(usage))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct TaxReportQuery {
+    start_date: Option<chrono::DateTime<chrono::Utc>>,
+    end_date: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// 获取代收税费报表，默认为今年至今
+#[utoipa::path(
+    get,
+    path = "/api/blog/revenue/tax",
+    tag = "revenue",
+    params(TaxReportQuery),
+    security(("bearer_auth" = []))
+)]
+async fn get_tax_report(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<TaxReportQuery>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    debug!("Getting tax report for user: {}", user.id);
+
+    let now = chrono::Utc::now();
+    let start_date = query.start_date.unwrap_or_else(|| {
+        chrono::TimeZone::from_utc_datetime(
+            &chrono::Utc,
+            &chrono::NaiveDate::from_ymd_opt(now.year(), 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+        )
+    });
+    let end_date = query.end_date.unwrap_or(now);
+
+    if start_date >= end_date {
+        return Err(AppError::BadRequest("开始日期必须早于结束日期".to_string()));
+    }
+
+    let report = state.revenue_service
+        .get_tax_report(&user.id, start_date, end_date)
+        .await?;
+
+    Ok(ApiResponse::ok(report))
 }
 
 /// 创建支付
+#[utoipa::path(
+    post,
+    path = "/api/blog/revenue/payouts",
+    tag = "revenue",
+    request_body = CreatePayoutRequest,
+    security(("bearer_auth" = []))
+)]
 async fn create_payout(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Json(payload): Json<CreatePayoutRequest>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Creating payout for user: {}", user.id);
 
     let payout = state.revenue_service
         .create_payout(&user.id, payload)
         .await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": payout
-    })))
+    Ok(ApiResponse::ok(payout))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct PayoutsQuery {
+    page: Option<i32>,
+    per_page: Option<i32>,
 }
 
 /// 获取支付列表
+#[utoipa::path(
+    get,
+    path = "/api/blog/revenue/payouts",
+    tag = "revenue",
+    params(PayoutsQuery),
+    security(("bearer_auth" = []))
+)]
 async fn get_payouts(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<PayoutsQuery>,
     Extension(user): Extension<User>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting payouts for user: {}", user.id);
 
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+    let offset = (page - 1) * per_page;
 
-    let payouts = state.revenue_service
-        .query_payouts(&user.id)
+    let (payouts, total) = state.revenue_service
+        .query_payouts(&user.id, offset, per_page)
         .await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": payouts
-    })))
+    Ok(ApiResponse::ok(serde_json::json!({
+        "payouts": payouts
+    })).with_pagination(PaginationMeta::new(page as usize, per_page as usize, total as usize)))
 }
 
 /// 获取支付详情
+#[utoipa::path(
+    get,
+    path = "/api/blog/revenue/payouts/{payout_id}",
+    tag = "revenue",
+    params(("payout_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn get_payout_details(
     State(state): State<Arc<AppState>>,
     Path(payout_id): Path<String>,
     Extension(user): Extension<User>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting payout details: {} for user: {}", payout_id, user.id);
 
 
     if let Some(payout) = state.revenue_service
         .query_payout_details(&payout_id, &user.id)
         .await? {
-        Ok(Json(serde_json::json!({
-            "success": true,
-            "data": payout
-        })))
+        Ok(ApiResponse::ok(payout))
     } else {
         Err(AppError::NotFound("支付记录不存在".to_string()))
     }
 }
 
 /// 获取银行账户列表
+#[utoipa::path(
+    get,
+    path = "/api/blog/revenue/bank-accounts",
+    tag = "revenue",
+    security(("bearer_auth" = []))
+)]
 async fn get_bank_accounts(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting bank accounts for user: {}", user.id);
 
     let bank_accounts = state.revenue_service
         .get_bank_accounts(&user.id)
         .await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": bank_accounts
-    })))
+    Ok(ApiResponse::ok(bank_accounts))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct AddBankAccountRequest {
     account_holder_name: String,
     bank_name: String,
@@ -253,11 +398,18 @@ struct AddBankAccountRequest {
 }
 
 /// 添加银行账户
+#[utoipa::path(
+    post,
+    path = "/api/blog/revenue/bank-accounts",
+    tag = "revenue",
+    request_body = AddBankAccountRequest,
+    security(("bearer_auth" = []))
+)]
 async fn add_bank_account(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Json(payload): Json<AddBankAccountRequest>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Adding bank account for user: {}", user.id);
 
     // TODO: 与Stripe集成验证银行账户
@@ -271,19 +423,22 @@ async fn add_bank_account(
         )
         .await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": account,
-        "message": "银行账户已添加，等待验证"
-    })))
+    Ok(ApiResponse::ok(account).with_message("银行账户已添加，等待验证"))
 }
 
 /// 验证银行账户
+#[utoipa::path(
+    post,
+    path = "/api/blog/revenue/bank-accounts/{account_id}/verify",
+    tag = "revenue",
+    params(("account_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn verify_bank_account(
     State(state): State<Arc<AppState>>,
     Path(account_id): Path<String>,
     Extension(user): Extension<User>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Verifying bank account: {} for user: {}", account_id, user.id);
 
     // TODO: 实现实际的银行账户验证逻辑
@@ -295,18 +450,22 @@ async fn verify_bank_account(
         return Err(AppError::NotFound("银行账户不存在".to_string()));
     }
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": "银行账户验证成功"
-    })))
+    Ok(ApiResponse::message("银行账户验证成功"))
 }
 
 /// 设置默认银行账户
+#[utoipa::path(
+    post,
+    path = "/api/blog/revenue/bank-accounts/{account_id}/default",
+    tag = "revenue",
+    params(("account_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn set_default_bank_account(
     State(state): State<Arc<AppState>>,
     Path(account_id): Path<String>,
     Extension(user): Extension<User>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Setting default bank account: {} for user: {}", account_id, user.id);
 
     let success = state.revenue_service
@@ -317,36 +476,36 @@ async fn set_default_bank_account(
         return Err(AppError::BadRequest("银行账户不存在或未验证".to_string()));
     }
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": "默认银行账户设置成功"
-    })))
+    Ok(ApiResponse::message("默认银行账户设置成功"))
 }
 
 /// 获取收益设置
+#[utoipa::path(
+    get,
+    path = "/api/blog/revenue/settings",
+    tag = "revenue",
+    security(("bearer_auth" = []))
+)]
 async fn get_revenue_settings(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting revenue settings for user: {}", user.id);
 
     // 返回收益分成配置和其他设置
     let settings = serde_json::json!({
-        "revenue_share": RevenueShare::default(),
-        "minimum_payout_amount": 5000, // $50
+        "revenue_share": state.revenue_service.revenue_share(),
+        "minimum_payout_amount": state.revenue_service.minimum_payout_amount(),
         "payout_schedule": "monthly",
         "payout_day": 1,
-        "auto_payout_enabled": false,
+        "auto_payout_enabled": true,
         "tax_reporting_enabled": false
     });
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": settings
-    })))
+    Ok(ApiResponse::ok(settings))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct UpdateRevenueSettingsRequest {
     auto_payout_enabled: Option<bool>,
     minimum_auto_payout_amount: Option<i64>,
@@ -354,17 +513,21 @@ struct UpdateRevenueSettingsRequest {
 }
 
 /// 更新收益设置
+#[utoipa::path(
+    post,
+    path = "/api/blog/revenue/settings",
+    tag = "revenue",
+    request_body = UpdateRevenueSettingsRequest,
+    security(("bearer_auth" = []))
+)]
 async fn update_revenue_settings(
     State(_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Json(_payload): Json<UpdateRevenueSettingsRequest>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Updating revenue settings for user: {}", user.id);
 
     // TODO: 实现设置更新逻辑
     // 目前返回成功响应
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": "收益设置更新成功"
-    })))
+    Ok(ApiResponse::message("收益设置更新成功"))
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
 use crate::{
     error::Result,
-    models::search::*,
+    models::{search::*, response::ApiResponse},
     state::AppState,
     utils::middleware::OptionalAuth,
 };
@@ -11,73 +11,114 @@ use axum::{
     Router,
 };
 use serde::Deserialize;
-use serde_json::{json, Value};
 use std::sync::Arc;
 use tracing::debug;
+use utoipa::{IntoParams, OpenApi};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct SuggestQuery {
     pub q: String,
     pub limit: Option<i32>,
 }
 
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TypeaheadQuery {
+    pub q: String,
+    pub limit: Option<usize>,
+}
+
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(search))
         .route("/advanced", post(advanced_search))
         .route("/suggestions", get(get_suggestions))
+        .route("/suggest", get(suggest))
 }
 
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(search, advanced_search, get_suggestions, suggest))]
+pub struct ApiDoc;
+
 /// 全局搜索
 /// GET /api/search?q=query&type=all&page=1&limit=10
+#[utoipa::path(
+    get,
+    path = "/api/blog/search",
+    tag = "search",
+    params(SearchQuery)
+)]
 async fn search(
     State(state): State<Arc<AppState>>,
     Query(query): Query<SearchQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Performing search with query: {:?}", query);
 
-    let results = state.search_service.search(query).await?;
+    let results = state.search()?.search(query).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": results
-    })))
+    Ok(ApiResponse::ok(results))
 }
 
 /// 高级搜索
 /// POST /api/search/advanced
 /// Body: AdvancedSearchQuery
+#[utoipa::path(
+    post,
+    path = "/api/blog/search/advanced",
+    tag = "search",
+    request_body = AdvancedSearchQuery,
+    security((), ("bearer_auth" = []))
+)]
 async fn advanced_search(
     State(state): State<Arc<AppState>>,
     OptionalAuth(user): OptionalAuth,
     Json(query): Json<AdvancedSearchQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Performing advanced search with query: {:?}", query);
     
     let user_id = user.as_ref().map(|u| u.id.as_str());
-    let results = state.search_service.advanced_search(user_id, query).await?;
+    let results = state.search()?.advanced_search(user_id, query).await?;
     
-    Ok(Json(json!({
-        "success": true,
-        "data": results
-    })))
+    Ok(ApiResponse::ok(results))
 }
 
 /// 获取搜索建议
 /// GET /api/search/suggestions?q=query&limit=10
+#[utoipa::path(
+    get,
+    path = "/api/blog/search/suggestions",
+    tag = "search",
+    params(SuggestQuery)
+)]
 async fn get_suggestions(
     State(state): State<Arc<AppState>>,
     Query(query): Query<SuggestQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting search suggestions for: {}", query.q);
 
     let suggestions = state
-        .search_service
+        .search()?
         .get_search_suggestions(&query.q, query.limit)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": suggestions
-    })))
-}
\ No newline at end of file
+    Ok(ApiResponse::ok(suggestions))
+}
+
+/// 输入联想：文章、标签、作者和出版物混合，按热度排序。只查内存索引，适合每次按键调用
+/// GET /api/blog/search/suggest?q=ru&limit=8
+#[utoipa::path(
+    get,
+    path = "/api/blog/search/suggest",
+    tag = "search",
+    params(TypeaheadQuery)
+)]
+async fn suggest(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<TypeaheadQuery>,
+) -> Result<ApiResponse> {
+    let suggestions = state.suggest_service.suggest(&query.q, query.limit);
+
+    Ok(ApiResponse::ok(suggestions))
+}
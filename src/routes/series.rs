@@ -1,6 +1,6 @@
 use crate::{
     error::{AppError, Result},
-    models::series::*,
+    models::{series::*, response::ApiResponse},
     services::auth::User,
     state::AppState,
     utils::middleware::OptionalAuth,
@@ -11,9 +11,9 @@ use axum::{
     routing::{delete, get, post, put},
     Extension, Router,
 };
-use serde_json::{json, Value};
 use std::sync::Arc;
 use tracing::debug;
+use utoipa::{IntoParams, OpenApi};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -22,16 +22,44 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/:slug", get(get_series).put(update_series).delete(delete_series))
         .route("/:id/articles", post(add_article).delete(remove_article))
         .route("/:id/articles/order", put(update_article_order))
+        .route("/:id/articles/reorder", put(reorder_articles))
+        .route("/:id/progress", get(get_series_progress))
         .route("/:id/subscribe", post(subscribe_series).delete(unsubscribe_series))
 }
 
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_series_list,
+    create_series,
+    get_subscribed_series,
+    get_series,
+    update_series,
+    delete_series,
+    add_article,
+    remove_article,
+    update_article_order,
+    reorder_articles,
+    get_series_progress,
+    subscribe_series,
+    unsubscribe_series,
+))]
+pub struct ApiDoc;
+
 /// 获取系列列表
 /// GET /api/series
+#[utoipa::path(
+    get,
+    path = "/api/blog/series",
+    tag = "series",
+    params(SeriesQuery),
+    security((), ("bearer_auth" = []))
+)]
 async fn get_series_list(
     State(state): State<Arc<AppState>>,
     Query(query): Query<SeriesQuery>,
     OptionalAuth(user): OptionalAuth,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting series list");
 
     // 如果未登录用户，只显示公开系列
@@ -42,19 +70,23 @@ async fn get_series_list(
 
     let series_list = state.series_service.get_series_list(final_query).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": series_list
-    })))
+    Ok(ApiResponse::ok(series_list))
 }
 
 /// 创建系列
 /// POST /api/series
+#[utoipa::path(
+    post,
+    path = "/api/blog/series",
+    tag = "series",
+    request_body = CreateSeriesRequest,
+    security(("bearer_auth" = []))
+)]
 async fn create_series(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Json(request): Json<CreateSeriesRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Creating series: {} for user: {}", request.title, user.id);
 
     let series = state
@@ -62,20 +94,23 @@ async fn create_series(
         .create_series(&user.id, request)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": series,
-        "message": "Series created successfully"
-    })))
+    Ok(ApiResponse::ok(series).with_message("Series created successfully"))
 }
 
 /// 获取系列详情
 /// GET /api/series/:slug
+#[utoipa::path(
+    get,
+    path = "/api/blog/series/{slug}",
+    tag = "series",
+    params(("slug" = String, Path)),
+    security((), ("bearer_auth" = []))
+)]
 async fn get_series(
     State(state): State<Arc<AppState>>,
     Path(slug): Path<String>,
     OptionalAuth(user): OptionalAuth,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting series: {}", slug);
 
     let user_id = user.as_ref().map(|u| u.id.as_str());
@@ -85,20 +120,25 @@ async fn get_series(
         .await?
         .ok_or_else(|| AppError::NotFound("Series not found".to_string()))?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": series
-    })))
+    Ok(ApiResponse::ok(series))
 }
 
 /// 更新系列
 /// PUT /api/series/:slug
+#[utoipa::path(
+    put,
+    path = "/api/blog/series/{slug}",
+    tag = "series",
+    params(("slug" = String, Path)),
+    request_body = UpdateSeriesRequest,
+    security(("bearer_auth" = []))
+)]
 async fn update_series(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(slug): Path<String>,
     Json(request): Json<UpdateSeriesRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Updating series: {} by user: {}", slug, user.id);
 
     // 先通过slug获取series_id
@@ -113,20 +153,23 @@ async fn update_series(
         .update_series(&existing.series.id, &user.id, request)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": updated_series,
-        "message": "Series updated successfully"
-    })))
+    Ok(ApiResponse::ok(updated_series).with_message("Series updated successfully"))
 }
 
 /// 删除系列
 /// DELETE /api/series/:slug
+#[utoipa::path(
+    delete,
+    path = "/api/blog/series/{slug}",
+    tag = "series",
+    params(("slug" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn delete_series(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(slug): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Deleting series: {} by user: {}", slug, user.id);
 
     // 先通过slug获取series_id
@@ -141,20 +184,25 @@ async fn delete_series(
         .delete_series(&existing.series.id, &user.id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Series deleted successfully"
-    })))
+    Ok(ApiResponse::message("Series deleted successfully"))
 }
 
 /// 添加文章到系列
 /// POST /api/series/:id/articles
+#[utoipa::path(
+    post,
+    path = "/api/blog/series/{id}/articles",
+    tag = "series",
+    params(("id" = String, Path)),
+    request_body = AddArticleToSeriesRequest,
+    security(("bearer_auth" = []))
+)]
 async fn add_article(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(series_id): Path<String>,
     Json(request): Json<AddArticleToSeriesRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Adding article to series: {}", series_id);
 
     let series_article = state
@@ -162,21 +210,24 @@ async fn add_article(
         .add_article_to_series(&series_id, &user.id, request)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": series_article,
-        "message": "Article added to series successfully"
-    })))
+    Ok(ApiResponse::ok(series_article).with_message("Article added to series successfully"))
 }
 
 /// 从系列中移除文章
 /// DELETE /api/series/:id/articles
+#[utoipa::path(
+    delete,
+    path = "/api/blog/series/{id}/articles",
+    tag = "series",
+    params(("id" = String, Path), RemoveArticleParams),
+    security(("bearer_auth" = []))
+)]
 async fn remove_article(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(series_id): Path<String>,
     Query(params): Query<RemoveArticleParams>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Removing article {} from series: {}", params.article_id, series_id);
 
     state
@@ -184,20 +235,25 @@ async fn remove_article(
         .remove_article_from_series(&series_id, &params.article_id, &user.id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Article removed from series successfully"
-    })))
+    Ok(ApiResponse::message("Article removed from series successfully"))
 }
 
 /// 更新文章顺序
 /// PUT /api/series/:id/articles/order
+#[utoipa::path(
+    put,
+    path = "/api/blog/series/{id}/articles/order",
+    tag = "series",
+    params(("id" = String, Path)),
+    request_body = UpdateArticleOrderRequest,
+    security(("bearer_auth" = []))
+)]
 async fn update_article_order(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(series_id): Path<String>,
     Json(request): Json<UpdateArticleOrderRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Updating article order for series: {}", series_id);
 
     state
@@ -205,19 +261,74 @@ async fn update_article_order(
         .update_article_order(&series_id, &user.id, request)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Article order updated successfully"
-    })))
+    Ok(ApiResponse::message("Article order updated successfully"))
+}
+
+/// 拖拽排序：提交文章 ID 的新顺序，服务端重新编号
+/// PUT /api/series/:id/articles/reorder
+#[utoipa::path(
+    put,
+    path = "/api/blog/series/{id}/articles/reorder",
+    tag = "series",
+    params(("id" = String, Path)),
+    request_body = ReorderSeriesArticlesRequest,
+    security(("bearer_auth" = []))
+)]
+async fn reorder_articles(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(series_id): Path<String>,
+    Json(request): Json<ReorderSeriesArticlesRequest>,
+) -> Result<ApiResponse> {
+    debug!("Reordering articles in series: {}", series_id);
+
+    let article_ids = state
+        .series_service
+        .reorder_articles(&series_id, &user.id, request)
+        .await?;
+
+    Ok(ApiResponse::ok(serde_json::json!({ "article_ids": article_ids }))
+        .with_message("Series articles reordered successfully"))
+}
+
+/// 当前用户在系列中的阅读进度
+/// GET /api/series/:id/progress
+#[utoipa::path(
+    get,
+    path = "/api/blog/series/{id}/progress",
+    tag = "series",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_series_progress(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(series_id): Path<String>,
+) -> Result<ApiResponse> {
+    debug!("Getting series progress for user: {}", user.id);
+
+    let progress = state
+        .series_service
+        .get_progress(&series_id, &user.id)
+        .await?;
+
+    Ok(ApiResponse::ok(progress))
 }
 
 /// 订阅系列
 /// POST /api/series/:id/subscribe
+#[utoipa::path(
+    post,
+    path = "/api/blog/series/{id}/subscribe",
+    tag = "series",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn subscribe_series(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(series_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("User {} subscribing to series: {}", user.id, series_id);
 
     state
@@ -225,19 +336,23 @@ async fn subscribe_series(
         .subscribe_series(&series_id, &user.id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Series subscribed successfully"
-    })))
+    Ok(ApiResponse::message("Series subscribed successfully"))
 }
 
 /// 取消订阅系列
 /// DELETE /api/series/:id/subscribe
+#[utoipa::path(
+    delete,
+    path = "/api/blog/series/{id}/subscribe",
+    tag = "series",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn unsubscribe_series(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(series_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("User {} unsubscribing from series: {}", user.id, series_id);
 
     state
@@ -245,19 +360,23 @@ async fn unsubscribe_series(
         .unsubscribe_series(&series_id, &user.id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Series unsubscribed successfully"
-    })))
+    Ok(ApiResponse::message("Series unsubscribed successfully"))
 }
 
 /// 获取用户订阅的系列
 /// GET /api/series/subscribed
+#[utoipa::path(
+    get,
+    path = "/api/blog/series/subscribed",
+    tag = "series",
+    params(PaginationQuery),
+    security(("bearer_auth" = []))
+)]
 async fn get_subscribed_series(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Query(pagination): Query<PaginationQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting subscribed series for user: {}", user.id);
 
     let page = pagination.page.unwrap_or(1);
@@ -268,18 +387,17 @@ async fn get_subscribed_series(
         .get_user_subscribed_series(&user.id, page, limit)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": series_list
-    })))
+    Ok(ApiResponse::ok(series_list))
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct RemoveArticleParams {
     article_id: String,
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct PaginationQuery {
     page: Option<usize>,
     limit: Option<usize>,
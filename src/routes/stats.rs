@@ -1,21 +1,30 @@
-use crate::{error::Result, state::AppState};
-use axum::{response::Json, routing::get, Router};
-use serde_json::{json, Value};
+use crate::{error::Result, models::response::ApiResponse, state::AppState};
+use axum::{routing::get, Router};
+use serde_json::json;
 use std::sync::Arc;
+use utoipa::OpenApi;
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(placeholder_handler))
 }
 
-async fn placeholder_handler() -> Result<Json<Value>> {
-    Ok(Json(json!({
-        "success": true,
-        "message": "Statistics API - Coming soon",
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(placeholder_handler))]
+pub struct ApiDoc;
+
+#[utoipa::path(
+    get,
+    path = "/api/blog/stats",
+    tag = "stats"
+)]
+async fn placeholder_handler() -> Result<ApiResponse> {
+    Ok(ApiResponse::ok(json!({
         "endpoints": [
             "GET /api/stats/dashboard - Dashboard statistics",
             "GET /api/stats/articles - Article statistics",
             "GET /api/stats/users - User statistics"
         ]
-    })))
+    })).with_message("Statistics API - Coming soon"))
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
 use axum::{
     body::Bytes,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::{HeaderMap, StatusCode},
     response::Json,
     routing::{get, post},
@@ -8,15 +8,17 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tracing::{debug, error, warn};
+use tracing::debug;
 use validator::Validate;
 
 use crate::{
-    error::{AppError, Result},
+    error::{AppError, ErrorCode, Result},
     models::{response::ApiResponse, stripe::*},
     services::auth::User,
     state::AppState,
+    utils::middleware::idempotency_key,
 };
+use utoipa::{IntoParams, OpenApi, ToSchema};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -43,56 +45,97 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/connect/accounts/:account_id", get(get_connect_account))
         // Webhook处理
         .route("/webhooks", post(handle_webhook))
+        .route("/webhooks/dead-letters", get(list_dead_letter_webhooks))
+        .route(
+            "/webhooks/dead-letters/:event_id/requeue",
+            post(requeue_dead_letter_webhook),
+        )
         // 支付统计
         .route("/stats", get(get_payment_stats))
 }
 
-#[derive(Debug, Deserialize)]
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    create_customer,
+    get_customer,
+    create_payment_intent,
+    get_payment_intent,
+    confirm_payment_intent,
+    create_subscription,
+    get_subscription,
+    cancel_subscription,
+    create_connect_account,
+    get_current_connect_account,
+    get_connect_account,
+    handle_webhook,
+    list_dead_letter_webhooks,
+    requeue_dead_letter_webhook,
+    get_payment_stats,
+))]
+pub struct ApiDoc;
+
+#[derive(Debug, Deserialize, ToSchema)]
 struct CreateCustomerRequest {
     email: String,
     name: Option<String>,
 }
 
 /// 创建Stripe客户
+#[utoipa::path(
+    post,
+    path = "/api/blog/stripe/customers",
+    tag = "stripe",
+    request_body = CreateCustomerRequest,
+    security(("bearer_auth" = []))
+)]
 async fn create_customer(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Json(payload): Json<CreateCustomerRequest>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Creating Stripe customer for user: {}", user.id);
 
     let customer = state
-        .stripe_service
+        .stripe()?
         .get_or_create_customer(&user.id, &payload.email, payload.name.as_deref())
         .await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": customer
-    })))
+    Ok(ApiResponse::ok(customer))
 }
 
 /// 获取客户信息
+#[utoipa::path(
+    get,
+    path = "/api/blog/stripe/customers/{customer_id}",
+    tag = "stripe",
+    params(("customer_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn get_customer(
     State(_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(_customer_id): Path<String>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting customer info for user: {}", user.id);
 
     // TODO: 实现获取客户详情逻辑
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": "Customer info retrieved successfully"
-    })))
+    Ok(ApiResponse::message("Customer info retrieved successfully"))
 }
 
 /// 创建支付意图
+#[utoipa::path(
+    post,
+    path = "/api/blog/stripe/payment-intents",
+    tag = "stripe",
+    request_body = CreateStripeIntentRequest,
+    security(("bearer_auth" = []))
+)]
 async fn create_payment_intent(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Json(payload): Json<CreateStripeIntentRequest>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Creating payment intent for user: {}", user.id);
 
     let display_name = user
@@ -101,92 +144,116 @@ async fn create_payment_intent(
         .or_else(|| user.username.as_deref());
 
     let payment_intent = state
-        .stripe_service
+        .stripe()?
         .create_payment_intent(&user.id, &user.email, display_name, payload)
         .await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": payment_intent
-    })))
+    Ok(ApiResponse::ok(payment_intent))
 }
 
 /// 获取支付意图
+#[utoipa::path(
+    get,
+    path = "/api/blog/stripe/payment-intents/{intent_id}",
+    tag = "stripe",
+    params(("intent_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn get_payment_intent(
     State(_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(_intent_id): Path<String>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting payment intent for user: {}", user.id);
 
     // TODO: 实现获取支付意图详情逻辑
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": "Payment intent retrieved successfully"
-    })))
+    Ok(ApiResponse::message("Payment intent retrieved successfully"))
 }
 
 /// 确认支付意图
+#[utoipa::path(
+    post,
+    path = "/api/blog/stripe/payment-intents/{intent_id}/confirm",
+    tag = "stripe",
+    params(("intent_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn confirm_payment_intent(
     State(_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(_intent_id): Path<String>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Confirming payment intent for user: {}", user.id);
 
     // TODO: 实现确认支付意图逻辑
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": "Payment intent confirmed successfully"
-    })))
+    Ok(ApiResponse::message("Payment intent confirmed successfully"))
 }
 
 /// 创建订阅
+#[utoipa::path(
+    post,
+    path = "/api/blog/stripe/subscriptions",
+    tag = "stripe",
+    request_body = CreateStripeSubscriptionRequest,
+    security(("bearer_auth" = []))
+)]
 async fn create_subscription(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-    Json(payload): Json<CreateStripeSubscriptionRequest>,
-) -> Result<Json<serde_json::Value>> {
+    headers: HeaderMap,
+    Json(mut payload): Json<CreateStripeSubscriptionRequest>,
+) -> Result<ApiResponse> {
     debug!("Creating Stripe subscription for user: {}", user.id);
 
+    payload.idempotency_key = idempotency_key(&headers);
+
     let subscription = state
-        .stripe_service
+        .stripe()?
         .create_subscription(&user.id, payload)
         .await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": subscription
-    })))
+    Ok(ApiResponse::ok(subscription))
 }
 
 /// 获取订阅信息
+#[utoipa::path(
+    get,
+    path = "/api/blog/stripe/subscriptions/{subscription_id}",
+    tag = "stripe",
+    params(("subscription_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn get_subscription(
     State(_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(_subscription_id): Path<String>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting subscription info for user: {}", user.id);
 
     // TODO: 实现获取订阅详情逻辑
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": "Subscription info retrieved successfully"
-    })))
+    Ok(ApiResponse::message("Subscription info retrieved successfully"))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct CancelSubscriptionRequest {
     at_period_end: Option<bool>,
 }
 
 /// 取消订阅
+#[utoipa::path(
+    post,
+    path = "/api/blog/stripe/subscriptions/{subscription_id}/cancel",
+    tag = "stripe",
+    params(("subscription_id" = String, Path)),
+    request_body = CancelSubscriptionRequest,
+    security(("bearer_auth" = []))
+)]
 async fn cancel_subscription(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(subscription_id): Path<String>,
     Json(payload): Json<CancelSubscriptionRequest>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!(
         "Canceling subscription: {} for user: {}",
         subscription_id, user.id
@@ -194,49 +261,60 @@ async fn cancel_subscription(
 
     let at_period_end = payload.at_period_end.unwrap_or(true);
     state
-        .stripe_service
+        .stripe()?
         .cancel_subscription(&subscription_id, at_period_end)
         .await?;
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": if at_period_end {
-            "订阅将在当前计费周期结束时取消"
-        } else {
-            "订阅已立即取消"
-        }
-    })))
+    Ok(ApiResponse::message(if at_period_end {
+        "订阅将在当前计费周期结束时取消"
+    } else {
+        "订阅已立即取消"
+    }))
 }
 
 /// 创建Connect账户
+#[utoipa::path(
+    post,
+    path = "/api/blog/stripe/connect/accounts",
+    tag = "stripe",
+    request_body = CreateConnectAccountRequest,
+    security(("bearer_auth" = []))
+)]
 async fn create_connect_account(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Json(payload): Json<CreateConnectAccountRequest>,
-) -> Result<Json<ApiResponse<ConnectAccountResponse>>> {
+) -> Result<ApiResponse<ConnectAccountResponse>> {
     debug!("Creating Connect account for user: {}", user.id);
 
     let account = state
-        .stripe_service
+        .stripe()?
         .create_connect_account(&user.id, payload)
         .await?;
 
-    Ok(Json(ApiResponse::success(account)))
+    Ok(ApiResponse::success(account))
 }
 
 /// 获取Connect账户信息
+#[utoipa::path(
+    get,
+    path = "/api/blog/stripe/connect/accounts/{account_id}",
+    tag = "stripe",
+    params(("account_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn get_connect_account(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(account_id): Path<String>,
-) -> Result<Json<ApiResponse<Option<ConnectAccountResponse>>>> {
+) -> Result<ApiResponse<Option<ConnectAccountResponse>>> {
     debug!(
         "Getting Connect account info {} for user: {}",
         account_id, user.id
     );
 
     let account = state
-        .stripe_service
+        .stripe()?
         .get_connect_account_by_identifier(&account_id)
         .await?;
 
@@ -248,28 +326,39 @@ async fn get_connect_account(
         }
     }
 
-    Ok(Json(ApiResponse::success(account)))
+    Ok(ApiResponse::success(account))
 }
 
 /// 获取当前用户的 Connect 账户信息
+#[utoipa::path(
+    get,
+    path = "/api/blog/stripe/connect/accounts/me",
+    tag = "stripe",
+    security(("bearer_auth" = []))
+)]
 async fn get_current_connect_account(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-) -> Result<Json<ApiResponse<Option<ConnectAccountResponse>>>> {
+) -> Result<ApiResponse<Option<ConnectAccountResponse>>> {
     let account = state
-        .stripe_service
+        .stripe()?
         .get_connect_account_for_user(&user.id)
         .await?;
 
-    Ok(Json(ApiResponse::success(account)))
+    Ok(ApiResponse::success(account))
 }
 
 /// 处理Stripe Webhook
+#[utoipa::path(
+    post,
+    path = "/api/blog/stripe/webhooks",
+    tag = "stripe"
+)]
 async fn handle_webhook(
     State(state): State<Arc<AppState>>,
     headers: HeaderMap,
     body: Bytes,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Handling Stripe webhook");
 
     // 解析webhook数据
@@ -283,62 +372,84 @@ async fn handle_webhook(
         .map_err(|_| AppError::BadRequest("无法解析 Stripe-Signature 请求头".to_string()))?;
 
     state
-        .stripe_service
+        .stripe()?
         .verify_webhook_signature(&webhook_body, signature)
         .await?;
 
     let event_data: serde_json::Value = serde_json::from_str(&webhook_body)
         .map_err(|e| AppError::BadRequest(format!("Invalid JSON in webhook: {}", e)))?;
 
-    // 处理webhook事件
-    match state.stripe_service.process_webhook_event(event_data).await {
-        Ok(outcome) => {
-            for purchase in &outcome.purchase_updates {
-                state
-                    .payment_service
-                    .handle_stripe_purchase_success(purchase)
-                    .await?;
-
-                let _ = state
-                    .revenue_service
-                    .record_purchase_revenue_from_webhook(purchase)
-                    .await?;
-            }
-
-            for revenue_event in &outcome.subscription_revenues {
-                let _ = state
-                    .revenue_service
-                    .record_subscription_revenue_from_webhook(revenue_event)
-                    .await?;
-            }
-
-            for status_update in &outcome.subscription_status_updates {
-                state
-                    .payment_service
-                    .handle_subscription_status_update(status_update)
-                    .await?;
-            }
-
-            if !outcome.subscription_status_updates.is_empty() {
-                debug!(
-                    "同步 Stripe 订阅状态更新: {}",
-                    outcome.subscription_status_updates.len()
-                );
-            }
-
-            debug!("Webhook processed successfully");
-            Ok(Json(serde_json::json!({
-                "success": true
-            })))
-        }
-        Err(e) => {
-            error!("Failed to process webhook: {}", e);
-            Err(e)
-        }
+    // 处理webhook事件，失败的事件保存后由后台重试，无需 Stripe 重新投递
+    let message = match state.webhook_service.handle_stripe_event(event_data).await? {
+        WebhookDelivery::Processed => "Webhook processed",
+        WebhookDelivery::QueuedForRetry => "Webhook queued for retry",
+        WebhookDelivery::DeadLettered => "Webhook moved to dead letter queue",
+    };
+
+    debug!("{}", message);
+    Ok(ApiResponse::message(message))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct DeadLetterQuery {
+    page: Option<usize>,
+    limit: Option<usize>,
+}
+
+/// 重试次数用尽的 Webhook 事件（管理员）
+/// GET /api/blog/stripe/webhooks/dead-letters
+#[utoipa::path(
+    get,
+    path = "/api/blog/stripe/webhooks/dead-letters",
+    tag = "stripe",
+    params(DeadLetterQuery),
+    security(("bearer_auth" = []))
+)]
+async fn list_dead_letter_webhooks(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Query(query): Query<DeadLetterQuery>,
+) -> Result<ApiResponse> {
+    if !user.permissions.contains(&"admin.payments".to_string()) {
+        return Err(AppError::coded(ErrorCode::AdminPermissionRequired, "Admin permission required"));
+    }
+
+    let result = state
+        .webhook_service
+        .list_dead_letters(query.page.unwrap_or(1), query.limit.unwrap_or(20))
+        .await?;
+
+    Ok(ApiResponse::ok(serde_json::json!({
+        "events": result.data
+    })).with_pagination(&result))
+}
+
+/// 把死信事件重新放回重试队列（管理员）
+/// POST /api/blog/stripe/webhooks/dead-letters/:event_id/requeue
+#[utoipa::path(
+    post,
+    path = "/api/blog/stripe/webhooks/dead-letters/{event_id}/requeue",
+    tag = "stripe",
+    params(("event_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn requeue_dead_letter_webhook(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(event_id): Path<String>,
+) -> Result<ApiResponse> {
+    if !user.permissions.contains(&"admin.payments".to_string()) {
+        return Err(AppError::coded(ErrorCode::AdminPermissionRequired, "Admin permission required"));
     }
+
+    let event = state.webhook_service.requeue_dead_letter(&event_id).await?;
+
+    Ok(ApiResponse::ok(event))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct PaymentStatsQuery {
     start_date: Option<chrono::DateTime<chrono::Utc>>,
     end_date: Option<chrono::DateTime<chrono::Utc>>,
@@ -346,11 +457,18 @@ struct PaymentStatsQuery {
 }
 
 /// 获取支付统计
+#[utoipa::path(
+    get,
+    path = "/api/blog/stripe/stats",
+    tag = "stripe",
+    params(PaymentStatsQuery),
+    security(("bearer_auth" = []))
+)]
 async fn get_payment_stats(
     State(_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     axum::extract::Query(_query): axum::extract::Query<PaymentStatsQuery>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting payment stats for user: {}", user.id);
 
     // TODO: 实现支付统计逻辑
@@ -365,10 +483,7 @@ async fn get_payment_stats(
         period_end: chrono::Utc::now(),
     };
 
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": stats
-    })))
+    Ok(ApiResponse::ok(stats))
 }
 
 #[cfg(test)]
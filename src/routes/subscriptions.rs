@@ -1,22 +1,24 @@
 use axum::{
     extract::{Path, Query, State},
+    http::HeaderMap,
     response::Json,
     routing::{delete, get, post, put},
     Extension, Router,
 };
 use serde::Deserialize;
-use serde_json::{json, Value};
 use std::sync::Arc;
 
 use crate::{
     error::{AppError, Result},
     models::{
-        response::{ApiResponse, ErrorResponse},
+        response::ApiResponse,
         subscription::*,
     },
     services::auth::User,
     state::AppState,
+    utils::middleware::idempotency_key,
 };
+use utoipa::{OpenApi, ToSchema};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -24,6 +26,9 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/plans/:plan_id", get(get_subscription_plan))
         .route("/plans/:plan_id", put(update_subscription_plan))
         .route("/plans/:plan_id", delete(deactivate_subscription_plan))
+        .route("/plans/:plan_id/coupons/:code", get(preview_coupon))
+        .route("/coupons", post(create_coupon).get(list_coupons))
+        .route("/coupons/:coupon_id", delete(deactivate_coupon))
         .route("/creator/:creator_id/plans", get(get_creator_plans))
         .route("/creator/:creator_id/revenue", get(get_creator_revenue))
         .route("/", post(create_subscription))
@@ -34,59 +39,109 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/webhook/stripe", post(handle_stripe_webhook))
 }
 
-#[derive(Debug, Deserialize)]
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    create_subscription_plan,
+    get_subscription_plan,
+    update_subscription_plan,
+    deactivate_subscription_plan,
+    preview_coupon,
+    create_coupon,
+    list_coupons,
+    deactivate_coupon,
+    get_creator_plans,
+    get_creator_revenue,
+    create_subscription,
+    get_subscription,
+    cancel_subscription,
+    get_user_subscriptions,
+    get_subscription_status,
+    handle_stripe_webhook,
+))]
+pub struct ApiDoc;
+
+#[derive(Debug, Deserialize, ToSchema)]
 struct CancelSubscriptionPayload {
     at_period_end: Option<bool>,
 }
 
 /// 创建订阅计划
+#[utoipa::path(
+    post,
+    path = "/api/blog/subscriptions/plans",
+    tag = "subscriptions",
+    request_body = CreateSubscriptionPlanRequest,
+    security(("bearer_auth" = []))
+)]
 async fn create_subscription_plan(
     State(app_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Json(request): Json<CreateSubscriptionPlanRequest>,
-) -> Result<Json<ApiResponse<SubscriptionPlan>>> {
+) -> Result<ApiResponse<SubscriptionPlan>> {
     let plan = app_state
         .subscription_service
         .create_subscription_plan(&user.id, request)
         .await?;
 
-    Ok(Json(ApiResponse::success(plan)))
+    Ok(ApiResponse::success(plan))
 }
 
 /// 获取订阅计划详情
+#[utoipa::path(
+    get,
+    path = "/api/blog/subscriptions/plans/{plan_id}",
+    tag = "subscriptions",
+    params(("plan_id" = String, Path))
+)]
 async fn get_subscription_plan(
     State(app_state): State<Arc<AppState>>,
     Path(plan_id): Path<String>,
-) -> Result<Json<ApiResponse<SubscriptionPlan>>> {
+) -> Result<ApiResponse<SubscriptionPlan>> {
     let plan = app_state
         .subscription_service
         .get_subscription_plan(&plan_id)
         .await?;
 
-    Ok(Json(ApiResponse::success(plan)))
+    Ok(ApiResponse::success(plan))
 }
 
 /// 更新订阅计划
+#[utoipa::path(
+    put,
+    path = "/api/blog/subscriptions/plans/{plan_id}",
+    tag = "subscriptions",
+    params(("plan_id" = String, Path)),
+    request_body = UpdateSubscriptionPlanRequest,
+    security(("bearer_auth" = []))
+)]
 async fn update_subscription_plan(
     State(app_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(plan_id): Path<String>,
     Json(request): Json<UpdateSubscriptionPlanRequest>,
-) -> Result<Json<ApiResponse<SubscriptionPlan>>> {
+) -> Result<ApiResponse<SubscriptionPlan>> {
     let plan = app_state
         .subscription_service
         .update_subscription_plan(&plan_id, &user.id, request)
         .await?;
 
-    Ok(Json(ApiResponse::success(plan)))
+    Ok(ApiResponse::success(plan))
 }
 
 /// 停用订阅计划
+#[utoipa::path(
+    delete,
+    path = "/api/blog/subscriptions/plans/{plan_id}",
+    tag = "subscriptions",
+    params(("plan_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn deactivate_subscription_plan(
     State(app_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(plan_id): Path<String>,
-) -> Result<Json<ApiResponse<()>>> {
+) -> Result<ApiResponse<()>> {
     let deactivate_request = UpdateSubscriptionPlanRequest {
         name: None,
         description: None,
@@ -100,29 +155,122 @@ async fn deactivate_subscription_plan(
         .update_subscription_plan(&plan_id, &user.id, deactivate_request)
         .await?;
 
-    Ok(Json(ApiResponse::success(())))
+    Ok(ApiResponse::success(()))
+}
+
+/// 创建订阅优惠券
+#[utoipa::path(
+    post,
+    path = "/api/blog/subscriptions/coupons",
+    tag = "subscriptions",
+    request_body = CreateCouponRequest,
+    security(("bearer_auth" = []))
+)]
+async fn create_coupon(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(request): Json<CreateCouponRequest>,
+) -> Result<ApiResponse<SubscriptionCoupon>> {
+    let coupon = app_state
+        .subscription_service
+        .create_coupon(&user.id, request)
+        .await?;
+
+    Ok(ApiResponse::success(coupon))
+}
+
+/// 获取当前创作者的优惠券列表
+#[utoipa::path(
+    get,
+    path = "/api/blog/subscriptions/coupons",
+    tag = "subscriptions",
+    security(("bearer_auth" = []))
+)]
+async fn list_coupons(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse<Vec<SubscriptionCoupon>>> {
+    let coupons = app_state
+        .subscription_service
+        .list_coupons(&user.id)
+        .await?;
+
+    Ok(ApiResponse::success(coupons))
+}
+
+/// 停用优惠券
+#[utoipa::path(
+    delete,
+    path = "/api/blog/subscriptions/coupons/{coupon_id}",
+    tag = "subscriptions",
+    params(("coupon_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn deactivate_coupon(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(coupon_id): Path<String>,
+) -> Result<ApiResponse<SubscriptionCoupon>> {
+    let coupon = app_state
+        .subscription_service
+        .deactivate_coupon(&user.id, &coupon_id)
+        .await?;
+
+    Ok(ApiResponse::success(coupon))
+}
+
+/// 预览优惠码对订阅计划价格的影响
+#[utoipa::path(
+    get,
+    path = "/api/blog/subscriptions/plans/{plan_id}/coupons/{code}",
+    tag = "subscriptions",
+    params(("plan_id" = String, Path), ("code" = String, Path))
+)]
+async fn preview_coupon(
+    State(app_state): State<Arc<AppState>>,
+    Path((plan_id, code)): Path<(String, String)>,
+) -> Result<ApiResponse<CouponPreview>> {
+    let preview = app_state
+        .subscription_service
+        .preview_coupon(&plan_id, &code)
+        .await?;
+
+    Ok(ApiResponse::success(preview))
 }
 
 /// 获取创作者的订阅计划列表
+#[utoipa::path(
+    get,
+    path = "/api/blog/subscriptions/creator/{creator_id}/plans",
+    tag = "subscriptions",
+    params(("creator_id" = String, Path), SubscriptionPlanQuery)
+)]
 async fn get_creator_plans(
     State(app_state): State<Arc<AppState>>,
     Path(creator_id): Path<String>,
     Query(query): Query<SubscriptionPlanQuery>,
-) -> Result<Json<ApiResponse<SubscriptionPlanListResponse>>> {
+) -> Result<ApiResponse<SubscriptionPlanListResponse>> {
     let plans = app_state
         .subscription_service
         .get_creator_plans(&creator_id, query)
         .await?;
 
-    Ok(Json(ApiResponse::success(plans)))
+    Ok(ApiResponse::success(plans))
 }
 
 /// 获取创作者收益统计
+#[utoipa::path(
+    get,
+    path = "/api/blog/subscriptions/creator/{creator_id}/revenue",
+    tag = "subscriptions",
+    params(("creator_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn get_creator_revenue(
     State(app_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(creator_id): Path<String>,
-) -> Result<Json<ApiResponse<CreatorRevenue>>> {
+) -> Result<ApiResponse<CreatorRevenue>> {
     if user.id != creator_id {
         return Err(AppError::Authorization(
             "无权限查看该创作者收益".to_string(),
@@ -134,29 +282,46 @@ async fn get_creator_revenue(
         .get_creator_revenue(&creator_id)
         .await?;
 
-    Ok(Json(ApiResponse::success(revenue)))
+    Ok(ApiResponse::success(revenue))
 }
 
 /// 创建订阅
+#[utoipa::path(
+    post,
+    path = "/api/blog/subscriptions",
+    tag = "subscriptions",
+    request_body = CreateSubscriptionRequest,
+    security(("bearer_auth" = []))
+)]
 async fn create_subscription(
     State(app_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-    Json(request): Json<CreateSubscriptionRequest>,
-) -> Result<Json<ApiResponse<SubscriptionDetails>>> {
+    headers: HeaderMap,
+    Json(mut request): Json<CreateSubscriptionRequest>,
+) -> Result<ApiResponse<SubscriptionDetails>> {
+    request.idempotency_key = idempotency_key(&headers);
+
     let subscription = app_state
         .subscription_service
         .create_subscription(&user.id, request)
         .await?;
 
-    Ok(Json(ApiResponse::success(subscription)))
+    Ok(ApiResponse::success(subscription))
 }
 
 /// 获取订阅详情
+#[utoipa::path(
+    get,
+    path = "/api/blog/subscriptions/{subscription_id}",
+    tag = "subscriptions",
+    params(("subscription_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn get_subscription(
     State(app_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(subscription_id): Path<String>,
-) -> Result<Json<ApiResponse<SubscriptionDetails>>> {
+) -> Result<ApiResponse<SubscriptionDetails>> {
     let subscription = app_state
         .subscription_service
         .get_subscription_with_plan(&subscription_id)
@@ -167,16 +332,24 @@ async fn get_subscription(
         return Err(AppError::Authorization("无权限查看该订阅详情".to_string()));
     }
 
-    Ok(Json(ApiResponse::success(subscription)))
+    Ok(ApiResponse::success(subscription))
 }
 
 /// 取消订阅
+#[utoipa::path(
+    post,
+    path = "/api/blog/subscriptions/{subscription_id}/cancel",
+    tag = "subscriptions",
+    params(("subscription_id" = String, Path)),
+    request_body = CancelSubscriptionPayload,
+    security(("bearer_auth" = []))
+)]
 async fn cancel_subscription(
     State(app_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(subscription_id): Path<String>,
     Json(payload): Json<CancelSubscriptionPayload>,
-) -> Result<Json<ApiResponse<SubscriptionDetails>>> {
+) -> Result<ApiResponse<SubscriptionDetails>> {
     let subscription = app_state
         .subscription_service
         .cancel_subscription(
@@ -186,16 +359,23 @@ async fn cancel_subscription(
         )
         .await?;
 
-    Ok(Json(ApiResponse::success(subscription)))
+    Ok(ApiResponse::success(subscription))
 }
 
 /// 获取用户的订阅列表
+#[utoipa::path(
+    get,
+    path = "/api/blog/subscriptions/user/{user_id}",
+    tag = "subscriptions",
+    params(("user_id" = String, Path), SubscriptionQuery),
+    security(("bearer_auth" = []))
+)]
 async fn get_user_subscriptions(
     State(app_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(user_id): Path<String>,
     Query(query): Query<SubscriptionQuery>,
-) -> Result<Json<ApiResponse<SubscriptionListResponse>>> {
+) -> Result<ApiResponse<SubscriptionListResponse>> {
     if user.id != user_id {
         return Err(AppError::Authorization(
             "无权限查看该用户订阅列表".to_string(),
@@ -207,15 +387,22 @@ async fn get_user_subscriptions(
         .get_user_subscriptions(&user_id, query)
         .await?;
 
-    Ok(Json(ApiResponse::success(subscriptions)))
+    Ok(ApiResponse::success(subscriptions))
 }
 
 /// 检查用户对创作者的订阅状态
+#[utoipa::path(
+    get,
+    path = "/api/blog/subscriptions/creator/{creator_id}/status",
+    tag = "subscriptions",
+    params(("creator_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn get_subscription_status(
     State(app_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(creator_id): Path<String>,
-) -> Result<Json<ApiResponse<SubscriptionDetails>>> {
+) -> Result<ApiResponse<SubscriptionDetails>> {
     let status = app_state
         .subscription_service
         .check_subscription(&user.id, &creator_id)
@@ -229,14 +416,20 @@ async fn get_subscription_status(
         .subscription
         .ok_or_else(|| AppError::NotFound("未找到订阅详情".to_string()))?;
 
-    Ok(Json(ApiResponse::success(subscription)))
+    Ok(ApiResponse::success(subscription))
 }
 
 /// 处理 Stripe Webhook
+#[utoipa::path(
+    post,
+    path = "/api/blog/subscriptions/webhook/stripe",
+    tag = "subscriptions",
+    request_body = StripeWebhookEvent
+)]
 async fn handle_stripe_webhook(
     State(app_state): State<Arc<AppState>>,
     Json(event): Json<StripeWebhookEvent>,
-) -> Result<Json<ApiResponse<()>>> {
+) -> Result<ApiResponse<()>> {
     tracing::info!("Received Stripe webhook: {} ({})", event.r#type, event.id);
 
     // 这里应该处理各种 Stripe 事件
@@ -263,5 +456,5 @@ async fn handle_stripe_webhook(
         }
     }
 
-    Ok(Json(ApiResponse::success(())))
+    Ok(ApiResponse::success(()))
 }
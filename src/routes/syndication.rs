@@ -0,0 +1,261 @@
+use crate::{
+    error::Result,
+    models::{social_share::*, syndication::*, response::ApiResponse},
+    services::auth::User,
+    state::AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+    routing::{get, put},
+    Extension, Router,
+};
+use std::sync::Arc;
+use tracing::debug;
+use utoipa::OpenApi;
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/accounts", get(get_accounts).post(connect_account))
+        .route("/accounts/:id", put(update_account).delete(disconnect_account))
+        .route("/articles/:article_id", get(get_article_syndication).post(syndicate_article))
+        .route("/shares", get(list_shares).post(schedule_share))
+        .route("/shares/:id", get(get_share).put(update_share).delete(cancel_share))
+}
+
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_accounts,
+    connect_account,
+    update_account,
+    disconnect_account,
+    get_article_syndication,
+    syndicate_article,
+    list_shares,
+    schedule_share,
+    get_share,
+    update_share,
+    cancel_share,
+))]
+pub struct ApiDoc;
+
+/// List the user's connected external accounts
+/// GET /api/blog/syndication/accounts
+#[utoipa::path(
+    get,
+    path = "/api/blog/syndication/accounts",
+    tag = "syndication",
+    security(("bearer_auth" = []))
+)]
+async fn get_accounts(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let accounts = state.syndication_service.list_accounts(&user.id).await?;
+
+    Ok(ApiResponse::ok(accounts))
+}
+
+/// Connect (or reconnect) an account on Dev.to, Hashnode or Twitter/X
+/// POST /api/blog/syndication/accounts
+#[utoipa::path(
+    post,
+    path = "/api/blog/syndication/accounts",
+    tag = "syndication",
+    request_body = ConnectSyndicationAccountRequest,
+    security(("bearer_auth" = []))
+)]
+async fn connect_account(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(request): Json<ConnectSyndicationAccountRequest>,
+) -> Result<ApiResponse> {
+    debug!("User {} connecting {} account", user.id, request.platform.as_str());
+
+    let account = state.syndication_service.connect_account(&user.id, request).await?;
+
+    Ok(ApiResponse::ok(account))
+}
+
+/// Toggle auto-posting or replace the account's token
+/// PUT /api/blog/syndication/accounts/:id
+#[utoipa::path(
+    put,
+    path = "/api/blog/syndication/accounts/{id}",
+    tag = "syndication",
+    params(("id" = String, Path)),
+    request_body = UpdateSyndicationAccountRequest,
+    security(("bearer_auth" = []))
+)]
+async fn update_account(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(account_id): Path<String>,
+    Json(request): Json<UpdateSyndicationAccountRequest>,
+) -> Result<ApiResponse> {
+    let account = state.syndication_service.update_account(&user.id, &account_id, request).await?;
+
+    Ok(ApiResponse::ok(account))
+}
+
+/// Disconnect an account
+/// DELETE /api/blog/syndication/accounts/:id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/syndication/accounts/{id}",
+    tag = "syndication",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn disconnect_account(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(account_id): Path<String>,
+) -> Result<ApiResponse> {
+    state.syndication_service.disconnect_account(&user.id, &account_id).await?;
+
+    Ok(ApiResponse::message("Account disconnected"))
+}
+
+/// Cross-post status of an article on each platform
+/// GET /api/blog/syndication/articles/:article_id
+#[utoipa::path(
+    get,
+    path = "/api/blog/syndication/articles/{article_id}",
+    tag = "syndication",
+    params(("article_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_article_syndication(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(article_id): Path<String>,
+) -> Result<ApiResponse> {
+    let posts = state.syndication_service.list_for_article(&user.id, &article_id).await?;
+
+    Ok(ApiResponse::ok(posts))
+}
+
+/// Cross-post an article now, or retry failed cross-posts
+/// POST /api/blog/syndication/articles/:article_id
+#[utoipa::path(
+    post,
+    path = "/api/blog/syndication/articles/{article_id}",
+    tag = "syndication",
+    params(("article_id" = String, Path)),
+    request_body = SyndicateArticleRequest,
+    security(("bearer_auth" = []))
+)]
+async fn syndicate_article(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(article_id): Path<String>,
+    request: Option<Json<SyndicateArticleRequest>>,
+) -> Result<ApiResponse> {
+    debug!("User {} cross-posting article {}", user.id, article_id);
+
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+    let posts = state.syndication_service.syndicate(&user.id, &article_id, request).await?;
+
+    Ok(ApiResponse::ok(posts))
+}
+
+/// List scheduled promotional posts
+/// GET /api/blog/syndication/shares
+#[utoipa::path(
+    get,
+    path = "/api/blog/syndication/shares",
+    tag = "syndication",
+    params(ScheduledShareQuery),
+    security(("bearer_auth" = []))
+)]
+async fn list_shares(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Query(query): Query<ScheduledShareQuery>,
+) -> Result<ApiResponse> {
+    let shares = state.social_share_service.list(&user.id, query).await?;
+
+    Ok(ApiResponse::ok(shares))
+}
+
+/// Queue a promotional post for a connected social account
+/// POST /api/blog/syndication/shares
+#[utoipa::path(
+    post,
+    path = "/api/blog/syndication/shares",
+    tag = "syndication",
+    request_body = CreateScheduledShareRequest,
+    security(("bearer_auth" = []))
+)]
+async fn schedule_share(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(request): Json<CreateScheduledShareRequest>,
+) -> Result<ApiResponse> {
+    debug!("User {} scheduling share for {}", user.id, request.scheduled_at);
+
+    let share = state.social_share_service.schedule(&user.id, request).await?;
+
+    Ok(ApiResponse::ok(share))
+}
+
+/// A scheduled post with its attempt log
+/// GET /api/blog/syndication/shares/:id
+#[utoipa::path(
+    get,
+    path = "/api/blog/syndication/shares/{id}",
+    tag = "syndication",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn get_share(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(share_id): Path<String>,
+) -> Result<ApiResponse> {
+    let share = state.social_share_service.get(&user.id, &share_id).await?;
+
+    Ok(ApiResponse::ok(share))
+}
+
+/// Edit or reschedule a post that has not been published yet
+/// PUT /api/blog/syndication/shares/:id
+#[utoipa::path(
+    put,
+    path = "/api/blog/syndication/shares/{id}",
+    tag = "syndication",
+    params(("id" = String, Path)),
+    request_body = UpdateScheduledShareRequest,
+    security(("bearer_auth" = []))
+)]
+async fn update_share(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(share_id): Path<String>,
+    Json(request): Json<UpdateScheduledShareRequest>,
+) -> Result<ApiResponse> {
+    let share = state.social_share_service.update(&user.id, &share_id, request).await?;
+
+    Ok(ApiResponse::ok(share))
+}
+
+/// Cancel a scheduled post
+/// DELETE /api/blog/syndication/shares/:id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/syndication/shares/{id}",
+    tag = "syndication",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn cancel_share(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(share_id): Path<String>,
+) -> Result<ApiResponse> {
+    let share = state.social_share_service.cancel(&user.id, &share_id).await?;
+
+    Ok(ApiResponse::ok(share).with_message("Share cancelled"))
+}
@@ -1,6 +1,6 @@
 use crate::{
     error::{Result, AppError},
-    models::tag::*,
+    models::{tag::*, response::ApiResponse},
     services::auth::User,
     state::AppState,
     require_permission,
@@ -12,9 +12,9 @@ use axum::{
     routing::{delete, get, post, put},
     Extension, Router,
 };
-use serde_json::{json, Value};
 use std::sync::Arc;
 use tracing::debug;
+use utoipa::OpenApi;
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -24,16 +24,50 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/article/:article_id", get(get_article_tags))
         .route("/article/:article_id/tags", post(add_article_tags).delete(remove_article_tags))
         .route("/:id/follow", post(follow_tag).delete(unfollow_tag))
+        .route("/:id/children", get(get_child_tags))
+        .route("/:id/parent", put(set_tag_parent))
+        .route("/:id/aliases", post(add_tag_alias))
+        .route("/:id/aliases/:alias", delete(remove_tag_alias))
+        .route("/:id/merge", post(merge_tag))
         .route("/followed", get(get_user_followed_tags))
 }
 
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    get_tags,
+    create_tag,
+    update_tag,
+    delete_tag,
+    get_tag_by_slug,
+    get_article_tags,
+    add_article_tags,
+    remove_article_tags,
+    follow_tag,
+    unfollow_tag,
+    get_child_tags,
+    set_tag_parent,
+    add_tag_alias,
+    remove_tag_alias,
+    merge_tag,
+    get_user_followed_tags,
+))]
+pub struct ApiDoc;
+
 /// Get all tags
 /// GET /api/tags
+#[utoipa::path(
+    get,
+    path = "/api/blog/tags",
+    tag = "tags",
+    params(TagQuery),
+    security((), ("bearer_auth" = []))
+)]
 async fn get_tags(
     State(state): State<Arc<AppState>>,
     Query(query): Query<TagQuery>,
     OptionalAuth(user): OptionalAuth,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting tags with query: {:?}", query);
 
     let tags = state.tag_service.get_tags(query).await?;
@@ -56,19 +90,23 @@ async fn get_tags(
             .collect::<Vec<TagWithFollowStatus>>()
     };
 
-    Ok(Json(json!({
-        "success": true,
-        "data": tags_response
-    })))
+    Ok(ApiResponse::ok(tags_response))
 }
 
 /// Create a new tag (admin only)
 /// POST /api/tags
+#[utoipa::path(
+    post,
+    path = "/api/blog/tags",
+    tag = "tags",
+    request_body = CreateTagRequest,
+    security(("bearer_auth" = []))
+)]
 async fn create_tag(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Json(request): Json<CreateTagRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Creating tag: {}", request.name);
 
     // Check permissions
@@ -76,21 +114,25 @@ async fn create_tag(
 
     let tag = state.tag_service.create_tag(request).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": tag,
-        "message": "Tag created successfully"
-    })))
+    Ok(ApiResponse::ok(tag).with_message("Tag created successfully"))
 }
 
 /// Update a tag (admin only)
 /// PUT /api/tags/:id
+#[utoipa::path(
+    put,
+    path = "/api/blog/tags/{id}",
+    tag = "tags",
+    params(("id" = String, Path)),
+    request_body = UpdateTagRequest,
+    security(("bearer_auth" = []))
+)]
 async fn update_tag(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(tag_id): Path<String>,
     Json(request): Json<UpdateTagRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Updating tag: {}", tag_id);
 
     // Check permissions
@@ -98,20 +140,23 @@ async fn update_tag(
 
     let tag = state.tag_service.update_tag(&tag_id, request).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": tag,
-        "message": "Tag updated successfully"
-    })))
+    Ok(ApiResponse::ok(tag).with_message("Tag updated successfully"))
 }
 
 /// Delete a tag (admin only)
 /// DELETE /api/tags/:id
+#[utoipa::path(
+    delete,
+    path = "/api/blog/tags/{id}",
+    tag = "tags",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn delete_tag(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(tag_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Deleting tag: {}", tag_id);
 
     // Check permissions
@@ -119,19 +164,138 @@ async fn delete_tag(
 
     state.tag_service.delete_tag(&tag_id).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Tag deleted successfully"
-    })))
+    Ok(ApiResponse::message("Tag deleted successfully"))
+}
+
+/// Get direct child tags
+/// GET /api/tags/:id/children
+#[utoipa::path(
+    get,
+    path = "/api/blog/tags/{id}/children",
+    tag = "tags",
+    params(("id" = String, Path))
+)]
+async fn get_child_tags(
+    State(state): State<Arc<AppState>>,
+    Path(tag_id): Path<String>,
+) -> Result<ApiResponse> {
+    let tags = state.tag_service.get_child_tags(&tag_id).await?;
+
+    Ok(ApiResponse::ok(tags))
+}
+
+/// Set or clear the parent tag (admin only)
+/// PUT /api/tags/:id/parent
+#[utoipa::path(
+    put,
+    path = "/api/blog/tags/{id}/parent",
+    tag = "tags",
+    params(("id" = String, Path)),
+    request_body = SetTagParentRequest,
+    security(("bearer_auth" = []))
+)]
+async fn set_tag_parent(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(tag_id): Path<String>,
+    Json(request): Json<SetTagParentRequest>,
+) -> Result<ApiResponse> {
+    require_permission!(state.auth_service, user, "tag.update");
+
+    let tag = state
+        .tag_service
+        .set_parent(&tag_id, request.parent_id.as_deref())
+        .await?;
+
+    Ok(ApiResponse::ok(tag))
+}
+
+/// Add an alias (admin only)
+/// POST /api/tags/:id/aliases
+#[utoipa::path(
+    post,
+    path = "/api/blog/tags/{id}/aliases",
+    tag = "tags",
+    params(("id" = String, Path)),
+    request_body = TagAliasRequest,
+    security(("bearer_auth" = []))
+)]
+async fn add_tag_alias(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(tag_id): Path<String>,
+    Json(request): Json<TagAliasRequest>,
+) -> Result<ApiResponse> {
+    require_permission!(state.auth_service, user, "tag.update");
+
+    let tag = state.tag_service.add_alias(&tag_id, request).await?;
+
+    Ok(ApiResponse::ok(tag))
+}
+
+/// Remove an alias (admin only)
+/// DELETE /api/tags/:id/aliases/:alias
+#[utoipa::path(
+    delete,
+    path = "/api/blog/tags/{id}/aliases/{alias}",
+    tag = "tags",
+    params(("id" = String, Path), ("alias" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn remove_tag_alias(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path((tag_id, alias)): Path<(String, String)>,
+) -> Result<ApiResponse> {
+    require_permission!(state.auth_service, user, "tag.update");
+
+    let tag = state.tag_service.remove_alias(&tag_id, &alias).await?;
+
+    Ok(ApiResponse::ok(tag))
+}
+
+/// Merge a duplicate tag into a canonical one (moderators)
+/// POST /api/tags/:id/merge
+#[utoipa::path(
+    post,
+    path = "/api/blog/tags/{id}/merge",
+    tag = "tags",
+    params(("id" = String, Path)),
+    request_body = MergeTagRequest,
+    security(("bearer_auth" = []))
+)]
+async fn merge_tag(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(tag_id): Path<String>,
+    Json(request): Json<MergeTagRequest>,
+) -> Result<ApiResponse> {
+    debug!("User {} merging tag {} into {}", user.id, tag_id, request.into_tag_id);
+
+    require_permission!(state.auth_service, user, "tag.merge");
+
+    let result = state
+        .tag_service
+        .merge_tags(&tag_id, &request.into_tag_id)
+        .await?;
+
+    Ok(ApiResponse::ok(result).with_message("Tags merged successfully"))
 }
 
-/// Get tag by slug
+/// Get tag by slug (aliases resolve to their canonical tag)
 /// GET /api/tags/slug/:slug
+#[utoipa::path(
+    get,
+    path = "/api/blog/tags/slug/{slug}",
+    tag = "tags",
+    params(("slug" = String, Path)),
+    security((), ("bearer_auth" = []))
+)]
 async fn get_tag_by_slug(
     State(state): State<Arc<AppState>>,
     Path(slug): Path<String>,
     OptionalAuth(user): OptionalAuth,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting tag by slug: {}", slug);
 
     let tag = state
@@ -154,40 +318,48 @@ async fn get_tag_by_slug(
         }
     };
 
-    Ok(Json(json!({
-        "success": true,
-        "data": tag_response
-    })))
+    Ok(ApiResponse::ok(tag_response))
 }
 
 /// Get tags for an article
 /// GET /api/tags/article/:article_id
+#[utoipa::path(
+    get,
+    path = "/api/blog/tags/article/{article_id}",
+    tag = "tags",
+    params(("article_id" = String, Path))
+)]
 async fn get_article_tags(
     State(state): State<Arc<AppState>>,
     Path(article_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting tags for article: {}", article_id);
 
     let tags = state.tag_service.get_article_tags(&article_id).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": tags
-    })))
+    Ok(ApiResponse::ok(tags))
 }
 
 /// Add tags to an article
 /// POST /api/tags/article/:article_id/tags
+#[utoipa::path(
+    post,
+    path = "/api/blog/tags/article/{article_id}/tags",
+    tag = "tags",
+    params(("article_id" = String, Path)),
+    request_body = Vec<String,
+    security(("bearer_auth" = []))
+)]
 async fn add_article_tags(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(article_id): Path<String>,
     Json(tag_ids): Json<Vec<String>>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Adding {} tags to article: {}", tag_ids.len(), article_id);
 
     // Verify user owns the article
-    let article = state.article_service.get_article_by_id(&article_id).await?
+    let article = state.articles()?.get_article_by_id(&article_id).await?
         .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
     
     if article.author_id != user.id {
@@ -199,24 +371,29 @@ async fn add_article_tags(
         .add_tags_to_article(&article_id, tag_ids)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Tags added successfully"
-    })))
+    Ok(ApiResponse::message("Tags added successfully"))
 }
 
 /// Remove tags from an article
 /// DELETE /api/tags/article/:article_id/tags
+#[utoipa::path(
+    delete,
+    path = "/api/blog/tags/article/{article_id}/tags",
+    tag = "tags",
+    params(("article_id" = String, Path)),
+    request_body = Vec<String,
+    security(("bearer_auth" = []))
+)]
 async fn remove_article_tags(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(article_id): Path<String>,
     Json(tag_ids): Json<Vec<String>>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Removing {} tags from article: {}", tag_ids.len(), article_id);
 
     // Verify user owns the article
-    let article = state.article_service.get_article_by_id(&article_id).await?
+    let article = state.articles()?.get_article_by_id(&article_id).await?
         .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
     
     if article.author_id != user.id {
@@ -228,52 +405,63 @@ async fn remove_article_tags(
         .remove_tags_from_article(&article_id, tag_ids)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Tags removed successfully"
-    })))
+    Ok(ApiResponse::message("Tags removed successfully"))
 }
 
 /// Follow a tag
 /// POST /api/tags/:id/follow
+#[utoipa::path(
+    post,
+    path = "/api/blog/tags/{id}/follow",
+    tag = "tags",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn follow_tag(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(tag_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("User {} following tag: {}", user.id, tag_id);
 
     state.tag_service.follow_tag(&tag_id, &user.id).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Tag followed successfully"
-    })))
+    Ok(ApiResponse::message("Tag followed successfully"))
 }
 
 /// Unfollow a tag
 /// DELETE /api/tags/:id/follow
+#[utoipa::path(
+    delete,
+    path = "/api/blog/tags/{id}/follow",
+    tag = "tags",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn unfollow_tag(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(tag_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("User {} unfollowing tag: {}", user.id, tag_id);
 
     state.tag_service.unfollow_tag(&tag_id, &user.id).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "message": "Tag unfollowed successfully"
-    })))
+    Ok(ApiResponse::message("Tag unfollowed successfully"))
 }
 
 /// Get user's followed tags
 /// GET /api/tags/followed
+#[utoipa::path(
+    get,
+    path = "/api/blog/tags/followed",
+    tag = "tags",
+    security(("bearer_auth" = []))
+)]
 async fn get_user_followed_tags(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting followed tags for user: {}", user.id);
 
     let tags = state
@@ -281,8 +469,5 @@ async fn get_user_followed_tags(
         .get_user_followed_tags(&user.id)
         .await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": tags
-    })))
+    Ok(ApiResponse::ok(tags))
 }
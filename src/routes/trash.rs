@@ -0,0 +1,87 @@
+use crate::{
+    error::Result,
+    models::{response::ApiResponse, trash::*},
+    services::auth::User,
+    state::AppState,
+};
+use axum::{
+    extract::{Path, Query, State},
+    routing::{get, post},
+    Extension, Router,
+};
+use std::sync::Arc;
+use tracing::info;
+use utoipa::OpenApi;
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/", get(list_trash))
+        .route("/articles/:id/restore", post(restore_article))
+        .route("/comments/:id/restore", post(restore_comment))
+}
+
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(list_trash, restore_article, restore_comment))]
+pub struct ApiDoc;
+
+/// 当前用户回收站中的文章和评论
+/// GET /api/blog/trash
+#[utoipa::path(
+    get,
+    path = "/api/blog/trash",
+    tag = "trash",
+    params(TrashQuery),
+    security(("bearer_auth" = []))
+)]
+async fn list_trash(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Query(query): Query<TrashQuery>,
+) -> Result<ApiResponse> {
+    let items = state.trash_service.list(&user.id, &query).await?;
+
+    Ok(ApiResponse::ok(items))
+}
+
+/// 从回收站恢复文章
+/// POST /api/blog/trash/articles/:id/restore
+#[utoipa::path(
+    post,
+    path = "/api/blog/trash/articles/{id}/restore",
+    tag = "trash",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn restore_article(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(article_id): Path<String>,
+) -> Result<ApiResponse> {
+    let article = state.trash_service.restore_article(&article_id, &user.id).await?;
+
+    info!("Restored article {} from trash by user {}", article_id, user.id);
+
+    Ok(ApiResponse::ok(article).with_message("Article restored"))
+}
+
+/// 从回收站恢复评论
+/// POST /api/blog/trash/comments/:id/restore
+#[utoipa::path(
+    post,
+    path = "/api/blog/trash/comments/{id}/restore",
+    tag = "trash",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn restore_comment(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(comment_id): Path<String>,
+) -> Result<ApiResponse> {
+    let comment = state.trash_service.restore_comment(&comment_id, &user.id).await?;
+
+    info!("Restored comment {} from trash by user {}", comment_id, user.id);
+
+    Ok(ApiResponse::ok(comment).with_message("Comment restored"))
+}
@@ -1,22 +1,24 @@
 use crate::{
-    error::{AppError, Result},
-    models::user::*,
+    error::{AppError, ErrorCode, Result},
+    models::{user::*, data_lifecycle::DeleteAccountRequest, milestone::MilestoneQuery, reputation::IssueStrikeRequest, response::ApiResponse},
     services::auth::User,
     state::AppState,
+    utils::middleware::OptionalAuth,
     require_permission,
 };
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
     response::Json,
-    routing::{get, post, put},
+    routing::{delete, get, post, put},
     Router,
     Extension,
 };
-use serde_json::{json, Value};
+use serde_json::json;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{info, debug};
+use utoipa::{IntoParams, OpenApi};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -29,6 +31,9 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/by-id/:user_id", get(get_user_profile_by_id))
         .route("/by-id/:user_id/articles", get(get_user_articles_by_id))
         .route("/by-id/:user_id/stats", get(get_user_activity_stats_by_id))
+        .route("/by-id/:user_id/reputation", get(get_user_reputation))
+        .route("/by-id/:user_id/milestones", get(get_user_milestones))
+        .route("/by-id/:user_id/strikes", get(list_user_strikes).post(issue_user_strike))
         
         // 基于用户名的路由
         .route("/:username", get(get_user_profile))
@@ -38,20 +43,51 @@ pub fn router() -> Router<Arc<AppState>> {
         // 需要认证的路由
         .route("/me", get(get_current_user_profile))
         .route("/me", put(update_current_user_profile))
+        .route("/me", delete(delete_current_user))
+        .route("/me/deletion", get(get_account_deletion))
+        .route("/me/deletion/cancel", post(cancel_account_deletion))
         .route("/me/articles", get(get_current_user_articles))
         
         // 用户资料创建（给前端注册后调用）
         .route("/profile", post(create_user_profile))
 }
 
-#[derive(Debug, Deserialize)]
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    list_users,
+    get_popular_users,
+    search_users,
+    get_user_profile_by_id,
+    get_user_articles_by_id,
+    get_user_activity_stats_by_id,
+    get_user_reputation,
+    get_user_milestones,
+    list_user_strikes,
+    issue_user_strike,
+    get_user_profile,
+    get_user_articles,
+    get_user_activity_stats,
+    get_current_user_profile,
+    update_current_user_profile,
+    delete_current_user,
+    get_account_deletion,
+    cancel_account_deletion,
+    get_current_user_articles,
+    create_user_profile,
+))]
+pub struct ApiDoc;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct UserListQuery {
     pub page: Option<usize>,
     pub limit: Option<usize>,
     pub search: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct UserArticlesQuery {
     pub page: Option<usize>,
     pub limit: Option<usize>,
@@ -60,10 +96,16 @@ pub struct UserArticlesQuery {
 
 /// 获取用户列表
 /// GET /api/users
+#[utoipa::path(
+    get,
+    path = "/api/blog/users",
+    tag = "users",
+    params(UserListQuery)
+)]
 pub async fn list_users(
     State(app_state): State<Arc<AppState>>,
     Query(query): Query<UserListQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Fetching users list with query: {:?}", query);
 
     let page = query.page.unwrap_or(1);
@@ -71,43 +113,39 @@ pub async fn list_users(
 
     let result = app_state.user_service.get_users(page, limit, query.search).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": {
-            "users": result.data,
-            "pagination": {
-                "current_page": result.page,
-                "total_pages": result.total_pages,
-                "total_items": result.total,
-                "items_per_page": result.per_page,
-                "has_next": result.page < result.total_pages,
-                "has_prev": result.page > 1,
-            }
-        }
-    })))
+    Ok(ApiResponse::ok(json!({
+        "users": result.data
+    })).with_pagination(&result))
 }
 
 /// 获取热门用户
 /// GET /api/users/popular
+#[utoipa::path(
+    get,
+    path = "/api/blog/users/popular",
+    tag = "users"
+)]
 pub async fn get_popular_users(
     State(app_state): State<Arc<AppState>>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Fetching popular users");
 
     let users = app_state.user_service.get_popular_users(20).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": users
-    })))
+    Ok(ApiResponse::ok(users))
 }
 
 /// 搜索用户
 /// GET /api/users/search
+#[utoipa::path(
+    get,
+    path = "/api/blog/users/search",
+    tag = "users"
+)]
 pub async fn search_users(
     State(app_state): State<Arc<AppState>>,
     Query(query): Query<serde_json::Value>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     let search_query = query.get("q")
         .and_then(|v| v.as_str())
         .ok_or_else(|| AppError::BadRequest("Search query parameter 'q' is required".to_string()))?;
@@ -122,18 +160,21 @@ pub async fn search_users(
 
     let users = app_state.user_service.search_users(search_query, limit).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": users
-    })))
+    Ok(ApiResponse::ok(users))
 }
 
 /// 根据用户名获取用户资料
 /// GET /api/users/:username
+#[utoipa::path(
+    get,
+    path = "/api/blog/users/{username}",
+    tag = "users",
+    params(("username" = String, Path))
+)]
 pub async fn get_user_profile(
     State(app_state): State<Arc<AppState>>,
     Path(username): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Fetching user profile for username: {}", username);
 
     let profile = app_state.user_service.get_profile_by_username(&username).await?
@@ -145,7 +186,7 @@ pub async fn get_user_profile(
     }
 
     // 获取用户最新文章
-    let recent_articles_result = app_state.article_service.get_user_articles(
+    let recent_articles_result = app_state.articles()?.get_user_articles(
         &profile.user_id,
         1, // 第一页
         5, // 限制5篇
@@ -166,7 +207,7 @@ pub async fn get_user_profile(
         Err(_) => vec![], // 如果获取文章失败，返回空数组
     };
 
-    Ok(Json(json!({
+    Ok(ApiResponse::ok(json!({
         "profile": profile.to_response(),
         "recent_articles": recent_articles
     })))
@@ -174,11 +215,17 @@ pub async fn get_user_profile(
 
 /// 获取用户的文章列表
 /// GET /api/users/:username/articles
+#[utoipa::path(
+    get,
+    path = "/api/blog/users/{username}/articles",
+    tag = "users",
+    params(("username" = String, Path), UserArticlesQuery)
+)]
 pub async fn get_user_articles(
     State(app_state): State<Arc<AppState>>,
     Path(username): Path<String>,
     Query(query): Query<UserArticlesQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Fetching articles for username: {}", username);
 
     // 获取用户资料
@@ -189,36 +236,31 @@ pub async fn get_user_articles(
     let limit = query.limit.unwrap_or(20).min(100);
 
     // 默认只显示已发布的文章
-    let result = app_state.article_service.get_user_articles(
+    let result = app_state.articles()?.get_user_articles(
         &profile.user_id,
         page,
         limit,
         false, // include_drafts = false for public access
     ).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": {
-            "articles": result.data,
-            "user": profile.to_response(),
-            "pagination": {
-                "current_page": result.page,
-                "total_pages": result.total_pages,
-                "total_items": result.total,
-                "items_per_page": result.per_page,
-                "has_next": result.page < result.total_pages,
-                "has_prev": result.page > 1,
-            }
-        }
-    })))
+    Ok(ApiResponse::ok(json!({
+        "articles": result.data,
+        "user": profile.to_response()
+    })).with_pagination(&result))
 }
 
 /// 获取用户活动统计
 /// GET /api/users/:username/stats
+#[utoipa::path(
+    get,
+    path = "/api/blog/users/{username}/stats",
+    tag = "users",
+    params(("username" = String, Path))
+)]
 pub async fn get_user_activity_stats(
     State(app_state): State<Arc<AppState>>,
     Path(username): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Fetching activity stats for username: {}", username);
 
     // 获取用户资料
@@ -227,21 +269,24 @@ pub async fn get_user_activity_stats(
 
     let stats = app_state.user_service.get_user_stats(&profile.user_id).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": {
-            "user": profile.to_response(),
-            "activity": stats
-        }
+    Ok(ApiResponse::ok(json!({
+        "user": profile.to_response(),
+        "activity": stats
     })))
 }
 
 /// 获取当前用户资料
 /// GET /api/users/me
+#[utoipa::path(
+    get,
+    path = "/api/blog/users/me",
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
 pub async fn get_current_user_profile(
     State(app_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Fetching current user profile for user: {}", user.id);
 
     let profile = app_state.user_service.get_or_create_profile(
@@ -254,30 +299,34 @@ pub async fn get_current_user_profile(
 
     let stats = app_state.user_service.get_user_stats(&user.id).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": {
-            "profile": profile.to_response(),
-            "auth_info": {
-                "id": user.id,
-                "email": user.email,
-                "is_verified": user.is_verified,
-                "created_at": user.created_at,
-                "roles": user.roles,
-                "permissions": user.permissions,
-            },
-            "activity": stats
-        }
+    Ok(ApiResponse::ok(json!({
+        "profile": profile.to_response(),
+        "auth_info": {
+            "id": user.id,
+            "email": user.email,
+            "is_verified": user.is_verified,
+            "created_at": user.created_at,
+            "roles": user.roles,
+            "permissions": user.permissions,
+        },
+        "activity": stats
     })))
 }
 
 /// 更新当前用户资料
 /// PUT /api/users/me
+#[utoipa::path(
+    put,
+    path = "/api/blog/users/me",
+    tag = "users",
+    request_body = UpdateUserProfileRequest,
+    security(("bearer_auth" = []))
+)]
 pub async fn update_current_user_profile(
     State(app_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Json(request): Json<UpdateUserProfileRequest>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Updating current user profile for user: {}", user.id);
 
     // 检查权限
@@ -288,20 +337,89 @@ pub async fn update_current_user_profile(
 
     info!("Updated user profile for user: {}", user.id);
 
-    Ok(Json(json!({
-        "success": true,
-        "data": profile.to_response(),
-        "message": "Profile updated successfully"
-    })))
+    Ok(ApiResponse::ok(profile.to_response()).with_message("Profile updated successfully"))
+}
+
+/// 申请删除当前账户，内容立即下线，宽限期结束后永久删除个人数据
+/// DELETE /api/users/me
+#[utoipa::path(
+    delete,
+    path = "/api/blog/users/me",
+    tag = "users",
+    request_body = DeleteAccountRequest,
+    security(("bearer_auth" = []))
+)]
+pub async fn delete_current_user(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Json(request): Json<DeleteAccountRequest>,
+) -> Result<ApiResponse> {
+    // 启用了两步验证的账户必须在已验证的会话中操作
+    if !user.two_factor_verified && app_state.two_factor_service.is_enabled(&user.id).await? {
+        return Err(AppError::coded(
+            ErrorCode::TwoFactorRequired,
+            "Two-factor verification is required before deleting the account",
+        ));
+    }
+
+    let profile = app_state.user_service.get_profile_by_user_id(&user.id).await?
+        .ok_or_else(|| AppError::NotFound("User profile not found".to_string()))?;
+
+    info!("User {} requested account deletion", user.id);
+    let deletion = app_state.data_lifecycle_service
+        .request_deletion(&user, &profile.username, request)
+        .await?;
+
+    Ok(ApiResponse::ok(deletion).with_message("账户删除申请已提交，宽限期内可以撤销"))
+}
+
+/// 查看账户删除进度
+/// GET /api/users/me/deletion
+#[utoipa::path(
+    get,
+    path = "/api/blog/users/me/deletion",
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn get_account_deletion(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    let deletion = app_state.data_lifecycle_service.get_deletion(&user.id).await?;
+    Ok(ApiResponse::ok(deletion))
+}
+
+/// 在宽限期内撤销删除，恢复被下线的文章和评论
+/// POST /api/users/me/deletion/cancel
+#[utoipa::path(
+    post,
+    path = "/api/blog/users/me/deletion/cancel",
+    tag = "users",
+    security(("bearer_auth" = []))
+)]
+pub async fn cancel_account_deletion(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+) -> Result<ApiResponse> {
+    info!("User {} cancelled account deletion", user.id);
+    let deletion = app_state.data_lifecycle_service.cancel_deletion(&user.id).await?;
+    Ok(ApiResponse::ok(deletion).with_message("账户删除已撤销"))
 }
 
 /// 获取当前用户的文章列表（包括草稿）
 /// GET /api/users/me/articles
+#[utoipa::path(
+    get,
+    path = "/api/blog/users/me/articles",
+    tag = "users",
+    params(UserArticlesQuery),
+    security(("bearer_auth" = []))
+)]
 pub async fn get_current_user_articles(
     State(app_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Query(query): Query<UserArticlesQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Fetching articles for current user: {}", user.id);
 
     let page = query.page.unwrap_or(1);
@@ -314,35 +432,30 @@ pub async fn get_current_user_articles(
         _ => true, // 默认包括所有状态
     };
 
-    let result = app_state.article_service.get_user_articles(
+    let result = app_state.articles()?.get_user_articles(
         &user.id,
         page,
         limit,
         include_drafts,
     ).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": {
-            "articles": result.data,
-            "pagination": {
-                "current_page": result.page,
-                "total_pages": result.total_pages,
-                "total_items": result.total,
-                "items_per_page": result.per_page,
-                "has_next": result.page < result.total_pages,
-                "has_prev": result.page > 1,
-            }
-        }
-    })))
+    Ok(ApiResponse::ok(json!({
+        "articles": result.data
+    })).with_pagination(&result))
 }
 
 /// 根据用户ID获取用户资料
 /// GET /api/users/by-id/:user_id
+#[utoipa::path(
+    get,
+    path = "/api/blog/users/by-id/{user_id}",
+    tag = "users",
+    params(("user_id" = String, Path))
+)]
 pub async fn get_user_profile_by_id(
     State(app_state): State<Arc<AppState>>,
     Path(user_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Fetching user profile for user_id: {}", user_id);
 
     let profile = app_state.user_service.get_profile_by_user_id(&user_id).await?
@@ -357,7 +470,7 @@ pub async fn get_user_profile_by_id(
     let article_count = app_state.user_service.count_published_articles(&user_id).await?;
 
     // 获取用户最新文章
-    let recent_articles_result = app_state.article_service.get_user_articles(
+    let recent_articles_result = app_state.articles()?.get_user_articles(
         &user_id,
         1, // 第一页
         5, // 限制5篇
@@ -388,7 +501,7 @@ pub async fn get_user_profile_by_id(
     profile_json["follower_count"] = json!(follower_count);
     profile_json["following_count"] = json!(following_count);
 
-    Ok(Json(json!({
+    Ok(ApiResponse::ok(json!({
         "profile": profile_json,
         "recent_articles": recent_articles
     })))
@@ -396,11 +509,17 @@ pub async fn get_user_profile_by_id(
 
 /// 根据用户ID获取用户的文章列表
 /// GET /api/users/by-id/:user_id/articles
+#[utoipa::path(
+    get,
+    path = "/api/blog/users/by-id/{user_id}/articles",
+    tag = "users",
+    params(("user_id" = String, Path), UserArticlesQuery)
+)]
 pub async fn get_user_articles_by_id(
     State(app_state): State<Arc<AppState>>,
     Path(user_id): Path<String>,
     Query(query): Query<UserArticlesQuery>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Fetching articles for user_id: {}", user_id);
 
     // 获取用户资料
@@ -411,36 +530,31 @@ pub async fn get_user_articles_by_id(
     let limit = query.limit.unwrap_or(20).min(100);
 
     // 默认只显示已发布的文章
-    let result = app_state.article_service.get_user_articles(
+    let result = app_state.articles()?.get_user_articles(
         &user_id,
         page,
         limit,
         false, // include_drafts = false for public access
     ).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": {
-            "articles": result.data,
-            "user": profile.to_response(),
-            "pagination": {
-                "current_page": result.page,
-                "total_pages": result.total_pages,
-                "total_items": result.total,
-                "items_per_page": result.per_page,
-                "has_next": result.page < result.total_pages,
-                "has_prev": result.page > 1,
-            }
-        }
-    })))
+    Ok(ApiResponse::ok(json!({
+        "articles": result.data,
+        "user": profile.to_response()
+    })).with_pagination(&result))
 }
 
 /// 根据用户ID获取用户活动统计
 /// GET /api/users/by-id/:user_id/stats
+#[utoipa::path(
+    get,
+    path = "/api/blog/users/by-id/{user_id}/stats",
+    tag = "users",
+    params(("user_id" = String, Path))
+)]
 pub async fn get_user_activity_stats_by_id(
     State(app_state): State<Arc<AppState>>,
     Path(user_id): Path<String>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Fetching activity stats for user_id: {}", user_id);
 
     // 获取用户资料
@@ -449,21 +563,24 @@ pub async fn get_user_activity_stats_by_id(
 
     let stats = app_state.user_service.get_user_stats(&user_id).await?;
 
-    Ok(Json(json!({
-        "success": true,
-        "data": {
-            "user": profile.to_response(),
-            "activity": stats
-        }
+    Ok(ApiResponse::ok(json!({
+        "user": profile.to_response(),
+        "activity": stats
     })))
 }
 
 /// 创建用户资料（给前端注册后调用）
 /// POST /api/users/profile
+#[utoipa::path(
+    post,
+    path = "/api/blog/users/profile",
+    tag = "users",
+    request_body = serde_json::Value
+)]
 pub async fn create_user_profile(
     State(app_state): State<Arc<AppState>>,
     Json(request): Json<serde_json::Value>,
-) -> Result<Json<Value>> {
+) -> Result<ApiResponse> {
     debug!("Creating user profile with request: {:?}", request);
 
     let auth_user_id = request.get("auth_user_id")
@@ -492,9 +609,98 @@ pub async fn create_user_profile(
 
     info!("Created user profile for user: {}", auth_user_id);
 
-    Ok(Json(json!({
-        "success": true,
-        "data": profile.to_response(),
-        "message": "User profile created successfully"
-    })))
+    Ok(ApiResponse::ok(profile.to_response()).with_message("User profile created successfully"))
+}
+
+/// 获取用户信誉分及其解锁的权限，处罚信息只对本人和管理员可见
+/// GET /api/blog/users/by-id/:user_id/reputation
+#[utoipa::path(
+    get,
+    path = "/api/blog/users/by-id/{user_id}/reputation",
+    tag = "users",
+    params(("user_id" = String, Path)),
+    security((), ("bearer_auth" = []))
+)]
+pub async fn get_user_reputation(
+    State(app_state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+    OptionalAuth(user): OptionalAuth,
+) -> Result<ApiResponse> {
+    debug!("Fetching reputation for user: {}", user_id);
+
+    let reputation = app_state.reputation_service.get_reputation(&user_id).await?;
+    let can_see_strikes = match &user {
+        Some(user) if user.id == user_id => true,
+        Some(user) => app_state.auth_service.has_permission(user, "user.moderate").await?,
+        None => false,
+    };
+    let reputation = if can_see_strikes { reputation } else { reputation.without_strikes() };
+
+    Ok(ApiResponse::ok(reputation))
+}
+
+/// 获取用户文章达成的里程碑（浏览数、点赞数、热度榜上榜）
+/// GET /api/users/by-id/:user_id/milestones?limit=20
+#[utoipa::path(
+    get,
+    path = "/api/blog/users/by-id/{user_id}/milestones",
+    tag = "users",
+    params(("user_id" = String, Path), MilestoneQuery)
+)]
+pub async fn get_user_milestones(
+    State(app_state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+    Query(query): Query<MilestoneQuery>,
+) -> Result<ApiResponse> {
+    debug!("Fetching milestones for user: {}", user_id);
+
+    let milestones = app_state.milestone_service.list_for_user(&user_id, query).await?;
+
+    Ok(ApiResponse::ok(milestones))
+}
+
+/// 获取用户的审核处罚记录（管理员）
+/// GET /api/blog/users/by-id/:user_id/strikes
+#[utoipa::path(
+    get,
+    path = "/api/blog/users/by-id/{user_id}/strikes",
+    tag = "users",
+    params(("user_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+pub async fn list_user_strikes(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(user_id): Path<String>,
+) -> Result<ApiResponse> {
+    require_permission!(app_state.auth_service, user, "user.moderate");
+
+    let strikes = app_state.reputation_service.get_strikes(&user_id).await?;
+
+    Ok(ApiResponse::ok(strikes))
+}
+
+/// 对用户记录审核处罚（管理员）
+/// POST /api/blog/users/by-id/:user_id/strikes
+#[utoipa::path(
+    post,
+    path = "/api/blog/users/by-id/{user_id}/strikes",
+    tag = "users",
+    params(("user_id" = String, Path)),
+    request_body = IssueStrikeRequest,
+    security(("bearer_auth" = []))
+)]
+pub async fn issue_user_strike(
+    State(app_state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(user_id): Path<String>,
+    Json(request): Json<IssueStrikeRequest>,
+) -> Result<ApiResponse> {
+    require_permission!(app_state.auth_service, user, "user.moderate");
+
+    let strike = app_state.reputation_service.issue_strike(&user_id, &user.id, request).await?;
+
+    info!("Moderator {} issued strike to user {}", user.id, user_id);
+
+    Ok(ApiResponse::ok(strike).with_message("Strike issued successfully"))
 }
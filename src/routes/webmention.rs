@@ -0,0 +1,124 @@
+//! Webmention / Pingback 接收端点，挂在按域名路由的根路由上
+
+use crate::{
+    error::{AppError, Result},
+    models::{article::Article, id::PublicationId, response::ApiResponse, webmention::*},
+    state::AppState,
+    utils::webmention as wm,
+};
+use axum::{
+    extract::{Form, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde_json::json;
+use std::sync::Arc;
+use tracing::debug;
+use url::Url;
+use utoipa::OpenApi;
+
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(receive_webmention, receive_pingback))]
+pub struct ApiDoc;
+
+/// 接收 Webmention，记录后在后台验证来源页面
+/// POST /webmention
+#[utoipa::path(
+    post,
+    path = "/webmention",
+    tag = "webmention",
+    request_body(content = WebmentionRequest, content_type = "application/x-www-form-urlencoded")
+)]
+pub async fn receive_webmention(
+    State(state): State<Arc<AppState>>,
+    Form(request): Form<WebmentionRequest>,
+) -> Result<Response> {
+    debug!("Received webmention from {} to {}", request.source, request.target);
+
+    let article = resolve_target(&state, &request.target).await?;
+    let mention = state
+        .webmention_service
+        .receive_async(&request.source, &request.target, &article.id)
+        .await?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        ApiResponse::ok(json!({
+            "id": mention.id,
+            "status": MentionStatus::Pending
+        })),
+    )
+        .into_response())
+}
+
+/// 接收 Pingback（XML-RPC `pingback.ping`），按规范同步验证并返回结果
+/// POST /xmlrpc
+#[utoipa::path(
+    post,
+    path = "/xmlrpc",
+    tag = "webmention"
+)]
+pub async fn receive_pingback(State(state): State<Arc<AppState>>, body: String) -> Response {
+    let xml = match handle_pingback(&state, &body).await {
+        Ok(message) => wm::pingback_response(&message),
+        Err((code, message)) => wm::pingback_fault(code, &message),
+    };
+
+    ([(header::CONTENT_TYPE, "text/xml; charset=utf-8")], xml).into_response()
+}
+
+async fn handle_pingback(state: &AppState, body: &str) -> std::result::Result<String, (i32, String)> {
+    let (source, target) = wm::parse_pingback_request(body).map_err(|e| (0, e.to_string()))?;
+    debug!("Received pingback from {} to {}", source, target);
+
+    let article = resolve_target(state, &target).await.map_err(|e| (33, e.to_string()))?;
+    let mention = state
+        .webmention_service
+        .receive(&source, &target, &article.id, MentionProtocol::Pingback)
+        .await
+        .map_err(|e| (16, e.to_string()))?;
+
+    match state.webmention_service.verify(&mention).await {
+        Ok(MentionStatus::Verified) => Ok(format!("Pingback from {} registered", source)),
+        Ok(_) => Err((17, "The source URI does not contain a link to the target URI".to_string())),
+        Err(e) => Err((0, e.to_string())),
+    }
+}
+
+/// 目标必须是本站已发布文章的地址：平台地址或出版物域名下的 /articles/{slug}
+async fn resolve_target(state: &AppState, target: &str) -> Result<Article> {
+    let invalid = || AppError::BadRequest("target is not a valid article URL on this site".to_string());
+
+    let url = Url::parse(target).map_err(|_| invalid())?;
+    let host = url.host_str().ok_or_else(invalid)?;
+    let slug = url
+        .path()
+        .trim_end_matches('/')
+        .strip_prefix("/articles/")
+        .filter(|slug| !slug.is_empty() && !slug.contains('/'))
+        .ok_or_else(invalid)?;
+
+    let article = state
+        .articles()?
+        .get_article_by_slug(slug)
+        .await?
+        .filter(|article| article.is_published())
+        .ok_or_else(invalid)?;
+
+    let frontend_host = Url::parse(&state.config.frontend_url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string));
+    if frontend_host.as_deref() == Some(host) {
+        return Ok(article);
+    }
+
+    // 出版物域名下只接受该出版物的文章
+    let publication_id = state.domain_service.find_publication_by_domain(host).await?
+        .map(|id| PublicationId::new(&id));
+    if publication_id.is_some() && publication_id == article.publication_id.as_deref().map(PublicationId::new) {
+        Ok(article)
+    } else {
+        Err(invalid())
+    }
+}
@@ -14,15 +14,20 @@ use tracing::{debug, info, error, warn};
 
 use crate::{
     error::{AppError, Result},
-    models::websocket::*,
+    models::{websocket::*, notification::DigestWindow, response::ApiResponse},
     services::auth::User,
     state::AppState,
 };
+use utoipa::{IntoParams, OpenApi, ToSchema};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         // WebSocket连接端点
         .route("/connect", get(websocket_handler))
+        // 断线期间错过的事件
+        .route("/sync", get(sync_missed_events))
+        // 草稿协作编辑
+        .route("/articles/:id/edit", get(collaborative_edit_handler))
         
         // 连接管理
         .route("/connections", get(list_connections))
@@ -40,6 +45,8 @@ pub fn router() -> Router<Arc<AppState>> {
         // 在线状态
         .route("/status/:user_id", get(get_user_status))
         .route("/online-users", get(list_online_users))
+        .route("/presence/articles/:article_id", get(get_article_presence))
+        .route("/presence/publications/:publication_id", get(get_publication_presence))
         
         // 统计信息
         .route("/stats", get(get_websocket_stats))
@@ -49,30 +56,137 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/config", post(update_notification_config))
 }
 
+/// 本模块路由的接口文档，由 `routes::openapi` 汇总
+#[derive(OpenApi)]
+#[openapi(paths(
+    websocket_handler,
+    sync_missed_events,
+    collaborative_edit_handler,
+    list_connections,
+    get_connection,
+    send_message,
+    broadcast_message,
+    list_channels,
+    subscribe_channel,
+    unsubscribe_channel,
+    get_user_status,
+    list_online_users,
+    get_article_presence,
+    get_publication_presence,
+    get_websocket_stats,
+    get_notification_config,
+    update_notification_config,
+))]
+pub struct ApiDoc;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ConnectQuery {
+    /// 上次连接最后收到的事件 ID
+    last_event_id: Option<String>,
+}
+
 /// WebSocket连接处理器
+#[utoipa::path(
+    get,
+    path = "/api/blog/ws/connect",
+    tag = "ws",
+    params(ConnectQuery),
+    security(("bearer_auth" = []))
+)]
 async fn websocket_handler(
     ws: WebSocketUpgrade,
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
+    Query(query): Query<ConnectQuery>,
 ) -> Response {
     let connection_id = format!("conn_{}", uuid::Uuid::new_v4());
     
     info!("WebSocket upgrade request from user: {} with connection: {}", user.id, connection_id);
     
-    ws.on_upgrade(move |socket| handle_websocket_connection(socket, state, user, connection_id))
+    ws.on_upgrade(move |socket| {
+        handle_websocket_connection(socket, state, user, connection_id, query.last_event_id)
+    })
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct SyncQuery {
+    cursor: Option<String>,
+    /// 逗号分隔的频道列表，个人通知和动态频道总是包含在内
+    channels: Option<String>,
+}
+
+/// 获取游标之后错过的实时事件，用于无法保持长连接的客户端或重连前的补齐
+#[utoipa::path(
+    get,
+    path = "/api/blog/ws/sync",
+    tag = "ws",
+    params(SyncQuery),
+    security(("bearer_auth" = []))
+)]
+async fn sync_missed_events(
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Query(query): Query<SyncQuery>,
+) -> Result<ApiResponse> {
+    debug!("Syncing missed realtime events for user: {}", user.id);
+
+    let channels: Vec<String> = query
+        .channels
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    let missed = state.websocket_service
+        .missed_events(&user.id, query.cursor.as_deref(), &channels)
+        .await?;
+
+    Ok(ApiResponse::ok(missed))
+}
+
+/// 草稿协作编辑连接：先检查权限，再升级为 WebSocket
+#[utoipa::path(
+    get,
+    path = "/api/blog/ws/articles/{id}/edit",
+    tag = "ws",
+    params(("id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
+async fn collaborative_edit_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<Arc<AppState>>,
+    Extension(user): Extension<User>,
+    Path(article_id): Path<String>,
+) -> Result<Response> {
+    let (article, access) = state.collaborative_editing_service
+        .authorize(&article_id, &user.id)
+        .await?;
+
+    info!("Collaborative editing request for article {} from user: {}", article_id, user.id);
+
+    Ok(ws.on_upgrade(move |socket| async move {
+        state.collaborative_editing_service
+            .handle_connection(socket, article, access, user)
+            .await;
+    }))
 }
 
-/// 处理WebSocket连接
 async fn handle_websocket_connection(
     socket: WebSocket,
     state: Arc<AppState>,
     user: User,
     connection_id: String,
+    last_event_id: Option<String>,
 ) {
     info!("Handling WebSocket connection: {} for user: {}", connection_id, user.id);
     
     if let Err(e) = state.websocket_service
-        .handle_connection(socket, user.id.clone(), connection_id.clone())
+        .handle_connection(socket, user.id.clone(), connection_id.clone(), last_event_id)
         .await 
     {
         error!("WebSocket connection error for {}: {}", connection_id, e);
@@ -82,41 +196,48 @@ async fn handle_websocket_connection(
 }
 
 /// 获取连接列表
+#[utoipa::path(
+    get,
+    path = "/api/blog/ws/connections",
+    tag = "ws",
+    security(("bearer_auth" = []))
+)]
 async fn list_connections(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting connections list for admin user: {}", user.id);
     
     // TODO: 检查管理员权限
     let stats = state.websocket_service.get_stats().await;
     
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": {
-            "total_connections": stats.total_connections,
-            "active_users": stats.active_users,
-            "channels": stats.channels
-        }
+    Ok(ApiResponse::ok(serde_json::json!({
+        "total_connections": stats.total_connections,
+        "active_users": stats.active_users,
+        "channels": stats.channels
     })))
 }
 
 /// 获取连接详情
+#[utoipa::path(
+    get,
+    path = "/api/blog/ws/connections/{connection_id}",
+    tag = "ws",
+    params(("connection_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn get_connection(
     State(_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(_connection_id): Path<String>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting connection details for user: {}", user.id);
     
     // TODO: 实现连接详情获取
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": "Connection details retrieved successfully"
-    })))
+    Ok(ApiResponse::message("Connection details retrieved successfully"))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct SendMessageRequest {
     message_type: WebSocketMessageType,
     data: serde_json::Value,
@@ -125,11 +246,18 @@ struct SendMessageRequest {
 }
 
 /// 发送消息
+#[utoipa::path(
+    post,
+    path = "/api/blog/ws/send",
+    tag = "ws",
+    request_body = SendMessageRequest,
+    security(("bearer_auth" = []))
+)]
 async fn send_message(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Json(payload): Json<SendMessageRequest>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Sending message from user: {}", user.id);
     
     let mut message = WebSocketMessage::new(payload.message_type, payload.data);
@@ -154,13 +282,10 @@ async fn send_message(
         }
     }
     
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": "Message sent successfully"
-    })))
+    Ok(ApiResponse::message("Message sent successfully"))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct BroadcastMessageRequest {
     message_type: WebSocketMessageType,
     channel: String,
@@ -168,11 +293,18 @@ struct BroadcastMessageRequest {
 }
 
 /// 广播消息
+#[utoipa::path(
+    post,
+    path = "/api/blog/ws/broadcast",
+    tag = "ws",
+    request_body = BroadcastMessageRequest,
+    security(("bearer_auth" = []))
+)]
 async fn broadcast_message(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Json(payload): Json<BroadcastMessageRequest>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Broadcasting message from user: {} to channel: {}", user.id, payload.channel);
     
     // TODO: 检查广播权限
@@ -187,135 +319,194 @@ async fn broadcast_message(
         .broadcast_to_channel(&payload.channel, message)
         .await?;
     
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": "Message broadcasted successfully"
-    })))
+    Ok(ApiResponse::message("Message broadcasted successfully"))
 }
 
 /// 获取频道列表
+#[utoipa::path(
+    get,
+    path = "/api/blog/ws/channels",
+    tag = "ws",
+    security(("bearer_auth" = []))
+)]
 async fn list_channels(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting channels list for user: {}", user.id);
     
     let stats = state.websocket_service.get_stats().await;
     let channels: Vec<_> = stats.channels.into_iter().collect();
     
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": {
-            "channels": channels,
-            "user_channels": [
-                ChannelType::UserNotifications.channel_name(&user.id),
-                ChannelType::UserActivity.channel_name(&user.id),
-                ChannelType::CreatorUpdates.channel_name(&user.id),
-                ChannelType::CreatorRevenue.channel_name(&user.id),
-            ]
-        }
+    Ok(ApiResponse::ok(serde_json::json!({
+        "channels": channels,
+        "user_channels": [
+            ChannelType::UserNotifications.channel_name(&user.id),
+            ChannelType::UserActivity.channel_name(&user.id),
+            ChannelType::CreatorUpdates.channel_name(&user.id),
+            ChannelType::CreatorRevenue.channel_name(&user.id),
+        ]
     })))
 }
 
 /// 订阅频道
+#[utoipa::path(
+    post,
+    path = "/api/blog/ws/channels/{channel}/subscribe",
+    tag = "ws",
+    params(("channel" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn subscribe_channel(
     State(_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(channel): Path<String>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("User {} subscribing to channel: {}", user.id, channel);
     
     // TODO: 实现频道订阅逻辑
     // 这个功能通常通过WebSocket连接直接处理
     
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": format!("Subscribed to channel: {}", channel)
-    })))
+    Ok(ApiResponse::message(format!("Subscribed to channel: {}", channel)))
 }
 
 /// 取消订阅频道
+#[utoipa::path(
+    post,
+    path = "/api/blog/ws/channels/{channel}/unsubscribe",
+    tag = "ws",
+    params(("channel" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn unsubscribe_channel(
     State(_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Path(channel): Path<String>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("User {} unsubscribing from channel: {}", user.id, channel);
     
     // TODO: 实现取消订阅逻辑
     
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": format!("Unsubscribed from channel: {}", channel)
-    })))
+    Ok(ApiResponse::message(format!("Unsubscribed from channel: {}", channel)))
 }
 
 /// 获取用户在线状态
+#[utoipa::path(
+    get,
+    path = "/api/blog/ws/status/{user_id}",
+    tag = "ws",
+    params(("user_id" = String, Path)),
+    security(("bearer_auth" = []))
+)]
 async fn get_user_status(
     State(state): State<Arc<AppState>>,
     Extension(_user): Extension<User>,
     Path(target_user_id): Path<String>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting online status for user: {}", target_user_id);
     
     let status = state.websocket_service
         .get_user_online_status(&target_user_id)
         .await;
     
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": status
-    })))
+    Ok(ApiResponse::ok(status))
+}
+
+/// 正在阅读文章的人数
+#[utoipa::path(
+    get,
+    path = "/api/blog/ws/presence/articles/{article_id}",
+    tag = "ws",
+    params(("article_id" = String, Path))
+)]
+async fn get_article_presence(
+    State(state): State<Arc<AppState>>,
+    Path(article_id): Path<String>,
+) -> Result<ApiResponse> {
+    let reader_count = state.websocket_service.article_reader_count(&article_id);
+
+    Ok(ApiResponse::ok(ArticlePresence {
+        article_id: crate::models::id::bare_id("article", &article_id).to_string(),
+        reader_count,
+    }))
 }
 
-#[derive(Debug, Deserialize)]
+/// 出版物各篇文章的在线读者
+#[utoipa::path(
+    get,
+    path = "/api/blog/ws/presence/publications/{publication_id}",
+    tag = "ws",
+    params(("publication_id" = String, Path))
+)]
+async fn get_publication_presence(
+    State(state): State<Arc<AppState>>,
+    Path(publication_id): Path<String>,
+) -> Result<ApiResponse> {
+    Ok(ApiResponse::ok(state.websocket_service.publication_presence(&publication_id)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 struct OnlineUsersQuery {
     limit: Option<i32>,
     offset: Option<i32>,
 }
 
 /// 获取在线用户列表
+#[utoipa::path(
+    get,
+    path = "/api/blog/ws/online-users",
+    tag = "ws",
+    params(OnlineUsersQuery),
+    security(("bearer_auth" = []))
+)]
 async fn list_online_users(
     State(_state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Query(_query): Query<OnlineUsersQuery>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting online users list for user: {}", user.id);
     
     // TODO: 实现在线用户列表获取
     let online_users = Vec::<OnlineStatus>::new();
     
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": {
-            "online_users": online_users,
-            "total_count": 0
-        }
+    Ok(ApiResponse::ok(serde_json::json!({
+        "online_users": online_users,
+        "total_count": 0
     })))
 }
 
 /// 获取WebSocket统计信息
+#[utoipa::path(
+    get,
+    path = "/api/blog/ws/stats",
+    tag = "ws",
+    security(("bearer_auth" = []))
+)]
 async fn get_websocket_stats(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting WebSocket stats for user: {}", user.id);
     
     // TODO: 检查管理员权限
     
     let stats = state.websocket_service.get_stats().await;
     
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": stats
-    })))
+    Ok(ApiResponse::ok(stats))
 }
 
 /// 获取通知配置
+#[utoipa::path(
+    get,
+    path = "/api/blog/ws/config",
+    tag = "ws",
+    security(("bearer_auth" = []))
+)]
 async fn get_notification_config(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Getting notification config for user: {}", user.id);
     
     // 从数据库获取用户通知配置
@@ -334,25 +525,7 @@ async fn get_notification_config(
             .map_err(|e| AppError::Internal(format!("Failed to parse notification config: {}", e)))?
     } else {
         // 创建默认配置
-        let default_config = NotificationConfig {
-            user_id: user.id.clone(),
-            email_notifications: true,
-            push_notifications: true,
-            websocket_notifications: true,
-            notification_types: vec![
-                "new_article".to_string(),
-                "new_comment".to_string(),
-                "new_follower".to_string(),
-                "article_clap".to_string(),
-                "subscription_update".to_string(),
-                "payment_update".to_string(),
-            ],
-            quiet_hours_start: Some("22:00".to_string()),
-            quiet_hours_end: Some("08:00".to_string()),
-            timezone: "UTC".to_string(),
-            created_at: chrono::Utc::now(),
-            updated_at: chrono::Utc::now(),
-        };
+        let default_config = NotificationConfig::default_for(&user.id);
         
         // 保存默认配置到数据库
         let create_query = r#"
@@ -366,13 +539,10 @@ async fn get_notification_config(
         default_config
     };
     
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "data": config
-    })))
+    Ok(ApiResponse::ok(config))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 struct UpdateNotificationConfigRequest {
     email_notifications: Option<bool>,
     push_notifications: Option<bool>,
@@ -381,14 +551,23 @@ struct UpdateNotificationConfigRequest {
     quiet_hours_start: Option<String>,
     quiet_hours_end: Option<String>,
     timezone: Option<String>,
+    email_digest_window: Option<DigestWindow>,
+    milestone_notifications: Option<bool>,
 }
 
 /// 更新通知配置
+#[utoipa::path(
+    post,
+    path = "/api/blog/ws/config",
+    tag = "ws",
+    request_body = UpdateNotificationConfigRequest,
+    security(("bearer_auth" = []))
+)]
 async fn update_notification_config(
     State(state): State<Arc<AppState>>,
     Extension(user): Extension<User>,
     Json(payload): Json<UpdateNotificationConfigRequest>,
-) -> Result<Json<serde_json::Value>> {
+) -> Result<ApiResponse> {
     debug!("Updating notification config for user: {}", user.id);
     
     // 构建更新查询
@@ -415,6 +594,12 @@ async fn update_notification_config(
     if let Some(timezone) = payload.timezone {
         updates.push(format!("timezone = '{}'", timezone));
     }
+    if let Some(email_digest_window) = payload.email_digest_window {
+        updates.push(format!("email_digest_window = '{}'", email_digest_window.as_str()));
+    }
+    if let Some(milestone_notifications) = payload.milestone_notifications {
+        updates.push(format!("milestone_notifications = {}", milestone_notifications));
+    }
     
     if updates.is_empty() {
         return Err(AppError::BadRequest("No fields to update".to_string()));
@@ -432,10 +617,7 @@ async fn update_notification_config(
         "updated_at": chrono::Utc::now()
     })).await?;
     
-    Ok(Json(serde_json::json!({
-        "success": true,
-        "message": "Notification config updated successfully"
-    })))
+    Ok(ApiResponse::message("Notification config updated successfully"))
 }
 
 #[cfg(test)]
@@ -0,0 +1,369 @@
+//! 内置 ACME 客户端（Let's Encrypt 等），支持 HTTP-01 和 DNS-01 验证
+//!
+//! HTTP-01 的验证值保存在 acme_challenge 表中，由 `/.well-known/acme-challenge/:token` 返回；
+//! DNS-01 通过 acme-dns 兼容的 API 发布 TXT 记录，域名的 `_acme-challenge` 记录以 CNAME
+//! 委托到注册得到的 acme-dns 子域名。
+
+use crate::{
+    error::{AppError, Result},
+    services::Database,
+};
+use chrono::{DateTime, Duration, Utc};
+use instant_acme::{
+    Account, AccountCredentials, AuthorizationStatus, ChallengeType, Identifier, NewAccount,
+    NewOrder, Order, OrderStatus,
+};
+use rcgen::{Certificate, CertificateParams, DistinguishedName};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+use tracing::{debug, info};
+
+pub const LETS_ENCRYPT_PRODUCTION: &str = "https://acme-v02.api.letsencrypt.org/directory";
+pub const LETS_ENCRYPT_STAGING: &str = "https://acme-staging-v02.api.letsencrypt.org/directory";
+
+/// 订单状态轮询次数（指数退避，约 5 分钟）
+const MAX_POLL_ATTEMPTS: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AcmeChallenge {
+    Http01,
+    Dns01,
+}
+
+impl AcmeChallenge {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value {
+            "http-01" => Ok(AcmeChallenge::Http01),
+            "dns-01" => Ok(AcmeChallenge::Dns01),
+            other => Err(AppError::Internal(format!("Unsupported ACME challenge: {}", other))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AcmeConfig {
+    pub directory_url: String,
+    pub contact_email: Option<String>,
+    /// acme-dns 兼容的 API 地址，未配置时不支持 DNS-01
+    pub dns_api_url: Option<String>,
+}
+
+/// 签发得到的证书
+#[derive(Debug, Clone)]
+pub struct IssuedCertificate {
+    pub certificate_pem: String,
+    pub private_key_pem: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct DnsRegistration {
+    username: String,
+    password: String,
+    fulldomain: String,
+    subdomain: String,
+}
+
+#[derive(Clone)]
+pub struct AcmeClient {
+    db: Arc<Database>,
+    config: AcmeConfig,
+    http_client: Client,
+    account: Arc<OnceCell<Account>>,
+}
+
+impl AcmeClient {
+    pub fn new(db: Arc<Database>, config: AcmeConfig, http_client: Client) -> Self {
+        Self {
+            db,
+            config,
+            http_client,
+            account: Arc::new(OnceCell::new()),
+        }
+    }
+
+    pub fn supports_dns01(&self) -> bool {
+        self.config.dns_api_url.is_some()
+    }
+
+    /// 为域名签发证书，完成后清理验证记录
+    pub async fn issue(&self, domain: &str, challenge: AcmeChallenge) -> Result<IssuedCertificate> {
+        info!("Requesting ACME certificate for {} via {:?}", domain, challenge);
+
+        let account = self.account().await?;
+        let mut order = account
+            .new_order(&NewOrder { identifiers: &[Identifier::Dns(domain.to_string())] })
+            .await
+            .map_err(acme_error)?;
+
+        let result = self.complete_order(&mut order, domain, challenge).await;
+        self.cleanup_http_challenges(domain).await?;
+        result
+    }
+
+    async fn complete_order(&self, order: &mut Order, domain: &str, challenge: AcmeChallenge) -> Result<IssuedCertificate> {
+        let authorizations = order.authorizations().await.map_err(acme_error)?;
+
+        let mut ready_urls = Vec::new();
+        for authorization in &authorizations {
+            match authorization.status {
+                AuthorizationStatus::Pending => {}
+                AuthorizationStatus::Valid => continue,
+                status => {
+                    return Err(AppError::ExternalService(format!("ACME authorization is {:?}", status)));
+                }
+            }
+
+            let challenge_type = match challenge {
+                AcmeChallenge::Http01 => ChallengeType::Http01,
+                AcmeChallenge::Dns01 => ChallengeType::Dns01,
+            };
+            let acme_challenge = authorization
+                .challenges
+                .iter()
+                .find(|c| c.r#type == challenge_type)
+                .ok_or_else(|| AppError::ExternalService(format!("No {:?} challenge offered", challenge_type)))?;
+
+            let key_authorization = order.key_authorization(acme_challenge);
+            match challenge {
+                AcmeChallenge::Http01 => {
+                    self.store_http_challenge(domain, &acme_challenge.token, key_authorization.as_str()).await?;
+                }
+                AcmeChallenge::Dns01 => {
+                    self.publish_dns_challenge(domain, &key_authorization.dns_value()).await?;
+                }
+            }
+            ready_urls.push(acme_challenge.url.clone());
+        }
+
+        for url in &ready_urls {
+            order.set_challenge_ready(url).await.map_err(acme_error)?;
+        }
+
+        // 等待验证完成
+        let mut delay = std::time::Duration::from_secs(2);
+        let mut attempts = 0;
+        loop {
+            tokio::time::sleep(delay).await;
+            let state = order.refresh().await.map_err(acme_error)?;
+            match state.status {
+                OrderStatus::Ready | OrderStatus::Valid => break,
+                OrderStatus::Invalid => {
+                    return Err(AppError::ExternalService(format!("ACME order for {} is invalid", domain)));
+                }
+                _ => {}
+            }
+
+            attempts += 1;
+            if attempts >= MAX_POLL_ATTEMPTS {
+                return Err(AppError::ExternalService(format!("ACME order for {} timed out", domain)));
+            }
+            delay = (delay * 2).min(std::time::Duration::from_secs(60));
+            debug!("ACME order for {} not ready yet (attempt {})", domain, attempts);
+        }
+
+        let mut params = CertificateParams::new(vec![domain.to_string()]);
+        params.distinguished_name = DistinguishedName::new();
+        let key = Certificate::from_params(params)
+            .map_err(|e| AppError::Internal(format!("Failed to generate certificate key: {}", e)))?;
+        let csr = key
+            .serialize_request_der()
+            .map_err(|e| AppError::Internal(format!("Failed to create CSR: {}", e)))?;
+
+        order.finalize(&csr).await.map_err(acme_error)?;
+
+        let mut certificate_pem = None;
+        for _ in 0..MAX_POLL_ATTEMPTS {
+            certificate_pem = order.certificate().await.map_err(acme_error)?;
+            if certificate_pem.is_some() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        }
+        let certificate_pem = certificate_pem
+            .ok_or_else(|| AppError::ExternalService(format!("ACME certificate for {} was not issued in time", domain)))?;
+
+        info!("ACME certificate issued for {}", domain);
+        Ok(IssuedCertificate {
+            certificate_pem,
+            private_key_pem: key.serialize_private_key_pem(),
+            // ACME CA（Let's Encrypt）签发的证书有效期为 90 天
+            expires_at: Utc::now() + Duration::days(90),
+        })
+    }
+
+    /// HTTP-01 验证请求的响应内容
+    pub async fn http_challenge_response(&self, token: &str) -> Result<Option<String>> {
+        let mut response = self.db.query_with_params(
+            "SELECT key_authorization FROM acme_challenge WHERE token = $token LIMIT 1",
+            json!({ "token": token }),
+        ).await?;
+        let rows: Vec<Value> = response.take(0)?;
+
+        Ok(rows
+            .first()
+            .and_then(|row| row.get("key_authorization"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()))
+    }
+
+    /// 域名 `_acme-challenge` 记录需要 CNAME 到的目标，首次使用时向 acme-dns 注册
+    pub async fn dns_delegation_target(&self, host: &str) -> Result<Option<String>> {
+        if !self.supports_dns01() {
+            return Ok(None);
+        }
+        Ok(Some(self.dns_registration(host).await?.fulldomain))
+    }
+
+    async fn account(&self) -> Result<Account> {
+        self.account
+            .get_or_try_init(|| async {
+                let mut response = self.db.query_with_params(
+                    "SELECT credentials FROM acme_account WHERE directory_url = $directory_url LIMIT 1",
+                    json!({ "directory_url": self.config.directory_url }),
+                ).await?;
+                let rows: Vec<Value> = response.take(0)?;
+
+                if let Some(credentials) = rows.first().and_then(|row| row.get("credentials")) {
+                    let credentials: AccountCredentials = serde_json::from_value(credentials.clone())
+                        .map_err(|e| AppError::Internal(format!("Invalid stored ACME credentials: {}", e)))?;
+                    return Account::from_credentials(credentials).await.map_err(acme_error);
+                }
+
+                let contact = self.config.contact_email.as_ref().map(|email| format!("mailto:{}", email));
+                let contacts: Vec<&str> = contact.iter().map(String::as_str).collect();
+                let (account, credentials) = Account::create(
+                    &NewAccount {
+                        contact: &contacts,
+                        terms_of_service_agreed: true,
+                        only_return_existing: false,
+                    },
+                    &self.config.directory_url,
+                    None,
+                )
+                .await
+                .map_err(acme_error)?;
+
+                self.db.query_with_params(
+                    r#"
+                        CREATE acme_account CONTENT {
+                            directory_url: $directory_url,
+                            credentials: $credentials,
+                            created_at: time::now()
+                        }
+                    "#,
+                    json!({
+                        "directory_url": self.config.directory_url,
+                        "credentials": serde_json::to_value(&credentials)
+                            .map_err(|e| AppError::Internal(format!("Failed to serialize ACME credentials: {}", e)))?,
+                    }),
+                ).await?;
+
+                info!("Registered ACME account with {}", self.config.directory_url);
+                Ok(account)
+            })
+            .await
+            .cloned()
+    }
+
+    async fn store_http_challenge(&self, domain: &str, token: &str, key_authorization: &str) -> Result<()> {
+        self.db.query_with_params(
+            r#"
+                CREATE acme_challenge CONTENT {
+                    domain: $domain,
+                    token: $token,
+                    key_authorization: $key_authorization,
+                    created_at: time::now()
+                }
+            "#,
+            json!({ "domain": domain, "token": token, "key_authorization": key_authorization }),
+        ).await?;
+        Ok(())
+    }
+
+    async fn cleanup_http_challenges(&self, domain: &str) -> Result<()> {
+        self.db.query_with_params(
+            "DELETE acme_challenge WHERE domain = $domain",
+            json!({ "domain": domain }),
+        ).await?;
+        Ok(())
+    }
+
+    async fn publish_dns_challenge(&self, domain: &str, value: &str) -> Result<()> {
+        let api_url = self.config.dns_api_url.as_ref()
+            .ok_or_else(|| AppError::BadRequest("DNS-01 requires ACME_DNS_API_URL to be configured".to_string()))?;
+        let host = domain.strip_prefix("*.").unwrap_or(domain);
+        let registration = self.dns_registration(host).await?;
+
+        let response = self.http_client
+            .post(format!("{}/update", api_url.trim_end_matches('/')))
+            .header("X-Api-User", &registration.username)
+            .header("X-Api-Key", &registration.password)
+            .json(&json!({ "subdomain": registration.subdomain, "txt": value }))
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("acme-dns update failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(AppError::ExternalService(format!("acme-dns update failed: {}", error_text)));
+        }
+
+        // 给权威 DNS 一点时间生效
+        tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+        Ok(())
+    }
+
+    async fn dns_registration(&self, host: &str) -> Result<DnsRegistration> {
+        let mut response = self.db.query_with_params(
+            "SELECT username, password, fulldomain, subdomain FROM acme_dns_registration WHERE host = $host LIMIT 1",
+            json!({ "host": host }),
+        ).await?;
+        let existing: Vec<DnsRegistration> = response.take(0)?;
+        if let Some(registration) = existing.into_iter().next() {
+            return Ok(registration);
+        }
+
+        let api_url = self.config.dns_api_url.as_ref()
+            .ok_or_else(|| AppError::BadRequest("DNS-01 requires ACME_DNS_API_URL to be configured".to_string()))?;
+        let registration: DnsRegistration = self.http_client
+            .post(format!("{}/register", api_url.trim_end_matches('/')))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| AppError::ExternalService(format!("acme-dns registration failed: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("Invalid acme-dns registration: {}", e)))?;
+
+        self.db.query_with_params(
+            r#"
+                CREATE acme_dns_registration CONTENT {
+                    host: $host,
+                    username: $username,
+                    password: $password,
+                    fulldomain: $fulldomain,
+                    subdomain: $subdomain,
+                    created_at: time::now()
+                }
+            "#,
+            json!({
+                "host": host,
+                "username": registration.username,
+                "password": registration.password,
+                "fulldomain": registration.fulldomain,
+                "subdomain": registration.subdomain,
+            }),
+        ).await?;
+
+        info!("Registered acme-dns delegation for {}", host);
+        Ok(registration)
+    }
+}
+
+fn acme_error(e: instant_acme::Error) -> AppError {
+    AppError::ExternalService(format!("ACME request failed: {}", e))
+}
@@ -0,0 +1,231 @@
+use crate::{
+    config::Config,
+    error::Result,
+    models::notification::{ActivityDigestFrequency, NotificationPreferences},
+    services::{
+        email::{ActivityDigestArticle, ActivityDigestSection, EmailService},
+        Database, NotificationService,
+    },
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// 每个分组最多列出的文章数
+const SECTION_LIMIT: usize = 10;
+/// 每轮最多处理的用户数，其余留到下一轮
+const USERS_PER_RUN: usize = 500;
+/// 后台任务每小时运行一次，提前一小时视为到期，避免发送时间逐轮后移
+const SCHEDULE_TOLERANCE_HOURS: i64 = 1;
+
+/// 摘要中每篇文章需要的字段。笔名文章显示笔名，不暴露真实作者
+const ARTICLE_FIELDS: &str = r#"
+    title,
+    subtitle,
+    slug,
+    published_at,
+    meta::id(id) AS article_id,
+    IF pseudonym_id != NONE
+        THEN (SELECT VALUE display_name FROM type::thing('pseudonym', $parent.pseudonym_id))[0]
+        ELSE (SELECT VALUE display_name FROM user_profile WHERE user_id = $parent.author_id LIMIT 1)[0]
+    END AS author_name
+"#;
+
+#[derive(Debug, Deserialize)]
+struct DigestArticleRow {
+    article_id: String,
+    title: String,
+    subtitle: Option<String>,
+    slug: String,
+    author_name: Option<String>,
+}
+
+/// 按用户设置的频率，把关注的作者、出版物和标签的新文章汇总成一封邮件
+#[derive(Clone)]
+pub struct ActivityDigestService {
+    db: Arc<Database>,
+    config: Config,
+    email_service: EmailService,
+    notification_service: NotificationService,
+}
+
+impl ActivityDigestService {
+    pub async fn new(
+        db: Arc<Database>,
+        config: &Config,
+        notification_service: NotificationService,
+    ) -> Result<Self> {
+        Ok(Self {
+            db,
+            config: config.clone(),
+            email_service: EmailService::new(config)?,
+            notification_service,
+        })
+    }
+
+    /// 给所有到期的用户发送摘要，返回发出的邮件数。没有新文章时不发邮件，但同样推进发送时间
+    pub async fn send_due_digests(&self) -> Result<usize> {
+        if !self.config.enable_email_notifications {
+            return Ok(0);
+        }
+
+        let now = Utc::now();
+        let tolerance = Duration::hours(SCHEDULE_TOLERANCE_HOURS);
+        let due: Vec<NotificationPreferences> = self
+            .db
+            .prepare(
+                r#"
+                SELECT * FROM notification_preference
+                WHERE (activity_digest = 'daily' AND (activity_digest_sent_at = NONE OR activity_digest_sent_at <= <datetime> $daily_cutoff))
+                    OR (activity_digest = 'weekly' AND (activity_digest_sent_at = NONE OR activity_digest_sent_at <= <datetime> $weekly_cutoff))
+                LIMIT $limit
+                "#,
+            )
+            .bind("daily_cutoff", now - Duration::days(1) + tolerance)
+            .bind("weekly_cutoff", now - Duration::weeks(1) + tolerance)
+            .bind("limit", USERS_PER_RUN)
+            .fetch()
+            .await?;
+
+        let mut sent = 0;
+        for preferences in due {
+            match self.send_digest(&preferences, now).await {
+                Ok(true) => sent += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    // 不推进发送时间，下一轮重试
+                    warn!("Failed to send activity digest to {}: {}", preferences.user_id, e);
+                    continue;
+                }
+            }
+
+            self.db
+                .prepare("UPDATE type::thing('notification_preference', $user_id) SET activity_digest_sent_at = <datetime> $sent_at")
+                .bind("user_id", &preferences.user_id)
+                .bind("sent_at", now)
+                .execute()
+                .await?;
+        }
+
+        if sent > 0 {
+            info!("Sent {} activity digest emails", sent);
+        }
+        Ok(sent)
+    }
+
+    async fn send_digest(&self, preferences: &NotificationPreferences, now: DateTime<Utc>) -> Result<bool> {
+        let Some(period) = preferences.activity_digest.period() else {
+            return Ok(false);
+        };
+        let since = preferences
+            .activity_digest_sent_at
+            .unwrap_or(now - period)
+            .max(now - period * 2);
+
+        let sections = self.compile_sections(&preferences.user_id, since).await?;
+        if sections.is_empty() {
+            debug!("No followed activity for {} since {}", preferences.user_id, since);
+            return Ok(false);
+        }
+
+        let Some(email) = self
+            .notification_service
+            .get_recipient_email(&preferences.user_id)
+            .await?
+        else {
+            warn!("No email address for user {} in Rainbow-Auth, skipping activity digest", preferences.user_id);
+            return Ok(false);
+        };
+
+        let count: usize = sections.iter().map(|s| s.articles.len()).sum();
+        let subject = match preferences.activity_digest {
+            ActivityDigestFrequency::Weekly => format!("This week from people you follow: {} new stories", count),
+            _ => format!("Today from people you follow: {} new stories", count),
+        };
+
+        self.email_service
+            .send_activity_digest(&email, &subject, &sections)
+            .await?;
+        Ok(true)
+    }
+
+    /// 按作者、出版物、标签分组，同一篇文章只出现在第一个匹配的分组中
+    async fn compile_sections(&self, user_id: &str, since: DateTime<Utc>) -> Result<Vec<ActivityDigestSection>> {
+        let sql = format!(
+            r#"
+            LET $authors = (SELECT VALUE following_id FROM follow WHERE follower_id = $user_id);
+            LET $publications = (SELECT VALUE publication_id FROM publication_follow WHERE user_id = $user_id);
+            LET $tags = (SELECT VALUE meta::id(tag_id) FROM user_tag_follow WHERE user_id = $user_id);
+            SELECT {fields} FROM article
+                WHERE author_id INSIDE $authors
+                AND pseudonym_id IS NONE
+                AND status = 'published' AND is_deleted = false
+                AND published_at > <datetime> $since
+                ORDER BY published_at DESC LIMIT $limit;
+            SELECT {fields} FROM article
+                WHERE publication_id INSIDE $publications
+                AND author_id != $user_id
+                AND status = 'published' AND is_deleted = false
+                AND published_at > <datetime> $since
+                ORDER BY published_at DESC LIMIT $limit;
+            SELECT {fields} FROM article
+                WHERE id INSIDE (
+                    SELECT VALUE article_id FROM article_tag
+                    WHERE meta::id(tag_id) INSIDE $tags OR tag_id.parent_id INSIDE $tags
+                )
+                AND author_id != $user_id
+                AND status = 'published' AND is_deleted = false
+                AND published_at > <datetime> $since
+                ORDER BY published_at DESC LIMIT $limit;
+            "#,
+            fields = ARTICLE_FIELDS
+        );
+
+        let mut response = self
+            .db
+            .prepare(&sql)
+            .bind("user_id", user_id)
+            .bind("since", since)
+            .bind("limit", SECTION_LIMIT)
+            .execute()
+            .await?;
+
+        let from_authors: Vec<DigestArticleRow> = response.take(3)?;
+        let from_publications: Vec<DigestArticleRow> = response.take(4)?;
+        let from_tags: Vec<DigestArticleRow> = response.take(5)?;
+
+        let mut seen = HashSet::new();
+        let sections = [
+            ("From writers you follow", from_authors),
+            ("From publications you follow", from_publications),
+            ("In topics you follow", from_tags),
+        ]
+        .into_iter()
+        .filter_map(|(heading, rows)| {
+            let articles: Vec<ActivityDigestArticle> = rows
+                .into_iter()
+                .filter(|row| seen.insert(row.article_id.clone()))
+                .map(|row| ActivityDigestArticle {
+                    url: self.article_url(&row.slug),
+                    title: row.title,
+                    subtitle: row.subtitle,
+                    author: row.author_name,
+                })
+                .collect();
+
+            (!articles.is_empty()).then(|| ActivityDigestSection {
+                heading: heading.to_string(),
+                articles,
+            })
+        })
+        .collect();
+
+        Ok(sections)
+    }
+
+    fn article_url(&self, slug: &str) -> String {
+        format!("{}/articles/{}", self.config.frontend_url.trim_end_matches('/'), slug)
+    }
+}
@@ -0,0 +1,542 @@
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+    models::{
+        admin::*,
+        article::Article,
+        id::bare_id,
+        notification::{CreateNotificationRequest, NotificationType},
+        user::UserProfile,
+    },
+    services::{
+        auth::User, database::PaginatedResult, response_cache::CacheNamespace, Database, NotificationService,
+        ResponseCacheService,
+    },
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{collections::HashMap, sync::Arc};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+use validator::Validate;
+
+/// 角色分配很少变化，缓存一分钟，避免每个请求都查询数据库
+const ROLE_CACHE_TTL_SECS: i64 = 60;
+
+#[derive(Debug, Deserialize)]
+struct CountRow {
+    total: usize,
+}
+
+#[derive(Default)]
+struct RoleCache {
+    loaded_at: Option<DateTime<Utc>>,
+    roles: HashMap<String, PlatformRole>,
+}
+
+/// 平台管理：角色、用户停用、内容下架和全站数据
+#[derive(Clone)]
+pub struct AdminService {
+    db: Arc<Database>,
+    notification_service: NotificationService,
+    response_cache: ResponseCacheService,
+    /// 通过 `PLATFORM_ADMIN_IDS` 配置的管理员，不能在后台撤销
+    bootstrap_admins: Arc<Vec<String>>,
+    role_cache: Arc<RwLock<RoleCache>>,
+}
+
+impl AdminService {
+    pub async fn new(
+        db: Arc<Database>,
+        config: &Config,
+        notification_service: NotificationService,
+        response_cache: ResponseCacheService,
+    ) -> Result<Self> {
+        Ok(Self {
+            db,
+            notification_service,
+            response_cache,
+            bootstrap_admins: Arc::new(config.platform_admin_ids.clone()),
+            role_cache: Arc::new(RwLock::new(RoleCache::default())),
+        })
+    }
+
+    pub async fn role_of(&self, user_id: &str) -> Result<Option<PlatformRole>> {
+        if self.bootstrap_admins.iter().any(|id| id == user_id) {
+            return Ok(Some(PlatformRole::Admin));
+        }
+
+        {
+            let cache = self.role_cache.read().await;
+            if cache.loaded_at.map_or(false, |at| at + Duration::seconds(ROLE_CACHE_TTL_SECS) > Utc::now()) {
+                return Ok(cache.roles.get(user_id).copied());
+            }
+        }
+
+        let assignments = self.list_roles().await?;
+        let mut cache = self.role_cache.write().await;
+        cache.roles = assignments.into_iter().map(|a| (a.user_id, a.role)).collect();
+        cache.loaded_at = Some(Utc::now());
+        Ok(cache.roles.get(user_id).copied())
+    }
+
+    /// 把平台角色的权限合并到用户的权限列表，查询失败时按普通用户处理
+    pub async fn apply_platform_role(&self, user: &mut User) {
+        match self.role_of(&user.id).await {
+            Ok(Some(role)) => {
+                for permission in role.permissions() {
+                    if !user.permissions.iter().any(|p| p == permission) {
+                        user.permissions.push(permission.to_string());
+                    }
+                }
+            }
+            Ok(None) => {}
+            Err(e) => warn!("Failed to load platform role for user {}: {}", user.id, e),
+        }
+    }
+
+    /// 拥有某项平台权限的用户，包括 `PLATFORM_ADMIN_IDS` 配置的管理员
+    pub async fn users_with_permission(&self, permission: &str) -> Result<Vec<String>> {
+        let mut user_ids: Vec<String> = self.bootstrap_admins.to_vec();
+        for assignment in self.list_roles().await? {
+            if assignment.role.permissions().contains(&permission) && !user_ids.contains(&assignment.user_id) {
+                user_ids.push(assignment.user_id);
+            }
+        }
+        Ok(user_ids)
+    }
+
+    async fn invalidate_roles(&self) {
+        self.role_cache.write().await.loaded_at = None;
+    }
+
+    // ---- 角色 ----
+
+    pub async fn list_roles(&self) -> Result<Vec<PlatformRoleAssignment>> {
+        self.db
+            .prepare("SELECT user_id, role, granted_by, granted_at FROM platform_role ORDER BY granted_at")
+            .fetch()
+            .await
+    }
+
+    pub async fn assign_role(&self, actor_id: &str, user_id: &str, role: PlatformRole) -> Result<PlatformRoleAssignment> {
+        if actor_id == user_id {
+            return Err(AppError::BadRequest("不能修改自己的平台角色".to_string()));
+        }
+        self.require_profile(user_id).await?;
+
+        let assignment: Option<PlatformRoleAssignment> = self
+            .db
+            .prepare(
+                r#"
+                UPSERT type::thing('platform_role', $user_id) SET
+                    user_id = $user_id,
+                    role = $role,
+                    granted_by = $granted_by,
+                    granted_at = time::now()
+                RETURN user_id, role, granted_by, granted_at
+                "#,
+            )
+            .bind("user_id", user_id)
+            .bind("role", role)
+            .bind("granted_by", actor_id)
+            .fetch_one()
+            .await?;
+        let assignment = assignment.ok_or_else(|| AppError::internal("Failed to assign platform role"))?;
+
+        self.invalidate_roles().await;
+        self.record(actor_id, "role.assign", "user", user_id, Some(role.as_str())).await;
+        Ok(assignment)
+    }
+
+    pub async fn revoke_role(&self, actor_id: &str, user_id: &str) -> Result<()> {
+        if actor_id == user_id {
+            return Err(AppError::BadRequest("不能修改自己的平台角色".to_string()));
+        }
+        if self.bootstrap_admins.iter().any(|id| id == user_id) {
+            return Err(AppError::BadRequest("该管理员由 PLATFORM_ADMIN_IDS 配置，不能在后台撤销".to_string()));
+        }
+
+        let deleted: Option<PlatformRoleAssignment> = self
+            .db
+            .prepare("DELETE type::thing('platform_role', $user_id) RETURN BEFORE")
+            .bind("user_id", user_id)
+            .fetch_one()
+            .await?;
+        if deleted.is_none() {
+            return Err(AppError::NotFound("该用户没有平台角色".to_string()));
+        }
+
+        self.invalidate_roles().await;
+        self.record(actor_id, "role.revoke", "user", user_id, None).await;
+        Ok(())
+    }
+
+    // ---- 用户 ----
+
+    pub async fn list_users(&self, query: AdminUserQuery) -> Result<PaginatedResult<AdminUserSummary>> {
+        let page = query.page.unwrap_or(1).max(1);
+        let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+        let mut conditions = vec!["true".to_string()];
+        if query.search.is_some() {
+            conditions.push(
+                "(string::contains(string::lowercase(username), $search) OR string::contains(string::lowercase(display_name), $search) OR user_id = $search_exact)"
+                    .to_string(),
+            );
+        }
+        match query.suspended {
+            Some(true) => conditions.push("suspended_at != NONE".to_string()),
+            Some(false) => conditions.push("suspended_at = NONE".to_string()),
+            None => {}
+        }
+        let condition = conditions.join(" AND ");
+
+        let search = query.search.as_deref().map(str::trim).unwrap_or_default();
+        let mut response = self
+            .db
+            .prepare(&format!(
+                r#"
+                SELECT count() AS total FROM user_profile WHERE {condition} GROUP ALL;
+                SELECT * FROM user_profile WHERE {condition} ORDER BY created_at DESC LIMIT $limit START $offset;
+                "#
+            ))
+            .bind("search", search.to_lowercase())
+            .bind("search_exact", search)
+            .bind("limit", limit)
+            .bind("offset", (page - 1) * limit)
+            .execute()
+            .await?;
+
+        let totals: Vec<CountRow> = response.take(0)?;
+        let total = totals.first().map_or(0, |t| t.total);
+        let profiles: Vec<UserProfile> = response.take(1)?;
+
+        let mut data = Vec::with_capacity(profiles.len());
+        for profile in profiles {
+            let platform_role = self.role_of(&profile.user_id).await?;
+            data.push(AdminUserSummary { profile, platform_role });
+        }
+
+        Ok(PaginatedResult {
+            data,
+            total,
+            page,
+            per_page: limit,
+            total_pages: (total + limit - 1) / limit,
+        })
+    }
+
+    pub async fn get_user(&self, user_id: &str) -> Result<AdminUserSummary> {
+        let profile = self.require_profile(user_id).await?;
+        let platform_role = self.role_of(user_id).await?;
+        Ok(AdminUserSummary { profile, platform_role })
+    }
+
+    async fn require_profile(&self, user_id: &str) -> Result<UserProfile> {
+        let profile: Option<UserProfile> = self
+            .db
+            .prepare("SELECT * FROM user_profile WHERE user_id = $user_id LIMIT 1")
+            .bind("user_id", user_id)
+            .fetch_one()
+            .await?;
+        profile.ok_or_else(|| AppError::NotFound("用户不存在".to_string()))
+    }
+
+    /// 停用账户：停用期间只能读取，资料不出现在搜索和推荐中
+    pub async fn suspend_user(&self, actor_id: &str, user_id: &str, request: ModerationReasonRequest) -> Result<AdminUserSummary> {
+        request.validate()?;
+        if actor_id == user_id {
+            return Err(AppError::BadRequest("不能停用自己的账户".to_string()));
+        }
+        if self.role_of(user_id).await? == Some(PlatformRole::Admin) {
+            return Err(AppError::forbidden("Platform admins cannot be suspended, revoke the role first"));
+        }
+
+        let profile: Option<UserProfile> = self
+            .db
+            .prepare(
+                r#"
+                UPDATE user_profile SET
+                    is_suspended = true,
+                    suspended_at = time::now(),
+                    suspension_reason = $reason,
+                    updated_at = time::now()
+                WHERE user_id = $user_id
+                "#,
+            )
+            .bind("user_id", user_id)
+            .bind("reason", &request.reason)
+            .fetch_one()
+            .await?;
+        let profile = profile.ok_or_else(|| AppError::NotFound("用户不存在".to_string()))?;
+
+        self.record(actor_id, "user.suspend", "user", user_id, Some(&request.reason)).await;
+        self.notify(
+            user_id,
+            "Your account has been suspended",
+            format!("Your account has been suspended by a moderator: {}", request.reason),
+            json!({ "reason": request.reason }),
+        )
+        .await;
+
+        info!("User {} suspended by admin {}", user_id, actor_id);
+        Ok(AdminUserSummary { profile, platform_role: self.role_of(user_id).await? })
+    }
+
+    pub async fn unsuspend_user(&self, actor_id: &str, user_id: &str) -> Result<AdminUserSummary> {
+        let profile: Option<UserProfile> = self
+            .db
+            .prepare(
+                r#"
+                UPDATE user_profile SET
+                    is_suspended = false,
+                    suspended_at = NONE,
+                    suspension_reason = NONE,
+                    updated_at = time::now()
+                WHERE user_id = $user_id AND suspended_at != NONE
+                "#,
+            )
+            .bind("user_id", user_id)
+            .fetch_one()
+            .await?;
+        let profile = profile.ok_or_else(|| AppError::NotFound("该用户未被停用".to_string()))?;
+
+        self.record(actor_id, "user.unsuspend", "user", user_id, None).await;
+        self.notify(
+            user_id,
+            "Your account has been restored",
+            "Your account suspension has been lifted.".to_string(),
+            json!({}),
+        )
+        .await;
+
+        info!("User {} unsuspended by admin {}", user_id, actor_id);
+        Ok(AdminUserSummary { profile, platform_role: self.role_of(user_id).await? })
+    }
+
+    // ---- 文章 ----
+
+    pub async fn list_articles(&self, query: AdminArticleQuery) -> Result<PaginatedResult<Article>> {
+        let page = query.page.unwrap_or(1).max(1);
+        let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+        let mut conditions = vec!["is_deleted = false".to_string()];
+        if query.author_id.is_some() {
+            conditions.push("author_id = $author_id".to_string());
+        }
+        match query.taken_down {
+            Some(true) => conditions.push("taken_down_at != NONE".to_string()),
+            Some(false) => conditions.push("taken_down_at = NONE".to_string()),
+            None => {}
+        }
+        let condition = conditions.join(" AND ");
+
+        let mut response = self
+            .db
+            .prepare(&format!(
+                r#"
+                SELECT count() AS total FROM article WHERE {condition} GROUP ALL;
+                SELECT * FROM article WHERE {condition} ORDER BY created_at DESC LIMIT $limit START $offset;
+                "#
+            ))
+            .bind("author_id", query.author_id)
+            .bind("limit", limit)
+            .bind("offset", (page - 1) * limit)
+            .execute()
+            .await?;
+
+        let totals: Vec<CountRow> = response.take(0)?;
+        let total = totals.first().map_or(0, |t| t.total);
+        let data: Vec<Article> = response.take(1)?;
+
+        Ok(PaginatedResult {
+            data,
+            total,
+            page,
+            per_page: limit,
+            total_pages: (total + limit - 1) / limit,
+        })
+    }
+
+    /// 下架文章：转为归档状态，作者在恢复之前不能重新发布
+    pub async fn take_down_article(&self, actor_id: &str, article_id: &str, request: ModerationReasonRequest) -> Result<Article> {
+        request.validate()?;
+
+        let article: Option<Article> = self
+            .db
+            .prepare(
+                r#"
+                UPDATE type::thing('article', $article_id) SET
+                    status = 'archived',
+                    scheduled_at = NONE,
+                    taken_down_at = time::now(),
+                    updated_at = time::now()
+                WHERE is_deleted = false AND taken_down_at = NONE
+                "#,
+            )
+            .bind("article_id", bare_id("article", article_id))
+            .fetch_one()
+            .await?;
+        let article = article.ok_or_else(|| AppError::NotFound("文章不存在或已被下架".to_string()))?;
+        // 下架立即生效，不等缓存过期
+        self.response_cache.invalidate(CacheNamespace::Article, &article.slug).await;
+
+        self.record(actor_id, "article.take_down", "article", &article.id, Some(&request.reason)).await;
+        self.notify(
+            &article.author_id,
+            "Your article has been taken down",
+            format!("\"{}\" has been taken down by a moderator: {}", article.title, request.reason),
+            json!({ "article_id": article.id, "reason": request.reason }),
+        )
+        .await;
+
+        info!("Article {} taken down by admin {}", article.id, actor_id);
+        Ok(article)
+    }
+
+    /// 恢复下架的文章：发布过的文章恢复为已发布，其余退回草稿
+    pub async fn restore_article(&self, actor_id: &str, article_id: &str) -> Result<Article> {
+        let article: Option<Article> = self
+            .db
+            .prepare(
+                r#"
+                UPDATE type::thing('article', $article_id) SET
+                    status = IF published_at != NONE THEN 'published' ELSE 'draft' END,
+                    taken_down_at = NONE,
+                    updated_at = time::now()
+                WHERE taken_down_at != NONE
+                "#,
+            )
+            .bind("article_id", bare_id("article", article_id))
+            .fetch_one()
+            .await?;
+        let article = article.ok_or_else(|| AppError::NotFound("文章不存在或未被下架".to_string()))?;
+        self.response_cache.invalidate(CacheNamespace::Article, &article.slug).await;
+
+        self.record(actor_id, "article.restore", "article", &article.id, None).await;
+        self.notify(
+            &article.author_id,
+            "Your article has been restored",
+            format!("\"{}\" has been restored by a moderator.", article.title),
+            json!({ "article_id": article.id }),
+        )
+        .await;
+
+        Ok(article)
+    }
+
+    // ---- 数据和审计 ----
+
+    pub async fn platform_stats(&self) -> Result<PlatformStats> {
+        let stats: Option<PlatformStats> = self
+            .db
+            .prepare(
+                r#"
+                RETURN {
+                    total_users: (SELECT count() FROM user_profile GROUP ALL)[0].count ?? 0,
+                    new_users_7d: (SELECT count() FROM user_profile WHERE created_at > time::now() - 7d GROUP ALL)[0].count ?? 0,
+                    active_users_30d: (SELECT count() FROM user_profile WHERE last_active_at > time::now() - 30d GROUP ALL)[0].count ?? 0,
+                    suspended_users: (SELECT count() FROM user_profile WHERE suspended_at != NONE GROUP ALL)[0].count ?? 0,
+                    published_articles: (SELECT count() FROM article WHERE status = 'published' AND is_deleted = false GROUP ALL)[0].count ?? 0,
+                    articles_published_7d: (SELECT count() FROM article WHERE status = 'published' AND is_deleted = false AND published_at > time::now() - 7d GROUP ALL)[0].count ?? 0,
+                    taken_down_articles: (SELECT count() FROM article WHERE taken_down_at != NONE GROUP ALL)[0].count ?? 0,
+                    pending_review_articles: (SELECT count() FROM article WHERE status = 'pending_review' AND is_deleted = false GROUP ALL)[0].count ?? 0,
+                    total_comments: (SELECT count() FROM comment WHERE is_deleted = false GROUP ALL)[0].count ?? 0,
+                    held_comments: (SELECT count() FROM comment WHERE is_deleted = false AND moderation_status INSIDE ['pending', 'spam'] GROUP ALL)[0].count ?? 0,
+                    publications: (SELECT count() FROM publication GROUP ALL)[0].count ?? 0,
+                    open_reports: (SELECT count() FROM report_case WHERE status = 'open' GROUP ALL)[0].count ?? 0
+                };
+                "#,
+            )
+            .fetch_one()
+            .await?;
+
+        Ok(stats.unwrap_or_default())
+    }
+
+    pub async fn audit_log(&self, query: AdminAuditQuery) -> Result<PaginatedResult<AdminAction>> {
+        let page = query.page.unwrap_or(1).max(1);
+        let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+        let mut conditions = vec!["true".to_string()];
+        if query.actor_id.is_some() {
+            conditions.push("actor_id = $actor_id".to_string());
+        }
+        if query.target_id.is_some() {
+            conditions.push("target_id = $target_id".to_string());
+        }
+        let condition = conditions.join(" AND ");
+
+        let mut response = self
+            .db
+            .prepare(&format!(
+                r#"
+                SELECT count() AS total FROM admin_action WHERE {condition} GROUP ALL;
+                SELECT * FROM admin_action WHERE {condition} ORDER BY created_at DESC LIMIT $limit START $offset;
+                "#
+            ))
+            .bind("actor_id", query.actor_id)
+            .bind("target_id", query.target_id)
+            .bind("limit", limit)
+            .bind("offset", (page - 1) * limit)
+            .execute()
+            .await?;
+
+        let totals: Vec<CountRow> = response.take(0)?;
+        let total = totals.first().map_or(0, |t| t.total);
+        let data: Vec<AdminAction> = response.take(1)?;
+
+        Ok(PaginatedResult {
+            data,
+            total,
+            page,
+            per_page: limit,
+            total_pages: (total + limit - 1) / limit,
+        })
+    }
+
+    /// 记录管理操作。审计写入失败不影响操作本身
+    pub(crate) async fn record(&self, actor_id: &str, action: &str, target_type: &str, target_id: &str, reason: Option<&str>) {
+        let result = self
+            .db
+            .prepare(
+                r#"
+                CREATE admin_action CONTENT {
+                    actor_id: $actor_id,
+                    action: $action,
+                    target_type: $target_type,
+                    target_id: $target_id,
+                    reason: $reason,
+                    created_at: time::now()
+                }
+                "#,
+            )
+            .bind("actor_id", actor_id)
+            .bind("action", action)
+            .bind("target_type", target_type)
+            .bind("target_id", target_id)
+            .bind("reason", reason)
+            .execute()
+            .await;
+
+        if let Err(e) = result {
+            warn!("Failed to record admin action {} on {}: {}", action, target_id, e);
+        }
+    }
+
+    async fn notify(&self, user_id: &str, title: &str, message: String, data: Value) {
+        let notification = CreateNotificationRequest {
+            recipient_id: user_id.to_string(),
+            notification_type: NotificationType::Moderation,
+            title: title.to_string(),
+            message,
+            data,
+        };
+
+        if let Err(e) = self.notification_service.create_notification(notification).await {
+            warn!("Failed to send moderation notice to {}: {}", user_id, e);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+    utils::spam::{SpamCheck, SpamFilter, SpamSignal},
+};
+use async_trait::async_trait;
+use reqwest::Client;
+use std::time::Duration;
+use tracing::debug;
+
+const AKISMET_API_URL: &str = "rest.akismet.com/1.1/comment-check";
+
+/// Akismet 垃圾评论检测，配置 AKISMET_API_KEY 后加入评论打分流程
+pub struct AkismetSpamFilter {
+    http_client: Client,
+    api_key: String,
+    blog_url: String,
+}
+
+impl AkismetSpamFilter {
+    /// 未配置 API key 时返回 None
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        let Some(api_key) = config.akismet_api_key.clone().filter(|key| !key.is_empty()) else {
+            return Ok(None);
+        };
+
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()?;
+
+        Ok(Some(Self {
+            http_client,
+            api_key,
+            blog_url: config.frontend_url.clone(),
+        }))
+    }
+}
+
+#[async_trait]
+impl SpamFilter for AkismetSpamFilter {
+    fn name(&self) -> &str {
+        "akismet"
+    }
+
+    async fn score(&self, check: &SpamCheck) -> Result<SpamSignal> {
+        let mut form = vec![
+            ("blog", self.blog_url.as_str()),
+            ("comment_type", "comment"),
+            ("comment_content", check.content.as_str()),
+            ("user_ip", check.user_ip.as_deref().unwrap_or_default()),
+        ];
+        if let Some(user_agent) = &check.user_agent {
+            form.push(("user_agent", user_agent));
+        }
+        if let Some(author_name) = &check.author_name {
+            form.push(("comment_author", author_name));
+        }
+        if let Some(permalink) = &check.permalink {
+            form.push(("permalink", permalink));
+        }
+
+        let response = self.http_client
+            .post(format!("https://{}.{}", self.api_key, AKISMET_API_URL))
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("Akismet request failed: {}", e)))?;
+
+        // discard 表示明显的垃圾评论，可以直接丢弃
+        let discard = response
+            .headers()
+            .get("x-akismet-pro-tip")
+            .and_then(|v| v.to_str().ok())
+            == Some("discard");
+        let body = response
+            .text()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("Akismet response failed: {}", e)))?;
+
+        let (score, reasons) = match body.trim() {
+            "true" if discard => (1.0, vec!["akismet: blatant spam".to_string()]),
+            "true" => (0.9, vec!["akismet: spam".to_string()]),
+            "false" => (0.0, Vec::new()),
+            other => {
+                return Err(AppError::ExternalService(format!("Unexpected Akismet response: {}", other)));
+            }
+        };
+        debug!("Akismet scored comment by {}: {}", check.author_id, score);
+
+        Ok(SpamSignal {
+            filter: self.name().to_string(),
+            score,
+            reasons,
+        })
+    }
+}
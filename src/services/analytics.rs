@@ -1,22 +1,229 @@
 use crate::{
     error::{AppError, Result},
-    models::analytics::*,
-    services::Database,
+    models::{analytics::*, article::Article, id::{ArticleId, UserId}, websocket::ArticlePresence},
+    services::{Database, WebSocketService},
+    utils::sentiment::{LexiconSentimentAnalyzer, SentimentAnalyzer, SentimentLabel},
 };
 use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
 use serde_json::{json, Value};
 use std::sync::Arc;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use tracing::{debug, info};
 
+/// 受众分析的最长时间范围
+const MAX_AUDIENCE_RANGE_DAYS: i64 = 366;
+
 #[derive(Clone)]
 pub struct AnalyticsService {
     db: Arc<Database>,
+    websocket_service: WebSocketService,
+    sentiment_analyzer: Arc<dyn SentimentAnalyzer>,
+}
+
+/// 受众分析：按读者和来源、国家、设备、浏览器分组的浏览数
+#[derive(Debug, Deserialize)]
+struct AudienceViewRow {
+    viewer_hash: String,
+    referrer: String,
+    country: Option<String>,
+    device_type: Option<String>,
+    browser: Option<String>,
+    views: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct HourlyViewRow {
+    article_id: String,
+    device_type: Option<String>,
+    hour: u8,
+    views: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReadingTimeRow {
+    id: String,
+    #[serde(default)]
+    reading_time: i64,
+}
+
+/// 评论统计所需的字段
+#[derive(Debug, Deserialize)]
+struct CommentStatRow {
+    author_id: String,
+    #[serde(default)]
+    parent_id: Option<String>,
+    #[serde(default)]
+    is_author_response: bool,
+    #[serde(default)]
+    clap_count: i64,
+    content: String,
+    created_at: DateTime<Utc>,
 }
 
 impl AnalyticsService {
-    pub async fn new(db: Arc<Database>) -> Result<Self> {
-        Ok(Self { db })
+    pub async fn new(db: Arc<Database>, websocket_service: WebSocketService) -> Result<Self> {
+        Ok(Self {
+            db,
+            websocket_service,
+            sentiment_analyzer: Arc::new(LexiconSentimentAnalyzer::new()),
+        })
+    }
+
+    /// 替换默认的词典情感分析器
+    pub fn with_sentiment_analyzer(mut self, analyzer: Arc<dyn SentimentAnalyzer>) -> Self {
+        self.sentiment_analyzer = analyzer;
+        self
+    }
+
+    /// 记录一次文章浏览事件，用于按小时的流量分析和异常检测
+    pub async fn record_article_view(&self, article: &Article, referrer: Option<&str>) -> Result<()> {
+        self.db.query_with_params(
+            r#"
+                CREATE article_view_event CONTENT {
+                    article_id: $article_id,
+                    publication_id: $publication_id ?? NONE,
+                    referrer: $referrer,
+                    created_at: time::now()
+                }
+            "#,
+            json!({
+                "article_id": article.id,
+                "publication_id": article.publication_id,
+                "referrer": referrer_source(referrer),
+            }),
+        ).await?;
+
+        Ok(())
+    }
+
+    /// 获取单篇文章的评论统计：评论量走势、活跃评论者和可选的情感分布
+    pub async fn get_article_comment_analytics(
+        &self,
+        article_id: &str,
+        query: CommentAnalyticsQuery,
+    ) -> Result<ArticleCommentAnalytics> {
+        debug!("Getting comment analytics for article: {}", article_id);
+
+        let days = query.days.unwrap_or(30).clamp(1, 365);
+        let top_limit = query.top_limit.unwrap_or(10).min(50);
+
+        // 评论的 article_id 可能以带或不带表前缀的形式保存
+        let article = ArticleId::new(article_id);
+        let bare_id = article.as_str();
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT author_id, parent_id, is_author_response, clap_count, content, created_at
+                FROM comment
+                WHERE article_id INSIDE $article_ids AND is_deleted = false
+            "#,
+            json!({ "article_ids": [bare_id, format!("article:{}", bare_id)] }),
+        ).await?;
+        let comments: Vec<CommentStatRow> = response.take(0)?;
+
+        // 每日评论量，没有评论的日期补零
+        let today = Utc::now().date_naive();
+        let start = today - Duration::days(days - 1);
+        let mut volume: BTreeMap<chrono::NaiveDate, (i64, i64)> = (0..days)
+            .map(|offset| (start + Duration::days(offset), (0, 0)))
+            .collect();
+
+        let mut commenters: HashMap<String, (i64, i64)> = HashMap::new();
+        let mut unique_commenters = HashSet::new();
+        let mut total_replies = 0;
+        let mut author_responses = 0;
+
+        for comment in &comments {
+            let is_reply = comment.parent_id.as_deref().map_or(false, |p| !p.is_empty());
+            if is_reply {
+                total_replies += 1;
+            }
+            if comment.is_author_response {
+                author_responses += 1;
+            } else {
+                let entry = commenters.entry(comment.author_id.clone()).or_insert((0, 0));
+                entry.0 += 1;
+                entry.1 += comment.clap_count;
+            }
+            unique_commenters.insert(comment.author_id.as_str());
+
+            if let Some(bucket) = volume.get_mut(&comment.created_at.date_naive()) {
+                if is_reply {
+                    bucket.1 += 1;
+                } else {
+                    bucket.0 += 1;
+                }
+            }
+        }
+
+        let mut top: Vec<(String, (i64, i64))> = commenters.into_iter().collect();
+        top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top.truncate(top_limit);
+        let top_commenters = self.build_top_commenters(top).await?;
+
+        let sentiment = if query.include_sentiment && !comments.is_empty() {
+            let texts: Vec<String> = comments.iter().map(|c| c.content.clone()).collect();
+            let labels = self.sentiment_analyzer.analyze_batch(&texts).await?;
+            let count = |label: SentimentLabel| labels.iter().filter(|l| **l == label).count() as i64;
+
+            Some(CommentSentimentDistribution {
+                analyzer: self.sentiment_analyzer.name().to_string(),
+                positive: count(SentimentLabel::Positive),
+                neutral: count(SentimentLabel::Neutral),
+                negative: count(SentimentLabel::Negative),
+            })
+        } else {
+            None
+        };
+
+        Ok(ArticleCommentAnalytics {
+            article_id: format!("article:{}", bare_id),
+            total_comments: comments.len() as i64 - total_replies,
+            total_replies,
+            unique_commenters: unique_commenters.len() as i64,
+            author_responses,
+            volume: volume
+                .into_iter()
+                .map(|(date, (comments, replies))| CommentVolumePoint {
+                    date: date.format("%Y-%m-%d").to_string(),
+                    comments,
+                    replies,
+                })
+                .collect(),
+            top_commenters,
+            sentiment,
+        })
+    }
+
+    async fn build_top_commenters(&self, top: Vec<(String, (i64, i64))>) -> Result<Vec<TopCommenter>> {
+        if top.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let user_ids: Vec<&String> = top.iter().map(|(id, _)| id).collect();
+        let mut response = self.db.query_with_params(
+            "SELECT user_id, username, display_name FROM user_profile WHERE user_id INSIDE $user_ids",
+            json!({ "user_ids": user_ids }),
+        ).await?;
+        let profiles: Vec<Value> = response.take(0)?;
+        let profiles: HashMap<String, Value> = profiles
+            .into_iter()
+            .filter_map(|p| p["user_id"].as_str().map(|id| (id.to_string(), p.clone())))
+            .collect();
+
+        Ok(top
+            .into_iter()
+            .map(|(user_id, (comment_count, total_claps))| {
+                let profile = profiles.get(&user_id);
+                TopCommenter {
+                    username: profile.and_then(|p| p["username"].as_str()).map(String::from),
+                    display_name: profile.and_then(|p| p["display_name"].as_str()).map(String::from),
+                    user_id,
+                    comment_count,
+                    total_claps,
+                }
+            })
+            .collect())
     }
 
     /// 获取用户的综合统计仪表板
@@ -70,6 +277,7 @@ impl AnalyticsService {
                 AVG(a.view_count) as avg_views_per_article
             FROM article a
             WHERE a.author_id = $user_id
+            AND a.pseudonym_id IS NONE
             AND a.status = 'published'
             AND a.is_deleted = false
         "#;
@@ -134,6 +342,7 @@ impl AnalyticsService {
                 (a.clap_count + a.comment_count + a.bookmark_count) * 100.0 / NULLIF(a.view_count, 0) as engagement_rate
             FROM article a
             WHERE a.author_id = $user_id
+            AND a.pseudonym_id IS NONE
             AND a.status = 'published'
             AND a.is_deleted = false
             ORDER BY a.published_at DESC
@@ -210,7 +419,7 @@ impl AnalyticsService {
             FROM (
                 SELECT 'view' as type, id, created_at FROM article_view WHERE author_id = $user_id
                 UNION ALL
-                SELECT 'clap' as type, id, created_at FROM clap WHERE article_id IN (SELECT id FROM article WHERE author_id = $user_id)
+                SELECT 'clap' as type, id, created_at FROM reaction WHERE reaction_type = 'clap' AND article_id IN (SELECT id FROM article WHERE author_id = $user_id)
                 UNION ALL
                 SELECT 'comment' as type, id, created_at FROM comment WHERE article_id IN (SELECT id FROM article WHERE author_id = $user_id)
                 UNION ALL
@@ -287,7 +496,15 @@ impl AnalyticsService {
         start_date: &DateTime<Utc>,
         end_date: &DateTime<Utc>,
     ) -> Result<AudienceAnalytics> {
-        // 使用真实的受众分析实现
+        if start_date >= end_date {
+            return Err(AppError::BadRequest("start_date must be before end_date".to_string()));
+        }
+        if *end_date - *start_date > Duration::days(MAX_AUDIENCE_RANGE_DAYS) {
+            return Err(AppError::BadRequest(format!(
+                "Audience analytics cover at most {} days",
+                MAX_AUDIENCE_RANGE_DAYS
+            )));
+        }
         self.get_real_audience_analytics(user_id, start_date, end_date).await
     }
 
@@ -481,11 +698,12 @@ impl AnalyticsService {
 
     /// 获取实时分析
     pub async fn get_realtime_analytics(&self, user_id: &str) -> Result<RealtimeAnalytics> {
-        // 获取当前活跃读者数
-        let active_readers = self.get_active_readers_count(user_id).await?;
+        // 当前通过 WebSocket 在线阅读作者文章的读者
+        let presence = self.websocket_service.author_presence(user_id);
+        let active_readers = presence.active_readers;
         
         // 获取正在被阅读的文章
-        let articles_being_read = self.get_articles_being_read(user_id, 5).await?;
+        let articles_being_read = self.get_articles_being_read(presence.articles, 5).await?;
         
         // 获取最近的互动
         let recent_interactions = self.get_recent_interactions(user_id, 10).await?;
@@ -517,16 +735,20 @@ impl AnalyticsService {
             .unwrap_or(0))
     }
 
+    /// 去重浏览记录中不同读者的数量（登录用户按账号，匿名读者按 IP + User-Agent 哈希）
     async fn get_unique_viewers_count(&self, article_id: &str) -> Result<i64> {
-        // 简化实现，实际应该有用户访问记录
-        let query = "SELECT view_count FROM article WHERE id = $article_id";
-        let mut response = self.db.query_with_params(query, json!({"article_id": article_id})).await?;
+        let query = r#"
+            SELECT count() AS count FROM (
+                SELECT viewer_hash FROM article_view WHERE article_id = $article_id GROUP BY viewer_hash
+            ) GROUP ALL
+        "#;
+        let mut response = self.db.query_with_params(query, json!({
+            "article_id": ArticleId::new(article_id).as_str()
+        })).await?;
         let result: Vec<Value> = response.take(0)?;
-        let total_views = result.first()
-            .and_then(|v| v["view_count"].as_i64())
-            .unwrap_or(0);
-        // 假设唯一查看者约为总查看数的70%
-        Ok((total_views as f64 * 0.7) as i64)
+        Ok(result.first()
+            .and_then(|v| v["count"].as_i64())
+            .unwrap_or(0))
     }
 
     async fn get_total_readers_count(
@@ -655,40 +877,32 @@ impl AnalyticsService {
         Ok(results)
     }
 
-    async fn get_active_readers_count(&self, user_id: &str) -> Result<i64> {
-        // 简化实现，返回一个模拟值
-        // 实际应该根据会话或WebSocket连接统计
-        Ok(5)
-    }
-
-    async fn get_articles_being_read(&self, user_id: &str, limit: i32) -> Result<Vec<ArticleReadInfo>> {
-        // 简化实现，返回最近发布的文章
-        let query = r#"
-            SELECT id as article_id, title
-            FROM article
-            WHERE author_id = $user_id
-            AND status = 'published'
-            ORDER BY published_at DESC
-            LIMIT $limit
-        "#;
-
-        let mut response = self.db.query_with_params(query, json!({
-            "user_id": user_id,
-            "limit": limit
-        })).await?;
-
-        let articles: Vec<Value> = response.take(0)?;
-        let mut results = Vec::new();
-
-        for (idx, article) in articles.iter().enumerate() {
-            results.push(ArticleReadInfo {
-                article_id: article["article_id"].as_str().unwrap_or("").to_string(),
-                title: article["title"].as_str().unwrap_or("").to_string(),
-                reader_count: (5 - idx) as i64, // 模拟递减的读者数
-            });
+    async fn get_articles_being_read(&self, mut articles: Vec<ArticlePresence>, limit: usize) -> Result<Vec<ArticleReadInfo>> {
+        articles.truncate(limit);
+        if articles.is_empty() {
+            return Ok(Vec::new());
         }
 
-        Ok(results)
+        let ids: Vec<&str> = articles.iter().map(|a| a.article_id.as_str()).collect();
+        let titles: Vec<Value> = self
+            .db
+            .prepare("SELECT meta::id(id) AS article_id, title FROM article WHERE meta::id(id) INSIDE $ids")
+            .bind("ids", ids)
+            .fetch()
+            .await?;
+        let titles: HashMap<&str, &str> = titles
+            .iter()
+            .filter_map(|t| Some((t["article_id"].as_str()?, t["title"].as_str()?)))
+            .collect();
+
+        Ok(articles
+            .iter()
+            .map(|a| ArticleReadInfo {
+                article_id: a.article_id.clone(),
+                title: titles.get(a.article_id.as_str()).copied().unwrap_or_default().to_string(),
+                reader_count: a.reader_count,
+            })
+            .collect())
     }
 
     async fn get_recent_interactions(&self, user_id: &str, limit: i32) -> Result<Vec<InteractionInfo>> {
@@ -699,9 +913,9 @@ impl AnalyticsService {
                 a.id as article_id,
                 a.title as article_title,
                 c.created_at as timestamp
-            FROM clap c
+            FROM reaction c
             JOIN article a ON c.article_id = a.id
-            WHERE a.author_id = $user_id
+            WHERE a.author_id = $user_id AND c.reaction_type = 'clap'
             ORDER BY c.created_at DESC
             LIMIT $limit
         "#;
@@ -954,219 +1168,142 @@ impl AnalyticsService {
         }
     }
 
-    /// 获取真实的受众分析数据
+    /// 受众分析：基于去重后的 article_view，统计读者、来源、国家、设备和浏览器。
+    /// 时长按读者浏览文章的预计阅读时间估算（秒）
     async fn get_real_audience_analytics(&self, user_id: &str, start_date: &DateTime<Utc>, end_date: &DateTime<Utc>) -> Result<AudienceAnalytics> {
-        // 获取读者统计
-        let reader_stats_query = r#"
-            LET user_articles = (SELECT id FROM articles WHERE author_id = $user_id);
-            
-            LET session_data = (
-                SELECT 
-                    user_id,
-                    count() as visit_count,
-                    array::len(array::distinct(article_id)) as articles_read,
-                    sum(reading_time) as total_reading_time,
-                    first(country) as country,
-                    first(city) as city,
-                    first(device_type) as device_type,
-                    first(browser) as browser
-                FROM reading_sessions 
-                WHERE article_id IN (SELECT VALUE id FROM $user_articles)
-                AND created_at >= $start_date AND created_at <= $end_date
-                GROUP BY user_id
-            );
-            
-            LET total_readers = array::len($session_data);
-            LET returning_readers = array::len(array::filter($session_data, |s| s.visit_count > 1));
-            LET new_readers = $total_readers - $returning_readers;
-            
-            LET avg_session = math::mean(array::map($session_data, |s| s.total_reading_time));
-            
-            RETURN {
-                total_readers: $total_readers,
-                returning_readers: $returning_readers,
-                new_readers: $new_readers,
-                avg_session_duration: $avg_session OR 180.0,
-                sessions: $session_data
-            };
-        "#;
-        
-        let mut response = self.db
-            .query_with_params(reader_stats_query, &[
-                ("user_id", json!(user_id)),
-                ("start_date", json!(start_date.to_rfc3339())),
-                ("end_date", json!(end_date.to_rfc3339())),
-            ])
-            .await?;
-        
-        let data: Vec<Value> = response.take(0)?;
-        if let Some(data) = data.into_iter().next() {
-            let empty_vec = vec![];
-            let sessions = data["sessions"].as_array().unwrap_or(&empty_vec);
-            
-            // 统计地理分布
-            let mut countries = HashMap::new();
-            let mut cities = HashMap::new();
-            let mut devices = HashMap::new();
-            let mut browsers = HashMap::new();
-            
-            for session in sessions {
-                // 统计国家
-                if let Some(country) = session["country"].as_str() {
-                    *countries.entry(country.to_string()).or_insert(0) += 1;
-                }
-                
-                // 统计城市
-                if let Some(city) = session["city"].as_str() {
-                    *cities.entry(city.to_string()).or_insert(0) += 1;
-                }
-                
-                // 统计设备
-                if let Some(device) = session["device_type"].as_str() {
-                    *devices.entry(device.to_string()).or_insert(0) += 1;
-                }
-                
-                // 统计浏览器
-                if let Some(browser) = session["browser"].as_str() {
-                    *browsers.entry(browser.to_string()).or_insert(0) += 1;
+        let author_id = UserId::new(user_id);
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT viewer_hash, referrer, country, device_type, browser, count() AS views
+                    FROM article_view
+                    WHERE author_id = $author_id AND created_at >= $start_date AND created_at <= $end_date
+                    GROUP BY viewer_hash, referrer, country, device_type, browser;
+                SELECT article_id, device_type, time::hour(created_at) AS hour, count() AS views
+                    FROM article_view
+                    WHERE author_id = $author_id AND created_at >= $start_date AND created_at <= $end_date
+                    GROUP BY article_id, device_type, hour;
+                SELECT meta::id(id) AS id, reading_time FROM article WHERE author_id = $author_id;
+            "#,
+            json!({
+                "author_id": author_id.as_str(),
+                "start_date": start_date,
+                "end_date": end_date,
+            }),
+        ).await?;
+        let rows: Vec<AudienceViewRow> = response.take(0)?;
+        let hourly: Vec<HourlyViewRow> = response.take(1)?;
+        let reading_times: Vec<ReadingTimeRow> = response.take(2)?;
+
+        // 同一读者可能通过不同来源、设备访问，按读者去重后再分组计数
+        let mut views_per_reader: HashMap<&str, i64> = HashMap::new();
+        let mut referrers: HashMap<&str, (i64, HashSet<&str>)> = HashMap::new();
+        let mut countries: HashMap<&str, HashSet<&str>> = HashMap::new();
+        let mut devices: HashMap<&str, HashSet<&str>> = HashMap::new();
+        let mut browsers: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for row in &rows {
+            let reader = row.viewer_hash.as_str();
+            *views_per_reader.entry(reader).or_insert(0) += row.views;
+
+            let referrer = referrers.entry(row.referrer.as_str()).or_default();
+            referrer.0 += row.views;
+            referrer.1.insert(reader);
+
+            countries.entry(row.country.as_deref().unwrap_or("unknown")).or_default().insert(reader);
+            devices.entry(row.device_type.as_deref().unwrap_or("unknown")).or_default().insert(reader);
+            browsers.entry(row.browser.as_deref().unwrap_or("unknown")).or_default().insert(reader);
+        }
+
+        let total_readers = views_per_reader.len() as i64;
+        let is_returning = |reader: &&str| views_per_reader.get(reader).map_or(false, |views| *views > 1);
+        let returning_readers = views_per_reader.values().filter(|views| **views > 1).count() as i64;
+
+        // 预计阅读时间（秒），按设备和小时汇总
+        let reading_seconds: HashMap<&str, f64> = reading_times
+            .iter()
+            .map(|r| (r.id.as_str(), r.reading_time as f64 * 60.0))
+            .collect();
+        let mut device_time: HashMap<&str, (i64, f64)> = HashMap::new();
+        let mut hour_time: BTreeMap<u8, (i64, f64)> = BTreeMap::new();
+        for row in &hourly {
+            let seconds = reading_seconds.get(row.article_id.as_str()).copied().unwrap_or(0.0) * row.views as f64;
+            let device = device_time.entry(row.device_type.as_deref().unwrap_or("unknown")).or_default();
+            device.0 += row.views;
+            device.1 += seconds;
+            let hour = hour_time.entry(row.hour).or_default();
+            hour.0 += row.views;
+            hour.1 += seconds;
+        }
+        let average = |(views, seconds): (i64, f64)| if views > 0 { seconds / views as f64 } else { 0.0 };
+        let total_time = device_time.values().fold((0, 0.0), |acc, t| (acc.0 + t.0, acc.1 + t.1));
+
+        let mut top_referrers: Vec<ReferrerData> = referrers
+            .into_iter()
+            .map(|(source, (visits, readers))| {
+                let returning = readers.iter().filter(|r| is_returning(r)).count();
+                ReferrerData {
+                    source: source.to_string(),
+                    visits,
+                    unique_visitors: readers.len() as i64,
+                    // 该来源的读者中再次阅读的比例
+                    conversion_rate: share(returning as i64, readers.len() as i64) as f64,
                 }
-            }
-            
-            let total_sessions = sessions.len() as f64;
-            
-            Ok(AudienceAnalytics {
-                total_readers: data["total_readers"].as_i64().unwrap_or(0),
-                returning_readers: data["returning_readers"].as_i64().unwrap_or(0),
-                new_readers: data["new_readers"].as_i64().unwrap_or(0),
-                avg_session_duration: data["avg_session_duration"].as_f64().unwrap_or(180.0),
-                top_referrers: self.get_top_referrers(user_id, start_date, end_date).await.unwrap_or_default(),
-                geographic_data: countries.into_iter()
-                    .map(|(country, count)| GeographicData {
-                        country: country.clone(),
-                        city: None,
-                        readers: count as i64,
-                        percentage: (count as f64 / total_sessions * 100.0) as f32,
-                    })
-                    .collect(),
-                device_data: devices.into_iter()
-                    .map(|(device, count)| DeviceData {
-                        device_type: device,
-                        readers: count as i64,
-                        percentage: (count as f64 / total_sessions * 100.0) as f32,
-                        avg_session_duration: 180.0, // 可以进一步细化
-                    })
-                    .collect(),
-                reading_patterns: self.get_reading_patterns(user_id, start_date, end_date).await.unwrap_or_default(),
             })
-        } else {
-            // 如果没有会话数据，返回估算的数据
-            let estimated_readers = self.get_estimated_readers_count(user_id, start_date, end_date).await?;
-            Ok(AudienceAnalytics {
-                total_readers: estimated_readers,
-                returning_readers: (estimated_readers as f64 * 0.35) as i64,
-                new_readers: (estimated_readers as f64 * 0.65) as i64,
-                avg_session_duration: 180.0,
-                top_referrers: vec![],
-                geographic_data: vec![],
-                device_data: vec![],
-                reading_patterns: vec![],
+            .collect();
+        top_referrers.sort_by(|a, b| b.visits.cmp(&a.visits).then_with(|| a.source.cmp(&b.source)));
+        top_referrers.truncate(10);
+
+        let mut geographic_data: Vec<GeographicData> = countries
+            .into_iter()
+            .map(|(country, readers)| GeographicData {
+                country: country.to_string(),
+                city: None,
+                readers: readers.len() as i64,
+                percentage: share(readers.len() as i64, total_readers),
             })
-        }
-    }
-
-    /// 获取主要流量来源
-    async fn get_top_referrers(&self, user_id: &str, start_date: &DateTime<Utc>, end_date: &DateTime<Utc>) -> Result<Vec<ReferrerData>> {
-        let query = r#"
-            SELECT 
-                referrer,
-                count() as visits,
-                array::len(array::distinct(user_id)) as unique_visitors
-            FROM page_visits 
-            WHERE article_id IN (SELECT id FROM articles WHERE author_id = $user_id)
-            AND created_at >= $start_date AND created_at <= $end_date
-            AND referrer IS NOT NULL
-            GROUP BY referrer
-            ORDER BY visits DESC
-            LIMIT 10
-        "#;
-        
-        let mut response = self.db
-            .query_with_params(query, &[
-                ("user_id", json!(user_id)),
-                ("start_date", json!(start_date.to_rfc3339())),
-                ("end_date", json!(end_date.to_rfc3339())),
-            ])
-            .await?;
-
-        let data: Vec<Value> = response.take(0)?;
-        
-        Ok(data.into_iter()
-            .map(|item| ReferrerData {
-                source: item["referrer"].as_str().unwrap_or("Unknown").to_string(),
-                visits: item["visits"].as_i64().unwrap_or(0),
-                unique_visitors: item["unique_visitors"].as_i64().unwrap_or(0),
-                conversion_rate: 0.0, // 可以根据需要实现转化率计算
+            .collect();
+        geographic_data.sort_by(|a, b| b.readers.cmp(&a.readers).then_with(|| a.country.cmp(&b.country)));
+
+        let mut device_data: Vec<DeviceData> = devices
+            .into_iter()
+            .map(|(device, readers)| DeviceData {
+                device_type: device.to_string(),
+                readers: readers.len() as i64,
+                percentage: share(readers.len() as i64, total_readers),
+                avg_session_duration: device_time.get(device).copied().map(average).unwrap_or(0.0),
             })
-            .collect())
-    }
-
-    /// 获取阅读模式
-    async fn get_reading_patterns(&self, user_id: &str, start_date: &DateTime<Utc>, end_date: &DateTime<Utc>) -> Result<Vec<ReadingPattern>> {
-        let query = r#"
-            SELECT 
-                time::hour(created_at) as hour,
-                count() as readings,
-                math::mean(reading_time) as avg_reading_time
-            FROM reading_sessions 
-            WHERE article_id IN (SELECT id FROM articles WHERE author_id = $user_id)
-            AND created_at >= $start_date AND created_at <= $end_date
-            GROUP BY hour
-            ORDER BY hour
-        "#;
-        
-        let mut response = self.db
-            .query_with_params(query, &[
-                ("user_id", json!(user_id)),
-                ("start_date", json!(start_date.to_rfc3339())),
-                ("end_date", json!(end_date.to_rfc3339())),
-            ])
-            .await?;
-        
-        let data: Vec<Value> = response.take(0)?;
-        
-        Ok(data.into_iter()
-            .map(|item| ReadingPattern {
-                hour: item["hour"].as_i64().unwrap_or(0) as u8,
-                readings: item["readings"].as_i64().unwrap_or(0),
-                avg_reading_time: item["avg_reading_time"].as_f64().unwrap_or(0.0),
+            .collect();
+        device_data.sort_by(|a, b| b.readers.cmp(&a.readers).then_with(|| a.device_type.cmp(&b.device_type)));
+
+        let mut browser_data: Vec<BrowserData> = browsers
+            .into_iter()
+            .map(|(browser, readers)| BrowserData {
+                browser: browser.to_string(),
+                readers: readers.len() as i64,
+                percentage: share(readers.len() as i64, total_readers),
             })
-            .collect())
-    }
-
-    /// 估算读者数量（当没有会话跟踪时）
-    async fn get_estimated_readers_count(&self, user_id: &str, start_date: &DateTime<Utc>, end_date: &DateTime<Utc>) -> Result<i64> {
-        let query = r#"
-            SELECT sum(view_count) as total_views 
-            FROM articles 
-            WHERE author_id = $user_id
-            AND updated_at >= $start_date AND updated_at <= $end_date
-        "#;
-        
-        let mut response = self.db
-            .query_with_params(query, &[
-                ("user_id", json!(user_id)),
-                ("start_date", json!(start_date.to_rfc3339())),
-                ("end_date", json!(end_date.to_rfc3339())),
-            ])
-            .await?;
-        
-        let data: Vec<Value> = response.take(0)?;
-        let total_views = data.into_iter().next()
-            .and_then(|data| data["total_views"].as_i64())
-            .unwrap_or(0);
-        
-        // 估算独立读者数 = 总浏览量 * 0.7 (假设每个读者平均浏览1.43篇文章)
-        Ok((total_views as f64 * 0.7) as i64)
+            .collect();
+        browser_data.sort_by(|a, b| b.readers.cmp(&a.readers).then_with(|| a.browser.cmp(&b.browser)));
+
+        let reading_patterns = hour_time
+            .into_iter()
+            .map(|(hour, time)| ReadingPattern {
+                hour,
+                readings: time.0,
+                avg_reading_time: average(time),
+            })
+            .collect();
+
+        Ok(AudienceAnalytics {
+            total_readers,
+            returning_readers,
+            new_readers: total_readers - returning_readers,
+            avg_session_duration: average(total_time),
+            top_referrers,
+            geographic_data,
+            device_data,
+            browser_data,
+            reading_patterns,
+        })
     }
 
     /// 计算标签的增长率
@@ -1202,4 +1339,21 @@ impl AnalyticsService {
         
         Ok(self.calculate_growth_rate(current_views, previous_views).await)
     }
-}
\ No newline at end of file
+}
+
+/// 占比（百分数）
+fn share(part: i64, total: i64) -> f32 {
+    if total > 0 {
+        (part as f64 / total as f64 * 100.0) as f32
+    } else {
+        0.0
+    }
+}
+
+/// 将 Referer 归一化为来源域名，没有来源时记为 direct
+pub(crate) fn referrer_source(referrer: Option<&str>) -> String {
+    referrer
+        .and_then(|r| url::Url::parse(r).ok())
+        .and_then(|u| u.host_str().map(|h| h.trim_start_matches("www.").to_string()))
+        .unwrap_or_else(|| "direct".to_string())
+}
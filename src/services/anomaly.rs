@@ -0,0 +1,368 @@
+use crate::{
+    error::{AppError, Result},
+    models::{analytics::*, article::Article, notification::*},
+    services::{Database, NotificationService},
+    utils::anomaly::{detect_traffic_shift, is_viral, series_stats, AnomalyThresholds, TrafficShift},
+};
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// 基线长度（小时）
+const BASELINE_HOURS: i64 = 24 * 7;
+/// 同一类异常的提醒冷却时间，持续的激增不会每小时重复提醒
+const ALERT_COOLDOWN_HOURS: i64 = 6;
+const CONTRIBUTING_ARTICLE_LIMIT: usize = 5;
+const REFERRER_LIMIT: usize = 5;
+
+#[derive(Clone)]
+pub struct AnomalyService {
+    db: Arc<Database>,
+    notification_service: NotificationService,
+    thresholds: AnomalyThresholds,
+}
+
+impl AnomalyService {
+    pub async fn new(db: Arc<Database>, notification_service: NotificationService) -> Result<Self> {
+        Ok(Self {
+            db,
+            notification_service,
+            thresholds: AnomalyThresholds::default(),
+        })
+    }
+
+    /// 检查所有近期有流量的出版物，返回新发现的异常数量
+    pub async fn scan_publications(&self) -> Result<usize> {
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT publication_id FROM article_view_event
+                WHERE publication_id != NONE AND created_at >= <datetime> $since
+                GROUP BY publication_id
+            "#,
+            json!({ "since": Utc::now() - Duration::hours(BASELINE_HOURS) }),
+        ).await?;
+        let rows: Vec<Value> = response.take(0)?;
+
+        let mut detected = 0;
+        for publication_id in rows.iter().filter_map(|r| r.get("publication_id").and_then(|v| v.as_str())) {
+            match self.detect_for_publication(publication_id).await {
+                Ok(anomalies) => detected += anomalies.len(),
+                Err(e) => warn!("Failed to detect anomalies for publication {}: {}", publication_id, e),
+            }
+        }
+
+        if detected > 0 {
+            info!("Detected {} publication traffic anomalies", detected);
+        }
+        Ok(detected)
+    }
+
+    /// 以最近一个完整小时为窗口，与前 7 天的逐小时流量比较
+    pub async fn detect_for_publication(&self, publication_id: &str) -> Result<Vec<PublicationAnomaly>> {
+        let window_end = Utc::now().duration_trunc(Duration::hours(1))
+            .map_err(|e| AppError::internal(&e.to_string()))?;
+        let window_start = window_end - Duration::hours(1);
+        let baseline_start = window_start - Duration::hours(BASELINE_HOURS);
+
+        let hourly = self.hourly_views(publication_id, baseline_start, window_end).await?;
+        let baseline: Vec<f64> = (0..BASELINE_HOURS)
+            .map(|h| *hourly.get(&(baseline_start + Duration::hours(h))).unwrap_or(&0) as f64)
+            .collect();
+        let observed = *hourly.get(&window_start).unwrap_or(&0);
+        let expected = series_stats(&baseline).mean;
+
+        let articles = self.article_views(publication_id, baseline_start, window_start, window_end).await?;
+        let referrers = self.top_referrers(publication_id, window_start, window_end).await?;
+
+        let mut candidates = Vec::new();
+        if let Some((shift, z)) = detect_traffic_shift(&baseline, observed as f64, &self.thresholds) {
+            let kind = match shift {
+                TrafficShift::Spike => AnomalyKind::TrafficSpike,
+                TrafficShift::Drop => AnomalyKind::TrafficDrop,
+            };
+            candidates.push((kind, None, observed, expected, z));
+        }
+        for article in &articles {
+            if is_viral(article.baseline_views, article.views as f64, &self.thresholds) {
+                let z = (article.views as f64 - article.baseline_views) / article.baseline_views.sqrt().max(1.0);
+                candidates.push((AnomalyKind::ViralArticle, Some(article.article_id.clone()), article.views, article.baseline_views, z));
+            }
+        }
+
+        let mut anomalies = Vec::new();
+        for (kind, article_id, observed_views, expected_views, z_score) in candidates {
+            if self.recently_alerted(publication_id, kind, article_id.as_deref()).await? {
+                debug!("Skipping {} alert for {} during cooldown", kind.as_str(), publication_id);
+                continue;
+            }
+
+            // 爆款提醒只附带该文章，整体流量异常附带贡献最大的文章
+            let contributing_articles: Vec<AnomalyArticle> = match &article_id {
+                Some(id) => articles.iter().filter(|a| &a.article_id == id).cloned().collect(),
+                None => articles.iter().take(CONTRIBUTING_ARTICLE_LIMIT).cloned().collect(),
+            };
+
+            let mut response = self.db.query_with_params(
+                r#"
+                    CREATE publication_anomaly CONTENT {
+                        publication_id: $publication_id,
+                        kind: $kind,
+                        article_id: $article_id ?? NONE,
+                        window_start: <datetime> $window_start,
+                        window_end: <datetime> $window_end,
+                        observed_views: $observed_views,
+                        expected_views: $expected_views,
+                        z_score: $z_score,
+                        contributing_articles: $contributing_articles,
+                        top_referrers: $top_referrers,
+                        created_at: time::now()
+                    }
+                "#,
+                json!({
+                    "publication_id": publication_id,
+                    "kind": kind,
+                    "article_id": article_id,
+                    "window_start": window_start,
+                    "window_end": window_end,
+                    "observed_views": observed_views,
+                    "expected_views": expected_views,
+                    "z_score": z_score,
+                    "contributing_articles": contributing_articles,
+                    "top_referrers": referrers,
+                }),
+            ).await?;
+            let created: Vec<PublicationAnomaly> = response.take(0)?;
+
+            if let Some(anomaly) = created.into_iter().next() {
+                self.notify_editors(&anomaly).await?;
+                anomalies.push(anomaly);
+            }
+        }
+
+        Ok(anomalies)
+    }
+
+    /// 获取出版物最近的异常记录
+    pub async fn get_publication_anomalies(&self, publication_id: &str, limit: usize) -> Result<Vec<PublicationAnomaly>> {
+        let mut response = self.db.query_with_params(
+            "SELECT * FROM publication_anomaly WHERE publication_id = $publication_id ORDER BY created_at DESC LIMIT $limit",
+            json!({ "publication_id": publication_id, "limit": limit }),
+        ).await?;
+        Ok(response.take(0)?)
+    }
+
+    async fn hourly_views(
+        &self,
+        publication_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<HashMap<DateTime<Utc>, i64>> {
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT time::floor(created_at, 1h) AS bucket, count() AS views
+                FROM article_view_event
+                WHERE publication_id = $publication_id
+                AND created_at >= <datetime> $start AND created_at < <datetime> $end
+                GROUP BY bucket
+            "#,
+            json!({ "publication_id": publication_id, "start": start, "end": end }),
+        ).await?;
+        let rows: Vec<Value> = response.take(0)?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|r| {
+                let bucket = r.get("bucket")?.as_str()?.parse::<DateTime<Utc>>().ok()?;
+                Some((bucket, r.get("views")?.as_i64()?))
+            })
+            .collect())
+    }
+
+    /// 窗口内各文章的浏览量及其基线，按窗口浏览量降序
+    async fn article_views(
+        &self,
+        publication_id: &str,
+        baseline_start: DateTime<Utc>,
+        window_start: DateTime<Utc>,
+        window_end: DateTime<Utc>,
+    ) -> Result<Vec<AnomalyArticle>> {
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT article_id, count() AS views FROM article_view_event
+                WHERE publication_id = $publication_id
+                AND created_at >= <datetime> $window_start AND created_at < <datetime> $window_end
+                GROUP BY article_id;
+                SELECT article_id, count() AS views FROM article_view_event
+                WHERE publication_id = $publication_id
+                AND created_at >= <datetime> $baseline_start AND created_at < <datetime> $window_start
+                GROUP BY article_id;
+            "#,
+            json!({
+                "publication_id": publication_id,
+                "baseline_start": baseline_start,
+                "window_start": window_start,
+                "window_end": window_end,
+            }),
+        ).await?;
+        let current: Vec<Value> = response.take(0)?;
+        let baseline: Vec<Value> = response.take(1)?;
+
+        let baseline_totals: HashMap<&str, i64> = baseline
+            .iter()
+            .filter_map(|r| Some((r.get("article_id")?.as_str()?, r.get("views")?.as_i64()?)))
+            .collect();
+
+        let mut articles = Vec::new();
+        for row in &current {
+            let (article_id, views) = match (
+                row.get("article_id").and_then(|v| v.as_str()),
+                row.get("views").and_then(|v| v.as_i64()),
+            ) {
+                (Some(id), Some(views)) => (id, views),
+                _ => continue,
+            };
+
+            let article: Article = match self.db.get_by_id("article", article_id).await? {
+                Some(article) => article,
+                None => continue,
+            };
+
+            articles.push(AnomalyArticle {
+                article_id: article_id.to_string(),
+                title: article.title,
+                slug: article.slug,
+                views,
+                baseline_views: *baseline_totals.get(article_id).unwrap_or(&0) as f64 / BASELINE_HOURS as f64,
+            });
+        }
+
+        articles.sort_by(|a, b| b.views.cmp(&a.views));
+        Ok(articles)
+    }
+
+    async fn top_referrers(
+        &self,
+        publication_id: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<AnomalyReferrer>> {
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT referrer, count() AS visits FROM article_view_event
+                WHERE publication_id = $publication_id
+                AND created_at >= <datetime> $start AND created_at < <datetime> $end
+                GROUP BY referrer
+            "#,
+            json!({ "publication_id": publication_id, "start": start, "end": end }),
+        ).await?;
+        let rows: Vec<Value> = response.take(0)?;
+
+        let mut referrers: Vec<AnomalyReferrer> = rows
+            .iter()
+            .filter_map(|r| Some(AnomalyReferrer {
+                source: r.get("referrer")?.as_str()?.to_string(),
+                visits: r.get("visits")?.as_i64()?,
+            }))
+            .collect();
+        referrers.sort_by(|a, b| b.visits.cmp(&a.visits));
+        referrers.truncate(REFERRER_LIMIT);
+
+        Ok(referrers)
+    }
+
+    async fn recently_alerted(&self, publication_id: &str, kind: AnomalyKind, article_id: Option<&str>) -> Result<bool> {
+        let article_condition = if article_id.is_some() { "article_id = $article_id" } else { "article_id IS NONE" };
+        let query = format!(
+            r#"
+                SELECT count() AS total FROM publication_anomaly
+                WHERE publication_id = $publication_id AND kind = $kind AND {}
+                AND created_at >= <datetime> $since
+                GROUP ALL
+            "#,
+            article_condition
+        );
+        let mut response = self.db.query_with_params(
+            &query,
+            json!({
+                "publication_id": publication_id,
+                "kind": kind,
+                "article_id": article_id,
+                "since": Utc::now() - Duration::hours(ALERT_COOLDOWN_HOURS),
+            }),
+        ).await?;
+        let rows: Vec<Value> = response.take(0)?;
+
+        Ok(rows.first().and_then(|r| r.get("total")).and_then(|v| v.as_i64()).unwrap_or(0) > 0)
+    }
+
+    /// 通知出版物的所有者和编辑
+    async fn notify_editors(&self, anomaly: &PublicationAnomaly) -> Result<()> {
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT user_id FROM publication_member
+                WHERE publication_id = $publication_id AND is_active = true AND role INSIDE ["owner", "editor"]
+            "#,
+            json!({ "publication_id": anomaly.publication_id }),
+        ).await?;
+        let rows: Vec<Value> = response.take(0)?;
+
+        let (title, message) = describe(anomaly);
+        for user_id in rows.iter().filter_map(|r| r.get("user_id").and_then(|v| v.as_str())) {
+            let notification = CreateNotificationRequest {
+                recipient_id: user_id.to_string(),
+                notification_type: NotificationType::PublicationAnomaly,
+                title: title.clone(),
+                message: message.clone(),
+                data: json!({
+                    "publication_id": anomaly.publication_id,
+                    "anomaly_id": anomaly.id,
+                    "kind": anomaly.kind,
+                    "article_id": anomaly.article_id,
+                    "contributing_articles": anomaly.contributing_articles,
+                    "top_referrers": anomaly.top_referrers,
+                }),
+            };
+
+            if let Err(e) = self.notification_service.create_notification(notification).await {
+                warn!("Failed to notify {} about anomaly {}: {}", user_id, anomaly.id, e);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// 生成通知标题和正文，正文包含贡献文章和主要来源
+fn describe(anomaly: &PublicationAnomaly) -> (String, String) {
+    let title = match anomaly.kind {
+        AnomalyKind::TrafficSpike => "Traffic spike on your publication".to_string(),
+        AnomalyKind::TrafficDrop => "Traffic drop on your publication".to_string(),
+        AnomalyKind::ViralArticle => match anomaly.contributing_articles.first() {
+            Some(article) => format!("\"{}\" is taking off", article.title),
+            None => "An article is taking off".to_string(),
+        },
+    };
+
+    let mut message = format!(
+        "{} views in the last hour (usually about {:.0}).",
+        anomaly.observed_views, anomaly.expected_views
+    );
+    if !anomaly.contributing_articles.is_empty() {
+        let articles: Vec<String> = anomaly.contributing_articles
+            .iter()
+            .map(|a| format!("{} ({})", a.title, a.views))
+            .collect();
+        message.push_str(&format!(" Top articles: {}.", articles.join(", ")));
+    }
+    if !anomaly.top_referrers.is_empty() {
+        let referrers: Vec<String> = anomaly.top_referrers
+            .iter()
+            .map(|r| format!("{} ({})", r.source, r.visits))
+            .collect();
+        message.push_str(&format!(" Top referrers: {}.", referrers.join(", ")));
+    }
+
+    (title, message)
+}
@@ -1,79 +1,133 @@
 use crate::{
-    error::{AppError, Result},
-    models::article::*,
-    services::Database,
-    utils::{markdown::MarkdownProcessor, slug},
+    config::Config,
+    error::{AppError, ErrorCode, Result},
+    models::{article::*, revision::*, collaborator::*, id::{bare_id, ArticleId, PublicationId, TagId}, media::MediaFile, pseudonym::Pseudonym, reaction::ReactionType, recommendation::RecommendedArticle, series::{completion_percentage, SeriesNavItem}, meter::MeterReader, payment::AccessType},
+    services::{
+        response_cache::CacheNamespace, Database, AssistService, EmbeddingService, MeterService, OEmbedService, PaymentService,
+        PluginManager, ResponseCacheService, SpeechService,
+    },
+    utils::{embed, figure, markdown::MarkdownProcessor, slug, scoring::{ClapperSignal, weighted_clap_score, normalized_reputation}},
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use tracing::{info, warn, error, debug};
 use validator::Validate;
 use std::collections::HashMap;
 use std::sync::Arc;
-use soulcore::prelude::Thing;
 use uuid::Uuid;
 
+/// `get_authors_by_user_ids` 查询的一行
+#[derive(Debug, Deserialize)]
+struct AuthorRow {
+    id: String,
+    user_id: String,
+    username: String,
+    #[serde(default)]
+    display_name: String,
+    avatar_url: Option<String>,
+    #[serde(default)]
+    is_verified: bool,
+}
+
+/// `get_tags_for_articles` 查询的一行，标签已删除时 name 和 slug 为空
+#[derive(Debug, Deserialize)]
+struct ArticleTagRow {
+    article_id: String,
+    tag_id: String,
+    name: Option<String>,
+    slug: Option<String>,
+}
+
+/// 响应缓存中的文章详情。`ArticleResponse.author_id` 不参与序列化，单独保存
+#[derive(Serialize, Deserialize)]
+struct CachedArticle {
+    author_id: String,
+    article: ArticleResponse,
+}
+
 #[derive(Clone)]
 pub struct ArticleService {
     db: Arc<Database>,
     markdown_processor: MarkdownProcessor,
+    assist_service: AssistService,
+    embedding_service: EmbeddingService,
+    plugins: PluginManager,
+    payment_service: PaymentService,
+    meter_service: MeterService,
+    oembed_service: OEmbedService,
+    speech_service: SpeechService,
+    response_cache: ResponseCacheService,
+    /// 付费文章对没有访问权限的读者保留的段落数
+    paywall_preview_paragraphs: usize,
 }
 
-fn normalize_surreal_id(id: &str) -> String {
-    fn try_from_json_str(s: &str) -> Option<String> {
-        serde_json::from_str::<serde_json::Value>(s)
-            .ok()
-            .and_then(|v| extract_id_from_json_value(&v))
-    }
+/// 摘要的最大长度（字符）
+const EXCERPT_MAX_LENGTH: usize = 300;
 
-    fn extract_id_from_json_value(value: &serde_json::Value) -> Option<String> {
-        match value {
-            serde_json::Value::String(s) => Some(s.clone()),
-            serde_json::Value::Object(map) => {
-                if let Some(serde_json::Value::String(s)) = map.get("String") {
-                    return Some(s.clone());
-                }
-                if let Some(serde_json::Value::String(s)) = map.get("id") {
-                    return Some(s.clone());
-                }
-                if let Some(serde_json::Value::Object(inner)) = map.get("id") {
-                    if let Some(serde_json::Value::String(s)) = inner.get("String") {
-                        return Some(s.clone());
-                    }
-                }
-                None
-            }
-            _ => None,
-        }
-    }
+/// 作者主页和出版物首页各自最多置顶的文章数
+const MAX_PINNED_ARTICLES: usize = 3;
 
-    let trimmed = id.trim();
-    if let Some(res) = try_from_json_str(trimmed) {
-        return res;
-    }
+/// 与 `/articles/:slug` 同级的路由，不能作为文章的 slug
+const RESERVED_SLUGS: &[&str] = &[
+    "trending", "popular", "view-token", "create", "pending-review", "collaborations",
+    "bulk", "import", "by-id", "preview",
+];
 
-    let cleaned = trimmed.replace('⟨', "").replace('⟩', "");
-    if let Some(res) = try_from_json_str(&cleaned) {
-        return res;
+/// 定时发布时间必须晚于当前时间
+fn validate_publish_at(publish_at: DateTime<Utc>) -> Result<()> {
+    if publish_at <= Utc::now() {
+        return Err(AppError::BadRequest("publish_at must be in the future".to_string()));
     }
+    Ok(())
+}
 
-    if let Some((_, rest)) = cleaned.split_once(':') {
-        if let Some(res) = try_from_json_str(rest) {
-            return res;
-        }
-        return rest.trim_matches('"').to_string();
+/// 被平台管理员下架的文章不能由作者重新发布、定时发布或提交审核
+fn ensure_not_taken_down(article: &Article) -> Result<()> {
+    if article.taken_down_at.is_some() {
+        return Err(AppError::forbidden("This article has been taken down by a moderator"));
     }
+    Ok(())
+}
+
+fn is_generated(metadata: &Value, key: &str) -> bool {
+    metadata.get(key).and_then(|v| v.as_bool()).unwrap_or(false)
+}
 
-    cleaned.trim_matches('"').to_string()
+fn mark_generated(metadata: &mut Value, key: &str, generated: bool) {
+    if !metadata.is_object() {
+        *metadata = json!({});
+    }
+    metadata[key] = json!(generated);
 }
 
 impl ArticleService {
-    pub async fn new(db: Arc<Database>) -> Result<Self> {
+    pub async fn new(
+        db: Arc<Database>,
+        config: &Config,
+        assist_service: AssistService,
+        embedding_service: EmbeddingService,
+        plugins: PluginManager,
+        payment_service: PaymentService,
+        meter_service: MeterService,
+        oembed_service: OEmbedService,
+        speech_service: SpeechService,
+        response_cache: ResponseCacheService,
+    ) -> Result<Self> {
         let markdown_processor = MarkdownProcessor::new();
 
         Ok(Self {
             db,
             markdown_processor,
+            assist_service,
+            embedding_service,
+            plugins,
+            payment_service,
+            meter_service,
+            oembed_service,
+            speech_service,
+            response_cache,
+            paywall_preview_paragraphs: config.paywall_preview_paragraphs,
         })
     }
 
@@ -84,6 +138,17 @@ impl ArticleService {
         // 验证输入
         request.validate()
             .map_err(|e| AppError::ValidatorError(e))?;
+        if let Some(publish_at) = request.publish_at {
+            validate_publish_at(publish_at)?;
+        }
+
+        let status = if request.publish_at.is_some() {
+            ArticleStatus::Scheduled
+        } else if request.save_as_draft.unwrap_or(true) {
+            ArticleStatus::Draft
+        } else {
+            ArticleStatus::Published
+        };
 
         // 创建文章对象
         let mut article = Article {
@@ -96,16 +161,21 @@ impl ArticleService {
             excerpt: request.excerpt,
             cover_image_url: request.cover_image_url,
             author_id: author_id.to_string(),
+            pseudonym_id: None,
             publication_id: request.publication_id,
             series_id: request.series_id,
             series_order: request.series_order,
-            status: if request.save_as_draft.unwrap_or(true) { ArticleStatus::Draft } else { ArticleStatus::Published },
+            status,
             is_paid_content: request.is_paid_content.unwrap_or(false),
             is_featured: false,
+            pinned_at: None,
+            publication_pinned_at: None,
             reading_time: 0, // 稍后计算
             word_count: 0, // 稍后计算
             view_count: 0,
             clap_count: 0,
+            weighted_clap_score: 0.0,
+            reaction_counts: Default::default(),
             comment_count: 0,
             bookmark_count: 0,
             share_count: 0,
@@ -116,24 +186,37 @@ impl ArticleService {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             published_at: None,
+            scheduled_at: request.publish_at,
             last_edited_at: None,
             is_deleted: false,
             deleted_at: None,
+            taken_down_at: None,
         };
 
-        // 生成唯一的 slug
-        article.slug = self.generate_unique_slug(&article.title).await?;
+        // 使用作者指定的 slug，否则根据标题生成唯一的 slug
+        article.slug = match request.slug.as_deref() {
+            Some(custom) => {
+                mark_generated(&mut article.metadata, "slug_custom", true);
+                self.check_slug(custom, None).await?
+            }
+            None => self.generate_unique_slug(&article.title, None).await?,
+        };
 
         // 处理 Markdown 内容
-        article.content_html = self.markdown_processor.to_html(&article.content);
+        article.content_html = self.render_content_html(&article.content).await?;
         
         // 计算阅读时间和字数
         article.reading_time = self.markdown_processor.estimate_reading_time(&article.content);
         article.word_count = self.markdown_processor.count_words(&article.content) as i32;
         
-        // 如果没有提供摘要，自动生成
+        // 如果没有提供摘要或 SEO 描述，自动生成并标记，便于内容大改时重新生成
         if article.excerpt.is_none() {
-            article.excerpt = Some(self.markdown_processor.generate_excerpt(&article.content, 300));
+            article.excerpt = Some(self.generate_excerpt(&article.content).await);
+            mark_generated(&mut article.metadata, "excerpt_generated", true);
+        }
+        if article.seo_description.is_none() {
+            article.seo_description = Some(self.markdown_processor.generate_seo_description(&article.content));
+            mark_generated(&mut article.metadata, "seo_description_generated", true);
         }
 
         // 如果没有封面图，尝试从内容中提取
@@ -198,15 +281,18 @@ impl ArticleService {
         if article.status == ArticleStatus::Published {
             fields.push("published_at: time::now()".to_string());
         }
+        if article.scheduled_at.is_some() {
+            fields.push("scheduled_at: $scheduled_at".to_string());
+        }
 
         // 使用具体的记录 ID 创建
         let query = format!(
-            "CREATE article:`{}` CONTENT {{ {} }} RETURN *",
-            article.id,
+            "CREATE type::thing('article', $id) CONTENT {{ {} }} RETURN *",
             fields.join(", ")
         );
         
         let params = json!({
+            "id": ArticleId::new(&article.id).as_str(),
             "title": article.title,
             "subtitle": article.subtitle,
             "slug": article.slug,
@@ -226,7 +312,8 @@ impl ArticleService {
             "seo_title": article.seo_title,
             "seo_description": article.seo_description,
             "seo_keywords": article.seo_keywords,
-            "metadata": article.metadata
+            "metadata": article.metadata,
+            "scheduled_at": article.scheduled_at
         });
         
         let mut response = self.db.query_with_params(&query, params).await?;
@@ -239,12 +326,32 @@ impl ArticleService {
             self.attach_tags_to_article(&created_article.id, tags).await?;
         }
 
+        self.record_revision(&created_article, author_id, false, None).await?;
+
+        if created_article.status == ArticleStatus::Published {
+            self.plugins.article_published(&created_article);
+        }
+        // slug 此前可能被缓存为不存在
+        self.invalidate_cached(&created_article).await;
+
         info!("Created article: {} by user: {}", created_article.id, author_id);
         Ok(created_article)
     }
 
     /// 更新文章
     pub async fn update_article(&self, article_id: &str, author_id: &str, request: UpdateArticleRequest) -> Result<Article> {
+        self.save_article(article_id, author_id, request, false, None).await
+    }
+
+    /// 更新文章并记录修订版本
+    async fn save_article(
+        &self,
+        article_id: &str,
+        author_id: &str,
+        request: UpdateArticleRequest,
+        is_autosave: bool,
+        change_summary: Option<String>,
+    ) -> Result<Article> {
         debug!("Updating article: {} by user: {}", article_id, author_id);
 
         // 验证输入
@@ -255,25 +362,49 @@ impl ArticleService {
         let mut article = self.get_article_by_id(article_id).await?
             .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
 
-        // 检查权限
-        if article.author_id != author_id {
-            return Err(AppError::Authorization("Only article author can update this article".to_string()));
+        // 检查权限：作者或已接受邀请的编辑者
+        self.ensure_can_edit(&article, author_id).await?;
+        let is_author = article.author_id == author_id;
+        if !is_author {
+            // 发布状态、出版物和付费设置只能由作者修改
+            let changes_publishing = request.status.is_some()
+                || request.slug.is_some()
+                || request.publish_at.is_some()
+                || request.publication_id.is_some()
+                || request.is_paid_content.is_some();
+            if changes_publishing {
+                return Err(AppError::Authorization("Only article author can change publishing settings".to_string()));
+            }
         }
 
+        // 修改前的 slug、出版物和标签对应的缓存也要清除
+        let mut stale_entries = self.cached_entries_for(&article).await;
+
         // 更新字段
         let mut content_updated = false;
-        
+        let mut newly_published = false;
+        let previous_slug = article.slug.clone();
+
+        if let Some(slug) = request.slug {
+            article.slug = self.check_slug(&slug, Some(&article.id)).await?;
+            mark_generated(&mut article.metadata, "slug_custom", true);
+        }
+
         if let Some(title) = request.title {
             if title != article.title {
                 article.title = title;
-                // 生成新的 slug
-                article.slug = self.generate_unique_slug(&article.title).await?;
+                // 作者自定义过 slug 时保留，否则按新标题生成
+                if !is_generated(&article.metadata, "slug_custom") {
+                    article.slug = self.generate_unique_slug(&article.title, Some(&article.id)).await?;
+                }
             }
         }
 
+        let mut regenerate_summaries = false;
         if let Some(content) = request.content {
+            regenerate_summaries = self.markdown_processor.is_significant_change(&article.content, &content);
             article.content = content;
-            article.content_html = self.markdown_processor.to_html(&article.content);
+            article.content_html = self.render_content_html(&article.content).await?;
             article.reading_time = self.markdown_processor.estimate_reading_time(&article.content);
             article.word_count = self.markdown_processor.count_words(&article.content) as i32;
             content_updated = true;
@@ -285,6 +416,7 @@ impl ArticleService {
 
         if let Some(excerpt) = request.excerpt {
             article.excerpt = Some(excerpt);
+            mark_generated(&mut article.metadata, "excerpt_generated", false);
         }
 
         if let Some(cover_image_url) = request.cover_image_url {
@@ -303,12 +435,32 @@ impl ArticleService {
             article.series_order = Some(series_order);
         }
 
-        if let Some(status) = request.status {
+        if let Some(publish_at) = request.publish_at {
+            validate_publish_at(publish_at)?;
+            ensure_not_taken_down(&article)?;
+            if article.status == ArticleStatus::Published {
+                return Err(AppError::coded(ErrorCode::ArticleAlreadyPublished, "Article is already published"));
+            }
+            if request.status.as_ref().map_or(false, |s| *s != ArticleStatus::Scheduled) {
+                return Err(AppError::BadRequest("publish_at can only be combined with the scheduled status".to_string()));
+            }
+            article.status = ArticleStatus::Scheduled;
+            article.scheduled_at = Some(publish_at);
+        } else if let Some(status) = request.status {
+            if status == ArticleStatus::Scheduled {
+                return Err(AppError::BadRequest("publish_at is required to schedule an article".to_string()));
+            }
+            if matches!(status, ArticleStatus::Published | ArticleStatus::Unlisted | ArticleStatus::PendingReview) {
+                ensure_not_taken_down(&article)?;
+            }
             if article.status != ArticleStatus::Published && status == ArticleStatus::Published {
                 // 首次发布
                 article.published_at = Some(Utc::now());
+                newly_published = true;
             }
             article.status = status;
+            // 改为其他状态即取消定时发布
+            article.scheduled_at = None;
         }
 
         if let Some(is_paid_content) = request.is_paid_content {
@@ -321,6 +473,7 @@ impl ArticleService {
         
         if let Some(seo_description) = request.seo_description {
             article.seo_description = Some(seo_description);
+            mark_generated(&mut article.metadata, "seo_description_generated", false);
         }
         
         if let Some(seo_keywords) = request.seo_keywords {
@@ -328,7 +481,34 @@ impl ArticleService {
         }
 
         if let Some(metadata) = request.metadata {
+            // 保留自动生成标记，客户端提交的 metadata 不应影响摘要的再生成
+            let excerpt_generated = is_generated(&article.metadata, "excerpt_generated");
+            let seo_description_generated = is_generated(&article.metadata, "seo_description_generated");
+            let slug_custom = is_generated(&article.metadata, "slug_custom");
+            // 创建时选择的模板同样由服务端维护
+            let template_id = article.metadata.get("template_id").cloned();
             article.metadata = metadata;
+            mark_generated(&mut article.metadata, "excerpt_generated", excerpt_generated);
+            mark_generated(&mut article.metadata, "seo_description_generated", seo_description_generated);
+            mark_generated(&mut article.metadata, "slug_custom", slug_custom);
+            match template_id {
+                Some(template_id) => article.metadata["template_id"] = template_id,
+                None => {
+                    if let Some(metadata) = article.metadata.as_object_mut() {
+                        metadata.remove("template_id");
+                    }
+                }
+            }
+        }
+
+        // 内容大幅修改时，重新生成自动摘要（作者手写的不会被覆盖）
+        if regenerate_summaries {
+            if is_generated(&article.metadata, "excerpt_generated") {
+                article.excerpt = Some(self.generate_excerpt(&article.content).await);
+            }
+            if is_generated(&article.metadata, "seo_description_generated") {
+                article.seo_description = Some(self.markdown_processor.generate_seo_description(&article.content));
+            }
         }
 
         // 更新时间戳
@@ -338,11 +518,7 @@ impl ArticleService {
         }
 
         // 更新文章
-        let thing = Thing {
-            tb: "article".to_string(),
-            id: surrealdb::sql::Id::String(article_id.to_string()),
-        };
-        let updated_article = self.db.update(thing, article).await?
+        let updated_article = self.db.update(ArticleId::new(article_id).to_thing(), article).await?
             .ok_or_else(|| AppError::NotFound("Failed to update article".to_string()))?;
 
         // 更新标签（如果提供）
@@ -350,10 +526,278 @@ impl ArticleService {
             self.update_article_tags(&updated_article.id, &tags).await?;
         }
 
+        // 发布过的文章的旧链接可能已经被分享或收录，保留跳转
+        if updated_article.slug != previous_slug && updated_article.published_at.is_some() {
+            self.record_slug_change(&updated_article.id, &previous_slug, &updated_article.slug).await?;
+        }
+
+        self.record_revision(&updated_article, author_id, is_autosave, change_summary).await?;
+
+        stale_entries.extend(self.cached_entries_for(&updated_article).await);
+        self.response_cache.invalidate_all(&stale_entries).await;
+
+        if newly_published {
+            self.plugins.article_published(&updated_article);
+        }
+
         info!("Updated article: {}", article_id);
         Ok(updated_article)
     }
 
+    /// 自动保存草稿内容，连续的自动保存合并为同一个修订版本
+    pub async fn autosave_article(&self, article_id: &str, author_id: &str, request: AutosaveArticleRequest) -> Result<Article> {
+        request.validate()
+            .map_err(|e| AppError::ValidatorError(e))?;
+
+        let update = UpdateArticleRequest {
+            title: request.title,
+            subtitle: request.subtitle,
+            content: request.content,
+            ..Default::default()
+        };
+
+        self.save_article(article_id, author_id, update, true, None).await
+    }
+
+    /// 获取文章的修订历史（新版本在前）
+    pub async fn get_revisions(&self, article_id: &str, author_id: &str) -> Result<Vec<ArticleRevisionSummary>> {
+        let article = self.get_own_article(article_id, author_id).await?;
+
+        let mut response = self.db.query_with_params(
+            "SELECT * FROM article_revision WHERE article_id = $article_id ORDER BY revision_number DESC",
+            json!({ "article_id": article.id }),
+        ).await?;
+        let revisions: Vec<ArticleRevision> = response.take(0)?;
+
+        Ok(revisions.into_iter().map(ArticleRevisionSummary::from).collect())
+    }
+
+    pub async fn get_revision(&self, article_id: &str, author_id: &str, revision_number: i32) -> Result<ArticleRevision> {
+        let article = self.get_own_article(article_id, author_id).await?;
+
+        self.find_revision(&article.id, revision_number).await?
+            .ok_or_else(|| AppError::NotFound("Revision not found".to_string()))
+    }
+
+    /// 比较两个修订版本，默认比较最新版本与其上一个版本
+    pub async fn diff_revisions(&self, article_id: &str, author_id: &str, query: RevisionDiffQuery) -> Result<RevisionDiff> {
+        let article = self.get_own_article(article_id, author_id).await?;
+
+        let to_number = match query.to {
+            Some(number) => number,
+            None => self.latest_revision(&article.id).await?
+                .map(|r| r.revision_number)
+                .ok_or_else(|| AppError::NotFound("Article has no revisions".to_string()))?,
+        };
+        let from_number = query.from.unwrap_or(to_number - 1);
+
+        let to = self.find_revision(&article.id, to_number).await?
+            .ok_or_else(|| AppError::NotFound(format!("Revision {} not found", to_number)))?;
+        let from = self.find_revision(&article.id, from_number).await?
+            .ok_or_else(|| AppError::NotFound(format!("Revision {} not found", from_number)))?;
+
+        Ok(RevisionDiff::between(&from, &to))
+    }
+
+    /// 将文章恢复到指定修订版本，恢复本身会产生一个新版本
+    pub async fn restore_revision(&self, article_id: &str, author_id: &str, revision_number: i32) -> Result<Article> {
+        let revision = self.get_revision(article_id, author_id, revision_number).await?;
+
+        // 更新请求中的 None 表示不修改，修订版本没有副标题时需要显式清除。
+        // 清除前先确认有编辑权限，只读的合著者不能借此修改文章
+        if revision.subtitle.is_none() {
+            let article = self.get_own_article(article_id, author_id).await?;
+            self.ensure_can_edit(&article, author_id).await?;
+            self.db
+                .prepare("UPDATE type::thing('article', $id) SET subtitle = NONE")
+                .bind("id", ArticleId::new(article_id).as_str())
+                .execute()
+                .await?;
+        }
+
+        let update = UpdateArticleRequest {
+            title: Some(revision.title),
+            subtitle: revision.subtitle,
+            content: Some(revision.content),
+            // 早期的修订版本没有记录标签，恢复时保留当前标签
+            tags: revision.tags,
+            ..Default::default()
+        };
+
+        info!("Restoring article {} to revision {}", article_id, revision_number);
+        self.save_article(article_id, author_id, update, false, Some(format!("Restored from revision {}", revision_number))).await
+    }
+
+    /// 渲染正文 HTML；引用的已上传图片有多个尺寸时附带 srcset，单独成段的嵌入链接展开为嵌入内容
+    async fn render_content_html(&self, content: &str) -> Result<String> {
+        let urls = figure::referenced_image_urls(content);
+        let mut srcsets = HashMap::new();
+        if !urls.is_empty() {
+            let files: Vec<MediaFile> = self.db
+                .prepare("SELECT * FROM media_file WHERE public_url INSIDE $urls")
+                .bind("urls", &urls)
+                .fetch()
+                .await?;
+            for file in files {
+                if let Some(srcset) = file.srcset() {
+                    srcsets.insert(file.public_url.clone(), srcset);
+                }
+            }
+        }
+
+        let embeds = self.oembed_service.resolve(&embed::referenced_embeds(content)).await;
+
+        Ok(self.markdown_processor.to_html_with_embeds(content, &srcsets, &embeds))
+    }
+
+    /// 作者或已接受的合著者才能读取未发布的文章
+    pub async fn get_own_article(&self, article_id: &str, author_id: &str) -> Result<Article> {
+        let article = self.get_article_by_id(article_id).await?
+            .filter(|a| !a.is_deleted)
+            .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
+
+        if article.author_id != author_id && self.get_collaborator_role(&article.id, author_id).await?.is_none() {
+            return Err(AppError::Authorization("Only article author or collaborators can access this article".to_string()));
+        }
+
+        Ok(article)
+    }
+
+    /// 用户在文章上已接受的合著者角色；待接受的邀请不授予任何权限
+    /// 作者或已接受邀请的编辑者才能修改文章
+    async fn ensure_can_edit(&self, article: &Article, user_id: &str) -> Result<()> {
+        if article.author_id == user_id {
+            return Ok(());
+        }
+        let can_edit = self.get_collaborator_role(&article.id, user_id).await?
+            .map_or(false, |role| role.can_edit());
+        if !can_edit {
+            return Err(AppError::Authorization("Only article author or editors can update this article".to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn get_collaborator_role(&self, article_id: &str, user_id: &str) -> Result<Option<CollaboratorRole>> {
+        let collaborator: Option<ArticleCollaborator> = self.db
+            .prepare("SELECT * FROM type::thing('article_collaborator', [$article_id, $user_id])")
+            .bind("article_id", ArticleId::new(article_id).as_str())
+            .bind("user_id", user_id)
+            .fetch_one()
+            .await?;
+
+        Ok(collaborator
+            .filter(|c| c.status == CollaboratorStatus::Accepted)
+            .map(|c| c.role))
+    }
+
+    /// 已接受邀请的合著者，按加入时间排序
+    async fn get_article_co_authors(&self, article_id: &str) -> Result<Vec<CoAuthorInfo>> {
+        let collaborators: Vec<ArticleCollaborator> = self.db
+            .prepare("SELECT * FROM article_collaborator WHERE article_id = $article_id AND status = 'accepted' ORDER BY accepted_at ASC")
+            .bind("article_id", ArticleId::new(article_id).as_str())
+            .fetch()
+            .await?;
+
+        let mut co_authors = Vec::with_capacity(collaborators.len());
+        for collaborator in collaborators {
+            match self.get_article_author(&collaborator.user_id).await {
+                Ok(author) => co_authors.push(CoAuthorInfo { author, role: collaborator.role }),
+                Err(e) => warn!("Skipping co-author {} of article {}: {}", collaborator.user_id, article_id, e),
+            }
+        }
+        Ok(co_authors)
+    }
+
+    async fn find_revision(&self, article_id: &str, revision_number: i32) -> Result<Option<ArticleRevision>> {
+        let mut response = self.db.query_with_params(
+            "SELECT * FROM article_revision WHERE article_id = $article_id AND revision_number = $revision_number LIMIT 1",
+            json!({ "article_id": article_id, "revision_number": revision_number }),
+        ).await?;
+        let revisions: Vec<ArticleRevision> = response.take(0)?;
+        Ok(revisions.into_iter().next())
+    }
+
+    async fn latest_revision(&self, article_id: &str) -> Result<Option<ArticleRevision>> {
+        let mut response = self.db.query_with_params(
+            "SELECT * FROM article_revision WHERE article_id = $article_id ORDER BY revision_number DESC LIMIT 1",
+            json!({ "article_id": article_id }),
+        ).await?;
+        let revisions: Vec<ArticleRevision> = response.take(0)?;
+        Ok(revisions.into_iter().next())
+    }
+
+    /// 保存文章快照；内容未变化时不产生新版本
+    async fn record_revision(
+        &self,
+        article: &Article,
+        editor_id: &str,
+        is_autosave: bool,
+        change_summary: Option<String>,
+    ) -> Result<()> {
+        let latest = self.latest_revision(&article.id).await?;
+        let tags: Vec<String> = self.get_article_tags(&article.id).await?
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect();
+
+        if let Some(latest) = &latest {
+            let unchanged = latest.title == article.title
+                && latest.subtitle == article.subtitle
+                && latest.content == article.content
+                && latest.tags.as_ref().map_or(true, |latest_tags| *latest_tags == tags);
+            if unchanged {
+                return Ok(());
+            }
+
+            // 同一编辑者的连续自动保存覆盖上一个自动保存版本
+            if is_autosave && latest.is_autosave && latest.editor_id == editor_id {
+                let _: Option<ArticleRevision> = self.db.update_by_id_with_json("article_revision", &latest.id, json!({
+                    "title": article.title,
+                    "subtitle": article.subtitle,
+                    "content": article.content,
+                    "tags": tags,
+                    "word_count": article.word_count,
+                    "created_at": Utc::now(),
+                })).await?;
+                return Ok(());
+            }
+        }
+
+        let revision_number = latest.map(|r| r.revision_number + 1).unwrap_or(1);
+        self.db.query_with_params(
+            r#"
+                CREATE article_revision CONTENT {
+                    article_id: $article_id,
+                    revision_number: $revision_number,
+                    title: $title,
+                    subtitle: $subtitle ?? NONE,
+                    content: $content,
+                    tags: $tags,
+                    word_count: $word_count,
+                    editor_id: $editor_id,
+                    is_autosave: $is_autosave,
+                    change_summary: $change_summary ?? NONE,
+                    created_at: time::now()
+                }
+            "#,
+            json!({
+                "article_id": article.id,
+                "revision_number": revision_number,
+                "title": article.title,
+                "subtitle": article.subtitle,
+                "content": article.content,
+                "tags": tags,
+                "word_count": article.word_count,
+                "editor_id": editor_id,
+                "is_autosave": is_autosave,
+                "change_summary": change_summary,
+            }),
+        ).await?;
+
+        debug!("Recorded revision {} for article {}", revision_number, article.id);
+        Ok(())
+    }
+
     /// 软删除文章
     pub async fn delete_article(&self, article_id: &str, author_id: &str) -> Result<()> {
         debug!("Deleting article: {} by user: {}", article_id, author_id);
@@ -362,41 +806,63 @@ impl ArticleService {
         let article = self.get_article_by_id(article_id).await?
             .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
 
+        // 合著者（包括编辑者）不能删除文章
         if article.author_id != author_id {
-            return Err(AppError::Authorization("Only article author can delete this article".to_string()));
+            let message = match self.get_collaborator_role(&article.id, author_id).await? {
+                Some(_) => "Collaborators cannot delete this article, only its author can",
+                None => "Only article author can delete this article",
+            };
+            return Err(AppError::Authorization(message.to_string()));
         }
 
         // 软删除
-        let query = "UPDATE article SET is_deleted = true, updated_at = $now WHERE id = $id";
-        self.db.query_with_params(query, json!({
-            "id": article_id,
-            "now": Utc::now()
-        })).await?;
+        self.db
+            .prepare("UPDATE type::thing('article', $id) SET is_deleted = true, deleted_at = $now, updated_at = $now")
+            .bind("id", ArticleId::new(article_id).as_str())
+            .bind("now", Utc::now())
+            .execute()
+            .await?;
+        self.invalidate_cached(&article).await;
 
         info!("Deleted article: {}", article_id);
         Ok(())
     }
 
+    /// 从回收站恢复文章，恢复后保持删除前的状态
+    pub async fn restore_article(&self, article_id: &str, author_id: &str) -> Result<Article> {
+        debug!("Restoring article: {} by user: {}", article_id, author_id);
+
+        let article = self.get_article_by_id(article_id).await?
+            .filter(|a| a.is_deleted)
+            .ok_or_else(|| AppError::NotFound("Article not found in trash".to_string()))?;
+
+        if article.author_id != author_id {
+            return Err(AppError::Authorization("Only article author can restore this article".to_string()));
+        }
+
+        let restored: Article = self.db
+            .prepare("UPDATE type::thing('article', $id) SET is_deleted = false, deleted_at = NONE, updated_at = $now RETURN *")
+            .bind("id", ArticleId::new(article_id).as_str())
+            .bind("now", Utc::now())
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::NotFound("Article not found in trash".to_string()))?;
+        // 删除期间 slug 可能被缓存为不存在
+        self.invalidate_cached(&restored).await;
+
+        info!("Restored article: {}", article_id);
+        Ok(restored)
+    }
+
     /// 根据 ID 获取文章
     pub async fn get_article_by_id(&self, article_id: &str) -> Result<Option<Article>> {
         debug!("Getting article by ID: {}", article_id);
 
-        // 获取纯 ID（不带 table 前缀）
-        let pure_id = if article_id.starts_with("article:") {
-            &article_id[8..]
-        } else {
-            article_id
-        };
-
-        // 使用反引号包裹 ID 以避免解析问题
-        let query = format!("SELECT * FROM article:`{}`", pure_id);
-        debug!("Executing query: {}", query);
-        
-        let mut response = self.db.query(&query).await?;
-        
-        let articles: Vec<Article> = response.take(0)?;
-        debug!("Found {} articles", articles.len());
-        Ok(articles.into_iter().next())
+        self.db
+            .prepare("SELECT * FROM type::thing('article', $id)")
+            .bind("id", ArticleId::new(article_id).as_str())
+            .fetch_one()
+            .await
     }
 
     /// 根据 slug 获取文章
@@ -406,18 +872,70 @@ impl ArticleService {
         self.db.find_one("article", "slug", slug).await
     }
 
-    /// 获取文章完整信息（包含作者、标签、统计等）
-    pub async fn get_article_with_details(&self, slug: &str, viewer_user_id: Option<&str>) -> Result<Option<ArticleResponse>> {
+    /// 批量获取已发布的文章，按不带表名的文章 ID 返回
+    pub async fn get_published_articles_by_ids(&self, article_ids: &[String]) -> Result<HashMap<String, Article>> {
+        if article_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let ids: Vec<&str> = article_ids.iter().map(|id| bare_id("article", id)).collect();
+        let articles: Vec<Article> = self.db
+            .prepare("SELECT * FROM article WHERE meta::id(id) INSIDE $ids AND status = 'published' AND is_deleted = false")
+            .bind("ids", &ids)
+            .fetch()
+            .await?;
+
+        Ok(articles.into_iter()
+            .map(|article| (bare_id("article", &article.id).to_string(), article))
+            .collect())
+    }
+
+    /// 获取文章完整信息（包含作者、标签、统计等）。
+    /// 提供 `meter` 时，没有订阅的读者可以用计量付费墙的免费额度阅读付费文章
+    pub async fn get_article_with_details(
+        &self,
+        slug: &str,
+        viewer_user_id: Option<&str>,
+        meter: Option<&MeterReader>,
+    ) -> Result<Option<ArticleResponse>> {
         debug!("Getting article with details for slug: {}", slug);
 
+        // 匿名读者看到的详情相同，走响应缓存；登录读者带有收藏、点赞等个人状态，直接查询
+        let article_response = match viewer_user_id {
+            Some(_) => self.load_article_details(slug, viewer_user_id).await?,
+            None => self
+                .response_cache
+                .get_or_load(CacheNamespace::Article, slug, "", || async {
+                    let article = self.load_article_details(slug, None).await?;
+                    Ok(article.map(|article| CachedArticle { author_id: article.author_id.clone(), article }))
+                })
+                .await?
+                .map(|cached| ArticleResponse { author_id: cached.author_id, ..cached.article }),
+        };
+        let Some(mut article_response) = article_response else {
+            return Ok(None);
+        };
+
+        // 付费墙按读者计算，不进入缓存
+        if article_response.is_paid_content {
+            self.apply_paywall(&mut article_response, viewer_user_id, meter).await?;
+        }
+        article_response.toc = self.markdown_processor.toc_tree(&article_response.content);
+
+        Ok(Some(article_response))
+    }
+
+    /// 文章详情中与付费墙无关的部分
+    async fn load_article_details(&self, slug: &str, viewer_user_id: Option<&str>) -> Result<Option<ArticleResponse>> {
         // 获取文章基础信息
         let article = match self.get_article_by_slug(slug).await? {
             Some(article) => article,
             None => return Ok(None),
         };
 
-        // 获取作者信息
-        let author = self.get_article_author(&article.author_id).await?;
+        // 获取作者信息（笔名文章显示笔名）
+        let author = self.get_public_author(&article).await?;
+        let co_authors = self.get_article_co_authors(&article.id).await?;
 
         // 获取文章标签
         let tags = self.get_article_tags(&article.id).await?;
@@ -430,39 +948,51 @@ impl ArticleService {
 
         // 获取系列信息（如果有）
         let series = match &article.series_id {
-            Some(series_id) => self.get_article_series(series_id, &article.id).await?,
+            Some(series_id) => self.get_article_series(series_id, &article.id, viewer_user_id).await?,
             None => None,
         };
 
         // 获取用户相关信息（如果已登录）
-        let (is_bookmarked, is_clapped, user_clap_count) = if let Some(user_id) = viewer_user_id {
+        let (is_bookmarked, is_clapped, user_clap_count, user_reactions) = if let Some(user_id) = viewer_user_id {
             let bookmarked = self.is_article_bookmarked(&article.id, user_id).await?;
             let clapped = self.is_article_clapped(&article.id, user_id).await?;
             let clap_count = self.get_user_clap_count(&article.id, user_id).await?;
-            (Some(bookmarked), Some(clapped), Some(clap_count))
+            let reactions = self.get_user_reactions(&article.id, user_id).await?;
+            (Some(bookmarked), Some(clapped), Some(clap_count), Some(reactions))
         } else {
-            (None, None, None)
+            (None, None, None, None)
         };
 
-        let article_response = ArticleResponse {
+        // clap_count 是点赞数的权威来源
+        let mut reactions = article.reaction_counts;
+        reactions.clap = article.clap_count;
+
+        let mut article_response = ArticleResponse {
             id: article.id,
             title: article.title,
             subtitle: article.subtitle,
             slug: article.slug,
             content: article.content,
             content_html: article.content_html,
+            toc: Vec::new(),
+            audio_url: None,
+            audio_duration: None,
             excerpt: article.excerpt,
             cover_image_url: article.cover_image_url,
             author,
+            author_id: article.author_id,
+            co_authors,
             publication,
             series,
             status: article.status,
             is_paid_content: article.is_paid_content,
+            paywall: None,
             is_featured: article.is_featured,
             reading_time: article.reading_time,
             word_count: article.word_count,
             view_count: article.view_count,
             clap_count: article.clap_count,
+            reactions,
             comment_count: article.comment_count,
             bookmark_count: article.bookmark_count,
             share_count: article.share_count,
@@ -473,16 +1003,135 @@ impl ArticleService {
             created_at: article.created_at,
             updated_at: article.updated_at,
             published_at: article.published_at,
+            scheduled_at: article.scheduled_at,
             is_bookmarked,
             is_clapped,
             user_clap_count,
+            user_reactions,
         };
 
+        if let Some(audio) = self.speech_service.get_audio(&article_response.id).await? {
+            article_response.audio_url = audio.audio_url;
+            article_response.audio_duration = audio.duration_seconds;
+        }
+
         Ok(Some(article_response))
     }
 
+    /// 文章变更影响的响应缓存：文章详情、所属出版物主页和标签页。查询出错时跳过对应条目，
+    /// 这些条目在有效期结束后自然过期
+    async fn cached_entries_for(&self, article: &Article) -> Vec<(CacheNamespace, String)> {
+        let mut entries = vec![(CacheNamespace::Article, article.slug.clone())];
+
+        if let Some(publication_id) = &article.publication_id {
+            match self.get_article_publication(publication_id).await {
+                Ok(Some(publication)) => entries.push((CacheNamespace::Publication, publication.slug)),
+                Ok(None) => {}
+                Err(e) => warn!("Failed to look up publication {} for cache invalidation: {}", publication_id, e),
+            }
+        }
+
+        // 标签页按文章上记录的标签名筛选，名称和 slug 都清除
+        match self.get_article_tags(&article.id).await {
+            Ok(tags) => {
+                for tag in tags {
+                    if tag.slug != tag.name {
+                        entries.push((CacheNamespace::Tag, tag.slug));
+                    }
+                    entries.push((CacheNamespace::Tag, tag.name));
+                }
+            }
+            Err(e) => warn!("Failed to look up tags of article {} for cache invalidation: {}", article.id, e),
+        }
+
+        entries
+    }
+
+    async fn invalidate_cached(&self, article: &Article) {
+        let entries = self.cached_entries_for(article).await;
+        self.response_cache.invalidate_all(&entries).await;
+    }
+
+    /// 付费文章：没有有效订阅或单篇购买、免费额度也已用完的读者只拿到前几段正文
+    async fn apply_paywall(&self, article: &mut ArticleResponse, viewer_user_id: Option<&str>, meter: Option<&MeterReader>) -> Result<()> {
+        let mut access = self.payment_service.check_content_access(&article.id, viewer_user_id).await?;
+        let pricing = self.payment_service.get_article_pricing(&article.id).await.ok();
+
+        let meter = match meter {
+            Some(reader) if !access.has_access => {
+                let publication_id = article.publication.as_ref().map(|p| p.id.as_str());
+                let status = self.meter_service.consume(reader, &article.id, publication_id).await?;
+                if status.granted {
+                    access.has_access = true;
+                    access.access_type = AccessType::Metered;
+                }
+                Some(status)
+            }
+            _ => None,
+        };
+
+        let preview = if access.has_access {
+            None
+        } else {
+            self.markdown_processor.truncate_paragraphs(&article.content, self.paywall_preview_paragraphs)
+        };
+        let is_truncated = preview.is_some();
+        if let Some(preview) = preview {
+            article.content_html = self.markdown_processor.to_html(&preview);
+            article.content = preview;
+            // 朗读音频包含全文
+            article.audio_url = None;
+            article.audio_duration = None;
+        }
+
+        article.paywall = Some(PaywallInfo {
+            has_access: access.has_access,
+            access_type: access.access_type,
+            is_truncated,
+            preview_paragraphs: self.paywall_preview_paragraphs,
+            paywall_message: pricing
+                .as_ref()
+                .map(|p| p.paywall_message.clone())
+                .unwrap_or_else(|| "订阅以继续阅读完整内容".to_string()),
+            subscription_required: pricing.as_ref().map_or(true, |p| p.subscription_required),
+            price: pricing.and_then(|p| p.price),
+            meter,
+        });
+        Ok(())
+    }
+
     /// 获取文章列表（分页）
     pub async fn get_articles(&self, query: ArticleQuery) -> Result<crate::services::database::PaginatedResult<ArticleListItem>> {
+        // 只按标签筛选的是标签页，走响应缓存；分页和排序参数区分同一标签下的条目
+        let is_tag_page = query.author.is_none()
+            && query.publication.is_none()
+            && query.search.is_none()
+            && query.status.is_none()
+            && query.pseudonym.is_none();
+        if let (Some(tag), true) = (query.tag.clone(), is_tag_page) {
+            let variant = serde_json::to_string(&query)?;
+            return self
+                .response_cache
+                .get_or_load(CacheNamespace::Tag, &tag, &variant, || self.load_article_list(query))
+                .await;
+        }
+
+        self.load_article_list(query).await
+    }
+
+    async fn load_article_list(&self, query: ArticleQuery) -> Result<crate::services::database::PaginatedResult<ArticleListItem>> {
+        let result = self.find_articles(query).await?;
+        Ok(crate::services::database::PaginatedResult {
+            data: self.articles_to_list_items(&result.data).await?,
+            total: result.total,
+            page: result.page,
+            per_page: result.per_page,
+            total_pages: result.total_pages,
+        })
+    }
+
+    /// 按列表条件查询文章本身，不加载作者、标签等关联信息
+    pub async fn find_articles(&self, query: ArticleQuery) -> Result<crate::services::database::PaginatedResult<Article>> {
         debug!("Getting articles list with query: {:?}", query);
 
         let page = query.page.unwrap_or(1);
@@ -499,9 +1148,17 @@ impl ArticleService {
             conditions.push("status = 'published'".to_string());
         }
 
-        // 作者过滤
+        // 作者过滤；笔名文章不出现在账号的文章列表中
         if let Some(author) = &query.author {
             conditions.push(format!("author_id = $author"));
+            if !query.include_pseudonymous {
+                conditions.push("pseudonym_id IS NONE".to_string());
+            }
+        }
+
+        // 笔名过滤
+        if query.pseudonym.is_some() {
+            conditions.push("pseudonym_id = $pseudonym".to_string());
         }
 
         // 标签过滤
@@ -529,11 +1186,14 @@ impl ArticleService {
         // 排序
         let (select_fields, order_by) = match query.sort.as_deref() {
             Some("oldest") => ("*", "created_at ASC"),
-            Some("popular") => ("*", "clap_count DESC, view_count DESC"),
+            Some("popular") => ("*", "weighted_clap_score DESC, view_count DESC"),
             Some("trending") => {
-                // 在 SELECT 中计算趋势分数
-                ("*, (clap_count + comment_count * 2 + view_count * 0.1) as trending_score", "trending_score DESC")
+                // 在 SELECT 中计算趋势分数（点赞使用加权分，避免刷赞）
+                ("*, (weighted_clap_score + comment_count * 2 + view_count * 0.1) as trending_score", "trending_score DESC")
             },
+            // 出版物和作者主页的默认排序中置顶文章在最前；降序时没有置顶时间（NONE）的排在后面
+            _ if query.publication.is_some() => ("*", "publication_pinned_at DESC, created_at DESC"),
+            _ if query.author.is_some() => ("*", "pinned_at DESC, created_at DESC"),
             _ => ("*", "created_at DESC"),
         };
 
@@ -553,6 +1213,9 @@ impl ArticleService {
         if let Some(author) = &query.author {
             params["author"] = json!(author);
         }
+        if let Some(pseudonym) = &query.pseudonym {
+            params["pseudonym"] = json!(bare_id("pseudonym", pseudonym));
+        }
         if let Some(tag) = &query.tag {
             params["tag"] = json!(tag);
         }
@@ -571,16 +1234,9 @@ impl ArticleService {
 
         let mut data_response = self.db.query_with_params(&data_query, params).await?;
         let articles: Vec<Article> = data_response.take(0)?;
-        
-        // 将Article转换为ArticleListItem，并填充作者信息
-        let mut article_list_items = Vec::new();
-        for article in articles {
-            let list_item = self.article_to_list_item(&article).await?;
-            article_list_items.push(list_item);
-        }
 
         Ok(crate::services::database::PaginatedResult {
-            data: article_list_items,
+            data: articles,
             total,
             page,
             per_page: limit,
@@ -601,6 +1257,7 @@ impl ArticleService {
 
         if include_drafts {
             query.status = None; // 返回所有状态的文章
+            query.include_pseudonymous = true; // 作者查看自己的文章
         }
 
         self.get_articles(query).await
@@ -610,11 +1267,12 @@ impl ArticleService {
     pub async fn increment_view_count(&self, article_id: &str) -> Result<()> {
         debug!("Incrementing view count for article: {}", article_id);
 
-        let query = "UPDATE article SET view_count += 1, updated_at = $now WHERE id = $id";
-        self.db.query_with_params(query, json!({
-            "id": article_id,
-            "now": Utc::now()
-        })).await?;
+        self.db
+            .prepare("UPDATE type::thing('article', $id) SET view_count += 1, updated_at = $now")
+            .bind("id", ArticleId::new(article_id).as_str())
+            .bind("now", Utc::now())
+            .execute()
+            .await?;
 
         Ok(())
     }
@@ -623,12 +1281,13 @@ impl ArticleService {
     pub async fn increment_clap_count(&self, article_id: &str, count: u32) -> Result<()> {
         debug!("Incrementing clap count for article: {} by {}", article_id, count);
 
-        let query = "UPDATE article SET clap_count += $count, updated_at = $now WHERE id = $id";
-        self.db.query_with_params(query, json!({
-            "id": article_id,
-            "count": count,
-            "now": Utc::now()
-        })).await?;
+        self.db
+            .prepare("UPDATE type::thing('article', $id) SET clap_count += $count, updated_at = $now")
+            .bind("id", ArticleId::new(article_id).as_str())
+            .bind("count", count)
+            .bind("now", Utc::now())
+            .execute()
+            .await?;
 
         Ok(())
     }
@@ -650,13 +1309,27 @@ impl ArticleService {
         Ok(())
     }
 
-    /// 生成唯一的 slug
-    async fn generate_unique_slug(&self, title: &str) -> Result<String> {
+    /// 生成摘要：已配置写作辅助服务时优先使用模型总结，失败时回退到句子边界抽取
+    async fn generate_excerpt(&self, content: &str) -> String {
+        if self.assist_service.is_enabled() {
+            let text = self.markdown_processor.to_text(content);
+            match self.assist_service.summarize(&text, EXCERPT_MAX_LENGTH).await {
+                Ok(Some(summary)) => return summary,
+                Ok(None) => {}
+                Err(e) => warn!("Assist excerpt generation failed, falling back: {}", e),
+            }
+        }
+
+        self.markdown_processor.generate_excerpt(content, EXCERPT_MAX_LENGTH)
+    }
+
+    /// 生成唯一的 slug，`article_id` 为正在修改的文章，它自己用过的 slug 不算冲突
+    async fn generate_unique_slug(&self, title: &str, article_id: Option<&str>) -> Result<String> {
         let base_slug = slug::generate_slug(title);
         let mut slug = base_slug.clone();
         let mut counter = 1;
 
-        while self.get_article_by_slug(&slug).await?.is_some() {
+        while RESERVED_SLUGS.contains(&slug.as_str()) || self.is_slug_taken(&slug, article_id).await? {
             slug = format!("{}-{}", base_slug, counter);
             counter += 1;
             
@@ -668,26 +1341,139 @@ impl ArticleService {
         Ok(slug)
     }
 
+    /// 规范化并检查作者指定的 slug：格式合法、不是保留路径、没有被其他文章占用
+    async fn check_slug(&self, slug: &str, article_id: Option<&str>) -> Result<String> {
+        let slug = slug.trim().to_lowercase();
+        if !slug::is_valid_slug(&slug) {
+            return Err(AppError::bad_request("Slug may only contain letters, digits, '-' and '_'"));
+        }
+        if RESERVED_SLUGS.contains(&slug.as_str()) {
+            return Err(AppError::bad_request(&format!("'{}' is reserved", slug)));
+        }
+        if self.is_slug_taken(&slug, article_id).await? {
+            return Err(AppError::Conflict(format!("An article with slug '{}' already exists", slug)));
+        }
+        Ok(slug)
+    }
+
+    /// slug 是否被其他文章的当前 slug 或旧 slug（跳转）占用
+    async fn is_slug_taken(&self, slug: &str, article_id: Option<&str>) -> Result<bool> {
+        let current_owner: Option<String> = self.db
+            .prepare("SELECT VALUE type::string(id) FROM article WHERE slug = $slug LIMIT 1")
+            .bind("slug", slug)
+            .fetch_one()
+            .await?;
+        let redirect_owner: Option<String> = self.db
+            .prepare("SELECT VALUE article_id FROM article_slug_history WHERE slug = $slug LIMIT 1")
+            .bind("slug", slug)
+            .fetch_one()
+            .await?;
+
+        let article_id = article_id.map(ArticleId::new);
+        Ok([current_owner, redirect_owner]
+            .into_iter()
+            .flatten()
+            .any(|owner| Some(ArticleId::new(&owner)) != article_id))
+    }
+
+    /// 记录旧 slug 用于跳转；改回以前用过的 slug 时删除对应的跳转
+    async fn record_slug_change(&self, article_id: &str, old_slug: &str, new_slug: &str) -> Result<()> {
+        self.db
+            .prepare(
+                r#"
+                DELETE article_slug_history WHERE slug = $old_slug OR slug = $new_slug;
+                CREATE article_slug_history CONTENT {
+                    article_id: $article_id,
+                    publication_id: (SELECT VALUE publication_id FROM type::thing('article', $article_key))[0],
+                    slug: $old_slug,
+                    new_slug: $new_slug,
+                    created_at: time::now()
+                };
+                "#,
+            )
+            .bind("article_id", ArticleId::new(article_id).to_record())
+            .bind("article_key", ArticleId::new(article_id).as_str())
+            .bind("old_slug", old_slug)
+            .bind("new_slug", new_slug)
+            .execute()
+            .await?;
+
+        debug!("Article {} slug changed from {} to {}", article_id, old_slug, new_slug);
+        Ok(())
+    }
+
+    /// 文章的旧 slug 列表（仅作者），最近改的在前
+    pub async fn list_slug_redirects(&self, article_id: &str, author_id: &str) -> Result<Vec<ArticleSlugRedirect>> {
+        let article = self.get_authored_article(article_id, author_id).await?;
+
+        self.db
+            .prepare("SELECT * FROM article_slug_history WHERE article_id = $article_id ORDER BY created_at DESC")
+            .bind("article_id", ArticleId::new(&article.id).to_record())
+            .fetch()
+            .await
+    }
+
+    /// 删除旧 slug 的跳转，之后该 slug 可以被其他文章使用
+    pub async fn delete_slug_redirect(&self, article_id: &str, author_id: &str, slug: &str) -> Result<()> {
+        let article = self.get_authored_article(article_id, author_id).await?;
+
+        let deleted: Vec<ArticleSlugRedirect> = self.db
+            .prepare("DELETE article_slug_history WHERE article_id = $article_id AND slug = $slug RETURN BEFORE")
+            .bind("article_id", ArticleId::new(&article.id).to_record())
+            .bind("slug", slug)
+            .fetch()
+            .await?;
+        if deleted.is_empty() {
+            return Err(AppError::NotFound("Slug redirect not found".to_string()));
+        }
+
+        info!("Removed slug redirect {} of article {}", slug, article.id);
+        Ok(())
+    }
+
+    /// 通过旧 slug 找到文章，只跳转到公开可见的文章，未发布的文章不暴露新 slug
+    pub async fn find_slug_redirect(&self, slug: &str) -> Result<Option<Article>> {
+        let article_id: Option<String> = self.db
+            .prepare("SELECT VALUE article_id FROM article_slug_history WHERE slug = $slug LIMIT 1")
+            .bind("slug", slug)
+            .fetch_one()
+            .await?;
+        let Some(article_id) = article_id else {
+            return Ok(None);
+        };
+
+        Ok(self.get_article_by_id(&article_id).await?
+            .filter(|a| !a.is_deleted && a.status.can_be_viewed_by_public()))
+    }
+
+    async fn get_authored_article(&self, article_id: &str, author_id: &str) -> Result<Article> {
+        let article = self.get_article_by_id(article_id).await?
+            .filter(|a| !a.is_deleted)
+            .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
+
+        if article.author_id != author_id {
+            return Err(AppError::Authorization("Only article author can manage slug redirects".to_string()));
+        }
+        Ok(article)
+    }
+
     /// 为文章附加标签
     async fn attach_tags_to_article(&self, article_id: &str, tags: &[String]) -> Result<()> {
         debug!("Attaching {} tags to article: {}", tags.len(), article_id);
 
-        let normalized_article_id = normalize_surreal_id(article_id);
+        let article_id = ArticleId::new(article_id);
 
         // 清理现有标签（规范为 record 类型进行匹配）
-        let clear_query = r#"
-            DELETE article_tag 
-            WHERE article_id = type::thing('article', $aid)
-        "#;
         self.db
-            .query_with_params(clear_query, json!({ "aid": normalized_article_id }))
+            .prepare("DELETE article_tag WHERE article_id = type::thing('article', $aid)")
+            .bind("aid", article_id.as_str())
+            .execute()
             .await?;
 
         // 添加新标签
         for tag_name in tags {
             // 获取或创建标签
             let tag_id = self.get_or_create_tag(tag_name).await?;
-            let normalized_tag_id = normalize_surreal_id(&tag_id);
 
             // 创建关联（确保以 record 类型写入）
             let create_query = r#"
@@ -696,41 +1482,35 @@ impl ArticleService {
                     tag_id = type::thing('tag', $tid)
             "#;
             self.db
-                .query_with_params(create_query, json!({
-                    "aid": normalized_article_id,
-                    "tid": normalized_tag_id
-                }))
+                .prepare(create_query)
+                .bind("aid", article_id.as_str())
+                .bind("tid", tag_id.as_str())
+                .execute()
                 .await?;
 
             // 更新该标签的文章计数
-            let count_query = r#"
-                SELECT VALUE count() FROM article_tag 
-                WHERE string::split(string::replace(string::replace(type::string(tag_id), '⟨', ''), '⟩', ''), ':')[1] = $tid
-            "#;
-            let mut resp = self
-                .db
-                .query_with_params(count_query, json!({ "tid": normalized_tag_id }))
+            let counts: Vec<i64> = self.db
+                .prepare("SELECT VALUE count() FROM article_tag WHERE tag_id = type::thing('tag', $tid) GROUP ALL")
+                .bind("tid", tag_id.as_str())
+                .fetch()
                 .await?;
-            let counts: Vec<i64> = resp.take(0)?;
             let count = counts.into_iter().next().unwrap_or(0);
 
-            let update_count = r#"
-                UPDATE type::thing('tag', $tid) SET article_count = $count
-            "#;
             self.db
-                .query_with_params(update_count, json!({
-                    "tid": normalized_tag_id,
-                    "count": count
-                }))
+                .prepare("UPDATE type::thing('tag', $tid) SET article_count = $count")
+                .bind("tid", tag_id.as_str())
+                .bind("count", count)
+                .execute()
                 .await?;
         }
 
         // 更新文章的标签字段
-        let update_query = "UPDATE article SET tags = $tags WHERE id = $id";
-        self.db.query_with_params(update_query, json!({
-            "id": article_id,
-            "tags": tags
-        })).await?;
+        self.db
+            .prepare("UPDATE type::thing('article', $id) SET tags = $tags")
+            .bind("id", article_id.as_str())
+            .bind("tags", tags)
+            .execute()
+            .await?;
 
         Ok(())
     }
@@ -740,48 +1520,93 @@ impl ArticleService {
         self.attach_tags_to_article(article_id, tags).await
     }
 
+    /// 在现有标签基础上增删标签，按 slug 比较，返回更新后的标签
+    pub async fn modify_article_tags(&self, article_id: &str, author_id: &str, add: &[String], remove: &[String]) -> Result<Vec<TagInfo>> {
+        let article = self.get_article_by_id(article_id).await?
+            .filter(|a| !a.is_deleted)
+            .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
+
+        self.ensure_can_edit(&article, author_id).await?;
+
+        let current = self.get_article_tags(&article.id).await?;
+        let remove_slugs: Vec<String> = remove.iter().map(|t| slug::generate_slug(t)).collect();
+        let (removed, kept): (Vec<TagInfo>, Vec<TagInfo>) = current
+            .into_iter()
+            .partition(|tag| remove_slugs.contains(&tag.slug));
+
+        let mut tags: Vec<String> = kept.iter().map(|tag| tag.name.clone()).collect();
+        let mut slugs: Vec<String> = kept.iter().map(|tag| tag.slug.clone()).collect();
+        let mut added = false;
+        for name in add {
+            let name = name.trim();
+            let tag_slug = slug::generate_slug(name);
+            if name.is_empty() || slugs.contains(&tag_slug) {
+                continue;
+            }
+            slugs.push(tag_slug);
+            tags.push(name.to_string());
+            added = true;
+        }
+
+        if removed.is_empty() && !added {
+            return Ok(kept);
+        }
+
+        // 先按旧标签清除缓存，被移除的标签页也要失效
+        let mut stale_entries = self.cached_entries_for(&article).await;
+
+        self.update_article_tags(&article.id, &tags).await?;
+
+        // attach_tags_to_article 只刷新仍然关联的标签计数
+        for tag in &removed {
+            let tag_id = TagId::new(&tag.id);
+            self.db
+                .prepare(
+                    r#"
+                    LET $count = (SELECT VALUE count() FROM article_tag WHERE tag_id = type::thing('tag', $tid) GROUP ALL)[0] ?? 0;
+                    UPDATE type::thing('tag', $tid) SET article_count = $count;
+                    "#,
+                )
+                .bind("tid", tag_id.as_str())
+                .execute()
+                .await?;
+        }
+
+        self.record_revision(&article, author_id, false, Some("Updated tags".to_string())).await?;
+
+        stale_entries.extend(self.cached_entries_for(&article).await);
+        self.response_cache.invalidate_all(&stale_entries).await;
+
+        self.get_article_tags(&article.id).await
+    }
+
     /// 获取或创建标签
-    async fn get_or_create_tag(&self, tag_name: &str) -> Result<String> {
+    async fn get_or_create_tag(&self, tag_name: &str) -> Result<TagId> {
+        #[derive(Deserialize)]
+        struct TagRecord {
+            id: TagId,
+        }
+
         let slug = slug::generate_slug(tag_name);
         debug!("Getting or creating tag with name: {}, slug: {}", tag_name, slug);
         
-        // 查找现有标签
-        if let Some(tag) = self.db.find_one::<Value>("tag", "slug", &slug).await? {
-            debug!("Found existing tag: {:?}", tag);
-            if let Some(id_value) = tag.get("id") {
-                match id_value {
-                    Value::String(s) => return Ok(s.clone()),
-                    Value::Object(obj) => {
-                        // 处理 Thing 格式 { "tb": "tag", "id": { "String": "xxx" } }
-                        if let (Some(tb), Some(id)) = (obj.get("tb"), obj.get("id")) {
-                            let tb_str = tb.as_str().unwrap_or("tag");
-                            let id_str = match id {
-                                Value::String(s) => s.clone(),
-                                Value::Object(id_obj) => {
-                                    id_obj.get("String")
-                                        .and_then(|v| v.as_str())
-                                        .unwrap_or("unknown")
-                                        .to_string()
-                                },
-                                _ => id.to_string()
-                            };
-                            return Ok(format!("{}:{}", tb_str, id_str));
-                        }
-                    },
-                    _ => {}
-                }
-            }
+        // 查找现有标签，别名指向规范标签
+        let existing: Option<TagRecord> = self.db
+            .prepare("SELECT id FROM tag WHERE slug = $slug OR $slug INSIDE aliases LIMIT 1")
+            .bind("slug", &slug)
+            .fetch_one()
+            .await?;
+        if let Some(tag) = existing {
+            debug!("Found existing tag: {}", tag.id);
+            return Ok(tag.id);
         }
 
         // 创建新标签
-        let tag_uuid = Uuid::new_v4().to_string();
-        let tag_id = format!("tag:{}", tag_uuid);
+        let tag_id = TagId::new(Uuid::new_v4().to_string());
         debug!("Creating new tag with id: {}", tag_id);
-        
-        // 使用 CREATE tag:id 语法而不是参数化的 $id
-        let query = format!(
-            r#"
-            CREATE tag:`{}` CONTENT {{
+
+        let query = r#"
+            CREATE type::thing('tag', $id) CONTENT {
                 name: $name,
                 slug: $slug,
                 follower_count: 0,
@@ -789,35 +1614,117 @@ impl ArticleService {
                 is_featured: false,
                 created_at: time::now(),
                 updated_at: time::now()
-            }} RETURN *
-            "#,
-            tag_uuid
-        );
+            } RETURN id
+        "#;
+        let created: Option<TagRecord> = self.db
+            .prepare(query)
+            .bind("id", tag_id.as_str())
+            .bind("name", tag_name)
+            .bind("slug", &slug)
+            .fetch_one()
+            .await?;
 
-        let result = self.db.query_with_params(
-            &query,
-            json!({ 
-                "name": tag_name,
-                "slug": slug
-            })
-        ).await?;
+        created.map(|tag| tag.id).ok_or_else(|| {
+            error!("Tag creation returned no results");
+            AppError::Internal("Tag creation returned no results".to_string())
+        })
+    }
 
-        let mut response = result;
-        match response.take::<Option<Value>>(0) {
-            Ok(Some(created)) => {
-                debug!("Tag created successfully: {:?}", created);
-                // 我们已经使用了完整的 tag:uuid 格式作为 ID
-                return Ok(tag_id);
-            },
-            Ok(None) => {
-                error!("Tag creation returned no results");
-                Err(AppError::Internal("Tag creation returned no results".to_string()))
-            },
-            Err(e) => {
-                error!("Failed to parse tag creation result: {:?}", e);
-                Err(AppError::Internal(format!("Failed to parse tag creation result: {:?}", e)))
+    /// 置顶到作者主页或取消置顶，只有作者可以操作。笔名文章不出现在作者主页，不能置顶
+    pub async fn pin_to_profile(&self, article_id: &str, author_id: &str, pinned: bool) -> Result<Article> {
+        let article = self.get_article_by_id(article_id).await?
+            .filter(|a| !a.is_deleted)
+            .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
+
+        if article.author_id != author_id {
+            return Err(AppError::Authorization("Only article author can pin this article".to_string()));
+        }
+        if pinned && article.pseudonym_id.is_some() {
+            return Err(AppError::BadRequest("Pseudonymous articles cannot be pinned to the profile".to_string()));
+        }
+
+        self.set_pinned(&article, PinTarget::Profile, pinned).await
+    }
+
+    /// 置顶到出版物首页或取消置顶。出版物权限由调用方检查
+    pub async fn pin_to_publication(&self, article: &Article, pinned: bool) -> Result<Article> {
+        if article.publication_id.is_none() {
+            return Err(AppError::BadRequest("Article does not belong to a publication".to_string()));
+        }
+
+        self.set_pinned(article, PinTarget::Publication, pinned).await
+    }
+
+    /// 只能置顶已发布的文章，重复置顶不改变顺序；取消发布或删除的文章保留置顶时间但不计入上限
+    async fn set_pinned(&self, article: &Article, target: PinTarget, pinned: bool) -> Result<Article> {
+        let (field, scope_field, scope_value, already_pinned) = match target {
+            PinTarget::Profile => ("pinned_at", "author_id", article.author_id.clone(), article.pinned_at.is_some()),
+            PinTarget::Publication => (
+                "publication_pinned_at",
+                "publication_id",
+                article.publication_id.clone().unwrap_or_default(),
+                article.publication_pinned_at.is_some(),
+            ),
+        };
+        if pinned == already_pinned {
+            return Ok(article.clone());
+        }
+
+        if pinned {
+            if !article.is_published() {
+                return Err(AppError::BadRequest("Only published articles can be pinned".to_string()));
+            }
+
+            let pinned_ids: Vec<String> = self.db
+                .prepare(&format!(
+                    "SELECT VALUE meta::id(id) FROM article WHERE {scope_field} = $scope AND {field} != NONE AND status = 'published' AND is_deleted = false"
+                ))
+                .bind("scope", &scope_value)
+                .fetch()
+                .await?;
+            if pinned_ids.len() >= MAX_PINNED_ARTICLES {
+                return Err(AppError::Conflict(format!(
+                    "At most {} articles can be pinned, unpin one first",
+                    MAX_PINNED_ARTICLES
+                )));
             }
         }
+
+        let updated: Article = self.db
+            .prepare(&format!(
+                "UPDATE type::thing('article', $id) SET {field} = $pinned_at RETURN AFTER"
+            ))
+            .bind("id", ArticleId::new(&article.id).as_str())
+            .bind("pinned_at", pinned.then(Utc::now))
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
+        self.invalidate_cached(&updated).await;
+
+        info!("{} article {} ({})", if pinned { "Pinned" } else { "Unpinned" }, updated.id, field);
+        Ok(updated)
+    }
+
+    /// 设置或取消精选。只能精选已发布的文章，权限由调用方检查
+    pub async fn set_featured(&self, article: &Article, featured: bool) -> Result<Article> {
+        if featured && !article.is_published() {
+            return Err(AppError::BadRequest("Only published articles can be featured".to_string()));
+        }
+        if article.is_featured == featured {
+            return Ok(article.clone());
+        }
+
+        let updated: Article = self.db
+            .prepare("UPDATE type::thing('article', $id) SET is_featured = $featured RETURN AFTER")
+            .bind("id", ArticleId::new(&article.id).as_str())
+            .bind("featured", featured)
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
+        self.invalidate_cached(&updated).await;
+
+        info!("Set article {} featured: {}", updated.id, featured);
+        Ok(updated)
     }
 
     /// 发布文章
@@ -832,32 +1739,25 @@ impl ArticleService {
         if article.author_id != author_id {
             return Err(AppError::Authorization("Only article author can publish this article".to_string()));
         }
+        ensure_not_taken_down(&article)?;
         
         // 检查是否已发布
         if article.status == ArticleStatus::Published {
-            return Err(AppError::BadRequest("Article is already published".to_string()));
+            return Err(AppError::coded(ErrorCode::ArticleAlreadyPublished, "Article is already published"));
         }
         
-        // 使用 UPDATE 查询而不是对象更新，避免 ID 格式问题
-        let id_without_prefix = if article_id.starts_with("article:") {
-            &article_id[8..]
-        } else {
-            article_id
-        };
-        
-        let update_query = format!(
-            "UPDATE article:`{}` SET status = $status, published_at = time::now(), updated_at = time::now() RETURN *",
-            id_without_prefix
-        );
-        
-        let mut response = self.db.query_with_params(&update_query, json!({
-            "status": "published"
-        })).await?;
-        
-        let updated_articles: Vec<Article> = response.take(0)?;
-        let updated_article = updated_articles.into_iter().next()
+        // 只更新状态字段，不整体覆盖文章
+        let updated_article: Article = self.db
+            .prepare("UPDATE type::thing('article', $id) SET status = $status, published_at = time::now(), scheduled_at = NONE, updated_at = time::now() RETURN *")
+            .bind("id", ArticleId::new(article_id).as_str())
+            .bind("status", "published")
+            .fetch_one()
+            .await?
             .ok_or_else(|| AppError::NotFound("Failed to publish article".to_string()))?;
         
+        self.invalidate_cached(&updated_article).await;
+        self.plugins.article_published(&updated_article);
+
         info!("Published article: {}", article_id);
         Ok(updated_article)
     }
@@ -880,30 +1780,106 @@ impl ArticleService {
             return Err(AppError::BadRequest("Article is already in draft status".to_string()));
         }
         
-        // 使用 UPDATE 查询而不是对象更新，避免 ID 格式问题
-        let id_without_prefix = if article_id.starts_with("article:") {
-            &article_id[8..]
-        } else {
-            article_id
-        };
-        
-        let update_query = format!(
-            "UPDATE article:`{}` SET status = $status, updated_at = time::now() RETURN *",
-            id_without_prefix
-        );
-        
-        let mut response = self.db.query_with_params(&update_query, json!({
-            "status": "draft"
-        })).await?;
-        
-        let updated_articles: Vec<Article> = response.take(0)?;
-        let updated_article = updated_articles.into_iter().next()
+        // 只更新状态字段，不整体覆盖文章
+        let updated_article: Article = self.db
+            .prepare("UPDATE type::thing('article', $id) SET status = $status, updated_at = time::now() RETURN *")
+            .bind("id", ArticleId::new(article_id).as_str())
+            .bind("status", "draft")
+            .fetch_one()
+            .await?
             .ok_or_else(|| AppError::NotFound("Failed to unpublish article".to_string()))?;
         
+        self.invalidate_cached(&updated_article).await;
+
         info!("Unpublished article: {}", article_id);
         Ok(updated_article)
     }
 
+    /// 提交文章等待审核（信誉不足以直接发布的作者）
+    pub async fn submit_for_review(&self, article_id: &str, author_id: &str) -> Result<Article> {
+        debug!("Submitting article {} for review by user: {}", article_id, author_id);
+
+        let article = self.get_article_by_id(article_id).await?
+            .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
+
+        if article.author_id != author_id {
+            return Err(AppError::Authorization("Only article author can submit this article".to_string()));
+        }
+        ensure_not_taken_down(&article)?;
+
+        if article.status == ArticleStatus::Published {
+            return Err(AppError::coded(ErrorCode::ArticleAlreadyPublished, "Article is already published"));
+        }
+
+        let updated: Option<Article> = self.db.update_by_id_with_json("article", article_id, json!({
+            "status": "pending_review",
+            "updated_at": Utc::now(),
+        })).await?;
+
+        info!("Article {} submitted for review", article_id);
+        updated.ok_or_else(|| AppError::NotFound("Failed to submit article for review".to_string()))
+    }
+
+    /// 审核待发布文章：通过则发布，驳回则退回草稿
+    pub async fn review_article(&self, article_id: &str, approve: bool) -> Result<Article> {
+        let article = self.get_article_by_id(article_id).await?
+            .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
+
+        if article.status != ArticleStatus::PendingReview {
+            return Err(AppError::BadRequest("Article is not pending review".to_string()));
+        }
+
+        let updates = if approve {
+            json!({
+                "status": "published",
+                "published_at": Utc::now(),
+                "updated_at": Utc::now(),
+            })
+        } else {
+            json!({
+                "status": "draft",
+                "updated_at": Utc::now(),
+            })
+        };
+
+        let updated: Option<Article> = self.db.update_by_id_with_json("article", article_id, updates).await?;
+        let updated = updated.ok_or_else(|| AppError::NotFound("Failed to review article".to_string()))?;
+        self.invalidate_cached(&updated).await;
+
+        if approve {
+            self.plugins.article_published(&updated);
+        }
+
+        info!("Article {} review {}", article_id, if approve { "approved" } else { "rejected" });
+        Ok(updated)
+    }
+
+    /// 发布已到时间的定时文章，返回本次发布的文章数
+    pub async fn publish_scheduled_articles(&self) -> Result<usize> {
+        let mut response = self.db.query(
+            "UPDATE article SET status = 'published', published_at = scheduled_at, scheduled_at = NONE, updated_at = time::now() \
+             WHERE status = 'scheduled' AND scheduled_at <= time::now() AND is_deleted = false RETURN AFTER",
+        ).await?;
+        let published: Vec<Article> = response.take(0)?;
+
+        for article in &published {
+            self.invalidate_cached(article).await;
+            self.plugins.article_published(article);
+            info!("Published scheduled article: {}", article.id);
+        }
+
+        Ok(published.len())
+    }
+
+    /// 获取等待审核的文章
+    pub async fn get_pending_review_articles(&self, limit: usize) -> Result<Vec<Article>> {
+        let mut response = self.db.query_with_params(
+            "SELECT * FROM article WHERE status = 'pending_review' AND is_deleted = false ORDER BY updated_at ASC LIMIT $limit",
+            json!({ "limit": limit }),
+        ).await?;
+        Ok(response.take(0)?)
+    }
+
     /// 聚合每日统计
     pub async fn aggregate_daily_stats(&self) -> Result<()> {
         debug!("Aggregating daily article stats");
@@ -958,7 +1934,7 @@ impl ArticleService {
     }
 
     /// 获取文章作者信息
-    async fn get_article_author(&self, author_id: &str) -> Result<AuthorInfo> {
+    pub async fn get_article_author(&self, author_id: &str) -> Result<AuthorInfo> {
         debug!("Getting author info for: {}", author_id);
 
         let query = r#"
@@ -997,11 +1973,25 @@ impl ArticleService {
             is_verified: author_data.get("is_verified")
                 .and_then(|v| v.as_bool())
                 .unwrap_or(false),
+            is_pseudonym: false,
         })
     }
 
+    /// 文章对外显示的作者：以笔名发布的文章只返回笔名资料。
+    /// 笔名被删除时显示为匿名作者，不会退回到账号资料
+    pub async fn get_public_author(&self, article: &Article) -> Result<AuthorInfo> {
+        let Some(pseudonym_id) = &article.pseudonym_id else {
+            return self.get_article_author(&article.author_id).await;
+        };
+
+        let pseudonym: Option<Pseudonym> = self.db
+            .get_by_id("pseudonym", bare_id("pseudonym", pseudonym_id))
+            .await?;
+        Ok(pseudonym.map(|p| p.author_info()).unwrap_or_else(AuthorInfo::anonymous))
+    }
+
     /// 获取文章标签
-    async fn get_article_tags(&self, article_id: &str) -> Result<Vec<TagInfo>> {
+    pub async fn get_article_tags(&self, article_id: &str) -> Result<Vec<TagInfo>> {
         debug!("Getting tags for article: {}", article_id);
 
         // First get article_tag relationships
@@ -1050,6 +2040,106 @@ impl ArticleService {
         Ok(tags)
     }
 
+    /// 批量获取作者资料，按用户 ID 返回；没有资料的用户不在结果中
+    pub async fn get_authors_by_user_ids(&self, user_ids: &[String]) -> Result<HashMap<String, AuthorInfo>> {
+        if user_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let rows: Vec<AuthorRow> = self.db
+            .prepare(
+                r#"
+                SELECT type::string(id) AS id, user_id, username, display_name, avatar_url, is_verified
+                FROM user_profile
+                WHERE user_id INSIDE $user_ids
+                "#,
+            )
+            .bind("user_ids", user_ids)
+            .fetch()
+            .await?;
+
+        Ok(rows.into_iter().map(|row| {
+            (row.user_id, AuthorInfo {
+                id: row.id,
+                username: row.username,
+                display_name: row.display_name,
+                avatar_url: row.avatar_url,
+                is_verified: row.is_verified,
+                is_pseudonym: false,
+            })
+        }).collect())
+    }
+
+    /// 批量获取笔名作者，按不带表名的笔名 ID 返回；已删除的笔名不在结果中
+    pub async fn get_pseudonym_authors(&self, pseudonym_ids: &[String]) -> Result<HashMap<String, AuthorInfo>> {
+        if pseudonym_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let ids: Vec<&str> = pseudonym_ids.iter().map(|id| bare_id("pseudonym", id)).collect();
+        let pseudonyms: Vec<Pseudonym> = self.db
+            .prepare("SELECT * FROM pseudonym WHERE meta::id(id) INSIDE $ids")
+            .bind("ids", &ids)
+            .fetch()
+            .await?;
+
+        Ok(pseudonyms.into_iter()
+            .map(|p| (bare_id("pseudonym", &p.id).to_string(), p.author_info()))
+            .collect())
+    }
+
+    /// 批量获取文章标签，按不带表名的文章 ID 分组，每组按名称排序
+    pub async fn get_tags_for_articles(&self, article_ids: &[String]) -> Result<HashMap<String, Vec<TagInfo>>> {
+        if article_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let ids: Vec<&str> = article_ids.iter().map(|id| bare_id("article", id)).collect();
+        let rows: Vec<ArticleTagRow> = self.db
+            .prepare(
+                r#"
+                SELECT meta::id(article_id) AS article_id, type::string(tag_id) AS tag_id,
+                    tag_id.name AS name, tag_id.slug AS slug
+                FROM article_tag
+                WHERE meta::id(article_id) INSIDE $ids
+                "#,
+            )
+            .bind("ids", &ids)
+            .fetch()
+            .await?;
+
+        let mut tags: HashMap<String, Vec<TagInfo>> = HashMap::new();
+        for row in rows {
+            // 标签已被删除的关联
+            let (Some(name), Some(slug)) = (row.name, row.slug) else {
+                continue;
+            };
+            tags.entry(row.article_id).or_default().push(TagInfo { id: row.tag_id, name, slug });
+        }
+        for list in tags.values_mut() {
+            list.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        Ok(tags)
+    }
+
+    /// 批量获取出版物信息，按不带表名的出版物 ID 返回
+    pub async fn get_publication_infos(&self, publication_ids: &[String]) -> Result<HashMap<String, PublicationInfo>> {
+        if publication_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let ids: Vec<&str> = publication_ids.iter().map(|id| bare_id("publication", id)).collect();
+        let publications: Vec<PublicationInfo> = self.db
+            .prepare("SELECT type::string(id) AS id, name, slug, logo_url FROM publication WHERE meta::id(id) INSIDE $ids")
+            .bind("ids", &ids)
+            .fetch()
+            .await?;
+
+        Ok(publications.into_iter()
+            .map(|p| (PublicationId::new(&p.id).as_str().to_string(), p))
+            .collect())
+    }
+
     /// 获取文章出版物信息
     async fn get_article_publication(&self, publication_id: &str) -> Result<Option<PublicationInfo>> {
         debug!("Getting publication info for: {}", publication_id);
@@ -1057,67 +2147,119 @@ impl ArticleService {
         let query = r#"
             SELECT id, name, slug, logo_url 
             FROM publication 
-            WHERE id = $publication_id
+            WHERE id = type::thing('publication', $publication_id)
         "#;
 
         let mut response = self.db.query_with_params(query, json!({
-            "publication_id": publication_id
+            "publication_id": PublicationId::new(publication_id).as_str()
         })).await?;
 
         let publications: Vec<PublicationInfo> = response.take(0)?;
         Ok(publications.into_iter().next())
     }
 
-    /// 获取文章系列信息
-    async fn get_article_series(&self, series_id: &str, article_id: &str) -> Result<Option<SeriesInfo>> {
+    /// 获取文章系列信息，包括系列中的上一篇、下一篇和登录读者的完成度
+    async fn get_article_series(&self, series_id: &str, article_id: &str, viewer_user_id: Option<&str>) -> Result<Option<SeriesInfo>> {
         debug!("Getting series info for: {}", series_id);
 
-        // First get the series
-        let series_query = r#"
-            SELECT id, title, slug FROM series WHERE id = $series_id LIMIT 1
-        "#;
+        #[derive(serde::Deserialize)]
+        struct SeriesMeta {
+            id: String,
+            title: String,
+            slug: String,
+        }
 
-        let mut response = self.db.query_with_params(series_query, json!({
-            "series_id": series_id
-        })).await?;
+        #[derive(serde::Deserialize)]
+        struct SeriesEntry {
+            article_id: String,
+            order_index: i32,
+        }
 
-        let series_data: Vec<Value> = response.take(0)?;
-        if let Some(series) = series_data.into_iter().next() {
-            // Then get the order from series_article
-            let order_query = r#"
-                SELECT `order` FROM series_article 
-                WHERE series_id = $series_id AND article_id = $article_id
-                LIMIT 1
-            "#;
-            
-            let mut order_response = self.db.query_with_params(order_query, json!({
-                "series_id": series_id,
-                "article_id": article_id
-            })).await?;
-            
-            let order_data: Vec<Value> = order_response.take(0)?;
-            let order = order_data.into_iter().next()
-                .and_then(|v| v.get("order").and_then(|o| o.as_i64()))
-                .unwrap_or(0) as i32;
-            
-            Ok(Some(SeriesInfo {
-                id: series.get("id")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or_default()
-                    .to_string(),
-                title: series.get("title")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or_default()
-                    .to_string(),
-                slug: series.get("slug")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or_default()
-                    .to_string(),
-                order,
-            }))
-        } else {
-            Ok(None)
+        #[derive(serde::Deserialize)]
+        struct SeriesArticleRow {
+            id: String,
+            title: String,
+            slug: String,
+            published: bool,
         }
+
+        let series_id = bare_id("series", series_id);
+        let series: Option<SeriesMeta> = self.db
+            .prepare("SELECT meta::id(id) AS id, title, slug FROM type::thing('series', $series_id)")
+            .bind("series_id", series_id)
+            .fetch_one()
+            .await?;
+        let Some(series) = series else {
+            return Ok(None);
+        };
+
+        let entries: Vec<SeriesEntry> = self.db
+            .prepare("SELECT article_id, order_index, added_at FROM series_article WHERE series_id = $series_id ORDER BY order_index ASC, added_at ASC")
+            .bind("series_id", series_id)
+            .fetch()
+            .await?;
+        let ids: Vec<&str> = entries.iter().map(|e| bare_id("article", &e.article_id)).collect();
+        let current_id = bare_id("article", article_id);
+
+        // 读者只看到已发布的文章；作者预览草稿时草稿本身也保留在序列中以确定位置
+        let rows: Vec<SeriesArticleRow> = self.db
+            .prepare(
+                r#"
+                SELECT meta::id(id) AS id, title, slug, status = 'published' AS published FROM article
+                WHERE meta::id(id) INSIDE $ids
+                    AND is_deleted = false
+                    AND (status = 'published' OR meta::id(id) = $current_id)
+                "#,
+            )
+            .bind("ids", &ids)
+            .bind("current_id", current_id)
+            .fetch()
+            .await?;
+        let mut by_id: HashMap<String, SeriesArticleRow> = rows.into_iter().map(|row| (row.id.clone(), row)).collect();
+        let mut ordered: Vec<SeriesNavItem> = Vec::with_capacity(by_id.len());
+        let mut published: Vec<&str> = Vec::with_capacity(by_id.len());
+        for (entry, id) in entries.iter().zip(&ids) {
+            if let Some(row) = by_id.remove(*id) {
+                if row.published {
+                    published.push(*id);
+                }
+                ordered.push(SeriesNavItem {
+                    id: row.id,
+                    title: row.title,
+                    slug: row.slug,
+                    order_index: entry.order_index,
+                });
+            }
+        }
+
+        let position = ordered.iter().position(|a| a.id == current_id);
+        let order = position.map(|p| ordered[p].order_index).unwrap_or_default();
+        let previous = position.and_then(|p| p.checked_sub(1)).map(|p| ordered[p].clone());
+        let next = position.and_then(|p| ordered.get(p + 1)).cloned();
+
+        let completion = match viewer_user_id {
+            Some(user_id) => {
+                let completed: Vec<String> = self.db
+                    .prepare("SELECT VALUE article_id FROM reading_progress WHERE user_id = $user_id AND completed = true AND article_id INSIDE $ids")
+                    .bind("user_id", user_id)
+                    .bind("ids", &published)
+                    .fetch()
+                    .await?;
+                Some(completion_percentage(completed.len(), published.len()))
+            }
+            None => None,
+        };
+
+        Ok(Some(SeriesInfo {
+            id: series.id,
+            title: series.title,
+            slug: series.slug,
+            order,
+            total_articles: published.len(),
+            previous,
+            next,
+            completion_percentage: completion,
+        }))
     }
 
     /// 检查用户是否收藏了文章
@@ -1146,12 +2288,12 @@ impl ArticleService {
     async fn is_article_clapped(&self, article_id: &str, user_id: &str) -> Result<bool> {
         let query = r#"
             SELECT count() as count 
-            FROM clap 
-            WHERE article_id = $article_id AND user_id = $user_id
+            FROM reaction 
+            WHERE article_id = type::thing('article', $article_id) AND user_id = $user_id AND reaction_type = 'clap'
         "#;
 
         let mut response = self.db.query_with_params(query, json!({
-            "article_id": article_id,
+            "article_id": ArticleId::new(article_id).as_str(),
             "user_id": user_id
         })).await?;
 
@@ -1168,13 +2310,13 @@ impl ArticleService {
     async fn get_user_clap_count(&self, article_id: &str, user_id: &str) -> Result<i32> {
         let query = r#"
             SELECT count 
-            FROM clap 
-            WHERE article_id = $article_id AND user_id = $user_id
+            FROM reaction 
+            WHERE article_id = type::thing('article', $article_id) AND user_id = $user_id AND reaction_type = 'clap'
             LIMIT 1
         "#;
 
         let mut response = self.db.query_with_params(query, json!({
-            "article_id": article_id,
+            "article_id": ArticleId::new(article_id).as_str(),
             "user_id": user_id
         })).await?;
 
@@ -1187,6 +2329,16 @@ impl ArticleService {
         Ok(count)
     }
 
+    /// 获取用户对文章的所有反应
+    async fn get_user_reactions(&self, article_id: &str, user_id: &str) -> Result<Vec<ReactionType>> {
+        self.db
+            .prepare("SELECT VALUE reaction_type FROM reaction WHERE article_id = type::thing('article', $article_id) AND user_id = $user_id")
+            .bind("article_id", ArticleId::new(article_id).as_str())
+            .bind("user_id", user_id)
+            .fetch()
+            .await
+    }
+
     /// 为文章添加点赞
     pub async fn clap_article(&self, article_id: &str, user_id: &str, count: i32) -> Result<crate::models::clap::ClapResponse> {
         debug!("User {} clapping article {} with count {}", user_id, article_id, count);
@@ -1206,95 +2358,39 @@ impl ArticleService {
             return Err(AppError::forbidden("Cannot clap unpublished articles"));
         }
 
-        // 获取用户现有的点赞
-        let query = format!(r#"
-            SELECT meta::tb(id) as tb, meta::id(id) as id_val, count FROM clap 
-            WHERE user_id = $user_id 
-            AND article_id = article:`{}`
-        "#, article_id);
-        
-        debug!("Querying existing claps with user_id: {} and article_id: {}", user_id, article_id);
-        
-        let mut response = self.db
-            .query_with_params(&query, json!({
-                "user_id": user_id
-            }))
-            .await
-            .map_err(|e| {
-                error!("Failed to query existing claps: {:?}", e);
-                e
-            })?;
-        let clap_data: Vec<Value> = response.take(0)?;
-        let existing_clap = clap_data.into_iter().next();
-
-        let user_clap_count = if let Some(clap_value) = existing_clap {
-            // 获取现有点赞数
-            let current_count = clap_value.get("count")
-                .and_then(|v| v.as_i64())
-                .unwrap_or(0) as i32;
-            
-            // 检查总数是否超过50
-            let new_total = current_count + count;
-            if new_total > 50 {
-                return Err(AppError::BadRequest(
-                    format!("Maximum claps per article is 50. You have {} claps already.", current_count)
-                ));
-            }
-
-            // 获取点赞ID - 使用meta函数返回的值
-            let tb = clap_value.get("tb")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| AppError::internal("Missing table name"))?;
-            let id_val = clap_value.get("id_val")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| AppError::internal("Missing ID value"))?;
-            
-            let clap_id = format!("{}:{}", tb, id_val);
-            debug!("Updating clap with ID: {}", clap_id);
-
-            // 更新现有点赞 - 使用反引号包裹ID
-            let update_query = format!(
-                "UPDATE clap:`{}` SET count = $count, updated_at = time::now() RETURN count",
-                id_val
-            );
-            
-            let mut update_response = self.db.query_with_params(&update_query, json!({
-                "count": new_total
-            })).await?;
-            
-            let result: Vec<Value> = update_response.take(0)?;
-            result.into_iter().next()
-                .and_then(|v| v.get("count").and_then(|c| c.as_i64()))
-                .unwrap_or(new_total as i64) as i32
-        } else {
-            // 创建新点赞
-            if count > 50 {
-                return Err(AppError::BadRequest("Maximum claps per article is 50".to_string()));
-            }
+        // 点赞是可以累加的反应，每人每篇最多 50 次
+        let existing: Option<i64> = self.db
+            .prepare("SELECT VALUE count FROM type::thing('reaction', [$article_id, $user_id, 'clap'])")
+            .bind("article_id", ArticleId::new(article_id).as_str())
+            .bind("user_id", user_id)
+            .fetch_one()
+            .await?;
+        let current_count = existing.unwrap_or(0) as i32;
+        if current_count + count > 50 {
+            return Err(AppError::BadRequest(
+                format!("Maximum claps per article is 50. You have {} claps already.", current_count)
+            ));
+        }
 
-            // 使用 SQL 创建点赞记录，article_id 使用 record 类型
-            let clap_id = Uuid::new_v4().to_string();
-            let create_query = format!(r#"
-                CREATE clap:`{}` CONTENT {{
+        let updated: Option<i64> = self.db
+            .prepare(
+                r#"
+                UPSERT type::thing('reaction', [$article_id, $user_id, 'clap']) MERGE {
                     user_id: $user_id,
-                    article_id: article:`{}`,
-                    count: $count,
-                    created_at: time::now(),
+                    article_id: type::thing('article', $article_id),
+                    reaction_type: 'clap',
+                    count: (count ?? 0) + $count,
+                    created_at: created_at ?? time::now(),
                     updated_at: time::now()
-                }}
-            "#, clap_id, article_id);
-            
-            let mut create_response = self.db.query_with_params(&create_query, json!({
-                "user_id": user_id,
-                "count": count
-            })).await?;
-            
-            // 检查创建是否成功
-            let created_results: Vec<Value> = create_response.take(0)?;
-            debug!("Created clap results: {:?}", created_results);
-            
-            count
-        };
+                } RETURN VALUE count
+                "#,
+            )
+            .bind("article_id", ArticleId::new(article_id).as_str())
+            .bind("user_id", user_id)
+            .bind("count", count)
+            .fetch_one()
+            .await?;
+        let user_clap_count = updated.unwrap_or((current_count + count) as i64) as i32;
 
         // 更新文章总点赞数
         debug!("Updating article clap count for article_id: {}", article_id);
@@ -1310,50 +2406,105 @@ impl ArticleService {
         })
     }
 
-    /// 更新文章的总点赞数
+    /// 更新文章的总点赞数和加权点赞分
     async fn update_article_clap_count(&self, article_id: &str) -> Result<()> {
-        // 获取所有点赞记录的count值
-        let count_query = format!(
-            "SELECT count FROM clap WHERE article_id = article:`{}`",
-            article_id
-        );
-        
+        let record_id = ArticleId::new(article_id);
+
+        // 获取所有点赞记录
         debug!("Getting all clap counts for article: {}", article_id);
         
-        let mut count_response = self.db.query(&count_query).await?;
-        let clap_records: Vec<Value> = count_response.take(0)?;
+        let clap_records: Vec<Value> = self.db
+            .prepare("SELECT user_id, count FROM reaction WHERE article_id = type::thing('article', $id) AND reaction_type = 'clap'")
+            .bind("id", record_id.as_str())
+            .fetch()
+            .await?;
         
         // 在应用层计算总和
         let total_claps: i64 = clap_records.iter()
             .filter_map(|v| v.get("count"))
             .filter_map(|v| v.as_i64())
             .sum();
+
+        let weighted_score = self.calculate_weighted_clap_score(article_id, &clap_records).await?;
         
-        debug!("Total claps calculated for article {}: {}", article_id, total_claps);
+        debug!("Total claps calculated for article {}: {} (weighted {})", article_id, total_claps, weighted_score);
         
         // 更新文章的点赞数
-        let update_query = format!(
-            "UPDATE article:`{}` SET clap_count = {}",
-            article_id, total_claps
-        );
-        
-        debug!("Updating article clap_count with query: {}", update_query);
-        
-        self.db.query(&update_query).await?;
+        self.db
+            .prepare("UPDATE type::thing('article', $id) SET clap_count = $clap_count, reaction_counts.clap = $clap_count, weighted_clap_score = $weighted_score")
+            .bind("id", record_id.as_str())
+            .bind("clap_count", total_claps)
+            .bind("weighted_score", weighted_score)
+            .execute()
+            .await?;
         
         info!("Successfully updated article {} clap_count to {}", article_id, total_claps);
 
         Ok(())
     }
 
-    /// 获取文章的总点赞数
-    async fn get_article_total_claps(&self, article_id: &str) -> Result<i64> {
-        let query = format!("SELECT clap_count FROM article:`{}`", article_id);
+    /// 根据点赞用户的账号年龄和信誉计算加权点赞分
+    async fn calculate_weighted_clap_score(&self, article_id: &str, clap_records: &[Value]) -> Result<f64> {
+        if clap_records.is_empty() {
+            return Ok(0.0);
+        }
 
-        let mut response = self.db.query(&query).await?;
+        let author_id = self.get_article_by_id(article_id).await?
+            .map(|a| a.author_id)
+            .unwrap_or_default();
 
-        let result: Vec<serde_json::Value> = response.take(0)?;
-        let count = result.first()
+        let user_ids: Vec<&str> = clap_records.iter()
+            .filter_map(|v| v.get("user_id").and_then(|u| u.as_str()))
+            .collect();
+        let mut response = self.db.query_with_params(
+            "SELECT user_id, created_at, reputation_score, reputation_updated_at FROM user_profile WHERE user_id INSIDE $user_ids",
+            json!({ "user_ids": user_ids }),
+        ).await?;
+        let profiles: Vec<Value> = response.take(0)?;
+
+        let now = Utc::now();
+        let account_ages: HashMap<String, i64> = profiles.iter()
+            .filter_map(|p| {
+                let user_id = p.get("user_id")?.as_str()?;
+                let created_at = p.get("created_at")?.as_str()?
+                    .parse::<chrono::DateTime<Utc>>().ok()?;
+                Some((user_id.to_string(), (now - created_at).num_days()))
+            })
+            .collect();
+        // 尚未计算过信誉的用户不参与信誉加权
+        let reputations: HashMap<String, f64> = profiles.iter()
+            .filter(|p| p.get("reputation_updated_at").map_or(false, |v| !v.is_null()))
+            .filter_map(|p| {
+                let user_id = p.get("user_id")?.as_str()?;
+                let score = p.get("reputation_score")?.as_i64()?;
+                Some((user_id.to_string(), normalized_reputation(score as i32)))
+            })
+            .collect();
+
+        let signals: Vec<ClapperSignal> = clap_records.iter()
+            .filter_map(|record| {
+                let user_id = record.get("user_id")?.as_str()?;
+                Some(ClapperSignal {
+                    clap_count: record.get("count")?.as_i64()? as i32,
+                    // 找不到资料的用户按新账号处理
+                    account_age_days: account_ages.get(user_id).copied().unwrap_or(0),
+                    reputation: reputations.get(user_id).copied(),
+                    is_author: user_id == author_id,
+                })
+            })
+            .collect();
+
+        Ok(weighted_clap_score(&signals))
+    }
+
+    /// 获取文章的总点赞数
+    async fn get_article_total_claps(&self, article_id: &str) -> Result<i64> {
+        let result: Option<Value> = self.db
+            .prepare("SELECT clap_count FROM type::thing('article', $id)")
+            .bind("id", ArticleId::new(article_id).as_str())
+            .fetch_one()
+            .await?;
+        let count = result.as_ref()
             .and_then(|v| v.get("clap_count"))
             .and_then(|v| v.as_i64())
             .unwrap_or(0);
@@ -1362,6 +2513,38 @@ impl ArticleService {
     }
 
     /// 获取出版物的文章列表
+    /// 获取订阅源所需的最近发布文章（包含正文），按发布时间倒序
+    pub async fn get_feed_articles(
+        &self,
+        author_id: Option<&str>,
+        publication_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<Article>> {
+        let mut conditions = vec![
+            "status = 'published'".to_string(),
+            "is_deleted = false".to_string(),
+        ];
+        if author_id.is_some() {
+            conditions.push("author_id = $author_id".to_string());
+            conditions.push("pseudonym_id IS NONE".to_string());
+        }
+        if publication_id.is_some() {
+            conditions.push("publication_id = $publication_id".to_string());
+        }
+
+        let query = format!(
+            "SELECT * FROM article WHERE {} ORDER BY published_at DESC LIMIT $limit",
+            conditions.join(" AND ")
+        );
+        let mut response = self.db.query_with_params(&query, json!({
+            "author_id": author_id,
+            "publication_id": publication_id,
+            "limit": limit,
+        })).await?;
+
+        Ok(response.take(0)?)
+    }
+
     pub async fn get_articles_by_publication(
         &self, 
         publication_id: &str, 
@@ -1398,10 +2581,10 @@ impl ArticleService {
                 id, title, subtitle, slug, excerpt, cover_image_url,
                 author_id, publication_id, reading_time, 
                 view_count, clap_count, comment_count,
-                created_at, published_at
+                created_at, published_at, publication_pinned_at
             FROM article 
             WHERE {}
-            ORDER BY published_at DESC
+            ORDER BY publication_pinned_at DESC, published_at DESC
             LIMIT $limit START $offset
         "#, where_clause);
         
@@ -1486,7 +2669,8 @@ impl ArticleService {
         &self,
         publication_id: &str,
         slug: &str,
-        viewer_user_id: Option<&str>
+        viewer_user_id: Option<&str>,
+        meter: Option<&MeterReader>,
     ) -> Result<Option<ArticleResponse>> {
         debug!("Getting article by slug {} in publication {}", slug, publication_id);
         
@@ -1502,10 +2686,28 @@ impl ArticleService {
         }
         
         // 获取完整的文章信息
-        self.get_article_with_details(slug, viewer_user_id).await
+        self.get_article_with_details(slug, viewer_user_id, meter).await
     }
     
-    /// 获取出版物中的相关文章
+    /// 内容相近的文章，按向量相似度排序。未启用向量或文章还没有向量时返回空列表
+    pub async fn get_related_articles(&self, article_id: &str, limit: usize) -> Result<Vec<RecommendedArticle>> {
+        let similar = self.embedding_service.similar_articles(article_id, None, limit).await?;
+
+        let mut related = Vec::with_capacity(similar.len());
+        for item in similar {
+            let Some(article) = self.get_article_by_id(&item.article_id).await? else {
+                continue;
+            };
+            related.push(RecommendedArticle {
+                article: self.article_to_list_item(&article).await?,
+                score: item.similarity,
+                reason: "内容相似".to_string(),
+            });
+        }
+        Ok(related)
+    }
+
+    /// 获取出版物中的相关文章，有向量时按内容相似度，否则按共同标签
     pub async fn get_related_articles_in_publication(
         &self,
         publication_id: &str,
@@ -1513,6 +2715,17 @@ impl ArticleService {
         limit: usize
     ) -> Result<Vec<ArticleListItem>> {
         debug!("Getting related articles for {} in publication {}", article_id, publication_id);
+
+        let similar = self.embedding_service.similar_articles(article_id, Some(publication_id), limit).await?;
+        if !similar.is_empty() {
+            let mut related = Vec::with_capacity(similar.len());
+            for item in similar {
+                if let Some(article) = self.get_article_by_id(&item.article_id).await? {
+                    related.push(self.article_to_list_item(&article).await?);
+                }
+            }
+            return Ok(related);
+        }
         
         // 获取当前文章的标签
         let tags = self.get_article_tags(article_id).await?;
@@ -1551,6 +2764,90 @@ impl ArticleService {
         Ok(response.take(0)?)
     }
     
+    /// 出版物中指定的已发布文章，按 `article_ids` 的顺序；不存在、未发布或不属于该出版物的跳过
+    pub async fn get_publication_articles_by_ids(
+        &self,
+        publication_id: &str,
+        article_ids: &[String],
+    ) -> Result<Vec<ArticleListItem>> {
+        if article_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let ids: Vec<&str> = article_ids.iter().map(|id| bare_id("article", id)).collect();
+
+        let articles: Vec<Article> = self.db
+            .prepare(
+                r#"
+                SELECT * FROM article
+                WHERE publication_id = type::thing('publication', $publication_id)
+                    AND meta::id(id) INSIDE $ids
+                    AND status = 'published'
+                    AND is_deleted = false
+                "#,
+            )
+            .bind("publication_id", bare_id("publication", publication_id))
+            .bind("ids", &ids)
+            .fetch()
+            .await?;
+
+        let mut by_id: HashMap<String, Article> = articles
+            .into_iter()
+            .map(|article| (bare_id("article", &article.id).to_string(), article))
+            .collect();
+        let mut items = Vec::with_capacity(by_id.len());
+        for id in ids {
+            if let Some(article) = by_id.remove(id) {
+                items.push(self.article_to_list_item(&article).await?);
+            }
+        }
+        Ok(items)
+    }
+
+    /// 出版物的最新文章。`tag` 为标签的 slug、别名或 ID，同时包括其下级标签的文章；
+    /// `exclude` 中的文章不返回
+    pub async fn get_latest_in_publication(
+        &self,
+        publication_id: &str,
+        tag: Option<&str>,
+        exclude: &[String],
+        limit: usize,
+    ) -> Result<Vec<ArticleListItem>> {
+        let tag_filter = if tag.is_some() {
+            "AND id INSIDE (SELECT VALUE article_id FROM article_tag WHERE meta::id(tag_id) = $tag_id OR tag_id.parent_id = $tag_id)"
+        } else {
+            ""
+        };
+        let query = format!(
+            r#"
+            LET $tag_id = (SELECT VALUE meta::id(id) FROM tag WHERE slug = $tag OR $tag INSIDE aliases OR meta::id(id) = $tag LIMIT 1)[0];
+            SELECT * FROM article
+            WHERE publication_id = type::thing('publication', $publication_id)
+                AND status = 'published'
+                AND is_deleted = false
+                AND meta::id(id) NOTINSIDE $exclude
+                {}
+            ORDER BY published_at DESC
+            LIMIT $limit
+            "#,
+            tag_filter
+        );
+        let exclude: Vec<&str> = exclude.iter().map(|id| bare_id("article", id)).collect();
+
+        let mut response = self.db.query_with_params(&query, json!({
+            "publication_id": bare_id("publication", publication_id),
+            "tag": tag.map(|t| t.trim().to_lowercase()),
+            "exclude": exclude,
+            "limit": limit,
+        })).await?;
+        let articles: Vec<Article> = response.take(1)?;
+
+        let mut items = Vec::with_capacity(articles.len());
+        for article in &articles {
+            items.push(self.article_to_list_item(article).await?);
+        }
+        Ok(items)
+    }
+
     /// 获取出版物中特定用户的文章数量
     pub async fn count_articles_by_user_in_publication(
         &self,
@@ -1609,105 +2906,64 @@ impl ArticleService {
     
     /// Helper method to convert article data to ArticleListItem
     async fn article_to_list_item(&self, article: &Article) -> Result<ArticleListItem> {
-        // Get author info
-        let author_query = r#"
-            SELECT id, username, display_name, avatar_url, is_verified
-            FROM user_profile
-            WHERE user_id = $author_id
-        "#;
-        
-        let mut author_response = self.db.query_with_params(author_query, json!({
-            "author_id": &article.author_id
-        })).await?;
-        
-        let author_data: Vec<Value> = author_response.take(0)?;
-        let author_info = if let Some(author) = author_data.first() {
-            AuthorInfo {
-                id: author["id"].as_str().unwrap_or("").to_string(),
-                username: author["username"].as_str().unwrap_or("").to_string(),
-                display_name: author["display_name"].as_str().unwrap_or("").to_string(),
-                avatar_url: author["avatar_url"].as_str().map(String::from),
-                is_verified: author["is_verified"].as_bool().unwrap_or(false),
-            }
-        } else {
-            AuthorInfo {
-                id: article.author_id.clone(),
-                username: "unknown".to_string(),
-                display_name: "Unknown Author".to_string(),
-                avatar_url: None,
-                is_verified: false,
-            }
-        };
-        
-        // Get publication info if exists
-        let publication_info = if let Some(pub_id) = &article.publication_id {
-            let pub_query = r#"
-                SELECT id, name, slug, logo_url
-                FROM publication
-                WHERE id = $publication_id
-            "#;
-            
-            let mut pub_response = self.db.query_with_params(pub_query, json!({
-                "publication_id": pub_id
-            })).await?;
-            
-            let pub_data: Vec<Value> = pub_response.take(0)?;
-            pub_data.first().map(|p| PublicationInfo {
-                id: p["id"].as_str().unwrap_or("").to_string(),
-                name: p["name"].as_str().unwrap_or("").to_string(),
-                slug: p["slug"].as_str().unwrap_or("").to_string(),
-                logo_url: p["logo_url"].as_str().map(String::from),
-            })
-        } else {
-            None
-        };
-        
-        // Get tags info - 先获取article_tag关系，再获取tag详情
-        let tag_relations_query = "SELECT tag_id FROM article_tag WHERE article_id = $article_id";
-        
-        let mut tag_rel_response = self.db.query_with_params(tag_relations_query, json!({
-            "article_id": &article.id
-        })).await?;
-        
-        let tag_relations: Vec<Value> = tag_rel_response.take(0)?;
-        let mut tags: Vec<TagInfo> = Vec::new();
-        
-        for rel in tag_relations {
-            if let Some(tag_id) = rel.get("tag_id").and_then(|v| v.as_str()) {
-                // 获取tag详情
-                if let Ok(mut tag_response) = self.db.query(&format!("SELECT * FROM {}", tag_id)).await {
-                    if let Ok(tag_values) = tag_response.take::<Vec<Value>>(0) {
-                        if let Some(tag_value) = tag_values.first() {
-                            tags.push(TagInfo {
-                                id: tag_value.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                                name: tag_value.get("name").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                                slug: tag_value.get("slug").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                            });
-                        }
-                    }
-                }
+        let mut items = self.articles_to_list_items(std::slice::from_ref(article)).await?;
+        Ok(items.remove(0))
+    }
+
+    /// 批量转换为列表项：作者、笔名、标签和出版物各查询一次，顺序与传入的文章一致
+    pub async fn articles_to_list_items(&self, articles: &[Article]) -> Result<Vec<ArticleListItem>> {
+        let user_ids: Vec<String> = articles.iter()
+            .filter(|a| a.pseudonym_id.is_none())
+            .map(|a| a.author_id.clone())
+            .collect();
+        let pseudonym_ids: Vec<String> = articles.iter().filter_map(|a| a.pseudonym_id.clone()).collect();
+        let article_ids: Vec<String> = articles.iter().map(|a| a.id.clone()).collect();
+        let publication_ids: Vec<String> = articles.iter().filter_map(|a| a.publication_id.clone()).collect();
+
+        let (authors, pseudonyms, mut tags, publications) = tokio::try_join!(
+            self.get_authors_by_user_ids(&user_ids),
+            self.get_pseudonym_authors(&pseudonym_ids),
+            self.get_tags_for_articles(&article_ids),
+            self.get_publication_infos(&publication_ids),
+        )?;
+
+        Ok(articles.iter().map(|article| {
+            let author = match &article.pseudonym_id {
+                Some(pseudonym_id) => pseudonyms
+                    .get(bare_id("pseudonym", pseudonym_id))
+                    .cloned()
+                    .unwrap_or_else(AuthorInfo::anonymous),
+                None => authors
+                    .get(&article.author_id)
+                    .cloned()
+                    .unwrap_or_else(|| AuthorInfo::unknown(&article.author_id)),
+            };
+            let publication = article.publication_id.as_ref()
+                .and_then(|id| publications.get(PublicationId::new(id).as_str()).cloned());
+
+            ArticleListItem {
+                id: article.id.clone(),
+                title: article.title.clone(),
+                subtitle: article.subtitle.clone(),
+                slug: article.slug.clone(),
+                excerpt: article.excerpt.clone(),
+                cover_image_url: article.cover_image_url.clone(),
+                author,
+                publication,
+                status: article.status.clone(),
+                is_paid_content: article.is_paid_content,
+                is_featured: article.is_featured,
+                pinned_at: article.pinned_at,
+                publication_pinned_at: article.publication_pinned_at,
+                reading_time: article.reading_time,
+                view_count: article.view_count,
+                clap_count: article.clap_count,
+                comment_count: article.comment_count,
+                tags: tags.remove(bare_id("article", &article.id)).unwrap_or_default(),
+                created_at: article.created_at,
+                published_at: article.published_at,
             }
-        }
-        
-        Ok(ArticleListItem {
-            id: article.id.clone(),
-            title: article.title.clone(),
-            subtitle: article.subtitle.clone(),
-            slug: article.slug.clone(),
-            excerpt: article.excerpt.clone(),
-            cover_image_url: article.cover_image_url.clone(),
-            author: author_info,
-            publication: publication_info,
-            status: article.status.clone(),
-            is_paid_content: article.is_paid_content,
-            is_featured: article.is_featured,
-            reading_time: article.reading_time,
-            view_count: article.view_count,
-            clap_count: article.clap_count,
-            comment_count: article.comment_count,
-            tags,
-            created_at: article.created_at,
-            published_at: article.published_at,
-        })
+        }).collect())
     }
+
 }
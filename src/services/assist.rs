@@ -0,0 +1,87 @@
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tracing::debug;
+
+/// 发送给模型的正文上限（字符），避免超长文章占满上下文
+const MAX_INPUT_CHARS: usize = 6000;
+
+/// 写作辅助服务：对接 OpenAI 兼容的 chat completions 接口，未配置时不启用
+#[derive(Clone)]
+pub struct AssistService {
+    http_client: Client,
+    api_url: Option<String>,
+    api_key: Option<String>,
+    model: String,
+}
+
+impl AssistService {
+    pub async fn new(config: &Config) -> Result<Self> {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(15))
+            .build()?;
+
+        Ok(Self {
+            http_client,
+            api_url: config.assist_api_url.clone().filter(|url| !url.is_empty()),
+            api_key: config.assist_api_key.clone(),
+            model: config.assist_model.clone(),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.api_url.is_some()
+    }
+
+    /// 生成不超过 max_length 个字符的摘要；未启用时返回 None
+    pub async fn summarize(&self, text: &str, max_length: usize) -> Result<Option<String>> {
+        let api_url = match &self.api_url {
+            Some(url) => url,
+            None => return Ok(None),
+        };
+
+        let input: String = text.chars().take(MAX_INPUT_CHARS).collect();
+        let body = json!({
+            "model": self.model,
+            "temperature": 0.3,
+            "messages": [
+                {
+                    "role": "system",
+                    "content": format!(
+                        "You write article summaries for a blog. Reply with a single plain-text summary of at most {} characters, in the same language as the article. No markdown, no quotes, no preamble.",
+                        max_length
+                    )
+                },
+                { "role": "user", "content": input }
+            ]
+        });
+
+        let mut request = self.http_client.post(api_url).json(&body);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(AppError::ExternalService(format!(
+                "Assist API returned status {}",
+                response.status()
+            )));
+        }
+
+        let payload: Value = response.json().await?;
+        let summary = payload["choices"][0]["message"]["content"]
+            .as_str()
+            .map(|s| s.trim().trim_matches('"').trim().to_string())
+            .filter(|s| !s.is_empty());
+
+        debug!("Assist summary generated: {}", summary.is_some());
+
+        // 模型不一定遵守长度限制，超长的结果交给调用方回退处理
+        Ok(summary.filter(|s| s.chars().count() <= max_length))
+    }
+}
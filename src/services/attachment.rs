@@ -0,0 +1,428 @@
+use crate::{
+    config::Config,
+    error::{AppError, ErrorCode, Result},
+    models::{
+        article::Article,
+        attachment::*,
+        id::{bare_id, ArticleId, UserId},
+    },
+    services::{Database, MediaService, SubscriptionService},
+};
+use chrono::{Duration, TimeZone, Utc};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+use validator::Validate;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 下载链接的有效期
+const DOWNLOAD_LINK_TTL_MINUTES: i64 = 10;
+
+/// 每篇文章最多的附件数量
+const MAX_ATTACHMENTS_PER_ARTICLE: usize = 20;
+
+#[derive(Debug, Deserialize)]
+struct AttachmentCountRow {
+    attachment_id: String,
+    count: i64,
+}
+
+/// 文章附件的上传、门槛检查和下载统计。
+/// 文件由 MediaService 保存在公开目录之外，读者通过短期有效的签名链接下载
+#[derive(Clone)]
+pub struct AttachmentService {
+    db: Arc<Database>,
+    media_service: MediaService,
+    subscription_service: SubscriptionService,
+    secret: Arc<Vec<u8>>,
+}
+
+impl AttachmentService {
+    pub async fn new(
+        config: &Config,
+        db: Arc<Database>,
+        media_service: MediaService,
+        subscription_service: SubscriptionService,
+    ) -> Result<Self> {
+        Ok(Self {
+            db,
+            media_service,
+            subscription_service,
+            secret: Arc::new(config.jwt_secret.as_bytes().to_vec()),
+        })
+    }
+
+    pub async fn list(&self, article: &Article) -> Result<Vec<ArticleAttachment>> {
+        self.db
+            .prepare("SELECT * FROM article_attachment WHERE article_id = $article_id ORDER BY created_at ASC")
+            .bind("article_id", ArticleId::new(&article.id).as_str())
+            .fetch()
+            .await
+    }
+
+    /// 读者看到的附件列表，标出哪些附件可以直接下载
+    pub async fn list_for_reader(&self, article: &Article, user_id: Option<&str>) -> Result<Vec<AttachmentListItem>> {
+        let attachments = self.list(article).await?;
+        let mut items = Vec::with_capacity(attachments.len());
+        for attachment in attachments {
+            let accessible = self.has_access(article, &attachment, user_id).await?;
+            items.push(AttachmentListItem { attachment, accessible });
+        }
+        Ok(items)
+    }
+
+    pub async fn get(&self, article: &Article, attachment_id: &str) -> Result<ArticleAttachment> {
+        let attachment: Option<ArticleAttachment> = self.db
+            .get_by_id("article_attachment", bare_id("article_attachment", attachment_id))
+            .await?;
+        attachment
+            .filter(|a| a.article_id == ArticleId::new(&article.id).as_str())
+            .ok_or_else(|| AppError::not_found("Attachment"))
+    }
+
+    /// 保存上传的附件。调用方需要先确认用户是文章作者或合著者
+    pub async fn create(
+        &self,
+        article: &Article,
+        user_id: &str,
+        request: CreateAttachmentRequest,
+        filename: &str,
+        content_type: &str,
+        data: Vec<u8>,
+    ) -> Result<ArticleAttachment> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        if self.list(article).await?.len() >= MAX_ATTACHMENTS_PER_ARTICLE {
+            return Err(AppError::BadRequest(format!(
+                "An article can have at most {} attachments",
+                MAX_ATTACHMENTS_PER_ARTICLE
+            )));
+        }
+
+        let storage_path = self.media_service.store_attachment(content_type, &data).await?;
+        let attachment: Option<ArticleAttachment> = self.db
+            .prepare(
+                r#"
+                CREATE article_attachment CONTENT {
+                    article_id: $article_id,
+                    author_id: $author_id,
+                    uploaded_by: $uploaded_by,
+                    title: $title,
+                    description: $description ?? NONE,
+                    original_filename: $original_filename,
+                    content_type: $content_type,
+                    size: $size,
+                    storage_path: $storage_path,
+                    gate: $gate,
+                    download_count: 0,
+                    created_at: time::now()
+                }
+                "#,
+            )
+            .bind("article_id", ArticleId::new(&article.id).as_str())
+            .bind("author_id", UserId::new(&article.author_id).as_str())
+            .bind("uploaded_by", UserId::new(user_id).as_str())
+            .bind("title", request.title.trim())
+            .bind("description", &request.description)
+            .bind("original_filename", sanitize_filename(filename))
+            .bind("content_type", content_type)
+            .bind("size", data.len() as i64)
+            .bind("storage_path", &storage_path)
+            .bind("gate", request.gate.as_str())
+            .fetch_one()
+            .await?;
+
+        match attachment {
+            Some(attachment) => {
+                info!("Attached {} to article {}", attachment.id, article.id);
+                Ok(attachment)
+            }
+            None => {
+                self.media_service.delete_attachment(&storage_path).await;
+                Err(AppError::internal("Failed to save attachment"))
+            }
+        }
+    }
+
+    pub async fn update(&self, article: &Article, attachment_id: &str, request: UpdateAttachmentRequest) -> Result<ArticleAttachment> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let mut attachment = self.get(article, attachment_id).await?;
+        if let Some(title) = request.title {
+            attachment.title = title.trim().to_string();
+        }
+        if let Some(description) = request.description {
+            attachment.description = Some(description).filter(|d| !d.trim().is_empty());
+        }
+        if let Some(gate) = request.gate {
+            attachment.gate = gate;
+        }
+
+        let updated: Option<ArticleAttachment> = self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('article_attachment', $attachment_id) SET
+                    title = $title,
+                    description = $description ?? NONE,
+                    gate = $gate
+                "#,
+            )
+            .bind("attachment_id", bare_id("article_attachment", &attachment.id))
+            .bind("title", &attachment.title)
+            .bind("description", &attachment.description)
+            .bind("gate", attachment.gate.as_str())
+            .fetch_one()
+            .await?;
+
+        updated.ok_or_else(|| AppError::internal("Failed to update attachment"))
+    }
+
+    pub async fn delete(&self, article: &Article, attachment_id: &str) -> Result<()> {
+        let attachment = self.get(article, attachment_id).await?;
+        self.db
+            .prepare("DELETE type::thing('article_attachment', $attachment_id)")
+            .bind("attachment_id", bare_id("article_attachment", &attachment.id))
+            .execute()
+            .await?;
+        self.media_service.delete_attachment(&attachment.storage_path).await;
+
+        info!("Deleted attachment {} from article {}", attachment.id, article.id);
+        Ok(())
+    }
+
+    /// 读者现在能否下载；邮箱门槛对已经留过邮箱的登录用户视为已满足
+    async fn has_access(&self, article: &Article, attachment: &ArticleAttachment, user_id: Option<&str>) -> Result<bool> {
+        if attachment.gate == AttachmentGate::Public {
+            return Ok(true);
+        }
+        let Some(user_id) = user_id.map(UserId::new) else {
+            return Ok(false);
+        };
+        if user_id.as_str() == UserId::new(&article.author_id).as_str() {
+            return Ok(true);
+        }
+
+        match attachment.gate {
+            AttachmentGate::Public => Ok(true),
+            AttachmentGate::Subscribers => Ok(self.subscription_service
+                .check_subscription(user_id.as_str(), &article.author_id)
+                .await?
+                .can_access_paid_content),
+            AttachmentGate::Email => {
+                let leads: Vec<AttachmentLead> = self.db
+                    .prepare("SELECT * FROM attachment_lead WHERE attachment_id = $attachment_id AND user_id = $user_id LIMIT 1")
+                    .bind("attachment_id", bare_id("article_attachment", &attachment.id))
+                    .bind("user_id", user_id.as_str())
+                    .fetch()
+                    .await?;
+                Ok(!leads.is_empty())
+            }
+        }
+    }
+
+    /// 检查附件门槛并签发下载链接。邮箱门槛会记录读者留下的邮箱
+    pub async fn request_download(
+        &self,
+        article: &Article,
+        attachment_id: &str,
+        user: Option<(&str, &str)>,
+        request: AttachmentAccessRequest,
+    ) -> Result<AttachmentDownloadLink> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let attachment = self.get(article, attachment_id).await?;
+        let user_id = user.map(|(id, _)| id);
+
+        if !self.has_access(article, &attachment, user_id).await? {
+            match attachment.gate {
+                AttachmentGate::Subscribers => {
+                    return Err(AppError::coded(ErrorCode::PaidSubscriptionRequired, "This attachment is available to paid subscribers only"));
+                }
+                AttachmentGate::Email => {
+                    let email = request.email
+                        .or_else(|| user.map(|(_, email)| email.to_string()))
+                        .ok_or_else(|| AppError::BadRequest("An email address is required to download this attachment".to_string()))?;
+                    self.record_lead(&attachment, &email, user_id).await?;
+                }
+                AttachmentGate::Public => {}
+            }
+        }
+
+        let expires_at = Utc::now() + Duration::minutes(DOWNLOAD_LINK_TTL_MINUTES);
+        let token = self.sign(bare_id("article_attachment", &attachment.id), expires_at.timestamp());
+
+        Ok(AttachmentDownloadLink {
+            download_url: format!("/api/blog/attachments/download/{}", token),
+            expires_at,
+        })
+    }
+
+    /// 同一邮箱重复下载只保留一条记录，created_at 为第一次留下邮箱的时间
+    async fn record_lead(&self, attachment: &ArticleAttachment, email: &str, user_id: Option<&str>) -> Result<()> {
+        let email = email.trim().to_lowercase();
+        self.db
+            .prepare(
+                r#"
+                UPSERT type::thing('attachment_lead', [$attachment_id, $email]) MERGE {
+                    attachment_id: $attachment_id,
+                    article_id: $article_id,
+                    author_id: $author_id,
+                    email: $email,
+                    user_id: $user_id ?? NONE
+                }
+                "#,
+            )
+            .bind("attachment_id", bare_id("article_attachment", &attachment.id))
+            .bind("article_id", &attachment.article_id)
+            .bind("author_id", &attachment.author_id)
+            .bind("email", &email)
+            .bind("user_id", user_id.map(|id| UserId::new(id).as_str().to_string()))
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 文章附件收集到的邮箱，按时间倒序
+    pub async fn list_leads(&self, article: &Article) -> Result<Vec<AttachmentLead>> {
+        self.db
+            .prepare("SELECT * FROM attachment_lead WHERE article_id = $article_id ORDER BY created_at DESC")
+            .bind("article_id", ArticleId::new(&article.id).as_str())
+            .fetch()
+            .await
+    }
+
+    /// 校验下载链接并读取附件，同时计入下载次数
+    pub async fn download(&self, token: &str, user_id: Option<&str>) -> Result<(ArticleAttachment, Vec<u8>)> {
+        let attachment_id = self.verify(token)
+            .ok_or_else(|| AppError::forbidden("Download link is invalid or has expired"))?;
+        let attachment: ArticleAttachment = self.db
+            .get_by_id("article_attachment", &attachment_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Attachment"))?;
+
+        let data = self.media_service.read_attachment(&attachment.storage_path).await?;
+
+        if let Err(e) = self.record_download(&attachment, user_id).await {
+            warn!("Failed to record download of attachment {}: {}", attachment.id, e);
+        }
+        Ok((attachment, data))
+    }
+
+    async fn record_download(&self, attachment: &ArticleAttachment, user_id: Option<&str>) -> Result<()> {
+        self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('article_attachment', $attachment_id) SET download_count += 1;
+                CREATE attachment_download CONTENT {
+                    attachment_id: $attachment_id,
+                    article_id: $article_id,
+                    author_id: $author_id,
+                    user_id: $user_id ?? NONE,
+                    created_at: time::now()
+                };
+                "#,
+            )
+            .bind("attachment_id", bare_id("article_attachment", &attachment.id))
+            .bind("article_id", &attachment.article_id)
+            .bind("author_id", &attachment.author_id)
+            .bind("user_id", user_id.map(|id| UserId::new(id).as_str().to_string()))
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 作者所有附件的下载次数和留下的邮箱数
+    pub async fn get_analytics(&self, user_id: &str, query: AttachmentAnalyticsQuery) -> Result<AttachmentAnalytics> {
+        let days = query.days.unwrap_or(30).clamp(1, 365);
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT * FROM article_attachment WHERE author_id = $user_id ORDER BY download_count DESC;
+                SELECT attachment_id, count() AS count FROM attachment_download
+                    WHERE author_id = $user_id AND created_at >= $since
+                    GROUP BY attachment_id;
+                SELECT attachment_id, count() AS count FROM attachment_lead
+                    WHERE author_id = $user_id AND created_at >= $since
+                    GROUP BY attachment_id;
+            "#,
+            json!({
+                "user_id": UserId::new(user_id).as_str(),
+                "since": Utc::now() - Duration::days(days),
+            }),
+        ).await?;
+        let attachments: Vec<ArticleAttachment> = response.take(0)?;
+        let downloads: Vec<AttachmentCountRow> = response.take(1)?;
+        let leads: Vec<AttachmentCountRow> = response.take(2)?;
+
+        let downloads: HashMap<String, i64> = downloads.into_iter().map(|r| (r.attachment_id, r.count)).collect();
+        let leads: HashMap<String, i64> = leads.into_iter().map(|r| (r.attachment_id, r.count)).collect();
+
+        let attachments: Vec<AttachmentDownloadStats> = attachments
+            .into_iter()
+            .map(|attachment| {
+                let key = bare_id("article_attachment", &attachment.id).to_string();
+                AttachmentDownloadStats {
+                    downloads: downloads.get(&key).copied().unwrap_or(0),
+                    leads: leads.get(&key).copied().unwrap_or(0),
+                    attachment_id: key,
+                    article_id: attachment.article_id,
+                    title: attachment.title,
+                    gate: attachment.gate,
+                    total_downloads: attachment.download_count,
+                }
+            })
+            .collect();
+
+        Ok(AttachmentAnalytics {
+            days,
+            total_downloads: attachments.iter().map(|a| a.downloads).sum(),
+            total_leads: attachments.iter().map(|a| a.leads).sum(),
+            attachments,
+        })
+    }
+
+    /// 下载令牌格式为 `{附件 ID}.{过期时间}.{签名}`
+    fn sign(&self, attachment_id: &str, expires: i64) -> String {
+        let signature = hex::encode(self.mac(attachment_id, expires).finalize().into_bytes());
+        format!("{}.{}.{}", attachment_id, expires, signature)
+    }
+
+    fn verify(&self, token: &str) -> Option<String> {
+        let mut parts = token.rsplitn(3, '.');
+        let (signature, expires, attachment_id) = (parts.next()?, parts.next()?, parts.next()?);
+        let expires: i64 = expires.parse().ok()?;
+        let signature = hex::decode(signature).ok()?;
+
+        let expires_at = Utc.timestamp_opt(expires, 0).single()?;
+        if expires_at < Utc::now() {
+            return None;
+        }
+        self.mac(attachment_id, expires).verify_slice(&signature).ok()?;
+        Some(attachment_id.to_string())
+    }
+
+    fn mac(&self, attachment_id: &str, expires: i64) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.secret).expect("HMAC can take key of any size");
+        mac.update(format!("attachment|{}|{}", attachment_id, expires).as_bytes());
+        mac
+    }
+}
+
+/// 下载时作为文件名返回，去掉路径和控制字符
+fn sanitize_filename(filename: &str) -> String {
+    let name = filename.rsplit(['/', '\\']).next().unwrap_or_default();
+    let name: String = name
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"')
+        .take(255)
+        .collect();
+    if name.trim().is_empty() {
+        "attachment".to_string()
+    } else {
+        name.trim().to_string()
+    }
+}
@@ -13,7 +13,8 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::collections::HashMap;
 use tokio::sync::RwLock;
-use chrono::{DateTime, Utc, Duration};
+use chrono::{DateTime, Utc, Duration, TimeZone};
+use sha2::{Digest, Sha256};
 use tracing::{info, warn, error, debug};
 
 #[derive(Clone)]
@@ -36,6 +37,14 @@ struct CachedPermission {
     expires_at: DateTime<Utc>,
 }
 
+/// 当前请求所属的登录会话，由 `auth_middleware` 放入请求扩展
+#[derive(Debug, Clone)]
+pub struct AuthSession {
+    /// JWT 中的 session_id，没有时使用令牌的哈希
+    pub key: String,
+    pub expires_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,        // 用户ID
@@ -56,6 +65,9 @@ pub struct User {
     pub permissions: Vec<String>,
     pub is_verified: bool,
     pub created_at: DateTime<Utc>,
+    /// 当前会话是否已通过两步验证，由 `auth_middleware` 按会话设置
+    #[serde(default)]
+    pub two_factor_verified: bool,
 }
 
 // 自定义反序列化函数，支持整数时间戳和 RFC 3339 字符串
@@ -194,6 +206,16 @@ impl AuthService {
         }
     }
 
+    /// 令牌所属的会话，两步验证按会话记录
+    pub fn session_for(&self, claims: &Claims, token: &str) -> AuthSession {
+        let key = claims
+            .session_id
+            .clone()
+            .unwrap_or_else(|| hex::encode(Sha256::digest(token.as_bytes())));
+        let expires_at = Utc.timestamp_opt(claims.exp, 0).single().unwrap_or_else(Utc::now);
+        AuthSession { key, expires_at }
+    }
+
     pub async fn get_user_from_rainbow_auth(&self, user_id: &str, token: &str) -> Result<User> {
         // 检查缓存
         if let Some(cached_user) = self.get_cached_user(user_id).await {
@@ -248,6 +270,7 @@ impl AuthService {
             permissions,
             is_verified: user_data.email_verified,
             created_at: user_data.created_at,
+            two_factor_verified: false,
         };
 
         // 缓存用户数据
@@ -256,6 +279,83 @@ impl AuthService {
         Ok(user)
     }
 
+    /// 用服务令牌从 Rainbow-Auth 读取任意用户的账户，用户不存在时为 `None`。
+    /// 后台任务（邮件摘要、邀请等）没有用户的令牌，通过这里取得邮箱地址
+    pub async fn lookup_account(&self, user_id: &str) -> Result<Option<RainbowAuthUserResponse>> {
+        let url = format!("{}/api/auth/users/{}", self.config.auth_service_url, user_id);
+
+        let response = self.http_client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.config.auth_service_token))
+            .send()
+            .await
+            .map_err(|e| {
+                error!("Failed to look up user {} in Rainbow-Auth: {}", user_id, e);
+                AppError::ExternalService("Failed to look up user in Rainbow-Auth".to_string())
+            })?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            warn!("Rainbow-Auth user lookup for {} returned status: {}", user_id, response.status());
+            return Err(AppError::ExternalService(format!(
+                "Rainbow-Auth user lookup failed with status {}",
+                response.status()
+            )));
+        }
+
+        let account = response.json::<RainbowAuthUserResponse>().await.map_err(|e| {
+            error!("Failed to parse Rainbow-Auth user {}: {}", user_id, e);
+            AppError::ExternalService("Invalid response from Rainbow-Auth".to_string())
+        })?;
+        Ok(Some(account))
+    }
+
+    /// 用服务令牌解析用户及其博客权限，供没有用户 JWT 的请求（第三方访问令牌）使用。
+    /// 与 JWT 请求共用用户缓存，账户被删除后最多在缓存过期前仍可解析
+    pub async fn resolve_user(&self, user_id: &str) -> Result<Option<User>> {
+        if let Some(cached_user) = self.get_cached_user(user_id).await {
+            return Ok(Some(cached_user));
+        }
+
+        let Some(account) = self.lookup_account(user_id).await? else {
+            return Ok(None);
+        };
+        let permissions = self
+            .get_blog_permissions(&account.id, &self.config.auth_service_token)
+            .await?;
+
+        let user = User {
+            id: account.id.clone(),
+            email: account.email.clone(),
+            username: Some(account.email.split('@').next().unwrap_or("user").to_string()),
+            display_name: None,
+            avatar_url: None,
+            roles: vec!["user".to_string()],
+            permissions,
+            is_verified: account.email_verified,
+            created_at: account.created_at,
+            two_factor_verified: false,
+        };
+        self.cache_user(&account.id, user.clone()).await;
+
+        Ok(Some(user))
+    }
+
+    /// 用户在 Rainbow-Auth 登记的邮箱地址，用户不存在或没有邮箱时为 `None`
+    pub async fn get_user_email(&self, user_id: &str) -> Result<Option<String>> {
+        if let Some(cached_user) = self.get_cached_user(user_id).await {
+            return Ok(Some(cached_user.email).filter(|email| !email.is_empty()));
+        }
+
+        Ok(self
+            .lookup_account(user_id)
+            .await?
+            .map(|account| account.email)
+            .filter(|email| !email.is_empty()))
+    }
+
     async fn get_cached_user(&self, user_id: &str) -> Option<User> {
         let cache = self.user_cache.read().await;
         if let Some(cached) = cache.get(user_id) {
@@ -296,6 +396,14 @@ impl AuthService {
         Ok(permissions)
     }
 
+    /// 先看平台角色授予的权限，再走通用的权限检查
+    pub async fn has_permission(&self, user: &User, permission: &str) -> Result<bool> {
+        if user.permissions.iter().any(|p| p == permission) {
+            return Ok(true);
+        }
+        self.check_permission(&user.id, permission).await
+    }
+
     pub async fn check_permission(&self, user_id: &str, permission: &str) -> Result<bool> {
         // 检查权限缓存
         let cache_key = format!("{}:{}", user_id, permission);
@@ -442,7 +550,7 @@ where
 #[macro_export]
 macro_rules! require_permission {
     ($auth_service:expr, $user:expr, $permission:expr) => {
-        if !$auth_service.check_permission(&$user.id, $permission).await? {
+        if !$auth_service.has_permission(&$user, $permission).await? {
             return Err(AppError::Authorization(format!("Permission '{}' required", $permission)));
         }
     };
@@ -456,4 +564,55 @@ macro_rules! require_article_ownership {
             return Err(AppError::Authorization("Only article author can perform this action".to_string()));
         }
     };
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn auth_service_for(server: &MockServer) -> AuthService {
+        std::env::set_var("JWT_SECRET", "test-secret");
+        let mut config = Config::from_env().expect("config from env");
+        config.auth_service_url = server.uri();
+        config.auth_service_token = "service-token".to_string();
+        AuthService::new(&config).await.expect("auth service")
+    }
+
+    #[tokio::test]
+    async fn recipient_email_is_resolved_from_rainbow_auth() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/auth/users/user-1"))
+            .and(header("Authorization", "Bearer service-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "user-1",
+                "email": "reader@example.com",
+                "email_verified": true,
+                "created_at": "2024-01-01T00:00:00Z"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let auth_service = auth_service_for(&server).await;
+        let email = auth_service.get_user_email("user-1").await.unwrap();
+
+        assert_eq!(email.as_deref(), Some("reader@example.com"));
+    }
+
+    #[tokio::test]
+    async fn unknown_recipient_has_no_email() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/auth/users/missing"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let auth_service = auth_service_for(&server).await;
+
+        assert_eq!(auth_service.get_user_email("missing").await.unwrap(), None);
+    }
+}
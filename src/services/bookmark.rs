@@ -1,6 +1,6 @@
 use crate::{
     error::{AppError, Result},
-    models::{bookmark::*, article::Article},
+    models::{bookmark::*, article::Article, id::ArticleId},
     services::Database,
 };
 use chrono::Utc;
@@ -66,11 +66,8 @@ impl BookmarkService {
         // Create bookmark using SQL to set article_id as a record(article)
         let bookmark_id = Uuid::new_v4().to_string();
         // Extract pure article uuid for record literal
-        let pure_article_id = if request.article_id.starts_with("article:") {
-            &request.article_id[8..]
-        } else {
-            &request.article_id
-        };
+        let article_id = ArticleId::new(&request.article_id);
+        let pure_article_id = article_id.as_str();
         let note_clause = match &request.note {
             Some(n) if !n.is_empty() => format!(", note = '{}'", n.replace("'", "''")),
             _ => String::new(),
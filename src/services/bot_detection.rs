@@ -0,0 +1,294 @@
+use crate::{
+    config::Config,
+    error::Result,
+    models::{bot::*, id::UserId},
+    services::Database,
+    utils::{bot::{classify_user_agent, missing_browser_headers}, middleware::client_ip},
+};
+use axum::http::{header, HeaderMap};
+use chrono::{Duration, NaiveDate, Utc};
+use dashmap::DashMap;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::Sha256;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tracing::{debug, warn};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 浏览令牌携带的请求头
+pub const VIEW_TOKEN_HEADER: &str = "x-view-token";
+/// 令牌签发后至少经过的秒数，过滤拿到令牌立即回放的脚本
+const VIEW_TOKEN_MIN_AGE_SECS: u64 = 1;
+const VIEW_TOKEN_MAX_AGE_SECS: u64 = 3600;
+/// 计数表超过这个大小时清理过期窗口
+const MAX_TRACKED_CLIENTS: usize = 50_000;
+
+#[derive(Debug, Deserialize)]
+struct DailyViews {
+    day: String,
+    views: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct BotViewRow {
+    day: String,
+    bot_name: String,
+    category: BotCategory,
+    reason: BotReason,
+    views: i64,
+}
+
+/// 识别爬虫和自动化流量，使其不计入浏览数、热度和受众分析
+#[derive(Clone)]
+pub struct BotDetectionService {
+    db: Arc<Database>,
+    secret: Arc<Vec<u8>>,
+    challenge_required: bool,
+    max_views_per_minute: u32,
+    /// 客户端 IP -> (当前分钟窗口的起点, 窗口内浏览数)
+    counters: Arc<DashMap<String, (Instant, u32)>>,
+}
+
+impl BotDetectionService {
+    pub async fn new(db: Arc<Database>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            db,
+            secret: Arc::new(config.jwt_secret.as_bytes().to_vec()),
+            challenge_required: config.bot_challenge_required,
+            max_views_per_minute: config.bot_max_views_per_minute.max(1),
+            counters: Arc::new(DashMap::new()),
+        })
+    }
+
+    /// 浏览是否只能通过携带令牌的 POST /view 计入
+    pub fn challenge_required(&self) -> bool {
+        self.challenge_required
+    }
+
+    /// 判断一次浏览是否来自机器：依次检查 User-Agent、请求头和同一 IP 的浏览频率
+    pub fn detect(&self, headers: &HeaderMap) -> Option<BotVerdict> {
+        let user_agent = headers
+            .get(header::USER_AGENT)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+
+        if let Some((name, category)) = classify_user_agent(user_agent) {
+            return Some(BotVerdict { name: Some(name), category, reason: BotReason::UserAgent });
+        }
+
+        if missing_browser_headers(headers) {
+            return Some(BotVerdict { name: None, category: BotCategory::Unknown, reason: BotReason::MissingHeaders });
+        }
+
+        if let Some(ip) = client_ip(headers) {
+            if self.exceeds_rate(&ip) {
+                debug!("Client {} exceeded {} views per minute", ip, self.max_views_per_minute);
+                return Some(BotVerdict { name: None, category: BotCategory::Automation, reason: BotReason::RequestRate });
+            }
+        }
+
+        None
+    }
+
+    /// 开启 JS 验证时，没有有效令牌的浏览视为机器流量
+    pub fn check_view_token(&self, headers: &HeaderMap) -> Option<BotVerdict> {
+        if !self.challenge_required {
+            return None;
+        }
+
+        let token = headers.get(VIEW_TOKEN_HEADER).and_then(|v| v.to_str().ok());
+        let ip = client_ip(headers).unwrap_or_default();
+        match token {
+            Some(token) if self.verify_view_token(token, &ip) => None,
+            _ => Some(BotVerdict { name: None, category: BotCategory::Unknown, reason: BotReason::Challenge }),
+        }
+    }
+
+    /// 签发绑定客户端 IP 的浏览令牌，格式为 `{签发时间}.{签名}`
+    pub fn issue_view_token(&self, headers: &HeaderMap) -> String {
+        let issued = unix_now();
+        let ip = client_ip(headers).unwrap_or_default();
+        format!("{}.{}", issued, hex::encode(self.mac(issued, &ip).finalize().into_bytes()))
+    }
+
+    fn verify_view_token(&self, token: &str, ip: &str) -> bool {
+        let Some((issued, signature)) = token.split_once('.') else {
+            return false;
+        };
+        let (Ok(issued), Ok(signature)) = (issued.parse::<u64>(), hex::decode(signature)) else {
+            return false;
+        };
+
+        let age = unix_now().saturating_sub(issued);
+        if !(VIEW_TOKEN_MIN_AGE_SECS..=VIEW_TOKEN_MAX_AGE_SECS).contains(&age) {
+            return false;
+        }
+
+        self.mac(issued, ip).verify_slice(&signature).is_ok()
+    }
+
+    fn mac(&self, issued: u64, ip: &str) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.secret).expect("HMAC can take key of any size");
+        mac.update(format!("view|{}|{}", ip, issued).as_bytes());
+        mac
+    }
+
+    fn exceeds_rate(&self, ip: &str) -> bool {
+        let now = Instant::now();
+        if self.counters.len() > MAX_TRACKED_CLIENTS {
+            self.counters.retain(|_, (start, _)| now.duration_since(*start).as_secs() < 60);
+        }
+
+        let mut entry = self.counters.entry(ip.to_string()).or_insert((now, 0));
+        if now.duration_since(entry.0).as_secs() >= 60 {
+            *entry = (now, 0);
+        }
+        entry.1 += 1;
+        entry.1 > self.max_views_per_minute
+    }
+
+    /// 记录一次被排除的机器浏览，用于机器流量报告
+    pub async fn record_bot_view(
+        &self,
+        article_id: &str,
+        author_id: &str,
+        publication_id: Option<&str>,
+        verdict: &BotVerdict,
+    ) -> Result<()> {
+        self.db.query_with_params(
+            r#"
+                CREATE bot_traffic_event CONTENT {
+                    article_id: $article_id,
+                    author_id: $author_id,
+                    publication_id: $publication_id ?? NONE,
+                    bot_name: $bot_name,
+                    category: $category,
+                    reason: $reason,
+                    created_at: time::now()
+                }
+            "#,
+            json!({
+                "article_id": article_id,
+                "author_id": UserId::new(author_id).as_str(),
+                "publication_id": publication_id,
+                "bot_name": verdict.name.as_deref().unwrap_or("unknown"),
+                "category": verdict.category.as_str(),
+                "reason": verdict.reason.as_str(),
+            }),
+        ).await?;
+
+        Ok(())
+    }
+
+    /// 在后台记录机器浏览，不阻塞请求
+    pub fn record_bot_view_async(
+        &self,
+        article_id: String,
+        author_id: String,
+        publication_id: Option<String>,
+        verdict: BotVerdict,
+    ) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service
+                .record_bot_view(&article_id, &author_id, publication_id.as_deref(), &verdict)
+                .await
+            {
+                warn!("Failed to record bot view for article {}: {}", article_id, e);
+            }
+        });
+    }
+
+    /// 作者文章最近 N 天的机器流量与真人流量对比
+    pub async fn get_bot_report(&self, user_id: &str, query: BotTrafficQuery) -> Result<BotTrafficReport> {
+        let days = query.days.unwrap_or(30).clamp(1, 90);
+        let today = Utc::now().date_naive();
+        let start = today - Duration::days(days - 1);
+        let user_id = UserId::new(user_id);
+
+        let mut response = self.db.query_with_params(
+            r#"
+                LET $article_ids = array::flatten((SELECT VALUE [meta::id(id), <string> id] FROM article
+                    WHERE author_id INSIDE [$user_id, $user_thing]));
+                SELECT time::format(created_at, '%Y-%m-%d') AS day, count() AS views
+                    FROM article_view_event
+                    WHERE article_id INSIDE $article_ids AND created_at >= $since
+                    GROUP BY day;
+                SELECT time::format(created_at, '%Y-%m-%d') AS day, bot_name, category, reason, count() AS views
+                    FROM bot_traffic_event
+                    WHERE author_id = $user_id AND created_at >= $since
+                    GROUP BY day, bot_name, category, reason;
+            "#,
+            json!({
+                "user_id": user_id.as_str(),
+                "user_thing": format!("user:{}", user_id.as_str()),
+                "since": Utc::now() - Duration::days(days),
+            }),
+        ).await?;
+        let human_rows: Vec<DailyViews> = response.take(1)?;
+        let bot_rows: Vec<BotViewRow> = response.take(2)?;
+
+        let mut daily: BTreeMap<NaiveDate, (i64, i64)> = (0..days)
+            .map(|offset| (start + Duration::days(offset), (0, 0)))
+            .collect();
+        for row in &human_rows {
+            if let Some(bucket) = parse_day(&row.day).and_then(|d| daily.get_mut(&d)) {
+                bucket.0 += row.views;
+            }
+        }
+
+        let mut sources: HashMap<(String, BotCategory), i64> = HashMap::new();
+        let mut reasons: HashMap<BotReason, i64> = HashMap::new();
+        for row in &bot_rows {
+            if let Some(bucket) = parse_day(&row.day).and_then(|d| daily.get_mut(&d)) {
+                bucket.1 += row.views;
+            }
+            *sources.entry((row.bot_name.clone(), row.category)).or_default() += row.views;
+            *reasons.entry(row.reason).or_default() += row.views;
+        }
+
+        let human_views: i64 = daily.values().map(|(human, _)| human).sum();
+        let bot_views: i64 = daily.values().map(|(_, bot)| bot).sum();
+        let total = human_views + bot_views;
+
+        let mut sources: Vec<BotTrafficSource> = sources
+            .into_iter()
+            .map(|((name, category), views)| BotTrafficSource { name, category, views })
+            .collect();
+        sources.sort_by(|a, b| b.views.cmp(&a.views).then_with(|| a.name.cmp(&b.name)));
+
+        let mut reasons: Vec<BotTrafficByReason> = reasons
+            .into_iter()
+            .map(|(reason, views)| BotTrafficByReason { reason, views })
+            .collect();
+        reasons.sort_by(|a, b| b.views.cmp(&a.views));
+
+        Ok(BotTrafficReport {
+            days,
+            human_views,
+            bot_views,
+            bot_share: if total > 0 { bot_views as f64 / total as f64 } else { 0.0 },
+            sources,
+            reasons,
+            daily: daily
+                .into_iter()
+                .map(|(date, (human_views, bot_views))| BotTrafficDay { date, human_views, bot_views })
+                .collect(),
+        })
+    }
+}
+
+fn parse_day(day: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(day, "%Y-%m-%d").ok()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
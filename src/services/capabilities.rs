@@ -0,0 +1,148 @@
+//! 可替换的服务能力，通过 [`ServiceRegistry`](super::registry::ServiceRegistry) 注册到 `AppState`
+
+use crate::{
+    error::Result,
+    models::{article::*, payment::*, search::*},
+    services::{database::PaginatedResult, ArticleService, PaymentService, SearchService},
+};
+use async_trait::async_trait;
+
+/// 文章读取
+#[async_trait]
+pub trait ArticleStore: Send + Sync {
+    async fn get_article_by_id(&self, article_id: &str) -> Result<Option<Article>>;
+    async fn get_article_by_slug(&self, slug: &str) -> Result<Option<Article>>;
+    async fn get_articles(&self, query: ArticleQuery) -> Result<PaginatedResult<ArticleListItem>>;
+    async fn get_user_articles(
+        &self,
+        user_id: &str,
+        page: usize,
+        limit: usize,
+        include_drafts: bool,
+    ) -> Result<PaginatedResult<ArticleListItem>>;
+}
+
+/// 付费内容：定价、访问控制和单篇购买
+#[async_trait]
+pub trait Payments: Send + Sync {
+    async fn check_content_access(&self, article_id: &str, user_id: Option<&str>) -> Result<ContentAccess>;
+    async fn get_content_preview(&self, article_id: &str, user_id: Option<&str>) -> Result<ContentPreview>;
+    async fn get_article_pricing(&self, article_id: &str) -> Result<ArticlePricing>;
+    async fn set_article_pricing(
+        &self,
+        article_id: &str,
+        creator_id: &str,
+        request: ArticlePricingRequest,
+    ) -> Result<ArticlePricing>;
+    async fn purchase_article(
+        &self,
+        buyer_id: &str,
+        buyer_email: &str,
+        buyer_display_name: Option<&str>,
+        request: ArticlePurchaseRequest,
+    ) -> Result<ArticlePurchaseResponse>;
+    async fn get_payment_dashboard(&self, creator_id: &str) -> Result<PaymentDashboard>;
+    async fn record_content_access(
+        &self,
+        user_id: &str,
+        article_id: &str,
+        access_type: AccessType,
+        reading_time: Option<i64>,
+    ) -> Result<()>;
+}
+
+/// 搜索后端
+#[async_trait]
+pub trait SearchBackend: Send + Sync {
+    async fn search(&self, query: SearchQuery) -> Result<SearchResults>;
+    async fn advanced_search(&self, user_id: Option<&str>, query: AdvancedSearchQuery) -> Result<AdvancedSearchResults>;
+    async fn get_search_suggestions(&self, query: &str, limit: Option<i32>) -> Result<Vec<SearchSuggestion>>;
+}
+
+#[async_trait]
+impl ArticleStore for ArticleService {
+    async fn get_article_by_id(&self, article_id: &str) -> Result<Option<Article>> {
+        ArticleService::get_article_by_id(self, article_id).await
+    }
+
+    async fn get_article_by_slug(&self, slug: &str) -> Result<Option<Article>> {
+        ArticleService::get_article_by_slug(self, slug).await
+    }
+
+    async fn get_articles(&self, query: ArticleQuery) -> Result<PaginatedResult<ArticleListItem>> {
+        ArticleService::get_articles(self, query).await
+    }
+
+    async fn get_user_articles(
+        &self,
+        user_id: &str,
+        page: usize,
+        limit: usize,
+        include_drafts: bool,
+    ) -> Result<PaginatedResult<ArticleListItem>> {
+        ArticleService::get_user_articles(self, user_id, page, limit, include_drafts).await
+    }
+}
+
+#[async_trait]
+impl Payments for PaymentService {
+    async fn check_content_access(&self, article_id: &str, user_id: Option<&str>) -> Result<ContentAccess> {
+        PaymentService::check_content_access(self, article_id, user_id).await
+    }
+
+    async fn get_content_preview(&self, article_id: &str, user_id: Option<&str>) -> Result<ContentPreview> {
+        PaymentService::get_content_preview(self, article_id, user_id).await
+    }
+
+    async fn get_article_pricing(&self, article_id: &str) -> Result<ArticlePricing> {
+        PaymentService::get_article_pricing(self, article_id).await
+    }
+
+    async fn set_article_pricing(
+        &self,
+        article_id: &str,
+        creator_id: &str,
+        request: ArticlePricingRequest,
+    ) -> Result<ArticlePricing> {
+        PaymentService::set_article_pricing(self, article_id, creator_id, request).await
+    }
+
+    async fn purchase_article(
+        &self,
+        buyer_id: &str,
+        buyer_email: &str,
+        buyer_display_name: Option<&str>,
+        request: ArticlePurchaseRequest,
+    ) -> Result<ArticlePurchaseResponse> {
+        PaymentService::purchase_article(self, buyer_id, buyer_email, buyer_display_name, request).await
+    }
+
+    async fn get_payment_dashboard(&self, creator_id: &str) -> Result<PaymentDashboard> {
+        PaymentService::get_payment_dashboard(self, creator_id).await
+    }
+
+    async fn record_content_access(
+        &self,
+        user_id: &str,
+        article_id: &str,
+        access_type: AccessType,
+        reading_time: Option<i64>,
+    ) -> Result<()> {
+        PaymentService::record_content_access(self, user_id, article_id, access_type, reading_time).await
+    }
+}
+
+#[async_trait]
+impl SearchBackend for SearchService {
+    async fn search(&self, query: SearchQuery) -> Result<SearchResults> {
+        SearchService::search(self, query).await
+    }
+
+    async fn advanced_search(&self, user_id: Option<&str>, query: AdvancedSearchQuery) -> Result<AdvancedSearchResults> {
+        SearchService::advanced_search(self, user_id, query).await
+    }
+
+    async fn get_search_suggestions(&self, query: &str, limit: Option<i32>) -> Result<Vec<SearchSuggestion>> {
+        SearchService::get_search_suggestions(self, query, limit).await
+    }
+}
@@ -0,0 +1,406 @@
+use crate::{
+    error::{AppError, Result},
+    models::{
+        article::Article,
+        collaborative_edit::*,
+        id::ArticleId,
+        revision::AutosaveArticleRequest,
+    },
+    services::{auth::User, ArticleService},
+    utils::ot::TextOperation,
+};
+use axum::extract::ws::{Message, WebSocket};
+use futures::{sink::SinkExt, stream::StreamExt};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, warn};
+
+/// 每个会话保留的历史操作数，落后更多的客户端需要重新同步
+const MAX_HISTORY: usize = 1000;
+/// 与 AutosaveArticleRequest 的正文长度限制一致
+const MAX_CONTENT_CHARS: usize = 50000;
+/// 有未保存修改时的自动保存间隔
+const SAVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 一篇草稿的协作编辑会话，在第一个人加入时从文章加载，最后一个人离开时保存并关闭
+struct EditSession {
+    content: String,
+    /// 当前版本号，等于会话开始以来接受的操作数
+    revision: usize,
+    /// 最近的操作，第一个操作产生的版本号为 `history_start + 1`
+    history: Vec<TextOperation>,
+    history_start: usize,
+    participants: HashMap<String, Participant>,
+    /// 最后一个修改者，None 表示没有未保存的修改
+    dirty_by: Option<String>,
+}
+
+struct Participant {
+    info: EditParticipant,
+    tx: mpsc::UnboundedSender<EditServerMessage>,
+}
+
+impl EditSession {
+    fn participant_list(&self) -> Vec<EditParticipant> {
+        self.participants.values().map(|p| p.info.clone()).collect()
+    }
+
+    fn init_message(&self, connection_id: &str, access: EditAccess) -> EditServerMessage {
+        EditServerMessage::Init {
+            connection_id: connection_id.to_string(),
+            revision: self.revision,
+            content: self.content.clone(),
+            access,
+            participants: self.participant_list(),
+        }
+    }
+
+    fn send_to(&self, connection_id: &str, message: EditServerMessage) {
+        if let Some(participant) = self.participants.get(connection_id) {
+            let _ = participant.tx.send(message);
+        }
+    }
+
+    fn broadcast(&self, message: EditServerMessage) {
+        for participant in self.participants.values() {
+            let _ = participant.tx.send(message.clone());
+        }
+    }
+
+    fn broadcast_except(&self, connection_id: &str, message: EditServerMessage) {
+        for (id, participant) in &self.participants {
+            if id != connection_id {
+                let _ = participant.tx.send(message.clone());
+            }
+        }
+    }
+
+    /// 把基于 `revision` 的操作变换到最新版本并应用，返回变换后的操作
+    fn apply_client_operation(&mut self, revision: usize, operation: TextOperation) -> std::result::Result<TextOperation, String> {
+        if revision > self.revision {
+            return Err(format!("Unknown revision {}", revision));
+        }
+        if revision < self.history_start {
+            return Err(format!("Revision {} is too old", revision));
+        }
+
+        let mut operation = operation;
+        for concurrent in &self.history[revision - self.history_start..] {
+            operation = TextOperation::transform(&operation, concurrent)?.0;
+        }
+        if operation.target_len() > MAX_CONTENT_CHARS {
+            return Err(format!("Content cannot exceed {} characters", MAX_CONTENT_CHARS));
+        }
+
+        self.content = operation.apply(&self.content)?;
+        self.revision += 1;
+        self.history.push(operation.clone());
+        if self.history.len() > MAX_HISTORY {
+            let excess = self.history.len() - MAX_HISTORY;
+            self.history.drain(..excess);
+            self.history_start += excess;
+        }
+
+        // 其他人的光标随文档移动
+        for participant in self.participants.values_mut() {
+            if let Some(selection) = participant.info.selection.as_mut() {
+                selection.anchor = operation.transform_index(selection.anchor);
+                selection.head = operation.transform_index(selection.head);
+            }
+        }
+        Ok(operation)
+    }
+}
+
+/// 草稿的实时协作编辑：客户端通过 WebSocket 提交基于某个版本的文本操作，
+/// 服务端用操作变换（OT）合并并发编辑后广播给其他参与者，
+/// 定期通过 ArticleService 自动保存（记录为自动保存修订版本）
+#[derive(Clone)]
+pub struct CollaborativeEditingService {
+    article_service: ArticleService,
+    sessions: Arc<Mutex<HashMap<String, Arc<Mutex<EditSession>>>>>,
+}
+
+impl CollaborativeEditingService {
+    pub async fn new(article_service: ArticleService) -> Result<Self> {
+        let service = Self {
+            article_service,
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+        };
+
+        // 定期保存有修改的会话
+        let service_clone = service.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(SAVE_INTERVAL);
+            loop {
+                interval.tick().await;
+                service_clone.save_dirty_sessions().await;
+            }
+        });
+
+        Ok(service)
+    }
+
+    /// 作者和合著者可以加入；只有作者和编辑者可以提交修改。已发布的文章不能协作编辑
+    pub async fn authorize(&self, article_id: &str, user_id: &str) -> Result<(Article, EditAccess)> {
+        let article = self.article_service.get_article_by_id(article_id).await?
+            .filter(|a| !a.is_deleted)
+            .ok_or_else(|| AppError::not_found("Article"))?;
+
+        if article.is_published() {
+            return Err(AppError::BadRequest("Published articles cannot be edited collaboratively".to_string()));
+        }
+
+        let access = if article.author_id == user_id {
+            EditAccess::Author
+        } else {
+            match self.article_service.get_collaborator_role(&article.id, user_id).await? {
+                Some(role) if role.can_edit() => EditAccess::Editor,
+                Some(_) => EditAccess::Viewer,
+                None => return Err(AppError::forbidden("Only article author or collaborators can join the editing session")),
+            }
+        };
+        Ok((article, access))
+    }
+
+    /// 处理一个编辑连接，直到客户端断开
+    pub async fn handle_connection(&self, socket: WebSocket, article: Article, access: EditAccess, user: User) {
+        let article_id = ArticleId::new(&article.id).as_str().to_string();
+        let connection_id = format!("edit_{}", uuid::Uuid::new_v4());
+        let (mut ws_tx, mut ws_rx) = socket.split();
+        let (tx, mut rx) = mpsc::unbounded_channel::<EditServerMessage>();
+
+        let profile = self.article_service.get_article_author(&user.id).await.ok();
+        let participant = EditParticipant {
+            connection_id: connection_id.clone(),
+            user_id: user.id.clone(),
+            display_name: profile
+                .as_ref()
+                .map(|p| p.display_name.clone())
+                .or_else(|| user.display_name.clone())
+                .or_else(|| user.username.clone())
+                .unwrap_or_else(|| user.id.clone()),
+            avatar_url: profile.and_then(|p| p.avatar_url).or_else(|| user.avatar_url.clone()),
+            access,
+            selection: None,
+        };
+
+        let session = self.join(&article_id, &article.content, participant, tx);
+        info!("User {} joined editing session for article {} ({})", user.id, article_id, connection_id);
+
+        let send_task = tokio::spawn(async move {
+            while let Some(message) = rx.recv().await {
+                match serde_json::to_string(&message) {
+                    Ok(json) => {
+                        if ws_tx.send(Message::Text(json)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => error!("Failed to serialize edit message: {}", e),
+                }
+            }
+        });
+
+        while let Some(message) = ws_rx.next().await {
+            match message {
+                Ok(Message::Text(text)) => {
+                    match serde_json::from_str::<EditClientMessage>(&text) {
+                        Ok(message) => self.handle_message(&article_id, &session, &connection_id, &user.id, access, message).await,
+                        Err(e) => {
+                            let error = EditServerMessage::Error { message: format!("Invalid message: {}", e) };
+                            session.lock().unwrap().send_to(&connection_id, error);
+                        }
+                    }
+                }
+                Ok(Message::Close(_)) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    debug!("Editing connection {} error: {}", connection_id, e);
+                    break;
+                }
+            }
+        }
+
+        self.leave(&article_id, &session, &connection_id).await;
+        send_task.abort();
+        info!("User {} left editing session for article {}", user.id, article_id);
+    }
+
+    fn join(
+        &self,
+        article_id: &str,
+        content: &str,
+        participant: EditParticipant,
+        tx: mpsc::UnboundedSender<EditServerMessage>,
+    ) -> Arc<Mutex<EditSession>> {
+        let session = {
+            let mut sessions = self.sessions.lock().unwrap();
+            sessions
+                .entry(article_id.to_string())
+                .or_insert_with(|| {
+                    Arc::new(Mutex::new(EditSession {
+                        content: content.to_string(),
+                        revision: 0,
+                        history: Vec::new(),
+                        history_start: 0,
+                        participants: HashMap::new(),
+                        dirty_by: None,
+                    }))
+                })
+                .clone()
+        };
+
+        {
+            let mut edit_session = session.lock().unwrap();
+            let connection_id = participant.connection_id.clone();
+            edit_session.broadcast_except(&connection_id, EditServerMessage::Join { participant: participant.clone() });
+            let access = participant.access;
+            edit_session.participants.insert(connection_id.clone(), Participant { info: participant, tx });
+            let init = edit_session.init_message(&connection_id, access);
+            edit_session.send_to(&connection_id, init);
+        }
+        session
+    }
+
+    async fn handle_message(
+        &self,
+        article_id: &str,
+        session: &Arc<Mutex<EditSession>>,
+        connection_id: &str,
+        user_id: &str,
+        access: EditAccess,
+        message: EditClientMessage,
+    ) {
+        match message {
+            EditClientMessage::Operation { revision, operation, selection } => {
+                let mut edit_session = session.lock().unwrap();
+                if !access.can_edit() {
+                    edit_session.send_to(connection_id, EditServerMessage::Error {
+                        message: "Viewers cannot edit this article".to_string(),
+                    });
+                    return;
+                }
+
+                match edit_session.apply_client_operation(revision, operation) {
+                    Ok(operation) => {
+                        if let Some(participant) = edit_session.participants.get_mut(connection_id) {
+                            participant.info.selection = selection;
+                        }
+                        edit_session.dirty_by = Some(user_id.to_string());
+                        let revision = edit_session.revision;
+                        edit_session.send_to(connection_id, EditServerMessage::Ack { revision });
+                        edit_session.broadcast_except(connection_id, EditServerMessage::Operation {
+                            revision,
+                            operation,
+                            connection_id: connection_id.to_string(),
+                            user_id: user_id.to_string(),
+                            selection,
+                        });
+                    }
+                    Err(e) => {
+                        // 客户端状态与服务端不一致，发送完整文档重新同步
+                        warn!("Rejected operation on article {} from {}: {}", article_id, connection_id, e);
+                        edit_session.send_to(connection_id, EditServerMessage::Error { message: e });
+                        let init = edit_session.init_message(connection_id, access);
+                        edit_session.send_to(connection_id, init);
+                    }
+                }
+            }
+            EditClientMessage::Selection { selection } => {
+                let mut edit_session = session.lock().unwrap();
+                if let Some(participant) = edit_session.participants.get_mut(connection_id) {
+                    participant.info.selection = selection;
+                }
+                edit_session.broadcast_except(connection_id, EditServerMessage::Selection {
+                    connection_id: connection_id.to_string(),
+                    selection,
+                });
+            }
+            EditClientMessage::Save => {
+                if let Err(e) = self.save(article_id, session).await {
+                    session.lock().unwrap().send_to(connection_id, EditServerMessage::Error {
+                        message: format!("Failed to save: {}", e),
+                    });
+                }
+            }
+        }
+    }
+
+    async fn leave(&self, article_id: &str, session: &Arc<Mutex<EditSession>>, connection_id: &str) {
+        let is_empty = {
+            let mut edit_session = session.lock().unwrap();
+            edit_session.participants.remove(connection_id);
+            edit_session.broadcast_except(connection_id, EditServerMessage::Leave {
+                connection_id: connection_id.to_string(),
+            });
+            edit_session.participants.is_empty()
+        };
+        if !is_empty {
+            return;
+        }
+
+        if let Err(e) = self.save(article_id, session).await {
+            error!("Failed to save editing session for article {}: {}", article_id, e);
+        }
+        self.close_if_idle(article_id, session);
+    }
+
+    /// 没有参与者且已保存的会话可以关闭；保存期间可能有人重新加入，此时保留会话。
+    /// 保存失败的会话留给定期保存任务重试
+    fn close_if_idle(&self, article_id: &str, session: &Arc<Mutex<EditSession>>) {
+        let mut sessions = self.sessions.lock().unwrap();
+        let idle = {
+            let edit_session = session.lock().unwrap();
+            edit_session.participants.is_empty() && edit_session.dirty_by.is_none()
+        };
+        if idle {
+            sessions.remove(article_id);
+            debug!("Closed editing session for article {}", article_id);
+        }
+    }
+
+    /// 把会话内容保存到文章；没有未保存的修改时直接返回
+    async fn save(&self, article_id: &str, session: &Arc<Mutex<EditSession>>) -> Result<()> {
+        let (editor_id, content, revision) = {
+            let edit_session = session.lock().unwrap();
+            match &edit_session.dirty_by {
+                Some(editor_id) => (editor_id.clone(), edit_session.content.clone(), edit_session.revision),
+                None => return Ok(()),
+            }
+        };
+
+        let request = AutosaveArticleRequest {
+            title: None,
+            subtitle: None,
+            content: Some(content),
+        };
+        self.article_service.autosave_article(article_id, &editor_id, request).await?;
+
+        let mut edit_session = session.lock().unwrap();
+        // 保存期间没有新的修改才清除标记
+        if edit_session.revision == revision {
+            edit_session.dirty_by = None;
+        }
+        edit_session.broadcast(EditServerMessage::Saved { revision });
+        debug!("Saved editing session for article {} at revision {}", article_id, revision);
+        Ok(())
+    }
+
+    async fn save_dirty_sessions(&self) {
+        let sessions: Vec<(String, Arc<Mutex<EditSession>>)> = {
+            let sessions = self.sessions.lock().unwrap();
+            sessions.iter().map(|(id, session)| (id.clone(), session.clone())).collect()
+        };
+
+        for (article_id, session) in sessions {
+            if let Err(e) = self.save(&article_id, &session).await {
+                error!("Failed to autosave editing session for article {}: {}", article_id, e);
+            }
+            self.close_if_idle(&article_id, &session);
+        }
+    }
+}
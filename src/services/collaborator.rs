@@ -0,0 +1,233 @@
+use crate::{
+    error::{AppError, Result},
+    models::{
+        article::Article,
+        collaborator::*,
+        id::ArticleId,
+        notification::{CreateNotificationRequest, NotificationType},
+    },
+    services::{ArticleService, Database, NotificationService},
+};
+use serde_json::json;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+use validator::Validate;
+
+/// 文章合著：邀请、接受和移除合著者。
+/// 合著者的编辑权限由 ArticleService 在保存文章时检查
+#[derive(Clone)]
+pub struct CollaboratorService {
+    db: Arc<Database>,
+    article_service: ArticleService,
+    notification_service: NotificationService,
+}
+
+impl CollaboratorService {
+    pub async fn new(
+        db: Arc<Database>,
+        article_service: ArticleService,
+        notification_service: NotificationService,
+    ) -> Result<Self> {
+        Ok(Self {
+            db,
+            article_service,
+            notification_service,
+        })
+    }
+
+    /// 邀请合著者，重复邀请待接受的用户会更新角色
+    pub async fn invite(&self, article_id: &str, inviter_id: &str, request: InviteCollaboratorRequest) -> Result<ArticleCollaborator> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let article = self.get_authored_article(article_id, inviter_id).await?;
+        if request.user_id == article.author_id {
+            return Err(AppError::BadRequest("The article author cannot be invited as a collaborator".to_string()));
+        }
+
+        let invitee = self.article_service.get_article_author(&request.user_id).await
+            .map_err(|_| AppError::not_found("User"))?;
+
+        let article_id = ArticleId::new(&article.id);
+        if let Some(existing) = self.find(article_id.as_str(), &request.user_id).await? {
+            if existing.status == CollaboratorStatus::Accepted {
+                return Err(AppError::Conflict("User is already a collaborator on this article".to_string()));
+            }
+        }
+
+        let collaborator: ArticleCollaborator = self.db
+            .prepare(
+                r#"
+                UPSERT type::thing('article_collaborator', [$article_id, $user_id]) MERGE {
+                    article_id: $article_id,
+                    user_id: $user_id,
+                    role: $role,
+                    status: 'pending',
+                    invited_by: $invited_by,
+                    created_at: created_at ?? time::now(),
+                    accepted_at: NONE
+                }
+                "#,
+            )
+            .bind("article_id", article_id.as_str())
+            .bind("user_id", &request.user_id)
+            .bind("role", request.role.as_str())
+            .bind("invited_by", inviter_id)
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::internal("Failed to create collaborator invitation"))?;
+
+        info!("User {} invited {} to article {} as {}", inviter_id, request.user_id, article_id, request.role.as_str());
+
+        if let Err(e) = self.notify_invitee(&article, &collaborator).await {
+            warn!("Failed to notify {} of collaboration invite: {}", invitee.username, e);
+        }
+
+        Ok(collaborator)
+    }
+
+    /// 接受邀请后才获得对应角色的权限
+    pub async fn accept(&self, article_id: &str, user_id: &str) -> Result<ArticleCollaborator> {
+        let article_id = ArticleId::new(article_id);
+        let invitation = self.find(article_id.as_str(), user_id).await?
+            .ok_or_else(|| AppError::not_found("Invitation"))?;
+        if invitation.status == CollaboratorStatus::Accepted {
+            return Ok(invitation);
+        }
+
+        let collaborator: ArticleCollaborator = self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('article_collaborator', [$article_id, $user_id])
+                SET status = 'accepted', accepted_at = time::now()
+                "#,
+            )
+            .bind("article_id", article_id.as_str())
+            .bind("user_id", user_id)
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::not_found("Invitation"))?;
+
+        info!("User {} accepted collaboration on article {}", user_id, article_id);
+        Ok(collaborator)
+    }
+
+    /// 文章的所有合著者（含待接受的邀请），作者和已接受的合著者可以查看
+    pub async fn list(&self, article_id: &str, user_id: &str) -> Result<Vec<CollaboratorResponse>> {
+        let article = self.get_article(article_id).await?;
+        if article.author_id != user_id
+            && self.article_service.get_collaborator_role(&article.id, user_id).await?.is_none()
+        {
+            return Err(AppError::forbidden("Only article author or collaborators can view collaborators"));
+        }
+
+        let collaborators: Vec<ArticleCollaborator> = self.db
+            .prepare("SELECT * FROM article_collaborator WHERE article_id = $article_id ORDER BY created_at ASC")
+            .bind("article_id", ArticleId::new(&article.id).as_str())
+            .fetch()
+            .await?;
+
+        let mut responses = Vec::with_capacity(collaborators.len());
+        for collaborator in collaborators {
+            let profile = self.article_service.get_article_author(&collaborator.user_id).await.ok();
+            responses.push(CollaboratorResponse { collaborator, profile });
+        }
+        Ok(responses)
+    }
+
+    /// 当前用户收到的待接受邀请
+    pub async fn list_invitations(&self, user_id: &str) -> Result<Vec<ArticleCollaborator>> {
+        self.db
+            .prepare("SELECT * FROM article_collaborator WHERE user_id = $user_id AND status = 'pending' ORDER BY created_at DESC")
+            .bind("user_id", user_id)
+            .fetch()
+            .await
+    }
+
+    pub async fn update_role(&self, article_id: &str, author_id: &str, user_id: &str, request: UpdateCollaboratorRequest) -> Result<ArticleCollaborator> {
+        let article = self.get_authored_article(article_id, author_id).await?;
+
+        let collaborator: ArticleCollaborator = self.db
+            .prepare("UPDATE type::thing('article_collaborator', [$article_id, $user_id]) SET role = $role")
+            .bind("article_id", ArticleId::new(&article.id).as_str())
+            .bind("user_id", user_id)
+            .bind("role", request.role.as_str())
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::not_found("Collaborator"))?;
+
+        debug!("Changed role of {} on article {} to {}", user_id, article.id, request.role.as_str());
+        Ok(collaborator)
+    }
+
+    /// 作者移除合著者，或合著者自己退出 / 拒绝邀请
+    pub async fn remove(&self, article_id: &str, requester_id: &str, user_id: &str) -> Result<()> {
+        let article = self.get_article(article_id).await?;
+        if requester_id != user_id && article.author_id != requester_id {
+            return Err(AppError::forbidden("Only article author can remove collaborators"));
+        }
+
+        let article_id = ArticleId::new(&article.id);
+        if self.find(article_id.as_str(), user_id).await?.is_none() {
+            return Err(AppError::not_found("Collaborator"));
+        }
+
+        self.db
+            .prepare("DELETE type::thing('article_collaborator', [$article_id, $user_id])")
+            .bind("article_id", article_id.as_str())
+            .bind("user_id", user_id)
+            .execute()
+            .await?;
+
+        info!("Removed collaborator {} from article {}", user_id, article_id);
+        Ok(())
+    }
+
+    async fn find(&self, article_id: &str, user_id: &str) -> Result<Option<ArticleCollaborator>> {
+        self.db
+            .prepare("SELECT * FROM type::thing('article_collaborator', [$article_id, $user_id])")
+            .bind("article_id", article_id)
+            .bind("user_id", user_id)
+            .fetch_one()
+            .await
+    }
+
+    async fn get_article(&self, article_id: &str) -> Result<Article> {
+        self.article_service.get_article_by_id(article_id).await?
+            .filter(|a| !a.is_deleted)
+            .ok_or_else(|| AppError::not_found("Article"))
+    }
+
+    /// 只有文章作者可以管理合著者
+    async fn get_authored_article(&self, article_id: &str, author_id: &str) -> Result<Article> {
+        let article = self.get_article(article_id).await?;
+        if article.author_id != author_id {
+            return Err(AppError::forbidden("Only article author can manage collaborators"));
+        }
+        Ok(article)
+    }
+
+    async fn notify_invitee(&self, article: &Article, collaborator: &ArticleCollaborator) -> Result<()> {
+        let inviter = self.article_service.get_article_author(&collaborator.invited_by).await?;
+
+        self.notification_service
+            .create_notification(CreateNotificationRequest {
+                recipient_id: collaborator.user_id.clone(),
+                notification_type: NotificationType::CollaborationInvite,
+                title: "Collaboration invite".to_string(),
+                message: format!(
+                    "{} invited you to collaborate on \"{}\" as {}.",
+                    inviter.display_name,
+                    article.title,
+                    collaborator.role.as_str()
+                ),
+                data: json!({
+                    "article_id": collaborator.article_id,
+                    "article_slug": article.slug,
+                    "role": collaborator.role,
+                    "invited_by": collaborator.invited_by,
+                }),
+            })
+            .await?;
+        Ok(())
+    }
+}
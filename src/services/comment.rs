@@ -1,19 +1,25 @@
 use crate::{
+    config::Config,
     error::{AppError, Result},
     models::comment::*,
-    models::article::Article,
-    services::Database,
+    models::{article::Article, id::{ArticleId, CommentId, PublicationId}},
+    services::{akismet::AkismetSpamFilter, Database, PluginManager},
+    utils::disqus::{self, DisqusPost},
+    utils::spam::{KeywordSpamFilter, SpamCheck, SpamFilter},
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde_json::{json, Value};
 use std::sync::Arc;
 use std::collections::HashMap;
 use surrealdb::sql::Thing;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 use validator::Validate;
 use uuid::Uuid;
 use serde::{Deserialize, Serialize};
 
+/// 导入的访客评论使用的作者 ID（没有对应的站内用户）
+pub const GUEST_AUTHOR_ID: &str = "guest";
+
 // 用于数据库插入的评论结构体（不包含时间戳字段，让数据库自动设置）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct CommentInsert {
@@ -29,21 +35,55 @@ struct CommentInsert {
     deleted_at: Option<String>,
 }
 
+/// 一页回复的数量上限
+const MAX_REPLIES_PER_PAGE: usize = 100;
+/// 查找祖先评论时最多向上的层数，防止错误数据形成环
+const MAX_THREAD_WALK: usize = 64;
+/// 审核队列每页数量上限
+const MAX_QUEUE_PAGE_SIZE: usize = 100;
+
 #[derive(Clone)]
 pub struct CommentService {
     db: Arc<Database>,
+    plugins: PluginManager,
+    /// 回复最多嵌套的层数（顶层评论为第 0 层）
+    max_depth: usize,
+    replies_per_page: usize,
+    /// 用于拼接文章地址，提供给垃圾评论过滤器
+    site_url: String,
+    /// 新评论依次经过的垃圾评论过滤器，得分取最高值
+    spam_filters: Vec<Arc<dyn SpamFilter>>,
 }
 
 impl CommentService {
-    pub async fn new(db: Arc<Database>) -> Result<Self> {
-        Ok(Self { db })
+    pub async fn new(db: Arc<Database>, plugins: PluginManager, config: &Config) -> Result<Self> {
+        let mut spam_filters: Vec<Arc<dyn SpamFilter>> = vec![Arc::new(KeywordSpamFilter::new())];
+        if let Some(akismet) = AkismetSpamFilter::from_config(config)? {
+            spam_filters.push(Arc::new(akismet));
+        }
+
+        Ok(Self {
+            db,
+            plugins,
+            max_depth: config.comment_max_depth.max(1),
+            replies_per_page: config.default_comments_per_page.clamp(1, MAX_REPLIES_PER_PAGE),
+            site_url: config.frontend_url.trim_end_matches('/').to_string(),
+            spam_filters,
+        })
+    }
+
+    /// 追加一个垃圾评论过滤器
+    pub fn with_spam_filter(mut self, filter: Arc<dyn SpamFilter>) -> Self {
+        self.spam_filters.push(filter);
+        self
     }
 
 
     pub async fn create_comment(
         &self,
         user_id: &str,
-        request: CreateCommentRequest,
+        mut request: CreateCommentRequest,
+        client: CommentClient,
     ) -> Result<Comment> {
         debug!("Creating comment for article: {}", request.article_id);
         info!("Received article_id: '{}'", request.article_id);
@@ -68,24 +108,39 @@ impl CommentService {
         // Verify parent comment exists if provided
         // Note: SurrealDB may return Thing objects for `id`, which don't deserialize into String directly.
         // Use a generic JSON value for existence checks to avoid id deserialization issues.
-        if let Some(parent_id) = &request.parent_id {
-            let parent: Option<serde_json::Value> = self.db.get_by_id("comment", parent_id).await?;
+        if let Some(parent_id) = request.parent_id.clone() {
+            let parent: Option<serde_json::Value> = self.db.get_by_id("comment", &parent_id).await?;
             match parent {
                 None => {
                     return Err(AppError::NotFound("Parent comment not found".to_string()));
                 }
                 Some(p) => {
-                    // Disallow replying to deleted comments
-                    if p.get("is_deleted").and_then(|v| v.as_bool()).unwrap_or(false) {
+                    // Disallow replying to deleted or unpublished (held, spam, rejected) comments
+                    let is_visible = p.get("moderation_status").and_then(|v| v.as_str()).map_or(true, |s| s == "approved");
+                    if p.get("is_deleted").and_then(|v| v.as_bool()).unwrap_or(false) || !is_visible {
                         return Err(AppError::NotFound("Parent comment not found".to_string()));
                     }
                 }
             }
+
+            // 超过最大深度的回复挂到最深一层允许回复的祖先评论下
+            let reply_to = self.resolve_reply_parent(&parent_id).await?;
+            if reply_to != parent_id {
+                debug!("Reply to {} exceeds max depth {}, attaching to {}", parent_id, self.max_depth, reply_to);
+                request.parent_id = Some(reply_to);
+            }
         }
 
         // Check if this is an author response
         let is_author_response = article.author_id == user_id;
 
+        // 作者回复不经过审核；其他评论先打分，再按出版物的审核设置决定是否展示
+        let (moderation_status, spam_score) = if is_author_response {
+            (CommentModerationStatus::Approved, None)
+        } else {
+            self.moderate_new_comment(&article, user_id, &request.content, client).await?
+        };
+
         let comment_id = Uuid::new_v4().to_string();
 
         // 使用 CREATE 语句创建评论，让数据库自动设置时间戳
@@ -94,13 +149,15 @@ impl CommentService {
             .unwrap_or_else(|| String::new());
             
         let query = format!(
-            "CREATE comment:`{}` SET article_id = '{}', author_id = '{}'{}, content = '{}', is_author_response = {}, clap_count = 0, is_edited = false, is_deleted = false",
+            "CREATE comment:`{}` SET article_id = '{}', author_id = '{}'{}, content = '{}', is_author_response = {}, clap_count = 0, is_edited = false, is_deleted = false, moderation_status = '{}', spam_score = {}",
             comment_id,
             request.article_id,
             user_id,
             parent_id_clause,
             request.content.replace("'", "''"), // 转义单引号
-            is_author_response
+            is_author_response,
+            moderation_status.as_str(),
+            spam_score.map(|s| s.to_string()).unwrap_or_else(|| "NONE".to_string())
         );
         
         debug!("Creating comment with query: {}", query);
@@ -131,12 +188,486 @@ impl CommentService {
         let created: Comment = serde_json::from_value(created_value)
             .map_err(|e| AppError::Internal(format!("Failed to deserialize comment: {}", e)))?;
 
-        // Update article comment count
-        self.update_article_comment_count(&request.article_id).await?;
+        // 等待审核或被判为垃圾的评论在审核通过后才计数和通知插件
+        if created.moderation_status == CommentModerationStatus::Approved {
+            // Update article comment count
+            self.update_article_comment_count(&request.article_id).await?;
+
+            self.plugins.comment_created(&created);
+        } else {
+            info!("Comment {} held for moderation ({})", created.id, created.moderation_status.as_str());
+        }
 
         Ok(created)
     }
 
+    /// 新评论的审核状态和垃圾评论得分
+    async fn moderate_new_comment(
+        &self,
+        article: &Article,
+        user_id: &str,
+        content: &str,
+        client: CommentClient,
+    ) -> Result<(CommentModerationStatus, Option<f64>)> {
+        let settings = match &article.publication_id {
+            Some(publication_id) => self.get_moderation_settings(publication_id).await?,
+            None => CommentModerationSettings::default_for(""),
+        };
+
+        let check = SpamCheck {
+            content: content.to_string(),
+            author_id: user_id.to_string(),
+            author_name: client.author_name,
+            permalink: Some(format!("{}/articles/{}", self.site_url, article.slug)),
+            user_ip: client.ip,
+            user_agent: client.user_agent,
+            blocked_keywords: settings.blocked_keywords.clone(),
+        };
+        let score = self.spam_score(&check).await;
+
+        let status = if score >= settings.spam_threshold {
+            CommentModerationStatus::Spam
+        } else if settings.hold_for_review {
+            CommentModerationStatus::Pending
+        } else {
+            CommentModerationStatus::Approved
+        };
+        Ok((status, Some(score)))
+    }
+
+    /// 所有过滤器中的最高分；某个过滤器出错时跳过它，不阻止发评论
+    pub async fn spam_score(&self, check: &SpamCheck) -> f64 {
+        let mut score: f64 = 0.0;
+        for filter in &self.spam_filters {
+            match filter.score(check).await {
+                Ok(signal) => {
+                    if signal.score > 0.0 {
+                        debug!("Spam filter {} scored {:.2}: {:?}", signal.filter, signal.score, signal.reasons);
+                    }
+                    score = score.max(signal.score);
+                }
+                Err(e) => warn!("Spam filter {} failed: {}", filter.name(), e),
+            }
+        }
+        score
+    }
+
+    /// 出版物的评论审核设置，没有保存过时返回默认设置
+    pub async fn get_moderation_settings(&self, publication_id: &str) -> Result<CommentModerationSettings> {
+        let publication_id = PublicationId::new(publication_id);
+        let settings: Option<CommentModerationSettings> = self.db
+            .prepare("SELECT * FROM type::thing('comment_moderation_settings', $publication_id)")
+            .bind("publication_id", publication_id.as_str())
+            .fetch_one()
+            .await?;
+
+        Ok(settings.unwrap_or_else(|| CommentModerationSettings::default_for(publication_id.as_str())))
+    }
+
+    pub async fn update_moderation_settings(
+        &self,
+        publication_id: &str,
+        request: UpdateModerationSettingsRequest,
+    ) -> Result<CommentModerationSettings> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let mut settings = self.get_moderation_settings(publication_id).await?;
+        if let Some(hold_for_review) = request.hold_for_review {
+            settings.hold_for_review = hold_for_review;
+        }
+        if let Some(spam_threshold) = request.spam_threshold {
+            settings.spam_threshold = spam_threshold;
+        }
+        if let Some(keywords) = request.blocked_keywords {
+            let mut keywords: Vec<String> = keywords
+                .iter()
+                .map(|k| k.trim().to_lowercase())
+                .filter(|k| !k.is_empty())
+                .collect();
+            keywords.sort();
+            keywords.dedup();
+            settings.blocked_keywords = keywords;
+        }
+
+        let updated: Option<CommentModerationSettings> = self.db
+            .prepare(
+                r#"
+                UPSERT type::thing('comment_moderation_settings', $publication_id) CONTENT {
+                    publication_id: $publication_id,
+                    hold_for_review: $hold_for_review,
+                    spam_threshold: $spam_threshold,
+                    blocked_keywords: $blocked_keywords,
+                    updated_at: time::now()
+                }
+                "#,
+            )
+            .bind("publication_id", PublicationId::new(publication_id).as_str())
+            .bind("hold_for_review", settings.hold_for_review)
+            .bind("spam_threshold", settings.spam_threshold)
+            .bind("blocked_keywords", &settings.blocked_keywords)
+            .fetch_one()
+            .await?;
+
+        info!("Updated comment moderation settings for publication {}", publication_id);
+        updated.ok_or_else(|| AppError::internal("Failed to update moderation settings"))
+    }
+
+    /// 出版物文章下等待审核（或被判为垃圾）的评论，最早的在前
+    pub async fn list_moderation_queue(
+        &self,
+        publication_id: &str,
+        query: ModerationQueueQuery,
+    ) -> Result<Vec<ModerationQueueItem>> {
+        let status = query.status.unwrap_or(CommentModerationStatus::Pending);
+        let limit = query.limit.unwrap_or(50).clamp(1, MAX_QUEUE_PAGE_SIZE);
+        let start = query.page.unwrap_or(1).max(1).saturating_sub(1) * limit;
+        let publication_id = PublicationId::new(publication_id);
+
+        let mut response = self.db
+            .prepare(
+                r#"
+                LET $articles = SELECT meta::id(id) AS id, title, slug FROM article
+                    WHERE publication_id INSIDE $publication_ids;
+                LET $article_ids = array::flatten($articles.map(|$a| [$a.id, 'article:' + $a.id]));
+                SELECT * FROM comment
+                    WHERE article_id INSIDE $article_ids AND moderation_status = $status AND is_deleted = false
+                    ORDER BY created_at ASC
+                    LIMIT $limit START $start;
+                RETURN $articles;
+                "#,
+            )
+            .bind("publication_ids", [publication_id.as_str().to_string(), publication_id.to_record()])
+            .bind("status", status.as_str())
+            .bind("limit", limit)
+            .bind("start", start)
+            .execute()
+            .await?;
+        let raw_comments: Vec<Value> = response.take(2)?;
+        let articles: Vec<Value> = response.take(3)?;
+
+        let titles: HashMap<&str, (&str, &str)> = articles
+            .iter()
+            .filter_map(|a| {
+                Some((
+                    a.get("id")?.as_str()?,
+                    (a.get("title")?.as_str().unwrap_or_default(), a.get("slug")?.as_str().unwrap_or_default()),
+                ))
+            })
+            .collect();
+
+        Ok(parse_comments(raw_comments)?
+            .into_iter()
+            .map(|comment| {
+                let (title, slug) = titles
+                    .get(ArticleId::new(&comment.article_id).as_str())
+                    .copied()
+                    .unwrap_or_default();
+                ModerationQueueItem {
+                    comment,
+                    article_title: title.to_string(),
+                    article_slug: slug.to_string(),
+                }
+            })
+            .collect())
+    }
+
+    /// 批量审核出版物文章下的评论，返回实际更新的数量；不属于该出版物的评论会被忽略。
+    /// 因举报被隐藏的评论由平台审核员处理，这里不会改动
+    pub async fn moderate_comments(
+        &self,
+        publication_id: &str,
+        moderator_id: &str,
+        request: BulkModerationRequest,
+    ) -> Result<usize> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let status = match request.action {
+            ModerationAction::Approve => CommentModerationStatus::Approved,
+            ModerationAction::Reject => CommentModerationStatus::Rejected,
+            ModerationAction::Spam => CommentModerationStatus::Spam,
+        };
+        let comment_ids: Vec<String> = request
+            .comment_ids
+            .iter()
+            .map(|id| CommentId::new(id).as_str().to_string())
+            .collect();
+        let publication_id = PublicationId::new(publication_id);
+
+        let mut response = self.db
+            .prepare(
+                r#"
+                LET $article_ids = array::flatten((SELECT VALUE [meta::id(id), 'article:' + meta::id(id)] FROM article
+                    WHERE publication_id INSIDE $publication_ids));
+                UPDATE comment SET
+                    moderation_status = $status,
+                    moderated_by = $moderator_id,
+                    moderated_at = time::now()
+                WHERE meta::id(id) INSIDE $comment_ids AND article_id INSIDE $article_ids AND is_deleted = false
+                    AND moderation_status != 'reported'
+                RETURN BEFORE;
+                "#,
+            )
+            .bind("publication_ids", [publication_id.as_str().to_string(), publication_id.to_record()])
+            .bind("comment_ids", &comment_ids)
+            .bind("status", status.as_str())
+            .bind("moderator_id", moderator_id)
+            .execute()
+            .await?;
+        let before = parse_comments(response.take(1)?)?;
+
+        // 只有公开展示的评论计入文章评论数
+        let mut article_ids: Vec<&str> = before.iter().map(|c| c.article_id.as_str()).collect();
+        article_ids.sort();
+        article_ids.dedup();
+        for article_id in article_ids {
+            self.update_article_comment_count(article_id).await?;
+        }
+
+        // 新通过审核的评论此时才通知插件
+        if status == CommentModerationStatus::Approved {
+            for comment in before.iter().filter(|c| c.moderation_status != CommentModerationStatus::Approved) {
+                let approved = Comment { moderation_status: status, ..comment.clone() };
+                self.plugins.comment_created(&approved);
+            }
+        }
+
+        info!(
+            "Moderator {} applied {:?} to {} comments in publication {}",
+            moderator_id, request.action, before.len(), publication_id
+        );
+        Ok(before.len())
+    }
+
+    /// 隐藏被多次举报的评论，等待平台审核员处理；未公开展示的评论不受影响
+    pub async fn hide_reported(&self, comment_id: &str) -> Result<Option<Comment>> {
+        self.set_reported(comment_id, true).await
+    }
+
+    /// 举报被驳回后重新展示评论
+    pub async fn restore_reported(&self, comment_id: &str) -> Result<Option<Comment>> {
+        self.set_reported(comment_id, false).await
+    }
+
+    async fn set_reported(&self, comment_id: &str, hidden: bool) -> Result<Option<Comment>> {
+        let (from, to) = if hidden {
+            (CommentModerationStatus::Approved, CommentModerationStatus::Reported)
+        } else {
+            (CommentModerationStatus::Reported, CommentModerationStatus::Approved)
+        };
+
+        let mut response = self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('comment', $comment_id) SET
+                    moderation_status = $to,
+                    moderated_at = time::now()
+                WHERE is_deleted = false AND (moderation_status ?? 'approved') = $from;
+                "#,
+            )
+            .bind("comment_id", CommentId::new(comment_id).as_str())
+            .bind("from", from.as_str())
+            .bind("to", to.as_str())
+            .execute()
+            .await?;
+        let comment = parse_comments(response.take(0)?)?.into_iter().next();
+
+        if let Some(comment) = &comment {
+            self.update_article_comment_count(&comment.article_id).await?;
+        }
+        Ok(comment)
+    }
+
+    /// 从 Disqus XML 导出导入评论
+    ///
+    /// 讨论串按原链接的 slug（或 Disqus 页面标识）匹配到文章，只导入到
+    /// `user_id` 自己的文章（`can_import_any` 时不限）。评论以访客身份保留原作者名和时间，
+    /// 已删除和垃圾评论会被跳过，重复导入时已导入的评论不会重复创建。
+    pub async fn import_disqus(
+        &self,
+        user_id: &str,
+        can_import_any: bool,
+        xml: &str,
+        dry_run: bool,
+    ) -> Result<CommentImportReport> {
+        let export = disqus::parse_export(xml)?;
+
+        let mut posts_by_thread: HashMap<&str, Vec<&DisqusPost>> = HashMap::new();
+        for post in &export.posts {
+            posts_by_thread.entry(post.thread_id.as_str()).or_default().push(post);
+        }
+
+        let mut report = CommentImportReport {
+            dry_run,
+            threads_total: posts_by_thread.len(),
+            ..Default::default()
+        };
+
+        for (thread_id, mut posts) in posts_by_thread {
+            let thread = export.threads.get(thread_id);
+            let article = match thread {
+                Some(thread) => self.match_disqus_thread(thread, user_id, can_import_any).await?,
+                None => None,
+            };
+            let article = match article {
+                Some(article) => article,
+                None => {
+                    report.unmatched_threads.push(UnmatchedThread {
+                        thread_id: thread_id.to_string(),
+                        link: thread.map(|t| t.link.clone()).unwrap_or_default(),
+                        title: thread.map(|t| t.title.clone()).unwrap_or_default(),
+                        post_count: posts.len(),
+                    });
+                    continue;
+                }
+            };
+            report.threads_matched += 1;
+
+            // 按时间顺序创建，保证父评论先于回复
+            posts.sort_by_key(|post| post.created_at);
+
+            let external_ids: Vec<&str> = posts.iter().map(|post| post.id.as_str()).collect();
+            let mut imported = self.get_imported_comment_ids("disqus", &external_ids).await?;
+
+            let mut created_any = false;
+            for post in posts {
+                if imported.contains_key(&post.id) {
+                    report.comments_skipped_existing += 1;
+                    continue;
+                }
+                let content = disqus::html_to_text(&post.message);
+                if post.is_deleted || post.is_spam || content.is_empty() {
+                    report.comments_skipped_removed += 1;
+                    continue;
+                }
+
+                report.comments_imported += 1;
+                if dry_run {
+                    continue;
+                }
+
+                // 父评论没有被导入（如已删除）时作为顶层评论
+                let parent_id = post.parent_id.as_ref().and_then(|id| imported.get(id)).cloned();
+                let comment_id = self.create_imported_comment(&article, post, parent_id, content).await?;
+                imported.insert(post.id.clone(), comment_id);
+                created_any = true;
+            }
+
+            if created_any {
+                self.update_article_comment_count(&article.id).await?;
+            }
+        }
+
+        info!(
+            "Disqus import by {}: {} comments into {} threads, {} unmatched threads{}",
+            user_id,
+            report.comments_imported,
+            report.threads_matched,
+            report.unmatched_threads.len(),
+            if dry_run { " (dry run)" } else { "" }
+        );
+        Ok(report)
+    }
+
+    async fn match_disqus_thread(
+        &self,
+        thread: &disqus::DisqusThread,
+        user_id: &str,
+        can_import_any: bool,
+    ) -> Result<Option<Article>> {
+        let mut candidates = Vec::new();
+        if let Some(slug) = disqus::slug_from_link(&thread.link) {
+            candidates.push(slug);
+        }
+        if let Some(slug) = thread.identifier.as_deref().and_then(disqus::slug_from_link) {
+            if !candidates.contains(&slug) {
+                candidates.push(slug);
+            }
+        }
+
+        for slug in candidates {
+            let article: Option<Article> = self.db.find_one("article", "slug", &slug).await?;
+            if let Some(article) = article {
+                if article.is_deleted {
+                    continue;
+                }
+                if !can_import_any && article.author_id != user_id {
+                    warn!("Disqus thread {} matches article {} owned by another user", thread.id, article.id);
+                    continue;
+                }
+                return Ok(Some(article));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// 已导入评论的来源 ID 到评论 ID 的映射
+    async fn get_imported_comment_ids(&self, source: &str, external_ids: &[&str]) -> Result<HashMap<String, String>> {
+        let mut response = self.db.query_with_params(
+            "SELECT type::string(id) AS id, external_id FROM comment WHERE imported_from = $source AND external_id INSIDE $external_ids",
+            json!({ "source": source, "external_ids": external_ids }),
+        ).await?;
+        let rows: Vec<Value> = response.take(0)?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                Some((
+                    row["external_id"].as_str()?.to_string(),
+                    row["id"].as_str()?.to_string(),
+                ))
+            })
+            .collect())
+    }
+
+    async fn create_imported_comment(
+        &self,
+        article: &Article,
+        post: &DisqusPost,
+        parent_id: Option<String>,
+        content: String,
+    ) -> Result<String> {
+        let comment_id = Uuid::new_v4().to_string();
+        let created_at = post.created_at.unwrap_or_else(Utc::now);
+        let guest_name = post
+            .author_name
+            .clone()
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| "Anonymous".to_string());
+
+        self.db.query_with_params(
+            r#"
+                CREATE type::thing('comment', $comment_id) CONTENT {
+                    article_id: $article_id,
+                    author_id: $author_id,
+                    parent_id: $parent_id ?? NONE,
+                    content: $content,
+                    is_author_response: false,
+                    clap_count: 0,
+                    is_edited: false,
+                    is_deleted: false,
+                    guest_name: $guest_name,
+                    imported_from: 'disqus',
+                    external_id: $external_id,
+                    created_at: <datetime> $created_at,
+                    updated_at: <datetime> $created_at
+                }
+            "#,
+            json!({
+                "comment_id": comment_id,
+                "article_id": article.id,
+                "author_id": GUEST_AUTHOR_ID,
+                "parent_id": parent_id,
+                "content": content,
+                "guest_name": guest_name,
+                "external_id": post.id,
+                "created_at": created_at,
+            }),
+        ).await?;
+
+        Ok(format!("comment:{}", comment_id))
+    }
+
     pub async fn get_comment(&self, comment_id: &str) -> Result<Option<Comment>> {
         let comment: Option<Comment> = self.db.get_by_id("comment", comment_id).await?;
         
@@ -160,6 +691,7 @@ impl CommentService {
             SELECT * FROM comment 
             WHERE article_id = $article_id 
             AND is_deleted = false 
+            AND (!moderation_status OR moderation_status = 'approved')
             ORDER BY created_at DESC
         "#;
 
@@ -172,60 +704,7 @@ impl CommentService {
         
         info!("Got {} raw comments from database", raw_comments.len());
         
-        // Process each comment to fix the ID format
-        let mut processed_comments = Vec::new();
-        for mut comment_value in raw_comments {
-            // Process the main comment ID
-            if let Some(id_value) = comment_value.get("id") {
-                if let Some(id_str) = id_value.as_str() {
-                    // Handle special bracket format: comment:⟨uuid⟩
-                    if id_str.contains("⟨") && id_str.contains("⟩") {
-                        if let Some(start) = id_str.find("⟨") {
-                            if let Some(end) = id_str.find("⟩") {
-                                let uuid = &id_str[start + 3..end];
-                                comment_value["id"] = json!(format!("comment:{}", uuid));
-                            }
-                        }
-                    }
-                } else if let Some(id_obj) = id_value.as_object() {
-                    // Handle SurrealDB Thing format: {"tb": "comment", "id": {"String": "uuid"}}
-                    if let Some(id_inner) = id_obj.get("id").and_then(|v| v.as_object()) {
-                        if let Some(id_str) = id_inner.get("String").and_then(|v| v.as_str()) {
-                            comment_value["id"] = json!(format!("comment:{}", id_str));
-                        }
-                    }
-                }
-            }
-            
-            // Also process parent_id if it exists
-            if let Some(parent_id_value) = comment_value.get_mut("parent_id") {
-                if let Some(parent_str) = parent_id_value.as_str() {
-                    if parent_str.contains("⟨") && parent_str.contains("⟩") {
-                        if let Some(start) = parent_str.find("⟨") {
-                            if let Some(end) = parent_str.find("⟩") {
-                                let uuid = &parent_str[start + 3..end];
-                                *parent_id_value = json!(format!("comment:{}", uuid));
-                            }
-                        }
-                    }
-                } else if let Some(parent_obj) = parent_id_value.as_object() {
-                    // Handle SurrealDB Thing format for parent_id
-                    if let Some(id_inner) = parent_obj.get("id").and_then(|v| v.as_object()) {
-                        if let Some(id_str) = id_inner.get("String").and_then(|v| v.as_str()) {
-                            *parent_id_value = json!(format!("comment:{}", id_str));
-                        }
-                    }
-                }
-            }
-            
-            match serde_json::from_value::<Comment>(comment_value.clone()) {
-                Ok(comment) => processed_comments.push(comment),
-                Err(e) => {
-                    error!("Failed to deserialize comment: {}, raw value: {:?}", e, comment_value);
-                    return Err(AppError::Internal(format!("Failed to deserialize comment: {}", e)));
-                }
-            }
-        }
+        let processed_comments = parse_comments(raw_comments)?;
         
         info!("Successfully processed {} comments", processed_comments.len());
 
@@ -235,6 +714,272 @@ impl CommentService {
         Ok(comment_tree)
     }
 
+    /// 批量获取多篇文章公开显示的评论，不组装回复树，按不带表名的文章 ID 分组，每组按时间倒序
+    pub async fn get_comments_for_articles(&self, article_ids: &[String]) -> Result<HashMap<String, Vec<Comment>>> {
+        if article_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        // article_id 可能带或不带表名前缀
+        let ids: Vec<String> = article_ids
+            .iter()
+            .map(ArticleId::new)
+            .flat_map(|id| [id.as_str().to_string(), id.to_record()])
+            .collect();
+        let mut response = self.db
+            .prepare(
+                r#"
+                SELECT * FROM comment
+                WHERE article_id INSIDE $article_ids
+                AND is_deleted = false
+                AND (!moderation_status OR moderation_status = 'approved')
+                ORDER BY created_at DESC
+                "#,
+            )
+            .bind("article_ids", &ids)
+            .execute()
+            .await?;
+        let raw_comments: Vec<Value> = response.take(0)?;
+
+        let mut comments: HashMap<String, Vec<Comment>> = HashMap::new();
+        for comment in parse_comments(raw_comments)? {
+            comments
+                .entry(ArticleId::new(&comment.article_id).as_str().to_string())
+                .or_default()
+                .push(comment);
+        }
+        Ok(comments)
+    }
+
+    /// 分页获取某条评论的直接回复，按时间倒序；更深的回复只返回数量
+    pub async fn get_replies(
+        &self,
+        comment_id: &str,
+        user_id: Option<&str>,
+        query: CommentRepliesQuery,
+    ) -> Result<CommentReplies> {
+        let parent = self.get_comment(comment_id).await?
+            .ok_or_else(|| AppError::not_found("Comment"))?;
+        let parent_id = CommentId::new(&parent.id);
+        let limit = query.limit.unwrap_or(self.replies_per_page).clamp(1, MAX_REPLIES_PER_PAGE);
+
+        // cursor 是上一页最后一条回复，从它之后继续
+        let cursor = match query.cursor.as_deref().filter(|c| !c.is_empty()) {
+            Some(cursor) => {
+                let cursor = self.get_comment(cursor).await?
+                    .filter(|c| c.parent_id.as_deref().map(CommentId::new) == Some(parent_id.clone()))
+                    .ok_or_else(|| AppError::BadRequest("Invalid cursor".to_string()))?;
+                Some((cursor.created_at, CommentId::new(&cursor.id).as_str().to_string()))
+            }
+            None => None,
+        };
+
+        // parent_id 可能带或不带表名前缀
+        let mut response = self.db
+            .prepare(
+                r#"
+                SELECT * FROM comment
+                WHERE parent_id INSIDE $parent_ids
+                AND is_deleted = false
+                AND (!moderation_status OR moderation_status = 'approved')
+                AND (!$cursor_at OR created_at < $cursor_at OR (created_at = $cursor_at AND meta::id(id) < $cursor_id))
+                ORDER BY created_at DESC, id DESC
+                LIMIT $limit
+                "#,
+            )
+            .bind("parent_ids", [parent_id.as_str().to_string(), parent_id.to_record()])
+            .bind("cursor_at", cursor.as_ref().map(|(at, _)| *at))
+            .bind("cursor_id", cursor.map(|(_, id)| id))
+            .bind("limit", limit + 1)
+            .execute()
+            .await?;
+        let raw_replies: Vec<Value> = response.take(0)?;
+
+        let mut replies = parse_comments(raw_replies)?;
+        let next_cursor = if replies.len() > limit {
+            replies.truncate(limit);
+            replies.last().map(|c| c.id.clone())
+        } else {
+            None
+        };
+
+        let reply_counts = self.count_replies(&replies).await?;
+        let mut replies = self.build_comment_tree(replies, user_id).await?;
+        for reply in &mut replies {
+            reply.reply_count = reply_counts.get(CommentId::new(&reply.comment.id).as_str()).copied().unwrap_or(0);
+        }
+
+        Ok(CommentReplies {
+            parent_id: parent_id.to_record(),
+            replies,
+            next_cursor,
+        })
+    }
+
+    /// 只保留 `depth` 层回复（默认最大嵌套深度），被截断的评论保留 reply_count 供按需加载
+    pub fn limit_depth(&self, comments: &mut [CommentWithAuthor], depth: Option<usize>) {
+        truncate_replies(comments, depth.unwrap_or(self.max_depth).min(self.max_depth));
+    }
+
+    /// 新回复实际挂载的父评论：父评论已在最大深度时，改为挂到允许回复的最深一层祖先下
+    async fn resolve_reply_parent(&self, parent_id: &str) -> Result<String> {
+        // 从父评论向上到顶层评论
+        let mut chain = vec![parent_id.to_string()];
+        while chain.len() <= MAX_THREAD_WALK {
+            let current = chain.last().map(String::as_str).unwrap_or_default();
+            let next: Option<Option<String>> = self.db
+                .prepare("SELECT VALUE parent_id FROM type::thing('comment', $id)")
+                .bind("id", CommentId::new(current).as_str())
+                .fetch_one()
+                .await?;
+            match next.flatten().filter(|p| !p.is_empty()) {
+                Some(next) => chain.push(next),
+                None => break,
+            }
+        }
+
+        // chain[i] 的深度为 chain.len() - 1 - i；新回复的父评论最深只能在 max_depth - 1 层
+        let parent_depth = chain.len() - 1;
+        if parent_depth < self.max_depth {
+            return Ok(parent_id.to_string());
+        }
+        Ok(chain[parent_depth + 1 - self.max_depth].clone())
+    }
+
+    /// 每条评论的直接回复数
+    async fn count_replies(&self, comments: &[Comment]) -> Result<HashMap<String, i64>> {
+        if comments.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let parent_ids: Vec<String> = comments
+            .iter()
+            .flat_map(|c| {
+                let id = CommentId::new(&c.id);
+                [id.as_str().to_string(), id.to_record()]
+            })
+            .collect();
+
+        #[derive(Deserialize)]
+        struct ReplyCount {
+            parent_id: String,
+            replies: i64,
+        }
+
+        let rows: Vec<ReplyCount> = self.db
+            .prepare(
+                r#"
+                SELECT parent_id, count() AS replies FROM comment
+                WHERE parent_id INSIDE $parent_ids AND is_deleted = false
+                AND (!moderation_status OR moderation_status = 'approved')
+                GROUP BY parent_id
+                "#,
+            )
+            .bind("parent_ids", parent_ids)
+            .fetch()
+            .await?;
+
+        let mut counts = HashMap::new();
+        for row in rows {
+            *counts.entry(CommentId::new(&row.parent_id).as_str().to_string()).or_insert(0) += row.replies;
+        }
+        Ok(counts)
+    }
+
+    /// 用户上次阅读该文章评论的时间
+    pub async fn get_read_marker(&self, user_id: &str, article_id: &str) -> Result<Option<DateTime<Utc>>> {
+        let marker: Option<Option<DateTime<Utc>>> = self.db
+            .prepare("SELECT VALUE last_read_at FROM type::thing('comment_read_marker', [$user_id, $article_id])")
+            .bind("user_id", user_id)
+            .bind("article_id", ArticleId::new(article_id).as_str())
+            .fetch_one()
+            .await?;
+        Ok(marker.flatten())
+    }
+
+    /// 更新阅读进度，进度只会向后移动
+    pub async fn mark_read(
+        &self,
+        user_id: &str,
+        article_id: &str,
+        read_until: Option<DateTime<Utc>>,
+    ) -> Result<DateTime<Utc>> {
+        let read_until = read_until.unwrap_or_else(Utc::now).min(Utc::now());
+        let last_read_at: Option<DateTime<Utc>> = self.db
+            .prepare(
+                r#"
+                UPSERT type::thing('comment_read_marker', [$user_id, $article_id]) SET
+                    user_id = $user_id,
+                    article_id = $article_id,
+                    last_read_at = IF last_read_at AND last_read_at > <datetime> $read_until THEN last_read_at ELSE <datetime> $read_until END,
+                    updated_at = time::now()
+                RETURN VALUE last_read_at
+                "#,
+            )
+            .bind("user_id", user_id)
+            .bind("article_id", ArticleId::new(article_id).as_str())
+            .bind("read_until", read_until)
+            .fetch_one()
+            .await?;
+
+        Ok(last_read_at.unwrap_or(read_until))
+    }
+
+    /// 按用户的阅读进度标记新评论，并统计未读数
+    ///
+    /// 没有阅读记录时，其他人的评论都算未读，但不标记新评论（首次访问不显示分隔线）。
+    pub async fn apply_read_state(
+        &self,
+        user_id: &str,
+        article_id: &str,
+        comments: &mut [CommentWithAuthor],
+    ) -> Result<CommentReadState> {
+        let last_read_at = self.get_read_marker(user_id, article_id).await?;
+
+        let mut unread_count = 0;
+        let mut first_unread = None;
+        mark_new_comments(comments, user_id, last_read_at, &mut unread_count, &mut first_unread);
+
+        Ok(CommentReadState {
+            article_id: article_id.to_string(),
+            last_read_at,
+            unread_count,
+            first_unread_comment_id: first_unread.map(|(_, id)| id),
+        })
+    }
+
+    /// 批量获取文章的未读评论数（不含自己的评论）
+    pub async fn get_unread_counts(&self, user_id: &str, article_ids: &[String]) -> Result<HashMap<String, i64>> {
+        let mut counts = HashMap::new();
+
+        for article_id in article_ids {
+            let article = ArticleId::new(article_id);
+            // 评论的 article_id 可能带或不带表名前缀
+            let mut response = self.db
+                .prepare(
+                    r#"
+                    LET $last_read_at = (SELECT VALUE last_read_at FROM type::thing('comment_read_marker', [$user_id, $article_id]))[0];
+                    SELECT count() AS total FROM comment
+                    WHERE article_id INSIDE $article_ids
+                    AND is_deleted = false
+                    AND (!moderation_status OR moderation_status = 'approved')
+                    AND author_id != $user_id
+                    AND (!$last_read_at OR created_at > $last_read_at)
+                    GROUP ALL;
+                    "#,
+                )
+                .bind("user_id", user_id)
+                .bind("article_id", article.as_str())
+                .bind("article_ids", [article.as_str().to_string(), article.to_record()])
+                .execute()
+                .await?;
+            let total: Option<i64> = response.take((1, "total"))?;
+            counts.insert(article.to_record(), total.unwrap_or(0));
+        }
+
+        Ok(counts)
+    }
+
     pub async fn update_comment(
         &self,
         comment_id: &str,
@@ -302,6 +1047,35 @@ impl CommentService {
         Ok(())
     }
 
+    /// 从回收站恢复自己删除的评论
+    pub async fn restore_comment(&self, comment_id: &str, user_id: &str) -> Result<Comment> {
+        let comment: Comment = self
+            .db
+            .get_by_id("comment", comment_id)
+            .await?
+            .filter(|c: &Comment| c.is_deleted)
+            .ok_or_else(|| AppError::NotFound("Comment not found in trash".to_string()))?;
+
+        if comment.author_id != user_id {
+            return Err(AppError::forbidden(
+                "You can only restore your own comments",
+            ));
+        }
+
+        let restored: Comment = self
+            .db
+            .prepare("UPDATE type::thing('comment', $id) SET is_deleted = false, deleted_at = NONE, updated_at = $now RETURN *")
+            .bind("id", CommentId::new(comment_id).as_str())
+            .bind("now", Utc::now())
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::NotFound("Comment not found in trash".to_string()))?;
+
+        self.update_article_comment_count(&comment.article_id).await?;
+
+        Ok(restored)
+    }
+
     pub async fn clap_comment(&self, comment_id: &str, user_id: &str) -> Result<()> {
         let comment: Comment = self
             .db
@@ -388,7 +1162,10 @@ impl CommentService {
 
         // Prepare nodes map with empty replies
         for comment in comments.into_iter() {
-            let author_info = authors.get(&comment.author_id).cloned().unwrap_or_default();
+            let author_info = match &comment.guest_name {
+                Some(guest_name) => (guest_name.clone(), String::new(), None),
+                None => authors.get(&comment.author_id).cloned().unwrap_or_default(),
+            };
             let user_has_clapped = user_claps.get(&comment.id).copied().unwrap_or(false);
 
             nodes.insert(
@@ -399,6 +1176,8 @@ impl CommentService {
                     author_username: author_info.1,
                     author_avatar: author_info.2,
                     user_has_clapped,
+                    is_new: false,
+                    reply_count: 0,
                     replies: Vec::new(),
                 },
             );
@@ -413,6 +1192,7 @@ impl CommentService {
                 if parent_id != id {
                     if let Some(child) = nodes.remove(&id) {
                         if let Some(parent) = nodes.get_mut(&parent_id) {
+                            parent.reply_count += 1;
                             parent.replies.push(child);
                         } else {
                             // Parent not found (shouldn't happen); place back as root
@@ -500,17 +1280,13 @@ impl CommentService {
         Ok(claps)
     }
 
-    async fn update_article_comment_count(&self, article_id: &str) -> Result<()> {
-        // 获取纯 ID（不带 table 前缀）
-        let pure_id = if article_id.starts_with("article:") {
-            &article_id[8..]
-        } else {
-            article_id
-        };
+    pub(crate) async fn update_article_comment_count(&self, article_id: &str) -> Result<()> {
+        let article = ArticleId::new(article_id);
+        let pure_id = article.as_str();
 
         // 使用反引号包裹 ID（与 article.rs 保持一致）
         let query = format!(r#"
-            LET $count = (SELECT count() FROM comment WHERE article_id = $article_id AND is_deleted = false);
+            LET $count = (SELECT count() FROM comment WHERE article_id = $article_id AND is_deleted = false AND (!moderation_status OR moderation_status = 'approved'));
             UPDATE article:`{}` SET comment_count = $count;
         "#, pure_id);
 
@@ -535,7 +1311,104 @@ impl CommentService {
     }
 }
 
+/// 把 SurrealDB 返回的评论行解析为 Comment，统一 ID 格式
+fn parse_comments(raw_comments: Vec<Value>) -> Result<Vec<Comment>> {
+    let mut comments = Vec::with_capacity(raw_comments.len());
+    for comment_value in raw_comments {
+        let comment_value = normalize_comment_ids(comment_value);
+        match serde_json::from_value::<Comment>(comment_value.clone()) {
+            Ok(comment) => comments.push(comment),
+            Err(e) => {
+                error!("Failed to deserialize comment: {}, raw value: {:?}", e, comment_value);
+                return Err(AppError::Internal(format!("Failed to deserialize comment: {}", e)));
+            }
+        }
+    }
+    Ok(comments)
+}
+
+/// Convert `comment:⟨uuid⟩` strings and Thing objects in `id` / `parent_id` to `comment:uuid`
+fn normalize_comment_ids(mut comment_value: Value) -> Value {
+    // Process the main comment ID
+    if let Some(id_value) = comment_value.get("id") {
+        if let Some(id_str) = id_value.as_str() {
+            // Handle special bracket format: comment:⟨uuid⟩
+            if id_str.contains("⟨") && id_str.contains("⟩") {
+                if let Some(start) = id_str.find("⟨") {
+                    if let Some(end) = id_str.find("⟩") {
+                        let uuid = &id_str[start + 3..end];
+                        comment_value["id"] = json!(format!("comment:{}", uuid));
+                    }
+                }
+            }
+        } else if let Some(id_obj) = id_value.as_object() {
+            // Handle SurrealDB Thing format: {"tb": "comment", "id": {"String": "uuid"}}
+            if let Some(id_inner) = id_obj.get("id").and_then(|v| v.as_object()) {
+                if let Some(id_str) = id_inner.get("String").and_then(|v| v.as_str()) {
+                    comment_value["id"] = json!(format!("comment:{}", id_str));
+                }
+            }
+        }
+    }
+    
+    // Also process parent_id if it exists
+    if let Some(parent_id_value) = comment_value.get_mut("parent_id") {
+        if let Some(parent_str) = parent_id_value.as_str() {
+            if parent_str.contains("⟨") && parent_str.contains("⟩") {
+                if let Some(start) = parent_str.find("⟨") {
+                    if let Some(end) = parent_str.find("⟩") {
+                        let uuid = &parent_str[start + 3..end];
+                        *parent_id_value = json!(format!("comment:{}", uuid));
+                    }
+                }
+            }
+        } else if let Some(parent_obj) = parent_id_value.as_object() {
+            // Handle SurrealDB Thing format for parent_id
+            if let Some(id_inner) = parent_obj.get("id").and_then(|v| v.as_object()) {
+                if let Some(id_str) = id_inner.get("String").and_then(|v| v.as_str()) {
+                    *parent_id_value = json!(format!("comment:{}", id_str));
+                }
+            }
+        }
+    }
+    comment_value
+}
+
+/// 清空 `depth` 层以下的回复，reply_count 保持不变
+fn truncate_replies(nodes: &mut [CommentWithAuthor], depth: usize) {
+    for node in nodes {
+        if depth == 0 {
+            node.replies.clear();
+        } else {
+            truncate_replies(&mut node.replies, depth - 1);
+        }
+    }
+}
+
 // Helper: recursively sort replies by created_at desc
+fn mark_new_comments(
+    nodes: &mut [CommentWithAuthor],
+    user_id: &str,
+    last_read_at: Option<DateTime<Utc>>,
+    unread_count: &mut i64,
+    first_unread: &mut Option<(DateTime<Utc>, String)>,
+) {
+    for node in nodes {
+        let created_at = node.comment.created_at;
+        let unread = node.comment.author_id != user_id && last_read_at.map_or(true, |at| created_at > at);
+        if unread {
+            *unread_count += 1;
+            if last_read_at.is_some() {
+                node.is_new = true;
+                if first_unread.as_ref().map_or(true, |(at, _)| created_at < *at) {
+                    *first_unread = Some((created_at, node.comment.id.clone()));
+                }
+            }
+        }
+        mark_new_comments(&mut node.replies, user_id, last_read_at, unread_count, first_unread);
+    }
+}
+
 fn sort_replies_by_time_desc(node: &mut crate::models::comment::CommentWithAuthor) {
     node.replies.sort_by(|a, b| b.comment.created_at.cmp(&a.comment.created_at));
     for child in &mut node.replies {
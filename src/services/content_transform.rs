@@ -0,0 +1,517 @@
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+    models::content_transform::*,
+    services::Database,
+    utils::markdown::MarkdownProcessor,
+};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::RwLock;
+use serde::Deserialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+use validator::Validate;
+
+/// 上传模块的大小上限
+const MAX_MODULE_BYTES: usize = 1024 * 1024;
+
+/// 失败达到该次数后自动关闭转换器
+const MAX_FAILURES: i64 = 5;
+
+/// 出版物转换器列表的缓存时间。本实例的修改会立即清除缓存，其他实例的修改最迟在此之后生效
+const TRANSFORM_CACHE_SECONDS: i64 = 60;
+
+/// 查询转换器时不读取模块内容
+const TRANSFORM_FIELDS: &str = "id, publication_id, name, version, module_hash, size_bytes, is_active, \
+    is_enabled, priority, failure_count, last_error, created_by, created_at, updated_at";
+
+#[derive(Debug, Deserialize)]
+struct TransformModule {
+    module_base64: String,
+}
+
+struct CachedTransforms {
+    transforms: Arc<Vec<ContentTransform>>,
+    expires_at: DateTime<Utc>,
+}
+
+/// 出版物自定义内容转换（WASM 沙箱执行）
+#[derive(Clone)]
+pub struct ContentTransformService {
+    db: Arc<Database>,
+    /// 平台级开关，关闭后所有转换器都不执行
+    enabled: bool,
+    /// 按出版物缓存生效的转换器，避免每次渲染文章都查询
+    cache: Arc<RwLock<HashMap<String, CachedTransforms>>>,
+    /// 转换输出按正文渲染的规则清理
+    markdown_processor: MarkdownProcessor,
+    #[cfg(feature = "wasm-plugins")]
+    sandbox: wasm::Sandbox,
+}
+
+impl ContentTransformService {
+    pub async fn new(db: Arc<Database>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            db,
+            enabled: config.content_transforms_enabled,
+            cache: Arc::new(RwLock::new(HashMap::new())),
+            markdown_processor: MarkdownProcessor::new(),
+            #[cfg(feature = "wasm-plugins")]
+            sandbox: wasm::Sandbox::new()?,
+        })
+    }
+
+    /// 上传新版本，新版本立即生效
+    pub async fn upload(
+        &self,
+        publication_id: &str,
+        user_id: &str,
+        request: UploadContentTransformRequest,
+    ) -> Result<ContentTransform> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let module = STANDARD
+            .decode(request.module_base64.trim())
+            .map_err(|_| AppError::BadRequest("module_base64 is not valid base64".to_string()))?;
+        if module.len() > MAX_MODULE_BYTES {
+            return Err(AppError::BadRequest(format!(
+                "Transform module exceeds {} bytes",
+                MAX_MODULE_BYTES
+            )));
+        }
+        self.validate_module(&module)?;
+
+        let previous = self.get_versions(publication_id, &request.name).await?;
+        let version = previous.iter().map(|t| t.version).max().unwrap_or(0) + 1;
+        let priority = request
+            .priority
+            .or_else(|| previous.first().map(|t| t.priority))
+            .unwrap_or(100);
+
+        self.db.query_with_params(
+            "UPDATE content_transform SET is_active = false, updated_at = time::now() \
+             WHERE publication_id = $publication_id AND name = $name AND is_active = true",
+            json!({ "publication_id": publication_id, "name": request.name }),
+        ).await?;
+
+        let mut response = self.db.query_with_params(
+            r#"
+                CREATE content_transform CONTENT {
+                    publication_id: $publication_id,
+                    name: $name,
+                    version: $version,
+                    module_base64: $module_base64,
+                    module_hash: $module_hash,
+                    size_bytes: $size_bytes,
+                    is_active: true,
+                    is_enabled: true,
+                    priority: $priority,
+                    failure_count: 0,
+                    created_by: $created_by,
+                    created_at: time::now(),
+                    updated_at: time::now()
+                }
+            "#,
+            json!({
+                "publication_id": publication_id,
+                "name": request.name,
+                "version": version,
+                "module_base64": STANDARD.encode(&module),
+                "module_hash": hex::encode(Sha256::digest(&module)),
+                "size_bytes": module.len(),
+                "priority": priority,
+                "created_by": user_id,
+            }),
+        ).await?;
+        let created: Vec<ContentTransform> = response.take(0)?;
+        let transform = created
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::internal("Failed to create content transform"))?;
+
+        self.invalidate(publication_id);
+        info!(
+            "Uploaded content transform {} v{} for publication {}",
+            transform.name, transform.version, publication_id
+        );
+        Ok(transform)
+    }
+
+    /// 出版物当前生效的转换器（按执行顺序）
+    pub async fn get_transforms(&self, publication_id: &str) -> Result<Vec<ContentTransform>> {
+        let query = format!(
+            "SELECT {} FROM content_transform WHERE publication_id = $publication_id AND is_active = true \
+             ORDER BY priority ASC, name ASC",
+            TRANSFORM_FIELDS
+        );
+        let mut response = self.db.query_with_params(&query, json!({ "publication_id": publication_id })).await?;
+        Ok(response.take(0)?)
+    }
+
+    /// 某个转换器的所有版本（新版本在前）
+    pub async fn get_versions(&self, publication_id: &str, name: &str) -> Result<Vec<ContentTransform>> {
+        let query = format!(
+            "SELECT {} FROM content_transform WHERE publication_id = $publication_id AND name = $name \
+             ORDER BY version DESC",
+            TRANSFORM_FIELDS
+        );
+        let mut response = self.db.query_with_params(&query, json!({ "publication_id": publication_id, "name": name })).await?;
+        Ok(response.take(0)?)
+    }
+
+    /// 切换生效版本（用于回滚）
+    pub async fn activate_version(&self, publication_id: &str, name: &str, version: i32) -> Result<ContentTransform> {
+        let versions = self.get_versions(publication_id, name).await?;
+        let target = versions
+            .iter()
+            .find(|t| t.version == version)
+            .ok_or_else(|| AppError::not_found("Transform version"))?;
+        let current = versions.iter().find(|t| t.is_active);
+
+        for transform in &versions {
+            if transform.is_active && transform.id != target.id {
+                let _: Option<ContentTransform> = self.db.update_by_id_with_json(
+                    "content_transform",
+                    &transform.id,
+                    json!({ "is_active": false, "updated_at": chrono::Utc::now() }),
+                ).await?;
+            }
+        }
+
+        // 回滚时沿用当前的执行顺序和开关状态
+        let updated: Option<ContentTransform> = self.db.update_by_id_with_json(
+            "content_transform",
+            &target.id,
+            json!({
+                "is_active": true,
+                "is_enabled": current.map_or(true, |t| t.is_enabled),
+                "priority": current.map_or(target.priority, |t| t.priority),
+                "updated_at": chrono::Utc::now(),
+            }),
+        ).await?;
+
+        self.invalidate(publication_id);
+        info!("Activated content transform {} v{} for publication {}", name, version, publication_id);
+        updated.ok_or_else(|| AppError::internal("Failed to activate transform version"))
+    }
+
+    /// 修改开关或执行顺序，重新启用时清空失败计数
+    pub async fn update_transform(
+        &self,
+        publication_id: &str,
+        name: &str,
+        request: UpdateContentTransformRequest,
+    ) -> Result<ContentTransform> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let transform = self
+            .get_transforms(publication_id)
+            .await?
+            .into_iter()
+            .find(|t| t.name == name)
+            .ok_or_else(|| AppError::not_found("Transform"))?;
+
+        let mut updates = json!({ "updated_at": chrono::Utc::now() });
+        if let Some(is_enabled) = request.is_enabled {
+            updates["is_enabled"] = json!(is_enabled);
+            if is_enabled {
+                updates["failure_count"] = json!(0);
+            }
+        }
+        if let Some(priority) = request.priority {
+            updates["priority"] = json!(priority);
+        }
+
+        let updated: Option<ContentTransform> = self
+            .db
+            .update_by_id_with_json("content_transform", &transform.id, updates)
+            .await?;
+        self.invalidate(publication_id);
+        updated.ok_or_else(|| AppError::internal("Failed to update transform"))
+    }
+
+    /// 删除转换器的所有版本
+    pub async fn delete_transform(&self, publication_id: &str, name: &str) -> Result<()> {
+        self.db.query_with_params(
+            "DELETE content_transform WHERE publication_id = $publication_id AND name = $name",
+            json!({ "publication_id": publication_id, "name": name }),
+        ).await?;
+
+        self.invalidate(publication_id);
+        info!("Deleted content transform {} for publication {}", name, publication_id);
+        Ok(())
+    }
+
+    /// 依次执行出版物启用的转换器，输出按正文渲染的规则清理后返回
+    ///
+    /// 单个转换器失败时跳过并记录，不影响文章渲染。
+    pub async fn apply(&self, publication_id: &str, html: String) -> String {
+        if !self.enabled {
+            return html;
+        }
+
+        let transforms = match self.active_transforms(publication_id).await {
+            Ok(transforms) => transforms,
+            Err(e) => {
+                warn!("Failed to load content transforms for {}: {}", publication_id, e);
+                return html;
+            }
+        };
+        if !transforms.iter().any(|t| t.is_enabled) {
+            return html;
+        }
+
+        debug!("Applying {} content transforms for publication {}", transforms.len(), publication_id);
+
+        let mut html = html;
+        for transform in transforms.iter().filter(|t| t.is_enabled) {
+            match self.run_transform(transform, &html).await {
+                Ok(output) => html = output,
+                Err(e) => {
+                    warn!("Content transform {} v{} failed: {}", transform.name, transform.version, e);
+                    if let Err(e) = self.record_failure(transform, &e.to_string()).await {
+                        warn!("Failed to record transform failure: {}", e);
+                    }
+                }
+            }
+        }
+
+        // 转换器由出版物上传，输出不可信
+        self.markdown_processor.sanitize_html(&html)
+    }
+
+    async fn active_transforms(&self, publication_id: &str) -> Result<Arc<Vec<ContentTransform>>> {
+        if let Some(cached) = self.cache.read().get(publication_id) {
+            if cached.expires_at > Utc::now() {
+                return Ok(cached.transforms.clone());
+            }
+        }
+
+        let transforms = Arc::new(self.get_transforms(publication_id).await?);
+        self.cache.write().insert(publication_id.to_string(), CachedTransforms {
+            transforms: transforms.clone(),
+            expires_at: Utc::now() + Duration::seconds(TRANSFORM_CACHE_SECONDS),
+        });
+        Ok(transforms)
+    }
+
+    fn invalidate(&self, publication_id: &str) {
+        self.cache.write().remove(publication_id);
+    }
+
+    async fn record_failure(&self, transform: &ContentTransform, error: &str) -> Result<()> {
+        let failure_count = transform.failure_count + 1;
+        let disable = failure_count >= MAX_FAILURES;
+        if disable {
+            warn!(
+                "Disabling content transform {} v{} after {} failures",
+                transform.name, transform.version, failure_count
+            );
+        }
+
+        let _: Option<ContentTransform> = self.db.update_by_id_with_json(
+            "content_transform",
+            &transform.id,
+            json!({
+                "failure_count": failure_count,
+                "last_error": error,
+                "is_enabled": !disable,
+                "updated_at": chrono::Utc::now(),
+            }),
+        ).await?;
+        // 失败计数和开关状态变化后重新读取
+        self.invalidate(&transform.publication_id);
+        Ok(())
+    }
+
+    #[cfg(feature = "wasm-plugins")]
+    async fn load_module(&self, transform: &ContentTransform) -> Result<Vec<u8>> {
+        let module: Option<TransformModule> = self.db.get_by_id("content_transform", &transform.id).await?;
+        let module = module.ok_or_else(|| AppError::not_found("Transform"))?;
+        STANDARD
+            .decode(module.module_base64)
+            .map_err(|e| AppError::Internal(format!("Stored transform module is corrupt: {}", e)))
+    }
+
+    #[cfg(feature = "wasm-plugins")]
+    fn validate_module(&self, module: &[u8]) -> Result<()> {
+        self.sandbox.validate(module)
+    }
+
+    #[cfg(not(feature = "wasm-plugins"))]
+    fn validate_module(&self, _module: &[u8]) -> Result<()> {
+        Err(AppError::BadRequest("WASM transforms are not supported on this server".to_string()))
+    }
+
+    #[cfg(feature = "wasm-plugins")]
+    async fn run_transform(&self, transform: &ContentTransform, html: &str) -> Result<String> {
+        let module = match self.sandbox.cached(&transform.id) {
+            Some(module) => module,
+            None => {
+                let bytes = self.load_module(transform).await?;
+                self.sandbox.compile(&transform.id, &bytes)?
+            }
+        };
+        self.sandbox.run(module, html.to_string()).await
+    }
+
+    #[cfg(not(feature = "wasm-plugins"))]
+    async fn run_transform(&self, _transform: &ContentTransform, _html: &str) -> Result<String> {
+        Err(AppError::internal("WASM transforms are not supported on this server"))
+    }
+}
+
+/// WASM 沙箱
+///
+/// 模块需要导出 `memory`、`alloc(len: i32) -> i32` 和
+/// `transform(ptr: i32, len: i32) -> i64`。输入为 UTF-8 编码的 HTML，
+/// 返回值高 32 位为输出地址、低 32 位为输出长度，负数表示失败。
+/// 引擎、燃料和内存限制与插件共用 [`wasm_runtime`](crate::services::wasm_runtime)。
+#[cfg(feature = "wasm-plugins")]
+mod wasm {
+    use crate::error::{AppError, Result};
+    use crate::services::wasm_runtime::{self, Call};
+    use parking_lot::RwLock;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use wasmtime::{Engine, ExternType, Module};
+
+    /// 单次转换可消耗的燃料上限
+    const FUEL_PER_RUN: u64 = 20_000_000;
+    /// 输出 HTML 的大小上限
+    const MAX_OUTPUT_BYTES: usize = 2 * 1024 * 1024;
+
+    #[derive(Clone)]
+    pub struct Sandbox {
+        engine: Engine,
+        /// 按转换器版本 ID 缓存编译结果，版本内容不可变
+        modules: Arc<RwLock<HashMap<String, Module>>>,
+    }
+
+    impl Sandbox {
+        pub fn new() -> Result<Self> {
+            Ok(Self {
+                engine: wasm_runtime::engine()?,
+                modules: Arc::new(RwLock::new(HashMap::new())),
+            })
+        }
+
+        /// 检查模块能否编译，以及是否导出了所需的函数
+        pub fn validate(&self, bytes: &[u8]) -> Result<()> {
+            let module = Module::new(&self.engine, bytes)
+                .map_err(|e| AppError::BadRequest(format!("Invalid WASM module: {}", e)))?;
+
+            for (name, expected) in [("memory", "memory"), ("alloc", "func"), ("transform", "func")] {
+                let found = match module.get_export(name) {
+                    Some(ExternType::Memory(_)) => "memory",
+                    Some(ExternType::Func(_)) => "func",
+                    _ => "",
+                };
+                if found != expected {
+                    return Err(AppError::BadRequest(format!("WASM module must export {} `{}`", expected, name)));
+                }
+            }
+
+            if module.imports().next().is_some() {
+                return Err(AppError::BadRequest("WASM transforms may not import host functions".to_string()));
+            }
+
+            Ok(())
+        }
+
+        pub fn cached(&self, id: &str) -> Option<Module> {
+            self.modules.read().get(id).cloned()
+        }
+
+        pub fn compile(&self, id: &str, bytes: &[u8]) -> Result<Module> {
+            let module = Module::new(&self.engine, bytes)
+                .map_err(|e| AppError::Internal(format!("WASM transform error: {}", e)))?;
+            self.modules.write().insert(id.to_string(), module.clone());
+            Ok(module)
+        }
+
+        pub async fn run(&self, module: Module, input: String) -> Result<String> {
+            let engine = self.engine.clone();
+            tokio::task::spawn_blocking(move || run(&engine, &module, input.as_bytes()))
+                .await
+                .map_err(|e| AppError::Internal(format!("WASM transform task failed: {}", e)))?
+        }
+    }
+
+    fn run(engine: &Engine, module: &Module, input: &[u8]) -> Result<String> {
+        let mut call = Call::new(engine, module, FUEL_PER_RUN)?;
+        let transform = call.func::<(i32, i32), i64>("transform")?;
+        let (ptr, len) = call.write_input(input)?;
+
+        let packed = call.call(&transform, (ptr, len))?;
+        if packed < 0 {
+            return Err(AppError::Internal(format!("transform returned {}", packed)));
+        }
+        let out_ptr = (packed >> 32) as usize;
+        let out_len = (packed & 0xffff_ffff) as usize;
+        if out_len > MAX_OUTPUT_BYTES {
+            return Err(AppError::Internal(format!("transform output exceeds {} bytes", MAX_OUTPUT_BYTES)));
+        }
+
+        let output = call.read_output(out_ptr, out_len)?;
+        String::from_utf8(output).map_err(|_| AppError::internal("transform output is not valid UTF-8"))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// 原样返回输入
+        const IDENTITY: &str = r#"
+            (module
+              (memory (export "memory") 1)
+              (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+              (func (export "transform") (param $ptr i32) (param $len i32) (result i64)
+                (i64.or
+                  (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+                  (i64.extend_i32_u (local.get $len)))))
+        "#;
+
+        /// 死循环，应被燃料限制终止
+        const SPIN: &str = r#"
+            (module
+              (memory (export "memory") 1)
+              (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+              (func (export "transform") (param i32 i32) (result i64)
+                (loop $l (br $l))
+                (i64.const 0)))
+        "#;
+
+        /// 尝试申请超过上限的内存
+        const GREEDY: &str = r#"
+            (module
+              (memory (export "memory") 1)
+              (func (export "alloc") (param i32) (result i32) (i32.const 1024))
+              (func (export "transform") (param i32 i32) (result i64)
+                (if (i32.eq (memory.grow (i32.const 1024)) (i32.const -1))
+                  (then (return (i64.const -1))))
+                (i64.const 0)))
+        "#;
+
+        #[tokio::test]
+        async fn test_sandbox_enforces_limits() {
+            let sandbox = Sandbox::new().unwrap();
+            let html = "<p>Hello</p>".to_string();
+
+            sandbox.validate(IDENTITY.as_bytes()).unwrap();
+            let identity = sandbox.compile("identity", IDENTITY.as_bytes()).unwrap();
+            assert_eq!(sandbox.run(identity, html.clone()).await.unwrap(), html);
+            assert!(sandbox.cached("identity").is_some());
+
+            let spin = sandbox.compile("spin", SPIN.as_bytes()).unwrap();
+            assert!(sandbox.run(spin, html.clone()).await.is_err());
+
+            let greedy = sandbox.compile("greedy", GREEDY.as_bytes()).unwrap();
+            assert!(sandbox.run(greedy, html).await.is_err());
+        }
+    }
+}
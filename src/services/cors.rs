@@ -0,0 +1,203 @@
+use crate::{
+    error::{AppError, Result},
+    models::{cors::*, id::{bare_id, PublicationId}},
+    services::Database,
+    utils::cache::Cache,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+use url::Url;
+
+/// 每个出版物允许的来源数量上限
+const MAX_ORIGINS_PER_PUBLICATION: usize = 20;
+/// 来源列表在内存中的缓存时间，修改时立即失效
+const ORIGIN_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// 出版物级别的 CORS 来源：只对该出版物的内容接口生效，不影响平台其他接口
+#[derive(Clone)]
+pub struct CorsService {
+    db: Arc<Database>,
+    /// 出版物 ID 或 slug -> 允许的来源
+    cache: Cache<Arc<Vec<String>>>,
+}
+
+impl CorsService {
+    pub async fn new(db: Arc<Database>) -> Result<Self> {
+        Ok(Self {
+            db,
+            cache: Cache::new(ORIGIN_CACHE_TTL),
+        })
+    }
+
+    pub async fn list_origins(&self, publication_id: &str) -> Result<Vec<PublicationCorsOrigin>> {
+        self.db
+            .prepare("SELECT * FROM publication_cors_origin WHERE publication_id = $publication_id ORDER BY created_at ASC")
+            .bind("publication_id", PublicationId::new(publication_id).as_str())
+            .fetch()
+            .await
+    }
+
+    pub async fn add_origin(
+        &self,
+        publication_id: &str,
+        user_id: &str,
+        request: AddCorsOriginRequest,
+    ) -> Result<PublicationCorsOrigin> {
+        let origin = normalize_origin(&request.origin).map_err(AppError::Validation)?;
+        let publication_id = PublicationId::new(publication_id);
+
+        let existing = self.list_origins(publication_id.as_str()).await?;
+        if existing.iter().any(|o| o.origin == origin) {
+            return Err(AppError::Conflict(format!("Origin {} is already allowed", origin)));
+        }
+        if existing.len() >= MAX_ORIGINS_PER_PUBLICATION {
+            return Err(AppError::BadRequest(format!(
+                "A publication can allow at most {} origins",
+                MAX_ORIGINS_PER_PUBLICATION
+            )));
+        }
+
+        let created: Option<PublicationCorsOrigin> = self.db
+            .prepare(
+                r#"
+                CREATE publication_cors_origin CONTENT {
+                    publication_id: $publication_id,
+                    origin: $origin,
+                    created_by: $user_id,
+                    created_at: time::now()
+                }
+                "#,
+            )
+            .bind("publication_id", publication_id.as_str())
+            .bind("origin", &origin)
+            .bind("user_id", user_id)
+            .fetch_one()
+            .await?;
+        self.invalidate();
+
+        info!("Allowed CORS origin {} for publication {}", origin, publication_id);
+        created.ok_or_else(|| AppError::Internal("Failed to add CORS origin".to_string()))
+    }
+
+    pub async fn remove_origin(&self, publication_id: &str, origin_id: &str) -> Result<()> {
+        let removed: Vec<PublicationCorsOrigin> = self.db
+            .prepare("DELETE publication_cors_origin WHERE id = type::thing('publication_cors_origin', $origin_id) AND publication_id = $publication_id RETURN BEFORE")
+            .bind("origin_id", bare_id("publication_cors_origin", origin_id))
+            .bind("publication_id", PublicationId::new(publication_id).as_str())
+            .fetch()
+            .await?;
+        if removed.is_empty() {
+            return Err(AppError::not_found("CORS origin"));
+        }
+        self.invalidate();
+        Ok(())
+    }
+
+    /// 请求来源是否被出版物允许，`publication` 可以是出版物 ID 或 slug
+    pub async fn is_allowed(&self, publication: &str, origin: &str) -> bool {
+        let key = PublicationId::new(publication).as_str().to_string();
+
+        let origins = match self.cache.get(&key) {
+            Ok(Some(origins)) => origins,
+            _ => match self.load_origins(&key).await {
+                Ok(origins) => {
+                    let origins = Arc::new(origins);
+                    let _ = self.cache.set(key, origins.clone());
+                    origins
+                }
+                Err(e) => {
+                    warn!("Failed to load CORS origins for publication {}: {}", publication, e);
+                    return false;
+                }
+            },
+        };
+
+        origins.iter().any(|allowed| origin_matches(allowed, origin))
+    }
+
+    async fn load_origins(&self, key: &str) -> Result<Vec<String>> {
+        self.db
+            .prepare(
+                r#"
+                LET $publication = (SELECT VALUE meta::id(id) FROM publication
+                    WHERE id = type::thing('publication', $key) OR slug = $key LIMIT 1)[0];
+                SELECT VALUE origin FROM publication_cors_origin WHERE publication_id = $publication;
+                "#,
+            )
+            .bind("key", key)
+            .execute()
+            .await?
+            .take(1)
+            .map_err(Into::into)
+    }
+
+    fn invalidate(&self) {
+        let _ = self.cache.clear();
+    }
+}
+
+/// 规范化为 `scheme://host[:port]`；只允许 https（本地开发的 localhost 除外）
+pub fn normalize_origin(origin: &str) -> std::result::Result<String, String> {
+    let origin = origin.trim().trim_end_matches('/');
+    let (wildcard, parse_target) = match origin.split_once("://*.") {
+        Some((scheme, rest)) => (true, format!("{}://{}", scheme, rest)),
+        None => (false, origin.to_string()),
+    };
+
+    let url = Url::parse(&parse_target).map_err(|_| format!("Invalid origin: {}", origin))?;
+    let host = url.host_str().ok_or_else(|| format!("Invalid origin: {}", origin))?;
+    if url.path() != "/" || url.query().is_some() || url.fragment().is_some() || !url.username().is_empty() {
+        return Err("Origin must not contain a path, query or credentials".to_string());
+    }
+
+    let is_local = matches!(host, "localhost" | "127.0.0.1");
+    match url.scheme() {
+        "https" => {}
+        "http" if is_local => {}
+        _ => return Err("Origin must use https".to_string()),
+    }
+    if wildcard && (is_local || !host.contains('.')) {
+        return Err("Wildcard origins must cover a registrable domain".to_string());
+    }
+
+    let port = url.port().map(|p| format!(":{}", p)).unwrap_or_default();
+    Ok(format!("{}://{}{}{}", url.scheme(), if wildcard { "*." } else { "" }, host, port))
+}
+
+/// `https://*.example.com` 匹配其任意子域名，但不匹配 `https://example.com` 本身
+pub fn origin_matches(allowed: &str, origin: &str) -> bool {
+    if allowed.eq_ignore_ascii_case(origin) {
+        return true;
+    }
+
+    match (allowed.split_once("://*."), origin.split_once("://")) {
+        (Some((scheme, suffix)), Some((origin_scheme, host))) => {
+            scheme.eq_ignore_ascii_case(origin_scheme)
+                && host.len() > suffix.len() + 1
+                && host.to_ascii_lowercase().ends_with(&format!(".{}", suffix.to_ascii_lowercase()))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_and_match_origins() {
+        assert_eq!(normalize_origin("https://Blog.Example.com/").unwrap(), "https://blog.example.com");
+        assert_eq!(normalize_origin("http://localhost:3000").unwrap(), "http://localhost:3000");
+        assert_eq!(normalize_origin("https://*.example.com").unwrap(), "https://*.example.com");
+        assert!(normalize_origin("http://example.com").is_err());
+        assert!(normalize_origin("https://example.com/embed").is_err());
+        assert!(normalize_origin("https://*.com").is_err());
+
+        assert!(origin_matches("https://blog.example.com", "https://blog.example.com"));
+        assert!(origin_matches("https://*.example.com", "https://shop.example.com"));
+        assert!(!origin_matches("https://*.example.com", "https://example.com"));
+        assert!(!origin_matches("https://*.example.com", "https://evil-example.com"));
+        assert!(!origin_matches("https://*.example.com", "http://shop.example.com"));
+    }
+}
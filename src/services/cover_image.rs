@@ -0,0 +1,379 @@
+use crate::{
+    error::{AppError, Result},
+    models::{article::Article, plugin::ArticlePublishedEvent},
+    services::{plugin::Plugin, Database, MediaService},
+};
+use async_trait::async_trait;
+use handlebars::Handlebars;
+use resvg::{
+    tiny_skia,
+    usvg::{self, fontdb, TreeParsing, TreeTextToPath},
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// 封面/OG 图尺寸（Open Graph 推荐 1200x630）
+const COVER_WIDTH: u32 = 1200;
+const COVER_HEIGHT: u32 = 630;
+const DEFAULT_THEME_COLOR: &str = "#1a1a2e";
+/// 标题每行可容纳的宽度单位（拉丁字符为 1，CJK 字符为 2）
+const TITLE_LINE_UNITS: usize = 32;
+const TITLE_MAX_LINES: usize = 3;
+
+const COVER_SVG_TEMPLATE: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="{{width}}" height="{{height}}" viewBox="0 0 {{width}} {{height}}">
+  <defs>
+    <linearGradient id="bg" x1="0" y1="0" x2="1" y2="1">
+      <stop offset="0%" stop-color="{{background}}"/>
+      <stop offset="100%" stop-color="{{background_dark}}"/>
+    </linearGradient>
+  </defs>
+  <rect width="100%" height="100%" fill="url(#bg)"/>
+  <rect x="80" y="96" width="96" height="8" rx="4" fill="{{foreground}}" fill-opacity="0.6"/>
+  {{#each title_lines}}
+  <text x="80" y="{{this.y}}" font-family="Noto Sans CJK SC, Noto Sans, DejaVu Sans, sans-serif" font-size="64" font-weight="700" fill="{{../foreground}}">{{this.text}}</text>
+  {{/each}}
+  <text x="80" y="540" font-family="Noto Sans CJK SC, Noto Sans, DejaVu Sans, sans-serif" font-size="32" fill="{{foreground}}" fill-opacity="0.85">{{byline}}</text>
+</svg>"##;
+
+#[derive(Debug, Serialize)]
+struct TitleLine {
+    text: String,
+    y: u32,
+}
+
+/// 品牌封面生成服务：文章没有封面时根据标题、作者和出版物配色生成 PNG，同时作为 OG 图片
+#[derive(Clone)]
+pub struct CoverImageService {
+    db: Arc<Database>,
+    media_service: MediaService,
+    templates: Arc<Handlebars<'static>>,
+    fonts: Arc<fontdb::Database>,
+}
+
+impl CoverImageService {
+    pub async fn new(db: Arc<Database>, media_service: MediaService) -> Result<Self> {
+        let mut templates = Handlebars::new();
+        templates
+            .register_template_string("cover_svg", COVER_SVG_TEMPLATE)
+            .map_err(|e| AppError::internal(&format!("Failed to register cover template: {}", e)))?;
+
+        let mut fonts = fontdb::Database::new();
+        fonts.load_system_fonts();
+        debug!("Loaded {} font faces for cover rendering", fonts.len());
+
+        Ok(Self {
+            db,
+            media_service,
+            templates: Arc::new(templates),
+            fonts: Arc::new(fonts),
+        })
+    }
+
+    /// 没有封面的文章生成品牌封面，并写回 cover_image_url 和 OG 图片地址
+    pub async fn apply_generated_cover(&self, mut article: Article) -> Result<Article> {
+        let cover_is_generated = article.metadata.get("cover_generated").and_then(|v| v.as_bool()).unwrap_or(false);
+        let generated_for_title = article.metadata.get("cover_title").and_then(|v| v.as_str());
+
+        // 作者自己的封面不覆盖；已生成的封面只在标题变化后重新生成
+        let needs_cover = match &article.cover_image_url {
+            None => true,
+            Some(_) => cover_is_generated && generated_for_title != Some(article.title.as_str()),
+        };
+        if !needs_cover {
+            return Ok(article);
+        }
+
+        let url = self.generate_for_article(&article).await?;
+        article.cover_image_url = Some(url.clone());
+        set_metadata(&mut article.metadata, "cover_generated", json!(true));
+        set_metadata(&mut article.metadata, "cover_title", json!(article.title));
+        set_metadata(&mut article.metadata, "og_image_url", json!(url));
+
+        self.db.update_by_id_with_json("article", &article.id, json!({
+            "cover_image_url": article.cover_image_url,
+            "metadata": article.metadata,
+        })).await?;
+
+        info!("Generated cover image for article {}", article.id);
+        Ok(article)
+    }
+
+    /// 已生成的 OG 图片地址，没有时退回到文章封面。只读，不在请求中渲染图片
+    pub fn og_image_url(&self, article: &Article) -> Option<String> {
+        article.metadata
+            .get("og_image_url")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .or_else(|| article.cover_image_url.clone())
+    }
+
+    /// 生成文章的 OG 图片并缓存到文章 metadata，标题没有变化时直接返回已有地址。
+    /// 在文章发布和更新时调用
+    pub async fn refresh_og_image(&self, article: &Article) -> Result<String> {
+        let cached = article.metadata.get("og_image_url").and_then(|v| v.as_str());
+        let generated_for_title = article.metadata.get("og_image_title")
+            .or_else(|| article.metadata.get("cover_title"))
+            .and_then(|v| v.as_str());
+
+        if let Some(url) = cached {
+            if generated_for_title == Some(article.title.as_str()) {
+                return Ok(url.to_string());
+            }
+        }
+
+        let url = self.generate_for_article(article).await?;
+        let mut metadata = article.metadata.clone();
+        set_metadata(&mut metadata, "og_image_url", json!(url));
+        set_metadata(&mut metadata, "og_image_title", json!(article.title));
+
+        self.db.update_by_id_with_json("article", &article.id, json!({ "metadata": metadata })).await?;
+
+        info!("Generated OG image for article {}", article.id);
+        Ok(url)
+    }
+
+    /// 渲染文章封面并通过媒体服务保存，返回公开地址
+    pub async fn generate_for_article(&self, article: &Article) -> Result<String> {
+        let author_name = self.get_author_name(&article.author_id).await?;
+        let (publication_name, theme_color) = match &article.publication_id {
+            Some(publication_id) => self.get_publication_branding(publication_id).await?,
+            None => (None, None),
+        };
+
+        let byline = match publication_name {
+            Some(publication) => format!("{} · {}", author_name, publication),
+            None => author_name,
+        };
+
+        let png = self.render_png(&article.title, &byline, theme_color.as_deref()).await?;
+        let filename = format!("cover-{}.png", article.slug);
+        let upload = self.media_service
+            .upload_image(&article.author_id, &filename, "image/png", png)
+            .await?;
+
+        Ok(upload.url)
+    }
+
+    /// 将 SVG 模板渲染为 PNG。栅格化在阻塞线程池中执行，不占用异步工作线程
+    pub async fn render_png(&self, title: &str, byline: &str, theme_color: Option<&str>) -> Result<Vec<u8>> {
+        let background = theme_color
+            .and_then(parse_hex_color)
+            .unwrap_or_else(|| parse_hex_color(DEFAULT_THEME_COLOR).unwrap());
+
+        let title_lines: Vec<TitleLine> = wrap_title(title, TITLE_LINE_UNITS, TITLE_MAX_LINES)
+            .into_iter()
+            .enumerate()
+            .map(|(i, text)| TitleLine { text, y: 220 + i as u32 * 84 })
+            .collect();
+
+        let svg = self.templates
+            .render("cover_svg", &json!({
+                "width": COVER_WIDTH,
+                "height": COVER_HEIGHT,
+                "background": to_hex(background),
+                "background_dark": to_hex(darken(background, 0.35)),
+                "foreground": if is_light(background) { "#111111" } else { "#ffffff" },
+                "title_lines": title_lines,
+                "byline": byline,
+            }))
+            .map_err(|e| AppError::ImageProcessing(format!("Failed to render cover template: {}", e)))?;
+
+        let fonts = self.fonts.clone();
+        tokio::task::spawn_blocking(move || rasterize(&svg, &fonts))
+            .await
+            .map_err(|e| AppError::internal(&format!("Cover rendering task failed: {}", e)))?
+    }
+
+    async fn get_author_name(&self, author_id: &str) -> Result<String> {
+        let mut response = self.db.query_with_params(
+            "SELECT display_name, username FROM user_profile WHERE user_id = $user_id LIMIT 1",
+            json!({ "user_id": author_id }),
+        ).await?;
+        let rows: Vec<Value> = response.take(0)?;
+
+        Ok(rows
+            .first()
+            .and_then(|r| {
+                r.get("display_name")
+                    .and_then(|v| v.as_str())
+                    .filter(|s| !s.is_empty())
+                    .or_else(|| r.get("username").and_then(|v| v.as_str()))
+            })
+            .unwrap_or("Rainbow Blog")
+            .to_string())
+    }
+
+    async fn get_publication_branding(&self, publication_id: &str) -> Result<(Option<String>, Option<String>)> {
+        let publication_id = if publication_id.starts_with("publication:") {
+            publication_id.to_string()
+        } else {
+            format!("publication:{}", publication_id)
+        };
+
+        let mut response = self.db.query_with_params(
+            "SELECT name, theme_color FROM publication WHERE type::string(id) = $id LIMIT 1",
+            json!({ "id": publication_id }),
+        ).await?;
+        let rows: Vec<Value> = response.take(0)?;
+        let row = match rows.first() {
+            Some(row) => row,
+            None => return Ok((None, None)),
+        };
+
+        Ok((
+            row.get("name").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            row.get("theme_color").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        ))
+    }
+}
+
+/// 文章发布后在后台生成 OG 图片，分享链接被抓取时图片已经就绪
+#[async_trait]
+impl Plugin for CoverImageService {
+    fn name(&self) -> &str {
+        "cover_image"
+    }
+
+    async fn on_article_published(&self, event: &ArticlePublishedEvent) -> Result<()> {
+        let service = self.clone();
+        let article_id = event.article_id.clone();
+        tokio::spawn(async move {
+            let article: Option<Article> = match service.db.get_by_id("article", &article_id).await {
+                Ok(article) => article,
+                Err(e) => {
+                    warn!("Failed to load article {} for OG image: {}", article_id, e);
+                    return;
+                }
+            };
+            if let Some(article) = article {
+                if let Err(e) = service.refresh_og_image(&article).await {
+                    warn!("Failed to generate OG image for article {}: {}", article_id, e);
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+fn rasterize(svg: &str, fonts: &fontdb::Database) -> Result<Vec<u8>> {
+    let mut tree = usvg::Tree::from_str(svg, &usvg::Options::default())
+        .map_err(|e| AppError::ImageProcessing(format!("Invalid cover SVG: {}", e)))?;
+    tree.convert_text(fonts);
+
+    let mut pixmap = tiny_skia::Pixmap::new(COVER_WIDTH, COVER_HEIGHT)
+        .ok_or_else(|| AppError::ImageProcessing("Failed to allocate cover canvas".to_string()))?;
+    resvg::Tree::from_usvg(&tree).render(tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    pixmap
+        .encode_png()
+        .map_err(|e| AppError::ImageProcessing(format!("Failed to encode cover PNG: {}", e)))
+}
+
+fn set_metadata(metadata: &mut Value, key: &str, value: Value) {
+    if !metadata.is_object() {
+        *metadata = json!({});
+    }
+    metadata[key] = value;
+}
+
+/// 按显示宽度折行（CJK 字符占两个单位），超出行数时在末行加省略号
+fn wrap_title(title: &str, line_units: usize, max_lines: usize) -> Vec<String> {
+    let char_units = |c: char| if c.is_ascii() { 1 } else { 2 };
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut current_units = 0;
+
+    // 英文按单词折行，CJK 逐字折行；记录原文中 token 前是否有空格
+    let mut tokens: Vec<(String, bool)> = Vec::new();
+    for (word_index, word) in title.split_whitespace().enumerate() {
+        if word.is_ascii() {
+            tokens.push((word.to_string(), word_index > 0));
+        } else {
+            tokens.extend(word.chars().enumerate().map(|(i, c)| (c.to_string(), word_index > 0 && i == 0)));
+        }
+    }
+
+    for (token, space_before) in tokens {
+        let token_units: usize = token.chars().map(char_units).sum();
+        let extra = if space_before && !current.is_empty() { 1 } else { 0 };
+
+        if current_units + extra + token_units > line_units && !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+            current_units = 0;
+        } else if extra == 1 {
+            current.push(' ');
+            current_units += 1;
+        }
+
+        current.push_str(&token);
+        current_units += token_units;
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    if lines.len() > max_lines {
+        lines.truncate(max_lines);
+        if let Some(last) = lines.last_mut() {
+            last.push('…');
+        }
+    }
+
+    lines
+}
+
+fn parse_hex_color(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.trim().strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+
+    let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+    let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+    let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+    Some((r, g, b))
+}
+
+fn to_hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+fn darken((r, g, b): (u8, u8, u8), amount: f64) -> (u8, u8, u8) {
+    let scale = |c: u8| (c as f64 * (1.0 - amount)).round() as u8;
+    (scale(r), scale(g), scale(b))
+}
+
+/// 根据相对亮度判断背景是否为浅色，决定使用深色还是浅色文字
+fn is_light((r, g, b): (u8, u8, u8)) -> bool {
+    let luminance = 0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64;
+    luminance > 160.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_title() {
+        let lines = wrap_title("Building a blog engine with Rust and SurrealDB from scratch", 20, 3);
+        assert_eq!(lines, vec!["Building a blog", "engine with Rust and", "SurrealDB from…"]);
+
+        let lines = wrap_title("Short title", 20, 3);
+        assert_eq!(lines, vec!["Short title"]);
+
+        let lines = wrap_title("用 Rust 构建博客系统", 8, 3);
+        assert_eq!(lines, vec!["用 Rust", "构建博客", "系统"]);
+    }
+
+    #[test]
+    fn test_theme_colors() {
+        assert_eq!(parse_hex_color("#ff8000"), Some((255, 128, 0)));
+        assert_eq!(parse_hex_color("red"), None);
+        assert_eq!(to_hex(darken((200, 100, 0), 0.5)), "#643200");
+        assert!(is_light((255, 255, 255)));
+        assert!(!is_light((26, 26, 46)));
+    }
+}
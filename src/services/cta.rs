@@ -0,0 +1,397 @@
+use crate::{
+    error::{AppError, Result},
+    models::{
+        cta::*,
+        id::{bare_id, ArticleId, UserId},
+    },
+    services::Database,
+    utils::cta::{expand_cta_blocks, is_valid_key, referenced_keys},
+};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+use validator::Validate;
+
+/// 每个作者最多的 CTA 数量
+const MAX_CTAS_PER_OWNER: usize = 100;
+
+#[derive(Debug, Deserialize)]
+struct CtaEventRow {
+    cta_id: String,
+    article_id: Option<String>,
+    event: CtaEventKind,
+    count: i64,
+}
+
+/// 作者统一管理的行动号召块：正文短代码的展开、点击和转化统计、邮箱收集
+#[derive(Clone)]
+pub struct CtaService {
+    db: Arc<Database>,
+}
+
+impl CtaService {
+    pub async fn new(db: Arc<Database>) -> Result<Self> {
+        Ok(Self { db })
+    }
+
+    pub async fn list(&self, owner_id: &str) -> Result<Vec<CallToAction>> {
+        self.db
+            .prepare("SELECT * FROM cta WHERE owner_id = $owner_id ORDER BY key ASC")
+            .bind("owner_id", UserId::new(owner_id).as_str())
+            .fetch()
+            .await
+    }
+
+    /// 按 ID 读取，不检查归属；用于读者端的点击和邮箱提交
+    pub async fn find(&self, cta_id: &str) -> Result<CallToAction> {
+        let cta: Option<CallToAction> = self.db.get_by_id("cta", bare_id("cta", cta_id)).await?;
+        cta.ok_or_else(|| AppError::not_found("Call to action"))
+    }
+
+    pub async fn get(&self, owner_id: &str, cta_id: &str) -> Result<CallToAction> {
+        let cta = self.find(cta_id).await?;
+        if cta.owner_id != UserId::new(owner_id).as_str() {
+            return Err(AppError::not_found("Call to action"));
+        }
+        Ok(cta)
+    }
+
+    pub async fn create(&self, owner_id: &str, request: CreateCtaRequest) -> Result<CallToAction> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let key = request.key.trim().to_lowercase();
+        if !is_valid_key(&key) {
+            return Err(AppError::BadRequest(
+                "CTA key may only contain lowercase letters, digits and hyphens".to_string(),
+            ));
+        }
+        if request.kind == CtaKind::ExternalLink && request.url.is_none() {
+            return Err(AppError::BadRequest("External link CTAs require a url".to_string()));
+        }
+
+        let existing = self.list(owner_id).await?;
+        if existing.iter().any(|c| c.key == key) {
+            return Err(AppError::Conflict(format!("A CTA with key '{}' already exists", key)));
+        }
+        if existing.len() >= MAX_CTAS_PER_OWNER {
+            return Err(AppError::BadRequest(format!(
+                "At most {} CTAs are allowed",
+                MAX_CTAS_PER_OWNER
+            )));
+        }
+
+        let button_label = request
+            .button_label
+            .as_deref()
+            .map(str::trim)
+            .unwrap_or(request.kind.default_button_label());
+
+        let cta: Option<CallToAction> = self.db
+            .prepare(
+                r#"
+                CREATE cta CONTENT {
+                    owner_id: $owner_id,
+                    key: $key,
+                    kind: $kind,
+                    title: $title,
+                    description: $description ?? NONE,
+                    button_label: $button_label,
+                    url: $url ?? NONE,
+                    click_count: 0,
+                    conversion_count: 0,
+                    created_at: time::now(),
+                    updated_at: time::now()
+                }
+                "#,
+            )
+            .bind("owner_id", UserId::new(owner_id).as_str())
+            .bind("key", &key)
+            .bind("kind", request.kind.as_str())
+            .bind("title", request.title.trim())
+            .bind("description", &request.description)
+            .bind("button_label", button_label)
+            .bind("url", &request.url)
+            .fetch_one()
+            .await?;
+
+        let cta = cta.ok_or_else(|| AppError::internal("Failed to create call to action"))?;
+        info!("Created CTA {} ({}) for user {}", cta.key, cta.kind.as_str(), owner_id);
+        Ok(cta)
+    }
+
+    /// key 和类型创建后不能修改，正文中的短代码依赖它们
+    pub async fn update(&self, owner_id: &str, cta_id: &str, request: UpdateCtaRequest) -> Result<CallToAction> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let mut cta = self.get(owner_id, cta_id).await?;
+        if let Some(title) = request.title {
+            cta.title = title.trim().to_string();
+        }
+        if let Some(description) = request.description {
+            cta.description = Some(description).filter(|d| !d.trim().is_empty());
+        }
+        if let Some(button_label) = request.button_label {
+            cta.button_label = button_label.trim().to_string();
+        }
+        if let Some(url) = request.url {
+            cta.url = Some(url);
+        }
+
+        let updated: Option<CallToAction> = self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('cta', $cta_id) SET
+                    title = $title,
+                    description = $description ?? NONE,
+                    button_label = $button_label,
+                    url = $url ?? NONE,
+                    updated_at = time::now()
+                "#,
+            )
+            .bind("cta_id", bare_id("cta", &cta.id))
+            .bind("title", &cta.title)
+            .bind("description", &cta.description)
+            .bind("button_label", &cta.button_label)
+            .bind("url", &cta.url)
+            .fetch_one()
+            .await?;
+
+        updated.ok_or_else(|| AppError::internal("Failed to update call to action"))
+    }
+
+    /// 删除后引用它的短代码不再渲染；统计记录保留
+    pub async fn delete(&self, owner_id: &str, cta_id: &str) -> Result<()> {
+        let cta = self.get(owner_id, cta_id).await?;
+        self.db
+            .prepare("DELETE type::thing('cta', $cta_id)")
+            .bind("cta_id", bare_id("cta", &cta.id))
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 展开文章正文中的 `{{cta key}}`，只使用文章作者自己的 CTA。
+    /// 加载失败时去掉短代码，不影响文章读取
+    pub async fn apply(&self, author_id: &str, article_id: &str, html: String) -> String {
+        let keys = referenced_keys(&html);
+        if keys.is_empty() {
+            return html;
+        }
+
+        let ctas: Vec<CallToAction> = match self.db
+            .prepare("SELECT * FROM cta WHERE owner_id = $owner_id AND key INSIDE $keys")
+            .bind("owner_id", UserId::new(author_id).as_str())
+            .bind("keys", &keys)
+            .fetch()
+            .await
+        {
+            Ok(ctas) => ctas,
+            Err(e) => {
+                warn!("Failed to load CTAs for article {}: {}", article_id, e);
+                Vec::new()
+            }
+        };
+
+        let ctas: HashMap<String, CallToAction> = ctas.into_iter().map(|c| (c.key.clone(), c)).collect();
+        expand_cta_blocks(&html, &ctas, ArticleId::new(article_id).as_str())
+    }
+
+    /// 前端上报的点击，以及关注、订阅成功后的转化
+    pub async fn record_event(
+        &self,
+        cta_id: &str,
+        request: CtaEventRequest,
+        user_id: Option<&str>,
+    ) -> Result<()> {
+        let cta = self.find(cta_id).await?;
+        if request.event == CtaEventKind::Conversion
+            && !matches!(cta.kind, CtaKind::Subscribe | CtaKind::Follow)
+        {
+            return Err(AppError::BadRequest(format!(
+                "Conversions for {} CTAs are recorded by the server",
+                cta.kind.as_str()
+            )));
+        }
+        self.insert_event(&cta, request.event, request.article_id.as_deref(), user_id).await
+    }
+
+    async fn insert_event(
+        &self,
+        cta: &CallToAction,
+        event: CtaEventKind,
+        article_id: Option<&str>,
+        user_id: Option<&str>,
+    ) -> Result<()> {
+        let counter = match event {
+            CtaEventKind::Click => "click_count",
+            CtaEventKind::Conversion => "conversion_count",
+        };
+        self.db
+            .prepare(&format!(
+                r#"
+                UPDATE type::thing('cta', $cta_id) SET {} += 1;
+                CREATE cta_event CONTENT {{
+                    cta_id: $cta_id,
+                    owner_id: $owner_id,
+                    article_id: $article_id ?? NONE,
+                    user_id: $user_id ?? NONE,
+                    event: $event,
+                    created_at: time::now()
+                }};
+                "#,
+                counter
+            ))
+            .bind("cta_id", bare_id("cta", &cta.id))
+            .bind("owner_id", &cta.owner_id)
+            .bind("article_id", article_id.map(|id| ArticleId::new(id).as_str().to_string()))
+            .bind("user_id", user_id.map(|id| UserId::new(id).as_str().to_string()))
+            .bind("event", event.as_str())
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 邮箱收集块提交的邮箱；同一邮箱只计一次转化
+    pub async fn capture_email(
+        &self,
+        cta_id: &str,
+        request: CtaEmailRequest,
+        user_id: Option<&str>,
+    ) -> Result<()> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let cta = self.find(cta_id).await?;
+        if cta.kind != CtaKind::EmailCapture {
+            return Err(AppError::BadRequest("This CTA does not collect emails".to_string()));
+        }
+
+        let email = request.email.trim().to_lowercase();
+        let cta_key = bare_id("cta", &cta.id);
+        let existing: Vec<CtaLead> = self.db
+            .prepare("SELECT * FROM type::thing('cta_lead', [$cta_id, $email])")
+            .bind("cta_id", cta_key)
+            .bind("email", &email)
+            .fetch()
+            .await?;
+        if !existing.is_empty() {
+            return Ok(());
+        }
+
+        let article_id = request.article_id.as_deref().map(|id| ArticleId::new(id).as_str().to_string());
+        self.db
+            .prepare(
+                r#"
+                UPSERT type::thing('cta_lead', [$cta_id, $email]) MERGE {
+                    cta_id: $cta_id,
+                    owner_id: $owner_id,
+                    email: $email,
+                    article_id: $article_id ?? NONE
+                }
+                "#,
+            )
+            .bind("cta_id", cta_key)
+            .bind("owner_id", &cta.owner_id)
+            .bind("email", &email)
+            .bind("article_id", &article_id)
+            .execute()
+            .await?;
+
+        self.insert_event(&cta, CtaEventKind::Conversion, article_id.as_deref(), user_id).await
+    }
+
+    /// 外部链接的跳转目标，同时计入点击
+    pub async fn redirect_target(
+        &self,
+        cta_id: &str,
+        article_id: Option<&str>,
+        user_id: Option<&str>,
+    ) -> Result<String> {
+        let cta = self.find(cta_id).await?;
+        let url = match (cta.kind, &cta.url) {
+            (CtaKind::ExternalLink, Some(url)) => url.clone(),
+            _ => return Err(AppError::not_found("Call to action link")),
+        };
+
+        if let Err(e) = self.insert_event(&cta, CtaEventKind::Click, article_id, user_id).await {
+            warn!("Failed to record click on CTA {}: {}", cta.id, e);
+        }
+        Ok(url)
+    }
+
+    pub async fn list_leads(&self, owner_id: &str, cta_id: &str) -> Result<Vec<CtaLead>> {
+        let cta = self.get(owner_id, cta_id).await?;
+        self.db
+            .prepare("SELECT * FROM cta_lead WHERE cta_id = $cta_id ORDER BY created_at DESC")
+            .bind("cta_id", bare_id("cta", &cta.id))
+            .fetch()
+            .await
+    }
+
+    /// 作者每个 CTA 在时间范围内的点击和转化，按文章拆分
+    pub async fn get_analytics(&self, owner_id: &str, query: CtaAnalyticsQuery) -> Result<CtaAnalytics> {
+        let days = query.days.unwrap_or(30).clamp(1, 365);
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT * FROM cta WHERE owner_id = $owner_id ORDER BY key ASC;
+                SELECT cta_id, article_id, event, count() AS count FROM cta_event
+                    WHERE owner_id = $owner_id AND created_at >= $since
+                    GROUP BY cta_id, article_id, event;
+            "#,
+            json!({
+                "owner_id": UserId::new(owner_id).as_str(),
+                "since": Utc::now() - Duration::days(days),
+            }),
+        ).await?;
+        let ctas: Vec<CallToAction> = response.take(0)?;
+        let rows: Vec<CtaEventRow> = response.take(1)?;
+
+        let ctas = ctas
+            .into_iter()
+            .map(|cta| {
+                let cta_id = bare_id("cta", &cta.id).to_string();
+                let mut by_article: Vec<CtaArticleStats> = Vec::new();
+                let (mut clicks, mut conversions) = (0, 0);
+
+                for row in rows.iter().filter(|r| r.cta_id == cta_id) {
+                    match row.event {
+                        CtaEventKind::Click => clicks += row.count,
+                        CtaEventKind::Conversion => conversions += row.count,
+                    }
+                    let Some(article_id) = &row.article_id else { continue };
+                    let index = match by_article.iter().position(|a| &a.article_id == article_id) {
+                        Some(index) => index,
+                        None => {
+                            by_article.push(CtaArticleStats {
+                                article_id: article_id.clone(),
+                                clicks: 0,
+                                conversions: 0,
+                            });
+                            by_article.len() - 1
+                        }
+                    };
+                    match row.event {
+                        CtaEventKind::Click => by_article[index].clicks += row.count,
+                        CtaEventKind::Conversion => by_article[index].conversions += row.count,
+                    }
+                }
+                by_article.sort_by(|a, b| b.clicks.cmp(&a.clicks).then(b.conversions.cmp(&a.conversions)));
+
+                CtaStats {
+                    cta_id,
+                    key: cta.key,
+                    kind: cta.kind,
+                    title: cta.title,
+                    clicks,
+                    conversions,
+                    conversion_rate: if clicks > 0 { conversions as f64 / clicks as f64 } else { 0.0 },
+                    by_article,
+                }
+            })
+            .collect();
+
+        Ok(CtaAnalytics { days, ctas })
+    }
+}
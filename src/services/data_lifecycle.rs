@@ -0,0 +1,491 @@
+use crate::{
+    config::Config,
+    error::{AppError, ErrorCode, Result},
+    models::{
+        data_lifecycle::*,
+        notification::{CreateNotificationRequest, NotificationType},
+    },
+    services::{
+        auth::User, CommentService, Database, NotificationService, StripeService, SubscriptionService,
+    },
+};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+use validator::Validate;
+
+/// 处理中超过该时间没有进展的删除视为中断，由后台任务继续执行
+const STALLED_AFTER_MINUTES: i64 = 15;
+
+/// 宽限期结束后永久删除的个人数据：(表, 用户字段)
+///
+/// 订阅、购买、收益和付款记录属于财务凭证，按法定要求保留，不在此列
+const ERASED_TABLES: &[(&str, &str)] = &[
+    ("article", "author_id"),
+    ("article_version", "author_id"),
+    ("article_attachment", "author_id"),
+    ("article_experiment", "author_id"),
+    ("article_milestone", "user_id"),
+    ("article_collaborator", "user_id"),
+    ("series", "author_id"),
+    ("comment", "author_id"),
+    ("bookmark", "user_id"),
+    ("clap", "user_id"),
+    ("comment_clap", "user_id"),
+    ("reaction", "user_id"),
+    ("highlight", "user_id"),
+    ("reading_progress", "user_id"),
+    ("reading_queue_item", "user_id"),
+    ("comment_read_marker", "user_id"),
+    ("follow", "follower_id"),
+    ("follow", "following_id"),
+    ("publication_follow", "user_id"),
+    ("publication_member", "user_id"),
+    ("publication_invite", "user_id"),
+    ("user_tag_follow", "user_id"),
+    ("pseudonym", "owner_id"),
+    ("cta", "owner_id"),
+    ("cta_event", "owner_id"),
+    ("cta_lead", "owner_id"),
+    ("attachment_lead", "author_id"),
+    ("syndication_account", "user_id"),
+    ("syndicated_post", "user_id"),
+    ("scheduled_share", "user_id"),
+    ("newsletter_delivery", "user_id"),
+    ("notification", "recipient_id"),
+    ("notification_digest_item", "recipient_id"),
+    ("notification_config", "user_id"),
+    ("notification_preference", "user_id"),
+    ("oauth_client", "owner_id"),
+    ("user_two_factor", "user_id"),
+    ("platform_role", "user_id"),
+    ("moderation_strike", "user_id"),
+    ("activity_log", "user_id"),
+    ("websocket_connection", "user_id"),
+    ("paid_content_access", "user_id"),
+    ("user_profile", "user_id"),
+];
+
+#[derive(Debug, Deserialize)]
+struct CountRow {
+    total: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubscriptionRef {
+    id: String,
+    subscriber_id: String,
+}
+
+/// 账户删除（GDPR 删除权）：先下线内容、取消订阅、删除支付信息并匿名化统计，
+/// 宽限期结束后永久删除个人数据。每一步的进度记录在 account_deletion 中，中断后可以继续
+#[derive(Clone)]
+pub struct DataLifecycleService {
+    db: Arc<Database>,
+    comment_service: CommentService,
+    subscription_service: SubscriptionService,
+    stripe_service: StripeService,
+    notification_service: NotificationService,
+    grace_days: i64,
+}
+
+impl DataLifecycleService {
+    pub async fn new(
+        db: Arc<Database>,
+        config: &Config,
+        comment_service: CommentService,
+        subscription_service: SubscriptionService,
+        stripe_service: StripeService,
+        notification_service: NotificationService,
+    ) -> Result<Self> {
+        Ok(Self {
+            db,
+            comment_service,
+            subscription_service,
+            stripe_service,
+            notification_service,
+            grace_days: config.account_deletion_grace_days,
+        })
+    }
+
+    /// 申请删除账户，立即在后台执行下线步骤
+    pub async fn request_deletion(&self, user: &User, username: &str, request: DeleteAccountRequest) -> Result<AccountDeletion> {
+        request.validate()?;
+        if request.confirm.trim() != username {
+            return Err(AppError::BadRequest("确认内容与用户名不一致".to_string()));
+        }
+
+        if let Some(existing) = self.find_deletion(&user.id).await? {
+            if existing.status != DeletionStatus::Cancelled {
+                return Err(AppError::coded(ErrorCode::AccountDeletionPending, "Account deletion has already been requested"));
+            }
+        }
+
+        // 出版物还有其他成员依赖，需要先转让或删除
+        let owned: Vec<CountRow> = self
+            .db
+            .prepare("SELECT count() AS total FROM publication WHERE owner_id = $user_id GROUP ALL")
+            .bind("user_id", &user.id)
+            .fetch()
+            .await?;
+        if owned.first().map_or(0, |row| row.total) > 0 {
+            return Err(AppError::BadRequest("请先转让或删除你拥有的出版物".to_string()));
+        }
+
+        let mut steps: Vec<DeletionStepProgress> = DeletionStep::IMMEDIATE.iter().map(|step| DeletionStepProgress::pending(*step)).collect();
+        steps.push(DeletionStepProgress::pending(DeletionStep::HardDelete));
+
+        let mut response = self
+            .db
+            .prepare(
+                r#"
+                UPSERT type::thing('account_deletion', $user_id) CONTENT {
+                    user_id: $user_id,
+                    status: 'pending',
+                    reason: $reason,
+                    steps: $steps,
+                    requested_at: time::now(),
+                    hard_delete_after: <datetime> $hard_delete_after,
+                    updated_at: time::now()
+                };
+                UPDATE user_profile SET deletion_requested_at = time::now() WHERE user_id = $user_id;
+                "#,
+            )
+            .bind("user_id", &user.id)
+            .bind("reason", &request.reason)
+            .bind("steps", &steps)
+            .bind("hard_delete_after", Utc::now() + Duration::days(self.grace_days))
+            .execute()
+            .await?;
+        let deletions: Vec<AccountDeletion> = response.take(0)?;
+        let deletion = deletions
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::internal("Failed to record account deletion"))?;
+
+        info!("User {} requested account deletion", user.id);
+
+        let service = self.clone();
+        let user_id = user.id.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.process(&user_id).await {
+                error!("Account deletion for {} failed: {}", user_id, e);
+            }
+        });
+
+        Ok(deletion)
+    }
+
+    pub async fn get_deletion(&self, user_id: &str) -> Result<AccountDeletion> {
+        self.find_deletion(user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("没有删除账户的申请".to_string()))
+    }
+
+    /// 宽限期内撤销删除：恢复下线的文章和评论。已取消的订阅和删除的支付方式无法恢复
+    pub async fn cancel_deletion(&self, user_id: &str) -> Result<AccountDeletion> {
+        let deletion = self.get_deletion(user_id).await?;
+        if !deletion.status.is_cancellable() || deletion.hard_delete_after <= Utc::now() {
+            return Err(AppError::BadRequest("当前状态不能撤销删除".to_string()));
+        }
+
+        let mut response = self
+            .db
+            .prepare(
+                r#"
+                LET $restored = UPDATE comment SET is_deleted = false, deleted_at = NONE
+                    WHERE author_id = $user_id AND is_deleted = true AND deleted_at >= <datetime> $requested_at
+                    RETURN article_id;
+                UPDATE article SET is_deleted = false, deleted_at = NONE
+                    WHERE author_id = $user_id AND is_deleted = true AND deleted_at >= <datetime> $requested_at;
+                UPDATE user_profile SET deletion_requested_at = NONE WHERE user_id = $user_id;
+                UPDATE type::thing('account_deletion', $user_id) SET
+                    status = 'cancelled',
+                    cancelled_at = time::now(),
+                    updated_at = time::now();
+                RETURN array::distinct($restored.article_id);
+                "#,
+            )
+            .bind("user_id", user_id)
+            .bind("requested_at", deletion.requested_at)
+            .execute()
+            .await?;
+        let deletions: Vec<AccountDeletion> = response.take(3)?;
+        let article_ids: Vec<String> = response.take(4)?;
+
+        for article_id in article_ids {
+            self.comment_service.update_article_comment_count(&article_id).await?;
+        }
+
+        info!("User {} cancelled account deletion", user_id);
+        deletions
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::internal("Failed to cancel account deletion"))
+    }
+
+    /// 继续中断或失败的删除，并永久删除宽限期已满的账户
+    pub async fn run(&self) -> Result<DataLifecycleRunReport> {
+        let mut report = DataLifecycleRunReport::default();
+
+        let stalled: Vec<String> = self
+            .db
+            .prepare(
+                r#"
+                SELECT VALUE user_id FROM account_deletion
+                WHERE status INSIDE ['pending', 'failed']
+                    OR (status = 'processing' AND updated_at < <datetime> $stalled_before)
+                "#,
+            )
+            .bind("stalled_before", Utc::now() - Duration::minutes(STALLED_AFTER_MINUTES))
+            .fetch()
+            .await?;
+        for user_id in stalled {
+            match self.process(&user_id).await {
+                Ok(()) => report.resumed += 1,
+                Err(e) => warn!("Failed to resume account deletion for {}: {}", user_id, e),
+            }
+        }
+
+        let due: Vec<String> = self
+            .db
+            .prepare("SELECT VALUE user_id FROM account_deletion WHERE status = 'scheduled' AND hard_delete_after <= time::now()")
+            .fetch()
+            .await?;
+        for user_id in due {
+            match self.hard_delete(&user_id).await {
+                Ok(()) => report.hard_deleted += 1,
+                Err(e) => warn!("Failed to erase account {}: {}", user_id, e),
+            }
+        }
+
+        if report.resumed + report.hard_deleted > 0 {
+            info!("Data lifecycle run: {:?}", report);
+        }
+        Ok(report)
+    }
+
+    /// 依次执行尚未完成的下线步骤，全部完成后等待宽限期结束
+    async fn process(&self, user_id: &str) -> Result<()> {
+        let deletion = self.get_deletion(user_id).await?;
+        if deletion.status == DeletionStatus::Cancelled || deletion.status == DeletionStatus::Completed {
+            return Ok(());
+        }
+        self.set_status(user_id, DeletionStatus::Processing).await?;
+
+        for step in DeletionStep::IMMEDIATE {
+            if deletion.is_step_completed(step) {
+                continue;
+            }
+
+            match self.execute_step(user_id, step).await {
+                Ok(affected) => self.update_step(user_id, step, StepStatus::Completed, affected, None).await?,
+                Err(e) => {
+                    warn!("Account deletion step {} failed for {}: {}", step.as_str(), user_id, e);
+                    self.update_step(user_id, step, StepStatus::Failed, 0, Some(e.to_string())).await?;
+                    self.set_status(user_id, DeletionStatus::Failed).await?;
+                    return Err(e);
+                }
+            }
+        }
+
+        self.set_status(user_id, DeletionStatus::Scheduled).await?;
+        self.notify(
+            user_id,
+            "Your account is scheduled for deletion",
+            format!(
+                "Your content has been removed and your account will be permanently deleted on {}. You can cancel until then.",
+                deletion.hard_delete_after.format("%Y-%m-%d")
+            ),
+        )
+        .await;
+        Ok(())
+    }
+
+    async fn execute_step(&self, user_id: &str, step: DeletionStep) -> Result<usize> {
+        match step {
+            DeletionStep::RevokeAccess => {
+                self.count_affected(
+                    r#"
+                    RETURN array::len((DELETE oauth_token WHERE user_id = $user_id RETURN BEFORE))
+                        + array::len((DELETE oauth_grant WHERE user_id = $user_id RETURN BEFORE))
+                        + array::len((DELETE oauth_code WHERE user_id = $user_id RETURN BEFORE))
+                        + array::len((DELETE push_subscription WHERE user_id = $user_id RETURN BEFORE));
+                    "#,
+                    user_id,
+                )
+                .await
+            }
+            DeletionStep::SoftDeleteArticles => {
+                self.count_affected(
+                    r#"
+                    RETURN array::len((UPDATE article SET is_deleted = true, deleted_at = time::now()
+                        WHERE author_id = $user_id AND is_deleted = false RETURN id));
+                    "#,
+                    user_id,
+                )
+                .await
+            }
+            DeletionStep::SoftDeleteComments => {
+                let article_ids: Vec<String> = self
+                    .db
+                    .prepare(
+                        r#"
+                        RETURN array::distinct((UPDATE comment SET is_deleted = true, deleted_at = time::now()
+                            WHERE author_id = $user_id AND is_deleted = false RETURN article_id).article_id);
+                        "#,
+                    )
+                    .bind("user_id", user_id)
+                    .fetch()
+                    .await?;
+
+                // 别人文章下的评论数需要重新统计
+                for article_id in &article_ids {
+                    self.comment_service.update_article_comment_count(article_id).await?;
+                }
+                Ok(article_ids.len())
+            }
+            DeletionStep::CancelSubscriptions => {
+                let subscriptions: Vec<SubscriptionRef> = self
+                    .db
+                    .prepare(
+                        r#"
+                        SELECT type::string(id) AS id, subscriber_id FROM subscription
+                        WHERE (subscriber_id = $user_id OR creator_id = $user_id) AND status INSIDE ['active', 'past_due'];
+                        "#,
+                    )
+                    .bind("user_id", user_id)
+                    .fetch()
+                    .await?;
+
+                for subscription in &subscriptions {
+                    self.subscription_service
+                        .cancel_subscription(&subscription.id, &subscription.subscriber_id, false)
+                        .await?;
+                }
+                self.db
+                    .prepare("UPDATE subscription_plan SET is_active = false WHERE creator_id = $user_id")
+                    .bind("user_id", user_id)
+                    .execute()
+                    .await?;
+                Ok(subscriptions.len())
+            }
+            DeletionStep::DetachStripeCustomer => Ok(self.stripe_service.delete_customer(user_id).await? as usize),
+            DeletionStep::AnonymizeAnalytics => {
+                // 浏览记录换成随机访客标识，文章的独立访客数保持不变
+                let counts: Vec<usize> = self
+                    .db
+                    .prepare(
+                        r#"
+                        RETURN array::len((UPDATE article_view SET user_id = NONE, viewer_hash = $viewer_hash WHERE user_id = $user_id RETURN id))
+                            + array::len((UPDATE attachment_download SET user_id = NONE WHERE user_id = $user_id RETURN id))
+                            + array::len((UPDATE cta_event SET user_id = NONE WHERE user_id = $user_id RETURN id));
+                        "#,
+                    )
+                    .bind("user_id", user_id)
+                    .bind("viewer_hash", format!("erased:{}", uuid::Uuid::new_v4()))
+                    .fetch()
+                    .await?;
+                Ok(counts.first().copied().unwrap_or(0))
+            }
+            DeletionStep::HardDelete => unreachable!("hard deletion runs after the grace period"),
+        }
+    }
+
+    /// 永久删除个人数据，保留一条不含内容的删除记录作为凭证
+    async fn hard_delete(&self, user_id: &str) -> Result<()> {
+        let mut affected = 0;
+        for (table, field) in ERASED_TABLES {
+            let deleted: Vec<usize> = self
+                .db
+                .prepare(&format!("RETURN array::len((DELETE {table} WHERE {field} = $user_id RETURN BEFORE))"))
+                .bind("user_id", user_id)
+                .fetch()
+                .await?;
+            affected += deleted.first().copied().unwrap_or(0);
+        }
+
+        self.update_step(user_id, DeletionStep::HardDelete, StepStatus::Completed, affected, None).await?;
+        self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('account_deletion', $user_id) SET
+                    status = 'completed',
+                    reason = NONE,
+                    completed_at = time::now(),
+                    updated_at = time::now()
+                "#,
+            )
+            .bind("user_id", user_id)
+            .execute()
+            .await?;
+
+        info!("Erased account {} ({} records)", user_id, affected);
+        Ok(())
+    }
+
+    async fn find_deletion(&self, user_id: &str) -> Result<Option<AccountDeletion>> {
+        self.db
+            .prepare("SELECT * FROM type::thing('account_deletion', $user_id)")
+            .bind("user_id", user_id)
+            .fetch_one()
+            .await
+    }
+
+    async fn count_affected(&self, sql: &str, user_id: &str) -> Result<usize> {
+        let counts: Vec<usize> = self.db.prepare(sql).bind("user_id", user_id).fetch().await?;
+        Ok(counts.first().copied().unwrap_or(0))
+    }
+
+    async fn set_status(&self, user_id: &str, status: DeletionStatus) -> Result<()> {
+        self.db
+            .prepare("UPDATE type::thing('account_deletion', $user_id) SET status = $status, updated_at = time::now()")
+            .bind("user_id", user_id)
+            .bind("status", status.as_str())
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    async fn update_step(&self, user_id: &str, step: DeletionStep, status: StepStatus, affected: usize, error: Option<String>) -> Result<()> {
+        self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('account_deletion', $user_id) SET
+                    steps = steps.map(|$progress| IF $progress.step = $step THEN {
+                        step: $step,
+                        status: $status,
+                        affected: $affected,
+                        error: $error,
+                        completed_at: IF $status = 'completed' THEN time::now() ELSE NONE END
+                    } ELSE $progress END),
+                    updated_at = time::now()
+                "#,
+            )
+            .bind("user_id", user_id)
+            .bind("step", step)
+            .bind("status", status)
+            .bind("affected", affected)
+            .bind("error", error)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 提醒失败不影响删除流程
+    async fn notify(&self, user_id: &str, title: &str, message: String) {
+        let notification = CreateNotificationRequest {
+            recipient_id: user_id.to_string(),
+            notification_type: NotificationType::AccountLifecycle,
+            title: title.to_string(),
+            message,
+            data: json!({ "status_url": "/api/blog/users/me/deletion" }),
+        };
+
+        if let Err(e) = self.notification_service.create_notification(notification).await {
+            warn!("Failed to send account deletion notice to {}: {}", user_id, e);
+        }
+    }
+}
@@ -1,14 +1,17 @@
 use crate::config::Config;
 use crate::error::{AppError, Result};
+use crate::models::id::bare_id;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
 use std::sync::Arc;
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::str::FromStr;
 use soulcore::prelude::*;
 use soulcore::engines::storage::StorageEngine;
 use surrealdb::Response;
 use surrealdb::sql::Thing;
-use tracing::{info, error, debug};
+use tracing::{info, error};
 
 /// 数据库服务
 #[derive(Clone)]
@@ -76,6 +79,15 @@ impl Database {
             .map_err(|e| AppError::from(e))
     }
 
+    /// 创建参数化查询，值通过 `bind` 绑定而不是拼接到 SQL 中
+    pub fn prepare(&self, sql: &str) -> PreparedQuery<'_> {
+        PreparedQuery {
+            db: self,
+            sql: sql.to_string(),
+            params: serde_json::Map::new(),
+        }
+    }
+
     /// 执行带参数的查询
     pub async fn query_with_params<P>(&self, sql: &str, params: P) -> Result<Response>
     where
@@ -130,8 +142,7 @@ impl Database {
 
     /// 通过ID删除记录
     pub async fn delete_by_id(&self, table: &str, id: &str) -> Result<()> {
-        let prefix = format!("{}:", table);
-        let pure_id = if id.starts_with(&prefix) { &id[prefix.len()..] } else { id };
+        let pure_id = bare_id(table, id);
         let thing = Thing::from((table, pure_id));
         self.delete(thing).await
     }
@@ -141,21 +152,11 @@ impl Database {
     where
         T: for<'de> Deserialize<'de> + Send + Sync + Debug,
     {
-        // 获取纯 ID（不带 table 前缀）
-        let prefix = format!("{}:", table);
-        let pure_id = if id.starts_with(&prefix) {
-            &id[prefix.len()..]
-        } else {
-            id
-        };
-        
-        // 使用反引号包裹 ID 以避免解析问题（与 article.rs 保持一致）
-        let query = format!("SELECT * FROM {}:`{}`", table, pure_id);
-        debug!("Executing query: {}", query);
-        
-        let mut response = self.storage.query(&query).await?;
-        let results: Vec<T> = response.take(0)?;
-        Ok(results.into_iter().next())
+        self.prepare("SELECT * FROM type::thing($table, $id)")
+            .bind("table", table)
+            .bind("id", bare_id(table, id))
+            .fetch_one()
+            .await
     }
 
     /// 通过ID更新记录
@@ -163,8 +164,7 @@ impl Database {
     where
         T: Serialize + for<'de> Deserialize<'de> + Send + Sync + Debug,
     {
-        let prefix = format!("{}:", table);
-        let pure_id = if id.starts_with(&prefix) { &id[prefix.len()..] } else { id };
+        let pure_id = bare_id(table, id);
         let thing = Thing::from((table, pure_id));
         self.update(thing, data).await
     }
@@ -174,13 +174,12 @@ impl Database {
     where
         T: for<'de> Deserialize<'de> + Send + Sync + Debug,
     {
-        // 获取纯 ID（不带 table 前缀），并用反引号包裹，兼容包含连字符的 ID
-        let prefix = format!("{}:", table);
-        let pure_id = if id.starts_with(&prefix) { &id[prefix.len()..] } else { id };
-        let query = format!("UPDATE {}:`{}` MERGE $updates RETURN *", table, pure_id);
-        let mut response = self.query_with_params(&query, json!({"updates": updates})).await?;
-        let results: Vec<T> = response.take(0)?;
-        Ok(results.into_iter().next())
+        self.prepare("UPDATE type::thing($table, $id) MERGE $updates RETURN *")
+            .bind("table", table)
+            .bind("id", bare_id(table, id))
+            .bind("updates", updates)
+            .fetch_one()
+            .await
     }
 
     /// 查找单个记录
@@ -201,6 +200,187 @@ impl Database {
     }
 }
 
+/// 参数化查询构建器
+///
+/// SQL 中只使用 `$name` 占位符，用户输入全部通过 `bind` 传入，避免 SurrealQL 注入：
+///
+/// ```ignore
+/// let domains: Vec<PublicationDomain> = db
+///     .prepare("SELECT * FROM publication_domain WHERE publication_id = $publication_id")
+///     .bind("publication_id", publication_id)
+///     .fetch()
+///     .await?;
+/// ```
+pub struct PreparedQuery<'a> {
+    db: &'a Database,
+    sql: String,
+    params: serde_json::Map<String, serde_json::Value>,
+}
+
+impl<'a> PreparedQuery<'a> {
+    /// 绑定参数，无法序列化的值绑定为 null
+    pub fn bind<V: Serialize>(mut self, name: &str, value: V) -> Self {
+        let value = serde_json::to_value(value).unwrap_or(serde_json::Value::Null);
+        self.params.insert(name.to_string(), value);
+        self
+    }
+
+    /// 执行查询并返回原始响应（多语句查询时使用）
+    pub async fn execute(self) -> Result<Response> {
+        self.db.query_with_params(&self.sql, serde_json::Value::Object(self.params)).await
+    }
+
+    /// 执行查询并取第一条语句的结果
+    pub async fn fetch<T>(self) -> Result<Vec<T>>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let mut response = self.execute().await?;
+        Ok(response.take(0)?)
+    }
+
+    /// 执行查询并取第一条记录
+    pub async fn fetch_one<T>(self) -> Result<Option<T>>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        Ok(self.fetch::<T>().await?.into_iter().next())
+    }
+}
+
+/// 记录 ID 所属的表
+///
+/// 每张表对应一个零大小的标记类型，见 `models::id`。
+pub trait Table {
+    const NAME: &'static str;
+}
+
+/// 带表名类型的记录 ID
+///
+/// 内部只保存不带表名的 ID。`new` 和反序列化都接受 `table:id`、
+/// `` table:`id` ``、`table:⟨id⟩`、裸 ID 以及 SurrealDB 的 Thing 对象；
+/// 序列化输出 `table:id`。写查询时用 `as_str` 绑定到 `type::thing(table, $id)`，
+/// 不要把 ID 拼接进 SQL。
+pub struct RecordId<T: Table> {
+    id: String,
+    table: PhantomData<fn() -> T>,
+}
+
+impl<T: Table> RecordId<T> {
+    pub const TABLE: &'static str = T::NAME;
+
+    /// 接受带或不带表名前缀的 ID
+    pub fn new(id: impl AsRef<str>) -> Self {
+        Self {
+            id: bare_id(T::NAME, id.as_ref()).to_string(),
+            table: PhantomData,
+        }
+    }
+
+    /// 不带表名的 ID，用于 `type::thing(table, $id)` 绑定
+    pub fn as_str(&self) -> &str {
+        &self.id
+    }
+
+    /// `table:id` 形式，与接口返回的 ID 一致
+    pub fn to_record(&self) -> String {
+        format!("{}:{}", T::NAME, self.id)
+    }
+
+    pub fn to_thing(&self) -> Thing {
+        Thing::from((T::NAME, self.id.as_str()))
+    }
+
+    /// 从查询结果的 ID 字段解析，字段缺失或为 null 时返回 `None`
+    pub fn from_value(value: &serde_json::Value) -> Option<Self> {
+        Self::deserialize(value).ok()
+    }
+}
+
+// 手写而不是 derive：derive 会要求标记类型也实现这些 trait
+
+impl<T: Table> Clone for RecordId<T> {
+    fn clone(&self) -> Self {
+        Self::new(&self.id)
+    }
+}
+
+impl<T: Table> PartialEq for RecordId<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T: Table> Eq for RecordId<T> {}
+
+impl<T: Table> Hash for RecordId<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<T: Table> PartialOrd for RecordId<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Table> Ord for RecordId<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+impl<T: Table> Debug for RecordId<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("RecordId").field(&self.to_record()).finish()
+    }
+}
+
+impl<T: Table> fmt::Display for RecordId<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", T::NAME, self.id)
+    }
+}
+
+impl<T: Table> FromStr for RecordId<T> {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(Self::new(s))
+    }
+}
+
+impl<T: Table> From<&str> for RecordId<T> {
+    fn from(id: &str) -> Self {
+        Self::new(id)
+    }
+}
+
+impl<T: Table> From<String> for RecordId<T> {
+    fn from(id: String) -> Self {
+        Self::new(id)
+    }
+}
+
+impl<T: Table> From<RecordId<T>> for String {
+    fn from(id: RecordId<T>) -> Self {
+        id.to_record()
+    }
+}
+
+impl<T: Table> Serialize for RecordId<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_record())
+    }
+}
+
+impl<'de, T: Table> Deserialize<'de> for RecordId<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        crate::utils::serde_helpers::thing_id::deserialize(deserializer).map(Self::new)
+    }
+}
+
 /// 分页结果结构
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PaginatedResult<T> {
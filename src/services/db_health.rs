@@ -0,0 +1,229 @@
+use crate::{
+    error::Result,
+    models::db_health::*,
+    services::Database,
+    utils::db_health::index_hints,
+};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{info, warn};
+
+/// 报告中列出的最大表数量
+const LARGEST_TABLES: usize = 10;
+
+/// 历史报告保留天数
+const REPORT_RETENTION_DAYS: i64 = 30;
+
+#[derive(Debug, Deserialize)]
+struct CountRow {
+    count: i64,
+}
+
+/// 关联字段的存储方式：record 类型可以直接解引用，字符串保存的是目标表中的记录 ID
+#[derive(Debug, Clone, Copy)]
+enum RelationKind {
+    Record,
+    StringId,
+}
+
+struct OrphanRule {
+    table: &'static str,
+    field: &'static str,
+    target: &'static str,
+    kind: RelationKind,
+}
+
+impl OrphanRule {
+    fn condition(&self) -> String {
+        match self.kind {
+            RelationKind::Record => format!("{field} != NONE AND {field}.id IS NONE", field = self.field),
+            RelationKind::StringId => format!(
+                "{field} != NONE AND type::thing('{target}', {field}).id IS NONE",
+                field = self.field,
+                target = self.target
+            ),
+        }
+    }
+}
+
+/// 需要检查的关联。文章等主记录一般是软删除，只有物理删除或迁移出错时才会留下孤立行
+const ORPHAN_RULES: &[OrphanRule] = &[
+    OrphanRule { table: "article_tag", field: "article_id", target: "article", kind: RelationKind::Record },
+    OrphanRule { table: "article_tag", field: "tag_id", target: "tag", kind: RelationKind::Record },
+    OrphanRule { table: "user_tag_follow", field: "tag_id", target: "tag", kind: RelationKind::Record },
+    OrphanRule { table: "reaction", field: "article_id", target: "article", kind: RelationKind::Record },
+    OrphanRule { table: "bookmark", field: "article_id", target: "article", kind: RelationKind::Record },
+    OrphanRule { table: "highlight", field: "article_id", target: "article", kind: RelationKind::Record },
+    OrphanRule { table: "search_index", field: "article_id", target: "article", kind: RelationKind::Record },
+    OrphanRule { table: "article_stats_daily", field: "article_id", target: "article", kind: RelationKind::Record },
+    OrphanRule { table: "comment", field: "article_id", target: "article", kind: RelationKind::StringId },
+    OrphanRule { table: "comment_clap", field: "comment_id", target: "comment", kind: RelationKind::Record },
+    OrphanRule { table: "article_collaborator", field: "article_id", target: "article", kind: RelationKind::StringId },
+    OrphanRule { table: "article_attachment", field: "article_id", target: "article", kind: RelationKind::StringId },
+    OrphanRule { table: "reading_queue_item", field: "article_id", target: "article", kind: RelationKind::StringId },
+    OrphanRule { table: "reading_progress", field: "article_id", target: "article", kind: RelationKind::StringId },
+    OrphanRule { table: "publication_member", field: "publication_id", target: "publication", kind: RelationKind::Record },
+    OrphanRule { table: "publication_follow", field: "publication_id", target: "publication", kind: RelationKind::Record },
+];
+
+/// 数据库统计和完整性检查：各表行数、索引建议、孤立的关联行和对应的清理计划。
+/// 定期任务生成报告并保存，管理员接口读取最近一次的结果；清理语句只列出，不自动执行
+#[derive(Clone)]
+pub struct DbHealthService {
+    db: Arc<Database>,
+}
+
+impl DbHealthService {
+    pub async fn new(db: Arc<Database>) -> Result<Self> {
+        Ok(Self { db })
+    }
+
+    /// 最近一次保存的报告
+    pub async fn latest_report(&self) -> Result<Option<DbHealthReport>> {
+        self.db
+            .prepare("SELECT * FROM db_health_report ORDER BY generated_at DESC LIMIT 1")
+            .fetch_one()
+            .await
+    }
+
+    /// 执行一次完整性检查并保存报告，同时清理过期的历史报告
+    pub async fn run_integrity_check(&self) -> Result<DbHealthReport> {
+        let started = Instant::now();
+
+        let mut tables = Vec::new();
+        let mut hints = Vec::new();
+        for table in self.list_tables().await? {
+            let rows = self.count_rows(&table, None).await?;
+            let (fields, indexes) = self.table_info(&table).await?;
+            hints.extend(index_hints(&table, rows, &fields, &indexes));
+            tables.push(TableStats { table, rows, index_count: indexes.len() });
+        }
+        tables.sort_by(|a, b| a.table.cmp(&b.table));
+
+        let mut orphans = Vec::new();
+        let mut action_plan = Vec::new();
+        for rule in ORPHAN_RULES {
+            if !tables.iter().any(|t| t.table == rule.table) {
+                continue;
+            }
+            let condition = rule.condition();
+            let rows = self.count_rows(rule.table, Some(&condition)).await?;
+            if rows == 0 {
+                continue;
+            }
+            action_plan.push(CleanupAction {
+                description: format!(
+                    "Delete {} {} rows whose {} points at a missing {}",
+                    rows, rule.table, rule.field, rule.target
+                ),
+                statement: format!("DELETE {} WHERE {};", rule.table, condition),
+                affected_rows: Some(rows),
+            });
+            orphans.push(OrphanReport {
+                table: rule.table.to_string(),
+                field: rule.field.to_string(),
+                target_table: rule.target.to_string(),
+                rows,
+            });
+        }
+        action_plan.extend(hints.iter().map(|hint| CleanupAction {
+            description: hint.message.clone(),
+            statement: hint.statement.clone(),
+            affected_rows: None,
+        }));
+
+        let mut largest_tables = tables.clone();
+        largest_tables.sort_by(|a, b| b.rows.cmp(&a.rows));
+        largest_tables.truncate(LARGEST_TABLES);
+
+        let report = DbHealthReport {
+            generated_at: Utc::now(),
+            duration_ms: started.elapsed().as_millis() as i64,
+            total_rows: tables.iter().map(|t| t.rows).sum(),
+            tables,
+            largest_tables,
+            index_hints: hints,
+            orphans,
+            action_plan,
+        };
+
+        self.db.create("db_health_report", report.clone()).await?;
+        self.db
+            .prepare("DELETE db_health_report WHERE generated_at < $cutoff")
+            .bind("cutoff", Utc::now() - Duration::days(REPORT_RETENTION_DAYS))
+            .execute()
+            .await?;
+
+        let orphan_rows: i64 = report.orphans.iter().map(|o| o.rows).sum();
+        if orphan_rows > 0 {
+            warn!("Database integrity check found {} orphaned relation rows", orphan_rows);
+        }
+        info!(
+            "Database integrity check finished in {} ms: {} tables, {} index hints",
+            report.duration_ms,
+            report.tables.len(),
+            report.index_hints.len()
+        );
+        Ok(report)
+    }
+
+    async fn list_tables(&self) -> Result<Vec<String>> {
+        let mut response = self.db.query("INFO FOR DB").await?;
+        let info: Option<Value> = response.take(0)?;
+        // SurrealDB 1.x 返回 tables，更早的版本返回 tb
+        let tables = info
+            .as_ref()
+            .and_then(|info| info.get("tables").or_else(|| info.get("tb")))
+            .and_then(Value::as_object)
+            .map(|tables| tables.keys().filter(|t| is_identifier(t)).cloned().collect())
+            .unwrap_or_default();
+        Ok(tables)
+    }
+
+    /// 字段名和索引名到定义语句的映射
+    async fn table_info(&self, table: &str) -> Result<(Vec<String>, HashMap<String, String>)> {
+        let mut response = self.db.query(&format!("INFO FOR TABLE {}", table)).await?;
+        let info: Option<Value> = response.take(0)?;
+        let Some(info) = info else {
+            return Ok((Vec::new(), HashMap::new()));
+        };
+
+        let fields = info
+            .get("fields")
+            .or_else(|| info.get("fd"))
+            .and_then(Value::as_object)
+            .map(|fields| fields.keys().cloned().collect())
+            .unwrap_or_default();
+        let indexes = info
+            .get("indexes")
+            .or_else(|| info.get("ix"))
+            .and_then(Value::as_object)
+            .map(|indexes| {
+                indexes
+                    .iter()
+                    .filter_map(|(name, definition)| Some((name.clone(), definition.as_str()?.to_string())))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok((fields, indexes))
+    }
+
+    async fn count_rows(&self, table: &str, condition: Option<&str>) -> Result<i64> {
+        let sql = match condition {
+            Some(condition) => format!("SELECT count() AS count FROM {} WHERE {} GROUP ALL", table, condition),
+            None => format!("SELECT count() AS count FROM {} GROUP ALL", table),
+        };
+        let mut response = self.db.query(&sql).await?;
+        let rows: Vec<CountRow> = response.take(0)?;
+        Ok(rows.first().map_or(0, |r| r.count))
+    }
+}
+
+/// 表名会拼接进 SQL，只接受普通标识符
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
@@ -3,17 +3,59 @@ use crate::{
     models::domain::*,
     services::Database,
 };
+use base64::{engine::general_purpose::STANDARD, Engine as _};
 use chrono::{Duration, Utc};
+use rsa::{
+    pkcs1::{EncodeRsaPrivateKey, LineEnding},
+    pkcs8::EncodePublicKey,
+    RsaPrivateKey,
+};
 use serde_json::json;
+use std::net::IpAddr;
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 use reqwest::Client;
+#[cfg(feature = "acme")]
+use crate::services::acme::{
+    AcmeChallenge, AcmeClient, AcmeConfig, IssuedCertificate, LETS_ENCRYPT_PRODUCTION, LETS_ENCRYPT_STAGING,
+};
 use trust_dns_resolver::{
     config::{ResolverConfig, ResolverOpts},
     TokioAsyncResolver,
 };
 
+/// Record name prefix of the ACME DNS-01 challenge delegation
+const ACME_CHALLENGE_PREFIX: &str = "_acme-challenge.";
+
+/// Label of the return-path (bounce) host under a sending domain
+const RETURN_PATH_LABEL: &str = "bounces";
+const DEFAULT_FROM_LOCAL_PART: &str = "newsletter";
+const DKIM_KEY_BITS: usize = 2048;
+
+/// The CNAME/A/ALIAS record that routes traffic for the domain itself to the platform
+fn is_routing_record(record: &DomainVerificationRecord, custom_domain: &str) -> bool {
+    record.record_name == custom_domain
+}
+
+/// The host that owns the ownership/ACME records (`*.example.com` -> `example.com`)
+fn record_host(custom_domain: &str) -> &str {
+    custom_domain.strip_prefix("*.").unwrap_or(custom_domain)
+}
+
+/// Root domains (`example.com`) cannot carry a CNAME record
+fn is_apex_domain(domain: &str) -> bool {
+    domain.split('.').count() == 2
+}
+
+/// Wildcard records cannot be queried directly, so look up a random label under them
+fn probe_name(name: &str) -> String {
+    match name.strip_prefix("*.") {
+        Some(parent) => format!("rainbow-probe-{}.{}", &Uuid::new_v4().simple().to_string()[..8], parent),
+        None => name.to_string(),
+    }
+}
+
 /// Configuration for domain service
 #[derive(Clone)]
 pub struct DomainConfig {
@@ -29,6 +71,18 @@ pub struct DomainConfig {
     pub auto_provision_ssl: bool,
     /// Webhook URL for SSL certificate events
     pub ssl_webhook_url: Option<String>,
+    /// Addresses apex domains point their A records at
+    pub apex_target_ips: Vec<IpAddr>,
+    /// ACME directory for the built-in certificate client ("letsencrypt",
+    /// "letsencrypt-staging" or a URL); takes precedence over the SSL provider
+    pub acme_directory_url: Option<String>,
+    pub acme_contact_email: Option<String>,
+    /// acme-dns compatible API used to publish DNS-01 challenges
+    pub acme_dns_api_url: Option<String>,
+    /// SPF include that authorizes the platform's outgoing mail servers
+    pub email_spf_include: String,
+    /// Host the return-path CNAME of sending domains points at
+    pub email_return_path_target: String,
 }
 
 #[derive(Clone)]
@@ -37,6 +91,8 @@ pub struct DomainService {
     config: DomainConfig,
     http_client: Client,
     dns_resolver: TokioAsyncResolver,
+    #[cfg(feature = "acme")]
+    acme: Option<AcmeClient>,
 }
 
 impl DomainService {
@@ -51,11 +107,35 @@ impl DomainService {
             ResolverOpts::default(),
         );
 
+        #[cfg(feature = "acme")]
+        let acme = config.acme_directory_url.as_deref().map(|directory_url| {
+            let directory_url = match directory_url {
+                "letsencrypt" => LETS_ENCRYPT_PRODUCTION.to_string(),
+                "letsencrypt-staging" => LETS_ENCRYPT_STAGING.to_string(),
+                url => url.to_string(),
+            };
+            AcmeClient::new(
+                db.clone(),
+                AcmeConfig {
+                    directory_url,
+                    contact_email: config.acme_contact_email.clone(),
+                    dns_api_url: config.acme_dns_api_url.clone(),
+                },
+                http_client.clone(),
+            )
+        });
+        #[cfg(not(feature = "acme"))]
+        if config.acme_directory_url.is_some() {
+            warn!("ACME_DIRECTORY_URL is set but the acme feature is not enabled, falling back to the SSL provider");
+        }
+
         Ok(Self {
             db,
             config,
             http_client,
             dns_resolver,
+            #[cfg(feature = "acme")]
+            acme,
         })
     }
 
@@ -95,6 +175,10 @@ impl DomainService {
             },
             ssl_expires_at: None,
             is_primary: request.is_primary.unwrap_or(false),
+            routing_mode: RoutingMode::Cname,
+            cutover_phase: None,
+            ownership_verified_at: None,
+            cutover_completed_at: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -137,6 +221,16 @@ impl DomainService {
 
         // Generate verification token
         let verification_token = self.generate_verification_token();
+        let cutover = request.cutover.unwrap_or(false);
+        let routing_mode = request.routing_mode.clone()
+            .unwrap_or_else(|| self.default_routing_mode(&request.domain));
+
+        if routing_mode == RoutingMode::A && self.config.apex_target_ips.is_empty() {
+            return Err(AppError::BadRequest("A record routing is not available: no target IPs are configured".to_string()));
+        }
+        if routing_mode == RoutingMode::Cname && is_apex_domain(&request.domain) {
+            return Err(AppError::BadRequest("Apex domains cannot use CNAME routing, use A or ALIAS records instead".to_string()));
+        }
 
         // Create domain record
         let domain = PublicationDomain {
@@ -152,6 +246,10 @@ impl DomainService {
             ssl_status: SSLStatus::None,
             ssl_expires_at: None,
             is_primary: request.is_primary.unwrap_or(false),
+            routing_mode,
+            cutover_phase: cutover.then_some(CutoverPhase::AwaitingOwnership),
+            ownership_verified_at: None,
+            cutover_completed_at: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -187,38 +285,14 @@ impl DomainService {
             return Err(AppError::BadRequest("Only custom domains need verification".to_string()));
         }
 
-        // Get verification records
-        let verification_records = self.get_verification_records(domain_id).await?;
+        // Transfer-in domains follow the cutover flow instead
+        if matches!(&domain.cutover_phase, Some(phase) if *phase != CutoverPhase::Completed) {
+            return self.advance_cutover(domain).await;
+        }
 
         // Perform DNS verification
-        let mut all_verified = true;
-        let mut errors = Vec::new();
-        let mut updated_records = Vec::new();
-
-        for mut record in verification_records {
-            match self.verify_dns_record(&domain, &record).await {
-                Ok(verified) => {
-                    record.is_verified = verified;
-                    record.last_checked_at = Some(Utc::now());
-                    if !verified {
-                        all_verified = false;
-                        errors.push(format!("DNS record {} not found or incorrect", record.record_name));
-                    }
-                }
-                Err(e) => {
-                    all_verified = false;
-                    errors.push(format!("Failed to verify {}: {}", record.record_name, e));
-                }
-            }
-            
-            // Update verification record
-            let thing = soulcore::prelude::Thing {
-                tb: "domain_verification_record".to_string(),
-                id: surrealdb::sql::Id::String(record.id.to_string()),
-            };
-            self.db.update(thing, record.clone()).await?;
-            updated_records.push(record);
-        }
+        let (updated_records, errors) = self.check_verification_records(&domain).await?;
+        let all_verified = errors.is_empty();
 
         // Update domain status
         let new_status = if all_verified {
@@ -253,6 +327,191 @@ impl DomainService {
         })
     }
 
+    /// Get the cutover status of a transfer-in domain
+    pub async fn get_cutover_status(&self, domain_id: &str) -> Result<CutoverStatusResponse> {
+        let domain: PublicationDomain = self.db
+            .get_by_id("publication_domain", domain_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Domain not found".to_string()))?;
+
+        let phase = domain.cutover_phase.clone()
+            .ok_or_else(|| AppError::BadRequest("Domain was not added in cutover mode".to_string()))?;
+        let custom_domain = domain.custom_domain.clone()
+            .ok_or_else(|| AppError::Internal("Custom domain not set".to_string()))?;
+
+        let verification_records = self.get_verification_records(domain_id).await?;
+        let dns_switched = phase == CutoverPhase::Completed
+            || verification_records.iter()
+                .filter(|record| is_routing_record(record, &custom_domain))
+                .all(|record| record.is_verified);
+
+        let next_step = match phase {
+            CutoverPhase::AwaitingOwnership => format!(
+                "Add the TXT record _rainbow-verify.{} and the _acme-challenge CNAME record. Keep the domain pointing at your current host.",
+                record_host(&custom_domain)
+            ),
+            CutoverPhase::IssuingCertificate => {
+                "SSL certificate is being issued. Keep the domain pointing at your current host.".to_string()
+            }
+            CutoverPhase::AwaitingDnsSwitch => format!(
+                "Ready to switch. Point {} to {}; the domain is activated as soon as the change is detected.",
+                custom_domain,
+                self.routing_target_description(&domain.routing_mode)
+            ),
+            CutoverPhase::Completed => "Cutover complete.".to_string(),
+        };
+
+        Ok(CutoverStatusResponse {
+            domain_id: domain.id,
+            domain: custom_domain,
+            phase,
+            ownership_verified: domain.ownership_verified_at.is_some(),
+            ssl_status: domain.ssl_status,
+            dns_switched,
+            next_step,
+            verification_records,
+            cutover_completed_at: domain.cutover_completed_at,
+        })
+    }
+
+    /// Check DNS for all transfer-in domains that have not been cut over yet
+    pub async fn check_pending_cutovers(&self) -> Result<()> {
+        let query = "SELECT * FROM publication_domain WHERE cutover_phase INSIDE ['awaiting_ownership', 'issuing_certificate', 'awaiting_dns_switch']";
+
+        let mut response = self.db.query(query).await?;
+        let domains: Vec<PublicationDomain> = response.take(0)?;
+
+        for domain in domains {
+            let domain_id = domain.id;
+            if let Err(e) = self.advance_cutover(domain).await {
+                error!("Failed to check cutover for domain {}: {}", domain_id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Move a transfer-in domain through its cutover phases.
+    ///
+    /// Ownership is proven via TXT and the certificate is issued via DNS-01 while
+    /// the domain still points at the old host; the domain only becomes active once
+    /// the routing CNAME is detected, so there is no gap between the DNS switch and
+    /// verification.
+    async fn advance_cutover(&self, domain: PublicationDomain) -> Result<DomainVerificationResponse> {
+        let domain_id = domain.id.to_string();
+        let custom_domain = domain.custom_domain.clone()
+            .ok_or_else(|| AppError::Internal("Custom domain not set".to_string()))?;
+
+        let (records, _) = self.check_verification_records(&domain).await?;
+
+        // The routing CNAME is expected to fail until the owner switches DNS
+        let ownership_verified = domain.ownership_verified_at.is_some()
+            || records.iter()
+                .filter(|record| !is_routing_record(record, &custom_domain))
+                .all(|record| record.is_verified);
+        let dns_switched = records.iter()
+            .filter(|record| is_routing_record(record, &custom_domain))
+            .all(|record| record.is_verified);
+
+        let mut status = domain.status.clone();
+        let mut errors = Vec::new();
+
+        if domain.ownership_verified_at.is_none() {
+            if ownership_verified {
+                self.db.update_by_id_with_json::<PublicationDomain>(
+                    "publication_domain",
+                    &domain_id,
+                    json!({
+                        "status": DomainStatus::Verifying,
+                        "cutover_phase": CutoverPhase::IssuingCertificate,
+                        "ownership_verified_at": Utc::now(),
+                        "updated_at": Utc::now(),
+                    }),
+                ).await?;
+                status = DomainStatus::Verifying;
+                info!("Ownership of {} verified, issuing certificate before cutover", custom_domain);
+
+                if let Err(e) = self.request_ssl_certificate(&domain_id, &custom_domain, "dns-01").await {
+                    warn!("Failed to pre-issue SSL for {}: {}", custom_domain, e);
+                    errors.push(format!("SSL pre-issuance failed: {}", e));
+                }
+            } else {
+                errors.push("Ownership records not found or incorrect".to_string());
+            }
+        }
+
+        if ownership_verified && dns_switched {
+            if self.complete_cutover(&domain_id).await? {
+                info!("Cutover completed for domain {}", custom_domain);
+            }
+            status = DomainStatus::Active;
+        }
+
+        Ok(DomainVerificationResponse {
+            domain_id: domain.id,
+            verified: status == DomainStatus::Active,
+            status,
+            verification_records: records,
+            errors: if errors.is_empty() { None } else { Some(errors) },
+        })
+    }
+
+    /// Activate a transfer-in domain in a single statement; returns false if it was already active
+    async fn complete_cutover(&self, domain_id: &str) -> Result<bool> {
+        let mut response = self.db.query_with_params(
+            r#"
+                UPDATE type::thing('publication_domain', $domain_id) SET
+                    status = 'active',
+                    verified_at = time::now(),
+                    cutover_phase = 'completed',
+                    cutover_completed_at = time::now(),
+                    updated_at = time::now()
+                WHERE status != 'active'
+                RETURN AFTER
+            "#,
+            json!({ "domain_id": domain_id }),
+        ).await?;
+        let updated: Vec<PublicationDomain> = response.take(0)?;
+
+        Ok(!updated.is_empty())
+    }
+
+    /// Check every verification record of a domain against DNS and persist the result
+    async fn check_verification_records(
+        &self,
+        domain: &PublicationDomain,
+    ) -> Result<(Vec<DomainVerificationRecord>, Vec<String>)> {
+        let verification_records = self.get_verification_records(&domain.id.to_string()).await?;
+
+        let mut errors = Vec::new();
+        let mut updated_records = Vec::new();
+
+        for mut record in verification_records {
+            match self.verify_dns_record(domain, &record).await {
+                Ok(verified) => {
+                    record.is_verified = verified;
+                    record.last_checked_at = Some(Utc::now());
+                    if !verified {
+                        errors.push(format!("DNS record {} not found or incorrect", record.record_name));
+                    }
+                }
+                Err(e) => {
+                    errors.push(format!("Failed to verify {}: {}", record.record_name, e));
+                }
+            }
+
+            // Update verification record
+            let thing = soulcore::prelude::Thing {
+                tb: "domain_verification_record".to_string(),
+                id: surrealdb::sql::Id::String(record.id.to_string()),
+            };
+            self.db.update(thing, record.clone()).await?;
+            updated_records.push(record);
+        }
+
+        Ok((updated_records, errors))
+    }
+
     /// Get all domains for a publication
     pub async fn get_publication_domains(
         &self,
@@ -260,13 +519,11 @@ impl DomainService {
     ) -> Result<DomainListResponse> {
         debug!("Getting domains for publication {}", publication_id);
 
-        let query = format!(
-            "SELECT * FROM publication_domain WHERE publication_id = '{}' ORDER BY is_primary DESC, created_at DESC",
-            publication_id
-        );
-
-        let mut response = self.db.query(&query).await?;
-        let domains: Vec<PublicationDomain> = response.take(0)?;
+        let domains: Vec<PublicationDomain> = self.db
+            .prepare("SELECT * FROM publication_domain WHERE publication_id = $publication_id ORDER BY is_primary DESC, created_at DESC")
+            .bind("publication_id", publication_id)
+            .fetch()
+            .await?;
         let total = domains.len() as i64;
 
         Ok(DomainListResponse {
@@ -345,13 +602,14 @@ impl DomainService {
             .await?
             .ok_or_else(|| AppError::NotFound("Domain not found".to_string()))?;
 
-        // Delete verification records if custom domain
+        // Delete verification records and email settings if custom domain
         if domain.domain_type == DomainType::Custom {
-            let query = format!(
-                "DELETE domain_verification_record WHERE domain_id = '{}'",
-                domain_id
-            );
-            self.db.query(&query).await?;
+            self.db
+                .prepare("DELETE domain_verification_record WHERE domain_id = $domain_id")
+                .bind("domain_id", domain_id)
+                .execute()
+                .await?;
+            self.remove_email_config(&domain.id.to_string()).await?;
         }
 
         // Delete the domain
@@ -361,6 +619,239 @@ impl DomainService {
         Ok(())
     }
 
+    /// Enable newsletter sending from a custom domain: generates a DKIM key and
+    /// the SPF, DKIM and return-path records the owner has to publish.
+    /// Reconfiguring keeps the existing key so published records stay valid.
+    pub async fn configure_email(
+        &self,
+        domain_id: &str,
+        request: ConfigureDomainEmailRequest,
+    ) -> Result<DomainEmailConfig> {
+        let domain: PublicationDomain = self.db
+            .get_by_id("publication_domain", domain_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Domain not found".to_string()))?;
+
+        if domain.domain_type != DomainType::Custom {
+            return Err(AppError::BadRequest("Email sending can only be configured for custom domains".to_string()));
+        }
+        if !domain.is_verified() && domain.ownership_verified_at.is_none() {
+            return Err(AppError::BadRequest("Verify domain ownership before configuring email sending".to_string()));
+        }
+
+        let custom_domain = domain.custom_domain.as_deref()
+            .ok_or_else(|| AppError::Internal("Custom domain not set".to_string()))?;
+        let sending_domain = record_host(custom_domain).to_string();
+        let from_local_part = request.from_local_part
+            .unwrap_or_else(|| DEFAULT_FROM_LOCAL_PART.to_string())
+            .to_lowercase();
+
+        if let Some(existing) = self.get_email_config(&domain.id.to_string()).await? {
+            let updated: Option<DomainEmailConfig> = self.db
+                .prepare("UPDATE type::thing('domain_email_config', $domain_id) SET from_local_part = $from_local_part, updated_at = time::now()")
+                .bind("domain_id", domain.id.to_string())
+                .bind("from_local_part", &from_local_part)
+                .fetch_one()
+                .await?;
+            return Ok(updated.unwrap_or(existing));
+        }
+
+        let (private_key, public_key) = generate_dkim_key()?;
+        let dkim_selector = format!("rainbow{}", Utc::now().format("%Y%m"));
+        let records = vec![
+            EmailDnsRecord {
+                purpose: EmailRecordPurpose::Spf,
+                record_type: "TXT".to_string(),
+                record_name: sending_domain.clone(),
+                record_value: format!("v=spf1 include:{} ~all", self.config.email_spf_include),
+                is_verified: false,
+            },
+            EmailDnsRecord {
+                purpose: EmailRecordPurpose::Dkim,
+                record_type: "TXT".to_string(),
+                record_name: format!("{}._domainkey.{}", dkim_selector, sending_domain),
+                record_value: format!("v=DKIM1; k=rsa; p={}", public_key),
+                is_verified: false,
+            },
+            EmailDnsRecord {
+                purpose: EmailRecordPurpose::ReturnPath,
+                record_type: "CNAME".to_string(),
+                record_name: format!("{}.{}", RETURN_PATH_LABEL, sending_domain),
+                record_value: self.config.email_return_path_target.clone(),
+                is_verified: false,
+            },
+        ];
+
+        let config: Option<DomainEmailConfig> = self.db
+            .prepare(
+                r#"
+                CREATE type::thing('domain_email_config', $domain_id) CONTENT {
+                    domain_id: $domain_id,
+                    publication_id: $publication_id,
+                    sending_domain: $sending_domain,
+                    from_local_part: $from_local_part,
+                    dkim_selector: $dkim_selector,
+                    dkim_private_key: $dkim_private_key,
+                    records: $records,
+                    status: 'pending',
+                    verified_at: NONE,
+                    last_checked_at: NONE,
+                    created_at: time::now(),
+                    updated_at: time::now()
+                }
+                "#,
+            )
+            .bind("domain_id", domain.id.to_string())
+            .bind("publication_id", domain.publication_id.to_string())
+            .bind("sending_domain", &sending_domain)
+            .bind("from_local_part", &from_local_part)
+            .bind("dkim_selector", &dkim_selector)
+            .bind("dkim_private_key", private_key)
+            .bind("records", &records)
+            .fetch_one()
+            .await?;
+
+        info!("Configured email sending for domain {}", sending_domain);
+        config.ok_or_else(|| AppError::Internal("Failed to create email configuration".to_string()))
+    }
+
+    /// Email-sending configuration of a domain, if any
+    pub async fn get_email_config(&self, domain_id: &str) -> Result<Option<DomainEmailConfig>> {
+        self.db
+            .prepare("SELECT * FROM type::thing('domain_email_config', $domain_id)")
+            .bind("domain_id", domain_id)
+            .fetch_one()
+            .await
+    }
+
+    /// Check the SPF, DKIM and return-path records against DNS
+    pub async fn verify_email(&self, domain_id: &str) -> Result<DomainEmailVerificationResponse> {
+        let config = self.get_email_config(domain_id).await?
+            .ok_or_else(|| AppError::NotFound("Email sending is not configured for this domain".to_string()))?;
+
+        let (config, errors) = self.check_email_records(config).await?;
+
+        Ok(DomainEmailVerificationResponse {
+            domain_id: config.domain_id,
+            status: config.status.clone(),
+            verified: errors.is_empty(),
+            records: config.records,
+            errors: if errors.is_empty() { None } else { Some(errors) },
+        })
+    }
+
+    /// Disable sending from a domain; newsletters fall back to the platform sender
+    pub async fn remove_email_config(&self, domain_id: &str) -> Result<()> {
+        self.db
+            .prepare("DELETE type::thing('domain_email_config', $domain_id)")
+            .bind("domain_id", domain_id)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// Verified sending domain of a publication, preferring its primary domain
+    pub async fn get_publication_email_sender(&self, publication_id: &str) -> Result<Option<DomainEmailConfig>> {
+        let configs: Vec<DomainEmailConfig> = self.db
+            .prepare("SELECT * FROM domain_email_config WHERE publication_id = $publication_id AND status = 'verified'")
+            .bind("publication_id", publication_id)
+            .fetch()
+            .await?;
+        if configs.len() <= 1 {
+            return Ok(configs.into_iter().next());
+        }
+
+        let primary_id: Option<String> = self.db
+            .prepare("SELECT VALUE meta::id(id) FROM publication_domain WHERE publication_id = $publication_id AND is_primary = true LIMIT 1")
+            .bind("publication_id", publication_id)
+            .fetch_one()
+            .await?;
+
+        Ok(configs
+            .iter()
+            .find(|config| Some(config.domain_id.to_string()) == primary_id)
+            .cloned()
+            .or_else(|| configs.into_iter().next()))
+    }
+
+    /// Re-check verified sending domains so removed records stop being used
+    pub async fn recheck_email_domains(&self) -> Result<()> {
+        let configs: Vec<DomainEmailConfig> = self.db
+            .prepare("SELECT * FROM domain_email_config WHERE status != 'pending' AND (last_checked_at = NONE OR last_checked_at < time::now() - 1d)")
+            .fetch()
+            .await?;
+
+        for config in configs {
+            let sending_domain = config.sending_domain.clone();
+            match self.check_email_records(config).await {
+                Ok((_, errors)) if !errors.is_empty() => {
+                    warn!("Email records of {} no longer verify: {}", sending_domain, errors.join("; "));
+                }
+                Ok(_) => {}
+                Err(e) => error!("Failed to re-check email records of {}: {}", sending_domain, e),
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn check_email_records(
+        &self,
+        mut config: DomainEmailConfig,
+    ) -> Result<(DomainEmailConfig, Vec<String>)> {
+        let mut errors = Vec::new();
+
+        for record in &mut config.records {
+            let result = match record.purpose {
+                EmailRecordPurpose::Spf => self.verify_spf_record(&record.record_name).await,
+                EmailRecordPurpose::Dkim => self.verify_dkim_record(&record.record_name, &record.record_value).await,
+                EmailRecordPurpose::ReturnPath => self.verify_cname_record(&record.record_name, &record.record_value).await,
+            };
+            match result {
+                Ok(verified) => {
+                    record.is_verified = verified;
+                    if !verified {
+                        errors.push(format!("DNS record {} not found or incorrect", record.record_name));
+                    }
+                }
+                Err(e) => {
+                    record.is_verified = false;
+                    errors.push(format!("Failed to verify {}: {}", record.record_name, e));
+                }
+            }
+        }
+
+        config.status = match (errors.is_empty(), &config.status) {
+            (true, _) => EmailDomainStatus::Verified,
+            (false, EmailDomainStatus::Pending) => EmailDomainStatus::Pending,
+            (false, _) => EmailDomainStatus::Failed,
+        };
+        if errors.is_empty() && config.verified_at.is_none() {
+            config.verified_at = Some(Utc::now());
+        }
+        config.last_checked_at = Some(Utc::now());
+
+        self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('domain_email_config', $domain_id) SET
+                    records = $records,
+                    status = $status,
+                    verified_at = IF $verified_at THEN <datetime> $verified_at ELSE NONE END,
+                    last_checked_at = time::now(),
+                    updated_at = time::now()
+                "#,
+            )
+            .bind("domain_id", config.domain_id.to_string())
+            .bind("records", &config.records)
+            .bind("status", &config.status)
+            .bind("verified_at", config.verified_at)
+            .execute()
+            .await?;
+
+        Ok((config, errors))
+    }
+
     /// Find publication by domain
     pub async fn find_publication_by_domain(
         &self,
@@ -369,38 +860,66 @@ impl DomainService {
         debug!("Finding publication for domain {}", domain);
 
         // First check subdomains
-        let subdomain_query = format!(
-            "SELECT publication_id FROM publication_domain WHERE subdomain = '{}' AND status = 'active' LIMIT 1",
-            domain
-        );
-        
-        let mut response = self.db.query(&subdomain_query).await?;
-        let results: Vec<serde_json::Value> = response.take(0)?;
-        
-        if let Some(result) = results.first() {
-            if let Some(pub_id) = result.get("publication_id").and_then(|v| v.as_str()) {
-                return Ok(Some(pub_id.to_string()));
-            }
+        if let Some(pub_id) = self.find_active_publication_id("subdomain", domain).await? {
+            return Ok(Some(pub_id));
         }
 
         // Then check custom domains
-        let custom_query = format!(
-            "SELECT publication_id FROM publication_domain WHERE custom_domain = '{}' AND status = 'active' LIMIT 1",
-            domain
-        );
-        
-        let mut response = self.db.query(&custom_query).await?;
-        let results: Vec<serde_json::Value> = response.take(0)?;
-        
-        if let Some(result) = results.first() {
-            if let Some(pub_id) = result.get("publication_id").and_then(|v| v.as_str()) {
-                return Ok(Some(pub_id.to_string()));
+        if let Some(pub_id) = self.find_active_publication_id("custom_domain", domain).await? {
+            return Ok(Some(pub_id));
+        }
+
+        // Finally fall back to a wildcard covering the host (one label deep)
+        if let Some((_, parent)) = domain.split_once('.') {
+            let wildcard = format!("*.{}", parent);
+            if let Some(pub_id) = self.find_active_publication_id("custom_domain", &wildcard).await? {
+                return Ok(Some(pub_id));
             }
         }
 
         Ok(None)
     }
 
+    /// Publication of the active domain whose `field` ("subdomain" or "custom_domain") equals `value`
+    async fn find_active_publication_id(&self, field: &'static str, value: &str) -> Result<Option<String>> {
+        let result: Option<serde_json::Value> = self.db
+            .prepare(&format!(
+                "SELECT publication_id FROM publication_domain WHERE {} = $value AND status = 'active' LIMIT 1",
+                field
+            ))
+            .bind("value", value)
+            .fetch_one()
+            .await?;
+
+        Ok(result
+            .as_ref()
+            .and_then(|r| r.get("publication_id"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string()))
+    }
+
+    /// Apex domains default to A records (or ALIAS when no target IPs are configured)
+    fn default_routing_mode(&self, domain: &str) -> RoutingMode {
+        if !is_apex_domain(domain) {
+            RoutingMode::Cname
+        } else if self.config.apex_target_ips.is_empty() {
+            RoutingMode::Alias
+        } else {
+            RoutingMode::A
+        }
+    }
+
+    fn routing_target_description(&self, mode: &RoutingMode) -> String {
+        match mode {
+            RoutingMode::Cname => format!("domains.{} with a CNAME record", self.config.base_domain),
+            RoutingMode::Alias => format!("domains.{} with an ALIAS/ANAME record", self.config.base_domain),
+            RoutingMode::A => {
+                let ips: Vec<String> = self.config.apex_target_ips.iter().map(|ip| ip.to_string()).collect();
+                format!("{} with A records", ips.join(", "))
+            }
+        }
+    }
+
     /// Check subdomain availability
     async fn check_subdomain_availability(&self, subdomain: &str) -> Result<()> {
         let full_subdomain = format!("{}.{}", subdomain, self.config.base_domain);
@@ -445,12 +964,15 @@ impl DomainService {
         let verification_token = domain.verification_token.as_ref()
             .ok_or_else(|| AppError::Internal("Verification token not set".to_string()))?;
 
+        // Wildcard domains are verified on the parent host
+        let host = record_host(custom_domain);
+
         // Create TXT record for domain ownership verification
         let txt_record = DomainVerificationRecord {
             id: Uuid::new_v4(),
             domain_id: domain.id,
             record_type: "TXT".to_string(),
-            record_name: format!("_rainbow-verify.{}", custom_domain),
+            record_name: format!("_rainbow-verify.{}", host),
             record_value: verification_token.clone(),
             is_verified: false,
             last_checked_at: None,
@@ -458,24 +980,50 @@ impl DomainService {
             updated_at: Utc::now(),
         };
 
-        // Create CNAME record for domain routing
-        let cname_record = DomainVerificationRecord {
-            id: Uuid::new_v4(),
-            domain_id: domain.id,
-            record_type: "CNAME".to_string(),
-            record_name: custom_domain.clone(),
-            record_value: format!("domains.{}", self.config.base_domain),
-            is_verified: false,
-            last_checked_at: None,
-            created_at: Utc::now(),
-            updated_at: Utc::now(),
+        // Create routing records for the domain itself
+        let routing_targets = match domain.routing_mode {
+            RoutingMode::Cname => vec![("CNAME", format!("domains.{}", self.config.base_domain))],
+            RoutingMode::Alias => vec![("ALIAS", format!("domains.{}", self.config.base_domain))],
+            RoutingMode::A => self.config.apex_target_ips.iter().map(|ip| ("A", ip.to_string())).collect(),
         };
 
         // Save records to database
         let txt_record: DomainVerificationRecord = self.db.create("domain_verification_record", txt_record).await?;
-        let cname_record: DomainVerificationRecord = self.db.create("domain_verification_record", cname_record).await?;
+        let mut records = vec![txt_record];
+
+        for (record_type, record_value) in routing_targets {
+            let routing_record = DomainVerificationRecord {
+                id: Uuid::new_v4(),
+                domain_id: domain.id,
+                record_type: record_type.to_string(),
+                record_name: custom_domain.clone(),
+                record_value,
+                is_verified: false,
+                last_checked_at: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            };
+            records.push(self.db.create("domain_verification_record", routing_record).await?);
+        }
+
+        // Transfer-in and wildcard domains delegate the ACME challenge so the certificate
+        // can be issued via DNS-01 (before traffic is switched over, or for the wildcard)
+        if domain.cutover_phase.is_some() || domain.is_wildcard() {
+            let acme_record = DomainVerificationRecord {
+                id: Uuid::new_v4(),
+                domain_id: domain.id,
+                record_type: "CNAME".to_string(),
+                record_name: format!("{}{}", ACME_CHALLENGE_PREFIX, host),
+                record_value: self.acme_delegation_target(host).await?,
+                is_verified: false,
+                last_checked_at: None,
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            };
+            records.push(self.db.create("domain_verification_record", acme_record).await?);
+        }
 
-        Ok(vec![txt_record, cname_record])
+        Ok(records)
     }
 
     /// Get verification records for a domain
@@ -483,15 +1031,11 @@ impl DomainService {
         &self,
         domain_id: &str,
     ) -> Result<Vec<DomainVerificationRecord>> {
-        let query = format!(
-            "SELECT * FROM domain_verification_record WHERE domain_id = '{}' ORDER BY created_at",
-            domain_id
-        );
-
-        let mut response = self.db.query(&query).await?;
-        let records: Vec<DomainVerificationRecord> = response.take(0)?;
-
-        Ok(records)
+        self.db
+            .prepare("SELECT * FROM domain_verification_record WHERE domain_id = $domain_id ORDER BY created_at")
+            .bind("domain_id", domain_id)
+            .fetch()
+            .await
     }
 
     /// Verify DNS record
@@ -502,7 +1046,9 @@ impl DomainService {
     ) -> Result<bool> {
         match record.record_type.as_str() {
             "TXT" => self.verify_txt_record(&record.record_name, &record.record_value).await,
-            "CNAME" => self.verify_cname_record(&record.record_name, &record.record_value).await,
+            "CNAME" => self.verify_cname_record(&probe_name(&record.record_name), &record.record_value).await,
+            "A" => self.verify_a_record(&probe_name(&record.record_name), &record.record_value).await,
+            "ALIAS" => self.verify_alias_record(&record.record_name, &record.record_value).await,
             _ => Err(AppError::Internal(format!("Unsupported record type: {}", record.record_type))),
         }
     }
@@ -528,6 +1074,49 @@ impl DomainService {
         Ok(false)
     }
 
+    /// The domain's SPF record must include the platform's mail servers
+    async fn verify_spf_record(&self, name: &str) -> Result<bool> {
+        debug!("Verifying SPF record for {}", name);
+
+        let include = format!("include:{}", self.config.email_spf_include);
+        Ok(self.lookup_txt(name).await?.iter().any(|txt| {
+            txt.starts_with("v=spf1") && txt.split_whitespace().any(|term| term.trim_start_matches('+') == include)
+        }))
+    }
+
+    /// The DKIM record must publish our public key; whitespace is ignored since
+    /// long keys are commonly split over several strings
+    async fn verify_dkim_record(&self, name: &str, expected_value: &str) -> Result<bool> {
+        debug!("Verifying DKIM record for {}", name);
+
+        let expected_key = expected_value
+            .split(';')
+            .map(str::trim)
+            .find_map(|tag| tag.strip_prefix("p="))
+            .unwrap_or_default();
+        Ok(self.lookup_txt(name).await?.iter().any(|txt| {
+            txt.split(';')
+                .map(|tag| tag.split_whitespace().collect::<String>())
+                .any(|tag| tag.strip_prefix("p=") == Some(expected_key))
+        }))
+    }
+
+    /// TXT records of a name, each with its character strings joined
+    async fn lookup_txt(&self, name: &str) -> Result<Vec<String>> {
+        let lookup = self.dns_resolver.txt_lookup(name).await
+            .map_err(|e| AppError::ExternalService(format!("DNS lookup failed: {}", e)))?;
+
+        Ok(lookup
+            .iter()
+            .map(|record| {
+                record
+                    .iter()
+                    .map(|data| String::from_utf8_lossy(data).into_owned())
+                    .collect::<String>()
+            })
+            .collect())
+    }
+
     /// Verify CNAME record
     async fn verify_cname_record(&self, name: &str, expected_value: &str) -> Result<bool> {
         debug!("Verifying CNAME record for {}", name);
@@ -544,6 +1133,39 @@ impl DomainService {
         Ok(false)
     }
 
+    /// Verify an A record resolves to the expected address
+    async fn verify_a_record(&self, name: &str, expected_ip: &str) -> Result<bool> {
+        debug!("Verifying A record for {}", name);
+
+        let expected_ip: IpAddr = expected_ip.parse()
+            .map_err(|_| AppError::Internal(format!("Invalid target IP: {}", expected_ip)))?;
+
+        Ok(self.resolve_ips(name).await?.contains(&expected_ip))
+    }
+
+    /// Verify an ALIAS/ANAME record: the apex must resolve only to addresses of
+    /// the target host or the configured apex IPs
+    async fn verify_alias_record(&self, name: &str, target: &str) -> Result<bool> {
+        debug!("Verifying ALIAS record for {}", name);
+
+        let resolved = self.resolve_ips(name).await?;
+        if resolved.is_empty() {
+            return Ok(false);
+        }
+
+        let mut allowed = self.resolve_ips(target).await?;
+        allowed.extend(self.config.apex_target_ips.iter().copied());
+
+        Ok(resolved.iter().all(|ip| allowed.contains(ip)))
+    }
+
+    async fn resolve_ips(&self, name: &str) -> Result<Vec<IpAddr>> {
+        let lookup = self.dns_resolver.lookup_ip(name).await
+            .map_err(|e| AppError::ExternalService(format!("DNS lookup failed: {}", e)))?;
+
+        Ok(lookup.iter().collect())
+    }
+
     /// Update primary domain for a publication
     async fn update_primary_domain(
         &self,
@@ -551,12 +1173,12 @@ impl DomainService {
         new_primary_id: &Uuid,
     ) -> Result<()> {
         // Remove primary flag from all other domains
-        let query = format!(
-            "UPDATE publication_domain SET is_primary = false WHERE publication_id = '{}' AND id != '{}'",
-            publication_id, new_primary_id
-        );
-
-        self.db.query(&query).await?;
+        self.db
+            .prepare("UPDATE publication_domain SET is_primary = false WHERE publication_id = $publication_id AND id != type::thing('publication_domain', $domain_id)")
+            .bind("publication_id", publication_id)
+            .bind("domain_id", new_primary_id.to_string())
+            .execute()
+            .await?;
         Ok(())
     }
 
@@ -579,11 +1201,37 @@ impl DomainService {
             DomainType::Custom => domain.custom_domain.as_ref(),
         }.ok_or_else(|| AppError::Internal("Domain name not found".to_string()))?;
 
+        // Wildcard certificates can only be issued via DNS-01
+        let challenge = if domain.is_wildcard() { "dns-01" } else { "http-01" };
+        self.request_ssl_certificate(domain_id, domain_name, challenge).await
+    }
+
+    /// Where the domain's `_acme-challenge` record has to be delegated to
+    async fn acme_delegation_target(&self, host: &str) -> Result<String> {
+        #[cfg(feature = "acme")]
+        if let Some(acme) = &self.acme {
+            if let Some(target) = acme.dns_delegation_target(host).await? {
+                return Ok(target);
+            }
+        }
+
+        Ok(format!("{}.acme.{}", host, self.config.base_domain))
+    }
+
+    /// Issue a certificate using the given ACME challenge type, via the built-in
+    /// ACME client when configured and the external SSL provider otherwise
+    async fn request_ssl_certificate(&self, domain_id: &str, domain_name: &str, challenge: &str) -> Result<()> {
+        #[cfg(feature = "acme")]
+        if let Some(acme) = &self.acme {
+            return self.issue_with_acme(acme.clone(), domain_id, domain_name, AcmeChallenge::parse(challenge)?).await;
+        }
+
         // Call SSL provider API if configured
         if let (Some(endpoint), Some(api_key)) = (&self.config.ssl_provider_endpoint, &self.config.ssl_provider_api_key) {
             let request_body = json!({
                 "domain": domain_name,
                 "type": "full",
+                "challenge": challenge,
                 "webhook_url": self.config.ssl_webhook_url,
             });
 
@@ -620,6 +1268,87 @@ impl DomainService {
         Ok(())
     }
 
+    /// Run an ACME order in the background; the domain's SSL status is updated when it finishes
+    #[cfg(feature = "acme")]
+    async fn issue_with_acme(
+        &self,
+        acme: AcmeClient,
+        domain_id: &str,
+        domain_name: &str,
+        challenge: AcmeChallenge,
+    ) -> Result<()> {
+        if challenge == AcmeChallenge::Dns01 && !acme.supports_dns01() {
+            return Err(AppError::BadRequest("DNS-01 issuance requires ACME_DNS_API_URL to be configured".to_string()));
+        }
+
+        // Renewals keep serving the current certificate until the new one is stored
+        self.db.query_with_params(
+            "UPDATE type::thing('publication_domain', $domain_id) SET ssl_status = 'pending', updated_at = time::now() WHERE ssl_status != 'active'",
+            json!({ "domain_id": domain_id }),
+        ).await?;
+
+        let service = self.clone();
+        let domain_id = domain_id.to_string();
+        let domain_name = domain_name.to_string();
+        tokio::spawn(async move {
+            let result = match acme.issue(&domain_name, challenge).await {
+                Ok(certificate) => service.store_certificate(&domain_id, &domain_name, &certificate).await,
+                Err(e) => Err(e),
+            };
+
+            if let Err(e) = result {
+                error!("ACME issuance failed for {}: {}", domain_name, e);
+                let failed = service.db.query_with_params(
+                    "UPDATE type::thing('publication_domain', $domain_id) SET ssl_status = 'failed', updated_at = time::now() WHERE ssl_status = 'pending'",
+                    json!({ "domain_id": domain_id }),
+                ).await;
+                if let Err(e) = failed {
+                    error!("Failed to record ACME failure for {}: {}", domain_name, e);
+                }
+            }
+        });
+
+        info!("ACME certificate issuance started for domain {}", domain_name);
+        Ok(())
+    }
+
+    /// Replace the stored certificate of a domain and mark its SSL as active
+    #[cfg(feature = "acme")]
+    async fn store_certificate(&self, domain_id: &str, domain_name: &str, certificate: &IssuedCertificate) -> Result<()> {
+        self.db.query_with_params(
+            r#"
+                DELETE ssl_certificate WHERE domain_id = $domain_id;
+                CREATE ssl_certificate CONTENT {
+                    domain_id: $domain_id,
+                    domain: $domain,
+                    certificate_pem: $certificate_pem,
+                    private_key_pem: $private_key_pem,
+                    issuer: 'acme',
+                    issued_at: time::now(),
+                    expires_at: <datetime> $expires_at
+                };
+            "#,
+            json!({
+                "domain_id": domain_id,
+                "domain": domain_name,
+                "certificate_pem": certificate.certificate_pem,
+                "private_key_pem": certificate.private_key_pem,
+                "expires_at": certificate.expires_at,
+            }),
+        ).await?;
+
+        self.update_ssl_status(domain_id, SSLStatus::Active, Some(certificate.expires_at)).await
+    }
+
+    /// Key authorization for an HTTP-01 challenge token
+    #[cfg(feature = "acme")]
+    pub async fn acme_http_challenge(&self, token: &str) -> Result<Option<String>> {
+        match &self.acme {
+            Some(acme) => acme.http_challenge_response(token).await,
+            None => Ok(None),
+        }
+    }
+
     /// Update SSL certificate status (called by webhook)
     pub async fn update_ssl_status(
         &self,
@@ -641,23 +1370,29 @@ impl DomainService {
             updates,
         ).await?;
 
+        // A pre-issued certificate means a transfer-in domain is ready for the DNS switch
+        if status == SSLStatus::Active {
+            self.db.query_with_params(
+                "UPDATE type::thing('publication_domain', $domain_id) SET cutover_phase = 'awaiting_dns_switch' WHERE cutover_phase = 'issuing_certificate'",
+                json!({ "domain_id": domain_id }),
+            ).await?;
+        }
+
         Ok(())
     }
 
     /// Get domains needing SSL renewal
     pub async fn get_domains_needing_ssl_renewal(&self) -> Result<Vec<PublicationDomain>> {
-        let query = format!(
-            "SELECT * FROM publication_domain 
-             WHERE ssl_status = 'active' 
-             AND ssl_expires_at < '{}' 
-             AND status = 'active'",
-            Utc::now() + Duration::days(30)
-        );
-
-        let mut response = self.db.query(&query).await?;
-        let domains: Vec<PublicationDomain> = response.take(0)?;
-
-        Ok(domains)
+        self.db
+            .prepare(
+                "SELECT * FROM publication_domain 
+                 WHERE ssl_status = 'active' 
+                 AND ssl_expires_at < <datetime> $renew_before 
+                 AND status = 'active'",
+            )
+            .bind("renew_before", Utc::now() + Duration::days(30))
+            .fetch()
+            .await
     }
 
     /// Renew SSL certificates
@@ -695,6 +1430,21 @@ impl DomainService {
     }
 }
 
+/// Generate a DKIM key pair: PKCS#1 PEM private key and base64 DER public key
+fn generate_dkim_key() -> Result<(String, String)> {
+    let private_key = RsaPrivateKey::new(&mut rand::thread_rng(), DKIM_KEY_BITS)
+        .map_err(|e| AppError::Internal(format!("Failed to generate DKIM key: {}", e)))?;
+    let private_pem = private_key
+        .to_pkcs1_pem(LineEnding::LF)
+        .map_err(|e| AppError::Internal(format!("Failed to encode DKIM key: {}", e)))?;
+    let public_der = private_key
+        .to_public_key()
+        .to_public_key_der()
+        .map_err(|e| AppError::Internal(format!("Failed to encode DKIM public key: {}", e)))?;
+
+    Ok((private_pem.to_string(), STANDARD.encode(public_der.as_bytes())))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,425 @@
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+};
+use async_trait::async_trait;
+use handlebars::Handlebars;
+use lettre::{
+    address::Envelope,
+    message::{
+        dkim::{DkimConfig, DkimSigningAlgorithm, DkimSigningKey},
+        header::{HeaderName, HeaderValue},
+        Mailbox, MultiPart,
+    },
+    transport::smtp::authentication::Credentials,
+    Address, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info};
+
+const SENDGRID_SEND_URL: &str = "https://api.sendgrid.com/v3/mail/send";
+
+const DIGEST_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<body style="font-family: -apple-system, Helvetica, Arial, sans-serif; color: #222;">
+  <h2>{{heading}}</h2>
+  <ul style="padding-left: 18px;">
+    {{#each items}}
+    <li style="margin-bottom: 12px;">
+      <strong>{{this.title}}</strong><br/>
+      <span>{{this.message}}</span>
+    </li>
+    {{/each}}
+  </ul>
+  <p style="font-size: 12px; color: #888;">
+    <a href="{{settings_url}}">Manage notification settings</a>
+  </p>
+</body>
+</html>"#;
+
+const DIGEST_TEXT_TEMPLATE: &str = r#"{{heading}}
+
+{{#each items}}
+- {{this.title}}
+  {{this.message}}
+{{/each}}
+
+Manage notification settings: {{settings_url}}
+"#;
+
+const ACTIVITY_DIGEST_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<body style="font-family: -apple-system, Helvetica, Arial, sans-serif; color: #222;">
+  <h2>{{heading}}</h2>
+  {{#each sections}}
+  <h3 style="margin-top: 24px;">{{this.heading}}</h3>
+  {{#each this.articles}}
+  <p style="margin-bottom: 16px;">
+    <a href="{{this.url}}" style="font-size: 16px; font-weight: bold; color: #222;">{{this.title}}</a><br/>
+    {{#if this.subtitle}}<span>{{this.subtitle}}</span><br/>{{/if}}
+    {{#if this.author}}<span style="font-size: 13px; color: #666;">{{this.author}}</span>{{/if}}
+  </p>
+  {{/each}}
+  {{/each}}
+  <p style="font-size: 12px; color: #888;">
+    <a href="{{settings_url}}">Change how often you receive this digest</a>
+  </p>
+</body>
+</html>"#;
+
+const ACTIVITY_DIGEST_TEXT_TEMPLATE: &str = r#"{{heading}}
+{{#each sections}}
+
+{{this.heading}}
+{{#each this.articles}}
+- {{this.title}}{{#if this.author}} ({{this.author}}){{/if}}
+  {{this.url}}
+{{/each}}
+{{/each}}
+
+Change how often you receive this digest: {{settings_url}}
+"#;
+
+/// 邮件模板中的单条通知
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestEmailEntry {
+    pub title: String,
+    pub message: String,
+}
+
+/// 关注动态摘要中的一组文章，例如“来自关注的作者”
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityDigestSection {
+    pub heading: String,
+    pub articles: Vec<ActivityDigestArticle>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityDigestArticle {
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub author: Option<String>,
+    pub url: String,
+}
+
+/// 一封待发送的邮件
+#[derive(Debug, Clone)]
+pub struct OutgoingEmail {
+    pub from_name: String,
+    pub from_email: String,
+    pub reply_to: Option<String>,
+    pub to: String,
+    pub subject: String,
+    pub text: String,
+    pub html: String,
+    /// 额外的邮件头，例如 List-Unsubscribe
+    pub headers: Vec<(String, String)>,
+    /// 信封发件人（退信地址），默认与发件人相同
+    pub return_path: Option<String>,
+    /// 使用出版物自己的发信域名时的 DKIM 签名
+    pub dkim: Option<DkimKey>,
+}
+
+/// 发信域名的 DKIM 私钥
+#[derive(Debug, Clone)]
+pub struct DkimKey {
+    pub domain: String,
+    pub selector: String,
+    /// PKCS#1 PEM 格式的 RSA 私钥
+    pub private_key: String,
+}
+
+/// 邮件服务商
+#[async_trait]
+pub trait EmailProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// 发送邮件，返回服务商的消息 ID（如果有）
+    async fn send(&self, email: &OutgoingEmail) -> Result<Option<String>>;
+}
+
+/// 根据 `EMAIL_PROVIDER` 选择邮件服务商
+pub fn email_provider(config: &Config) -> Result<Arc<dyn EmailProvider>> {
+    match config.email_provider.as_str() {
+        "smtp" => Ok(Arc::new(SmtpProvider::new(config))),
+        "sendgrid" => {
+            let api_key = config
+                .sendgrid_api_key
+                .clone()
+                .ok_or_else(|| AppError::Email("SENDGRID_API_KEY is required for the sendgrid provider".to_string()))?;
+            Ok(Arc::new(SendGridProvider::new(api_key)?))
+        }
+        other => Err(AppError::Email(format!("Unknown email provider: {}", other))),
+    }
+}
+
+/// 通过 SMTP 发送
+pub struct SmtpProvider {
+    config: Config,
+}
+
+impl SmtpProvider {
+    pub fn new(config: &Config) -> Self {
+        Self { config: config.clone() }
+    }
+
+    fn transport(&self) -> Result<AsyncSmtpTransport<Tokio1Executor>> {
+        if self.config.smtp_username.is_empty() {
+            // 本地开发环境通常使用无认证的 SMTP（例如 MailHog）
+            return Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&self.config.smtp_host)
+                .port(self.config.smtp_port)
+                .build());
+        }
+
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&self.config.smtp_host)
+            .map_err(|e| AppError::Email(format!("Failed to create SMTP transport: {}", e)))?
+            .port(self.config.smtp_port)
+            .credentials(Credentials::new(
+                self.config.smtp_username.clone(),
+                self.config.smtp_password.clone(),
+            ))
+            .build();
+
+        Ok(transport)
+    }
+}
+
+#[async_trait]
+impl EmailProvider for SmtpProvider {
+    fn name(&self) -> &'static str {
+        "smtp"
+    }
+
+    async fn send(&self, email: &OutgoingEmail) -> Result<Option<String>> {
+        let from: Mailbox = format!("{} <{}>", email.from_name, email.from_email)
+            .parse()
+            .map_err(|e| AppError::Email(format!("Invalid sender address: {}", e)))?;
+        let to: Mailbox = email
+            .to
+            .parse()
+            .map_err(|e| AppError::Email(format!("Invalid recipient address {}: {}", email.to, e)))?;
+
+        let mut builder = Message::builder().from(from).to(to).subject(&email.subject);
+        if let Some(reply_to) = &email.reply_to {
+            let reply_to: Mailbox = reply_to
+                .parse()
+                .map_err(|e| AppError::Email(format!("Invalid reply-to address: {}", e)))?;
+            builder = builder.reply_to(reply_to);
+        }
+        for (name, value) in &email.headers {
+            let name = HeaderName::new_from_ascii(name.clone())
+                .map_err(|e| AppError::Email(format!("Invalid header name {}: {}", name, e)))?;
+            builder = builder.raw_header(HeaderValue::new(name, value.clone()));
+        }
+
+        if let Some(return_path) = &email.return_path {
+            let parse = |address: &str| {
+                address
+                    .parse::<Address>()
+                    .map_err(|e| AppError::Email(format!("Invalid address {}: {}", address, e)))
+            };
+            let envelope = Envelope::new(Some(parse(return_path)?), vec![parse(&email.to)?])
+                .map_err(|e| AppError::Email(format!("Invalid envelope: {}", e)))?;
+            builder = builder.envelope(envelope);
+        }
+
+        let mut message = builder
+            .multipart(MultiPart::alternative_plain_html(email.text.clone(), email.html.clone()))
+            .map_err(|e| AppError::Email(format!("Failed to build email: {}", e)))?;
+
+        if let Some(dkim) = &email.dkim {
+            let key = DkimSigningKey::new(&dkim.private_key, DkimSigningAlgorithm::Rsa)
+                .map_err(|e| AppError::Email(format!("Invalid DKIM key for {}: {}", dkim.domain, e)))?;
+            message.sign(&DkimConfig::default_config(dkim.selector.clone(), dkim.domain.clone(), key));
+        }
+
+        self.transport()?
+            .send(message)
+            .await
+            .map_err(|e| AppError::Email(format!("SMTP delivery failed: {}", e)))?;
+
+        Ok(None)
+    }
+}
+
+/// 通过 SendGrid Web API 发送
+pub struct SendGridProvider {
+    api_key: String,
+    client: Client,
+}
+
+impl SendGridProvider {
+    pub fn new(api_key: String) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| AppError::Email(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self { api_key, client })
+    }
+}
+
+#[async_trait]
+impl EmailProvider for SendGridProvider {
+    fn name(&self) -> &'static str {
+        "sendgrid"
+    }
+
+    /// SendGrid 使用自己的 DKIM 签名和退信地址，`dkim` 与 `return_path` 在这里不生效
+    async fn send(&self, email: &OutgoingEmail) -> Result<Option<String>> {
+        let mut body = json!({
+            "personalizations": [{ "to": [{ "email": email.to }] }],
+            "from": { "email": email.from_email, "name": email.from_name },
+            "subject": email.subject,
+            "content": [
+                { "type": "text/plain", "value": email.text },
+                { "type": "text/html", "value": email.html },
+            ],
+        });
+        if let Some(reply_to) = &email.reply_to {
+            body["reply_to"] = json!({ "email": reply_to });
+        }
+        if !email.headers.is_empty() {
+            body["headers"] = email
+                .headers
+                .iter()
+                .map(|(name, value)| (name.clone(), json!(value)))
+                .collect::<serde_json::Map<_, _>>()
+                .into();
+        }
+
+        let response = self.client
+            .post(SENDGRID_SEND_URL)
+            .bearer_auth(&self.api_key)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| AppError::Email(format!("SendGrid request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let detail = response.text().await.unwrap_or_default();
+            return Err(AppError::Email(format!("SendGrid rejected email ({}): {}", status, detail)));
+        }
+
+        Ok(response
+            .headers()
+            .get("x-message-id")
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string()))
+    }
+}
+
+/// 邮件发送服务
+#[derive(Clone)]
+pub struct EmailService {
+    config: Config,
+    templates: Arc<Handlebars<'static>>,
+    provider: Arc<dyn EmailProvider>,
+}
+
+impl EmailService {
+    pub fn new(config: &Config) -> Result<Self> {
+        let mut templates = Handlebars::new();
+        templates
+            .register_template_string("digest_html", DIGEST_HTML_TEMPLATE)
+            .map_err(|e| AppError::Email(format!("Failed to register template: {}", e)))?;
+        templates
+            .register_template_string("digest_text", DIGEST_TEXT_TEMPLATE)
+            .map_err(|e| AppError::Email(format!("Failed to register template: {}", e)))?;
+        templates
+            .register_template_string("activity_digest_html", ACTIVITY_DIGEST_HTML_TEMPLATE)
+            .map_err(|e| AppError::Email(format!("Failed to register template: {}", e)))?;
+        templates
+            .register_template_string("activity_digest_text", ACTIVITY_DIGEST_TEXT_TEMPLATE)
+            .map_err(|e| AppError::Email(format!("Failed to register template: {}", e)))?;
+
+        Ok(Self {
+            config: config.clone(),
+            templates: Arc::new(templates),
+            provider: email_provider(config)?,
+        })
+    }
+
+    pub fn provider_name(&self) -> &'static str {
+        self.provider.name()
+    }
+
+    /// 将若干通知合并渲染为一封摘要邮件并发送
+    pub async fn send_digest(&self, to: &str, entries: &[DigestEmailEntry]) -> Result<()> {
+        let subject = if entries.len() == 1 {
+            entries[0].title.clone()
+        } else {
+            format!("You have {} new notifications", entries.len())
+        };
+
+        let data = serde_json::json!({
+            "heading": subject,
+            "items": entries,
+            "settings_url": format!("{}/settings/notifications", self.config.frontend_url),
+        });
+
+        let html = self.templates
+            .render("digest_html", &data)
+            .map_err(|e| AppError::Email(format!("Failed to render digest: {}", e)))?;
+        let text = self.templates
+            .render("digest_text", &data)
+            .map_err(|e| AppError::Email(format!("Failed to render digest: {}", e)))?;
+
+        self.send(to, &subject, text, html).await
+    }
+
+    /// 渲染并发送关注动态摘要
+    pub async fn send_activity_digest(
+        &self,
+        to: &str,
+        subject: &str,
+        sections: &[ActivityDigestSection],
+    ) -> Result<()> {
+        let data = serde_json::json!({
+            "heading": subject,
+            "sections": sections,
+            "settings_url": format!("{}/settings/notifications", self.config.frontend_url),
+        });
+
+        let html = self.templates
+            .render("activity_digest_html", &data)
+            .map_err(|e| AppError::Email(format!("Failed to render activity digest: {}", e)))?;
+        let text = self.templates
+            .render("activity_digest_text", &data)
+            .map_err(|e| AppError::Email(format!("Failed to render activity digest: {}", e)))?;
+
+        self.send(to, subject, text, html).await
+    }
+
+    /// 以平台默认发件人发送一封同时包含纯文本和 HTML 的邮件
+    pub async fn send(&self, to: &str, subject: &str, text: String, html: String) -> Result<()> {
+        self.deliver(&OutgoingEmail {
+            from_name: self.config.smtp_from_name.clone(),
+            from_email: self.config.smtp_from_email.clone(),
+            reply_to: None,
+            to: to.to_string(),
+            subject: subject.to_string(),
+            text,
+            html,
+            headers: Vec::new(),
+            return_path: None,
+            dkim: None,
+        })
+        .await
+        .map(|_| ())
+    }
+
+    /// 通过当前配置的服务商发送邮件，返回服务商的消息 ID
+    pub async fn deliver(&self, email: &OutgoingEmail) -> Result<Option<String>> {
+        debug!("Sending email '{}' to {} via {}", email.subject, email.to, self.provider.name());
+        let message_id = self.provider.send(email).await?;
+
+        info!("Email '{}' delivered to {}", email.subject, email.to);
+        Ok(message_id)
+    }
+}
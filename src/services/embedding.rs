@@ -0,0 +1,308 @@
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+    models::id::{bare_id, ArticleId},
+    services::Database,
+    utils::embedding::{article_text, content_hash, hashed_embedding, LOCAL_DIMENSIONS},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info};
+
+/// 每轮重建向量最多处理的文章数
+const REINDEX_BATCH: usize = 50;
+/// 每次请求接口时一起计算的文本数
+const PROVIDER_BATCH: usize = 16;
+
+/// 向量模型
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// 保存在向量旁边的模型标识，换了模型的旧向量不参与比较，并会被重新计算
+    fn model(&self) -> &str;
+
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+/// 根据 `EMBEDDING_PROVIDER` 选择向量模型，`none` 表示不启用
+pub fn embedding_provider(config: &Config) -> Result<Option<Arc<dyn EmbeddingProvider>>> {
+    match config.embedding_provider.as_str() {
+        "none" | "" => Ok(None),
+        "local" => Ok(Some(Arc::new(LocalHashProvider))),
+        "openai" => Ok(Some(Arc::new(OpenAiCompatibleProvider::new(config)?))),
+        other => Err(AppError::internal(&format!("Unknown embedding provider: {}", other))),
+    }
+}
+
+/// 进程内的哈希向量，见 `utils::embedding`
+pub struct LocalHashProvider;
+
+#[async_trait]
+impl EmbeddingProvider for LocalHashProvider {
+    fn model(&self) -> &str {
+        "local-hash-384"
+    }
+
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        Ok(inputs.iter().map(|text| hashed_embedding(text, LOCAL_DIMENSIONS)).collect())
+    }
+}
+
+/// OpenAI 兼容的 `/v1/embeddings` 接口，也可以指向本地的 Ollama、vLLM 等服务
+pub struct OpenAiCompatibleProvider {
+    client: Client,
+    api_url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+impl OpenAiCompatibleProvider {
+    pub fn new(config: &Config) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()?;
+
+        Ok(Self {
+            client,
+            api_url: config.embedding_api_url.clone(),
+            api_key: config.embedding_api_key.clone(),
+            model: config.embedding_model.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OpenAiCompatibleProvider {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut request = self.client
+            .post(&self.api_url)
+            .json(&json!({ "model": self.model, "input": inputs }));
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(AppError::ExternalService(format!(
+                "Embedding API returned status {}",
+                response.status()
+            )));
+        }
+
+        let payload: Value = response.json().await?;
+        let mut data: Vec<(usize, Vec<f32>)> = payload["data"]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(position, item)| {
+                        let index = item["index"].as_u64().map_or(position, |i| i as usize);
+                        let vector = serde_json::from_value(item["embedding"].clone()).ok()?;
+                        Some((index, vector))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if data.len() != inputs.len() {
+            return Err(AppError::ExternalService(format!(
+                "Embedding API returned {} vectors for {} inputs",
+                data.len(),
+                inputs.len()
+            )));
+        }
+
+        data.sort_by_key(|(index, _)| *index);
+        Ok(data.into_iter().map(|(_, vector)| vector).collect())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StaleArticle {
+    id: String,
+    title: String,
+    subtitle: Option<String>,
+    excerpt: Option<String>,
+    content: String,
+    updated_at: DateTime<Utc>,
+    indexed_hash: Option<String>,
+    indexed_model: Option<String>,
+}
+
+/// 与某篇文章内容相近的文章
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimilarArticle {
+    pub article_id: String,
+    pub similarity: f64,
+}
+
+/// 文章向量：保存在 `article_embedding`（每篇文章一条，记录 ID 与文章相同），
+/// 用于相关文章和语义搜索。文章内容变化后由后台任务重新计算
+#[derive(Clone)]
+pub struct EmbeddingService {
+    db: Arc<Database>,
+    provider: Option<Arc<dyn EmbeddingProvider>>,
+}
+
+impl EmbeddingService {
+    pub async fn new(config: &Config, db: Arc<Database>) -> Result<Self> {
+        Ok(Self {
+            db,
+            provider: embedding_provider(config)?,
+        })
+    }
+
+    /// 当前模型标识，未启用时为 None
+    pub fn model(&self) -> Option<&str> {
+        self.provider.as_ref().map(|provider| provider.model())
+    }
+
+    /// 搜索词的向量，未启用时返回 None
+    pub async fn embed_query(&self, query: &str) -> Result<Option<Vec<f32>>> {
+        let Some(provider) = &self.provider else {
+            return Ok(None);
+        };
+        let mut vectors = provider.embed(&[query.to_string()]).await?;
+        Ok(vectors.pop())
+    }
+
+    /// 按向量相似度排列的已发布文章，不含文章本身。
+    /// 未启用或文章还没有向量时返回空列表，调用方自行回退到按标签推荐
+    pub async fn similar_articles(
+        &self,
+        article_id: &str,
+        publication_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<SimilarArticle>> {
+        let Some(model) = self.model() else {
+            return Ok(vec![]);
+        };
+
+        let publication_filter = if publication_id.is_some() {
+            "AND type::thing('article', article_id).publication_id = type::thing('publication', $publication_id)"
+        } else {
+            ""
+        };
+        let query = format!(
+            r#"
+            LET $source = type::thing('article_embedding', $article_id);
+            SELECT article_id, vector::similarity::cosine(vector, $source.vector) AS similarity
+            FROM article_embedding
+            WHERE $source.model = $model
+                AND model = $model
+                AND article_id != $article_id
+                AND type::thing('article', article_id).status = 'published'
+                AND type::thing('article', article_id).is_deleted = false
+                {}
+            ORDER BY similarity DESC
+            LIMIT $limit
+            "#,
+            publication_filter
+        );
+
+        let mut response = self.db.query_with_params(&query, json!({
+            "article_id": ArticleId::new(article_id).as_str(),
+            "publication_id": publication_id.map(|id| bare_id("publication", id)),
+            "model": model,
+            "limit": limit,
+        })).await?;
+
+        Ok(response.take(1)?)
+    }
+
+    /// 为新发布或内容有变化的文章计算向量，并清理已删除文章的向量。返回重新计算的数量
+    pub async fn reindex_stale(&self) -> Result<usize> {
+        let Some(provider) = &self.provider else {
+            return Ok(0);
+        };
+
+        self.db
+            .query("DELETE article_embedding WHERE type::thing('article', article_id).id IS NONE OR type::thing('article', article_id).is_deleted = true")
+            .await?;
+
+        // 文章更新时间和记录的不一致才需要检查，内容摘要相同时只同步时间，不重新计算
+        let stale: Vec<StaleArticle> = self.db
+            .prepare(
+                r#"
+                SELECT
+                    meta::id(id) AS id, title, subtitle, excerpt, content, updated_at,
+                    type::thing('article_embedding', meta::id(id)).content_hash AS indexed_hash,
+                    type::thing('article_embedding', meta::id(id)).model AS indexed_model
+                FROM article
+                WHERE status = 'published'
+                    AND is_deleted = false
+                    AND (type::thing('article_embedding', meta::id(id)).source_updated_at != updated_at
+                        OR type::thing('article_embedding', meta::id(id)).model != $model)
+                LIMIT $limit
+                "#,
+            )
+            .bind("model", provider.model())
+            .bind("limit", REINDEX_BATCH)
+            .fetch()
+            .await?;
+        if stale.is_empty() {
+            return Ok(0);
+        }
+
+        let mut changed = Vec::new();
+        for article in stale {
+            let text = article_text(&article.title, article.subtitle.as_deref(), article.excerpt.as_deref(), &article.content);
+            let hash = content_hash(&text);
+            let unchanged = article.indexed_hash.as_deref() == Some(hash.as_str())
+                && article.indexed_model.as_deref() == Some(provider.model());
+            if unchanged {
+                self.db
+                    .prepare("UPDATE type::thing('article_embedding', $article_id) SET source_updated_at = $updated_at")
+                    .bind("article_id", &article.id)
+                    .bind("updated_at", article.updated_at)
+                    .execute()
+                    .await?;
+            } else {
+                changed.push((article, text, hash));
+            }
+        }
+
+        for chunk in changed.chunks(PROVIDER_BATCH) {
+            let inputs: Vec<String> = chunk.iter().map(|(_, text, _)| text.clone()).collect();
+            let vectors = provider.embed(&inputs).await?;
+            for ((article, _, hash), vector) in chunk.iter().zip(vectors) {
+                self.db
+                    .prepare(
+                        r#"
+                        UPSERT type::thing('article_embedding', $article_id) CONTENT {
+                            article_id: $article_id,
+                            model: $model,
+                            dimensions: $dimensions,
+                            vector: $vector,
+                            content_hash: $content_hash,
+                            source_updated_at: $updated_at,
+                            updated_at: time::now()
+                        }
+                        "#,
+                    )
+                    .bind("article_id", &article.id)
+                    .bind("model", provider.model())
+                    .bind("dimensions", vector.len())
+                    .bind("vector", &vector)
+                    .bind("content_hash", hash)
+                    .bind("updated_at", article.updated_at)
+                    .execute()
+                    .await?;
+                debug!("Embedded article {}", article.id);
+            }
+        }
+
+        if !changed.is_empty() {
+            info!("Computed embeddings for {} articles with model {}", changed.len(), provider.model());
+        }
+        Ok(changed.len())
+    }
+}
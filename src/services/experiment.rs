@@ -0,0 +1,324 @@
+use crate::{
+    error::{AppError, Result},
+    models::{
+        article::{Article, ArticleListItem, UpdateArticleRequest},
+        experiment::*,
+        id::{bare_id, ArticleId},
+    },
+    services::{
+        article::ArticleService, bot_detection::BotDetectionService,
+        view_tracking::ViewTrackingService, Database,
+    },
+    utils::experiment::{assign_variant, VARIANT_KEYS},
+};
+use axum::http::HeaderMap;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+use validator::Validate;
+
+#[derive(Debug, Deserialize)]
+struct VariantCountRow {
+    variant: String,
+    impressions: i64,
+    clicks: i64,
+}
+
+/// 文章标题和封面的 A/B 实验。读者按 `viewer_hash` 固定分组；
+/// 在列表和推荐中看到某个版本记为一次曝光，之后打开文章记为一次点击，都按读者去重
+#[derive(Clone)]
+pub struct ExperimentService {
+    db: Arc<Database>,
+    article_service: ArticleService,
+    view_tracking_service: ViewTrackingService,
+    bot_detection_service: BotDetectionService,
+}
+
+impl ExperimentService {
+    pub async fn new(
+        db: Arc<Database>,
+        article_service: ArticleService,
+        view_tracking_service: ViewTrackingService,
+        bot_detection_service: BotDetectionService,
+    ) -> Result<Self> {
+        Ok(Self { db, article_service, view_tracking_service, bot_detection_service })
+    }
+
+    pub async fn list(&self, article_id: &str) -> Result<Vec<ArticleExperiment>> {
+        self.db
+            .prepare("SELECT * FROM article_experiment WHERE article_id = $article_id ORDER BY created_at DESC")
+            .bind("article_id", ArticleId::new(article_id).as_str())
+            .fetch()
+            .await
+    }
+
+    pub async fn get(&self, article_id: &str, experiment_id: &str) -> Result<ArticleExperiment> {
+        let experiment: Option<ArticleExperiment> = self.db
+            .get_by_id("article_experiment", bare_id("article_experiment", experiment_id))
+            .await?;
+        experiment
+            .filter(|e| e.article_id == ArticleId::new(article_id).as_str())
+            .ok_or_else(|| AppError::not_found("Experiment"))
+    }
+
+    /// 每篇文章同时只能运行一个实验
+    pub async fn create(&self, article: &Article, request: CreateExperimentRequest) -> Result<ArticleExperiment> {
+        request.validate().map_err(AppError::ValidatorError)?;
+        for variant in &request.variants {
+            variant.validate().map_err(AppError::ValidatorError)?;
+        }
+        if !article.can_be_viewed_by_public() {
+            return Err(AppError::BadRequest("Experiments can only run on published articles".to_string()));
+        }
+        if self.list(&article.id).await?.iter().any(|e| e.status == ExperimentStatus::Running) {
+            return Err(AppError::Conflict("This article already has a running experiment".to_string()));
+        }
+
+        let variants: Vec<ExperimentVariant> = request
+            .variants
+            .into_iter()
+            .zip(VARIANT_KEYS)
+            .map(|(input, key)| ExperimentVariant {
+                key: key.to_string(),
+                title: input.title.trim().to_string(),
+                cover_image_url: input.cover_image_url,
+            })
+            .collect();
+
+        let experiment: Option<ArticleExperiment> = self.db
+            .prepare(
+                r#"
+                CREATE article_experiment CONTENT {
+                    article_id: $article_id,
+                    author_id: $author_id,
+                    variants: $variants,
+                    status: 'running',
+                    created_at: time::now()
+                }
+                "#,
+            )
+            .bind("article_id", ArticleId::new(&article.id).as_str())
+            .bind("author_id", &article.author_id)
+            .bind("variants", &variants)
+            .fetch_one()
+            .await?;
+
+        let experiment = experiment.ok_or_else(|| AppError::internal("Failed to create experiment"))?;
+        info!("Started experiment {} on article {} with {} variants", experiment.id, article.id, variants.len());
+        Ok(experiment)
+    }
+
+    pub async fn get_results(&self, article_id: &str, experiment_id: &str) -> Result<ExperimentResults> {
+        let experiment = self.get(article_id, experiment_id).await?;
+
+        let rows: Vec<VariantCountRow> = self.db
+            .prepare(
+                r#"
+                SELECT variant, count() AS impressions, count(clicked = true) AS clicks
+                FROM experiment_exposure
+                WHERE experiment_id = $experiment_id
+                GROUP BY variant
+                "#,
+            )
+            .bind("experiment_id", bare_id("article_experiment", &experiment.id))
+            .fetch()
+            .await?;
+        let counts: HashMap<String, VariantCountRow> = rows.into_iter().map(|r| (r.variant.clone(), r)).collect();
+
+        let variants: Vec<VariantStats> = experiment
+            .variants
+            .iter()
+            .map(|variant| {
+                let (impressions, clicks) = counts
+                    .get(&variant.key)
+                    .map_or((0, 0), |r| (r.impressions, r.clicks));
+                VariantStats {
+                    key: variant.key.clone(),
+                    title: variant.title.clone(),
+                    cover_image_url: variant.cover_image_url.clone(),
+                    impressions,
+                    clicks,
+                    click_through_rate: if impressions > 0 { clicks as f64 / impressions as f64 } else { 0.0 },
+                }
+            })
+            .collect();
+
+        let leader = variants
+            .iter()
+            .filter(|v| v.impressions > 0)
+            .max_by(|a, b| a.click_through_rate.total_cmp(&b.click_through_rate))
+            .map(|v| v.key.clone());
+
+        Ok(ExperimentResults { experiment, variants, leader })
+    }
+
+    /// 采用胜出版本：写入文章的标题和封面（记录一个修订版本），并结束实验
+    pub async fn promote(
+        &self,
+        article: &Article,
+        user_id: &str,
+        experiment_id: &str,
+        request: PromoteVariantRequest,
+    ) -> Result<ArticleExperiment> {
+        let experiment = self.get(&article.id, experiment_id).await?;
+        if experiment.status != ExperimentStatus::Running {
+            return Err(AppError::BadRequest("Experiment has already finished".to_string()));
+        }
+        let variant = experiment
+            .variant(&request.variant)
+            .ok_or_else(|| AppError::BadRequest(format!("Unknown variant '{}'", request.variant)))?;
+
+        self.article_service
+            .update_article(
+                &article.id,
+                user_id,
+                UpdateArticleRequest {
+                    title: Some(variant.title.clone()),
+                    cover_image_url: variant.cover_image_url.clone(),
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        info!("Promoted variant {} of experiment {} on article {}", variant.key, experiment.id, article.id);
+        self.complete(&experiment, Some(&variant.key)).await
+    }
+
+    /// 不采用任何版本直接结束，文章保持原来的标题和封面
+    pub async fn stop(&self, article_id: &str, experiment_id: &str) -> Result<ArticleExperiment> {
+        let experiment = self.get(article_id, experiment_id).await?;
+        if experiment.status != ExperimentStatus::Running {
+            return Err(AppError::BadRequest("Experiment has already finished".to_string()));
+        }
+        self.complete(&experiment, None).await
+    }
+
+    async fn complete(&self, experiment: &ArticleExperiment, winner: Option<&str>) -> Result<ArticleExperiment> {
+        let updated: Option<ArticleExperiment> = self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('article_experiment', $experiment_id) SET
+                    status = 'completed',
+                    winner = $winner ?? NONE,
+                    completed_at = time::now()
+                "#,
+            )
+            .bind("experiment_id", bare_id("article_experiment", &experiment.id))
+            .bind("winner", winner)
+            .fetch_one()
+            .await?;
+
+        updated.ok_or_else(|| AppError::internal("Failed to update experiment"))
+    }
+
+    /// 把列表中正在做实验的文章替换成读者所在分组的版本，并记录曝光。
+    /// 失败时保留原标题，不影响列表返回；机器流量只替换不计入曝光
+    pub async fn apply_variants<'a>(
+        &self,
+        items: impl IntoIterator<Item = &'a mut ArticleListItem>,
+        headers: &HeaderMap,
+        user_id: Option<&str>,
+    ) {
+        let mut items: Vec<&mut ArticleListItem> = items.into_iter().collect();
+        if items.is_empty() {
+            return;
+        }
+
+        let article_ids: Vec<String> = items.iter().map(|item| ArticleId::new(&item.id).as_str().to_string()).collect();
+        let experiments: Vec<ArticleExperiment> = match self.db
+            .prepare("SELECT * FROM article_experiment WHERE article_id INSIDE $article_ids AND status = 'running'")
+            .bind("article_ids", &article_ids)
+            .fetch()
+            .await
+        {
+            Ok(experiments) => experiments,
+            Err(e) => {
+                warn!("Failed to load running experiments: {}", e);
+                return;
+            }
+        };
+        if experiments.is_empty() {
+            return;
+        }
+
+        let visitor = self.view_tracking_service.viewer_hash(headers, user_id);
+        let by_article: HashMap<&str, &ArticleExperiment> =
+            experiments.iter().map(|e| (e.article_id.as_str(), e)).collect();
+
+        let mut exposures = Vec::new();
+        for item in items.iter_mut() {
+            let Some(experiment) = by_article.get(ArticleId::new(&item.id).as_str()).copied() else {
+                continue;
+            };
+            let experiment_id = bare_id("article_experiment", &experiment.id);
+            let variant = &experiment.variants[assign_variant(experiment_id, &visitor, experiment.variants.len())];
+
+            item.title = variant.title.clone();
+            if variant.cover_image_url.is_some() {
+                item.cover_image_url = variant.cover_image_url.clone();
+            }
+            exposures.push(json!({
+                "experiment_id": experiment_id,
+                "article_id": experiment.article_id,
+                "variant": variant.key,
+            }));
+        }
+
+        if exposures.is_empty() || self.bot_detection_service.detect(headers).is_some() {
+            return;
+        }
+
+        // 同一读者重复看到只记一次曝光
+        let result = self.db
+            .prepare(
+                r#"
+                FOR $exposure IN $exposures {
+                    UPSERT type::thing('experiment_exposure', [$exposure.experiment_id, $visitor]) MERGE {
+                        experiment_id: $exposure.experiment_id,
+                        article_id: $exposure.article_id,
+                        variant: $exposure.variant,
+                        viewer_hash: $visitor
+                    };
+                };
+                "#,
+            )
+            .bind("exposures", &exposures)
+            .bind("visitor", &visitor)
+            .execute()
+            .await;
+        if let Err(e) = result {
+            warn!("Failed to record experiment exposures: {}", e);
+        }
+    }
+
+    /// 读者打开正在做实验的文章。只有之前看到过某个版本的读者才计为点击
+    pub async fn record_click(&self, article_id: &str, headers: &HeaderMap, user_id: Option<&str>) -> Result<()> {
+        let experiment: Option<ArticleExperiment> = self.db
+            .prepare("SELECT * FROM article_experiment WHERE article_id = $article_id AND status = 'running' LIMIT 1")
+            .bind("article_id", ArticleId::new(article_id).as_str())
+            .fetch_one()
+            .await?;
+        let Some(experiment) = experiment else {
+            return Ok(());
+        };
+
+        let visitor = self.view_tracking_service.viewer_hash(headers, user_id);
+        // 按 ID 条件更新，曝光记录不存在时不会新建
+        self.db
+            .prepare(
+                r#"
+                UPDATE experiment_exposure SET clicked = true, clicked_at = time::now()
+                WHERE id = type::thing('experiment_exposure', [$experiment_id, $visitor]) AND clicked = false
+                "#,
+            )
+            .bind("experiment_id", bare_id("article_experiment", &experiment.id))
+            .bind("visitor", &visitor)
+            .execute()
+            .await?;
+
+        debug!("Recorded click for experiment {} on article {}", experiment.id, article_id);
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use crate::{config::Config, error::Result, utils::middleware::client_ip};
+use axum::http::HeaderMap;
+use maxminddb::{geoip2, Reader};
+use std::net::IpAddr;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// CDN 写入的访客国家代码，按优先级排列
+const COUNTRY_HEADERS: [&str; 3] = ["cf-ipcountry", "cloudfront-viewer-country", "x-country-code"];
+
+/// 访客国家：优先使用 CDN 提供的国家代码，没有时在配置的 MaxMind 数据库中查询客户端 IP。
+/// 未配置数据库时只使用 CDN 请求头
+#[derive(Clone)]
+pub struct GeoIpService {
+    reader: Option<Arc<Reader<Vec<u8>>>>,
+}
+
+impl GeoIpService {
+    pub async fn new(config: &Config) -> Result<Self> {
+        let reader = match &config.geoip_database_path {
+            Some(path) => match Reader::open_readfile(path) {
+                Ok(reader) => {
+                    info!("Loaded GeoIP database {} ({})", path, reader.metadata.database_type);
+                    Some(Arc::new(reader))
+                }
+                Err(e) => {
+                    warn!("Failed to open GeoIP database {}, falling back to CDN headers: {}", path, e);
+                    None
+                }
+            },
+            None => None,
+        };
+        Ok(Self { reader })
+    }
+
+    /// ISO 3166-1 alpha-2 国家代码
+    pub fn country(&self, headers: &HeaderMap) -> Option<String> {
+        country_from_headers(headers).or_else(|| {
+            let ip: IpAddr = client_ip(headers)?.parse().ok()?;
+            self.lookup(ip)
+        })
+    }
+
+    fn lookup(&self, ip: IpAddr) -> Option<String> {
+        let reader = self.reader.as_ref()?;
+        let record: geoip2::Country = reader.lookup(ip).ok()?;
+        record.country?.iso_code.map(str::to_string)
+    }
+}
+
+fn country_from_headers(headers: &HeaderMap) -> Option<String> {
+    COUNTRY_HEADERS
+        .iter()
+        .filter_map(|name| headers.get(*name).and_then(|v| v.to_str().ok()))
+        .map(|code| code.trim().to_uppercase())
+        // Cloudflare 用 XX 表示未知，T1 表示 Tor
+        .find(|code| code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic()) && code != "XX")
+}
@@ -0,0 +1,593 @@
+use crate::{
+    config::Config,
+    error::{AppError, ErrorCode, Result},
+    models::{
+        article::ArticleStatus,
+        gift::*,
+        id::bare_id,
+        notification::{CreateNotificationRequest, NotificationType},
+        stripe::{CreateStripeIntentRequest, StripeIntentMode},
+        subscription::SubscriptionStatus,
+    },
+    services::{
+        database::PaginatedResult,
+        stripe::{
+            StripeChargeReversal, StripeChargeReversalKind, StripeGiftPayment, StripeService,
+            StripeSubscriptionStatusUpdate,
+        },
+        ArticleService, Database, NotificationService, PaymentService, SubscriptionService,
+    },
+};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+use validator::Validate;
+
+const CLAIM_TOKEN_LEN: usize = 32;
+
+#[derive(Debug, Deserialize)]
+struct CountRow {
+    total: usize,
+}
+
+/// 代他人购买文章或订阅：赠送人支付后收礼人用领取令牌兑换
+#[derive(Clone)]
+pub struct GiftService {
+    db: Arc<Database>,
+    stripe_service: Arc<StripeService>,
+    article_service: ArticleService,
+    subscription_service: SubscriptionService,
+    payment_service: PaymentService,
+    notification_service: NotificationService,
+    frontend_url: String,
+}
+
+impl GiftService {
+    pub async fn new(
+        db: Arc<Database>,
+        config: &Config,
+        stripe_service: Arc<StripeService>,
+        article_service: ArticleService,
+        subscription_service: SubscriptionService,
+        payment_service: PaymentService,
+        notification_service: NotificationService,
+    ) -> Result<Self> {
+        Ok(Self {
+            db,
+            stripe_service,
+            article_service,
+            subscription_service,
+            payment_service,
+            notification_service,
+            frontend_url: config.frontend_url.clone(),
+        })
+    }
+
+    /// 创建礼物并发起支付。支付成功的 Webhook 到达后礼物才能被领取
+    pub async fn create_gift(
+        &self,
+        sender_id: &str,
+        sender_email: &str,
+        sender_name: Option<&str>,
+        request: CreateGiftRequest,
+    ) -> Result<GiftPurchaseResponse> {
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(format!("礼物请求验证失败: {}", e)))?;
+
+        let recipient_id = request.recipient_id.as_deref().filter(|id| !id.trim().is_empty());
+        let recipient_email = request
+            .recipient_email
+            .as_deref()
+            .map(|email| email.trim().to_lowercase())
+            .filter(|email| !email.is_empty());
+
+        match (recipient_id, &recipient_email) {
+            (Some(_), Some(_)) | (None, None) => {
+                return Err(AppError::BadRequest("请指定收礼用户或收礼邮箱中的一个".to_string()));
+            }
+            (Some(id), None) if id == sender_id => {
+                return Err(AppError::BadRequest("不能把礼物送给自己".to_string()));
+            }
+            _ => {}
+        }
+
+        let (creator_id, title, amount, currency) = match request.gift_type {
+            GiftType::Article => {
+                let article_id = request
+                    .article_id
+                    .as_deref()
+                    .ok_or_else(|| AppError::BadRequest("文章礼物需要指定 article_id".to_string()))?;
+                let article = self
+                    .article_service
+                    .get_article_by_id(article_id)
+                    .await?
+                    .filter(|a| a.status == ArticleStatus::Published)
+                    .ok_or_else(|| AppError::NotFound("文章不存在".to_string()))?;
+                let pricing = self.payment_service.get_article_pricing(article_id).await?;
+                let price = pricing
+                    .price
+                    .filter(|_| pricing.is_paid_content)
+                    .ok_or_else(|| AppError::BadRequest("文章不支持单次购买".to_string()))?;
+
+                (article.author_id, article.title, price, "USD".to_string())
+            }
+            GiftType::Subscription => {
+                let plan_id = request
+                    .plan_id
+                    .as_deref()
+                    .ok_or_else(|| AppError::BadRequest("订阅礼物需要指定 plan_id".to_string()))?;
+                let months = request
+                    .months
+                    .ok_or_else(|| AppError::BadRequest("订阅礼物需要指定月数".to_string()))?;
+                let plan = self.subscription_service.get_subscription_plan(plan_id).await?;
+                if !plan.is_active {
+                    return Err(AppError::BadRequest("订阅计划已停用".to_string()));
+                }
+
+                (plan.creator_id, plan.name, plan.price * i64::from(months), plan.currency)
+            }
+        };
+
+        if recipient_id == Some(creator_id.as_str()) {
+            return Err(AppError::BadRequest("不能把作者自己的内容送给作者".to_string()));
+        }
+
+        let payment_method_id = match request
+            .payment_method_id
+            .as_deref()
+            .map(str::trim)
+            .filter(|pm| !pm.is_empty())
+        {
+            Some(pm) => pm.to_string(),
+            None => self
+                .stripe_service
+                .list_payment_methods(sender_id)
+                .await?
+                .into_iter()
+                .find(|m| m.is_default)
+                .map(|m| m.stripe_payment_method_id)
+                .ok_or_else(|| AppError::coded(ErrorCode::PaymentMethodRequired, "请先添加并设置默认支付方式"))?,
+        };
+
+        let gift: Option<Gift> = self.db
+            .prepare(
+                r#"
+                CREATE gift CONTENT {
+                    sender_id: $sender_id,
+                    gift_type: $gift_type,
+                    creator_id: $creator_id,
+                    title: $title,
+                    article_id: $article_id,
+                    plan_id: $plan_id,
+                    months: $months,
+                    recipient_id: $recipient_id,
+                    recipient_email: $recipient_email,
+                    message: $message,
+                    amount: $amount,
+                    currency: $currency,
+                    status: "pending",
+                    claim_token: $claim_token,
+                    created_at: time::now(),
+                    updated_at: time::now()
+                }
+                "#,
+            )
+            .bind("sender_id", sender_id)
+            .bind("gift_type", request.gift_type)
+            .bind("creator_id", &creator_id)
+            .bind("title", &title)
+            .bind("article_id", request.article_id.as_ref().filter(|_| request.gift_type == GiftType::Article))
+            .bind("plan_id", request.plan_id.as_ref().filter(|_| request.gift_type == GiftType::Subscription))
+            .bind("months", request.months.filter(|_| request.gift_type == GiftType::Subscription))
+            .bind("recipient_id", recipient_id)
+            .bind("recipient_email", &recipient_email)
+            .bind("message", request.message.as_deref().map(str::trim).filter(|m| !m.is_empty()))
+            .bind("amount", amount)
+            .bind("currency", &currency)
+            .bind("claim_token", generate_claim_token())
+            .fetch_one()
+            .await?;
+        let gift = gift.ok_or_else(|| AppError::internal("Failed to create gift"))?;
+
+        // 不传 article_id：礼物支付不能给赠送人自己开通文章
+        let payment = match self
+            .stripe_service
+            .create_payment_intent(
+                sender_id,
+                sender_email,
+                sender_name,
+                CreateStripeIntentRequest {
+                    mode: StripeIntentMode::Payment,
+                    amount: Some(amount),
+                    currency: Some(currency),
+                    payment_method_id: Some(payment_method_id),
+                    article_id: None,
+                    confirm: Some(false),
+                    metadata: Some(json!({
+                        "gift_id": gift.id,
+                        "creator_id": creator_id,
+                        "sender_id": sender_id,
+                    })),
+                },
+            )
+            .await
+        {
+            Ok(payment) => payment,
+            Err(e) => {
+                self.db
+                    .prepare("DELETE type::thing('gift', $gift_id)")
+                    .bind("gift_id", bare_id("gift", &gift.id))
+                    .execute()
+                    .await?;
+                return Err(e);
+            }
+        };
+
+        let intent_id = payment
+            .payment_intent
+            .as_ref()
+            .map(|intent| intent.stripe_payment_intent_id.clone())
+            .ok_or_else(|| AppError::Internal("Stripe 未返回 payment_intent".to_string()))?;
+
+        let gift: Option<Gift> = self.db
+            .prepare("UPDATE type::thing('gift', $gift_id) SET stripe_payment_intent_id = $intent_id, updated_at = time::now() RETURN AFTER")
+            .bind("gift_id", bare_id("gift", &gift.id))
+            .bind("intent_id", intent_id)
+            .fetch_one()
+            .await?;
+        let gift = gift.ok_or_else(|| AppError::internal("Failed to update gift"))?;
+
+        info!("Gift {} created by {} ({:?})", gift.id, sender_id, gift.gift_type);
+        Ok(GiftPurchaseResponse { gift, payment })
+    }
+
+    /// 礼物支付成功：标记为可领取并通知收礼人。已处理过的事件返回 `None`
+    pub async fn handle_gift_payment(&self, payment: &StripeGiftPayment) -> Result<Option<Gift>> {
+        let gift: Option<Gift> = self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('gift', $gift_id)
+                SET status = "paid", stripe_payment_intent_id = $intent_id, tax_amount = $tax_amount, updated_at = time::now()
+                WHERE status = "pending"
+                RETURN AFTER
+                "#,
+            )
+            .bind("gift_id", bare_id("gift", &payment.gift_id))
+            .bind("intent_id", &payment.stripe_payment_intent_id)
+            .bind("tax_amount", payment.tax.amount)
+            .fetch_one()
+            .await?;
+
+        let Some(gift) = gift else {
+            debug!("Gift {} is not pending, skipping payment event", payment.gift_id);
+            return Ok(None);
+        };
+
+        if payment.amount - payment.tax.amount != gift.amount {
+            warn!(
+                "Gift {} was paid {} (tax {}) but priced at {}",
+                gift.id, payment.amount, payment.tax.amount, gift.amount
+            );
+        }
+
+        self.deliver(&gift).await;
+        info!("Gift {} paid and ready to claim", gift.id);
+        Ok(Some(gift))
+    }
+
+    /// Stripe 报告支付失败时把还在等待支付的礼物标记为失败
+    pub async fn handle_gift_payment_failure(&self, stripe_payment_intent_id: &str) -> Result<()> {
+        self.db
+            .prepare(
+                r#"
+                UPDATE gift SET status = "failed", updated_at = time::now()
+                WHERE stripe_payment_intent_id = $intent_id AND status = "pending"
+                "#,
+            )
+            .bind("intent_id", stripe_payment_intent_id)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 处理礼物收款的退款和争议。全额退款或发起争议时作废礼物，已领取的收回兑换的权限，
+    /// 部分退款保留礼物；争议胜诉时恢复。返回受影响的礼物，不是礼物的收款返回 `None`
+    pub async fn handle_gift_charge_reversal(&self, reversal: &StripeChargeReversal) -> Result<Option<Gift>> {
+        let gift: Option<Gift> = self.db
+            .prepare(
+                r#"
+                SELECT * FROM gift
+                WHERE stripe_payment_intent_id = $intent_id AND status INSIDE ["paid", "claimed", "revoked"]
+                LIMIT 1
+                "#,
+            )
+            .bind("intent_id", &reversal.stripe_payment_intent_id)
+            .fetch_one()
+            .await?;
+        let Some(gift) = gift else {
+            return Ok(None);
+        };
+
+        // 领取失败时 claimed_by 会被清空，有值即说明权限已经兑换
+        let restored = match reversal.kind {
+            StripeChargeReversalKind::Refund if reversal.amount < gift.charged_amount() => {
+                info!("Partial refund of {} on gift {}, gift kept", reversal.amount, gift.id);
+                return Ok(Some(gift));
+            }
+            StripeChargeReversalKind::Refund | StripeChargeReversalKind::Dispute => {
+                if gift.status == GiftStatus::Revoked {
+                    return Ok(Some(gift));
+                }
+                "revoked"
+            }
+            StripeChargeReversalKind::DisputeWon => {
+                if gift.status != GiftStatus::Revoked {
+                    return Ok(Some(gift));
+                }
+                if gift.claimed_by.is_some() { "claimed" } else { "paid" }
+            }
+        };
+
+        let updated: Option<Gift> = self.db
+            .prepare("UPDATE type::thing('gift', $gift_id) SET status = $status, updated_at = time::now() RETURN AFTER")
+            .bind("gift_id", bare_id("gift", &gift.id))
+            .bind("status", restored)
+            .fetch_one()
+            .await?;
+        let gift = updated.unwrap_or(gift);
+
+        if let Some(recipient_id) = &gift.claimed_by {
+            if reversal.kind == StripeChargeReversalKind::DisputeWon {
+                self.payment_service.restore_gifted_access(&gift, recipient_id).await?;
+            } else {
+                self.payment_service.revoke_gifted_access(&gift, recipient_id, reversal.kind).await?;
+            }
+        }
+
+        info!("Gift {} is now {} after {}", gift.id, restored, reversal.kind.as_str());
+        Ok(Some(gift))
+    }
+
+    /// 用领取令牌兑换礼物。指定了收礼用户的礼物只能由该用户领取；
+    /// 发到邮箱的礼物由持有令牌的登录用户领取
+    pub async fn claim_gift(&self, claim_token: &str, user_id: &str) -> Result<Gift> {
+        let gift: Option<Gift> = self.db
+            .prepare("SELECT * FROM gift WHERE claim_token = $claim_token LIMIT 1")
+            .bind("claim_token", claim_token)
+            .fetch_one()
+            .await?;
+        let gift = gift.ok_or_else(|| AppError::NotFound("礼物不存在".to_string()))?;
+
+        match gift.status {
+            GiftStatus::Paid => {}
+            GiftStatus::Claimed => return Err(AppError::Conflict("礼物已被领取".to_string())),
+            GiftStatus::Pending | GiftStatus::Failed => {
+                return Err(AppError::BadRequest("礼物尚未完成支付".to_string()));
+            }
+            GiftStatus::Revoked => return Err(AppError::BadRequest("礼物的付款已被退回".to_string())),
+        }
+        if gift.sender_id == user_id {
+            return Err(AppError::BadRequest("不能领取自己送出的礼物".to_string()));
+        }
+        if gift.recipient_id.as_deref().is_some_and(|id| id != user_id) {
+            return Err(AppError::Authorization("这份礼物不是送给您的".to_string()));
+        }
+
+        // 先占住礼物，避免同一个令牌被并发领取两次
+        let claimed: Option<Gift> = self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('gift', $gift_id)
+                SET status = "claimed", claimed_by = $user_id, claimed_at = time::now(), updated_at = time::now()
+                WHERE status = "paid"
+                RETURN AFTER
+                "#,
+            )
+            .bind("gift_id", bare_id("gift", &gift.id))
+            .bind("user_id", user_id)
+            .fetch_one()
+            .await?;
+        let claimed = claimed.ok_or_else(|| AppError::Conflict("礼物已被领取".to_string()))?;
+
+        if let Err(e) = self.redeem(&claimed, user_id).await {
+            self.db
+                .prepare(
+                    r#"
+                    UPDATE type::thing('gift', $gift_id)
+                    SET status = "paid", claimed_by = NONE, claimed_at = NONE, updated_at = time::now()
+                    "#,
+                )
+                .bind("gift_id", bare_id("gift", &claimed.id))
+                .execute()
+                .await?;
+            return Err(e);
+        }
+
+        self.notify(
+            &claimed.sender_id,
+            "Your gift was claimed",
+            format!("Your gift \"{}\" was claimed.", claimed.title),
+            &claimed,
+        )
+        .await;
+
+        info!("Gift {} claimed by {}", claimed.id, user_id);
+        Ok(claimed)
+    }
+
+    /// 当前用户送出的礼物
+    pub async fn list_sent(&self, sender_id: &str, page: usize, limit: usize) -> Result<PaginatedResult<Gift>> {
+        self.list_where("sender_id = $user_id", sender_id, None, page, limit).await
+    }
+
+    /// 当前用户收到的礼物：指定给该用户、发到该用户邮箱或已由该用户领取的已支付礼物
+    pub async fn list_received(
+        &self,
+        user_id: &str,
+        email: &str,
+        page: usize,
+        limit: usize,
+    ) -> Result<PaginatedResult<Gift>> {
+        self.list_where(
+            r#"status INSIDE ["paid", "claimed"]
+            AND (recipient_id = $user_id OR claimed_by = $user_id OR recipient_email = $email)"#,
+            user_id,
+            Some(email),
+            page,
+            limit,
+        )
+        .await
+    }
+
+    /// 礼物订阅到期后收回对应创作者付费文章的阅读权限
+    pub async fn expire_gift_subscriptions(&self) -> Result<usize> {
+        let expired = self.subscription_service.expire_gift_subscriptions().await?;
+
+        for subscription in &expired {
+            self.payment_service
+                .handle_subscription_status_update(&StripeSubscriptionStatusUpdate {
+                    subscription_id: subscription.id.clone(),
+                    creator_id: subscription.creator_id.clone(),
+                    subscriber_id: subscription.subscriber_id.clone(),
+                    status: SubscriptionStatus::Expired,
+                    current_period_end: Some(subscription.current_period_end),
+                    cancel_at_period_end: None,
+                    canceled_at: None,
+                })
+                .await?;
+        }
+
+        if !expired.is_empty() {
+            info!("Expired {} gift subscriptions", expired.len());
+        }
+        Ok(expired.len())
+    }
+
+    async fn list_where(
+        &self,
+        condition: &str,
+        user_id: &str,
+        email: Option<&str>,
+        page: usize,
+        limit: usize,
+    ) -> Result<PaginatedResult<Gift>> {
+        let page = page.max(1);
+        let limit = limit.clamp(1, 100);
+        let offset = (page - 1) * limit;
+
+        let mut response = self.db
+            .prepare(&format!(
+                r#"
+                SELECT count() AS total FROM gift WHERE {condition} GROUP ALL;
+                SELECT * FROM gift WHERE {condition} ORDER BY created_at DESC LIMIT $limit START $offset;
+                "#
+            ))
+            .bind("user_id", user_id)
+            .bind("email", email.map(str::to_lowercase))
+            .bind("limit", limit)
+            .bind("offset", offset)
+            .execute()
+            .await?;
+
+        let totals: Vec<CountRow> = response.take(0)?;
+        let total = totals.first().map_or(0, |t| t.total);
+        let data: Vec<Gift> = response.take(1)?;
+
+        Ok(PaginatedResult {
+            data,
+            total,
+            page,
+            per_page: limit,
+            total_pages: (total + limit - 1) / limit,
+        })
+    }
+
+    async fn redeem(&self, gift: &Gift, user_id: &str) -> Result<()> {
+        match gift.gift_type {
+            GiftType::Article => {
+                self.payment_service.grant_gifted_article(user_id, gift).await?;
+            }
+            GiftType::Subscription => {
+                let (Some(plan_id), Some(months)) = (gift.plan_id.as_deref(), gift.months) else {
+                    return Err(AppError::Internal("订阅礼物缺少计划或月数".to_string()));
+                };
+                let subscription = self
+                    .subscription_service
+                    .create_gift_subscription(user_id, plan_id, months, &gift.id)
+                    .await?;
+
+                self.payment_service
+                    .handle_subscription_status_update(&StripeSubscriptionStatusUpdate {
+                        subscription_id: subscription.id,
+                        creator_id: gift.creator_id.clone(),
+                        subscriber_id: user_id.to_string(),
+                        status: SubscriptionStatus::Active,
+                        current_period_end: Some(subscription.current_period_end),
+                        cancel_at_period_end: None,
+                        canceled_at: None,
+                    })
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 通知收礼人：站内用户发通知，邮箱收礼人发一封带领取链接的邮件
+    async fn deliver(&self, gift: &Gift) {
+        let claim_url = format!("{}/gifts/claim/{}", self.frontend_url, gift.claim_token);
+        let what = match gift.gift_type {
+            GiftType::Article => format!("the article \"{}\"", gift.title),
+            GiftType::Subscription => format!(
+                "{} month(s) of \"{}\"",
+                gift.months.unwrap_or(1),
+                gift.title
+            ),
+        };
+        let mut message = format!("Someone sent you {} as a gift.", what);
+        if let Some(note) = &gift.message {
+            message.push_str(&format!(" They wrote: \"{}\"", note));
+        }
+        message.push_str(&format!(" Claim it at {}", claim_url));
+
+        if let Some(recipient_id) = &gift.recipient_id {
+            self.notify(recipient_id, "You received a gift", message, gift).await;
+        } else if let Some(email) = &gift.recipient_email {
+            if let Err(e) = self
+                .notification_service
+                .send_email(email, "You received a gift".to_string(), message)
+                .await
+            {
+                warn!("Failed to email gift {} to recipient: {}", gift.id, e);
+            }
+        }
+    }
+
+    async fn notify(&self, recipient_id: &str, title: &str, message: String, gift: &Gift) {
+        let request = CreateNotificationRequest {
+            recipient_id: recipient_id.to_string(),
+            notification_type: NotificationType::Payment,
+            title: title.to_string(),
+            message,
+            data: json!({
+                "gift_id": gift.id,
+                "gift_type": gift.gift_type,
+                "article_id": gift.article_id,
+                "plan_id": gift.plan_id,
+            }),
+        };
+        if let Err(e) = self.notification_service.create_notification(request).await {
+            warn!("Failed to send gift notification to {}: {}", recipient_id, e);
+        }
+    }
+}
+
+fn generate_claim_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(CLAIM_TOKEN_LEN)
+        .map(char::from)
+        .collect()
+}
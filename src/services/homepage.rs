@@ -0,0 +1,134 @@
+use crate::{
+    error::{AppError, Result},
+    models::{
+        homepage::*,
+        id::bare_id,
+    },
+    services::{ArticleService, Database},
+    utils::markdown::MarkdownProcessor,
+};
+use std::sync::Arc;
+use tracing::info;
+use validator::Validate;
+
+/// 出版物首页布局的保存和渲染。布局保存在 `publication_homepage`，记录 ID 与出版物相同
+#[derive(Clone)]
+pub struct HomepageService {
+    db: Arc<Database>,
+    article_service: ArticleService,
+}
+
+impl HomepageService {
+    pub async fn new(db: Arc<Database>, article_service: ArticleService) -> Result<Self> {
+        Ok(Self { db, article_service })
+    }
+
+    /// 出版物的首页布局，没有保存过时返回默认布局
+    pub async fn get(&self, publication_id: &str) -> Result<HomepageLayout> {
+        let publication_id = bare_id("publication", publication_id);
+        let layout: Option<HomepageLayout> = self.db
+            .prepare("SELECT * FROM type::thing('publication_homepage', $publication_id)")
+            .bind("publication_id", publication_id)
+            .fetch_one()
+            .await?;
+
+        Ok(layout.unwrap_or_else(|| HomepageLayout::default_for(publication_id)))
+    }
+
+    /// 整体替换首页布局
+    pub async fn update(&self, publication_id: &str, user_id: &str, request: UpdateHomepageRequest) -> Result<HomepageLayout> {
+        request.validate().map_err(AppError::ValidatorError)?;
+        request.featured.validate().map_err(AppError::ValidatorError)?;
+        for section in &request.sections {
+            section.validate().map_err(AppError::ValidatorError)?;
+            match &section.source {
+                SectionSource::Tag { tag } if tag.trim().is_empty() => {
+                    return Err(AppError::bad_request("Tag sections need a tag"));
+                }
+                SectionSource::Manual { article_ids } if article_ids.is_empty() || article_ids.len() > section.limit => {
+                    return Err(AppError::bad_request(&format!(
+                        "Section '{}' needs between 1 and {} articles",
+                        section.title, section.limit
+                    )));
+                }
+                _ => {}
+            }
+        }
+        let about = match request.about {
+            Some(about) => {
+                about.validate().map_err(AppError::ValidatorError)?;
+                let body_html = MarkdownProcessor::new().to_html(&about.body);
+                Some(AboutBlock { title: about.title, body: about.body, body_html })
+            }
+            None => None,
+        };
+
+        let publication_id = bare_id("publication", publication_id);
+        let layout: HomepageLayout = self.db
+            .prepare(
+                r#"
+                UPSERT type::thing('publication_homepage', $publication_id) CONTENT {
+                    publication_id: $publication_id,
+                    featured: $featured,
+                    sections: $sections,
+                    about: $about ?? NONE,
+                    updated_by: $updated_by,
+                    updated_at: time::now()
+                }
+                "#,
+            )
+            .bind("publication_id", publication_id)
+            .bind("featured", &request.featured)
+            .bind("sections", &request.sections)
+            .bind("about", &about)
+            .bind("updated_by", user_id)
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::internal("Failed to save homepage layout"))?;
+
+        info!("Updated homepage layout for publication {} by user {}", publication_id, user_id);
+        Ok(layout)
+    }
+
+    /// 按布局填入文章。精选区已经出现的文章不会在按标签或最新文章的栏目中重复出现
+    pub async fn render(&self, publication_id: &str) -> Result<RenderedHomepage> {
+        let layout = self.get(publication_id).await?;
+        let articles = &self.article_service;
+
+        let featured_articles = if layout.featured.article_ids.is_empty() {
+            articles.get_latest_in_publication(publication_id, None, &[], layout.featured.limit).await?
+        } else {
+            articles.get_publication_articles_by_ids(publication_id, &layout.featured.article_ids).await?
+        };
+        let shown: Vec<String> = featured_articles.iter().map(|a| a.id.clone()).collect();
+
+        let mut sections = Vec::with_capacity(layout.sections.len());
+        for section in layout.sections {
+            let items = match &section.source {
+                SectionSource::Tag { tag } => {
+                    articles.get_latest_in_publication(publication_id, Some(tag), &shown, section.limit).await?
+                }
+                SectionSource::Manual { article_ids } => {
+                    articles.get_publication_articles_by_ids(publication_id, article_ids).await?
+                }
+                SectionSource::Latest => {
+                    articles.get_latest_in_publication(publication_id, None, &shown, section.limit).await?
+                }
+            };
+            sections.push(RenderedSection {
+                title: section.title,
+                style: section.style,
+                articles: items,
+            });
+        }
+
+        Ok(RenderedHomepage {
+            featured: RenderedFeatured {
+                style: layout.featured.style,
+                articles: featured_articles,
+            },
+            sections,
+            about: layout.about,
+        })
+    }
+}
@@ -0,0 +1,234 @@
+use crate::{
+    error::{AppError, Result},
+    models::{article::*, id::ArticleId, import::*},
+    services::{ArticleService, Database, MediaService},
+    utils::{
+        markdown::MarkdownProcessor,
+        post_import::{self, ImportedPost},
+    },
+};
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+const TITLE_MAX_CHARS: usize = 150;
+const SUBTITLE_MAX_CHARS: usize = 200;
+const EXCERPT_MAX_CHARS: usize = 300;
+
+/// 从 Medium / WordPress 导入文章：HTML 转为 Markdown，图片转存到媒体库，保留标签和发布时间
+#[derive(Clone)]
+pub struct ImportService {
+    db: Arc<Database>,
+    article_service: ArticleService,
+    media_service: MediaService,
+    markdown_processor: Arc<MarkdownProcessor>,
+    http_client: Client,
+}
+
+impl ImportService {
+    pub async fn new(db: Arc<Database>, article_service: ArticleService, media_service: MediaService) -> Result<Self> {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| AppError::Internal(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self {
+            db,
+            article_service,
+            media_service,
+            markdown_processor: Arc::new(MarkdownProcessor::new()),
+            http_client,
+        })
+    }
+
+    /// 导入导出文件中的全部文章，单篇失败不影响其他文章
+    pub async fn import(
+        &self,
+        user_id: &str,
+        format: ImportFormat,
+        data: &[u8],
+        as_drafts: bool,
+    ) -> Result<ArticleImportReport> {
+        let posts = match format {
+            ImportFormat::Medium => post_import::parse_medium_zip(data)?,
+            ImportFormat::Wordpress => {
+                let xml = std::str::from_utf8(data)
+                    .map_err(|_| AppError::BadRequest("WordPress export must be UTF-8 encoded".to_string()))?;
+                post_import::parse_wordpress(xml)?
+            }
+        };
+
+        let mut report = ArticleImportReport {
+            format,
+            posts_total: posts.len(),
+            imported: Vec::new(),
+            skipped_existing: 0,
+            failed: Vec::new(),
+            images_imported: 0,
+            images_failed: 0,
+        };
+        let mut image_urls: HashMap<String, Option<String>> = HashMap::new();
+
+        for post in posts {
+            if let Some(source_url) = &post.source_url {
+                if self.is_already_imported(user_id, source_url).await? {
+                    report.skipped_existing += 1;
+                    continue;
+                }
+            }
+
+            let title = post.title.clone();
+            match self.import_post(user_id, format, post, as_drafts, &mut image_urls, &mut report).await {
+                Ok(article) => report.imported.push(article),
+                Err(e) => {
+                    warn!("Failed to import post '{}': {}", title, e);
+                    report.failed.push(ImportFailure { title, error: e.to_string() });
+                }
+            }
+        }
+
+        info!(
+            "Imported {}/{} {} posts for user {}",
+            report.imported.len(),
+            report.posts_total,
+            format.as_str(),
+            user_id
+        );
+        Ok(report)
+    }
+
+    async fn import_post(
+        &self,
+        user_id: &str,
+        format: ImportFormat,
+        post: ImportedPost,
+        as_drafts: bool,
+        image_urls: &mut HashMap<String, Option<String>>,
+        report: &mut ArticleImportReport,
+    ) -> Result<ImportedArticle> {
+        let mut content = self.markdown_processor.from_html(&post.html);
+        if content.is_empty() {
+            return Err(AppError::BadRequest("Post has no content".to_string()));
+        }
+
+        // 图片转存到媒体库，同一张图片只下载一次
+        for url in self.markdown_processor.extract_images(&content) {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                continue;
+            }
+            if !image_urls.contains_key(&url) {
+                let stored = match self.import_image(user_id, &url).await {
+                    Ok(stored) => {
+                        report.images_imported += 1;
+                        Some(stored)
+                    }
+                    Err(e) => {
+                        debug!("Keeping original image {}: {}", url, e);
+                        report.images_failed += 1;
+                        None
+                    }
+                };
+                image_urls.insert(url.clone(), stored);
+            }
+            if let Some(Some(stored)) = image_urls.get(&url) {
+                content = content.replace(&url, stored);
+            }
+        }
+
+        let is_draft = as_drafts || post.is_draft;
+        let request = CreateArticleRequest {
+            title: truncate_chars(&post.title, TITLE_MAX_CHARS),
+            slug: None,
+            subtitle: post.subtitle.as_deref().map(|s| truncate_chars(s, SUBTITLE_MAX_CHARS)),
+            content,
+            excerpt: post.excerpt.as_deref().map(|e| truncate_chars(e, EXCERPT_MAX_CHARS)),
+            cover_image_url: None,
+            publication_id: None,
+            series_id: None,
+            series_order: None,
+            is_paid_content: None,
+            tags: Some(post.tags.clone()).filter(|tags| !tags.is_empty()),
+            seo_title: None,
+            seo_description: None,
+            seo_keywords: None,
+            save_as_draft: Some(is_draft),
+            publish_at: None,
+        };
+        let article = self.article_service.create_article(user_id, request).await?;
+
+        // 保留原发布时间，并记录来源用于去重
+        let original_date = post.published_at.map(|dt| dt.to_rfc3339());
+        self.db.query_with_params(
+            r#"
+                UPDATE type::thing('article', $article_id) SET
+                    metadata.imported_from = $source,
+                    metadata.import_source_url = $source_url,
+                    created_at = IF $original_date THEN <datetime> $original_date ELSE created_at END,
+                    published_at = IF $original_date AND published_at THEN <datetime> $original_date ELSE published_at END
+            "#,
+            json!({
+                "article_id": ArticleId::new(&article.id).as_str(),
+                "source": format.as_str(),
+                "source_url": post.source_url,
+                "original_date": original_date,
+            }),
+        ).await?;
+
+        Ok(ImportedArticle {
+            id: article.id,
+            title: article.title,
+            status: if is_draft { "draft" } else { "published" }.to_string(),
+            source_url: post.source_url,
+        })
+    }
+
+    /// 下载外部图片并上传到媒体库，返回新地址
+    async fn import_image(&self, user_id: &str, url: &str) -> Result<String> {
+        let response = self.http_client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("Failed to download image: {}", e)))?
+            .error_for_status()
+            .map_err(|e| AppError::ExternalService(format!("Failed to download image: {}", e)))?;
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.split(';').next().unwrap_or(v).trim().to_string())
+            .unwrap_or_default();
+        let data = response
+            .bytes()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("Failed to download image: {}", e)))?;
+
+        let filename = url
+            .split(['?', '#'])
+            .next()
+            .and_then(|path| path.rsplit('/').next())
+            .filter(|name| !name.is_empty())
+            .unwrap_or("imported-image");
+
+        let uploaded = self.media_service
+            .upload_image(user_id, filename, &content_type, data.to_vec())
+            .await?;
+        Ok(uploaded.url)
+    }
+
+    async fn is_already_imported(&self, user_id: &str, source_url: &str) -> Result<bool> {
+        let mut response = self.db.query_with_params(
+            "SELECT id FROM article WHERE author_id = $user_id AND metadata.import_source_url = $source_url AND is_deleted = false LIMIT 1",
+            json!({ "user_id": user_id, "source_url": source_url }),
+        ).await?;
+        let existing: Vec<Value> = response.take(0)?;
+        Ok(!existing.is_empty())
+    }
+}
+
+fn truncate_chars(value: &str, max: usize) -> String {
+    value.trim().chars().take(max).collect()
+}
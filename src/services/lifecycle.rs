@@ -0,0 +1,419 @@
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+    models::{id::ArticleId, lifecycle::*, notification::*},
+    services::{Database, NotificationService},
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// 每次执行最多处理的草稿/账户数量，积压会在后续执行中继续处理
+const BATCH_SIZE: usize = 500;
+
+#[derive(Debug, Deserialize)]
+struct StaleDraft {
+    id: String,
+    author_id: String,
+    title: String,
+    updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InactiveAccount {
+    user_id: String,
+    last_active_at: DateTime<Utc>,
+}
+
+/// 草稿和账户的生命周期维护
+///
+/// 所有操作都先提醒、等待提醒期后再执行，并记录到 lifecycle_action 以便审计和撤销。
+#[derive(Clone)]
+pub struct LifecycleService {
+    db: Arc<Database>,
+    notification_service: NotificationService,
+    config: Config,
+}
+
+impl LifecycleService {
+    pub async fn new(db: Arc<Database>, notification_service: NotificationService, config: &Config) -> Result<Self> {
+        Ok(Self {
+            db,
+            notification_service,
+            config: config.clone(),
+        })
+    }
+
+    /// 执行所有已启用的生命周期任务
+    pub async fn run(&self) -> Result<LifecycleRunReport> {
+        let mut report = LifecycleRunReport::default();
+
+        if self.config.draft_archive_after_months > 0 {
+            let (notified, archived) = self.process_stale_drafts().await?;
+            report.drafts_notified = notified;
+            report.drafts_archived = archived;
+        }
+
+        if self.config.inactive_account_after_years > 0 {
+            let (notified, deactivated) = self.process_inactive_accounts().await?;
+            report.accounts_notified = notified;
+            report.accounts_deactivated = deactivated;
+        }
+
+        if report.drafts_notified + report.drafts_archived + report.accounts_notified + report.accounts_deactivated > 0 {
+            info!("Lifecycle run: {:?}", report);
+        }
+        Ok(report)
+    }
+
+    async fn process_stale_drafts(&self) -> Result<(usize, usize)> {
+        let cutoff = Utc::now() - Duration::days(30 * self.config.draft_archive_after_months as i64);
+
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT type::string(id) AS id, author_id, title, updated_at FROM article
+                WHERE status = 'draft' AND is_deleted = false AND updated_at < <datetime> $cutoff
+                ORDER BY updated_at
+                LIMIT $limit
+            "#,
+            json!({ "cutoff": cutoff, "limit": BATCH_SIZE }),
+        ).await?;
+        let drafts: Vec<StaleDraft> = response.take(0)?;
+
+        let ids: Vec<String> = drafts.iter().map(|draft| draft.id.clone()).collect();
+        let notices = self.latest_notices(LifecycleActionKind::DraftArchiveNotice, &ids).await?;
+        let grace = Duration::days(self.config.draft_archive_notice_days);
+
+        let (mut notified, mut archived) = (0, 0);
+        for draft in drafts {
+            // 提醒之后修改过的草稿需要重新提醒
+            match notices.get(&draft.id).filter(|noticed_at| **noticed_at > draft.updated_at) {
+                None => {
+                    self.notify(
+                        &draft.author_id,
+                        "Your draft will be archived",
+                        format!(
+                            "\"{}\" hasn't been edited in over {} months and will be archived in {} days. Edit it to keep it in your drafts.",
+                            draft.title, self.config.draft_archive_after_months, self.config.draft_archive_notice_days
+                        ),
+                        json!({ "article_id": draft.id }),
+                    ).await;
+                    self.record(LifecycleActionKind::DraftArchiveNotice, "article", &draft.id, &draft.author_id, json!({})).await?;
+                    notified += 1;
+                }
+                Some(noticed_at) if *noticed_at + grace <= Utc::now() => {
+                    if self.archive_draft(&draft).await? {
+                        archived += 1;
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok((notified, archived))
+    }
+
+    async fn archive_draft(&self, draft: &StaleDraft) -> Result<bool> {
+        let article_id = ArticleId::new(&draft.id);
+        let bare_id = article_id.as_str();
+
+        // 保留 updated_at，归档不算作者编辑
+        let mut response = self.db.query_with_params(
+            "UPDATE type::thing('article', $id) SET status = 'archived' WHERE status = 'draft' RETURN AFTER",
+            json!({ "id": bare_id }),
+        ).await?;
+        let updated: Vec<Value> = response.take(0)?;
+        if updated.is_empty() {
+            return Ok(false);
+        }
+
+        self.record(
+            LifecycleActionKind::DraftArchived,
+            "article",
+            &draft.id,
+            &draft.author_id,
+            json!({ "previous_status": "draft", "title": draft.title }),
+        ).await?;
+        debug!("Archived stale draft {}", draft.id);
+        Ok(true)
+    }
+
+    async fn process_inactive_accounts(&self) -> Result<(usize, usize)> {
+        let cutoff = Utc::now() - Duration::days(365 * self.config.inactive_account_after_years as i64);
+
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT user_id, (last_active_at ?? updated_at) AS last_active_at FROM user_profile
+                WHERE is_suspended = false AND (last_active_at ?? updated_at) < <datetime> $cutoff
+                LIMIT $limit
+            "#,
+            json!({ "cutoff": cutoff, "limit": BATCH_SIZE }),
+        ).await?;
+        let accounts: Vec<InactiveAccount> = response.take(0)?;
+
+        let ids: Vec<String> = accounts.iter().map(|account| account.user_id.clone()).collect();
+        let notices = self.latest_notices(LifecycleActionKind::AccountInactiveNotice, &ids).await?;
+        let grace = Duration::days(self.config.inactive_account_notice_days);
+
+        let (mut notified, mut deactivated) = (0, 0);
+        for account in accounts {
+            // 资料长期未更新但仍在写作或评论的用户不算未活跃
+            if self.has_recent_content(&account.user_id, cutoff).await? {
+                continue;
+            }
+
+            match notices.get(&account.user_id).filter(|noticed_at| **noticed_at > account.last_active_at) {
+                None => {
+                    self.notify(
+                        &account.user_id,
+                        "Your account will be deactivated",
+                        format!(
+                            "You haven't been active in over {} years. Your account will be deactivated in {} days unless you sign in. You can download a copy of your data at /api/blog/lifecycle/export.",
+                            self.config.inactive_account_after_years, self.config.inactive_account_notice_days
+                        ),
+                        json!({ "export_url": "/api/blog/lifecycle/export" }),
+                    ).await;
+                    self.record(LifecycleActionKind::AccountInactiveNotice, "user", &account.user_id, &account.user_id, json!({})).await?;
+                    notified += 1;
+                }
+                Some(noticed_at) if *noticed_at + grace <= Utc::now() => {
+                    if self.deactivate_account(&account.user_id).await? {
+                        deactivated += 1;
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+
+        Ok((notified, deactivated))
+    }
+
+    async fn has_recent_content(&self, user_id: &str, since: DateTime<Utc>) -> Result<bool> {
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT count() AS total FROM article WHERE author_id = $user_id AND updated_at >= <datetime> $since GROUP ALL;
+                SELECT count() AS total FROM comment WHERE author_id = $user_id AND created_at >= <datetime> $since GROUP ALL;
+            "#,
+            json!({ "user_id": user_id, "since": since }),
+        ).await?;
+        let articles: Vec<Value> = response.take(0)?;
+        let comments: Vec<Value> = response.take(1)?;
+
+        let total = |rows: &[Value]| rows.first().and_then(|r| r.get("total")).and_then(|v| v.as_i64()).unwrap_or(0);
+        Ok(total(&articles) + total(&comments) > 0)
+    }
+
+    async fn deactivate_account(&self, user_id: &str) -> Result<bool> {
+        let mut response = self.db.query_with_params(
+            r#"
+                UPDATE user_profile SET is_suspended = true, deactivated_at = time::now()
+                WHERE user_id = $user_id AND is_suspended = false
+                RETURN AFTER
+            "#,
+            json!({ "user_id": user_id }),
+        ).await?;
+        let updated: Vec<Value> = response.take(0)?;
+        if updated.is_empty() {
+            return Ok(false);
+        }
+
+        self.record(LifecycleActionKind::AccountDeactivated, "user", user_id, user_id, json!({})).await?;
+        debug!("Deactivated inactive account {}", user_id);
+        Ok(true)
+    }
+
+    /// 记录用户活跃；被生命周期任务停用的账户在再次登录时自动恢复
+    pub async fn record_activity(&self, user_id: &str) -> Result<()> {
+        let mut response = self.db.query_with_params(
+            r#"
+                UPDATE user_profile SET last_active_at = time::now() WHERE user_id = $user_id;
+                SELECT * FROM lifecycle_action
+                WHERE kind = 'account_deactivated' AND user_id = $user_id AND reverted_at = NONE;
+            "#,
+            json!({ "user_id": user_id }),
+        ).await?;
+        let deactivations: Vec<LifecycleAction> = response.take(1)?;
+
+        for action in deactivations {
+            self.revert_action(&action, user_id).await?;
+            info!("Reactivated account {} on sign-in", user_id);
+        }
+
+        Ok(())
+    }
+
+    /// 查询审计记录
+    pub async fn get_actions(&self, query: &LifecycleActionQuery) -> Result<Vec<LifecycleAction>> {
+        let limit = query.limit.unwrap_or(20).clamp(1, 100);
+        let offset = (query.page.unwrap_or(1).max(1) - 1) * limit;
+
+        let mut conditions = Vec::new();
+        if query.kind.is_some() {
+            conditions.push("kind = $kind");
+        }
+        if query.user_id.is_some() {
+            conditions.push("user_id = $user_id");
+        }
+        if !query.include_reverted {
+            conditions.push("reverted_at = NONE");
+        }
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        let sql = format!(
+            "SELECT * FROM lifecycle_action {} ORDER BY created_at DESC LIMIT $limit START $offset",
+            where_clause
+        );
+        let mut response = self.db.query_with_params(
+            &sql,
+            json!({
+                "kind": query.kind.map(|kind| kind.as_str()),
+                "user_id": query.user_id,
+                "limit": limit,
+                "offset": offset,
+            }),
+        ).await?;
+
+        Ok(response.take(0)?)
+    }
+
+    pub async fn get_action(&self, action_id: &str) -> Result<LifecycleAction> {
+        let bare_id = action_id.strip_prefix("lifecycle_action:").unwrap_or(action_id);
+        self.db
+            .get_by_id("lifecycle_action", bare_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Lifecycle action"))
+    }
+
+    /// 撤销归档或停用，恢复原状态并在审计记录上标记
+    pub async fn revert_action(&self, action: &LifecycleAction, reverted_by: &str) -> Result<LifecycleAction> {
+        if !action.kind.is_reversible() {
+            return Err(AppError::bad_request("Notices cannot be reverted"));
+        }
+        if action.reverted_at.is_some() {
+            return Err(AppError::bad_request("Action has already been reverted"));
+        }
+
+        match action.kind {
+            LifecycleActionKind::DraftArchived => {
+                let article_id = ArticleId::new(&action.resource_id);
+                let bare_id = article_id.as_str();
+                let previous_status = action.details.get("previous_status").and_then(|v| v.as_str()).unwrap_or("draft");
+                self.db.query_with_params(
+                    "UPDATE type::thing('article', $id) SET status = $status WHERE status = 'archived'",
+                    json!({ "id": bare_id, "status": previous_status }),
+                ).await?;
+            }
+            LifecycleActionKind::AccountDeactivated => {
+                // 只恢复被生命周期任务停用的账户，不影响管理员的封禁
+                self.db.query_with_params(
+                    "UPDATE user_profile SET is_suspended = false, deactivated_at = NONE WHERE user_id = $user_id AND deactivated_at != NONE",
+                    json!({ "user_id": action.resource_id }),
+                ).await?;
+            }
+            LifecycleActionKind::DraftArchiveNotice | LifecycleActionKind::AccountInactiveNotice => unreachable!(),
+        }
+
+        let bare_id = action.id.strip_prefix("lifecycle_action:").unwrap_or(&action.id);
+        let mut response = self.db.query_with_params(
+            "UPDATE type::thing('lifecycle_action', $id) SET reverted_at = time::now(), reverted_by = $reverted_by RETURN AFTER",
+            json!({ "id": bare_id, "reverted_by": reverted_by }),
+        ).await?;
+        let updated: Vec<LifecycleAction> = response.take(0)?;
+
+        info!("Lifecycle action {} reverted by {}", action.id, reverted_by);
+        updated.into_iter().next().ok_or_else(|| AppError::internal("Failed to revert lifecycle action"))
+    }
+
+    /// 导出用户的资料、文章（含草稿）、评论和书签
+    pub async fn export_account(&self, user_id: &str) -> Result<AccountExport> {
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT * OMIT id FROM user_profile WHERE user_id = $user_id LIMIT 1;
+                SELECT *, type::string(id) AS id FROM article WHERE author_id = $user_id AND is_deleted = false ORDER BY created_at;
+                SELECT *, type::string(id) AS id FROM comment WHERE author_id = $user_id AND is_deleted = false ORDER BY created_at;
+                SELECT *, type::string(id) AS id FROM bookmark WHERE user_id = $user_id ORDER BY created_at;
+            "#,
+            json!({ "user_id": user_id }),
+        ).await?;
+        let profiles: Vec<Value> = response.take(0)?;
+
+        Ok(AccountExport {
+            exported_at: Utc::now(),
+            profile: profiles.into_iter().next().ok_or_else(|| AppError::not_found("User profile"))?,
+            articles: response.take(1)?,
+            comments: response.take(2)?,
+            bookmarks: response.take(3)?,
+        })
+    }
+
+    /// 每个资源最近一次未撤销的提醒时间
+    async fn latest_notices(&self, kind: LifecycleActionKind, resource_ids: &[String]) -> Result<HashMap<String, DateTime<Utc>>> {
+        if resource_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT resource_id, math::max(created_at) AS noticed_at FROM lifecycle_action
+                WHERE kind = $kind AND resource_id INSIDE $ids AND reverted_at = NONE
+                GROUP BY resource_id
+            "#,
+            json!({ "kind": kind.as_str(), "ids": resource_ids }),
+        ).await?;
+        let rows: Vec<Value> = response.take(0)?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let resource_id = row.get("resource_id")?.as_str()?.to_string();
+                let noticed_at = serde_json::from_value::<DateTime<Utc>>(row.get("noticed_at")?.clone()).ok()?;
+                Some((resource_id, noticed_at))
+            })
+            .collect())
+    }
+
+    async fn record(&self, kind: LifecycleActionKind, resource_type: &str, resource_id: &str, user_id: &str, details: Value) -> Result<()> {
+        self.db.query_with_params(
+            r#"
+                CREATE lifecycle_action CONTENT {
+                    kind: $kind,
+                    resource_type: $resource_type,
+                    resource_id: $resource_id,
+                    user_id: $user_id,
+                    details: $details,
+                    created_at: time::now()
+                }
+            "#,
+            json!({
+                "kind": kind.as_str(),
+                "resource_type": resource_type,
+                "resource_id": resource_id,
+                "user_id": user_id,
+                "details": details,
+            }),
+        ).await?;
+        Ok(())
+    }
+
+    /// 提醒失败不影响任务执行
+    async fn notify(&self, user_id: &str, title: &str, message: String, data: Value) {
+        let notification = CreateNotificationRequest {
+            recipient_id: user_id.to_string(),
+            notification_type: NotificationType::AccountLifecycle,
+            title: title.to_string(),
+            message,
+            data,
+        };
+
+        if let Err(e) = self.notification_service.create_notification(notification).await {
+            warn!("Failed to send lifecycle notice to {}: {}", user_id, e);
+        }
+    }
+}
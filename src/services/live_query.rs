@@ -0,0 +1,261 @@
+//! SurrealDB LIVE SELECT 订阅
+//!
+//! 每个应用实例各自订阅 comment / reaction / notification 表的变更，并推送给连接到本实例的
+//! WebSocket 客户端。这样无论写入发生在哪个实例上，所有实例的客户端都能收到事件。
+//! 浏览和点赞事件同时用于计算实时热度榜。
+//! 订阅使用独立的 WebSocket 数据库连接，断开后会按指数退避重新连接并重新订阅。
+
+use crate::{
+    config::Config,
+    error::Result,
+    models::{id::ArticleId, reaction::ReactionType, websocket::WebSocketMessageType},
+    services::{popularity::PopularityService, realtime::RealtimeService},
+};
+use futures::stream::{select_all, BoxStream, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+use surrealdb::{
+    engine::remote::ws::{Client, Ws, Wss},
+    opt::auth::Root,
+    Action, Surreal,
+};
+use tokio::time::{interval, sleep};
+use tracing::{debug, error, info, warn};
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// 定期检查连接，连接失效时重新订阅
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Deserialize)]
+struct LiveComment {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    id: String,
+    article_id: String,
+    author_id: String,
+    #[serde(default)]
+    parent_id: Option<String>,
+    content: String,
+    #[serde(default)]
+    is_deleted: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct LiveReaction {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    article_id: String,
+    user_id: String,
+    reaction_type: ReactionType,
+    count: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct LiveViewEvent {
+    article_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LiveNotification {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    id: String,
+    recipient_id: String,
+    notification_type: String,
+    title: String,
+    message: String,
+    #[serde(default)]
+    data: Value,
+}
+
+enum LiveEvent {
+    Comment(Action, LiveComment),
+    Reaction(Action, LiveReaction),
+    View(Action, LiveViewEvent),
+    Notification(Action, LiveNotification),
+}
+
+type LiveEvents = BoxStream<'static, surrealdb::Result<LiveEvent>>;
+
+#[derive(Clone)]
+pub struct LiveQueryService {
+    config: Config,
+    realtime_service: RealtimeService,
+    popularity_service: PopularityService,
+}
+
+impl LiveQueryService {
+    pub fn new(config: &Config, realtime_service: RealtimeService, popularity_service: PopularityService) -> Self {
+        Self {
+            config: config.clone(),
+            realtime_service,
+            popularity_service,
+        }
+    }
+
+    /// 持续运行订阅，连接断开或失效时自动重新订阅
+    pub async fn run(self) {
+        let mut backoff = INITIAL_BACKOFF;
+
+        loop {
+            match self.subscribe().await {
+                Ok((db, events)) => {
+                    info!("Live queries subscribed on {}", self.config.database_url);
+                    backoff = INITIAL_BACKOFF;
+                    self.consume(db, events).await;
+                    warn!("Live query connection lost, re-subscribing");
+                }
+                Err(e) => error!("Failed to subscribe live queries: {}", e),
+            }
+
+            sleep(backoff).await;
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+
+    async fn connect(&self) -> Result<Surreal<Client>> {
+        let url = self.config.database_url.trim_end_matches('/');
+        let db = match url.strip_prefix("https://").or_else(|| url.strip_prefix("wss://")) {
+            Some(address) => Surreal::new::<Wss>(address).await?,
+            None => {
+                let address = url
+                    .strip_prefix("http://")
+                    .or_else(|| url.strip_prefix("ws://"))
+                    .unwrap_or(url);
+                Surreal::new::<Ws>(address).await?
+            }
+        };
+
+        db.signin(Root {
+            username: &self.config.database_username,
+            password: &self.config.database_password,
+        })
+        .await?;
+        db.use_ns(&self.config.database_namespace)
+            .use_db(&self.config.database_name)
+            .await?;
+
+        Ok(db)
+    }
+
+    async fn subscribe(&self) -> Result<(Surreal<Client>, LiveEvents)> {
+        let db = self.connect().await?;
+
+        let comments = db
+            .select::<Vec<LiveComment>>("comment")
+            .live()
+            .await?
+            .map(|result| result.map(|n| LiveEvent::Comment(n.action, n.data)))
+            .boxed();
+        let reactions = db
+            .select::<Vec<LiveReaction>>("reaction")
+            .live()
+            .await?
+            .map(|result| result.map(|n| LiveEvent::Reaction(n.action, n.data)))
+            .boxed();
+        let views = db
+            .select::<Vec<LiveViewEvent>>("article_view_event")
+            .live()
+            .await?
+            .map(|result| result.map(|n| LiveEvent::View(n.action, n.data)))
+            .boxed();
+        let notifications = db
+            .select::<Vec<LiveNotification>>("notification")
+            .live()
+            .await?
+            .map(|result| result.map(|n| LiveEvent::Notification(n.action, n.data)))
+            .boxed();
+
+        Ok((db, select_all(vec![comments, reactions, views, notifications]).boxed()))
+    }
+
+    /// 消费事件直到任一订阅结束或连接健康检查失败
+    async fn consume(&self, db: Surreal<Client>, mut events: LiveEvents) {
+        let mut health_check = interval(HEALTH_CHECK_INTERVAL);
+        health_check.tick().await;
+
+        loop {
+            tokio::select! {
+                event = events.next() => match event {
+                    Some(Ok(event)) => {
+                        if let Err(e) = self.dispatch(&db, event).await {
+                            error!("Failed to push live query event: {}", e);
+                        }
+                    }
+                    // 单条记录无法解析时跳过，不影响订阅
+                    Some(Err(e)) => warn!("Skipping live query notification: {}", e),
+                    None => return,
+                },
+                _ = health_check.tick() => {
+                    if let Err(e) = db.health().await {
+                        error!("Live query connection health check failed: {}", e);
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn dispatch(&self, db: &Surreal<Client>, event: LiveEvent) -> Result<()> {
+        match event {
+            LiveEvent::Comment(action, comment) => {
+                debug!("Live comment {:?}: {}", action, comment.id);
+                let message_type = match action {
+                    Action::Create => WebSocketMessageType::NewComment,
+                    _ => WebSocketMessageType::CommentUpdate,
+                };
+                self.realtime_service
+                    .broadcast_comment_change(
+                        message_type,
+                        &comment.article_id,
+                        json!({
+                            "comment_id": comment.id,
+                            "article_id": comment.article_id,
+                            "user_id": comment.author_id,
+                            "parent_id": comment.parent_id,
+                            "content": comment.content,
+                            "is_deleted": comment.is_deleted || matches!(action, Action::Delete),
+                        }),
+                    )
+                    .await
+            }
+            // 只有点赞计入热度和实时推送
+            LiveEvent::Reaction(Action::Delete, _) => Ok(()),
+            LiveEvent::Reaction(_, clap) if clap.reaction_type != ReactionType::Clap => Ok(()),
+            LiveEvent::Reaction(_, clap) => {
+                self.popularity_service.record_clap(&clap.article_id);
+
+                let article = ArticleId::new(&clap.article_id);
+                let article_id = article.as_str();
+                let mut response = db
+                    .query("SELECT math::sum(count) AS total FROM reaction WHERE article_id = type::thing('article', $article_id) AND reaction_type = 'clap' GROUP ALL")
+                    .bind(("article_id", article_id.to_string()))
+                    .await?;
+                let total: Option<i64> = response.take((0, "total"))?;
+
+                self.realtime_service
+                    .notify_article_clapped(article_id, &clap.user_id, clap.count, total.unwrap_or(0) as i32)
+                    .await
+            }
+            LiveEvent::View(Action::Create, view) => {
+                self.popularity_service.record_view(&view.article_id);
+                Ok(())
+            }
+            LiveEvent::View(..) => Ok(()),
+            LiveEvent::Notification(Action::Create, notification) => {
+                self.realtime_service
+                    .push_notification(
+                        &notification.recipient_id,
+                        json!({
+                            "id": notification.id,
+                            "type": notification.notification_type,
+                            "title": notification.title,
+                            "content": notification.message,
+                            "data": notification.data,
+                        }),
+                    )
+                    .await
+            }
+            LiveEvent::Notification(..) => Ok(()),
+        }
+    }
+}
@@ -1,8 +1,8 @@
 use crate::{
     error::{Result, AppError},
     config::Config,
-    models::media::{MediaFile, MediaUploadResponse},
-    utils::image::ImageProcessor,
+    models::media::{MediaFile, MediaUploadResponse, MediaVariant},
+    utils::image::{ImageProcessor, ResponsiveImageConfig},
     services::database::Database,
 };
 use std::path::Path;
@@ -43,14 +43,22 @@ impl MediaService {
             return Err(AppError::BadRequest("无效的图片格式".to_string()));
         }
 
-        // 获取图片尺寸
-        let dimensions = image_processor.get_dimensions(&data).map_err(|e| AppError::BadRequest(e))?;
-        let (width, height) = (dimensions.width, dimensions.height);
+        // 缩放、转码、生成缩略图和占位，编码比较耗时，放到阻塞线程中执行
+        let responsive_config = ResponsiveImageConfig {
+            max_width: self.config.image_max_width,
+            max_height: self.config.image_max_height,
+            quality: self.config.image_quality,
+            widths: self.config.image_variant_widths.clone(),
+            avif: self.config.image_avif_enabled,
+        };
+        let processed = tokio::task::spawn_blocking(move || ImageProcessor::process_responsive(&data, &responsive_config))
+            .await
+            .map_err(|e| AppError::Internal(format!("图片处理任务失败: {}", e)))?
+            .map_err(AppError::BadRequest)?;
 
         // 生成文件名和存储路径
-        let file_extension = self.get_file_extension(content_type);
         let file_id = Uuid::new_v4().to_string();
-        let stored_filename = format!("{}.{}", file_id, file_extension);
+        let stored_filename = format!("{}.{}", file_id, processed.original.format.to_extension());
         
         // 创建存储目录结构 (按日期分组)
         let now = Utc::now();
@@ -65,13 +73,21 @@ impl MediaService {
         }
 
         // 保存文件到磁盘
-        if let Err(e) = fs::write(&storage_path, &data).await {
-            tracing::error!("Failed to write file: {}", e);
-            return Err(AppError::Internal("保存文件失败".to_string()));
+        let public_url = self.write_public_file(&storage_path, &processed.original.data).await?;
+
+        let mut variants = Vec::with_capacity(processed.variants.len());
+        for variant in &processed.variants {
+            let path = format!("{}/{}-{}.{}", storage_dir, file_id, variant.width, variant.format.to_extension());
+            variants.push(MediaVariant {
+                url: self.write_public_file(&path, &variant.data).await?,
+                width: variant.width,
+                height: Some(variant.height),
+                content_type: variant.format.to_mime_type().to_string(),
+            });
         }
 
-        // 生成公开访问URL
-        let public_url = format!("/api/blog/media/files/{}", storage_path.replace("uploads/", ""));
+        let thumbnail_path = format!("{}/{}-thumb.{}", storage_dir, file_id, processed.thumbnail.format.to_extension());
+        let thumbnail_url = self.write_public_file(&thumbnail_path, &processed.thumbnail.data).await?;
 
         // 创建数据库记录
         let media_file = MediaFile {
@@ -82,12 +98,15 @@ impl MediaService {
             user_id: user_id.to_string(),
             filename: stored_filename.clone(),
             original_filename: filename.to_string(),
-            content_type: content_type.to_string(),
-            size: data.len() as i64,
-            width: Some(width),
-            height: Some(height),
+            content_type: processed.original.format.to_mime_type().to_string(),
+            size: processed.original.data.len() as i64,
+            width: Some(processed.original.width),
+            height: Some(processed.original.height),
             storage_path: storage_path.clone(),
-            public_url: public_url.clone(),
+            public_url,
+            variants,
+            thumbnail_url: Some(thumbnail_url),
+            blurhash: Some(processed.blurhash),
             created_at: now,
         };
 
@@ -100,11 +119,49 @@ impl MediaService {
                 AppError::Internal("保存文件信息到数据库失败".to_string())
             })?;
 
-        tracing::info!("Successfully uploaded image: {} for user: {}", stored_filename, user_id);
+        tracing::info!(
+            "Successfully uploaded image: {} ({} variants) for user: {}",
+            stored_filename,
+            media_file.variants.len(),
+            user_id
+        );
 
         Ok(media_file.to_response())
     }
 
+    /// 写入 uploads 下的文件，返回公开访问URL
+    async fn write_public_file(&self, storage_path: &str, data: &[u8]) -> Result<String> {
+        if let Err(e) = fs::write(storage_path, data).await {
+            tracing::error!("Failed to write file {}: {}", storage_path, e);
+            return Err(AppError::Internal("保存文件失败".to_string()));
+        }
+        Ok(format!("/api/blog/media/files/{}", storage_path.trim_start_matches("uploads/")))
+    }
+
+    /// 保存生成的音频（如文章朗读），返回公开访问URL
+    pub async fn store_audio(&self, extension: &str, data: &[u8]) -> Result<String> {
+        let now = Utc::now();
+        let storage_dir = format!("uploads/audio/{}/{:02}/{:02}", now.year(), now.month(), now.day());
+        if let Err(e) = fs::create_dir_all(&storage_dir).await {
+            tracing::error!("Failed to create audio directory: {}", e);
+            return Err(AppError::Internal("创建音频目录失败".to_string()));
+        }
+
+        let storage_path = format!("{}/{}.{}", storage_dir, Uuid::new_v4(), extension);
+        self.write_public_file(&storage_path, data).await
+    }
+
+    /// 按公开访问URL删除生成的文件，文件不存在时只记录日志
+    pub async fn delete_public_file(&self, public_url: &str) {
+        let Some(path) = public_url.strip_prefix("/api/blog/media/files/") else {
+            return;
+        };
+        let path = format!("uploads/{}", path);
+        if let Err(e) = fs::remove_file(&path).await {
+            tracing::warn!("Failed to delete physical file {}: {}", path, e);
+        }
+    }
+
     pub async fn get_file(&self, file_path: &str) -> Result<Vec<u8>> {
         let full_path = format!("uploads/{}", file_path);
         
@@ -141,9 +198,16 @@ impl MediaService {
             return Err(AppError::Authorization("无权限删除此文件".to_string()));
         }
 
-        // 删除物理文件
-        if let Err(e) = fs::remove_file(&media_file.storage_path).await {
-            tracing::warn!("Failed to delete physical file: {}", e);
+        // 删除物理文件，包括各尺寸和缩略图
+        let generated_urls = media_file.variants.iter().map(|v| &v.url).chain(media_file.thumbnail_url.as_ref());
+        let storage_paths = std::iter::once(media_file.storage_path.clone())
+            .chain(generated_urls.filter_map(|url| {
+                url.strip_prefix("/api/blog/media/files/").map(|path| format!("uploads/{}", path))
+            }));
+        for path in storage_paths {
+            if let Err(e) = fs::remove_file(&path).await {
+                tracing::warn!("Failed to delete physical file {}: {}", path, e);
+            }
         }
 
         // 删除数据库记录
@@ -160,6 +224,56 @@ impl MediaService {
         Ok(())
     }
 
+    /// 保存文章附件，返回存储路径。
+    /// 附件存放在 uploads 之外，不能通过公开的文件地址访问，只能经由附件下载链接获取
+    pub async fn store_attachment(&self, content_type: &str, data: &[u8]) -> Result<String> {
+        let allowed = self.config.allowed_attachment_types
+            .split(',')
+            .any(|t| t.trim().eq_ignore_ascii_case(content_type));
+        if !allowed {
+            return Err(AppError::BadRequest(format!("不支持的附件类型: {}", content_type)));
+        }
+        if data.len() as u64 > self.config.max_attachment_size {
+            return Err(AppError::BadRequest("附件大小超出限制".to_string()));
+        }
+        if data.is_empty() {
+            return Err(AppError::BadRequest("附件内容为空".to_string()));
+        }
+
+        let now = Utc::now();
+        let storage_dir = format!("attachments/{}/{:02}/{:02}", now.year(), now.month(), now.day());
+        let storage_path = format!("{}/{}", storage_dir, Uuid::new_v4());
+
+        if let Err(e) = fs::create_dir_all(&storage_dir).await {
+            tracing::error!("Failed to create attachment directory: {}", e);
+            return Err(AppError::Internal("创建附件目录失败".to_string()));
+        }
+        if let Err(e) = fs::write(&storage_path, data).await {
+            tracing::error!("Failed to write attachment: {}", e);
+            return Err(AppError::Internal("保存附件失败".to_string()));
+        }
+
+        Ok(storage_path)
+    }
+
+    pub async fn read_attachment(&self, storage_path: &str) -> Result<Vec<u8>> {
+        let path = Path::new(storage_path);
+        let inside_attachments = path.starts_with("attachments")
+            && path.components().all(|c| matches!(c, std::path::Component::Normal(_)));
+        if !inside_attachments {
+            return Err(AppError::BadRequest("非法的附件路径".to_string()));
+        }
+
+        fs::read(path).await
+            .map_err(|_| AppError::NotFound("附件不存在".to_string()))
+    }
+
+    pub async fn delete_attachment(&self, storage_path: &str) {
+        if let Err(e) = fs::remove_file(storage_path).await {
+            tracing::warn!("Failed to delete attachment file {}: {}", storage_path, e);
+        }
+    }
+
     pub async fn get_user_files(&self, user_id: &str, page: usize, limit: usize) -> Result<(Vec<MediaFile>, usize)> {
         let offset = (page - 1) * limit;
 
@@ -225,14 +339,4 @@ impl MediaService {
 
         Ok(())
     }
-
-    fn get_file_extension(&self, content_type: &str) -> &str {
-        match content_type {
-            "image/jpeg" => "jpg",
-            "image/png" => "png",
-            "image/gif" => "gif",
-            "image/webp" => "webp",
-            _ => "bin",
-        }
-    }
 }
\ No newline at end of file
@@ -0,0 +1,195 @@
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+    models::{id::bare_id, meter::*},
+    services::{Database, ViewTrackingService},
+};
+use axum::http::{header, HeaderMap};
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::{debug, info};
+use validator::Validate;
+
+/// 匿名读者的计量 cookie
+pub const METER_COOKIE: &str = "rb_meter";
+
+/// 不属于出版物的文章共用的计数范围
+const PLATFORM_SCOPE: &str = "platform";
+
+#[derive(Debug, Deserialize)]
+struct MeterOutcome {
+    used: u32,
+    granted: bool,
+}
+
+/// 计量付费墙：没有订阅的读者每月可以免费阅读若干篇付费文章，按出版物分别计数
+#[derive(Clone)]
+pub struct MeterService {
+    db: Arc<Database>,
+    view_tracking: ViewTrackingService,
+    default_quota: u32,
+}
+
+impl MeterService {
+    pub async fn new(db: Arc<Database>, config: &Config, view_tracking: ViewTrackingService) -> Result<Self> {
+        Ok(Self {
+            db,
+            view_tracking,
+            default_quota: config.metered_free_articles,
+        })
+    }
+
+    /// 识别计量读者。匿名读者没有 cookie 时用 IP 和 User-Agent 的哈希，并把它写入 cookie，
+    /// 之后即使 IP 变化也按同一个读者计数
+    pub fn reader(&self, headers: &HeaderMap, user_id: Option<&str>) -> MeterReader {
+        if let Some(user_id) = user_id {
+            return MeterReader {
+                key: format!("user:{}", bare_id("user", user_id)),
+                new_cookie: None,
+            };
+        }
+
+        match meter_cookie(headers) {
+            Some(value) => MeterReader {
+                key: format!("anon:{}", value),
+                new_cookie: None,
+            },
+            None => {
+                let value = self.view_tracking.viewer_hash(headers, None);
+                MeterReader {
+                    key: format!("anon:{}", value),
+                    new_cookie: Some(value),
+                }
+            }
+        }
+    }
+
+    pub async fn get_settings(&self, publication_id: &str) -> Result<MeterSettings> {
+        let publication_id = bare_id("publication", publication_id);
+        let settings: Option<MeterSettings> = self.db
+            .prepare("SELECT * FROM type::thing('publication_meter', $publication_id)")
+            .bind("publication_id", publication_id)
+            .fetch_one()
+            .await?;
+
+        Ok(settings.unwrap_or_else(|| MeterSettings {
+            publication_id: publication_id.to_string(),
+            free_articles_per_month: self.default_quota,
+            updated_at: None,
+        }))
+    }
+
+    pub async fn update_settings(&self, publication_id: &str, request: UpdateMeterSettingsRequest) -> Result<MeterSettings> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let publication_id = bare_id("publication", publication_id);
+        let settings: Option<MeterSettings> = self.db
+            .prepare(
+                r#"
+                UPSERT type::thing('publication_meter', $publication_id) CONTENT {
+                    publication_id: $publication_id,
+                    free_articles_per_month: $free_articles_per_month,
+                    updated_at: time::now()
+                }
+                "#,
+            )
+            .bind("publication_id", publication_id)
+            .bind("free_articles_per_month", request.free_articles_per_month)
+            .fetch_one()
+            .await?;
+
+        info!("Updated metered paywall quota for publication {}", publication_id);
+        settings.ok_or_else(|| AppError::internal("Failed to update meter settings"))
+    }
+
+    /// 读者打开一篇没有访问权限的付费文章时调用：本月读过的文章直接放行，
+    /// 额度未用完时计入一篇并放行，否则返回 `granted: false`
+    pub async fn consume(&self, reader: &MeterReader, article_id: &str, publication_id: Option<&str>) -> Result<MeterStatus> {
+        let now = Utc::now();
+        let month = now.format("%Y-%m").to_string();
+        let resets_at = next_month_start(now);
+        let scope = publication_id.map_or(PLATFORM_SCOPE, |id| bare_id("publication", id));
+        let article_id = bare_id("article", article_id);
+
+        let quota = match publication_id {
+            Some(publication_id) => self.get_settings(publication_id).await?.free_articles_per_month,
+            None => self.default_quota,
+        };
+
+        // 检查额度、判断是否已读和写入记录放在同一个事务里；同一读者同一范围的
+        // 并发请求都会写 meter_usage 的同一条记录，提交时冲突，不会一起越过额度
+        let mut response = self.db
+            .prepare(
+                r#"
+                BEGIN TRANSACTION;
+                LET $read = type::thing('metered_read', [$reader, $month, $article_id]);
+                LET $already_read = array::len((SELECT id FROM $read)) > 0;
+                LET $used = (SELECT count() AS count FROM metered_read
+                    WHERE reader = $reader AND month = $month AND scope = $scope
+                    GROUP ALL)[0].count ?? 0;
+                LET $consumed = !$already_read AND $used < $quota;
+                IF $consumed THEN
+                    (CREATE $read CONTENT {
+                        reader: $reader,
+                        month: $month,
+                        scope: $scope,
+                        article_id: $article_id,
+                        created_at: time::now()
+                    })
+                END;
+                LET $used = IF $consumed THEN $used + 1 ELSE $used END;
+                UPSERT type::thing('meter_usage', [$reader, $month, $scope]) SET
+                    used = $used,
+                    updated_at = time::now();
+                RETURN { used: $used, granted: $already_read OR $consumed };
+                COMMIT TRANSACTION;
+                "#,
+            )
+            .bind("reader", &reader.key)
+            .bind("month", &month)
+            .bind("scope", scope)
+            .bind("article_id", article_id)
+            .bind("quota", quota)
+            .execute()
+            .await?;
+        let outcome: Option<MeterOutcome> = response.take(response.num_statements() - 1)?;
+        let outcome = outcome.ok_or_else(|| AppError::internal("Failed to record metered read"))?;
+
+        if !outcome.granted {
+            debug!("Metered quota of {} exhausted for {} in {}", quota, reader.key, scope);
+        }
+
+        Ok(MeterStatus {
+            quota,
+            used: outcome.used,
+            remaining: quota.saturating_sub(outcome.used),
+            granted: outcome.granted,
+            resets_at,
+        })
+    }
+}
+
+/// 写入计量 cookie 的 `Set-Cookie` 头，保存一年
+pub fn meter_set_cookie(value: &str) -> String {
+    format!("{}={}; Path=/; Max-Age=31536000; HttpOnly; SameSite=Lax", METER_COOKIE, value)
+}
+
+/// 计量 cookie 的值，只接受 `viewer_hash` 格式（32 位十六进制）
+fn meter_cookie(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == METER_COOKIE)
+        .map(|(_, value)| value.trim())
+        .filter(|value| value.len() == 32 && value.chars().all(|c| c.is_ascii_hexdigit()))
+        .map(str::to_string)
+}
+
+fn next_month_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    let (year, month) = if now.month() == 12 { (now.year() + 1, 1) } else { (now.year(), now.month() + 1) };
+    Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single().unwrap_or(now)
+}
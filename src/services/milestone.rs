@@ -0,0 +1,254 @@
+use crate::{
+    error::{AppError, Result},
+    models::{id::{ArticleId, UserId}, milestone::*, notification::*},
+    services::{Database, NotificationService, PopularityService},
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// 浏览数里程碑
+const VIEW_MILESTONES: &[i64] = &[1_000, 10_000, 100_000, 1_000_000];
+/// 点赞数里程碑
+const CLAP_MILESTONES: &[i64] = &[100, 1_000, 10_000];
+/// 进入实时热度榜前几名算作上榜
+const TRENDING_RANK: usize = 10;
+const MAX_HISTORY: usize = 100;
+
+/// 达成里程碑的候选文章
+#[derive(Debug, Clone, Deserialize)]
+struct MilestoneCandidate {
+    id: String,
+    title: String,
+    slug: String,
+    author_id: String,
+    #[serde(default)]
+    view_count: i64,
+    #[serde(default)]
+    clap_count: i64,
+}
+
+/// 文章里程碑：浏览数、点赞数和热度榜上榜，达成时通知作者（可在通知配置中关闭）
+#[derive(Clone)]
+pub struct MilestoneService {
+    db: Arc<Database>,
+    notification_service: NotificationService,
+    popularity_service: PopularityService,
+}
+
+impl MilestoneService {
+    pub async fn new(
+        db: Arc<Database>,
+        notification_service: NotificationService,
+        popularity_service: PopularityService,
+    ) -> Result<Self> {
+        Ok(Self {
+            db,
+            notification_service,
+            popularity_service,
+        })
+    }
+
+    /// 检查所有文章的计数和当前热度榜，记录新达成的里程碑，返回数量
+    pub async fn check_milestones(&self) -> Result<usize> {
+        let mut candidates: Vec<MilestoneCandidate> = self.db
+            .prepare(
+                r#"
+                SELECT meta::id(id) AS id, title, slug, author_id, view_count, clap_count FROM article
+                WHERE status = 'published' AND is_deleted != true
+                AND (view_count >= $min_views OR clap_count >= $min_claps)
+                "#,
+            )
+            .bind("min_views", VIEW_MILESTONES[0])
+            .bind("min_claps", CLAP_MILESTONES[0])
+            .fetch()
+            .await?;
+
+        // 热度榜来自浏览和点赞事件的实时计分
+        let mut trending = HashSet::new();
+        if let Some(snapshot) = self.popularity_service.latest() {
+            for article in snapshot.articles.iter().take(TRENDING_RANK) {
+                let id = ArticleId::new(&article.article_id).as_str().to_string();
+                if !candidates.iter().any(|c| c.id == id) {
+                    candidates.push(MilestoneCandidate {
+                        id: id.clone(),
+                        title: article.title.clone(),
+                        slug: article.slug.clone(),
+                        author_id: article.author_id.clone(),
+                        view_count: 0,
+                        clap_count: 0,
+                    });
+                }
+                trending.insert(id);
+            }
+        }
+        if candidates.is_empty() {
+            return Ok(0);
+        }
+
+        let article_ids: Vec<&str> = candidates.iter().map(|c| c.id.as_str()).collect();
+        let achieved: Vec<String> = self.db
+            .prepare("SELECT VALUE meta::id(id) FROM article_milestone WHERE article_id INSIDE $article_ids")
+            .bind("article_ids", &article_ids)
+            .fetch()
+            .await?;
+        let achieved: HashSet<String> = achieved.into_iter().collect();
+
+        let mut created = 0;
+        for candidate in &candidates {
+            let mut reached = Vec::new();
+            reached.extend(crossed(VIEW_MILESTONES, candidate.view_count).map(|t| (MilestoneKind::Views, t)));
+            reached.extend(crossed(CLAP_MILESTONES, candidate.clap_count).map(|t| (MilestoneKind::Claps, t)));
+            if trending.contains(&candidate.id) {
+                reached.push((MilestoneKind::Trending, TRENDING_RANK as i64));
+            }
+
+            // 第一次检查时可能一次跨过多个阈值，每类只通知最高的一个
+            let mut highest: HashMap<MilestoneKind, ArticleMilestone> = HashMap::new();
+            for (kind, threshold) in reached {
+                let key = milestone_key(&candidate.id, kind, threshold);
+                if achieved.contains(&key) {
+                    continue;
+                }
+                match self.record(&key, candidate, kind, threshold).await {
+                    Ok(milestone) => {
+                        created += 1;
+                        highest.insert(kind, milestone);
+                    }
+                    Err(e) => warn!("Failed to record milestone {}: {}", key, e),
+                }
+            }
+
+            for milestone in highest.values() {
+                if let Err(e) = self.notify(milestone).await {
+                    warn!("Failed to send milestone notification {}: {}", milestone.id, e);
+                }
+            }
+        }
+
+        if created > 0 {
+            info!("Recorded {} article milestones", created);
+        }
+        Ok(created)
+    }
+
+    /// 用户的里程碑历史，最新的在前
+    pub async fn list_for_user(&self, user_id: &str, query: MilestoneQuery) -> Result<Vec<ArticleMilestone>> {
+        let user_id = UserId::new(user_id);
+        self.db
+            .prepare("SELECT * FROM article_milestone WHERE user_id INSIDE $user_ids ORDER BY achieved_at DESC LIMIT $limit")
+            .bind("user_ids", [user_id.as_str().to_string(), user_id.to_record()])
+            .bind("limit", query.limit.unwrap_or(20).clamp(1, MAX_HISTORY))
+            .fetch()
+            .await
+    }
+
+    async fn record(
+        &self,
+        key: &str,
+        article: &MilestoneCandidate,
+        kind: MilestoneKind,
+        threshold: i64,
+    ) -> Result<ArticleMilestone> {
+        let created: Option<ArticleMilestone> = self.db
+            .prepare(
+                r#"
+                CREATE type::thing('article_milestone', $key) CONTENT {
+                    user_id: $user_id,
+                    article_id: $article_id,
+                    article_title: $title,
+                    article_slug: $slug,
+                    kind: $kind,
+                    threshold: $threshold,
+                    achieved_at: time::now()
+                }
+                "#,
+            )
+            .bind("key", key)
+            .bind("user_id", UserId::new(&article.author_id).as_str())
+            .bind("article_id", &article.id)
+            .bind("title", &article.title)
+            .bind("slug", &article.slug)
+            .bind("kind", kind.as_str())
+            .bind("threshold", threshold)
+            .fetch_one()
+            .await?;
+
+        debug!("Article {} reached {} milestone {}", article.id, kind.as_str(), threshold);
+        created.ok_or_else(|| AppError::Internal("Failed to record milestone".to_string()))
+    }
+
+    async fn notify(&self, milestone: &ArticleMilestone) -> Result<()> {
+        let preferences = self.notification_service.get_notification_config(&milestone.user_id).await?;
+        if !preferences.milestone_notifications {
+            debug!("Milestone notifications disabled for {}", milestone.user_id);
+            return Ok(());
+        }
+
+        let (title, message) = match milestone.kind {
+            MilestoneKind::Views => (
+                format!("{} views!", format_count(milestone.threshold)),
+                format!("\"{}\" has been read {} times.", milestone.article_title, format_count(milestone.threshold)),
+            ),
+            MilestoneKind::Claps => (
+                format!("{} claps!", format_count(milestone.threshold)),
+                format!("Readers have clapped {} times for \"{}\".", format_count(milestone.threshold), milestone.article_title),
+            ),
+            MilestoneKind::Trending => (
+                "Your story is trending".to_string(),
+                format!("\"{}\" is in the top {} popular stories right now.", milestone.article_title, milestone.threshold),
+            ),
+        };
+
+        self.notification_service
+            .create_notification(CreateNotificationRequest {
+                recipient_id: milestone.user_id.clone(),
+                notification_type: NotificationType::Milestone,
+                title,
+                message,
+                data: json!({
+                    "milestone_id": milestone.id,
+                    "article_id": milestone.article_id,
+                    "article_slug": milestone.article_slug,
+                    "kind": milestone.kind,
+                    "threshold": milestone.threshold,
+                }),
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+/// 计数已经达到的阈值
+fn crossed(thresholds: &'static [i64], count: i64) -> impl Iterator<Item = i64> {
+    thresholds.iter().copied().filter(move |t| count >= *t)
+}
+
+/// 里程碑的记录 ID，同一篇文章的同一个里程碑只记录一次
+fn milestone_key(article_id: &str, kind: MilestoneKind, threshold: i64) -> String {
+    format!("{}_{}_{}", article_id, kind.as_str(), threshold)
+}
+
+fn format_count(count: i64) -> String {
+    match count {
+        c if c >= 1_000_000 && c % 1_000_000 == 0 => format!("{}M", c / 1_000_000),
+        c if c >= 1_000 && c % 1_000 == 0 => format!("{}k", c / 1_000),
+        c => c.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crossed_thresholds() {
+        assert_eq!(crossed(VIEW_MILESTONES, 999).count(), 0);
+        assert_eq!(crossed(VIEW_MILESTONES, 12_000).collect::<Vec<_>>(), vec![1_000, 10_000]);
+        assert_eq!(format_count(1_000), "1k");
+        assert_eq!(format_count(1_000_000), "1M");
+        assert_eq!(format_count(100), "100");
+    }
+}
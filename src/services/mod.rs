@@ -4,6 +4,7 @@ pub mod user;
 pub mod article;
 pub mod comment;
 pub mod notification;
+pub mod email;
 pub mod search;
 pub mod media;
 pub mod recommendation;
@@ -20,6 +21,66 @@ pub mod stripe;
 pub mod websocket;
 pub mod realtime;
 pub mod domain;
+#[cfg(feature = "acme")]
+pub mod acme;
+pub mod reading_queue;
+pub mod assist;
+pub mod cover_image;
+pub mod reputation;
+pub mod anomaly;
+pub mod registry;
+pub mod capabilities;
+pub mod plugin;
+#[cfg(feature = "wasm-plugins")]
+pub mod wasm_runtime;
+pub mod content_transform;
+pub mod lifecycle;
+pub mod live_query;
+pub mod import;
+pub mod popularity;
+pub mod newsletter;
+pub mod webmention;
+pub mod cors;
+pub mod bot_detection;
+pub mod milestone;
+pub mod akismet;
+pub mod syndication;
+pub mod reaction;
+pub mod collaborator;
+pub mod social_share;
+pub mod collaborative_edit;
+pub mod template;
+pub mod reading_progress;
+pub mod style_guide;
+pub mod homepage;
+pub mod theme;
+pub mod page;
+pub mod meter;
+pub mod attachment;
+pub mod view_tracking;
+pub mod sponsorship;
+pub mod cta;
+pub mod geoip;
+pub mod pseudonym;
+pub mod db_health;
+pub mod experiment;
+pub mod suggest;
+pub mod embedding;
+pub mod gift;
+pub mod webhook;
+pub mod activity_digest;
+pub mod web_push;
+pub mod rate_limit;
+pub mod response_cache;
+pub mod oauth;
+pub mod two_factor;
+pub mod admin;
+pub mod report;
+pub mod data_lifecycle;
+pub mod oembed;
+pub mod speech;
+pub mod trash;
+pub mod preview;
 
 // 重新导出常用类型
 pub use database::Database;
@@ -28,6 +89,7 @@ pub use user::UserService;
 pub use article::ArticleService;
 pub use comment::CommentService;
 pub use notification::NotificationService;
+pub use email::EmailService;
 pub use search::SearchService;
 pub use media::MediaService;
 pub use recommendation::RecommendationService;
@@ -43,4 +105,59 @@ pub use revenue::RevenueService;
 pub use stripe::StripeService;
 pub use websocket::WebSocketService;
 pub use realtime::RealtimeService;
-pub use domain::{DomainService, DomainConfig};
\ No newline at end of file
+pub use domain::{DomainService, DomainConfig};
+pub use reading_queue::ReadingQueueService;
+pub use assist::AssistService;
+pub use cover_image::CoverImageService;
+pub use reputation::ReputationService;
+pub use anomaly::AnomalyService;
+pub use registry::ServiceRegistry;
+pub use capabilities::{ArticleStore, Payments, SearchBackend};
+pub use plugin::{Plugin, PluginManager};
+pub use content_transform::ContentTransformService;
+pub use lifecycle::LifecycleService;
+pub use live_query::LiveQueryService;
+pub use import::ImportService;
+pub use popularity::PopularityService;
+pub use newsletter::NewsletterService;
+pub use webmention::WebmentionService;
+pub use cors::CorsService;
+pub use bot_detection::BotDetectionService;
+pub use milestone::MilestoneService;
+pub use syndication::OutboundSyndicationService;
+pub use reaction::ReactionService;
+pub use collaborator::CollaboratorService;
+pub use social_share::SocialShareService;
+pub use collaborative_edit::CollaborativeEditingService;
+pub use template::TemplateService;
+pub use reading_progress::ReadingProgressService;
+pub use style_guide::StyleGuideService;
+pub use homepage::HomepageService;
+pub use theme::ThemeService;
+pub use page::PageService;
+pub use meter::MeterService;
+pub use attachment::AttachmentService;
+pub use view_tracking::ViewTrackingService;
+pub use sponsorship::SponsorshipService;
+pub use cta::CtaService;
+pub use geoip::GeoIpService;
+pub use pseudonym::PseudonymService;
+pub use db_health::DbHealthService;
+pub use experiment::ExperimentService;
+pub use suggest::SuggestService;
+pub use embedding::EmbeddingService;
+pub use gift::GiftService;
+pub use webhook::WebhookService;
+pub use activity_digest::ActivityDigestService;
+pub use web_push::WebPushService;
+pub use rate_limit::RateLimitService;
+pub use response_cache::ResponseCacheService;
+pub use oauth::OAuthService;
+pub use two_factor::TwoFactorService;
+pub use admin::AdminService;
+pub use report::ReportService;
+pub use data_lifecycle::DataLifecycleService;
+pub use oembed::OEmbedService;
+pub use speech::SpeechService;
+pub use trash::TrashService;
+pub use preview::PreviewService;
@@ -0,0 +1,581 @@
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+    models::{
+        article::Article,
+        domain::DomainEmailConfig,
+        id::{bare_id, ArticleId, PublicationId},
+        newsletter::*,
+    },
+    services::{
+        database::PaginatedResult,
+        email::{DkimKey, EmailService, OutgoingEmail},
+        Database, DomainService,
+    },
+};
+use handlebars::Handlebars;
+use rand::{distributions::Alphanumeric, Rng};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+use validator::Validate;
+
+const NEWSLETTER_HTML_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html>
+<body style="font-family: Georgia, 'Times New Roman', serif; color: #222; max-width: 640px; margin: 0 auto; padding: 16px;">
+  <p style="font-family: -apple-system, Helvetica, Arial, sans-serif; font-size: 13px; color: #888;">{{publication_name}}</p>
+  <h1 style="margin-bottom: 4px;">{{title}}</h1>
+  {{#if subtitle}}<h3 style="margin-top: 0; color: #555; font-weight: normal;">{{subtitle}}</h3>{{/if}}
+  {{#if cover_image_url}}<img src="{{cover_image_url}}" alt="" style="max-width: 100%;"/>{{/if}}
+  <div>{{{body_html}}}</div>
+  {{#if truncated}}<p><a href="{{article_url}}">Continue reading on {{publication_name}}</a></p>{{/if}}
+  <hr style="border: none; border-top: 1px solid #eee;"/>
+  <p style="font-family: -apple-system, Helvetica, Arial, sans-serif; font-size: 12px; color: #888;">
+    <a href="{{article_url}}">View online</a> ·
+    <a href="{{unsubscribe_url}}">Unsubscribe from {{publication_name}}</a>
+  </p>
+</body>
+</html>"#;
+
+const NEWSLETTER_TEXT_TEMPLATE: &str = r#"{{publication_name}}
+
+{{title}}
+{{#if subtitle}}{{subtitle}}
+{{/if}}
+{{body_text}}
+
+{{#if truncated}}Continue reading: {{/if}}{{article_url}}
+
+Unsubscribe from {{publication_name}}: {{unsubscribe_url}}
+"#;
+
+/// 退订令牌长度
+const UNSUBSCRIBE_TOKEN_LEN: usize = 40;
+
+#[derive(Debug, Clone, Deserialize)]
+struct NewsletterPublication {
+    name: String,
+    slug: String,
+    owner_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Recipient {
+    user_id: String,
+    email: String,
+}
+
+/// 出版物 Newsletter：把文章作为邮件发给关注者和订阅者，记录每个收件人的投递状态
+#[derive(Clone)]
+pub struct NewsletterService {
+    db: Arc<Database>,
+    config: Config,
+    email_service: EmailService,
+    domain_service: DomainService,
+    templates: Arc<Handlebars<'static>>,
+}
+
+impl NewsletterService {
+    pub async fn new(db: Arc<Database>, config: &Config, domain_service: DomainService) -> Result<Self> {
+        let mut templates = Handlebars::new();
+        templates
+            .register_template_string("newsletter_html", NEWSLETTER_HTML_TEMPLATE)
+            .map_err(|e| AppError::Email(format!("Failed to register template: {}", e)))?;
+        templates
+            .register_template_string("newsletter_text", NEWSLETTER_TEXT_TEMPLATE)
+            .map_err(|e| AppError::Email(format!("Failed to register template: {}", e)))?;
+
+        Ok(Self {
+            db,
+            config: config.clone(),
+            email_service: EmailService::new(config)?,
+            domain_service,
+            templates: Arc::new(templates),
+        })
+    }
+
+    /// 创建一期 Newsletter 并在后台逐个投递
+    pub async fn send_newsletter(
+        &self,
+        publication_id: &str,
+        user_id: &str,
+        request: SendNewsletterRequest,
+    ) -> Result<Newsletter> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let publication_id = PublicationId::new(publication_id);
+        let publication = self.get_publication(&publication_id).await?;
+
+        let article: Article = self.db
+            .get_by_id("article", &request.article_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Article"))?;
+        if !article.is_published() {
+            return Err(AppError::bad_request("Only published articles can be sent as a newsletter"));
+        }
+        if article.publication_id.as_deref().map(PublicationId::new).as_ref() != Some(&publication_id) {
+            return Err(AppError::bad_request("Article does not belong to this publication"));
+        }
+        let article_id = ArticleId::new(&article.id);
+
+        let already_sent: Option<Value> = self.db
+            .prepare(
+                "SELECT id FROM newsletter WHERE publication_id = $publication_id AND article_id = $article_id AND status != 'failed' LIMIT 1",
+            )
+            .bind("publication_id", publication_id.as_str())
+            .bind("article_id", article_id.as_str())
+            .fetch_one()
+            .await?;
+        if already_sent.is_some() {
+            return Err(AppError::Conflict("This article has already been sent as a newsletter".to_string()));
+        }
+
+        let recipients = self
+            .get_recipients(&publication_id, &publication.owner_id, request.subscribers_only)
+            .await?;
+        if recipients.is_empty() {
+            return Err(AppError::bad_request("This publication has no newsletter recipients"));
+        }
+
+        let newsletter_id = Uuid::new_v4().to_string();
+        let subject = request.subject.unwrap_or_else(|| article.title.clone());
+        let newsletter: Option<Newsletter> = self.db
+            .prepare(
+                r#"
+                CREATE type::thing('newsletter', $id) CONTENT {
+                    publication_id: $publication_id,
+                    article_id: $article_id,
+                    subject: $subject,
+                    sent_by: $sent_by,
+                    status: 'sending',
+                    provider: $provider,
+                    recipient_count: $recipient_count,
+                    sent_count: 0,
+                    failed_count: 0,
+                    created_at: time::now(),
+                    completed_at: NONE
+                }
+                "#,
+            )
+            .bind("id", &newsletter_id)
+            .bind("publication_id", publication_id.as_str())
+            .bind("article_id", article_id.as_str())
+            .bind("subject", &subject)
+            .bind("sent_by", user_id)
+            .bind("provider", self.email_service.provider_name())
+            .bind("recipient_count", recipients.len())
+            .fetch_one()
+            .await?;
+        let newsletter = newsletter.ok_or_else(|| AppError::internal("Failed to create newsletter"))?;
+
+        let deliveries: Vec<Value> = recipients
+            .iter()
+            .map(|recipient| {
+                json!({
+                    "newsletter_id": newsletter_id,
+                    "publication_id": publication_id.as_str(),
+                    "user_id": recipient.user_id,
+                    "email": recipient.email,
+                    "status": DeliveryStatus::Pending.as_str(),
+                    "unsubscribe_token": generate_unsubscribe_token(),
+                })
+            })
+            .collect();
+        self.db
+            .prepare("INSERT INTO newsletter_delivery $deliveries RETURN NONE")
+            .bind("deliveries", deliveries)
+            .execute()
+            .await?;
+
+        info!(
+            "Newsletter {} for article {} queued to {} recipients by user {}",
+            newsletter_id,
+            article_id,
+            recipients.len(),
+            user_id
+        );
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.deliver(&newsletter_id).await {
+                error!("Failed to deliver newsletter {}: {}", newsletter_id, e);
+            }
+        });
+
+        Ok(newsletter)
+    }
+
+    /// 重启后继续投递仍处于发送中的 Newsletter
+    pub async fn resume_unfinished(&self) -> Result<usize> {
+        let ids: Vec<String> = self.db
+            .prepare("SELECT VALUE meta::id(id) FROM newsletter WHERE status = 'sending'")
+            .fetch()
+            .await?;
+
+        for id in &ids {
+            if let Err(e) = self.deliver(id).await {
+                error!("Failed to resume newsletter {}: {}", id, e);
+            }
+        }
+
+        Ok(ids.len())
+    }
+
+    /// 投递所有待发送的收件人，完成后汇总状态
+    async fn deliver(&self, newsletter_id: &str) -> Result<()> {
+        let newsletter = self.load_newsletter(newsletter_id).await?;
+        let publication = self.get_publication(&PublicationId::new(&newsletter.publication_id)).await?;
+        let article: Article = self.db
+            .get_by_id("article", &newsletter.article_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Article"))?;
+
+        let pending: Vec<NewsletterDelivery> = self.db
+            .prepare("SELECT * FROM newsletter_delivery WHERE newsletter_id = $newsletter_id AND status = 'pending'")
+            .bind("newsletter_id", newsletter_id)
+            .fetch()
+            .await?;
+        debug!("Delivering newsletter {} to {} pending recipients", newsletter_id, pending.len());
+
+        // 出版物配置并验证了自己的发信域名时从该域名发送，否则使用平台发件地址
+        let sender = self.domain_service
+            .get_publication_email_sender(&newsletter.publication_id)
+            .await?;
+
+        for delivery in pending {
+            let result = match self.render(&newsletter, &publication, sender.as_ref(), &article, &delivery) {
+                Ok(email) => self.email_service.deliver(&email).await,
+                Err(e) => Err(e),
+            };
+
+            let (status, message_id, error) = match result {
+                Ok(message_id) => (DeliveryStatus::Sent, message_id, None),
+                Err(e) => {
+                    warn!("Newsletter {} delivery to {} failed: {}", newsletter_id, delivery.email, e);
+                    (DeliveryStatus::Failed, None, Some(e.to_string()))
+                }
+            };
+
+            self.db
+                .prepare(
+                    r#"
+                    UPDATE type::thing('newsletter_delivery', $id) SET
+                        status = $status,
+                        provider_message_id = $message_id,
+                        error = $error,
+                        sent_at = IF $status = 'sent' THEN time::now() ELSE NONE END
+                    "#,
+                )
+                .bind("id", bare_id("newsletter_delivery", &delivery.id))
+                .bind("status", status.as_str())
+                .bind("message_id", message_id)
+                .bind("error", error)
+                .execute()
+                .await?;
+        }
+
+        self.finish(newsletter_id).await
+    }
+
+    async fn finish(&self, newsletter_id: &str) -> Result<()> {
+        let mut response = self.db
+            .prepare(
+                r#"
+                SELECT status, count() AS total FROM newsletter_delivery
+                WHERE newsletter_id = $newsletter_id
+                GROUP BY status
+                "#,
+            )
+            .bind("newsletter_id", newsletter_id)
+            .execute()
+            .await?;
+        let counts: Vec<Value> = response.take(0)?;
+        let count_of = |status: DeliveryStatus| {
+            counts
+                .iter()
+                .find(|row| row.get("status").and_then(|s| s.as_str()) == Some(status.as_str()))
+                .and_then(|row| row.get("total").and_then(|t| t.as_i64()))
+                .unwrap_or(0)
+        };
+        let sent = count_of(DeliveryStatus::Sent);
+        let failed = count_of(DeliveryStatus::Failed);
+
+        let status = match (sent, failed) {
+            (_, 0) => NewsletterStatus::Sent,
+            (0, _) => NewsletterStatus::Failed,
+            _ => NewsletterStatus::PartiallySent,
+        };
+
+        self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('newsletter', $id) SET
+                    status = $status,
+                    sent_count = $sent,
+                    failed_count = $failed,
+                    completed_at = time::now()
+                "#,
+            )
+            .bind("id", newsletter_id)
+            .bind("status", status.as_str())
+            .bind("sent", sent)
+            .bind("failed", failed)
+            .execute()
+            .await?;
+
+        info!("Newsletter {} finished: {} sent, {} failed", newsletter_id, sent, failed);
+        Ok(())
+    }
+
+    fn render(
+        &self,
+        newsletter: &Newsletter,
+        publication: &NewsletterPublication,
+        sender: Option<&DomainEmailConfig>,
+        article: &Article,
+        delivery: &NewsletterDelivery,
+    ) -> Result<OutgoingEmail> {
+        let article_url = format!("{}/articles/{}", self.config.frontend_url.trim_end_matches('/'), article.slug);
+        let unsubscribe_url = format!(
+            "{}/api/blog/newsletters/unsubscribe?token={}",
+            self.config.public_api_url.trim_end_matches('/'),
+            delivery.unsubscribe_token
+        );
+
+        // 付费文章只发送摘要，引导读者到站内阅读
+        let truncated = article.is_paid_content;
+        let excerpt = article.excerpt.clone().unwrap_or_default();
+        let data = json!({
+            "publication_name": publication.name,
+            "title": article.title,
+            "subtitle": article.subtitle,
+            "cover_image_url": article.cover_image_url,
+            "body_html": if truncated { format!("<p>{}</p>", html_escape(&excerpt)) } else { article.content_html.clone() },
+            "body_text": if truncated { excerpt } else { article.content.clone() },
+            "truncated": truncated,
+            "article_url": article_url,
+            "unsubscribe_url": unsubscribe_url,
+        });
+
+        let html = self.templates
+            .render("newsletter_html", &data)
+            .map_err(|e| AppError::Email(format!("Failed to render newsletter: {}", e)))?;
+        let text = self.templates
+            .render("newsletter_text", &data)
+            .map_err(|e| AppError::Email(format!("Failed to render newsletter: {}", e)))?;
+
+        Ok(OutgoingEmail {
+            from_name: publication.name.clone(),
+            from_email: sender
+                .map(|sender| sender.from_address())
+                .unwrap_or_else(|| self.config.smtp_from_email.clone()),
+            reply_to: None,
+            to: delivery.email.clone(),
+            subject: newsletter.subject.clone(),
+            text,
+            html,
+            headers: vec![
+                ("List-Unsubscribe".to_string(), format!("<{}>", unsubscribe_url)),
+                ("List-Unsubscribe-Post".to_string(), "List-Unsubscribe=One-Click".to_string()),
+                ("List-Id".to_string(), format!("{} <{}.newsletter>", publication.name, publication.slug)),
+            ],
+            return_path: sender.and_then(|sender| sender.return_path()),
+            dkim: sender.map(|sender| DkimKey {
+                domain: sender.sending_domain.clone(),
+                selector: sender.dkim_selector.clone(),
+                private_key: sender.dkim_private_key.clone(),
+            }),
+        })
+    }
+
+    /// 使用收件人的退订令牌退订该出版物的 Newsletter
+    pub async fn unsubscribe(&self, token: &str) -> Result<String> {
+        let delivery: NewsletterDelivery = self.db
+            .prepare("SELECT * FROM newsletter_delivery WHERE unsubscribe_token = $token LIMIT 1")
+            .bind("token", token)
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::not_found("Unsubscribe token"))?;
+
+        self.db
+            .prepare(
+                r#"
+                UPSERT type::thing('newsletter_unsubscribe', [$publication_id, $user_id]) SET
+                    publication_id = $publication_id,
+                    user_id = $user_id,
+                    email = $email,
+                    newsletter_id = $newsletter_id,
+                    created_at = time::now()
+                "#,
+            )
+            .bind("publication_id", &delivery.publication_id)
+            .bind("user_id", &delivery.user_id)
+            .bind("email", &delivery.email)
+            .bind("newsletter_id", &delivery.newsletter_id)
+            .execute()
+            .await?;
+
+        info!("User {} unsubscribed from newsletters of publication {}", delivery.user_id, delivery.publication_id);
+
+        let publication = self.get_publication(&PublicationId::new(&delivery.publication_id)).await?;
+        Ok(publication.name)
+    }
+
+    pub async fn list_newsletters(
+        &self,
+        publication_id: &str,
+        query: NewsletterListQuery,
+    ) -> Result<PaginatedResult<Newsletter>> {
+        let publication_id = PublicationId::new(publication_id);
+        let page = query.page.unwrap_or(1).max(1);
+        let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+        let mut response = self.db
+            .prepare(
+                r#"
+                SELECT count() AS total FROM newsletter WHERE publication_id = $publication_id GROUP ALL;
+                SELECT * FROM newsletter WHERE publication_id = $publication_id
+                ORDER BY created_at DESC LIMIT $limit START $offset;
+                "#,
+            )
+            .bind("publication_id", publication_id.as_str())
+            .bind("limit", limit)
+            .bind("offset", (page - 1) * limit)
+            .execute()
+            .await?;
+        let total: Option<i64> = response.take((0, "total"))?;
+        let newsletters: Vec<Newsletter> = response.take(1)?;
+        let total = total.unwrap_or(0) as usize;
+
+        Ok(PaginatedResult {
+            data: newsletters,
+            total,
+            page,
+            per_page: limit,
+            total_pages: (total + limit - 1) / limit,
+        })
+    }
+
+    pub async fn get_newsletter(&self, publication_id: &str, newsletter_id: &str) -> Result<Newsletter> {
+        let newsletter = self.load_newsletter(newsletter_id).await?;
+        if PublicationId::new(&newsletter.publication_id) != PublicationId::new(publication_id) {
+            return Err(AppError::not_found("Newsletter"));
+        }
+        Ok(newsletter)
+    }
+
+    pub async fn list_deliveries(
+        &self,
+        publication_id: &str,
+        newsletter_id: &str,
+        query: DeliveryListQuery,
+    ) -> Result<PaginatedResult<NewsletterDelivery>> {
+        let newsletter = self.get_newsletter(publication_id, newsletter_id).await?;
+        let page = query.page.unwrap_or(1).max(1);
+        let limit = query.limit.unwrap_or(50).clamp(1, 200);
+
+        let mut response = self.db
+            .prepare(
+                r#"
+                SELECT count() AS total FROM newsletter_delivery
+                WHERE newsletter_id = $newsletter_id AND (!$status OR status = $status) GROUP ALL;
+                SELECT * FROM newsletter_delivery
+                WHERE newsletter_id = $newsletter_id AND (!$status OR status = $status)
+                ORDER BY email LIMIT $limit START $offset;
+                "#,
+            )
+            .bind("newsletter_id", bare_id("newsletter", &newsletter.id))
+            .bind("status", query.status.map(|s| s.as_str()))
+            .bind("limit", limit)
+            .bind("offset", (page - 1) * limit)
+            .execute()
+            .await?;
+        let total: Option<i64> = response.take((0, "total"))?;
+        let deliveries: Vec<NewsletterDelivery> = response.take(1)?;
+        let total = total.unwrap_or(0) as usize;
+
+        Ok(PaginatedResult {
+            data: deliveries,
+            total,
+            page,
+            per_page: limit,
+            total_pages: (total + limit - 1) / limit,
+        })
+    }
+
+    // Helper methods
+
+    async fn load_newsletter(&self, newsletter_id: &str) -> Result<Newsletter> {
+        self.db
+            .get_by_id("newsletter", newsletter_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Newsletter"))
+    }
+
+    async fn get_publication(&self, publication_id: &PublicationId) -> Result<NewsletterPublication> {
+        self.db
+            .prepare("SELECT name, slug, owner_id FROM type::thing('publication', $publication_id)")
+            .bind("publication_id", publication_id.as_str())
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::not_found("Publication"))
+    }
+
+    /// 出版物关注者和出版物所有者的付费订阅者，排除已退订和没有邮箱的用户
+    async fn get_recipients(
+        &self,
+        publication_id: &PublicationId,
+        owner_id: &str,
+        subscribers_only: bool,
+    ) -> Result<Vec<Recipient>> {
+        let mut response = self.db
+            .prepare(
+                r#"
+                SELECT VALUE user_id FROM publication_follow WHERE publication_id = type::thing('publication', $publication_id);
+                SELECT VALUE subscriber_id FROM subscription WHERE creator_id = $owner_id AND status = 'active';
+                SELECT VALUE user_id FROM newsletter_unsubscribe WHERE publication_id = $publication_id;
+                "#,
+            )
+            .bind("publication_id", publication_id.as_str())
+            .bind("owner_id", owner_id)
+            .execute()
+            .await?;
+        let followers: Vec<String> = response.take(0)?;
+        let subscribers: Vec<String> = response.take(1)?;
+        let unsubscribed: HashSet<String> = response.take::<Vec<String>>(2)?.into_iter().collect();
+
+        let mut seen = HashSet::new();
+        let user_ids: Vec<String> = subscribers
+            .into_iter()
+            .chain(if subscribers_only { Vec::new() } else { followers })
+            .filter(|id| !unsubscribed.contains(id) && seen.insert(id.clone()))
+            .collect();
+        if user_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.db
+            .prepare("SELECT user_id, email FROM user_profile WHERE user_id INSIDE $user_ids AND email != NONE AND email != ''")
+            .bind("user_ids", user_ids)
+            .fetch()
+            .await
+    }
+}
+
+fn generate_unsubscribe_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(UNSUBSCRIBE_TOKEN_LEN)
+        .map(char::from)
+        .collect()
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -1,10 +1,20 @@
 use crate::{
-    error::Result, 
-    services::Database, 
+    error::{AppError, Result},
+    services::{AuthService, Database, email::{EmailService, DigestEmailEntry}, plugin::Plugin, web_push::WebPushService},
     config::Config,
-    models::notification::*,
+    models::{
+        id::bare_id,
+        notification::*,
+        plugin::{ArticlePublishedEvent, CommentCreatedEvent},
+        websocket::NotificationConfig,
+    },
+    utils::i18n::{self, Locale},
 };
+use async_trait::async_trait;
+use std::collections::HashMap;
 use std::sync::Arc;
+use serde_json::{json, Value};
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 use chrono::Utc;
 
@@ -12,30 +22,438 @@ use chrono::Utc;
 pub struct NotificationService {
     db: Arc<Database>,
     config: Config,
+    email_service: EmailService,
+    web_push: WebPushService,
+    auth_service: AuthService,
 }
 
 impl NotificationService {
-    pub async fn new(db: Arc<Database>, config: &Config) -> Result<Self> {
-        Ok(Self { 
+    pub async fn new(db: Arc<Database>, config: &Config, auth_service: AuthService) -> Result<Self> {
+        Ok(Self {
+            web_push: WebPushService::new(db.clone(), config)?,
             db,
             config: config.clone(),
+            email_service: EmailService::new(config)?,
+            auth_service,
         })
     }
 
-    pub async fn create_notification(&self, request: CreateNotificationRequest) -> Result<Notification> {
+    /// 按收件人的偏好创建站内通知，并投递邮件和浏览器推送。站内通知被关闭时返回 `None`
+    pub async fn create_notification(&self, request: CreateNotificationRequest) -> Result<Option<Notification>> {
+        let channels = self
+            .get_preferences(&request.recipient_id)
+            .await?
+            .channels_for(&request.notification_type);
+        let transactional = request.notification_type.is_transactional();
+        let supports_web_push = request.notification_type.supports_web_push();
+
+        if channels.is_muted() {
+            debug!(
+                "Notification {} muted by {}",
+                request.notification_type.preference_key(),
+                request.recipient_id
+            );
+            return Ok(None);
+        }
+
+        // 收件人设置了语言时按该语言保存，之后的邮件和推送使用同样的文字
+        let (title, message) = match self.recipient_locale(&request.recipient_id).await {
+            Some(locale) => (i18n::translate(locale, &request.title), i18n::translate(locale, &request.message)),
+            None => (request.title, request.message),
+        };
+
         let notification = Notification {
             id: Uuid::new_v4().to_string(),
             recipient_id: request.recipient_id,
             notification_type: format!("{:?}", request.notification_type),
-            title: request.title,
-            message: request.message,
+            title,
+            message,
             data: request.data,
             is_read: false,
             read_at: None,
             created_at: Utc::now(),
         };
 
-        let created: Notification = self.db.create("notification", notification).await?;
-        Ok(created)
+        let notification: Notification = if channels.in_app {
+            self.db.create("notification", notification).await?
+        } else {
+            notification
+        };
+
+        // 邮件投递失败不影响站内通知
+        if channels.email {
+            if let Err(e) = self.dispatch_email(&notification, channels.digest, transactional).await {
+                warn!("Failed to dispatch email for notification {}: {}", notification.id, e);
+            }
+        }
+
+        // 浏览器推送，不依赖 WebSocket 连接
+        if channels.push && supports_web_push {
+            if let Err(e) = self.web_push.send_notification(&notification).await {
+                warn!("Failed to send web push for notification {}: {}", notification.id, e);
+            }
+        }
+
+        Ok(channels.in_app.then_some(notification))
+    }
+
+    /// Web Push 订阅管理
+    pub fn web_push(&self) -> &WebPushService {
+        &self.web_push
     }
-}
\ No newline at end of file
+
+    /// 用户的通知偏好。没有单独保存过时按旧的通知配置推导
+    pub async fn get_preferences(&self, user_id: &str) -> Result<NotificationPreferences> {
+        let saved: Option<NotificationPreferences> = self.db
+            .prepare("SELECT * FROM type::thing('notification_preference', $user_id)")
+            .bind("user_id", user_id)
+            .fetch_one()
+            .await?;
+
+        match saved {
+            Some(mut preferences) => {
+                // 新增的事件类型沿用默认设置
+                let defaults = NotificationPreferences::from_config(&self.get_notification_config(user_id).await?);
+                for (event, preference) in defaults.events {
+                    preferences.events.entry(event).or_insert(preference);
+                }
+                Ok(preferences)
+            }
+            None => Ok(NotificationPreferences::from_config(&self.get_notification_config(user_id).await?)),
+        }
+    }
+
+    /// 更新部分事件的渠道开关，未提及的事件保持不变
+    pub async fn update_preferences(
+        &self,
+        user_id: &str,
+        request: UpdateNotificationPreferencesRequest,
+    ) -> Result<NotificationPreferences> {
+        let mut preferences = self.get_preferences(user_id).await?;
+
+        for (event, update) in request.events {
+            let preference = preferences
+                .events
+                .get_mut(&event)
+                .ok_or_else(|| AppError::BadRequest(format!("未知的通知类型: {}", event)))?;
+
+            if let Some(in_app) = update.in_app {
+                preference.in_app = in_app;
+            }
+            if let Some(email) = update.email {
+                preference.email = email;
+            }
+            if let Some(push) = update.push {
+                preference.push = push;
+            }
+            if let Some(digest) = update.digest {
+                preference.digest = digest;
+            }
+        }
+
+        if let Some(frequency) = request.activity_digest {
+            preferences.activity_digest = frequency;
+        }
+
+        let updated: Option<NotificationPreferences> = self.db
+            .prepare(
+                r#"
+                UPSERT type::thing('notification_preference', $user_id) SET
+                    user_id = $user_id,
+                    events = $events,
+                    activity_digest = $activity_digest,
+                    updated_at = time::now()
+                "#,
+            )
+            .bind("user_id", user_id)
+            .bind("events", &preferences.events)
+            .bind("activity_digest", preferences.activity_digest.as_str())
+            .fetch_one()
+            .await?;
+
+        info!("Updated notification preferences for user {}", user_id);
+        updated.ok_or_else(|| AppError::internal("Failed to update notification preferences"))
+    }
+
+    /// 某类通知对用户启用的渠道，供实时推送等站内通知以外的渠道使用
+    pub async fn channels_for(&self, user_id: &str, notification_type: &NotificationType) -> Result<ChannelPreference> {
+        Ok(self.get_preferences(user_id).await?.channels_for(notification_type))
+    }
+
+    /// 给站外邮箱发送一封事务邮件（例如赠送到邮箱的礼物），不经过站内通知和偏好设置
+    pub async fn send_email(&self, email: &str, title: String, message: String) -> Result<()> {
+        if !self.config.enable_email_notifications {
+            return Ok(());
+        }
+
+        self.email_service
+            .send_digest(email, &[DigestEmailEntry { title, message }])
+            .await
+    }
+
+    /// 获取用户的通知偏好，没有保存过时返回默认配置
+    pub async fn get_notification_config(&self, user_id: &str) -> Result<NotificationConfig> {
+        let mut response = self.db.query_with_params(
+            "SELECT * FROM notification_config WHERE user_id = $user_id LIMIT 1",
+            json!({ "user_id": user_id }),
+        ).await?;
+        let configs: Vec<Value> = response.take(0)?;
+
+        Ok(configs
+            .into_iter()
+            .next()
+            .and_then(|v| serde_json::from_value::<NotificationConfig>(v).ok())
+            .unwrap_or_else(|| NotificationConfig::default_for(user_id)))
+    }
+
+    /// 根据摘要窗口立即发送邮件或放入摘要队列
+    async fn dispatch_email(&self, notification: &Notification, window: DigestWindow, transactional: bool) -> Result<()> {
+        if !self.config.enable_email_notifications {
+            return Ok(());
+        }
+
+        if transactional || window == DigestWindow::Immediate {
+            match self.get_recipient_email(&notification.recipient_id).await? {
+                Some(email) => {
+                    self.email_service.send_digest(&email, &[DigestEmailEntry {
+                        title: notification.title.clone(),
+                        message: notification.message.clone(),
+                    }]).await?;
+                }
+                None => warn!("No email address for user {}, skipping notification email", notification.recipient_id),
+            }
+            return Ok(());
+        }
+
+        self.db.query_with_params(
+            r#"
+                CREATE notification_digest_item CONTENT {
+                    recipient_id: $recipient_id,
+                    notification_id: $notification_id,
+                    notification_type: $notification_type,
+                    title: $title,
+                    message: $message,
+                    window: $window,
+                    deliver_after: <datetime> $deliver_after,
+                    created_at: time::now()
+                }
+            "#,
+            json!({
+                "recipient_id": notification.recipient_id,
+                "notification_id": notification.id,
+                "notification_type": notification.notification_type,
+                "title": notification.title,
+                "message": notification.message,
+                "window": window.as_str(),
+                "deliver_after": window.next_delivery_at(Utc::now()),
+            }),
+        ).await?;
+
+        Ok(())
+    }
+
+    /// 发送所有已到期窗口的摘要邮件，每个用户合并为一封
+    pub async fn flush_due_email_digests(&self) -> Result<usize> {
+        let mut response = self.db.query(
+            r#"
+                SELECT * FROM notification_digest_item
+                WHERE delivered_at IS NONE AND failed_at IS NONE AND deliver_after <= time::now()
+                ORDER BY created_at ASC
+                LIMIT 1000
+            "#,
+        ).await?;
+        let items: Vec<EmailDigestItem> = response.take(0)?;
+
+        if items.is_empty() {
+            return Ok(0);
+        }
+
+        let mut by_recipient: HashMap<String, Vec<EmailDigestItem>> = HashMap::new();
+        for item in items {
+            by_recipient.entry(item.recipient_id.clone()).or_default().push(item);
+        }
+
+        let mut sent = 0;
+        for (recipient_id, items) in by_recipient {
+            let ids: Vec<String> = items.iter().map(|i| i.id.clone()).collect();
+
+            let email = match self.get_recipient_email(&recipient_id).await {
+                Ok(Some(email)) => email,
+                Ok(None) => {
+                    // 没有可用地址的摘要标记为失败，不再重试，也不算作已发送
+                    warn!("No email address for user {}, marking {} digest items as failed", recipient_id, ids.len());
+                    self.db.query_with_params(
+                        "UPDATE notification_digest_item SET failed_at = time::now(), failure_reason = 'no_email_address' WHERE type::string(id) INSIDE $ids",
+                        json!({ "ids": ids }),
+                    ).await?;
+                    continue;
+                }
+                Err(e) => {
+                    // 查询失败时保留队列项，下一轮重试
+                    warn!("Failed to resolve email of {}: {}", recipient_id, e);
+                    continue;
+                }
+            };
+
+            let entries: Vec<DigestEmailEntry> = items
+                .iter()
+                .map(|i| DigestEmailEntry {
+                    title: i.title.clone(),
+                    message: i.message.clone(),
+                })
+                .collect();
+
+            if let Err(e) = self.email_service.send_digest(&email, &entries).await {
+                // 保留队列项，下一轮重试
+                warn!("Failed to send digest to {}: {}", recipient_id, e);
+                continue;
+            }
+            sent += 1;
+
+            self.db.query_with_params(
+                "UPDATE notification_digest_item SET delivered_at = time::now() WHERE type::string(id) INSIDE $ids",
+                json!({ "ids": ids }),
+            ).await?;
+        }
+
+        info!("Sent {} notification digest emails", sent);
+        Ok(sent)
+    }
+
+    /// 用户资料中设置的语言，没有设置或不支持时为 `None`
+    async fn recipient_locale(&self, user_id: &str) -> Option<Locale> {
+        let language: Option<Option<String>> = self.db
+            .prepare("SELECT VALUE preferred_language FROM user_profile WHERE user_id = $user_id LIMIT 1")
+            .bind("user_id", user_id)
+            .fetch_one()
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Failed to read preferred language of {}: {}", user_id, e);
+                None
+            });
+        language.flatten().as_deref().and_then(Locale::parse)
+    }
+
+    /// 用户在 Rainbow-Auth 登记的邮箱地址，用户资料中不保存邮箱
+    pub async fn get_recipient_email(&self, user_id: &str) -> Result<Option<String>> {
+        self.auth_service.get_user_email(user_id).await
+    }
+}
+
+/// 作为内置插件通知关注者的新文章和评论的回复，在后台逐个创建通知
+#[async_trait]
+impl Plugin for NotificationService {
+    fn name(&self) -> &str {
+        "notifications"
+    }
+
+    async fn on_article_published(&self, event: &ArticlePublishedEvent) -> Result<()> {
+        let service = self.clone();
+        let event = event.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.notify_followers_of_article(&event).await {
+                warn!("Failed to notify followers of article {}: {}", event.article_id, e);
+            }
+        });
+        Ok(())
+    }
+
+    async fn on_comment_created(&self, event: &CommentCreatedEvent) -> Result<()> {
+        if event.parent_id.is_none() {
+            return Ok(());
+        }
+
+        let service = self.clone();
+        let event = event.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.notify_comment_reply(&event).await {
+                warn!("Failed to notify reply to comment {}: {}", event.comment_id, e);
+            }
+        });
+        Ok(())
+    }
+}
+
+impl NotificationService {
+    async fn notify_followers_of_article(&self, event: &ArticlePublishedEvent) -> Result<()> {
+        // 笔名文章不通知账号的关注者，否则等于公开了笔名背后的作者
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT VALUE pseudonym_id FROM type::thing('article', $article_id);
+                SELECT VALUE display_name FROM user_profile WHERE user_id = $author_id LIMIT 1;
+                SELECT VALUE follower_id FROM follow WHERE following_id = $author_id;
+            "#,
+            json!({
+                "article_id": bare_id("article", &event.article_id),
+                "author_id": event.author_id,
+            }),
+        ).await?;
+        let pseudonym: Vec<Option<String>> = response.take(0)?;
+        if pseudonym.into_iter().flatten().next().is_some() {
+            return Ok(());
+        }
+        let author_name: Vec<String> = response.take(1)?;
+        let author_name = author_name.into_iter().next().unwrap_or_else(|| "A writer you follow".to_string());
+        let followers: Vec<String> = response.take(2)?;
+
+        for follower_id in followers {
+            let request = CreateNotificationRequest {
+                recipient_id: follower_id,
+                notification_type: NotificationType::ArticlePublished,
+                title: format!("New story from {}", author_name),
+                message: event.title.clone(),
+                data: json!({
+                    "article_id": event.article_id,
+                    "article_slug": event.slug,
+                    "author_id": event.author_id,
+                }),
+            };
+            if let Err(e) = self.create_notification(request).await {
+                warn!("Failed to notify follower about article {}: {}", event.article_id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn notify_comment_reply(&self, event: &CommentCreatedEvent) -> Result<()> {
+        let Some(parent_id) = &event.parent_id else {
+            return Ok(());
+        };
+
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT VALUE author_id FROM type::thing('comment', $parent_id);
+                SELECT VALUE display_name FROM user_profile WHERE user_id = $author_id LIMIT 1;
+            "#,
+            json!({
+                "parent_id": bare_id("comment", parent_id),
+                "author_id": event.author_id,
+            }),
+        ).await?;
+        let parent_author: Vec<String> = response.take(0)?;
+        let Some(recipient_id) = parent_author.into_iter().next() else {
+            return Ok(());
+        };
+        if recipient_id == event.author_id {
+            return Ok(());
+        }
+        let replier: Vec<String> = response.take(1)?;
+        let replier = replier.into_iter().next().unwrap_or_else(|| "Someone".to_string());
+
+        let excerpt: String = event.content.chars().take(140).collect();
+        self.create_notification(CreateNotificationRequest {
+            recipient_id,
+            notification_type: NotificationType::CommentReply,
+            title: format!("{} replied to your comment", replier),
+            message: excerpt,
+            data: json!({
+                "comment_id": event.comment_id,
+                "parent_id": parent_id,
+                "article_id": event.article_id,
+            }),
+        }).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,579 @@
+use crate::{
+    error::{AppError, Result},
+    models::oauth::*,
+    services::{auth::User, Database},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{DateTime, Duration, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tracing::{debug, info};
+use url::Url;
+use validator::Validate;
+
+/// 第三方访问令牌的前缀，`auth_middleware` 据此区分平台 JWT
+pub const ACCESS_TOKEN_PREFIX: &str = "rbo_";
+const REFRESH_TOKEN_PREFIX: &str = "rbr_";
+const TOKEN_LEN: usize = 40;
+const CLIENT_ID_LEN: usize = 24;
+
+const AUTHORIZATION_CODE_TTL_MINUTES: i64 = 10;
+const ACCESS_TOKEN_TTL_SECS: i64 = 3600;
+const REFRESH_TOKEN_TTL_DAYS: i64 = 60;
+/// 每个用户最多注册的应用数
+const MAX_CLIENTS_PER_USER: usize = 20;
+
+#[derive(Debug, Deserialize)]
+struct AuthorizationCode {
+    client_id: String,
+    user_id: String,
+    redirect_uri: String,
+    scopes: Vec<String>,
+    code_challenge: String,
+    expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssuedToken {
+    #[serde(with = "crate::utils::serde_helpers::thing_id")]
+    id: String,
+    client_id: String,
+    user_id: String,
+    scopes: Vec<String>,
+    access_expires_at: DateTime<Utc>,
+    refresh_expires_at: DateTime<Utc>,
+}
+
+/// OAuth2 授权码模式（强制 PKCE），让第三方应用在用户同意的权限范围内代表用户调用 API
+#[derive(Clone)]
+pub struct OAuthService {
+    db: Arc<Database>,
+}
+
+impl OAuthService {
+    pub async fn new(db: Arc<Database>) -> Result<Self> {
+        Ok(Self { db })
+    }
+
+    /// 注册应用。机密客户端的密钥只在这里返回一次
+    pub async fn create_client(&self, owner_id: &str, request: CreateOAuthClientRequest) -> Result<OAuthClientCreated> {
+        request.validate()?;
+
+        if let Some(scope) = request.scopes.iter().find(|s| !is_known_scope(s)) {
+            return Err(AppError::BadRequest(format!("未知的权限范围: {}", scope)));
+        }
+        for uri in &request.redirect_uris {
+            validate_redirect_uri(uri)?;
+        }
+        if self.list_clients(owner_id).await?.len() >= MAX_CLIENTS_PER_USER {
+            return Err(AppError::BadRequest(format!("每个用户最多注册 {} 个应用", MAX_CLIENTS_PER_USER)));
+        }
+
+        let client_id = random_token(CLIENT_ID_LEN);
+        let client_secret = request.confidential.then(|| random_token(TOKEN_LEN));
+        let mut scopes = request.scopes;
+        scopes.sort();
+        scopes.dedup();
+
+        let client: Option<OAuthClient> = self
+            .db
+            .prepare(
+                r#"
+                CREATE type::thing('oauth_client', $client_id) CONTENT {
+                    client_id: $client_id,
+                    owner_id: $owner_id,
+                    name: $name,
+                    redirect_uris: $redirect_uris,
+                    scopes: $scopes,
+                    confidential: $confidential,
+                    secret_hash: $secret_hash,
+                    created_at: time::now()
+                }
+                "#,
+            )
+            .bind("client_id", &client_id)
+            .bind("owner_id", owner_id)
+            .bind("name", &request.name)
+            .bind("redirect_uris", &request.redirect_uris)
+            .bind("scopes", &scopes)
+            .bind("confidential", request.confidential)
+            .bind("secret_hash", client_secret.as_deref().map(hash_token))
+            .fetch_one()
+            .await?;
+
+        let client = client.ok_or_else(|| AppError::internal("Failed to create OAuth client"))?;
+        info!("User {} registered OAuth client {}", owner_id, client.client_id);
+        Ok(OAuthClientCreated { client, client_secret })
+    }
+
+    pub async fn list_clients(&self, owner_id: &str) -> Result<Vec<OAuthClient>> {
+        self.db
+            .prepare("SELECT * FROM oauth_client WHERE owner_id = $owner_id ORDER BY created_at DESC")
+            .bind("owner_id", owner_id)
+            .fetch()
+            .await
+    }
+
+    async fn get_client(&self, client_id: &str) -> Result<Option<OAuthClient>> {
+        self.db
+            .prepare("SELECT * FROM type::thing('oauth_client', $client_id)")
+            .bind("client_id", client_id)
+            .fetch_one()
+            .await
+    }
+
+    /// 删除应用，同时撤销它的所有令牌和授权
+    pub async fn delete_client(&self, owner_id: &str, client_id: &str) -> Result<()> {
+        let deleted: Option<OAuthClient> = self
+            .db
+            .prepare("DELETE type::thing('oauth_client', $client_id) WHERE owner_id = $owner_id RETURN BEFORE")
+            .bind("client_id", client_id)
+            .bind("owner_id", owner_id)
+            .fetch_one()
+            .await?;
+        if deleted.is_none() {
+            return Err(AppError::NotFound("应用不存在".to_string()));
+        }
+
+        self.db
+            .prepare(
+                r#"
+                UPDATE oauth_token SET revoked_at = time::now() WHERE client_id = $client_id AND revoked_at IS NONE;
+                DELETE oauth_grant WHERE client_id = $client_id;
+                DELETE oauth_code WHERE client_id = $client_id;
+                "#,
+            )
+            .bind("client_id", client_id)
+            .execute()
+            .await?;
+
+        info!("Deleted OAuth client {}", client_id);
+        Ok(())
+    }
+
+    /// 校验授权请求，返回授权页需要展示的应用和权限范围
+    pub async fn authorize_prompt(&self, user: &User, request: &AuthorizeRequest) -> Result<AuthorizePrompt> {
+        let (client, scopes) = self.validate_authorize_request(request).await?;
+
+        let granted: Option<Vec<String>> = self
+            .db
+            .prepare("SELECT VALUE scopes FROM type::thing('oauth_grant', $grant_id)")
+            .bind("grant_id", grant_id(&user.id, &client.client_id))
+            .fetch_one()
+            .await?;
+        let previously_granted = granted.map_or(false, |granted| scopes.iter().all(|s| granted.contains(s)));
+
+        Ok(AuthorizePrompt {
+            client_id: client.client_id,
+            client_name: client.name,
+            redirect_uri: request.redirect_uri.clone(),
+            scopes: scopes
+                .into_iter()
+                .map(|scope| ScopeDescription {
+                    description: OAUTH_SCOPES
+                        .iter()
+                        .find(|(name, _)| *name == scope)
+                        .map(|(_, description)| description.to_string())
+                        .unwrap_or_default(),
+                    scope,
+                })
+                .collect(),
+            previously_granted,
+        })
+    }
+
+    /// 处理用户的同意或拒绝，返回要跳转回应用的地址（带授权码或 `access_denied`）
+    pub async fn authorize(&self, user: &User, decision: AuthorizeDecision) -> Result<String> {
+        let request = &decision.request;
+        let (client, scopes) = self.validate_authorize_request(request).await?;
+
+        let mut redirect = Url::parse(&request.redirect_uri)
+            .map_err(|_| AppError::BadRequest("Invalid redirect_uri".to_string()))?;
+
+        if !decision.approve {
+            {
+                let mut query = redirect.query_pairs_mut();
+                query.append_pair("error", "access_denied");
+                if let Some(state) = &request.state {
+                    query.append_pair("state", state);
+                }
+            }
+            return Ok(redirect.to_string());
+        }
+
+        let code = random_token(TOKEN_LEN);
+
+        self.db
+            .prepare(
+                r#"
+                CREATE type::thing('oauth_code', $code_hash) CONTENT {
+                    client_id: $client_id,
+                    user_id: $user_id,
+                    redirect_uri: $redirect_uri,
+                    scopes: $scopes,
+                    code_challenge: $code_challenge,
+                    expires_at: <datetime> $expires_at
+                };
+                UPSERT type::thing('oauth_grant', $grant_id) SET
+                    user_id = $user_id,
+                    client_id = $client_id,
+                    client_name = $client_name,
+                    scopes = array::union(scopes ?? [], $scopes),
+                    granted_at = time::now();
+                "#,
+            )
+            .bind("code_hash", hash_token(&code))
+            .bind("client_id", &client.client_id)
+            .bind("client_name", &client.name)
+            .bind("user_id", &user.id)
+            .bind("redirect_uri", &request.redirect_uri)
+            .bind("scopes", &scopes)
+            .bind("code_challenge", &request.code_challenge)
+            .bind("expires_at", Utc::now() + Duration::minutes(AUTHORIZATION_CODE_TTL_MINUTES))
+            .bind("grant_id", grant_id(&user.id, &client.client_id))
+            .execute()
+            .await?;
+
+        {
+            let mut query = redirect.query_pairs_mut();
+            query.append_pair("code", &code);
+            if let Some(state) = &request.state {
+                query.append_pair("state", state);
+            }
+        }
+
+        info!("User {} authorized OAuth client {} for {:?}", user.id, client.client_id, scopes);
+        Ok(redirect.to_string())
+    }
+
+    /// 回调地址校验通过之前的错误直接返回给用户，不跳转到未经确认的地址
+    async fn validate_authorize_request(&self, request: &AuthorizeRequest) -> Result<(OAuthClient, Vec<String>)> {
+        let client = self
+            .get_client(&request.client_id)
+            .await?
+            .ok_or_else(|| AppError::BadRequest("Unknown client_id".to_string()))?;
+
+        if !client.redirect_uris.contains(&request.redirect_uri) {
+            return Err(AppError::BadRequest("redirect_uri is not registered for this client".to_string()));
+        }
+        // 规则收紧之前登记的地址同样要符合当前规则
+        validate_redirect_uri(&request.redirect_uri)?;
+        if request.response_type != "code" {
+            return Err(AppError::BadRequest("Only response_type=code is supported".to_string()));
+        }
+        if request.code_challenge_method != "S256" {
+            return Err(AppError::BadRequest("code_challenge_method must be S256".to_string()));
+        }
+        if !(43..=128).contains(&request.code_challenge.len()) {
+            return Err(AppError::BadRequest("Invalid code_challenge".to_string()));
+        }
+
+        let scopes = parse_scopes(&request.scope);
+        if scopes.is_empty() {
+            return Err(AppError::BadRequest("scope is required".to_string()));
+        }
+        if let Some(scope) = scopes.iter().find(|s| !client.scopes.contains(s)) {
+            return Err(AppError::BadRequest(format!("Scope not allowed for this client: {}", scope)));
+        }
+
+        Ok((client, scopes))
+    }
+
+    /// 令牌端点：用授权码或刷新令牌换取新的访问令牌
+    pub async fn exchange(&self, request: TokenRequest) -> std::result::Result<TokenResponse, OAuthError> {
+        let client_id = request
+            .client_id
+            .as_deref()
+            .ok_or_else(|| OAuthError::invalid_request("client_id is required"))?;
+        let client = self
+            .get_client(client_id)
+            .await?
+            .ok_or_else(|| OAuthError::invalid_client("Unknown client"))?;
+        authenticate_client(&client, request.client_secret.as_deref())?;
+
+        match request.grant_type.as_str() {
+            "authorization_code" => self.exchange_code(&client, &request).await,
+            "refresh_token" => self.refresh(&client, &request).await,
+            _ => Err(OAuthError {
+                error: "unsupported_grant_type",
+                description: format!("Unsupported grant_type: {}", request.grant_type),
+            }),
+        }
+    }
+
+    async fn exchange_code(&self, client: &OAuthClient, request: &TokenRequest) -> std::result::Result<TokenResponse, OAuthError> {
+        let (Some(code), Some(redirect_uri), Some(verifier)) =
+            (&request.code, &request.redirect_uri, &request.code_verifier)
+        else {
+            return Err(OAuthError::invalid_request("code, redirect_uri and code_verifier are required"));
+        };
+
+        // 授权码只能使用一次，先删除再校验
+        let stored: Option<AuthorizationCode> = self
+            .db
+            .prepare("DELETE type::thing('oauth_code', $code_hash) RETURN BEFORE")
+            .bind("code_hash", hash_token(code))
+            .fetch_one()
+            .await?;
+        let stored = stored.ok_or_else(|| OAuthError::invalid_grant("Invalid authorization code"))?;
+
+        if stored.client_id != client.client_id
+            || stored.redirect_uri != *redirect_uri
+            || stored.expires_at < Utc::now()
+        {
+            return Err(OAuthError::invalid_grant("Invalid authorization code"));
+        }
+        if !verify_pkce(verifier, &stored.code_challenge) {
+            return Err(OAuthError::invalid_grant("PKCE verification failed"));
+        }
+
+        Ok(self.issue_tokens(&client.client_id, &stored.user_id, &stored.scopes).await?)
+    }
+
+    /// 刷新令牌轮换：每次刷新都撤销旧令牌对并签发新的
+    async fn refresh(&self, client: &OAuthClient, request: &TokenRequest) -> std::result::Result<TokenResponse, OAuthError> {
+        let refresh_token = request
+            .refresh_token
+            .as_deref()
+            .ok_or_else(|| OAuthError::invalid_request("refresh_token is required"))?;
+
+        let stored: Option<IssuedToken> = self
+            .db
+            .prepare(
+                r#"
+                UPDATE oauth_token SET revoked_at = time::now()
+                WHERE refresh_hash = $refresh_hash AND revoked_at IS NONE
+                RETURN BEFORE
+                "#,
+            )
+            .bind("refresh_hash", hash_token(refresh_token))
+            .fetch_one()
+            .await?;
+        let stored = stored.ok_or_else(|| OAuthError::invalid_grant("Invalid refresh token"))?;
+
+        if stored.client_id != client.client_id || stored.refresh_expires_at < Utc::now() {
+            return Err(OAuthError::invalid_grant("Invalid refresh token"));
+        }
+
+        let scopes = match request.scope.as_deref() {
+            Some(scope) => {
+                let requested = parse_scopes(scope);
+                if requested.is_empty() || requested.iter().any(|s| !stored.scopes.contains(s)) {
+                    return Err(OAuthError::invalid_scope("Requested scope exceeds the original grant"));
+                }
+                requested
+            }
+            None => stored.scopes,
+        };
+
+        debug!("Rotated OAuth token {} for client {}", stored.id, client.client_id);
+        Ok(self.issue_tokens(&client.client_id, &stored.user_id, &scopes).await?)
+    }
+
+    async fn issue_tokens(&self, client_id: &str, user_id: &str, scopes: &[String]) -> Result<TokenResponse> {
+        let access_token = format!("{}{}", ACCESS_TOKEN_PREFIX, random_token(TOKEN_LEN));
+        let refresh_token = format!("{}{}", REFRESH_TOKEN_PREFIX, random_token(TOKEN_LEN));
+        let now = Utc::now();
+
+        self.db
+            .prepare(
+                r#"
+                CREATE oauth_token CONTENT {
+                    access_hash: $access_hash,
+                    refresh_hash: $refresh_hash,
+                    client_id: $client_id,
+                    user_id: $user_id,
+                    scopes: $scopes,
+                    access_expires_at: <datetime> $access_expires_at,
+                    refresh_expires_at: <datetime> $refresh_expires_at,
+                    created_at: time::now()
+                }
+                "#,
+            )
+            .bind("access_hash", hash_token(&access_token))
+            .bind("refresh_hash", hash_token(&refresh_token))
+            .bind("client_id", client_id)
+            .bind("user_id", user_id)
+            .bind("scopes", scopes)
+            .bind("access_expires_at", now + Duration::seconds(ACCESS_TOKEN_TTL_SECS))
+            .bind("refresh_expires_at", now + Duration::days(REFRESH_TOKEN_TTL_DAYS))
+            .execute()
+            .await?;
+
+        Ok(TokenResponse {
+            access_token,
+            token_type: "Bearer",
+            expires_in: ACCESS_TOKEN_TTL_SECS,
+            refresh_token,
+            scope: scopes.join(" "),
+        })
+    }
+
+    /// 撤销访问令牌或刷新令牌（RFC 7009）。未知令牌同样视为成功
+    pub async fn revoke(&self, token: &str) -> Result<()> {
+        self.db
+            .prepare(
+                r#"
+                UPDATE oauth_token SET revoked_at = time::now()
+                WHERE (access_hash = $hash OR refresh_hash = $hash) AND revoked_at IS NONE
+                "#,
+            )
+            .bind("hash", hash_token(token))
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 校验第三方访问令牌，返回授权用户的 ID 和权限范围。
+    /// 令牌只记录用户 ID，账户状态和权限由调用方每次重新读取
+    pub async fn authenticate(&self, access_token: &str) -> Result<Option<(String, OAuthAccess)>> {
+        let token: Option<IssuedToken> = self
+            .db
+            .prepare(
+                r#"
+                UPDATE oauth_token SET last_used_at = time::now()
+                WHERE access_hash = $access_hash AND revoked_at IS NONE AND access_expires_at > time::now()
+                "#,
+            )
+            .bind("access_hash", hash_token(access_token))
+            .fetch_one()
+            .await?;
+
+        Ok(token.filter(|t| t.access_expires_at > Utc::now()).map(|t| {
+            (
+                t.user_id,
+                OAuthAccess {
+                    client_id: t.client_id,
+                    scopes: t.scopes,
+                },
+            )
+        }))
+    }
+
+    /// 用户授权过的应用
+    pub async fn list_grants(&self, user_id: &str) -> Result<Vec<OAuthGrant>> {
+        self.db
+            .prepare(
+                r#"
+                SELECT client_id, client_name, scopes, granted_at,
+                    math::max((SELECT VALUE last_used_at FROM oauth_token WHERE user_id = $parent.user_id AND client_id = $parent.client_id)) AS last_used_at
+                FROM oauth_grant WHERE user_id = $user_id
+                ORDER BY granted_at DESC
+                "#,
+            )
+            .bind("user_id", user_id)
+            .fetch()
+            .await
+    }
+
+    /// 取消对某个应用的授权，撤销该应用持有的所有令牌
+    pub async fn revoke_grant(&self, user_id: &str, client_id: &str) -> Result<()> {
+        let deleted: Option<OAuthGrant> = self
+            .db
+            .prepare("DELETE type::thing('oauth_grant', $grant_id) RETURN BEFORE")
+            .bind("grant_id", grant_id(user_id, client_id))
+            .fetch_one()
+            .await?;
+        if deleted.is_none() {
+            return Err(AppError::NotFound("没有授权过该应用".to_string()));
+        }
+
+        self.db
+            .prepare(
+                r#"
+                UPDATE oauth_token SET revoked_at = time::now()
+                WHERE user_id = $user_id AND client_id = $client_id AND revoked_at IS NONE
+                "#,
+            )
+            .bind("user_id", user_id)
+            .bind("client_id", client_id)
+            .execute()
+            .await?;
+
+        info!("User {} revoked OAuth client {}", user_id, client_id);
+        Ok(())
+    }
+
+    /// 清理过期的授权码和令牌
+    pub async fn purge_expired(&self) -> Result<()> {
+        self.db
+            .prepare(
+                r#"
+                DELETE oauth_code WHERE expires_at < time::now();
+                DELETE oauth_token WHERE refresh_expires_at < time::now() OR (revoked_at != NONE AND revoked_at < time::now() - 7d);
+                "#,
+            )
+            .execute()
+            .await?;
+        Ok(())
+    }
+}
+
+fn authenticate_client(client: &OAuthClient, secret: Option<&str>) -> std::result::Result<(), OAuthError> {
+    match (&client.secret_hash, secret) {
+        (None, _) => Ok(()),
+        (Some(expected), Some(secret)) if *expected == hash_token(secret) => Ok(()),
+        (Some(_), _) => Err(OAuthError::invalid_client("Client authentication failed")),
+    }
+}
+
+/// 回调地址只能是 HTTPS 或本机回环地址上的 HTTP，且不能带片段
+fn validate_redirect_uri(uri: &str) -> Result<()> {
+    let url = Url::parse(uri).map_err(|_| AppError::BadRequest(format!("回调地址格式不正确: {}", uri)))?;
+    if url.fragment().is_some() {
+        return Err(AppError::BadRequest("回调地址不能包含 # 片段".to_string()));
+    }
+
+    let is_loopback = matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+    match url.scheme() {
+        "https" => Ok(()),
+        "http" if is_loopback => Ok(()),
+        _ => Err(AppError::BadRequest(format!("回调地址必须使用 HTTPS: {}", uri))),
+    }
+}
+
+fn verify_pkce(verifier: &str, challenge: &str) -> bool {
+    (43..=128).contains(&verifier.len())
+        && URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes())) == challenge
+}
+
+fn grant_id(user_id: &str, client_id: &str) -> String {
+    format!("{}_{}", user_id, client_id)
+}
+
+fn random_token(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pkce_s256() {
+        // RFC 7636 附录 B 的示例
+        let verifier = "dBjftJeZ4CVP-1jMnIl9Fe7I9EhwVU6wV2JLrp5CGjE";
+        assert!(verify_pkce(verifier, "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGKSsw5-cM"));
+        assert!(!verify_pkce(verifier, "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGKSsw5-cN"));
+    }
+
+    #[test]
+    fn test_redirect_uri_rules() {
+        assert!(validate_redirect_uri("https://app.example.com/callback").is_ok());
+        assert!(validate_redirect_uri("http://127.0.0.1:8765/callback").is_ok());
+        assert!(validate_redirect_uri("com.example.app:/oauth").is_err());
+        assert!(validate_redirect_uri("javascript:alert(1)").is_err());
+        assert!(validate_redirect_uri("http://app.example.com/callback").is_err());
+        assert!(validate_redirect_uri("https://app.example.com/cb#x").is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+    services::Database,
+    utils::embed::EmbedTarget,
+};
+use chrono::{DateTime, Duration, Utc};
+use futures::future::join_all;
+use reqwest::Client;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// 每篇文章最多展开的嵌入数，超出的链接原样保留
+const MAX_EMBEDS_PER_ARTICLE: usize = 20;
+/// 成功获取的嵌入内容缓存 7 天
+const CACHE_TTL_HOURS: i64 = 24 * 7;
+/// 获取失败（链接失效、推文被删、服务商不可用）后 1 小时内不再重试
+const FAILURE_TTL_HOURS: i64 = 1;
+
+/// oEmbed 响应和 Gist JSON 嵌入接口中用到的字段
+#[derive(Debug, Deserialize)]
+struct ProviderResponse {
+    title: Option<String>,
+    html: Option<String>,
+    /// Gist 的代码 HTML
+    div: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CachedEmbed {
+    url: String,
+    html: Option<String>,
+}
+
+/// 服务端展开正文中的嵌入链接（YouTube、Twitter/X、Gist、CodePen），
+/// 结果按链接缓存在 oembed_cache 中，保存文章时不必每次请求服务商
+#[derive(Clone)]
+pub struct OEmbedService {
+    db: Arc<Database>,
+    http_client: Client,
+    enabled: bool,
+}
+
+impl OEmbedService {
+    pub async fn new(db: Arc<Database>, config: &Config) -> Result<Self> {
+        let http_client = Client::builder()
+            .timeout(std::time::Duration::from_secs(5))
+            .user_agent("Rainbow-Blog oEmbed")
+            .build()
+            .map_err(|e| AppError::Internal(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self {
+            db,
+            http_client,
+            enabled: config.oembed_enabled,
+        })
+    }
+
+    /// 返回链接到嵌入 HTML 的映射。获取失败的链接不在结果中，渲染时原样保留
+    pub async fn resolve(&self, targets: &[EmbedTarget]) -> HashMap<String, String> {
+        let mut embeds = HashMap::new();
+        if !self.enabled || targets.is_empty() {
+            return embeds;
+        }
+
+        let targets = &targets[..targets.len().min(MAX_EMBEDS_PER_ARTICLE)];
+        let urls: Vec<&str> = targets.iter().map(|target| target.url.as_str()).collect();
+        let cached: Vec<CachedEmbed> = match self
+            .db
+            .prepare("SELECT url, html FROM oembed_cache WHERE url INSIDE $urls AND expires_at > time::now()")
+            .bind("urls", &urls)
+            .fetch()
+            .await
+        {
+            Ok(cached) => cached,
+            Err(e) => {
+                warn!("Failed to read oEmbed cache: {}", e);
+                Vec::new()
+            }
+        };
+
+        let mut missing = Vec::new();
+        for target in targets {
+            match cached.iter().find(|entry| entry.url == target.url) {
+                Some(entry) => embeds.extend(entry.html.clone().map(|html| (target.url.clone(), html))),
+                None => missing.push(target),
+            }
+        }
+
+        let fetched = join_all(missing.iter().map(|target| self.fetch(target))).await;
+        for (target, html) in missing.into_iter().zip(fetched) {
+            self.store(target, html.as_deref()).await;
+            if let Some(html) = html {
+                embeds.insert(target.url.clone(), html);
+            }
+        }
+
+        embeds
+    }
+
+    async fn fetch(&self, target: &EmbedTarget) -> Option<String> {
+        let response = match self.http_client.get(target.fetch_url()).send().await {
+            Ok(response) if response.status().is_success() => response,
+            Ok(response) => {
+                debug!("{} embed for {} returned {}", target.provider.as_str(), target.url, response.status());
+                return None;
+            }
+            Err(e) => {
+                warn!("Failed to fetch {} embed for {}: {}", target.provider.as_str(), target.url, e);
+                return None;
+            }
+        };
+
+        match response.json::<ProviderResponse>().await {
+            Ok(body) => target.render(body.title.as_deref(), body.html.or(body.div).as_deref()),
+            Err(e) => {
+                warn!("Invalid {} embed response for {}: {}", target.provider.as_str(), target.url, e);
+                None
+            }
+        }
+    }
+
+    /// 失败也缓存（html 为空），避免每次保存文章都请求失效的链接
+    async fn store(&self, target: &EmbedTarget, html: Option<&str>) {
+        let ttl = if html.is_some() { CACHE_TTL_HOURS } else { FAILURE_TTL_HOURS };
+        let now = Utc::now();
+        let expires_at: DateTime<Utc> = now + Duration::hours(ttl);
+
+        let result = self
+            .db
+            .prepare(
+                r#"
+                UPSERT type::thing('oembed_cache', $key) CONTENT {
+                    url: $url,
+                    provider: $provider,
+                    html: $html,
+                    fetched_at: <datetime> $now,
+                    expires_at: <datetime> $expires_at
+                }
+                "#,
+            )
+            .bind("key", hex::encode(Sha256::digest(target.url.as_bytes())))
+            .bind("url", &target.url)
+            .bind("provider", target.provider.as_str())
+            .bind("html", html)
+            .bind("now", now)
+            .bind("expires_at", expires_at)
+            .execute()
+            .await;
+
+        if let Err(e) = result {
+            warn!("Failed to cache embed for {}: {}", target.url, e);
+        }
+    }
+}
@@ -0,0 +1,253 @@
+use crate::{
+    error::{AppError, Result},
+    models::{id::bare_id, page::*},
+    services::Database,
+    utils::{markdown::MarkdownProcessor, slug},
+};
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+use validator::Validate;
+
+const PAGE_FIELDS: &str = "meta::id(id) AS id, publication_id, title, slug, content, content_html, status, show_in_nav, nav_order, created_by, created_at, updated_at, published_at";
+
+/// 出版物域名下已有固定路由的路径，页面不能使用
+const RESERVED_SLUGS: [&str; 9] = ["about", "articles", "writers", "api", "webmention", "xmlrpc", "feed.xml", "atom.xml", "health"];
+
+/// 出版物静态页面
+#[derive(Clone)]
+pub struct PageService {
+    db: Arc<Database>,
+    markdown: MarkdownProcessor,
+}
+
+impl PageService {
+    pub async fn new(db: Arc<Database>) -> Result<Self> {
+        Ok(Self {
+            db,
+            markdown: MarkdownProcessor::new(),
+        })
+    }
+
+    /// 出版物的所有页面（包括草稿），按导航顺序
+    pub async fn list(&self, publication_id: &str) -> Result<Vec<PublicationPage>> {
+        let query = format!(
+            "SELECT {} FROM publication_page WHERE publication_id = $publication_id ORDER BY nav_order ASC, created_at ASC",
+            PAGE_FIELDS
+        );
+        self.db
+            .prepare(&query)
+            .bind("publication_id", bare_id("publication", publication_id))
+            .fetch()
+            .await
+    }
+
+    /// 导航中显示的已发布页面
+    pub async fn navigation(&self, publication_id: &str) -> Result<Vec<PageNavItem>> {
+        self.db
+            .prepare(
+                "SELECT title, slug, nav_order FROM publication_page
+                 WHERE publication_id = $publication_id AND status = 'published' AND show_in_nav = true
+                 ORDER BY nav_order ASC",
+            )
+            .bind("publication_id", bare_id("publication", publication_id))
+            .fetch()
+            .await
+    }
+
+    pub async fn get(&self, publication_id: &str, page_id: &str) -> Result<PublicationPage> {
+        let query = format!(
+            "SELECT {} FROM type::thing('publication_page', $page_id) WHERE publication_id = $publication_id",
+            PAGE_FIELDS
+        );
+        self.db
+            .prepare(&query)
+            .bind("page_id", bare_id("publication_page", page_id))
+            .bind("publication_id", bare_id("publication", publication_id))
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::not_found("Page"))
+    }
+
+    /// 出版物域名下 `/{slug}` 对应的已发布页面
+    pub async fn get_published_by_slug(&self, publication_id: &str, slug: &str) -> Result<Option<PublicationPage>> {
+        let query = format!(
+            "SELECT {} FROM publication_page WHERE publication_id = $publication_id AND slug = $slug AND status = 'published' LIMIT 1",
+            PAGE_FIELDS
+        );
+        self.db
+            .prepare(&query)
+            .bind("publication_id", bare_id("publication", publication_id))
+            .bind("slug", slug.to_lowercase())
+            .fetch_one()
+            .await
+    }
+
+    pub async fn create(&self, publication_id: &str, user_id: &str, request: CreatePageRequest) -> Result<PublicationPage> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let publication_id = bare_id("publication", publication_id);
+        let slug = match request.slug {
+            Some(slug) => self.check_slug(publication_id, &slug, None).await?,
+            None => {
+                let existing: Vec<String> = self.db
+                    .prepare("SELECT VALUE slug FROM publication_page WHERE publication_id = $publication_id")
+                    .bind("publication_id", publication_id)
+                    .fetch()
+                    .await?;
+                let mut taken = existing;
+                taken.extend(RESERVED_SLUGS.iter().map(|s| s.to_string()));
+                slug::make_slug_unique(&slug::generate_slug(&request.title), &taken)
+            }
+        };
+        let status = request.status.unwrap_or_default();
+
+        let query = format!(
+            r#"
+            LET $last = (SELECT VALUE nav_order FROM publication_page WHERE publication_id = $publication_id ORDER BY nav_order DESC LIMIT 1)[0] ?? -1;
+            CREATE type::thing('publication_page', $id) CONTENT {{
+                publication_id: $publication_id,
+                title: $title,
+                slug: $slug,
+                content: $content,
+                content_html: $content_html,
+                status: $status,
+                show_in_nav: $show_in_nav,
+                nav_order: $last + 1,
+                created_by: $created_by,
+                published_at: IF $status = 'published' THEN time::now() ELSE NONE END
+            }} RETURN {};
+            "#,
+            PAGE_FIELDS
+        );
+        let page: Option<PublicationPage> = self.db
+            .prepare(&query)
+            .bind("id", Uuid::new_v4().to_string())
+            .bind("publication_id", publication_id)
+            .bind("title", request.title.trim())
+            .bind("slug", &slug)
+            .bind("content_html", self.markdown.to_html(&request.content))
+            .bind("content", &request.content)
+            .bind("status", status.as_str())
+            .bind("show_in_nav", request.show_in_nav.unwrap_or(true))
+            .bind("created_by", user_id)
+            .execute()
+            .await?
+            .take(1)?;
+        let page = page.ok_or_else(|| AppError::internal("Failed to create page"))?;
+
+        info!("Created page '{}' for publication {}", page.slug, publication_id);
+        Ok(page)
+    }
+
+    pub async fn update(&self, publication_id: &str, page_id: &str, request: UpdatePageRequest) -> Result<PublicationPage> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let mut page = self.get(publication_id, page_id).await?;
+        if let Some(slug) = request.slug {
+            page.slug = self.check_slug(&page.publication_id, &slug, Some(&page.id)).await?;
+        }
+        if let Some(title) = request.title {
+            page.title = title.trim().to_string();
+        }
+        if let Some(content) = request.content {
+            page.content_html = self.markdown.to_html(&content);
+            page.content = content;
+        }
+        if let Some(show_in_nav) = request.show_in_nav {
+            page.show_in_nav = show_in_nav;
+        }
+        let status = request.status.unwrap_or(page.status);
+
+        let query = format!(
+            r#"
+            UPDATE type::thing('publication_page', $page_id) SET
+                title = $title,
+                slug = $slug,
+                content = $content,
+                content_html = $content_html,
+                show_in_nav = $show_in_nav,
+                published_at = IF $status = 'published' THEN published_at ?? time::now() ELSE published_at END,
+                status = $status,
+                updated_at = time::now()
+            RETURN {}
+            "#,
+            PAGE_FIELDS
+        );
+        self.db
+            .prepare(&query)
+            .bind("page_id", &page.id)
+            .bind("title", &page.title)
+            .bind("slug", &page.slug)
+            .bind("content", &page.content)
+            .bind("content_html", &page.content_html)
+            .bind("show_in_nav", page.show_in_nav)
+            .bind("status", status.as_str())
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::internal("Failed to update page"))
+    }
+
+    pub async fn delete(&self, publication_id: &str, page_id: &str) -> Result<()> {
+        let page = self.get(publication_id, page_id).await?;
+        self.db.delete_by_id("publication_page", &page.id).await?;
+
+        info!("Deleted page '{}' from publication {}", page.slug, page.publication_id);
+        Ok(())
+    }
+
+    /// 按请求中的顺序重新编号，未列出的页面保持原有的相对顺序排在后面
+    pub async fn reorder(&self, publication_id: &str, request: ReorderPagesRequest) -> Result<Vec<PublicationPage>> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let pages = self.list(publication_id).await?;
+        let mut ordered: Vec<String> = Vec::with_capacity(pages.len());
+        for id in &request.page_ids {
+            let id = bare_id("publication_page", id);
+            if !pages.iter().any(|p| p.id == id) {
+                return Err(AppError::bad_request(&format!("Page {} does not belong to this publication", id)));
+            }
+            if !ordered.iter().any(|o| o == id) {
+                ordered.push(id.to_string());
+            }
+        }
+        for page in &pages {
+            if !ordered.contains(&page.id) {
+                ordered.push(page.id.clone());
+            }
+        }
+
+        for (index, page_id) in ordered.iter().enumerate() {
+            self.db
+                .prepare("UPDATE type::thing('publication_page', $page_id) SET nav_order = $nav_order")
+                .bind("page_id", page_id)
+                .bind("nav_order", index as i64)
+                .execute()
+                .await?;
+        }
+
+        self.list(publication_id).await
+    }
+
+    /// 规范化并检查 slug：格式合法、不是保留路径、出版物内未被其他页面使用
+    async fn check_slug(&self, publication_id: &str, slug: &str, page_id: Option<&str>) -> Result<String> {
+        let slug = slug.trim().to_lowercase();
+        if !slug::is_valid_slug(&slug) {
+            return Err(AppError::bad_request("Slug may only contain letters, digits, '-' and '_'"));
+        }
+        if RESERVED_SLUGS.contains(&slug.as_str()) {
+            return Err(AppError::bad_request(&format!("'{}' is reserved", slug)));
+        }
+
+        let existing: Option<String> = self.db
+            .prepare("SELECT VALUE meta::id(id) FROM publication_page WHERE publication_id = $publication_id AND slug = $slug LIMIT 1")
+            .bind("publication_id", bare_id("publication", publication_id))
+            .bind("slug", &slug)
+            .fetch_one()
+            .await?;
+        if existing.is_some_and(|id| Some(id.as_str()) != page_id) {
+            return Err(AppError::Conflict(format!("A page with slug '{}' already exists", slug)));
+        }
+        Ok(slug)
+    }
+}
@@ -1,19 +1,27 @@
 use crate::{
-    error::{AppError, Result},
+    error::{AppError, ErrorCode, Result},
     models::{
         article::Article,
+        gift::{Gift, GiftType},
+        id::bare_id,
         payment::*,
+        revenue::SubscriptionRevenueReversal,
         stripe::{CreateStripeIntentRequest, StripeIntentMode},
         subscription::{SubscriptionCheck, SubscriptionStatus},
     },
     services::{
-        stripe::{StripePurchaseUpdate, StripeService, StripeSubscriptionStatusUpdate},
+        stripe::{
+            StripeChargeReversal, StripeChargeReversalKind, StripePurchaseUpdate, StripeService,
+            StripeSubscriptionStatusUpdate,
+        },
+        database::PaginatedResult,
         Database, SubscriptionService,
     },
     utils::markdown::MarkdownProcessor,
 };
 use chrono::{DateTime, Duration, Utc};
 use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
@@ -296,6 +304,10 @@ impl PaymentService {
         let article = self.get_article_info(&request.article_id).await?;
         let pricing = self.get_article_pricing(&request.article_id).await?;
 
+        if article.status != crate::models::article::ArticleStatus::Published {
+            return Err(AppError::NotFound("文章不存在".to_string()));
+        }
+
         if !pricing.is_paid_content {
             return Err(AppError::BadRequest("文章不是付费内容".to_string()));
         }
@@ -305,13 +317,19 @@ impl PaymentService {
         };
 
         // 检查是否已经购买
-        if let Ok(existing_purchase) = self
+        let latest_purchase = match self
             .check_article_purchase(&request.article_id, buyer_id)
             .await
         {
-            if existing_purchase.status == PurchaseStatus::Completed {
-                return Err(AppError::BadRequest("您已经购买了这篇文章".to_string()));
-            }
+            Ok(purchase) => Some(purchase),
+            Err(AppError::NotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+        if latest_purchase
+            .as_ref()
+            .is_some_and(|p| p.status == PurchaseStatus::Completed)
+        {
+            return Err(AppError::BadRequest("您已经购买了这篇文章".to_string()));
         }
 
         // 检查是否已经有订阅访问权限
@@ -327,7 +345,19 @@ impl PaymentService {
             }
         }
 
-        let purchase_id = format!("article_purchase:{}", Uuid::new_v4());
+        // 重复提交复用同一个购买记录，购买 ID 又决定了 PaymentIntent 的幂等键，
+        // 因此重试不会在 Stripe 创建第二个支付意图
+        let purchase_id = match &latest_purchase {
+            Some(pending) if pending.status == PurchaseStatus::Pending => pending.id.clone(),
+            previous => purchase_key(
+                buyer_id,
+                &request.article_id,
+                request
+                    .idempotency_key
+                    .as_deref()
+                    .or(previous.as_ref().map(|p| p.id.as_str())),
+            ),
+        };
         let currency = "USD".to_string();
 
         let payment_method_id = if let Some(pm) =
@@ -349,7 +379,7 @@ impl PaymentService {
         };
 
         let payment_method_id = payment_method_id
-            .ok_or_else(|| AppError::BadRequest("请先添加并设置默认支付方式".to_string()))?;
+            .ok_or_else(|| AppError::coded(ErrorCode::PaymentMethodRequired, "请先添加并设置默认支付方式"))?;
 
         let metadata = json!({
             "purchase_id": purchase_id,
@@ -379,6 +409,17 @@ impl PaymentService {
             .map(|intent| intent.stripe_payment_intent_id.clone())
             .ok_or_else(|| AppError::Internal("Stripe 未返回 payment_intent".to_string()))?;
 
+        match self.get_purchase(&purchase_id, buyer_id).await {
+            Ok(purchase) => {
+                return Ok(ArticlePurchaseResponse {
+                    purchase,
+                    payment: payment_intent,
+                })
+            }
+            Err(AppError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+
         let query = r#"
             CREATE article_purchase CONTENT {
                 id: $purchase_id,
@@ -429,6 +470,103 @@ impl PaymentService {
         })
     }
 
+    /// 获取购买记录，只有购买者本人可以查看
+    pub async fn get_purchase(&self, purchase_id: &str, buyer_id: &str) -> Result<ArticlePurchase> {
+        let mut response = self
+            .db
+            .query_with_params(
+                "SELECT * FROM type::thing('article_purchase', $purchase_id) WHERE buyer_id = $buyer_id",
+                json!({
+                    "purchase_id": bare_id("article_purchase", purchase_id),
+                    "buyer_id": buyer_id,
+                }),
+            )
+            .await?;
+
+        let purchases: Vec<Value> = response.take(0)?;
+        let purchase = purchases
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::NotFound("购买记录不存在".to_string()))?;
+
+        self.parse_article_purchase(purchase)
+    }
+
+    /// 用户的购买历史，按时间倒序
+    pub async fn get_purchase_history(
+        &self,
+        buyer_id: &str,
+        page: usize,
+        limit: usize,
+    ) -> Result<PaginatedResult<PurchaseHistoryItem>> {
+        let page = page.max(1);
+        let limit = limit.clamp(1, 100);
+        let offset = (page - 1) * limit;
+
+        let mut response = self
+            .db
+            .query_with_params(
+                r#"
+                SELECT count() AS total FROM article_purchase WHERE buyer_id = $buyer_id GROUP ALL;
+                SELECT * FROM article_purchase WHERE buyer_id = $buyer_id
+                ORDER BY created_at DESC LIMIT $limit START $offset;
+                "#,
+                json!({
+                    "buyer_id": buyer_id,
+                    "limit": limit,
+                    "offset": offset,
+                }),
+            )
+            .await?;
+
+        let totals: Vec<Value> = response.take(0)?;
+        let total = totals
+            .first()
+            .and_then(|t| t["total"].as_u64())
+            .unwrap_or(0) as usize;
+
+        let records: Vec<Value> = response.take(1)?;
+        let purchases = records
+            .into_iter()
+            .map(|record| self.parse_article_purchase(record))
+            .collect::<Result<Vec<_>>>()?;
+
+        let article_ids: Vec<&str> = purchases
+            .iter()
+            .map(|p| bare_id("article", &p.article_id))
+            .collect();
+        let mut response = self
+            .db
+            .query_with_params(
+                "SELECT meta::id(id) AS id, title, slug FROM article WHERE meta::id(id) INSIDE $ids",
+                json!({ "ids": article_ids }),
+            )
+            .await?;
+        let articles: Vec<Value> = response.take(0)?;
+
+        let data = purchases
+            .into_iter()
+            .map(|purchase| {
+                let article = articles
+                    .iter()
+                    .find(|a| a["id"].as_str() == Some(bare_id("article", &purchase.article_id)));
+                PurchaseHistoryItem {
+                    article_title: article.and_then(|a| a["title"].as_str()).map(str::to_string),
+                    article_slug: article.and_then(|a| a["slug"].as_str()).map(str::to_string),
+                    purchase,
+                }
+            })
+            .collect();
+
+        Ok(PaginatedResult {
+            data,
+            total,
+            page,
+            per_page: limit,
+            total_pages: (total + limit - 1) / limit,
+        })
+    }
+
     /// 获取付费内容仪表板数据
     pub async fn get_payment_dashboard(&self, creator_id: &str) -> Result<PaymentDashboard> {
         debug!("Getting payment dashboard for creator: {}", creator_id);
@@ -495,6 +633,7 @@ impl PaymentService {
                         AccessType::OneTime => "one_time",
                         AccessType::Author => "author",
                         AccessType::Preview => "preview",
+                        AccessType::Metered => "metered",
                     },
                     "reading_time": reading_time,
                     "completed": completed
@@ -534,6 +673,8 @@ impl PaymentService {
             is_paid_content: article["is_paid_content"].as_bool().unwrap_or(false),
             status: match article["status"].as_str().unwrap_or("draft") {
                 "published" => crate::models::article::ArticleStatus::Published,
+                "pending_review" => crate::models::article::ArticleStatus::PendingReview,
+                "scheduled" => crate::models::article::ArticleStatus::Scheduled,
                 "unlisted" => crate::models::article::ArticleStatus::Unlisted,
                 "archived" => crate::models::article::ArticleStatus::Archived,
                 _ => crate::models::article::ArticleStatus::Draft,
@@ -556,14 +697,19 @@ impl PaymentService {
             subtitle: None,
             excerpt: None,
             cover_image_url: None,
+            pseudonym_id: None,
             publication_id: None,
             series_id: None,
             series_order: None,
             is_featured: false,
+            pinned_at: None,
+            publication_pinned_at: None,
             reading_time: 0,
             word_count: 0,
             view_count: 0,
             clap_count: 0,
+            weighted_clap_score: 0.0,
+            reaction_counts: Default::default(),
             comment_count: 0,
             bookmark_count: 0,
             share_count: 0,
@@ -571,9 +717,11 @@ impl PaymentService {
             seo_description: None,
             seo_keywords: vec![],
             metadata: serde_json::Value::Object(serde_json::Map::new()),
+            scheduled_at: None,
             last_edited_at: None,
             is_deleted: false,
             deleted_at: None,
+            taken_down_at: None,
         })
     }
 
@@ -737,6 +885,154 @@ impl PaymentService {
         Ok(())
     }
 
+    /// Stripe 报告支付失败时，把仍在等待的购买记录标记为失败，已完成的记录不受影响
+    pub async fn handle_stripe_purchase_failure(&self, stripe_payment_intent_id: &str) -> Result<()> {
+        let mut response = self
+            .db
+            .query_with_params(
+                r#"
+                UPDATE article_purchase SET status = "failed", updated_at = time::now()
+                WHERE stripe_payment_intent_id = $intent_id AND status = "pending"
+                "#,
+                json!({ "intent_id": stripe_payment_intent_id }),
+            )
+            .await?;
+
+        let failed: Vec<Value> = response.take(0)?;
+        if !failed.is_empty() {
+            warn!("Article purchase failed for payment intent: {}", stripe_payment_intent_id);
+        }
+        Ok(())
+    }
+
+    /// 处理退款和争议。全额退款或发起争议时收回阅读权限，部分退款保留权限；
+    /// 争议胜诉时恢复购买。返回受影响的购买记录，不是文章购买的收款返回 `None`
+    pub async fn handle_stripe_charge_reversal(
+        &self,
+        reversal: &StripeChargeReversal,
+    ) -> Result<Option<ArticlePurchase>> {
+        let Some(purchase) = self
+            .find_purchase_by_intent(&reversal.stripe_payment_intent_id)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let status = match reversal.kind {
+            StripeChargeReversalKind::Refund if reversal.amount < purchase.charged_amount() => {
+                info!(
+                    "Partial refund of {} on purchase {}, access kept",
+                    reversal.amount, purchase.id
+                );
+                return Ok(Some(purchase));
+            }
+            StripeChargeReversalKind::Refund => "refunded",
+            StripeChargeReversalKind::Dispute => "disputed",
+            StripeChargeReversalKind::DisputeWon => "completed",
+        };
+
+        let mut response = self
+            .db
+            .query_with_params(
+                "UPDATE article_purchase SET status = $status, updated_at = time::now() WHERE stripe_payment_intent_id = $intent_id RETURN AFTER",
+                json!({
+                    "status": status,
+                    "intent_id": reversal.stripe_payment_intent_id,
+                }),
+            )
+            .await?;
+        let records: Vec<Value> = response.take(0)?;
+        let purchase = match records.into_iter().next() {
+            Some(record) => self.parse_article_purchase(record)?,
+            None => purchase,
+        };
+
+        if reversal.kind == StripeChargeReversalKind::DisputeWon {
+            self.grant_paid_access(
+                &purchase.buyer_id,
+                &purchase.article_id,
+                AccessType::OneTime,
+                Some(&purchase.id),
+                None,
+            )
+            .await?;
+        } else {
+            self.db
+                .query_with_params(
+                    "DELETE paid_content_access WHERE user_id = $user_id AND article_id = $article_id AND access_type = 'one_time_purchase'",
+                    json!({
+                        "user_id": purchase.buyer_id,
+                        "article_id": purchase.article_id,
+                    }),
+                )
+                .await?;
+        }
+
+        info!("Purchase {} is now {} after {}", purchase.id, status, reversal.kind.as_str());
+        Ok(Some(purchase))
+    }
+
+    /// 处理订阅发票的退款和争议。全额退款或发起争议时订阅按未付款处理并收回订阅权限，
+    /// 部分退款保留权限；争议胜诉时恢复订阅
+    pub async fn handle_subscription_charge_reversal(
+        &self,
+        reversed: &SubscriptionRevenueReversal,
+        reversal: &StripeChargeReversal,
+    ) -> Result<()> {
+        let status = match reversal.kind {
+            StripeChargeReversalKind::Refund if reversal.amount < reversed.charged_amount => {
+                info!(
+                    "Partial refund of {} on subscription {}, access kept",
+                    reversal.amount, reversed.subscription_id
+                );
+                return Ok(());
+            }
+            StripeChargeReversalKind::Refund | StripeChargeReversalKind::Dispute => SubscriptionStatus::PastDue,
+            StripeChargeReversalKind::DisputeWon => SubscriptionStatus::Active,
+        };
+
+        let mut response = self
+            .db
+            .query_with_params(
+                "UPDATE type::thing('subscription', $subscription_id) SET status = $status, updated_at = time::now() RETURN AFTER",
+                json!({
+                    "subscription_id": bare_id("subscription", &reversed.subscription_id),
+                    "status": status.to_string(),
+                }),
+            )
+            .await?;
+        let records: Vec<Value> = response.take(0)?;
+        let Some(record) = records.into_iter().next() else {
+            warn!("Subscription {} not found for {}", reversed.subscription_id, reversal.kind.as_str());
+            return Ok(());
+        };
+
+        let subscriber_id = record["subscriber_id"].as_str().unwrap_or_default();
+        let creator_id = record["creator_id"].as_str().unwrap_or_default();
+        if status == SubscriptionStatus::Active {
+            let current_period_end = record["current_period_end"]
+                .as_str()
+                .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+                .map(|dt| dt.with_timezone(&Utc));
+            self.grant_subscription_access_for_creator(
+                subscriber_id,
+                creator_id,
+                &reversed.subscription_id,
+                current_period_end,
+            )
+            .await?;
+        } else {
+            self.revoke_subscription_access_for_creator(subscriber_id, creator_id)
+                .await?;
+        }
+
+        info!(
+            "Subscription {} is now {} after {}",
+            reversed.subscription_id, status, reversal.kind.as_str()
+        );
+        Ok(())
+    }
+
     pub async fn handle_subscription_status_update(
         &self,
         update: &StripeSubscriptionStatusUpdate,
@@ -792,11 +1088,12 @@ impl PaymentService {
         self
             .db
             .query_with_params(
-                "UPDATE article_purchase SET stripe_payment_intent_id = $intent_id, amount = $amount, currency = $currency, updated_at = time::now() WHERE id = $purchase_id",
+                "UPDATE article_purchase SET stripe_payment_intent_id = $intent_id, amount = $amount, tax_amount = $tax_amount, currency = $currency, updated_at = time::now() WHERE id = $purchase_id",
                 json!({
                     "purchase_id": purchase_id,
                     "intent_id": update.stripe_payment_intent_id,
-                    "amount": update.amount,
+                    "amount": update.amount - update.tax.amount,
+                    "tax_amount": update.tax.amount,
                     "currency": update.currency,
                 }),
             )
@@ -826,7 +1123,19 @@ impl PaymentService {
     }
 
     async fn create_purchase_from_stripe(&self, update: &StripePurchaseUpdate) -> Result<String> {
-        let purchase_id = format!("article_purchase:{}", Uuid::new_v4());
+        // 重复提交复用同一个购买记录，购买 ID 又决定了 PaymentIntent 的幂等键，
+        // 因此重试不会在 Stripe 创建第二个支付意图
+        let purchase_id = match &latest_purchase {
+            Some(pending) if pending.status == PurchaseStatus::Pending => pending.id.clone(),
+            previous => purchase_key(
+                buyer_id,
+                &request.article_id,
+                request
+                    .idempotency_key
+                    .as_deref()
+                    .or(previous.as_ref().map(|p| p.id.as_str())),
+            ),
+        };
 
         self.db
             .query_with_params(
@@ -836,6 +1145,7 @@ impl PaymentService {
                     buyer_id: $buyer_id,
                     creator_id: $creator_id,
                     amount: $amount,
+                    tax_amount: $tax_amount,
                     currency: $currency,
                     stripe_payment_intent_id: $intent_id,
                     status: 'pending',
@@ -847,7 +1157,8 @@ impl PaymentService {
                     "article_id": update.article_id,
                     "buyer_id": update.buyer_id,
                     "creator_id": update.creator_id,
-                    "amount": update.amount,
+                    "amount": update.amount - update.tax.amount,
+                    "tax_amount": update.tax.amount,
                     "currency": update.currency,
                     "intent_id": update.stripe_payment_intent_id,
                 }),
@@ -857,6 +1168,165 @@ impl PaymentService {
         Ok(purchase_id)
     }
 
+    /// 收礼人领取文章礼物：记一笔已完成的购买并开通阅读权限
+    pub async fn grant_gifted_article(&self, recipient_id: &str, gift: &Gift) -> Result<ArticlePurchase> {
+        let article_id = gift
+            .article_id
+            .as_deref()
+            .ok_or_else(|| AppError::Internal("文章礼物缺少 article_id".to_string()))?;
+
+        if let Ok(existing) = self.check_article_purchase(article_id, recipient_id).await {
+            if existing.status == PurchaseStatus::Completed {
+                return Err(AppError::BadRequest("您已经购买了这篇文章".to_string()));
+            }
+        }
+
+        let mut response = self
+            .db
+            .query_with_params(
+                "CREATE article_purchase CONTENT {
+                    id: $purchase_id,
+                    article_id: $article_id,
+                    buyer_id: $buyer_id,
+                    creator_id: $creator_id,
+                    amount: $amount,
+                    currency: $currency,
+                    stripe_payment_intent_id: NONE,
+                    status: 'completed',
+                    gift_id: $gift_id,
+                    created_at: time::now(),
+                    updated_at: time::now()
+                }",
+                json!({
+                    "purchase_id": format!("article_purchase:{}", Uuid::new_v4()),
+                    "article_id": article_id,
+                    "buyer_id": recipient_id,
+                    "creator_id": gift.creator_id,
+                    "amount": gift.amount,
+                    "currency": gift.currency,
+                    "gift_id": gift.id,
+                }),
+            )
+            .await?;
+
+        let records: Vec<Value> = response.take(0)?;
+        let purchase = records
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::Internal("Failed to create gifted purchase".to_string()))?;
+        let purchase = self.parse_article_purchase(purchase)?;
+
+        self.grant_paid_access(
+            recipient_id,
+            article_id,
+            AccessType::OneTime,
+            Some(&purchase.id),
+            None,
+        )
+        .await?;
+
+        Ok(purchase)
+    }
+
+    /// 礼物的付款被全额退款或发起争议时收回收礼人兑换的文章或订阅权限
+    pub async fn revoke_gifted_access(
+        &self,
+        gift: &Gift,
+        recipient_id: &str,
+        kind: StripeChargeReversalKind,
+    ) -> Result<()> {
+        match gift.gift_type {
+            GiftType::Article => {
+                let status = match kind {
+                    StripeChargeReversalKind::Dispute => "disputed",
+                    _ => "refunded",
+                };
+                self.db
+                    .query_with_params(
+                        r#"
+                        UPDATE article_purchase SET status = $status, updated_at = time::now() WHERE gift_id = $gift_id;
+                        DELETE paid_content_access WHERE user_id = $user_id AND article_id = $article_id AND access_type = 'one_time_purchase';
+                        "#,
+                        json!({
+                            "status": status,
+                            "gift_id": gift.id,
+                            "user_id": recipient_id,
+                            "article_id": gift.article_id,
+                        }),
+                    )
+                    .await?;
+            }
+            GiftType::Subscription => {
+                // 与订阅发票被冲回一样按未付款处理，到期任务只处理 active 的礼物订阅
+                self.db
+                    .query_with_params(
+                        "UPDATE subscription SET status = 'past_due', updated_at = time::now() WHERE gift_id = $gift_id AND status = 'active'",
+                        json!({ "gift_id": gift.id }),
+                    )
+                    .await?;
+                self.revoke_subscription_access_for_creator(recipient_id, &gift.creator_id)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 争议胜诉后恢复礼物兑换的权限；礼物订阅已经过了有效期的不再恢复
+    pub async fn restore_gifted_access(&self, gift: &Gift, recipient_id: &str) -> Result<()> {
+        match gift.gift_type {
+            GiftType::Article => {
+                let mut response = self
+                    .db
+                    .query_with_params(
+                        "UPDATE article_purchase SET status = 'completed', updated_at = time::now() WHERE gift_id = $gift_id RETURN AFTER",
+                        json!({ "gift_id": gift.id }),
+                    )
+                    .await?;
+                let records: Vec<Value> = response.take(0)?;
+                if let (Some(record), Some(article_id)) = (records.into_iter().next(), gift.article_id.as_deref()) {
+                    let purchase = self.parse_article_purchase(record)?;
+                    self.grant_paid_access(
+                        recipient_id,
+                        article_id,
+                        AccessType::OneTime,
+                        Some(&purchase.id),
+                        None,
+                    )
+                    .await?;
+                }
+            }
+            GiftType::Subscription => {
+                let mut response = self
+                    .db
+                    .query_with_params(
+                        r#"
+                        UPDATE subscription SET status = 'active', updated_at = time::now()
+                        WHERE gift_id = $gift_id AND status = 'past_due' AND current_period_end > time::now()
+                        RETURN AFTER
+                        "#,
+                        json!({ "gift_id": gift.id }),
+                    )
+                    .await?;
+                let records: Vec<Value> = response.take(0)?;
+                if let Some(record) = records.into_iter().next() {
+                    let subscription_id = record["id"].as_str().unwrap_or_default();
+                    let current_period_end = record["current_period_end"]
+                        .as_str()
+                        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+                        .map(|dt| dt.with_timezone(&Utc));
+                    self.grant_subscription_access_for_creator(
+                        recipient_id,
+                        &gift.creator_id,
+                        subscription_id,
+                        current_period_end,
+                    )
+                    .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub async fn grant_paid_access(
         &self,
         user_id: &str,
@@ -1183,6 +1653,7 @@ impl PaymentService {
             "completed" => PurchaseStatus::Completed,
             "failed" => PurchaseStatus::Failed,
             "refunded" => PurchaseStatus::Refunded,
+            "disputed" => PurchaseStatus::Disputed,
             _ => PurchaseStatus::Pending,
         };
 
@@ -1192,6 +1663,7 @@ impl PaymentService {
             buyer_id: purchase_data["buyer_id"].as_str().unwrap().to_string(),
             creator_id: purchase_data["creator_id"].as_str().unwrap().to_string(),
             amount: purchase_data["amount"].as_i64().unwrap(),
+            tax_amount: purchase_data["tax_amount"].as_i64().unwrap_or(0),
             currency: purchase_data["currency"]
                 .as_str()
                 .unwrap_or("USD")
@@ -1200,6 +1672,7 @@ impl PaymentService {
                 .as_str()
                 .map(|s| s.to_string()),
             status,
+            gift_id: purchase_data["gift_id"].as_str().map(|s| s.to_string()),
             created_at: chrono::DateTime::parse_from_rfc3339(
                 purchase_data["created_at"].as_str().unwrap(),
             )
@@ -1213,3 +1686,12 @@ impl PaymentService {
         })
     }
 }
+
+/// 同一买家、同一文章和同一个幂等种子（客户端的 `Idempotency-Key`，或上一次失败的购买）
+/// 总是得到同一个购买 ID
+fn purchase_key(buyer_id: &str, article_id: &str, seed: Option<&str>) -> String {
+    let digest = Sha256::digest(
+        format!("{}|{}|{}", buyer_id, article_id, seed.unwrap_or("initial")).as_bytes(),
+    );
+    format!("article_purchase:{}", hex::encode(&digest[..16]))
+}
@@ -0,0 +1,357 @@
+use crate::{
+    config::Config,
+    error::Result,
+    models::{article::Article, comment::Comment, plugin::*, user::UserProfile},
+};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+#[cfg(feature = "wasm-plugins")]
+use crate::error::AppError;
+
+/// 插件接口：所有钩子都有默认的空实现，插件只需覆盖关心的钩子
+#[async_trait]
+pub trait Plugin: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn on_article_published(&self, _event: &ArticlePublishedEvent) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_comment_created(&self, _event: &CommentCreatedEvent) -> Result<()> {
+        Ok(())
+    }
+
+    async fn on_user_registered(&self, _event: &UserRegisteredEvent) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// 插件管理器
+///
+/// 钩子在后台任务中执行，每个插件独立运行并受超时限制，
+/// 插件出错、超时或 panic 只会记录日志，不影响请求和其他插件。
+#[derive(Clone)]
+pub struct PluginManager {
+    plugins: Arc<Vec<Arc<dyn Plugin>>>,
+    hook_timeout: Duration,
+}
+
+impl PluginManager {
+    /// 加载编译内置的插件以及插件目录中的 WASM 模块
+    pub async fn new(config: &Config) -> Result<Self> {
+        let mut plugins: Vec<Arc<dyn Plugin>> = Vec::new();
+
+        if let Some(url) = &config.plugin_webhook_url {
+            plugins.push(Arc::new(WebhookPlugin::new(url.clone())));
+        }
+
+        if let Some(dir) = &config.plugin_dir {
+            plugins.extend(load_wasm_plugins(dir)?);
+        }
+
+        let manager = Self {
+            plugins: Arc::new(plugins),
+            hook_timeout: Duration::from_millis(config.plugin_hook_timeout_ms),
+        };
+
+        if !manager.plugins.is_empty() {
+            info!("Loaded plugins: {}", manager.plugin_names().join(", "));
+        }
+
+        Ok(manager)
+    }
+
+    /// 注册额外的插件
+    pub fn with_plugin(mut self, plugin: Arc<dyn Plugin>) -> Self {
+        Arc::make_mut(&mut self.plugins).push(plugin);
+        self
+    }
+
+    pub fn plugin_names(&self) -> Vec<String> {
+        self.plugins.iter().map(|p| p.name().to_string()).collect()
+    }
+
+    pub fn article_published(&self, article: &Article) {
+        self.dispatch(PluginEvent::ArticlePublished(article.into()));
+    }
+
+    pub fn comment_created(&self, comment: &Comment) {
+        self.dispatch(PluginEvent::CommentCreated(comment.into()));
+    }
+
+    pub fn user_registered(&self, profile: &UserProfile) {
+        self.dispatch(PluginEvent::UserRegistered(profile.into()));
+    }
+
+    fn dispatch(&self, event: PluginEvent) {
+        if self.plugins.is_empty() {
+            return;
+        }
+
+        let event = Arc::new(event);
+        for plugin in self.plugins.iter().cloned() {
+            let event = event.clone();
+            let hook_timeout = self.hook_timeout;
+
+            tokio::spawn(async move {
+                let hook = event.hook();
+                let name = plugin.name().to_string();
+                let handle = tokio::spawn(async move { call_hook(plugin.as_ref(), &event).await });
+                let abort = handle.abort_handle();
+
+                match tokio::time::timeout(hook_timeout, handle).await {
+                    Ok(Ok(Ok(()))) => debug!("Plugin {} handled {}", name, hook.as_str()),
+                    Ok(Ok(Err(e))) => warn!("Plugin {} failed in {}: {}", name, hook.as_str(), e),
+                    Ok(Err(e)) => warn!("Plugin {} panicked in {}: {}", name, hook.as_str(), e),
+                    Err(_) => {
+                        abort.abort();
+                        warn!("Plugin {} timed out in {} after {:?}", name, hook.as_str(), hook_timeout);
+                    }
+                }
+            });
+        }
+    }
+}
+
+async fn call_hook(plugin: &dyn Plugin, event: &PluginEvent) -> Result<()> {
+    match event {
+        PluginEvent::ArticlePublished(e) => plugin.on_article_published(e).await,
+        PluginEvent::CommentCreated(e) => plugin.on_comment_created(e).await,
+        PluginEvent::UserRegistered(e) => plugin.on_user_registered(e).await,
+    }
+}
+
+/// 内置插件：将所有事件以 JSON 形式 POST 到配置的地址
+pub struct WebhookPlugin {
+    url: String,
+    client: Client,
+}
+
+impl WebhookPlugin {
+    pub fn new(url: String) -> Self {
+        Self { url, client: Client::new() }
+    }
+
+    async fn post(&self, hook: PluginHook, payload: serde_json::Value) -> Result<()> {
+        self.client
+            .post(&self.url)
+            .json(&json!({ "hook": hook, "payload": payload }))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Plugin for WebhookPlugin {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    async fn on_article_published(&self, event: &ArticlePublishedEvent) -> Result<()> {
+        self.post(PluginHook::ArticlePublished, serde_json::to_value(event)?).await
+    }
+
+    async fn on_comment_created(&self, event: &CommentCreatedEvent) -> Result<()> {
+        self.post(PluginHook::CommentCreated, serde_json::to_value(event)?).await
+    }
+
+    async fn on_user_registered(&self, event: &UserRegisteredEvent) -> Result<()> {
+        self.post(PluginHook::UserRegistered, serde_json::to_value(event)?).await
+    }
+}
+
+#[cfg(not(feature = "wasm-plugins"))]
+fn load_wasm_plugins(dir: &str) -> Result<Vec<Arc<dyn Plugin>>> {
+    warn!("PLUGIN_DIR is set to {} but the wasm-plugins feature is not enabled", dir);
+    Ok(Vec::new())
+}
+
+#[cfg(feature = "wasm-plugins")]
+fn load_wasm_plugins(dir: &str) -> Result<Vec<Arc<dyn Plugin>>> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| AppError::Internal(format!("Failed to read plugin dir {}: {}", dir, e)))?;
+
+    let engine = super::wasm_runtime::engine()?;
+    let mut plugins: Vec<Arc<dyn Plugin>> = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("wasm") {
+            continue;
+        }
+        match wasm::WasmPlugin::load(&engine, &path) {
+            Ok(plugin) => plugins.push(Arc::new(plugin)),
+            Err(e) => warn!("Skipping plugin {}: {}", path.display(), e),
+        }
+    }
+
+    Ok(plugins)
+}
+
+/// WASM 插件
+///
+/// 模块需要导出 `memory`、`alloc(len: i32) -> i32`，以及任意需要的钩子函数
+/// （如 `on_article_published(ptr: i32, len: i32) -> i32`），参数为 JSON 编码的事件，
+/// 返回 0 表示成功。引擎和资源限制与内容转换共用 [`wasm_runtime`](super::wasm_runtime)。
+#[cfg(feature = "wasm-plugins")]
+mod wasm {
+    use super::*;
+    use crate::services::wasm_runtime::Call;
+    use std::path::Path;
+    use wasmtime::{Engine, Module};
+
+    /// 单次钩子调用可消耗的燃料上限
+    const FUEL_PER_CALL: u64 = 50_000_000;
+
+    pub struct WasmPlugin {
+        name: String,
+        engine: Engine,
+        module: Module,
+    }
+
+    impl WasmPlugin {
+        pub fn load(engine: &Engine, path: &Path) -> Result<Self> {
+            let module = Module::from_file(engine, path)
+                .map_err(|e| AppError::Internal(format!("Invalid WASM module: {}", e)))?;
+            let name = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("wasm")
+                .to_string();
+
+            Ok(Self { name, engine: engine.clone(), module })
+        }
+
+        async fn invoke(&self, hook: PluginHook, payload: Vec<u8>) -> Result<()> {
+            // 模块没有导出该钩子时直接跳过
+            if self.module.get_export(hook.as_str()).is_none() {
+                return Ok(());
+            }
+
+            let engine = self.engine.clone();
+            let module = self.module.clone();
+            tokio::task::spawn_blocking(move || run(&engine, &module, hook, &payload))
+                .await
+                .map_err(|e| AppError::Internal(format!("WASM plugin task failed: {}", e)))?
+        }
+    }
+
+    fn run(engine: &Engine, module: &Module, hook: PluginHook, payload: &[u8]) -> Result<()> {
+        let mut call = Call::new(engine, module, FUEL_PER_CALL)?;
+        let hook_fn = call.func::<(i32, i32), i32>(hook.as_str())?;
+        let (ptr, len) = call.write_input(payload)?;
+
+        match call.call(&hook_fn, (ptr, len))? {
+            0 => Ok(()),
+            code => Err(AppError::Internal(format!("{} returned {}", hook.as_str(), code))),
+        }
+    }
+
+    #[async_trait]
+    impl Plugin for WasmPlugin {
+        fn name(&self) -> &str {
+            &self.name
+        }
+
+        async fn on_article_published(&self, event: &ArticlePublishedEvent) -> Result<()> {
+            self.invoke(PluginHook::ArticlePublished, serde_json::to_vec(event)?).await
+        }
+
+        async fn on_comment_created(&self, event: &CommentCreatedEvent) -> Result<()> {
+            self.invoke(PluginHook::CommentCreated, serde_json::to_vec(event)?).await
+        }
+
+        async fn on_user_registered(&self, event: &UserRegisteredEvent) -> Result<()> {
+            self.invoke(PluginHook::UserRegistered, serde_json::to_vec(event)?).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Counting(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl Plugin for Counting {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        async fn on_comment_created(&self, _event: &CommentCreatedEvent) -> Result<()> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    struct Slow;
+
+    #[async_trait]
+    impl Plugin for Slow {
+        fn name(&self) -> &str {
+            "slow"
+        }
+
+        async fn on_comment_created(&self, _event: &CommentCreatedEvent) -> Result<()> {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(())
+        }
+    }
+
+    struct Panicking;
+
+    #[async_trait]
+    impl Plugin for Panicking {
+        fn name(&self) -> &str {
+            "panicking"
+        }
+
+        async fn on_comment_created(&self, _event: &CommentCreatedEvent) -> Result<()> {
+            panic!("boom");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failing_plugins_do_not_block_others() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let manager = PluginManager {
+            plugins: Arc::new(Vec::new()),
+            hook_timeout: Duration::from_millis(50),
+        }
+        .with_plugin(Arc::new(Slow))
+        .with_plugin(Arc::new(Panicking))
+        .with_plugin(Arc::new(Counting(calls.clone())));
+
+        let comment = Comment {
+            id: "comment:1".to_string(),
+            article_id: "article:1".to_string(),
+            author_id: "user-1".to_string(),
+            parent_id: None,
+            content: "Nice post".to_string(),
+            is_author_response: false,
+            clap_count: 0,
+            is_edited: false,
+            is_deleted: false,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+            deleted_at: None,
+            guest_name: None,
+            imported_from: None,
+            external_id: None,
+            moderation_status: Default::default(),
+            spam_score: None,
+        };
+        manager.comment_created(&comment);
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{
+    error::Result,
+    models::{article::*, id::ArticleId},
+    services::Database,
+};
+use chrono::Utc;
+use parking_lot::{Mutex, RwLock};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tracing::debug;
+
+const VIEW_WEIGHT: f64 = 1.0;
+const CLAP_WEIGHT: f64 = 3.0;
+/// 榜单长度
+const TOP_ARTICLES: usize = 20;
+/// 衰减到该值以下的文章从内存中移除
+const MIN_SCORE: f64 = 0.05;
+/// 榜单顺序没有变化时，至少每隔这么久推送一次最新分数
+const MAX_SNAPSHOT_AGE: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy)]
+struct DecayedScore {
+    score: f64,
+    updated_at: Instant,
+}
+
+impl DecayedScore {
+    fn value_at(&self, now: Instant, half_life: Duration) -> f64 {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.score * 0.5_f64.powf(elapsed / half_life.as_secs_f64())
+    }
+}
+
+/// 实时热度榜：浏览和点赞事件按半衰期衰减累计，榜单变化时推送给订阅者
+#[derive(Clone)]
+pub struct PopularityService {
+    db: Arc<Database>,
+    half_life: Duration,
+    scores: Arc<Mutex<HashMap<String, DecayedScore>>>,
+    /// 文章标题等展示信息，None 表示文章不可公开展示
+    articles: Arc<RwLock<HashMap<String, Option<PopularNowArticle>>>>,
+    latest: Arc<RwLock<Option<(Instant, Arc<PopularNowSnapshot>)>>>,
+    updates: broadcast::Sender<Arc<PopularNowSnapshot>>,
+}
+
+impl PopularityService {
+    pub async fn new(db: Arc<Database>, half_life_minutes: u64) -> Result<Self> {
+        let (updates, _) = broadcast::channel(16);
+
+        Ok(Self {
+            db,
+            half_life: Duration::from_secs(half_life_minutes.max(1) * 60),
+            scores: Arc::new(Mutex::new(HashMap::new())),
+            articles: Arc::new(RwLock::new(HashMap::new())),
+            latest: Arc::new(RwLock::new(None)),
+            updates,
+        })
+    }
+
+    pub fn record_view(&self, article_id: &str) {
+        self.record(article_id, VIEW_WEIGHT);
+    }
+
+    pub fn record_clap(&self, article_id: &str) {
+        self.record(article_id, CLAP_WEIGHT);
+    }
+
+    fn record(&self, article_id: &str, weight: f64) {
+        let key = ArticleId::new(article_id).as_str().to_string();
+        let now = Instant::now();
+        let mut scores = self.scores.lock();
+        let entry = scores.entry(key).or_insert(DecayedScore { score: 0.0, updated_at: now });
+        entry.score = entry.value_at(now, self.half_life) + weight;
+        entry.updated_at = now;
+    }
+
+    /// 当前榜单和后续更新
+    pub fn subscribe(&self) -> (Option<Arc<PopularNowSnapshot>>, broadcast::Receiver<Arc<PopularNowSnapshot>>) {
+        let receiver = self.updates.subscribe();
+        (self.latest(), receiver)
+    }
+
+    /// 最近一次计算的榜单
+    pub fn latest(&self) -> Option<Arc<PopularNowSnapshot>> {
+        self.latest.read().as_ref().map(|(_, snapshot)| snapshot.clone())
+    }
+
+    /// 重新计算榜单，顺序变化或上次推送过旧时推送新快照
+    pub async fn refresh(&self) -> Result<()> {
+        let now = Instant::now();
+        let mut ranked: Vec<(String, f64)> = {
+            let mut scores = self.scores.lock();
+            scores.retain(|_, score| score.value_at(now, self.half_life) >= MIN_SCORE);
+            scores
+                .iter()
+                .map(|(id, score)| (id.clone(), score.value_at(now, self.half_life)))
+                .collect()
+        };
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        self.load_missing_articles(&ranked).await?;
+
+        let articles: Vec<PopularNowArticle> = {
+            let cache = self.articles.read();
+            ranked
+                .iter()
+                .filter_map(|(id, score)| {
+                    cache.get(id).cloned().flatten().map(|article| PopularNowArticle {
+                        score: (score * 100.0).round() / 100.0,
+                        ..article
+                    })
+                })
+                .take(TOP_ARTICLES)
+                .collect()
+        };
+
+        // 清理已经不在计分表中的文章信息
+        {
+            let scores = self.scores.lock();
+            self.articles.write().retain(|id, _| scores.contains_key(id));
+        }
+
+        let changed = match self.latest.read().as_ref() {
+            Some((sent_at, previous)) => {
+                now.duration_since(*sent_at) >= MAX_SNAPSHOT_AGE
+                    || previous.articles.len() != articles.len()
+                    || previous.articles.iter().zip(&articles).any(|(a, b)| a.article_id != b.article_id)
+            }
+            None => true,
+        };
+        if !changed {
+            return Ok(());
+        }
+
+        let snapshot = Arc::new(PopularNowSnapshot {
+            generated_at: Utc::now(),
+            articles,
+        });
+        *self.latest.write() = Some((now, snapshot.clone()));
+        // 没有订阅者时发送失败，可以忽略
+        let _ = self.updates.send(snapshot);
+
+        Ok(())
+    }
+
+    async fn load_missing_articles(&self, ranked: &[(String, f64)]) -> Result<()> {
+        let missing: Vec<String> = {
+            let cache = self.articles.read();
+            ranked
+                .iter()
+                .map(|(id, _)| id)
+                .filter(|id| !cache.contains_key(*id))
+                .take(TOP_ARTICLES * 2)
+                .cloned()
+                .collect()
+        };
+
+        for id in missing {
+            let article: Option<Article> = self.db.get_by_id("article", &id).await?;
+            let entry = article.filter(|a| a.is_published()).map(|a| PopularNowArticle {
+                author_id: a.public_author_id().to_string(),
+                article_id: a.id,
+                title: a.title,
+                slug: a.slug,
+                score: 0.0,
+            });
+            debug!("Loaded popularity info for article {}", id);
+            self.articles.write().insert(id, entry);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_decays_by_half_life() {
+        let start = Instant::now();
+        let score = DecayedScore { score: 8.0, updated_at: start };
+        let half_life = Duration::from_secs(600);
+
+        assert!((score.value_at(start, half_life) - 8.0).abs() < 1e-9);
+        assert!((score.value_at(start + half_life, half_life) - 4.0).abs() < 1e-9);
+        assert!((score.value_at(start + half_life * 3, half_life) - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{
+    error::{AppError, Result},
+    models::{
+        article::Article,
+        id::{bare_id, ArticleId},
+        preview::*,
+    },
+    services::{ArticleService, Database},
+};
+use chrono::{Duration, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tracing::{debug, info};
+use validator::Validate;
+
+const TOKEN_LEN: usize = 32;
+const DEFAULT_EXPIRES_IN_HOURS: i64 = 7 * 24;
+/// 每篇文章同时有效的预览链接上限
+const MAX_ACTIVE_LINKS_PER_ARTICLE: usize = 20;
+
+/// 未发布文章的预览链接：作者生成带有效期的秘密链接分享草稿，
+/// 持有链接的人无需登录即可只读查看，作者可以随时撤销
+#[derive(Clone)]
+pub struct PreviewService {
+    db: Arc<Database>,
+    article_service: ArticleService,
+}
+
+impl PreviewService {
+    pub async fn new(db: Arc<Database>, article_service: ArticleService) -> Result<Self> {
+        Ok(Self { db, article_service })
+    }
+
+    pub async fn create_link(&self, article_id: &str, user_id: &str, request: CreatePreviewLinkRequest) -> Result<CreatedPreviewLink> {
+        request.validate()?;
+        let article = self.authored_article(article_id, user_id).await?;
+
+        if article.status.can_be_viewed_by_public() {
+            return Err(AppError::BadRequest("Published articles can be shared with their public link".to_string()));
+        }
+
+        let active = self.links_of(&article.id).await?
+            .into_iter()
+            .filter(PreviewLink::is_active)
+            .count();
+        if active >= MAX_ACTIVE_LINKS_PER_ARTICLE {
+            return Err(AppError::BadRequest(format!(
+                "An article can have at most {} active preview links, revoke one first",
+                MAX_ACTIVE_LINKS_PER_ARTICLE
+            )));
+        }
+
+        let token = random_token(TOKEN_LEN);
+        let expires_at = Utc::now() + Duration::hours(request.expires_in_hours.unwrap_or(DEFAULT_EXPIRES_IN_HOURS));
+        let label = request.label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty());
+
+        let link: PreviewLink = self.db
+            .prepare(
+                r#"
+                CREATE preview_link CONTENT {
+                    article_id: $article_id,
+                    created_by: $user_id,
+                    label: $label,
+                    token_hash: $token_hash,
+                    expires_at: <datetime> $expires_at,
+                    revoked_at: NONE,
+                    view_count: 0,
+                    last_viewed_at: NONE,
+                    created_at: time::now()
+                }
+                "#,
+            )
+            .bind("article_id", ArticleId::new(&article.id).to_record())
+            .bind("user_id", user_id)
+            .bind("label", label)
+            .bind("token_hash", hash_token(&token))
+            .bind("expires_at", expires_at)
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::Internal("Failed to create preview link".to_string()))?;
+
+        info!("Created preview link {} for article {}", link.id, article.id);
+        Ok(CreatedPreviewLink { link, token })
+    }
+
+    /// 文章的所有预览链接（包括已过期和已撤销的），最新的在前
+    pub async fn list_links(&self, article_id: &str, user_id: &str) -> Result<Vec<PreviewLink>> {
+        let article = self.authored_article(article_id, user_id).await?;
+        self.links_of(&article.id).await
+    }
+
+    pub async fn revoke_link(&self, article_id: &str, link_id: &str, user_id: &str) -> Result<PreviewLink> {
+        let article = self.authored_article(article_id, user_id).await?;
+
+        let link: PreviewLink = self.db
+            .get_by_id("preview_link", link_id)
+            .await?
+            .filter(|link: &PreviewLink| ArticleId::new(&link.article_id) == ArticleId::new(&article.id))
+            .ok_or_else(|| AppError::NotFound("Preview link not found".to_string()))?;
+        if link.revoked_at.is_some() {
+            return Ok(link);
+        }
+
+        let link: PreviewLink = self.db
+            .prepare("UPDATE type::thing('preview_link', $id) SET revoked_at = time::now() RETURN AFTER")
+            .bind("id", bare_id("preview_link", &link.id))
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::NotFound("Preview link not found".to_string()))?;
+
+        info!("Revoked preview link {} of article {}", link.id, article.id);
+        Ok(link)
+    }
+
+    /// 通过令牌读取文章并记一次浏览。无效、过期、已撤销的令牌和已删除的文章都返回 404
+    pub async fn get_preview(&self, token: &str) -> Result<ArticlePreview> {
+        let not_found = || AppError::NotFound("Preview not found or expired".to_string());
+
+        let link: PreviewLink = self.db
+            .prepare("SELECT * FROM preview_link WHERE token_hash = $token_hash LIMIT 1")
+            .bind("token_hash", hash_token(token))
+            .fetch_one()
+            .await?
+            .filter(PreviewLink::is_active)
+            .ok_or_else(not_found)?;
+
+        let article = self.article_service.get_article_by_id(&link.article_id).await?
+            .filter(|a| !a.is_deleted)
+            .ok_or_else(not_found)?;
+
+        self.db
+            .prepare("UPDATE type::thing('preview_link', $id) SET view_count += 1, last_viewed_at = time::now()")
+            .bind("id", bare_id("preview_link", &link.id))
+            .execute()
+            .await?;
+        debug!("Preview link {} viewed", link.id);
+
+        let author = self.article_service.get_public_author(&article).await?;
+        let tags = self.article_service.get_article_tags(&article.id).await?;
+
+        Ok(ArticlePreview {
+            id: article.id,
+            title: article.title,
+            subtitle: article.subtitle,
+            content_html: article.content_html,
+            cover_image_url: article.cover_image_url,
+            author,
+            tags,
+            status: article.status,
+            reading_time: article.reading_time,
+            word_count: article.word_count,
+            updated_at: article.updated_at,
+            preview_expires_at: link.expires_at,
+        })
+    }
+
+    async fn links_of(&self, article_id: &str) -> Result<Vec<PreviewLink>> {
+        self.db
+            .prepare("SELECT * FROM preview_link WHERE article_id = $article_id ORDER BY created_at DESC")
+            .bind("article_id", ArticleId::new(article_id).to_record())
+            .fetch()
+            .await
+    }
+
+    /// 只有作者本人可以管理预览链接，合著者不能把草稿分享给其他人
+    async fn authored_article(&self, article_id: &str, user_id: &str) -> Result<Article> {
+        let article = self.article_service.get_article_by_id(article_id).await?
+            .filter(|a| !a.is_deleted)
+            .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
+
+        if article.author_id != user_id {
+            return Err(AppError::Authorization("Only article author can manage preview links".to_string()));
+        }
+        Ok(article)
+    }
+}
+
+fn random_token(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
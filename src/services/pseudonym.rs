@@ -0,0 +1,287 @@
+use crate::{
+    error::{AppError, Result},
+    models::{
+        article::Article,
+        id::{bare_id, UserId},
+        pseudonym::*,
+    },
+    services::Database,
+};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use tracing::info;
+use validator::Validate;
+
+/// 每个账号最多的笔名数量
+const MAX_PSEUDONYMS_PER_OWNER: usize = 5;
+
+#[derive(Debug, Deserialize)]
+struct CountRow {
+    count: i64,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ArticleTotalsRow {
+    published_articles: i64,
+    total_views: Option<i64>,
+    total_claps: Option<i64>,
+    total_comments: Option<i64>,
+}
+
+/// 笔名发布：作者可以为单篇文章选择一个笔名，公开页面只显示笔名。
+/// 笔名和账号的对应关系只有本人可见，收益和审核仍按 `article.author_id` 处理
+#[derive(Clone)]
+pub struct PseudonymService {
+    db: Arc<Database>,
+}
+
+impl PseudonymService {
+    pub async fn new(db: Arc<Database>) -> Result<Self> {
+        Ok(Self { db })
+    }
+
+    pub async fn list(&self, owner_id: &str) -> Result<Vec<Pseudonym>> {
+        self.db
+            .prepare("SELECT * FROM pseudonym WHERE owner_id = $owner_id ORDER BY created_at ASC")
+            .bind("owner_id", UserId::new(owner_id).as_str())
+            .fetch()
+            .await
+    }
+
+    /// 不是本人的笔名一律返回 404，避免通过 ID 试探归属
+    pub async fn get(&self, owner_id: &str, pseudonym_id: &str) -> Result<Pseudonym> {
+        let pseudonym: Option<Pseudonym> = self.db
+            .get_by_id("pseudonym", bare_id("pseudonym", pseudonym_id))
+            .await?;
+        pseudonym
+            .filter(|p| p.owner_id == UserId::new(owner_id).as_str())
+            .ok_or_else(|| AppError::not_found("Pseudonym"))
+    }
+
+    pub async fn get_by_username(&self, username: &str) -> Result<Option<Pseudonym>> {
+        self.db
+            .prepare("SELECT * FROM pseudonym WHERE username = $username LIMIT 1")
+            .bind("username", username.trim().to_lowercase())
+            .fetch_one()
+            .await
+    }
+
+    pub async fn create(&self, owner_id: &str, request: CreatePseudonymRequest) -> Result<Pseudonym> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let username = request.username.trim().to_lowercase();
+        if !username.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(AppError::BadRequest(
+                "Pseudonym username may only contain letters, digits, underscores and hyphens".to_string(),
+            ));
+        }
+        if self.list(owner_id).await?.len() >= MAX_PSEUDONYMS_PER_OWNER {
+            return Err(AppError::BadRequest(format!(
+                "At most {} pseudonyms are allowed",
+                MAX_PSEUDONYMS_PER_OWNER
+            )));
+        }
+        if self.is_username_taken(&username).await? {
+            return Err(AppError::Conflict(format!("Username '{}' is already taken", username)));
+        }
+
+        let pseudonym: Option<Pseudonym> = self.db
+            .prepare(
+                r#"
+                CREATE pseudonym CONTENT {
+                    owner_id: $owner_id,
+                    username: $username,
+                    display_name: $display_name,
+                    bio: $bio ?? NONE,
+                    avatar_url: $avatar_url ?? NONE,
+                    created_at: time::now(),
+                    updated_at: time::now()
+                }
+                "#,
+            )
+            .bind("owner_id", UserId::new(owner_id).as_str())
+            .bind("username", &username)
+            .bind("display_name", request.display_name.trim())
+            .bind("bio", &request.bio)
+            .bind("avatar_url", &request.avatar_url)
+            .fetch_one()
+            .await?;
+
+        let pseudonym = pseudonym.ok_or_else(|| AppError::internal("Failed to create pseudonym"))?;
+        info!("Created pseudonym {} for user {}", pseudonym.id, owner_id);
+        Ok(pseudonym)
+    }
+
+    /// 用户名创建后不能修改，读者收藏的笔名主页链接依赖它
+    pub async fn update(&self, owner_id: &str, pseudonym_id: &str, request: UpdatePseudonymRequest) -> Result<Pseudonym> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let mut pseudonym = self.get(owner_id, pseudonym_id).await?;
+        if let Some(display_name) = request.display_name {
+            pseudonym.display_name = display_name.trim().to_string();
+        }
+        if let Some(bio) = request.bio {
+            pseudonym.bio = Some(bio).filter(|b| !b.trim().is_empty());
+        }
+        if let Some(avatar_url) = request.avatar_url {
+            pseudonym.avatar_url = Some(avatar_url);
+        }
+
+        let updated: Option<Pseudonym> = self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('pseudonym', $pseudonym_id) SET
+                    display_name = $display_name,
+                    bio = $bio ?? NONE,
+                    avatar_url = $avatar_url ?? NONE,
+                    updated_at = time::now()
+                "#,
+            )
+            .bind("pseudonym_id", bare_id("pseudonym", &pseudonym.id))
+            .bind("display_name", &pseudonym.display_name)
+            .bind("bio", &pseudonym.bio)
+            .bind("avatar_url", &pseudonym.avatar_url)
+            .fetch_one()
+            .await?;
+
+        updated.ok_or_else(|| AppError::internal("Failed to update pseudonym"))
+    }
+
+    /// 删除笔名后，已发布的文章显示为匿名作者，不会改回账号身份；草稿恢复以账号身份发布
+    pub async fn delete(&self, owner_id: &str, pseudonym_id: &str) -> Result<()> {
+        let pseudonym = self.get(owner_id, pseudonym_id).await?;
+        self.db
+            .prepare(
+                r#"
+                UPDATE article SET pseudonym_id = NONE
+                    WHERE pseudonym_id = $pseudonym_id AND published_at IS NONE;
+                DELETE type::thing('pseudonym', $pseudonym_id);
+                "#,
+            )
+            .bind("pseudonym_id", bare_id("pseudonym", &pseudonym.id))
+            .execute()
+            .await?;
+
+        info!("Deleted pseudonym {} of user {}", pseudonym.id, owner_id);
+        Ok(())
+    }
+
+    /// 设置或取消文章的笔名。只有文章作者本人可以设置，且只能在首次发布前设置：
+    /// 发布后再切换会把笔名和账号关联起来
+    pub async fn set_article_pseudonym(
+        &self,
+        article: &Article,
+        user_id: &str,
+        pseudonym_id: Option<&str>,
+    ) -> Result<()> {
+        if article.author_id != UserId::new(user_id).as_str() {
+            return Err(AppError::forbidden("Only the author can choose the byline of an article"));
+        }
+        if article.published_at.is_some() {
+            return Err(AppError::BadRequest(
+                "The byline of an article cannot be changed after it has been published".to_string(),
+            ));
+        }
+
+        let pseudonym_id = match pseudonym_id {
+            Some(id) => Some(bare_id("pseudonym", &self.get(user_id, id).await?.id).to_string()),
+            None => None,
+        };
+
+        self.db
+            .prepare("UPDATE type::thing('article', $article_id) SET pseudonym_id = $pseudonym_id ?? NONE")
+            .bind("article_id", bare_id("article", &article.id))
+            .bind("pseudonym_id", &pseudonym_id)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_public_profile(&self, username: &str) -> Result<PseudonymProfile> {
+        let pseudonym = self
+            .get_by_username(username)
+            .await?
+            .ok_or_else(|| AppError::not_found("Pseudonym"))?;
+
+        let count: Option<CountRow> = self.db
+            .prepare(
+                r#"
+                SELECT count() AS count FROM article
+                WHERE pseudonym_id = $pseudonym_id AND status = 'published' AND is_deleted = false
+                GROUP ALL
+                "#,
+            )
+            .bind("pseudonym_id", bare_id("pseudonym", &pseudonym.id))
+            .fetch_one()
+            .await?;
+
+        Ok(pseudonym.public_profile(count.map_or(0, |c| c.count)))
+    }
+
+    /// 笔名文章的累计数据和最近 N 天的浏览，只对笔名所有者开放
+    pub async fn get_stats(&self, owner_id: &str, pseudonym_id: &str, query: PseudonymStatsQuery) -> Result<PseudonymStats> {
+        let pseudonym = self.get(owner_id, pseudonym_id).await?;
+        let pseudonym_id = bare_id("pseudonym", &pseudonym.id).to_string();
+        let days = query.days.unwrap_or(30).clamp(1, 365);
+
+        let mut response = self.db.query_with_params(
+            r#"
+                LET $articles = (
+                    SELECT VALUE meta::id(id) FROM article
+                    WHERE pseudonym_id = $pseudonym_id AND status = 'published' AND is_deleted = false
+                );
+                SELECT
+                    count() AS published_articles,
+                    math::sum(view_count) AS total_views,
+                    math::sum(clap_count) AS total_claps,
+                    math::sum(comment_count) AS total_comments
+                FROM article
+                WHERE pseudonym_id = $pseudonym_id AND status = 'published' AND is_deleted = false
+                GROUP ALL;
+                SELECT count() AS count FROM article_view
+                    WHERE article_id INSIDE $articles AND created_at >= $since
+                    GROUP ALL;
+                SELECT count() AS count FROM (
+                    SELECT viewer_hash FROM article_view
+                        WHERE article_id INSIDE $articles AND created_at >= $since
+                        GROUP BY viewer_hash
+                ) GROUP ALL;
+            "#,
+            json!({
+                "pseudonym_id": pseudonym_id,
+                "since": Utc::now() - Duration::days(days),
+            }),
+        ).await?;
+        let totals: Vec<ArticleTotalsRow> = response.take(1)?;
+        let views: Vec<CountRow> = response.take(2)?;
+        let viewers: Vec<CountRow> = response.take(3)?;
+        let totals = totals.into_iter().next().unwrap_or_default();
+
+        Ok(PseudonymStats {
+            pseudonym_id,
+            days,
+            published_articles: totals.published_articles,
+            total_views: totals.total_views.unwrap_or(0),
+            total_claps: totals.total_claps.unwrap_or(0),
+            total_comments: totals.total_comments.unwrap_or(0),
+            views: views.first().map_or(0, |r| r.count),
+            unique_viewers: viewers.first().map_or(0, |r| r.count),
+        })
+    }
+
+    /// 笔名和用户名共用一个命名空间，否则笔名主页和个人主页的地址会冲突
+    async fn is_username_taken(&self, username: &str) -> Result<bool> {
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT count() AS count FROM pseudonym WHERE username = $username GROUP ALL;
+                SELECT count() AS count FROM user_profile WHERE username = $username GROUP ALL;
+            "#,
+            json!({ "username": username }),
+        ).await?;
+        let pseudonyms: Vec<CountRow> = response.take(0)?;
+        let users: Vec<CountRow> = response.take(1)?;
+        Ok(pseudonyms.first().map_or(0, |r| r.count) + users.first().map_or(0, |r| r.count) > 0)
+    }
+}
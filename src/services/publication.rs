@@ -1,27 +1,57 @@
 use crate::{
-    error::{AppError, Result},
+    config::Config,
+    error::{AppError, ErrorCode, Result},
     models::{
+        id::bare_id,
         publication::*,
         article::{Article, ArticleListItem, ArticleStatus},
     },
-    services::Database,
+    services::{auth::User, response_cache::CacheNamespace, AuthService, Database, EmailService, ResponseCacheService},
     utils::slug,
 };
-use chrono::Utc;
+use chrono::{DateTime, Duration, Utc};
+use rand::{distributions::Alphanumeric, Rng};
 use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 use validator::Validate;
 
+/// 邀请默认有效期（天）
+const DEFAULT_INVITE_DAYS: i64 = 7;
+/// 导出关注者时，国家取自这段时间内的阅读会话
+const EXPORT_COUNTRY_LOOKBACK_DAYS: i64 = 90;
+const INVITE_TOKEN_LEN: usize = 40;
+
+/// 成员查询返回的字段，记录 ID 转成字符串
+const MEMBER_FIELDS: &str = "type::string(id) AS id, type::string(publication_id) AS publication_id, user_id, role, permissions, joined_at, is_active";
+const INVITE_FIELDS: &str = "meta::id(id) AS id, publication_id, email, user_id, role, invited_by, expires_at, accepted_at, created_at";
+
 #[derive(Clone)]
 pub struct PublicationService {
     db: Arc<Database>,
+    config: Config,
+    email_service: EmailService,
+    response_cache: ResponseCacheService,
+    auth_service: AuthService,
 }
 
 impl PublicationService {
-    pub async fn new(db: Arc<Database>) -> Result<Self> {
-        Ok(Self { db })
+    pub async fn new(
+        db: Arc<Database>,
+        config: &Config,
+        response_cache: ResponseCacheService,
+        auth_service: AuthService,
+    ) -> Result<Self> {
+        Ok(Self {
+            db,
+            config: config.clone(),
+            email_service: EmailService::new(config)?,
+            response_cache,
+            auth_service,
+        })
     }
 
     /// 创建出版物
@@ -75,6 +105,7 @@ impl PublicationService {
                 follower_count = 0,
                 is_verified = false,
                 is_suspended = false,
+                require_two_factor = false,
                 created_at = time::now(),
                 updated_at = time::now();
 
@@ -82,7 +113,7 @@ impl PublicationService {
                    name, slug, description, tagline, logo_url, cover_image_url,
                    owner_id, homepage_layout, theme_color, custom_domain,
                    member_count, article_count, follower_count, is_verified, is_suspended,
-                   created_at, updated_at
+                   require_two_factor, created_at, updated_at
             FROM publication
             WHERE id = type::thing('publication', $id);
         "#;
@@ -123,6 +154,27 @@ impl PublicationService {
     ) -> Result<Option<PublicationResponse>> {
         debug!("Getting publication: {}", slug);
 
+        // 主页内容对所有读者相同，走响应缓存；成员和关注状态按读者单独查询
+        let response = self
+            .response_cache
+            .get_or_load(CacheNamespace::Publication, slug, "", || self.load_publication_home(slug))
+            .await?;
+        let Some(mut response) = response else {
+            return Ok(None);
+        };
+
+        if let Some(uid) = user_id {
+            let member_info = self.get_member_info(&response.publication.id, uid).await?;
+            response.is_following = self.is_following_publication(&response.publication.id, uid).await?;
+            response.is_member = member_info.is_some();
+            response.member_role = member_info.map(|m| m.role);
+        }
+
+        Ok(Some(response))
+    }
+
+    /// 出版物主页中与读者无关的部分：出版物本身和最近文章
+    async fn load_publication_home(&self, slug: &str) -> Result<Option<PublicationResponse>> {
         // 使用显式查询并将 id 转换为字符串，避免 Surreal record -> String 反序列化问题
         let query = r#"
             SELECT 
@@ -145,31 +197,46 @@ impl PublicationService {
             None => return Ok(None),
         };
 
-        // 获取用户相关信息
-        let (is_member, member_role, is_following) = if let Some(uid) = user_id {
-            let member_info = self.get_member_info(&publication.id, uid).await?;
-            let following = self.is_following_publication(&publication.id, uid).await?;
-            (
-                member_info.is_some(),
-                member_info.map(|m| m.role),
-                following,
-            )
-        } else {
-            (false, None, false)
-        };
-
         // 获取最近文章
         let recent_articles = self.get_publication_recent_articles(&publication.id, 5).await?;
 
-        let response = PublicationResponse {
+        Ok(Some(PublicationResponse {
             publication,
-            is_member,
-            member_role,
-            is_following,
+            is_member: false,
+            member_role: None,
+            is_following: false,
             recent_articles,
-        };
+        }))
+    }
 
-        Ok(Some(response))
+    /// 批量获取出版物，按不带表名的出版物 ID 返回；被暂停的出版物不在结果中
+    pub async fn get_publications_by_ids(&self, publication_ids: &[String]) -> Result<HashMap<String, Publication>> {
+        if publication_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let ids: Vec<&str> = publication_ids.iter().map(|id| bare_id("publication", id)).collect();
+        let publications: Vec<Publication> = self.db
+            .prepare(
+                r#"
+                SELECT
+                    type::string(id) AS id,
+                    name, slug, description, tagline, logo_url, cover_image_url,
+                    owner_id, homepage_layout, theme_color, custom_domain,
+                    member_count, article_count, follower_count,
+                    is_verified, is_suspended,
+                    created_at, updated_at
+                FROM publication
+                WHERE meta::id(id) INSIDE $ids AND is_suspended = false
+                "#,
+            )
+            .bind("ids", &ids)
+            .fetch()
+            .await?;
+
+        Ok(publications.into_iter()
+            .map(|p| (bare_id("publication", &p.id).to_string(), p))
+            .collect())
     }
 
     /// 更新出版物
@@ -188,6 +255,7 @@ impl PublicationService {
 
         let mut publication: Publication = self.db.get_by_id("publication", publication_id).await?
             .ok_or_else(|| AppError::NotFound("Publication not found".to_string()))?;
+        let previous_slug = publication.slug.clone();
 
         // 更新字段
         if let Some(name) = request.name {
@@ -232,11 +300,21 @@ impl PublicationService {
             publication.custom_domain = Some(custom_domain);
         }
 
+        if let Some(require_two_factor) = request.require_two_factor {
+            publication.require_two_factor = require_two_factor;
+        }
+
         publication.updated_at = Utc::now();
 
         let updated: Publication = self.db.update_by_id("publication", publication_id, publication).await?
             .ok_or_else(|| AppError::internal("Failed to update publication"))?;
 
+        // 改名会生成新 slug，新旧 slug 的缓存都要清除
+        self.response_cache.invalidate(CacheNamespace::Publication, &previous_slug).await;
+        if updated.slug != previous_slug {
+            self.response_cache.invalidate(CacheNamespace::Publication, &updated.slug).await;
+        }
+
         Ok(updated)
     }
 
@@ -250,7 +328,7 @@ impl PublicationService {
 
         // 只有Owner可以删除出版物
         let member = self.get_member_info(publication_id, user_id).await?
-            .ok_or_else(|| AppError::forbidden("You are not a member of this publication"))?;
+            .ok_or_else(|| AppError::coded(ErrorCode::NotPublicationMember, "You are not a member of this publication"))?;
 
         if member.role != MemberRole::Owner {
             return Err(AppError::forbidden("Only publication owner can delete the publication"));
@@ -262,7 +340,10 @@ impl PublicationService {
             "updated_at": Utc::now()
         });
 
-        self.db.update_by_id_with_json::<Value>("publication", publication_id, updates).await?;
+        let deleted: Option<Publication> = self.db.update_by_id_with_json("publication", publication_id, updates).await?;
+        if let Some(publication) = deleted {
+            self.response_cache.invalidate(CacheNamespace::Publication, &publication.slug).await;
+        }
 
         info!("Deleted publication: {}", publication_id);
         Ok(())
@@ -358,12 +439,12 @@ impl PublicationService {
         let data_query = r#"
             SELECT id, title, subtitle, slug, excerpt, cover_image_url, author_id, 
                    reading_time, view_count, clap_count, comment_count, bookmark_count,
-                   tags, created_at, published_at
+                   tags, created_at, published_at, publication_pinned_at
             FROM article 
             WHERE publication_id = $publication_id 
             AND status = 'published' 
             AND is_deleted = false
-            ORDER BY published_at DESC, created_at DESC
+            ORDER BY publication_pinned_at DESC, published_at DESC, created_at DESC
             LIMIT $limit START $offset
         "#;
 
@@ -402,7 +483,8 @@ impl PublicationService {
         request.validate().map_err(|e| AppError::ValidatorError(e))?;
 
         // 检查权限
-        self.check_permission(publication_id, requester_id, "publication.manage_members").await?;
+        let requester = self.require_member_permission(publication_id, requester_id, "publication.manage_members").await?;
+        ensure_can_assign(&requester, &request.role)?;
 
         // 检查用户是否已经是成员
         if self.get_member_info(publication_id, &request.user_id).await?.is_some() {
@@ -430,17 +512,19 @@ impl PublicationService {
         request.validate().map_err(|e| AppError::ValidatorError(e))?;
 
         // 检查权限
-        self.check_permission(publication_id, requester_id, "publication.manage_members").await?;
+        let requester = self.require_member_permission(publication_id, requester_id, "publication.manage_members").await?;
 
         let mut member = self.get_member_info(publication_id, member_user_id).await?
             .ok_or_else(|| AppError::NotFound("Member not found".to_string()))?;
 
-        // 不能修改Owner的角色（除非Owner自己修改）
-        if member.role == MemberRole::Owner && requester_id != member_user_id {
-            return Err(AppError::forbidden("Cannot modify owner role"));
-        }
+        // 只能修改级别比自己低的成员（Owner 可以修改自己）
+        ensure_can_manage(&requester, &member)?;
 
         if let Some(role) = request.role {
+            ensure_can_assign(&requester, &role)?;
+            if member.role == MemberRole::Owner && role != MemberRole::Owner {
+                self.ensure_other_owner(publication_id).await?;
+            }
             member.role = role;
             member.permissions = member.role.default_permissions();
         }
@@ -453,10 +537,23 @@ impl PublicationService {
             member.is_active = is_active;
         }
 
-        let member_id = member.id.clone();
-        let updated: PublicationMember = self.db.update_by_id("publication_member", &member_id, member).await?
+        let query = format!(
+            "UPDATE type::thing('publication_member', $id) SET role = $role, permissions = $permissions, is_active = $is_active, updated_at = time::now() RETURN {}",
+            MEMBER_FIELDS
+        );
+        let updated: PublicationMember = self.db
+            .prepare(&query)
+            .bind("id", bare_id("publication_member", &member.id))
+            .bind("role", member.role.as_str())
+            .bind("permissions", &member.permissions)
+            .bind("is_active", member.is_active)
+            .fetch_one()
+            .await?
             .ok_or_else(|| AppError::internal("Failed to update member"))?;
 
+        // 停用或恢复成员会改变成员数
+        self.update_member_count(publication_id).await?;
+
         Ok(updated)
     }
 
@@ -469,26 +566,20 @@ impl PublicationService {
     ) -> Result<()> {
         debug!("Removing member {} from publication: {}", member_user_id, publication_id);
 
-        // 检查权限
-        self.check_permission(publication_id, requester_id, "publication.manage_members").await?;
-
         let member = self.get_member_info(publication_id, member_user_id).await?
             .ok_or_else(|| AppError::NotFound("Member not found".to_string()))?;
 
-        // 不能移除Owner（除非Owner自己离开）
-        if member.role == MemberRole::Owner && requester_id != member_user_id {
-            return Err(AppError::forbidden("Cannot remove owner"));
+        // 成员可以自己退出，移除别人需要管理成员的权限且级别更高
+        if requester_id != member_user_id {
+            let requester = self.require_member_permission(publication_id, requester_id, "publication.manage_members").await?;
+            ensure_can_manage(&requester, &member)?;
         }
 
-        // 如果是Owner要离开，检查是否有其他Owner
-        if member.role == MemberRole::Owner && requester_id == member_user_id {
-            let other_owners_count = self.count_members_by_role(publication_id, MemberRole::Owner).await?;
-            if other_owners_count <= 1 {
-                return Err(AppError::bad_request("Publication must have at least one owner"));
-            }
+        if member.role == MemberRole::Owner {
+            self.ensure_other_owner(publication_id).await?;
         }
 
-        self.db.delete_by_id("publication_member", &member.id).await?;
+        self.db.delete_by_id("publication_member", bare_id("publication_member", &member.id)).await?;
 
         // 更新成员数量
         self.update_member_count(publication_id).await?;
@@ -511,20 +602,24 @@ impl PublicationService {
         let count_query = r#"
             SELECT count() AS total 
             FROM publication_member 
-            WHERE publication_id = $publication_id 
+            WHERE publication_id = type::thing('publication', $publication_id) 
             AND is_active = true
+            GROUP ALL
         "#;
 
-        let data_query = r#"
-            SELECT * FROM publication_member 
-            WHERE publication_id = $publication_id 
+        let data_query = format!(
+            r#"
+            SELECT {} FROM publication_member 
+            WHERE publication_id = type::thing('publication', $publication_id) 
             AND is_active = true
             ORDER BY joined_at ASC
             LIMIT $limit START $offset
-        "#;
+            "#,
+            MEMBER_FIELDS
+        );
 
         let params = json!({
-            "publication_id": publication_id,
+            "publication_id": bare_id("publication", publication_id),
             "limit": limit,
             "offset": offset
         });
@@ -534,7 +629,7 @@ impl PublicationService {
             result.get("total").and_then(|v| v.as_i64()).unwrap_or(0) as usize
         } else { 0 };
 
-        let mut data_response = self.db.query_with_params(data_query, params).await?;
+        let mut data_response = self.db.query_with_params(&data_query, params).await?;
         let members: Vec<PublicationMember> = data_response.take(0)?;
 
         Ok(crate::services::database::PaginatedResult {
@@ -546,6 +641,176 @@ impl PublicationService {
         })
     }
 
+    /// 邀请成员。按邮箱邀请时发送邀请邮件，按用户邀请时发到该用户的邮箱（如果有）。
+    /// 同一个人尚未接受的旧邀请会被新邀请替换
+    pub async fn create_invite(
+        &self,
+        publication_id: &str,
+        requester_id: &str,
+        request: CreateInviteRequest,
+    ) -> Result<CreatedInvite> {
+        debug!("Inviting member to publication: {}", publication_id);
+
+        request.validate().map_err(AppError::ValidatorError)?;
+        if request.email.is_none() == request.user_id.is_none() {
+            return Err(AppError::bad_request("Provide either email or user_id"));
+        }
+
+        let requester = self.require_member_permission(publication_id, requester_id, "publication.manage_members").await?;
+        ensure_can_assign(&requester, &request.role)?;
+
+        let publication_id = bare_id("publication", publication_id);
+        let email = request.email.as_deref().map(|email| email.trim().to_lowercase());
+        if let Some(user_id) = &request.user_id {
+            if self.get_member_info(publication_id, user_id).await?.is_some() {
+                return Err(AppError::Conflict("User is already a member".to_string()));
+            }
+        }
+
+        let token = generate_invite_token();
+        let expires_at = Utc::now() + Duration::days(request.expires_in_days.unwrap_or(DEFAULT_INVITE_DAYS));
+        let query = format!(
+            r#"
+            DELETE publication_invite
+                WHERE publication_id = $publication_id
+                AND accepted_at IS NONE
+                AND (($email AND email = $email) OR ($user_id AND user_id = $user_id));
+            CREATE publication_invite CONTENT {{
+                publication_id: $publication_id,
+                email: $email ?? NONE,
+                user_id: $user_id ?? NONE,
+                role: $role,
+                token_hash: $token_hash,
+                invited_by: $invited_by,
+                expires_at: <datetime> $expires_at
+            }} RETURN {};
+            "#,
+            INVITE_FIELDS
+        );
+        let mut response = self.db.query_with_params(&query, json!({
+            "publication_id": publication_id,
+            "email": email,
+            "user_id": request.user_id,
+            "role": request.role.as_str(),
+            "token_hash": hash_invite_token(&token),
+            "invited_by": requester_id,
+            "expires_at": expires_at,
+        })).await?;
+        let invite: PublicationInvite = response
+            .take::<Vec<PublicationInvite>>(1)?
+            .pop()
+            .ok_or_else(|| AppError::internal("Failed to create invite"))?;
+
+        let accept_url = format!("{}/publications/invites/accept?token={}", self.config.frontend_url, token);
+        let recipient = match (&invite.email, &invite.user_id) {
+            (Some(email), _) => Some(email.clone()),
+            (None, Some(user_id)) => self.get_user_email(user_id).await.unwrap_or_else(|e| {
+                warn!("Failed to resolve email of invitee {}: {}", user_id, e);
+                None
+            }),
+            (None, None) => None,
+        };
+        if let Some(to) = recipient {
+            // 邮件发送失败不影响邀请本身，邀请人仍可以把链接发给对方
+            if let Err(e) = self.send_invite_email(&to, publication_id, &invite, &accept_url).await {
+                warn!("Failed to send publication invite {} to {}: {}", invite.id, to, e);
+            }
+        }
+
+        info!("Created invite {} for publication {} by user {}", invite.id, publication_id, requester_id);
+        Ok(CreatedInvite { invite, token, accept_url })
+    }
+
+    /// 尚未接受且未过期的邀请
+    pub async fn get_invites(&self, publication_id: &str, requester_id: &str) -> Result<Vec<PublicationInvite>> {
+        self.check_permission(publication_id, requester_id, "publication.manage_members").await?;
+
+        let query = format!(
+            r#"
+            SELECT {} FROM publication_invite
+            WHERE publication_id = $publication_id
+            AND accepted_at IS NONE
+            AND expires_at > time::now()
+            ORDER BY created_at DESC
+            "#,
+            INVITE_FIELDS
+        );
+        self.db
+            .prepare(&query)
+            .bind("publication_id", bare_id("publication", publication_id))
+            .fetch()
+            .await
+    }
+
+    /// 撤销邀请
+    pub async fn revoke_invite(&self, publication_id: &str, invite_id: &str, requester_id: &str) -> Result<()> {
+        self.check_permission(publication_id, requester_id, "publication.manage_members").await?;
+
+        let deleted: Option<Value> = self.db
+            .prepare(
+                "DELETE type::thing('publication_invite', $invite_id)
+                 WHERE publication_id = $publication_id AND accepted_at IS NONE
+                 RETURN BEFORE",
+            )
+            .bind("invite_id", bare_id("publication_invite", invite_id))
+            .bind("publication_id", bare_id("publication", publication_id))
+            .fetch_one()
+            .await?;
+        if deleted.is_none() {
+            return Err(AppError::not_found("Invite"));
+        }
+
+        info!("Revoked invite {} for publication {}", invite_id, publication_id);
+        Ok(())
+    }
+
+    /// 接受邀请。指定了用户或邮箱的邀请只能由对应的用户接受
+    pub async fn accept_invite(
+        &self,
+        user_id: &str,
+        user_email: &str,
+        request: AcceptInviteRequest,
+    ) -> Result<PublicationMember> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let query = format!("SELECT {} FROM publication_invite WHERE token_hash = $token_hash LIMIT 1", INVITE_FIELDS);
+        let invite: PublicationInvite = self.db
+            .prepare(&query)
+            .bind("token_hash", hash_invite_token(request.token.trim()))
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::not_found("Invite"))?;
+
+        if invite.accepted_at.is_some() || invite.expires_at <= Utc::now() {
+            return Err(AppError::coded(ErrorCode::InviteExpired, "This invite has expired or has already been used"));
+        }
+        let addressed_to_user = match (&invite.user_id, &invite.email) {
+            (Some(invited_user), _) => invited_user == user_id,
+            (None, Some(email)) => email.eq_ignore_ascii_case(user_email),
+            (None, None) => true,
+        };
+        if !addressed_to_user {
+            return Err(AppError::forbidden("This invite was sent to someone else"));
+        }
+        if self.get_member_info(&invite.publication_id, user_id).await?.is_some() {
+            return Err(AppError::Conflict("You are already a member of this publication".to_string()));
+        }
+
+        let member = self
+            .add_member_internal(&invite.publication_id, user_id, invite.role.clone(), &invite.invited_by)
+            .await?;
+        self.db
+            .prepare("UPDATE type::thing('publication_invite', $invite_id) SET accepted_at = time::now(), accepted_by = $user_id")
+            .bind("invite_id", &invite.id)
+            .bind("user_id", user_id)
+            .execute()
+            .await?;
+        self.update_member_count(&invite.publication_id).await?;
+
+        info!("User {} joined publication {} as {}", user_id, invite.publication_id, invite.role.as_str());
+        Ok(member)
+    }
+
     /// 关注出版物
     pub async fn follow_publication(
         &self,
@@ -605,6 +870,186 @@ impl PublicationService {
         Ok(())
     }
 
+    /// 获取出版物受众洞察（关注者增长、地区和语言分布）
+    pub async fn get_audience_insights(
+        &self,
+        publication_id: &str,
+        user_id: &str,
+        days: i64,
+    ) -> Result<PublicationAudienceInsights> {
+        debug!("Getting audience insights for publication: {}", publication_id);
+
+        let publication_id = normalize_publication_id(publication_id);
+        self.check_audience_access(&publication_id, user_id).await?;
+
+        let days = days.clamp(1, 365);
+        let follows = self.get_publication_follows(&publication_id).await?;
+        let now = Utc::now();
+        // 曲线从第一天的零点开始，累计值以此刻的关注者数为起点
+        let range_start = (now - chrono::Duration::days(days - 1))
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+            .and_utc();
+
+        // 按天统计新增关注者
+        let mut before_window = 0i64;
+        let mut daily: BTreeMap<String, i64> = BTreeMap::new();
+        for (_, followed_at) in &follows {
+            if *followed_at < range_start {
+                before_window += 1;
+            } else {
+                *daily.entry(followed_at.format("%Y-%m-%d").to_string()).or_insert(0) += 1;
+            }
+        }
+
+        let mut running = before_window;
+        let mut growth = Vec::with_capacity(days as usize);
+        for offset in (0..days).rev() {
+            let date = (now - chrono::Duration::days(offset)).format("%Y-%m-%d").to_string();
+            let new_followers = daily.get(&date).copied().unwrap_or(0);
+            running += new_followers;
+            growth.push(FollowerGrowthPoint {
+                date,
+                new_followers,
+                total_followers: running,
+            });
+        }
+
+        // 分布数据只使用同意受众洞察的关注者
+        let follower_ids: Vec<String> = follows.iter().map(|(id, _)| id.clone()).collect();
+        let consenting = self.get_consenting_profiles(&follower_ids).await?;
+        let countries_by_user = self.get_latest_countries(
+            &consenting.iter().map(|p| p.0.clone()).collect::<Vec<_>>(),
+            range_start,
+        ).await?;
+
+        let mut countries: HashMap<String, i64> = HashMap::new();
+        let mut languages: HashMap<String, i64> = HashMap::new();
+        for (uid, language) in &consenting {
+            let country = countries_by_user.get(uid).cloned().unwrap_or_else(|| "unknown".to_string());
+            *countries.entry(country).or_insert(0) += 1;
+            let language = language.clone().unwrap_or_else(|| "unknown".to_string());
+            *languages.entry(language).or_insert(0) += 1;
+        }
+
+        let consenting_followers = consenting.len() as i64;
+        Ok(PublicationAudienceInsights {
+            publication_id,
+            total_followers: follows.len() as i64,
+            new_followers: follows.len() as i64 - before_window,
+            consenting_followers,
+            growth,
+            countries: build_breakdown(countries, consenting_followers),
+            languages: build_breakdown(languages, consenting_followers),
+        })
+    }
+
+    /// 获取最近活跃的关注者（仅包含同意受众洞察的用户）
+    pub async fn get_recently_active_followers(
+        &self,
+        publication_id: &str,
+        user_id: &str,
+        limit: usize,
+    ) -> Result<Vec<PublicationFollowerInfo>> {
+        let publication_id = normalize_publication_id(publication_id);
+        self.check_audience_access(&publication_id, user_id).await?;
+
+        let follows = self.get_publication_follows(&publication_id).await?;
+        let follower_ids: Vec<String> = follows.iter().map(|(id, _)| id.clone()).collect();
+        let consenting: Vec<String> = self.get_consenting_profiles(&follower_ids).await?
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+
+        if consenting.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let query = r#"
+            SELECT user_id, math::max(created_at) AS last_active_at
+            FROM activity_log
+            WHERE user_id INSIDE $user_ids
+            GROUP BY user_id
+        "#;
+        let mut response = self.db.query_with_params(query, json!({ "user_ids": consenting })).await?;
+        let activity: Vec<Value> = response.take(0)?;
+
+        let mut last_active: Vec<(String, DateTime<Utc>)> = activity
+            .into_iter()
+            .filter_map(|row| {
+                let uid = row.get("user_id")?.as_str()?.to_string();
+                let at = row.get("last_active_at")
+                    .and_then(|v| serde_json::from_value::<DateTime<Utc>>(v.clone()).ok())?;
+                Some((uid, at))
+            })
+            .collect();
+        last_active.sort_by(|a, b| b.1.cmp(&a.1));
+        last_active.truncate(limit.clamp(1, 100));
+
+        let followed_at: HashMap<String, DateTime<Utc>> = follows.into_iter().collect();
+        let profiles = self.get_follower_profiles(
+            &last_active.iter().map(|(uid, _)| uid.clone()).collect::<Vec<_>>(),
+        ).await?;
+        let empty = json!({});
+        let mut result = Vec::with_capacity(last_active.len());
+        for (uid, at) in last_active {
+            let profile = profiles.get(&uid).unwrap_or(&empty);
+            result.push(PublicationFollowerInfo {
+                username: profile.get("username").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                display_name: profile.get("display_name").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                avatar_url: profile.get("avatar_url").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                followed_at: followed_at.get(&uid).copied().unwrap_or_else(Utc::now),
+                last_active_at: Some(at),
+                user_id: uid,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// 导出关注者列表，未同意受众洞察的关注者不会出现在导出中
+    pub async fn export_followers(
+        &self,
+        publication_id: &str,
+        user_id: &str,
+    ) -> Result<Vec<FollowerExportRow>> {
+        let publication_id = normalize_publication_id(publication_id);
+        self.check_audience_access(&publication_id, user_id).await?;
+
+        let follows = self.get_publication_follows(&publication_id).await?;
+        let follower_ids: Vec<String> = follows.iter().map(|(id, _)| id.clone()).collect();
+        let consenting = self.get_consenting_profiles(&follower_ids).await?;
+        let consenting_ids: Vec<String> = consenting.iter().map(|p| p.0.clone()).collect();
+        let countries = self.get_latest_countries(
+            &consenting_ids,
+            Utc::now() - chrono::Duration::days(EXPORT_COUNTRY_LOOKBACK_DAYS),
+        ).await?;
+        let profiles = self.get_follower_profiles(&consenting_ids).await?;
+        let languages: HashMap<String, Option<String>> = consenting.into_iter().collect();
+
+        let empty = json!({});
+        let mut rows = Vec::with_capacity(languages.len());
+        for (uid, followed_at) in follows {
+            let language = match languages.get(&uid) {
+                Some(language) => language.clone(),
+                None => continue,
+            };
+            let profile = profiles.get(&uid).unwrap_or(&empty);
+            rows.push(FollowerExportRow {
+                username: profile.get("username").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                display_name: profile.get("display_name").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                followed_at,
+                country: countries.get(&uid).cloned(),
+                language,
+                user_id: uid,
+            });
+        }
+
+        info!("Exported {} followers for publication {} by user {}", rows.len(), publication_id, user_id);
+        Ok(rows)
+    }
+
     // Helper methods
 
     async fn generate_unique_slug(&self, name: &str) -> Result<String> {
@@ -634,12 +1079,6 @@ impl PublicationService {
         // 使用 SQL 显式设置 joined_at 为 time::now()，避免时间类型不匹配
         let id = Uuid::new_v4().to_string();
         let permissions = role.default_permissions();
-        let role_str = match role {
-            MemberRole::Owner => "owner",
-            MemberRole::Editor => "editor",
-            MemberRole::Writer => "writer",
-            MemberRole::Contributor => "writer",
-        };
 
         let sql = r#"
             CREATE publication_member CONTENT {
@@ -654,7 +1093,7 @@ impl PublicationService {
                 type::string(id) AS id,
                 type::string(publication_id) AS publication_id,
                 user_id,
-                IF role = 'owner' THEN 'Owner' ELSE IF role = 'editor' THEN 'Editor' ELSE 'Writer' END AS role,
+                role,
                 permissions,
                 joined_at,
                 is_active;
@@ -664,7 +1103,7 @@ impl PublicationService {
             "id": id,
             "publication_id": publication_id,
             "user_id": user_id,
-            "role": role_str,
+            "role": role.as_str(),
             "permissions": permissions,
             "invited_by": invited_by,
         });
@@ -678,44 +1117,269 @@ impl PublicationService {
         Ok(created)
     }
 
+    async fn get_user_email(&self, user_id: &str) -> Result<Option<String>> {
+        self.auth_service.get_user_email(user_id).await
+    }
+
+    async fn send_invite_email(
+        &self,
+        to: &str,
+        publication_id: &str,
+        invite: &PublicationInvite,
+        accept_url: &str,
+    ) -> Result<()> {
+        let name: String = self.db
+            .prepare("SELECT VALUE name FROM type::thing('publication', $publication_id)")
+            .bind("publication_id", publication_id)
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::not_found("Publication"))?;
+
+        let subject = format!("You're invited to join {}", name);
+        let expires = invite.expires_at.format("%Y-%m-%d");
+        let text = format!(
+            "You have been invited to join {} as {}.\n\nAccept the invite: {}\n\nThis link expires on {}.",
+            name,
+            invite.role.as_str(),
+            accept_url,
+            expires
+        );
+        let html = format!(
+            "<p>You have been invited to join <strong>{}</strong> as {}.</p><p><a href=\"{}\">Accept the invite</a></p><p>This link expires on {}.</p>",
+            html_escape(&name),
+            invite.role.as_str(),
+            html_escape(accept_url),
+            expires
+        );
+        self.email_service.send(to, &subject, text, html).await
+    }
+
     async fn get_member_info(
         &self,
         publication_id: &str,
         user_id: &str,
     ) -> Result<Option<PublicationMember>> {
-        let query = r#"
-            SELECT * FROM publication_member 
-            WHERE publication_id = $publication_id 
+        let query = format!(
+            r#"
+            SELECT {} FROM publication_member 
+            WHERE publication_id = type::thing('publication', $publication_id) 
             AND user_id = $user_id 
             AND is_active = true
             LIMIT 1
-        "#;
+            "#,
+            MEMBER_FIELDS
+        );
 
-        let mut response = self.db.query_with_params(query, json!({
-            "publication_id": publication_id,
-            "user_id": user_id
-        })).await?;
+        self.db
+            .prepare(&query)
+            .bind("publication_id", bare_id("publication", publication_id))
+            .bind("user_id", user_id)
+            .fetch_one()
+            .await
+    }
 
-        let members: Vec<PublicationMember> = response.take(0)?;
-        Ok(members.into_iter().next())
+    /// 检查权限并返回请求者的成员信息
+    async fn require_member_permission(
+        &self,
+        publication_id: &str,
+        user_id: &str,
+        permission: &str,
+    ) -> Result<PublicationMember> {
+        let member = self.get_member_info(publication_id, user_id).await?
+            .ok_or_else(|| AppError::coded(ErrorCode::NotPublicationMember, "You are not a member of this publication"))?;
+
+        if !member.permissions.iter().any(|p| p == permission) {
+            return Err(AppError::forbidden(&format!("Permission '{}' required", permission)));
+        }
+
+        Ok(member)
     }
 
-    async fn check_permission(
+    /// 用户是否为成员且拥有某项权限，不是成员时返回 false
+    pub async fn has_permission(&self, publication_id: &str, user_id: &str, permission: &str) -> Result<bool> {
+        Ok(self
+            .get_member_info(publication_id, user_id)
+            .await?
+            .map_or(false, |member| member.permissions.iter().any(|p| p == permission)))
+    }
+
+    /// 出版物至少要保留一个 Owner
+    async fn ensure_other_owner(&self, publication_id: &str) -> Result<()> {
+        if self.count_members_by_role(publication_id, MemberRole::Owner).await? <= 1 {
+            return Err(AppError::bad_request("Publication must have at least one owner"));
+        }
+        Ok(())
+    }
+
+    pub async fn check_permission(
         &self,
         publication_id: &str,
         user_id: &str,
         permission: &str,
     ) -> Result<()> {
+        self.require_member_permission(publication_id, user_id, permission).await.map(|_| ())
+    }
+
+    /// 出版物要求两步验证时，有发布权限的成员必须在当前会话完成验证
+    pub async fn check_two_factor(&self, publication_id: &str, user: &User) -> Result<()> {
+        if user.two_factor_verified {
+            return Ok(());
+        }
+
+        let required: Option<bool> = self.db
+            .prepare("SELECT VALUE require_two_factor FROM type::thing('publication', $publication_id)")
+            .bind("publication_id", bare_id("publication", publication_id))
+            .fetch_one()
+            .await?;
+        if required != Some(true) || !self.has_permission(publication_id, &user.id, "article.publish").await? {
+            return Ok(());
+        }
+
+        Err(AppError::coded(
+            ErrorCode::TwoFactorRequired,
+            "This publication requires two-factor verification before publishing",
+        ))
+    }
+
+    pub async fn check_audience_access(&self, publication_id: &str, user_id: &str) -> Result<()> {
         let member = self.get_member_info(publication_id, user_id).await?
-            .ok_or_else(|| AppError::forbidden("You are not a member of this publication"))?;
+            .ok_or_else(|| AppError::coded(ErrorCode::NotPublicationMember, "You are not a member of this publication"))?;
 
-        if !member.permissions.contains(&permission.to_string()) {
-            return Err(AppError::forbidden(&format!("Permission '{}' required", permission)));
+        if !member.role.can_view_audience() {
+            return Err(AppError::forbidden("Only owners and editors can view audience insights"));
         }
 
         Ok(())
     }
 
+    pub async fn check_comment_moderation_access(&self, publication_id: &str, user_id: &str) -> Result<()> {
+        let member = self.get_member_info(publication_id, user_id).await?
+            .ok_or_else(|| AppError::coded(ErrorCode::NotPublicationMember, "You are not a member of this publication"))?;
+
+        if !member.role.can_moderate_comments() {
+            return Err(AppError::forbidden("Only owners and editors can moderate comments"));
+        }
+
+        Ok(())
+    }
+
+    pub async fn check_template_management_access(&self, publication_id: &str, user_id: &str) -> Result<()> {
+        let member = self.get_member_info(publication_id, user_id).await?
+            .ok_or_else(|| AppError::coded(ErrorCode::NotPublicationMember, "You are not a member of this publication"))?;
+
+        if !member.role.can_manage_templates() {
+            return Err(AppError::forbidden("Only owners and editors can manage article templates"));
+        }
+
+        Ok(())
+    }
+
+    /// 返回 (user_id, followed_at) 列表，按关注时间升序
+    async fn get_publication_follows(&self, publication_id: &str) -> Result<Vec<(String, DateTime<Utc>)>> {
+        let query = r#"
+            SELECT user_id, created_at
+            FROM publication_follow
+            WHERE publication_id = type::thing('publication', $publication_id)
+            ORDER BY created_at ASC
+        "#;
+
+        let mut response = self.db.query_with_params(query, json!({
+            "publication_id": bare_id("publication", publication_id)
+        })).await?;
+        let rows: Vec<Value> = response.take(0)?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let uid = row.get("user_id")?.as_str()?.to_string();
+                let created_at = serde_json::from_value::<DateTime<Utc>>(row.get("created_at")?.clone()).ok()?;
+                Some((uid, created_at))
+            })
+            .collect())
+    }
+
+    /// 返回同意受众洞察的关注者 (user_id, preferred_language)
+    async fn get_consenting_profiles(&self, user_ids: &[String]) -> Result<Vec<(String, Option<String>)>> {
+        if user_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let query = r#"
+            SELECT user_id, preferred_language
+            FROM user_profile
+            WHERE user_id INSIDE $user_ids
+            AND allow_audience_insights = true
+        "#;
+
+        let mut response = self.db.query_with_params(query, json!({ "user_ids": user_ids })).await?;
+        let rows: Vec<Value> = response.take(0)?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                let uid = row.get("user_id")?.as_str()?.to_string();
+                let language = row.get("preferred_language").and_then(|v| v.as_str()).map(|s| s.to_string());
+                Some((uid, language))
+            })
+            .collect())
+    }
+
+    /// 从 `since` 之后的阅读会话中取每个用户最近一次记录的国家
+    async fn get_latest_countries(
+        &self,
+        user_ids: &[String],
+        since: DateTime<Utc>,
+    ) -> Result<HashMap<String, String>> {
+        if user_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let query = r#"
+            SELECT user_id, country, created_at
+            FROM reading_sessions
+            WHERE user_id INSIDE $user_ids
+            AND country != NONE
+            AND created_at >= <datetime> $since
+            ORDER BY created_at DESC
+        "#;
+
+        let mut response = self.db.query_with_params(query, json!({
+            "user_ids": user_ids,
+            "since": since,
+        })).await?;
+        let rows: Vec<Value> = response.take(0)?;
+
+        let mut countries = HashMap::new();
+        for row in rows {
+            if let (Some(uid), Some(country)) = (
+                row.get("user_id").and_then(|v| v.as_str()),
+                row.get("country").and_then(|v| v.as_str()),
+            ) {
+                countries.entry(uid.to_string()).or_insert_with(|| country.to_string());
+            }
+        }
+
+        Ok(countries)
+    }
+
+    /// 一次读取多个关注者的资料，按 user_id 索引
+    async fn get_follower_profiles(&self, user_ids: &[String]) -> Result<HashMap<String, Value>> {
+        if user_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let mut response = self.db.query_with_params(
+            "SELECT user_id, username, display_name, avatar_url FROM user_profile WHERE user_id INSIDE $user_ids",
+            json!({ "user_ids": user_ids }),
+        ).await?;
+        let rows: Vec<Value> = response.take(0)?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| Some((row.get("user_id")?.as_str()?.to_string(), row)))
+            .collect())
+    }
+
     async fn is_following_publication(
         &self,
         publication_id: &str,
@@ -770,12 +1434,13 @@ impl PublicationService {
 
     async fn update_member_count(&self, publication_id: &str) -> Result<()> {
         let query = r#"
-            LET $count = (SELECT count() FROM publication_member WHERE publication_id = $publication_id AND is_active = true);
-            UPDATE publication SET member_count = $count WHERE id = $publication_id;
+            LET $publication = type::thing('publication', $publication_id);
+            LET $count = (SELECT count() FROM publication_member WHERE publication_id = $publication AND is_active = true GROUP ALL)[0].count ?? 0;
+            UPDATE $publication SET member_count = $count;
         "#;
 
         self.db.query_with_params(query, json!({
-            "publication_id": publication_id
+            "publication_id": bare_id("publication", publication_id)
         })).await?;
 
         Ok(())
@@ -802,14 +1467,15 @@ impl PublicationService {
         let query = r#"
             SELECT count() as count 
             FROM publication_member 
-            WHERE publication_id = $publication_id 
+            WHERE publication_id = type::thing('publication', $publication_id) 
             AND role = $role 
             AND is_active = true
+            GROUP ALL
         "#;
 
         let mut response = self.db.query_with_params(query, json!({
-            "publication_id": publication_id,
-            "role": format!("{:?}", role)
+            "publication_id": bare_id("publication", publication_id),
+            "role": role.as_str()
         })).await?;
 
         let result: Vec<Value> = response.take(0)?;
@@ -829,11 +1495,14 @@ impl PublicationService {
     ) -> Result<Vec<PublicationMember>> {
         debug!("Getting members for publication: {}", publication_id);
         
-        let mut query = String::from(r#"
-            SELECT * FROM publication_member 
-            WHERE publication_id = $publication_id 
+        let mut query = format!(
+            r#"
+            SELECT {} FROM publication_member 
+            WHERE publication_id = type::thing('publication', $publication_id) 
             AND is_active = true
-        "#);
+            "#,
+            MEMBER_FIELDS
+        );
         
         if role_filter.is_some() {
             query.push_str(" AND role = $role");
@@ -842,11 +1511,11 @@ impl PublicationService {
         query.push_str(" ORDER BY joined_at DESC");
         
         let mut params = json!({
-            "publication_id": publication_id
+            "publication_id": bare_id("publication", publication_id)
         });
         
         if let Some(role) = role_filter {
-            params["role"] = json!(format!("{:?}", role));
+            params["role"] = json!(role.as_str());
         }
         
         let mut response = self.db.query_with_params(&query, params).await?;
@@ -879,3 +1548,61 @@ impl PublicationService {
         Ok(count)
     }
 }
+
+/// 请求者只能授予比自己低的角色，Owner 可以授予任意角色（包括共同 Owner）
+fn ensure_can_assign(requester: &PublicationMember, role: &MemberRole) -> Result<()> {
+    if requester.role == MemberRole::Owner || role.rank() < requester.role.rank() {
+        return Ok(());
+    }
+    Err(AppError::forbidden(&format!("Cannot grant the {} role", role.as_str())))
+}
+
+/// 请求者只能管理级别比自己低的成员，Owner 还可以管理自己
+fn ensure_can_manage(requester: &PublicationMember, member: &PublicationMember) -> Result<()> {
+    let is_self = requester.user_id == member.user_id;
+    if member.role.rank() < requester.role.rank() || (is_self && requester.role == MemberRole::Owner) {
+        return Ok(());
+    }
+    Err(AppError::forbidden("Cannot manage a member with the same or a higher role"))
+}
+
+fn generate_invite_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(INVITE_TOKEN_LEN)
+        .map(char::from)
+        .collect()
+}
+
+fn hash_invite_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn normalize_publication_id(publication_id: &str) -> String {
+    if publication_id.starts_with("publication:") {
+        publication_id.to_string()
+    } else {
+        format!("publication:{}", publication_id)
+    }
+}
+
+/// 将计数转换为按数量降序的分布列表
+fn build_breakdown(counts: HashMap<String, i64>, total: i64) -> Vec<AudienceBreakdownItem> {
+    let mut items: Vec<AudienceBreakdownItem> = counts
+        .into_iter()
+        .map(|(key, followers)| AudienceBreakdownItem {
+            percentage: if total > 0 { followers as f64 * 100.0 / total as f64 } else { 0.0 },
+            key,
+            followers,
+        })
+        .collect();
+    items.sort_by(|a, b| b.followers.cmp(&a.followers).then_with(|| a.key.cmp(&b.key)));
+    items
+}
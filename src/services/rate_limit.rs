@@ -0,0 +1,393 @@
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+    services::Database,
+};
+use async_trait::async_trait;
+use axum::http::{HeaderMap, HeaderValue};
+use chrono::{DateTime, TimeZone, Utc};
+use dashmap::DashMap;
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// 默认策略的突发窗口（秒）
+const DEFAULT_BURST_WINDOW_SECS: u64 = 5;
+/// 内存计数器超过这个数量时清理已过期的窗口
+const MEMORY_SWEEP_THRESHOLD: usize = 50_000;
+
+/// 一个固定窗口内允许的请求数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct Limit {
+    pub requests: u64,
+    pub window_secs: u64,
+}
+
+/// 按谁计数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitScope {
+    /// 按客户端 IP
+    Ip,
+    /// 按登录用户，未登录的请求按 IP
+    User,
+}
+
+/// 限流策略：按路径前缀和方法匹配，同时检查短时突发和长时持续两个窗口。
+/// 两个窗口都不设置时该路由不限流
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateLimitPolicy {
+    pub name: String,
+    #[serde(default)]
+    pub path_prefix: String,
+    /// 为空时匹配所有方法
+    #[serde(default)]
+    pub methods: Vec<String>,
+    pub scope: RateLimitScope,
+    pub burst: Option<Limit>,
+    pub sustained: Option<Limit>,
+}
+
+impl RateLimitPolicy {
+    fn matches(&self, method: &str, path: &str) -> bool {
+        path.starts_with(&self.path_prefix)
+            && (self.methods.is_empty() || self.methods.iter().any(|m| m.eq_ignore_ascii_case(method)))
+    }
+
+    fn limits(&self) -> impl Iterator<Item = Limit> {
+        self.burst.into_iter().chain(self.sustained)
+    }
+
+    /// `RateLimit-Policy` 响应头，例如 `20;w=5, 100;w=60`
+    fn header(&self) -> String {
+        self.limits()
+            .map(|l| format!("{};w={}", l.requests, l.window_secs))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// 一次请求的限流结果，取策略中最紧的那个窗口
+#[derive(Debug, Clone)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    pub policy: String,
+    pub limit: u64,
+    pub remaining: u64,
+    /// 距离窗口重置的秒数
+    pub reset_secs: u64,
+}
+
+impl RateLimitDecision {
+    /// 写入 `RateLimit-*` 响应头，拒绝时另加 `Retry-After`
+    pub fn apply_headers(&self, headers: &mut HeaderMap) {
+        headers.insert("ratelimit-limit", HeaderValue::from(self.limit));
+        headers.insert("ratelimit-remaining", HeaderValue::from(self.remaining));
+        headers.insert("ratelimit-reset", HeaderValue::from(self.reset_secs));
+        if let Ok(policy) = HeaderValue::from_str(&self.policy) {
+            headers.insert("ratelimit-policy", policy);
+        }
+        if !self.allowed {
+            headers.insert("retry-after", HeaderValue::from(self.reset_secs.max(1)));
+        }
+    }
+}
+
+/// 限流计数器的存储。多副本部署时使用共享存储，重启后计数也不会清零
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// 计数加一并返回新值，计数器在 `expires_at` 之后可以丢弃
+    async fn increment(&self, key: &str, expires_at: DateTime<Utc>) -> Result<u64>;
+
+    /// 删除过期的计数器，自带过期机制的存储不需要实现
+    async fn purge_expired(&self) -> Result<u64> {
+        Ok(0)
+    }
+}
+
+/// 进程内计数，仅适合单实例部署
+#[derive(Default)]
+struct MemoryStore {
+    counters: DashMap<String, (u64, DateTime<Utc>)>,
+}
+
+#[async_trait]
+impl RateLimitStore for MemoryStore {
+    async fn increment(&self, key: &str, expires_at: DateTime<Utc>) -> Result<u64> {
+        if self.counters.len() > MEMORY_SWEEP_THRESHOLD {
+            let now = Utc::now();
+            self.counters.retain(|_, (_, expires)| *expires > now);
+        }
+
+        let mut counter = self.counters.entry(key.to_string()).or_insert((0, expires_at));
+        counter.0 += 1;
+        Ok(counter.0)
+    }
+}
+
+/// 计数保存在 SurrealDB 的 `rate_limit_counter` 表
+struct DatabaseStore {
+    db: Arc<Database>,
+}
+
+#[async_trait]
+impl RateLimitStore for DatabaseStore {
+    async fn increment(&self, key: &str, expires_at: DateTime<Utc>) -> Result<u64> {
+        let count: Option<u64> = self
+            .db
+            .prepare(
+                "UPSERT type::thing('rate_limit_counter', $key) SET count += 1, expires_at = <datetime> $expires_at RETURN VALUE count",
+            )
+            .bind("key", key)
+            .bind("expires_at", expires_at)
+            .fetch_one()
+            .await?;
+
+        Ok(count.unwrap_or(1))
+    }
+
+    async fn purge_expired(&self) -> Result<u64> {
+        let deleted: Vec<serde_json::Value> = self
+            .db
+            .prepare("DELETE rate_limit_counter WHERE expires_at < time::now() RETURN BEFORE")
+            .fetch()
+            .await?;
+
+        Ok(deleted.len() as u64)
+    }
+}
+
+/// 计数保存在 Redis，键随窗口一起过期
+#[cfg(feature = "redis-cache")]
+struct RedisStore {
+    connection: redis::aio::ConnectionManager,
+}
+
+#[cfg(feature = "redis-cache")]
+impl RedisStore {
+    async fn connect(url: &str) -> Result<Self> {
+        let client = redis::Client::open(url)
+            .map_err(|e| AppError::Internal(format!("Invalid REDIS_URL: {}", e)))?;
+        let connection = redis::aio::ConnectionManager::new(client)
+            .await
+            .map_err(|e| AppError::ExternalService(format!("Failed to connect to Redis: {}", e)))?;
+
+        Ok(Self { connection })
+    }
+}
+
+#[cfg(feature = "redis-cache")]
+#[async_trait]
+impl RateLimitStore for RedisStore {
+    async fn increment(&self, key: &str, expires_at: DateTime<Utc>) -> Result<u64> {
+        let key = format!("rate_limit:{}", key);
+        let ttl = (expires_at - Utc::now()).num_seconds().max(1) as usize;
+        let mut connection = self.connection.clone();
+
+        let (count,): (u64,) = redis::pipe()
+            .atomic()
+            .incr(&key, 1)
+            .expire(&key, ttl)
+            .ignore()
+            .query_async(&mut connection)
+            .await
+            .map_err(|e| AppError::ExternalService(format!("Redis rate limit counter failed: {}", e)))?;
+
+        Ok(count)
+    }
+}
+
+/// 按路由策略限流。计数存储不可用时放行请求，不让限流拖垮整个站点
+#[derive(Clone)]
+pub struct RateLimitService {
+    store: Arc<dyn RateLimitStore>,
+    /// 按顺序匹配，先匹配的生效
+    policies: Arc<Vec<RateLimitPolicy>>,
+    default_policy: Arc<RateLimitPolicy>,
+}
+
+impl RateLimitService {
+    pub async fn new(db: Arc<Database>, config: &Config) -> Result<Self> {
+        let store: Arc<dyn RateLimitStore> = match config.rate_limit_store.as_str() {
+            "memory" => Arc::new(MemoryStore::default()),
+            "database" => Arc::new(DatabaseStore { db }),
+            #[cfg(feature = "redis-cache")]
+            "redis" => {
+                let url = config.redis_url.as_deref().ok_or_else(|| {
+                    AppError::Internal("RATE_LIMIT_STORE=redis requires REDIS_URL".to_string())
+                })?;
+                Arc::new(RedisStore::connect(url).await?)
+            }
+            #[cfg(not(feature = "redis-cache"))]
+            "redis" => {
+                return Err(AppError::Internal(
+                    "RATE_LIMIT_STORE=redis requires the redis-cache feature".to_string(),
+                ))
+            }
+            other => {
+                return Err(AppError::Internal(format!("Unknown RATE_LIMIT_STORE: {}", other)));
+            }
+        };
+
+        // 配置的策略优先于内置策略
+        let mut policies: Vec<RateLimitPolicy> = match &config.rate_limit_policies {
+            Some(json) => serde_json::from_str(json)
+                .map_err(|e| AppError::Internal(format!("Invalid RATE_LIMIT_POLICIES: {}", e)))?,
+            None => Vec::new(),
+        };
+        policies.extend(builtin_policies());
+
+        let default_policy = RateLimitPolicy {
+            name: "default".to_string(),
+            path_prefix: String::new(),
+            methods: Vec::new(),
+            scope: RateLimitScope::User,
+            burst: Some(Limit {
+                requests: config.rate_limit_burst,
+                window_secs: DEFAULT_BURST_WINDOW_SECS,
+            }),
+            sustained: Some(Limit {
+                requests: config.rate_limit_requests as u64,
+                window_secs: config.rate_limit_window,
+            }),
+        };
+
+        info!(
+            "Rate limiting with {} store and {} route policies",
+            config.rate_limit_store,
+            policies.len()
+        );
+        Ok(Self::from_parts(store, policies, default_policy))
+    }
+
+    fn from_parts(
+        store: Arc<dyn RateLimitStore>,
+        policies: Vec<RateLimitPolicy>,
+        default_policy: RateLimitPolicy,
+    ) -> Self {
+        Self {
+            store,
+            policies: Arc::new(policies),
+            default_policy: Arc::new(default_policy),
+        }
+    }
+
+    fn policy_for(&self, method: &str, path: &str) -> &RateLimitPolicy {
+        self.policies
+            .iter()
+            .find(|p| p.matches(method, path))
+            .unwrap_or(&self.default_policy)
+    }
+
+    /// 记录一次请求并返回限流结果。路由不限流或计数存储出错时返回 None
+    pub async fn check(
+        &self,
+        method: &str,
+        path: &str,
+        user_id: Option<&str>,
+        ip: &str,
+    ) -> Option<RateLimitDecision> {
+        let policy = self.policy_for(method, path);
+        let subject = match (policy.scope, user_id) {
+            (RateLimitScope::User, Some(user_id)) => format!("user:{}", user_id),
+            _ => format!("ip:{}", ip),
+        };
+
+        let now = Utc::now();
+        let mut decision: Option<RateLimitDecision> = None;
+        for limit in policy.limits() {
+            let window = limit.window_secs.max(1) as i64;
+            let window_start = now.timestamp() - now.timestamp().rem_euclid(window);
+            let reset_at = Utc
+                .timestamp_opt(window_start + window, 0)
+                .single()
+                .unwrap_or(now);
+            let key = format!("{}:{}:{}:{}", policy.name, window, subject, window_start);
+
+            let count = match self.store.increment(&key, reset_at).await {
+                Ok(count) => count,
+                Err(e) => {
+                    warn!("Rate limit store unavailable, allowing request: {}", e);
+                    return None;
+                }
+            };
+
+            let current = RateLimitDecision {
+                allowed: count <= limit.requests,
+                policy: policy.header(),
+                limit: limit.requests,
+                remaining: limit.requests.saturating_sub(count),
+                reset_secs: (reset_at - now).num_seconds().max(0) as u64,
+            };
+
+            // 已超限的窗口优先，其次剩余最少的窗口
+            decision = match decision {
+                Some(d) if (d.allowed, d.remaining) <= (current.allowed, current.remaining) => Some(d),
+                _ => Some(current),
+            };
+        }
+
+        decision
+    }
+
+    /// 清理过期的计数器
+    pub async fn purge_expired(&self) -> Result<u64> {
+        self.store.purge_expired().await
+    }
+}
+
+/// 内置的路由策略：写操作按用户限制，搜索按 IP 限制，Stripe Webhook 不限流
+fn builtin_policies() -> Vec<RateLimitPolicy> {
+    let policy = |name: &str, path_prefix: &str, methods: &[&str], scope, burst, sustained| RateLimitPolicy {
+        name: name.to_string(),
+        path_prefix: path_prefix.to_string(),
+        methods: methods.iter().map(|m| m.to_string()).collect(),
+        scope,
+        burst,
+        sustained,
+    };
+    let limit = |requests, window_secs| Some(Limit { requests, window_secs });
+
+    vec![
+        policy("stripe-webhooks", "/api/blog/stripe/webhooks", &["POST"], RateLimitScope::Ip, None, None),
+        policy("comments", "/api/blog/comments", &["POST"], RateLimitScope::User, limit(5, 30), limit(60, 3600)),
+        policy("media-upload", "/api/blog/media", &["POST"], RateLimitScope::User, limit(10, 60), limit(100, 3600)),
+        policy("search", "/api/blog/search", &["GET"], RateLimitScope::Ip, limit(10, 5), limit(120, 60)),
+        policy("two-factor", "/api/blog/auth/2fa", &["POST"], RateLimitScope::User, limit(5, 60), limit(30, 3600)),
+        policy("reports", "/api/blog/reports", &["POST"], RateLimitScope::User, limit(5, 60), limit(30, 86400)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_route_policy_and_burst_limit() {
+        let default_policy = RateLimitPolicy {
+            name: "default".to_string(),
+            path_prefix: String::new(),
+            methods: Vec::new(),
+            scope: RateLimitScope::User,
+            burst: Some(Limit { requests: 2, window_secs: 3600 }),
+            sustained: Some(Limit { requests: 100, window_secs: 3600 }),
+        };
+        let service = RateLimitService::from_parts(Arc::new(MemoryStore::default()), builtin_policies(), default_policy);
+
+        assert!(service.check("POST", "/api/blog/stripe/webhooks", None, "1.2.3.4").await.is_none());
+
+        let first = service.check("GET", "/api/blog/articles", Some("u1"), "1.2.3.4").await.unwrap();
+        assert!(first.allowed);
+        assert_eq!(first.remaining, 1);
+        assert_eq!(first.policy, "2;w=3600, 100;w=3600");
+
+        service.check("GET", "/api/blog/articles", Some("u1"), "1.2.3.4").await.unwrap();
+        let third = service.check("GET", "/api/blog/articles", Some("u1"), "1.2.3.4").await.unwrap();
+        assert!(!third.allowed);
+        assert_eq!(third.limit, 2);
+
+        // 同一 IP 下的其他用户单独计数
+        let other = service.check("GET", "/api/blog/articles", Some("u2"), "1.2.3.4").await.unwrap();
+        assert!(other.allowed);
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{
+    error::{AppError, Result},
+    models::{article::Article, id::ArticleId, reaction::*},
+    services::Database,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+/// 旧版 clap 表迁移到 reaction 表后写入的标记记录
+const CLAP_MIGRATION_MARKER: &str = "reactions_from_claps";
+
+#[derive(Debug, Deserialize)]
+struct ReactionTotal {
+    reaction_type: ReactionType,
+    total: i64,
+}
+
+/// 文章反应：点赞之外的 insightful、funny 等反应，每人每种一次。
+/// 点赞同样存放在 reaction 表中，但增加点赞仍通过 ArticleService::clap_article（需要计算加权点赞分）
+#[derive(Clone)]
+pub struct ReactionService {
+    db: Arc<Database>,
+}
+
+impl ReactionService {
+    pub async fn new(db: Arc<Database>) -> Result<Self> {
+        Ok(Self { db })
+    }
+
+    /// 文章 ID 或 slug 对应的已发布文章
+    pub async fn resolve_article(&self, id_or_slug: &str) -> Result<Article> {
+        let article: Option<Article> = self.db.get_by_id("article", ArticleId::new(id_or_slug).as_str()).await?;
+        let article = match article {
+            Some(article) => Some(article),
+            None => self.db
+                .prepare("SELECT * FROM article WHERE slug = $slug AND is_deleted = false LIMIT 1")
+                .bind("slug", id_or_slug)
+                .fetch_one()
+                .await?,
+        };
+
+        article
+            .filter(|a| a.is_published())
+            .ok_or_else(|| AppError::not_found("Article"))
+    }
+
+    pub async fn get_article_reactions(&self, article: &Article, user_id: Option<&str>) -> Result<ArticleReactions> {
+        let article_id = ArticleId::new(&article.id);
+        let mut counts = article.reaction_counts.clone();
+        counts.clap = article.clap_count;
+
+        let (user_reactions, user_clap_count) = match user_id {
+            Some(user_id) => {
+                let reactions: Vec<Reaction> = self.db
+                    .prepare("SELECT * FROM reaction WHERE article_id = type::thing('article', $article_id) AND user_id = $user_id")
+                    .bind("article_id", article_id.as_str())
+                    .bind("user_id", user_id)
+                    .fetch()
+                    .await?;
+                let clap_count = reactions
+                    .iter()
+                    .find(|r| r.reaction_type == ReactionType::Clap)
+                    .map_or(0, |r| r.count);
+                (Some(reactions.into_iter().map(|r| r.reaction_type).collect()), Some(clap_count))
+            }
+            None => (None, None),
+        };
+
+        Ok(ArticleReactions {
+            article_id: article_id.as_str().to_string(),
+            counts,
+            user_reactions,
+            user_clap_count,
+        })
+    }
+
+    /// 添加点赞以外的反应，重复添加不会重复计数
+    pub async fn add_reaction(&self, article: &Article, user_id: &str, reaction_type: ReactionType) -> Result<ReactionCounts> {
+        if reaction_type == ReactionType::Clap {
+            return Err(AppError::BadRequest("Use the clap endpoint to add claps".to_string()));
+        }
+
+        let article_id = ArticleId::new(&article.id);
+        self.db
+            .prepare(
+                r#"
+                UPSERT type::thing('reaction', [$article_id, $user_id, $reaction_type]) MERGE {
+                    user_id: $user_id,
+                    article_id: type::thing('article', $article_id),
+                    reaction_type: $reaction_type,
+                    count: 1,
+                    created_at: created_at ?? time::now(),
+                    updated_at: time::now()
+                }
+                "#,
+            )
+            .bind("article_id", article_id.as_str())
+            .bind("user_id", user_id)
+            .bind("reaction_type", reaction_type.as_str())
+            .execute()
+            .await?;
+
+        debug!("User {} reacted {} to article {}", user_id, reaction_type.as_str(), article_id);
+        self.refresh_counts(article_id.as_str()).await
+    }
+
+    /// 撤销点赞以外的反应
+    pub async fn remove_reaction(&self, article: &Article, user_id: &str, reaction_type: ReactionType) -> Result<ReactionCounts> {
+        if reaction_type == ReactionType::Clap {
+            return Err(AppError::BadRequest("Claps cannot be removed".to_string()));
+        }
+
+        let article_id = ArticleId::new(&article.id);
+        self.db
+            .prepare("DELETE type::thing('reaction', [$article_id, $user_id, $reaction_type])")
+            .bind("article_id", article_id.as_str())
+            .bind("user_id", user_id)
+            .bind("reaction_type", reaction_type.as_str())
+            .execute()
+            .await?;
+
+        self.refresh_counts(article_id.as_str()).await
+    }
+
+    /// 按 reaction 表重新统计文章的各反应总数
+    async fn refresh_counts(&self, article_id: &str) -> Result<ReactionCounts> {
+        let totals: Vec<ReactionTotal> = self.db
+            .prepare(
+                r#"
+                SELECT reaction_type, math::sum(count) AS total FROM reaction
+                WHERE article_id = type::thing('article', $article_id)
+                GROUP BY reaction_type
+                "#,
+            )
+            .bind("article_id", article_id)
+            .fetch()
+            .await?;
+
+        let mut counts = ReactionCounts::default();
+        for total in totals {
+            counts.set(total.reaction_type, total.total);
+        }
+
+        self.db
+            .prepare("UPDATE type::thing('article', $article_id) SET reaction_counts = $counts")
+            .bind("article_id", article_id)
+            .bind("counts", &counts)
+            .execute()
+            .await?;
+        Ok(counts)
+    }
+
+    /// 把旧版 clap 表中的点赞复制到 reaction 表，并补上文章的 reaction_counts。
+    /// 迁移完成后写入标记，之后启动时直接跳过；中途失败可以安全地重新执行
+    pub async fn migrate_claps(&self) -> Result<bool> {
+        let done: Option<serde_json::Value> = self.db
+            .prepare("SELECT * FROM type::thing('schema_migration', $name)")
+            .bind("name", CLAP_MIGRATION_MARKER)
+            .fetch_one()
+            .await?;
+        if done.is_some() {
+            return Ok(false);
+        }
+
+        let mut response = self.db
+            .prepare(
+                r#"
+                FOR $clap IN (SELECT * FROM clap) {
+                    UPSERT type::thing('reaction', [meta::id($clap.article_id), $clap.user_id, 'clap']) MERGE {
+                        user_id: $clap.user_id,
+                        article_id: $clap.article_id,
+                        reaction_type: 'clap',
+                        count: math::max([count ?? 0, $clap.count]),
+                        created_at: created_at ?? $clap.created_at,
+                        updated_at: updated_at ?? $clap.updated_at
+                    };
+                };
+                UPDATE article SET reaction_counts = { clap: clap_count ?? 0 } WHERE !reaction_counts;
+                CREATE type::thing('schema_migration', $name) SET completed_at = time::now();
+                RETURN (SELECT count() FROM clap GROUP ALL)[0].count ?? 0;
+                "#,
+            )
+            .bind("name", CLAP_MIGRATION_MARKER)
+            .execute()
+            .await?;
+        let migrated: Option<i64> = response.take(3)?;
+
+        info!("Migrated {} claps to reactions", migrated.unwrap_or(0));
+        Ok(true)
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{
+    error::{AppError, Result},
+    models::{
+        article::Article,
+        id::ArticleId,
+        reading_progress::*,
+        recommendation::InteractionType,
+    },
+    services::{Database, RecommendationService},
+};
+use chrono::Utc;
+use std::sync::Arc;
+use tracing::{debug, warn};
+use validator::Validate;
+
+/// 滚动到这个位置即视为读完，文章末尾通常是评论和推荐区域
+const COMPLETED_THRESHOLD: f64 = 0.95;
+
+/// 每位读者在每篇文章中的阅读位置
+#[derive(Clone)]
+pub struct ReadingProgressService {
+    db: Arc<Database>,
+    recommendation_service: RecommendationService,
+}
+
+impl ReadingProgressService {
+    pub async fn new(db: Arc<Database>, recommendation_service: RecommendationService) -> Result<Self> {
+        Ok(Self {
+            db,
+            recommendation_service,
+        })
+    }
+
+    pub async fn get(&self, user_id: &str, article: &Article) -> Result<Option<ReadingProgress>> {
+        self.db
+            .prepare("SELECT * FROM type::thing('reading_progress', [$user_id, $article_id])")
+            .bind("user_id", user_id)
+            .bind("article_id", ArticleId::new(&article.id).as_str())
+            .fetch_one()
+            .await
+    }
+
+    /// 保存阅读位置。多台设备同时阅读时以记录时间最新的为准，返回保存后的位置
+    pub async fn save(&self, user_id: &str, article: &Article, request: UpdateReadingProgressRequest) -> Result<ReadingProgress> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let updated_at = request.updated_at.unwrap_or_else(Utc::now).min(Utc::now());
+        let existing = self.get(user_id, article).await?;
+        if let Some(existing) = &existing {
+            if existing.updated_at > updated_at {
+                debug!("Ignoring stale reading position from {:?} for user {}", request.device, user_id);
+                return Ok(existing.clone());
+            }
+        }
+
+        let completed = request.progress >= COMPLETED_THRESHOLD;
+        let progress: ReadingProgress = self.db
+            .prepare(
+                r#"
+                UPSERT type::thing('reading_progress', [$user_id, $article_id]) CONTENT {
+                    user_id: $user_id,
+                    article_id: $article_id,
+                    progress: $progress,
+                    last_paragraph: $last_paragraph ?? NONE,
+                    device: $device ?? NONE,
+                    completed: $completed,
+                    updated_at: <datetime> $updated_at
+                }
+                "#,
+            )
+            .bind("user_id", user_id)
+            .bind("article_id", ArticleId::new(&article.id).as_str())
+            .bind("progress", request.progress)
+            .bind("last_paragraph", request.last_paragraph)
+            .bind("device", &request.device)
+            .bind("completed", completed)
+            .bind("updated_at", updated_at)
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::internal("Failed to save reading progress"))?;
+
+        // 第一次读完时计入推荐的用户兴趣
+        if completed && !existing.map_or(false, |p| p.completed) {
+            if let Err(e) = self.recommendation_service
+                .record_interaction(user_id, &article.id, InteractionType::ReadComplete)
+                .await
+            {
+                warn!("Failed to record read completion for article {}: {}", article.id, e);
+            }
+        }
+
+        Ok(progress)
+    }
+
+    pub async fn clear(&self, user_id: &str, article: &Article) -> Result<()> {
+        self.db
+            .prepare("DELETE type::thing('reading_progress', [$user_id, $article_id])")
+            .bind("user_id", user_id)
+            .bind("article_id", ArticleId::new(&article.id).as_str())
+            .execute()
+            .await?;
+        Ok(())
+    }
+}
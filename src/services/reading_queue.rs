@@ -0,0 +1,306 @@
+use crate::{
+    error::{AppError, Result},
+    models::{reading_queue::*, article::Article},
+    services::Database,
+};
+use chrono::Utc;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::{debug, info};
+use validator::Validate;
+
+#[derive(Clone)]
+pub struct ReadingQueueService {
+    db: Arc<Database>,
+}
+
+impl ReadingQueueService {
+    pub async fn new(db: Arc<Database>) -> Result<Self> {
+        Ok(Self { db })
+    }
+
+    /// 获取用户的阅读队列（按顺序），附带预计总阅读时间
+    pub async fn get_queue(&self, user_id: &str) -> Result<ReadingQueueResponse> {
+        debug!("Getting reading queue for user: {}", user_id);
+
+        let items = self.get_active_items(user_id).await?;
+        let mut entries = Vec::with_capacity(items.len());
+        let mut remaining_minutes = 0.0;
+
+        for item in items {
+            let article: Option<Article> = self.db.get_by_id("article", &item.article_id).await?;
+            let article = match article {
+                Some(a) if !a.is_deleted => a,
+                _ => continue,
+            };
+
+            remaining_minutes += article.reading_time as f64 * (1.0 - item.progress.clamp(0.0, 1.0));
+            entries.push(ReadingQueueEntry {
+                item,
+                article_title: article.title,
+                article_slug: article.slug,
+                article_reading_time: article.reading_time,
+            });
+        }
+
+        Ok(ReadingQueueResponse {
+            total_items: entries.len(),
+            items: entries,
+            estimated_total_reading_time: remaining_minutes.ceil() as i32,
+            sync_token: encode_sync_token(Utc::now()),
+        })
+    }
+
+    /// 加入队列；已存在（或曾被移除）的项会被恢复并移动到指定位置
+    pub async fn add_to_queue(&self, user_id: &str, request: AddToQueueRequest) -> Result<ReadingQueueItem> {
+        request.validate()
+            .map_err(|e| AppError::ValidatorError(e))?;
+
+        let article_id = normalize_article_id(&request.article_id);
+        self.ensure_queueable(&article_id).await?;
+
+        let position = match request.position {
+            Some(p) => p.max(0),
+            None => self.next_position(user_id).await?,
+        };
+
+        self.upsert_item(user_id, &article_id, Some(position), None, false).await
+    }
+
+    /// 从队列移除（保留墓碑供其他设备同步）
+    pub async fn remove_from_queue(&self, user_id: &str, article_id: &str) -> Result<()> {
+        let article_id = normalize_article_id(article_id);
+        if self.find_item(user_id, &article_id).await?.is_none() {
+            return Err(AppError::not_found("Queue item"));
+        }
+
+        self.upsert_item(user_id, &article_id, None, None, true).await?;
+        Ok(())
+    }
+
+    /// 按给定顺序重排队列，未列出的项保持原有相对顺序排在后面
+    pub async fn reorder(&self, user_id: &str, request: ReorderQueueRequest) -> Result<ReadingQueueResponse> {
+        request.validate()
+            .map_err(|e| AppError::ValidatorError(e))?;
+
+        let items = self.get_active_items(user_id).await?;
+        let mut ordered: Vec<String> = request.article_ids.iter().map(|id| normalize_article_id(id)).collect();
+        for item in &items {
+            if !ordered.contains(&item.article_id) {
+                ordered.push(item.article_id.clone());
+            }
+        }
+
+        let now = Utc::now();
+        for (index, article_id) in ordered.iter().enumerate() {
+            if items.iter().any(|i| &i.article_id == article_id) {
+                self.db.query_with_params(
+                    "UPDATE reading_queue_item SET position = $position, updated_at = <datetime> $now WHERE user_id = $user_id AND article_id = $article_id",
+                    json!({
+                        "position": index as i32,
+                        "now": now,
+                        "user_id": user_id,
+                        "article_id": article_id,
+                    }),
+                ).await?;
+            }
+        }
+
+        self.get_queue(user_id).await
+    }
+
+    pub async fn update_progress(&self, user_id: &str, article_id: &str, request: UpdateQueueProgressRequest) -> Result<ReadingQueueItem> {
+        request.validate()
+            .map_err(|e| AppError::ValidatorError(e))?;
+
+        let article_id = normalize_article_id(article_id);
+        if self.find_item(user_id, &article_id).await?.is_none() {
+            return Err(AppError::not_found("Queue item"));
+        }
+
+        self.upsert_item(user_id, &article_id, None, Some(request.progress), false).await
+    }
+
+    /// 离线同步：应用客户端变更（最后写入者胜出），返回 since 之后的服务器变更
+    pub async fn sync(&self, user_id: &str, request: QueueSyncRequest) -> Result<QueueSyncResponse> {
+        request.validate()
+            .map_err(|e| AppError::ValidatorError(e))?;
+
+        let since = match &request.since {
+            Some(token) => Some(decode_sync_token(token)
+                .ok_or_else(|| AppError::bad_request("Invalid sync token"))?),
+            None => None,
+        };
+        // 先记录令牌时间，保证本次同步期间产生的变更在下次同步中可见
+        let sync_started_at = Utc::now();
+
+        let mut rejected = Vec::new();
+        for change in request.changes {
+            let article_id = normalize_article_id(&change.article_id);
+            let existing = self.find_item(user_id, &article_id).await?;
+
+            if let Some(existing) = &existing {
+                if existing.updated_at > change.modified_at {
+                    rejected.push(article_id);
+                    continue;
+                }
+            }
+
+            match change.action {
+                QueueChangeAction::Remove => {
+                    if existing.is_some() {
+                        self.upsert_item(user_id, &article_id, None, None, true).await?;
+                    }
+                }
+                QueueChangeAction::Upsert => {
+                    // 与单条加入相同的校验，不存在、已删除或未发布的文章被拒绝
+                    match self.ensure_queueable(&article_id).await {
+                        Ok(()) => {}
+                        Err(e @ (AppError::NotFound(_) | AppError::Authorization(_))) => {
+                            debug!("Rejected queue sync upsert of {}: {}", article_id, e);
+                            rejected.push(article_id);
+                            continue;
+                        }
+                        Err(e) => return Err(e),
+                    }
+                    let position = match (change.position, &existing) {
+                        (Some(p), _) => Some(p),
+                        (None, Some(_)) => None,
+                        (None, None) => Some(self.next_position(user_id).await?),
+                    };
+                    self.upsert_item(user_id, &article_id, position, change.progress, false).await?;
+                }
+            }
+        }
+
+        let changes = match since {
+            Some(since) => {
+                let mut response = self.db.query_with_params(
+                    "SELECT * FROM reading_queue_item WHERE user_id = $user_id AND updated_at > <datetime> $since ORDER BY updated_at ASC",
+                    json!({ "user_id": user_id, "since": since }),
+                ).await?;
+                response.take(0)?
+            }
+            None => self.get_active_items(user_id).await?,
+        };
+
+        info!("Synced reading queue for user {}: {} server changes, {} rejected", user_id, changes.len(), rejected.len());
+
+        Ok(QueueSyncResponse {
+            changes,
+            rejected,
+            sync_token: encode_sync_token(sync_started_at),
+        })
+    }
+
+    /// 只有已发布且未删除的文章可以加入队列
+    async fn ensure_queueable(&self, article_id: &str) -> Result<()> {
+        let article: Article = self.db
+            .get_by_id("article", article_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
+
+        if !article.is_published() {
+            return Err(AppError::forbidden("Cannot queue unpublished articles"));
+        }
+        Ok(())
+    }
+
+    async fn get_active_items(&self, user_id: &str) -> Result<Vec<ReadingQueueItem>> {
+        let mut response = self.db.query_with_params(
+            "SELECT * FROM reading_queue_item WHERE user_id = $user_id AND is_removed = false ORDER BY position ASC, added_at ASC",
+            json!({ "user_id": user_id }),
+        ).await?;
+        Ok(response.take(0)?)
+    }
+
+    async fn find_item(&self, user_id: &str, article_id: &str) -> Result<Option<ReadingQueueItem>> {
+        let mut response = self.db.query_with_params(
+            "SELECT * FROM reading_queue_item WHERE user_id = $user_id AND article_id = $article_id LIMIT 1",
+            json!({ "user_id": user_id, "article_id": article_id }),
+        ).await?;
+        let items: Vec<ReadingQueueItem> = response.take(0)?;
+        Ok(items.into_iter().next())
+    }
+
+    async fn next_position(&self, user_id: &str) -> Result<i32> {
+        let mut response = self.db.query_with_params(
+            "SELECT math::max(position) AS max_position FROM reading_queue_item WHERE user_id = $user_id AND is_removed = false GROUP ALL",
+            json!({ "user_id": user_id }),
+        ).await?;
+        let rows: Vec<Value> = response.take(0)?;
+
+        Ok(rows
+            .first()
+            .and_then(|r| r.get("max_position"))
+            .and_then(|v| v.as_i64())
+            .map(|max| max as i32 + 1)
+            .unwrap_or(0))
+    }
+
+    async fn upsert_item(
+        &self,
+        user_id: &str,
+        article_id: &str,
+        position: Option<i32>,
+        progress: Option<f64>,
+        is_removed: bool,
+    ) -> Result<ReadingQueueItem> {
+        // updated_at 始终使用服务器时间，保证其他设备基于令牌的增量同步不会漏掉变更
+        let updated_at = Utc::now();
+        let existing = self.find_item(user_id, article_id).await?;
+
+        let saved: Option<ReadingQueueItem> = match existing {
+            Some(item) => {
+                let mut updates = json!({
+                    "is_removed": is_removed,
+                    "updated_at": updated_at,
+                });
+                if let Some(position) = position {
+                    updates["position"] = json!(position);
+                }
+                if let Some(progress) = progress {
+                    updates["progress"] = json!(progress.clamp(0.0, 1.0));
+                }
+
+                self.db.update_by_id_with_json("reading_queue_item", &item.id, updates).await?
+            }
+            None => {
+                let mut response = self.db.query_with_params(
+                    r#"
+                        CREATE reading_queue_item CONTENT {
+                            user_id: $user_id,
+                            article_id: $article_id,
+                            position: $position,
+                            progress: $progress,
+                            is_removed: $is_removed,
+                            added_at: time::now(),
+                            updated_at: <datetime> $updated_at
+                        }
+                    "#,
+                    json!({
+                        "user_id": user_id,
+                        "article_id": article_id,
+                        "position": position.unwrap_or(0),
+                        "progress": progress.unwrap_or(0.0).clamp(0.0, 1.0),
+                        "is_removed": is_removed,
+                        "updated_at": updated_at,
+                    }),
+                ).await?;
+                let items: Vec<ReadingQueueItem> = response.take(0)?;
+                items.into_iter().next()
+            }
+        };
+
+        saved.ok_or_else(|| AppError::internal("Failed to save reading queue item"))
+    }
+}
+
+/// 统一使用 article:<id> 形式存储文章引用
+fn normalize_article_id(article_id: &str) -> String {
+    if article_id.starts_with("article:") {
+        article_id.to_string()
+    } else {
+        format!("article:{}", article_id)
+    }
+}
@@ -14,7 +14,7 @@ use crate::{
 use chrono::{DateTime, Utc};
 use serde_json::json;
 use std::sync::Arc;
-use tracing::{debug, info, error};
+use tracing::{debug, info, error, warn};
 
 /// 实时通知集成服务
 /// 负责将传统通知与WebSocket实时推送整合
@@ -47,10 +47,19 @@ impl RealtimeService {
     ) -> Result<()> {
         debug!("Sending realtime notification to user: {} type: {}", user_id, notification_type);
 
+        let mapped_type = self.map_notification_type(notification_type);
+        let push_enabled = match self.notification_service.channels_for(user_id, &mapped_type).await {
+            Ok(channels) => channels.push,
+            Err(e) => {
+                warn!("Failed to load notification preferences for {}: {}", user_id, e);
+                true
+            }
+        };
+
         // 1. 发送传统通知（存储到数据库）
         let notification_request = crate::models::notification::CreateNotificationRequest {
             recipient_id: user_id.to_string(),
-            notification_type: self.map_notification_type(notification_type),
+            notification_type: mapped_type,
             title: title.to_string(),
             message: content.to_string(),
             data: data.clone().unwrap_or_else(|| json!({})),
@@ -61,6 +70,10 @@ impl RealtimeService {
         }
 
         // 2. 发送实时WebSocket通知
+        if !push_enabled {
+            return Ok(());
+        }
+
         let ws_message = WebSocketMessage::notification(
             json!({
                 "type": notification_type,
@@ -83,8 +96,12 @@ impl RealtimeService {
     pub async fn notify_article_published(&self, article: &Article) -> Result<()> {
         info!("Broadcasting article published: {}", article.id);
 
-        // 通知作者粉丝
-        let followers = self.get_user_followers(&article.author_id).await?;
+        // 通知作者粉丝；笔名文章不通知账号的粉丝，否则等于公开了笔名背后的作者
+        let followers = if article.pseudonym_id.is_some() {
+            Vec::new()
+        } else {
+            self.get_user_followers(&article.author_id).await?
+        };
         
         for follower_id in followers {
             self.send_notification(
@@ -107,7 +124,7 @@ impl RealtimeService {
             ChannelType::GlobalActivity.channel_name(""),
             json!({
                 "article_id": article.id,
-                "author_id": article.author_id,
+                "author_id": article.public_author_id(),
                 "title": article.title,
                 "excerpt": article.excerpt.clone().unwrap_or_default(),
                 "published_at": article.published_at
@@ -162,6 +179,28 @@ impl RealtimeService {
         Ok(())
     }
 
+    /// 广播评论变更（仅推送，不创建通知；由实时订阅调用）
+    pub async fn broadcast_comment_change(
+        &self,
+        message_type: WebSocketMessageType,
+        article_id: &str,
+        data: serde_json::Value,
+    ) -> Result<()> {
+        let channel = ChannelType::ArticleComments.channel_name(article_id);
+        let broadcast_message = WebSocketMessage::broadcast(message_type, channel.clone(), data);
+
+        self.websocket_service
+            .broadcast_to_channel(&channel, broadcast_message)
+            .await
+    }
+
+    /// 将已存储的通知推送到用户的WebSocket连接
+    pub async fn push_notification(&self, user_id: &str, data: serde_json::Value) -> Result<()> {
+        let ws_message = WebSocketMessage::notification(data, user_id.to_string());
+
+        self.websocket_service.send_to_user(user_id, ws_message).await
+    }
+
     /// 点赞相关实时事件
     pub async fn notify_article_clapped(&self, article_id: &str, user_id: &str, clap_count: i32, total_claps: i32) -> Result<()> {
         debug!("Broadcasting article clap: {} by user: {} count: {}", article_id, user_id, clap_count);
@@ -2,6 +2,7 @@ use crate::{
     error::{AppError, Result},
     models::{
         recommendation::*,
+        reading_progress::{ContinueReadingItem, ReadingProgress},
         article::{Article, ArticleListItem, ArticleStatus, AuthorInfo, PublicationInfo, TagInfo},
         user::UserProfile,
         follow::Follow,
@@ -9,6 +10,8 @@ use crate::{
         bookmark::Bookmark,
         comment::Comment,
         tag::Tag,
+        id::bare_id,
+        pseudonym::Pseudonym,
     },
     services::Database,
 };
@@ -19,6 +22,12 @@ use serde_json::{json, Value};
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+/// 关注一个标签相当于点赞了这么多篇带该标签的文章
+const FOLLOWED_TAG_WEIGHT: f64 = 5.0;
+
+/// 首页关注流每页的最大条数
+const MAX_HOME_FEED_LIMIT: usize = 50;
+
 #[derive(Clone)]
 pub struct RecommendationService {
     db: Arc<Database>,
@@ -144,7 +153,7 @@ impl RecommendationService {
 
         let mut query = r#"
             SELECT *, 
-                (clap_count * 0.3 + view_count * 0.1 + comment_count * 0.4 + bookmark_count * 0.2) as trending_score
+                (weighted_clap_score * 0.3 + view_count * 0.1 + comment_count * 0.4 + bookmark_count * 0.2) as trending_score
             FROM article 
             WHERE status = 'published' 
             AND is_deleted = false
@@ -166,7 +175,8 @@ impl RecommendationService {
         }
 
         if let Some(authors) = &request.authors {
-            query.push_str(" AND author_id IN $authors");
+            // 笔名文章不能通过账号关联出来
+            query.push_str(" AND author_id IN $authors AND pseudonym_id IS NONE");
             params["authors"] = json!(authors);
         }
 
@@ -247,16 +257,13 @@ impl RecommendationService {
     /// 获取用户偏好标签
     async fn get_user_preferred_tags(&self, user_id: &str) -> Result<Vec<TagPreference>> {
         // 先获取用户点赞的文章
-        let clapped_query = "SELECT article_id FROM clap WHERE user_id = $user_id";
+        let clapped_query = "SELECT article_id FROM reaction WHERE user_id = $user_id AND reaction_type = 'clap'";
         
         let mut clap_response = self.db.query_with_params(clapped_query, json!({
             "user_id": user_id
         })).await?;
         
         let clapped_articles: Vec<Value> = clap_response.take(0)?;
-        if clapped_articles.is_empty() {
-            return Ok(Vec::new());
-        }
         
         // 统计每个标签出现的次数
         let mut tag_weights: HashMap<String, (String, f64)> = HashMap::new();
@@ -294,6 +301,23 @@ impl RecommendationService {
             }
         }
         
+        // 主动关注的标签是比点赞更明确的兴趣信号
+        let mut follow_response = self.db.query_with_params(
+            "SELECT type::string(tag_id) AS id, tag_id.name AS name FROM user_tag_follow WHERE user_id = $user_id",
+            json!({ "user_id": user_id })
+        ).await?;
+        let followed_tags: Vec<Value> = follow_response.take(0)?;
+        for tag in followed_tags {
+            if let (Some(id), Some(name)) = (
+                tag.get("id").and_then(|v| v.as_str()),
+                tag.get("name").and_then(|v| v.as_str())
+            ) {
+                let entry = tag_weights.entry(id.replace(['⟨', '⟩'], ""))
+                    .or_insert((name.to_string(), 0.0));
+                entry.1 += FOLLOWED_TAG_WEIGHT;
+            }
+        }
+        
         // 转换为 TagPreference 并排序
         let mut preferences: Vec<TagPreference> = tag_weights.into_iter()
             .map(|(id, (name, weight))| TagPreference {
@@ -314,7 +338,7 @@ impl RecommendationService {
         let query = r#"
             SELECT a.author_id, count() * 1.0 as weight
             FROM article a
-            JOIN clap c ON a.id = c.article_id
+            JOIN reaction c ON a.id = c.article_id AND c.reaction_type = 'clap'
             WHERE c.user_id = $user_id
             GROUP BY a.author_id
             ORDER BY weight DESC
@@ -388,9 +412,9 @@ impl RecommendationService {
             AND a.is_deleted = false
             AND a.author_id != $user_id
             AND a.id NOT IN (
-                SELECT article_id FROM clap WHERE user_id = $user_id
+                SELECT article_id FROM reaction WHERE user_id = $user_id AND reaction_type = 'clap'
             )
-            ORDER BY a.clap_count DESC, a.created_at DESC
+            ORDER BY a.weighted_clap_score DESC, a.created_at DESC
             LIMIT $limit
         "#;
 
@@ -428,10 +452,11 @@ impl RecommendationService {
         let query = r#"
             SELECT * FROM article
             WHERE author_id IN $author_ids
+            AND pseudonym_id IS NONE
             AND status = 'published'
             AND is_deleted = false
             AND id NOT IN (
-                SELECT article_id FROM clap WHERE user_id = $user_id
+                SELECT article_id FROM reaction WHERE user_id = $user_id AND reaction_type = 'clap'
             )
             ORDER BY created_at DESC
             LIMIT $limit
@@ -464,9 +489,9 @@ impl RecommendationService {
         // 简化的相似性计算：基于共同点赞的文章
         let query = r#"
             SELECT c2.user_id, count() as common_claps
-            FROM clap c1
-            JOIN clap c2 ON c1.article_id = c2.article_id
-            WHERE c1.user_id = $user_id
+            FROM reaction c1
+            JOIN reaction c2 ON c1.article_id = c2.article_id AND c2.reaction_type = 'clap'
+            WHERE c1.user_id = $user_id AND c1.reaction_type = 'clap'
             AND c2.user_id != $user_id
             GROUP BY c2.user_id
             ORDER BY common_claps DESC
@@ -504,12 +529,12 @@ impl RecommendationService {
         let query = r#"
             SELECT DISTINCT a.*, count() as popularity
             FROM article a
-            JOIN clap c ON a.id = c.article_id
+            JOIN reaction c ON a.id = c.article_id AND c.reaction_type = 'clap'
             WHERE c.user_id IN $similar_users
             AND a.status = 'published'
             AND a.is_deleted = false
             AND a.id NOT IN (
-                SELECT article_id FROM clap WHERE user_id = $user_id
+                SELECT article_id FROM reaction WHERE user_id = $user_id AND reaction_type = 'clap'
             )
             GROUP BY a.id
             ORDER BY popularity DESC, a.created_at DESC
@@ -614,7 +639,7 @@ impl RecommendationService {
                 created_at,
                 (
                     view_count * 0.1 + 
-                    clap_count * 0.3 + 
+                    weighted_clap_score * 0.3 + 
                     comment_count * 0.4 + 
                     bookmark_count * 0.2 +
                     IF created_at > $week_ago THEN 20 ELSE 0 END
@@ -810,7 +835,7 @@ impl RecommendationService {
             AND a.status = 'published'
             AND a.is_deleted = false
             GROUP BY a.id
-            ORDER BY common_tags DESC, a.clap_count DESC
+            ORDER BY common_tags DESC, a.weighted_clap_score DESC
             LIMIT $limit
         "#;
 
@@ -832,7 +857,7 @@ impl RecommendationService {
 
                 recommendations.push(RecommendedArticle {
                     article: list_item,
-                    score: common_tags * 10.0 + related_article.clap_count as f64 * 0.1,
+                    score: common_tags * 10.0 + related_article.weighted_clap_score * 0.1,
                     reason: "相关主题".to_string(),
                 });
             }
@@ -841,35 +866,147 @@ impl RecommendationService {
         Ok(recommendations)
     }
 
-    /// Helper method to convert article data to ArticleListItem
-    async fn article_to_list_item(&self, article: &Article) -> Result<ArticleListItem> {
-        // Get author info
+    /// 首页关注流：关注的作者和关注的标签（包括其下级标签）的最新文章交替排列。
+    /// 两路各取到当前页为止的文章，交替合并、去重后切出当前页
+    pub async fn get_home_feed(&self, user_id: &str, query: HomeFeedQuery) -> Result<HomeFeed> {
+        let page = query.page.unwrap_or(1).max(1);
+        let limit = query.limit.unwrap_or(20).clamp(1, MAX_HOME_FEED_LIMIT);
+        // 多取一条用来判断是否还有下一页
+        let window = page * limit + 1;
+
         let author_query = r#"
-            SELECT id, username, display_name, avatar_url, is_verified
-            FROM user_profile
-            WHERE user_id = $author_id
+            SELECT * FROM article
+            WHERE author_id INSIDE (SELECT VALUE following_id FROM follow WHERE follower_id = $user_id)
+            AND pseudonym_id IS NONE
+            AND status = 'published'
+            AND is_deleted = false
+            ORDER BY published_at DESC
+            LIMIT $limit
         "#;
-        
-        let mut author_response = self.db.query_with_params(author_query, json!({
-            "author_id": &article.author_id
-        })).await?;
-        
-        let author_data: Vec<Value> = author_response.take(0)?;
-        let author_info = if let Some(author) = author_data.first() {
-            AuthorInfo {
-                id: author["id"].as_str().unwrap_or("").to_string(),
-                username: author["username"].as_str().unwrap_or("").to_string(),
-                display_name: author["display_name"].as_str().unwrap_or("").to_string(),
-                avatar_url: author["avatar_url"].as_str().map(String::from),
-                is_verified: author["is_verified"].as_bool().unwrap_or(false),
+        let tag_query = r#"
+            LET $tags = (SELECT VALUE meta::id(tag_id) FROM user_tag_follow WHERE user_id = $user_id);
+            SELECT *,
+                (SELECT VALUE tag_id.name FROM article_tag
+                    WHERE article_id = $parent.id AND (meta::id(tag_id) INSIDE $tags OR tag_id.parent_id INSIDE $tags)
+                    LIMIT 1)[0] AS matched_tag
+            FROM article
+            WHERE id INSIDE (
+                SELECT VALUE article_id FROM article_tag
+                WHERE meta::id(tag_id) INSIDE $tags OR tag_id.parent_id INSIDE $tags
+            )
+            AND author_id != $user_id
+            AND status = 'published'
+            AND is_deleted = false
+            ORDER BY published_at DESC
+            LIMIT $limit
+        "#;
+        let params = json!({ "user_id": user_id, "limit": window });
+
+        let mut author_response = self.db.query_with_params(author_query, params.clone()).await?;
+        let from_authors: Vec<Article> = author_response.take(0)?;
+        let mut tag_response = self.db.query_with_params(tag_query, params).await?;
+        let from_tags: Vec<Value> = tag_response.take(1)?;
+
+        let mut author_items = Vec::with_capacity(from_authors.len());
+        for article in from_authors {
+            author_items.push((article, "来自您关注的作者".to_string()));
+        }
+        let mut tag_items = Vec::with_capacity(from_tags.len());
+        for article_data in from_tags {
+            let reason = match article_data.get("matched_tag").and_then(|v| v.as_str()) {
+                Some(tag) => format!("来自您关注的标签 #{}", tag),
+                None => "来自您关注的标签".to_string(),
+            };
+            if let Ok(article) = serde_json::from_value::<Article>(article_data) {
+                tag_items.push((article, reason));
             }
+        }
+
+        let merged = interleave(author_items, tag_items, |(article, _)| article.id.clone());
+        let has_more = merged.len() > page * limit;
+
+        let mut articles = Vec::with_capacity(limit);
+        for (position, (article, reason)) in merged.into_iter().enumerate().skip((page - 1) * limit).take(limit) {
+            articles.push(RecommendedArticle {
+                article: self.article_to_list_item(&article).await?,
+                // 按流中的位置给分，越靠前越高
+                score: (window - position) as f64,
+                reason,
+            });
+        }
+
+        Ok(HomeFeed { articles, page, has_more })
+    }
+
+    /// 读到一半的文章，最近读过的在前
+    pub async fn get_continue_reading(&self, user_id: &str, limit: usize) -> Result<Vec<ContinueReadingItem>> {
+        let positions: Vec<ReadingProgress> = self.db
+            .prepare(&format!(
+                "SELECT * FROM reading_progress WHERE user_id = $user_id AND completed = false AND progress > 0 ORDER BY updated_at DESC LIMIT {}",
+                limit
+            ))
+            .bind("user_id", user_id)
+            .fetch()
+            .await?;
+
+        let mut items = Vec::with_capacity(positions.len());
+        for position in positions {
+            let article: Option<Article> = self.db.get_by_id("article", &position.article_id).await?;
+            let Some(article) = article.filter(|a| a.is_published()) else {
+                continue;
+            };
+
+            let remaining = article.reading_time as f64 * (1.0 - position.progress.clamp(0.0, 1.0));
+            items.push(ContinueReadingItem {
+                article: self.article_to_list_item(&article).await?,
+                progress: position.progress,
+                last_paragraph: position.last_paragraph,
+                remaining_minutes: remaining.ceil() as i32,
+                last_read_at: position.updated_at,
+            });
+        }
+
+        Ok(items)
+    }
+
+    /// Helper method to convert article data to ArticleListItem
+    async fn article_to_list_item(&self, article: &Article) -> Result<ArticleListItem> {
+        // Get author info (pseudonymous articles only expose the pseudonym)
+        let author_info = if let Some(pseudonym_id) = &article.pseudonym_id {
+            let pseudonym: Option<Pseudonym> = self.db
+                .get_by_id("pseudonym", bare_id("pseudonym", pseudonym_id))
+                .await?;
+            pseudonym.map(|p| p.author_info()).unwrap_or_else(AuthorInfo::anonymous)
         } else {
-            AuthorInfo {
-                id: article.author_id.clone(),
-                username: "unknown".to_string(),
-                display_name: "Unknown Author".to_string(),
-                avatar_url: None,
-                is_verified: false,
+            let author_query = r#"
+                SELECT id, username, display_name, avatar_url, is_verified
+                FROM user_profile
+                WHERE user_id = $author_id
+            "#;
+        
+            let mut author_response = self.db.query_with_params(author_query, json!({
+                "author_id": &article.author_id
+            })).await?;
+        
+            let author_data: Vec<Value> = author_response.take(0)?;
+            if let Some(author) = author_data.first() {
+                AuthorInfo {
+                    id: author["id"].as_str().unwrap_or("").to_string(),
+                    username: author["username"].as_str().unwrap_or("").to_string(),
+                    display_name: author["display_name"].as_str().unwrap_or("").to_string(),
+                    avatar_url: author["avatar_url"].as_str().map(String::from),
+                    is_verified: author["is_verified"].as_bool().unwrap_or(false),
+                    is_pseudonym: false,
+                }
+            } else {
+                AuthorInfo {
+                    id: article.author_id.clone(),
+                    username: "unknown".to_string(),
+                    display_name: "Unknown Author".to_string(),
+                    avatar_url: None,
+                    is_verified: false,
+                    is_pseudonym: false,
+                }
             }
         };
         
@@ -935,6 +1072,8 @@ impl RecommendationService {
             status: article.status.clone(),
             is_paid_content: article.is_paid_content,
             is_featured: article.is_featured,
+            pinned_at: article.pinned_at,
+            publication_pinned_at: article.publication_pinned_at,
             reading_time: article.reading_time,
             view_count: article.view_count,
             clap_count: article.clap_count,
@@ -944,4 +1083,37 @@ impl RecommendationService {
             published_at: article.published_at,
         })
     }
-}
\ No newline at end of file
+}
+
+/// 两个列表交替合并（从 `first` 开始），`key` 相同的只保留先出现的一个；一方用完后接上另一方剩下的部分
+fn interleave<T, K: Eq + std::hash::Hash>(first: Vec<T>, second: Vec<T>, key: impl Fn(&T) -> K) -> Vec<T> {
+    let mut seen = std::collections::HashSet::new();
+    let mut merged = Vec::with_capacity(first.len() + second.len());
+    let mut first = first.into_iter();
+    let mut second = second.into_iter();
+    loop {
+        let a = first.next();
+        let b = second.next();
+        if a.is_none() && b.is_none() {
+            break;
+        }
+        for item in a.into_iter().chain(b) {
+            if seen.insert(key(&item)) {
+                merged.push(item);
+            }
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interleave_dedupes_and_drains() {
+        let merged = interleave(vec![1, 2, 3, 4], vec![10, 2, 11], |n| *n);
+        assert_eq!(merged, vec![1, 10, 2, 3, 11, 4]);
+        assert!(interleave(Vec::<i32>::new(), vec![], |n| *n).is_empty());
+    }
+}
@@ -0,0 +1,86 @@
+use crate::error::{AppError, Result};
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 以 trait 类型为键的服务注册表
+///
+/// 注册的值是 `Arc<dyn Trait>`，部署时可以替换实现（或不注册以禁用某项能力），
+/// 测试中也可以只注入需要的假实现。
+#[derive(Clone, Default)]
+pub struct ServiceRegistry {
+    services: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+}
+
+impl ServiceRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册（或替换）某个 trait 的实现
+    pub fn insert<T: ?Sized + Send + Sync + 'static>(&mut self, service: Arc<T>) {
+        self.services.insert(TypeId::of::<T>(), Arc::new(service));
+    }
+
+    pub fn with<T: ?Sized + Send + Sync + 'static>(mut self, service: Arc<T>) -> Self {
+        self.insert(service);
+        self
+    }
+
+    pub fn remove<T: ?Sized + 'static>(&mut self) {
+        self.services.remove(&TypeId::of::<T>());
+    }
+
+    pub fn contains<T: ?Sized + 'static>(&self) -> bool {
+        self.services.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn get<T: ?Sized + Send + Sync + 'static>(&self) -> Option<Arc<T>> {
+        self.services
+            .get(&TypeId::of::<T>())
+            .and_then(|service| service.downcast_ref::<Arc<T>>())
+            .cloned()
+    }
+
+    /// 获取必需的服务，未注册时返回内部错误
+    pub fn require<T: ?Sized + Send + Sync + 'static>(&self) -> Result<Arc<T>> {
+        self.get::<T>()
+            .ok_or_else(|| AppError::Internal(format!("Service {} is not registered", type_name::<T>())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    trait Greeter: Send + Sync {
+        fn greet(&self) -> String;
+    }
+
+    struct English;
+    impl Greeter for English {
+        fn greet(&self) -> String {
+            "hello".to_string()
+        }
+    }
+
+    struct Fake;
+    impl Greeter for Fake {
+        fn greet(&self) -> String {
+            "fake".to_string()
+        }
+    }
+
+    #[test]
+    fn test_registry_swaps_trait_implementations() {
+        let mut registry = ServiceRegistry::new().with::<dyn Greeter>(Arc::new(English));
+        assert_eq!(registry.require::<dyn Greeter>().unwrap().greet(), "hello");
+
+        registry.insert::<dyn Greeter>(Arc::new(Fake));
+        assert_eq!(registry.get::<dyn Greeter>().unwrap().greet(), "fake");
+
+        registry.remove::<dyn Greeter>();
+        assert!(!registry.contains::<dyn Greeter>());
+        assert!(registry.require::<dyn Greeter>().is_err());
+    }
+}
@@ -0,0 +1,535 @@
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+    models::{
+        id::bare_id,
+        notification::{CreateNotificationRequest, NotificationType},
+        report::*,
+    },
+    services::{database::PaginatedResult, AdminService, CommentService, Database, NotificationService},
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::{info, warn};
+use validator::Validate;
+
+/// 自动隐藏以系统身份写入审计记录
+const SYSTEM_ACTOR: &str = "system";
+
+#[derive(Debug, Deserialize)]
+struct CountRow {
+    total: usize,
+}
+
+/// 内容举报：用户提交举报，同一对象的举报汇总为案件，达到阈值后自动隐藏，审核员处理案件
+#[derive(Clone)]
+pub struct ReportService {
+    db: Arc<Database>,
+    admin_service: AdminService,
+    comment_service: CommentService,
+    notification_service: NotificationService,
+    hide_threshold: usize,
+}
+
+impl ReportService {
+    pub async fn new(
+        db: Arc<Database>,
+        config: &Config,
+        admin_service: AdminService,
+        comment_service: CommentService,
+        notification_service: NotificationService,
+    ) -> Result<Self> {
+        Ok(Self {
+            db,
+            admin_service,
+            comment_service,
+            notification_service,
+            hide_threshold: config.report_hide_threshold,
+        })
+    }
+
+    /// 提交举报。同一用户在案件处理前不能重复举报同一对象
+    pub async fn create_report(&self, reporter_id: &str, request: CreateReportRequest) -> Result<ContentReport> {
+        request.validate()?;
+
+        let target_id = bare_id(request.target_type.as_str(), &request.target_id).to_string();
+        let owner_id = self.target_owner(request.target_type, &target_id).await?;
+        if owner_id == reporter_id {
+            return Err(AppError::BadRequest("不能举报自己或自己的内容".to_string()));
+        }
+
+        let case_key = ReportCase::key(request.target_type, &target_id);
+        let existing: Option<ReportCase> = self
+            .db
+            .prepare("SELECT * FROM type::thing('report_case', $case_key)")
+            .bind("case_key", &case_key)
+            .fetch_one()
+            .await?;
+
+        match existing {
+            Some(case) if case.status == ReportStatus::Open => {
+                let duplicates: Vec<CountRow> = self
+                    .db
+                    .prepare(
+                        r#"
+                        SELECT count() AS total FROM content_report
+                        WHERE case_id = $case_key AND reporter_id = $reporter_id AND status = 'open'
+                        GROUP ALL
+                        "#,
+                    )
+                    .bind("case_key", &case_key)
+                    .bind("reporter_id", reporter_id)
+                    .fetch()
+                    .await?;
+                if duplicates.first().map_or(0, |d| d.total) > 0 {
+                    return Err(AppError::Conflict("你已经举报过该内容，请等待处理".to_string()));
+                }
+            }
+            // 新对象，或者之前的案件已处理：开启新一轮
+            _ => {
+                self.db
+                    .prepare(
+                        r#"
+                        UPSERT type::thing('report_case', $case_key) CONTENT {
+                            target_type: $target_type,
+                            target_id: $target_id,
+                            target_owner_id: $owner_id,
+                            status: 'open',
+                            report_count: 0,
+                            reasons: [],
+                            first_reported_at: time::now(),
+                            last_reported_at: time::now()
+                        }
+                        "#,
+                    )
+                    .bind("case_key", &case_key)
+                    .bind("target_type", request.target_type)
+                    .bind("target_id", &target_id)
+                    .bind("owner_id", &owner_id)
+                    .execute()
+                    .await?;
+            }
+        }
+
+        let report: Option<ContentReport> = self
+            .db
+            .prepare(
+                r#"
+                CREATE content_report CONTENT {
+                    case_id: $case_key,
+                    target_type: $target_type,
+                    target_id: $target_id,
+                    reporter_id: $reporter_id,
+                    reason: $reason,
+                    details: $details,
+                    status: 'open',
+                    created_at: time::now()
+                }
+                "#,
+            )
+            .bind("case_key", &case_key)
+            .bind("target_type", request.target_type)
+            .bind("target_id", &target_id)
+            .bind("reporter_id", reporter_id)
+            .bind("reason", request.reason)
+            .bind("details", &request.details)
+            .fetch_one()
+            .await?;
+        let report = report.ok_or_else(|| AppError::internal("Failed to create report"))?;
+
+        // 举报人数按本轮未处理的举报重新统计
+        let case: Option<ReportCase> = self
+            .db
+            .prepare(
+                r#"
+                UPDATE type::thing('report_case', $case_key) SET
+                    report_count = (SELECT count() FROM content_report WHERE case_id = $case_key AND status = 'open' GROUP ALL)[0].count ?? 0,
+                    reasons = array::union(reasons, [$reason]),
+                    last_reported_at = time::now()
+                "#,
+            )
+            .bind("case_key", &case_key)
+            .bind("reason", request.reason)
+            .fetch_one()
+            .await?;
+        let case = case.ok_or_else(|| AppError::internal("Failed to update report case"))?;
+
+        if case.report_count == 1 {
+            self.notify_moderators(
+                "New content report",
+                format!("A {} was reported for {}.", case.target_type.as_str(), request.reason.as_str()),
+                json!({ "case_id": case.id, "target_type": case.target_type, "target_id": case.target_id }),
+            )
+            .await;
+        }
+
+        if should_hide(&case, self.hide_threshold) {
+            self.hide_target(&case).await?;
+        }
+
+        info!("User {} reported {} {}", reporter_id, case.target_type.as_str(), case.target_id);
+        Ok(report)
+    }
+
+    /// 当前用户提交过的举报，最新的在前
+    pub async fn list_my_reports(&self, reporter_id: &str, query: ReportQuery) -> Result<PaginatedResult<ContentReport>> {
+        let page = query.page.unwrap_or(1).max(1);
+        let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+        let mut response = self
+            .db
+            .prepare(
+                r#"
+                SELECT count() AS total FROM content_report WHERE reporter_id = $reporter_id GROUP ALL;
+                SELECT * FROM content_report WHERE reporter_id = $reporter_id ORDER BY created_at DESC LIMIT $limit START $offset;
+                "#,
+            )
+            .bind("reporter_id", reporter_id)
+            .bind("limit", limit)
+            .bind("offset", (page - 1) * limit)
+            .execute()
+            .await?;
+
+        let totals: Vec<CountRow> = response.take(0)?;
+        let total = totals.first().map_or(0, |t| t.total);
+        let data: Vec<ContentReport> = response.take(1)?;
+
+        Ok(PaginatedResult {
+            data,
+            total,
+            page,
+            per_page: limit,
+            total_pages: (total + limit - 1) / limit,
+        })
+    }
+
+    /// 审核队列：举报人数多的案件在前
+    pub async fn list_cases(&self, query: ReportQuery) -> Result<PaginatedResult<ReportCase>> {
+        let page = query.page.unwrap_or(1).max(1);
+        let limit = query.limit.unwrap_or(20).clamp(1, 100);
+
+        let mut conditions = vec!["true"];
+        if query.status.is_some() {
+            conditions.push("status = $status");
+        }
+        if query.target_type.is_some() {
+            conditions.push("target_type = $target_type");
+        }
+        match query.hidden {
+            Some(true) => conditions.push("hidden_at != NONE"),
+            Some(false) => conditions.push("hidden_at = NONE"),
+            None => {}
+        }
+        let condition = conditions.join(" AND ");
+
+        let mut response = self
+            .db
+            .prepare(&format!(
+                r#"
+                SELECT count() AS total FROM report_case WHERE {condition} GROUP ALL;
+                SELECT * FROM report_case WHERE {condition}
+                    ORDER BY report_count DESC, first_reported_at ASC
+                    LIMIT $limit START $offset;
+                "#
+            ))
+            .bind("status", query.status)
+            .bind("target_type", query.target_type)
+            .bind("limit", limit)
+            .bind("offset", (page - 1) * limit)
+            .execute()
+            .await?;
+
+        let totals: Vec<CountRow> = response.take(0)?;
+        let total = totals.first().map_or(0, |t| t.total);
+        let data: Vec<ReportCase> = response.take(1)?;
+
+        Ok(PaginatedResult {
+            data,
+            total,
+            page,
+            per_page: limit,
+            total_pages: (total + limit - 1) / limit,
+        })
+    }
+
+    /// 案件及本轮的全部举报；已处理的案件返回处理时关联的举报
+    pub async fn get_case(&self, case_id: &str) -> Result<ReportCaseDetail> {
+        let case_key = bare_id("report_case", case_id);
+        let mut response = self
+            .db
+            .prepare(
+                r#"
+                SELECT * FROM type::thing('report_case', $case_key);
+                SELECT * FROM content_report WHERE case_id = $case_key ORDER BY created_at DESC LIMIT 200;
+                "#,
+            )
+            .bind("case_key", case_key)
+            .execute()
+            .await?;
+
+        let cases: Vec<ReportCase> = response.take(0)?;
+        let case = cases
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::NotFound("举报案件不存在".to_string()))?;
+        let reports: Vec<ContentReport> = response.take(1)?;
+        let reports = match case.status {
+            ReportStatus::Open => reports.into_iter().filter(|r| r.status == ReportStatus::Open).collect(),
+            _ => reports
+                .into_iter()
+                .filter(|r| r.reviewed_at.is_some() && r.reviewed_at == case.reviewed_at)
+                .collect(),
+        };
+
+        Ok(ReportCaseDetail { case, reports })
+    }
+
+    /// 处理案件，本轮的举报一并结案。驳回时恢复自动隐藏的内容；
+    /// 确认违规时自动隐藏的内容保持隐藏，进一步处置（停用账户等）通过管理接口完成
+    pub async fn review_case(&self, actor_id: &str, case_id: &str, request: ReviewReportRequest) -> Result<ReportCase> {
+        request.validate()?;
+        if request.status == ReportStatus::Open {
+            return Err(AppError::BadRequest("status 必须是 resolved 或 dismissed".to_string()));
+        }
+
+        let mut response = self
+            .db
+            .prepare(
+                r#"
+                LET $reviewed_at = time::now();
+                LET $case = UPDATE type::thing('report_case', $case_key) SET
+                    status = $status,
+                    reviewed_by = $reviewed_by,
+                    reviewed_at = $reviewed_at,
+                    resolution_note = $note
+                WHERE status = 'open';
+                IF array::len($case) > 0 {
+                    UPDATE content_report SET
+                        status = $status,
+                        reviewed_by = $reviewed_by,
+                        reviewed_at = $reviewed_at,
+                        resolution_note = $note
+                    WHERE case_id = $case_key AND status = 'open';
+                };
+                RETURN $case;
+                "#,
+            )
+            .bind("case_key", bare_id("report_case", case_id))
+            .bind("status", request.status)
+            .bind("reviewed_by", actor_id)
+            .bind("note", &request.note)
+            .execute()
+            .await?;
+        let cases: Vec<ReportCase> = response.take(3)?;
+        let case = cases
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::NotFound("举报案件不存在或已处理".to_string()))?;
+
+        if case.hidden_at.is_some() {
+            match request.status {
+                ReportStatus::Dismissed => self.restore_target(actor_id, &case).await,
+                _ => {
+                    self.notify_owner(
+                        &case,
+                        "Reported content removed",
+                        format!("Your {} was removed after review.", case.target_type.as_str()),
+                    )
+                    .await
+                }
+            }
+        }
+
+        let action = format!("report.{}", request.status.as_str());
+        self.admin_service
+            .record(actor_id, &action, case.target_type.as_str(), &case.target_id, request.note.as_deref())
+            .await;
+        Ok(case)
+    }
+
+    async fn target_owner(&self, target_type: ReportTargetType, target_id: &str) -> Result<String> {
+        let owner: Option<String> = match target_type {
+            ReportTargetType::Article => {
+                self.db
+                    .prepare("SELECT VALUE author_id FROM type::thing('article', $id) WHERE is_deleted = false")
+                    .bind("id", target_id)
+                    .fetch_one()
+                    .await?
+            }
+            ReportTargetType::Comment => self.comment_service.get_comment(target_id).await?.map(|c| c.author_id),
+            ReportTargetType::User => {
+                self.db
+                    .prepare("SELECT VALUE user_id FROM user_profile WHERE user_id = $id")
+                    .bind("id", target_id)
+                    .fetch_one()
+                    .await?
+            }
+        };
+
+        owner.ok_or_else(|| AppError::NotFound(format!("被举报的{}不存在", target_type.as_str())))
+    }
+
+    async fn hide_target(&self, case: &ReportCase) -> Result<()> {
+        let hidden = match case.target_type {
+            ReportTargetType::Article => {
+                // 与管理员下架相同：归档并标记下架时间，作者在处理前不能重新发布
+                let hidden: Vec<Value> = self
+                    .db
+                    .prepare(
+                        r#"
+                        UPDATE type::thing('article', $id) SET
+                            status = 'archived',
+                            scheduled_at = NONE,
+                            taken_down_at = time::now(),
+                            updated_at = time::now()
+                        WHERE is_deleted = false AND taken_down_at = NONE AND status INSIDE ['published', 'unlisted']
+                        RETURN id
+                        "#,
+                    )
+                    .bind("id", &case.target_id)
+                    .fetch()
+                    .await?;
+                !hidden.is_empty()
+            }
+            ReportTargetType::Comment => self.comment_service.hide_reported(&case.target_id).await?.is_some(),
+            ReportTargetType::User => false,
+        };
+        if !hidden {
+            return Ok(());
+        }
+
+        self.db
+            .prepare("UPDATE type::thing('report_case', $case_key) SET hidden_at = time::now()")
+            .bind("case_key", bare_id("report_case", &case.id))
+            .execute()
+            .await?;
+
+        let reason = format!("{} reports", case.report_count);
+        self.admin_service
+            .record(SYSTEM_ACTOR, "report.auto_hide", case.target_type.as_str(), &case.target_id, Some(&reason))
+            .await;
+        self.notify_moderators(
+            "Reported content hidden",
+            format!("A {} was hidden after {} reports and is waiting for review.", case.target_type.as_str(), case.report_count),
+            json!({ "case_id": case.id, "target_type": case.target_type, "target_id": case.target_id }),
+        )
+        .await;
+        self.notify_owner(
+            case,
+            "Your content is under review",
+            format!("Your {} has been hidden after multiple reports while a moderator reviews it.", case.target_type.as_str()),
+        )
+        .await;
+
+        info!("{} {} hidden after {} reports", case.target_type.as_str(), case.target_id, case.report_count);
+        Ok(())
+    }
+
+    /// 恢复自动隐藏的内容。管理员已手动恢复过时不再处理
+    async fn restore_target(&self, actor_id: &str, case: &ReportCase) {
+        let result = match case.target_type {
+            ReportTargetType::Article => match self.admin_service.restore_article(actor_id, &case.target_id).await {
+                Err(AppError::NotFound(_)) => Ok(()),
+                other => other.map(|_| ()),
+            },
+            ReportTargetType::Comment => match self.comment_service.restore_reported(&case.target_id).await {
+                Ok(Some(_)) => {
+                    self.notify_owner(
+                        case,
+                        "Your comment has been restored",
+                        "Your comment is visible again after review.".to_string(),
+                    )
+                    .await;
+                    Ok(())
+                }
+                other => other.map(|_| ()),
+            },
+            ReportTargetType::User => Ok(()),
+        };
+
+        if let Err(e) = result {
+            warn!("Failed to restore {} {} after dismissed report: {}", case.target_type.as_str(), case.target_id, e);
+        }
+    }
+
+    async fn notify_moderators(&self, title: &str, message: String, data: Value) {
+        let moderators = match self.admin_service.users_with_permission("admin.reports").await {
+            Ok(moderators) => moderators,
+            Err(e) => {
+                warn!("Failed to load moderators for report notification: {}", e);
+                return;
+            }
+        };
+
+        for moderator_id in moderators {
+            let notification = CreateNotificationRequest {
+                recipient_id: moderator_id.clone(),
+                notification_type: NotificationType::ReportQueue,
+                title: title.to_string(),
+                message: message.clone(),
+                data: data.clone(),
+            };
+            if let Err(e) = self.notification_service.create_notification(notification).await {
+                warn!("Failed to notify moderator {} about report: {}", moderator_id, e);
+            }
+        }
+    }
+
+    async fn notify_owner(&self, case: &ReportCase, title: &str, message: String) {
+        let notification = CreateNotificationRequest {
+            recipient_id: case.target_owner_id.clone(),
+            notification_type: NotificationType::Moderation,
+            title: title.to_string(),
+            message,
+            data: json!({ "target_type": case.target_type, "target_id": case.target_id }),
+        };
+        if let Err(e) = self.notification_service.create_notification(notification).await {
+            warn!("Failed to send report outcome to {}: {}", case.target_owner_id, e);
+        }
+    }
+}
+
+/// 文章和评论在达到阈值后自动隐藏；被举报的用户只进入审核队列
+fn should_hide(case: &ReportCase, threshold: usize) -> bool {
+    threshold > 0
+        && case.target_type != ReportTargetType::User
+        && case.hidden_at.is_none()
+        && case.report_count >= threshold
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn case(target_type: ReportTargetType, report_count: usize) -> ReportCase {
+        ReportCase {
+            id: ReportCase::key(target_type, "abc"),
+            target_type,
+            target_id: "abc".to_string(),
+            target_owner_id: "user_1".to_string(),
+            status: ReportStatus::Open,
+            report_count,
+            reasons: vec![ReportReason::Spam],
+            hidden_at: None,
+            first_reported_at: Utc::now(),
+            last_reported_at: Utc::now(),
+            reviewed_by: None,
+            reviewed_at: None,
+            resolution_note: None,
+        }
+    }
+
+    #[test]
+    fn test_hide_threshold() {
+        assert!(!should_hide(&case(ReportTargetType::Article, 4), 5));
+        assert!(should_hide(&case(ReportTargetType::Article, 5), 5));
+        assert!(should_hide(&case(ReportTargetType::Comment, 6), 5));
+        assert!(!should_hide(&case(ReportTargetType::User, 10), 5));
+        assert!(!should_hide(&case(ReportTargetType::Article, 10), 0));
+
+        let hidden = ReportCase { hidden_at: Some(Utc::now()), ..case(ReportTargetType::Article, 8) };
+        assert!(!should_hide(&hidden, 5));
+    }
+}
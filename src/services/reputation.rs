@@ -0,0 +1,199 @@
+use crate::{
+    error::{AppError, Result},
+    models::reputation::*,
+    services::Database,
+    utils::scoring::{reputation_components, ReputationInputs},
+};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+use validator::Validate;
+
+#[derive(Clone)]
+pub struct ReputationService {
+    db: Arc<Database>,
+}
+
+impl ReputationService {
+    pub async fn new(db: Arc<Database>) -> Result<Self> {
+        Ok(Self { db })
+    }
+
+    /// 计算用户信誉，只读取不写入。保存的分数由处罚和定期任务更新
+    pub async fn get_reputation(&self, user_id: &str) -> Result<UserReputation> {
+        debug!("Calculating reputation for user: {}", user_id);
+
+        let inputs = self.gather_inputs(user_id).await?;
+        let components = reputation_components(&inputs);
+        let score = components.total();
+        let level = ReputationLevel::from_score(score);
+
+        Ok(UserReputation {
+            user_id: user_id.to_string(),
+            score,
+            level,
+            breakdown: ReputationBreakdown {
+                engagement: components.engagement,
+                publication_acceptances: components.publication_acceptances,
+                account_age: components.account_age,
+                strike_penalty: Some(components.strike_penalty),
+                accepted_articles: inputs.accepted_articles,
+                active_strikes: Some(inputs.active_strikes),
+                account_age_days: inputs.account_age_days,
+            },
+            privileges: ReputationPrivileges::for_level(level, inputs.active_strikes),
+            calculated_at: Utc::now(),
+        })
+    }
+
+    /// 是否允许在评论中发布链接
+    pub async fn can_post_links(&self, user_id: &str) -> Result<bool> {
+        Ok(self.get_reputation(user_id).await?.privileges.can_post_links)
+    }
+
+    /// 是否允许跳过审核直接发布文章
+    pub async fn can_publish_immediately(&self, user_id: &str) -> Result<bool> {
+        Ok(self.get_reputation(user_id).await?.privileges.can_publish_immediately)
+    }
+
+    /// 对用户记录一次审核处罚
+    pub async fn issue_strike(&self, user_id: &str, issued_by: &str, request: IssueStrikeRequest) -> Result<ModerationStrike> {
+        request.validate()
+            .map_err(|e| AppError::ValidatorError(e))?;
+
+        if user_id == issued_by {
+            return Err(AppError::bad_request("Cannot issue a strike to yourself"));
+        }
+
+        let expires_at = request.duration_days.map(|days| Utc::now() + Duration::days(days));
+        let mut response = self.db.query_with_params(
+            r#"
+                CREATE moderation_strike CONTENT {
+                    user_id: $user_id,
+                    reason: $reason,
+                    issued_by: $issued_by,
+                    expires_at: IF $expires_at THEN <datetime> $expires_at ELSE NONE END,
+                    created_at: time::now()
+                }
+            "#,
+            json!({
+                "user_id": user_id,
+                "reason": request.reason,
+                "issued_by": issued_by,
+                "expires_at": expires_at,
+            }),
+        ).await?;
+        let strikes: Vec<ModerationStrike> = response.take(0)?;
+        let strike = strikes.into_iter().next()
+            .ok_or_else(|| AppError::internal("Failed to create moderation strike"))?;
+
+        // 处罚立即影响信誉
+        self.recalculate(user_id).await?;
+
+        info!("Issued moderation strike to user {} by {}", user_id, issued_by);
+        Ok(strike)
+    }
+
+    pub async fn get_strikes(&self, user_id: &str) -> Result<Vec<ModerationStrike>> {
+        let mut response = self.db.query_with_params(
+            "SELECT * FROM moderation_strike WHERE user_id = $user_id ORDER BY created_at DESC",
+            json!({ "user_id": user_id }),
+        ).await?;
+        Ok(response.take(0)?)
+    }
+
+    /// 重新计算超过一天未更新的信誉分
+    pub async fn refresh_stale_reputations(&self, limit: usize) -> Result<usize> {
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT user_id FROM user_profile
+                WHERE reputation_updated_at IS NONE OR reputation_updated_at < time::now() - 1d
+                LIMIT $limit
+            "#,
+            json!({ "limit": limit }),
+        ).await?;
+        let rows: Vec<Value> = response.take(0)?;
+
+        let mut refreshed = 0;
+        for user_id in rows.iter().filter_map(|r| r.get("user_id").and_then(|v| v.as_str())) {
+            match self.recalculate(user_id).await {
+                Ok(_) => refreshed += 1,
+                Err(e) => warn!("Failed to refresh reputation for {}: {}", user_id, e),
+            }
+        }
+
+        Ok(refreshed)
+    }
+
+    /// 重新计算信誉并写回用户资料
+    async fn recalculate(&self, user_id: &str) -> Result<UserReputation> {
+        let reputation = self.get_reputation(user_id).await?;
+
+        self.db.query_with_params(
+            "UPDATE user_profile SET reputation_score = $score, reputation_updated_at = time::now() WHERE user_id = $user_id",
+            json!({ "user_id": user_id, "score": reputation.score }),
+        ).await?;
+
+        Ok(reputation)
+    }
+
+    async fn gather_inputs(&self, user_id: &str) -> Result<ReputationInputs> {
+        let mut response = self.db.query_with_params(
+            "SELECT created_at, follower_count FROM user_profile WHERE user_id = $user_id LIMIT 1",
+            json!({ "user_id": user_id }),
+        ).await?;
+        let profiles: Vec<Value> = response.take(0)?;
+        let profile = profiles.into_iter().next()
+            .ok_or_else(|| AppError::not_found("User profile"))?;
+
+        let account_age_days = profile.get("created_at")
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.parse::<DateTime<Utc>>().ok())
+            .map(|created_at| (Utc::now() - created_at).num_days())
+            .unwrap_or(0);
+
+        // 收到的互动以及被出版物收录的文章
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT weighted_clap_score, comment_count, publication_id FROM article
+                WHERE author_id = $user_id AND status = 'published' AND is_deleted = false
+            "#,
+            json!({ "user_id": user_id }),
+        ).await?;
+        let articles: Vec<Value> = response.take(0)?;
+
+        let weighted_claps_received = articles.iter()
+            .filter_map(|a| a.get("weighted_clap_score").and_then(|v| v.as_f64()))
+            .sum();
+        let comments_received = articles.iter()
+            .filter_map(|a| a.get("comment_count").and_then(|v| v.as_i64()))
+            .sum();
+        let accepted_articles = articles.iter()
+            .filter(|a| a.get("publication_id").and_then(|v| v.as_str()).map_or(false, |p| !p.is_empty()))
+            .count() as i64;
+
+        let mut response = self.db.query_with_params(
+            r#"
+                SELECT count() AS total FROM moderation_strike
+                WHERE user_id = $user_id AND (expires_at IS NONE OR expires_at > time::now())
+                GROUP ALL
+            "#,
+            json!({ "user_id": user_id }),
+        ).await?;
+        let strike_rows: Vec<Value> = response.take(0)?;
+        let active_strikes = strike_rows.first()
+            .and_then(|r| r.get("total"))
+            .and_then(|v| v.as_i64())
+            .unwrap_or(0);
+
+        Ok(ReputationInputs {
+            weighted_claps_received,
+            comments_received,
+            followers: profile.get("follower_count").and_then(|v| v.as_i64()).unwrap_or(0),
+            accepted_articles,
+            active_strikes,
+            account_age_days,
+        })
+    }
+}
@@ -0,0 +1,363 @@
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+    models::admin::ResponseCacheStats,
+};
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tracing::{debug, info, warn};
+
+/// 被缓存的读取接口，失效时按命名空间和 ID 清除
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheNamespace {
+    /// 文章详情，按 slug
+    Article,
+    /// 出版物主页，按 slug
+    Publication,
+    /// 标签页的文章列表，按标签 slug，不同分页和排序是同一 ID 下的不同条目
+    Tag,
+}
+
+impl CacheNamespace {
+    const ALL: [CacheNamespace; 3] = [CacheNamespace::Article, CacheNamespace::Publication, CacheNamespace::Tag];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheNamespace::Article => "article",
+            CacheNamespace::Publication => "publication",
+            CacheNamespace::Tag => "tag",
+        }
+    }
+}
+
+/// 缓存条目的存储，值为 JSON 序列化后的字节
+#[async_trait]
+pub trait ResponseCacheStore: Send + Sync {
+    async fn get(&self, key: &str) -> Result<Option<Arc<[u8]>>>;
+
+    async fn set(&self, key: &str, value: Arc<[u8]>) -> Result<()>;
+
+    /// 删除以 `prefix` 开头的所有条目
+    async fn invalidate_prefix(&self, prefix: &str) -> Result<()>;
+}
+
+/// 进程内缓存，多副本部署时各副本分别失效，只能靠写入所在的副本清除
+struct MemoryStore {
+    cache: moka::future::Cache<String, Arc<[u8]>>,
+}
+
+impl MemoryStore {
+    fn new(capacity: u64, ttl: Duration) -> Self {
+        let cache = moka::future::Cache::builder()
+            .max_capacity(capacity)
+            .time_to_live(ttl)
+            .support_invalidation_closures()
+            .build();
+
+        Self { cache }
+    }
+}
+
+#[async_trait]
+impl ResponseCacheStore for MemoryStore {
+    async fn get(&self, key: &str) -> Result<Option<Arc<[u8]>>> {
+        Ok(self.cache.get(key).await)
+    }
+
+    async fn set(&self, key: &str, value: Arc<[u8]>) -> Result<()> {
+        self.cache.insert(key.to_string(), value).await;
+        Ok(())
+    }
+
+    async fn invalidate_prefix(&self, prefix: &str) -> Result<()> {
+        let prefix = prefix.to_string();
+        self.cache
+            .invalidate_entries_if(move |key, _| key.starts_with(&prefix))
+            .map_err(|e| AppError::Internal(format!("Failed to invalidate response cache: {}", e)))?;
+        Ok(())
+    }
+}
+
+/// 缓存保存在 Redis，所有副本共享，写入后的失效对所有副本生效
+#[cfg(feature = "redis-cache")]
+struct RedisStore {
+    connection: redis::aio::ConnectionManager,
+    ttl_secs: u64,
+}
+
+#[cfg(feature = "redis-cache")]
+impl RedisStore {
+    const KEY_PREFIX: &'static str = "response_cache:";
+
+    async fn connect(url: &str, ttl: Duration) -> Result<Self> {
+        let client = redis::Client::open(url)
+            .map_err(|e| AppError::Internal(format!("Invalid REDIS_URL: {}", e)))?;
+        let connection = redis::aio::ConnectionManager::new(client)
+            .await
+            .map_err(|e| AppError::ExternalService(format!("Failed to connect to Redis: {}", e)))?;
+
+        Ok(Self { connection, ttl_secs: ttl.as_secs().max(1) })
+    }
+}
+
+#[cfg(feature = "redis-cache")]
+#[async_trait]
+impl ResponseCacheStore for RedisStore {
+    async fn get(&self, key: &str) -> Result<Option<Arc<[u8]>>> {
+        let mut connection = self.connection.clone();
+        let value: Option<Vec<u8>> = redis::cmd("GET")
+            .arg(format!("{}{}", Self::KEY_PREFIX, key))
+            .query_async(&mut connection)
+            .await
+            .map_err(|e| AppError::ExternalService(format!("Redis cache read failed: {}", e)))?;
+
+        Ok(value.map(Arc::from))
+    }
+
+    async fn set(&self, key: &str, value: Arc<[u8]>) -> Result<()> {
+        let mut connection = self.connection.clone();
+        redis::cmd("SET")
+            .arg(format!("{}{}", Self::KEY_PREFIX, key))
+            .arg(&*value)
+            .arg("EX")
+            .arg(self.ttl_secs)
+            .query_async::<_, ()>(&mut connection)
+            .await
+            .map_err(|e| AppError::ExternalService(format!("Redis cache write failed: {}", e)))
+    }
+
+    async fn invalidate_prefix(&self, prefix: &str) -> Result<()> {
+        // slug 中可能出现 glob 字符，匹配前转义
+        let escaped: String = prefix
+            .chars()
+            .flat_map(|c| match c {
+                '*' | '?' | '[' | ']' | '\\' => vec!['\\', c],
+                c => vec![c],
+            })
+            .collect();
+        let pattern = format!("{}{}*", Self::KEY_PREFIX, escaped);
+
+        let mut connection = self.connection.clone();
+        let mut cursor: u64 = 0;
+        loop {
+            let (next, keys): (u64, Vec<String>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(&pattern)
+                .arg("COUNT")
+                .arg(500)
+                .query_async(&mut connection)
+                .await
+                .map_err(|e| AppError::ExternalService(format!("Redis cache scan failed: {}", e)))?;
+            if !keys.is_empty() {
+                redis::cmd("DEL")
+                    .arg(keys)
+                    .query_async::<_, ()>(&mut connection)
+                    .await
+                    .map_err(|e| AppError::ExternalService(format!("Redis cache invalidation failed: {}", e)))?;
+            }
+            if next == 0 {
+                return Ok(());
+            }
+            cursor = next;
+        }
+    }
+}
+
+#[derive(Default)]
+struct NamespaceCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// 热点读取的响应缓存。缓存存储出错时直接读数据库，不影响请求
+#[derive(Clone)]
+pub struct ResponseCacheService {
+    /// RESPONSE_CACHE_STORE=none 时为 None
+    store: Option<Arc<dyn ResponseCacheStore>>,
+    counters: Arc<[NamespaceCounters; 3]>,
+}
+
+impl ResponseCacheService {
+    pub async fn new(config: &Config) -> Result<Self> {
+        let ttl = Duration::from_secs(config.response_cache_ttl);
+        let store: Option<Arc<dyn ResponseCacheStore>> = match config.response_cache_store.as_str() {
+            "memory" => Some(Arc::new(MemoryStore::new(config.response_cache_capacity, ttl))),
+            #[cfg(feature = "redis-cache")]
+            "redis" => {
+                let url = config.redis_url.as_deref().ok_or_else(|| {
+                    AppError::Internal("RESPONSE_CACHE_STORE=redis requires REDIS_URL".to_string())
+                })?;
+                Some(Arc::new(RedisStore::connect(url, ttl).await?))
+            }
+            #[cfg(not(feature = "redis-cache"))]
+            "redis" => {
+                return Err(AppError::Internal(
+                    "RESPONSE_CACHE_STORE=redis requires the redis-cache feature".to_string(),
+                ))
+            }
+            "none" => None,
+            other => {
+                return Err(AppError::Internal(format!("Unknown RESPONSE_CACHE_STORE: {}", other)));
+            }
+        };
+
+        info!(
+            "Response cache using {} store, ttl {}s",
+            config.response_cache_store, config.response_cache_ttl
+        );
+        Ok(Self::from_store(store))
+    }
+
+    fn from_store(store: Option<Arc<dyn ResponseCacheStore>>) -> Self {
+        Self {
+            store,
+            counters: Arc::new(Default::default()),
+        }
+    }
+
+    /// 读取缓存，未命中时调用 `load` 并写入缓存。`variant` 区分同一 ID 下的不同条目（如分页），
+    /// 失效时一起清除
+    pub async fn get_or_load<T, F, Fut>(&self, namespace: CacheNamespace, id: &str, variant: &str, load: F) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let Some(store) = &self.store else {
+            return load().await;
+        };
+
+        let key = format!("{}{}", entry_prefix(namespace, id), variant);
+        match store.get(&key).await {
+            Ok(Some(bytes)) => match serde_json::from_slice(&bytes) {
+                Ok(value) => {
+                    self.record(namespace, true);
+                    return Ok(value);
+                }
+                Err(e) => warn!("Discarding unreadable response cache entry {}: {}", key, e),
+            },
+            Ok(None) => {}
+            Err(e) => warn!("Response cache unavailable, reading from database: {}", e),
+        }
+        self.record(namespace, false);
+
+        let value = load().await?;
+        match serde_json::to_vec(&value) {
+            Ok(bytes) => {
+                if let Err(e) = store.set(&key, Arc::from(bytes)).await {
+                    warn!("Failed to write response cache entry {}: {}", key, e);
+                }
+            }
+            Err(e) => warn!("Failed to serialize response cache entry {}: {}", key, e),
+        }
+        Ok(value)
+    }
+
+    /// 清除一个 ID 下的所有条目。失败只记录日志，条目最多在有效期结束后过期
+    pub async fn invalidate(&self, namespace: CacheNamespace, id: &str) {
+        let Some(store) = &self.store else {
+            return;
+        };
+
+        debug!("Invalidating response cache for {} {}", namespace.as_str(), id);
+        if let Err(e) = store.invalidate_prefix(&entry_prefix(namespace, id)).await {
+            warn!("Failed to invalidate response cache for {} {}: {}", namespace.as_str(), id, e);
+        }
+    }
+
+    pub async fn invalidate_all(&self, entries: &[(CacheNamespace, String)]) {
+        for (namespace, id) in entries {
+            self.invalidate(*namespace, id).await;
+        }
+    }
+
+    /// 各命名空间自启动以来的命中率
+    pub fn stats(&self) -> Vec<ResponseCacheStats> {
+        CacheNamespace::ALL
+            .iter()
+            .map(|namespace| {
+                let counters = &self.counters[*namespace as usize];
+                let hits = counters.hits.load(Ordering::Relaxed);
+                let misses = counters.misses.load(Ordering::Relaxed);
+                let total = hits + misses;
+                ResponseCacheStats {
+                    namespace: namespace.as_str().to_string(),
+                    hits,
+                    misses,
+                    hit_rate: if total == 0 { 0.0 } else { hits as f64 / total as f64 },
+                }
+            })
+            .collect()
+    }
+
+    fn record(&self, namespace: CacheNamespace, hit: bool) {
+        let counters = &self.counters[namespace as usize];
+        if hit {
+            counters.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            counters.misses.fetch_add(1, Ordering::Relaxed);
+        }
+
+        #[cfg(feature = "metrics")]
+        {
+            let name = if hit { "response_cache_hits_total" } else { "response_cache_misses_total" };
+            metrics::increment_counter!(name, "namespace" => namespace.as_str());
+        }
+    }
+}
+
+/// 同一 ID 的条目共用的键前缀，末尾的分隔符避免 `a` 的失效波及 `ab`
+fn entry_prefix(namespace: CacheNamespace, id: &str) -> String {
+    format!("{}:{}:", namespace.as_str(), id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_hits_and_invalidation() {
+        let store: Arc<dyn ResponseCacheStore> = Arc::new(MemoryStore::new(100, Duration::from_secs(60)));
+        let cache = ResponseCacheService::from_store(Some(store));
+
+        let first: String = cache
+            .get_or_load(CacheNamespace::Tag, "rust", "page=1", || async { Ok("v1".to_string()) })
+            .await
+            .unwrap();
+        let cached: String = cache
+            .get_or_load(CacheNamespace::Tag, "rust", "page=1", || async { Ok("v2".to_string()) })
+            .await
+            .unwrap();
+        assert_eq!((first.as_str(), cached.as_str()), ("v1", "v1"));
+
+        // 相同前缀的其他 ID 不受影响
+        cache
+            .get_or_load(CacheNamespace::Tag, "rust-lang", "page=1", || async { Ok("other".to_string()) })
+            .await
+            .unwrap();
+        cache.invalidate(CacheNamespace::Tag, "rust").await;
+
+        let reloaded: String = cache
+            .get_or_load(CacheNamespace::Tag, "rust", "page=1", || async { Ok("v2".to_string()) })
+            .await
+            .unwrap();
+        let untouched: String = cache
+            .get_or_load(CacheNamespace::Tag, "rust-lang", "page=1", || async { Ok("changed".to_string()) })
+            .await
+            .unwrap();
+        assert_eq!((reloaded.as_str(), untouched.as_str()), ("v2", "other"));
+
+        let tag = cache.stats().into_iter().find(|s| s.namespace == "tag").unwrap();
+        assert_eq!((tag.hits, tag.misses), (2, 3));
+        assert!((tag.hit_rate - 0.4).abs() < f64::EPSILON);
+    }
+}
@@ -1,14 +1,25 @@
 use crate::{
+    config::Config,
     error::{AppError, Result},
-    models::revenue::*,
+    models::{
+        gift::{Gift, GiftType},
+        payment::ArticlePurchase,
+        revenue::*,
+    },
     services::{
-        stripe::{StripePurchaseUpdate, StripeService, StripeSubscriptionRevenue},
+        stripe::{
+            StripeChargeReversal, StripeChargeReversalKind, StripePurchaseUpdate, StripeService,
+            StripeSubscriptionRevenue,
+        },
         Database,
     },
 };
 use chrono::{DateTime, Datelike, Duration, Utc};
 use serde_json::{json, Value};
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+};
 use tracing::{debug, error, info, warn};
 use validator::Validate;
 
@@ -18,19 +29,29 @@ pub struct RevenueService {
     stripe_service: Arc<StripeService>,
     revenue_share: RevenueShare,
     minimum_payout_amount: i64, // 最低提现金额（美分）
+    payout_hold_days: i64,
 }
 
 impl RevenueService {
-    pub async fn new(db: Arc<Database>, stripe_service: Arc<StripeService>) -> Result<Self> {
+    pub async fn new(db: Arc<Database>, stripe_service: Arc<StripeService>, config: &Config) -> Result<Self> {
         Ok(Self {
             db,
             stripe_service,
-            revenue_share: RevenueShare::default(),
-            minimum_payout_amount: 5000, // $50最低提现
+            revenue_share: RevenueShare::with_platform_fee(config.platform_fee_percentage),
+            minimum_payout_amount: config.minimum_payout_amount,
+            payout_hold_days: config.payout_hold_days,
         })
     }
 
-    /// 记录收益
+    pub fn revenue_share(&self) -> &RevenueShare {
+        &self.revenue_share
+    }
+
+    pub fn minimum_payout_amount(&self) -> i64 {
+        self.minimum_payout_amount
+    }
+
+    /// 记录收益。`gross_amount` 不含代收税费，税费单独记录，不参与分成
     pub async fn record_revenue(
         &self,
         creator_id: &str,
@@ -38,6 +59,7 @@ impl RevenueService {
         source_id: &str,
         gross_amount: i64,
         currency: &str,
+        tax: &CollectedTax,
     ) -> Result<RevenueRecord> {
         debug!("Recording revenue for creator: {}", creator_id);
 
@@ -67,6 +89,8 @@ impl RevenueService {
                 amount: $amount,
                 platform_fee: $platform_fee,
                 processing_fee: $processing_fee,
+                tax_amount: $tax_amount,
+                tax_country: $tax_country,
                 currency: $currency,
                 status: $status,
                 period_start: $period_start,
@@ -92,6 +116,8 @@ impl RevenueService {
                     "amount": creator_amount,
                     "platform_fee": platform_fee,
                     "processing_fee": processing_fee,
+                    "tax_amount": tax.amount,
+                    "tax_country": tax.country,
                     "currency": currency,
                     "status": RevenueStatus::Pending,
                     "period_start": period_start,
@@ -146,33 +172,263 @@ impl RevenueService {
             &update.creator_id,
             RevenueSourceType::ArticlePurchase,
             &source_id,
-            update.amount,
+            update.amount - update.tax.amount,
             &update.currency,
+            &update.tax,
         )
         .await
         .map(Some)
     }
 
+    /// 每张订阅发票记一条收益，并记下发票和 PaymentIntent，退款或争议时据此冲回
     pub async fn record_subscription_revenue_from_webhook(
         &self,
         revenue: &StripeSubscriptionRevenue,
     ) -> Result<Option<RevenueRecord>> {
-        if self
-            .revenue_record_exists(RevenueSourceType::Subscription, &revenue.subscription_id)
-            .await?
-        {
+        let recorded = match &revenue.stripe_invoice_id {
+            Some(invoice_id) => self.subscription_invoice_recorded(invoice_id).await?,
+            None => {
+                self.revenue_record_exists(RevenueSourceType::Subscription, &revenue.subscription_id)
+                    .await?
+            }
+        };
+        if recorded {
             return Ok(None);
         }
 
-        self.record_revenue(
-            &revenue.creator_id,
-            RevenueSourceType::Subscription,
-            &revenue.subscription_id,
-            revenue.amount,
-            &revenue.currency,
-        )
-        .await
-        .map(Some)
+        let record = self
+            .record_revenue(
+                &revenue.creator_id,
+                RevenueSourceType::Subscription,
+                &revenue.subscription_id,
+                revenue.amount - revenue.tax.amount,
+                &revenue.currency,
+                &revenue.tax,
+            )
+            .await?;
+
+        self.db
+            .query_with_params(
+                "UPDATE type::thing($id) SET stripe_invoice_id = $invoice_id, stripe_payment_intent_id = $intent_id",
+                json!({
+                    "id": record.id,
+                    "invoice_id": revenue.stripe_invoice_id,
+                    "intent_id": revenue.stripe_payment_intent_id,
+                }),
+            )
+            .await?;
+
+        Ok(Some(record))
+    }
+
+    /// 礼物支付成功后按礼物内容记入文章购买或订阅收益，重复的 Webhook 不会重复记账
+    pub async fn record_gift_revenue(
+        &self,
+        gift: &Gift,
+        tax: &CollectedTax,
+    ) -> Result<Option<RevenueRecord>> {
+        let source_type = match gift.gift_type {
+            GiftType::Article => RevenueSourceType::ArticlePurchase,
+            GiftType::Subscription => RevenueSourceType::Subscription,
+        };
+
+        if self.revenue_record_exists(source_type.clone(), &gift.id).await? {
+            return Ok(None);
+        }
+
+        self.record_revenue(&gift.creator_id, source_type, &gift.id, gift.amount, &gift.currency, tax)
+            .await
+            .map(Some)
+    }
+
+    /// 按退款或争议冲回文章购买收益，返回本次调整的创作者金额（负数表示争议胜诉后恢复）。
+    /// 以累计退款金额为准，重复或乱序的事件不会重复扣减
+    pub async fn reverse_purchase_revenue(
+        &self,
+        purchase: &ArticlePurchase,
+        reversal: &StripeChargeReversal,
+    ) -> Result<i64> {
+        let mut response = self
+            .db
+            .query_with_params(
+                r#"
+                SELECT * FROM revenue
+                WHERE source_type = 'article_purchase' AND source_id INSIDE $source_ids AND !reverses
+                LIMIT 1
+                "#,
+                json!({
+                    "source_ids": [Some(&purchase.id), purchase.stripe_payment_intent_id.as_ref()],
+                }),
+            )
+            .await?;
+
+        let records: Vec<Value> = response.take(0)?;
+        let Some(original) = records.into_iter().next() else {
+            warn!("No revenue recorded for purchase {}, nothing to reverse", purchase.id);
+            return Ok(0);
+        };
+
+        self.reverse_revenue(&original, purchase.amount, purchase.charged_amount(), reversal)
+            .await
+    }
+
+    /// 按退款或争议冲回礼物收益，礼物收益按礼物内容记在文章购买或订阅下
+    pub async fn reverse_gift_revenue(
+        &self,
+        gift: &Gift,
+        reversal: &StripeChargeReversal,
+    ) -> Result<i64> {
+        let mut response = self
+            .db
+            .query_with_params(
+                r#"
+                SELECT * FROM revenue
+                WHERE source_type INSIDE ['article_purchase', 'subscription'] AND source_id = $source_id AND !reverses
+                LIMIT 1
+                "#,
+                json!({ "source_id": gift.id }),
+            )
+            .await?;
+
+        let records: Vec<Value> = response.take(0)?;
+        let Some(original) = records.into_iter().next() else {
+            warn!("No revenue recorded for gift {}, nothing to reverse", gift.id);
+            return Ok(0);
+        };
+
+        self.reverse_revenue(&original, gift.amount, gift.charged_amount(), reversal)
+            .await
+    }
+
+    /// 按退款或争议冲回订阅发票的收益。先按发票 ID 匹配，争议事件没有发票 ID 时按 PaymentIntent 匹配；
+    /// 不是订阅发票的收款返回 `None`
+    pub async fn reverse_subscription_revenue(
+        &self,
+        reversal: &StripeChargeReversal,
+    ) -> Result<Option<SubscriptionRevenueReversal>> {
+        let mut response = self
+            .db
+            .query_with_params(
+                r#"
+                SELECT * FROM revenue
+                WHERE source_type = 'subscription' AND !reverses
+                    AND ((stripe_invoice_id != NONE AND stripe_invoice_id = $invoice_id)
+                        OR stripe_payment_intent_id = $intent_id)
+                LIMIT 1
+                "#,
+                json!({
+                    "invoice_id": reversal.stripe_invoice_id,
+                    "intent_id": reversal.stripe_payment_intent_id,
+                }),
+            )
+            .await?;
+
+        let records: Vec<Value> = response.take(0)?;
+        let Some(original) = records.into_iter().next() else {
+            return Ok(None);
+        };
+
+        let gross_amount = original["gross_amount"].as_i64().unwrap_or(0);
+        let charged_amount = gross_amount + original["tax_amount"].as_i64().unwrap_or(0);
+        let reversed_amount = self
+            .reverse_revenue(&original, gross_amount, charged_amount, reversal)
+            .await?;
+
+        Ok(Some(SubscriptionRevenueReversal {
+            subscription_id: original["source_id"].as_str().unwrap_or_default().to_string(),
+            charged_amount,
+            reversed_amount,
+        }))
+    }
+
+    /// 冲回一条收益记录：原记录累计冲回 `reversed_amount`，另记一条负数收益并扣减创作者余额。
+    /// `gross_amount` 是原收款不含税的金额，`charged_amount` 是含税的实际扣款
+    async fn reverse_revenue(
+        &self,
+        original: &Value,
+        gross_amount: i64,
+        charged_amount: i64,
+        reversal: &StripeChargeReversal,
+    ) -> Result<i64> {
+        let original_amount = original["amount"].as_i64().unwrap_or(0);
+        let already_reversed = original["reversed_amount"].as_i64().unwrap_or(0);
+        // 退款金额含税，按比例折算出不含税的部分
+        let refunded_gross = match reversal.kind {
+            StripeChargeReversalKind::DisputeWon => 0,
+            _ => {
+                let charged = charged_amount.max(1);
+                (reversal.amount * gross_amount / charged).min(gross_amount)
+            }
+        };
+        let target = calculate_creator_revenue(refunded_gross, &self.revenue_share).clamp(0, original_amount);
+        let delta = target - already_reversed;
+        if delta == 0 {
+            return Ok(0);
+        }
+
+        let original_id = original["id"].as_str().unwrap_or_default().to_string();
+        let released = original["status"].as_str() == Some("completed");
+        let now = Utc::now();
+
+        // 等待期内的收益结算时会扣除 reversed_amount；已结算的从可提现余额扣
+        let balance_field = if released { "available_balance" } else { "pending_balance" };
+        let query = format!(
+            r#"
+            UPDATE type::thing($original_id) SET reversed_amount = $target, refunded_gross = $refunded_gross;
+            CREATE revenue CONTENT {{
+                id: $revenue_id,
+                creator_id: $creator_id,
+                source_type: $source_type,
+                source_id: $source_id,
+                gross_amount: $gross_delta,
+                amount: $amount,
+                platform_fee: 0,
+                processing_fee: 0,
+                currency: $currency,
+                status: 'completed',
+                reverses: $original_id,
+                reversal_reason: $reason,
+                period_start: $now,
+                period_end: $now,
+                created_at: $now,
+                processed_at: $now
+            }};
+            UPDATE creator_earnings SET
+                {balance_field} -= $delta,
+                total_earnings -= $delta,
+                lifetime_earnings -= $delta,
+                updated_at = $now
+            WHERE creator_id = $creator_id;
+            "#,
+            balance_field = balance_field
+        );
+
+        self.db
+            .query_with_params(
+                &query,
+                json!({
+                    "original_id": original_id,
+                    "target": target,
+                    "refunded_gross": refunded_gross,
+                    "revenue_id": format!("revenue:{}", uuid::Uuid::new_v4()),
+                    "creator_id": original["creator_id"],
+                    "source_type": original["source_type"],
+                    "source_id": original["source_id"],
+                    "gross_delta": -(refunded_gross - original["refunded_gross"].as_i64().unwrap_or(0)),
+                    "amount": -delta,
+                    "delta": delta,
+                    "currency": reversal.currency,
+                    "reason": reversal.kind.as_str(),
+                    "now": now
+                }),
+            )
+            .await?;
+
+        info!(
+            "Reversed {} of creator revenue {} ({})",
+            delta, original_id, reversal.kind.as_str()
+        );
+        Ok(delta)
     }
 
     /// 更新创作者收益汇总
@@ -230,6 +486,19 @@ impl RevenueService {
         Ok(())
     }
 
+    async fn subscription_invoice_recorded(&self, stripe_invoice_id: &str) -> Result<bool> {
+        let mut response = self
+            .db
+            .query_with_params(
+                "SELECT id FROM revenue WHERE source_type = 'subscription' AND stripe_invoice_id = $invoice_id LIMIT 1",
+                json!({ "invoice_id": stripe_invoice_id }),
+            )
+            .await?;
+
+        let records: Vec<Value> = response.take(0)?;
+        Ok(!records.is_empty())
+    }
+
     async fn revenue_record_exists(
         &self,
         source_type: RevenueSourceType,
@@ -529,7 +798,7 @@ impl RevenueService {
         Ok(content_earnings)
     }
 
-    /// 创建支付
+    /// 创作者手动发起打款，立即向 Connect 账户转账
     pub async fn create_payout(
         &self,
         creator_id: &str,
@@ -562,8 +831,289 @@ impl RevenueService {
             )));
         }
 
-        let payout_id = format!("payout:{}", uuid::Uuid::new_v4());
+        let Some(stripe_account_id) = self.payout_destination(creator_id).await? else {
+            return Err(AppError::BadRequest(
+                "请先完成 Stripe Connect 账户开通后再提现".to_string(),
+            ));
+        };
+
+        let payout = self
+            .insert_payout(
+                creator_id,
+                request.amount,
+                &earnings.currency,
+                request.bank_account_id.as_deref(),
+                request.description.as_deref(),
+            )
+            .await?;
+
+        self.execute_payout(payout, &stripe_account_id).await
+    }
+
+    /// 定时打款：把过了等待期的收益转为可用余额，重试未完成的打款，
+    /// 每月 1 日再为可用余额达到最低金额的创作者发起转账。返回成功转账的笔数
+    pub async fn run_scheduled_payouts(&self) -> Result<usize> {
+        let cutoff_date = Utc::now() - Duration::days(self.payout_hold_days);
+        let mut response = self
+            .db
+            .query_with_params(
+                "SELECT creator_id FROM revenue WHERE status = 'pending' AND created_at <= $cutoff_date GROUP BY creator_id",
+                json!({ "cutoff_date": cutoff_date }),
+            )
+            .await?;
+        let matured: Vec<Value> = response.take(0)?;
+        for creator_id in matured.iter().filter_map(|r| r["creator_id"].as_str()) {
+            self.process_pending_revenues(creator_id).await?;
+        }
+
+        let mut completed = 0;
+
+        // 转账前中断的打款（余额已经扣除）。转账带幂等键，重试不会重复打款
+        let mut response = self
+            .db
+            .query_with_params(
+                r#"
+                SELECT * FROM payout
+                WHERE status IN ['pending', 'processing'] AND method = 'stripe' AND created_at <= $stale_before
+                ORDER BY created_at ASC
+                "#,
+                json!({ "stale_before": Utc::now() - Duration::minutes(10) }),
+            )
+            .await?;
+        let stalled: Vec<Value> = response.take(0)?;
+        for payout in stalled {
+            let payout = self.parse_payout(payout)?;
+            let Some(stripe_account_id) = self.payout_destination(&payout.creator_id).await? else {
+                continue;
+            };
+            if self.execute_payout(payout, &stripe_account_id).await?.status == PayoutStatus::Completed {
+                completed += 1;
+            }
+        }
+
+        // 新的打款每月 1 日（UTC）发起，当天已经打过款的创作者不再重复
         let now = Utc::now();
+        if now.day() != 1 {
+            return Ok(completed);
+        }
+        let today = now.date_naive().and_hms_opt(0, 0, 0).unwrap().and_utc();
+
+        let mut response = self
+            .db
+            .query_with_params(
+                r#"
+                SELECT creator_id, available_balance, currency FROM creator_earnings
+                WHERE available_balance >= $minimum AND (!last_payout_at OR last_payout_at < $today)
+                "#,
+                json!({ "minimum": self.minimum_payout_amount, "today": today }),
+            )
+            .await?;
+        let eligible: Vec<Value> = response.take(0)?;
+
+        for record in eligible {
+            let (Some(creator_id), Some(amount)) = (
+                record["creator_id"].as_str(),
+                record["available_balance"].as_i64(),
+            ) else {
+                continue;
+            };
+            let Some(stripe_account_id) = self.payout_destination(creator_id).await? else {
+                debug!("Skipping scheduled payout for {}: payouts not enabled", creator_id);
+                continue;
+            };
+
+            let currency = record["currency"].as_str().unwrap_or("USD");
+            let payout = match self
+                .insert_payout(creator_id, amount, currency, None, Some("自动打款"))
+                .await
+            {
+                Ok(payout) => payout,
+                Err(e) => {
+                    warn!("Failed to create scheduled payout for {}: {}", creator_id, e);
+                    continue;
+                }
+            };
+
+            if self.execute_payout(payout, &stripe_account_id).await?.status == PayoutStatus::Completed {
+                completed += 1;
+            }
+        }
+
+        if completed > 0 {
+            info!("Completed {} scheduled creator payouts", completed);
+        }
+        Ok(completed)
+    }
+
+    /// 创作者余额概览
+    pub async fn get_balance(&self, creator_id: &str) -> Result<CreatorBalance> {
+        let earnings = self.get_creator_earnings(creator_id).await?;
+        let in_transit: i64 = self
+            .get_pending_payouts(creator_id)
+            .await?
+            .iter()
+            .map(|p| p.amount)
+            .sum();
+        let payouts_enabled = self.payout_destination(creator_id).await?.is_some();
+
+        Ok(CreatorBalance {
+            available_balance: earnings.available_balance,
+            pending_balance: earnings.pending_balance,
+            in_transit,
+            lifetime_earnings: earnings.lifetime_earnings,
+            currency: earnings.currency,
+            platform_fee_percentage: self.revenue_share.platform_fee_percentage,
+            minimum_payout_amount: self.minimum_payout_amount,
+            payout_hold_days: self.payout_hold_days,
+            payouts_enabled,
+            last_payout_at: earnings.last_payout_at,
+            next_payout_date: payouts_enabled.then(next_scheduled_payout),
+        })
+    }
+
+    /// 创作者各个订阅优惠券的兑换次数、仍在使用的订阅数和累计减免金额
+    pub async fn get_coupon_usage(&self, creator_id: &str) -> Result<Vec<CouponUsage>> {
+        let query = r#"
+            SELECT * FROM subscription_coupon WHERE creator_id = $creator_id ORDER BY created_at DESC;
+            SELECT coupon_id, math::sum(discount_amount) AS discount_given
+                FROM coupon_redemption WHERE creator_id = $creator_id GROUP BY coupon_id;
+            SELECT coupon_id, count() AS active_subscriptions
+                FROM subscription
+                WHERE creator_id = $creator_id AND coupon_id != NONE AND status = "active"
+                GROUP BY coupon_id;
+        "#;
+
+        let mut response = self
+            .db
+            .query_with_params(query, json!({ "creator_id": creator_id }))
+            .await?;
+
+        let coupons: Vec<Value> = response.take(0)?;
+        let redemptions: Vec<Value> = response.take(1)?;
+        let subscriptions: Vec<Value> = response.take(2)?;
+
+        let lookup = |rows: &[Value], coupon_id: &str, field: &str| {
+            rows.iter()
+                .find(|row| row["coupon_id"].as_str() == Some(coupon_id))
+                .and_then(|row| row[field].as_i64())
+                .unwrap_or(0)
+        };
+
+        Ok(coupons
+            .iter()
+            .filter_map(|coupon| {
+                let coupon_id = coupon["id"].as_str()?;
+                Some(CouponUsage {
+                    coupon_id: coupon_id.to_string(),
+                    code: coupon["code"].as_str().unwrap_or_default().to_string(),
+                    is_active: coupon["is_active"].as_bool().unwrap_or(false),
+                    times_redeemed: coupon["times_redeemed"].as_i64().unwrap_or(0),
+                    max_redemptions: coupon["max_redemptions"].as_i64(),
+                    active_subscriptions: lookup(&subscriptions, coupon_id, "active_subscriptions"),
+                    discount_given: lookup(&redemptions, coupon_id, "discount_given"),
+                    created_at: coupon["created_at"]
+                        .as_str()
+                        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                        .map(|dt| dt.with_timezone(&Utc)),
+                })
+            })
+            .collect())
+    }
+
+    /// 按计税国家和收益来源汇总代收税费。部分退款按比例扣减销售额和税额，退款冲回记录本身不计入
+    pub async fn get_tax_report(
+        &self,
+        creator_id: &str,
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+    ) -> Result<TaxReport> {
+        let query = r#"
+            SELECT source_type, currency, gross_amount, refunded_gross, tax_amount, tax_country
+            FROM revenue
+            WHERE
+                creator_id = $creator_id AND
+                created_at >= $start_date AND
+                created_at < $end_date AND
+                !reverses
+        "#;
+
+        let mut response = self
+            .db
+            .query_with_params(
+                query,
+                json!({
+                    "creator_id": creator_id,
+                    "start_date": start_date,
+                    "end_date": end_date
+                }),
+            )
+            .await?;
+
+        let records: Vec<Value> = response.take(0)?;
+
+        let mut by_country: BTreeMap<(String, String), TaxBreakdown> = BTreeMap::new();
+        let mut by_source_type: BTreeMap<(String, String), TaxBreakdown> = BTreeMap::new();
+
+        for record in &records {
+            let gross = record["gross_amount"].as_i64().unwrap_or(0);
+            let taxable = gross - record["refunded_gross"].as_i64().unwrap_or(0).clamp(0, gross.max(0));
+            let tax = if gross > 0 {
+                record["tax_amount"].as_i64().unwrap_or(0) * taxable / gross
+            } else {
+                0
+            };
+            let currency = record["currency"].as_str().unwrap_or("USD").to_string();
+            let country = record["tax_country"].as_str().unwrap_or("unknown").to_string();
+            let source_type = record["source_type"].as_str().unwrap_or("unknown").to_string();
+
+            for (groups, key) in [(&mut by_country, country), (&mut by_source_type, source_type)] {
+                let row = groups
+                    .entry((key.clone(), currency.clone()))
+                    .or_insert_with(|| TaxBreakdown {
+                        key,
+                        currency: currency.clone(),
+                        transactions: 0,
+                        taxable_amount: 0,
+                        tax_amount: 0,
+                    });
+                row.transactions += 1;
+                row.taxable_amount += taxable;
+                row.tax_amount += tax;
+            }
+        }
+
+        Ok(TaxReport {
+            start_date,
+            end_date,
+            by_country: by_country.into_values().collect(),
+            by_source_type: by_source_type.into_values().collect(),
+        })
+    }
+
+    /// 已开通打款的 Connect 账户 ID
+    async fn payout_destination(&self, creator_id: &str) -> Result<Option<String>> {
+        let connect = self
+            .stripe_service
+            .get_connect_account_for_user(creator_id)
+            .await?;
+
+        Ok(connect
+            .filter(|c| c.account.payouts_enabled)
+            .map(|c| c.account.stripe_account_id))
+    }
+
+    /// 先扣除可用余额再创建打款记录，余额不足时失败
+    async fn insert_payout(
+        &self,
+        creator_id: &str,
+        amount: i64,
+        currency: &str,
+        bank_account_id: Option<&str>,
+        description: Option<&str>,
+    ) -> Result<Payout> {
+        self.update_balance_for_payout(creator_id, amount).await?;
+
+        let payout_id = format!("payout:{}", uuid::Uuid::new_v4());
 
         let query = r#"
             CREATE payout CONTENT {
@@ -589,13 +1139,13 @@ impl RevenueService {
                 json!({
                     "payout_id": payout_id,
                     "creator_id": creator_id,
-                    "amount": request.amount,
-                    "currency": earnings.currency,
+                    "amount": amount,
+                    "currency": currency,
                     "method": PayoutMethod::Stripe,
                     "status": PayoutStatus::Pending,
-                    "bank_account_id": request.bank_account_id,
-                    "description": request.description,
-                    "created_at": now
+                    "bank_account_id": bank_account_id,
+                    "description": description,
+                    "created_at": Utc::now()
                 }),
             )
             .await?;
@@ -606,11 +1156,29 @@ impl RevenueService {
             .next()
             .ok_or_else(|| AppError::Internal("Failed to create payout".to_string()))?;
 
-        // 更新创作者余额
-        self.update_balance_for_payout(creator_id, request.amount)
+        self.parse_payout(payout)
+    }
+
+    /// 向 Connect 账户转账，失败时把金额退回可用余额
+    async fn execute_payout(&self, payout: Payout, stripe_account_id: &str) -> Result<Payout> {
+        self.db
+            .query_with_params(
+                "UPDATE payout SET status = 'processing' WHERE id = $payout_id",
+                json!({ "payout_id": &payout.id }),
+            )
             .await?;
 
-        Ok(self.parse_payout(payout)?)
+        match self
+            .stripe_service
+            .create_transfer(stripe_account_id, payout.amount, &payout.currency, &payout.id)
+            .await
+        {
+            Ok(transfer_id) => self.complete_payout(&payout.id, &transfer_id).await,
+            Err(e) => {
+                error!("Stripe transfer for payout {} failed: {}", payout.id, e);
+                self.fail_payout(&payout, &e.to_string()).await
+            }
+        }
     }
 
     /// 更新余额（支付时）
@@ -646,11 +1214,7 @@ impl RevenueService {
     }
 
     /// 处理支付完成
-    pub async fn complete_payout(
-        &self,
-        payout_id: &str,
-        stripe_payout_id: Option<String>,
-    ) -> Result<Payout> {
+    async fn complete_payout(&self, payout_id: &str, stripe_transfer_id: &str) -> Result<Payout> {
         debug!("Completing payout: {}", payout_id);
 
         let now = Utc::now();
@@ -658,11 +1222,11 @@ impl RevenueService {
             UPDATE payout 
             SET 
                 status = 'completed',
-                stripe_payout_id = $stripe_payout_id,
+                stripe_transfer_id = $stripe_transfer_id,
                 processed_at = $now
             WHERE 
                 id = $payout_id AND
-                status = 'pending'
+                status IN ['pending', 'processing']
         "#;
 
         let mut response = self
@@ -671,7 +1235,7 @@ impl RevenueService {
                 query,
                 json!({
                     "payout_id": payout_id,
-                    "stripe_payout_id": stripe_payout_id,
+                    "stripe_transfer_id": stripe_transfer_id,
                     "now": now
                 }),
             )
@@ -689,11 +1253,42 @@ impl RevenueService {
         self.update_last_payout_time(&parsed_payout.creator_id)
             .await?;
 
-        // 将待结算余额转为可用余额
-        self.process_pending_revenues(&parsed_payout.creator_id)
+        info!(
+            "Payout {} transferred to creator {} ({})",
+            payout_id, parsed_payout.creator_id, stripe_transfer_id
+        );
+        Ok(parsed_payout)
+    }
+
+    /// 标记打款失败并退回余额
+    async fn fail_payout(&self, payout: &Payout, reason: &str) -> Result<Payout> {
+        let now = Utc::now();
+        let mut response = self
+            .db
+            .query_with_params(
+                r#"
+                UPDATE payout SET status = 'failed', failed_at = $now, failure_reason = $reason
+                WHERE id = $payout_id AND status IN ['pending', 'processing'];
+                UPDATE creator_earnings SET available_balance += $amount, updated_at = $now
+                WHERE creator_id = $creator_id;
+                "#,
+                json!({
+                    "payout_id": &payout.id,
+                    "creator_id": &payout.creator_id,
+                    "amount": payout.amount,
+                    "reason": reason,
+                    "now": now
+                }),
+            )
             .await?;
 
-        Ok(parsed_payout)
+        let payouts: Vec<Value> = response.take(0)?;
+        let payout = payouts
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::NotFound("Payout not found".to_string()))?;
+
+        self.parse_payout(payout)
     }
 
     /// 更新最后支付时间
@@ -723,8 +1318,8 @@ impl RevenueService {
     async fn process_pending_revenues(&self, creator_id: &str) -> Result<()> {
         let now = Utc::now();
 
-        // 将30天前的待结算收益转为可用余额
-        let cutoff_date = now - Duration::days(30);
+        // 将等待期之前的待结算收益转为可用余额
+        let cutoff_date = now - Duration::days(self.payout_hold_days);
 
         let query = r#"
             UPDATE revenue 
@@ -751,10 +1346,10 @@ impl RevenueService {
 
         let revenues: Vec<Value> = response.take(0)?;
 
-        // 计算总金额
+        // 计算总金额，已被退款冲回的部分不再结算
         let total_amount: i64 = revenues
             .iter()
-            .map(|r| r["amount"].as_i64().unwrap_or(0))
+            .map(|r| r["amount"].as_i64().unwrap_or(0) - r["reversed_amount"].as_i64().unwrap_or(0))
             .sum();
 
         if total_amount > 0 {
@@ -1080,6 +1675,7 @@ impl RevenueService {
             method: serde_json::from_value(value["method"].clone())?,
             status: serde_json::from_value(value["status"].clone())?,
             stripe_payout_id: value["stripe_payout_id"].as_str().map(String::from),
+            stripe_transfer_id: value["stripe_transfer_id"].as_str().map(String::from),
             bank_account_id: value["bank_account_id"].as_str().map(String::from),
             description: value["description"].as_str().map(String::from),
             created_at: DateTime::parse_from_rfc3339(value["created_at"].as_str().unwrap())
@@ -1200,13 +1796,22 @@ impl RevenueService {
         Ok((transactions, total))
     }
 
-    /// 查询支付列表
-    pub async fn query_payouts(&self, creator_id: &str) -> Result<Vec<Value>> {
+    /// 查询支付列表（打款历史），按时间倒序
+    pub async fn query_payouts(
+        &self,
+        creator_id: &str,
+        offset: i32,
+        limit: i32,
+    ) -> Result<(Vec<Payout>, i64)> {
         let query = r#"
             SELECT * FROM payout
             WHERE creator_id = $creator_id
             ORDER BY created_at DESC
-            LIMIT 50
+            START $offset
+            LIMIT $limit;
+            SELECT count() AS total FROM payout
+            WHERE creator_id = $creator_id
+            GROUP ALL;
         "#;
 
         let mut response = self
@@ -1214,13 +1819,26 @@ impl RevenueService {
             .query_with_params(
                 query,
                 json!({
-                    "creator_id": creator_id
+                    "creator_id": creator_id,
+                    "offset": offset,
+                    "limit": limit
                 }),
             )
             .await?;
 
         let payouts: Vec<Value> = response.take(0)?;
-        Ok(payouts)
+        let payouts = payouts
+            .into_iter()
+            .map(|p| self.parse_payout(p))
+            .collect::<Result<Vec<_>>>()?;
+
+        let count_results: Vec<Value> = response.take(1)?;
+        let total = count_results
+            .first()
+            .and_then(|r| r["total"].as_i64())
+            .unwrap_or(0);
+
+        Ok((payouts, total))
     }
 
     /// 查询支付详情
@@ -1377,3 +1995,12 @@ impl RevenueService {
         Ok(!accounts.is_empty())
     }
 }
+
+/// 下一个自动打款日（下月 1 日 UTC）
+fn next_scheduled_payout() -> DateTime<Utc> {
+    let now = Utc::now();
+    let (year, month) = if now.month() == 12 { (now.year() + 1, 1) } else { (now.year(), now.month() + 1) };
+    chrono::TimeZone::with_ymd_and_hms(&Utc, year, month, 1, 0, 0, 0)
+        .single()
+        .unwrap_or(now)
+}
@@ -1,7 +1,8 @@
 use crate::{
     error::{AppError, Result},
-    models::search::*,
-    services::Database,
+    models::{article::AuthorInfo, pseudonym::Pseudonym, search::*},
+    services::{Database, EmbeddingService},
+    utils::search::{crop_highlight, mark_terms, SNIPPET_RADIUS},
 };
 use chrono::{Utc, DateTime, Duration};
 use serde_json::{json, Value};
@@ -9,14 +10,49 @@ use std::{sync::Arc, collections::HashMap};
 use tracing::{debug, info};
 use validator::Validate;
 
+/// 文章检索的匹配方式，决定相关度和高亮从哪里来
+#[derive(Debug, Clone, Copy)]
+enum ArticleMatch {
+    /// 只有筛选条件，没有关键词
+    FiltersOnly,
+    /// 全文索引（BM25），按 `@0@`、`@1@`、`@2@` 的顺序对应标题、摘要、正文
+    FullText,
+    /// 全文检索没有命中时的模糊匹配，只比较标题和摘要
+    Fuzzy,
+    /// 搜索词向量和文章向量的余弦相似度
+    Semantic,
+}
+
+impl ArticleMatch {
+    fn select_fields(&self) -> &'static str {
+        match self {
+            ArticleMatch::FiltersOnly => "0 AS relevance",
+            ArticleMatch::FullText => {
+                "search::score(0) * 3 + search::score(1) * 2 + search::score(2) AS relevance,
+                search::highlight('<mark>', '</mark>', 0) AS title_highlight,
+                search::highlight('<mark>', '</mark>', 1) AS excerpt_highlight,
+                search::highlight('<mark>', '</mark>', 2) AS content_highlight"
+            }
+            ArticleMatch::Fuzzy => "string::similarity::fuzzy(title, $q) AS relevance",
+            ArticleMatch::Semantic => {
+                "vector::similarity::cosine(type::thing('article_embedding', meta::id(id)).vector, $vector) AS relevance"
+            }
+        }
+    }
+}
+
+/// 语义搜索结果的最低相似度，低于它的文章和搜索词基本无关
+const SEMANTIC_MIN_SIMILARITY: f64 = 0.3;
+
 #[derive(Clone)]
 pub struct SearchService {
     db: Arc<Database>,
+    embedding_service: EmbeddingService,
 }
 
 impl SearchService {
-    pub async fn new(db: Arc<Database>) -> Result<Self> {
-        Ok(Self { db })
+    pub async fn new(db: Arc<Database>, embedding_service: EmbeddingService) -> Result<Self> {
+        Ok(Self { db, embedding_service })
     }
 
     pub async fn search(&self, query: SearchQuery) -> Result<SearchResults> {
@@ -48,7 +84,7 @@ impl SearchService {
         match search_type {
             SearchType::All => {
                 // 搜索所有类型，每种类型限制数量
-                results.articles = self.search_articles(search_term, 1, 5).await?;
+                results.articles = self.search_articles(&query, 1, 5).await?;
                 results.users = self.search_users(search_term, 1, 5).await?;
                 results.tags = self.search_tags(search_term, 1, 5).await?;
                 results.publications = self.search_publications(search_term, 1, 5).await?;
@@ -59,8 +95,11 @@ impl SearchService {
                     + results.publications.len()) as i64;
             }
             SearchType::Articles => {
-                results.articles = self.search_articles(search_term, page, limit).await?;
-                results.total_results = results.articles.len() as i64;
+                let (articles, total) = self
+                    .advanced_article_search_with_count(&query.to_advanced(), page, limit, None)
+                    .await?;
+                results.articles = articles;
+                results.total_results = total;
             }
             SearchType::Users => {
                 results.users = self.search_users(search_term, page, limit).await?;
@@ -79,74 +118,9 @@ impl SearchService {
         Ok(results)
     }
 
-    async fn search_articles(&self, search_term: &str, page: i32, limit: i32) -> Result<Vec<ArticleSearchResult>> {
-        let offset = (page - 1) * limit;
-
-        let query = r#"
-            SELECT 
-                a.id,
-                a.title,
-                a.slug,
-                a.excerpt,
-                a.cover_image_url,
-                a.reading_time,
-                a.published_at,
-                a.clap_count,
-                a.comment_count,
-                u.display_name as author_name,
-                u.username as author_username
-            FROM article a
-            JOIN user_profile u ON a.author_id = u.user_id
-            WHERE a.status = 'published'
-            AND a.is_deleted = false
-            AND (
-                a.title CONTAINS $search_term
-                OR a.content CONTAINS $search_term
-                OR a.excerpt CONTAINS $search_term
-                OR u.display_name CONTAINS $search_term
-                OR u.username CONTAINS $search_term
-            )
-            ORDER BY a.popularity_score DESC, a.published_at DESC
-            LIMIT $limit
-            START $offset
-        "#;
-
-        let mut response = self.db.query_with_params(query, json!({
-            "search_term": search_term,
-            "limit": limit,
-            "offset": offset
-        })).await?;
-        let articles: Vec<Value> = response.take(0)?;
-
-        let mut results = Vec::new();
-        for article_data in articles {
-            let article_id = article_data["id"].as_str().unwrap_or("");
-            
-            // 获取文章标签
-            let tags = self.get_article_tags(article_id).await?;
-
-            let mut article_result: ArticleSearchResult = serde_json::from_value(article_data)?;
-            article_result.tags = tags;
-            
-            // 添加搜索高亮（简化版本）
-            if article_result.title.to_lowercase().contains(&search_term.to_lowercase()) {
-                article_result.highlight = Some(SearchHighlight {
-                    field: "title".to_string(),
-                    snippet: self.create_highlight_snippet(&article_result.title, search_term),
-                });
-            } else if let Some(ref excerpt) = article_result.excerpt {
-                if excerpt.to_lowercase().contains(&search_term.to_lowercase()) {
-                    article_result.highlight = Some(SearchHighlight {
-                        field: "excerpt".to_string(),
-                        snippet: self.create_highlight_snippet(excerpt, search_term),
-                    });
-                }
-            }
-
-            results.push(article_result);
-        }
-
-        Ok(results)
+    async fn search_articles(&self, query: &SearchQuery, page: i32, limit: i32) -> Result<Vec<ArticleSearchResult>> {
+        let (articles, _) = self.advanced_article_search_with_count(&query.to_advanced(), page, limit, None).await?;
+        Ok(articles)
     }
 
     async fn search_users(&self, search_term: &str, page: i32, limit: i32) -> Result<Vec<UserSearchResult>> {
@@ -358,7 +332,11 @@ impl SearchService {
 
         let query = r#"
             LET $article = (SELECT * FROM article WHERE id = $article_id);
-            LET $author = (SELECT display_name FROM user_profile WHERE user_id = $article.author_id);
+            LET $author = IF $article[0].pseudonym_id != NONE THEN
+                (SELECT display_name FROM type::thing('pseudonym', $article[0].pseudonym_id))
+            ELSE
+                (SELECT display_name FROM user_profile WHERE user_id = $article.author_id)
+            END;
             LET $tags = (SELECT name FROM tag JOIN article_tag ON tag.id = article_tag.tag_id WHERE article_tag.article_id = $article_id);
             LET $publication = (SELECT name FROM publication WHERE id = $article.publication_id);
             
@@ -372,7 +350,7 @@ impl SearchService {
                 publication_name: $publication[0].name,
                 is_published: $article.status == 'published',
                 published_at: $article.published_at,
-                popularity_score: $article.view_count * 0.1 + $article.clap_count * 1 + $article.comment_count * 2 + $article.bookmark_count * 3,
+                popularity_score: $article.view_count * 0.1 + $article.weighted_clap_score * 1 + $article.comment_count * 2 + $article.bookmark_count * 3,
                 updated_at: time::now()
             };
         "#;
@@ -384,25 +362,6 @@ impl SearchService {
         Ok(())
     }
 
-    async fn get_article_tags(&self, article_id: &str) -> Result<Vec<String>> {
-        let query = r#"
-            SELECT t.name 
-            FROM tag t
-            JOIN article_tag at ON t.id = at.tag_id
-            WHERE at.article_id = $article_id
-        "#;
-
-        let mut response = self.db.query_with_params(query, json!({
-            "article_id": article_id
-        })).await?;
-        let tags: Vec<Value> = response.take(0)?;
-
-        Ok(tags
-            .into_iter()
-            .filter_map(|v| v.get("name").and_then(|n| n.as_str()).map(String::from))
-            .collect())
-    }
-
     async fn get_popular_searches(&self, prefix: &str, limit: i32) -> Result<Vec<String>> {
         // 简化版本，实际应该从搜索日志中获取
         let searches = vec![
@@ -456,27 +415,8 @@ impl SearchService {
     }
 
     fn create_highlight_snippet(&self, text: &str, search_term: &str) -> String {
-        let lower_text = text.to_lowercase();
-        let lower_term = search_term.to_lowercase();
-        
-        if let Some(pos) = lower_text.find(&lower_term) {
-            let start = pos.saturating_sub(30);
-            let end = (pos + search_term.len() + 30).min(text.len());
-            
-            let mut snippet = String::new();
-            if start > 0 {
-                snippet.push_str("...");
-            }
-            snippet.push_str(&text[start..end]);
-            if end < text.len() {
-                snippet.push_str("...");
-            }
-            
-            // 简单的高亮标记
-            snippet.replace(search_term, &format!("<mark>{}</mark>", search_term))
-        } else {
-            text.chars().take(100).collect::<String>() + "..."
-        }
+        crop_highlight(&mark_terms(text, search_term), 30)
+            .unwrap_or_else(|| text.chars().take(100).collect::<String>() + "...")
     }
 
     /// 高级搜索功能
@@ -575,7 +515,8 @@ impl SearchService {
         Ok(articles)
     }
     
-    /// 高级文章搜索（带总数）
+    /// 高级文章搜索（带总数）。有关键词时使用标题、摘要和正文上的全文索引，按 BM25 相关度排序；
+    /// 全文检索没有命中时（拼写错误、词形不同）按模糊匹配重试。语义模式按文章向量的相似度排序
     async fn advanced_article_search_with_count(
         &self,
         query: &AdvancedSearchQuery,
@@ -583,199 +524,213 @@ impl SearchService {
         limit: i32,
         user_id: Option<&str>,
     ) -> Result<(Vec<ArticleSearchResult>, i64)> {
-        let offset = (page - 1) * limit;
-        
-        // 构建查询条件
-        let mut where_conditions = vec!["a.status = 'published'".to_string()];
-        let mut params = json!({
-            "limit": limit,
-            "offset": offset
-        });
-        
-        // 文本搜索
-        if let Some(ref q) = query.q {
-            where_conditions.push("(a.title ~ $q OR a.content ~ $q OR a.excerpt ~ $q)".to_string());
-            params["q"] = json!(q);
+        let (mut conditions, mut params) = self.article_filters(query, user_id);
+        params["limit"] = json!(limit);
+        params["offset"] = json!((page - 1) * limit);
+
+        let Some(search_term) = query.q.as_deref().map(str::trim).filter(|q| !q.is_empty()) else {
+            return self.run_article_search(&conditions, ArticleMatch::FiltersOnly, query, &params).await;
+        };
+        params["q"] = json!(search_term);
+
+        if query.mode.unwrap_or_default() == SearchMode::Semantic {
+            let vector = self.embedding_service.embed_query(search_term).await?
+                .ok_or_else(|| AppError::ServiceUnavailable("Semantic search is not enabled".to_string()))?;
+            params["vector"] = json!(vector);
+            params["embedding_model"] = json!(self.embedding_service.model());
+            params["min_similarity"] = json!(SEMANTIC_MIN_SIMILARITY);
+            conditions.push(
+                "id IN (SELECT VALUE type::thing('article', article_id) FROM article_embedding
+                    WHERE model = $embedding_model AND vector::similarity::cosine(vector, $vector) >= $min_similarity)"
+                    .to_string(),
+            );
+            return self.run_article_search(&conditions, ArticleMatch::Semantic, query, &params).await;
         }
-        
-        // 作者筛选
+
+        let mut full_text = conditions.clone();
+        full_text.push("(title @0@ $q OR excerpt @1@ $q OR content @2@ $q)".to_string());
+        let (results, total) = self.run_article_search(&full_text, ArticleMatch::FullText, query, &params).await?;
+        if total > 0 || !query.fuzzy.unwrap_or(true) {
+            return Ok((results, total));
+        }
+
+        debug!("No full-text match for '{}', retrying with fuzzy matching", search_term);
+        conditions.push("(title ~ $q OR excerpt ~ $q)".to_string());
+        self.run_article_search(&conditions, ArticleMatch::Fuzzy, query, &params).await
+    }
+
+    /// 文章的筛选条件：作者、标签、出版物、日期、付费等
+    fn article_filters(&self, query: &AdvancedSearchQuery, user_id: Option<&str>) -> (Vec<String>, Value) {
+        let mut conditions = vec!["status = 'published'".to_string(), "is_deleted = false".to_string()];
+        let mut params = json!({});
+
+        // 笔名文章不出现在账号的作者筛选结果中
         if let Some(ref author) = query.author {
-            where_conditions.push("(u.username = $author OR u.display_name ~ $author)".to_string());
+            conditions.push("pseudonym_id IS NONE AND author_id IN (SELECT VALUE user_id FROM user_profile WHERE username = $author)".to_string());
             params["author"] = json!(author);
         }
-        
-        // 标签筛选
+
         if let Some(ref tags) = query.tags {
             if !tags.is_empty() {
-                where_conditions.push("a.id IN (SELECT article_id FROM article_tag WHERE tag_id IN (SELECT id FROM tag WHERE name IN $tags))".to_string());
+                conditions.push("id IN (SELECT VALUE article_id FROM article_tag WHERE tag_id.slug INSIDE $tags OR tag_id.name INSIDE $tags OR tag_id.aliases CONTAINSANY $tags)".to_string());
                 params["tags"] = json!(tags);
             }
         }
-        
-        // 出版物筛选
+
         if let Some(ref publication) = query.publication {
-            where_conditions.push("p.slug = $publication".to_string());
+            conditions.push("publication_id.slug = $publication".to_string());
             params["publication"] = json!(publication);
         }
-        
-        // 系列筛选
+
         if let Some(ref series) = query.series {
-            where_conditions.push("a.id IN (SELECT article_id FROM series_article WHERE series_id = (SELECT id FROM series WHERE slug = $series))".to_string());
+            conditions.push("series_id.slug = $series".to_string());
             params["series"] = json!(series);
         }
-        
-        // 日期范围筛选
+
         if let Some(ref date_from) = query.date_from {
-            where_conditions.push("a.published_at >= $date_from".to_string());
+            conditions.push("published_at >= $date_from".to_string());
             params["date_from"] = json!(date_from);
         }
-        
+
         if let Some(ref date_to) = query.date_to {
-            where_conditions.push("a.published_at <= $date_to".to_string());
+            conditions.push("published_at <= $date_to".to_string());
             params["date_to"] = json!(date_to);
         }
-        
-        // 阅读时间筛选
+
         if let Some(min_reading) = query.min_reading_time {
-            where_conditions.push(format!("a.reading_time >= {}", min_reading));
+            conditions.push(format!("reading_time >= {}", min_reading));
         }
-        
+
         if let Some(max_reading) = query.max_reading_time {
-            where_conditions.push(format!("a.reading_time <= {}", max_reading));
+            conditions.push(format!("reading_time <= {}", max_reading));
         }
-        
-        // 鼓掌数筛选
+
         if let Some(min_claps) = query.min_claps {
-            where_conditions.push(format!("a.clap_count >= {}", min_claps));
+            conditions.push(format!("clap_count >= {}", min_claps));
         }
-        
-        // 特色文章筛选
+
         if let Some(is_featured) = query.is_featured {
-            where_conditions.push(format!("a.is_featured = {}", is_featured));
+            conditions.push(format!("is_featured = {}", is_featured));
         }
-        
-        // 付费内容筛选
+
         if let Some(is_paid) = query.is_paid {
-            where_conditions.push(format!("a.is_paid_content = {}", is_paid));
+            conditions.push(format!("is_paid_content = {}", is_paid));
         }
-        
+
         // 排除已读（需要用户ID）
-        if let Some(true) = query.exclude_read {
-            if let Some(user_id) = &user_id {
-                where_conditions.push(format!(
-                    "a.id NOT IN (SELECT article_id FROM user_read_history WHERE user_id = '{}')",
-                    user_id
-                ));
-            }
+        if let (Some(true), Some(user_id)) = (query.exclude_read, user_id) {
+            conditions.push("id NOT IN (SELECT VALUE article_id FROM user_read_history WHERE user_id = $user_id)".to_string());
+            params["user_id"] = json!(user_id);
         }
-        
-        // 构建排序
-        let order_by = match query.sort_by.as_ref().unwrap_or(&SortBy::Relevance) {
-            SortBy::Relevance => {
-                if query.q.is_some() {
-                    "score() DESC, a.published_at DESC"
-                } else {
-                    "a.popularity_score DESC, a.published_at DESC"
-                }
-            }
-            SortBy::PublishedAt => "a.published_at",
-            SortBy::UpdatedAt => "a.updated_at",
-            SortBy::ClapCount => "a.clap_count",
-            SortBy::CommentCount => "a.comment_count",
-            SortBy::ViewCount => "a.view_count",
-            SortBy::ReadingTime => "a.reading_time",
-            SortBy::Title => "a.title",
-            SortBy::AuthorName => "u.display_name",
-        };
-        
-        let sort_order = match query.sort_order.as_ref().unwrap_or(&SortOrder::Desc) {
+
+        (conditions, params)
+    }
+
+    async fn run_article_search(
+        &self,
+        conditions: &[String],
+        matching: ArticleMatch,
+        query: &AdvancedSearchQuery,
+        params: &Value,
+    ) -> Result<(Vec<ArticleSearchResult>, i64)> {
+        let where_clause = conditions.join(" AND ");
+
+        let count_query = format!("SELECT count() AS total FROM article WHERE {} GROUP ALL", where_clause);
+        let mut count_response = self.db.query_with_params(&count_query, params).await?;
+        let total_count = if let Ok(Some(result)) = count_response.take::<Option<Value>>(0) {
+            result.get("total").and_then(|v| v.as_i64()).unwrap_or(0)
+        } else { 0 };
+        if total_count == 0 {
+            return Ok((vec![], 0));
+        }
+
+        let matched = !matches!(matching, ArticleMatch::FiltersOnly);
+        let sort_order = match query.sort_order.unwrap_or(SortOrder::Desc) {
             SortOrder::Asc => "ASC",
             SortOrder::Desc => "DESC",
         };
-        
-        let where_clause = if where_conditions.is_empty() {
-            String::new()
-        } else {
-            format!("WHERE {}", where_conditions.join(" AND "))
+        let order_by = match query.sort_by.unwrap_or(SortBy::Relevance) {
+            SortBy::Relevance if matched => "relevance DESC, published_at DESC".to_string(),
+            SortBy::Relevance => "weighted_clap_score DESC, published_at DESC".to_string(),
+            SortBy::PublishedAt => format!("published_at {}", sort_order),
+            SortBy::UpdatedAt => format!("updated_at {}", sort_order),
+            SortBy::ClapCount => format!("clap_count {}", sort_order),
+            SortBy::CommentCount => format!("comment_count {}", sort_order),
+            SortBy::ViewCount => format!("view_count {}", sort_order),
+            SortBy::ReadingTime => format!("reading_time {}", sort_order),
+            SortBy::Title => format!("title {}", sort_order),
+            SortBy::AuthorName => format!("author_name {}", sort_order),
         };
-        
-        // 获取总数
-        let count_query = format!(
-            r#"
-            SELECT count() AS total
-            FROM article a
-            JOIN user_profile u ON a.author_id = u.user_id
-            LEFT JOIN publication p ON a.publication_id = p.id
-            {}
-            "#,
-            where_clause
-        );
-        
-        let mut count_response = self.db.query_with_params(&count_query, &params).await?;
-        let total_count = if let Ok(Some(result)) = count_response.take::<Option<Value>>(0) {
-            result.get("total").and_then(|v| v.as_i64()).unwrap_or(0)
-        } else { 0 };
-        
-        // 获取文章数据
+
         let data_query = format!(
             r#"
-            SELECT 
-                a.id,
-                a.title,
-                a.slug,
-                a.excerpt,
-                a.cover_image_url,
-                a.reading_time,
-                a.published_at,
-                a.clap_count,
-                a.comment_count,
-                u.display_name as author_name,
-                u.username as author_username,
-                p.name as publication_name,
-                p.slug as publication_slug
-            FROM article a
-            JOIN user_profile u ON a.author_id = u.user_id
-            LEFT JOIN publication p ON a.publication_id = p.id
-            {}
-            ORDER BY {} {}
+            SELECT
+                meta::id(id) AS id,
+                title,
+                slug,
+                excerpt,
+                cover_image_url,
+                reading_time,
+                published_at,
+                clap_count,
+                comment_count,
+                weighted_clap_score,
+                view_count,
+                updated_at,
+                pseudonym_id,
+                (SELECT VALUE display_name FROM user_profile WHERE user_id = $parent.author_id LIMIT 1)[0] AS author_name,
+                (SELECT VALUE username FROM user_profile WHERE user_id = $parent.author_id LIMIT 1)[0] AS author_username,
+                (SELECT VALUE tag_id.name FROM article_tag WHERE article_id = $parent.id) AS tags,
+                {}
+            FROM article
+            WHERE {}
+            ORDER BY {}
             LIMIT $limit START $offset
             "#,
-            where_clause, order_by, sort_order
+            matching.select_fields(),
+            where_clause,
+            order_by
         );
-        
+
         let mut response = self.db.query_with_params(&data_query, params).await?;
         let articles: Vec<Value> = response.take(0)?;
-        
-        let mut results = Vec::new();
-        for article_data in articles {
-            let article_id = article_data["id"].as_str().unwrap_or("");
-            
-            // 获取文章标签
-            let tags = self.get_article_tags(article_id).await?;
-            
+        let search_term = params["q"].as_str().unwrap_or_default();
+
+        let mut results = Vec::with_capacity(articles.len());
+        for mut article_data in articles {
+            self.mask_pseudonymous_author(&mut article_data).await?;
+
+            let highlights = match matching {
+                ArticleMatch::FullText => ["title", "excerpt", "content"]
+                    .into_iter()
+                    .filter_map(|field| {
+                        let marked = article_data[format!("{}_highlight", field)].as_str()?;
+                        Some(SearchHighlight {
+                            field: field.to_string(),
+                            snippet: crop_highlight(marked, SNIPPET_RADIUS)?,
+                        })
+                    })
+                    .collect(),
+                // 语义匹配的文章不一定包含搜索词，没有命中词时不返回片段
+                ArticleMatch::Fuzzy | ArticleMatch::Semantic => ["title", "excerpt"]
+                    .into_iter()
+                    .filter_map(|field| {
+                        let text = article_data[field].as_str()?;
+                        Some(SearchHighlight {
+                            field: field.to_string(),
+                            snippet: crop_highlight(&mark_terms(text, search_term), SNIPPET_RADIUS)?,
+                        })
+                    })
+                    .collect(),
+                ArticleMatch::FiltersOnly => vec![],
+            };
+
             let mut article_result: ArticleSearchResult = serde_json::from_value(article_data)?;
-            article_result.tags = tags;
-            
-            // 添加搜索高亮
-            if let Some(ref q) = query.q {
-                if article_result.title.to_lowercase().contains(&q.to_lowercase()) {
-                    article_result.highlight = Some(SearchHighlight {
-                        field: "title".to_string(),
-                        snippet: self.create_highlight_snippet(&article_result.title, q),
-                    });
-                } else if let Some(ref excerpt) = article_result.excerpt {
-                    if excerpt.to_lowercase().contains(&q.to_lowercase()) {
-                        article_result.highlight = Some(SearchHighlight {
-                            field: "excerpt".to_string(),
-                            snippet: self.create_highlight_snippet(excerpt, q),
-                        });
-                    }
-                }
-            }
-            
+            article_result.highlight = highlights.first().cloned();
+            article_result.highlights = highlights;
             results.push(article_result);
         }
-        
+
         Ok((results, total_count))
     }
     
@@ -840,6 +795,19 @@ impl SearchService {
         Ok(results)
     }
     
+    /// 笔名文章的作者显示为笔名，不暴露账号
+    async fn mask_pseudonymous_author(&self, article_data: &mut Value) -> Result<()> {
+        let Some(pseudonym_id) = article_data["pseudonym_id"].as_str().map(str::to_string) else {
+            return Ok(());
+        };
+
+        let pseudonym: Option<Pseudonym> = self.db.get_by_id("pseudonym", &pseudonym_id).await?;
+        let author = pseudonym.map(|p| p.author_info()).unwrap_or_else(AuthorInfo::anonymous);
+        article_data["author_name"] = json!(author.display_name);
+        article_data["author_username"] = json!(author.username);
+        Ok(())
+    }
+    
     /// 获取搜索 facets
     async fn get_search_facets(
         &self,
@@ -885,7 +853,7 @@ impl SearchService {
             SELECT u.username as value, u.display_name as label, COUNT(DISTINCT a.id) as count
             FROM article a
             JOIN user_profile u ON a.author_id = u.user_id
-            {}
+            {} AND a.pseudonym_id IS NONE
             GROUP BY u.user_id, u.username, u.display_name
             ORDER BY count DESC
             LIMIT 20
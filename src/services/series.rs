@@ -3,13 +3,16 @@ use crate::{
     models::{
         series::*,
         article::{Article, ArticleStatus},
+        id::bare_id,
         user::UserProfile,
     },
     services::Database,
     utils::slug,
 };
 use chrono::Utc;
+use serde::Deserialize;
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
@@ -435,6 +438,138 @@ impl SeriesService {
         Ok(())
     }
 
+    /// 按拖拽后的顺序重新编号系列中的文章
+    pub async fn reorder_articles(
+        &self,
+        series_id: &str,
+        author_id: &str,
+        request: ReorderSeriesArticlesRequest,
+    ) -> Result<Vec<String>> {
+        debug!("Reordering articles in series: {}", series_id);
+
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let series: Series = self.db.get_by_id("series", series_id).await?
+            .ok_or_else(|| AppError::NotFound("Series not found".to_string()))?;
+
+        if series.author_id != author_id {
+            return Err(AppError::forbidden("Only the author can reorder articles in this series"));
+        }
+
+        let current: Vec<String> = self.get_series_entries(series_id).await?
+            .into_iter()
+            .map(|entry| entry.article_id)
+            .collect();
+
+        let mut ordered: Vec<String> = Vec::with_capacity(current.len());
+        for article_id in &request.article_ids {
+            let stored = current.iter()
+                .find(|id| bare_id("article", id) == bare_id("article", article_id))
+                .ok_or_else(|| AppError::bad_request(&format!("Article {} is not in this series", article_id)))?;
+            if !ordered.contains(stored) {
+                ordered.push(stored.clone());
+            }
+        }
+        for article_id in current {
+            if !ordered.contains(&article_id) {
+                ordered.push(article_id);
+            }
+        }
+
+        for (index, article_id) in ordered.iter().enumerate() {
+            let query = r#"
+                UPDATE series_article 
+                SET order_index = $order_index 
+                WHERE series_id = $series_id 
+                AND article_id = $article_id
+            "#;
+
+            self.db.query_with_params(query, json!({
+                "series_id": series_id,
+                "article_id": article_id,
+                "order_index": index as i32
+            })).await?;
+
+            self.update_article_series_info(article_id, series_id, index as i32).await?;
+        }
+
+        Ok(ordered)
+    }
+
+    /// 读者在系列中的阅读进度，按 reading_progress 中读完的文章计算
+    pub async fn get_progress(&self, series_id: &str, user_id: &str) -> Result<SeriesProgress> {
+        debug!("Getting progress of user {} in series: {}", user_id, series_id);
+
+        let series: Series = self.db.get_by_id("series", series_id).await?
+            .ok_or_else(|| AppError::NotFound("Series not found".to_string()))?;
+
+        if !series.is_public && series.author_id != user_id {
+            return Err(AppError::NotFound("Series not found".to_string()));
+        }
+
+        #[derive(Deserialize)]
+        struct PublishedArticle {
+            id: String,
+            title: String,
+            slug: String,
+        }
+
+        #[derive(Deserialize)]
+        struct ArticleProgress {
+            article_id: String,
+            progress: f64,
+            completed: bool,
+        }
+
+        let entries = self.get_series_entries(series_id).await?;
+        let ids: Vec<&str> = entries.iter().map(|e| bare_id("article", &e.article_id)).collect();
+
+        let published: Vec<PublishedArticle> = self.db
+            .prepare("SELECT meta::id(id) AS id, title, slug FROM article WHERE meta::id(id) INSIDE $ids AND status = 'published' AND is_deleted = false")
+            .bind("ids", &ids)
+            .fetch()
+            .await?;
+        let mut published: HashMap<String, PublishedArticle> = published.into_iter().map(|a| (a.id.clone(), a)).collect();
+
+        let progress: Vec<ArticleProgress> = self.db
+            .prepare("SELECT article_id, progress, completed FROM reading_progress WHERE user_id = $user_id AND article_id INSIDE $ids")
+            .bind("user_id", user_id)
+            .bind("ids", &ids)
+            .fetch()
+            .await?;
+        let progress: HashMap<String, ArticleProgress> = progress.into_iter().map(|p| (p.article_id.clone(), p)).collect();
+
+        let mut articles = Vec::with_capacity(published.len());
+        for (entry, id) in entries.iter().zip(&ids) {
+            let Some(article) = published.remove(*id) else {
+                continue;
+            };
+            let read = progress.get(*id);
+            articles.push(SeriesArticleProgress {
+                article: SeriesNavItem {
+                    id: article.id,
+                    title: article.title,
+                    slug: article.slug,
+                    order_index: entry.order_index,
+                },
+                progress: read.map_or(0.0, |p| p.progress),
+                completed: read.map_or(false, |p| p.completed),
+            });
+        }
+
+        let completed_articles = articles.iter().filter(|a| a.completed).count();
+        let next_article = articles.iter().find(|a| !a.completed).map(|a| a.article.clone());
+
+        Ok(SeriesProgress {
+            series_id: series.id,
+            total_articles: articles.len(),
+            completed_articles,
+            completion_percentage: completion_percentage(completed_articles, articles.len()),
+            articles,
+            next_article,
+        })
+    }
+
     /// 订阅系列
     pub async fn subscribe_series(
         &self,
@@ -636,6 +771,15 @@ impl SeriesService {
         Ok(articles)
     }
 
+    /// 系列中的文章关联，按系列顺序
+    async fn get_series_entries(&self, series_id: &str) -> Result<Vec<SeriesArticle>> {
+        self.db
+            .prepare("SELECT meta::id(id) AS id, series_id, article_id, order_index, added_at FROM series_article WHERE series_id = $series_id ORDER BY order_index ASC, added_at ASC")
+            .bind("series_id", series_id)
+            .fetch()
+            .await
+    }
+
     async fn is_subscribed(&self, series_id: &str, user_id: &str) -> Result<bool> {
         let query = r#"
             SELECT count() as count 
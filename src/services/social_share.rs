@@ -0,0 +1,385 @@
+use crate::{
+    error::{AppError, Result},
+    models::{
+        article::Article,
+        id::{bare_id, ArticleId, UserId},
+        social_share::*,
+        syndication::{ExternalPost, SyndicationAccount, SyndicationPlatform},
+    },
+    services::{Database, OutboundSyndicationService, WebmentionService},
+    utils::syndication::{share_text, share_tweet_length, TWEET_MAX_CHARS},
+};
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+use validator::Validate;
+
+/// 每次检查最多发布的帖子数
+const DUE_BATCH_SIZE: usize = 50;
+/// 发布失败后的最大尝试次数，之后标记为失败
+const MAX_ATTEMPTS: i64 = 3;
+/// 失败重试的间隔（乘以已尝试次数）
+const RETRY_DELAY_MINUTES: i64 = 5;
+/// 外部平台返回的错误信息最多保存的长度
+const MAX_ERROR_CHARS: usize = 500;
+
+/// 排期的社交推广帖子：作者为连接的社交账号排好时间，
+/// 后台任务每分钟发布到期的帖子，并按账号限制每小时的发布数
+#[derive(Clone)]
+pub struct SocialShareService {
+    db: Arc<Database>,
+    syndication_service: OutboundSyndicationService,
+    webmention_service: WebmentionService,
+    max_posts_per_hour: u32,
+}
+
+impl SocialShareService {
+    pub async fn new(
+        db: Arc<Database>,
+        syndication_service: OutboundSyndicationService,
+        webmention_service: WebmentionService,
+        max_posts_per_hour: u32,
+    ) -> Result<Self> {
+        Ok(Self {
+            db,
+            syndication_service,
+            webmention_service,
+            max_posts_per_hour,
+        })
+    }
+
+    pub async fn schedule(&self, user_id: &str, request: CreateScheduledShareRequest) -> Result<ScheduledShare> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let account = self.syndication_service.get_account(user_id, &request.account_id).await?;
+        if !account.platform.supports_social_posts() {
+            return Err(AppError::BadRequest(format!("{} accounts cannot be used for social posts", account.platform.as_str())));
+        }
+        if request.scheduled_at <= Utc::now() {
+            return Err(AppError::BadRequest("scheduled_at must be in the future".to_string()));
+        }
+
+        let article_id = match &request.article_id {
+            Some(article_id) => Some(ArticleId::new(&self.get_own_article(user_id, article_id).await?.id)),
+            None => None,
+        };
+        let has_link = request.link.is_some() || article_id.is_some();
+        check_length(account.platform, &request.text, has_link)?;
+
+        let share: ScheduledShare = self.db
+            .prepare(
+                r#"
+                CREATE scheduled_share CONTENT {
+                    user_id: $user_id,
+                    account_id: $account_id,
+                    platform: $platform,
+                    article_id: $article_id ?? NONE,
+                    text: $text,
+                    link: $link ?? NONE,
+                    image_url: $image_url ?? NONE,
+                    scheduled_at: <datetime> $scheduled_at,
+                    status: 'scheduled',
+                    attempts: 0,
+                    created_at: time::now(),
+                    updated_at: time::now()
+                }
+                "#,
+            )
+            .bind("user_id", UserId::new(user_id).as_str())
+            .bind("account_id", account_key(&account))
+            .bind("platform", account.platform.as_str())
+            .bind("article_id", article_id.as_ref().map(|id| id.as_str().to_string()))
+            .bind("text", &request.text)
+            .bind("link", &request.link)
+            .bind("image_url", &request.image_url)
+            .bind("scheduled_at", request.scheduled_at)
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::internal("Failed to schedule share"))?;
+
+        info!("User {} scheduled share {} for {}", user_id, share.id, share.scheduled_at);
+        Ok(share)
+    }
+
+    pub async fn list(&self, user_id: &str, query: ScheduledShareQuery) -> Result<Vec<ScheduledShare>> {
+        let limit = query.limit.unwrap_or(50).min(200);
+        let status_filter = if query.status.is_some() { "AND status = $status" } else { "" };
+
+        self.db
+            .prepare(&format!(
+                "SELECT * FROM scheduled_share WHERE user_id = $user_id {} ORDER BY scheduled_at DESC LIMIT {}",
+                status_filter, limit
+            ))
+            .bind("user_id", UserId::new(user_id).as_str())
+            .bind("status", query.status.map(|s| s.as_str()))
+            .fetch()
+            .await
+    }
+
+    pub async fn get(&self, user_id: &str, share_id: &str) -> Result<ScheduledShareDetail> {
+        let share = self.get_own_share(user_id, share_id).await?;
+        let attempts_log: Vec<ShareAttemptLog> = self.db
+            .prepare("SELECT * FROM social_share_log WHERE share_id = $share_id ORDER BY attempted_at ASC")
+            .bind("share_id", share_key(&share))
+            .fetch()
+            .await?;
+
+        Ok(ScheduledShareDetail { share, attempts_log })
+    }
+
+    /// 只有还未发布的帖子可以修改
+    pub async fn update(&self, user_id: &str, share_id: &str, request: UpdateScheduledShareRequest) -> Result<ScheduledShare> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let share = self.get_own_share(user_id, share_id).await?;
+        if share.status != ScheduledShareStatus::Scheduled {
+            return Err(AppError::Conflict(format!("Share is already {}", share.status.as_str())));
+        }
+        if let Some(scheduled_at) = request.scheduled_at {
+            if scheduled_at <= Utc::now() {
+                return Err(AppError::BadRequest("scheduled_at must be in the future".to_string()));
+            }
+        }
+
+        let text = request.text.as_deref().unwrap_or(&share.text);
+        let has_link = request.link.is_some() || share.link.is_some() || share.article_id.is_some();
+        check_length(share.platform, text, has_link)?;
+
+        let updated: ScheduledShare = self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('scheduled_share', $share_id) SET
+                    text = $text ?? text,
+                    link = $link ?? link,
+                    image_url = $image_url ?? image_url,
+                    scheduled_at = IF $scheduled_at THEN <datetime> $scheduled_at ELSE scheduled_at END,
+                    updated_at = time::now()
+                WHERE status = 'scheduled'
+                "#,
+            )
+            .bind("share_id", share_key(&share))
+            .bind("text", &request.text)
+            .bind("link", &request.link)
+            .bind("image_url", &request.image_url)
+            .bind("scheduled_at", request.scheduled_at)
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::Conflict("Share is no longer scheduled".to_string()))?;
+
+        Ok(updated)
+    }
+
+    pub async fn cancel(&self, user_id: &str, share_id: &str) -> Result<ScheduledShare> {
+        let share = self.get_own_share(user_id, share_id).await?;
+
+        let cancelled: Option<ScheduledShare> = self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('scheduled_share', $share_id)
+                SET status = 'cancelled', updated_at = time::now()
+                WHERE status = 'scheduled'
+                "#,
+            )
+            .bind("share_id", share_key(&share))
+            .fetch_one()
+            .await?;
+
+        cancelled.ok_or_else(|| AppError::Conflict(format!("Share is already {}", share.status.as_str())))
+    }
+
+    /// 发布到期的帖子；超过账号每小时限额的帖子留到下次检查
+    pub async fn publish_due_shares(&self) -> Result<usize> {
+        let due: Vec<ScheduledShare> = self.db
+            .prepare(&format!(
+                "SELECT * FROM scheduled_share WHERE status = 'scheduled' AND scheduled_at <= time::now() ORDER BY scheduled_at ASC LIMIT {}",
+                DUE_BATCH_SIZE
+            ))
+            .fetch()
+            .await?;
+
+        let mut remaining: HashMap<String, u32> = HashMap::new();
+        let mut posted = 0;
+        for share in due {
+            let quota = match remaining.get(&share.account_id) {
+                Some(quota) => *quota,
+                None => self.max_posts_per_hour.saturating_sub(self.posts_in_last_hour(&share.account_id).await?),
+            };
+            if quota == 0 {
+                debug!("Account {} reached its hourly share limit, deferring {}", share.account_id, share.id);
+                remaining.insert(share.account_id.clone(), 0);
+                continue;
+            }
+
+            // 先领取，避免多个实例重复发布
+            let Some(share) = self.claim(&share).await? else {
+                continue;
+            };
+            remaining.insert(share.account_id.clone(), quota - 1);
+
+            if self.execute(&share).await? {
+                posted += 1;
+            }
+        }
+
+        if posted > 0 {
+            info!("Published {} scheduled social shares", posted);
+        }
+        Ok(posted)
+    }
+
+    async fn claim(&self, share: &ScheduledShare) -> Result<Option<ScheduledShare>> {
+        self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('scheduled_share', $share_id)
+                SET status = 'posting', attempts += 1, updated_at = time::now()
+                WHERE status = 'scheduled'
+                "#,
+            )
+            .bind("share_id", share_key(share))
+            .fetch_one()
+            .await
+    }
+
+    /// 发布一条已领取的帖子并记录结果，返回是否成功
+    async fn execute(&self, share: &ScheduledShare) -> Result<bool> {
+        let result = self.post(share).await;
+        let (external, error) = match result {
+            Ok(post) => (Some(post), None),
+            Err(e) => (None, Some(e.to_string().chars().take(MAX_ERROR_CHARS).collect::<String>())),
+        };
+
+        self.db
+            .prepare(
+                r#"
+                CREATE social_share_log CONTENT {
+                    share_id: $share_id,
+                    account_id: $account_id,
+                    success: $success,
+                    external_id: $external_id ?? NONE,
+                    error: $error ?? NONE,
+                    attempted_at: time::now()
+                }
+                "#,
+            )
+            .bind("share_id", share_key(share))
+            .bind("account_id", &share.account_id)
+            .bind("success", external.is_some())
+            .bind("external_id", external.as_ref().map(|p| p.id.clone()))
+            .bind("error", &error)
+            .execute()
+            .await?;
+
+        let query = match (&external, share.attempts >= MAX_ATTEMPTS) {
+            (Some(_), _) => r#"
+                UPDATE type::thing('scheduled_share', $share_id) SET
+                    status = 'posted', external_id = $external_id, external_url = $external_url,
+                    error = NONE, posted_at = time::now(), updated_at = time::now()
+            "#,
+            (None, true) => r#"
+                UPDATE type::thing('scheduled_share', $share_id) SET
+                    status = 'failed', error = $error, updated_at = time::now()
+            "#,
+            (None, false) => r#"
+                UPDATE type::thing('scheduled_share', $share_id) SET
+                    status = 'scheduled', error = $error, scheduled_at = <datetime> $retry_at, updated_at = time::now()
+            "#,
+        };
+        self.db
+            .prepare(query)
+            .bind("share_id", share_key(share))
+            .bind("external_id", external.as_ref().map(|p| p.id.clone()))
+            .bind("external_url", external.as_ref().map(|p| p.url.clone()))
+            .bind("error", &error)
+            .bind("retry_at", Utc::now() + Duration::minutes(RETRY_DELAY_MINUTES * share.attempts))
+            .execute()
+            .await?;
+
+        match &error {
+            Some(error) => warn!("Scheduled share {} failed (attempt {}): {}", share.id, share.attempts, error),
+            None => debug!("Published scheduled share {}", share.id),
+        }
+        Ok(external.is_some())
+    }
+
+    async fn post(&self, share: &ScheduledShare) -> Result<ExternalPost> {
+        let account = self.syndication_service.get_account(&share.user_id, &share.account_id).await?;
+
+        // 未指定链接时使用文章发布后的地址
+        let link = match (&share.link, &share.article_id) {
+            (Some(link), _) => Some(link.clone()),
+            (None, Some(article_id)) => {
+                let article: Article = self.db
+                    .get_by_id("article", ArticleId::new(article_id).as_str())
+                    .await?
+                    .filter(|a: &Article| !a.is_deleted)
+                    .ok_or_else(|| AppError::not_found("Article"))?;
+                if !article.is_published() {
+                    return Err(AppError::BadRequest("The promoted article is not published".to_string()));
+                }
+                Some(self.webmention_service.article_url(&article).await?)
+            }
+            (None, None) => None,
+        };
+
+        self.syndication_service
+            .post_share(&account, &share_text(&share.text, link.as_deref()), share.image_url.as_deref())
+            .await
+    }
+
+    async fn posts_in_last_hour(&self, account_id: &str) -> Result<u32> {
+        let mut response = self.db
+            .prepare(
+                r#"
+                RETURN (SELECT count() FROM scheduled_share
+                    WHERE account_id = $account_id AND status = 'posted' AND posted_at > time::now() - 1h
+                    GROUP ALL)[0].count ?? 0;
+                "#,
+            )
+            .bind("account_id", account_id)
+            .execute()
+            .await?;
+        let count: Option<i64> = response.take(0)?;
+        Ok(count.unwrap_or(0).max(0) as u32)
+    }
+
+    async fn get_own_share(&self, user_id: &str, share_id: &str) -> Result<ScheduledShare> {
+        let share: Option<ScheduledShare> = self.db
+            .get_by_id("scheduled_share", bare_id("scheduled_share", share_id))
+            .await?;
+        share
+            .filter(|s| s.user_id == UserId::new(user_id).as_str())
+            .ok_or_else(|| AppError::not_found("Scheduled share"))
+    }
+
+    async fn get_own_article(&self, user_id: &str, article_id: &str) -> Result<Article> {
+        let article: Article = self.db
+            .get_by_id("article", ArticleId::new(article_id).as_str())
+            .await?
+            .filter(|a: &Article| !a.is_deleted)
+            .ok_or_else(|| AppError::not_found("Article"))?;
+        if UserId::new(&article.author_id) != UserId::new(user_id) {
+            return Err(AppError::forbidden("Only the author can promote this article"));
+        }
+        Ok(article)
+    }
+}
+
+fn check_length(platform: SyndicationPlatform, text: &str, has_link: bool) -> Result<()> {
+    if platform == SyndicationPlatform::Twitter && share_tweet_length(text, has_link) > TWEET_MAX_CHARS {
+        return Err(AppError::BadRequest(format!(
+            "Post is too long for Twitter/X ({} characters max, links count as 23)",
+            TWEET_MAX_CHARS
+        )));
+    }
+    Ok(())
+}
+
+fn share_key(share: &ScheduledShare) -> &str {
+    bare_id("scheduled_share", &share.id)
+}
+
+fn account_key(account: &SyndicationAccount) -> &str {
+    bare_id("syndication_account", &account.id)
+}
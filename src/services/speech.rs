@@ -0,0 +1,370 @@
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+    models::{id::bare_id, plugin::ArticlePublishedEvent, speech::ArticleAudio},
+    services::{plugin::Plugin, Database, MediaService},
+    utils::speech::{changed_ratio, chunk_text, mp3_duration, sentence_fingerprints, speech_text},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// 每轮检查最多生成的文章数，合成比较慢，批次不宜过大
+const REGENERATE_BATCH: usize = 5;
+/// 生成失败后 1 小时内不再重试
+const RETRY_AFTER_HOURS: i64 = 1;
+
+/// 语音合成服务。返回的音频必须是 MP3，分段合成的结果直接拼接
+#[async_trait]
+pub trait SpeechProvider: Send + Sync {
+    /// 保存在音频旁边的模型和音色标识，换了之后旧音频会重新生成
+    fn voice(&self) -> &str;
+
+    /// 单次请求的文本上限（字符）
+    fn max_input_chars(&self) -> usize;
+
+    async fn synthesize(&self, text: &str) -> Result<Vec<u8>>;
+}
+
+/// 根据 `TTS_PROVIDER` 选择语音合成服务，`none` 表示不生成朗读音频
+pub fn speech_provider(config: &Config) -> Result<Option<Arc<dyn SpeechProvider>>> {
+    match config.tts_provider.as_str() {
+        "none" | "" => Ok(None),
+        "openai" => Ok(Some(Arc::new(OpenAiSpeechProvider::new(config)?))),
+        other => Err(AppError::internal(&format!("Unknown TTS provider: {}", other))),
+    }
+}
+
+/// OpenAI 兼容的 `/v1/audio/speech` 接口
+pub struct OpenAiSpeechProvider {
+    client: Client,
+    api_url: String,
+    api_key: Option<String>,
+    model: String,
+    voice: String,
+    identifier: String,
+}
+
+impl OpenAiSpeechProvider {
+    pub fn new(config: &Config) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(120))
+            .build()?;
+
+        Ok(Self {
+            client,
+            api_url: config.tts_api_url.clone(),
+            api_key: config.tts_api_key.clone(),
+            model: config.tts_model.clone(),
+            voice: config.tts_voice.clone(),
+            identifier: format!("{}/{}", config.tts_model, config.tts_voice),
+        })
+    }
+}
+
+#[async_trait]
+impl SpeechProvider for OpenAiSpeechProvider {
+    fn voice(&self) -> &str {
+        &self.identifier
+    }
+
+    fn max_input_chars(&self) -> usize {
+        4000
+    }
+
+    async fn synthesize(&self, text: &str) -> Result<Vec<u8>> {
+        let mut request = self.client.post(&self.api_url).json(&json!({
+            "model": self.model,
+            "voice": self.voice,
+            "input": text,
+            "response_format": "mp3",
+        }));
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(AppError::ExternalService(format!(
+                "Speech API returned status {}",
+                response.status()
+            )));
+        }
+        Ok(response.bytes().await?.to_vec())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SpeechSource {
+    id: String,
+    title: String,
+    subtitle: Option<String>,
+    content: String,
+    status: String,
+    is_deleted: bool,
+    updated_at: DateTime<Utc>,
+}
+
+/// 文章朗读音频：发布时生成，保存在媒体目录中，记录在 `article_audio`（记录ID与文章相同）。
+/// 后台任务检查内容有变化的文章，变化的句子超过一定比例时重新生成
+#[derive(Clone)]
+pub struct SpeechService {
+    db: Arc<Database>,
+    media_service: MediaService,
+    provider: Option<Arc<dyn SpeechProvider>>,
+    max_chars: usize,
+    regenerate_threshold: f64,
+    /// 正在生成的文章，避免发布时的生成和后台任务重复合成
+    in_progress: Arc<Mutex<HashSet<String>>>,
+}
+
+impl SpeechService {
+    pub async fn new(db: Arc<Database>, config: &Config, media_service: MediaService) -> Result<Self> {
+        Ok(Self {
+            db,
+            media_service,
+            provider: speech_provider(config)?,
+            max_chars: config.tts_max_chars,
+            regenerate_threshold: config.tts_regenerate_threshold,
+            in_progress: Arc::new(Mutex::new(HashSet::new())),
+        })
+    }
+
+    /// 文章已生成的朗读音频
+    pub async fn get_audio(&self, article_id: &str) -> Result<Option<ArticleAudio>> {
+        let audio: Option<ArticleAudio> = self.db
+            .prepare("SELECT * FROM type::thing('article_audio', $article_id) WHERE audio_url != NONE")
+            .bind("article_id", bare_id("article", article_id))
+            .fetch_one()
+            .await?;
+        Ok(audio)
+    }
+
+    /// 为新发布或内容有明显变化的文章生成音频，并清理已删除文章的音频。返回生成的数量
+    pub async fn regenerate_stale(&self) -> Result<usize> {
+        let Some(provider) = &self.provider else {
+            return Ok(0);
+        };
+
+        self.purge_deleted().await?;
+
+        // 文章更新时间和记录的不一致、换了音色，或者上次失败已过重试间隔
+        let stale: Vec<String> = self.db
+            .prepare(
+                r#"
+                SELECT VALUE meta::id(id)
+                FROM article
+                WHERE status = 'published'
+                    AND is_deleted = false
+                    AND (type::thing('article_audio', meta::id(id)).source_updated_at != updated_at
+                        OR type::thing('article_audio', meta::id(id)).voice != $voice
+                        OR (type::thing('article_audio', meta::id(id)).failed_at != NONE
+                            AND type::thing('article_audio', meta::id(id)).failed_at < time::now() - <duration> $retry_after))
+                LIMIT $limit
+                "#,
+            )
+            .bind("voice", provider.voice())
+            .bind("retry_after", format!("{}h", RETRY_AFTER_HOURS))
+            .bind("limit", REGENERATE_BATCH)
+            .fetch()
+            .await?;
+
+        let mut generated = 0;
+        for article_id in stale {
+            match self.generate(&article_id).await {
+                Ok(true) => generated += 1,
+                Ok(false) => {}
+                Err(e) => warn!("Failed to generate audio for article {}: {}", article_id, e),
+            }
+        }
+
+        if generated > 0 {
+            info!("Generated audio for {} articles with voice {}", generated, provider.voice());
+        }
+        Ok(generated)
+    }
+
+    /// 检查一篇文章，需要时生成音频。返回是否生成了新的音频
+    pub async fn generate(&self, article_id: &str) -> Result<bool> {
+        if self.provider.is_none() {
+            return Ok(false);
+        }
+        if !self.in_progress.lock().unwrap().insert(article_id.to_string()) {
+            debug!("Audio for article {} is already being generated", article_id);
+            return Ok(false);
+        }
+
+        let result = self.generate_inner(article_id).await;
+        self.in_progress.lock().unwrap().remove(article_id);
+        result
+    }
+
+    async fn generate_inner(&self, article_id: &str) -> Result<bool> {
+        let Some(provider) = &self.provider else {
+            return Ok(false);
+        };
+
+        let source: Option<SpeechSource> = self.db
+            .prepare(
+                r#"
+                SELECT meta::id(id) AS id, title, subtitle, content, status, is_deleted, updated_at
+                FROM type::thing('article', $article_id)
+                "#,
+            )
+            .bind("article_id", bare_id("article", article_id))
+            .fetch_one()
+            .await?;
+        let Some(source) = source.filter(|s| s.status == "published" && !s.is_deleted) else {
+            return Ok(false);
+        };
+
+        let text: String = speech_text(&source.title, source.subtitle.as_deref(), &source.content)
+            .chars()
+            .take(self.max_chars)
+            .collect();
+        let fingerprints = sentence_fingerprints(&text);
+        let existing: Option<ArticleAudio> = self.db
+            .prepare("SELECT * FROM type::thing('article_audio', $article_id)")
+            .bind("article_id", &source.id)
+            .fetch_one()
+            .await?;
+
+        // 和生成音频时的文本比较，小修改累积到一定程度才重新生成
+        if let Some(existing) = &existing {
+            let minor_change = existing.audio_url.is_some()
+                && existing.voice == provider.voice()
+                && changed_ratio(&existing.sentence_hashes, &fingerprints) <= self.regenerate_threshold;
+            if minor_change {
+                self.db
+                    .prepare("UPDATE type::thing('article_audio', $article_id) SET source_updated_at = <datetime> $updated_at, last_error = NONE, failed_at = NONE")
+                    .bind("article_id", &source.id)
+                    .bind("updated_at", source.updated_at)
+                    .execute()
+                    .await?;
+                return Ok(false);
+            }
+        }
+
+        match self.synthesize(provider.as_ref(), &text).await {
+            Ok((audio, duration)) => {
+                let audio_url = self.media_service.store_audio("mp3", &audio).await?;
+                self.db
+                    .prepare(
+                        r#"
+                        UPSERT type::thing('article_audio', $article_id) CONTENT {
+                            article_id: $article_id,
+                            audio_url: $audio_url,
+                            duration_seconds: $duration,
+                            size: $size,
+                            voice: $voice,
+                            sentence_hashes: $sentence_hashes,
+                            source_updated_at: <datetime> $updated_at,
+                            generated_at: time::now(),
+                            last_error: NONE,
+                            failed_at: NONE
+                        }
+                        "#,
+                    )
+                    .bind("article_id", &source.id)
+                    .bind("audio_url", &audio_url)
+                    .bind("duration", duration)
+                    .bind("size", audio.len())
+                    .bind("voice", provider.voice())
+                    .bind("sentence_hashes", &fingerprints)
+                    .bind("updated_at", source.updated_at)
+                    .execute()
+                    .await?;
+
+                if let Some(previous) = existing.and_then(|audio| audio.audio_url) {
+                    self.media_service.delete_public_file(&previous).await;
+                }
+                info!("Generated {:.0}s of audio for article {}", duration, source.id);
+                Ok(true)
+            }
+            Err(e) => {
+                // 保留之前的音频，过一段时间后由后台任务重试
+                let sql = if existing.is_some() {
+                    "UPDATE type::thing('article_audio', $article_id) SET source_updated_at = <datetime> $updated_at, last_error = $error, failed_at = time::now()"
+                } else {
+                    r#"
+                    CREATE type::thing('article_audio', $article_id) CONTENT {
+                        article_id: $article_id,
+                        voice: $voice,
+                        source_updated_at: <datetime> $updated_at,
+                        last_error: $error,
+                        failed_at: time::now()
+                    }
+                    "#
+                };
+                self.db
+                    .prepare(sql)
+                    .bind("article_id", &source.id)
+                    .bind("voice", provider.voice())
+                    .bind("updated_at", source.updated_at)
+                    .bind("error", e.to_string())
+                    .execute()
+                    .await?;
+                Err(e)
+            }
+        }
+    }
+
+    /// 分段合成后拼接，返回音频和时长
+    async fn synthesize(&self, provider: &dyn SpeechProvider, text: &str) -> Result<(Vec<u8>, f64)> {
+        let chunks = chunk_text(text, provider.max_input_chars());
+        if chunks.is_empty() {
+            return Err(AppError::BadRequest("文章没有可以朗读的文字".to_string()));
+        }
+
+        let mut audio = Vec::new();
+        for chunk in &chunks {
+            audio.extend(provider.synthesize(chunk).await?);
+        }
+        let duration = mp3_duration(&audio)
+            .ok_or_else(|| AppError::ExternalService("Speech API did not return MP3 audio".to_string()))?;
+        Ok((audio, duration))
+    }
+
+    /// 删除已删除文章的音频文件和记录
+    async fn purge_deleted(&self) -> Result<()> {
+        let orphaned: Vec<ArticleAudio> = self.db
+            .query("DELETE article_audio WHERE type::thing('article', article_id).id IS NONE OR type::thing('article', article_id).is_deleted = true RETURN BEFORE")
+            .await?
+            .take(0)?;
+        for audio in orphaned {
+            if let Some(url) = audio.audio_url {
+                self.media_service.delete_public_file(&url).await;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 文章发布后在后台生成音频，不占用插件钩子的超时时间
+#[async_trait]
+impl Plugin for SpeechService {
+    fn name(&self) -> &str {
+        "speech"
+    }
+
+    async fn on_article_published(&self, event: &ArticlePublishedEvent) -> Result<()> {
+        if self.provider.is_none() {
+            return Ok(());
+        }
+
+        let service = self.clone();
+        let article_id = event.article_id.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.generate(&article_id).await {
+                warn!("Failed to generate audio for article {}: {}", article_id, e);
+            }
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,443 @@
+use crate::{
+    error::{AppError, Result},
+    models::{
+        id::{bare_id, PublicationId, UserId},
+        sponsorship::*,
+    },
+    services::{Database, MediaService},
+};
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tracing::{info, warn};
+use validator::Validate;
+
+/// 出版物直接售出的赞助位：投放排期、赞助素材、带披露标签的渲染和展示统计。
+/// 权限由路由检查，管理赞助需要 `publication.manage_settings`
+#[derive(Clone)]
+pub struct SponsorshipService {
+    db: Arc<Database>,
+    media_service: MediaService,
+}
+
+impl SponsorshipService {
+    pub async fn new(db: Arc<Database>, media_service: MediaService) -> Result<Self> {
+        Ok(Self { db, media_service })
+    }
+
+    pub async fn list_slots(&self, publication_id: &str) -> Result<Vec<SponsorSlot>> {
+        self.db
+            .prepare("SELECT * FROM sponsor_slot WHERE publication_id = $publication_id ORDER BY created_at ASC")
+            .bind("publication_id", PublicationId::new(publication_id).as_str())
+            .fetch()
+            .await
+    }
+
+    pub async fn get_slot(&self, publication_id: &str, slot_id: &str) -> Result<SponsorSlot> {
+        let slot: Option<SponsorSlot> = self.db
+            .get_by_id("sponsor_slot", bare_id("sponsor_slot", slot_id))
+            .await?;
+        slot.filter(|s| s.publication_id == PublicationId::new(publication_id).as_str())
+            .ok_or_else(|| AppError::not_found("Sponsor slot"))
+    }
+
+    pub async fn create_slot(&self, publication_id: &str, request: CreateSponsorSlotRequest) -> Result<SponsorSlot> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let slot: Option<SponsorSlot> = self.db
+            .prepare(
+                r#"
+                CREATE sponsor_slot CONTENT {
+                    publication_id: $publication_id,
+                    name: $name,
+                    placement: $placement,
+                    description: $description ?? NONE,
+                    created_at: time::now()
+                }
+                "#,
+            )
+            .bind("publication_id", PublicationId::new(publication_id).as_str())
+            .bind("name", request.name.trim())
+            .bind("placement", request.placement.as_str())
+            .bind("description", &request.description)
+            .fetch_one()
+            .await?;
+
+        slot.ok_or_else(|| AppError::internal("Failed to create sponsor slot"))
+    }
+
+    /// 删除赞助位；还有未结束的赞助时拒绝
+    pub async fn delete_slot(&self, publication_id: &str, slot_id: &str) -> Result<()> {
+        let slot = self.get_slot(publication_id, slot_id).await?;
+        let slot_key = bare_id("sponsor_slot", &slot.id);
+
+        let upcoming: Vec<Sponsorship> = self.db
+            .prepare("SELECT * FROM sponsorship WHERE slot_id = $slot_id AND ends_at > time::now() LIMIT 1")
+            .bind("slot_id", slot_key)
+            .fetch()
+            .await?;
+        if !upcoming.is_empty() {
+            return Err(AppError::Conflict("Sponsor slot has scheduled sponsorships".to_string()));
+        }
+
+        self.db
+            .prepare("DELETE type::thing('sponsor_slot', $slot_id)")
+            .bind("slot_id", slot_key)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    pub async fn list(&self, publication_id: &str) -> Result<Vec<Sponsorship>> {
+        self.db
+            .prepare("SELECT * FROM sponsorship WHERE publication_id = $publication_id ORDER BY starts_at DESC")
+            .bind("publication_id", PublicationId::new(publication_id).as_str())
+            .fetch()
+            .await
+    }
+
+    pub async fn get(&self, publication_id: &str, sponsorship_id: &str) -> Result<Sponsorship> {
+        let sponsorship: Option<Sponsorship> = self.db
+            .get_by_id("sponsorship", bare_id("sponsorship", sponsorship_id))
+            .await?;
+        sponsorship
+            .filter(|s| s.publication_id == PublicationId::new(publication_id).as_str())
+            .ok_or_else(|| AppError::not_found("Sponsorship"))
+    }
+
+    /// 同一赞助位的投放期不能重叠
+    async fn ensure_flight_available(
+        &self,
+        slot_id: &str,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+        exclude: Option<&str>,
+    ) -> Result<()> {
+        if ends_at <= starts_at {
+            return Err(AppError::BadRequest("ends_at must be after starts_at".to_string()));
+        }
+
+        let overlapping: Vec<Sponsorship> = self.db
+            .prepare(
+                r#"
+                SELECT * FROM sponsorship
+                WHERE slot_id = $slot_id
+                AND starts_at < $ends_at AND ends_at > $starts_at
+                AND meta::id(id) != $exclude
+                LIMIT 1
+                "#,
+            )
+            .bind("slot_id", slot_id)
+            .bind("starts_at", starts_at)
+            .bind("ends_at", ends_at)
+            .bind("exclude", exclude.unwrap_or_default())
+            .fetch()
+            .await?;
+
+        match overlapping.first() {
+            Some(other) => Err(AppError::Conflict(format!(
+                "Slot is already booked by {} from {} to {}",
+                other.sponsor_name, other.starts_at, other.ends_at
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    pub async fn create(&self, publication_id: &str, user_id: &str, request: CreateSponsorshipRequest) -> Result<Sponsorship> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let slot = self.get_slot(publication_id, &request.slot_id).await?;
+        let slot_key = bare_id("sponsor_slot", &slot.id);
+        self.ensure_flight_available(slot_key, request.starts_at, request.ends_at, None).await?;
+
+        let sponsorship: Option<Sponsorship> = self.db
+            .prepare(
+                r#"
+                CREATE sponsorship CONTENT {
+                    publication_id: $publication_id,
+                    slot_id: $slot_id,
+                    sponsor_name: $sponsor_name,
+                    sponsor_url: $sponsor_url,
+                    headline: $headline,
+                    body: $body ?? NONE,
+                    asset_url: NONE,
+                    disclosure_label: $disclosure_label,
+                    starts_at: <datetime> $starts_at,
+                    ends_at: <datetime> $ends_at,
+                    status: 'active',
+                    impression_count: 0,
+                    created_by: $created_by,
+                    created_at: time::now(),
+                    updated_at: time::now()
+                }
+                "#,
+            )
+            .bind("publication_id", PublicationId::new(publication_id).as_str())
+            .bind("slot_id", slot_key)
+            .bind("sponsor_name", request.sponsor_name.trim())
+            .bind("sponsor_url", &request.sponsor_url)
+            .bind("headline", request.headline.trim())
+            .bind("body", &request.body)
+            .bind("disclosure_label", request.disclosure_label.as_deref().unwrap_or(Sponsorship::DEFAULT_DISCLOSURE_LABEL))
+            .bind("starts_at", request.starts_at)
+            .bind("ends_at", request.ends_at)
+            .bind("created_by", UserId::new(user_id).as_str())
+            .fetch_one()
+            .await?;
+
+        let sponsorship = sponsorship.ok_or_else(|| AppError::internal("Failed to create sponsorship"))?;
+        info!("Scheduled sponsorship {} in slot {} of publication {}", sponsorship.id, slot.id, publication_id);
+        Ok(sponsorship)
+    }
+
+    pub async fn update(&self, publication_id: &str, sponsorship_id: &str, request: UpdateSponsorshipRequest) -> Result<Sponsorship> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let mut sponsorship = self.get(publication_id, sponsorship_id).await?;
+        if request.starts_at.is_some() || request.ends_at.is_some() {
+            sponsorship.starts_at = request.starts_at.unwrap_or(sponsorship.starts_at);
+            sponsorship.ends_at = request.ends_at.unwrap_or(sponsorship.ends_at);
+            self.ensure_flight_available(
+                &sponsorship.slot_id,
+                sponsorship.starts_at,
+                sponsorship.ends_at,
+                Some(bare_id("sponsorship", &sponsorship.id)),
+            ).await?;
+        }
+        if let Some(sponsor_name) = request.sponsor_name {
+            sponsorship.sponsor_name = sponsor_name.trim().to_string();
+        }
+        if let Some(sponsor_url) = request.sponsor_url {
+            sponsorship.sponsor_url = sponsor_url;
+        }
+        if let Some(headline) = request.headline {
+            sponsorship.headline = headline.trim().to_string();
+        }
+        if let Some(body) = request.body {
+            sponsorship.body = Some(body).filter(|b| !b.trim().is_empty());
+        }
+        if let Some(disclosure_label) = request.disclosure_label {
+            sponsorship.disclosure_label = disclosure_label.trim().to_string();
+        }
+        if let Some(status) = request.status {
+            sponsorship.status = status;
+        }
+
+        let updated: Option<Sponsorship> = self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('sponsorship', $sponsorship_id) SET
+                    sponsor_name = $sponsor_name,
+                    sponsor_url = $sponsor_url,
+                    headline = $headline,
+                    body = $body ?? NONE,
+                    disclosure_label = $disclosure_label,
+                    starts_at = <datetime> $starts_at,
+                    ends_at = <datetime> $ends_at,
+                    status = $status,
+                    updated_at = time::now()
+                "#,
+            )
+            .bind("sponsorship_id", bare_id("sponsorship", &sponsorship.id))
+            .bind("sponsor_name", &sponsorship.sponsor_name)
+            .bind("sponsor_url", &sponsorship.sponsor_url)
+            .bind("headline", &sponsorship.headline)
+            .bind("body", &sponsorship.body)
+            .bind("disclosure_label", &sponsorship.disclosure_label)
+            .bind("starts_at", sponsorship.starts_at)
+            .bind("ends_at", sponsorship.ends_at)
+            .bind("status", sponsorship.status)
+            .fetch_one()
+            .await?;
+
+        updated.ok_or_else(|| AppError::internal("Failed to update sponsorship"))
+    }
+
+    pub async fn delete(&self, publication_id: &str, sponsorship_id: &str) -> Result<()> {
+        let sponsorship = self.get(publication_id, sponsorship_id).await?;
+        self.db
+            .prepare("DELETE type::thing('sponsorship', $sponsorship_id)")
+            .bind("sponsorship_id", bare_id("sponsorship", &sponsorship.id))
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 上传赞助商图片，通过 MediaService 保存并替换原来的素材
+    pub async fn upload_asset(
+        &self,
+        publication_id: &str,
+        sponsorship_id: &str,
+        user_id: &str,
+        filename: &str,
+        content_type: &str,
+        data: Vec<u8>,
+    ) -> Result<Sponsorship> {
+        let sponsorship = self.get(publication_id, sponsorship_id).await?;
+        let upload = self.media_service.upload_image(user_id, filename, content_type, data).await?;
+
+        let updated: Option<Sponsorship> = self.db
+            .prepare("UPDATE type::thing('sponsorship', $sponsorship_id) SET asset_url = $asset_url, updated_at = time::now()")
+            .bind("sponsorship_id", bare_id("sponsorship", &sponsorship.id))
+            .bind("asset_url", &upload.url)
+            .fetch_one()
+            .await?;
+
+        updated.ok_or_else(|| AppError::internal("Failed to update sponsorship"))
+    }
+
+    /// 当前投放中的赞助内容，用于渲染出版物页面
+    pub async fn active_blocks(&self, publication_id: &str, placements: &[SponsorPlacement]) -> Result<Vec<SponsorBlock>> {
+        if placements.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let slots: Vec<SponsorSlot> = self.list_slots(publication_id).await?
+            .into_iter()
+            .filter(|s| placements.contains(&s.placement))
+            .collect();
+        if slots.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let slot_ids: Vec<&str> = slots.iter().map(|s| bare_id("sponsor_slot", &s.id)).collect();
+        let live: Vec<Sponsorship> = self.db
+            .prepare(
+                r#"
+                SELECT * FROM sponsorship
+                WHERE slot_id INSIDE $slot_ids
+                AND status = 'active'
+                AND starts_at <= time::now() AND ends_at > time::now()
+                "#,
+            )
+            .bind("slot_ids", &slot_ids)
+            .fetch()
+            .await?;
+
+        let now = Utc::now();
+        let blocks: Vec<SponsorBlock> = placements
+            .iter()
+            .flat_map(|placement| slots.iter().filter(move |s| s.placement == *placement))
+            .filter_map(|slot| {
+                let slot_key = bare_id("sponsor_slot", &slot.id);
+                live.iter()
+                    .find(|s| s.slot_id == slot_key && s.is_live(now))
+                    .map(|sponsorship| render_block(sponsorship, slot))
+            })
+            .collect();
+
+        Ok(blocks)
+    }
+
+    /// 渲染赞助内容并在后台计入展示次数
+    pub async fn render_for_page(&self, publication_id: &str, placements: &[SponsorPlacement]) -> Vec<SponsorBlock> {
+        let blocks = match self.active_blocks(publication_id, placements).await {
+            Ok(blocks) => blocks,
+            Err(e) => {
+                warn!("Failed to load sponsor blocks for publication {}: {}", publication_id, e);
+                return Vec::new();
+            }
+        };
+
+        if !blocks.is_empty() {
+            let service = self.clone();
+            let sponsorship_ids: Vec<String> = blocks.iter().map(|b| b.sponsorship_id.clone()).collect();
+            tokio::spawn(async move {
+                if let Err(e) = service.record_impressions(&sponsorship_ids).await {
+                    warn!("Failed to record sponsor impressions: {}", e);
+                }
+            });
+        }
+        blocks
+    }
+
+    async fn record_impressions(&self, sponsorship_ids: &[String]) -> Result<()> {
+        let day = Utc::now().format("%Y-%m-%d").to_string();
+        for sponsorship_id in sponsorship_ids {
+            self.db
+                .prepare(
+                    r#"
+                    UPDATE type::thing('sponsorship', $sponsorship_id) SET impression_count += 1;
+                    UPSERT type::thing('sponsorship_impression', [$sponsorship_id, $day]) SET
+                        sponsorship_id = $sponsorship_id,
+                        day = $day,
+                        impressions = (impressions ?? 0) + 1;
+                    "#,
+                )
+                .bind("sponsorship_id", sponsorship_id)
+                .bind("day", &day)
+                .execute()
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn report(&self, publication_id: &str, sponsorship_id: &str) -> Result<SponsorshipReport> {
+        let sponsorship = self.get(publication_id, sponsorship_id).await?;
+        let daily: Vec<DailyImpressions> = self.db
+            .prepare("SELECT day, impressions FROM sponsorship_impression WHERE sponsorship_id = $sponsorship_id ORDER BY day ASC")
+            .bind("sponsorship_id", bare_id("sponsorship", &sponsorship.id))
+            .fetch()
+            .await?;
+
+        Ok(SponsorshipReport {
+            total_impressions: sponsorship.impression_count,
+            sponsorship,
+            daily,
+        })
+    }
+}
+
+/// 带披露标签的赞助内容 HTML，链接标记为 `rel="sponsored"`
+fn render_block(sponsorship: &Sponsorship, slot: &SponsorSlot) -> SponsorBlock {
+    let sponsorship_id = bare_id("sponsorship", &sponsorship.id).to_string();
+    let mut html = format!(
+        r#"<aside class="sponsor-block sponsor-{}" data-sponsorship="{}" aria-label="{}">"#,
+        slot.placement.as_str(),
+        escape_attribute(&sponsorship_id),
+        escape_attribute(&sponsorship.disclosure_label),
+    );
+    html.push_str(&format!(
+        r#"<span class="sponsor-disclosure">{}</span>"#,
+        escape_text(&sponsorship.disclosure_label)
+    ));
+    html.push_str(&format!(
+        r#"<a href="{}" rel="sponsored noopener" target="_blank">"#,
+        escape_attribute(&sponsorship.sponsor_url)
+    ));
+    if let Some(asset_url) = &sponsorship.asset_url {
+        html.push_str(&format!(
+            r#"<img src="{}" alt="{}" loading="lazy">"#,
+            escape_attribute(asset_url),
+            escape_attribute(&sponsorship.sponsor_name)
+        ));
+    }
+    html.push_str(&format!("<strong>{}</strong>", escape_text(&sponsorship.headline)));
+    if let Some(body) = &sponsorship.body {
+        html.push_str(&format!("<p>{}</p>", escape_text(body)));
+    }
+    html.push_str(&format!(
+        r#"<span class="sponsor-name">{}</span></a></aside>"#,
+        escape_text(&sponsorship.sponsor_name)
+    ));
+
+    SponsorBlock {
+        sponsorship_id,
+        slot_id: bare_id("sponsor_slot", &slot.id).to_string(),
+        placement: slot.placement,
+        disclosure_label: sponsorship.disclosure_label.clone(),
+        sponsor_name: sponsorship.sponsor_name.clone(),
+        sponsor_url: sponsorship.sponsor_url.clone(),
+        headline: sponsorship.headline.clone(),
+        body: sponsorship.body.clone(),
+        asset_url: sponsorship.asset_url.clone(),
+        html,
+    }
+}
+
+fn escape_text(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn escape_attribute(value: &str) -> String {
+    escape_text(value).replace('"', "&quot;")
+}
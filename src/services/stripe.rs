@@ -1,8 +1,11 @@
 use crate::{
     error::{AppError, Result},
     models::{
-        payment::AccessType, revenue::RevenueSourceType, stripe::*,
-        subscription::SubscriptionStatus,
+        id::{ArticleId, ConnectAccountId, StripeSubscriptionId, SubscriptionId, WebhookEventId},
+        payment::AccessType,
+        revenue::{CollectedTax, RevenueSourceType},
+        stripe::*,
+        subscription::{CouponDiscountType, CreateCouponRequest, SubscriptionStatus},
     },
     services::Database,
 };
@@ -10,19 +13,33 @@ use chrono::{DateTime, Utc};
 use hmac::{Hmac, Mac};
 use reqwest::{
     header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE},
-    Client,
+    Client, StatusCode,
 };
+use serde::Serialize;
 use serde_json::{json, Map, Value};
 use sha2::Sha256;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tracing::{debug, error, info, warn};
 use validator::Validate;
 type HmacSha256 = Hmac<Sha256>;
 
+/// 单个 Stripe 请求的最大尝试次数（含首次请求）
+const STRIPE_MAX_ATTEMPTS: u32 = 3;
+/// 第一次重试前的等待时间，之后每次翻倍
+const STRIPE_RETRY_BASE_DELAY_MS: u64 = 500;
+/// 幂等键及其结果的保留天数
+const IDEMPOTENCY_KEY_RETENTION_DAYS: i64 = 30;
+
 #[derive(Debug, Default)]
 pub struct StripeWebhookOutcome {
+    /// 本次处理的 webhook_event 记录 ID，事件已经处理过时为 `None`
+    pub event_id: Option<String>,
     pub purchase_updates: Vec<StripePurchaseUpdate>,
+    /// 支付失败的 Stripe PaymentIntent ID
+    pub failed_payment_intents: Vec<String>,
+    pub charge_reversals: Vec<StripeChargeReversal>,
+    pub gift_payments: Vec<StripeGiftPayment>,
     pub subscription_revenues: Vec<StripeSubscriptionRevenue>,
     pub subscription_status_updates: Vec<StripeSubscriptionStatusUpdate>,
 }
@@ -40,6 +57,48 @@ pub struct StripePurchaseUpdate {
     pub creator_id: String,
     pub article_id: String,
     pub purchase_id: Option<String>,
+    /// 实际扣款金额，含代收税费
+    pub amount: i64,
+    pub currency: String,
+    pub tax: CollectedTax,
+}
+
+/// 礼物的支付成功。礼物的 PaymentIntent 在 metadata 中带有 `gift_id`
+#[derive(Debug, Clone)]
+pub struct StripeGiftPayment {
+    pub gift_id: String,
+    pub stripe_payment_intent_id: String,
+    /// 实际扣款金额，含代收税费
+    pub amount: i64,
+    pub tax: CollectedTax,
+}
+
+/// 退款或争议导致的收款冲回
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StripeChargeReversalKind {
+    Refund,
+    Dispute,
+    /// 争议以平台胜诉结束，资金退回
+    DisputeWon,
+}
+
+impl StripeChargeReversalKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StripeChargeReversalKind::Refund => "refund",
+            StripeChargeReversalKind::Dispute => "dispute",
+            StripeChargeReversalKind::DisputeWon => "dispute_won",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StripeChargeReversal {
+    pub stripe_payment_intent_id: String,
+    /// 订阅发票的收款带有发票 ID；争议事件不带，只能按 PaymentIntent 匹配
+    pub stripe_invoice_id: Option<String>,
+    pub kind: StripeChargeReversalKind,
+    /// 累计退款或争议金额（美分）。部分退款会多次触发事件，每次都是累计值
     pub amount: i64,
     pub currency: String,
 }
@@ -47,10 +106,15 @@ pub struct StripePurchaseUpdate {
 #[derive(Debug, Clone)]
 pub struct StripeSubscriptionRevenue {
     pub subscription_id: String,
+    pub stripe_invoice_id: Option<String>,
+    /// 发票的 PaymentIntent，退款和争议按它或发票 ID 找回这笔收益
+    pub stripe_payment_intent_id: Option<String>,
     pub creator_id: String,
     pub subscriber_id: String,
+    /// 发票实付金额，含代收税费
     pub amount: i64,
     pub currency: String,
+    pub tax: CollectedTax,
     pub current_period_end: Option<DateTime<Utc>>,
 }
 
@@ -65,6 +129,15 @@ pub struct StripeSubscriptionStatusUpdate {
     pub canceled_at: Option<DateTime<Utc>>,
 }
 
+/// Stripe Tax 的计税结果
+#[derive(Debug, Clone)]
+struct TaxCalculation {
+    id: String,
+    /// 含税总额
+    amount_total: i64,
+    tax: CollectedTax,
+}
+
 #[derive(Clone)]
 pub struct StripeService {
     db: Arc<Database>,
@@ -171,6 +244,180 @@ impl StripeService {
         headers
     }
 
+    // ============ 幂等请求 ============
+
+    /// 由操作名和业务对象 ID 派生稳定的幂等键，同一逻辑操作重复调用时得到同一个键
+    fn idempotency_key(operation: &str, parts: &[&str]) -> String {
+        let mut key = operation.to_string();
+        for part in parts {
+            key.push('-');
+            key.push_str(part);
+        }
+        key
+    }
+
+    /// 修改已有对象等没有稳定业务标识的操作使用一次性的键，只保护本次调用内的重试
+    fn one_off_idempotency_key(operation: &str) -> String {
+        Self::idempotency_key(operation, &[&uuid::Uuid::new_v4().to_string()])
+    }
+
+    /// 带 `Idempotency-Key` 向 Stripe 发送 POST 请求。网络错误、限流、幂等冲突和 5xx
+    /// 按指数退避重试（Stripe 返回 `Stripe-Should-Retry` 时以它为准）。
+    /// 每个键的结果都会保存，同一个键已经成功的请求直接返回保存的响应
+    async fn post_idempotent<T: Serialize + ?Sized>(
+        &self,
+        url: &str,
+        params: &T,
+        idempotency_key: &str,
+        action: &str,
+    ) -> Result<Value> {
+        if let Some(stored) = self.find_idempotent_response(idempotency_key).await? {
+            debug!("Reusing stored Stripe response for idempotency key {}", idempotency_key);
+            return Ok(stored);
+        }
+
+        let mut attempt = 1;
+        loop {
+            let result = self
+                .http_client
+                .post(url)
+                .headers(self.get_headers())
+                .header("Idempotency-Key", idempotency_key)
+                .form(params)
+                .send()
+                .await;
+
+            let (error, retryable) = match result {
+                Ok(response) if response.status().is_success() => {
+                    let body: Value = response.json().await.map_err(|e| {
+                        AppError::Internal(format!("Failed to parse Stripe response: {}", e))
+                    })?;
+                    self.save_idempotent_outcome(idempotency_key, action, attempt, Ok(&body))
+                        .await;
+                    return Ok(body);
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    let retryable = match response
+                        .headers()
+                        .get("Stripe-Should-Retry")
+                        .and_then(|v| v.to_str().ok())
+                    {
+                        Some(should_retry) => should_retry == "true",
+                        None => {
+                            status == StatusCode::CONFLICT
+                                || status == StatusCode::TOO_MANY_REQUESTS
+                                || status.is_server_error()
+                        }
+                    };
+                    let error_text = response.text().await.unwrap_or_default();
+                    (
+                        AppError::ExternalService(format!("Stripe {} failed: {}", action, error_text)),
+                        retryable,
+                    )
+                }
+                Err(e) => (
+                    AppError::Internal(format!("Stripe API error: {}", e)),
+                    !e.is_builder(),
+                ),
+            };
+
+            if !retryable || attempt >= STRIPE_MAX_ATTEMPTS {
+                self.save_idempotent_outcome(
+                    idempotency_key,
+                    action,
+                    attempt,
+                    Err(&error.to_string()),
+                )
+                .await;
+                return Err(error);
+            }
+
+            let delay = STRIPE_RETRY_BASE_DELAY_MS * 2u64.pow(attempt - 1);
+            warn!(
+                "Stripe {} failed (attempt {}/{}), retrying in {}ms: {}",
+                action, attempt, STRIPE_MAX_ATTEMPTS, delay, error
+            );
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            attempt += 1;
+        }
+    }
+
+    async fn find_idempotent_response(&self, idempotency_key: &str) -> Result<Option<Value>> {
+        let mut response = self
+            .db
+            .query_with_params(
+                "SELECT response FROM type::thing('stripe_idempotency_key', $key) WHERE status = 'succeeded'",
+                json!({ "key": idempotency_key }),
+            )
+            .await?;
+
+        let records: Vec<Value> = response.take(0)?;
+        Ok(records
+            .into_iter()
+            .next()
+            .and_then(|record| record.get("response").cloned())
+            .filter(|response| !response.is_null()))
+    }
+
+    /// 保存幂等键的最终结果。保存失败只记录日志，不影响已经完成的 Stripe 请求
+    async fn save_idempotent_outcome(
+        &self,
+        idempotency_key: &str,
+        action: &str,
+        attempts: u32,
+        outcome: std::result::Result<&Value, &str>,
+    ) {
+        let (status, response, error) = match outcome {
+            Ok(body) => ("succeeded", Some(body), None),
+            Err(e) => ("failed", None, Some(e)),
+        };
+
+        let result = self
+            .db
+            .query_with_params(
+                r#"
+                UPSERT type::thing('stripe_idempotency_key', $key) SET
+                    key = $key,
+                    action = $action,
+                    status = $status,
+                    attempts = (attempts ?? 0) + $attempts,
+                    response = $response,
+                    error = $error,
+                    created_at = created_at ?? time::now(),
+                    updated_at = time::now()
+                "#,
+                json!({
+                    "key": idempotency_key,
+                    "action": action,
+                    "status": status,
+                    "attempts": attempts,
+                    "response": response,
+                    "error": error,
+                }),
+            )
+            .await;
+
+        if let Err(e) = result {
+            warn!("Failed to save Stripe idempotency key {}: {}", idempotency_key, e);
+        }
+    }
+
+    /// 清理过期的幂等记录，返回删除的条数
+    pub async fn purge_idempotency_keys(&self) -> Result<usize> {
+        let cutoff = Utc::now() - chrono::Duration::days(IDEMPOTENCY_KEY_RETENTION_DAYS);
+        let mut response = self
+            .db
+            .query_with_params(
+                "DELETE stripe_idempotency_key WHERE updated_at < $cutoff RETURN BEFORE",
+                json!({ "cutoff": cutoff }),
+            )
+            .await?;
+
+        let deleted: Vec<Value> = response.take(0)?;
+        Ok(deleted.len())
+    }
+
     // ============ 客户管理 ============
 
     /// 创建或获取Stripe客户
@@ -188,7 +435,7 @@ impl StripeService {
         }
 
         // 创建新的Stripe客户
-        let stripe_customer = self.create_stripe_customer(email, name).await?;
+        let stripe_customer = self.create_stripe_customer(user_id, email, name).await?;
 
         // 保存到数据库
         let customer_id = format!("stripe_customer:{}", uuid::Uuid::new_v4());
@@ -244,6 +491,46 @@ impl StripeService {
         })
     }
 
+    /// 删除用户的 Stripe Customer 及保存的支付方式，返回是否存在过客户记录。
+    /// Stripe 删除客户时会一并解绑支付方式并取消该客户名下的订阅
+    pub async fn delete_customer(&self, user_id: &str) -> Result<bool> {
+        let Some(customer) = self.get_customer_by_user_id(user_id).await? else {
+            return Ok(false);
+        };
+
+        let url = format!("https://api.stripe.com/v1/customers/{}", customer.stripe_customer_id);
+        let response = self
+            .http_client
+            .delete(url)
+            .headers(self.get_headers())
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("Stripe API error: {}", e)))?;
+
+        // 已在 Stripe 侧删除的客户视为成功
+        if !response.status().is_success() && response.status() != StatusCode::NOT_FOUND {
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(AppError::ExternalService(format!(
+                "Stripe customer deletion failed: {}",
+                error_text
+            )));
+        }
+
+        self.db
+            .query_with_params(
+                r#"
+                    DELETE stripe_payment_method WHERE user_id = $user_id;
+                    DELETE stripe_customer WHERE user_id = $user_id;
+                    UPDATE user_profile SET stripe_customer_id = NONE WHERE user_id = $user_id;
+                "#,
+                json!({ "user_id": user_id }),
+            )
+            .await?;
+
+        info!("Deleted Stripe customer for user {}", user_id);
+        Ok(true)
+    }
+
     /// 从数据库获取客户信息
     async fn get_customer_by_user_id(&self, user_id: &str) -> Result<Option<StripeCustomer>> {
         let query = r#"
@@ -298,35 +585,24 @@ impl StripeService {
     }
 
     /// 在Stripe创建客户
-    async fn create_stripe_customer(&self, email: &str, name: Option<&str>) -> Result<Value> {
-        let mut params = vec![("email", email)];
+    async fn create_stripe_customer(
+        &self,
+        user_id: &str,
+        email: &str,
+        name: Option<&str>,
+    ) -> Result<Value> {
+        let mut params = vec![("email", email), ("metadata[user_id]", user_id)];
         if let Some(name) = name {
             params.push(("name", name));
         }
 
-        let response = self
-            .http_client
-            .post("https://api.stripe.com/v1/customers")
-            .headers(self.get_headers())
-            .form(&params)
-            .send()
-            .await
-            .map_err(|e| AppError::Internal(format!("Stripe API error: {}", e)))?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Internal(format!(
-                "Stripe customer creation failed: {}",
-                error_text
-            )));
-        }
-
-        let customer: Value = response
-            .json()
-            .await
-            .map_err(|e| AppError::Internal(format!("Failed to parse Stripe response: {}", e)))?;
-
-        Ok(customer)
+        self.post_idempotent(
+            "https://api.stripe.com/v1/customers",
+            &params,
+            &Self::idempotency_key("customer", &[user_id]),
+            "customer creation",
+        )
+        .await
     }
 
     // ============ 支付意图 ============
@@ -401,6 +677,44 @@ impl StripeService {
                     ));
                 }
 
+                // 税费加在价格之上；计税失败（如客户没有账单地址）时按原价收款
+                let tax_calculation = if self.config.tax_enabled {
+                    let reference = metadata_map
+                        .get("article_id")
+                        .or_else(|| metadata_map.get("gift_id"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("payment")
+                        .to_string();
+                    match self
+                        .calculate_tax(&customer.stripe_customer_id, amount, &currency, &reference)
+                        .await
+                    {
+                        Ok(calculation) => Some(calculation),
+                        Err(e) => {
+                            warn!("Stripe Tax calculation failed for user {}, charging without tax: {}", user_id, e);
+                            None
+                        }
+                    }
+                } else {
+                    None
+                };
+
+                let tax = tax_calculation
+                    .as_ref()
+                    .map(|calculation| calculation.tax.clone())
+                    .unwrap_or_default();
+                let amount = match &tax_calculation {
+                    Some(calculation) => {
+                        metadata_map.insert("tax_calculation".to_string(), json!(calculation.id));
+                        metadata_map.insert("tax_amount".to_string(), json!(tax.amount.to_string()));
+                        if let Some(country) = &tax.country {
+                            metadata_map.insert("tax_country".to_string(), json!(country));
+                        }
+                        calculation.amount_total
+                    }
+                    None => amount,
+                };
+
                 let stripe_intent = self
                     .create_stripe_payment_intent(
                         &customer.stripe_customer_id,
@@ -447,6 +761,7 @@ impl StripeService {
                                 "stripe_payment_intent_id": stripe_intent["id"],
                                 "user_id": user_id,
                                 "amount": amount,
+                                "tax_amount": tax.amount,
                                 "currency": currency,
                                 "status": status_str,
                                 "mode": StripeIntentMode::Payment,
@@ -479,6 +794,80 @@ impl StripeService {
         }
     }
 
+    /// 用 Stripe Tax 按客户地址计算一笔付费内容的税费（价格不含税）
+    async fn calculate_tax(
+        &self,
+        customer_id: &str,
+        amount: i64,
+        currency: &str,
+        reference: &str,
+    ) -> Result<TaxCalculation> {
+        let params: Vec<(&str, String)> = vec![
+            ("currency", currency.to_lowercase()),
+            ("customer", customer_id.to_string()),
+            ("line_items[0][amount]", amount.to_string()),
+            ("line_items[0][reference]", reference.to_string()),
+            ("line_items[0][tax_code]", self.config.tax_code.clone()),
+            ("line_items[0][tax_behavior]", "exclusive".to_string()),
+        ];
+
+        let calculation = self
+            .post_idempotent(
+                "https://api.stripe.com/v1/tax/calculations",
+                &params,
+                &Self::one_off_idempotency_key("tax-calculation"),
+                "tax calculation",
+            )
+            .await?;
+
+        let id = calculation["id"]
+            .as_str()
+            .ok_or_else(|| AppError::Internal("Stripe tax calculation missing id".to_string()))?
+            .to_string();
+        let tax_amount = calculation["tax_amount_exclusive"].as_i64().unwrap_or(0);
+
+        Ok(TaxCalculation {
+            id,
+            amount_total: calculation["amount_total"].as_i64().unwrap_or(amount + tax_amount),
+            tax: CollectedTax {
+                amount: tax_amount,
+                country: calculation["customer_details"]["address"]["country"]
+                    .as_str()
+                    .map(|s| s.to_uppercase()),
+            },
+        })
+    }
+
+    /// 支付成功后把计税结果登记为 Stripe Tax 交易，供税务申报使用
+    async fn record_tax_transaction(&self, calculation_id: &str, stripe_payment_intent_id: &str) -> Result<()> {
+        let params = [
+            ("calculation", calculation_id),
+            ("reference", stripe_payment_intent_id),
+        ];
+
+        self.post_idempotent(
+            "https://api.stripe.com/v1/tax/transactions/create_from_calculation",
+            &params,
+            &Self::idempotency_key("tax-transaction", &[stripe_payment_intent_id]),
+            "tax transaction creation",
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    /// 创建支付意图时写入 metadata 的代收税费
+    fn parse_intent_tax(payment_intent: &Value) -> CollectedTax {
+        let metadata = &payment_intent["metadata"];
+        CollectedTax {
+            amount: metadata["tax_amount"]
+                .as_str()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            country: metadata["tax_country"].as_str().map(|s| s.to_string()),
+        }
+    }
+
     /// 在 Stripe 创建支付意图
     async fn create_stripe_payment_intent(
         &self,
@@ -517,29 +906,23 @@ impl StripeService {
             params.push((meta_key, meta_value));
         }
 
-        let response = self
-            .http_client
-            .post("https://api.stripe.com/v1/payment_intents")
-            .headers(self.get_headers())
-            .form(&params)
-            .send()
-            .await
-            .map_err(|e| AppError::Internal(format!("Stripe API error: {}", e)))?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Internal(format!(
-                "Stripe payment intent creation failed: {}",
-                error_text
-            )));
-        }
-
-        let intent: Value = response
-            .json()
-            .await
-            .map_err(|e| AppError::Internal(format!("Failed to parse Stripe response: {}", e)))?;
+        // 文章购买和礼物各自只对应一个 PaymentIntent，重复提交时复用已创建的意图
+        let idempotency_key = match (
+            metadata.get("purchase_id").and_then(|v| v.as_str()),
+            metadata.get("gift_id").and_then(|v| v.as_str()),
+        ) {
+            (Some(purchase_id), _) => Self::idempotency_key("payment-intent", &[purchase_id]),
+            (None, Some(gift_id)) => Self::idempotency_key("payment-intent", &[gift_id]),
+            (None, None) => Self::one_off_idempotency_key("payment-intent"),
+        };
 
-        Ok(intent)
+        self.post_idempotent(
+            "https://api.stripe.com/v1/payment_intents",
+            &params,
+            &idempotency_key,
+            "payment intent creation",
+        )
+        .await
     }
 
     /// 创建 Stripe SetupIntent
@@ -564,29 +947,13 @@ impl StripeService {
             params.push((meta_key, meta_value));
         }
 
-        let response = self
-            .http_client
-            .post("https://api.stripe.com/v1/setup_intents")
-            .headers(self.get_headers())
-            .form(&params)
-            .send()
-            .await
-            .map_err(|e| AppError::Internal(format!("Stripe API error: {}", e)))?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Internal(format!(
-                "Stripe setup intent creation failed: {}",
-                error_text
-            )));
-        }
-
-        let intent: Value = response
-            .json()
-            .await
-            .map_err(|e| AppError::Internal(format!("Failed to parse Stripe response: {}", e)))?;
-
-        Ok(intent)
+        self.post_idempotent(
+            "https://api.stripe.com/v1/setup_intents",
+            &params,
+            &Self::one_off_idempotency_key("setup-intent"),
+            "setup intent creation",
+        )
+        .await
     }
 
     fn prepare_intent_metadata(
@@ -664,27 +1031,18 @@ impl StripeService {
             product_params.push(("description".to_string(), desc.to_string()));
         }
 
-        let product_response = self
-            .http_client
-            .post("https://api.stripe.com/v1/products")
-            .headers(self.get_headers())
-            .form(&product_params)
-            .send()
-            .await
-            .map_err(|e| AppError::Internal(format!("Stripe API error: {}", e)))?;
-
-        if !product_response.status().is_success() {
-            let error_text = product_response.text().await.unwrap_or_default();
-            return Err(AppError::Internal(format!(
-                "Stripe product creation failed: {}",
-                error_text
-            )));
+        if self.config.tax_enabled {
+            product_params.push(("tax_code".to_string(), self.config.tax_code.clone()));
         }
 
-        let product: Value = product_response
-            .json()
-            .await
-            .map_err(|e| AppError::Internal(format!("Failed to parse Stripe response: {}", e)))?;
+        let product = self
+            .post_idempotent(
+                "https://api.stripe.com/v1/products",
+                &product_params,
+                &Self::idempotency_key("product", &[plan_id]),
+                "product creation",
+            )
+            .await?;
 
         let product_id = product
             .get("id")
@@ -701,27 +1059,19 @@ impl StripeService {
             ("metadata[creator_id]".to_string(), creator_id.to_string()),
         ];
 
-        let price_response = self
-            .http_client
-            .post("https://api.stripe.com/v1/prices")
-            .headers(self.get_headers())
-            .form(&price_params)
-            .send()
-            .await
-            .map_err(|e| AppError::Internal(format!("Stripe API error: {}", e)))?;
-
-        if !price_response.status().is_success() {
-            let error_text = price_response.text().await.unwrap_or_default();
-            return Err(AppError::Internal(format!(
-                "Stripe price creation failed: {}",
-                error_text
-            )));
+        // 订阅价格不含税，税费由 automatic_tax 在发票上另加
+        if self.config.tax_enabled {
+            price_params.push(("tax_behavior".to_string(), "exclusive".to_string()));
         }
 
-        let price: Value = price_response
-            .json()
-            .await
-            .map_err(|e| AppError::Internal(format!("Failed to parse Stripe response: {}", e)))?;
+        let price = self
+            .post_idempotent(
+                "https://api.stripe.com/v1/prices",
+                &price_params,
+                &Self::idempotency_key("price", &[plan_id]),
+                "price creation",
+            )
+            .await?;
 
         let price_id = price
             .get("id")
@@ -756,22 +1106,13 @@ impl StripeService {
         }
 
         let url = format!("https://api.stripe.com/v1/products/{}", product_id);
-        let response = self
-            .http_client
-            .post(url)
-            .headers(self.get_headers())
-            .form(&params)
-            .send()
-            .await
-            .map_err(|e| AppError::Internal(format!("Stripe API error: {}", e)))?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Internal(format!(
-                "Stripe product update failed: {}",
-                error_text
-            )));
-        }
+        self.post_idempotent(
+            &url,
+            &params,
+            &Self::one_off_idempotency_key("product-update"),
+            "product update",
+        )
+        .await?;
 
         Ok(())
     }
@@ -783,42 +1124,115 @@ impl StripeService {
         amount: i64,
         currency: &str,
     ) -> Result<String> {
-        let params = vec![
+        let mut params = vec![
             ("product".to_string(), product_id.to_string()),
             ("currency".to_string(), currency.to_lowercase()),
             ("unit_amount".to_string(), amount.to_string()),
             ("recurring[interval]".to_string(), "month".to_string()),
         ];
 
+        if self.config.tax_enabled {
+            params.push(("tax_behavior".to_string(), "exclusive".to_string()));
+        }
+
+        let price = self
+            .post_idempotent(
+                "https://api.stripe.com/v1/prices",
+                &params,
+                &Self::one_off_idempotency_key("price"),
+                "price creation",
+            )
+            .await?;
+
+        let price_id = price
+            .get("id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| AppError::Internal("Stripe price missing id".to_string()))?
+            .to_string();
+
+        Ok(price_id)
+    }
+
+    /// 为创作者的订阅计划创建 Stripe Coupon，返回 coupon ID
+    pub async fn create_coupon(
+        &self,
+        creator_id: &str,
+        code: &str,
+        request: &CreateCouponRequest,
+        currency: Option<&str>,
+    ) -> Result<String> {
+        let duration = match request.duration {
+            CouponDuration::Once => "once",
+            CouponDuration::Repeating => "repeating",
+            CouponDuration::Forever => "forever",
+        };
+        let mut params: Vec<(String, String)> = vec![
+            ("name".to_string(), code.to_string()),
+            ("duration".to_string(), duration.to_string()),
+            ("metadata[creator_id]".to_string(), creator_id.to_string()),
+            ("metadata[code]".to_string(), code.to_string()),
+        ];
+
+        match request.discount_type {
+            CouponDiscountType::Percentage => {
+                params.push(("percent_off".to_string(), request.discount_value.to_string()));
+            }
+            CouponDiscountType::Fixed => {
+                let currency = currency
+                    .ok_or_else(|| AppError::BadRequest("固定金额优惠券需要指定货币".to_string()))?;
+                params.push(("amount_off".to_string(), request.discount_value.to_string()));
+                params.push(("currency".to_string(), currency.to_lowercase()));
+            }
+        }
+
+        if let Some(months) = request.duration_in_months {
+            params.push(("duration_in_months".to_string(), months.to_string()));
+        }
+        if let Some(max_redemptions) = request.max_redemptions {
+            params.push(("max_redemptions".to_string(), max_redemptions.to_string()));
+        }
+        if let Some(redeem_by) = request.redeem_by {
+            params.push(("redeem_by".to_string(), redeem_by.timestamp().to_string()));
+        }
+
+        let coupon = self
+            .post_idempotent(
+                "https://api.stripe.com/v1/coupons",
+                &params,
+                &Self::idempotency_key("coupon", &[creator_id, code]),
+                "coupon creation",
+            )
+            .await?;
+
+        let coupon_id = coupon
+            .get("id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| AppError::Internal("Stripe coupon missing id".to_string()))?
+            .to_string();
+
+        Ok(coupon_id)
+    }
+
+    /// 删除 Stripe Coupon。已经使用该优惠券的订阅不受影响，只是不能再被新订阅使用
+    pub async fn delete_coupon(&self, stripe_coupon_id: &str) -> Result<()> {
+        let url = format!("https://api.stripe.com/v1/coupons/{}", stripe_coupon_id);
         let response = self
             .http_client
-            .post("https://api.stripe.com/v1/prices")
+            .delete(url)
             .headers(self.get_headers())
-            .form(&params)
             .send()
             .await
             .map_err(|e| AppError::Internal(format!("Stripe API error: {}", e)))?;
 
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Internal(format!(
-                "Stripe price creation failed: {}",
+            return Err(AppError::ExternalService(format!(
+                "Stripe coupon deletion failed: {}",
                 error_text
             )));
         }
 
-        let price: Value = response
-            .json()
-            .await
-            .map_err(|e| AppError::Internal(format!("Failed to parse Stripe response: {}", e)))?;
-
-        let price_id = price
-            .get("id")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| AppError::Internal("Stripe price missing id".to_string()))?
-            .to_string();
-
-        Ok(price_id)
+        Ok(())
     }
 
     // ============ 支付方式管理 ============
@@ -1025,29 +1439,13 @@ impl StripeService {
             payment_method_id
         );
 
-        let response = self
-            .http_client
-            .post(url)
-            .headers(self.get_headers())
-            .form(&[("customer", customer_id)])
-            .send()
-            .await
-            .map_err(|e| AppError::Internal(format!("Stripe API error: {}", e)))?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Internal(format!(
-                "Stripe attach payment method failed: {}",
-                error_text
-            )));
-        }
-
-        let payment_method: Value = response
-            .json()
-            .await
-            .map_err(|e| AppError::Internal(format!("Failed to parse Stripe response: {}", e)))?;
-
-        Ok(payment_method)
+        self.post_idempotent(
+            &url,
+            &[("customer", customer_id)],
+            &Self::one_off_idempotency_key("attach-payment-method"),
+            "attach payment method",
+        )
+        .await
     }
 
     async fn detach_payment_method(&self, payment_method_id: &str) -> Result<()> {
@@ -1056,21 +1454,13 @@ impl StripeService {
             payment_method_id
         );
 
-        let response = self
-            .http_client
-            .post(url)
-            .headers(self.get_headers())
-            .send()
-            .await
-            .map_err(|e| AppError::Internal(format!("Stripe API error: {}", e)))?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Internal(format!(
-                "Stripe detach payment method failed: {}",
-                error_text
-            )));
-        }
+        self.post_idempotent(
+            &url,
+            &[] as &[(&str, &str)],
+            &Self::one_off_idempotency_key("detach-payment-method"),
+            "detach payment method",
+        )
+        .await?;
 
         Ok(())
     }
@@ -1082,35 +1472,26 @@ impl StripeService {
         payment_method_id: Option<&str>,
     ) -> Result<()> {
         let mut form_params: Vec<(String, String)> = Vec::new();
-        match payment_method_id {
-            Some(id) => form_params.push((
-                "invoice_settings[default_payment_method]".to_string(),
-                id.to_string(),
-            )),
-            None => form_params.push((
-                "invoice_settings[default_payment_method]".to_string(),
-                String::new(),
-            )),
-        }
-
-        let update_url = format!("https://api.stripe.com/v1/customers/{}", customer_id);
-        let response = self
-            .http_client
-            .post(update_url)
-            .headers(self.get_headers())
-            .form(&form_params)
-            .send()
-            .await
-            .map_err(|e| AppError::Internal(format!("Stripe API error: {}", e)))?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Internal(format!(
-                "Stripe update default payment method failed: {}",
-                error_text
-            )));
+        match payment_method_id {
+            Some(id) => form_params.push((
+                "invoice_settings[default_payment_method]".to_string(),
+                id.to_string(),
+            )),
+            None => form_params.push((
+                "invoice_settings[default_payment_method]".to_string(),
+                String::new(),
+            )),
         }
 
+        let update_url = format!("https://api.stripe.com/v1/customers/{}", customer_id);
+        self.post_idempotent(
+            &update_url,
+            &form_params,
+            &Self::one_off_idempotency_key("customer-update"),
+            "update default payment method",
+        )
+        .await?;
+
         self
             .db
             .query_with_params(
@@ -1324,11 +1705,35 @@ impl StripeService {
             .await?
             .ok_or_else(|| AppError::BadRequest("Customer not found".to_string()))?;
 
+        // 同一客户对同一价格的重复提交使用同一个幂等键。客户端没有提供幂等键时，
+        // 以该价格上一条已结束的订阅区分"重试"和"取消后重新订阅"
+        let key_seed = match request.idempotency_key.clone() {
+            Some(key) => key,
+            None => self
+                .last_ended_subscription(&customer.stripe_customer_id, &request.price_id)
+                .await?
+                .unwrap_or_else(|| "initial".to_string()),
+        };
+        let idempotency_key = Self::idempotency_key(
+            "subscription",
+            &[&customer.stripe_customer_id, &request.price_id, &key_seed],
+        );
+
         // 创建Stripe订阅
         let stripe_subscription = self
-            .create_stripe_subscription(&customer.stripe_customer_id, &request)
+            .create_stripe_subscription(&customer.stripe_customer_id, &request, &idempotency_key)
             .await?;
 
+        // 重试拿到的是已保存的响应，本地记录也已经存在
+        if let Some(existing) = stripe_subscription["id"]
+            .as_str()
+            .map(|id| self.get_subscription_by_stripe_id(id))
+        {
+            if let Some(existing) = existing.await? {
+                return Ok(existing);
+            }
+        }
+
         // 保存到数据库
         let subscription_id = format!("stripe_subscription:{}", uuid::Uuid::new_v4());
         let now = Utc::now();
@@ -1404,10 +1809,9 @@ impl StripeService {
             .ok_or_else(|| AppError::Internal("Failed to create subscription".to_string()))?;
 
         Ok(StripeSubscription {
-            id: subscription_data["id"]
-                .as_str()
-                .unwrap_or_default()
-                .to_string(),
+            id: StripeSubscriptionId::from_value(&subscription_data["id"])
+                .map(|id| id.to_record())
+                .unwrap_or_default(),
             subscription_id: subscription_data["subscription_id"]
                 .as_str()
                 .unwrap_or_default()
@@ -1435,6 +1839,7 @@ impl StripeService {
         &self,
         customer_id: &str,
         request: &CreateStripeSubscriptionRequest,
+        idempotency_key: &str,
     ) -> Result<Value> {
         let mut params = vec![
             ("customer", customer_id.to_string()),
@@ -1453,6 +1858,10 @@ impl StripeService {
             params.push(("coupon", coupon.clone()));
         }
 
+        if self.config.tax_enabled {
+            params.push(("automatic_tax[enabled]", "true".to_string()));
+        }
+
         let mut metadata_params = Vec::new();
         if let Some(metadata) = &request.metadata {
             if let Some(obj) = metadata.as_object() {
@@ -1469,34 +1878,13 @@ impl StripeService {
             params.push((key.as_str(), value.clone()));
         }
 
-        let response = self
-            .http_client
-            .post("https://api.stripe.com/v1/subscriptions")
-            .headers(self.get_headers())
-            .form(
-                &params
-                    .iter()
-                    .map(|(k, v)| (*k, v.as_str()))
-                    .collect::<Vec<_>>(),
-            )
-            .send()
-            .await
-            .map_err(|e| AppError::Internal(format!("Stripe API error: {}", e)))?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Internal(format!(
-                "Stripe subscription creation failed: {}",
-                error_text
-            )));
-        }
-
-        let subscription: Value = response
-            .json()
-            .await
-            .map_err(|e| AppError::Internal(format!("Failed to parse Stripe response: {}", e)))?;
-
-        Ok(subscription)
+        self.post_idempotent(
+            "https://api.stripe.com/v1/subscriptions",
+            &params,
+            idempotency_key,
+            "subscription creation",
+        )
+        .await
     }
 
     /// 取消订阅
@@ -1520,24 +1908,22 @@ impl StripeService {
         // 更新数据库
         let query = if at_period_end {
             r#"
-                UPDATE stripe_subscription SET 
+                UPDATE type::thing('stripe_subscription', $subscription_id) SET 
                     cancel_at_period_end = true,
                     updated_at = $updated_at
-                WHERE id = $subscription_id
             "#
         } else {
             r#"
-                UPDATE stripe_subscription SET 
+                UPDATE type::thing('stripe_subscription', $subscription_id) SET 
                     status = $status,
                     canceled_at = $canceled_at,
                     updated_at = $updated_at
-                WHERE id = $subscription_id
             "#
         };
 
         let now = Utc::now();
         let mut params = json!({
-            "subscription_id": subscription_id,
+            "subscription_id": StripeSubscriptionId::new(subscription_id).as_str(),
             "updated_at": now
         });
 
@@ -1557,7 +1943,7 @@ impl StripeService {
         subscription_id: &str,
     ) -> Result<Option<StripeSubscription>> {
         let query = r#"
-            SELECT * FROM stripe_subscription WHERE id = $subscription_id LIMIT 1
+            SELECT * FROM type::thing('stripe_subscription', $subscription_id)
         "#;
 
         let mut response = self
@@ -1565,72 +1951,121 @@ impl StripeService {
             .query_with_params(
                 query,
                 json!({
-                    "subscription_id": subscription_id
+                    "subscription_id": StripeSubscriptionId::new(subscription_id).as_str()
                 }),
             )
             .await?;
 
         let subscriptions: Vec<Value> = response.take(0)?;
 
-        if let Some(sub_data) = subscriptions.into_iter().next() {
-            Ok(Some(StripeSubscription {
-                id: sub_data["id"].as_str().unwrap_or_default().to_string(),
-                subscription_id: sub_data["subscription_id"]
-                    .as_str()
-                    .unwrap_or_default()
-                    .to_string(),
-                stripe_subscription_id: sub_data["stripe_subscription_id"]
-                    .as_str()
-                    .unwrap_or_default()
-                    .to_string(),
-                stripe_customer_id: sub_data["stripe_customer_id"]
-                    .as_str()
-                    .unwrap_or_default()
-                    .to_string(),
-                stripe_price_id: sub_data["stripe_price_id"]
-                    .as_str()
-                    .unwrap_or_default()
-                    .to_string(),
-                status: serde_json::from_value(sub_data["status"].clone())
-                    .unwrap_or(StripeSubscriptionStatus::Active),
-                current_period_start: chrono::DateTime::parse_from_rfc3339(
-                    sub_data["current_period_start"]
-                        .as_str()
-                        .unwrap_or_default(),
-                )
+        Ok(subscriptions.first().map(Self::parse_stripe_subscription))
+    }
+
+    /// 按 Stripe 订阅 ID 查找本地记录
+    async fn get_subscription_by_stripe_id(
+        &self,
+        stripe_subscription_id: &str,
+    ) -> Result<Option<StripeSubscription>> {
+        let mut response = self
+            .db
+            .query_with_params(
+                "SELECT * FROM stripe_subscription WHERE stripe_subscription_id = $stripe_subscription_id LIMIT 1",
+                json!({ "stripe_subscription_id": stripe_subscription_id }),
+            )
+            .await?;
+
+        let subscriptions: Vec<Value> = response.take(0)?;
+        Ok(subscriptions.first().map(Self::parse_stripe_subscription))
+    }
+
+    /// 该客户在该价格上最近一条已结束的订阅
+    async fn last_ended_subscription(
+        &self,
+        stripe_customer_id: &str,
+        stripe_price_id: &str,
+    ) -> Result<Option<String>> {
+        let mut response = self
+            .db
+            .query_with_params(
+                r#"
+                SELECT id, created_at FROM stripe_subscription
+                WHERE stripe_customer_id = $stripe_customer_id
+                    AND stripe_price_id = $stripe_price_id
+                    AND status IN ['canceled', 'incomplete_expired']
+                ORDER BY created_at DESC LIMIT 1
+                "#,
+                json!({
+                    "stripe_customer_id": stripe_customer_id,
+                    "stripe_price_id": stripe_price_id,
+                }),
+            )
+            .await?;
+
+        let subscriptions: Vec<Value> = response.take(0)?;
+        Ok(subscriptions
+            .first()
+            .and_then(|s| StripeSubscriptionId::from_value(&s["id"]))
+            .map(|id| id.as_str().to_string()))
+    }
+
+    fn parse_stripe_subscription(sub_data: &Value) -> StripeSubscription {
+        StripeSubscription {
+            id: StripeSubscriptionId::from_value(&sub_data["id"])
+                .map(|id| id.to_record())
+                .unwrap_or_default(),
+            subscription_id: sub_data["subscription_id"]
+                .as_str()
                 .unwrap_or_default()
-                .with_timezone(&Utc),
-                current_period_end: chrono::DateTime::parse_from_rfc3339(
-                    sub_data["current_period_end"].as_str().unwrap_or_default(),
-                )
+                .to_string(),
+            stripe_subscription_id: sub_data["stripe_subscription_id"]
+                .as_str()
                 .unwrap_or_default()
-                .with_timezone(&Utc),
-                cancel_at_period_end: sub_data["cancel_at_period_end"].as_bool().unwrap_or(false),
-                canceled_at: sub_data["canceled_at"]
-                    .as_str()
-                    .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
-                    .map(|dt| dt.with_timezone(&Utc)),
-                trial_start: sub_data["trial_start"]
-                    .as_str()
-                    .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
-                    .map(|dt| dt.with_timezone(&Utc)),
-                trial_end: sub_data["trial_end"]
-                    .as_str()
-                    .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
-                    .map(|dt| dt.with_timezone(&Utc)),
-                created_at: chrono::DateTime::parse_from_rfc3339(
-                    sub_data["created_at"].as_str().unwrap_or_default(),
-                )
+                .to_string(),
+            stripe_customer_id: sub_data["stripe_customer_id"]
+                .as_str()
                 .unwrap_or_default()
-                .with_timezone(&Utc),
-                updated_at: chrono::DateTime::parse_from_rfc3339(
-                    sub_data["updated_at"].as_str().unwrap_or_default(),
-                )
+                .to_string(),
+            stripe_price_id: sub_data["stripe_price_id"]
+                .as_str()
                 .unwrap_or_default()
-                .with_timezone(&Utc),
-            }))
-        } else {
-            Ok(None)
+                .to_string(),
+            status: serde_json::from_value(sub_data["status"].clone())
+                .unwrap_or(StripeSubscriptionStatus::Active),
+            current_period_start: chrono::DateTime::parse_from_rfc3339(
+                sub_data["current_period_start"]
+                    .as_str()
+                    .unwrap_or_default(),
+            )
+            .unwrap_or_default()
+            .with_timezone(&Utc),
+            current_period_end: chrono::DateTime::parse_from_rfc3339(
+                sub_data["current_period_end"].as_str().unwrap_or_default(),
+            )
+            .unwrap_or_default()
+            .with_timezone(&Utc),
+            cancel_at_period_end: sub_data["cancel_at_period_end"].as_bool().unwrap_or(false),
+            canceled_at: sub_data["canceled_at"]
+                .as_str()
+                .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+            trial_start: sub_data["trial_start"]
+                .as_str()
+                .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+            trial_end: sub_data["trial_end"]
+                .as_str()
+                .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+            created_at: chrono::DateTime::parse_from_rfc3339(
+                sub_data["created_at"].as_str().unwrap_or_default(),
+            )
+            .unwrap_or_default()
+            .with_timezone(&Utc),
+            updated_at: chrono::DateTime::parse_from_rfc3339(
+                sub_data["updated_at"].as_str().unwrap_or_default(),
+            )
+            .unwrap_or_default()
+            .with_timezone(&Utc),
         }
     }
 
@@ -1650,22 +2085,13 @@ impl StripeService {
             vec![("cancel_at_period_end", "false")]
         };
 
-        let response = self
-            .http_client
-            .post(&url)
-            .headers(self.get_headers())
-            .form(&params)
-            .send()
-            .await
-            .map_err(|e| AppError::Internal(format!("Stripe API error: {}", e)))?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Internal(format!(
-                "Stripe subscription cancellation failed: {}",
-                error_text
-            )));
-        }
+        self.post_idempotent(
+            &url,
+            &params,
+            &Self::one_off_idempotency_key("subscription-update"),
+            "subscription cancellation",
+        )
+        .await?;
 
         Ok(())
     }
@@ -1690,17 +2116,43 @@ impl StripeService {
             return Ok(StripeWebhookOutcome::default());
         }
 
-        let mut outcome = StripeWebhookOutcome::default();
+        let mut outcome = StripeWebhookOutcome {
+            event_id: Some(saved_event.id),
+            ..Default::default()
+        };
 
         // 根据事件类型处理
         match event_type {
             "payment_intent.succeeded" => {
+                let payment_intent = &event_data["data"]["object"];
+                if let (Some(calculation_id), Some(intent_id)) = (
+                    payment_intent["metadata"]["tax_calculation"].as_str(),
+                    payment_intent["id"].as_str(),
+                ) {
+                    if let Err(e) = self.record_tax_transaction(calculation_id, intent_id).await {
+                        warn!("Failed to record Stripe Tax transaction for {}: {}", intent_id, e);
+                    }
+                }
+                if let Some(payment) = Self::parse_gift_payment(&event_data) {
+                    outcome.gift_payments.push(payment);
+                }
                 if let Some(update) = self.handle_payment_intent_succeeded(&event_data).await? {
                     outcome.purchase_updates.push(update);
                 }
             }
             "payment_intent.payment_failed" => {
-                self.handle_payment_intent_failed(&event_data).await?;
+                let intent_id = self.handle_payment_intent_failed(&event_data).await?;
+                outcome.failed_payment_intents.push(intent_id);
+            }
+            "charge.refunded" => {
+                if let Some(reversal) = Self::parse_charge_refunded(&event_data) {
+                    outcome.charge_reversals.push(reversal);
+                }
+            }
+            "charge.dispute.created" | "charge.dispute.closed" => {
+                if let Some(reversal) = Self::parse_charge_dispute(&event_data) {
+                    outcome.charge_reversals.push(reversal);
+                }
             }
             "invoice.payment_succeeded" => {
                 if let Some(revenue) = self.handle_invoice_payment_succeeded(&event_data).await? {
@@ -1727,15 +2179,6 @@ impl StripeService {
             }
         }
 
-        let summary = json!({
-            "purchase_updates": outcome.purchase_updates.len(),
-            "subscription_revenues": outcome.subscription_revenues.len(),
-            "subscription_status_updates": outcome.subscription_status_updates.len(),
-        });
-
-        self.mark_webhook_event_processed(&saved_event.id, summary)
-            .await?;
-
         Ok(outcome)
     }
 
@@ -1757,9 +2200,8 @@ impl StripeService {
         if let Some(record) = records.into_iter().next() {
             let id = record
                 .get("id")
-                .and_then(|v| v.as_str())
-                .unwrap_or_default()
-                .to_string();
+                .and_then(WebhookEventId::from_value)
+                .ok_or_else(|| AppError::Internal("webhook_event 记录缺少 ID".to_string()))?;
             let processed = record
                 .get("processed")
                 .and_then(|v| v.as_bool())
@@ -1768,9 +2210,9 @@ impl StripeService {
             if !processed {
                 self.db
                     .query_with_params(
-                        "UPDATE webhook_event SET data = $data WHERE id = $event_id",
+                        "UPDATE type::thing('webhook_event', $event_id) SET data = $data",
                         json!({
-                            "event_id": &id,
+                            "event_id": id.as_str(),
                             "data": event_data,
                         }),
                     )
@@ -1778,12 +2220,12 @@ impl StripeService {
             }
 
             return Ok(SavedWebhookEvent {
-                id,
+                id: id.to_record(),
                 already_processed: processed,
             });
         }
 
-        let event_id = format!("webhook_event:{}", uuid::Uuid::new_v4());
+        let event_id = WebhookEventId::new(uuid::Uuid::new_v4().to_string());
         let now = Utc::now();
         let event_type = event_data
             .get("type")
@@ -1791,8 +2233,7 @@ impl StripeService {
             .unwrap_or("unknown");
 
         let query = r#"
-            CREATE webhook_event CONTENT {
-                id: $event_id,
+            CREATE type::thing('webhook_event', $event_id) CONTENT {
                 stripe_event_id: $stripe_event_id,
                 event_type: $event_type,
                 processed: false,
@@ -1807,7 +2248,7 @@ impl StripeService {
             .query_with_params(
                 query,
                 json!({
-                    "event_id": &event_id,
+                    "event_id": event_id.as_str(),
                     "stripe_event_id": stripe_event_id,
                     "event_type": event_type,
                     "data": event_data,
@@ -1817,23 +2258,38 @@ impl StripeService {
             .await?;
 
         Ok(SavedWebhookEvent {
-            id: event_id,
+            id: event_id.to_record(),
             already_processed: false,
         })
     }
 
-    async fn mark_webhook_event_processed(&self, event_id: &str, summary: Value) -> Result<()> {
+    /// 事件的所有后续处理（购买、收益、订阅状态等）都成功后再标记为已处理，
+    /// 中途失败的事件留给重试队列
+    pub async fn mark_webhook_event_processed(&self, outcome: &StripeWebhookOutcome) -> Result<()> {
+        let Some(event_id) = &outcome.event_id else {
+            return Ok(());
+        };
+
+        let summary = json!({
+            "purchase_updates": outcome.purchase_updates.len(),
+            "failed_payment_intents": outcome.failed_payment_intents.len(),
+            "charge_reversals": outcome.charge_reversals.len(),
+            "gift_payments": outcome.gift_payments.len(),
+            "subscription_revenues": outcome.subscription_revenues.len(),
+            "subscription_status_updates": outcome.subscription_status_updates.len(),
+        });
+
         self.db
             .query_with_params(
                 r#"
-            UPDATE webhook_event SET
+            UPDATE type::thing('webhook_event', $event_id) SET
                 processed = true,
                 processed_at = time::now(),
-                processing_summary = $summary
-            WHERE id = $event_id
+                processing_summary = $summary,
+                next_retry_at = NONE
         "#,
                 json!({
-                    "event_id": event_id,
+                    "event_id": WebhookEventId::new(event_id).as_str(),
                     "summary": summary,
                 }),
             )
@@ -1906,7 +2362,8 @@ impl StripeService {
 
         let article_id = record
             .get("article_id")
-            .and_then(Self::extract_record_id)
+            .and_then(ArticleId::from_value)
+            .map(|id| id.to_record())
             .or_else(|| {
                 metadata
                     .get("article_id")
@@ -1966,11 +2423,12 @@ impl StripeService {
             purchase_id,
             amount,
             currency,
+            tax: Self::parse_intent_tax(payment_intent),
         }))
     }
 
     /// 处理支付意图失败事件
-    async fn handle_payment_intent_failed(&self, event_data: &Value) -> Result<()> {
+    async fn handle_payment_intent_failed(&self, event_data: &Value) -> Result<String> {
         let payment_intent = &event_data["data"]["object"];
         let stripe_payment_intent_id = payment_intent["id"]
             .as_str()
@@ -2000,7 +2458,63 @@ impl StripeService {
             )
             .await?;
 
-        Ok(())
+        Ok(stripe_payment_intent_id.to_string())
+    }
+
+    fn parse_gift_payment(event_data: &Value) -> Option<StripeGiftPayment> {
+        let payment_intent = &event_data["data"]["object"];
+        Some(StripeGiftPayment {
+            gift_id: payment_intent["metadata"]["gift_id"].as_str()?.to_string(),
+            stripe_payment_intent_id: payment_intent["id"].as_str()?.to_string(),
+            amount: payment_intent["amount_received"]
+                .as_i64()
+                .or_else(|| payment_intent["amount"].as_i64())?,
+            tax: Self::parse_intent_tax(payment_intent),
+        })
+    }
+
+    /// 解析 `charge.refunded`。没有关联 PaymentIntent 的收款（如手动收款）不处理
+    fn parse_charge_refunded(event_data: &Value) -> Option<StripeChargeReversal> {
+        let charge = &event_data["data"]["object"];
+        let stripe_payment_intent_id = charge["payment_intent"].as_str()?;
+        let amount = charge["amount_refunded"].as_i64().filter(|amount| *amount > 0)?;
+
+        debug!("Handling charge refunded for intent: {}", stripe_payment_intent_id);
+
+        Some(StripeChargeReversal {
+            stripe_payment_intent_id: stripe_payment_intent_id.to_string(),
+            stripe_invoice_id: charge["invoice"].as_str().map(str::to_string),
+            kind: StripeChargeReversalKind::Refund,
+            amount,
+            currency: charge["currency"].as_str().unwrap_or("usd").to_uppercase(),
+        })
+    }
+
+    /// 解析争议事件：创建时冲回收款，结束时只有胜诉需要恢复，败诉已在创建时处理
+    fn parse_charge_dispute(event_data: &Value) -> Option<StripeChargeReversal> {
+        let dispute = &event_data["data"]["object"];
+        let stripe_payment_intent_id = dispute["payment_intent"].as_str()?;
+
+        let kind = match event_data["type"].as_str()? {
+            "charge.dispute.created" => StripeChargeReversalKind::Dispute,
+            _ if dispute["status"].as_str() == Some("won") => StripeChargeReversalKind::DisputeWon,
+            _ => return None,
+        };
+
+        info!(
+            "Stripe {} for payment intent {} (reason: {})",
+            kind.as_str(),
+            stripe_payment_intent_id,
+            dispute["reason"].as_str().unwrap_or("unknown")
+        );
+
+        Some(StripeChargeReversal {
+            stripe_payment_intent_id: stripe_payment_intent_id.to_string(),
+            stripe_invoice_id: None,
+            kind,
+            amount: dispute["amount"].as_i64().unwrap_or(0),
+            currency: dispute["currency"].as_str().unwrap_or("usd").to_uppercase(),
+        })
     }
 
     /// 处理发票支付成功事件
@@ -2073,6 +2587,13 @@ impl StripeService {
             .unwrap_or("usd")
             .to_uppercase();
 
+        let tax = CollectedTax {
+            amount: invoice.get("tax").and_then(|v| v.as_i64()).unwrap_or(0),
+            country: invoice["customer_address"]["country"]
+                .as_str()
+                .map(|s| s.to_uppercase()),
+        };
+
         let period_end = invoice
             .get("lines")
             .and_then(|v| v.get("data"))
@@ -2096,10 +2617,13 @@ impl StripeService {
 
         Ok(Some(StripeSubscriptionRevenue {
             subscription_id,
+            stripe_invoice_id: invoice["id"].as_str().map(str::to_string),
+            stripe_payment_intent_id: invoice["payment_intent"].as_str().map(str::to_string),
             creator_id,
             subscriber_id,
             amount,
             currency,
+            tax,
             current_period_end: period_end,
         }))
     }
@@ -2262,12 +2786,13 @@ impl StripeService {
         cancel_at_period_end: Option<bool>,
         canceled_at: Option<DateTime<Utc>>,
     ) -> Result<StripeSubscriptionStatusUpdate> {
+        let record_id = SubscriptionId::new(subscription_id);
         let mut fetch_response = self
             .db
             .query_with_params(
-                "SELECT subscriber_id, creator_id FROM subscription WHERE id = $subscription_id LIMIT 1",
+                "SELECT subscriber_id, creator_id FROM type::thing('subscription', $subscription_id)",
                 json!({
-                    "subscription_id": subscription_id,
+                    "subscription_id": record_id.as_str(),
                 }),
             )
             .await?;
@@ -2293,11 +2818,11 @@ impl StripeService {
 
         self.db
             .query_with_params(
-                "UPDATE subscription SET status = $status, updated_at = $updated_at WHERE id = $subscription_id",
+                "UPDATE type::thing('subscription', $subscription_id) SET status = $status, updated_at = $updated_at",
                 json!({
                     "status": status.to_string(),
                     "updated_at": now,
-                    "subscription_id": subscription_id,
+                    "subscription_id": record_id.as_str(),
                 }),
             )
             .await?;
@@ -2305,11 +2830,11 @@ impl StripeService {
         if let Some(period_end) = current_period_end {
             self.db
                 .query_with_params(
-                    "UPDATE subscription SET current_period_end = $current_period_end, updated_at = $updated_at WHERE id = $subscription_id",
+                    "UPDATE type::thing('subscription', $subscription_id) SET current_period_end = $current_period_end, updated_at = $updated_at",
                     json!({
                         "current_period_end": period_end,
                         "updated_at": now,
-                        "subscription_id": subscription_id,
+                        "subscription_id": record_id.as_str(),
                     }),
                 )
                 .await?;
@@ -2318,11 +2843,11 @@ impl StripeService {
         if let Some(flag) = cancel_at_period_end {
             self.db
                 .query_with_params(
-                    "UPDATE subscription SET cancel_at_period_end = $flag, updated_at = $updated_at WHERE id = $subscription_id",
+                    "UPDATE type::thing('subscription', $subscription_id) SET cancel_at_period_end = $flag, updated_at = $updated_at",
                     json!({
                         "flag": flag,
                         "updated_at": now,
-                        "subscription_id": subscription_id,
+                        "subscription_id": record_id.as_str(),
                     }),
                 )
                 .await?;
@@ -2331,11 +2856,11 @@ impl StripeService {
         if let Some(canceled) = canceled_at {
             self.db
                 .query_with_params(
-                    "UPDATE subscription SET canceled_at = $canceled_at, updated_at = $updated_at WHERE id = $subscription_id",
+                    "UPDATE type::thing('subscription', $subscription_id) SET canceled_at = $canceled_at, updated_at = $updated_at",
                     json!({
                         "canceled_at": canceled,
                         "updated_at": now,
-                        "subscription_id": subscription_id,
+                        "subscription_id": record_id.as_str(),
                     }),
                 )
                 .await?;
@@ -2423,7 +2948,7 @@ impl StripeService {
 
         debug!("Creating Connect account for user: {}", user_id);
 
-        let stripe_account = self.create_stripe_connect_account(&request).await?;
+        let stripe_account = self.create_stripe_connect_account(user_id, &request).await?;
         let account = self
             .upsert_connect_account_record(user_id, &stripe_account)
             .await?;
@@ -2472,6 +2997,7 @@ impl StripeService {
     /// 在Stripe创建Connect账户
     async fn create_stripe_connect_account(
         &self,
+        user_id: &str,
         request: &CreateConnectAccountRequest,
     ) -> Result<Value> {
         let account_type_str = match request.account_type {
@@ -2505,29 +3031,13 @@ impl StripeService {
             }
         }
 
-        let response = self
-            .http_client
-            .post("https://api.stripe.com/v1/accounts")
-            .headers(self.get_headers())
-            .form(&params)
-            .send()
-            .await
-            .map_err(|e| AppError::Internal(format!("Stripe API error: {}", e)))?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::Internal(format!(
-                "Stripe Connect account creation failed: {}",
-                error_text
-            )));
-        }
-
-        let account: Value = response
-            .json()
-            .await
-            .map_err(|e| AppError::Internal(format!("Failed to parse Stripe response: {}", e)))?;
-
-        Ok(account)
+        self.post_idempotent(
+            "https://api.stripe.com/v1/accounts",
+            &params,
+            &Self::idempotency_key("connect-account", &[user_id]),
+            "Connect account creation",
+        )
+        .await
     }
 
     async fn get_connect_account_record_by_user(
@@ -2558,10 +3068,10 @@ impl StripeService {
         &self,
         identifier: &str,
     ) -> Result<Option<StripeConnectAccount>> {
-        let (query, params) = if identifier.starts_with("connect_account:") {
+        let (query, params) = if identifier.starts_with(&format!("{}:", ConnectAccountId::TABLE)) {
             (
-                "SELECT * FROM connect_account WHERE id = type::thing($record_id) LIMIT 1",
-                json!({ "record_id": identifier }),
+                "SELECT * FROM type::thing('connect_account', $record_id)",
+                json!({ "record_id": ConnectAccountId::new(identifier).as_str() }),
             )
         } else {
             (
@@ -2676,27 +3186,14 @@ impl StripeService {
             ("type", "account_onboarding"),
         ];
 
-        let response = self
-            .http_client
-            .post("https://api.stripe.com/v1/account_links")
-            .headers(self.get_headers())
-            .form(&params)
-            .send()
-            .await
-            .map_err(|e| AppError::Internal(format!("Stripe API error: {}", e)))?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(AppError::ExternalService(format!(
-                "Stripe account link creation failed: {}",
-                error_text
-            )));
-        }
-
-        let body: Value = response
-            .json()
-            .await
-            .map_err(|e| AppError::Internal(format!("Failed to parse Stripe response: {}", e)))?;
+        let body = self
+            .post_idempotent(
+                "https://api.stripe.com/v1/account_links",
+                &params,
+                &Self::one_off_idempotency_key("account-link"),
+                "account link creation",
+            )
+            .await?;
 
         body.get("url")
             .and_then(|v| v.as_str())
@@ -2704,6 +3201,38 @@ impl StripeService {
             .ok_or_else(|| AppError::Internal("Stripe response missing onboarding url".to_string()))
     }
 
+    /// 从平台余额向创作者的 Connect 账户转账，返回 Stripe transfer ID。
+    /// 以打款记录 ID 作为幂等键，重试同一笔打款不会重复转账
+    pub async fn create_transfer(
+        &self,
+        stripe_account_id: &str,
+        amount: i64,
+        currency: &str,
+        payout_id: &str,
+    ) -> Result<String> {
+        let params = [
+            ("amount", amount.to_string()),
+            ("currency", currency.to_lowercase()),
+            ("destination", stripe_account_id.to_string()),
+            ("transfer_group", payout_id.to_string()),
+            ("metadata[payout_id]", payout_id.to_string()),
+        ];
+
+        let body = self
+            .post_idempotent(
+                "https://api.stripe.com/v1/transfers",
+                &params,
+                &Self::idempotency_key("payout", &[payout_id]),
+                "transfer creation",
+            )
+            .await?;
+
+        body.get("id")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .ok_or_else(|| AppError::Internal("Stripe response missing transfer id".to_string()))
+    }
+
     async fn upsert_connect_account_record(
         &self,
         user_id: &str,
@@ -2859,8 +3388,8 @@ impl StripeService {
         let mut response = self
             .db
             .query_with_params(
-                "SELECT author_id FROM article WHERE id = $article_id LIMIT 1",
-                json!({ "article_id": article_id }),
+                "SELECT author_id FROM type::thing('article', $article_id)",
+                json!({ "article_id": ArticleId::new(article_id).as_str() }),
             )
             .await?;
 
@@ -2873,23 +3402,6 @@ impl StripeService {
         }))
     }
 
-    fn extract_record_id(value: &Value) -> Option<String> {
-        if let Some(s) = value.as_str() {
-            return Some(s.to_string());
-        }
-
-        if let Some(obj) = value.as_object() {
-            if let Some(id) = obj.get("id").and_then(|v| v.as_str()) {
-                if let Some(tb) = obj.get("tb").and_then(|v| v.as_str()) {
-                    return Some(format!("{}:{}", tb, id));
-                }
-                return Some(id.to_string());
-            }
-        }
-
-        None
-    }
-
     fn parse_connect_account_record(&self, record: Value) -> Result<StripeConnectAccount> {
         let account_type = match record
             .get("account_type")
@@ -2904,9 +3416,9 @@ impl StripeService {
         Ok(StripeConnectAccount {
             id: record
                 .get("id")
-                .and_then(|v| v.as_str())
-                .unwrap_or_default()
-                .to_string(),
+                .and_then(ConnectAccountId::from_value)
+                .map(|id| id.to_record())
+                .unwrap_or_default(),
             user_id: record
                 .get("user_id")
                 .and_then(|v| v.as_str())
@@ -0,0 +1,132 @@
+use crate::{
+    error::{AppError, Result},
+    models::{
+        article::Article,
+        id::PublicationId,
+        style_guide::*,
+    },
+    services::{ArticleService, Database},
+    utils::prose_lint::lint_markdown,
+};
+use std::sync::Arc;
+use tracing::info;
+use validator::Validate;
+
+/// 出版物写作规范的保存和草稿检查
+#[derive(Clone)]
+pub struct StyleGuideService {
+    db: Arc<Database>,
+    article_service: ArticleService,
+}
+
+impl StyleGuideService {
+    pub async fn new(db: Arc<Database>, article_service: ArticleService) -> Result<Self> {
+        Ok(Self { db, article_service })
+    }
+
+    /// 出版物的写作规范，没有保存过（或文章不属于出版物）时返回默认规范
+    pub async fn get(&self, publication_id: Option<&str>) -> Result<StyleGuide> {
+        let Some(publication_id) = publication_id.map(PublicationId::new) else {
+            return Ok(StyleGuide::default_for(None));
+        };
+
+        let guide: Option<StyleGuide> = self.db
+            .prepare("SELECT * FROM type::thing('style_guide', $publication_id)")
+            .bind("publication_id", publication_id.as_str())
+            .fetch_one()
+            .await?;
+
+        Ok(guide.unwrap_or_else(|| StyleGuide::default_for(Some(publication_id.as_str()))))
+    }
+
+    pub async fn update(&self, publication_id: &str, request: UpdateStyleGuideRequest) -> Result<StyleGuide> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let mut guide = self.get(Some(publication_id)).await?;
+        if let Some(phrases) = request.banned_phrases {
+            let mut phrases: Vec<String> = phrases
+                .iter()
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect();
+            phrases.sort_by_key(|p| p.to_lowercase());
+            phrases.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+            guide.banned_phrases = phrases;
+        }
+        if let Some(check_passive_voice) = request.check_passive_voice {
+            guide.check_passive_voice = check_passive_voice;
+        }
+        if let Some(max_heading_depth) = request.max_heading_depth {
+            guide.max_heading_depth = max_heading_depth;
+        }
+        if let Some(require_alt_text) = request.require_alt_text {
+            guide.require_alt_text = require_alt_text;
+        }
+        if let Some(enforce_on_submit) = request.enforce_on_submit {
+            guide.enforce_on_submit = enforce_on_submit;
+        }
+
+        let updated: Option<StyleGuide> = self.db
+            .prepare(
+                r#"
+                UPSERT type::thing('style_guide', $publication_id) CONTENT {
+                    publication_id: $publication_id,
+                    banned_phrases: $banned_phrases,
+                    check_passive_voice: $check_passive_voice,
+                    max_heading_depth: $max_heading_depth,
+                    require_alt_text: $require_alt_text,
+                    enforce_on_submit: $enforce_on_submit,
+                    updated_at: time::now()
+                }
+                "#,
+            )
+            .bind("publication_id", PublicationId::new(publication_id).as_str())
+            .bind("banned_phrases", &guide.banned_phrases)
+            .bind("check_passive_voice", guide.check_passive_voice)
+            .bind("max_heading_depth", guide.max_heading_depth)
+            .bind("require_alt_text", guide.require_alt_text)
+            .bind("enforce_on_submit", guide.enforce_on_submit)
+            .fetch_one()
+            .await?;
+
+        info!("Updated style guide for publication {}", publication_id);
+        updated.ok_or_else(|| AppError::internal("Failed to update style guide"))
+    }
+
+    /// 按文章所属出版物的规范检查正文；`content` 用于检查编辑器中尚未保存的内容
+    pub async fn lint_article(&self, article: &Article, content: Option<&str>) -> Result<LintReport> {
+        let guide = self.get(article.publication_id.as_deref()).await?;
+        let issues = lint_markdown(content.unwrap_or(&article.content), &guide);
+        Ok(LintReport::new(issues, guide.enforce_on_submit && article.publication_id.is_some()))
+    }
+
+    /// 发布到出版物或提交审核前调用：规范开启强制检查且有错误时拒绝。
+    /// 发布时同时修改正文的，传入新的正文
+    pub async fn ensure_submittable(&self, article_id: &str, content: Option<&str>) -> Result<()> {
+        let Some(article) = self.article_service.get_article_by_id(article_id).await? else {
+            return Ok(());
+        };
+        self.check_submission(article.publication_id.as_deref(), content.unwrap_or(&article.content)).await
+    }
+
+    /// 检查将要提交到出版物的正文，不属于出版物的文章不做强制检查
+    pub async fn check_submission(&self, publication_id: Option<&str>, content: &str) -> Result<()> {
+        let Some(publication_id) = publication_id else {
+            return Ok(());
+        };
+
+        let guide = self.get(Some(publication_id)).await?;
+        if !guide.enforce_on_submit {
+            return Ok(());
+        }
+
+        let report = LintReport::new(lint_markdown(content, &guide), true);
+        if report.blocks_submission() {
+            return Err(AppError::BadRequest(format!(
+                "Article has {} style guide error(s), run the linter and fix them before submitting",
+                report.error_count
+            )));
+        }
+        Ok(())
+    }
+}
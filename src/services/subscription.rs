@@ -1,13 +1,14 @@
 use crate::{
-    error::{AppError, Result},
+    error::{AppError, ErrorCode, Result},
     models::{
-        stripe::{CreateStripeSubscriptionRequest, StripeSubscriptionStatus},
+        id::bare_id,
+        stripe::{CouponDuration, CreateStripeSubscriptionRequest, StripeSubscriptionStatus},
         subscription::*,
         user::UserProfile,
     },
     services::{stripe::StripeService, Database},
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Months, Utc};
 use serde_json::{json, Value};
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
@@ -365,6 +366,11 @@ impl SubscriptionService {
             AppError::BadRequest("订阅计划尚未配置 Stripe 价格，请联系管理员".to_string())
         })?;
 
+        let coupon = match request.coupon_code.as_deref().filter(|c| !c.trim().is_empty()) {
+            Some(code) => Some(self.find_redeemable_coupon(&plan, code).await?),
+            None => None,
+        };
+
         let payment_method_id = if let Some(pm) = request
             .payment_method_id
             .as_ref()
@@ -381,7 +387,7 @@ impl SubscriptionService {
                 .into_iter()
                 .find(|pm| pm.is_default)
                 .map(|pm| pm.stripe_payment_method_id.clone())
-                .ok_or_else(|| AppError::BadRequest("请先添加并设置默认支付方式".to_string()))?
+                .ok_or_else(|| AppError::coded(ErrorCode::PaymentMethodRequired, "请先添加并设置默认支付方式"))?
         };
 
         let stripe_subscription = self
@@ -392,15 +398,29 @@ impl SubscriptionService {
                     price_id: stripe_price_id.clone(),
                     payment_method_id: Some(payment_method_id.clone()),
                     trial_period_days: None,
-                    coupon: None,
+                    coupon: coupon.as_ref().map(|c| c.stripe_coupon_id.clone()),
                     metadata: Some(json!({
                         "plan_id": plan.id,
                         "creator_id": plan.creator_id
                     })),
+                    idempotency_key: request.idempotency_key.clone(),
                 },
             )
             .await?;
 
+        // 重复提交时 Stripe 返回的是同一个订阅，直接返回已经创建的本地记录
+        let mut response = self
+            .db
+            .query_with_params(
+                "SELECT * FROM subscription WHERE stripe_subscription_id = $stripe_subscription_id LIMIT 1",
+                json!({ "stripe_subscription_id": stripe_subscription.stripe_subscription_id }),
+            )
+            .await?;
+        let existing: Vec<Value> = response.take(0)?;
+        if let Some(existing) = existing.into_iter().next() {
+            return self.build_subscription_details_sync(existing, plan);
+        }
+
         let subscription_id = format!("subscription:{}", uuid::Uuid::new_v4());
         let started_at = stripe_subscription.current_period_start;
         let current_period_end = stripe_subscription.current_period_end;
@@ -421,6 +441,7 @@ impl SubscriptionService {
                 canceled_at: NULL,
                 stripe_subscription_id: $stripe_subscription_id,
                 stripe_subscription_record_id: $stripe_subscription_record_id,
+                coupon_id: $coupon_id,
                 created_at: time::now(),
                 updated_at: time::now()
             }
@@ -435,6 +456,7 @@ impl SubscriptionService {
                     "current_period_end": current_period_end.to_rfc3339(),
                     "stripe_subscription_id": stripe_subscription.stripe_subscription_id,
                     "stripe_subscription_record_id": stripe_subscription.id,
+                    "coupon_id": coupon.as_ref().map(|c| c.id.clone()),
                 }),
             )
             .await?;
@@ -445,11 +467,112 @@ impl SubscriptionService {
             .next()
             .ok_or_else(|| AppError::Internal("Failed to create subscription".to_string()))?;
 
+        if let Some(coupon) = &coupon {
+            // Stripe 已经应用了折扣，本地记录失败只影响统计，不让订阅失败
+            if let Err(e) = self
+                .record_coupon_redemption(coupon, &plan, &subscription_id, subscriber_id)
+                .await
+            {
+                error!("Failed to record redemption of coupon {}: {}", coupon.id, e);
+            }
+        }
+
         let subscription_details = self.build_subscription_details_sync(created, plan)?;
         info!("Subscription created: {}", subscription_details.id);
         Ok(subscription_details)
     }
 
+    /// 领取礼物订阅：创建一条不经过 Stripe、到期后不续费的订阅
+    pub async fn create_gift_subscription(
+        &self,
+        subscriber_id: &str,
+        plan_id: &str,
+        months: u32,
+        gift_id: &str,
+    ) -> Result<SubscriptionDetails> {
+        let plan = self.get_subscription_plan(plan_id).await?;
+
+        if plan.creator_id == subscriber_id {
+            return Err(AppError::BadRequest("无法订阅自己的计划".to_string()));
+        }
+
+        if self
+            .check_existing_subscription(subscriber_id, &plan.creator_id)
+            .await?
+        {
+            return Err(AppError::BadRequest("您已经订阅了该创作者".to_string()));
+        }
+
+        let started_at = Utc::now();
+        let current_period_end = started_at
+            .checked_add_months(Months::new(months))
+            .ok_or_else(|| AppError::BadRequest("礼物订阅月数无效".to_string()))?;
+
+        let mut response = self
+            .db
+            .query_with_params(
+                r#"
+            CREATE subscription CONTENT {
+                id: $subscription_id,
+                subscriber_id: $subscriber_id,
+                plan_id: $plan_id,
+                creator_id: $creator_id,
+                status: "active",
+                started_at: $started_at,
+                current_period_end: $current_period_end,
+                canceled_at: NULL,
+                gift_id: $gift_id,
+                created_at: time::now(),
+                updated_at: time::now()
+            }
+        "#,
+                json!({
+                    "subscription_id": format!("subscription:{}", uuid::Uuid::new_v4()),
+                    "subscriber_id": subscriber_id,
+                    "plan_id": plan.id,
+                    "creator_id": plan.creator_id,
+                    "started_at": started_at.to_rfc3339(),
+                    "current_period_end": current_period_end.to_rfc3339(),
+                    "gift_id": gift_id,
+                }),
+            )
+            .await?;
+
+        let subscriptions: Vec<Value> = response.take(0)?;
+        let created = subscriptions
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::Internal("Failed to create gift subscription".to_string()))?;
+
+        let subscription_details = self.build_subscription_details_sync(created, plan)?;
+        info!(
+            "Gift subscription {} created for {} until {}",
+            subscription_details.id, subscriber_id, current_period_end
+        );
+        Ok(subscription_details)
+    }
+
+    /// 把已经过了有效期的礼物订阅标记为过期，返回这些订阅以便收回阅读权限。
+    /// 礼物订阅没有 Stripe 订阅，不会收到到期的 Webhook
+    pub async fn expire_gift_subscriptions(&self) -> Result<Vec<Subscription>> {
+        let mut response = self
+            .db
+            .query(
+                r#"
+                UPDATE subscription SET status = "expired", updated_at = time::now()
+                WHERE gift_id != NONE AND status = "active" AND current_period_end <= time::now()
+                RETURN AFTER
+                "#,
+            )
+            .await?;
+
+        let expired: Vec<Value> = response.take(0)?;
+        expired
+            .into_iter()
+            .map(|s| serde_json::from_value(s).map_err(AppError::from))
+            .collect()
+    }
+
     /// 取消订阅
     pub async fn cancel_subscription(
         &self,
@@ -719,6 +842,290 @@ impl SubscriptionService {
         })
     }
 
+    // ============ 优惠券 ============
+
+    /// 创建订阅优惠券：先在 Stripe 创建 Coupon，再在本地保存一份
+    pub async fn create_coupon(
+        &self,
+        creator_id: &str,
+        request: CreateCouponRequest,
+    ) -> Result<SubscriptionCoupon> {
+        request
+            .validate()
+            .map_err(|e| AppError::Validation(format!("优惠券数据验证失败: {}", e)))?;
+
+        let code = normalize_coupon_code(&request.code)?;
+
+        match (&request.duration, request.duration_in_months) {
+            (CouponDuration::Repeating, None) => {
+                return Err(AppError::BadRequest("按月重复的优惠券需要指定持续月数".to_string()));
+            }
+            (CouponDuration::Once | CouponDuration::Forever, Some(_)) => {
+                return Err(AppError::BadRequest("只有按月重复的优惠券可以指定持续月数".to_string()));
+            }
+            _ => {}
+        }
+
+        if request.discount_type == CouponDiscountType::Percentage && request.discount_value > 100 {
+            return Err(AppError::BadRequest("百分比折扣不能超过100".to_string()));
+        }
+
+        if request.redeem_by.is_some_and(|redeem_by| redeem_by <= Utc::now()) {
+            return Err(AppError::BadRequest("兑换截止时间必须晚于当前时间".to_string()));
+        }
+
+        for plan_id in &request.plan_ids {
+            self.verify_plan_ownership(plan_id, creator_id).await?;
+        }
+
+        // 固定金额优惠券未指定货币时沿用第一个适用计划的货币
+        let currency = match request.discount_type {
+            CouponDiscountType::Percentage => None,
+            CouponDiscountType::Fixed => Some(match (&request.currency, request.plan_ids.first()) {
+                (Some(currency), _) => currency.to_uppercase(),
+                (None, Some(plan_id)) => self.get_subscription_plan(plan_id).await?.currency,
+                (None, None) => "USD".to_string(),
+            }),
+        };
+
+        if self.find_coupon_by_code(creator_id, &code).await?.is_some() {
+            return Err(AppError::Conflict("优惠码已存在".to_string()));
+        }
+
+        let stripe_coupon_id = self
+            .stripe_service
+            .create_coupon(creator_id, &code, &request, currency.as_deref())
+            .await?;
+
+        let query = r#"
+            CREATE subscription_coupon CONTENT {
+                id: $coupon_id,
+                creator_id: $creator_id,
+                code: $code,
+                stripe_coupon_id: $stripe_coupon_id,
+                discount_type: $discount_type,
+                discount_value: $discount_value,
+                currency: $currency,
+                duration: $duration,
+                duration_in_months: $duration_in_months,
+                max_redemptions: $max_redemptions,
+                times_redeemed: 0,
+                plan_ids: $plan_ids,
+                redeem_by: $redeem_by,
+                is_active: true,
+                created_at: time::now(),
+                updated_at: time::now()
+            }
+        "#;
+
+        let mut response = self
+            .db
+            .query_with_params(
+                query,
+                json!({
+                    "coupon_id": format!("subscription_coupon:{}", uuid::Uuid::new_v4()),
+                    "creator_id": creator_id,
+                    "code": code,
+                    "stripe_coupon_id": stripe_coupon_id,
+                    "discount_type": request.discount_type,
+                    "discount_value": request.discount_value,
+                    "currency": currency,
+                    "duration": request.duration,
+                    "duration_in_months": request.duration_in_months,
+                    "max_redemptions": request.max_redemptions,
+                    "plan_ids": request.plan_ids,
+                    "redeem_by": request.redeem_by,
+                }),
+            )
+            .await?;
+
+        let coupons: Vec<Value> = response.take(0)?;
+        let coupon = coupons
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::Internal("Failed to create coupon".to_string()))?;
+
+        let coupon: SubscriptionCoupon = serde_json::from_value(coupon)?;
+        info!("Coupon {} created for creator {}", coupon.code, creator_id);
+        Ok(coupon)
+    }
+
+    /// 创作者的全部优惠券（包括已停用的），按创建时间倒序
+    pub async fn list_coupons(&self, creator_id: &str) -> Result<Vec<SubscriptionCoupon>> {
+        let mut response = self
+            .db
+            .query_with_params(
+                "SELECT * FROM subscription_coupon WHERE creator_id = $creator_id ORDER BY created_at DESC",
+                json!({ "creator_id": creator_id }),
+            )
+            .await?;
+
+        let coupons: Vec<Value> = response.take(0)?;
+        coupons
+            .into_iter()
+            .map(|c| serde_json::from_value(c).map_err(AppError::from))
+            .collect()
+    }
+
+    /// 停用优惠券。已经使用该优惠券的订阅继续享受折扣
+    pub async fn deactivate_coupon(
+        &self,
+        creator_id: &str,
+        coupon_id: &str,
+    ) -> Result<SubscriptionCoupon> {
+        let mut response = self
+            .db
+            .query_with_params(
+                r#"
+                UPDATE type::thing('subscription_coupon', $coupon_id)
+                SET is_active = false, updated_at = time::now()
+                WHERE creator_id = $creator_id
+                RETURN AFTER
+                "#,
+                json!({
+                    "coupon_id": bare_id("subscription_coupon", coupon_id),
+                    "creator_id": creator_id,
+                }),
+            )
+            .await?;
+
+        let coupons: Vec<Value> = response.take(0)?;
+        let coupon = coupons
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::NotFound("优惠券不存在".to_string()))?;
+        let coupon: SubscriptionCoupon = serde_json::from_value(coupon)?;
+
+        if let Err(e) = self.stripe_service.delete_coupon(&coupon.stripe_coupon_id).await {
+            warn!("Failed to delete Stripe coupon {}: {}", coupon.stripe_coupon_id, e);
+        }
+
+        info!("Coupon {} deactivated by creator {}", coupon.code, creator_id);
+        Ok(coupon)
+    }
+
+    /// 结账前预览优惠码对计划价格的影响
+    pub async fn preview_coupon(&self, plan_id: &str, code: &str) -> Result<CouponPreview> {
+        let plan = self.get_subscription_plan(plan_id).await?;
+        if !plan.is_active {
+            return Err(AppError::BadRequest("订阅计划已停用".to_string()));
+        }
+
+        let coupon = self.find_redeemable_coupon(&plan, code).await?;
+
+        Ok(CouponPreview {
+            code: coupon.code.clone(),
+            plan_id: plan.id.clone(),
+            original_price: plan.price,
+            discounted_price: coupon.discounted_price(plan.price),
+            currency: plan.currency,
+            duration: coupon.duration,
+            duration_in_months: coupon.duration_in_months,
+        })
+    }
+
+    async fn find_coupon_by_code(
+        &self,
+        creator_id: &str,
+        code: &str,
+    ) -> Result<Option<SubscriptionCoupon>> {
+        let mut response = self
+            .db
+            .query_with_params(
+                "SELECT * FROM subscription_coupon WHERE creator_id = $creator_id AND code = $code LIMIT 1",
+                json!({
+                    "creator_id": creator_id,
+                    "code": code,
+                }),
+            )
+            .await?;
+
+        let coupons: Vec<Value> = response.take(0)?;
+        coupons
+            .into_iter()
+            .next()
+            .map(|c| serde_json::from_value(c).map_err(AppError::from))
+            .transpose()
+    }
+
+    /// 查找可以用于该计划的优惠券，不可用时返回具体原因
+    async fn find_redeemable_coupon(
+        &self,
+        plan: &SubscriptionPlan,
+        code: &str,
+    ) -> Result<SubscriptionCoupon> {
+        let code = normalize_coupon_code(code)?;
+        let coupon = self
+            .find_coupon_by_code(&plan.creator_id, &code)
+            .await?
+            .filter(|c| c.is_active)
+            .ok_or_else(|| AppError::NotFound("优惠码无效".to_string()))?;
+
+        if coupon.redeem_by.is_some_and(|redeem_by| redeem_by <= Utc::now()) {
+            return Err(AppError::BadRequest("优惠码已过期".to_string()));
+        }
+
+        if coupon
+            .max_redemptions
+            .is_some_and(|max| coupon.times_redeemed >= max)
+        {
+            return Err(AppError::BadRequest("优惠码已达到使用上限".to_string()));
+        }
+
+        if !coupon.plan_ids.is_empty() && !coupon.plan_ids.contains(&plan.id) {
+            return Err(AppError::BadRequest("优惠码不适用于该订阅计划".to_string()));
+        }
+
+        if coupon
+            .currency
+            .as_deref()
+            .is_some_and(|currency| !currency.eq_ignore_ascii_case(&plan.currency))
+        {
+            return Err(AppError::BadRequest("优惠码的货币与订阅计划不一致".to_string()));
+        }
+
+        Ok(coupon)
+    }
+
+    async fn record_coupon_redemption(
+        &self,
+        coupon: &SubscriptionCoupon,
+        plan: &SubscriptionPlan,
+        subscription_id: &str,
+        subscriber_id: &str,
+    ) -> Result<()> {
+        let query = r#"
+            UPDATE type::thing($coupon_id) SET times_redeemed += 1, updated_at = time::now();
+            CREATE coupon_redemption CONTENT {
+                coupon_id: $coupon_id,
+                creator_id: $creator_id,
+                plan_id: $plan_id,
+                subscription_id: $subscription_id,
+                subscriber_id: $subscriber_id,
+                discount_amount: $discount_amount,
+                currency: $currency,
+                created_at: time::now()
+            };
+        "#;
+
+        self.db
+            .query_with_params(
+                query,
+                json!({
+                    "coupon_id": coupon.id,
+                    "creator_id": plan.creator_id,
+                    "plan_id": plan.id,
+                    "subscription_id": subscription_id,
+                    "subscriber_id": subscriber_id,
+                    "discount_amount": plan.price - coupon.discounted_price(plan.price),
+                    "currency": plan.currency,
+                }),
+            )
+            .await?;
+
+        Ok(())
+    }
+
     // 私有辅助方法
     async fn verify_creator_exists(&self, creator_id: &str) -> Result<()> {
         let query = "SELECT id FROM user_profile WHERE user_id = $creator_id";
@@ -1002,3 +1409,16 @@ impl SubscriptionService {
         }
     }
 }
+
+/// 优惠码统一为大写，只允许字母、数字、`-` 和 `_`
+fn normalize_coupon_code(code: &str) -> Result<String> {
+    let code = code.trim().to_uppercase();
+    if code.is_empty()
+        || !code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(AppError::BadRequest("优惠码只能包含字母、数字、-和_".to_string()));
+    }
+    Ok(code)
+}
@@ -0,0 +1,164 @@
+use crate::{
+    error::Result,
+    models::search::*,
+    services::Database,
+    utils::suggest::{popularity, PrefixIndex},
+};
+use parking_lot::RwLock;
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::info;
+
+/// 每类条目最多载入索引的数量，按热度取前面的
+const MAX_ARTICLES: usize = 5000;
+const MAX_OTHERS: usize = 2000;
+
+pub const DEFAULT_SUGGEST_LIMIT: usize = 8;
+pub const MAX_SUGGEST_LIMIT: usize = 20;
+
+#[derive(Debug, Deserialize)]
+struct ArticleRow {
+    id: String,
+    title: String,
+    slug: String,
+    score: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct NamedRow {
+    id: String,
+    name: String,
+    slug: String,
+    score: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthorRow {
+    user_id: String,
+    username: String,
+    display_name: String,
+    avatar_url: Option<String>,
+    score: f64,
+}
+
+/// 搜索框联想：文章、标签、作者和出版物的前缀索引常驻内存，
+/// 输入时只查内存，索引由后台任务定期重建
+#[derive(Clone)]
+pub struct SuggestService {
+    db: Arc<Database>,
+    index: Arc<RwLock<Arc<PrefixIndex<SearchSuggestion>>>>,
+}
+
+impl SuggestService {
+    pub async fn new(db: Arc<Database>) -> Result<Self> {
+        Ok(Self {
+            db,
+            index: Arc::new(RwLock::new(Arc::new(PrefixIndex::default()))),
+        })
+    }
+
+    /// 按前缀返回联想，索引尚未建立时返回空列表
+    pub fn suggest(&self, query: &str, limit: Option<usize>) -> Vec<SearchSuggestion> {
+        let limit = limit.unwrap_or(DEFAULT_SUGGEST_LIMIT).clamp(1, MAX_SUGGEST_LIMIT);
+        let index = self.index.read().clone();
+        index.lookup(query, limit).into_iter().cloned().collect()
+    }
+
+    /// 从数据库重新载入所有条目，建好后整体替换旧索引
+    pub async fn rebuild(&self) -> Result<()> {
+        let started = Instant::now();
+        let mut items = Vec::new();
+
+        let articles: Vec<ArticleRow> = self
+            .db
+            .prepare(
+                "SELECT meta::id(id) AS id, title, slug, weighted_clap_score + view_count / 10 AS score
+                 FROM article
+                 WHERE status = 'published' AND is_deleted = false
+                 ORDER BY score DESC LIMIT $limit",
+            )
+            .bind("limit", MAX_ARTICLES)
+            .fetch()
+            .await?;
+        for row in articles {
+            let suggestion = SearchSuggestion {
+                text: row.title.clone(),
+                suggestion_type: SuggestionType::Article,
+                metadata: Some(json!({ "id": row.id, "slug": row.slug })),
+            };
+            items.push((row.title, suggestion, popularity(row.score)));
+        }
+
+        let tags: Vec<NamedRow> = self
+            .db
+            .prepare(
+                "SELECT meta::id(id) AS id, name, slug, article_count + follower_count AS score
+                 FROM tag ORDER BY score DESC LIMIT $limit",
+            )
+            .bind("limit", MAX_OTHERS)
+            .fetch()
+            .await?;
+        for row in tags {
+            let suggestion = SearchSuggestion {
+                text: row.name.clone(),
+                suggestion_type: SuggestionType::Tag,
+                metadata: Some(json!({ "id": row.id, "slug": row.slug })),
+            };
+            items.push((row.name, suggestion, popularity(row.score)));
+        }
+
+        let authors: Vec<AuthorRow> = self
+            .db
+            .prepare(
+                "SELECT user_id, username, display_name, avatar_url, follower_count AS score
+                 FROM user_profile
+                 WHERE is_suspended = false AND deactivated_at IS NONE AND article_count > 0
+                 ORDER BY score DESC LIMIT $limit",
+            )
+            .bind("limit", MAX_OTHERS)
+            .fetch()
+            .await?;
+        for row in authors {
+            let metadata = json!({
+                "user_id": row.user_id,
+                "username": row.username,
+                "avatar_url": row.avatar_url,
+            });
+            // 显示名和用户名都能匹配到作者
+            let text = format!("{} {}", row.display_name, row.username);
+            let suggestion = SearchSuggestion {
+                text: row.display_name,
+                suggestion_type: SuggestionType::User,
+                metadata: Some(metadata),
+            };
+            items.push((text, suggestion, popularity(row.score)));
+        }
+
+        let publications: Vec<NamedRow> = self
+            .db
+            .prepare(
+                "SELECT meta::id(id) AS id, name, slug, follower_count AS score
+                 FROM publication WHERE is_suspended = false
+                 ORDER BY score DESC LIMIT $limit",
+            )
+            .bind("limit", MAX_OTHERS)
+            .fetch()
+            .await?;
+        for row in publications {
+            let suggestion = SearchSuggestion {
+                text: row.name.clone(),
+                suggestion_type: SuggestionType::Publication,
+                metadata: Some(json!({ "id": row.id, "slug": row.slug })),
+            };
+            items.push((row.name, suggestion, popularity(row.score)));
+        }
+
+        let index = PrefixIndex::build(items);
+        info!("Rebuilt search suggest index with {} entries in {:?}", index.len(), started.elapsed());
+        *self.index.write() = Arc::new(index);
+
+        Ok(())
+    }
+}
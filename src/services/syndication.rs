@@ -0,0 +1,553 @@
+use crate::{
+    error::{AppError, Result},
+    models::{
+        article::Article,
+        id::{bare_id, ArticleId, UserId},
+        plugin::ArticlePublishedEvent,
+        syndication::*,
+    },
+    services::{plugin::Plugin, Database, WebmentionService},
+    utils::syndication::SyndicationContent,
+};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+use validator::Validate;
+
+const DEV_TO_API_URL: &str = "https://dev.to/api/articles";
+const HASHNODE_API_URL: &str = "https://gql.hashnode.com";
+const TWITTER_API_URL: &str = "https://api.twitter.com/2/tweets";
+const TWITTER_MEDIA_UPLOAD_URL: &str = "https://api.x.com/2/media/upload";
+/// 推广帖子图片的最大大小（Twitter 图片上限为 5MB）
+const MAX_SHARE_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+/// 外部平台返回的错误信息最多保存的长度
+const MAX_ERROR_CHARS: usize = 500;
+
+const HASHNODE_PUBLISH_MUTATION: &str = r#"
+mutation PublishPost($input: PublishPostInput!) {
+  publishPost(input: $input) {
+    post { id url }
+  }
+}
+"#;
+
+/// 把文章同步到作者连接的外部平台（Dev.to、Hashnode、Twitter/X 推文串），
+/// 同步的内容都带有指向原文的 canonical 链接
+#[derive(Clone)]
+pub struct OutboundSyndicationService {
+    db: Arc<Database>,
+    webmention_service: WebmentionService,
+    http_client: Client,
+}
+
+impl OutboundSyndicationService {
+    pub async fn new(db: Arc<Database>, webmention_service: WebmentionService) -> Result<Self> {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(20))
+            .user_agent("Rainbow-Blog Syndication")
+            .build()
+            .map_err(|e| AppError::Internal(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self {
+            db,
+            webmention_service,
+            http_client,
+        })
+    }
+
+    pub async fn list_accounts(&self, user_id: &str) -> Result<Vec<SyndicationAccount>> {
+        self.db
+            .prepare("SELECT * FROM syndication_account WHERE user_id = $user_id ORDER BY created_at ASC")
+            .bind("user_id", UserId::new(user_id).as_str())
+            .fetch()
+            .await
+    }
+
+    /// 连接外部账号，每个平台一个账号，重复连接时替换原来的凭据
+    pub async fn connect_account(
+        &self,
+        user_id: &str,
+        request: ConnectSyndicationAccountRequest,
+    ) -> Result<SyndicationAccount> {
+        request.validate().map_err(AppError::ValidatorError)?;
+        if request.platform == SyndicationPlatform::Hashnode && request.hashnode_publication_id.is_none() {
+            return Err(AppError::BadRequest("hashnode_publication_id is required for Hashnode".to_string()));
+        }
+
+        let user_id = UserId::new(user_id);
+        let account_id = format!("{}_{}", user_id.as_str(), request.platform.as_str());
+        let account: Option<SyndicationAccount> = self.db
+            .prepare(
+                r#"
+                UPSERT type::thing('syndication_account', $account_id) MERGE {
+                    user_id: $user_id,
+                    platform: $platform,
+                    account_name: $account_name,
+                    access_token: $access_token,
+                    hashnode_publication_id: $hashnode_publication_id,
+                    auto_post: $auto_post,
+                    created_at: created_at ?? time::now(),
+                    updated_at: time::now()
+                }
+                "#,
+            )
+            .bind("account_id", &account_id)
+            .bind("user_id", user_id.as_str())
+            .bind("platform", request.platform.as_str())
+            .bind("account_name", &request.account_name)
+            .bind("access_token", request.access_token.trim())
+            .bind("hashnode_publication_id", &request.hashnode_publication_id)
+            .bind("auto_post", request.auto_post)
+            .fetch_one()
+            .await?;
+
+        info!("User {} connected {} for syndication", user_id, request.platform.as_str());
+        account.ok_or_else(|| AppError::Internal("Failed to connect account".to_string()))
+    }
+
+    pub async fn update_account(
+        &self,
+        user_id: &str,
+        account_id: &str,
+        request: UpdateSyndicationAccountRequest,
+    ) -> Result<SyndicationAccount> {
+        request.validate().map_err(AppError::ValidatorError)?;
+        let account = self.get_account(user_id, account_id).await?;
+
+        let updated: Option<SyndicationAccount> = self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('syndication_account', $account_id) SET
+                    auto_post = $auto_post,
+                    access_token = $access_token,
+                    hashnode_publication_id = $hashnode_publication_id,
+                    updated_at = time::now()
+                "#,
+            )
+            .bind("account_id", account_key(&account))
+            .bind("auto_post", request.auto_post.unwrap_or(account.auto_post))
+            .bind("access_token", request.access_token.as_deref().map(str::trim).unwrap_or(&account.access_token))
+            .bind("hashnode_publication_id", request.hashnode_publication_id.or(account.hashnode_publication_id))
+            .fetch_one()
+            .await?;
+
+        updated.ok_or_else(|| AppError::Internal("Failed to update account".to_string()))
+    }
+
+    /// 断开账号，已同步的帖子记录保留
+    pub async fn disconnect_account(&self, user_id: &str, account_id: &str) -> Result<()> {
+        let account = self.get_account(user_id, account_id).await?;
+        self.db
+            .prepare("DELETE type::thing('syndication_account', $account_id)")
+            .bind("account_id", account_key(&account))
+            .execute()
+            .await?;
+
+        info!("User {} disconnected {}", account.user_id, account.platform.as_str());
+        Ok(())
+    }
+
+    pub async fn get_account(&self, user_id: &str, account_id: &str) -> Result<SyndicationAccount> {
+        let account: Option<SyndicationAccount> = self.db
+            .get_by_id("syndication_account", bare_id("syndication_account", account_id))
+            .await?;
+        account
+            .filter(|a| a.user_id == UserId::new(user_id).as_str())
+            .ok_or_else(|| AppError::not_found("Syndication account"))
+    }
+
+    /// 文章在各外部平台上的同步状态
+    pub async fn list_for_article(&self, user_id: &str, article_id: &str) -> Result<Vec<SyndicatedPost>> {
+        let article = self.get_own_article(user_id, article_id).await?;
+        self.db
+            .prepare("SELECT * FROM syndicated_post WHERE article_id = $article_id ORDER BY created_at ASC")
+            .bind("article_id", ArticleId::new(&article.id).as_str())
+            .fetch()
+            .await
+    }
+
+    /// 手动同步（或重试失败的同步），已经同步成功的账号会跳过
+    pub async fn syndicate(
+        &self,
+        user_id: &str,
+        article_id: &str,
+        request: SyndicateArticleRequest,
+    ) -> Result<Vec<SyndicatedPost>> {
+        let article = self.get_own_article(user_id, article_id).await?;
+        if !article.is_published() {
+            return Err(AppError::BadRequest("Only published articles can be cross-posted".to_string()));
+        }
+        if article.is_paid_content {
+            return Err(AppError::BadRequest("Paid articles cannot be cross-posted".to_string()));
+        }
+
+        let accounts: Vec<SyndicationAccount> = self
+            .list_accounts(user_id)
+            .await?
+            .into_iter()
+            .filter(|a| request.account_ids.is_empty() || request.account_ids.iter().any(|id| bare_id("syndication_account", id) == account_key(a)))
+            .collect();
+        if accounts.is_empty() {
+            return Err(AppError::BadRequest("No connected accounts to cross-post to".to_string()));
+        }
+
+        self.syndicate_to(&article, &accounts).await
+    }
+
+    /// 文章发布时同步到开启了自动同步的账号
+    pub async fn auto_syndicate(&self, article_id: &str) -> Result<usize> {
+        let article: Article = self.db
+            .get_by_id("article", ArticleId::new(article_id).as_str())
+            .await?
+            .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
+        if !article.is_published() || article.is_paid_content {
+            return Ok(0);
+        }
+
+        let accounts: Vec<SyndicationAccount> = self
+            .list_accounts(&article.author_id)
+            .await?
+            .into_iter()
+            .filter(|a| a.auto_post)
+            .collect();
+        if accounts.is_empty() {
+            return Ok(0);
+        }
+
+        let posts = self.syndicate_to(&article, &accounts).await?;
+        Ok(posts.iter().filter(|p| p.status == SyndicationStatus::Published).count())
+    }
+
+    async fn syndicate_to(&self, article: &Article, accounts: &[SyndicationAccount]) -> Result<Vec<SyndicatedPost>> {
+        let content = self.build_content(article).await?;
+        let article_id = ArticleId::new(&article.id);
+
+        let mut posts = Vec::new();
+        for account in accounts {
+            let post_id = format!("{}_{}", article_id.as_str(), account_key(account));
+            let existing: Option<SyndicatedPost> = self.db.get_by_id("syndicated_post", &post_id).await?;
+            if let Some(post) = existing.filter(|p| p.status != SyndicationStatus::Failed) {
+                // 已经同步过（或正在同步），重新发布文章不会重复发帖
+                posts.push(post);
+                continue;
+            }
+
+            self.set_status(&post_id, article_id.as_str(), account, SyndicationStatus::Pending, None, None)
+                .await?;
+            let result = self.publish(account, &content).await;
+            let post = match result {
+                Ok(external) => {
+                    info!("Cross-posted article {} to {}: {}", article_id, account.platform.as_str(), external.url);
+                    self.set_status(&post_id, article_id.as_str(), account, SyndicationStatus::Published, Some(external), None)
+                        .await?
+                }
+                Err(e) => {
+                    warn!("Failed to cross-post article {} to {}: {}", article_id, account.platform.as_str(), e);
+                    let error: String = e.to_string().chars().take(MAX_ERROR_CHARS).collect();
+                    self.set_status(&post_id, article_id.as_str(), account, SyndicationStatus::Failed, None, Some(error))
+                        .await?
+                }
+            };
+            posts.push(post);
+        }
+
+        Ok(posts)
+    }
+
+    async fn build_content(&self, article: &Article) -> Result<SyndicationContent> {
+        let tags: Vec<String> = self.db
+            .prepare("SELECT VALUE tag_id.name FROM article_tag WHERE article_id = type::thing('article', $article_id)")
+            .bind("article_id", ArticleId::new(&article.id).as_str())
+            .fetch()
+            .await?;
+
+        Ok(SyndicationContent {
+            title: article.title.clone(),
+            subtitle: article.subtitle.clone(),
+            markdown: article.content.clone(),
+            excerpt: article.excerpt.clone(),
+            cover_image_url: article.cover_image_url.clone(),
+            canonical_url: self.webmention_service.article_url(article).await?,
+            tags,
+        })
+    }
+
+    async fn set_status(
+        &self,
+        post_id: &str,
+        article_id: &str,
+        account: &SyndicationAccount,
+        status: SyndicationStatus,
+        external: Option<ExternalPost>,
+        error: Option<String>,
+    ) -> Result<SyndicatedPost> {
+        let post: Option<SyndicatedPost> = self.db
+            .prepare(
+                r#"
+                UPSERT type::thing('syndicated_post', $post_id) MERGE {
+                    article_id: $article_id,
+                    account_id: $account_id,
+                    user_id: $user_id,
+                    platform: $platform,
+                    status: $status,
+                    external_id: $external_id,
+                    external_url: $external_url,
+                    error: $error,
+                    attempts: IF $status = 'pending' THEN (attempts ?? 0) + 1 ELSE attempts END,
+                    created_at: created_at ?? time::now(),
+                    updated_at: time::now()
+                }
+                "#,
+            )
+            .bind("post_id", post_id)
+            .bind("article_id", article_id)
+            .bind("account_id", account_key(account))
+            .bind("user_id", &account.user_id)
+            .bind("platform", account.platform.as_str())
+            .bind("status", status.as_str())
+            .bind("external_id", external.as_ref().map(|p| p.id.clone()))
+            .bind("external_url", external.as_ref().map(|p| p.url.clone()))
+            .bind("error", error)
+            .fetch_one()
+            .await?;
+
+        post.ok_or_else(|| AppError::Internal("Failed to record syndication status".to_string()))
+    }
+
+    async fn get_own_article(&self, user_id: &str, article_id: &str) -> Result<Article> {
+        let article: Article = self.db
+            .get_by_id("article", ArticleId::new(article_id).as_str())
+            .await?
+            .ok_or_else(|| AppError::not_found("Article"))?;
+        if UserId::new(&article.author_id) != UserId::new(user_id) {
+            return Err(AppError::forbidden("Only the author can cross-post this article"));
+        }
+        Ok(article)
+    }
+
+    async fn publish(&self, account: &SyndicationAccount, content: &SyndicationContent) -> Result<ExternalPost> {
+        match account.platform {
+            SyndicationPlatform::DevTo => self.publish_dev_to(account, content).await,
+            SyndicationPlatform::Hashnode => self.publish_hashnode(account, content).await,
+            SyndicationPlatform::Twitter => self.publish_thread(account, content).await,
+        }
+    }
+
+    async fn publish_dev_to(&self, account: &SyndicationAccount, content: &SyndicationContent) -> Result<ExternalPost> {
+        let body = json!({
+            "article": {
+                "title": content.title,
+                "body_markdown": content.markdown_with_backlink(),
+                "published": true,
+                "canonical_url": content.canonical_url,
+                "description": content.excerpt.as_ref().or(content.subtitle.as_ref()),
+                "main_image": content.cover_image_url,
+                "tags": content.dev_to_tags(),
+            }
+        });
+
+        let response = self
+            .send(self.http_client.post(DEV_TO_API_URL).header("api-key", &account.access_token).json(&body))
+            .await?;
+        Ok(ExternalPost {
+            id: response.get("id").map(|id| id.to_string()).unwrap_or_default(),
+            url: string_field(&response, "url")?,
+        })
+    }
+
+    async fn publish_hashnode(&self, account: &SyndicationAccount, content: &SyndicationContent) -> Result<ExternalPost> {
+        let publication_id = account
+            .hashnode_publication_id
+            .as_deref()
+            .ok_or_else(|| AppError::BadRequest("Hashnode publication ID is not set".to_string()))?;
+        let tags: Vec<Value> = content
+            .hashnode_tags()
+            .into_iter()
+            .map(|(slug, name)| json!({ "slug": slug, "name": name }))
+            .collect();
+        let mut input = json!({
+            "title": content.title,
+            "subtitle": content.subtitle,
+            "contentMarkdown": content.markdown_with_backlink(),
+            "publicationId": publication_id,
+            "originalArticleURL": content.canonical_url,
+            "tags": tags,
+        });
+        if let Some(cover) = &content.cover_image_url {
+            input["coverImageOptions"] = json!({ "coverImageURL": cover });
+        }
+
+        let response = self
+            .send(
+                self.http_client
+                    .post(HASHNODE_API_URL)
+                    .header("Authorization", &account.access_token)
+                    .json(&json!({ "query": HASHNODE_PUBLISH_MUTATION, "variables": { "input": input } })),
+            )
+            .await?;
+        // GraphQL 错误以 200 返回
+        if let Some(error) = response.pointer("/errors/0/message").and_then(|m| m.as_str()) {
+            return Err(AppError::ExternalService(format!("Hashnode: {}", error)));
+        }
+        let post = response
+            .pointer("/data/publishPost/post")
+            .ok_or_else(|| AppError::ExternalService("Hashnode returned no post".to_string()))?;
+        Ok(ExternalPost {
+            id: string_field(post, "id")?,
+            url: string_field(post, "url")?,
+        })
+    }
+
+    /// 依次发推，每条回复上一条；中途失败时已发出的推文保留，记录第一条
+    async fn publish_thread(&self, account: &SyndicationAccount, content: &SyndicationContent) -> Result<ExternalPost> {
+        let mut first_id: Option<String> = None;
+        let mut previous_id: Option<String> = None;
+
+        for (index, text) in content.thread().iter().enumerate() {
+            let mut body = json!({ "text": text });
+            if let Some(previous) = &previous_id {
+                body["reply"] = json!({ "in_reply_to_tweet_id": previous });
+            }
+
+            let response = self
+                .send(self.http_client.post(TWITTER_API_URL).bearer_auth(&account.access_token).json(&body))
+                .await
+                .map_err(|e| match &first_id {
+                    Some(first) => AppError::ExternalService(format!("Thread stopped at tweet {} (first tweet {}): {}", index + 1, first, e)),
+                    None => e,
+                })?;
+            let id = response
+                .pointer("/data/id")
+                .and_then(|id| id.as_str())
+                .ok_or_else(|| AppError::ExternalService("Twitter returned no tweet ID".to_string()))?
+                .to_string();
+            debug!("Posted tweet {} of thread for {}", index + 1, content.canonical_url);
+
+            first_id.get_or_insert_with(|| id.clone());
+            previous_id = Some(id);
+        }
+
+        let id = first_id.ok_or_else(|| AppError::Internal("Empty thread".to_string()))?;
+        Ok(ExternalPost {
+            url: format!("https://x.com/i/web/status/{}", id),
+            id,
+        })
+    }
+
+    /// 在社交账号上发布一条推广帖子，可附带一张图片
+    pub async fn post_share(&self, account: &SyndicationAccount, text: &str, image_url: Option<&str>) -> Result<ExternalPost> {
+        if !account.platform.supports_social_posts() {
+            return Err(AppError::BadRequest(format!("{} does not support social posts", account.platform.as_str())));
+        }
+
+        let mut body = json!({ "text": text });
+        if let Some(image_url) = image_url {
+            let media_id = self.upload_tweet_image(account, image_url).await?;
+            body["media"] = json!({ "media_ids": [media_id] });
+        }
+
+        let response = self
+            .send(self.http_client.post(TWITTER_API_URL).bearer_auth(&account.access_token).json(&body))
+            .await?;
+        let id = response
+            .pointer("/data/id")
+            .and_then(|id| id.as_str())
+            .ok_or_else(|| AppError::ExternalService("Twitter returned no tweet ID".to_string()))?
+            .to_string();
+
+        Ok(ExternalPost {
+            url: format!("https://x.com/i/web/status/{}", id),
+            id,
+        })
+    }
+
+    async fn upload_tweet_image(&self, account: &SyndicationAccount, image_url: &str) -> Result<String> {
+        let image = self.http_client
+            .get(image_url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| AppError::ExternalService(format!("Failed to download image: {}", e)))?;
+        let content_type = image
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("image/jpeg")
+            .to_string();
+        let bytes = image
+            .bytes()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("Failed to download image: {}", e)))?;
+        if bytes.len() > MAX_SHARE_IMAGE_BYTES {
+            return Err(AppError::BadRequest("Image is larger than 5MB".to_string()));
+        }
+
+        let part = reqwest::multipart::Part::bytes(bytes.to_vec())
+            .file_name("image")
+            .mime_str(&content_type)
+            .map_err(|e| AppError::BadRequest(format!("Invalid image type: {}", e)))?;
+        let form = reqwest::multipart::Form::new()
+            .text("media_category", "tweet_image")
+            .part("media", part);
+
+        let response = self
+            .send(self.http_client.post(TWITTER_MEDIA_UPLOAD_URL).bearer_auth(&account.access_token).multipart(form))
+            .await?;
+        response
+            .pointer("/data/id")
+            .and_then(|id| id.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| AppError::ExternalService("Twitter returned no media ID".to_string()))
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<Value> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("Request failed: {}", e)))?;
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("Failed to read response: {}", e)))?;
+        if !status.is_success() {
+            return Err(AppError::ExternalService(format!("HTTP {}: {}", status, body)));
+        }
+        serde_json::from_str(&body)
+            .map_err(|e| AppError::ExternalService(format!("Invalid response: {}", e)))
+    }
+}
+
+fn account_key(account: &SyndicationAccount) -> &str {
+    bare_id("syndication_account", &account.id)
+}
+
+fn string_field(value: &Value, field: &str) -> Result<String> {
+    value
+        .get(field)
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .ok_or_else(|| AppError::ExternalService(format!("Response is missing {}", field)))
+}
+
+/// 文章发布时在后台自动同步，不阻塞其他插件
+#[async_trait]
+impl Plugin for OutboundSyndicationService {
+    fn name(&self) -> &str {
+        "syndication"
+    }
+
+    async fn on_article_published(&self, event: &ArticlePublishedEvent) -> Result<()> {
+        let service = self.clone();
+        let article_id = event.article_id.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.auto_syndicate(&article_id).await {
+                warn!("Failed to cross-post article {}: {}", article_id, e);
+            }
+        });
+        Ok(())
+    }
+}
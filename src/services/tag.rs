@@ -5,6 +5,7 @@ use crate::{
     utils::slug,
 };
 use chrono::Utc;
+use serde::Deserialize;
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -12,6 +13,14 @@ use tracing::{debug, info};
 use validator::Validate;
 use uuid::Uuid;
 
+/// 标签层级的最大深度（根标签为第 1 层）
+const MAX_TAG_DEPTH: usize = 4;
+
+#[derive(Debug, Deserialize)]
+struct CountRow {
+    total: i64,
+}
+
 #[derive(Clone)]
 pub struct TagService {
     db: Arc<Database>,
@@ -29,14 +38,15 @@ impl TagService {
             .validate()
             .map_err(|e| AppError::ValidatorError(e))?;
 
-        // Check if tag name already exists
+        // Check if tag name already exists, either as a tag or as another tag's alias
         let mut response = self.db.query_with_params(
             r#"
                 SELECT * FROM tag 
-                WHERE name = $name
+                WHERE name = $name OR $slug INSIDE aliases
             "#,
             json!({
-                "name": &request.name
+                "name": &request.name,
+                "slug": slug::generate_slug(&request.name)
             })
         ).await?;
         let existing: Vec<Tag> = response.take(0)?;
@@ -55,6 +65,8 @@ impl TagService {
             follower_count: 0,
             article_count: 0,
             is_featured: false,
+            parent_id: None,
+            aliases: vec![],
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -72,7 +84,7 @@ impl TagService {
         let limit = query.limit.unwrap_or(20).min(100);
         let offset = (page - 1) * limit;
         let mut sql = String::from(
-            "SELECT id, name, slug, description, follower_count, article_count, is_featured, created_at, updated_at FROM tag"
+            "SELECT id, name, slug, description, follower_count, article_count, is_featured, parent_id, aliases, created_at, updated_at FROM tag"
         );
         let mut conditions: Vec<String> = Vec::new();
         let mut params = serde_json::Map::new();
@@ -124,10 +136,11 @@ impl TagService {
         Ok(tag)
     }
 
+    /// 按 slug 查找标签，别名返回它指向的标签
     pub async fn get_tag_by_slug(&self, slug: &str) -> Result<Option<Tag>> {
         let sql = r#"
-            SELECT id, name, slug, description, follower_count, article_count, is_featured, created_at, updated_at
-            FROM tag WHERE slug = $slug LIMIT 1
+            SELECT id, name, slug, description, follower_count, article_count, is_featured, parent_id, aliases, created_at, updated_at
+            FROM tag WHERE slug = $slug OR $slug INSIDE aliases LIMIT 1
         "#;
         let mut response = self.db.query_with_params(sql, json!({"slug": slug})).await?;
         let mut tags: Vec<Tag> = response.take(0)?;
@@ -156,9 +169,10 @@ impl TagService {
         if let Some(ref new_name) = request.name {
             if new_name != &tag.name {
                 let mut response = self.db.query_with_params(
-                    "SELECT * FROM tag WHERE name = $name AND id != $id",
+                    "SELECT * FROM tag WHERE (name = $name OR $slug INSIDE aliases) AND id != $id",
                     json!({
                         "name": new_name,
+                        "slug": slug::generate_slug(new_name),
                         "id": tag_id
                     })
                 ).await?;
@@ -216,6 +230,12 @@ impl TagService {
             json!({ "tag_id": tag_id })
         ).await?;
 
+        // Child tags move up to the root
+        self.db.query_with_params(
+            "UPDATE tag SET parent_id = NONE WHERE parent_id = $tag_id",
+            json!({ "tag_id": normalize_surreal_id(tag_id) })
+        ).await?;
+
         // Delete the tag
         self.db.delete_by_id("tag", tag_id).await?;
         
@@ -223,6 +243,221 @@ impl TagService {
         Ok(())
     }
 
+    /// 直接下级标签
+    pub async fn get_child_tags(&self, tag_id: &str) -> Result<Vec<Tag>> {
+        self.db
+            .prepare("SELECT * FROM tag WHERE parent_id = $tag_id ORDER BY name ASC")
+            .bind("tag_id", normalize_surreal_id(tag_id))
+            .fetch()
+            .await
+    }
+
+    /// 设置或清除上级标签，不允许形成环，层级不超过 `MAX_TAG_DEPTH`
+    pub async fn set_parent(&self, tag_id: &str, parent_id: Option<&str>) -> Result<Tag> {
+        let tag_id = normalize_surreal_id(tag_id);
+        self.get_tag_by_id(&tag_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Tag"))?;
+
+        let parent_id = parent_id.map(normalize_surreal_id);
+        if let Some(parent_id) = &parent_id {
+            if *parent_id == tag_id {
+                return Err(AppError::bad_request("A tag cannot be its own parent"));
+            }
+            let ancestors = self.ancestor_ids(parent_id).await?;
+            if ancestors.contains(&tag_id) {
+                return Err(AppError::bad_request("The parent tag is already below this tag"));
+            }
+            if ancestors.len() + self.subtree_height(&tag_id).await? > MAX_TAG_DEPTH {
+                return Err(AppError::bad_request(&format!(
+                    "Tag hierarchy cannot be deeper than {} levels",
+                    MAX_TAG_DEPTH
+                )));
+            }
+        }
+
+        let updated: Option<Tag> = self.db
+            .prepare("UPDATE type::thing('tag', $tag_id) SET parent_id = $parent_id ?? NONE, updated_at = time::now()")
+            .bind("tag_id", &tag_id)
+            .bind("parent_id", &parent_id)
+            .fetch_one()
+            .await?;
+
+        info!("Set parent of tag {} to {:?}", tag_id, parent_id);
+        updated.ok_or_else(|| AppError::internal("Failed to update tag"))
+    }
+
+    /// 添加别名，别名不能和其他标签的 slug 或别名重复
+    pub async fn add_alias(&self, tag_id: &str, request: TagAliasRequest) -> Result<Tag> {
+        request
+            .validate()
+            .map_err(|e| AppError::ValidatorError(e))?;
+
+        let tag_id = normalize_surreal_id(tag_id);
+        let alias = slug::generate_slug(&request.alias);
+        if alias.is_empty() {
+            return Err(AppError::bad_request("Alias must contain letters or digits"));
+        }
+
+        let existing: Option<Tag> = self.db
+            .prepare("SELECT * FROM tag WHERE slug = $alias OR $alias INSIDE aliases LIMIT 1")
+            .bind("alias", &alias)
+            .fetch_one()
+            .await?;
+        if let Some(existing) = existing {
+            if normalize_surreal_id(&existing.id) != tag_id {
+                return Err(AppError::Conflict(format!("'{}' is already used by tag '{}'", alias, existing.name)));
+            }
+            if existing.slug == alias {
+                return Err(AppError::bad_request("Alias is the same as the tag's slug"));
+            }
+            return Ok(existing);
+        }
+
+        let updated: Option<Tag> = self.db
+            .prepare("UPDATE type::thing('tag', $tag_id) SET aliases = array::union(aliases, [$alias]), updated_at = time::now()")
+            .bind("tag_id", &tag_id)
+            .bind("alias", &alias)
+            .fetch_one()
+            .await?;
+
+        updated.ok_or_else(|| AppError::not_found("Tag"))
+    }
+
+    pub async fn remove_alias(&self, tag_id: &str, alias: &str) -> Result<Tag> {
+        let updated: Option<Tag> = self.db
+            .prepare("UPDATE type::thing('tag', $tag_id) SET aliases = array::complement(aliases, [$alias]), updated_at = time::now()")
+            .bind("tag_id", normalize_surreal_id(tag_id))
+            .bind("alias", slug::generate_slug(alias))
+            .fetch_one()
+            .await?;
+
+        updated.ok_or_else(|| AppError::not_found("Tag"))
+    }
+
+    /// 把重复的标签合并到规范标签：文章和关注者转到规范标签（已经同时有两个标签的去重），
+    /// 下级标签改挂到规范标签下，重复标签的 slug 和别名成为规范标签的别名，然后删除重复标签
+    pub async fn merge_tags(&self, source_id: &str, target_id: &str) -> Result<TagMergeResult> {
+        let source_id = normalize_surreal_id(source_id);
+        let target_id = normalize_surreal_id(target_id);
+        if source_id == target_id {
+            return Err(AppError::bad_request("Cannot merge a tag into itself"));
+        }
+
+        let source = self.get_tag_by_id(&source_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Tag"))?;
+        let target = self.get_tag_by_id(&target_id)
+            .await?
+            .ok_or_else(|| AppError::not_found("Target tag"))?;
+
+        // 规范标签原本在重复标签下面时，接替重复标签的位置，避免下级标签挂回自己形成环
+        let target_parent = if self.ancestor_ids(&target_id).await?.contains(&source_id) {
+            source.parent_id.clone()
+        } else {
+            target.parent_id.clone()
+        };
+
+        let moved_articles = self.count_movable("article_tag", "article_id", &source_id, &target_id).await?;
+        let moved_followers = self.count_movable("user_tag_follow", "user_id", &source_id, &target_id).await?;
+
+        let mut aliases = source.aliases.clone();
+        aliases.push(source.slug.clone());
+
+        self.db.query_with_params(
+            r#"
+            BEGIN TRANSACTION;
+            LET $source = type::thing('tag', $source_id);
+            LET $target = type::thing('tag', $target_id);
+            DELETE article_tag WHERE tag_id = $source AND article_id INSIDE (SELECT VALUE article_id FROM article_tag WHERE tag_id = $target);
+            UPDATE article_tag SET tag_id = $target WHERE tag_id = $source;
+            DELETE user_tag_follow WHERE tag_id = $source AND user_id INSIDE (SELECT VALUE user_id FROM user_tag_follow WHERE tag_id = $target);
+            UPDATE user_tag_follow SET tag_id = $target WHERE tag_id = $source;
+            UPDATE tag SET parent_id = $target_id WHERE parent_id = $source_id AND id != $target;
+            UPDATE $target SET
+                parent_id = $target_parent ?? NONE,
+                aliases = array::complement(array::union(aliases, $aliases), [slug]),
+                updated_at = time::now();
+            DELETE $source;
+            COMMIT TRANSACTION;
+            "#,
+            json!({
+                "source_id": source_id,
+                "target_id": target_id,
+                "target_parent": target_parent,
+                "aliases": aliases,
+            }),
+        ).await?;
+
+        self.update_tag_article_count(&target_id).await?;
+        self.update_tag_follower_count(&target_id).await?;
+
+        let tag = self.get_tag_by_id(&target_id)
+            .await?
+            .ok_or_else(|| AppError::internal("Merged tag disappeared"))?;
+
+        info!(
+            "Merged tag {} ({}) into {} ({}): {} articles, {} followers moved",
+            source.name, source_id, tag.name, target_id, moved_articles, moved_followers
+        );
+        Ok(TagMergeResult { tag, moved_articles, moved_followers })
+    }
+
+    /// 从该标签开始向上的标签 ID（包括自身）
+    async fn ancestor_ids(&self, tag_id: &str) -> Result<Vec<String>> {
+        let mut ids = Vec::new();
+        let mut current = Some(tag_id.to_string());
+        while let Some(id) = current {
+            // 历史数据里如果已经有环，到深度上限就停下
+            if ids.len() > MAX_TAG_DEPTH || ids.contains(&id) {
+                break;
+            }
+            let tag = self.get_tag_by_id(&id)
+                .await?
+                .ok_or_else(|| AppError::not_found("Tag"))?;
+            current = tag.parent_id;
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+
+    /// 以该标签为根的子树层数（只有自身时为 1）
+    async fn subtree_height(&self, tag_id: &str) -> Result<usize> {
+        let mut height = 1;
+        let mut level = vec![tag_id.to_string()];
+        while height <= MAX_TAG_DEPTH {
+            let children: Vec<String> = self.db
+                .prepare("SELECT VALUE meta::id(id) FROM tag WHERE parent_id INSIDE $ids")
+                .bind("ids", &level)
+                .fetch()
+                .await?;
+            if children.is_empty() {
+                break;
+            }
+            height += 1;
+            level = children;
+        }
+        Ok(height)
+    }
+
+    /// 合并时实际转移的关联数：`owner_field` 已经关联到目标标签的不算
+    async fn count_movable(&self, table: &str, owner_field: &str, source_id: &str, target_id: &str) -> Result<i64> {
+        let row: Option<CountRow> = self.db
+            .prepare(&format!(
+                "SELECT count() AS total FROM {table}
+                 WHERE tag_id = type::thing('tag', $source_id)
+                 AND {field} NOTINSIDE (SELECT VALUE {field} FROM {table} WHERE tag_id = type::thing('tag', $target_id))
+                 GROUP ALL",
+                table = table,
+                field = owner_field
+            ))
+            .bind("source_id", source_id)
+            .bind("target_id", target_id)
+            .fetch_one()
+            .await?;
+        Ok(row.map_or(0, |r| r.total))
+    }
+
     pub async fn add_tags_to_article(&self, article_id: &str, tag_ids: Vec<String>) -> Result<()> {
         debug!("Adding {} tags to article: {}", tag_ids.len(), article_id);
 
@@ -322,6 +557,8 @@ impl TagService {
     pub async fn follow_tag(&self, tag_id: &str, user_id: &str) -> Result<()> {
         debug!("User {} following tag: {}", user_id, tag_id);
 
+        let tag_id = &normalize_surreal_id(tag_id);
+
         // Check if tag exists
         let tag: Option<Tag> = self.db.get_by_id("tag", tag_id).await?;
         if tag.is_none() {
@@ -333,7 +570,7 @@ impl TagService {
             r#"
                 SELECT * FROM user_tag_follow 
                 WHERE user_id = $user_id 
-                AND tag_id = $tag_id
+                AND tag_id = type::thing('tag', $tag_id)
             "#,
             json!({
                 "user_id": user_id,
@@ -346,14 +583,19 @@ impl TagService {
             return Err(AppError::Conflict("Already following this tag".to_string()));
         }
 
-        let follow = UserTagFollow {
-            id: Uuid::new_v4().to_string(),
-            user_id: user_id.to_string(),
-            tag_id: tag_id.to_string(),
-            created_at: Utc::now(),
-        };
-
-        self.db.create("user_tag_follow", follow).await?;
+        // tag_id is a record link, so it has to be built with type::thing rather than bound as a string
+        self.db.query_with_params(
+            r#"
+                CREATE user_tag_follow SET
+                    user_id = $user_id,
+                    tag_id = type::thing('tag', $tag_id),
+                    created_at = time::now()
+            "#,
+            json!({
+                "user_id": user_id,
+                "tag_id": tag_id
+            })
+        ).await?;
 
         // Update tag follower count
         self.update_tag_follower_count(tag_id).await?;
@@ -364,11 +606,12 @@ impl TagService {
     pub async fn unfollow_tag(&self, tag_id: &str, user_id: &str) -> Result<()> {
         debug!("User {} unfollowing tag: {}", user_id, tag_id);
 
+        let tag_id = &normalize_surreal_id(tag_id);
         self.db.query_with_params(
             r#"
                 DELETE user_tag_follow 
                 WHERE user_id = $user_id 
-                AND tag_id = $tag_id
+                AND tag_id = type::thing('tag', $tag_id)
             "#,
             json!({
                 "user_id": user_id,
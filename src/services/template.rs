@@ -0,0 +1,233 @@
+use crate::{
+    error::{AppError, Result},
+    models::{
+        article::Article,
+        id::{bare_id, ArticleId, PublicationId, UserId},
+        template::*,
+    },
+    services::{ArticleService, Database},
+    utils::markdown::MarkdownProcessor,
+};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{debug, info};
+use validator::Validate;
+
+/// 出版物的文章模板。
+/// 权限由路由通过 PublicationService 检查：成员可以查看和使用模板，所有者和编辑可以管理模板
+#[derive(Clone)]
+pub struct TemplateService {
+    db: Arc<Database>,
+    article_service: ArticleService,
+    markdown_processor: MarkdownProcessor,
+}
+
+impl TemplateService {
+    pub async fn new(db: Arc<Database>, article_service: ArticleService) -> Result<Self> {
+        Ok(Self {
+            db,
+            article_service,
+            markdown_processor: MarkdownProcessor::new(),
+        })
+    }
+
+    pub async fn list(&self, publication_id: &str) -> Result<Vec<ArticleTemplate>> {
+        self.db
+            .prepare("SELECT * FROM article_template WHERE publication_id = $publication_id ORDER BY name ASC")
+            .bind("publication_id", PublicationId::new(publication_id).as_str())
+            .fetch()
+            .await
+    }
+
+    /// 按 ID 获取模板，不限定出版物（创建文章时只传模板 ID）
+    pub async fn get_template(&self, template_id: &str) -> Result<ArticleTemplate> {
+        let template: Option<ArticleTemplate> = self.db
+            .get_by_id("article_template", bare_id("article_template", template_id))
+            .await?;
+        template.ok_or_else(|| AppError::not_found("Article template"))
+    }
+
+    pub async fn get(&self, publication_id: &str, template_id: &str) -> Result<ArticleTemplate> {
+        let template = self.get_template(template_id).await?;
+        if template.publication_id != PublicationId::new(publication_id).as_str() {
+            return Err(AppError::not_found("Article template"));
+        }
+        Ok(template)
+    }
+
+    pub async fn create(&self, publication_id: &str, user_id: &str, request: CreateTemplateRequest) -> Result<ArticleTemplate> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let template: ArticleTemplate = self.db
+            .prepare(
+                r#"
+                CREATE article_template CONTENT {
+                    publication_id: $publication_id,
+                    name: $name,
+                    description: $description ?? NONE,
+                    content: $content,
+                    required_sections: $required_sections,
+                    default_tags: $default_tags,
+                    seo_checklist: $seo_checklist,
+                    created_by: $created_by,
+                    created_at: time::now(),
+                    updated_at: time::now()
+                }
+                "#,
+            )
+            .bind("publication_id", PublicationId::new(publication_id).as_str())
+            .bind("name", request.name.trim())
+            .bind("description", &request.description)
+            .bind("content", &request.content)
+            .bind("required_sections", normalize_list(&request.required_sections))
+            .bind("default_tags", normalize_list(&request.default_tags))
+            .bind("seo_checklist", &request.seo_checklist)
+            .bind("created_by", UserId::new(user_id).as_str())
+            .fetch_one()
+            .await?
+            .ok_or_else(|| AppError::internal("Failed to create article template"))?;
+
+        info!("User {} created template {} for publication {}", user_id, template.id, publication_id);
+        Ok(template)
+    }
+
+    pub async fn update(&self, publication_id: &str, template_id: &str, request: UpdateTemplateRequest) -> Result<ArticleTemplate> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let template = self.get(publication_id, template_id).await?;
+        let updated: Option<ArticleTemplate> = self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('article_template', $template_id) SET
+                    name = $name ?? name,
+                    description = $description ?? description,
+                    content = $content ?? content,
+                    required_sections = $required_sections ?? required_sections,
+                    default_tags = $default_tags ?? default_tags,
+                    seo_checklist = $seo_checklist ?? seo_checklist,
+                    updated_at = time::now()
+                "#,
+            )
+            .bind("template_id", template_key(&template))
+            .bind("name", request.name.as_deref().map(str::trim))
+            .bind("description", &request.description)
+            .bind("content", &request.content)
+            .bind("required_sections", request.required_sections.as_deref().map(normalize_list))
+            .bind("default_tags", request.default_tags.as_deref().map(normalize_list))
+            .bind("seo_checklist", &request.seo_checklist)
+            .fetch_one()
+            .await?;
+
+        updated.ok_or_else(|| AppError::not_found("Article template"))
+    }
+
+    /// 删除模板不影响已经用它创建的文章，这些文章不再做模板检查
+    pub async fn delete(&self, publication_id: &str, template_id: &str) -> Result<()> {
+        let template = self.get(publication_id, template_id).await?;
+        self.db
+            .prepare("DELETE type::thing('article_template', $template_id)")
+            .bind("template_id", template_key(&template))
+            .execute()
+            .await?;
+
+        info!("Deleted template {} from publication {}", template.id, publication_id);
+        Ok(())
+    }
+
+    /// 记录文章使用的模板，发布前据此检查
+    pub async fn attach(&self, mut article: Article, template: &ArticleTemplate) -> Result<Article> {
+        self.db
+            .prepare("UPDATE type::thing('article', $article_id) SET metadata.template_id = $template_id")
+            .bind("article_id", ArticleId::new(&article.id).as_str())
+            .bind("template_id", template_key(template))
+            .execute()
+            .await?;
+
+        if !article.metadata.is_object() {
+            article.metadata = Value::Object(Default::default());
+        }
+        article.metadata["template_id"] = Value::String(template_key(template).to_string());
+        Ok(article)
+    }
+
+    /// 正文中缺少的必需章节（按 Markdown 标题比较）
+    pub fn missing_sections(&self, template: &ArticleTemplate, content: &str) -> Vec<String> {
+        let headings: Vec<String> = self.markdown_processor
+            .extract_toc(content)
+            .into_iter()
+            .map(|item| item.title)
+            .collect();
+        missing_sections(&template.required_sections, &headings)
+    }
+
+    /// 对照文章使用的模板检查必需章节和 SEO 清单；没有使用模板（或模板已删除）时返回 None
+    pub async fn check_article(&self, article: &Article) -> Result<Option<TemplateCheckResult>> {
+        let Some(template_id) = article.metadata.get("template_id").and_then(|v| v.as_str()) else {
+            return Ok(None);
+        };
+        let template = match self.get_template(template_id).await {
+            Ok(template) => template,
+            Err(AppError::NotFound(_)) => {
+                debug!("Template {} of article {} no longer exists", template_id, article.id);
+                return Ok(None);
+            }
+            Err(e) => return Err(e),
+        };
+
+        let tags: Vec<String> = self.article_service
+            .get_article_tags(&article.id)
+            .await?
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect();
+        let missing_sections = self.missing_sections(&template, &article.content);
+        let seo_checks = template.seo_checklist
+            .iter()
+            .map(|check| check.evaluate(article, &tags))
+            .collect();
+
+        Ok(Some(TemplateCheckResult {
+            template_id: template.id,
+            template_name: template.name,
+            can_publish: missing_sections.is_empty(),
+            missing_sections,
+            seo_checks,
+        }))
+    }
+
+    /// 发布前调用：缺少模板要求的章节时拒绝发布，SEO 检查只作提示。
+    /// 发布时同时修改正文的，传入新的正文
+    pub async fn ensure_publishable(&self, article_id: &str, content: Option<&str>) -> Result<()> {
+        let Some(mut article) = self.article_service.get_article_by_id(article_id).await? else {
+            return Ok(());
+        };
+        if let Some(content) = content {
+            article.content = content.to_string();
+        }
+        if let Some(check) = self.check_article(&article).await? {
+            if !check.can_publish {
+                return Err(AppError::BadRequest(format!(
+                    "Article is missing sections required by template '{}': {}",
+                    check.template_name,
+                    check.missing_sections.join(", ")
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn template_key(template: &ArticleTemplate) -> &str {
+    bare_id("article_template", &template.id)
+}
+
+/// 去掉首尾空白、空项和重复项（不区分大小写）
+fn normalize_list(items: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for item in items.iter().map(|i| i.trim()).filter(|i| !i.is_empty()) {
+        if !normalized.iter().any(|n| n.eq_ignore_ascii_case(item)) {
+            normalized.push(item.to_string());
+        }
+    }
+    normalized
+}
@@ -0,0 +1,126 @@
+use crate::{
+    error::{AppError, Result},
+    models::{id::bare_id, theme::*},
+    services::Database,
+    utils::theme::{is_font_family, is_hex_color, sanitize_css},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::info;
+use validator::Validate;
+
+#[derive(Debug, Deserialize)]
+struct PublicationBranding {
+    theme_color: Option<String>,
+    logo_url: Option<String>,
+}
+
+/// 出版物主题。保存在 `publication_theme`，记录 ID 与出版物相同
+#[derive(Clone)]
+pub struct ThemeService {
+    db: Arc<Database>,
+}
+
+impl ThemeService {
+    pub async fn new(db: Arc<Database>) -> Result<Self> {
+        Ok(Self { db })
+    }
+
+    /// 出版物的主题，没有设置过时由出版物的主色和标志生成默认主题
+    pub async fn get(&self, publication_id: &str) -> Result<PublicationTheme> {
+        let publication_id = bare_id("publication", publication_id);
+        let theme: Option<PublicationTheme> = self.db
+            .prepare("SELECT * FROM type::thing('publication_theme', $publication_id)")
+            .bind("publication_id", publication_id)
+            .fetch_one()
+            .await?;
+        if let Some(theme) = theme {
+            return Ok(theme);
+        }
+
+        let branding: Option<PublicationBranding> = self.db
+            .prepare("SELECT theme_color, logo_url FROM type::thing('publication', $publication_id)")
+            .bind("publication_id", publication_id)
+            .fetch_one()
+            .await?;
+        let (theme_color, logo_url) = branding.map_or((None, None), |b| (b.theme_color, b.logo_url));
+        Ok(PublicationTheme::default_for(publication_id, theme_color, logo_url))
+    }
+
+    /// 只修改提交的字段。主色同时同步到出版物的 `theme_color`
+    pub async fn update(&self, publication_id: &str, request: UpdateThemeRequest) -> Result<PublicationTheme> {
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let mut theme = self.get(publication_id).await?;
+        for (color, target) in [
+            (request.primary_color, &mut theme.colors.primary),
+            (request.accent_color, &mut theme.colors.accent),
+            (request.background_color, &mut theme.colors.background),
+            (request.text_color, &mut theme.colors.text),
+        ] {
+            if let Some(color) = color {
+                let color = color.trim().to_lowercase();
+                if !is_hex_color(&color) {
+                    return Err(AppError::bad_request(&format!("Invalid color '{}', use #rgb or #rrggbb", color)));
+                }
+                *target = color;
+            }
+        }
+        for (font, target) in [
+            (request.heading_font, &mut theme.fonts.heading),
+            (request.body_font, &mut theme.fonts.body),
+        ] {
+            if let Some(font) = font {
+                *target = match font.trim() {
+                    "" => None,
+                    font if is_font_family(font) => Some(font.to_string()),
+                    font => return Err(AppError::bad_request(&format!("Invalid font family '{}'", font))),
+                };
+            }
+        }
+        for (url, target) in [
+            (request.logo_light_url, &mut theme.logos.light),
+            (request.logo_dark_url, &mut theme.logos.dark),
+            (request.icon_url, &mut theme.logos.icon),
+        ] {
+            if let Some(url) = url {
+                *target = match url.trim() {
+                    "" => None,
+                    url if url.starts_with("https://") && validator::validate_url(url) => Some(url.to_string()),
+                    _ => return Err(AppError::bad_request("Logo URLs must be https URLs")),
+                };
+            }
+        }
+        if let Some(css) = request.custom_css {
+            let css = sanitize_css(&css);
+            theme.custom_css = (!css.is_empty()).then_some(css);
+        }
+
+        let publication_id = bare_id("publication", publication_id);
+        let updated: Option<PublicationTheme> = self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('publication', $publication_id) SET theme_color = $colors.primary, updated_at = time::now();
+                UPSERT type::thing('publication_theme', $publication_id) CONTENT {
+                    publication_id: $publication_id,
+                    colors: $colors,
+                    fonts: $fonts,
+                    logos: $logos,
+                    custom_css: $custom_css ?? NONE,
+                    updated_at: time::now()
+                };
+                "#,
+            )
+            .bind("publication_id", publication_id)
+            .bind("colors", &theme.colors)
+            .bind("fonts", &theme.fonts)
+            .bind("logos", &theme.logos)
+            .bind("custom_css", &theme.custom_css)
+            .execute()
+            .await?
+            .take(1)?;
+
+        info!("Updated theme for publication {}", publication_id);
+        updated.ok_or_else(|| AppError::internal("Failed to update theme"))
+    }
+}
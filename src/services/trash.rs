@@ -0,0 +1,259 @@
+use crate::{
+    config::Config,
+    error::Result,
+    models::{article::Article, comment::Comment, id::{ArticleId, CommentId}, trash::*},
+    services::{ArticleService, CommentService, Database},
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::{debug, info};
+
+/// 每次清理最多永久删除的文章和评论数量，积压会在后续执行中继续处理
+const PURGE_BATCH_SIZE: usize = 200;
+
+/// 评论在回收站中显示的内容长度（字符）
+const COMMENT_PREVIEW_LENGTH: usize = 80;
+
+/// 永久删除文章时一并删除的关联数据表，按 article_id 关联
+///
+/// 这些表的 article_id 有的存 record，有的存带或不带表名的字符串，删除时三种写法都匹配。
+/// 购买、收益等支付记录需要留档，不在其中
+const ARTICLE_DEPENDENT_TABLES: &[&str] = &[
+    "article_tag",
+    "article_revision",
+    "article_version",
+    "article_collaborator",
+    "article_experiment",
+    "experiment_exposure",
+    "article_slug_history",
+    "article_attachment",
+    "attachment_download",
+    "attachment_lead",
+    "article_audio",
+    "article_embedding",
+    "article_milestone",
+    "article_stats_daily",
+    "article_view",
+    "article_view_event",
+    "bot_traffic_event",
+    "preview_link",
+    "series_article",
+    "comment",
+    "comment_read_marker",
+    "clap",
+    "reaction",
+    "highlight",
+    "bookmark",
+    "reading_progress",
+    "reading_queue_item",
+    "metered_read",
+    "cta_event",
+    "scheduled_share",
+    "search_index",
+    "webmention",
+];
+
+#[derive(Debug, Deserialize)]
+struct TrashedArticle {
+    id: String,
+    title: String,
+    deleted_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TrashedComment {
+    id: String,
+    article_id: String,
+    content: String,
+    deleted_at: DateTime<Utc>,
+}
+
+/// 回收站：列出和恢复软删除的文章、评论，保留期满后由后台任务永久删除
+#[derive(Clone)]
+pub struct TrashService {
+    db: Arc<Database>,
+    article_service: ArticleService,
+    comment_service: CommentService,
+    /// 0 表示不自动清理
+    retention_days: i64,
+}
+
+impl TrashService {
+    pub async fn new(
+        db: Arc<Database>,
+        config: &Config,
+        article_service: ArticleService,
+        comment_service: CommentService,
+    ) -> Result<Self> {
+        Ok(Self {
+            db,
+            article_service,
+            comment_service,
+            retention_days: config.trash_retention_days,
+        })
+    }
+
+    /// 列出用户回收站中的文章和评论，最近删除的在前
+    pub async fn list(&self, user_id: &str, query: &TrashQuery) -> Result<Vec<TrashItem>> {
+        let limit = query.limit.unwrap_or(20).clamp(1, 100);
+        let offset = (query.page.unwrap_or(1).max(1) - 1) * limit;
+        // 两类记录合并排序，每类最多需要取到 offset + limit 条
+        let fetch = offset + limit;
+
+        let mut items = Vec::new();
+
+        if query.item_type != Some(TrashItemType::Comment) {
+            // 旧数据软删除时没有写 deleted_at，按最后修改时间算
+            let articles: Vec<TrashedArticle> = self.db
+                .prepare(
+                    r#"
+                    SELECT type::string(id) AS id, title, deleted_at ?? updated_at AS deleted_at FROM article
+                    WHERE author_id = $user_id AND is_deleted = true
+                    ORDER BY deleted_at DESC
+                    LIMIT $limit
+                    "#,
+                )
+                .bind("user_id", user_id)
+                .bind("limit", fetch)
+                .fetch()
+                .await?;
+
+            items.extend(articles.into_iter().map(|article| TrashItem {
+                item_type: TrashItemType::Article,
+                id: article.id,
+                title: article.title,
+                article_id: None,
+                deleted_at: article.deleted_at,
+                purge_at: self.purge_at(article.deleted_at),
+            }));
+        }
+
+        if query.item_type != Some(TrashItemType::Article) {
+            let comments: Vec<TrashedComment> = self.db
+                .prepare(
+                    r#"
+                    SELECT type::string(id) AS id, article_id, content, deleted_at ?? updated_at AS deleted_at FROM comment
+                    WHERE author_id = $user_id AND is_deleted = true
+                    ORDER BY deleted_at DESC
+                    LIMIT $limit
+                    "#,
+                )
+                .bind("user_id", user_id)
+                .bind("limit", fetch)
+                .fetch()
+                .await?;
+
+            items.extend(comments.into_iter().map(|comment| TrashItem {
+                item_type: TrashItemType::Comment,
+                id: comment.id,
+                title: comment.content.chars().take(COMMENT_PREVIEW_LENGTH).collect(),
+                article_id: Some(ArticleId::new(&comment.article_id).to_record()),
+                deleted_at: comment.deleted_at,
+                purge_at: self.purge_at(comment.deleted_at),
+            }));
+        }
+
+        items.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+        Ok(items.into_iter().skip(offset).take(limit).collect())
+    }
+
+    pub async fn restore_article(&self, article_id: &str, user_id: &str) -> Result<Article> {
+        self.article_service.restore_article(article_id, user_id).await
+    }
+
+    pub async fn restore_comment(&self, comment_id: &str, user_id: &str) -> Result<Comment> {
+        self.comment_service.restore_comment(comment_id, user_id).await
+    }
+
+    /// 永久删除超过保留期的文章（连同关联数据）和评论
+    ///
+    /// 申请删除账户的用户在宽限期内还可以撤销，他们的内容留给账户删除流程处理；
+    /// 还有回复的评论保留在回收站，等回复都被清理后再删除
+    pub async fn purge_expired(&self) -> Result<TrashPurgeReport> {
+        let mut report = TrashPurgeReport::default();
+        if self.retention_days <= 0 {
+            return Ok(report);
+        }
+        let cutoff = Utc::now() - Duration::days(self.retention_days);
+
+        let article_ids: Vec<String> = self.db
+            .prepare(
+                r#"
+                SELECT VALUE meta::id(id) FROM article
+                WHERE is_deleted = true AND (deleted_at ?? updated_at) < <datetime> $cutoff
+                    AND author_id NOTINSIDE (SELECT VALUE user_id FROM user_profile WHERE deletion_requested_at != NONE)
+                LIMIT $limit
+                "#,
+            )
+            .bind("cutoff", cutoff)
+            .bind("limit", PURGE_BATCH_SIZE)
+            .fetch()
+            .await?;
+
+        for article_id in &article_ids {
+            self.purge_article(article_id).await?;
+            report.articles += 1;
+        }
+
+        let comment_ids: Vec<String> = self.db
+            .prepare(
+                r#"
+                SELECT VALUE meta::id(id) FROM comment
+                WHERE is_deleted = true AND (deleted_at ?? updated_at) < <datetime> $cutoff
+                    AND author_id NOTINSIDE (SELECT VALUE user_id FROM user_profile WHERE deletion_requested_at != NONE)
+                    AND count(SELECT id FROM comment WHERE parent_id INSIDE [meta::id($parent.id), <string> $parent.id]) = 0
+                LIMIT $limit
+                "#,
+            )
+            .bind("cutoff", cutoff)
+            .bind("limit", PURGE_BATCH_SIZE)
+            .fetch()
+            .await?;
+
+        if !comment_ids.is_empty() {
+            // comment_clap.comment_id 可能带或不带表名
+            let records: Vec<String> = comment_ids.iter().map(|id| CommentId::new(id).to_record()).collect();
+            self.db
+                .prepare(
+                    r#"
+                    DELETE comment_clap WHERE comment_id INSIDE $ids OR comment_id INSIDE $records;
+                    DELETE comment WHERE meta::id(id) INSIDE $ids;
+                    "#,
+                )
+                .bind("ids", &comment_ids)
+                .bind("records", &records)
+                .execute()
+                .await?;
+            report.comments = comment_ids.len();
+        }
+
+        if report.articles + report.comments > 0 {
+            info!("Purged {} articles and {} comments from trash", report.articles, report.comments);
+        }
+        Ok(report)
+    }
+
+    async fn purge_article(&self, article_id: &str) -> Result<()> {
+        let id = ArticleId::new(article_id);
+
+        let mut sql = String::from("LET $ids = [type::thing('article', $id), $id, 'article:' + $id];\n");
+        for table in ARTICLE_DEPENDENT_TABLES {
+            sql.push_str(&format!("DELETE {table} WHERE article_id INSIDE $ids;\n"));
+        }
+        sql.push_str("DELETE type::thing('article', $id);");
+
+        self.db
+            .prepare(&sql)
+            .bind("id", id.as_str())
+            .execute()
+            .await?;
+
+        debug!("Permanently deleted article {}", id);
+        Ok(())
+    }
+
+    fn purge_at(&self, deleted_at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        (self.retention_days > 0).then(|| deleted_at + Duration::days(self.retention_days))
+    }
+}
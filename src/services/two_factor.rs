@@ -0,0 +1,365 @@
+use crate::{
+    error::{AppError, Result},
+    models::two_factor::*,
+    services::{
+        auth::{AuthSession, User},
+        Database,
+    },
+};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use totp_rs::{Algorithm, Secret, TOTP};
+use tracing::{info, warn};
+
+const TOTP_ISSUER: &str = "Rainbow Blog";
+const TOTP_DIGITS: usize = 6;
+const TOTP_STEP_SECS: u64 = 30;
+/// 允许前后各一个时间步的时钟偏差
+const TOTP_SKEW_STEPS: u64 = 1;
+
+const RECOVERY_CODE_COUNT: usize = 10;
+/// 恢复码去掉了容易混淆的 0/o、1/l/i
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+#[derive(Debug, Deserialize)]
+struct TwoFactorRecord {
+    secret: String,
+    enabled: bool,
+    last_used_step: Option<u64>,
+    #[serde(default)]
+    recovery_codes: Vec<String>,
+    enabled_at: Option<DateTime<Utc>>,
+}
+
+/// 基于 TOTP 的两步验证：绑定验证器、登录后按会话验证、恢复码
+#[derive(Clone)]
+pub struct TwoFactorService {
+    db: Arc<Database>,
+}
+
+impl TwoFactorService {
+    pub async fn new(db: Arc<Database>) -> Result<Self> {
+        Ok(Self { db })
+    }
+
+    async fn get_record(&self, user_id: &str) -> Result<Option<TwoFactorRecord>> {
+        self.db
+            .prepare("SELECT * FROM type::thing('user_two_factor', $user_id)")
+            .bind("user_id", user_id)
+            .fetch_one()
+            .await
+    }
+
+    async fn get_enabled_record(&self, user_id: &str) -> Result<TwoFactorRecord> {
+        self.get_record(user_id)
+            .await?
+            .filter(|record| record.enabled)
+            .ok_or_else(|| AppError::BadRequest("尚未开启两步验证".to_string()))
+    }
+
+    pub async fn is_enabled(&self, user_id: &str) -> Result<bool> {
+        Ok(self.get_record(user_id).await?.map_or(false, |record| record.enabled))
+    }
+
+    pub async fn status(&self, user: &User) -> Result<TwoFactorStatus> {
+        let record = self.get_record(&user.id).await?.filter(|record| record.enabled);
+
+        Ok(TwoFactorStatus {
+            enabled: record.is_some(),
+            enabled_at: record.as_ref().and_then(|record| record.enabled_at),
+            recovery_codes_remaining: record.as_ref().map_or(0, |record| record.recovery_codes.len()),
+            session_verified: user.two_factor_verified,
+        })
+    }
+
+    /// 生成新密钥并返回二维码。在 `confirm` 之前不会生效，重复调用会替换未确认的密钥
+    pub async fn enroll(&self, user: &User) -> Result<TwoFactorEnrollment> {
+        if self.is_enabled(&user.id).await? {
+            return Err(AppError::BadRequest("已开启两步验证，如需更换验证器请先关闭".to_string()));
+        }
+
+        let secret = match Secret::generate_secret().to_encoded() {
+            Secret::Encoded(secret) => secret,
+            Secret::Raw(_) => return Err(AppError::internal("Failed to encode TOTP secret")),
+        };
+        let totp = build_totp(&secret, &user.email)?;
+        let qr_code = totp
+            .get_qr_base64()
+            .map_err(|e| AppError::Internal(format!("Failed to render TOTP QR code: {}", e)))?;
+
+        self.db
+            .prepare(
+                r#"
+                UPSERT type::thing('user_two_factor', $user_id) CONTENT {
+                    user_id: $user_id,
+                    secret: $secret,
+                    enabled: false,
+                    recovery_codes: [],
+                    created_at: time::now()
+                }
+                "#,
+            )
+            .bind("user_id", &user.id)
+            .bind("secret", &secret)
+            .execute()
+            .await?;
+
+        Ok(TwoFactorEnrollment {
+            secret,
+            otpauth_url: totp.get_url(),
+            qr_code: format!("data:image/png;base64,{}", qr_code),
+        })
+    }
+
+    /// 用验证器上的动态码确认绑定，开启两步验证并返回恢复码。当前会话同时视为已验证
+    pub async fn confirm(&self, user: &User, session: &AuthSession, code: &str) -> Result<RecoveryCodes> {
+        let record = self
+            .get_record(&user.id)
+            .await?
+            .ok_or_else(|| AppError::BadRequest("请先生成两步验证密钥".to_string()))?;
+        if record.enabled {
+            return Err(AppError::BadRequest("已开启两步验证".to_string()));
+        }
+
+        let totp = build_totp(&record.secret, &user.email)?;
+        let step = match_totp(&totp, code, unix_now())
+            .ok_or_else(|| AppError::Authentication("验证码不正确".to_string()))?;
+
+        let recovery_codes = generate_recovery_codes();
+        self.db
+            .prepare(
+                r#"
+                UPDATE type::thing('user_two_factor', $user_id) SET
+                    enabled = true,
+                    enabled_at = time::now(),
+                    last_used_step = $step,
+                    recovery_codes = $recovery_codes
+                "#,
+            )
+            .bind("user_id", &user.id)
+            .bind("step", step)
+            .bind("recovery_codes", recovery_codes.iter().map(|c| hash_recovery_code(c)).collect::<Vec<_>>())
+            .execute()
+            .await?;
+
+        self.mark_session_verified(&user.id, session).await?;
+        info!("User {} enabled two-factor authentication", user.id);
+        Ok(RecoveryCodes { recovery_codes })
+    }
+
+    /// 登录后验证当前会话，接受动态码或一次性的恢复码
+    pub async fn verify_session(&self, user: &User, session: &AuthSession, code: &str) -> Result<()> {
+        self.check_code(user, code).await?;
+        self.mark_session_verified(&user.id, session).await?;
+        Ok(())
+    }
+
+    /// 重新生成恢复码，旧的恢复码全部作废。需要当前会话已通过两步验证
+    pub async fn regenerate_recovery_codes(&self, user: &User) -> Result<RecoveryCodes> {
+        self.get_enabled_record(&user.id).await?;
+        if !user.two_factor_verified {
+            return Err(AppError::forbidden("Two-factor verification required for this session"));
+        }
+
+        let recovery_codes = generate_recovery_codes();
+        self.db
+            .prepare("UPDATE type::thing('user_two_factor', $user_id) SET recovery_codes = $recovery_codes")
+            .bind("user_id", &user.id)
+            .bind("recovery_codes", recovery_codes.iter().map(|c| hash_recovery_code(c)).collect::<Vec<_>>())
+            .execute()
+            .await?;
+
+        Ok(RecoveryCodes { recovery_codes })
+    }
+
+    /// 关闭两步验证，需要再输入一次动态码或恢复码
+    pub async fn disable(&self, user: &User, code: &str) -> Result<()> {
+        self.check_code(user, code).await?;
+
+        self.db
+            .prepare("DELETE type::thing('user_two_factor', $user_id)")
+            .bind("user_id", &user.id)
+            .execute()
+            .await?;
+
+        self.db
+            .prepare("DELETE two_factor_session WHERE user_id = $user_id")
+            .bind("user_id", &user.id)
+            .execute()
+            .await?;
+        info!("User {} disabled two-factor authentication", user.id);
+        Ok(())
+    }
+
+    /// 标记会话已通过两步验证，到 JWT 过期为止有效。保存在数据库中，重启和多实例部署都不会丢失
+    async fn mark_session_verified(&self, user_id: &str, session: &AuthSession) -> Result<()> {
+        self.db
+            .prepare(
+                r#"
+                UPSERT type::thing('two_factor_session', $session_key) CONTENT {
+                    user_id: $user_id,
+                    expires_at: <datetime> $expires_at,
+                    verified_at: time::now()
+                }
+                "#,
+            )
+            .bind("session_key", &session.key)
+            .bind("user_id", user_id)
+            .bind("expires_at", session.expires_at)
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 会话是否已通过两步验证。查询失败时按未验证处理
+    pub async fn is_session_verified(&self, session: &AuthSession) -> bool {
+        let verified: Result<Option<String>> = self
+            .db
+            .prepare(
+                "SELECT VALUE user_id FROM type::thing('two_factor_session', $session_key) WHERE expires_at > time::now()",
+            )
+            .bind("session_key", &session.key)
+            .fetch_one()
+            .await;
+
+        match verified {
+            Ok(verified) => verified.is_some(),
+            Err(e) => {
+                warn!("Failed to look up two-factor session: {}", e);
+                false
+            }
+        }
+    }
+
+    /// 清理已过期会话的验证标记
+    pub async fn purge_expired(&self) -> Result<()> {
+        self.db
+            .prepare("DELETE two_factor_session WHERE expires_at < time::now()")
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    /// 校验动态码（同一时间步只能用一次）或消耗一个恢复码
+    async fn check_code(&self, user: &User, code: &str) -> Result<()> {
+        let record = self.get_enabled_record(&user.id).await?;
+        let totp = build_totp(&record.secret, &user.email)?;
+
+        if let Some(step) = match_totp(&totp, code, unix_now()) {
+            if record.last_used_step.map_or(false, |last| step <= last) {
+                return Err(AppError::Authentication("验证码已使用，请等待下一个验证码".to_string()));
+            }
+            self.db
+                .prepare("UPDATE type::thing('user_two_factor', $user_id) SET last_used_step = $step")
+                .bind("user_id", &user.id)
+                .bind("step", step)
+                .execute()
+                .await?;
+            return Ok(());
+        }
+
+        let hash = hash_recovery_code(code);
+        if record.recovery_codes.contains(&hash) {
+            self.db
+                .prepare("UPDATE type::thing('user_two_factor', $user_id) SET recovery_codes -= $hash")
+                .bind("user_id", &user.id)
+                .bind("hash", &hash)
+                .execute()
+                .await?;
+            info!("User {} used a two-factor recovery code", user.id);
+            return Ok(());
+        }
+
+        Err(AppError::Authentication("验证码不正确".to_string()))
+    }
+}
+
+fn build_totp(secret: &str, account_name: &str) -> Result<TOTP> {
+    let secret = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|e| AppError::Internal(format!("Invalid TOTP secret: {:?}", e)))?;
+
+    TOTP::new(
+        Algorithm::SHA1,
+        TOTP_DIGITS,
+        TOTP_SKEW_STEPS as u8,
+        TOTP_STEP_SECS,
+        secret,
+        Some(TOTP_ISSUER.to_string()),
+        account_name.to_string(),
+    )
+    .map_err(|e| AppError::Internal(format!("Failed to build TOTP: {}", e)))
+}
+
+/// 返回与动态码匹配的时间步
+fn match_totp(totp: &TOTP, code: &str, now: u64) -> Option<u64> {
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS || !code.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let current = now / TOTP_STEP_SECS;
+    (current.saturating_sub(TOTP_SKEW_STEPS)..=current + TOTP_SKEW_STEPS)
+        .find(|step| totp.generate(step * TOTP_STEP_SECS) == code)
+}
+
+fn unix_now() -> u64 {
+    Utc::now().timestamp().max(0) as u64
+}
+
+/// 生成 `xxxxx-xxxxx` 格式的恢复码
+fn generate_recovery_codes() -> Vec<String> {
+    let mut rng = rand::thread_rng();
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let chars: String = (0..10)
+                .map(|_| RECOVERY_CODE_ALPHABET[rng.gen_range(0..RECOVERY_CODE_ALPHABET.len())] as char)
+                .collect();
+            format!("{}-{}", &chars[..5], &chars[5..])
+        })
+        .collect()
+}
+
+/// 恢复码只保存哈希，比较时忽略大小写、空格和连字符
+fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    hex::encode(Sha256::digest(normalized.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_totp_matches_adjacent_steps() {
+        let totp = build_totp("JBSWY3DPEHPK3PXPJBSWY3DPEHPK3PXP", "reader@example.com").unwrap();
+        let now = 1_700_000_000;
+        let step = now / TOTP_STEP_SECS;
+
+        let current = totp.generate(now);
+        assert_eq!(match_totp(&totp, &current, now), Some(step));
+
+        let previous = totp.generate(now - TOTP_STEP_SECS);
+        assert_eq!(match_totp(&totp, &previous, now), Some(step - 1));
+
+        let stale = totp.generate(now - 3 * TOTP_STEP_SECS);
+        assert_eq!(match_totp(&totp, &stale, now), None);
+        assert_eq!(match_totp(&totp, "12345", now), None);
+    }
+
+    #[test]
+    fn test_recovery_code_normalization() {
+        let codes = generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+
+        let code = &codes[0];
+        assert_eq!(hash_recovery_code(code), hash_recovery_code(&code.to_uppercase().replace('-', " ")));
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     error::{AppError, Result},
     models::user::*,
-    services::Database,
+    services::{Database, PluginManager},
 };
 use chrono::Utc;
 use serde_json::{json, Value};
@@ -15,12 +15,13 @@ use validator::Validate;
 #[derive(Clone)]
 pub struct UserService {
     db: Arc<Database>,
+    plugins: PluginManager,
 }
 
 impl UserService {
     /// 创建新的用户服务实例
-    pub async fn new(db: Arc<Database>) -> Result<Self> {
-        Ok(Self { db })
+    pub async fn new(db: Arc<Database>, plugins: PluginManager) -> Result<Self> {
+        Ok(Self { db, plugins })
     }
 
     /// 创建新用户资料
@@ -81,6 +82,15 @@ impl UserService {
             total_claps_received: 0,
             is_verified: false,
             is_suspended: false,
+            allow_audience_insights: false,
+            preferred_language: None,
+            reputation_score: 0,
+            reputation_updated_at: None,
+            last_active_at: None,
+            deactivated_at: None,
+            suspended_at: None,
+            suspension_reason: None,
+            deletion_requested_at: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -208,6 +218,8 @@ impl UserService {
             user_id, created_profile.username
         );
 
+        self.plugins.user_registered(&created_profile);
+
         Ok(created_profile)
     }
 
@@ -224,12 +236,13 @@ impl UserService {
         Ok(profiles.into_iter().next())
     }
 
-    /// 统计用户已发布且未删除的文章数量
+    /// 统计用户已发布且未删除的文章数量（不含笔名文章）
     pub async fn count_published_articles(&self, user_id: &str) -> Result<i64> {
         let query = r#"
             SELECT count() AS count 
             FROM article 
             WHERE author_id = $user_id 
+            AND pseudonym_id IS NONE
             AND is_deleted = false 
             AND status = 'published'
         "#;
@@ -295,11 +308,12 @@ impl UserService {
         }
     }
 
-    /// 根据用户名获取用户资料
+    /// 根据用户名获取用户资料，申请删除的账户不再公开
     pub async fn get_profile_by_username(&self, username: &str) -> Result<Option<UserProfile>> {
         debug!("Getting user profile by username: {}", username);
 
-        self.db.find_one("user_profile", "username", username).await
+        let profile: Option<UserProfile> = self.db.find_one("user_profile", "username", username).await?;
+        Ok(profile.filter(|p| p.deletion_requested_at.is_none()))
     }
 
     /// 更新用户资料
@@ -352,6 +366,12 @@ impl UserService {
         if let Some(facebook_url) = update_request.facebook_url {
             profile.facebook_url = Some(facebook_url);
         }
+        if let Some(allow_audience_insights) = update_request.allow_audience_insights {
+            profile.allow_audience_insights = allow_audience_insights;
+        }
+        if let Some(preferred_language) = update_request.preferred_language {
+            profile.preferred_language = Some(preferred_language);
+        }
 
         profile.updated_at = Utc::now();
 
@@ -368,8 +388,14 @@ impl UserService {
     }
 
     /// 检查用户名是否已被使用
+    /// 笔名占用的用户名也算已被使用
     pub async fn is_username_taken(&self, username: &str) -> Result<bool> {
-        let query = "SELECT count() AS count FROM user_profile WHERE username = $username";
+        let query = r#"
+            RETURN {
+                count: count((SELECT id FROM user_profile WHERE username = $username))
+                    + count((SELECT id FROM pseudonym WHERE username = $username))
+            }
+        "#;
         let mut response = self
             .db
             .query_with_params(query, json!({ "username": username }))
@@ -440,8 +466,8 @@ impl UserService {
         // 获取用户给出的拍手数
         let claps_given_query = r#"
             SELECT COALESCE(sum(count), 0) as total_claps 
-            FROM clap 
-            WHERE user_id = $user_id
+            FROM reaction 
+            WHERE user_id = $user_id AND reaction_type = 'clap'
         "#;
 
         let mut claps_given_response = self
@@ -466,9 +492,9 @@ impl UserService {
         // 获取用户收到的拍手数（通过用户的文章）
         let claps_received_query = r#"
             SELECT sum(c.count) as total_claps 
-            FROM clap c
+            FROM reaction c
             JOIN article a ON c.article_id = a.id
-            WHERE a.author_id = $user_id
+            WHERE a.author_id = $user_id AND a.pseudonym_id IS NONE AND c.reaction_type = 'clap'
         "#;
 
         let mut claps_received_response = self
@@ -745,6 +771,15 @@ impl UserService {
             total_claps_received: 0,
             is_verified: false,
             is_suspended: false,
+            allow_audience_insights: false,
+            preferred_language: None,
+            reputation_score: 0,
+            reputation_updated_at: None,
+            last_active_at: None,
+            deactivated_at: None,
+            suspended_at: None,
+            suspension_reason: None,
+            deletion_requested_at: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -859,6 +894,7 @@ impl UserService {
 
         // 从数据库重新获取创建的记录，确保数据已经持久化
         if let Some(created_profile) = self.get_profile_by_user_id(user_id).await? {
+            self.plugins.user_registered(&created_profile);
             Ok(created_profile)
         } else {
             error!("Failed to retrieve created profile for user: {}", user_id);
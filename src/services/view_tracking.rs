@@ -0,0 +1,170 @@
+use crate::{
+    config::Config,
+    error::Result,
+    models::{
+        id::{ArticleId, PublicationId, UserId},
+        view_tracking::*,
+    },
+    services::{analytics::referrer_source, Database},
+    utils::{middleware::client_ip, user_agent::parse_user_agent},
+};
+use axum::http::{header, HeaderMap};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tracing::debug;
+
+#[derive(Debug, Deserialize)]
+struct CountRow {
+    count: i64,
+}
+
+/// 浏览去重：同一读者在时间窗口内重复浏览同一篇文章只计一次，
+/// 计入的浏览写入 article_view，用于文章和出版物的独立访客统计
+#[derive(Clone)]
+pub struct ViewTrackingService {
+    db: Arc<Database>,
+    secret: Arc<Vec<u8>>,
+    window: Duration,
+}
+
+impl ViewTrackingService {
+    pub async fn new(db: Arc<Database>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            db,
+            secret: Arc::new(config.jwt_secret.as_bytes().to_vec()),
+            window: Duration::minutes(config.view_dedup_window_minutes.max(1)),
+        })
+    }
+
+    /// 读者标识。匿名读者的 IP 和 User-Agent 加盐后哈希，数据库中不出现原始 IP
+    pub fn viewer_hash(&self, headers: &HeaderMap, user_id: Option<&str>) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.secret.as_slice());
+        match user_id {
+            Some(user_id) => hasher.update(format!("user|{}", UserId::new(user_id).as_str())),
+            None => {
+                let ip = client_ip(headers).unwrap_or_default();
+                let user_agent = headers
+                    .get(header::USER_AGENT)
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default();
+                hasher.update(format!("anon|{}|{}", ip, user_agent));
+            }
+        }
+        hex::encode(&hasher.finalize()[..16])
+    }
+
+    /// 记录一次浏览，窗口内的重复浏览不记录。返回这次浏览是否计入。
+    /// `country` 来自 `geoip_middleware` 写入的 `VisitorGeo`
+    pub async fn record_view(
+        &self,
+        article_id: &str,
+        author_id: &str,
+        publication_id: Option<&str>,
+        headers: &HeaderMap,
+        user_id: Option<&str>,
+        country: Option<&str>,
+    ) -> Result<bool> {
+        let article_id = ArticleId::new(article_id);
+        let viewer_hash = self.viewer_hash(headers, user_id);
+
+        let recent: Vec<CountRow> = self.db
+            .prepare(
+                r#"
+                SELECT count() AS count FROM article_view
+                WHERE article_id = $article_id AND viewer_hash = $viewer_hash AND created_at > $since
+                GROUP ALL
+                "#,
+            )
+            .bind("article_id", article_id.as_str())
+            .bind("viewer_hash", &viewer_hash)
+            .bind("since", Utc::now() - self.window)
+            .fetch()
+            .await?;
+        if recent.first().map_or(false, |r| r.count > 0) {
+            debug!("Duplicate view of article {} within the dedup window", article_id.as_str());
+            return Ok(false);
+        }
+
+        let referrer = headers.get(header::REFERER).and_then(|v| v.to_str().ok());
+        let client = parse_user_agent(
+            headers.get(header::USER_AGENT).and_then(|v| v.to_str().ok()).unwrap_or_default(),
+        );
+        self.db
+            .prepare(
+                r#"
+                CREATE article_view CONTENT {
+                    article_id: $article_id,
+                    author_id: $author_id,
+                    publication_id: $publication_id ?? NONE,
+                    viewer_hash: $viewer_hash,
+                    user_id: $user_id ?? NONE,
+                    referrer: $referrer,
+                    country: $country ?? NONE,
+                    device_type: $device_type,
+                    browser: $browser,
+                    os: $os,
+                    created_at: time::now()
+                }
+                "#,
+            )
+            .bind("article_id", article_id.as_str())
+            .bind("author_id", UserId::new(author_id).as_str())
+            .bind("publication_id", publication_id.map(|id| PublicationId::new(id).as_str().to_string()))
+            .bind("viewer_hash", &viewer_hash)
+            .bind("user_id", user_id.map(|id| UserId::new(id).as_str().to_string()))
+            .bind("referrer", referrer_source(referrer))
+            .bind("country", country)
+            .bind("device_type", client.device_type)
+            .bind("browser", client.browser)
+            .bind("os", client.os)
+            .execute()
+            .await?;
+
+        Ok(true)
+    }
+
+    /// 文章最近 N 天去重后的浏览数和独立访客数
+    pub async fn get_article_stats(&self, article_id: &str, query: ViewStatsQuery) -> Result<ViewStats> {
+        self.get_stats("article_id", ArticleId::new(article_id).as_str(), query).await
+    }
+
+    /// 出版物最近 N 天的浏览数和独立访客数，同一读者浏览多篇文章只算一位访客
+    pub async fn get_publication_stats(&self, publication_id: &str, query: ViewStatsQuery) -> Result<ViewStats> {
+        self.get_stats("publication_id", PublicationId::new(publication_id).as_str(), query).await
+    }
+
+    async fn get_stats(&self, field: &'static str, value: &str, query: ViewStatsQuery) -> Result<ViewStats> {
+        let days = query.days.unwrap_or(30).clamp(1, 365);
+        let mut response = self.db.query_with_params(
+            &format!(
+                r#"
+                    SELECT count() AS count FROM article_view
+                        WHERE {field} = $value AND created_at >= $since
+                        GROUP ALL;
+                    SELECT count() AS count FROM (
+                        SELECT viewer_hash FROM article_view
+                            WHERE {field} = $value AND created_at >= $since
+                            GROUP BY viewer_hash
+                    ) GROUP ALL;
+                "#,
+                field = field
+            ),
+            json!({
+                "value": value,
+                "since": Utc::now() - Duration::days(days),
+            }),
+        ).await?;
+        let views: Vec<CountRow> = response.take(0)?;
+        let viewers: Vec<CountRow> = response.take(1)?;
+
+        Ok(ViewStats {
+            days,
+            views: views.first().map_or(0, |r| r.count),
+            unique_viewers: viewers.first().map_or(0, |r| r.count),
+        })
+    }
+}
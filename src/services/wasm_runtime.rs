@@ -0,0 +1,78 @@
+//! 插件和内容转换共用的 WASM 运行时
+//!
+//! 模块需要导出 `memory` 和 `alloc(len: i32) -> i32`，宿主通过 `alloc` 申请的内存传入输入。
+//! 每次调用使用新的实例，并限制燃料（CPU）和线性内存大小，调用之间不共享状态。
+
+use crate::error::{AppError, Result};
+use once_cell::sync::OnceCell;
+use wasmtime::{Engine, Instance, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, TypedFunc, WasmParams, WasmResults};
+
+/// 线性内存上限
+pub const MAX_MEMORY_BYTES: usize = 16 * 1024 * 1024;
+
+static ENGINE: OnceCell<Engine> = OnceCell::new();
+
+/// 开启燃料计量的引擎，插件和内容转换在同一进程内共用
+pub fn engine() -> Result<Engine> {
+    ENGINE
+        .get_or_try_init(|| {
+            let mut config = wasmtime::Config::new();
+            config.consume_fuel(true);
+            Engine::new(&config).map_err(|e| AppError::Internal(format!("Failed to create WASM engine: {}", e)))
+        })
+        .cloned()
+}
+
+fn wasm_err(e: wasmtime::Error) -> AppError {
+    AppError::Internal(format!("WASM error: {}", e))
+}
+
+/// 一次受限的调用：新的实例、燃料上限和内存上限
+pub struct Call {
+    store: Store<StoreLimits>,
+    instance: Instance,
+    memory: Memory,
+}
+
+impl Call {
+    pub fn new(engine: &Engine, module: &Module, fuel: u64) -> Result<Self> {
+        let limits = StoreLimitsBuilder::new().memory_size(MAX_MEMORY_BYTES).build();
+        let mut store = Store::new(engine, limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(fuel).map_err(wasm_err)?;
+
+        let instance = Instance::new(&mut store, module, &[]).map_err(wasm_err)?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| AppError::internal("WASM module does not export memory"))?;
+
+        Ok(Self { store, instance, memory })
+    }
+
+    pub fn func<P: WasmParams, R: WasmResults>(&mut self, name: &str) -> Result<TypedFunc<P, R>> {
+        self.instance.get_typed_func::<P, R>(&mut self.store, name).map_err(wasm_err)
+    }
+
+    pub fn call<P: WasmParams, R: WasmResults>(&mut self, func: &TypedFunc<P, R>, params: P) -> Result<R> {
+        func.call(&mut self.store, params).map_err(wasm_err)
+    }
+
+    /// 通过模块的 `alloc` 申请内存并写入输入，返回地址和长度
+    pub fn write_input(&mut self, input: &[u8]) -> Result<(i32, i32)> {
+        let alloc = self.func::<i32, i32>("alloc")?;
+        let len = input.len() as i32;
+        let ptr = self.call(&alloc, len)?;
+        self.memory
+            .write(&mut self.store, ptr as usize, input)
+            .map_err(|e| AppError::Internal(format!("WASM memory error: {}", e)))?;
+        Ok((ptr, len))
+    }
+
+    pub fn read_output(&self, ptr: usize, len: usize) -> Result<Vec<u8>> {
+        let mut output = vec![0u8; len];
+        self.memory
+            .read(&self.store, ptr, &mut output)
+            .map_err(|e| AppError::Internal(format!("WASM memory error: {}", e)))?;
+        Ok(output)
+    }
+}
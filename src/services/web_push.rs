@@ -0,0 +1,224 @@
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+    models::notification::{CreatePushSubscriptionRequest, Notification, PushSubscription},
+    services::Database,
+};
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+use validator::Validate;
+use web_push::{
+    ContentEncoding, SubscriptionInfo, VapidSignatureBuilder, WebPushMessageBuilder,
+};
+
+/// 推送服务保留未送达消息的时间（秒）
+const PUSH_TTL_SECS: u32 = 24 * 3600;
+/// 每个用户最多保留的订阅（设备）数
+const MAX_SUBSCRIPTIONS_PER_USER: usize = 20;
+
+/// VAPID 密钥对
+#[derive(Clone)]
+struct VapidKeys {
+    public_key: String,
+    private_key: String,
+    subject: String,
+}
+
+/// 浏览器 Web Push：保存订阅，并用 VAPID 签名加密后发送到浏览器厂商的推送服务
+#[derive(Clone)]
+pub struct WebPushService {
+    db: Arc<Database>,
+    client: Client,
+    vapid: Option<VapidKeys>,
+}
+
+impl WebPushService {
+    pub fn new(db: Arc<Database>, config: &Config) -> Result<Self> {
+        let vapid = match (&config.vapid_public_key, &config.vapid_private_key) {
+            (Some(public_key), Some(private_key)) => Some(VapidKeys {
+                public_key: public_key.clone(),
+                private_key: private_key.clone(),
+                subject: config.vapid_subject.clone(),
+            }),
+            _ => None,
+        };
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| AppError::Internal(format!("Failed to create push client: {}", e)))?;
+
+        Ok(Self { db, client, vapid })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.vapid.is_some()
+    }
+
+    /// 客户端调用 `pushManager.subscribe` 时使用的 applicationServerKey
+    pub fn public_key(&self) -> Option<&str> {
+        self.vapid.as_ref().map(|v| v.public_key.as_str())
+    }
+
+    /// 保存订阅。同一推送地址重复订阅时更新密钥和所属用户
+    pub async fn subscribe(&self, user_id: &str, request: CreatePushSubscriptionRequest) -> Result<PushSubscription> {
+        if !self.is_enabled() {
+            return Err(AppError::BadRequest("浏览器推送未启用".to_string()));
+        }
+        request.validate().map_err(AppError::ValidatorError)?;
+
+        let existing = self.list_subscriptions(user_id).await?;
+        let id = subscription_id(&request.endpoint);
+        if existing.len() >= MAX_SUBSCRIPTIONS_PER_USER && !existing.iter().any(|s| s.id.ends_with(&id)) {
+            return Err(AppError::BadRequest(format!(
+                "最多只能在 {} 个设备上开启推送",
+                MAX_SUBSCRIPTIONS_PER_USER
+            )));
+        }
+
+        let subscription: Option<PushSubscription> = self
+            .db
+            .prepare(
+                r#"
+                UPSERT type::thing('push_subscription', $id) SET
+                    user_id = $user_id,
+                    endpoint = $endpoint,
+                    p256dh = $p256dh,
+                    auth = $auth,
+                    user_agent = $user_agent,
+                    created_at = created_at ?? time::now()
+                "#,
+            )
+            .bind("id", &id)
+            .bind("user_id", user_id)
+            .bind("endpoint", &request.endpoint)
+            .bind("p256dh", &request.keys.p256dh)
+            .bind("auth", &request.keys.auth)
+            .bind("user_agent", &request.user_agent)
+            .fetch_one()
+            .await?;
+
+        info!("Saved push subscription for user {}", user_id);
+        subscription.ok_or_else(|| AppError::internal("Failed to save push subscription"))
+    }
+
+    pub async fn list_subscriptions(&self, user_id: &str) -> Result<Vec<PushSubscription>> {
+        self.db
+            .prepare("SELECT * FROM push_subscription WHERE user_id = $user_id ORDER BY created_at DESC")
+            .bind("user_id", user_id)
+            .fetch()
+            .await
+    }
+
+    pub async fn unsubscribe(&self, user_id: &str, subscription_id: &str) -> Result<()> {
+        let deleted: Option<PushSubscription> = self
+            .db
+            .prepare("DELETE type::thing('push_subscription', $id) WHERE user_id = $user_id RETURN BEFORE")
+            .bind("id", crate::models::id::bare_id("push_subscription", subscription_id))
+            .bind("user_id", user_id)
+            .fetch_one()
+            .await?;
+
+        deleted
+            .map(|_| ())
+            .ok_or_else(|| AppError::NotFound("推送订阅不存在".to_string()))
+    }
+
+    /// 推送到用户的所有设备，返回送达的设备数。推送服务报告订阅已失效时删除订阅
+    pub async fn send_notification(&self, notification: &Notification) -> Result<usize> {
+        let Some(vapid) = &self.vapid else {
+            return Ok(0);
+        };
+
+        let subscriptions = self.list_subscriptions(&notification.recipient_id).await?;
+        if subscriptions.is_empty() {
+            return Ok(0);
+        }
+
+        let payload = serde_json::to_vec(&json!({
+            "title": notification.title,
+            "body": notification.message,
+            "type": notification.notification_type,
+            "data": notification.data,
+        }))?;
+
+        let mut delivered = 0;
+        for subscription in subscriptions {
+            match self.push(vapid, &subscription, &payload).await {
+                Ok(true) => delivered += 1,
+                Ok(false) => {
+                    debug!("Push subscription {} expired, removing", subscription.id);
+                    self.db
+                        .prepare("DELETE type::thing($id)")
+                        .bind("id", &subscription.id)
+                        .execute()
+                        .await?;
+                }
+                Err(e) => warn!("Failed to push to subscription {}: {}", subscription.id, e),
+            }
+        }
+
+        Ok(delivered)
+    }
+
+    /// 发送一条加密消息。订阅已失效（404/410）时返回 `Ok(false)`
+    async fn push(&self, vapid: &VapidKeys, subscription: &PushSubscription, payload: &[u8]) -> Result<bool> {
+        let info = SubscriptionInfo::new(&subscription.endpoint, &subscription.p256dh, &subscription.auth);
+
+        let mut signature = VapidSignatureBuilder::from_base64(&vapid.private_key, &info)
+            .map_err(|e| AppError::Internal(format!("Invalid VAPID private key: {}", e)))?;
+        signature.add_claim("sub", vapid.subject.as_str());
+
+        let mut builder = WebPushMessageBuilder::new(&info);
+        builder.set_ttl(PUSH_TTL_SECS);
+        builder.set_payload(ContentEncoding::Aes128Gcm, payload);
+        builder.set_vapid_signature(
+            signature
+                .build()
+                .map_err(|e| AppError::Internal(format!("Failed to sign push message: {}", e)))?,
+        );
+        let message = builder
+            .build()
+            .map_err(|e| AppError::Internal(format!("Failed to build push message: {}", e)))?;
+
+        let mut request = self
+            .client
+            .post(message.endpoint.to_string())
+            .header("TTL", message.ttl.to_string());
+        if let Some(payload) = message.payload {
+            request = request
+                .header("Content-Encoding", payload.content_encoding.to_str())
+                .header("Content-Type", "application/octet-stream");
+            for (name, value) in payload.crypto_headers {
+                request = request.header(name, value);
+            }
+            request = request.body(payload.content);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AppError::ExternalService(format!("Push service request failed: {}", e)))?;
+
+        match response.status() {
+            status if status.is_success() => Ok(true),
+            StatusCode::NOT_FOUND | StatusCode::GONE => Ok(false),
+            status => {
+                let detail = response.text().await.unwrap_or_default();
+                Err(AppError::ExternalService(format!(
+                    "Push service rejected message ({}): {}",
+                    status, detail
+                )))
+            }
+        }
+    }
+}
+
+/// 订阅的记录 ID：推送地址的 SHA-256，同一浏览器重复订阅时覆盖原记录
+fn subscription_id(endpoint: &str) -> String {
+    hex::encode(Sha256::digest(endpoint.as_bytes()))
+}
@@ -0,0 +1,415 @@
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+    models::{
+        id::bare_id,
+        notification::{CreateNotificationRequest, NotificationType},
+        payment::ArticlePurchase,
+        stripe::{StripeWebhookEvent, WebhookDelivery},
+    },
+    services::{
+        database::PaginatedResult,
+        stripe::{StripeChargeReversal, StripeChargeReversalKind, StripeService, StripeWebhookOutcome},
+        ArticleService, Database, GiftService, NotificationService, PaymentService, RevenueService,
+    },
+};
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{debug, error, info, warn};
+
+/// 第一次重试前的等待秒数，之后每次翻倍
+const RETRY_BASE_DELAY_SECS: i64 = 60;
+/// 两次重试之间的最长间隔
+const RETRY_MAX_DELAY_SECS: i64 = 6 * 3600;
+/// 每轮最多重试的事件数
+const RETRY_BATCH_SIZE: usize = 50;
+
+#[derive(Debug, Deserialize)]
+struct CountRow {
+    total: usize,
+}
+
+/// Stripe Webhook 事件的处理和重试。事件的后续处理全部成功后才标记为已处理；
+/// 失败的事件保存错误并按指数退避重试，次数用尽后进入死信，等待管理员重新入队
+#[derive(Clone)]
+pub struct WebhookService {
+    db: Arc<Database>,
+    stripe_service: Arc<StripeService>,
+    payment_service: PaymentService,
+    revenue_service: RevenueService,
+    gift_service: GiftService,
+    article_service: ArticleService,
+    notification_service: NotificationService,
+    max_attempts: u32,
+}
+
+impl WebhookService {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn new(
+        db: Arc<Database>,
+        config: &Config,
+        stripe_service: Arc<StripeService>,
+        payment_service: PaymentService,
+        revenue_service: RevenueService,
+        gift_service: GiftService,
+        article_service: ArticleService,
+        notification_service: NotificationService,
+    ) -> Result<Self> {
+        Ok(Self {
+            db,
+            stripe_service,
+            payment_service,
+            revenue_service,
+            gift_service,
+            article_service,
+            notification_service,
+            max_attempts: config.webhook_max_attempts.max(1),
+        })
+    }
+
+    /// 处理一条已通过签名验证的事件。失败时保存错误并安排重试；
+    /// 连失败都无法记录时返回错误，交给 Stripe 重新投递
+    pub async fn handle_stripe_event(&self, event_data: Value) -> Result<WebhookDelivery> {
+        let stripe_event_id = event_data["id"]
+            .as_str()
+            .ok_or_else(|| AppError::BadRequest("Stripe webhook 缺少事件 ID".to_string()))?
+            .to_string();
+
+        let error = match self.process(event_data).await {
+            Ok(()) => return Ok(WebhookDelivery::Processed),
+            Err(e) => e,
+        };
+
+        error!("Failed to process Stripe webhook {}: {}", stripe_event_id, error);
+
+        match self.record_failure(&stripe_event_id, &error.to_string()).await {
+            Ok(Some(delivery)) => Ok(delivery),
+            Ok(None) => Err(error),
+            Err(record_error) => {
+                error!(
+                    "Failed to record webhook failure for {}: {}",
+                    stripe_event_id, record_error
+                );
+                Err(error)
+            }
+        }
+    }
+
+    /// 重试到期的失败事件，返回本轮处理成功的数量
+    pub async fn retry_failed_events(&self) -> Result<usize> {
+        let events: Vec<StripeWebhookEvent> = self
+            .db
+            .prepare(
+                r#"
+                SELECT * FROM webhook_event
+                WHERE processed = false AND dead_lettered_at = NONE AND next_retry_at <= time::now()
+                ORDER BY next_retry_at ASC
+                LIMIT $limit
+                "#,
+            )
+            .bind("limit", RETRY_BATCH_SIZE)
+            .fetch()
+            .await?;
+
+        let mut processed = 0;
+        for event in events {
+            debug!(
+                "Retrying Stripe webhook {} (attempt {})",
+                event.stripe_event_id,
+                event.attempts + 1
+            );
+            match self.handle_stripe_event(event.data).await {
+                Ok(WebhookDelivery::Processed) => processed += 1,
+                Ok(_) => {}
+                Err(e) => warn!("Retry of Stripe webhook {} failed: {}", event.stripe_event_id, e),
+            }
+        }
+
+        Ok(processed)
+    }
+
+    /// 死信中的事件，最近进入的在前
+    pub async fn list_dead_letters(
+        &self,
+        page: usize,
+        per_page: usize,
+    ) -> Result<PaginatedResult<StripeWebhookEvent>> {
+        let page = page.max(1);
+        let per_page = per_page.clamp(1, 100);
+        let offset = (page - 1) * per_page;
+
+        let mut response = self
+            .db
+            .prepare(
+                r#"
+                SELECT count() AS total FROM webhook_event
+                    WHERE processed = false AND dead_lettered_at != NONE GROUP ALL;
+                SELECT * FROM webhook_event
+                    WHERE processed = false AND dead_lettered_at != NONE
+                    ORDER BY dead_lettered_at DESC LIMIT $limit START $offset;
+                "#,
+            )
+            .bind("limit", per_page)
+            .bind("offset", offset)
+            .execute()
+            .await?;
+
+        let totals: Vec<CountRow> = response.take(0)?;
+        let total = totals.first().map_or(0, |t| t.total);
+        let data: Vec<StripeWebhookEvent> = response.take(1)?;
+
+        Ok(PaginatedResult {
+            data,
+            total,
+            page,
+            per_page,
+            total_pages: (total + per_page - 1) / per_page,
+        })
+    }
+
+    /// 把死信事件放回重试队列并重置尝试次数，下一轮重试时立即处理
+    pub async fn requeue_dead_letter(&self, event_id: &str) -> Result<StripeWebhookEvent> {
+        let event: Option<StripeWebhookEvent> = self
+            .db
+            .prepare(
+                r#"
+                UPDATE type::thing('webhook_event', $id) SET
+                    attempts = 0,
+                    dead_lettered_at = NONE,
+                    next_retry_at = time::now()
+                WHERE processed = false AND dead_lettered_at != NONE
+                RETURN AFTER
+                "#,
+            )
+            .bind("id", bare_id("webhook_event", event_id))
+            .fetch_one()
+            .await?;
+
+        let event = event.ok_or_else(|| AppError::NotFound("死信事件不存在".to_string()))?;
+        info!("Requeued dead-lettered Stripe webhook {}", event.stripe_event_id);
+        Ok(event)
+    }
+
+    async fn process(&self, event_data: Value) -> Result<()> {
+        let outcome = self.stripe_service.process_webhook_event(event_data).await?;
+        self.apply_outcome(&outcome).await?;
+        self.stripe_service.mark_webhook_event_processed(&outcome).await
+    }
+
+    /// 把 Stripe 事件的结果同步到购买、礼物、收益和订阅状态。各步骤本身可重复执行，
+    /// 部分成功后重试不会重复记账
+    async fn apply_outcome(&self, outcome: &StripeWebhookOutcome) -> Result<()> {
+        for purchase in &outcome.purchase_updates {
+            self.payment_service
+                .handle_stripe_purchase_success(purchase)
+                .await?;
+
+            self.revenue_service
+                .record_purchase_revenue_from_webhook(purchase)
+                .await?;
+        }
+
+        for payment in &outcome.gift_payments {
+            if let Some(gift) = self.gift_service.handle_gift_payment(payment).await? {
+                self.revenue_service
+                    .record_gift_revenue(&gift, &payment.tax)
+                    .await?;
+            }
+        }
+
+        for intent_id in &outcome.failed_payment_intents {
+            self.payment_service
+                .handle_stripe_purchase_failure(intent_id)
+                .await?;
+            self.gift_service
+                .handle_gift_payment_failure(intent_id)
+                .await?;
+        }
+
+        for reversal in &outcome.charge_reversals {
+            if let Some(purchase) = self
+                .payment_service
+                .handle_stripe_charge_reversal(reversal)
+                .await?
+            {
+                self.revenue_service
+                    .reverse_purchase_revenue(&purchase, reversal)
+                    .await?;
+
+                self.notify_charge_reversal(&purchase, reversal).await;
+                continue;
+            }
+
+            if let Some(gift) = self.gift_service.handle_gift_charge_reversal(reversal).await? {
+                self.revenue_service
+                    .reverse_gift_revenue(&gift, reversal)
+                    .await?;
+                continue;
+            }
+
+            // 既不是文章购买也不是礼物时按订阅发票处理：冲回该发票的收益并收回订阅权限
+            if let Some(reversed) = self
+                .revenue_service
+                .reverse_subscription_revenue(reversal)
+                .await?
+            {
+                self.payment_service
+                    .handle_subscription_charge_reversal(&reversed, reversal)
+                    .await?;
+            }
+        }
+
+        for revenue_event in &outcome.subscription_revenues {
+            self.revenue_service
+                .record_subscription_revenue_from_webhook(revenue_event)
+                .await?;
+        }
+
+        for status_update in &outcome.subscription_status_updates {
+            self.payment_service
+                .handle_subscription_status_update(status_update)
+                .await?;
+        }
+
+        if !outcome.subscription_status_updates.is_empty() {
+            debug!(
+                "同步 Stripe 订阅状态更新: {}",
+                outcome.subscription_status_updates.len()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// 记录一次处理失败：安排下一次重试，次数用尽后转入死信。
+    /// 事件还没有保存（如保存时数据库出错）时返回 `None`
+    async fn record_failure(
+        &self,
+        stripe_event_id: &str,
+        error: &str,
+    ) -> Result<Option<WebhookDelivery>> {
+        let event: Option<StripeWebhookEvent> = self
+            .db
+            .prepare("SELECT * FROM webhook_event WHERE stripe_event_id = $stripe_event_id LIMIT 1")
+            .bind("stripe_event_id", stripe_event_id)
+            .fetch_one()
+            .await?;
+
+        let Some(event) = event else {
+            return Ok(None);
+        };
+
+        let attempts = event.attempts + 1;
+        let delivery = if attempts >= self.max_attempts {
+            self.db
+                .prepare(
+                    r#"
+                    UPDATE type::thing('webhook_event', $id) SET
+                        attempts = $attempts,
+                        last_error = $error,
+                        next_retry_at = NONE,
+                        dead_lettered_at = time::now()
+                    "#,
+                )
+                .bind("id", bare_id("webhook_event", &event.id))
+                .bind("attempts", attempts)
+                .bind("error", error)
+                .execute()
+                .await?;
+
+            warn!(
+                "Stripe webhook {} ({}) moved to dead letter after {} attempts",
+                stripe_event_id, event.event_type, attempts
+            );
+            WebhookDelivery::DeadLettered
+        } else {
+            self.db
+                .prepare(
+                    r#"
+                    UPDATE type::thing('webhook_event', $id) SET
+                        attempts = $attempts,
+                        last_error = $error,
+                        next_retry_at = <datetime> $next_retry_at
+                    "#,
+                )
+                .bind("id", bare_id("webhook_event", &event.id))
+                .bind("attempts", attempts)
+                .bind("error", error)
+                .bind("next_retry_at", Utc::now() + retry_delay(attempts))
+                .execute()
+                .await?;
+
+            WebhookDelivery::QueuedForRetry
+        };
+
+        Ok(Some(delivery))
+    }
+
+    /// 通知买家和作者退款或争议结果。通知失败只记录日志，不影响事件处理
+    async fn notify_charge_reversal(&self, purchase: &ArticlePurchase, reversal: &StripeChargeReversal) {
+        let title = match self.article_service.get_article_by_id(&purchase.article_id).await {
+            Ok(Some(article)) => article.title,
+            _ => "an article".to_string(),
+        };
+        let amount = format!("{:.2} {}", reversal.amount as f64 / 100.0, reversal.currency);
+
+        let (buyer, creator) = match reversal.kind {
+            StripeChargeReversalKind::Refund if reversal.amount < purchase.charged_amount() => (
+                ("Partial refund processed", format!("You were refunded {} for \"{}\". You still have access to the article.", amount, title)),
+                ("Purchase partially refunded", format!("A purchase of \"{}\" was partially refunded ({}). Your earnings were adjusted.", title, amount)),
+            ),
+            StripeChargeReversalKind::Refund => (
+                ("Refund processed", format!("Your purchase of \"{}\" was refunded ({}). Access to the article has been removed.", title, amount)),
+                ("Purchase refunded", format!("A purchase of \"{}\" was refunded ({}). The earnings were reversed.", title, amount)),
+            ),
+            StripeChargeReversalKind::Dispute => (
+                ("Payment disputed", format!("A dispute was opened for your purchase of \"{}\". Access is suspended until it is resolved.", title)),
+                ("Purchase disputed", format!("A reader disputed their purchase of \"{}\" ({}). The earnings are on hold.", title, amount)),
+            ),
+            StripeChargeReversalKind::DisputeWon => (
+                ("Dispute resolved", format!("The dispute for \"{}\" was resolved and your access has been restored.", title)),
+                ("Dispute resolved", format!("The dispute for \"{}\" was resolved in your favor. The earnings were restored.", title)),
+            ),
+        };
+
+        for (recipient_id, (heading, message)) in [(&purchase.buyer_id, buyer), (&purchase.creator_id, creator)] {
+            let request = CreateNotificationRequest {
+                recipient_id: recipient_id.clone(),
+                notification_type: NotificationType::Payment,
+                title: heading.to_string(),
+                message,
+                data: serde_json::json!({
+                    "purchase_id": purchase.id,
+                    "article_id": purchase.article_id,
+                    "event": reversal.kind.as_str(),
+                    "amount": reversal.amount,
+                    "currency": reversal.currency,
+                }),
+            };
+            if let Err(e) = self.notification_service.create_notification(request).await {
+                warn!("Failed to send {} notification to {}: {}", reversal.kind.as_str(), recipient_id, e);
+            }
+        }
+    }
+}
+
+/// 第 n 次失败后的等待时间：60 秒起每次翻倍，最长 6 小时
+fn retry_delay(attempts: u32) -> Duration {
+    let exponent = attempts.saturating_sub(1).min(16);
+    Duration::seconds((RETRY_BASE_DELAY_SECS << exponent).min(RETRY_MAX_DELAY_SECS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_backoff() {
+        assert_eq!(retry_delay(1), Duration::seconds(60));
+        assert_eq!(retry_delay(2), Duration::seconds(120));
+        assert_eq!(retry_delay(4), Duration::seconds(480));
+        assert_eq!(retry_delay(20), Duration::seconds(RETRY_MAX_DELAY_SECS));
+    }
+}
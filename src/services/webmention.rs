@@ -0,0 +1,391 @@
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+    models::{
+        article::Article,
+        domain::PublicationDomain,
+        id::{bare_id, ArticleId},
+        plugin::ArticlePublishedEvent,
+        webmention::*,
+    },
+    services::{plugin::Plugin, Database},
+    utils::webmention as wm,
+};
+use async_trait::async_trait;
+use reqwest::{header, redirect, Client, StatusCode};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+use url::Url;
+
+/// 抓取来源页面或发现端点时读取的最大字节数
+const MAX_PAGE_BYTES: usize = 1024 * 1024;
+/// 每篇文章发布时最多通知的外部链接数
+const MAX_OUTGOING_LINKS: usize = 30;
+const MAX_REDIRECTS: usize = 5;
+
+/// 抓取来源页面的结果
+enum SourceCheck {
+    Verified { title: Option<String>, author_name: Option<String>, excerpt: Option<String> },
+    /// 来源页面已删除（410），对应的提及一并删除
+    Gone,
+    Rejected(String),
+}
+
+/// Webmention / Pingback：接收其他站点对文章的提及，并在文章发布时通知文中链接到的站点
+#[derive(Clone)]
+pub struct WebmentionService {
+    db: Arc<Database>,
+    config: Config,
+    http_client: Client,
+}
+
+impl WebmentionService {
+    pub async fn new(db: Arc<Database>, config: &Config) -> Result<Self> {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .user_agent("Rainbow-Blog Webmention")
+            // 重定向同样不能指向内网地址
+            .redirect(redirect::Policy::custom(|attempt| {
+                if attempt.previous().len() >= MAX_REDIRECTS || !wm::is_public_url(attempt.url()) {
+                    attempt.stop()
+                } else {
+                    attempt.follow()
+                }
+            }))
+            .build()
+            .map_err(|e| AppError::Internal(format!("Failed to create HTTP client: {}", e)))?;
+
+        Ok(Self {
+            db,
+            config: config.clone(),
+            http_client,
+        })
+    }
+
+    /// 记录一条提及，同一来源对同一篇文章的重复通知会重新验证
+    pub async fn receive(
+        &self,
+        source: &str,
+        target: &str,
+        article_id: &str,
+        protocol: MentionProtocol,
+    ) -> Result<Webmention> {
+        let source_url = Url::parse(source)
+            .map_err(|_| AppError::BadRequest("source must be an absolute URL".to_string()))?;
+        if !wm::is_public_url(&source_url) {
+            return Err(AppError::BadRequest("source must be a public http(s) URL".to_string()));
+        }
+        if source == target {
+            return Err(AppError::BadRequest("source and target must be different".to_string()));
+        }
+
+        let article_id = ArticleId::new(article_id);
+        let mention: Option<Webmention> = self.db
+            .prepare(
+                r#"
+                UPSERT type::thing('webmention', $id) SET
+                    article_id = $article_id,
+                    source = $source,
+                    target = $target,
+                    protocol = $protocol,
+                    status = IF status = 'verified' THEN status ELSE 'pending' END,
+                    created_at = created_at ?? time::now(),
+                    updated_at = time::now()
+                "#,
+            )
+            .bind("id", mention_key(article_id.as_str(), source))
+            .bind("article_id", article_id.as_str())
+            .bind("source", source)
+            .bind("target", target)
+            .bind("protocol", protocol.as_str())
+            .fetch_one()
+            .await?;
+
+        mention.ok_or_else(|| AppError::Internal("Failed to record webmention".to_string()))
+    }
+
+    /// 抓取来源页面，确认其中链接到了目标文章
+    pub async fn verify(&self, mention: &Webmention) -> Result<MentionStatus> {
+        let id = bare_id("webmention", &mention.id);
+        let check = self.check_source(&mention.source, &mention.target).await;
+
+        let status = match check {
+            SourceCheck::Gone => {
+                self.delete_mention(&mention.id).await?;
+                debug!("Removed webmention from {} (source gone)", mention.source);
+                return Ok(MentionStatus::Rejected);
+            }
+            SourceCheck::Verified { title, author_name, excerpt } => {
+                self.db
+                    .prepare(
+                        r#"
+                        UPDATE type::thing('webmention', $id) SET
+                            status = 'verified',
+                            title = $title,
+                            author_name = $author_name,
+                            excerpt = $excerpt,
+                            error = NONE,
+                            verified_at = verified_at ?? time::now(),
+                            updated_at = time::now()
+                        "#,
+                    )
+                    .bind("id", id)
+                    .bind("title", title)
+                    .bind("author_name", author_name)
+                    .bind("excerpt", excerpt)
+                    .execute()
+                    .await?;
+                MentionStatus::Verified
+            }
+            SourceCheck::Rejected(reason) => {
+                self.db
+                    .prepare("UPDATE type::thing('webmention', $id) SET status = 'rejected', error = $error, updated_at = time::now()")
+                    .bind("id", id)
+                    .bind("error", &reason)
+                    .execute()
+                    .await?;
+                debug!("Rejected webmention from {}: {}", mention.source, reason);
+                MentionStatus::Rejected
+            }
+        };
+
+        info!("Webmention from {} to article {} is {}", mention.source, mention.article_id, status.as_str());
+        Ok(status)
+    }
+
+    /// 记录并在后台验证（Webmention 规范要求异步处理）
+    pub async fn receive_async(&self, source: &str, target: &str, article_id: &str) -> Result<Webmention> {
+        let mention = self.receive(source, target, article_id, MentionProtocol::Webmention).await?;
+
+        let service = self.clone();
+        let pending = mention.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.verify(&pending).await {
+                warn!("Failed to verify webmention from {}: {}", pending.source, e);
+            }
+        });
+
+        Ok(mention)
+    }
+
+    /// 文章已验证的提及，按时间先后排列
+    pub async fn list_for_article(&self, article_id: &str) -> Result<Vec<Webmention>> {
+        self.db
+            .prepare("SELECT * FROM webmention WHERE article_id = $article_id AND status = 'verified' ORDER BY verified_at ASC")
+            .bind("article_id", ArticleId::new(article_id).as_str())
+            .fetch()
+            .await
+    }
+
+    pub async fn get_mention(&self, mention_id: &str) -> Result<Option<Webmention>> {
+        self.db.get_by_id("webmention", bare_id("webmention", mention_id)).await
+    }
+
+    /// 删除一条提及（文章作者处理垃圾提及）
+    pub async fn delete_mention(&self, mention_id: &str) -> Result<()> {
+        self.db
+            .prepare("DELETE type::thing('webmention', $id)")
+            .bind("id", bare_id("webmention", mention_id))
+            .execute()
+            .await?;
+        Ok(())
+    }
+
+    async fn check_source(&self, source: &str, target: &str) -> SourceCheck {
+        let (Ok(source_url), Ok(target_url)) = (Url::parse(source), Url::parse(target)) else {
+            return SourceCheck::Rejected("Invalid source or target URL".to_string());
+        };
+
+        let (status, final_url, _, html) = match self.fetch_page(&source_url).await {
+            Ok(page) => page,
+            Err(e) => return SourceCheck::Rejected(format!("Failed to fetch source: {}", e)),
+        };
+
+        if status == StatusCode::GONE {
+            return SourceCheck::Gone;
+        }
+        if !status.is_success() {
+            return SourceCheck::Rejected(format!("Source returned HTTP {}", status.as_u16()));
+        }
+        if !wm::links_to(&html, &final_url, &target_url) {
+            return SourceCheck::Rejected("Source does not link to target".to_string());
+        }
+
+        SourceCheck::Verified {
+            title: wm::page_title(&html),
+            author_name: wm::page_author(&html),
+            excerpt: wm::excerpt_around(&html, &final_url, &target_url),
+        }
+    }
+
+    /// GET 页面，返回状态码、最终地址、Link 响应头和（截断后的）正文
+    async fn fetch_page(&self, url: &Url) -> Result<(StatusCode, Url, Vec<String>, String)> {
+        if !wm::is_public_url(url) {
+            return Err(AppError::BadRequest(format!("{} is not a public URL", url)));
+        }
+
+        let mut response = self.http_client
+            .get(url.clone())
+            .header(header::ACCEPT, "text/html, */*;q=0.5")
+            .send()
+            .await?;
+        let status = response.status();
+        let final_url = response.url().clone();
+        let links = link_headers(response.headers());
+
+        let is_html = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.contains("html"))
+            .unwrap_or(true);
+        let mut body = Vec::new();
+        if is_html {
+            while let Some(chunk) = response.chunk().await? {
+                body.extend_from_slice(&chunk);
+                if body.len() >= MAX_PAGE_BYTES {
+                    body.truncate(MAX_PAGE_BYTES);
+                    break;
+                }
+            }
+        }
+
+        Ok((status, final_url, links, String::from_utf8_lossy(&body).into_owned()))
+    }
+
+    /// 文章的公开地址：出版物已启用的域名（优先主域名），否则为平台地址
+    pub async fn article_url(&self, article: &Article) -> Result<String> {
+        if let Some(publication_id) = &article.publication_id {
+            let domains: Vec<PublicationDomain> = self.db
+                .prepare("SELECT * FROM publication_domain WHERE publication_id = $publication_id AND status = 'active' ORDER BY is_primary DESC")
+                .bind("publication_id", publication_id)
+                .fetch()
+                .await?;
+            if let Some(domain) = domains.iter().find(|d| !d.is_wildcard()) {
+                return Ok(format!("{}/articles/{}", domain.get_full_url(true), article.slug));
+            }
+        }
+
+        Ok(format!("{}/articles/{}", self.config.frontend_url.trim_end_matches('/'), article.slug))
+    }
+
+    /// 向文章中链接到的外部页面发送 Webmention，对方不支持时尝试 Pingback
+    pub async fn send_for_article(&self, article_id: &str) -> Result<usize> {
+        let article: Article = self.db
+            .get_by_id("article", ArticleId::new(article_id).as_str())
+            .await?
+            .ok_or_else(|| AppError::NotFound("Article not found".to_string()))?;
+        if !article.is_published() {
+            return Ok(0);
+        }
+
+        let source = self.article_url(&article).await?;
+        let source_url = Url::parse(&source)
+            .map_err(|e| AppError::Internal(format!("Invalid article URL {}: {}", source, e)))?;
+
+        let targets: Vec<Url> = wm::extract_links(&article.content_html, &source_url)
+            .into_iter()
+            .filter(|link| link.host_str() != source_url.host_str() && wm::is_public_url(link))
+            .take(MAX_OUTGOING_LINKS)
+            .collect();
+
+        let mut sent = 0;
+        for target in targets {
+            match self.notify(&source, &target).await {
+                Ok(true) => sent += 1,
+                Ok(false) => debug!("{} does not accept webmentions or pingbacks", target),
+                Err(e) => warn!("Failed to send webmention to {}: {}", target, e),
+            }
+        }
+
+        if sent > 0 {
+            info!("Sent {} webmentions for article {}", sent, article_id);
+        }
+        Ok(sent)
+    }
+
+    /// 通知单个目标页面，返回对方是否支持
+    async fn notify(&self, source: &str, target: &Url) -> Result<bool> {
+        let (status, final_url, links, html) = self.fetch_page(target).await?;
+        if !status.is_success() {
+            return Ok(false);
+        }
+
+        if let Some(endpoint) = wm::discover_endpoint(&links, &html, &final_url, "webmention") {
+            if !wm::is_public_url(&endpoint) {
+                return Ok(false);
+            }
+            self.http_client
+                .post(endpoint)
+                .form(&[("source", source), ("target", target.as_str())])
+                .send()
+                .await?
+                .error_for_status()?;
+            return Ok(true);
+        }
+
+        // Pingback 端点通过 X-Pingback 头或 <link rel="pingback"> 公布
+        let pingback = self
+            .http_client
+            .head(target.clone())
+            .send()
+            .await
+            .ok()
+            .and_then(|r| r.headers().get("X-Pingback").and_then(|v| v.to_str().ok()).map(String::from))
+            .and_then(|v| final_url.join(&v).ok())
+            .or_else(|| wm::discover_endpoint(&[], &html, &final_url, "pingback"));
+        let Some(endpoint) = pingback.filter(wm::is_public_url) else {
+            return Ok(false);
+        };
+
+        let response = self.http_client
+            .post(endpoint)
+            .header(header::CONTENT_TYPE, "text/xml")
+            .body(wm::pingback_request(source, target.as_str()))
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        if wm::is_pingback_fault(&response) {
+            return Err(AppError::ExternalService(format!("Pingback to {} was refused", target)));
+        }
+
+        Ok(true)
+    }
+}
+
+/// 同一来源对同一篇文章只保留一条记录
+fn mention_key(article_id: &str, source: &str) -> String {
+    let digest = Sha256::digest(format!("{}|{}", article_id, source).as_bytes());
+    hex::encode(&digest[..16])
+}
+
+fn link_headers(headers: &header::HeaderMap) -> Vec<String> {
+    headers
+        .get_all(header::LINK)
+        .iter()
+        .filter_map(|v| v.to_str().ok().map(String::from))
+        .collect()
+}
+
+/// 文章发布后在后台发送 Webmention，不占用插件钩子的超时
+#[async_trait]
+impl Plugin for WebmentionService {
+    fn name(&self) -> &str {
+        "webmention"
+    }
+
+    async fn on_article_published(&self, event: &ArticlePublishedEvent) -> Result<()> {
+        let service = self.clone();
+        let article_id = event.article_id.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.send_for_article(&article_id).await {
+                warn!("Failed to send webmentions for article {}: {}", article_id, e);
+            }
+        });
+        Ok(())
+    }
+}
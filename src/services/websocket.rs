@@ -1,12 +1,13 @@
 use crate::{
     error::{AppError, Result},
-    models::websocket::*,
+    models::{id::bare_id, websocket::*},
     services::Database,
 };
 use chrono::{DateTime, Utc};
+use serde::Deserialize;
 use serde_json::{json, Value};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     sync::{Arc, RwLock},
 };
 use tokio::sync::{broadcast, mpsc};
@@ -14,6 +15,11 @@ use tracing::{debug, info, warn, error};
 use axum::extract::ws::{WebSocket, Message};
 use futures::{sink::SinkExt, stream::StreamExt};
 
+/// 事件缓冲区最多保留的事件数
+const REPLAY_BUFFER_CAPACITY: usize = 10_000;
+/// 事件缓冲区保留的时长（秒），断线超过这个时间的客户端需要重新加载
+const REPLAY_WINDOW_SECS: i64 = 600;
+
 /// WebSocket连接管理器
 #[derive(Clone)]
 pub struct WebSocketService {
@@ -28,6 +34,120 @@ pub struct WebSocketService {
     broadcast_tx: broadcast::Sender<WebSocketMessage>,
     // 消息队列发送端
     message_queue_tx: mpsc::UnboundedSender<MessageQueueItem>,
+    // 最近发给用户和频道的事件，用于断线重连后补发
+    event_buffer: Arc<RwLock<EventBuffer>>,
+    // 文章ID到正在阅读的读者
+    presence: Arc<RwLock<HashMap<String, ArticleReaders>>>,
+}
+
+/// 最近事件的环形缓冲区。游标为 `{epoch}-{sequence}`，服务重启后 epoch 改变，旧游标随之失效
+#[derive(Debug)]
+struct EventBuffer {
+    epoch: String,
+    last_sequence: u64,
+    events: VecDeque<BufferedEvent>,
+}
+
+#[derive(Debug, Clone)]
+struct BufferedEvent {
+    sequence: u64,
+    user_id: Option<String>,
+    channel: Option<String>,
+    message: WebSocketMessage,
+}
+
+impl EventBuffer {
+    fn new() -> Self {
+        Self {
+            epoch: uuid::Uuid::new_v4().simple().to_string()[..12].to_string(),
+            last_sequence: 0,
+            events: VecDeque::new(),
+        }
+    }
+
+    fn cursor(&self, sequence: u64) -> String {
+        format!("{}-{}", self.epoch, sequence)
+    }
+
+    /// 记录事件并把游标写入 `metadata.event_id`
+    fn push(&mut self, user_id: Option<&str>, channel: Option<&str>, message: &mut WebSocketMessage) {
+        self.last_sequence += 1;
+        message.metadata.insert("event_id".to_string(), self.cursor(self.last_sequence));
+
+        self.events.push_back(BufferedEvent {
+            sequence: self.last_sequence,
+            user_id: user_id.map(str::to_string),
+            channel: channel.map(str::to_string),
+            message: message.clone(),
+        });
+
+        let expires_before = Utc::now() - chrono::Duration::seconds(REPLAY_WINDOW_SECS);
+        while self.events.len() > REPLAY_BUFFER_CAPACITY
+            || self.events.front().map_or(false, |e| e.message.timestamp < expires_before)
+        {
+            self.events.pop_front();
+        }
+    }
+
+    /// 游标之后发给该用户或这些频道的事件
+    fn since(&self, cursor: &str, user_id: &str, channels: &HashSet<String>) -> MissedEvents {
+        let current = self.cursor(self.last_sequence);
+        let after = cursor
+            .rsplit_once('-')
+            .filter(|(epoch, _)| *epoch == self.epoch)
+            .and_then(|(_, sequence)| sequence.parse::<u64>().ok())
+            .filter(|sequence| *sequence <= self.last_sequence);
+
+        let Some(after) = after else {
+            return MissedEvents { events: Vec::new(), cursor: current, reset: true };
+        };
+
+        // 缓冲区中最早的事件之前还有事件被丢弃
+        let oldest = self.events.front().map_or(self.last_sequence + 1, |e| e.sequence);
+        let events = self
+            .events
+            .iter()
+            .filter(|e| e.sequence > after)
+            .filter(|e| {
+                e.user_id.as_deref() == Some(user_id)
+                    || e.channel.as_ref().map_or(false, |c| channels.contains(c))
+            })
+            .map(|e| e.message.clone())
+            .collect();
+
+        MissedEvents { events, cursor: current, reset: after + 1 < oldest }
+    }
+}
+
+/// 一篇文章的在线读者。按用户记录连接数，同一读者打开多个页面只算一次
+#[derive(Debug, Clone)]
+struct ArticleReaders {
+    author_id: String,
+    publication_id: Option<String>,
+    readers: HashMap<String, usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PresenceTarget {
+    author_id: String,
+    publication_id: Option<String>,
+}
+
+/// 在线阅读频道对应的文章ID
+fn presence_article_id(channel: &str) -> Option<&str> {
+    channel
+        .strip_prefix("article_presence:")
+        .map(|id| bare_id("article", id))
+}
+
+/// 用户始终可以补发的个人频道
+fn personal_channels(user_id: &str) -> HashSet<String> {
+    [
+        ChannelType::UserNotifications.channel_name(user_id),
+        ChannelType::UserActivity.channel_name(user_id),
+    ]
+    .into_iter()
+    .collect()
 }
 
 /// 连接信息
@@ -53,6 +173,8 @@ impl WebSocketService {
             channel_subscriptions: Arc::new(RwLock::new(HashMap::new())),
             broadcast_tx,
             message_queue_tx,
+            event_buffer: Arc::new(RwLock::new(EventBuffer::new())),
+            presence: Arc::new(RwLock::new(HashMap::new())),
         };
 
         // 启动消息队列处理器
@@ -78,12 +200,13 @@ impl WebSocketService {
         Ok(service)
     }
 
-    /// 处理新的WebSocket连接
+    /// 处理新的WebSocket连接。重连时带上 `last_event_id`，补发断线期间的个人事件
     pub async fn handle_connection(
         &self,
         websocket: WebSocket,
         user_id: String,
         connection_id: String,
+        last_event_id: Option<String>,
     ) -> Result<()> {
         info!("New WebSocket connection: {} for user: {}", connection_id, user_id);
 
@@ -103,12 +226,28 @@ impl WebSocketService {
         // 注册连接
         self.register_connection(connection_info).await;
 
+        // 断线期间错过的事件
+        let missed = match &last_event_id {
+            Some(cursor) => {
+                let channels = personal_channels(&user_id);
+                Some(self.event_buffer.read().unwrap().since(cursor, &user_id, &channels))
+            }
+            None => None,
+        };
+        let resume_token = missed
+            .as_ref()
+            .map(|m| m.cursor.clone())
+            .unwrap_or_else(|| self.current_cursor());
+
         // 发送连接确认消息
         let connect_msg = WebSocketMessage::new(
             WebSocketMessageType::Connect,
             json!({
                 "connection_id": connection_id,
                 "user_id": user_id,
+                "event_id": resume_token,
+                "replayed": missed.as_ref().map_or(0, |m| m.events.len()),
+                "reset": missed.as_ref().map_or(false, |m| m.reset),
                 "timestamp": Utc::now()
             })
         );
@@ -117,6 +256,12 @@ impl WebSocketService {
             error!("Failed to send connect message: {}", e);
         }
 
+        for message in missed.into_iter().flat_map(|m| m.events) {
+            if tx.send(message).is_err() {
+                break;
+            }
+        }
+
         // 处理发送消息任务
         let connection_id_clone = connection_id.clone();
         let send_task = tokio::spawn(async move {
@@ -230,6 +375,9 @@ impl WebSocketService {
 
         // 清理频道订阅
         for channel in subscriptions {
+            if let Some(article_id) = presence_article_id(&channel) {
+                self.leave_presence(article_id, user_id).await;
+            }
             self.unsubscribe_from_channel(connection_id, &channel).await;
         }
 
@@ -301,16 +449,27 @@ impl WebSocketService {
             }
         }
 
+        // 只补发新订阅频道的事件，个人事件已在连接时补发
+        let missed = subscribe_req.last_event_id.as_deref().map(|cursor| {
+            let channels: HashSet<String> = subscribed_channels.iter().cloned().collect();
+            self.event_buffer.read().unwrap().since(cursor, "", &channels)
+        });
+
         // 发送订阅确认
         let ack_msg = WebSocketMessage::new(
             WebSocketMessageType::SubscribeAck,
             json!({
                 "subscribed_channels": subscribed_channels,
+                "replayed": missed.as_ref().map_or(0, |m| m.events.len()),
+                "reset": missed.as_ref().map_or(false, |m| m.reset),
                 "timestamp": Utc::now()
             })
         );
 
         self.send_to_connection(connection_id, ack_msg).await?;
+        for message in missed.into_iter().flat_map(|m| m.events) {
+            self.send_to_connection(connection_id, message).await?;
+        }
         Ok(())
     }
 
@@ -343,12 +502,13 @@ impl WebSocketService {
     /// 订阅频道
     async fn subscribe_to_channel(&self, connection_id: &str, channel: &str) {
         // 更新连接的订阅列表
-        {
+        let joined_by = {
             let mut connections = self.connections.write().unwrap();
-            if let Some(conn) = connections.get_mut(connection_id) {
-                conn.subscriptions.insert(channel.to_string());
-            }
-        }
+            connections
+                .get_mut(connection_id)
+                .filter(|conn| conn.subscriptions.insert(channel.to_string()))
+                .map(|conn| conn.user_id.clone())
+        };
 
         // 更新频道订阅列表
         {
@@ -359,17 +519,28 @@ impl WebSocketService {
                 .insert(connection_id.to_string());
         }
 
+        if let (Some(article_id), Some(user_id)) = (presence_article_id(channel), joined_by) {
+            if let Err(e) = self.join_presence(article_id, &user_id).await {
+                warn!("Failed to track presence on article {}: {}", article_id, e);
+            }
+        }
+
         debug!("Connection {} subscribed to channel: {}", connection_id, channel);
     }
 
     /// 取消订阅频道
     async fn unsubscribe_from_channel(&self, connection_id: &str, channel: &str) {
         // 更新连接的订阅列表
-        {
+        let left_by = {
             let mut connections = self.connections.write().unwrap();
-            if let Some(conn) = connections.get_mut(connection_id) {
-                conn.subscriptions.remove(channel);
-            }
+            connections
+                .get_mut(connection_id)
+                .filter(|conn| conn.subscriptions.remove(channel))
+                .map(|conn| conn.user_id.clone())
+        };
+
+        if let (Some(article_id), Some(user_id)) = (presence_article_id(channel), left_by) {
+            self.leave_presence(article_id, &user_id).await;
         }
 
         // 更新频道订阅列表
@@ -394,6 +565,10 @@ impl WebSocketService {
             return Ok(channel_user_id == user_id);
         }
 
+        if let Some(article_id) = presence_article_id(channel) {
+            return Ok(self.presence_target(article_id).await?.is_some());
+        }
+
         if channel.starts_with("creator_revenue:") {
             let channel_creator_id = channel.split(':').nth(1).unwrap_or("");
             // TODO: 检查用户是否为该创作者
@@ -431,8 +606,14 @@ impl WebSocketService {
         Ok(())
     }
 
-    /// 发送消息到用户的所有连接
-    pub async fn send_to_user(&self, user_id: &str, message: WebSocketMessage) -> Result<()> {
+    /// 发送消息到用户的所有连接，用户离线时事件仍进入缓冲区等待补发
+    pub async fn send_to_user(&self, user_id: &str, mut message: WebSocketMessage) -> Result<()> {
+        self.event_buffer.write().unwrap().push(Some(user_id), None, &mut message);
+        self.deliver_to_user(user_id, message).await;
+        Ok(())
+    }
+
+    async fn deliver_to_user(&self, user_id: &str, message: WebSocketMessage) {
         let connection_ids = {
             let user_connections = self.user_connections.read().unwrap();
             user_connections.get(user_id).cloned()
@@ -445,11 +626,16 @@ impl WebSocketService {
                 }
             }
         }
-        Ok(())
     }
 
     /// 广播消息到频道
-    pub async fn broadcast_to_channel(&self, channel: &str, message: WebSocketMessage) -> Result<()> {
+    pub async fn broadcast_to_channel(&self, channel: &str, mut message: WebSocketMessage) -> Result<()> {
+        self.event_buffer.write().unwrap().push(None, Some(channel), &mut message);
+        self.deliver_to_channel(channel, message).await;
+        Ok(())
+    }
+
+    async fn deliver_to_channel(&self, channel: &str, message: WebSocketMessage) {
         let subscribers = {
             let channel_subscriptions = self.channel_subscriptions.read().unwrap();
             channel_subscriptions.get(channel).cloned()
@@ -464,9 +650,161 @@ impl WebSocketService {
                 }
             }
         }
+    }
+
+    /// 已发布文章的作者和出版物，文章不存在或未发布时返回 None
+    async fn presence_target(&self, article_id: &str) -> Result<Option<PresenceTarget>> {
+        self.db
+            .prepare(
+                "SELECT author_id, publication_id FROM type::thing('article', $id) WHERE status = 'published' AND is_deleted = false",
+            )
+            .bind("id", article_id)
+            .fetch_one()
+            .await
+    }
+
+    /// 读者开始阅读文章。作者查看自己的文章不计入
+    async fn join_presence(&self, article_id: &str, user_id: &str) -> Result<()> {
+        let cached = self
+            .presence
+            .read()
+            .unwrap()
+            .get(article_id)
+            .map(|r| (r.author_id.clone(), r.publication_id.clone()));
+        let (author_id, publication_id) = match cached {
+            Some(target) => target,
+            None => match self.presence_target(article_id).await? {
+                Some(target) => (target.author_id, target.publication_id),
+                None => return Ok(()),
+            },
+        };
+        if author_id == user_id {
+            return Ok(());
+        }
+
+        let joined = {
+            let mut presence = self.presence.write().unwrap();
+            let entry = presence
+                .entry(article_id.to_string())
+                .or_insert_with(|| ArticleReaders {
+                    author_id: author_id.clone(),
+                    publication_id: publication_id.clone(),
+                    readers: HashMap::new(),
+                });
+            let connections = entry.readers.entry(user_id.to_string()).or_insert(0);
+            *connections += 1;
+            *connections == 1
+        };
+
+        if joined {
+            self.notify_presence(article_id, &author_id, publication_id.as_deref()).await;
+        }
         Ok(())
     }
 
+    /// 读者关闭文章，最后一个连接离开时才减少读者数
+    async fn leave_presence(&self, article_id: &str, user_id: &str) {
+        let left = {
+            let mut presence = self.presence.write().unwrap();
+            let Some(entry) = presence.get_mut(article_id) else {
+                return;
+            };
+            let Some(connections) = entry.readers.get_mut(user_id) else {
+                return;
+            };
+
+            *connections -= 1;
+            if *connections > 0 {
+                None
+            } else {
+                entry.readers.remove(user_id);
+                let target = (entry.author_id.clone(), entry.publication_id.clone());
+                if entry.readers.is_empty() {
+                    presence.remove(article_id);
+                }
+                Some(target)
+            }
+        };
+
+        if let Some((author_id, publication_id)) = left {
+            self.notify_presence(article_id, &author_id, publication_id.as_deref()).await;
+        }
+    }
+
+    /// 读者数变化时通知文章频道、出版物频道和作者本人。在线状态变化频繁，不进入补发缓冲区
+    async fn notify_presence(&self, article_id: &str, author_id: &str, publication_id: Option<&str>) {
+        let mut data = json!({
+            "article_id": article_id,
+            "reader_count": self.article_reader_count(article_id),
+        });
+
+        let article_channel = ChannelType::ArticlePresence.channel_name(article_id);
+        let message = WebSocketMessage::broadcast(
+            WebSocketMessageType::PresenceUpdate,
+            article_channel.clone(),
+            data.clone(),
+        );
+        self.deliver_to_channel(&article_channel, message).await;
+
+        if let Some(publication_id) = publication_id {
+            data["publication_id"] = json!(publication_id);
+            data["publication_readers"] = json!(self.publication_presence(publication_id).active_readers);
+
+            let publication_channel = ChannelType::PublicationPresence.channel_name(publication_id);
+            let message = WebSocketMessage::broadcast(
+                WebSocketMessageType::PresenceUpdate,
+                publication_channel.clone(),
+                data.clone(),
+            );
+            self.deliver_to_channel(&publication_channel, message).await;
+        }
+
+        let mut message = WebSocketMessage::new(WebSocketMessageType::PresenceUpdate, data);
+        message.to_user_id = Some(author_id.to_string());
+        self.deliver_to_user(author_id, message).await;
+    }
+
+    /// 正在阅读文章的人数
+    pub fn article_reader_count(&self, article_id: &str) -> i64 {
+        self.presence
+            .read()
+            .unwrap()
+            .get(bare_id("article", article_id))
+            .map_or(0, |r| r.readers.len() as i64)
+    }
+
+    /// 作者所有文章的在线读者
+    pub fn author_presence(&self, author_id: &str) -> PresenceSummary {
+        self.presence_summary(|r| r.author_id == author_id)
+    }
+
+    /// 出版物所有文章的在线读者
+    pub fn publication_presence(&self, publication_id: &str) -> PresenceSummary {
+        self.presence_summary(|r| r.publication_id.as_deref() == Some(publication_id))
+    }
+
+    fn presence_summary(&self, filter: impl Fn(&ArticleReaders) -> bool) -> PresenceSummary {
+        let presence = self.presence.read().unwrap();
+        let mut readers = HashSet::new();
+        let mut articles: Vec<ArticlePresence> = presence
+            .iter()
+            .filter(|(_, r)| filter(r))
+            .map(|(article_id, r)| {
+                readers.extend(r.readers.keys());
+                ArticlePresence {
+                    article_id: article_id.clone(),
+                    reader_count: r.readers.len() as i64,
+                }
+            })
+            .collect();
+        articles.sort_by(|a, b| b.reader_count.cmp(&a.reader_count));
+
+        PresenceSummary {
+            active_readers: readers.len() as i64,
+            articles,
+        }
+    }
+
     /// 处理Ping
     async fn handle_ping(&self, connection_id: &str, _data: Vec<u8>) {
         self.update_last_ping(connection_id).await;
@@ -520,6 +858,38 @@ impl WebSocketService {
         Ok(())
     }
 
+    /// 游标之后错过的事件：发给用户本人的事件，以及用户有权订阅的 `channels` 中的广播。
+    /// 没有游标时只返回当前游标
+    pub async fn missed_events(
+        &self,
+        user_id: &str,
+        cursor: Option<&str>,
+        channels: &[String],
+    ) -> Result<MissedEvents> {
+        let Some(cursor) = cursor else {
+            return Ok(MissedEvents {
+                events: Vec::new(),
+                cursor: self.current_cursor(),
+                reset: false,
+            });
+        };
+
+        let mut allowed = personal_channels(user_id);
+        for channel in channels {
+            if self.can_subscribe_to_channel(user_id, channel).await? {
+                allowed.insert(channel.clone());
+            }
+        }
+
+        Ok(self.event_buffer.read().unwrap().since(cursor, user_id, &allowed))
+    }
+
+    /// 最新事件的游标
+    pub fn current_cursor(&self) -> String {
+        let buffer = self.event_buffer.read().unwrap();
+        buffer.cursor(buffer.last_sequence)
+    }
+
     /// 获取在线统计
     pub async fn get_stats(&self) -> WebSocketStats {
         let connections = self.connections.read().unwrap();
@@ -595,4 +965,29 @@ mod tests {
     fn test_channel_authorization() {
         // TODO: 添加频道权限测试
     }
+
+    #[test]
+    fn test_event_buffer_replay() {
+        let mut buffer = EventBuffer::new();
+        let start = buffer.cursor(buffer.last_sequence);
+
+        let mut own = WebSocketMessage::notification(json!({}), "user_1".to_string());
+        buffer.push(Some("user_1"), None, &mut own);
+        let mut other = WebSocketMessage::notification(json!({}), "user_2".to_string());
+        buffer.push(Some("user_2"), None, &mut other);
+        let mut comment = WebSocketMessage::broadcast(WebSocketMessageType::NewComment, "article_comments:a1".to_string(), json!({}));
+        buffer.push(None, Some("article_comments:a1"), &mut comment);
+
+        let channels: HashSet<String> = ["article_comments:a1".to_string()].into_iter().collect();
+        let missed = buffer.since(&start, "user_1", &channels);
+        assert!(!missed.reset);
+        assert_eq!(missed.events.len(), 2);
+        assert_eq!(missed.events[0].metadata.get("event_id"), own.metadata.get("event_id"));
+
+        let after_own = own.metadata["event_id"].clone();
+        assert_eq!(buffer.since(&after_own, "user_1", &HashSet::new()).events.len(), 0);
+
+        // 其他进程或重启前的游标
+        assert!(buffer.since("abcdef123456-1", "user_1", &channels).reset);
+    }
 }
\ No newline at end of file
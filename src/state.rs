@@ -1,6 +1,10 @@
 use crate::{
     config::Config,
+    error::{AppError, Result},
+    models::stripe::StripeConfig,
     services::{
+        capabilities::{ArticleStore, Payments, SearchBackend},
+        registry::ServiceRegistry,
         database::Database,
         auth::AuthService,
         article::ArticleService,
@@ -23,8 +27,62 @@ use crate::{
         websocket::WebSocketService,
         realtime::RealtimeService,
         domain::{DomainService, DomainConfig},
+        reading_queue::ReadingQueueService,
+        assist::AssistService,
+        cover_image::CoverImageService,
+        reputation::ReputationService,
+        anomaly::AnomalyService,
+        plugin::{Plugin, PluginManager},
+        content_transform::ContentTransformService,
+        lifecycle::LifecycleService,
+        live_query::LiveQueryService,
+        import::ImportService,
+        popularity::PopularityService,
+        newsletter::NewsletterService,
+        webmention::WebmentionService,
+        cors::CorsService,
+        bot_detection::BotDetectionService,
+        milestone::MilestoneService,
+        syndication::OutboundSyndicationService,
+        reaction::ReactionService,
+        collaborator::CollaboratorService,
+        social_share::SocialShareService,
+        collaborative_edit::CollaborativeEditingService,
+        template::TemplateService,
+        reading_progress::ReadingProgressService,
+        style_guide::StyleGuideService,
+        homepage::HomepageService,
+        theme::ThemeService,
+        page::PageService,
+        meter::MeterService,
+        attachment::AttachmentService,
+        view_tracking::ViewTrackingService,
+        geoip::GeoIpService,
+        sponsorship::SponsorshipService,
+        cta::CtaService,
+        pseudonym::PseudonymService,
+        db_health::DbHealthService,
+        experiment::ExperimentService,
+        suggest::SuggestService,
+        embedding::EmbeddingService,
+        gift::GiftService,
+        webhook::WebhookService,
+        activity_digest::ActivityDigestService,
+        rate_limit::RateLimitService,
+        response_cache::ResponseCacheService,
+        oauth::OAuthService,
+        two_factor::TwoFactorService,
+        admin::AdminService,
+        report::ReportService,
+        data_lifecycle::DataLifecycleService,
+        oembed::OEmbedService,
+        speech::SpeechService,
+        trash::TrashService,
+        preview::PreviewService,
     },
+    utils::{http_cache::HttpCacheRules, sentiment::SentimentAnalyzer},
 };
+use std::sync::Arc;
 
 /// 应用程序的共享状态
 /// 包含所有服务和配置的引用
@@ -39,7 +97,7 @@ pub struct AppState {
     /// 认证服务
     pub auth_service: AuthService,
     
-    /// 文章服务
+    /// 文章的编辑和发布。读取文章通过 `articles()`，以便替换实现
     pub article_service: ArticleService,
     
     /// 用户服务
@@ -51,9 +109,6 @@ pub struct AppState {
     /// 通知服务
     pub notification_service: NotificationService,
     
-    /// 搜索服务
-    pub search_service: SearchService,
-    
     /// 媒体服务
     pub media_service: MediaService,
     
@@ -81,15 +136,9 @@ pub struct AppState {
     /// 订阅服务
     pub subscription_service: SubscriptionService,
     
-    /// 付费内容服务
-    pub payment_service: PaymentService,
-    
     /// 收益管理服务
     pub revenue_service: RevenueService,
     
-    /// Stripe支付服务
-    pub stripe_service: StripeService,
-    
     /// WebSocket实时通知服务
     pub websocket_service: WebSocketService,
     
@@ -98,6 +147,165 @@ pub struct AppState {
     
     /// 域名管理服务
     pub domain_service: DomainService,
+    
+    /// 阅读队列服务
+    pub reading_queue_service: ReadingQueueService,
+    
+    /// 写作辅助服务
+    pub assist_service: AssistService,
+    
+    /// 封面图生成服务
+    pub cover_image_service: CoverImageService,
+    
+    /// 信誉服务
+    pub reputation_service: ReputationService,
+    
+    /// 出版物流量异常检测服务
+    pub anomaly_service: AnomalyService,
+    
+    /// 插件管理器
+    pub plugin_manager: PluginManager,
+    
+    /// 出版物自定义内容转换服务
+    pub content_transform_service: ContentTransformService,
+    
+    /// 草稿和账户生命周期维护服务
+    pub lifecycle_service: LifecycleService,
+    
+    /// 数据库实时订阅服务
+    pub live_query_service: LiveQueryService,
+    
+    /// Medium/WordPress 文章导入服务
+    pub import_service: ImportService,
+    
+    /// 实时热度榜服务
+    pub popularity_service: PopularityService,
+    
+    /// Newsletter 邮件服务
+    pub newsletter_service: NewsletterService,
+    
+    /// Webmention / Pingback 服务
+    pub webmention_service: WebmentionService,
+    
+    /// 出版物级别的 CORS 来源
+    pub cors_service: CorsService,
+    
+    /// 爬虫识别，排除机器浏览
+    pub bot_detection_service: BotDetectionService,
+    
+    /// 文章里程碑
+    pub milestone_service: MilestoneService,
+    
+    /// 同步文章到外部平台
+    pub syndication_service: OutboundSyndicationService,
+    
+    /// 文章反应（点赞、insightful、funny 等）
+    pub reaction_service: ReactionService,
+    
+    /// 文章合著者
+    pub collaborator_service: CollaboratorService,
+    
+    /// 排期的社交推广帖子
+    pub social_share_service: SocialShareService,
+    
+    /// 草稿的实时协作编辑会话
+    pub collaborative_editing_service: CollaborativeEditingService,
+    
+    /// 出版物的文章模板
+    pub template_service: TemplateService,
+    
+    /// 跨设备同步的阅读位置
+    pub reading_progress_service: ReadingProgressService,
+    
+    /// 出版物写作规范和草稿检查
+    pub style_guide_service: StyleGuideService,
+    
+    /// 出版物首页布局（精选、栏目、关于）
+    pub homepage_service: HomepageService,
+    
+    /// 出版物主题（颜色、字体、标志、自定义 CSS）
+    pub theme_service: ThemeService,
+    
+    /// 出版物静态页面
+    pub page_service: PageService,
+    
+    /// 计量付费墙（每月免费阅读的付费文章）
+    pub meter_service: MeterService,
+    
+    /// 文章附件和下载统计
+    pub attachment_service: AttachmentService,
+    
+    /// 浏览去重和独立访客统计
+    pub view_tracking_service: ViewTrackingService,
+    
+    /// 访客国家查询（CDN 请求头或 MaxMind 数据库）
+    pub geoip_service: GeoIpService,
+    
+    /// 出版物赞助位和赞助展示
+    pub sponsorship_service: SponsorshipService,
+    
+    /// 作者管理的行动号召块和点击转化统计
+    pub cta_service: CtaService,
+    
+    /// 作者的笔名和笔名文章的署名
+    pub pseudonym_service: PseudonymService,
+    
+    /// 数据库统计和定期完整性检查报告
+    pub db_health_service: DbHealthService,
+    
+    /// 文章标题和封面的 A/B 实验
+    pub experiment_service: ExperimentService,
+    
+    /// 搜索框联想的内存前缀索引
+    pub suggest_service: SuggestService,
+    
+    /// 文章向量，用于相关文章和语义搜索
+    pub embedding_service: EmbeddingService,
+    
+    /// 文章和订阅礼物的赠送与领取
+    pub gift_service: GiftService,
+    
+    /// Stripe Webhook 的处理、失败重试和死信
+    pub webhook_service: WebhookService,
+    
+    /// 关注动态的每日/每周摘要邮件
+    pub activity_digest_service: ActivityDigestService,
+    
+    /// 按路由策略的请求限流
+    pub rate_limit_service: RateLimitService,
+    
+    /// 只读接口的 Cache-Control 规则
+    pub http_cache_rules: HttpCacheRules,
+    
+    /// 文章详情、出版物主页和标签页的服务端缓存
+    pub response_cache_service: ResponseCacheService,
+    
+    /// 第三方应用的 OAuth2 授权
+    pub oauth_service: OAuthService,
+    
+    /// TOTP 两步验证和恢复码
+    pub two_factor_service: TwoFactorService,
+    
+    /// 平台角色、用户停用和内容下架
+    pub admin_service: AdminService,
+    
+    /// 内容举报、举报案件和自动隐藏
+    pub report_service: ReportService,
+    
+    /// 账户删除：内容下线、数据匿名化和宽限期后的永久删除
+    pub data_lifecycle_service: DataLifecycleService,
+    
+    /// 文章朗读音频的生成
+    pub speech_service: SpeechService,
+    
+    /// 回收站：恢复删除的文章和评论，保留期满后永久删除
+    pub trash_service: TrashService,
+    
+    /// 草稿的秘密预览链接
+    pub preview_service: PreviewService,
+    
+    /// 可替换的服务能力（文章读取、付费内容、搜索等）
+    pub registry: ServiceRegistry,
 }
 
 impl Default for AppState {
@@ -107,9 +315,34 @@ impl Default for AppState {
 }
 
 impl AppState {
+    pub fn builder(config: Config, db: Arc<Database>) -> AppStateBuilder {
+        AppStateBuilder::new(config, db)
+    }
+
+    /// 文章读取实现
+    pub fn articles(&self) -> Result<Arc<dyn ArticleStore>> {
+        self.registry.require::<dyn ArticleStore>()
+    }
+
+    /// 付费内容实现，部署禁用付费功能时返回错误
+    pub fn payments(&self) -> Result<Arc<dyn Payments>> {
+        registered_payments(&self.registry)
+    }
+
+    /// 搜索实现
+    pub fn search(&self) -> Result<Arc<dyn SearchBackend>> {
+        self.registry.require::<dyn SearchBackend>()
+    }
+
+    /// Stripe 支付，和付费内容一起启用或禁用
+    pub fn stripe(&self) -> Result<Arc<StripeService>> {
+        registered_stripe(&self.registry)
+    }
+
     /// 检查功能是否启用
     pub fn is_feature_enabled(&self, feature: &str) -> bool {
         match feature {
+            "payments" => self.registry.contains::<dyn Payments>(),
             "registrations" => self.config.enable_registrations,
             "comments" => self.config.enable_comments,
             "subscriptions" => self.config.enable_subscriptions,
@@ -137,4 +370,386 @@ impl AppState {
     pub fn is_development(&self) -> bool {
         self.config.is_development()
     }
-}
\ No newline at end of file
+}
+
+/// `AppState` 构建器
+///
+/// 默认使用内置的具体服务；通过 `with_*` 方法可以替换注册表中的实现，
+/// `without_payments` 可以在不需要付费功能的部署中关闭付费内容。
+fn registered_payments(registry: &ServiceRegistry) -> Result<Arc<dyn Payments>> {
+    registry
+        .get::<dyn Payments>()
+        .ok_or_else(|| AppError::NotFound("Payments are not enabled".to_string()))
+}
+
+fn registered_stripe(registry: &ServiceRegistry) -> Result<Arc<StripeService>> {
+    registry
+        .get::<StripeService>()
+        .ok_or_else(|| AppError::NotFound("Payments are not enabled".to_string()))
+}
+
+pub struct AppStateBuilder {
+    config: Config,
+    db: Arc<Database>,
+    registry: ServiceRegistry,
+    payments_enabled: bool,
+    plugins: Vec<Arc<dyn Plugin>>,
+}
+
+impl AppStateBuilder {
+    pub fn new(config: Config, db: Arc<Database>) -> Self {
+        let payments_enabled = config.enable_payments;
+        Self {
+            config,
+            db,
+            registry: ServiceRegistry::new(),
+            payments_enabled,
+            plugins: Vec::new(),
+        }
+    }
+
+    pub fn with_article_store(mut self, store: Arc<dyn ArticleStore>) -> Self {
+        self.registry.insert::<dyn ArticleStore>(store);
+        self
+    }
+
+    pub fn with_payments(mut self, payments: Arc<dyn Payments>) -> Self {
+        self.registry.insert::<dyn Payments>(payments);
+        self.payments_enabled = true;
+        self
+    }
+
+    pub fn without_payments(mut self) -> Self {
+        self.registry.remove::<dyn Payments>();
+        self.payments_enabled = false;
+        self
+    }
+
+    pub fn with_search(mut self, search: Arc<dyn SearchBackend>) -> Self {
+        self.registry.insert::<dyn SearchBackend>(search);
+        self
+    }
+
+    /// 替换评论情感分析使用的默认词典分析器
+    pub fn with_sentiment_analyzer(mut self, analyzer: Arc<dyn SentimentAnalyzer>) -> Self {
+        self.registry.insert::<dyn SentimentAnalyzer>(analyzer);
+        self
+    }
+
+    /// 注册编译内置的插件
+    pub fn with_plugin(mut self, plugin: Arc<dyn Plugin>) -> Self {
+        self.plugins.push(plugin);
+        self
+    }
+
+    /// 注册任意 trait 的实现，供扩展能力使用
+    pub fn with_service<T: ?Sized + Send + Sync + 'static>(mut self, service: Arc<T>) -> Self {
+        self.registry.insert(service);
+        self
+    }
+
+    /// 初始化所有服务，未被替换的能力使用默认实现
+    pub async fn build(self) -> Result<AppState> {
+        let Self { config, db, mut registry, payments_enabled, plugins } = self;
+
+        // 通知需要从 Rainbow-Auth 读取收件人邮箱，认证服务最先创建
+        let auth_service = AuthService::new(&config).await?;
+        // 文章发布时发送 Webmention、同步到外部平台、通知关注者、生成 OG 图片和朗读音频作为内置插件注册
+        let notification_service = NotificationService::new(db.clone(), &config, auth_service.clone()).await?;
+        let webmention_service = WebmentionService::new(db.clone(), &config).await?;
+        let syndication_service = OutboundSyndicationService::new(db.clone(), webmention_service.clone()).await?;
+        let media_service = MediaService::new(&config, db.clone()).await?;
+        let speech_service = SpeechService::new(db.clone(), &config, media_service.clone()).await?;
+        let cover_image_service = CoverImageService::new(db.clone(), media_service.clone()).await?;
+        let plugin_manager = plugins
+            .into_iter()
+            .fold(PluginManager::new(&config).await?, PluginManager::with_plugin)
+            .with_plugin(Arc::new(webmention_service.clone()))
+            .with_plugin(Arc::new(syndication_service.clone()))
+            .with_plugin(Arc::new(notification_service.clone()))
+            .with_plugin(Arc::new(cover_image_service.clone()))
+            .with_plugin(Arc::new(speech_service.clone()));
+        let assist_service = AssistService::new(&config).await?;
+        let embedding_service = EmbeddingService::new(&config, db.clone()).await?;
+        // 文章详情按订阅和单篇购买截断付费正文，所以支付相关服务先于文章服务创建
+        let stripe_service = StripeService::new(db.clone(), StripeConfig::default()).await?;
+        let stripe_service_arc = Arc::new(stripe_service.clone());
+        let subscription_service = SubscriptionService::new(db.clone(), stripe_service_arc.clone()).await?;
+        let subscription_service_arc = Arc::new(subscription_service.clone());
+        let payment_service = PaymentService::new(
+            db.clone(),
+            subscription_service_arc.clone(),
+            stripe_service_arc.clone(),
+        )
+        .await?;
+        let view_tracking_service = ViewTrackingService::new(db.clone(), &config).await?;
+        let meter_service = MeterService::new(db.clone(), &config, view_tracking_service.clone()).await?;
+        let oembed_service = OEmbedService::new(db.clone(), &config).await?;
+        let response_cache_service = ResponseCacheService::new(&config).await?;
+        let article_service = ArticleService::new(
+            db.clone(),
+            &config,
+            assist_service.clone(),
+            embedding_service.clone(),
+            plugin_manager.clone(),
+            payment_service.clone(),
+            meter_service.clone(),
+            oembed_service,
+            speech_service.clone(),
+            response_cache_service.clone(),
+        ).await?;
+        let user_service = UserService::new(db.clone(), plugin_manager.clone()).await?;
+        let comment_service = CommentService::new(db.clone(), plugin_manager.clone(), &config).await?;
+        let search_service = SearchService::new(db.clone(), embedding_service.clone()).await?;
+        let recommendation_service = RecommendationService::new(db.clone()).await?;
+        let publication_service = PublicationService::new(db.clone(), &config, response_cache_service.clone(), auth_service.clone()).await?;
+        let bookmark_service = BookmarkService::new(db.clone()).await?;
+        let follow_service = FollowService::new(db.clone(), notification_service.clone()).await?;
+        let tag_service = TagService::new(db.clone()).await?;
+        let series_service = SeriesService::new(db.clone()).await?;
+        let websocket_service = WebSocketService::new(db.clone()).await?;
+        let mut analytics_service = AnalyticsService::new(db.clone(), websocket_service.clone()).await?;
+        if let Some(analyzer) = registry.get::<dyn SentimentAnalyzer>() {
+            analytics_service = analytics_service.with_sentiment_analyzer(analyzer);
+        }
+        let revenue_service = RevenueService::new(db.clone(), stripe_service_arc.clone(), &config).await?;
+        let realtime_service = RealtimeService::new(Arc::new(websocket_service.clone()), Arc::new(notification_service.clone()));
+
+        let domain_config = DomainConfig {
+            base_domain: config.base_domain.clone().unwrap_or_else(|| "platform.local".to_string()),
+            dns_verification_timeout: 300, // 5 minutes
+            ssl_provider_endpoint: config.ssl_provider_endpoint.clone(),
+            ssl_provider_api_key: config.ssl_provider_api_key.clone(),
+            auto_provision_ssl: config.auto_provision_ssl.unwrap_or(false),
+            ssl_webhook_url: config.ssl_webhook_url.clone(),
+            apex_target_ips: config.domain_apex_target_ips
+                .iter()
+                .filter_map(|ip| ip.parse().ok())
+                .collect(),
+            acme_directory_url: config.acme_directory_url.clone(),
+            acme_contact_email: config.acme_contact_email.clone(),
+            acme_dns_api_url: config.acme_dns_api_url.clone(),
+            email_spf_include: config.email_spf_include.clone()
+                .unwrap_or_else(|| format!("_spf.{}", config.base_domain.as_deref().unwrap_or("platform.local"))),
+            email_return_path_target: config.email_return_path_target.clone()
+                .unwrap_or_else(|| format!("bounces.{}", config.base_domain.as_deref().unwrap_or("platform.local"))),
+        };
+        let domain_service = DomainService::new(db.clone(), domain_config).await?;
+        let reading_queue_service = ReadingQueueService::new(db.clone()).await?;
+        let reputation_service = ReputationService::new(db.clone()).await?;
+        let anomaly_service = AnomalyService::new(db.clone(), notification_service.clone()).await?;
+        let content_transform_service = ContentTransformService::new(db.clone(), &config).await?;
+        let lifecycle_service = LifecycleService::new(db.clone(), notification_service.clone(), &config).await?;
+        let import_service = ImportService::new(db.clone(), article_service.clone(), media_service.clone()).await?;
+        let popularity_service = PopularityService::new(db.clone(), config.popularity_half_life_minutes).await?;
+        let live_query_service = LiveQueryService::new(&config, realtime_service.clone(), popularity_service.clone());
+        let newsletter_service = NewsletterService::new(db.clone(), &config, domain_service.clone()).await?;
+        let cors_service = CorsService::new(db.clone()).await?;
+        let bot_detection_service = BotDetectionService::new(db.clone(), &config).await?;
+        let reaction_service = ReactionService::new(db.clone()).await?;
+        let social_share_service = SocialShareService::new(
+            db.clone(),
+            syndication_service.clone(),
+            webmention_service.clone(),
+            config.social_share_max_per_hour,
+        ).await?;
+        let collaborative_editing_service = CollaborativeEditingService::new(article_service.clone()).await?;
+        let template_service = TemplateService::new(db.clone(), article_service.clone()).await?;
+        let reading_progress_service = ReadingProgressService::new(db.clone(), recommendation_service.clone()).await?;
+        let style_guide_service = StyleGuideService::new(db.clone(), article_service.clone()).await?;
+        let homepage_service = HomepageService::new(db.clone(), article_service.clone()).await?;
+        let theme_service = ThemeService::new(db.clone()).await?;
+        let page_service = PageService::new(db.clone()).await?;
+        let attachment_service = AttachmentService::new(&config, db.clone(), media_service.clone(), subscription_service.clone()).await?;
+        let geoip_service = GeoIpService::new(&config).await?;
+        let sponsorship_service = SponsorshipService::new(db.clone(), media_service.clone()).await?;
+        let cta_service = CtaService::new(db.clone()).await?;
+        let pseudonym_service = PseudonymService::new(db.clone()).await?;
+        let db_health_service = DbHealthService::new(db.clone()).await?;
+        let experiment_service = ExperimentService::new(
+            db.clone(),
+            article_service.clone(),
+            view_tracking_service.clone(),
+            bot_detection_service.clone(),
+        ).await?;
+        let suggest_service = SuggestService::new(db.clone()).await?;
+        let collaborator_service = CollaboratorService::new(db.clone(), article_service.clone(), notification_service.clone()).await?;
+        let milestone_service = MilestoneService::new(db.clone(), notification_service.clone(), popularity_service.clone()).await?;
+        let gift_service = GiftService::new(
+            db.clone(),
+            &config,
+            stripe_service_arc.clone(),
+            article_service.clone(),
+            subscription_service.clone(),
+            payment_service.clone(),
+            notification_service.clone(),
+        ).await?;
+        let webhook_service = WebhookService::new(
+            db.clone(),
+            &config,
+            stripe_service_arc.clone(),
+            payment_service.clone(),
+            revenue_service.clone(),
+            gift_service.clone(),
+            article_service.clone(),
+            notification_service.clone(),
+        ).await?;
+        let activity_digest_service = ActivityDigestService::new(db.clone(), &config, notification_service.clone()).await?;
+        let rate_limit_service = RateLimitService::new(db.clone(), &config).await?;
+        let http_cache_rules = HttpCacheRules::new(&config)?;
+        let oauth_service = OAuthService::new(db.clone()).await?;
+        let two_factor_service = TwoFactorService::new(db.clone()).await?;
+        let admin_service = AdminService::new(db.clone(), &config, notification_service.clone(), response_cache_service.clone()).await?;
+        let report_service = ReportService::new(
+            db.clone(),
+            &config,
+            admin_service.clone(),
+            comment_service.clone(),
+            notification_service.clone(),
+        ).await?;
+        let data_lifecycle_service = DataLifecycleService::new(
+            db.clone(),
+            &config,
+            comment_service.clone(),
+            subscription_service.clone(),
+            stripe_service.clone(),
+            notification_service.clone(),
+        ).await?;
+        let trash_service = TrashService::new(
+            db.clone(),
+            &config,
+            article_service.clone(),
+            comment_service.clone(),
+        ).await?;
+        let preview_service = PreviewService::new(db.clone(), article_service.clone()).await?;
+
+        // 未被替换的能力注册默认实现
+        if !registry.contains::<dyn ArticleStore>() {
+            registry.insert::<dyn ArticleStore>(Arc::new(article_service.clone()));
+        }
+        if payments_enabled && !registry.contains::<dyn Payments>() {
+            registry.insert::<dyn Payments>(Arc::new(payment_service));
+        }
+        if payments_enabled {
+            registry.insert::<StripeService>(stripe_service_arc);
+        }
+        if !registry.contains::<dyn SearchBackend>() {
+            registry.insert::<dyn SearchBackend>(Arc::new(search_service));
+        }
+
+        Ok(AppState {
+            config: config.clone(),
+            db: (*db).clone(),
+            auth_service,
+            article_service,
+            user_service,
+            comment_service,
+            notification_service,
+            media_service,
+            recommendation_service,
+            publication_service,
+            bookmark_service,
+            follow_service,
+            tag_service,
+            series_service,
+            analytics_service,
+            subscription_service,
+            revenue_service,
+            websocket_service,
+            realtime_service,
+            domain_service,
+            reading_queue_service,
+            assist_service,
+            cover_image_service,
+            reputation_service,
+            anomaly_service,
+            plugin_manager,
+            content_transform_service,
+            lifecycle_service,
+            live_query_service,
+            import_service,
+            popularity_service,
+            newsletter_service,
+            webmention_service,
+            cors_service,
+            bot_detection_service,
+            milestone_service,
+            syndication_service,
+            reaction_service,
+            collaborator_service,
+            social_share_service,
+            collaborative_editing_service,
+            template_service,
+            reading_progress_service,
+            style_guide_service,
+            homepage_service,
+            theme_service,
+            page_service,
+            meter_service,
+            attachment_service,
+            view_tracking_service,
+            geoip_service,
+            sponsorship_service,
+            cta_service,
+            pseudonym_service,
+            db_health_service,
+            experiment_service,
+            suggest_service,
+            embedding_service,
+            gift_service,
+            webhook_service,
+            activity_digest_service,
+            rate_limit_service,
+            http_cache_rules,
+            response_cache_service,
+            oauth_service,
+            two_factor_service,
+            admin_service,
+            report_service,
+            data_lifecycle_service,
+            speech_service,
+            trash_service,
+            preview_service,
+            registry,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::search::*;
+    use async_trait::async_trait;
+
+    struct FakeSearch;
+
+    #[async_trait]
+    impl SearchBackend for FakeSearch {
+        async fn search(&self, _query: SearchQuery) -> Result<SearchResults> {
+            Err(AppError::internal("fake search"))
+        }
+
+        async fn advanced_search(&self, _user_id: Option<&str>, _query: AdvancedSearchQuery) -> Result<AdvancedSearchResults> {
+            Err(AppError::internal("fake search"))
+        }
+
+        async fn get_search_suggestions(&self, _query: &str, _limit: Option<i32>) -> Result<Vec<SearchSuggestion>> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[test]
+    fn test_capabilities_are_served_from_registry() {
+        let mut registry = ServiceRegistry::new();
+        let fake: Arc<dyn SearchBackend> = Arc::new(FakeSearch);
+        registry.insert::<dyn SearchBackend>(fake.clone());
+
+        let search = registry.require::<dyn SearchBackend>().unwrap();
+        assert_eq!(Arc::as_ptr(&search) as *const (), Arc::as_ptr(&fake) as *const ());
+
+        // 没有注册付费实现时付费和 Stripe 都不可用，而不是退回到别的实例
+        assert!(registered_payments(&registry).is_err());
+        assert!(registered_stripe(&registry).is_err());
+        assert!(!registry.contains::<dyn Payments>());
+    }
+}
@@ -0,0 +1,104 @@
+/// 流量异常检测阈值
+#[derive(Debug, Clone, Copy)]
+pub struct AnomalyThresholds {
+    /// 偏离基线的标准差倍数
+    pub z_score: f64,
+    /// 触发流量激增提醒所需的最少浏览量，避免小流量抖动
+    pub min_spike_views: f64,
+    /// 基线平均值低于此值时不检测流量骤降
+    pub min_baseline_for_drop: f64,
+    /// 单篇文章浏览量达到基线的倍数才视为爆款
+    pub viral_multiplier: f64,
+    pub viral_min_views: f64,
+}
+
+impl Default for AnomalyThresholds {
+    fn default() -> Self {
+        Self {
+            z_score: 3.0,
+            min_spike_views: 50.0,
+            min_baseline_for_drop: 20.0,
+            viral_multiplier: 5.0,
+            viral_min_views: 100.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrafficShift {
+    Spike,
+    Drop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeriesStats {
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+pub fn series_stats(values: &[f64]) -> SeriesStats {
+    if values.is_empty() {
+        return SeriesStats { mean: 0.0, std_dev: 0.0 };
+    }
+
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+
+    SeriesStats { mean, std_dev: variance.sqrt() }
+}
+
+/// 当前窗口相对基线的 z 分数。
+/// 浏览量近似泊松分布，标准差不低于 sqrt(mean)，避免平稳序列上的微小波动被放大
+pub fn z_score(stats: &SeriesStats, current: f64) -> f64 {
+    let sigma = stats.std_dev.max(stats.mean.sqrt()).max(1.0);
+    (current - stats.mean) / sigma
+}
+
+/// 检测整体流量的激增或骤降
+pub fn detect_traffic_shift(baseline: &[f64], current: f64, thresholds: &AnomalyThresholds) -> Option<(TrafficShift, f64)> {
+    let stats = series_stats(baseline);
+    let z = z_score(&stats, current);
+
+    if z >= thresholds.z_score && current >= thresholds.min_spike_views {
+        Some((TrafficShift::Spike, z))
+    } else if z <= -thresholds.z_score && stats.mean >= thresholds.min_baseline_for_drop {
+        Some((TrafficShift::Drop, z))
+    } else {
+        None
+    }
+}
+
+/// 单篇文章的浏览量是否远超其基线
+pub fn is_viral(baseline_mean: f64, current: f64, thresholds: &AnomalyThresholds) -> bool {
+    current >= thresholds.viral_min_views && current >= thresholds.viral_multiplier * baseline_mean.max(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_traffic_shift() {
+        let thresholds = AnomalyThresholds::default();
+        let baseline: Vec<f64> = (0..168).map(|i| if i % 2 == 0 { 38.0 } else { 42.0 }).collect();
+
+        assert_eq!(detect_traffic_shift(&baseline, 45.0, &thresholds), None);
+        assert!(matches!(detect_traffic_shift(&baseline, 200.0, &thresholds), Some((TrafficShift::Spike, _))));
+        assert!(matches!(detect_traffic_shift(&baseline, 0.0, &thresholds), Some((TrafficShift::Drop, _))));
+
+        // 小流量出版物不报告骤降，激增也需要达到最低浏览量
+        let quiet = vec![2.0; 168];
+        assert_eq!(detect_traffic_shift(&quiet, 0.0, &thresholds), None);
+        assert_eq!(detect_traffic_shift(&quiet, 20.0, &thresholds), None);
+    }
+
+    #[test]
+    fn test_is_viral() {
+        let thresholds = AnomalyThresholds::default();
+
+        assert!(is_viral(0.5, 150.0, &thresholds));
+        assert!(!is_viral(40.0, 150.0, &thresholds));
+        assert!(!is_viral(0.0, 80.0, &thresholds));
+    }
+}
@@ -0,0 +1,121 @@
+//! 按 User-Agent 和请求头识别爬虫
+
+use crate::models::bot::BotCategory;
+use axum::http::{header, HeaderMap};
+
+/// 已知爬虫的 User-Agent 片段（小写），按匹配优先级排列
+const KNOWN_BOTS: &[(&str, &str, BotCategory)] = &[
+    ("googlebot", "Googlebot", BotCategory::SearchEngine),
+    ("google-inspectiontool", "Google Inspection Tool", BotCategory::SearchEngine),
+    ("bingbot", "Bingbot", BotCategory::SearchEngine),
+    ("duckduckbot", "DuckDuckBot", BotCategory::SearchEngine),
+    ("baiduspider", "Baiduspider", BotCategory::SearchEngine),
+    ("yandexbot", "YandexBot", BotCategory::SearchEngine),
+    ("sogou", "Sogou", BotCategory::SearchEngine),
+    ("applebot", "Applebot", BotCategory::SearchEngine),
+    ("petalbot", "PetalBot", BotCategory::SearchEngine),
+    ("facebookexternalhit", "Facebook", BotCategory::SocialPreview),
+    ("twitterbot", "Twitterbot", BotCategory::SocialPreview),
+    ("linkedinbot", "LinkedInBot", BotCategory::SocialPreview),
+    ("slackbot", "Slackbot", BotCategory::SocialPreview),
+    ("discordbot", "Discordbot", BotCategory::SocialPreview),
+    ("telegrambot", "TelegramBot", BotCategory::SocialPreview),
+    ("whatsapp", "WhatsApp", BotCategory::SocialPreview),
+    ("pinterestbot", "Pinterestbot", BotCategory::SocialPreview),
+    ("ahrefsbot", "AhrefsBot", BotCategory::SeoTool),
+    ("semrushbot", "SemrushBot", BotCategory::SeoTool),
+    ("mj12bot", "MJ12bot", BotCategory::SeoTool),
+    ("dotbot", "DotBot", BotCategory::SeoTool),
+    ("gptbot", "GPTBot", BotCategory::AiCrawler),
+    ("chatgpt-user", "ChatGPT-User", BotCategory::AiCrawler),
+    ("claudebot", "ClaudeBot", BotCategory::AiCrawler),
+    ("ccbot", "CCBot", BotCategory::AiCrawler),
+    ("perplexitybot", "PerplexityBot", BotCategory::AiCrawler),
+    ("bytespider", "Bytespider", BotCategory::AiCrawler),
+    ("uptimerobot", "UptimeRobot", BotCategory::Monitoring),
+    ("pingdom", "Pingdom", BotCategory::Monitoring),
+    ("statuscake", "StatusCake", BotCategory::Monitoring),
+    ("headlesschrome", "HeadlessChrome", BotCategory::Automation),
+    ("phantomjs", "PhantomJS", BotCategory::Automation),
+    ("python-requests", "python-requests", BotCategory::Automation),
+    ("python-urllib", "python-urllib", BotCategory::Automation),
+    ("aiohttp", "aiohttp", BotCategory::Automation),
+    ("scrapy", "Scrapy", BotCategory::Automation),
+    ("curl/", "curl", BotCategory::Automation),
+    ("wget/", "Wget", BotCategory::Automation),
+    ("go-http-client", "Go-http-client", BotCategory::Automation),
+    ("okhttp", "okhttp", BotCategory::Automation),
+    ("node-fetch", "node-fetch", BotCategory::Automation),
+    ("axios/", "axios", BotCategory::Automation),
+    ("java/", "Java", BotCategory::Automation),
+    ("libwww-perl", "libwww-perl", BotCategory::Automation),
+];
+
+/// 通用的爬虫关键字，未命中已知列表时使用
+const GENERIC_MARKERS: &[&str] = &["bot", "crawler", "spider", "crawl", "slurp", "fetcher", "preview"];
+
+/// 按 User-Agent 识别爬虫，返回名称和类别
+pub fn classify_user_agent(user_agent: &str) -> Option<(String, BotCategory)> {
+    let ua = user_agent.to_ascii_lowercase();
+
+    if let Some((_, name, category)) = KNOWN_BOTS.iter().find(|(marker, _, _)| ua.contains(marker)) {
+        return Some((name.to_string(), *category));
+    }
+
+    if GENERIC_MARKERS.iter().any(|marker| ua.contains(marker)) {
+        // 取 User-Agent 的第一个产品名作为爬虫名称
+        let name = user_agent
+            .split(|c: char| c == '/' || c.is_whitespace() || c == ';' || c == '(')
+            .find(|part| !part.is_empty() && !part.eq_ignore_ascii_case("mozilla"))
+            .unwrap_or("unknown")
+            .to_string();
+        return Some((name, BotCategory::Unknown));
+    }
+
+    None
+}
+
+/// 自称浏览器却缺少浏览器总会发送的请求头
+pub fn missing_browser_headers(headers: &HeaderMap) -> bool {
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if user_agent.trim().is_empty() {
+        return true;
+    }
+
+    user_agent.starts_with("Mozilla/") && !headers.contains_key(header::ACCEPT_LANGUAGE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_classify_user_agent() {
+        let google = "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)";
+        assert_eq!(classify_user_agent(google), Some(("Googlebot".to_string(), BotCategory::SearchEngine)));
+        assert_eq!(classify_user_agent("curl/8.4.0").map(|b| b.1), Some(BotCategory::Automation));
+        assert_eq!(
+            classify_user_agent("FeedFetcher-Example/1.0"),
+            Some(("FeedFetcher-Example".to_string(), BotCategory::Unknown))
+        );
+
+        let firefox = "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
+        assert_eq!(classify_user_agent(firefox), None);
+    }
+
+    #[test]
+    fn test_missing_browser_headers() {
+        let mut headers = HeaderMap::new();
+        assert!(missing_browser_headers(&headers));
+
+        headers.insert(header::USER_AGENT, HeaderValue::from_static("Mozilla/5.0 (Macintosh) Safari/605.1"));
+        assert!(missing_browser_headers(&headers));
+
+        headers.insert(header::ACCEPT_LANGUAGE, HeaderValue::from_static("en-US,en;q=0.9"));
+        assert!(!missing_browser_headers(&headers));
+    }
+}
@@ -0,0 +1,131 @@
+//! 正文中的行动号召块：单独成段的 `{{cta key}}` 短代码在渲染时替换为作者管理的 CTA。
+//!
+//! 短代码经过 Markdown 渲染和 HTML 清理后保留为 `<p>{{cta key}}</p>`，读取文章时再替换，
+//! 这样修改 CTA 后所有引用它的文章立即生效，生成的 HTML 也不会被清理器去掉。
+
+use crate::models::{
+    cta::{CallToAction, CtaKind},
+    id::bare_id,
+};
+use regex::{Captures, Regex};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+fn shortcode_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"<p>\{\{cta ([a-z0-9][a-z0-9-]*)\}\}</p>").expect("valid cta shortcode regex"))
+}
+
+/// CTA 的 key 只能包含小写字母、数字和连字符
+pub fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && !key.starts_with('-')
+        && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// 渲染后的正文引用的 CTA key
+pub fn referenced_keys(html: &str) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    for captures in shortcode_regex().captures_iter(html) {
+        let key = captures[1].to_string();
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    keys
+}
+
+/// 把短代码替换为 CTA 块；找不到的 key 直接去掉，避免读者看到短代码
+pub fn expand_cta_blocks(html: &str, ctas: &HashMap<String, CallToAction>, article_id: &str) -> String {
+    shortcode_regex()
+        .replace_all(html, |captures: &Captures| {
+            ctas.get(&captures[1])
+                .map(|cta| render_block(cta, article_id))
+                .unwrap_or_default()
+        })
+        .into_owned()
+}
+
+/// 点击由前端上报到 `/api/blog/ctas/{id}/events`；外部链接经过跳转接口记录点击
+fn render_block(cta: &CallToAction, article_id: &str) -> String {
+    let cta_id = bare_id("cta", &cta.id);
+    let mut html = format!(
+        r#"<aside class="cta cta-{kind}" data-cta-id="{id}" data-cta-kind="{kind}" data-article-id="{article}">"#,
+        kind = cta.kind.as_str().replace('_', "-"),
+        id = escape_attribute(cta_id),
+        article = escape_attribute(article_id),
+    );
+    html.push_str(&format!(r#"<strong class="cta-title">{}</strong>"#, escape_text(&cta.title)));
+    if let Some(description) = &cta.description {
+        html.push_str(&format!(r#"<p class="cta-description">{}</p>"#, escape_text(description)));
+    }
+
+    let label = escape_text(&cta.button_label);
+    match cta.kind {
+        CtaKind::ExternalLink => html.push_str(&format!(
+            r#"<a class="cta-button" href="/api/blog/ctas/{}/go?article={}" rel="noopener" target="_blank">{}</a>"#,
+            escape_attribute(cta_id),
+            escape_attribute(article_id),
+            label
+        )),
+        CtaKind::EmailCapture => html.push_str(&format!(
+            r#"<form class="cta-form" data-cta-action="email" action="/api/blog/ctas/{}/email" method="post"><input type="email" name="email" required placeholder="you@example.com"><button type="submit" class="cta-button">{}</button></form>"#,
+            escape_attribute(cta_id),
+            label
+        )),
+        CtaKind::Subscribe | CtaKind::Follow => html.push_str(&format!(
+            r#"<button type="button" class="cta-button" data-cta-action="{}" data-author-id="{}">{}</button>"#,
+            cta.kind.as_str(),
+            escape_attribute(&cta.owner_id),
+            label
+        )),
+    }
+
+    html.push_str("</aside>");
+    html
+}
+
+fn escape_text(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn escape_attribute(value: &str) -> String {
+    escape_text(value).replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_expand_cta_blocks() {
+        let cta = CallToAction {
+            id: "cta:abc".to_string(),
+            owner_id: "u1".to_string(),
+            key: "newsletter".to_string(),
+            kind: CtaKind::EmailCapture,
+            title: "Get <new> posts".to_string(),
+            description: None,
+            button_label: "Sign up".to_string(),
+            url: None,
+            click_count: 0,
+            conversion_count: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let ctas = HashMap::from([("newsletter".to_string(), cta)]);
+        let html = "<p>Intro</p>\n<p>{{cta newsletter}}</p>\n<p>{{cta missing}}</p>\n<p>Inline {{cta newsletter}} stays</p>";
+
+        assert_eq!(referenced_keys(html), vec!["newsletter", "missing"]);
+
+        let expanded = expand_cta_blocks(html, &ctas, "a1");
+        assert!(expanded.starts_with(
+            r#"<p>Intro</p>
+<aside class="cta cta-email-capture" data-cta-id="abc" data-cta-kind="email-capture" data-article-id="a1"><strong class="cta-title">Get &lt;new&gt; posts</strong><form"#
+        ));
+        assert!(!expanded.contains("{{cta missing}}"));
+        assert!(expanded.contains("<p>Inline {{cta newsletter}} stays</p>"));
+        assert!(!is_valid_key("-x") && is_valid_key("spring-sale-2"));
+    }
+}
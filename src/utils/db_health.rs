@@ -0,0 +1,134 @@
+//! 数据库健康报告的索引建议：从 `INFO FOR TABLE` 返回的索引定义中取出列，
+//! 找出大表上缺少索引的关联字段和列完全相同的重复索引。
+
+use crate::models::db_health::{IndexHint, IndexHintKind};
+use std::collections::HashMap;
+
+/// 行数达到这个量级才建议为关联字段加索引，小表全表扫描的代价可以忽略
+pub const INDEX_HINT_MIN_ROWS: i64 = 10_000;
+
+/// 取出索引定义中的列，例如 `DEFINE INDEX a ON article FIELDS author_id, status UNIQUE`
+pub fn index_columns(definition: &str) -> Vec<String> {
+    let upper = definition.to_uppercase();
+    let Some(start) = [" FIELDS ", " COLUMNS "]
+        .iter()
+        .filter_map(|keyword| upper.find(keyword).map(|i| i + keyword.len()))
+        .min()
+    else {
+        return Vec::new();
+    };
+
+    let rest = &definition[start..];
+    let rest_upper = &upper[start..];
+    let end = [" UNIQUE", " SEARCH", " MTREE", " HNSW", " COMMENT", ";"]
+        .iter()
+        .filter_map(|keyword| rest_upper.find(keyword))
+        .min()
+        .unwrap_or(rest.len());
+
+    rest[..end]
+        .split(',')
+        .map(|column| column.trim().to_string())
+        .filter(|column| !column.is_empty())
+        .collect()
+}
+
+/// `fields` 是表的字段名，`indexes` 是索引名到定义语句的映射
+pub fn index_hints(
+    table: &str,
+    rows: i64,
+    fields: &[String],
+    indexes: &HashMap<String, String>,
+) -> Vec<IndexHint> {
+    let mut hints = Vec::new();
+
+    let mut definitions: Vec<(&String, Vec<String>)> = indexes
+        .iter()
+        .map(|(name, definition)| (name, index_columns(definition)))
+        .collect();
+    definitions.sort_by(|a, b| a.0.cmp(b.0));
+
+    if rows >= INDEX_HINT_MIN_ROWS {
+        let mut relation_fields: Vec<&String> = fields
+            .iter()
+            .filter(|field| field.ends_with("_id") && !field.contains('.'))
+            .collect();
+        relation_fields.sort();
+
+        for field in relation_fields {
+            let covered = definitions
+                .iter()
+                .any(|(_, columns)| columns.first() == Some(field));
+            if !covered {
+                hints.push(IndexHint {
+                    kind: IndexHintKind::MissingIndex,
+                    table: table.to_string(),
+                    columns: vec![field.clone()],
+                    message: format!(
+                        "{} has {} rows but no index starts with {}",
+                        table, rows, field
+                    ),
+                    statement: format!("DEFINE INDEX {}_{}_idx ON {} COLUMNS {};", table, field, table, field),
+                });
+            }
+        }
+    }
+
+    for (i, (name, columns)) in definitions.iter().enumerate() {
+        if columns.is_empty() {
+            continue;
+        }
+        if let Some((duplicate, _)) = definitions[i + 1..].iter().find(|(_, other)| other == columns) {
+            let unique = |index: &str| {
+                indexes
+                    .get(index)
+                    .map_or(false, |definition| definition.to_uppercase().contains(" UNIQUE"))
+            };
+            // 保留带唯一约束的那个
+            let redundant = if unique(duplicate) && !unique(name) { *name } else { *duplicate };
+            hints.push(IndexHint {
+                kind: IndexHintKind::DuplicateIndex,
+                table: table.to_string(),
+                columns: columns.clone(),
+                message: format!(
+                    "Indexes {} and {} on {} cover the same columns",
+                    name, duplicate, table
+                ),
+                statement: format!("REMOVE INDEX {} ON {};", redundant, table),
+            });
+        }
+    }
+
+    hints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_hints() {
+        assert_eq!(
+            index_columns("DEFINE INDEX cta_owner_key_idx ON cta FIELDS owner_id, key UNIQUE"),
+            vec!["owner_id", "key"]
+        );
+        assert_eq!(index_columns("DEFINE TABLE cta SCHEMAFULL"), Vec::<String>::new());
+
+        let fields = vec!["article_id".to_string(), "tag_id".to_string(), "created_at".to_string()];
+        let indexes = HashMap::from([
+            ("a_idx".to_string(), "DEFINE INDEX a_idx ON article_tag FIELDS article_id".to_string()),
+            ("b_idx".to_string(), "DEFINE INDEX b_idx ON article_tag FIELDS article_id UNIQUE".to_string()),
+        ]);
+
+        let hints = index_hints("article_tag", 50_000, &fields, &indexes);
+        assert_eq!(hints.len(), 2);
+        assert_eq!(hints[0].kind, IndexHintKind::MissingIndex);
+        assert_eq!(hints[0].columns, vec!["tag_id"]);
+        assert_eq!(hints[1].kind, IndexHintKind::DuplicateIndex);
+        assert_eq!(hints[1].statement, "REMOVE INDEX a_idx ON article_tag;");
+
+        // 小表不建议加索引
+        let hints = index_hints("article_tag", 100, &fields, &indexes);
+        assert_eq!(hints.len(), 1);
+    }
+}
@@ -0,0 +1,282 @@
+//! Disqus XML 导出文件解析
+
+use crate::error::{AppError, Result};
+use chrono::{DateTime, Utc};
+use quick_xml::{events::{BytesStart, Event}, Reader};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// 一个讨论串，对应原站点的一个页面
+#[derive(Debug, Clone, Default)]
+pub struct DisqusThread {
+    pub id: String,
+    /// 页面标识（Disqus 的 `<id>`，通常为文章路径或 slug）
+    pub identifier: Option<String>,
+    pub link: String,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DisqusPost {
+    pub id: String,
+    pub thread_id: String,
+    pub parent_id: Option<String>,
+    /// HTML 格式的评论内容
+    pub message: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub author_name: Option<String>,
+    pub author_username: Option<String>,
+    pub is_deleted: bool,
+    pub is_spam: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct DisqusExport {
+    pub threads: HashMap<String, DisqusThread>,
+    pub posts: Vec<DisqusPost>,
+}
+
+fn dsq_id(element: &BytesStart) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attr| attr.key.as_ref() == b"dsq:id")
+        .and_then(|attr| attr.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+fn parse_error(e: impl std::fmt::Display) -> AppError {
+    AppError::BadRequest(format!("Invalid Disqus export: {}", e))
+}
+
+pub fn parse_export(xml: &str) -> Result<DisqusExport> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut export = DisqusExport::default();
+    let mut path: Vec<String> = Vec::new();
+    let mut thread: Option<DisqusThread> = None;
+    let mut post: Option<DisqusPost> = None;
+
+    loop {
+        let event = reader.read_event().map_err(parse_error)?;
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                match (path.len(), name.as_str()) {
+                    (1, "thread") => {
+                        thread = Some(DisqusThread {
+                            id: dsq_id(e).unwrap_or_default(),
+                            ..Default::default()
+                        });
+                    }
+                    (1, "post") => {
+                        post = Some(DisqusPost {
+                            id: dsq_id(e).unwrap_or_default(),
+                            ..Default::default()
+                        });
+                    }
+                    (2, "thread") if path[1] == "post" => {
+                        if let Some(post) = post.as_mut() {
+                            post.thread_id = dsq_id(e).unwrap_or_default();
+                        }
+                    }
+                    (2, "parent") if path[1] == "post" => {
+                        if let Some(post) = post.as_mut() {
+                            post.parent_id = dsq_id(e);
+                        }
+                    }
+                    _ => {}
+                }
+
+                if matches!(event, Event::Start(_)) {
+                    path.push(name);
+                } else {
+                    finish_element(&name, path.len(), &mut export, &mut thread, &mut post);
+                }
+            }
+            Event::End(_) => {
+                if let Some(name) = path.pop() {
+                    finish_element(&name, path.len(), &mut export, &mut thread, &mut post);
+                }
+            }
+            Event::Text(e) => {
+                let text = e.unescape().map_err(parse_error)?;
+                apply_text(&path, &text, &mut thread, &mut post);
+            }
+            Event::CData(e) => {
+                let text = String::from_utf8_lossy(&e.into_inner()).into_owned();
+                apply_text(&path, &text, &mut thread, &mut post);
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if export.threads.is_empty() && export.posts.is_empty() {
+        return Err(parse_error("no threads or posts found"));
+    }
+
+    Ok(export)
+}
+
+/// 顶层的 thread / post 元素结束时保存
+fn finish_element(
+    name: &str,
+    depth: usize,
+    export: &mut DisqusExport,
+    thread: &mut Option<DisqusThread>,
+    post: &mut Option<DisqusPost>,
+) {
+    if depth != 1 {
+        return;
+    }
+    match name {
+        "thread" => {
+            if let Some(thread) = thread.take() {
+                export.threads.insert(thread.id.clone(), thread);
+            }
+        }
+        "post" => {
+            if let Some(post) = post.take() {
+                export.posts.push(post);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn apply_text(path: &[String], text: &str, thread: &mut Option<DisqusThread>, post: &mut Option<DisqusPost>) {
+    let path: Vec<&str> = path.iter().map(String::as_str).collect();
+    match path.as_slice() {
+        [_, "thread", field] => {
+            if let Some(thread) = thread.as_mut() {
+                match *field {
+                    "id" => thread.identifier = Some(text.to_string()),
+                    "link" => thread.link.push_str(text),
+                    "title" => thread.title.push_str(text),
+                    _ => {}
+                }
+            }
+        }
+        [_, "post", field] => {
+            if let Some(post) = post.as_mut() {
+                match *field {
+                    "message" => post.message.push_str(text),
+                    "createdAt" => {
+                        post.created_at = DateTime::parse_from_rfc3339(text.trim())
+                            .ok()
+                            .map(|dt| dt.with_timezone(&Utc))
+                    }
+                    "isDeleted" => post.is_deleted = text.trim() == "true",
+                    "isSpam" => post.is_spam = text.trim() == "true",
+                    _ => {}
+                }
+            }
+        }
+        [_, "post", "author", field] => {
+            if let Some(post) = post.as_mut() {
+                match *field {
+                    "name" => post.author_name = Some(text.to_string()),
+                    "username" => post.author_username = Some(text.to_string()),
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 将 Disqus 的 HTML 评论转换为纯文本，保留段落和换行
+pub fn html_to_text(html: &str) -> String {
+    static BREAKS: OnceLock<Regex> = OnceLock::new();
+    static TAGS: OnceLock<Regex> = OnceLock::new();
+    static BLANK_LINES: OnceLock<Regex> = OnceLock::new();
+
+    let breaks = BREAKS.get_or_init(|| Regex::new(r"(?i)<br\s*/?>|</p\s*>").unwrap());
+    let tags = TAGS.get_or_init(|| Regex::new(r"<[^>]*>").unwrap());
+    let blank_lines = BLANK_LINES.get_or_init(|| Regex::new(r"\n{3,}").unwrap());
+
+    let text = breaks.replace_all(html, "\n");
+    let text = tags.replace_all(&text, "");
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+
+    blank_lines.replace_all(text.trim(), "\n\n").to_string()
+}
+
+/// 从原文章链接中提取 slug（最后一个非空路径段，去掉扩展名）
+pub fn slug_from_link(link: &str) -> Option<String> {
+    let path = url::Url::parse(link)
+        .map(|url| url.path().to_string())
+        .unwrap_or_else(|_| link.to_string());
+
+    path.split('/')
+        .rev()
+        .find(|segment| !segment.is_empty())
+        .map(|segment| segment.split('.').next().unwrap_or(segment).to_string())
+        .filter(|slug| !slug.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<disqus xmlns="http://disqus.com" xmlns:dsq="http://disqus.com/disqus-internals">
+  <category dsq:id="1"><forum>myblog</forum><title>General</title></category>
+  <thread dsq:id="100">
+    <id>hello-world</id>
+    <forum>myblog</forum>
+    <link>https://old.example.com/2019/05/hello-world.html</link>
+    <title>Hello &amp; World</title>
+    <createdAt>2019-05-01T10:00:00Z</createdAt>
+    <author><name>Owner</name><isAnonymous>false</isAnonymous></author>
+  </thread>
+  <post dsq:id="200">
+    <message><![CDATA[<p>Great post!</p>]]></message>
+    <createdAt>2019-05-02T08:30:00Z</createdAt>
+    <isDeleted>false</isDeleted>
+    <isSpam>false</isSpam>
+    <author><email>bob@example.com</email><name>Bob</name><isAnonymous>false</isAnonymous><username>bob</username></author>
+    <thread dsq:id="100" />
+  </post>
+  <post dsq:id="201">
+    <message><![CDATA[<p>Thanks Bob</p>]]></message>
+    <createdAt>2019-05-02T09:00:00Z</createdAt>
+    <isDeleted>false</isDeleted>
+    <isSpam>true</isSpam>
+    <author><name>Alice</name><isAnonymous>true</isAnonymous></author>
+    <thread dsq:id="100" />
+    <parent dsq:id="200" />
+  </post>
+</disqus>"#;
+
+    #[test]
+    fn test_parse_export() {
+        let export = parse_export(EXPORT).unwrap();
+
+        let thread = &export.threads["100"];
+        assert_eq!(thread.identifier.as_deref(), Some("hello-world"));
+        assert_eq!(thread.title, "Hello & World");
+        assert_eq!(slug_from_link(&thread.link).as_deref(), Some("hello-world"));
+
+        assert_eq!(export.posts.len(), 2);
+        let first = &export.posts[0];
+        assert_eq!(first.thread_id, "100");
+        assert_eq!(first.message, "<p>Great post!</p>");
+        assert_eq!(first.author_name.as_deref(), Some("Bob"));
+        assert_eq!(first.created_at.unwrap().to_rfc3339(), "2019-05-02T08:30:00+00:00");
+
+        let reply = &export.posts[1];
+        assert_eq!(reply.parent_id.as_deref(), Some("200"));
+        assert!(reply.is_spam);
+
+        assert_eq!(html_to_text("<p>One &amp; two</p><p>Three<br/>four</p>"), "One & two\nThree\nfour");
+    }
+}
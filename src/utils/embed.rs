@@ -0,0 +1,275 @@
+//! 正文中的嵌入内容：单独成段的 YouTube、Twitter/X、GitHub Gist、CodePen 链接
+//! 在服务端展开为嵌入 HTML。
+//!
+//! ```text
+//! 下面是演示视频：
+//!
+//! https://www.youtube.com/watch?v=dQw4w9WgXcQ
+//!
+//! 以及完整代码：
+//!
+//! https://gist.github.com/octocat/6cad326836d38bd3a7ae
+//! ```
+//!
+//! 视频和 CodePen 使用我们自己拼出的 iframe（地址固定为服务商的嵌入域名），
+//! 推文和 Gist 使用服务商返回的 HTML，清理后再缓存。没有取到嵌入内容的链接原样保留。
+
+use ammonia::Builder;
+use maplit::hashset;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// iframe 只允许指向这些地址
+pub const IFRAME_SOURCES: &[&str] = &["https://www.youtube-nocookie.com/embed/", "https://codepen.io/"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbedProvider {
+    YouTube,
+    Twitter,
+    Gist,
+    CodePen,
+}
+
+impl EmbedProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmbedProvider::YouTube => "youtube",
+            EmbedProvider::Twitter => "twitter",
+            EmbedProvider::Gist => "gist",
+            EmbedProvider::CodePen => "codepen",
+        }
+    }
+}
+
+/// 识别出的嵌入链接
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbedTarget {
+    pub provider: EmbedProvider,
+    /// 视频ID、推文ID、Gist ID 或 `用户/pen ID`
+    pub id: String,
+    pub url: String,
+}
+
+fn youtube_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"^https?://(?:(?:www|m)\.)?(?:youtube\.com/(?:watch\?(?:\S*&)?v=|shorts/|embed/)|youtu\.be/)([\w-]{11})(?:[?&#]\S*)?$")
+            .expect("valid youtube regex")
+    })
+}
+
+fn twitter_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"^https?://(?:(?:www|mobile)\.)?(?:twitter|x)\.com/\w{1,15}/status(?:es)?/(\d+)(?:[?#]\S*)?$")
+            .expect("valid twitter regex")
+    })
+}
+
+fn gist_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"^https?://gist\.github\.com/(?:[\w-]+/)?([0-9a-f]{5,40})/?(?:[?#]\S*)?$").expect("valid gist regex")
+    })
+}
+
+fn codepen_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r"^https?://codepen\.io/([\w-]+)/(?:pen|full|details)/(\w+)/?(?:[?#]\S*)?$").expect("valid codepen regex")
+    })
+}
+
+impl EmbedTarget {
+    pub fn parse(url: &str) -> Option<Self> {
+        let target = |provider, id: String| Some(Self { provider, id, url: url.to_string() });
+
+        if let Some(captures) = youtube_regex().captures(url) {
+            return target(EmbedProvider::YouTube, captures[1].to_string());
+        }
+        if let Some(captures) = twitter_regex().captures(url) {
+            return target(EmbedProvider::Twitter, captures[1].to_string());
+        }
+        if let Some(captures) = gist_regex().captures(url) {
+            return target(EmbedProvider::Gist, captures[1].to_string());
+        }
+        if let Some(captures) = codepen_regex().captures(url) {
+            return target(EmbedProvider::CodePen, format!("{}/{}", &captures[1], &captures[2]));
+        }
+        None
+    }
+
+    /// 获取嵌入信息的地址。Gist 没有 oEmbed，使用它的 JSON 嵌入接口
+    pub fn fetch_url(&self) -> String {
+        let encoded = urlencoding::encode(&self.url);
+        match self.provider {
+            EmbedProvider::YouTube => format!("https://www.youtube.com/oembed?format=json&url={}", encoded),
+            EmbedProvider::Twitter => {
+                format!("https://publish.twitter.com/oembed?omit_script=true&dnt=true&url={}", encoded)
+            }
+            EmbedProvider::Gist => format!("https://gist.github.com/{}.json", self.id),
+            EmbedProvider::CodePen => format!("https://codepen.io/api/oembed?format=json&url={}", encoded),
+        }
+    }
+
+    /// 由服务商返回的标题或 HTML 生成嵌入 HTML。推文和 Gist 需要 `html`，没有时返回 None
+    pub fn render(&self, title: Option<&str>, html: Option<&str>) -> Option<String> {
+        let body = match self.provider {
+            EmbedProvider::YouTube => render_iframe(
+                &format!("https://www.youtube-nocookie.com/embed/{}", self.id),
+                title.unwrap_or("YouTube video"),
+            ),
+            EmbedProvider::CodePen => {
+                let (user, pen) = self.id.split_once('/')?;
+                render_iframe(
+                    &format!("https://codepen.io/{}/embed/{}?default-tab=result", user, pen),
+                    title.unwrap_or("CodePen"),
+                )
+            }
+            EmbedProvider::Twitter | EmbedProvider::Gist => sanitize_provider_html(html?),
+        };
+
+        // HTML 块在空行处结束，嵌入内容中不能有空行
+        let body = body.lines().filter(|line| !line.trim().is_empty()).collect::<Vec<_>>().join("\n");
+        Some(format!(r#"<div class="embed embed-{}">{}</div>"#, self.provider.as_str(), body))
+    }
+}
+
+fn render_iframe(src: &str, title: &str) -> String {
+    format!(
+        r#"<iframe src="{}" title="{}" loading="lazy" allowfullscreen></iframe>"#,
+        escape_attribute(src),
+        escape_attribute(title)
+    )
+}
+
+/// 清理服务商返回的 HTML：去掉脚本和事件属性，只保留展示需要的标签和 class
+pub fn sanitize_provider_html(html: &str) -> String {
+    Builder::default()
+        .tags(hashset![
+            "blockquote", "p", "a", "br", "div", "span", "pre", "code",
+            "table", "tbody", "tr", "td"
+        ])
+        .generic_attributes(hashset!["class"])
+        .url_schemes(hashset!["http", "https"])
+        .clean(html)
+        .to_string()
+}
+
+/// 单独成段、且能识别为嵌入内容的链接所在的行，跳过代码块
+fn standalone_embed_lines<'a>(lines: &[&'a str]) -> Vec<(usize, &'a str)> {
+    let mut found = Vec::new();
+    let mut fence: Option<&str> = None;
+
+    for (index, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            continue;
+        }
+        // 缩进代码块
+        if line.starts_with("    ") || line.starts_with('\t') {
+            continue;
+        }
+
+        let url = trimmed.strip_prefix('<').and_then(|u| u.strip_suffix('>')).unwrap_or(trimmed);
+        let standalone = (index == 0 || lines[index - 1].trim().is_empty())
+            && lines.get(index + 1).map_or(true, |next| next.trim().is_empty());
+        if standalone && EmbedTarget::parse(url).is_some() {
+            found.push((index, url));
+        }
+    }
+    found
+}
+
+/// 正文中可以嵌入的链接，去重后按出现顺序返回
+pub fn referenced_embeds(markdown: &str) -> Vec<EmbedTarget> {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut targets: Vec<EmbedTarget> = Vec::new();
+    for (_, url) in standalone_embed_lines(&lines) {
+        if targets.iter().all(|target| target.url != url) {
+            targets.extend(EmbedTarget::parse(url));
+        }
+    }
+    targets
+}
+
+/// 把单独成段的链接替换为嵌入 HTML。`embeds` 为链接到嵌入 HTML 的映射，不在其中的链接原样保留
+pub fn expand_embeds(markdown: &str, embeds: &HashMap<String, String>) -> String {
+    if embeds.is_empty() {
+        return markdown.to_string();
+    }
+
+    let mut lines: Vec<String> = markdown.lines().map(str::to_string).collect();
+    let borrowed: Vec<&str> = markdown.lines().collect();
+    for (index, url) in standalone_embed_lines(&borrowed) {
+        if let Some(html) = embeds.get(url) {
+            lines[index] = html.clone();
+        }
+    }
+
+    let mut expanded = lines.join("\n");
+    if markdown.ends_with('\n') {
+        expanded.push('\n');
+    }
+    expanded
+}
+
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_embed_targets() {
+        let youtube = EmbedTarget::parse("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=42").unwrap();
+        assert_eq!((youtube.provider, youtube.id.as_str()), (EmbedProvider::YouTube, "dQw4w9WgXcQ"));
+        assert_eq!(EmbedTarget::parse("https://youtu.be/dQw4w9WgXcQ").unwrap().id, "dQw4w9WgXcQ");
+        assert_eq!(EmbedTarget::parse("https://x.com/rustlang/status/1234567890").unwrap().provider, EmbedProvider::Twitter);
+        assert_eq!(EmbedTarget::parse("https://gist.github.com/octocat/6cad326836d38bd3a7ae").unwrap().id, "6cad326836d38bd3a7ae");
+        assert_eq!(EmbedTarget::parse("https://codepen.io/team/pen/abcDEF").unwrap().id, "team/abcDEF");
+        assert!(EmbedTarget::parse("https://example.com/watch?v=dQw4w9WgXcQ").is_none());
+    }
+
+    #[test]
+    fn test_expand_embeds() {
+        let markdown = "Intro https://youtu.be/dQw4w9WgXcQ inline.\n\nhttps://youtu.be/dQw4w9WgXcQ\n\n```\nhttps://youtu.be/dQw4w9WgXcQ\n```\n";
+        assert_eq!(referenced_embeds(markdown).len(), 1);
+
+        let target = EmbedTarget::parse("https://youtu.be/dQw4w9WgXcQ").unwrap();
+        let html = target.render(Some("Demo \"video\""), None).unwrap();
+        assert_eq!(
+            html,
+            r#"<div class="embed embed-youtube"><iframe src="https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ" title="Demo &quot;video&quot;" loading="lazy" allowfullscreen></iframe></div>"#
+        );
+
+        let embeds = HashMap::from([(target.url.clone(), html.clone())]);
+        let expanded = expand_embeds(markdown, &embeds);
+        assert!(expanded.starts_with("Intro https://youtu.be/dQw4w9WgXcQ inline.\n\n<div class=\"embed embed-youtube\">"));
+        assert!(expanded.contains("```\nhttps://youtu.be/dQw4w9WgXcQ\n```"));
+    }
+
+    #[test]
+    fn test_sanitize_provider_html() {
+        let html = r#"<blockquote class="twitter-tweet" onclick="x()"><p>Hi</p><a href="javascript:alert(1)">link</a></blockquote><script src="https://platform.twitter.com/widgets.js"></script>"#;
+        let cleaned = sanitize_provider_html(html);
+        assert!(cleaned.starts_with(r#"<blockquote class="twitter-tweet"><p>Hi</p>"#));
+        assert!(!cleaned.contains("script"));
+        assert!(!cleaned.contains("javascript"));
+        assert!(!cleaned.contains("onclick"));
+    }
+}
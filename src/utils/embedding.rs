@@ -0,0 +1,115 @@
+//! 文章向量的输入文本和进程内的哈希向量模型。
+//!
+//! 哈希模型把词（中日韩文字按相邻两字）散列到固定维度，效果不如真正的语义模型，
+//! 但不依赖外部服务，适合开发环境或没有模型接口的部署。
+
+use sha2::{Digest, Sha256};
+
+/// 本地哈希模型的向量维度
+pub const LOCAL_DIMENSIONS: usize = 384;
+
+/// 送去计算向量的文本上限（字符），正文只取开头部分
+const MAX_INPUT_CHARS: usize = 8000;
+
+/// 计算向量用的文本：标题、副标题、摘要和正文
+pub fn article_text(title: &str, subtitle: Option<&str>, excerpt: Option<&str>, content: &str) -> String {
+    let text = [Some(title), subtitle, excerpt, Some(content)]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    text.chars().take(MAX_INPUT_CHARS).collect()
+}
+
+/// 输入文本的摘要，文本没变时不重新计算向量
+pub fn content_hash(text: &str) -> String {
+    hex::encode(Sha256::digest(text.as_bytes()))
+}
+
+/// 本地哈希模型：词频按符号散列到各维度后做 L2 归一化
+pub fn hashed_embedding(text: &str, dimensions: usize) -> Vec<f32> {
+    let mut vector = vec![0.0f32; dimensions];
+    for token in tokens(text) {
+        let hash = fnv1a(token.as_bytes());
+        let index = (hash % dimensions as u64) as usize;
+        vector[index] += if hash >> 63 == 0 { 1.0 } else { -1.0 };
+    }
+
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+    vector
+}
+
+/// 小写的字母数字词；中日韩文字没有空格分词，取相邻两字
+fn tokens(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut previous_cjk: Option<char> = None;
+
+    for ch in text.chars() {
+        if is_cjk(ch) {
+            if !word.is_empty() {
+                tokens.push(std::mem::take(&mut word));
+            }
+            match previous_cjk {
+                Some(prev) => tokens.push(format!("{}{}", prev, ch)),
+                None => tokens.push(ch.to_string()),
+            }
+            previous_cjk = Some(ch);
+            continue;
+        }
+
+        previous_cjk = None;
+        if ch.is_alphanumeric() {
+            word.extend(ch.to_lowercase());
+        } else if !word.is_empty() {
+            tokens.push(std::mem::take(&mut word));
+        }
+    }
+    if !word.is_empty() {
+        tokens.push(word);
+    }
+    tokens
+}
+
+fn is_cjk(ch: char) -> bool {
+    matches!(ch,
+        '\u{3040}'..='\u{30ff}'   // 平假名、片假名
+        | '\u{3400}'..='\u{4dbf}' // 扩展 A
+        | '\u{4e00}'..='\u{9fff}' // 基本汉字
+        | '\u{ac00}'..='\u{d7af}' // 韩文音节
+    )
+}
+
+/// 固定的 FNV-1a，保证不同版本、不同机器上算出的向量一致
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    #[test]
+    fn test_hashed_embedding() {
+        let rust = hashed_embedding("Writing async services in Rust with Tokio", LOCAL_DIMENSIONS);
+        let rust_again = hashed_embedding("Async Rust: building services on Tokio", LOCAL_DIMENSIONS);
+        let baking = hashed_embedding("A simple recipe for sourdough bread", LOCAL_DIMENSIONS);
+
+        assert_eq!(rust.len(), LOCAL_DIMENSIONS);
+        assert!((cosine(&rust, &rust) - 1.0).abs() < 1e-5);
+        assert!(cosine(&rust, &rust_again) > cosine(&rust, &baking));
+
+        assert_eq!(tokens("用Rust写服务"), vec!["用", "rust", "写", "写服", "服务"]);
+        assert!(hashed_embedding("", 8).iter().all(|v| *v == 0.0));
+    }
+}
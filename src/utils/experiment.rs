@@ -0,0 +1,37 @@
+//! 标题和封面实验的分组：同一读者在同一个实验中总是看到同一个版本，
+//! 不需要保存分组结果。
+
+use sha2::{Digest, Sha256};
+
+/// 版本的 key，按创建顺序分配
+pub const VARIANT_KEYS: [&str; 3] = ["a", "b", "c"];
+
+/// `visitor` 是 `ViewTrackingService::viewer_hash` 计算的读者标识。
+/// 加入实验 ID，使同一读者在不同实验中的分组互不相关
+pub fn assign_variant(experiment_id: &str, visitor: &str, variant_count: usize) -> usize {
+    if variant_count == 0 {
+        return 0;
+    }
+    let digest = Sha256::digest(format!("{}|{}", experiment_id, visitor).as_bytes());
+    let bucket = u64::from_be_bytes(digest[..8].try_into().expect("digest has 32 bytes"));
+    (bucket % variant_count as u64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assign_variant() {
+        let first = assign_variant("exp1", "visitor", 3);
+        assert!(first < 3);
+        assert_eq!(assign_variant("exp1", "visitor", 3), first);
+
+        // 读者足够多时每个版本都有人看到，分布大致均匀
+        let mut counts = [0usize; 2];
+        for i in 0..1000 {
+            counts[assign_variant("exp1", &format!("visitor{}", i), 2)] += 1;
+        }
+        assert!(counts.iter().all(|&c| c > 400), "{:?}", counts);
+    }
+}
@@ -0,0 +1,184 @@
+//! RSS 2.0 / Atom 订阅源渲染
+
+use crate::models::article::Article;
+use atom_syndication::{
+    ContentBuilder, EntryBuilder, FeedBuilder, LinkBuilder, PersonBuilder, Text,
+};
+use chrono::{DateTime, Utc};
+use rss::{extension::dublincore::DublinCoreExtensionBuilder, ChannelBuilder, GuidBuilder, ItemBuilder};
+
+/// 订阅源的频道信息
+#[derive(Debug, Clone)]
+pub struct FeedChannel {
+    pub title: String,
+    /// 频道主页
+    pub link: String,
+    /// 订阅源自身的地址
+    pub feed_url: String,
+    pub description: Option<String>,
+    pub image_url: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 订阅源中的一篇文章
+#[derive(Debug, Clone)]
+pub struct FeedEntry {
+    pub id: String,
+    pub title: String,
+    pub link: String,
+    pub author_name: Option<String>,
+    pub summary: Option<String>,
+    /// 付费文章为 None，只输出摘要
+    pub content_html: Option<String>,
+    pub published_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl FeedEntry {
+    pub fn from_article(article: &Article, link: String, author_name: Option<String>) -> Self {
+        Self {
+            id: article.id.clone(),
+            title: article.title.clone(),
+            link,
+            author_name,
+            summary: article.excerpt.clone().or_else(|| article.subtitle.clone()),
+            content_html: if article.is_paid_content {
+                None
+            } else {
+                Some(article.content_html.clone())
+            },
+            published_at: article.published_at.unwrap_or(article.created_at),
+            updated_at: article.updated_at,
+        }
+    }
+}
+
+pub fn render_rss(channel: &FeedChannel, entries: &[FeedEntry]) -> String {
+    let items = entries
+        .iter()
+        .map(|entry| {
+            let mut item = ItemBuilder::default();
+            item.title(Some(entry.title.clone()))
+                .link(Some(entry.link.clone()))
+                .guid(Some(GuidBuilder::default().value(entry.id.clone()).permalink(false).build()))
+                .pub_date(Some(entry.published_at.to_rfc2822()))
+                .description(entry.summary.clone())
+                .content(entry.content_html.clone());
+            if let Some(author_name) = &entry.author_name {
+                item.dublin_core_ext(Some(
+                    DublinCoreExtensionBuilder::default()
+                        .creators(vec![author_name.clone()])
+                        .build(),
+                ));
+            }
+            item.build()
+        })
+        .collect::<Vec<_>>();
+
+    let mut builder = ChannelBuilder::default();
+    builder
+        .title(channel.title.clone())
+        .link(channel.link.clone())
+        .description(channel.description.clone().unwrap_or_else(|| channel.title.clone()))
+        .last_build_date(Some(channel.updated_at.to_rfc2822()))
+        .generator(Some("Rainbow Blog".to_string()))
+        .items(items);
+    if let Some(image_url) = &channel.image_url {
+        builder.image(Some(
+            rss::ImageBuilder::default()
+                .url(image_url.clone())
+                .title(channel.title.clone())
+                .link(channel.link.clone())
+                .build(),
+        ));
+    }
+
+    builder.build().to_string()
+}
+
+pub fn render_atom(channel: &FeedChannel, entries: &[FeedEntry]) -> String {
+    let entries = entries
+        .iter()
+        .map(|entry| {
+            let mut builder = EntryBuilder::default();
+            builder
+                .id(entry.id.clone())
+                .title(Text::plain(entry.title.clone()))
+                .updated(entry.updated_at)
+                .published(Some(entry.published_at.into()))
+                .links(vec![LinkBuilder::default().href(entry.link.clone()).rel("alternate").build()])
+                .summary(entry.summary.clone().map(Text::plain))
+                .content(entry.content_html.clone().map(|html| {
+                    ContentBuilder::default()
+                        .value(Some(html))
+                        .content_type(Some("html".to_string()))
+                        .build()
+                }));
+            if let Some(author_name) = &entry.author_name {
+                builder.authors(vec![PersonBuilder::default().name(author_name.clone()).build()]);
+            }
+            builder.build()
+        })
+        .collect::<Vec<_>>();
+
+    FeedBuilder::default()
+        .id(channel.feed_url.clone())
+        .title(Text::plain(channel.title.clone()))
+        .subtitle(channel.description.clone().map(Text::plain))
+        .updated(channel.updated_at)
+        .icon(channel.image_url.clone())
+        .links(vec![
+            LinkBuilder::default().href(channel.link.clone()).rel("alternate").build(),
+            LinkBuilder::default().href(channel.feed_url.clone()).rel("self").build(),
+        ])
+        .entries(entries)
+        .build()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(content_html: Option<&str>) -> FeedEntry {
+        FeedEntry {
+            id: "article:1".to_string(),
+            title: "Hello & welcome".to_string(),
+            link: "https://blog.example.com/articles/hello".to_string(),
+            author_name: Some("Alice".to_string()),
+            summary: Some("Short excerpt".to_string()),
+            content_html: content_html.map(String::from),
+            published_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn channel() -> FeedChannel {
+        FeedChannel {
+            title: "Example".to_string(),
+            link: "https://blog.example.com".to_string(),
+            feed_url: "https://blog.example.com/feed.xml".to_string(),
+            description: None,
+            image_url: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_feeds_omit_content_for_paid_entries() {
+        let free = entry(Some("<p>Full body</p>"));
+        let paid = entry(None);
+
+        let rss = render_rss(&channel(), &[free.clone()]);
+        assert!(rss.contains("Hello &amp; welcome"));
+        assert!(rss.contains("Full body"));
+        assert!(!render_rss(&channel(), &[paid.clone()]).contains("Full body"));
+
+        let atom = render_atom(&channel(), &[free]);
+        assert!(atom.contains("Full body"));
+        assert!(atom.contains("rel=\"self\""));
+        let atom = render_atom(&channel(), &[paid]);
+        assert!(atom.contains("Short excerpt"));
+        assert!(!atom.contains("Full body"));
+    }
+}
@@ -0,0 +1,241 @@
+//! Markdown 中的图片排版：带说明的图片、多图画廊和对齐方式，渲染为 `<figure>`。
+//!
+//! - 单独成段的图片渲染为 figure，标题（`"..."`）作为说明：`![alt](url "说明"){.wide}`
+//! - 对齐方式：`{.left}`、`{.right}`、`{.center}`、`{.wide}`、`{.full}`
+//! - 画廊使用 `gallery` 代码块，每行一张图片，可以指定列数：
+//!
+//! ````text
+//! ```gallery columns=3
+//! ![](a.jpg "第一张")
+//! ![](b.jpg)
+//! ```
+//! ````
+//!
+//! 在交给 pulldown-cmark 之前展开为 HTML 块；图片有多个尺寸时附带 srcset。
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// 画廊的最大列数
+const MAX_GALLERY_COLUMNS: usize = 6;
+const DEFAULT_GALLERY_COLUMNS: usize = 3;
+
+fn figure_line_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r#"^\s*!\[([^\]]*)\]\((\S+?)(?:\s+"([^"]*)")?\)(?:\{\.(left|right|center|wide|full)\})?\s*$"#)
+            .expect("valid figure regex")
+    })
+}
+
+fn image_url_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"!\[[^\]]*\]\((\S+?)(?:\s+[^)]*)?\)").expect("valid image url regex"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Alignment {
+    Default,
+    Left,
+    Right,
+    Center,
+    Wide,
+    Full,
+}
+
+impl Alignment {
+    fn parse(value: Option<&str>) -> Self {
+        match value {
+            Some("left") => Alignment::Left,
+            Some("right") => Alignment::Right,
+            Some("center") => Alignment::Center,
+            Some("wide") => Alignment::Wide,
+            Some("full") => Alignment::Full,
+            _ => Alignment::Default,
+        }
+    }
+
+    fn class(&self) -> &'static str {
+        match self {
+            Alignment::Default => "figure",
+            Alignment::Left => "figure align-left",
+            Alignment::Right => "figure align-right",
+            Alignment::Center => "figure align-center",
+            Alignment::Wide => "figure align-wide",
+            Alignment::Full => "figure align-full",
+        }
+    }
+
+    /// 与前端版式一致：正文宽 768px，宽幅 1200px，左右浮动占半栏
+    fn sizes(&self) -> &'static str {
+        match self {
+            Alignment::Default | Alignment::Center => "(max-width: 768px) 100vw, 768px",
+            Alignment::Left | Alignment::Right => "(max-width: 768px) 100vw, 384px",
+            Alignment::Wide => "(max-width: 1200px) 100vw, 1200px",
+            Alignment::Full => "100vw",
+        }
+    }
+}
+
+struct FigureImage<'a> {
+    alt: &'a str,
+    url: &'a str,
+    caption: Option<&'a str>,
+    alignment: Alignment,
+}
+
+fn parse_figure_line(line: &str) -> Option<FigureImage<'_>> {
+    let captures = figure_line_regex().captures(line)?;
+    Some(FigureImage {
+        alt: captures.get(1).map_or("", |m| m.as_str()),
+        url: captures.get(2)?.as_str(),
+        caption: captures.get(3).map(|m| m.as_str()).filter(|c| !c.trim().is_empty()),
+        alignment: Alignment::parse(captures.get(4).map(|m| m.as_str())),
+    })
+}
+
+/// 正文引用的所有图片地址（包括画廊中的图片），用于查询图片的多个尺寸
+pub fn referenced_image_urls(markdown: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for captures in image_url_regex().captures_iter(markdown) {
+        let url = captures[1].to_string();
+        if !urls.contains(&url) {
+            urls.push(url);
+        }
+    }
+    urls
+}
+
+/// 把单独成段的图片和画廊代码块展开为 HTML 块，其他内容原样保留。
+/// `srcsets` 为图片地址到 srcset 属性值的映射
+pub fn expand_figures(markdown: &str, srcsets: &HashMap<String, String>) -> String {
+    let lines: Vec<&str> = markdown.lines().collect();
+    let mut output: Vec<String> = Vec::with_capacity(lines.len());
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = lines[index];
+        let trimmed = line.trim_start();
+
+        if trimmed.starts_with("```gallery") {
+            if let Some(end) = lines[index + 1..].iter().position(|l| l.trim_start().starts_with("```")) {
+                let columns = gallery_columns(trimmed);
+                let images: Vec<FigureImage> = lines[index + 1..index + 1 + end]
+                    .iter()
+                    .filter_map(|l| parse_figure_line(l))
+                    .collect();
+                output.push(render_gallery(&images, columns, srcsets));
+                // HTML 块在空行处结束，避免吞掉紧跟在后面的段落
+                output.push(String::new());
+                index += end + 2;
+                continue;
+            }
+        }
+
+        // 其他代码块原样保留
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            let marker = &trimmed[..3];
+            output.push(line.to_string());
+            index += 1;
+            while index < lines.len() {
+                output.push(lines[index].to_string());
+                index += 1;
+                if lines[index - 1].trim_start().starts_with(marker) {
+                    break;
+                }
+            }
+            continue;
+        }
+
+        let standalone = (index == 0 || lines[index - 1].trim().is_empty())
+            && lines.get(index + 1).map_or(true, |next| next.trim().is_empty());
+        match parse_figure_line(line).filter(|_| standalone) {
+            Some(image) => output.push(render_figure(&image, srcsets)),
+            None => output.push(line.to_string()),
+        }
+        index += 1;
+    }
+
+    let mut expanded = output.join("\n");
+    if markdown.ends_with('\n') {
+        expanded.push('\n');
+    }
+    expanded
+}
+
+fn gallery_columns(fence: &str) -> usize {
+    fence
+        .split_whitespace()
+        .find_map(|part| part.strip_prefix("columns="))
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_GALLERY_COLUMNS)
+        .clamp(1, MAX_GALLERY_COLUMNS)
+}
+
+fn render_img(image: &FigureImage, sizes: &str, srcsets: &HashMap<String, String>) -> String {
+    let mut img = format!(
+        r#"<img src="{}" alt="{}" loading="lazy""#,
+        escape_attribute(image.url),
+        escape_attribute(image.alt)
+    );
+    if let Some(srcset) = srcsets.get(image.url) {
+        img.push_str(&format!(r#" srcset="{}" sizes="{}""#, escape_attribute(srcset), sizes));
+    }
+    img.push('>');
+    img
+}
+
+fn render_figure(image: &FigureImage, srcsets: &HashMap<String, String>) -> String {
+    let mut html = format!(r#"<figure class="{}">"#, image.alignment.class());
+    html.push_str(&render_img(image, image.alignment.sizes(), srcsets));
+    if let Some(caption) = image.caption {
+        html.push_str(&format!("<figcaption>{}</figcaption>", escape_text(caption)));
+    }
+    html.push_str("</figure>");
+    html
+}
+
+fn render_gallery(images: &[FigureImage], columns: usize, srcsets: &HashMap<String, String>) -> String {
+    let sizes = format!("(max-width: 768px) 100vw, {}px", 1200 / columns);
+    let mut html = format!(r#"<div class="gallery gallery-columns-{}">"#, columns);
+    for image in images {
+        html.push_str(r#"<figure class="gallery-item">"#);
+        html.push_str(&render_img(image, &sizes, srcsets));
+        if let Some(caption) = image.caption {
+            html.push_str(&format!("<figcaption>{}</figcaption>", escape_text(caption)));
+        }
+        html.push_str("</figure>");
+    }
+    html.push_str("</div>");
+    html
+}
+
+fn escape_text(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn escape_attribute(value: &str) -> String {
+    escape_text(value).replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_figures() {
+        let markdown = "Intro text.\n\n![Sunset](/img/sunset.jpg \"Golden hour\"){.wide}\n\n```gallery columns=2\n![A](/a.jpg)\n![B](/b.jpg \"Second\")\n```\n\n```md\n![Code](/c.jpg)\n```\n\nInline ![icon](/i.png) stays.\n";
+        let srcsets = HashMap::from([("/a.jpg".to_string(), "/a-480.jpg 480w, /a.jpg 1200w".to_string())]);
+
+        let expanded = expand_figures(markdown, &srcsets);
+
+        assert!(expanded.contains(
+            r#"<figure class="figure align-wide"><img src="/img/sunset.jpg" alt="Sunset" loading="lazy"><figcaption>Golden hour</figcaption></figure>"#
+        ));
+        assert!(expanded.contains(r#"<div class="gallery gallery-columns-2"><figure class="gallery-item"><img src="/a.jpg" alt="A" loading="lazy" srcset="/a-480.jpg 480w, /a.jpg 1200w" sizes="(max-width: 768px) 100vw, 600px">"#));
+        assert!(expanded.contains("```md\n![Code](/c.jpg)\n```"));
+        assert!(expanded.contains("Inline ![icon](/i.png) stays."));
+        assert_eq!(referenced_image_urls(markdown), vec!["/img/sunset.jpg", "/a.jpg", "/b.jpg", "/c.jpg", "/i.png"]);
+    }
+}
@@ -0,0 +1,163 @@
+//! 只读接口的 HTTP 缓存：按路由设置 `Cache-Control`，根据响应体计算 ETag，
+//! 处理 `If-None-Match` 和 `If-Modified-Since` 条件请求。
+//!
+//! 路由规则按顺序匹配，`HTTP_CACHE_RULES` 中配置的规则优先于内置规则：
+//!
+//! ```json
+//! [{ "path": "/api/blog/articles/:slug", "cache_control": "public, max-age=300" },
+//!  { "path": "/api/blog/tags/*", "cache_control": null }]
+//! ```
+//!
+//! `:name` 匹配一段路径，末尾的 `*` 匹配剩余部分；`cache_control` 为空表示该路由不缓存。
+
+use crate::{
+    config::Config,
+    error::{AppError, Result},
+};
+use axum::http::HeaderValue;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+/// 登录用户的响应包含个人状态（是否已收藏、已点赞），只允许浏览器缓存并且每次重新验证
+pub const PRIVATE_CACHE_CONTROL: &str = "private, no-cache";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CacheRule {
+    pub path: String,
+    pub cache_control: Option<String>,
+}
+
+impl CacheRule {
+    fn matches(&self, path: &str) -> bool {
+        let mut pattern = self.path.trim_end_matches('/').split('/');
+        let mut segments = path.trim_end_matches('/').split('/');
+        loop {
+            match (pattern.next(), segments.next()) {
+                (Some("*"), _) => return true,
+                (Some(expected), Some(actual)) => {
+                    let matched = if expected.starts_with(':') { !actual.is_empty() } else { expected == actual };
+                    if !matched {
+                        return false;
+                    }
+                }
+                (None, None) => return true,
+                _ => return false,
+            }
+        }
+    }
+}
+
+/// 各路由的缓存规则
+#[derive(Debug, Clone)]
+pub struct HttpCacheRules {
+    rules: Arc<Vec<CacheRule>>,
+}
+
+impl HttpCacheRules {
+    pub fn new(config: &Config) -> Result<Self> {
+        let mut rules: Vec<CacheRule> = match &config.http_cache_rules {
+            Some(json) => serde_json::from_str(json)
+                .map_err(|e| AppError::Internal(format!("Invalid HTTP_CACHE_RULES: {}", e)))?,
+            None => Vec::new(),
+        };
+        rules.extend(builtin_rules());
+        Ok(Self { rules: Arc::new(rules) })
+    }
+
+    /// 路由的 `Cache-Control`，不缓存的路由返回 None
+    pub fn cache_control_for(&self, path: &str) -> Option<&str> {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(path))
+            .and_then(|rule| rule.cache_control.as_deref())
+    }
+}
+
+/// 内置规则：文章、出版物和订阅源的公开读取接口。与 slug 路由同级的个人接口放在前面排除
+fn builtin_rules() -> Vec<CacheRule> {
+    let rule = |path: &str, cache_control: Option<&str>| CacheRule {
+        path: path.to_string(),
+        cache_control: cache_control.map(str::to_string),
+    };
+
+    vec![
+        rule("/api/blog/articles/view-token", None),
+        rule("/api/blog/articles/pending-review", None),
+        rule("/api/blog/articles/popular/stream", None),
+        rule("/api/blog/articles", Some("public, max-age=60")),
+        rule("/api/blog/articles/:slug", Some("public, max-age=60, stale-while-revalidate=300")),
+        rule("/api/blog/publications", Some("public, max-age=120")),
+        rule("/api/blog/publications/:slug", Some("public, max-age=120")),
+        rule("/api/blog/publications/:slug/articles", Some("public, max-age=60")),
+        rule("/api/blog/feeds/*", Some("public, max-age=300")),
+        rule("/feed.xml", Some("public, max-age=300")),
+        rule("/atom.xml", Some("public, max-age=300")),
+    ]
+}
+
+/// 响应体的弱 ETag。压缩在外层进行，压缩后的字节不同，所以不能作为强校验
+pub fn etag(body: &[u8]) -> String {
+    format!("W/\"{}\"", hex::encode(&Sha256::digest(body)[..16]))
+}
+
+/// `If-None-Match` 是否包含当前 ETag（弱比较）
+pub fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let current = opaque(etag);
+    if_none_match
+        .split(',')
+        .any(|candidate| candidate.trim() == "*" || opaque(candidate) == current)
+}
+
+/// `Last-Modified` 响应头，HTTP 日期精确到秒
+pub fn last_modified(time: DateTime<Utc>) -> HeaderValue {
+    HeaderValue::from_str(&time.format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+        .expect("HTTP date is a valid header value")
+}
+
+/// `If-Modified-Since` 之后是否没有修改。日期无法解析时视为已修改
+pub fn not_modified_since(if_modified_since: &str, last_modified: &str) -> bool {
+    match (
+        DateTime::parse_from_rfc2822(if_modified_since.trim()),
+        DateTime::parse_from_rfc2822(last_modified.trim()),
+    ) {
+        (Ok(since), Ok(modified)) => modified <= since,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_rule_matching() {
+        let rules = HttpCacheRules { rules: Arc::new(builtin_rules()) };
+        assert!(rules.cache_control_for("/api/blog/articles/hello-world").is_some());
+        assert!(rules.cache_control_for("/api/blog/articles/").is_some());
+        assert!(rules.cache_control_for("/api/blog/articles/view-token").is_none());
+        assert!(rules.cache_control_for("/api/blog/articles/by-id/1/revisions").is_none());
+        assert!(rules.cache_control_for("/api/blog/feeds/users/alice/rss").is_some());
+        assert!(rules.cache_control_for("/api/blog/publications/acme/members").is_none());
+    }
+
+    #[test]
+    fn test_conditional_headers() {
+        let tag = etag(b"{\"success\":true}");
+        assert!(tag.starts_with("W/\""));
+        assert!(etag_matches(&tag, &tag));
+        assert!(etag_matches(&format!("\"other\", {}", tag.trim_start_matches("W/")), &tag));
+        assert!(etag_matches("*", &tag));
+        assert!(!etag_matches("\"other\"", &tag));
+
+        let modified = Utc.with_ymd_and_hms(2024, 1, 20, 8, 30, 0).unwrap();
+        let header = last_modified(modified);
+        assert_eq!(header, "Sat, 20 Jan 2024 08:30:00 GMT");
+        assert!(not_modified_since("Sat, 20 Jan 2024 08:30:00 GMT", header.to_str().unwrap()));
+        assert!(!not_modified_since("Sat, 20 Jan 2024 08:29:59 GMT", header.to_str().unwrap()));
+        assert!(!not_modified_since("yesterday", header.to_str().unwrap()));
+    }
+}
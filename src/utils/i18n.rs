@@ -0,0 +1,219 @@
+//! 错误信息和通知的多语言支持。
+//!
+//! 代码中的提示文字仍然直接写成中文或英文，翻译放在 `locales/<语言>.json` 的
+//! `messages` 中，以原文为键（与 gettext 的 msgid 相同）。原文中有变化部分的，
+//! 键里用 `{name}` 占位，翻译中用同名占位引用：
+//!
+//! ```json
+//! { "messages": { "New story from {author}": "{author} 发布了新文章" } }
+//! ```
+//!
+//! 目录中没有的文字原样返回。请求的语言由 `locale_middleware` 根据 `Accept-Language`
+//! 协商，在处理请求期间可以通过 `current_locale` 读取。
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::future::Future;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Locale {
+    ZhCn,
+    En,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::ZhCn, Locale::En];
+
+    /// BCP 47 语言标签，用于 `Content-Language`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::ZhCn => "zh-CN",
+            Locale::En => "en",
+        }
+    }
+
+    /// 按主语言匹配，`zh-TW`、`zh-Hans` 等都使用简体中文
+    pub fn parse(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        match primary.as_str() {
+            "zh" => Some(Locale::ZhCn),
+            "en" => Some(Locale::En),
+            _ => None,
+        }
+    }
+}
+
+/// 按 `Accept-Language` 的权重选出支持的语言，没有可用的语言时返回 None
+pub fn negotiate(accept_language: &str) -> Option<Locale> {
+    let mut candidates: Vec<(f32, usize, &str)> = accept_language
+        .split(',')
+        .enumerate()
+        .filter_map(|(position, part)| {
+            let mut pieces = part.split(';');
+            let tag = pieces.next()?.trim();
+            let quality = pieces
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+            (!tag.is_empty() && quality > 0.0).then_some((quality, position, tag))
+        })
+        .collect();
+    // 权重相同时保持原来的顺序
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+    candidates.into_iter().find_map(|(_, _, tag)| Locale::parse(tag))
+}
+
+tokio::task_local! {
+    static CURRENT_LOCALE: Locale;
+}
+
+/// 在指定语言下执行，期间 `current_locale` 返回该语言
+pub async fn scope<F: Future>(locale: Locale, future: F) -> F::Output {
+    CURRENT_LOCALE.scope(locale, future).await
+}
+
+/// 当前请求协商出的语言，不在请求处理中（如后台任务）时返回 None
+pub fn current_locale() -> Option<Locale> {
+    CURRENT_LOCALE.try_with(|locale| *locale).ok()
+}
+
+/// 把一段文字翻译为指定语言，目录中没有时原样返回
+pub fn translate(locale: Locale, text: &str) -> String {
+    CATALOGS
+        .get(&locale)
+        .and_then(|catalog| catalog.translate(text))
+        .unwrap_or_else(|| text.to_string())
+}
+
+/// 按当前请求的语言翻译
+pub fn translate_current(text: &str) -> String {
+    match current_locale() {
+        Some(locale) => translate(locale, text),
+        None => text.to_string(),
+    }
+}
+
+#[derive(Deserialize)]
+struct CatalogFile {
+    messages: HashMap<String, String>,
+}
+
+struct Template {
+    pattern: Regex,
+    names: Vec<String>,
+    translation: String,
+}
+
+struct Catalog {
+    exact: HashMap<String, String>,
+    templates: Vec<Template>,
+}
+
+fn placeholder_regex() -> &'static Regex {
+    static REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{(\w+)\}").expect("valid placeholder regex"));
+    &REGEX
+}
+
+impl Catalog {
+    fn parse(source: &str) -> Self {
+        let file: CatalogFile = serde_json::from_str(source).expect("valid message catalog");
+        let mut exact = HashMap::new();
+        let mut templates = Vec::new();
+
+        for (key, translation) in file.messages {
+            if !placeholder_regex().is_match(&key) {
+                exact.insert(key, translation);
+                continue;
+            }
+
+            let mut pattern = String::from("^");
+            let mut names = Vec::new();
+            let mut last = 0;
+            for captures in placeholder_regex().captures_iter(&key) {
+                let whole = captures.get(0).expect("whole match");
+                pattern.push_str(&regex::escape(&key[last..whole.start()]));
+                pattern.push_str("(.+?)");
+                names.push(captures[1].to_string());
+                last = whole.end();
+            }
+            pattern.push_str(&regex::escape(&key[last..]));
+            pattern.push('$');
+
+            templates.push(Template {
+                pattern: Regex::new(&pattern).expect("valid message template"),
+                names,
+                translation,
+            });
+        }
+
+        // 固定部分越长的模板越具体，优先匹配
+        templates.sort_by_key(|t| std::cmp::Reverse(t.pattern.as_str().len()));
+        Self { exact, templates }
+    }
+
+    fn translate(&self, text: &str) -> Option<String> {
+        if let Some(translation) = self.exact.get(text) {
+            return Some(translation.clone());
+        }
+
+        self.templates.iter().find_map(|template| {
+            let captures = template.pattern.captures(text)?;
+            let values: HashMap<&str, &str> = template
+                .names
+                .iter()
+                .enumerate()
+                .map(|(index, name)| (name.as_str(), captures.get(index + 1).map_or("", |m| m.as_str())))
+                .collect();
+            Some(
+                placeholder_regex()
+                    .replace_all(&template.translation, |c: &regex::Captures| {
+                        values.get(&c[1]).copied().unwrap_or_default().to_string()
+                    })
+                    .into_owned(),
+            )
+        })
+    }
+}
+
+static CATALOGS: Lazy<HashMap<Locale, Catalog>> = Lazy::new(|| {
+    HashMap::from([
+        (Locale::ZhCn, Catalog::parse(include_str!("../../locales/zh-CN.json"))),
+        (Locale::En, Catalog::parse(include_str!("../../locales/en.json"))),
+    ])
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_accept_language() {
+        assert_eq!(negotiate("en-US,en;q=0.9,zh-CN;q=0.8"), Some(Locale::En));
+        assert_eq!(negotiate("fr-FR, zh-TW;q=0.7, en;q=0.5"), Some(Locale::ZhCn));
+        assert_eq!(negotiate("en;q=0.3, zh;q=0.9"), Some(Locale::ZhCn));
+        assert_eq!(negotiate("fr, de;q=0.5"), None);
+        assert_eq!(negotiate("en;q=0, zh"), Some(Locale::ZhCn));
+    }
+
+    #[test]
+    fn test_translate_messages_and_templates() {
+        assert_eq!(translate(Locale::ZhCn, "Article not found"), "文章不存在");
+        assert_eq!(translate(Locale::En, "文章不存在"), "Article not found");
+        assert_eq!(translate(Locale::ZhCn, "New story from Alice"), "Alice 发布了新文章");
+        assert_eq!(translate(Locale::En, "Article not found"), "Article not found");
+        assert_eq!(translate(Locale::ZhCn, "Something without an entry"), "Something without an entry");
+    }
+
+    #[test]
+    fn test_catalogs_cover_the_same_templates() {
+        // 每个模板的翻译只能引用原文中出现过的占位
+        for locale in Locale::ALL {
+            for template in &CATALOGS[&locale].templates {
+                for name in placeholder_regex().captures_iter(&template.translation) {
+                    assert!(template.names.contains(&name[1].to_string()), "{:?}: {}", locale, template.translation);
+                }
+            }
+        }
+    }
+}
@@ -1,5 +1,10 @@
 use std::io::Cursor;
 use base64::{Engine as _, engine::general_purpose};
+use image::{
+    codecs::{avif::AvifEncoder, jpeg::JpegEncoder, png::PngEncoder, webp::{WebPEncoder, WebPQuality}},
+    imageops::FilterType,
+    ColorType, DynamicImage, ImageEncoder,
+};
 use serde::{Deserialize, Serialize};
 
 /// 图片格式枚举
@@ -9,6 +14,7 @@ pub enum ImageFormat {
     Png,
     Webp,
     Gif,
+    Avif,
 }
 
 impl ImageFormat {
@@ -19,6 +25,7 @@ impl ImageFormat {
             "image/png" => Some(Self::Png),
             "image/webp" => Some(Self::Webp),
             "image/gif" => Some(Self::Gif),
+            "image/avif" => Some(Self::Avif),
             _ => None,
         }
     }
@@ -30,6 +37,7 @@ impl ImageFormat {
             Self::Png => "image/png",
             Self::Webp => "image/webp",
             Self::Gif => "image/gif",
+            Self::Avif => "image/avif",
         }
     }
     
@@ -40,6 +48,7 @@ impl ImageFormat {
             Self::Png => "png",
             Self::Webp => "webp",
             Self::Gif => "gif",
+            Self::Avif => "avif",
         }
     }
 }
@@ -124,6 +133,8 @@ impl ImageProcessor {
                     && &data[0..4] == b"RIFF" 
                     && &data[8..12] == b"WEBP" {
                     Ok(ImageFormat::Webp)
+                } else if data.len() >= 12 && &data[4..12] == b"ftypavif" {
+                    Ok(ImageFormat::Avif)
                 } else {
                     Err("不支持的图片格式".to_string())
                 }
@@ -140,6 +151,9 @@ impl ImageProcessor {
             ImageFormat::Jpeg => Self::get_jpeg_dimensions(data),
             ImageFormat::Gif => Self::get_gif_dimensions(data),
             ImageFormat::Webp => Self::get_webp_dimensions(data),
+            ImageFormat::Avif => imagesize::blob_size(data)
+                .map(|size| ImageDimensions { width: size.width as u32, height: size.height as u32 })
+                .map_err(|e| format!("无法读取AVIF尺寸: {}", e)),
         }
     }
 
@@ -228,6 +242,7 @@ impl ImageProcessor {
             ImageFormat::Png => true,  // PNG支持透明度
             ImageFormat::Gif => true,  // GIF支持透明度
             ImageFormat::Webp => true, // WebP支持透明度
+            ImageFormat::Avif => true,
             ImageFormat::Jpeg => false, // JPEG不支持透明度
         }
     }
@@ -323,8 +338,7 @@ impl ImageProcessor {
         format!("data:{};base64,{}", format.to_mime_type(), encoded)
     }
 
-    /// 生成图片缩略图（简化版本，返回原始数据）
-    /// 在实际项目中，应该使用image crate来进行真正的图片处理
+    /// 按配置缩放图片，格式未指定时保持原格式
     pub fn generate_thumbnail(
         data: &[u8],
         config: &ImageProcessConfig,
@@ -334,25 +348,16 @@ impl ImageProcessor {
         
         let metadata = Self::get_metadata(data)?;
         let new_dimensions = Self::calculate_resize_dimensions(&metadata.dimensions, config);
+        let format = config.format.clone().unwrap_or_else(|| metadata.format.clone());
         
-        // 如果尺寸没有变化，直接返回原始数据
-        if new_dimensions == metadata.dimensions {
+        // 尺寸和格式都没有变化，直接返回原始数据
+        if new_dimensions == metadata.dimensions && format == metadata.format {
             return Ok(data.to_vec());
         }
         
-        // TODO: 实际的图片缩放处理
-        // 这里应该使用image crate进行真正的图片处理
-        // 现在返回原始数据作为占位符
-        
-        tracing::warn!(
-            "图片缩放功能尚未实现，返回原始图片。原始尺寸: {}x{}, 目标尺寸: {}x{}",
-            metadata.dimensions.width,
-            metadata.dimensions.height,
-            new_dimensions.width,
-            new_dimensions.height
-        );
-        
-        Ok(data.to_vec())
+        let image = Self::decode_oriented(data)?;
+        let resized = image.resize_exact(new_dimensions.width, new_dimensions.height, FilterType::Lanczos3);
+        Self::encode(&resized, &format, config.quality.unwrap_or(85))
     }
 
     /// 优化图片（简化版本）
@@ -362,6 +367,180 @@ impl ImageProcessor {
     ) -> Result<Vec<u8>, String> {
         Self::generate_thumbnail(data, config)
     }
+
+    /// 解码图片并按 EXIF 方向旋转。解码后的像素不带任何元数据
+    pub fn decode_oriented(data: &[u8]) -> Result<DynamicImage, String> {
+        let image = image::load_from_memory(data).map_err(|e| format!("图片解码失败: {}", e))?;
+
+        let orientation = exif::Reader::new()
+            .read_from_container(&mut Cursor::new(data))
+            .ok()
+            .and_then(|exif| {
+                exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)
+                    .and_then(|field| field.value.get_uint(0))
+            })
+            .unwrap_or(1);
+
+        Ok(match orientation {
+            2 => image.fliph(),
+            3 => image.rotate180(),
+            4 => image.flipv(),
+            5 => image.rotate90().fliph(),
+            6 => image.rotate90(),
+            7 => image.rotate270().fliph(),
+            8 => image.rotate270(),
+            _ => image,
+        })
+    }
+
+    /// 编码为指定格式，`quality` 用于 JPEG、WebP 和 AVIF
+    pub fn encode(image: &DynamicImage, format: &ImageFormat, quality: u8) -> Result<Vec<u8>, String> {
+        let quality = quality.clamp(1, 100);
+        let mut buffer = Vec::new();
+        let result = match format {
+            ImageFormat::Jpeg => {
+                // JPEG 不支持透明通道
+                let rgb = image.to_rgb8();
+                JpegEncoder::new_with_quality(&mut buffer, quality)
+                    .write_image(&rgb, rgb.width(), rgb.height(), ColorType::Rgb8)
+            }
+            ImageFormat::Png => {
+                let rgba = image.to_rgba8();
+                PngEncoder::new(&mut buffer).write_image(&rgba, rgba.width(), rgba.height(), ColorType::Rgba8)
+            }
+            ImageFormat::Webp => {
+                let rgba = image.to_rgba8();
+                WebPEncoder::new_with_quality(&mut buffer, WebPQuality::lossy(quality))
+                    .write_image(&rgba, rgba.width(), rgba.height(), ColorType::Rgba8)
+            }
+            ImageFormat::Avif => {
+                let rgba = image.to_rgba8();
+                AvifEncoder::new_with_speed_quality(&mut buffer, AVIF_SPEED, quality)
+                    .write_image(&rgba, rgba.width(), rgba.height(), ColorType::Rgba8)
+            }
+            ImageFormat::Gif => return Err("不支持编码为GIF".to_string()),
+        };
+        result.map_err(|e| format!("{} 编码失败: {}", format.to_extension(), e))?;
+        Ok(buffer)
+    }
+
+    /// 生成原图、响应式尺寸、缩略图和 blurhash 占位。
+    /// 所有输出都重新编码，上传文件中的 EXIF（拍摄地点、设备等）不会保留；
+    /// GIF 可能是动图，原图保持不变，只生成缩略图和占位
+    pub fn process_responsive(data: &[u8], config: &ResponsiveImageConfig) -> Result<ProcessedImage, String> {
+        let format = Self::detect_format(data)?;
+        let mut image = Self::decode_oriented(data)?;
+        if image.width() == 0 || image.height() == 0 {
+            return Err("图片尺寸无效".to_string());
+        }
+
+        let thumbnail = Self::encode_sized(
+            &image.resize_to_fill(THUMBNAIL_SIZE, THUMBNAIL_SIZE, FilterType::Lanczos3),
+            ImageFormat::Webp,
+            config.quality,
+        )?;
+        let blurhash = Self::blurhash(&image)?;
+
+        if format == ImageFormat::Gif {
+            return Ok(ProcessedImage {
+                original: EncodedImage {
+                    format,
+                    width: image.width(),
+                    height: image.height(),
+                    data: data.to_vec(),
+                },
+                variants: Vec::new(),
+                thumbnail,
+                blurhash,
+            });
+        }
+
+        // 过大的原图按最大尺寸缩小后再保存
+        if image.width() > config.max_width || image.height() > config.max_height {
+            image = image.resize(config.max_width, config.max_height, FilterType::Lanczos3);
+        }
+        let original = Self::encode_sized(&image, format.clone(), config.quality)?;
+
+        let mut modern_formats = vec![ImageFormat::Webp];
+        if config.avif {
+            modern_formats.insert(0, ImageFormat::Avif);
+        }
+
+        let mut variants = Vec::new();
+        for width in responsive_widths(image.width(), &config.widths) {
+            let resized = image.resize(width, image.height(), FilterType::Lanczos3);
+            variants.push(Self::encode_sized(&resized, format.clone(), config.quality)?);
+            for modern in &modern_formats {
+                variants.push(Self::encode_sized(&resized, modern.clone(), config.quality)?);
+            }
+        }
+        // 原尺寸的 WebP / AVIF 版本
+        for modern in modern_formats {
+            if modern != format {
+                variants.push(Self::encode_sized(&image, modern, config.quality)?);
+            }
+        }
+
+        Ok(ProcessedImage { original, variants, thumbnail, blurhash })
+    }
+
+    fn encode_sized(image: &DynamicImage, format: ImageFormat, quality: u8) -> Result<EncodedImage, String> {
+        Ok(EncodedImage {
+            data: Self::encode(image, &format, quality)?,
+            format,
+            width: image.width(),
+            height: image.height(),
+        })
+    }
+
+    /// 缩小后计算 blurhash，避免对大图逐像素计算
+    fn blurhash(image: &DynamicImage) -> Result<String, String> {
+        let small = image.thumbnail(BLURHASH_SAMPLE_SIZE, BLURHASH_SAMPLE_SIZE).to_rgba8();
+        blurhash::encode(4, 3, small.width(), small.height(), small.as_raw())
+            .map_err(|e| format!("生成 blurhash 失败: {}", e))
+    }
+}
+
+/// 缩略图为正方形裁剪
+const THUMBNAIL_SIZE: u32 = 320;
+const BLURHASH_SAMPLE_SIZE: u32 = 32;
+/// rav1e 速度档位（1-10），越大越快、压缩率越低
+const AVIF_SPEED: u8 = 8;
+
+/// 响应式图片配置
+#[derive(Debug, Clone)]
+pub struct ResponsiveImageConfig {
+    pub max_width: u32,
+    pub max_height: u32,
+    pub quality: u8,
+    /// 生成的宽度，大于等于原图宽度的会被跳过
+    pub widths: Vec<u32>,
+    pub avif: bool,
+}
+
+/// 编码后的单个图片文件
+#[derive(Debug, Clone)]
+pub struct EncodedImage {
+    pub format: ImageFormat,
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProcessedImage {
+    pub original: EncodedImage,
+    pub variants: Vec<EncodedImage>,
+    pub thumbnail: EncodedImage,
+    pub blurhash: String,
+}
+
+/// 小于原图宽度的目标宽度，从小到大去重
+pub fn responsive_widths(original_width: u32, widths: &[u32]) -> Vec<u32> {
+    let mut widths: Vec<u32> = widths.iter().copied().filter(|w| *w > 0 && *w < original_width).collect();
+    widths.sort_unstable();
+    widths.dedup();
+    widths
 }
 
 /// 图片工具函数
@@ -451,6 +630,34 @@ mod tests {
         assert_eq!(new_dims.height, 450); // 保持16:9比例
     }
 
+    #[test]
+    fn test_responsive_widths() {
+        assert_eq!(responsive_widths(1200, &[1600, 480, 960, 480]), vec![480, 960]);
+        assert!(responsive_widths(300, &[480, 960]).is_empty());
+    }
+
+    #[test]
+    fn test_process_responsive() {
+        let source = DynamicImage::new_rgb8(1000, 500);
+        let png = ImageProcessor::encode(&source, &ImageFormat::Png, 85).unwrap();
+        let config = ResponsiveImageConfig {
+            max_width: 800,
+            max_height: 800,
+            quality: 80,
+            widths: vec![400, 1600],
+            avif: false,
+        };
+
+        let processed = ImageProcessor::process_responsive(&png, &config).unwrap();
+        assert_eq!((processed.original.width, processed.original.height), (800, 400));
+        assert_eq!(processed.thumbnail.width, THUMBNAIL_SIZE);
+        assert!(!processed.blurhash.is_empty());
+
+        // 400 宽的 PNG 和 WebP，加上原尺寸的 WebP
+        let variants: Vec<(u32, ImageFormat)> = processed.variants.iter().map(|v| (v.width, v.format.clone())).collect();
+        assert_eq!(variants, vec![(400, ImageFormat::Png), (400, ImageFormat::Webp), (800, ImageFormat::Webp)]);
+    }
+
     #[test]
     fn test_base64_encoding_decoding() {
         let test_data = b"test image data";
@@ -1,6 +1,7 @@
 use pulldown_cmark::{html, Options, Parser, Event, Tag, CodeBlockKind};
-use syntect::html::{ClassedHTMLGenerator, ClassStyle};
-use syntect::parsing::SyntaxSet;
+use syntect::html::{line_tokens_to_classed_spans, ClassStyle};
+use syntect::parsing::{ParseState, ScopeStack, SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
 use syntect::highlighting::ThemeSet;
 use ammonia::Builder;
 use std::collections::{HashMap, HashSet};
@@ -8,10 +9,16 @@ use regex::Regex;
 use once_cell::sync::Lazy;
 use serde::{Serialize, Deserialize};
 use maplit::{hashset, hashmap};
+use utoipa::ToSchema;
+
+use super::{embed, figure};
 
 static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
 static THEME_SET: Lazy<ThemeSet> = Lazy::new(ThemeSet::load_defaults);
 
+const SEO_DESCRIPTION_MAX_LENGTH: usize = 160;
+const SIGNIFICANT_CHANGE_LEAD_LENGTH: usize = 500;
+
 #[derive(Clone)]
 pub struct MarkdownProcessor {}
 
@@ -40,7 +47,9 @@ impl MarkdownProcessor {
             "a", "img",
             "table", "thead", "tbody", "tr", "th", "td",
             "div", "span",
-            "sup", "sub"
+            "sup", "sub",
+            "figure", "figcaption",
+            "iframe"
         ]);
 
         // 配置标签属性
@@ -48,30 +57,56 @@ impl MarkdownProcessor {
         // 注意：ammonia 3.3.0 有一个bug，不能显式地设置 'rel' 属性
         // 它会自动为外部链接添加 rel="noopener noreferrer"
         tag_attrs.insert("a", hashset!["href", "title", "target"]);
-        tag_attrs.insert("img", hashset!["src", "alt", "title", "width", "height"]);
+        for heading in ["h1", "h2", "h3", "h4", "h5", "h6"] {
+            tag_attrs.insert(heading, hashset!["id"]);
+        }
+        tag_attrs.insert("img", hashset!["src", "alt", "title", "width", "height", "srcset", "sizes", "loading"]);
         tag_attrs.insert("pre", hashset!["class"]);
         tag_attrs.insert("code", hashset!["class"]);
         tag_attrs.insert("div", hashset!["class"]);
-        tag_attrs.insert("span", hashset!["class"]);
+        tag_attrs.insert("span", hashset!["class", "data-line"]);
+        tag_attrs.insert("figure", hashset!["class"]);
+        // 嵌入内容：推文的 blockquote、Gist 的代码表格和视频/CodePen 的 iframe
+        tag_attrs.insert("blockquote", hashset!["class"]);
+        tag_attrs.insert("table", hashset!["class"]);
+        tag_attrs.insert("tr", hashset!["class"]);
+        tag_attrs.insert("td", hashset!["class"]);
+        tag_attrs.insert("iframe", hashset!["src", "title", "loading", "allowfullscreen"]);
         
         sanitizer.tag_attributes(tag_attrs);
+        sanitizer.attribute_filter(|element, attribute, value| {
+            if element == "iframe" && attribute == "src" && !embed::IFRAME_SOURCES.iter().any(|source| value.starts_with(source)) {
+                return None;
+            }
+            Some(value.into())
+        });
         sanitizer
     }
 
     /// 将 Markdown 转换为 HTML
     pub fn to_html(&self, markdown: &str) -> String {
-        // 配置 CommonMark 选项
-        let mut options = Options::empty();
-        options.insert(Options::ENABLE_STRIKETHROUGH);
-        options.insert(Options::ENABLE_TABLES);
-        options.insert(Options::ENABLE_FOOTNOTES);
-        options.insert(Options::ENABLE_TASKLISTS);
-        options.insert(Options::ENABLE_SMART_PUNCTUATION);
+        self.to_html_with_srcsets(markdown, &HashMap::new())
+    }
 
-        let parser = Parser::new_ext(markdown, options);
+    /// 将 Markdown 转换为 HTML，`srcsets` 为图片地址到响应式尺寸（srcset）的映射
+    pub fn to_html_with_srcsets(&self, markdown: &str, srcsets: &HashMap<String, String>) -> String {
+        self.to_html_with_embeds(markdown, srcsets, &HashMap::new())
+    }
+
+    /// 同 `to_html_with_srcsets`，另外把单独成段的链接替换为 `embeds` 中对应的嵌入 HTML
+    pub fn to_html_with_embeds(
+        &self,
+        markdown: &str,
+        srcsets: &HashMap<String, String>,
+        embeds: &HashMap<String, String>,
+    ) -> String {
+        // 图片说明、画廊和对齐方式先展开为 figure，嵌入链接展开为 HTML 块
+        let markdown = embed::expand_embeds(&figure::expand_figures(markdown, srcsets), embeds);
+
+        let parser = Parser::new_ext(&markdown, parser_options());
         
-        // 处理代码块语法高亮
-        let events = self.highlight_code_blocks(parser);
+        // 处理代码块语法高亮，标题加上锚点 id
+        let events = anchor_headings(self.highlight_code_blocks(parser));
         
         // 转换为 HTML
         let mut html_output = String::new();
@@ -82,6 +117,18 @@ impl MarkdownProcessor {
         sanitizer.clean(&html_output).to_string()
     }
 
+    /// 按正文渲染相同的规则清理 HTML，用于不经过 Markdown 渲染产生的正文（如内容转换的输出）
+    pub fn sanitize_html(&self, html: &str) -> String {
+        Self::get_sanitizer().clean(html).to_string()
+    }
+
+    /// 将外部平台导出的 HTML 转换为 Markdown（先清理不允许的标签）
+    pub fn from_html(&self, html: &str) -> String {
+        let sanitizer = Self::get_sanitizer();
+        let cleaned = sanitizer.clean(html).to_string();
+        html2md::parse_html(&cleaned).trim().to_string()
+    }
+
     /// 从 Markdown 提取纯文本（用于搜索和摘要）
     pub fn to_text(&self, markdown: &str) -> String {
         let options = Options::empty();
@@ -103,24 +150,100 @@ impl MarkdownProcessor {
     }
 
     /// 生成文章摘要
+    ///
+    /// 只从正文段落中抽取（跳过标题、代码块、表格、图片和 HTML），
+    /// 尽量在句子边界处结束；首句已超长时才退回到单词边界截断。
     pub fn generate_excerpt(&self, markdown: &str, max_length: usize) -> String {
-        let text = self.to_text(markdown);
-        
-        if text.len() <= max_length {
+        let paragraphs = self.extract_prose_paragraphs(markdown);
+        let text = if paragraphs.is_empty() {
+            self.to_text(markdown)
+        } else {
+            paragraphs.join(" ")
+        };
+
+        if text.chars().count() <= max_length {
             return text;
         }
-        
-        // 在最接近的单词边界处截断
-        let mut end = max_length;
-        while end > 0 && !text.chars().nth(end).map_or(false, |c| c.is_whitespace()) {
-            end -= 1;
+
+        // 取不超过长度限制的最长句子前缀
+        let excerpt = sentence_boundaries(&text)
+            .into_iter()
+            .map(|end| text[..end].trim())
+            .take_while(|candidate| candidate.chars().count() <= max_length)
+            .last();
+
+        if let Some(excerpt) = excerpt {
+            return excerpt.to_string();
         }
-        
-        if end == 0 {
-            end = max_length;
+
+        truncate_at_word_boundary(&text, max_length)
+    }
+
+    /// 生成 SEO 描述（搜索引擎一般展示约 160 个字符）
+    pub fn generate_seo_description(&self, markdown: &str) -> String {
+        self.generate_excerpt(markdown, SEO_DESCRIPTION_MAX_LENGTH)
+    }
+
+    /// 判断内容修改是否足以重新生成自动摘要：开头正文发生变化，或字数变化超过 20%
+    pub fn is_significant_change(&self, old_markdown: &str, new_markdown: &str) -> bool {
+        let lead = |markdown: &str| -> String {
+            self.extract_prose_paragraphs(markdown)
+                .join(" ")
+                .chars()
+                .take(SIGNIFICANT_CHANGE_LEAD_LENGTH)
+                .collect()
+        };
+
+        if lead(old_markdown) != lead(new_markdown) {
+            return true;
         }
-        
-        format!("{}...", text.chars().take(end).collect::<String>().trim())
+
+        let old_words = self.count_words(old_markdown) as f64;
+        let new_words = self.count_words(new_markdown) as f64;
+        if old_words == 0.0 {
+            return new_words > 0.0;
+        }
+
+        ((new_words - old_words).abs() / old_words) >= 0.2
+    }
+
+    /// 提取正文段落的纯文本，忽略标题、代码块、表格、图片和内嵌 HTML
+    pub fn extract_prose_paragraphs(&self, markdown: &str) -> Vec<String> {
+        let parser = Parser::new_ext(markdown, Options::ENABLE_TABLES);
+
+        let mut paragraphs = Vec::new();
+        let mut current = String::new();
+        let mut in_paragraph = false;
+        let mut skip_depth = 0usize;
+
+        for event in parser {
+            match event {
+                Event::Start(Tag::Heading(..))
+                | Event::Start(Tag::CodeBlock(_))
+                | Event::Start(Tag::Table(_))
+                | Event::Start(Tag::Image(..)) => skip_depth += 1,
+                Event::End(Tag::Heading(..))
+                | Event::End(Tag::CodeBlock(_))
+                | Event::End(Tag::Table(_))
+                | Event::End(Tag::Image(..)) => skip_depth = skip_depth.saturating_sub(1),
+                Event::Start(Tag::Paragraph) => {
+                    in_paragraph = true;
+                    current.clear();
+                }
+                Event::End(Tag::Paragraph) => {
+                    in_paragraph = false;
+                    let paragraph = current.split_whitespace().collect::<Vec<_>>().join(" ");
+                    if !paragraph.is_empty() {
+                        paragraphs.push(paragraph);
+                    }
+                }
+                Event::Text(t) | Event::Code(t) if in_paragraph && skip_depth == 0 => current.push_str(&t),
+                Event::SoftBreak | Event::HardBreak if in_paragraph => current.push(' '),
+                _ => {}
+            }
+        }
+
+        paragraphs
     }
 
     /// 提取文章中的图片
@@ -148,19 +271,22 @@ impl MarkdownProcessor {
         let mut events = Vec::new();
         let mut in_code_block = false;
         let mut code_buffer = String::new();
-        let mut language = String::new();
+        let mut info = String::new();
 
         for event in parser {
             match event {
-                Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(lang))) => {
+                Event::Start(Tag::CodeBlock(kind)) => {
                     in_code_block = true;
-                    language = lang.to_string();
+                    info = match kind {
+                        CodeBlockKind::Fenced(info) => info.to_string(),
+                        CodeBlockKind::Indented => String::new(),
+                    };
                     code_buffer.clear();
                     // 不添加任何事件，等待代码块结束
                 }
-                Event::End(Tag::CodeBlock(CodeBlockKind::Fenced(_))) => {
+                Event::End(Tag::CodeBlock(_)) => {
                     in_code_block = false;
-                    let highlighted = self.highlight_code(&code_buffer, &language);
+                    let highlighted = self.highlight_code(&code_buffer, &CodeFence::parse(&info));
                     events.push(Event::Html(highlighted.into()));
                 }
                 Event::Text(text) if in_code_block => {
@@ -176,28 +302,62 @@ impl MarkdownProcessor {
     }
 
     /// 语法高亮代码
-    fn highlight_code(&self, code: &str, language: &str) -> String {
-        let syntax = SYNTAX_SET.find_syntax_by_token(language)
-            .or_else(|| SYNTAX_SET.find_syntax_by_extension(language))
+    ///
+    /// 每行包在 `span.line` 中，行号放在 `data-line` 属性里由前端用 CSS 显示，
+    /// 这样 `<code>` 的文本内容就是原始代码，复制按钮可以直接读取
+    fn highlight_code(&self, code: &str, fence: &CodeFence) -> String {
+        let syntax = fence.language.as_deref()
+            .and_then(|language| SYNTAX_SET.find_syntax_by_token(language).or_else(|| SYNTAX_SET.find_syntax_by_extension(language)))
+            .or_else(|| code.lines().next().and_then(|line| SYNTAX_SET.find_syntax_by_first_line(line)))
+            .or_else(|| detect_language(code).and_then(|token| SYNTAX_SET.find_syntax_by_token(token)))
             .unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text());
+        let language = fence.language.clone().unwrap_or_else(|| {
+            if syntax.name == SYNTAX_SET.find_syntax_plain_text().name {
+                "text".to_string()
+            } else {
+                syntax.file_extensions.first().cloned().unwrap_or_else(|| "text".to_string())
+            }
+        });
 
-        let mut html_generator = ClassedHTMLGenerator::new_with_class_style(
-            syntax,
-            &SYNTAX_SET,
-            ClassStyle::Spaced,
-        );
+        let lines = highlight_lines(code, syntax).unwrap_or_else(|| code.lines().map(escape_html).collect());
+        let body = lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let number = index + 1;
+                let class = if fence.is_highlighted(number) { "line highlighted" } else { "line" };
+                format!(r#"<span class="{}" data-line="{}">{}</span>"#, class, number, line)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
 
-        for line in code.lines() {
-            html_generator.parse_html_for_line_which_includes_newline(line);
+        let mut header = String::from(r#"<div class="code-header">"#);
+        if let Some(title) = &fence.title {
+            header.push_str(&format!(r#"<span class="code-title">{}</span>"#, escape_html(title)));
         }
+        header.push_str(&format!(r#"<span class="code-language">{}</span></div>"#, escape_html(&language)));
 
+        let pre_class = if fence.line_numbers { "highlight line-numbers" } else { "highlight" };
         format!(
-            r#"<pre class="highlight"><code class="language-{}">{}</code></pre>"#,
-            language,
-            html_generator.finalize()
+            r#"<div class="code-block">{}<pre class="{}"><code class="language-{}">{}</code></pre></div>"#,
+            header,
+            pre_class,
+            escape_html(&language),
+            body
         )
     }
 
+    /// 代码高亮使用的 CSS（`ClassStyle::Spaced` 的 class），主题不存在时返回 None
+    pub fn highlight_css(&self, theme: &str) -> Option<String> {
+        let theme = THEME_SET.themes.get(theme)?;
+        syntect::html::css_for_theme_with_class_style(theme, ClassStyle::Spaced).ok()
+    }
+
+    /// 可用的代码高亮主题
+    pub fn highlight_themes(&self) -> Vec<&'static str> {
+        THEME_SET.themes.keys().map(String::as_str).collect()
+    }
+
     /// 估算阅读时间（分钟）
     pub fn estimate_reading_time(&self, markdown: &str) -> i32 {
         let word_count = self.count_words(markdown);
@@ -245,54 +405,19 @@ impl MarkdownProcessor {
         count + english_words
     }
 
-    /// 提取文章目录
+    /// 提取文章目录（按出现顺序的扁平列表），`id` 与 `to_html` 输出中标题的 id 一致
     pub fn extract_toc(&self, markdown: &str) -> Vec<TocItem> {
-        let options = Options::empty();
-        let parser = Parser::new_ext(markdown, options);
-        
-        let mut toc = Vec::new();
-        let mut current_text = String::new();
-        let mut in_heading = false;
-        let mut heading_level = 0;
-
-        for event in parser {
-            match event {
-                Event::Start(Tag::Heading(level, _, _)) => {
-                    in_heading = true;
-                    heading_level = level as u8;
-                    current_text.clear();
-                }
-                Event::End(Tag::Heading(_, _, _)) => {
-                    if in_heading && !current_text.is_empty() {
-                        let id = self.generate_heading_id(&current_text);
-                        toc.push(TocItem {
-                            level: heading_level,
-                            title: current_text.clone(),
-                            id,
-                        });
-                    }
-                    in_heading = false;
-                }
-                Event::Text(text) if in_heading => {
-                    current_text.push_str(&text);
-                }
-                _ => {}
-            }
-        }
-
-        toc
+        let events: Vec<Event> = Parser::new_ext(markdown, parser_options()).collect();
+        collect_headings(&events)
     }
 
-    /// 为标题生成ID
-    fn generate_heading_id(&self, title: &str) -> String {
-        title
-            .to_lowercase()
-            .chars()
-            .map(|c| if c.is_alphanumeric() || c == ' ' { c } else { ' ' })
-            .collect::<String>()
-            .split_whitespace()
-            .collect::<Vec<_>>()
-            .join("-")
+    /// 按标题层级嵌套的目录，跳级的标题（如 h2 下直接是 h4）挂在最近的上级标题下
+    pub fn toc_tree(&self, markdown: &str) -> Vec<TocItem> {
+        let mut roots = Vec::new();
+        for item in self.extract_toc(markdown) {
+            insert_toc_item(&mut roots, item);
+        }
+        roots
     }
 
     /// 提取内容预览（用于付费内容）
@@ -320,6 +445,35 @@ impl MarkdownProcessor {
         (preview_markdown, preview_html)
     }
 
+    /// 只保留前 `count` 个段落（以空行分隔，代码块整体算一段），内容本来就不超过时返回 None
+    pub fn truncate_paragraphs(&self, markdown: &str, count: usize) -> Option<String> {
+        let mut kept = 0;
+        let mut in_fence = false;
+        let mut in_paragraph = false;
+        let mut end = 0;
+
+        for line in markdown.split_inclusive('\n') {
+            let trimmed = line.trim();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_fence = !in_fence;
+            }
+            if trimmed.is_empty() && !in_fence {
+                if in_paragraph {
+                    kept += 1;
+                    in_paragraph = false;
+                }
+            } else if !in_paragraph {
+                if kept >= count {
+                    return Some(markdown[..end].trim_end().to_string());
+                }
+                in_paragraph = true;
+            }
+            end += line.len();
+        }
+
+        None
+    }
+
     /// 在 Markdown 中添加目录链接
     pub fn add_toc_links(&self, markdown: &str) -> String {
         let toc = self.extract_toc(markdown);
@@ -351,11 +505,238 @@ impl MarkdownProcessor {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 渲染和提取目录使用同一套选项，保证两边识别出的标题一致。
+/// 开启标题属性后作者可以用 `## 标题 {#custom-id}` 固定锚点
+fn parser_options() -> Options {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_FOOTNOTES);
+    options.insert(Options::ENABLE_TASKLISTS);
+    options.insert(Options::ENABLE_SMART_PUNCTUATION);
+    options.insert(Options::ENABLE_HEADING_ATTRIBUTES);
+    options
+}
+
+/// 按顺序收集标题，生成不重复的 id：重名的标题依次加 `-1`、`-2` 后缀
+fn collect_headings(events: &[Event]) -> Vec<TocItem> {
+    let mut headings = Vec::new();
+    let mut used: HashMap<String, usize> = HashMap::new();
+    let mut taken: HashSet<String> = HashSet::new();
+    let mut current: Option<(u8, Option<String>, String)> = None;
+
+    for event in events {
+        match event {
+            Event::Start(Tag::Heading(level, id, _)) => {
+                current = Some((*level as u8, id.map(str::to_string), String::new()));
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((_, _, title)) = current.as_mut() {
+                    title.push_str(text);
+                }
+            }
+            Event::End(Tag::Heading(..)) => {
+                if let Some((level, explicit_id, title)) = current.take() {
+                    let title = title.trim().to_string();
+                    let base = explicit_id.unwrap_or_else(|| heading_slug(&title));
+                    let mut id = base.clone();
+                    let count = used.entry(base.clone()).or_insert(0);
+                    while taken.contains(&id) {
+                        *count += 1;
+                        id = format!("{}-{}", base, count);
+                    }
+                    taken.insert(id.clone());
+                    headings.push(TocItem { level, title, id, children: Vec::new() });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    headings
+}
+
+/// 标题的锚点：小写，保留字母、数字（包括中日韩文字），其他字符换成连字符
+fn heading_slug(title: &str) -> String {
+    let slug = title
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() { "section".to_string() } else { slug }
+}
+
+/// 把标题的开始、结束事件换成带 id 的 HTML 标签
+fn anchor_headings(events: Vec<Event<'_>>) -> Vec<Event<'_>> {
+    let mut ids = collect_headings(&events).into_iter().map(|heading| heading.id);
+
+    events
+        .into_iter()
+        .map(|event| match event {
+            Event::Start(Tag::Heading(level, ..)) => match ids.next() {
+                Some(id) => Event::Html(format!(r#"<h{} id="{}">"#, level as usize, escape_html(&id)).into()),
+                None => Event::Html(format!("<h{}>", level as usize).into()),
+            },
+            Event::End(Tag::Heading(level, ..)) => Event::Html(format!("</h{}>\n", level as usize).into()),
+            event => event,
+        })
+        .collect()
+}
+
+fn insert_toc_item(siblings: &mut Vec<TocItem>, item: TocItem) {
+    match siblings.last_mut() {
+        Some(last) if last.level < item.level => insert_toc_item(&mut last.children, item),
+        _ => siblings.push(item),
+    }
+}
+
+/// 代码块的 info string：语言、需要高亮的行、标题和是否显示行号。
+/// 例如 ```` ```rust {1,4-6} title="main.rs" ````，加 `nolinenums` 不显示行号
+#[derive(Debug, Clone, PartialEq)]
+struct CodeFence {
+    language: Option<String>,
+    highlighted: Vec<(usize, usize)>,
+    title: Option<String>,
+    line_numbers: bool,
+}
+
+impl CodeFence {
+    fn parse(info: &str) -> Self {
+        static HIGHLIGHT_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{([\d,\s-]*)\}").unwrap());
+        static TITLE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r#"title="([^"]*)""#).unwrap());
+
+        // `rust,ignore` 这种写法只取语言部分
+        let language = info
+            .split_whitespace()
+            .next()
+            .filter(|token| !token.starts_with('{') && !token.contains('='))
+            .and_then(|token| token.split(',').next())
+            .filter(|language| !language.is_empty() && *language != "nolinenums")
+            .map(|language| language.to_lowercase());
+
+        let highlighted = HIGHLIGHT_REGEX
+            .captures(info)
+            .map(|captures| {
+                captures[1]
+                    .split(',')
+                    .filter_map(|part| {
+                        let part = part.trim();
+                        match part.split_once('-') {
+                            Some((start, end)) => Some((start.trim().parse().ok()?, end.trim().parse().ok()?)),
+                            None => part.parse().ok().map(|line| (line, line)),
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self {
+            language,
+            highlighted,
+            title: TITLE_REGEX.captures(info).map(|captures| captures[1].to_string()),
+            line_numbers: !info.split_whitespace().any(|token| token == "nolinenums"),
+        }
+    }
+
+    fn is_highlighted(&self, line: usize) -> bool {
+        self.highlighted.iter().any(|(start, end)| (*start..=*end).contains(&line))
+    }
+}
+
+/// 逐行生成带 class 的 HTML。跨行的作用域（块注释、多行字符串）在行尾关闭、下一行重新打开，
+/// 保证每行的标签都是完整的
+fn highlight_lines(code: &str, syntax: &SyntaxReference) -> Option<Vec<String>> {
+    let mut parse_state = ParseState::new(syntax);
+    let mut scope_stack = ScopeStack::new();
+    let mut lines = Vec::new();
+
+    for line in LinesWithEndings::from(code) {
+        let reopened: String = scope_stack
+            .as_slice()
+            .iter()
+            .map(|scope| format!(r#"<span class="{}">"#, scope.build_string().replace('.', " ")))
+            .collect();
+
+        let ops = parse_state.parse_line(line, &SYNTAX_SET).ok()?;
+        let (html, _) = line_tokens_to_classed_spans(line, &ops, ClassStyle::Spaced, &mut scope_stack).ok()?;
+        let closing = "</span>".repeat(scope_stack.len());
+        lines.push(format!("{}{}{}", reopened, html.replace('\n', ""), closing));
+    }
+
+    Some(lines)
+}
+
+/// 没有标注语言时按常见特征猜测，返回 syntect 的语言 token
+fn detect_language(code: &str) -> Option<&'static str> {
+    static PATTERNS: Lazy<Vec<(Regex, &'static str)>> = Lazy::new(|| {
+        [
+            (r"(?m)^\s*(fn |let mut |impl |pub (fn|struct|enum) |use \w+::)", "rs"),
+            (r"(?m)^\s*(package main|func \w+\(.*\)\s*\{)", "go"),
+            (r"(?m)^\s*(def \w+\(.*\):|from \w+ import |import \w+$|class \w+(\(.*\))?:)", "py"),
+            (r"(?m)^\s*(public (static )?(class|void)|import java\.)", "java"),
+            (r"(?m)^\s*#include\s*<", "cpp"),
+            (r"(?m)^\s*(const|let|var) \w+ = |=> \{|function \w*\(|console\.log\(", "js"),
+            (r"(?im)^\s*(SELECT .+ FROM|INSERT INTO|CREATE TABLE|UPDATE \w+ SET)", "sql"),
+            (r"(?i)^\s*(<!doctype html|<html|<div|<head)", "html"),
+            (r#"^\s*[\{\[]\s*"\w+"\s*:"#, "json"),
+            (r"(?m)^\s*(\$ |sudo |apt(-get)? |npm |cargo |git |cd |export \w+=)", "sh"),
+        ]
+        .into_iter()
+        .map(|(pattern, token)| (Regex::new(pattern).unwrap(), token))
+        .collect()
+    });
+
+    PATTERNS.iter().find(|(pattern, _)| pattern.is_match(code)).map(|(_, token)| *token)
+}
+
+fn escape_html(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// 返回每个句子结束处的字节偏移（兼容中英文句末标点）
+fn sentence_boundaries(text: &str) -> Vec<usize> {
+    let mut boundaries = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let is_terminal = match c {
+            '。' | '！' | '？' | '…' => true,
+            '.' | '!' | '?' => chars.peek().map_or(true, |(_, next)| next.is_whitespace()),
+            _ => false,
+        };
+
+        if is_terminal {
+            boundaries.push(i + c.len_utf8());
+        }
+    }
+
+    boundaries
+}
+
+/// 在不超过 max_length 个字符的最后一个空白处截断；没有空白（如中文）时按字符截断
+fn truncate_at_word_boundary(text: &str, max_length: usize) -> String {
+    let prefix: String = text.chars().take(max_length).collect();
+    let cut = match prefix.rfind(char::is_whitespace) {
+        Some(idx) if idx > 0 => &prefix[..idx],
+        _ => prefix.as_str(),
+    };
+
+    format!("{}...", cut.trim_end())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TocItem {
     pub level: u8,
     pub title: String,
+    /// 标题的锚点，链接为 `#id`
     pub id: String,
+    /// 下级标题，只在 `toc_tree` 中填充
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schema(no_recursion)]
+    pub children: Vec<TocItem>,
 }
 
 // 便利宏
@@ -385,6 +766,48 @@ mod tests {
         assert!(html.contains("<strong>bold</strong>"));
     }
 
+    #[test]
+    fn test_embeds_survive_sanitizer() {
+        let processor = MarkdownProcessor::new();
+        let url = "https://youtu.be/dQw4w9WgXcQ";
+        let embed = embed::EmbedTarget::parse(url).unwrap().render(Some("Demo"), None).unwrap();
+        let markdown = format!("Watch this:\n\n{}\n\n<iframe src=\"https://evil.example/x\"></iframe>\n", url);
+
+        let html = processor.to_html_with_embeds(&markdown, &HashMap::new(), &HashMap::from([(url.to_string(), embed)]));
+
+        assert!(html.contains(r#"<iframe src="https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ" title="Demo" loading="lazy" allowfullscreen"#));
+        assert!(!html.contains("evil.example"));
+    }
+
+    #[test]
+    fn test_code_block_highlighting() {
+        let processor = MarkdownProcessor::new();
+        let markdown = "```rust {2} title=\"main.rs\"\n/* a\nb */\nfn main() {}\n```\n";
+        let html = processor.to_html(markdown);
+
+        assert!(html.starts_with(r#"<div class="code-block"><div class="code-header"><span class="code-title">main.rs</span><span class="code-language">rust</span></div><pre class="highlight line-numbers"><code class="language-rust">"#));
+        assert!(html.contains(r#"<span class="line highlighted" data-line="2">"#));
+        assert!(html.contains(r#"<span class="line" data-line="3">"#));
+
+        // 去掉标签后就是原始代码，复制按钮可以直接使用
+        let text = Regex::new(r"<[^>]+>").unwrap().replace_all(&html, "").to_string();
+        assert!(text.contains("/* a\nb */\nfn main() {}"));
+    }
+
+    #[test]
+    fn test_code_fence_and_language_detection() {
+        let fence = CodeFence::parse("rust,ignore {1, 3-4} nolinenums");
+        assert_eq!(fence.language.as_deref(), Some("rust"));
+        assert!(fence.is_highlighted(1) && fence.is_highlighted(4) && !fence.is_highlighted(2));
+        assert!(!fence.line_numbers);
+        assert_eq!(CodeFence::parse("{1}").language, None);
+
+        assert_eq!(detect_language("fn main() {\n    println!(\"hi\");\n}"), Some("rs"));
+        assert_eq!(detect_language("def greet(name):\n    return name"), Some("py"));
+        assert_eq!(detect_language("SELECT id FROM users;"), Some("sql"));
+        assert_eq!(detect_language("just some words"), None);
+    }
+
     #[test]
     fn test_extract_text() {
         let processor = MarkdownProcessor::new();
@@ -406,6 +829,51 @@ mod tests {
         assert!(excerpt.ends_with("..."));
     }
 
+    #[test]
+    fn test_generate_excerpt_skips_headings_and_code() {
+        let processor = MarkdownProcessor::new();
+
+        let markdown = "# Title\n\n```rust\nfn main() { println!(\"hi\"); }\n```\n\nFirst sentence here. Second sentence is a bit longer than the first one. Third.";
+        let excerpt = processor.generate_excerpt(markdown, 60);
+
+        assert_eq!(excerpt, "First sentence here.");
+    }
+
+    #[test]
+    fn test_generate_excerpt_cjk_sentences() {
+        let processor = MarkdownProcessor::new();
+
+        let markdown = "这是第一句话。这是第二句话，稍微长一点。第三句。";
+        let excerpt = processor.generate_excerpt(markdown, 20);
+
+        assert_eq!(excerpt, "这是第一句话。这是第二句话，稍微长一点。");
+    }
+
+    #[test]
+    fn test_truncate_paragraphs() {
+        let processor = MarkdownProcessor::new();
+
+        let markdown = "First.\n\n```rust\nlet a = 1;\n\nlet b = 2;\n```\n\nThird.\nStill third.\n\nFourth.";
+        assert_eq!(
+            processor.truncate_paragraphs(markdown, 3).as_deref(),
+            Some("First.\n\n```rust\nlet a = 1;\n\nlet b = 2;\n```\n\nThird.\nStill third.")
+        );
+        assert_eq!(processor.truncate_paragraphs(markdown, 1).as_deref(), Some("First."));
+        assert_eq!(processor.truncate_paragraphs(markdown, 4), None);
+    }
+
+    #[test]
+    fn test_is_significant_change() {
+        let processor = MarkdownProcessor::new();
+
+        let original = "# Title\n\nIntro paragraph stays the same.\n\nSome body text that goes on.";
+        let typo_fix = "# Titel\n\nIntro paragraph stays the same.\n\nSome body text that goes on.";
+        let new_intro = "# Title\n\nA completely different intro.\n\nSome body text that goes on.";
+
+        assert!(!processor.is_significant_change(original, typo_fix));
+        assert!(processor.is_significant_change(original, new_intro));
+    }
+
     #[test]
     fn test_extract_toc() {
         let processor = MarkdownProcessor::new();
@@ -419,4 +887,24 @@ mod tests {
         assert_eq!(toc[1].level, 2);
         assert_eq!(toc[1].title, "Section 1.1");
     }
+
+    #[test]
+    fn test_heading_anchors_and_toc_tree() {
+        let processor = MarkdownProcessor::new();
+        let markdown = "# Intro\n\n## Setup `cargo`\n\n#### Deep\n\n## Setup cargo\n\n## 安装 {#install}\n\n```md\n# not a heading\n```\n\n# Intro\n";
+
+        let html = processor.to_html(markdown);
+        assert!(html.contains(r#"<h1 id="intro">Intro</h1>"#));
+        assert!(html.contains(r#"<h2 id="setup-cargo">Setup <code>cargo</code></h2>"#));
+        assert!(html.contains(r#"<h2 id="setup-cargo-1">Setup cargo</h2>"#));
+        assert!(html.contains(r#"<h2 id="install">安装</h2>"#));
+        assert!(html.contains(r#"<h1 id="intro-1">Intro</h1>"#));
+
+        let tree = processor.toc_tree(markdown);
+        assert_eq!(tree.len(), 2);
+        let ids: Vec<&str> = tree[0].children.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(ids, vec!["setup-cargo", "setup-cargo-1", "install"]);
+        assert_eq!(tree[0].children[0].children[0].id, "deep");
+        assert_eq!(tree[1].id, "intro-1");
+    }
 }
\ No newline at end of file
@@ -1,29 +1,32 @@
-use crate::{error::AppError, services::AuthService, state::AppState};
+use crate::{
+    error::{AppError, ErrorCode},
+    models::{
+        admin::{is_platform_permission, required_admin_permission},
+        oauth::required_scope,
+        response::ErrorResponse,
+    },
+    services::{oauth::ACCESS_TOKEN_PREFIX, AuthService},
+    state::AppState,
+    utils::{
+        http_cache,
+        i18n::{self, Locale},
+    },
+};
 use axum::{
-    extract::State,
-    http::{HeaderMap, StatusCode, Request},
+    extract::{ConnectInfo, OriginalUri, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode, Request},
     middleware::Next,
-    response::Response,
-    body::Body,
+    response::{IntoResponse, Response},
+    body::{Body, HttpBody},
 };
 use serde::{Deserialize, Serialize};
 use async_trait::async_trait;
-use governor::{
-    clock::DefaultClock,
-    state::{InMemoryState, NotKeyed, keyed::DashMapStateStore},
-    Quota, RateLimiter,
-};
 use std::{
-    net::SocketAddr,
-    num::NonZeroU32,
+    net::{IpAddr, SocketAddr},
     sync::Arc,
     time::Duration,
 };
 use tracing::{debug, warn, info};
-use tokio::sync::OnceCell;
-
-type KeyedRateLimiter = RateLimiter<String, DashMapStateStore<String>, DefaultClock>;
-static RATE_LIMITER: OnceCell<KeyedRateLimiter> = OnceCell::const_new();
 
 /// 认证中间件
 pub async fn auth_middleware(
@@ -38,6 +41,43 @@ pub async fn auth_middleware(
     // 将认证服务和用户服务添加到请求扩展中，供后续处理器使用
     request.extensions_mut().insert(app_state.auth_service.clone());
     request.extensions_mut().insert(app_state.user_service.clone());
+
+    // 第三方应用的 OAuth 访问令牌：按令牌的权限范围检查路由
+    let oauth_token = headers
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .filter(|token| token.starts_with(ACCESS_TOKEN_PREFIX));
+    if let Some(token) = oauth_token {
+        let (user_id, access) = app_state
+            .oauth_service
+            .authenticate(token)
+            .await?
+            .ok_or_else(|| AppError::Authentication("Invalid or expired access token".to_string()))?;
+
+        // 令牌只记录用户 ID，每次请求重新解析账户和权限
+        let mut user = app_state
+            .auth_service
+            .resolve_user(&user_id)
+            .await?
+            .ok_or_else(|| AppError::Authentication("The account behind this access token no longer exists".to_string()))?;
+        // 第三方令牌不带平台管理权限，也不继承任何会话的两步验证状态
+        user.permissions.retain(|p| !is_platform_permission(p));
+        user.two_factor_verified = false;
+        check_account_status(&app_state, &user, request.method(), request.uri().path()).await?;
+
+        let scope = required_scope(request.method(), request.uri().path()).ok_or_else(|| {
+            AppError::forbidden("This endpoint is not available to third-party applications")
+        })?;
+        if !access.scopes.iter().any(|s| s == scope) {
+            return Err(AppError::Authorization(format!("Access token is missing the '{}' scope", scope)));
+        }
+
+        debug!("Authenticated user {} via OAuth client {}", user.id, access.client_id);
+        request.extensions_mut().insert(user);
+        request.extensions_mut().insert(access);
+        return Ok(next.run(request).await);
+    }
     
     // 检查是否有 Authorization 头
     if let Some(auth_header) = headers.get("authorization") {
@@ -50,23 +90,18 @@ pub async fn auth_middleware(
                     Ok(claims) => {
                         // 尝试获取用户信息
                         match app_state.auth_service.get_user_from_rainbow_auth(&claims.sub, token).await {
-                            Ok(user) => {
+                            Ok(mut user) => {
                                 debug!("Authenticated user: {} ({})", user.id, user.email);
+
+                                // 两步验证按会话记录，同一账号的其他登录需要单独验证
+                                let session = app_state.auth_service.session_for(&claims, token);
+                                user.two_factor_verified = app_state.two_factor_service.is_session_verified(&session).await;
+                                request.extensions_mut().insert(session);
+
+                                // 平台管理员和审核员的权限
+                                app_state.admin_service.apply_platform_role(&mut user).await;
                                 
-                                // 确保用户的 profile 存在
-                                let profile_result = app_state.user_service.get_or_create_profile(
-                                    &user.id,
-                                    &user.email,
-                                    user.is_verified,
-                                    user.username.clone(),
-                                    user.display_name.clone(),
-                                ).await;
-                                
-                                if let Err(e) = profile_result {
-                                    warn!("Failed to ensure user profile exists for user {}: {}", user.id, e);
-                                } else {
-                                    debug!("Successfully ensured user profile exists for user {}", user.id);
-                                }
+                                check_account_status(&app_state, &user, request.method(), request.uri().path()).await?;
                                 
                                 // 将用户信息添加到请求中
                                 info!("Inserting user into request extensions: {}", user.id);
@@ -90,33 +125,152 @@ pub async fn auth_middleware(
     Ok(next.run(request).await)
 }
 
-/// 速率限制中间件
-pub async fn rate_limit_middleware(
+fn is_read_only(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// 确保用户的 profile 存在，并拦截停用账户和待删除账户的写操作。
+/// 平台 JWT 和第三方访问令牌走同一套检查
+async fn check_account_status(
+    app_state: &AppState,
+    user: &crate::services::auth::User,
+    method: &Method,
+    path: &str,
+) -> Result<(), AppError> {
+    let profile_result = app_state.user_service.get_or_create_profile(
+        &user.id,
+        &user.email,
+        user.is_verified,
+        user.username.clone(),
+        user.display_name.clone(),
+    ).await;
+
+    match profile_result {
+        Err(e) => {
+            warn!("Failed to ensure user profile exists for user {}: {}", user.id, e);
+        }
+        // 被管理员停用的账户只能读取
+        Ok(profile) if profile.suspended_at.is_some() && !is_read_only(method) => {
+            return Err(AppError::coded(ErrorCode::AccountSuspended, "Your account has been suspended"));
+        }
+        // 申请删除后的宽限期内只能读取或撤销删除
+        Ok(profile) if profile.deletion_requested_at.is_some()
+            && !is_read_only(method)
+            && !path.starts_with("/api/blog/users/me/deletion") =>
+        {
+            return Err(AppError::coded(
+                ErrorCode::AccountDeletionPending,
+                "Your account is scheduled for deletion",
+            ));
+        }
+        Ok(_) => {
+            debug!("Successfully ensured user profile exists for user {}", user.id);
+        }
+    }
+
+    Ok(())
+}
+
+/// 管理后台授权中间件
+///
+/// 挂在 `/api/blog/admin` 上，按路由检查平台角色授予的权限；未登记权限的路由一律拒绝
+pub async fn admin_middleware(
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Result<Response, AppError> {
+    let user = request
+        .extensions()
+        .get::<crate::services::auth::User>()
+        .ok_or_else(|| AppError::Authentication("Authentication required".to_string()))?;
+
+    let path = request
+        .extensions()
+        .get::<OriginalUri>()
+        .map_or_else(|| request.uri().path().to_string(), |uri| uri.0.path().to_string());
+    let permission = required_admin_permission(&path)
+        .ok_or_else(|| AppError::coded(ErrorCode::AdminPermissionRequired, "Admin permission required"))?;
+
+    if !user.permissions.iter().any(|p| p == permission) {
+        warn!("User {} denied access to {} (missing {})", user.id, path, permission);
+        return Err(AppError::coded(
+            ErrorCode::AdminPermissionRequired,
+            &format!("Permission '{}' required", permission),
+        ));
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// 解析出的客户端 IP，由 [`client_ip_middleware`] 写入请求扩展
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub IpAddr);
+
+/// 客户端 IP 中间件，放在最外层
+///
+/// 只信任来自 `TRUSTED_PROXIES` 的连接转发的地址。解析结果写入 [`ClientIp`] 扩展和
+/// `X-Real-IP` 头，客户端自带的转发头一律移除，下游拿到的只有解析后的地址
+pub async fn client_ip_middleware(
     State(app_state): State<Arc<AppState>>,
     mut request: Request<Body>,
     next: Next<Body>,
-) -> Result<Response, AppError> {
-    // 获取或创建速率限制器
-    let rate_limiter = RATE_LIMITER.get_or_init(|| async {
-        let quota = Quota::per_minute(NonZeroU32::new(app_state.config.rate_limit_requests).unwrap())
-            .allow_burst(NonZeroU32::new(10).unwrap());
-        RateLimiter::dashmap(quota)
-    }).await;
-
-    // 获取客户端 IP
-    let client_ip = get_client_ip(&request);
-    
-    // 检查速率限制
-    match rate_limiter.check_key(&client_ip) {
-        Ok(_) => {
-            debug!("Rate limit check passed for IP: {}", client_ip);
-            Ok(next.run(request).await)
-        }
-        Err(_) => {
-            warn!("Rate limit exceeded for IP: {}", client_ip);
-            Err(AppError::RateLimitExceeded)
+) -> Response {
+    let ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(peer)| {
+            resolve_client_ip(peer.ip(), request.headers(), &app_state.config.trusted_proxies)
+        });
+
+    let headers = request.headers_mut();
+    headers.remove("x-forwarded-for");
+    headers.remove("x-real-ip");
+    headers.remove(header::FORWARDED);
+
+    if let Some(ip) = ip {
+        if let Ok(value) = HeaderValue::from_str(&ip.to_string()) {
+            request.headers_mut().insert("x-real-ip", value);
         }
+        request.extensions_mut().insert(ClientIp(ip));
     }
+
+    next.run(request).await
+}
+
+/// 速率限制中间件
+///
+/// 放在认证中间件之后，按用户限流的策略才能拿到当前用户。响应带上 `RateLimit-*` 头
+pub async fn rate_limit_middleware(
+    State(app_state): State<Arc<AppState>>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let client_ip = get_client_ip(&request);
+    let user_id = request
+        .extensions()
+        .get::<crate::services::auth::User>()
+        .map(|user| user.id.clone());
+
+    let decision = app_state
+        .rate_limit_service
+        .check(request.method().as_str(), request.uri().path(), user_id.as_deref(), &client_ip)
+        .await;
+    let Some(decision) = decision else {
+        return next.run(request).await;
+    };
+
+    let mut response = if decision.allowed {
+        next.run(request).await
+    } else {
+        warn!(
+            "Rate limit exceeded for {} on {}",
+            user_id.as_deref().unwrap_or(&client_ip),
+            request.uri().path()
+        );
+        AppError::RateLimitExceeded.into_response()
+    };
+
+    decision.apply_headers(response.headers_mut());
+    response
 }
 
 /// 请求日志中间件
@@ -148,30 +302,105 @@ pub async fn request_logging_middleware(
     response
 }
 
-/// CORS 中间件（如果需要自定义逻辑）
+/// 平台前端（CORS_ALLOWED_ORIGINS）可以调用的方法
+const PLATFORM_CORS_METHODS: &str = "GET, POST, PUT, DELETE, OPTIONS";
+/// 出版物允许的来源只能读取内容接口
+const PUBLICATION_CORS_METHODS: &str = "GET, HEAD, OPTIONS";
+
+/// CORS 中间件
+///
+/// 平台前端的来源可以访问所有接口；出版物配置的来源只能读取该出版物的内容接口
+/// （出版物域名下的内容路由、出版物详情/文章列表和订阅源），不会对平台整体开放。
 pub async fn cors_middleware(
+    State(app_state): State<Arc<AppState>>,
     request: Request<Body>,
     next: Next<Body>,
 ) -> Response {
-    let origin = request
+    let Some(origin) = request
         .headers()
-        .get("origin")
+        .get(header::ORIGIN)
         .and_then(|h| h.to_str().ok())
-        .unwrap_or("*")
-        .to_string();
-    
+        .map(str::to_string)
+    else {
+        return next.run(request).await;
+    };
+
+    let requested_method = request
+        .headers()
+        .get(header::ACCESS_CONTROL_REQUEST_METHOD)
+        .and_then(|h| h.to_str().ok())
+        .map(str::to_string);
+    let is_preflight = request.method() == Method::OPTIONS && requested_method.is_some();
+    let method = requested_method.unwrap_or_else(|| request.method().to_string());
+
+    let is_platform_origin = app_state
+        .config
+        .cors_allowed_origins
+        .split(',')
+        .any(|allowed| allowed.trim() == origin);
+    let allowed_methods = if is_platform_origin {
+        Some(PLATFORM_CORS_METHODS)
+    } else {
+        match publication_cors_scope(&request) {
+            Some(publication) if matches!(method.as_str(), "GET" | "HEAD")
+                && app_state.cors_service.is_allowed(&publication, &origin).await =>
+            {
+                Some(PUBLICATION_CORS_METHODS)
+            }
+            _ => None,
+        }
+    };
+
+    if is_preflight {
+        let requested_headers = request.headers().get(header::ACCESS_CONTROL_REQUEST_HEADERS).cloned();
+        let mut response = StatusCode::NO_CONTENT.into_response();
+        let headers = response.headers_mut();
+        headers.insert(header::VARY, HeaderValue::from_static("origin"));
+        if let (Some(methods), Ok(origin)) = (allowed_methods, HeaderValue::from_str(&origin)) {
+            headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+            headers.insert(header::ACCESS_CONTROL_ALLOW_METHODS, HeaderValue::from_static(methods));
+            headers.insert(
+                header::ACCESS_CONTROL_ALLOW_HEADERS,
+                requested_headers.unwrap_or_else(|| HeaderValue::from_static("content-type, authorization")),
+            );
+            headers.insert(header::ACCESS_CONTROL_MAX_AGE, HeaderValue::from_static("3600"));
+        }
+        return response;
+    }
+
     let mut response = next.run(request).await;
-    
-    // 添加 CORS 头
     let headers = response.headers_mut();
-    headers.insert("access-control-allow-origin", origin.parse().unwrap());
-    headers.insert("access-control-allow-methods", "GET, POST, PUT, DELETE, OPTIONS".parse().unwrap());
-    headers.insert("access-control-allow-headers", "content-type, authorization".parse().unwrap());
-    headers.insert("access-control-max-age", "3600".parse().unwrap());
-    
+    headers.append(header::VARY, HeaderValue::from_static("origin"));
+    if let (Some(_), Ok(origin)) = (allowed_methods, HeaderValue::from_str(&origin)) {
+        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+    }
+
     response
 }
 
+/// 出版物范围的内容接口，返回出版物 ID 或 slug
+fn publication_cors_scope(request: &Request<Body>) -> Option<String> {
+    let path = request.uri().path();
+
+    // 通过出版物域名访问的内容路由
+    if !path.starts_with("/api/blog/") {
+        return request
+            .extensions()
+            .get::<PublicationContext>()
+            .map(|context| context.publication_id.clone());
+    }
+
+    let scoped = path
+        .strip_prefix("/api/blog/publications/")
+        .or_else(|| path.strip_prefix("/api/blog/feeds/publications/"))?;
+    let (key, rest) = scoped.split_once('/').unwrap_or((scoped, ""));
+    if key.is_empty() {
+        return None;
+    }
+
+    matches!(rest, "" | "articles" | "rss" | "atom").then(|| key.to_string())
+}
+
 /// 安全头中间件
 pub async fn security_headers_middleware(
     request: Request<Body>,
@@ -198,6 +427,15 @@ pub async fn security_headers_middleware(
 
 /// 内容压缩中间件（已在 main.rs 中使用 tower-http 的 CompressionLayer）
 
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// 当前请求的 ID，写入错误响应中方便对照日志。不在请求处理中时返回 None
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
 /// 请求 ID 中间件
 pub async fn request_id_middleware(
     mut request: Request<Body>,
@@ -208,7 +446,7 @@ pub async fn request_id_middleware(
     // 添加到请求扩展中
     request.extensions_mut().insert(RequestId(request_id.clone()));
     
-    let mut response = next.run(request).await;
+    let mut response = REQUEST_ID.scope(request_id.clone(), next.run(request)).await;
     
     // 添加到响应头中
     response.headers_mut().insert("x-request-id", request_id.parse().unwrap());
@@ -216,6 +454,179 @@ pub async fn request_id_middleware(
     response
 }
 
+/// 读取非 JSON 错误响应体的上限，超出部分不作为错误信息
+const MAX_PLAIN_ERROR_BODY: usize = 4 * 1024;
+
+/// 错误响应统一格式：axum 的请求提取失败、路由不存在等返回的纯文本错误
+/// 改写为和 `AppError` 相同的 JSON 结构，保留原来的状态码
+pub async fn error_envelope_middleware(
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let response = next.run(request).await;
+    let status = response.status();
+    if !status.is_client_error() && !status.is_server_error() {
+        return response;
+    }
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| value.starts_with("application/json") || value.contains("+json"));
+    // 已压缩的响应体无法直接读出文字，保持原样
+    if is_json || response.headers().contains_key(header::CONTENT_ENCODING) {
+        return response;
+    }
+
+    let (mut parts, mut body) = response.into_parts();
+    let mut text = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let Ok(chunk) = chunk else { break };
+        text.extend_from_slice(&chunk);
+        if text.len() >= MAX_PLAIN_ERROR_BODY {
+            text.truncate(MAX_PLAIN_ERROR_BODY);
+            break;
+        }
+    }
+
+    let text = String::from_utf8_lossy(&text);
+    let message = match text.trim() {
+        "" => status.canonical_reason().unwrap_or("Error").to_string(),
+        text => text.to_string(),
+    };
+    let body = ErrorResponse::new(ErrorCode::from_status(status), i18n::translate_current(&message))
+        .with_request_id(current_request_id());
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    let mut response = (parts.status, axum::Json(body)).into_response();
+    for (name, value) in parts.headers.iter() {
+        if name != header::CONTENT_TYPE {
+            response.headers_mut().entry(name).or_insert_with(|| value.clone());
+        }
+    }
+    response
+}
+
+/// 只读接口的条件请求：按 `state.http_cache_rules` 设置 `Cache-Control`，根据响应体计算 ETag，
+/// `If-None-Match` 或 `If-Modified-Since` 命中时返回 304。处理器设置的缓存头优先
+pub async fn http_cache_middleware(
+    State(app_state): State<Arc<AppState>>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    // HEAD 的响应体已被去掉，无法计算出与 GET 一致的 ETag
+    if request.method() != Method::GET {
+        return next.run(request).await;
+    }
+    let Some(cache_control) = app_state
+        .http_cache_rules
+        .cache_control_for(request.uri().path())
+        .map(str::to_string)
+    else {
+        return next.run(request).await;
+    };
+
+    let request_headers = request.headers();
+    let authenticated = request_headers.contains_key(header::AUTHORIZATION);
+    let if_none_match = request_headers.get(header::IF_NONE_MATCH).cloned();
+    let if_modified_since = request_headers.get(header::IF_MODIFIED_SINCE).cloned();
+
+    let response = next.run(request).await;
+    let streaming = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .map_or(false, |value| value.as_bytes().starts_with(b"text/event-stream"));
+    if response.status() != StatusCode::OK || streaming || response.headers().contains_key(header::CONTENT_ENCODING) {
+        return response;
+    }
+
+    let (mut parts, mut body) = response.into_parts();
+    let mut bytes = Vec::new();
+    while let Some(chunk) = body.data().await {
+        match chunk {
+            Ok(chunk) => bytes.extend_from_slice(&chunk),
+            Err(e) => {
+                warn!("Failed to buffer response body for ETag: {}", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to read response body").into_response();
+            }
+        }
+    }
+
+    let headers = &mut parts.headers;
+    if !headers.contains_key(header::ETAG) {
+        if let Ok(value) = HeaderValue::from_str(&http_cache::etag(&bytes)) {
+            headers.insert(header::ETAG, value);
+        }
+    }
+    if !headers.contains_key(header::CACHE_CONTROL) {
+        // 登录用户的响应带有个人状态，不能进入共享缓存
+        let cache_control = if authenticated { http_cache::PRIVATE_CACHE_CONTROL } else { cache_control.as_str() };
+        if let Ok(value) = HeaderValue::from_str(cache_control) {
+            headers.insert(header::CACHE_CONTROL, value);
+        }
+    }
+    headers.append(header::VARY, HeaderValue::from_static("Authorization"));
+
+    let headers = &parts.headers;
+    let header_str = move |name: header::HeaderName| headers.get(name).and_then(|value| value.to_str().ok()).unwrap_or_default();
+    // 同时带有两个条件时只看 If-None-Match
+    let not_modified = match (if_none_match, if_modified_since) {
+        (Some(if_none_match), _) => http_cache::etag_matches(if_none_match.to_str().unwrap_or_default(), header_str(header::ETAG)),
+        (None, Some(since)) => http_cache::not_modified_since(since.to_str().unwrap_or_default(), header_str(header::LAST_MODIFIED)),
+        (None, None) => false,
+    };
+
+    if not_modified {
+        let mut response = StatusCode::NOT_MODIFIED.into_response();
+        for name in [header::ETAG, header::CACHE_CONTROL, header::LAST_MODIFIED, header::VARY] {
+            for value in parts.headers.get_all(&name) {
+                response.headers_mut().append(name.clone(), value.clone());
+            }
+        }
+        return response;
+    }
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, axum::body::boxed(Body::from(bytes)))
+}
+
+/// 语言协商中间件：按 `Accept-Language` 选择错误信息的语言，放入请求扩展，
+/// 处理期间 `i18n::current_locale` 返回该语言。错误响应带有 `Content-Language`
+pub async fn locale_middleware(
+    State(app_state): State<Arc<AppState>>,
+    mut request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let locale = request
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(i18n::negotiate)
+        .or_else(|| Locale::parse(&app_state.config.default_locale))
+        .unwrap_or(Locale::ZhCn);
+    request.extensions_mut().insert(locale);
+
+    let mut response = i18n::scope(locale, next.run(request)).await;
+    if response.status().is_client_error() || response.status().is_server_error() {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_LANGUAGE, HeaderValue::from_static(locale.as_str()));
+    }
+    response
+}
+
+/// 访客国家中间件：解析一次国家代码放入请求扩展，浏览统计从 `VisitorGeo` 读取
+pub async fn geoip_middleware(
+    State(app_state): State<Arc<AppState>>,
+    mut request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let country = app_state.geoip_service.country(request.headers());
+    request.extensions_mut().insert(VisitorGeo { country });
+
+    next.run(request).await
+}
+
 /// 健康检查绕过中间件
 pub async fn health_check_bypass_middleware(
     request: Request<Body>,
@@ -233,42 +644,79 @@ pub async fn health_check_bypass_middleware(
 
 /// 获取客户端 IP 地址
 fn get_client_ip(request: &Request<Body>) -> String {
-    // 尝试从各种头中获取真实 IP
-    let headers = request.headers();
-    
-    // 检查常见的代理头
-    if let Some(forwarded_for) = headers.get("x-forwarded-for") {
-        if let Ok(ip_str) = forwarded_for.to_str() {
-            if let Some(ip) = ip_str.split(',').next() {
-                return ip.trim().to_string();
-            }
-        }
+    request
+        .extensions()
+        .get::<ClientIp>()
+        .map(|ClientIp(ip)| ip.to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// 客户端为可重试的写操作提供的 `Idempotency-Key`，过长或为空时忽略
+pub fn idempotency_key(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("idempotency-key")
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+        .filter(|key| !key.is_empty() && key.len() <= 255)
+        .map(String::from)
+}
+
+/// [`client_ip_middleware`] 解析出的客户端 IP，供只拿到请求头的处理函数使用
+pub fn client_ip(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("x-real-ip")
+        .and_then(|v| v.to_str().ok())
+        .map(|ip| ip.trim().to_string())
+}
+
+/// 直接连接不是可信代理时就是客户端本身；否则从右往左读 `X-Forwarded-For`，
+/// 取第一个不可信的地址。全部是可信代理时取最左边的地址
+fn resolve_client_ip(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[String]) -> IpAddr {
+    if !is_trusted_proxy(peer, trusted_proxies) {
+        return peer;
     }
-    
-    if let Some(real_ip) = headers.get("x-real-ip") {
-        if let Ok(ip_str) = real_ip.to_str() {
-            return ip_str.to_string();
+
+    let hops: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .collect();
+
+    let mut client = peer;
+    for hop in hops.into_iter().rev() {
+        // 无法解析的地址之后的内容都不可信，停在最后一个可信代理上
+        let Ok(ip) = hop.trim().parse::<IpAddr>() else {
+            break;
+        };
+        client = ip;
+        if !is_trusted_proxy(ip, trusted_proxies) {
+            break;
         }
     }
-    
-    if let Some(forwarded) = headers.get("forwarded") {
-        if let Ok(forwarded_str) = forwarded.to_str() {
-            // 解析 Forwarded 头（简化版本）
-            for part in forwarded_str.split(';') {
-                if part.trim().starts_with("for=") {
-                    let ip = part.trim().strip_prefix("for=").unwrap_or("");
-                    return ip.trim_matches('"').to_string();
-                }
-            }
+    client
+}
+
+fn is_trusted_proxy(ip: IpAddr, trusted_proxies: &[String]) -> bool {
+    trusted_proxies.iter().any(|proxy| match proxy.split_once('/') {
+        Some((network, prefix)) => match (network.parse::<IpAddr>(), prefix.parse::<u32>()) {
+            (Ok(network), Ok(prefix)) => in_network(ip, network, prefix),
+            _ => false,
+        },
+        None => proxy.parse::<IpAddr>().is_ok_and(|proxy| proxy == ip),
+    })
+}
+
+fn in_network(ip: IpAddr, network: IpAddr, prefix: u32) -> bool {
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) if prefix <= 32 => {
+            prefix == 0 || (u32::from(ip) ^ u32::from(network)) >> (32 - prefix) == 0
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) if prefix <= 128 => {
+            prefix == 0 || (u128::from(ip) ^ u128::from(network)) >> (128 - prefix) == 0
         }
+        _ => false,
     }
-    
-    // 如果都没有，使用连接信息（在实际部署中可能不可用）
-    request
-        .extensions()
-        .get::<SocketAddr>()
-        .map(|addr| addr.ip().to_string())
-        .unwrap_or_else(|| "unknown".to_string())
 }
 
 /// 检查请求是否为 HTTPS
@@ -300,20 +748,10 @@ fn is_https_request(request: &Request<Body>) -> bool {
 #[derive(Debug, Clone)]
 pub struct RequestId(pub String);
 
-/// 速率限制配置
-#[derive(Debug, Clone)]
-pub struct RateLimitConfig {
-    pub requests_per_minute: u32,
-    pub burst_size: u32,
-}
-
-impl Default for RateLimitConfig {
-    fn default() -> Self {
-        Self {
-            requests_per_minute: 60,
-            burst_size: 10,
-        }
-    }
+/// 访客所在国家（ISO 3166-1 alpha-2），由 `geoip_middleware` 写入
+#[derive(Debug, Clone, Default)]
+pub struct VisitorGeo {
+    pub country: Option<String>,
 }
 
 /// Domain-based routing middleware
@@ -423,3 +861,26 @@ where
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_ip_only_trusts_configured_proxies() {
+        let trusted = vec!["10.0.0.0/8".to_string(), "192.168.1.1".to_string()];
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_static("6.6.6.6, 1.2.3.4, 10.0.0.2"));
+
+        // 不可信的连接伪造的转发头被忽略
+        let direct: IpAddr = "5.5.5.5".parse().unwrap();
+        assert_eq!(resolve_client_ip(direct, &headers, &trusted), direct);
+
+        // 经过可信代理时取最右边的不可信地址，而不是客户端可以伪造的最左边
+        let proxy: IpAddr = "192.168.1.1".parse().unwrap();
+        assert_eq!(
+            resolve_client_ip(proxy, &headers, &trusted),
+            "1.2.3.4".parse::<IpAddr>().unwrap()
+        );
+    }
+}
@@ -3,5 +3,31 @@ pub mod markdown;
 pub mod slug;
 pub mod image;
 pub mod cache;
+pub mod http_cache;
 pub mod validation;
-pub mod serde_helpers;
\ No newline at end of file
+pub mod serde_helpers;
+pub mod sentiment;
+pub mod scoring;
+pub mod anomaly;
+pub mod disqus;
+pub mod post_import;
+pub mod webmention;
+pub mod bot;
+pub mod spam;
+pub mod syndication;
+pub mod ot;
+pub mod prose_lint;
+pub mod figure;
+pub mod embed;
+pub mod cta;
+pub mod user_agent;
+pub mod db_health;
+pub mod experiment;
+pub mod search;
+pub mod suggest;
+pub mod embedding;
+pub mod speech;
+pub mod i18n;
+pub mod theme;
+#[cfg(feature = "rss")]
+pub mod feed;
@@ -0,0 +1,270 @@
+//! 纯文本的操作变换（OT），用于多人同时编辑草稿。
+//!
+//! 操作的格式与 ot.js 相同：正整数表示保留若干字符，负整数表示删除若干字符，字符串表示插入。
+//! 位置按 Unicode 字符（而不是 UTF-16 码元）计算。
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpComponent {
+    Retain(usize),
+    Insert(String),
+    Delete(usize),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum RawComponent {
+    Insert(String),
+    Count(i64),
+}
+
+/// 对整篇文档的一次编辑：依次覆盖原文档的每个字符
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<RawComponent>", into = "Vec<RawComponent>")]
+pub struct TextOperation {
+    ops: Vec<OpComponent>,
+    /// 操作要求的原文档长度
+    base_len: usize,
+    /// 应用后的文档长度
+    target_len: usize,
+}
+
+impl TextOperation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn base_len(&self) -> usize {
+        self.base_len
+    }
+
+    pub fn target_len(&self) -> usize {
+        self.target_len
+    }
+
+    pub fn ops(&self) -> &[OpComponent] {
+        &self.ops
+    }
+
+    /// 只包含保留的操作不会改变文档
+    pub fn is_noop(&self) -> bool {
+        self.ops.iter().all(|op| matches!(op, OpComponent::Retain(_)))
+    }
+
+    pub fn retain(mut self, n: usize) -> Self {
+        if n == 0 {
+            return self;
+        }
+        self.base_len += n;
+        self.target_len += n;
+        match self.ops.last_mut() {
+            Some(OpComponent::Retain(last)) => *last += n,
+            _ => self.ops.push(OpComponent::Retain(n)),
+        }
+        self
+    }
+
+    pub fn insert(mut self, text: &str) -> Self {
+        if text.is_empty() {
+            return self;
+        }
+        self.target_len += text.chars().count();
+        // 插入总是放在相邻的删除之前，保证等价的操作有相同的表示
+        match self.ops.as_mut_slice() {
+            [.., OpComponent::Insert(last)] => last.push_str(text),
+            [.., OpComponent::Insert(before), OpComponent::Delete(_)] => before.push_str(text),
+            [.., OpComponent::Delete(_)] => {
+                let index = self.ops.len() - 1;
+                self.ops.insert(index, OpComponent::Insert(text.to_string()));
+            }
+            _ => self.ops.push(OpComponent::Insert(text.to_string())),
+        }
+        self
+    }
+
+    pub fn delete(mut self, n: usize) -> Self {
+        if n == 0 {
+            return self;
+        }
+        self.base_len += n;
+        match self.ops.last_mut() {
+            Some(OpComponent::Delete(last)) => *last += n,
+            _ => self.ops.push(OpComponent::Delete(n)),
+        }
+        self
+    }
+
+    /// 把操作应用到文档上
+    pub fn apply(&self, document: &str) -> Result<String, String> {
+        let chars: Vec<char> = document.chars().collect();
+        if chars.len() != self.base_len {
+            return Err(format!(
+                "Operation expects a document of {} characters, got {}",
+                self.base_len,
+                chars.len()
+            ));
+        }
+
+        let mut result = String::with_capacity(document.len());
+        let mut position = 0;
+        for op in &self.ops {
+            match op {
+                OpComponent::Retain(n) => {
+                    result.extend(&chars[position..position + n]);
+                    position += n;
+                }
+                OpComponent::Insert(text) => result.push_str(text),
+                OpComponent::Delete(n) => position += n,
+            }
+        }
+        Ok(result)
+    }
+
+    /// 把光标位置映射到操作之后的文档上；恰好在插入点的光标移到插入内容之后
+    pub fn transform_index(&self, index: usize) -> usize {
+        let mut new_index = index;
+        let mut position = 0;
+        for op in &self.ops {
+            if position > index {
+                break;
+            }
+            match op {
+                OpComponent::Retain(n) => position += n,
+                OpComponent::Insert(text) => new_index += text.chars().count(),
+                OpComponent::Delete(n) => {
+                    new_index -= (*n).min(index - position);
+                    position += n;
+                }
+            }
+        }
+        new_index
+    }
+
+    /// 变换两个基于同一文档的并发操作，返回 (a', b')，
+    /// 满足 apply(apply(doc, a), b') == apply(apply(doc, b), a')。
+    /// 同一位置的插入，a 的内容排在前面
+    pub fn transform(a: &TextOperation, b: &TextOperation) -> Result<(TextOperation, TextOperation), String> {
+        if a.base_len != b.base_len {
+            return Err("Concurrent operations must have the same base length".to_string());
+        }
+
+        let mut a_prime = TextOperation::new();
+        let mut b_prime = TextOperation::new();
+        let mut ops_a = a.ops.iter().cloned();
+        let mut ops_b = b.ops.iter().cloned();
+        let mut op_a = ops_a.next();
+        let mut op_b = ops_b.next();
+
+        loop {
+            match (op_a.take(), op_b.take()) {
+                (None, None) => break,
+                (Some(OpComponent::Insert(text)), other) => {
+                    b_prime = b_prime.retain(text.chars().count());
+                    a_prime = a_prime.insert(&text);
+                    op_a = ops_a.next();
+                    op_b = other;
+                }
+                (other, Some(OpComponent::Insert(text))) => {
+                    a_prime = a_prime.retain(text.chars().count());
+                    b_prime = b_prime.insert(&text);
+                    op_a = other;
+                    op_b = ops_b.next();
+                }
+                (None, Some(_)) | (Some(_), None) => {
+                    return Err("Operations do not cover the same document".to_string());
+                }
+                (Some(OpComponent::Retain(x)), Some(OpComponent::Retain(y))) => {
+                    let n = x.min(y);
+                    a_prime = a_prime.retain(n);
+                    b_prime = b_prime.retain(n);
+                    (op_a, op_b) = split_remainders(x, y, OpComponent::Retain, OpComponent::Retain, &mut ops_a, &mut ops_b);
+                }
+                (Some(OpComponent::Delete(x)), Some(OpComponent::Delete(y))) => {
+                    // 双方删除了同一段，变换后都不需要再删除
+                    (op_a, op_b) = split_remainders(x, y, OpComponent::Delete, OpComponent::Delete, &mut ops_a, &mut ops_b);
+                }
+                (Some(OpComponent::Delete(x)), Some(OpComponent::Retain(y))) => {
+                    a_prime = a_prime.delete(x.min(y));
+                    (op_a, op_b) = split_remainders(x, y, OpComponent::Delete, OpComponent::Retain, &mut ops_a, &mut ops_b);
+                }
+                (Some(OpComponent::Retain(x)), Some(OpComponent::Delete(y))) => {
+                    b_prime = b_prime.delete(x.min(y));
+                    (op_a, op_b) = split_remainders(x, y, OpComponent::Retain, OpComponent::Delete, &mut ops_a, &mut ops_b);
+                }
+            }
+        }
+
+        Ok((a_prime, b_prime))
+    }
+}
+
+/// 两个长度为 x、y 的操作消耗掉较短的部分后，返回各自剩下的部分（用完则取下一个）
+fn split_remainders<I: Iterator<Item = OpComponent>>(
+    x: usize,
+    y: usize,
+    make_a: fn(usize) -> OpComponent,
+    make_b: fn(usize) -> OpComponent,
+    ops_a: &mut I,
+    ops_b: &mut I,
+) -> (Option<OpComponent>, Option<OpComponent>) {
+    match x.cmp(&y) {
+        std::cmp::Ordering::Greater => (Some(make_a(x - y)), ops_b.next()),
+        std::cmp::Ordering::Less => (ops_a.next(), Some(make_b(y - x))),
+        std::cmp::Ordering::Equal => (ops_a.next(), ops_b.next()),
+    }
+}
+
+impl TryFrom<Vec<RawComponent>> for TextOperation {
+    type Error = String;
+
+    fn try_from(components: Vec<RawComponent>) -> Result<Self, Self::Error> {
+        let mut operation = TextOperation::new();
+        for component in components {
+            operation = match component {
+                RawComponent::Insert(text) => operation.insert(&text),
+                RawComponent::Count(n) if n > 0 => operation.retain(n as usize),
+                RawComponent::Count(n) if n < 0 => operation.delete(n.unsigned_abs() as usize),
+                RawComponent::Count(_) => return Err("Operation components cannot be 0".to_string()),
+            };
+        }
+        Ok(operation)
+    }
+}
+
+impl From<TextOperation> for Vec<RawComponent> {
+    fn from(operation: TextOperation) -> Self {
+        operation
+            .ops
+            .into_iter()
+            .map(|op| match op {
+                OpComponent::Retain(n) => RawComponent::Count(n as i64),
+                OpComponent::Insert(text) => RawComponent::Insert(text),
+                OpComponent::Delete(n) => RawComponent::Count(-(n as i64)),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concurrent_edits_converge() {
+        let document = "Hello wörld";
+        // a: 在末尾追加 "!"；b: 删除 "Hello " 并在开头插入 "Hi, "
+        let a: TextOperation = serde_json::from_str(r#"[11, "!"]"#).unwrap();
+        let b = TextOperation::new().insert("Hi, ").delete(6).retain(5);
+
+        let (a_prime, b_prime) = TextOperation::transform(&a, &b).unwrap();
+        let via_a = b_prime.apply(&a.apply(document).unwrap()).unwrap();
+        let via_b = a_prime.apply(&b.apply(document).unwrap()).unwrap();
+        assert_eq!(via_a, "Hi, wörld!");
+        assert_eq!(via_a, via_b);
+
+        // 光标在 "wörld" 之前，删除 "Hello " 并插入 "Hi, " 后仍在它之前
+        assert_eq!(b.transform_index(6), 4);
+        assert_eq!(serde_json::to_string(&b).unwrap(), r#"["Hi, ",-6,5]"#);
+    }
+}
@@ -0,0 +1,307 @@
+//! Medium 导出包与 WordPress WXR 文件解析
+
+use crate::error::{AppError, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use quick_xml::{events::Event, Reader};
+use regex::Regex;
+use std::io::{Cursor, Read};
+use std::sync::OnceLock;
+
+/// 从外部平台解析出的一篇文章，正文仍为 HTML
+#[derive(Debug, Clone, Default)]
+pub struct ImportedPost {
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub html: String,
+    pub excerpt: Option<String>,
+    pub tags: Vec<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub is_draft: bool,
+    /// 原文链接，用于避免重复导入
+    pub source_url: Option<String>,
+}
+
+fn parse_error(source: &str, e: impl std::fmt::Display) -> AppError {
+    AppError::BadRequest(format!("Invalid {} export: {}", source, e))
+}
+
+/// 解析 WordPress 导出的 WXR 文件，只保留文章（post），忽略页面和附件
+pub fn parse_wordpress(xml: &str) -> Result<Vec<ImportedPost>> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut posts = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut item: Option<WxrItem> = None;
+    let mut category_domain: Option<String> = None;
+
+    loop {
+        match reader.read_event().map_err(|e| parse_error("WordPress", e))? {
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                if name == "item" {
+                    item = Some(WxrItem::default());
+                } else if name == "category" {
+                    category_domain = e
+                        .attributes()
+                        .flatten()
+                        .find(|attr| attr.key.as_ref() == b"domain")
+                        .and_then(|attr| attr.unescape_value().ok().map(|v| v.into_owned()));
+                }
+                path.push(name);
+            }
+            Event::End(_) => {
+                if path.pop().as_deref() == Some("item") {
+                    if let Some(post) = item.take().and_then(WxrItem::into_post) {
+                        posts.push(post);
+                    }
+                }
+            }
+            Event::Text(e) => {
+                let text = e.unescape().map_err(|e| parse_error("WordPress", e))?;
+                apply_wxr_text(&path, &text, &mut item, category_domain.as_deref());
+            }
+            Event::CData(e) => {
+                let text = String::from_utf8_lossy(&e.into_inner()).into_owned();
+                apply_wxr_text(&path, &text, &mut item, category_domain.as_deref());
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if posts.is_empty() {
+        return Err(parse_error("WordPress", "no posts found"));
+    }
+
+    Ok(posts)
+}
+
+#[derive(Debug, Default)]
+struct WxrItem {
+    title: String,
+    link: String,
+    content: String,
+    excerpt: String,
+    post_date_gmt: String,
+    status: String,
+    post_type: String,
+    tags: Vec<String>,
+}
+
+impl WxrItem {
+    fn into_post(self) -> Option<ImportedPost> {
+        if self.post_type != "post" || self.status == "trash" || self.status == "auto-draft" {
+            return None;
+        }
+
+        let published_at = NaiveDateTime::parse_from_str(self.post_date_gmt.trim(), "%Y-%m-%d %H:%M:%S")
+            .ok()
+            .map(|dt| dt.and_utc());
+
+        Some(ImportedPost {
+            title: self.title.trim().to_string(),
+            subtitle: None,
+            html: wordpress_autop(&self.content),
+            excerpt: Some(self.excerpt.trim().to_string()).filter(|e| !e.is_empty()),
+            tags: self.tags,
+            published_at,
+            is_draft: self.status != "publish",
+            source_url: Some(self.link.trim().to_string()).filter(|l| !l.is_empty()),
+        })
+    }
+}
+
+fn apply_wxr_text(path: &[String], text: &str, item: &mut Option<WxrItem>, category_domain: Option<&str>) {
+    let Some(item) = item.as_mut() else { return };
+    let Some(field) = path.last() else { return };
+    if path.len() < 2 || path[path.len() - 2] != "item" {
+        return;
+    }
+
+    match field.as_str() {
+        "title" => item.title.push_str(text),
+        "link" => item.link.push_str(text),
+        "content:encoded" => item.content.push_str(text),
+        "excerpt:encoded" => item.excerpt.push_str(text),
+        "wp:post_date_gmt" => item.post_date_gmt.push_str(text),
+        "wp:status" => item.status.push_str(text),
+        "wp:post_type" => item.post_type.push_str(text),
+        "category" => {
+            let tag = text.trim();
+            let is_tag = matches!(category_domain, Some("post_tag") | Some("category"));
+            if is_tag && !tag.is_empty() && !tag.eq_ignore_ascii_case("uncategorized") && !item.tags.iter().any(|t| t == tag) {
+                item.tags.push(tag.to_string());
+            }
+        }
+        _ => {}
+    }
+}
+
+/// WordPress 正文不带段落标签（由 wpautop 在渲染时添加），按空行补上 `<p>`
+fn wordpress_autop(content: &str) -> String {
+    let content = content.replace("\r\n", "\n");
+    if content.contains("<p") {
+        return content;
+    }
+
+    content
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|block| !block.is_empty())
+        .map(|block| {
+            if block.starts_with('<') && !block.starts_with("<a ") && !block.starts_with("<img") {
+                block.to_string()
+            } else {
+                format!("<p>{}</p>", block.replace('\n', "<br/>"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 解析 Medium 导出的 zip 包，文章位于 `posts/*.html`，草稿文件名以 `draft_` 开头
+pub fn parse_medium_zip(data: &[u8]) -> Result<Vec<ImportedPost>> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(data)).map_err(|e| parse_error("Medium", e))?;
+    let mut posts = Vec::new();
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(|e| parse_error("Medium", e))?;
+        let name = file.name().to_string();
+        if !name.starts_with("posts/") || !name.ends_with(".html") {
+            continue;
+        }
+
+        let mut html = String::new();
+        file.read_to_string(&mut html).map_err(|e| parse_error("Medium", e))?;
+
+        let is_draft = name.trim_start_matches("posts/").starts_with("draft_");
+        if let Some(post) = parse_medium_post(&html, is_draft) {
+            posts.push(post);
+        }
+    }
+
+    if posts.is_empty() {
+        return Err(parse_error("Medium", "no posts found under posts/"));
+    }
+
+    Ok(posts)
+}
+
+fn medium_patterns() -> &'static [Regex; 6] {
+    static PATTERNS: OnceLock<[Regex; 6]> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            Regex::new(r#"(?s)<h1 class="p-name">(.*?)</h1>"#).unwrap(),
+            Regex::new(r#"(?s)<section data-field="subtitle" class="p-summary">(.*?)</section>"#).unwrap(),
+            Regex::new(r#"(?s)<section data-field="body" class="e-content">(.*)</section>\s*<footer>"#).unwrap(),
+            Regex::new(r#"<time class="dt-published" datetime="([^"]+)""#).unwrap(),
+            Regex::new(r#"<a href="([^"]+)" class="p-canonical""#).unwrap(),
+            // 正文开头重复的标题
+            Regex::new(r#"(?s)<h3[^>]*graf--title[^>]*>.*?</h3>"#).unwrap(),
+        ]
+    })
+}
+
+fn parse_medium_post(html: &str, is_draft: bool) -> Option<ImportedPost> {
+    let [title, subtitle, body, published, canonical, body_title] = medium_patterns();
+    let capture = |re: &Regex| re.captures(html).map(|c| c[1].trim().to_string());
+
+    let title = capture(title).map(|t| strip_tags(&t)).filter(|t| !t.is_empty())?;
+    let body = capture(body)?;
+
+    Some(ImportedPost {
+        title,
+        subtitle: capture(subtitle).map(|s| strip_tags(&s)).filter(|s| !s.is_empty()),
+        html: body_title.replace(&body, "").into_owned(),
+        excerpt: None,
+        // Medium 的导出文件不包含标签
+        tags: Vec::new(),
+        published_at: capture(published)
+            .and_then(|dt| DateTime::parse_from_rfc3339(&dt).ok())
+            .map(|dt| dt.with_timezone(&Utc)),
+        is_draft,
+        source_url: capture(canonical),
+    })
+}
+
+fn strip_tags(html: &str) -> String {
+    static TAGS: OnceLock<Regex> = OnceLock::new();
+    let tags = TAGS.get_or_init(|| Regex::new(r"<[^>]*>").unwrap());
+    tags.replace_all(html, "").replace("&amp;", "&").trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const WXR: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/" xmlns:excerpt="http://wordpress.org/export/1.2/excerpt/" xmlns:wp="http://wordpress.org/export/1.2/">
+<channel>
+  <title>My Blog</title>
+  <item>
+    <title>Hello &amp; Welcome</title>
+    <link>https://old.example.com/hello-welcome/</link>
+    <content:encoded><![CDATA[First paragraph.
+
+Second <strong>paragraph</strong>.]]></content:encoded>
+    <excerpt:encoded><![CDATA[]]></excerpt:encoded>
+    <wp:post_date_gmt>2019-05-01 10:00:00</wp:post_date_gmt>
+    <wp:status>publish</wp:status>
+    <wp:post_type>post</wp:post_type>
+    <category domain="category" nicename="uncategorized"><![CDATA[Uncategorized]]></category>
+    <category domain="post_tag" nicename="rust"><![CDATA[Rust]]></category>
+  </item>
+  <item>
+    <title>About</title>
+    <wp:status>publish</wp:status>
+    <wp:post_type>page</wp:post_type>
+  </item>
+</channel>
+</rss>"#;
+
+    #[test]
+    fn test_parse_wordpress() {
+        let posts = parse_wordpress(WXR).unwrap();
+        assert_eq!(posts.len(), 1);
+
+        let post = &posts[0];
+        assert_eq!(post.title, "Hello & Welcome");
+        assert_eq!(post.html, "<p>First paragraph.</p>\n<p>Second <strong>paragraph</strong>.</p>");
+        assert_eq!(post.tags, vec!["Rust".to_string()]);
+        assert_eq!(post.published_at.unwrap().to_rfc3339(), "2019-05-01T10:00:00+00:00");
+        assert!(!post.is_draft);
+        assert_eq!(post.excerpt, None);
+    }
+
+    #[test]
+    fn test_parse_medium_zip() {
+        let post = r#"<!DOCTYPE html><html><body><article class="h-entry">
+<header><h1 class="p-name">Moving Fast</h1></header>
+<section data-field="subtitle" class="p-summary">Notes on shipping</section>
+<section data-field="body" class="e-content"><section><h3 class="graf graf--h3 graf--title">Moving Fast</h3><p>Body text.</p></section></section>
+<footer><p>By <a class="p-author h-card">Bob</a> on <a href="https://medium.com/p/abc"><time class="dt-published" datetime="2020-02-03T04:05:06.789Z">February 3, 2020</time></a>.</p><p><a href="https://medium.com/@bob/moving-fast-abc" class="p-canonical">Canonical link</a></p></footer></article></body></html>"#;
+
+        let mut buffer = Vec::new();
+        {
+            let mut writer = zip::ZipWriter::new(Cursor::new(&mut buffer));
+            let options = zip::write::FileOptions::default();
+            writer.start_file("posts/2020-02-03_Moving-Fast-abc.html", options).unwrap();
+            writer.write_all(post.as_bytes()).unwrap();
+            writer.start_file("profile/profile.html", options).unwrap();
+            writer.write_all(b"<html></html>").unwrap();
+            writer.finish().unwrap();
+        }
+
+        let posts = parse_medium_zip(&buffer).unwrap();
+        assert_eq!(posts.len(), 1);
+
+        let post = &posts[0];
+        assert_eq!(post.title, "Moving Fast");
+        assert_eq!(post.subtitle.as_deref(), Some("Notes on shipping"));
+        assert_eq!(post.html, "<section><p>Body text.</p></section>");
+        assert_eq!(post.source_url.as_deref(), Some("https://medium.com/@bob/moving-fast-abc"));
+        assert_eq!(post.published_at.unwrap().timestamp(), 1580702706);
+    }
+}
@@ -0,0 +1,184 @@
+//! 按出版物写作规范检查 Markdown 草稿：禁用词、被动语态、标题层级和图片替代文本。
+//!
+//! 逐行检查，跳过围栏代码块；被动语态只是英文的启发式规则，作为警告提示。
+
+use crate::models::style_guide::{LintIssue, LintRule, LintSeverity, StyleGuide};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// be 动词 + 可选的 -ly 副词 + 过去分词（规则变化或常见的不规则变化）
+const PASSIVE_PATTERN: &str = r"(?i)\b(?:am|is|are|was|were|be|been|being)\s+(?:\w+ly\s+)?(?:\w{3,}ed|known|written|given|taken|made|done|seen|shown|built|found|held|kept|left|lost|paid|sent|told|thought|brought|bought|caught|taught|chosen|driven|eaten|forgotten|hidden|broken|spoken|stolen|begun|drawn|grown|thrown|worn|torn)\b";
+
+fn passive_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(PASSIVE_PATTERN).expect("valid passive voice regex"))
+}
+
+fn markdown_image_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"!\[([^\]]*)\]\(([^)\s]*)").expect("valid image regex"))
+}
+
+fn html_image_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"(?i)<img\b[^>]*>").expect("valid img regex"))
+}
+
+fn html_alt_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r#"(?i)\balt\s*=\s*("[^"]*\S[^"]*"|'[^']*\S[^']*')"#).expect("valid alt regex"))
+}
+
+/// 禁用词的匹配规则；以字母或数字开头/结尾的词语按单词边界匹配，避免 "just" 命中 "adjust"
+fn phrase_regex(phrase: &str) -> Option<Regex> {
+    let phrase = phrase.trim();
+    if phrase.is_empty() {
+        return None;
+    }
+    let boundary = |c: Option<char>| if c.map_or(false, |c| c.is_ascii_alphanumeric()) { r"\b" } else { "" };
+    let pattern = format!(
+        "(?i){}{}{}",
+        boundary(phrase.chars().next()),
+        regex::escape(phrase),
+        boundary(phrase.chars().last())
+    );
+    Regex::new(&pattern).ok()
+}
+
+pub fn lint_markdown(markdown: &str, guide: &StyleGuide) -> Vec<LintIssue> {
+    let banned: Vec<(String, Regex)> = guide
+        .banned_phrases
+        .iter()
+        .filter_map(|p| phrase_regex(p).map(|r| (p.trim().to_string(), r)))
+        .collect();
+
+    let mut issues = Vec::new();
+    let mut fence: Option<&str> = None;
+    let mut previous_heading: Option<usize> = None;
+
+    for (index, raw_line) in markdown.lines().enumerate() {
+        let line_number = index + 1;
+        let line = raw_line.trim_start();
+
+        if let Some(marker) = fence {
+            if line.starts_with(marker) {
+                fence = None;
+            }
+            continue;
+        }
+        if line.starts_with("```") || line.starts_with("~~~") {
+            fence = Some(&line[..3]);
+            continue;
+        }
+
+        if let Some(level) = heading_level(line) {
+            if level > guide.max_heading_depth as usize {
+                issues.push(LintIssue {
+                    rule: LintRule::HeadingDepth,
+                    severity: LintSeverity::Error,
+                    message: format!("Headings deeper than level {} are not allowed", guide.max_heading_depth),
+                    line: line_number,
+                    excerpt: line.to_string(),
+                });
+            }
+            if let Some(previous) = previous_heading {
+                if level > previous + 1 {
+                    issues.push(LintIssue {
+                        rule: LintRule::HeadingSkip,
+                        severity: LintSeverity::Warning,
+                        message: format!("Heading jumps from level {} to level {}", previous, level),
+                        line: line_number,
+                        excerpt: line.to_string(),
+                    });
+                }
+            }
+            previous_heading = Some(level);
+        }
+
+        if guide.require_alt_text {
+            let markdown_images = markdown_image_regex()
+                .captures_iter(line)
+                .filter(|c| c[1].trim().is_empty())
+                .map(|c| c[0].to_string());
+            let html_images = html_image_regex()
+                .find_iter(line)
+                .filter(|m| !html_alt_regex().is_match(m.as_str()))
+                .map(|m| m.as_str().to_string());
+            for excerpt in markdown_images.chain(html_images) {
+                issues.push(LintIssue {
+                    rule: LintRule::MissingAltText,
+                    severity: LintSeverity::Error,
+                    message: "Images need alt text describing them".to_string(),
+                    line: line_number,
+                    excerpt,
+                });
+            }
+        }
+
+        for (phrase, regex) in &banned {
+            for m in regex.find_iter(line) {
+                issues.push(LintIssue {
+                    rule: LintRule::BannedPhrase,
+                    severity: LintSeverity::Error,
+                    message: format!("\"{}\" is not allowed by the style guide", phrase),
+                    line: line_number,
+                    excerpt: m.as_str().to_string(),
+                });
+            }
+        }
+
+        if guide.check_passive_voice {
+            for m in passive_regex().find_iter(line) {
+                issues.push(LintIssue {
+                    rule: LintRule::PassiveVoice,
+                    severity: LintSeverity::Warning,
+                    message: "Possible passive voice, consider an active construction".to_string(),
+                    line: line_number,
+                    excerpt: m.as_str().to_string(),
+                });
+            }
+        }
+    }
+
+    issues
+}
+
+/// ATX 标题的级别（`# ` 到 `###### `）
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    match line[level..].chars().next() {
+        None | Some(' ') | Some('\t') => Some(level),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_markdown_rules() {
+        let mut guide = StyleGuide::default_for(None);
+        guide.banned_phrases = vec!["just".to_string(), "显然".to_string()];
+
+        let markdown = "# Title\n\nWe just adjust the config. The bug was quickly fixed.\n\n### Deep\n\n![](a.png) ![chart](b.png)\n\n```\nthe value is cached\n```\n\n#### 显然不行\n";
+        let issues = lint_markdown(markdown, &guide);
+        let rules: Vec<(LintRule, usize)> = issues.iter().map(|i| (i.rule, i.line)).collect();
+
+        assert_eq!(
+            rules,
+            vec![
+                (LintRule::BannedPhrase, 3),
+                (LintRule::PassiveVoice, 3),
+                (LintRule::HeadingSkip, 5),
+                (LintRule::MissingAltText, 7),
+                (LintRule::HeadingDepth, 13),
+                (LintRule::BannedPhrase, 13),
+            ]
+        );
+        assert_eq!(issues[1].excerpt, "was quickly fixed");
+    }
+}
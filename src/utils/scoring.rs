@@ -0,0 +1,201 @@
+//! 互动评分：用加权点赞代替原始点赞数，降低新账号刷赞的影响
+
+/// 账号满多少天后不再降权
+const ACCOUNT_AGE_FULL_WEIGHT_DAYS: f64 = 30.0;
+/// 新注册账号的最低权重
+const NEW_ACCOUNT_MIN_WEIGHT: f64 = 0.2;
+
+/// 单个用户对一篇文章的点赞情况
+#[derive(Debug, Clone)]
+pub struct ClapperSignal {
+    /// 该用户给这篇文章的点赞次数（1-50）
+    pub clap_count: i32,
+    /// 账号注册天数
+    pub account_age_days: i64,
+    /// 归一化信誉值 0.0 - 1.0，未知时为 None
+    pub reputation: Option<f64>,
+    /// 作者给自己文章点赞
+    pub is_author: bool,
+}
+
+/// 同一用户的多次点赞边际递减：n 次点赞计为 sqrt(n)
+pub fn diminishing_claps(clap_count: i32) -> f64 {
+    (clap_count.max(0) as f64).sqrt()
+}
+
+/// 账号年龄系数：新账号从 0.2 线性增长到 30 天后的 1.0
+pub fn account_age_factor(account_age_days: i64) -> f64 {
+    let progress = (account_age_days.max(0) as f64 / ACCOUNT_AGE_FULL_WEIGHT_DAYS).min(1.0);
+    NEW_ACCOUNT_MIN_WEIGHT + (1.0 - NEW_ACCOUNT_MIN_WEIGHT) * progress
+}
+
+/// 信誉系数：0.5 - 1.5，信誉未知时取中性值 1.0
+pub fn reputation_factor(reputation: Option<f64>) -> f64 {
+    reputation.map_or(1.0, |r| 0.5 + r.clamp(0.0, 1.0))
+}
+
+/// 单个用户点赞的有效分值
+pub fn clapper_weight(signal: &ClapperSignal) -> f64 {
+    if signal.is_author {
+        return 0.0;
+    }
+
+    diminishing_claps(signal.clap_count)
+        * account_age_factor(signal.account_age_days)
+        * reputation_factor(signal.reputation)
+}
+
+/// 文章的加权点赞分
+pub fn weighted_clap_score(signals: &[ClapperSignal]) -> f64 {
+    let score: f64 = signals.iter().map(clapper_weight).sum();
+    (score * 100.0).round() / 100.0
+}
+
+/// 计算信誉分所需的原始数据
+#[derive(Debug, Clone, Default)]
+pub struct ReputationInputs {
+    /// 作者所有已发布文章的加权点赞分之和
+    pub weighted_claps_received: f64,
+    pub comments_received: i64,
+    pub followers: i64,
+    /// 被出版物收录并发布的文章数
+    pub accepted_articles: i64,
+    /// 仍在有效期内的处罚次数
+    pub active_strikes: i64,
+    pub account_age_days: i64,
+}
+
+/// 信誉分各部分的得分
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReputationComponents {
+    pub engagement: i32,
+    pub publication_acceptances: i32,
+    pub account_age: i32,
+    pub strike_penalty: i32,
+}
+
+impl ReputationComponents {
+    pub fn total(&self) -> i32 {
+        (self.engagement + self.publication_acceptances + self.account_age - self.strike_penalty).max(0)
+    }
+}
+
+const STRIKE_PENALTY: i32 = 50;
+const ACCEPTANCE_POINTS: i32 = 15;
+const MAX_ACCEPTANCE_POINTS: i32 = 150;
+const MAX_ACCOUNT_AGE_POINTS: i32 = 52;
+/// 归一化信誉时视为满分的分值
+const FULL_REPUTATION_SCORE: f64 = 150.0;
+
+/// 计算信誉分：互动按对数增长防止刷量，收录和账号年龄有上限，每次有效处罚扣 50 分
+pub fn reputation_components(inputs: &ReputationInputs) -> ReputationComponents {
+    let engagement_raw = inputs.weighted_claps_received.max(0.0)
+        + inputs.comments_received.max(0) as f64 * 2.0
+        + inputs.followers.max(0) as f64 * 3.0;
+
+    ReputationComponents {
+        engagement: (10.0 * (1.0 + engagement_raw).ln()).round() as i32,
+        publication_acceptances: (inputs.accepted_articles.max(0) as i32 * ACCEPTANCE_POINTS).min(MAX_ACCEPTANCE_POINTS),
+        // 每满一周 1 分
+        account_age: ((inputs.account_age_days.max(0) / 7) as i32).min(MAX_ACCOUNT_AGE_POINTS),
+        strike_penalty: inputs.active_strikes.max(0) as i32 * STRIKE_PENALTY,
+    }
+}
+
+/// 将信誉分映射到 0.0 - 1.0，供点赞加权使用
+pub fn normalized_reputation(score: i32) -> f64 {
+    (score.max(0) as f64 / FULL_REPUTATION_SCORE).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signal(clap_count: i32, account_age_days: i64) -> ClapperSignal {
+        ClapperSignal {
+            clap_count,
+            account_age_days,
+            reputation: None,
+            is_author: false,
+        }
+    }
+
+    #[test]
+    fn test_diminishing_claps() {
+        assert_eq!(diminishing_claps(1), 1.0);
+        assert_eq!(diminishing_claps(4), 2.0);
+        assert!(diminishing_claps(50) < 8.0);
+        assert_eq!(diminishing_claps(-3), 0.0);
+    }
+
+    #[test]
+    fn test_account_age_factor() {
+        assert_eq!(account_age_factor(0), 0.2);
+        assert!((account_age_factor(15) - 0.6).abs() < 1e-9);
+        assert_eq!(account_age_factor(30), 1.0);
+        assert_eq!(account_age_factor(365), 1.0);
+    }
+
+    #[test]
+    fn test_reputation_factor() {
+        assert_eq!(reputation_factor(None), 1.0);
+        assert_eq!(reputation_factor(Some(0.0)), 0.5);
+        assert_eq!(reputation_factor(Some(2.0)), 1.5);
+    }
+
+    #[test]
+    fn test_many_readers_beat_one_heavy_clapper() {
+        // 一个老用户点 50 次 vs 十个老用户各点 1 次
+        let single = weighted_clap_score(&[signal(50, 400)]);
+        let many = weighted_clap_score(&(0..10).map(|_| signal(1, 400)).collect::<Vec<_>>());
+        assert!(many > single);
+    }
+
+    #[test]
+    fn test_sockpuppets_are_discounted() {
+        // 五个新注册账号各点 50 次，不应超过五个老用户各点 10 次
+        let fresh = weighted_clap_score(&(0..5).map(|_| signal(50, 0)).collect::<Vec<_>>());
+        let established = weighted_clap_score(&(0..5).map(|_| signal(10, 200)).collect::<Vec<_>>());
+        assert!(fresh < established);
+    }
+
+    #[test]
+    fn test_reputation_components() {
+        let fresh = reputation_components(&ReputationInputs::default());
+        assert_eq!(fresh.total(), 0);
+
+        let regular = reputation_components(&ReputationInputs {
+            weighted_claps_received: 40.0,
+            comments_received: 10,
+            followers: 20,
+            accepted_articles: 2,
+            active_strikes: 0,
+            account_age_days: 70,
+        });
+        // ln(1 + 40 + 20 + 60) * 10 ≈ 48
+        assert_eq!(regular.engagement, 48);
+        assert_eq!(regular.publication_acceptances, 30);
+        assert_eq!(regular.account_age, 10);
+        assert_eq!(regular.total(), 88);
+
+        let penalized = reputation_components(&ReputationInputs {
+            active_strikes: 2,
+            ..ReputationInputs::default()
+        });
+        assert_eq!(penalized.total(), 0);
+    }
+
+    #[test]
+    fn test_normalized_reputation() {
+        assert_eq!(normalized_reputation(0), 0.0);
+        assert_eq!(normalized_reputation(75), 0.5);
+        assert_eq!(normalized_reputation(1000), 1.0);
+    }
+
+    #[test]
+    fn test_author_claps_ignored() {
+        let mut own = signal(50, 400);
+        own.is_author = true;
+        assert_eq!(weighted_clap_score(&[own]), 0.0);
+    }
+}
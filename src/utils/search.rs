@@ -0,0 +1,97 @@
+//! 搜索结果的高亮片段。数据库用 `search::highlight` 给整段文本加 `<mark>`，
+//! 这里把它截成命中位置附近的一小段，并转义其余的 HTML。
+
+pub const MARK_OPEN: &str = "<mark>";
+pub const MARK_CLOSE: &str = "</mark>";
+
+/// 命中词前后各保留的字符数
+pub const SNIPPET_RADIUS: usize = 80;
+
+/// 截取第一个命中位置附近的片段；文本中没有 `<mark>` 时返回 `None`
+pub fn crop_highlight(text: &str, radius: usize) -> Option<String> {
+    let first = text.find(MARK_OPEN)?;
+
+    let start = text[..first]
+        .char_indices()
+        .rev()
+        .nth(radius.saturating_sub(1))
+        .map_or(0, |(i, _)| i);
+    let mut end = text[first..]
+        .char_indices()
+        .nth(radius * 2)
+        .map_or(text.len(), |(i, _)| first + i);
+
+    // 不从标记中间截断：未闭合的 <mark> 延伸到闭合处，截到一半的标签直接丢掉
+    if let Some(open) = text[..end].rfind(MARK_OPEN) {
+        if !text[open..end].contains(MARK_CLOSE) {
+            end = text[open..].find(MARK_CLOSE).map_or(text.len(), |i| open + i + MARK_CLOSE.len());
+        }
+    }
+    if let Some(lt) = text[..end].rfind('<') {
+        let tail = &text[lt..end];
+        if MARK_OPEN.starts_with(tail) || MARK_CLOSE.starts_with(tail) {
+            end = lt;
+        }
+    }
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push_str("...");
+    }
+    snippet.push_str(&escape_keeping_marks(&text[start..end]));
+    if end < text.len() {
+        snippet.push_str("...");
+    }
+    Some(snippet.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// 数据库没有返回高亮时（例如模糊匹配），在文本中标记查询词，忽略 ASCII 大小写
+pub fn mark_terms(text: &str, query: &str) -> String {
+    let terms: Vec<&str> = query.split_whitespace().filter(|t| !t.is_empty()).collect();
+    let mut marked = String::with_capacity(text.len());
+    let mut i = 0;
+    'outer: while i < text.len() {
+        for term in &terms {
+            if text.get(i..i + term.len()).map_or(false, |s| s.eq_ignore_ascii_case(term)) {
+                marked.push_str(MARK_OPEN);
+                marked.push_str(&text[i..i + term.len()]);
+                marked.push_str(MARK_CLOSE);
+                i += term.len();
+                continue 'outer;
+            }
+        }
+        let ch = text[i..].chars().next().expect("index is on a char boundary");
+        marked.push(ch);
+        i += ch.len_utf8();
+    }
+    marked
+}
+
+fn escape_keeping_marks(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace("&lt;mark&gt;", MARK_OPEN)
+        .replace("&lt;/mark&gt;", MARK_CLOSE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crop_highlight() {
+        let text = format!("{}前面的<b>内容</b> <mark>Rust</mark> 后面{}", "很长的开头".repeat(40), "的内容".repeat(40));
+        let snippet = crop_highlight(&text, 20).unwrap();
+        assert!(snippet.starts_with("..."));
+        assert!(snippet.ends_with("..."));
+        assert!(snippet.contains("&lt;b&gt;内容&lt;/b&gt; <mark>Rust</mark>"));
+
+        // 截断位置落在后一个标记内部时补全标记
+        let snippet = crop_highlight("<mark>a</mark> bc <mark>def</mark>", 3).unwrap();
+        assert_eq!(snippet.matches(MARK_OPEN).count(), snippet.matches(MARK_CLOSE).count());
+
+        assert_eq!(crop_highlight("no match here", 10), None);
+        assert_eq!(mark_terms("Learning rust, RUST!", "rust"), "Learning <mark>rust</mark>, <mark>RUST</mark>!");
+    }
+}
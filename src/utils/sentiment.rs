@@ -0,0 +1,100 @@
+use crate::error::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum SentimentLabel {
+    Positive,
+    Neutral,
+    Negative,
+}
+
+/// 可插拔的情感分析器，默认使用内置词典实现，部署方可替换为外部模型
+#[async_trait]
+pub trait SentimentAnalyzer: Send + Sync {
+    /// 分析器名称，随统计结果一起返回
+    fn name(&self) -> &str;
+
+    /// 批量分析，返回结果与输入一一对应
+    async fn analyze_batch(&self, texts: &[String]) -> Result<Vec<SentimentLabel>>;
+}
+
+const POSITIVE_WORDS: &[&str] = &[
+    "good", "great", "excellent", "amazing", "awesome", "love", "loved", "helpful", "thanks",
+    "thank", "useful", "clear", "insightful", "brilliant", "nice", "agree", "fantastic", "perfect",
+    "好", "棒", "赞", "感谢", "谢谢", "喜欢", "有用", "清晰", "精彩", "支持", "学习了",
+];
+
+const NEGATIVE_WORDS: &[&str] = &[
+    "bad", "wrong", "terrible", "awful", "hate", "useless", "confusing", "misleading", "disagree",
+    "boring", "poor", "worst", "broken", "incorrect", "spam", "nonsense",
+    "差", "错", "垃圾", "无聊", "误导", "不同意", "看不懂", "失望", "烂",
+];
+
+/// 基于词典计数的轻量分析器，无外部依赖
+#[derive(Debug, Clone, Default)]
+pub struct LexiconSentimentAnalyzer;
+
+impl LexiconSentimentAnalyzer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn analyze(&self, text: &str) -> SentimentLabel {
+        let lower = text.to_lowercase();
+        let words: Vec<&str> = lower
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        // 英文按整词匹配，中文没有分词，按子串匹配
+        let score_of = |lexicon: &[&str]| -> i32 {
+            lexicon
+                .iter()
+                .filter(|term| {
+                    if term.is_ascii() {
+                        words.contains(term)
+                    } else {
+                        lower.contains(*term)
+                    }
+                })
+                .count() as i32
+        };
+
+        let score = score_of(POSITIVE_WORDS) - score_of(NEGATIVE_WORDS);
+        match score {
+            s if s > 0 => SentimentLabel::Positive,
+            s if s < 0 => SentimentLabel::Negative,
+            _ => SentimentLabel::Neutral,
+        }
+    }
+}
+
+#[async_trait]
+impl SentimentAnalyzer for LexiconSentimentAnalyzer {
+    fn name(&self) -> &str {
+        "lexicon"
+    }
+
+    async fn analyze_batch(&self, texts: &[String]) -> Result<Vec<SentimentLabel>> {
+        Ok(texts.iter().map(|t| self.analyze(t)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lexicon_analyzer() {
+        let analyzer = LexiconSentimentAnalyzer::new();
+
+        assert_eq!(analyzer.analyze("Great article, thanks!"), SentimentLabel::Positive);
+        assert_eq!(analyzer.analyze("This is wrong and misleading."), SentimentLabel::Negative);
+        assert_eq!(analyzer.analyze("I read it on the train."), SentimentLabel::Neutral);
+        assert_eq!(analyzer.analyze("写得很清晰，感谢分享"), SentimentLabel::Positive);
+        // "goodness" 不应命中 "good"
+        assert_eq!(analyzer.analyze("goodness me"), SentimentLabel::Neutral);
+    }
+}
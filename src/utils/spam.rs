@@ -0,0 +1,158 @@
+use crate::error::Result;
+use async_trait::async_trait;
+use serde::Serialize;
+use std::sync::OnceLock;
+use regex::Regex;
+
+/// 送去打分的评论及其提交环境
+#[derive(Debug, Clone)]
+pub struct SpamCheck {
+    pub content: String,
+    pub author_id: String,
+    pub author_name: Option<String>,
+    /// 评论所在文章的地址
+    pub permalink: Option<String>,
+    pub user_ip: Option<String>,
+    pub user_agent: Option<String>,
+    /// 出版物额外屏蔽的关键词
+    pub blocked_keywords: Vec<String>,
+}
+
+/// 单个过滤器的打分结果，score 在 0-1 之间
+#[derive(Debug, Clone, Serialize)]
+pub struct SpamSignal {
+    pub filter: String,
+    pub score: f64,
+    pub reasons: Vec<String>,
+}
+
+/// 可插拔的垃圾评论过滤器，评论的最终得分取所有过滤器中的最高分
+#[async_trait]
+pub trait SpamFilter: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn score(&self, check: &SpamCheck) -> Result<SpamSignal>;
+}
+
+/// 常见的垃圾评论用语
+const SPAM_PHRASES: &[&str] = &[
+    "buy now", "cheap", "casino", "viagra", "cialis", "crypto giveaway", "earn money fast",
+    "work from home", "click here", "free followers", "payday loan", "seo services",
+    "whatsapp me", "telegram me", "limited offer",
+    "加微信", "加我微信", "代开发票", "刷单", "兼职日结", "博彩", "私聊",
+];
+/// 链接数超过该值时开始计分
+const LINK_ALLOWANCE: usize = 1;
+
+/// 内置的关键词规则：垃圾用语、出版物屏蔽词、链接数量和重复字符
+#[derive(Debug, Default)]
+pub struct KeywordSpamFilter;
+
+impl KeywordSpamFilter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn evaluate(&self, check: &SpamCheck) -> SpamSignal {
+        let content = check.content.to_lowercase();
+        let mut score: f64 = 0.0;
+        let mut reasons = Vec::new();
+
+        for keyword in &check.blocked_keywords {
+            let keyword = keyword.trim().to_lowercase();
+            if !keyword.is_empty() && content.contains(&keyword) {
+                score = 1.0;
+                reasons.push(format!("blocked keyword \"{}\"", keyword));
+            }
+        }
+
+        let phrases: Vec<&str> = SPAM_PHRASES.iter().copied().filter(|p| content.contains(p)).collect();
+        if !phrases.is_empty() {
+            score += 0.4 * phrases.len() as f64;
+            reasons.push(format!("spam phrases: {}", phrases.join(", ")));
+        }
+
+        let links = count_links(&check.content);
+        if links > LINK_ALLOWANCE {
+            score += 0.2 * (links - LINK_ALLOWANCE) as f64;
+            reasons.push(format!("{} links", links));
+        }
+
+        if has_long_repeat(&check.content) {
+            score += 0.3;
+            reasons.push("repeated characters".to_string());
+        }
+
+        SpamSignal {
+            filter: self.name().to_string(),
+            score: score.min(1.0),
+            reasons,
+        }
+    }
+}
+
+#[async_trait]
+impl SpamFilter for KeywordSpamFilter {
+    fn name(&self) -> &str {
+        "keywords"
+    }
+
+    async fn score(&self, check: &SpamCheck) -> Result<SpamSignal> {
+        Ok(self.evaluate(check))
+    }
+}
+
+fn count_links(text: &str) -> usize {
+    static LINK_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+    let pattern = LINK_PATTERN.get_or_init(|| Regex::new(r"(?i)(https?://|www\.)").unwrap());
+    pattern.find_iter(text).count()
+}
+
+/// 同一个字符连续出现 10 次以上
+fn has_long_repeat(text: &str) -> bool {
+    let mut previous = None;
+    let mut run = 0;
+    for c in text.chars().filter(|c| !c.is_whitespace()) {
+        if Some(c) == previous {
+            run += 1;
+            if run >= 10 {
+                return true;
+            }
+        } else {
+            previous = Some(c);
+            run = 1;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(content: &str, blocked: &[&str]) -> SpamCheck {
+        SpamCheck {
+            content: content.to_string(),
+            author_id: "user_1".to_string(),
+            author_name: None,
+            permalink: None,
+            user_ip: None,
+            user_agent: None,
+            blocked_keywords: blocked.iter().map(|k| k.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_keyword_filter_scores() {
+        let filter = KeywordSpamFilter::new();
+
+        assert_eq!(filter.evaluate(&check("Great write-up, thanks for sharing!", &[])).score, 0.0);
+        assert!(filter.evaluate(&check("Cheap casino bonus, click here", &[])).score >= 0.8);
+        assert_eq!(filter.evaluate(&check("Try our Widget today", &["widget"])).score, 1.0);
+
+        let links = "see https://a.example https://b.example https://c.example";
+        assert!((filter.evaluate(&check(links, &[])).score - 0.4).abs() < 1e-9);
+        assert!(filter.evaluate(&check("woooooooooooooow", &[])).score > 0.0);
+    }
+}
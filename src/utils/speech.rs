@@ -0,0 +1,219 @@
+//! 文章朗读音频：朗读文本的提取、按句分段、内容变化程度的判断和 MP3 时长计算。
+//!
+//! 朗读文本只保留标题和正文中的文字，代码块、图片和 HTML 不朗读。
+//! 每句话保存一个短摘要，文章更新后按变化的句子占比决定是否重新生成，
+//! 改错字之类的小修改不会重新生成音频。
+
+use pulldown_cmark::{Event, Options, Parser, Tag};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+
+/// 朗读文本：标题、副标题和正文，段落之间用空行分隔
+pub fn speech_text(title: &str, subtitle: Option<&str>, markdown: &str) -> String {
+    let mut blocks: Vec<String> = [Some(title), subtitle]
+        .into_iter()
+        .flatten()
+        .map(|part| part.trim().to_string())
+        .filter(|part| !part.is_empty())
+        .collect();
+
+    let mut current = String::new();
+    let mut skip_depth = 0usize;
+    for event in Parser::new_ext(markdown, Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TABLES) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) | Event::Start(Tag::Image(..)) => skip_depth += 1,
+            Event::End(Tag::CodeBlock(_)) | Event::End(Tag::Image(..)) => skip_depth = skip_depth.saturating_sub(1),
+            Event::Text(text) | Event::Code(text) if skip_depth == 0 => current.push_str(&text),
+            Event::SoftBreak | Event::HardBreak if skip_depth == 0 => current.push(' '),
+            Event::End(Tag::Paragraph)
+            | Event::End(Tag::Heading(..))
+            | Event::End(Tag::Item)
+            | Event::End(Tag::TableCell) => {
+                let block = current.split_whitespace().collect::<Vec<_>>().join(" ");
+                if !block.is_empty() {
+                    blocks.push(block);
+                }
+                current.clear();
+            }
+            _ => {}
+        }
+    }
+
+    blocks.join("\n\n")
+}
+
+/// 按句子切分，保留句末标点
+pub fn sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    for (index, ch) in text.char_indices() {
+        if matches!(ch, '.' | '!' | '?' | '。' | '！' | '？' | '\n') {
+            let end = index + ch.len_utf8();
+            let sentence = text[start..end].trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence);
+            }
+            start = end;
+        }
+    }
+    let rest = text[start..].trim();
+    if !rest.is_empty() {
+        sentences.push(rest);
+    }
+    sentences
+}
+
+/// 把文本拼成不超过 `max_chars` 个字符的片段，尽量在句子边界切开
+pub fn chunk_text(text: &str, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(1);
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_chars = 0;
+
+    for sentence in sentences(text) {
+        let length = sentence.chars().count();
+        if current_chars > 0 && current_chars + 1 + length > max_chars {
+            chunks.push(std::mem::take(&mut current));
+            current_chars = 0;
+        }
+        if length > max_chars {
+            // 超长的句子只能硬切
+            let chars: Vec<char> = sentence.chars().collect();
+            for piece in chars.chunks(max_chars) {
+                chunks.push(piece.iter().collect());
+            }
+            continue;
+        }
+        if current_chars > 0 {
+            current.push(' ');
+            current_chars += 1;
+        }
+        current.push_str(sentence);
+        current_chars += length;
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// 每句话的短摘要，用于比较两次内容之间变化了多少
+pub fn sentence_fingerprints(text: &str) -> Vec<String> {
+    sentences(text)
+        .into_iter()
+        .map(|sentence| {
+            let normalized = sentence.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+            hex::encode(&Sha256::digest(normalized.as_bytes())[..8])
+        })
+        .collect()
+}
+
+/// 变化的句子占两边句子总数的比例，0 表示没有变化，1 表示完全不同
+pub fn changed_ratio(previous: &[String], current: &[String]) -> f64 {
+    let total = previous.len() + current.len();
+    if total == 0 {
+        return 0.0;
+    }
+    let previous_set: HashSet<&String> = previous.iter().collect();
+    let current_set: HashSet<&String> = current.iter().collect();
+    let changed = previous.iter().filter(|hash| !current_set.contains(hash)).count()
+        + current.iter().filter(|hash| !previous_set.contains(hash)).count();
+    changed as f64 / total as f64
+}
+
+/// MP3 的时长（秒），逐帧累加 Layer III 帧的采样数。不是有效的 MP3 时返回 None
+pub fn mp3_duration(data: &[u8]) -> Option<f64> {
+    const MPEG1_BITRATES: [u32; 15] = [0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320];
+    const MPEG2_BITRATES: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+    let mut offset = id3v2_size(data);
+    let mut seconds = 0.0;
+    let mut frames = 0usize;
+
+    while offset + 4 <= data.len() {
+        let header = &data[offset..offset + 4];
+        let version = (header[1] >> 3) & 0b11;
+        let layer = (header[1] >> 1) & 0b11;
+        let bitrate_index = (header[2] >> 4) as usize;
+        let sample_rate_index = ((header[2] >> 2) & 0b11) as usize;
+        let padding = ((header[2] >> 1) & 1) as u32;
+
+        let valid = header[0] == 0xFF
+            && header[1] & 0xE0 == 0xE0
+            && version != 1
+            && layer == 1
+            && (1..15).contains(&bitrate_index)
+            && sample_rate_index < 3;
+        if !valid {
+            offset += 1;
+            continue;
+        }
+
+        let (bitrate, sample_rate, samples) = match version {
+            3 => (MPEG1_BITRATES[bitrate_index], [44100, 48000, 32000][sample_rate_index], 1152),
+            2 => (MPEG2_BITRATES[bitrate_index], [22050, 24000, 16000][sample_rate_index], 576),
+            _ => (MPEG2_BITRATES[bitrate_index], [11025, 12000, 8000][sample_rate_index], 576),
+        };
+        let coefficient = if version == 3 { 144 } else { 72 };
+        let frame_length = (coefficient * bitrate * 1000 / sample_rate + padding) as usize;
+
+        seconds += samples as f64 / sample_rate as f64;
+        frames += 1;
+        offset += frame_length.max(4);
+    }
+
+    (frames > 0).then_some(seconds)
+}
+
+/// 文件开头 ID3v2 标签的长度
+fn id3v2_size(data: &[u8]) -> usize {
+    if data.len() < 10 || &data[..3] != b"ID3" {
+        return 0;
+    }
+    let size = data[6..10].iter().fold(0usize, |size, byte| (size << 7) | (*byte & 0x7F) as usize);
+    let footer = if data[5] & 0x10 != 0 { 10 } else { 0 };
+    10 + size + footer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speech_text_skips_code_and_images() {
+        let markdown = "# 开始\n\n第一段，包含 `inline` 代码。\n\n```rust\nfn main() {}\n```\n\n![封面](cover.jpg)\n\n- 列表一\n- 列表二\n";
+        let text = speech_text("标题", None, markdown);
+        assert_eq!(text, "标题\n\n开始\n\n第一段，包含 inline 代码。\n\n列表一\n\n列表二");
+    }
+
+    #[test]
+    fn test_chunk_text_respects_limit() {
+        let text = "First sentence. Second sentence! Third one?";
+        let chunks = chunk_text(text, 20);
+        assert_eq!(chunks, vec!["First sentence.", "Second sentence!", "Third one?"]);
+        assert!(chunk_text(&"a".repeat(45), 20).iter().all(|chunk| chunk.chars().count() <= 20));
+    }
+
+    #[test]
+    fn test_changed_ratio() {
+        let before = sentence_fingerprints("One. Two. Three. Four.");
+        assert_eq!(changed_ratio(&before, &sentence_fingerprints("One.  Two. three. Four.")), 0.0);
+        assert_eq!(changed_ratio(&before, &sentence_fingerprints("One. Two. Three. Five.")), 0.25);
+        assert_eq!(changed_ratio(&before, &sentence_fingerprints("Something else entirely.")), 1.0);
+    }
+
+    #[test]
+    fn test_mp3_duration() {
+        // MPEG-1 Layer III，128 kbps，44.1 kHz，每帧 417 字节
+        let mut frame = vec![0u8; 417];
+        frame[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x00]);
+        let mut data = b"ID3\x04\x00\x00\x00\x00\x00\x05hello".to_vec();
+        for _ in 0..100 {
+            data.extend_from_slice(&frame);
+        }
+
+        let duration = mp3_duration(&data).unwrap();
+        assert!((duration - 100.0 * 1152.0 / 44100.0).abs() < 1e-9);
+        assert!(mp3_duration(b"not audio").is_none());
+    }
+}
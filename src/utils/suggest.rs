@@ -0,0 +1,103 @@
+//! 搜索联想的内存前缀索引。每个条目按完整文本和其中每个词建立键，
+//! 键排序后用二分查找定位前缀范围，命中的条目按热度排序。
+
+use std::collections::HashMap;
+
+/// 热度相同时，完整文本以输入开头的条目优先
+const FULL_MATCH_BOOST: f64 = 1.5;
+
+struct IndexEntry<T> {
+    item: T,
+    popularity: f64,
+}
+
+pub struct PrefixIndex<T> {
+    entries: Vec<IndexEntry<T>>,
+    /// (规范化的键, 条目下标, 是否为完整文本)，按键排序
+    keys: Vec<(String, usize, bool)>,
+}
+
+impl<T> Default for PrefixIndex<T> {
+    fn default() -> Self {
+        Self { entries: Vec::new(), keys: Vec::new() }
+    }
+}
+
+impl<T> PrefixIndex<T> {
+    /// `items` 为 (文本, 条目, 热度)
+    pub fn build(items: impl IntoIterator<Item = (String, T, f64)>) -> Self {
+        let mut index = Self::default();
+        for (text, item, popularity) in items {
+            let id = index.entries.len();
+            let full = normalize(&text);
+            if full.is_empty() {
+                continue;
+            }
+            for word in full.split_whitespace().skip(1) {
+                index.keys.push((word.to_string(), id, false));
+            }
+            index.keys.push((full, id, true));
+            index.entries.push(IndexEntry { item, popularity });
+        }
+        index.keys.sort_by(|a, b| a.0.cmp(&b.0));
+        index
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 以 `prefix` 开头的条目，按热度从高到低
+    pub fn lookup(&self, prefix: &str, limit: usize) -> Vec<&T> {
+        let prefix = normalize(prefix);
+        if prefix.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+
+        let start = self.keys.partition_point(|(key, _, _)| key.as_str() < prefix.as_str());
+        let mut scores: HashMap<usize, f64> = HashMap::new();
+        for (key, id, full) in &self.keys[start..] {
+            if !key.starts_with(&prefix) {
+                break;
+            }
+            let score = self.entries[*id].popularity * if *full { FULL_MATCH_BOOST } else { 1.0 };
+            let best = scores.entry(*id).or_insert(score);
+            *best = best.max(score);
+        }
+
+        let mut matches: Vec<(f64, usize)> = scores.into_iter().map(|(id, score)| (score, id)).collect();
+        matches.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+        matches.into_iter().take(limit).map(|(_, id)| &self.entries[id].item).collect()
+    }
+}
+
+/// 小写并合并空白，联想按这个形式比较
+pub fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// 把文章数、关注数等计数换算成热度。取对数，避免某一类条目总是排在最前
+pub fn popularity(count: f64) -> f64 {
+    (1.0 + count.max(0.0)).ln() + 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_lookup() {
+        let index = PrefixIndex::build(vec![
+            ("Async Rust in Practice".to_string(), "article", popularity(10.0)),
+            ("rust".to_string(), "tag", popularity(500.0)),
+            ("Rustaceans Weekly".to_string(), "publication", popularity(50.0)),
+            ("Python tips".to_string(), "other", popularity(1000.0)),
+        ]);
+
+        assert_eq!(index.lookup("RUS", 10), vec![&"tag", &"publication", &"article"]);
+        assert_eq!(index.lookup("rust", 1), vec![&"tag"]);
+        assert_eq!(index.lookup("async r", 10), vec![&"article"]);
+        assert!(index.lookup("go", 10).is_empty());
+        assert!(index.lookup("  ", 10).is_empty());
+    }
+}
@@ -0,0 +1,203 @@
+//! 同步到外部平台时的内容格式：各平台的标签规则、原文链接和推文串拆分
+
+use crate::utils::markdown::MarkdownProcessor;
+
+/// 单条推文的最大长度（字符）
+pub const TWEET_MAX_CHARS: usize = 280;
+/// 推文串最多的条数（含最后一条原文链接）
+pub const MAX_THREAD_TWEETS: usize = 10;
+/// Twitter 把所有链接都缩短为 t.co，按固定长度计数
+pub const TWEET_URL_CHARS: usize = 23;
+const DEV_TO_MAX_TAGS: usize = 4;
+const HASHNODE_MAX_TAGS: usize = 5;
+
+/// 待同步的文章内容，各平台的格式都由它生成
+#[derive(Debug, Clone)]
+pub struct SyndicationContent {
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub markdown: String,
+    pub excerpt: Option<String>,
+    pub cover_image_url: Option<String>,
+    /// 原文地址，作为 canonical 链接
+    pub canonical_url: String,
+    pub tags: Vec<String>,
+}
+
+impl SyndicationContent {
+    /// 正文末尾附上原文链接
+    pub fn markdown_with_backlink(&self) -> String {
+        format!(
+            "{}\n\n---\n\n*Originally published at [{}]({}).*\n",
+            self.markdown.trim_end(),
+            self.title.replace(['[', ']'], ""),
+            self.canonical_url
+        )
+    }
+
+    /// Dev.to 的标签只允许小写字母和数字，最多 4 个
+    pub fn dev_to_tags(&self) -> Vec<String> {
+        normalized_tags(&self.tags, DEV_TO_MAX_TAGS, "")
+    }
+
+    /// Hashnode 的标签以 slug 标识，最多 5 个，返回 (slug, name)
+    pub fn hashnode_tags(&self) -> Vec<(String, String)> {
+        let mut tags: Vec<(String, String)> = Vec::new();
+        for name in &self.tags {
+            let Some(slug) = normalized_tags(std::slice::from_ref(name), 1, "-").pop() else {
+                continue;
+            };
+            if !tags.iter().any(|(existing, _)| *existing == slug) {
+                tags.push((slug, name.trim().to_string()));
+            }
+            if tags.len() == HASHNODE_MAX_TAGS {
+                break;
+            }
+        }
+        tags
+    }
+
+    /// 拆成推文串：标题和摘要、正文段落，最后一条为原文链接
+    pub fn thread(&self) -> Vec<String> {
+        let mut tweets = Vec::new();
+
+        let lead = match self.excerpt.as_deref().or(self.subtitle.as_deref()) {
+            Some(summary) if !summary.trim().is_empty() => format!("{}\n\n{}", self.title, summary.trim()),
+            _ => self.title.clone(),
+        };
+        tweets.push(truncate(&format!("{} 🧵", lead.trim()), TWEET_MAX_CHARS));
+
+        let paragraphs = MarkdownProcessor::new().extract_prose_paragraphs(&self.markdown);
+        let mut current = String::new();
+        'paragraphs: for paragraph in paragraphs {
+            for piece in split_to_fit(&paragraph, TWEET_MAX_CHARS) {
+                if !current.is_empty() && char_len(&current) + 2 + char_len(&piece) > TWEET_MAX_CHARS {
+                    tweets.push(std::mem::take(&mut current));
+                    if tweets.len() >= MAX_THREAD_TWEETS - 1 {
+                        break 'paragraphs;
+                    }
+                }
+                if !current.is_empty() {
+                    current.push_str("\n\n");
+                }
+                current.push_str(&piece);
+            }
+        }
+        if !current.is_empty() && tweets.len() < MAX_THREAD_TWEETS - 1 {
+            tweets.push(current);
+        }
+
+        tweets.push(format!("Read the full post: {}", self.canonical_url));
+        tweets
+    }
+}
+
+/// 推广帖子的正文，链接放在最后
+pub fn share_text(text: &str, link: Option<&str>) -> String {
+    match link {
+        Some(link) => format!("{}\n\n{}", text.trim_end(), link),
+        None => text.trim_end().to_string(),
+    }
+}
+
+/// 推广帖子按 Twitter 规则计算的长度
+pub fn share_tweet_length(text: &str, has_link: bool) -> usize {
+    let text_len = char_len(text.trim_end());
+    if has_link {
+        text_len + 2 + TWEET_URL_CHARS
+    } else {
+        text_len
+    }
+}
+
+fn char_len(text: &str) -> usize {
+    text.chars().count()
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if char_len(text) <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// 过长的段落按句子（必要时按单词）拆开，每段不超过 `max_chars`
+fn split_to_fit(paragraph: &str, max_chars: usize) -> Vec<String> {
+    if char_len(paragraph) <= max_chars {
+        return vec![paragraph.to_string()];
+    }
+
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for word in paragraph.split_whitespace() {
+        let word = truncate(word, max_chars);
+        if !current.is_empty() && char_len(&current) + 1 + char_len(&word) > max_chars {
+            pieces.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(&word);
+        // 句子结束且已经够长时在这里断开，读起来更自然
+        if char_len(&current) > max_chars / 2 && current.ends_with(['.', '!', '?', '。', '！', '？']) {
+            pieces.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
+
+fn normalized_tags(tags: &[String], max: usize, separator: &str) -> Vec<String> {
+    let mut normalized = Vec::new();
+    for tag in tags {
+        let words: Vec<String> = tag
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(|w| w.to_lowercase())
+            .collect();
+        let tag = words.join(separator);
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+        if normalized.len() == max {
+            break;
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_fits_tweet_limits() {
+        let paragraph = "Rust makes systems programming approachable. ".repeat(20);
+        let content = SyndicationContent {
+            title: "Why Rust".to_string(),
+            subtitle: None,
+            markdown: format!("{}\n\n{}\n\n# Heading\n\nShort closing note.", paragraph, paragraph),
+            excerpt: Some("A short tour.".to_string()),
+            cover_image_url: None,
+            canonical_url: "https://blog.example.com/articles/why-rust".to_string(),
+            tags: vec!["Rust Lang".to_string(), "web-dev".to_string()],
+        };
+
+        let thread = content.thread();
+        assert!(thread.len() > 2 && thread.len() <= MAX_THREAD_TWEETS);
+        assert!(thread.iter().all(|t| t.chars().count() <= TWEET_MAX_CHARS));
+        assert!(thread[0].starts_with("Why Rust"));
+        assert!(thread.last().unwrap().ends_with(&content.canonical_url));
+
+        assert_eq!(content.dev_to_tags(), vec!["rustlang", "webdev"]);
+        assert_eq!(content.hashnode_tags()[0], ("rust-lang".to_string(), "Rust Lang".to_string()));
+
+        let text = "New post is up";
+        assert_eq!(share_text(text, Some(&content.canonical_url)), format!("New post is up\n\n{}", content.canonical_url));
+        assert_eq!(share_tweet_length(text, true), text.len() + 2 + TWEET_URL_CHARS);
+    }
+}
@@ -0,0 +1,104 @@
+//! 出版物主题的校验：颜色、字体名和自定义 CSS。
+//!
+//! 自定义 CSS 会原样插入自定义域名的页面，所以按声明逐条检查，去掉能执行脚本、
+//! 加载外部样式或跳出 `<style>` 的部分，其余保持不变。
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// `#rgb`、`#rrggbb` 或 `#rrggbbaa`
+pub fn is_hex_color(value: &str) -> bool {
+    static HEX: OnceLock<Regex> = OnceLock::new();
+    HEX.get_or_init(|| Regex::new(r"^#([0-9a-fA-F]{3}|[0-9a-fA-F]{6}|[0-9a-fA-F]{8})$").unwrap())
+        .is_match(value)
+}
+
+/// 字体名只允许字母、数字、空格和连字符，避免借字体名注入 CSS
+pub fn is_font_family(value: &str) -> bool {
+    let value = value.trim();
+    !value.is_empty()
+        && value.len() <= 64
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-')
+}
+
+/// 去掉注释和不安全的声明、规则，返回清理后的 CSS
+pub fn sanitize_css(css: &str) -> String {
+    static COMMENT: OnceLock<Regex> = OnceLock::new();
+    let css = COMMENT.get_or_init(|| Regex::new(r"(?s)/\*.*?\*/").unwrap()).replace_all(css, "");
+
+    let mut sanitized = String::with_capacity(css.len());
+    let mut chunk = String::new();
+    for ch in css.chars() {
+        match ch {
+            ';' | '{' | '}' => {
+                let safe = is_safe_chunk(&chunk);
+                if safe {
+                    sanitized.push_str(&chunk);
+                }
+                // 被去掉的声明连同分号一起去掉，花括号保留以免打乱其余规则的结构
+                if safe || ch != ';' {
+                    sanitized.push(ch);
+                }
+                chunk.clear();
+            }
+            '<' => {}
+            _ => chunk.push(ch),
+        }
+    }
+    if is_safe_chunk(&chunk) {
+        sanitized.push_str(&chunk);
+    }
+    sanitized.trim().to_string()
+}
+
+fn is_safe_chunk(chunk: &str) -> bool {
+    // 反斜杠转义可以拼出 expression 等关键字，直接拒绝
+    if chunk.contains('\\') {
+        return false;
+    }
+    let compact: String = chunk.to_lowercase().split_whitespace().collect();
+    if compact.starts_with("@import") {
+        return false;
+    }
+    const FORBIDDEN: [&str; 5] = ["expression(", "javascript:", "vbscript:", "behavior:", "-moz-binding"];
+    if FORBIDDEN.iter().any(|pattern| compact.contains(pattern)) {
+        return false;
+    }
+
+    static URL: OnceLock<Regex> = OnceLock::new();
+    URL.get_or_init(|| Regex::new(r#"(?i)url\(\s*['"]?\s*([^'")\s]*)"#).unwrap())
+        .captures_iter(chunk)
+        .all(|c| {
+            let target = c[1].to_lowercase();
+            target.starts_with("https://")
+                || target.starts_with("data:image/png")
+                || target.starts_with("data:image/jpeg")
+                || target.starts_with("data:image/webp")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_css() {
+        let css = r#"/* brand */ @import url("https://evil.test/x.css");
+.header { color: #123456; background: url(https://cdn.example.com/bg.png); }
+.bad { background: url(javascript:alert(1)); width: expression(alert(1)); margin: 0 }
+.esc { b\65havior: url(x.htc) }
+</style><script>alert(1)</script>"#;
+        let sanitized = sanitize_css(css);
+
+        assert!(sanitized.starts_with(".header { color: #123456; background: url(https://cdn.example.com/bg.png); }"));
+        assert!(sanitized.contains(".bad { margin: 0 }"));
+        assert!(!sanitized.contains("@import"));
+        assert!(!sanitized.contains("javascript"));
+        assert!(!sanitized.contains("expression"));
+        assert!(!sanitized.contains('\\'));
+        assert!(!sanitized.contains('<'));
+
+        assert!(is_hex_color("#1a2B3c") && is_hex_color("#fff") && !is_hex_color("red"));
+        assert!(is_font_family("IBM Plex Sans") && !is_font_family("x; } body { color: red"));
+    }
+}
@@ -0,0 +1,94 @@
+//! 从 User-Agent 推断读者的设备类型、浏览器和操作系统，用于受众分析。
+//!
+//! 只区分常见的几类，识别不了的记为 `other`；机器流量在计数前已经由 `utils::bot` 排除。
+
+/// 设备、浏览器和操作系统，取值都是固定的小写名称
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientInfo {
+    /// desktop、mobile、tablet
+    pub device_type: &'static str,
+    pub browser: &'static str,
+    pub os: &'static str,
+}
+
+pub fn parse_user_agent(user_agent: &str) -> ClientInfo {
+    let ua = user_agent.to_lowercase();
+    ClientInfo {
+        device_type: device_type(&ua),
+        browser: browser(&ua),
+        os: os(&ua),
+    }
+}
+
+fn device_type(ua: &str) -> &'static str {
+    // iPadOS 默认使用桌面版 UA，只能识别明确标注的 iPad
+    if ua.contains("ipad") || ua.contains("tablet") || (ua.contains("android") && !ua.contains("mobile")) {
+        "tablet"
+    } else if ua.contains("mobi") || ua.contains("iphone") || ua.contains("ipod") || ua.contains("windows phone") {
+        "mobile"
+    } else {
+        "desktop"
+    }
+}
+
+/// 顺序很重要：Edge、Opera、三星浏览器的 UA 同时包含 chrome，Chrome 的 UA 同时包含 safari
+fn browser(ua: &str) -> &'static str {
+    if ua.contains("edg/") || ua.contains("edga/") || ua.contains("edgios/") {
+        "edge"
+    } else if ua.contains("opr/") || ua.contains("opera") {
+        "opera"
+    } else if ua.contains("samsungbrowser/") {
+        "samsung"
+    } else if ua.contains("firefox/") || ua.contains("fxios/") {
+        "firefox"
+    } else if ua.contains("chrome/") || ua.contains("crios/") || ua.contains("chromium/") {
+        "chrome"
+    } else if ua.contains("safari/") {
+        "safari"
+    } else {
+        "other"
+    }
+}
+
+fn os(ua: &str) -> &'static str {
+    if ua.contains("windows") {
+        "windows"
+    } else if ua.contains("iphone") || ua.contains("ipad") || ua.contains("ipod") {
+        "ios"
+    } else if ua.contains("android") {
+        "android"
+    } else if ua.contains("mac os x") || ua.contains("macintosh") {
+        "macos"
+    } else if ua.contains("cros") {
+        "chromeos"
+    } else if ua.contains("linux") {
+        "linux"
+    } else {
+        "other"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_user_agent() {
+        let iphone = parse_user_agent(
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_4 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1",
+        );
+        assert_eq!(iphone, ClientInfo { device_type: "mobile", browser: "safari", os: "ios" });
+
+        let edge = parse_user_agent(
+            "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36 Edg/124.0.2478.51",
+        );
+        assert_eq!(edge, ClientInfo { device_type: "desktop", browser: "edge", os: "windows" });
+
+        let android_tablet = parse_user_agent(
+            "Mozilla/5.0 (Linux; Android 14; SM-X710) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36",
+        );
+        assert_eq!(android_tablet, ClientInfo { device_type: "tablet", browser: "chrome", os: "android" });
+
+        assert_eq!(parse_user_agent("").browser, "other");
+    }
+}
@@ -52,6 +52,17 @@ fn is_disposable_email_domain(email: &str) -> bool {
     pattern.is_match(&email.to_lowercase())
 }
 
+/// 检查文本中是否包含链接（URL、www 域名或 Markdown 链接）
+pub fn contains_link(text: &str) -> bool {
+    static LINK_PATTERN: OnceLock<Regex> = OnceLock::new();
+
+    let pattern = LINK_PATTERN.get_or_init(|| {
+        Regex::new(r"(?i)(https?://|www\.|\[[^\]]*\]\([^)]+\))").unwrap()
+    });
+
+    pattern.is_match(text)
+}
+
 /// 验证用户名格式（用于博客系统）
 pub fn validate_username(username: &str) -> Result<()> {
     if username.trim().is_empty() {
@@ -141,6 +152,14 @@ mod tests {
         assert!(validate_username(&"a".repeat(31)).is_err());
     }
 
+    #[test]
+    fn test_contains_link() {
+        assert!(contains_link("see https://example.com"));
+        assert!(contains_link("visit WWW.example.com"));
+        assert!(contains_link("[click](/somewhere)"));
+        assert!(!contains_link("no links here, just text."));
+    }
+
     #[test]
     fn test_validate_display_name() {
         // 有效显示名称
@@ -0,0 +1,316 @@
+//! Webmention / Pingback 辅助函数：链接提取、端点发现和 XML-RPC 报文
+
+use crate::error::{AppError, Result};
+use quick_xml::{escape::escape, events::Event, Reader};
+use regex::Regex;
+use std::net::IpAddr;
+use std::sync::OnceLock;
+use url::Url;
+
+/// 摘要的最大长度（字符）
+const EXCERPT_MAX_CHARS: usize = 280;
+
+fn anchor_pattern() -> &'static Regex {
+    static ANCHOR: OnceLock<Regex> = OnceLock::new();
+    ANCHOR.get_or_init(|| Regex::new(r#"(?is)<a\s[^>]*?href\s*=\s*["']([^"']*)["']"#).unwrap())
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let pattern = Regex::new(&format!(r#"(?i)\s{}\s*=\s*["']([^"']*)["']"#, name)).ok()?;
+    pattern.captures(tag).map(|c| c[1].replace("&amp;", "&"))
+}
+
+/// 比较时忽略片段和末尾的斜杠
+fn normalize(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url.as_str().trim_end_matches('/').to_string()
+}
+
+/// HTML 中所有指向 http(s) 地址的链接，相对地址按 `base` 解析，去重后保持原顺序
+pub fn extract_links(html: &str, base: &Url) -> Vec<Url> {
+    let mut seen = Vec::new();
+    let mut links = Vec::new();
+
+    for captures in anchor_pattern().captures_iter(html) {
+        let Ok(link) = base.join(&captures[1].replace("&amp;", "&")) else {
+            continue;
+        };
+        if !matches!(link.scheme(), "http" | "https") {
+            continue;
+        }
+        let key = normalize(&link);
+        if !seen.contains(&key) {
+            seen.push(key);
+            links.push(link);
+        }
+    }
+
+    links
+}
+
+/// 来源页面是否链接到了目标地址
+pub fn links_to(html: &str, base: &Url, target: &Url) -> bool {
+    let target = normalize(target);
+    extract_links(html, base).iter().any(|link| normalize(link) == target)
+}
+
+/// 按 Link 响应头、`<link>`、`<a>` 的顺序发现 `rel` 对应的端点（webmention 或 pingback）
+pub fn discover_endpoint(link_headers: &[String], html: &str, base: &Url, rel: &str) -> Option<Url> {
+    for header in link_headers {
+        for value in header.split(',') {
+            let Some((target, params)) = value.trim().split_once('>') else {
+                continue;
+            };
+            let has_rel = params.split(';').any(|param| {
+                param
+                    .trim()
+                    .strip_prefix("rel=")
+                    .map(|rels| rels.trim_matches('"').split_whitespace().any(|r| r.eq_ignore_ascii_case(rel)))
+                    .unwrap_or(false)
+            });
+            if has_rel {
+                if let Ok(endpoint) = base.join(target.trim().trim_start_matches('<')) {
+                    return Some(endpoint);
+                }
+            }
+        }
+    }
+
+    static TAGS: OnceLock<Regex> = OnceLock::new();
+    let tags = TAGS.get_or_init(|| Regex::new(r"(?is)<(?:link|a)\s[^>]*>").unwrap());
+    tags.find_iter(html).find_map(|tag| {
+        let tag = tag.as_str();
+        let rels = attribute(tag, "rel")?;
+        if !rels.split_whitespace().any(|r| r.eq_ignore_ascii_case(rel)) {
+            return None;
+        }
+        // 空的 href 表示页面本身
+        base.join(&attribute(tag, "href")?).ok()
+    })
+}
+
+/// 页面标题，优先使用 h-entry 的 p-name
+pub fn page_title(html: &str) -> Option<String> {
+    static NAME: OnceLock<Regex> = OnceLock::new();
+    static TITLE: OnceLock<Regex> = OnceLock::new();
+    let name = NAME.get_or_init(|| Regex::new(r#"(?is)<[a-z0-9]+\s[^>]*class\s*=\s*["'][^"']*\bp-name\b[^"']*["'][^>]*>(.*?)</"#).unwrap());
+    let title = TITLE.get_or_init(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap());
+
+    name.captures(html)
+        .or_else(|| title.captures(html))
+        .map(|c| html_to_text(&c[1]))
+        .filter(|t| !t.is_empty())
+}
+
+/// 页面作者，取 h-card 的 p-author 或 `<meta name="author">`
+pub fn page_author(html: &str) -> Option<String> {
+    static AUTHOR: OnceLock<Regex> = OnceLock::new();
+    static META: OnceLock<Regex> = OnceLock::new();
+    let author = AUTHOR.get_or_init(|| Regex::new(r#"(?is)<[a-z0-9]+\s[^>]*class\s*=\s*["'][^"']*\bp-author\b[^"']*["'][^>]*>(.*?)</"#).unwrap());
+    let meta = META.get_or_init(|| Regex::new(r#"(?is)<meta\s[^>]*name\s*=\s*["']author["'][^>]*>"#).unwrap());
+
+    author
+        .captures(html)
+        .map(|c| html_to_text(&c[1]))
+        .or_else(|| meta.find(html).and_then(|tag| attribute(tag.as_str(), "content")))
+        .filter(|a| !a.is_empty())
+}
+
+/// 来源页面中目标链接附近的一段文本
+pub fn excerpt_around(html: &str, base: &Url, target: &Url) -> Option<String> {
+    let target = normalize(target);
+    let position = anchor_pattern().captures_iter(html).find_map(|c| {
+        let link = base.join(&c[1].replace("&amp;", "&")).ok()?;
+        (normalize(&link) == target).then(|| c.get(0).unwrap().start())
+    })?;
+
+    let start = html[..position].rfind("<p").unwrap_or(position);
+    let end = html[position..].find("</p>").map(|i| position + i).unwrap_or(html.len());
+    let text = html_to_text(&html[start..end]);
+    if text.is_empty() {
+        return None;
+    }
+
+    Some(match text.char_indices().nth(EXCERPT_MAX_CHARS) {
+        Some((index, _)) => format!("{}…", &text[..index]),
+        None => text,
+    })
+}
+
+fn html_to_text(html: &str) -> String {
+    static TAGS: OnceLock<Regex> = OnceLock::new();
+    static SPACES: OnceLock<Regex> = OnceLock::new();
+    let tags = TAGS.get_or_init(|| Regex::new(r"<[^>]*>?").unwrap());
+    let spaces = SPACES.get_or_init(|| Regex::new(r"\s+").unwrap());
+
+    let text = tags.replace_all(html, " ");
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    spaces.replace_all(text.trim(), " ").to_string()
+}
+
+/// 只允许请求公网地址，避免借助来源地址访问内网服务
+pub fn is_public_url(url: &Url) -> bool {
+    if !matches!(url.scheme(), "http" | "https") {
+        return false;
+    }
+
+    match url.host_str() {
+        None => false,
+        Some(host) if host.eq_ignore_ascii_case("localhost") || host.ends_with(".localhost") || host.ends_with(".local") => false,
+        Some(host) => match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+            Ok(IpAddr::V4(ip)) => !(ip.is_private() || ip.is_loopback() || ip.is_link_local() || ip.is_unspecified()),
+            Ok(IpAddr::V6(ip)) => !(ip.is_loopback() || ip.is_unspecified() || (ip.segments()[0] & 0xfe00) == 0xfc00),
+            Err(_) => true,
+        },
+    }
+}
+
+fn xml_error(e: impl std::fmt::Display) -> AppError {
+    AppError::BadRequest(format!("Invalid XML-RPC request: {}", e))
+}
+
+/// 解析 `pingback.ping` 请求，返回 (source, target)
+pub fn parse_pingback_request(xml: &str) -> Result<(String, String)> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut path: Vec<String> = Vec::new();
+    let mut method = None;
+    let mut params = Vec::new();
+
+    loop {
+        match reader.read_event().map_err(xml_error)? {
+            Event::Start(element) => {
+                path.push(String::from_utf8_lossy(element.name().as_ref()).into_owned());
+            }
+            Event::End(_) => {
+                path.pop();
+            }
+            Event::Text(text) => {
+                let text = text.unescape().map_err(xml_error)?.into_owned();
+                match path.last().map(String::as_str) {
+                    Some("methodName") => method = Some(text),
+                    // 未标注类型的 <value> 默认为字符串
+                    Some("string") | Some("value") if path.iter().any(|p| p == "param") => params.push(text),
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if method.as_deref() != Some("pingback.ping") {
+        return Err(AppError::BadRequest("Unsupported XML-RPC method".to_string()));
+    }
+
+    let mut params = params.into_iter();
+    match (params.next(), params.next()) {
+        (Some(source), Some(target)) => Ok((source, target)),
+        _ => Err(AppError::BadRequest("pingback.ping requires source and target".to_string())),
+    }
+}
+
+/// 发送 Pingback 时的 XML-RPC 请求体
+pub fn pingback_request(source: &str, target: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\"?>\n<methodCall><methodName>pingback.ping</methodName><params>\
+         <param><value><string>{}</string></value></param>\
+         <param><value><string>{}</string></value></param>\
+         </params></methodCall>",
+        escape(source),
+        escape(target)
+    )
+}
+
+pub fn pingback_response(message: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\"?>\n<methodResponse><params><param><value><string>{}</string></value></param></params></methodResponse>",
+        escape(message)
+    )
+}
+
+/// Pingback 规范定义的错误码（如 16 来源不存在、17 来源没有链接、32 目标不存在）
+pub fn pingback_fault(code: i32, message: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\"?>\n<methodResponse><fault><value><struct>\
+         <member><name>faultCode</name><value><int>{}</int></value></member>\
+         <member><name>faultString</name><value><string>{}</string></value></member>\
+         </struct></value></fault></methodResponse>",
+        code,
+        escape(message)
+    )
+}
+
+/// XML-RPC 响应是否为 fault
+pub fn is_pingback_fault(xml: &str) -> bool {
+    xml.contains("<fault>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover_endpoint() {
+        let base = Url::parse("https://example.com/posts/hello").unwrap();
+
+        let headers = vec![r#"<https://example.com/feed>; rel="alternate", </webmention?x=1>; rel="webmention""#.to_string()];
+        assert_eq!(
+            discover_endpoint(&headers, "", &base, "webmention").unwrap().as_str(),
+            "https://example.com/webmention?x=1"
+        );
+
+        let html = r#"<head><link href="https://hooks.example.net/wm" rel="me webmention"><link rel="pingback" href="/xmlrpc.php"></head>"#;
+        assert_eq!(
+            discover_endpoint(&[], html, &base, "webmention").unwrap().as_str(),
+            "https://hooks.example.net/wm"
+        );
+        assert_eq!(
+            discover_endpoint(&[], html, &base, "pingback").unwrap().as_str(),
+            "https://example.com/xmlrpc.php"
+        );
+        assert!(discover_endpoint(&[], "<a href=\"/x\">x</a>", &base, "webmention").is_none());
+    }
+
+    #[test]
+    fn test_links_and_excerpt() {
+        let base = Url::parse("https://reply.example.org/notes/1").unwrap();
+        let target = Url::parse("https://blog.example.com/articles/hello").unwrap();
+        let html = r#"<title>A reply</title><p>Nice read: <a href="https://blog.example.com/articles/hello/#top">hello</a> &amp; more</p>
+            <p><a href="/about">about</a> <a href="mailto:me@example.org">mail</a></p>"#;
+
+        assert!(links_to(html, &base, &target));
+        assert_eq!(extract_links(html, &base).len(), 2);
+        assert_eq!(page_title(html).as_deref(), Some("A reply"));
+        assert_eq!(excerpt_around(html, &base, &target).as_deref(), Some("Nice read: hello & more"));
+        assert!(!links_to("<p>no links</p>", &base, &target));
+    }
+
+    #[test]
+    fn test_pingback_round_trip() {
+        let request = pingback_request("https://a.example/post?x=1&y=2", "https://b.example/articles/hi");
+        let (source, target) = parse_pingback_request(&request).unwrap();
+        assert_eq!(source, "https://a.example/post?x=1&y=2");
+        assert_eq!(target, "https://b.example/articles/hi");
+
+        assert!(is_pingback_fault(&pingback_fault(17, "no link")));
+        assert!(!is_pingback_fault(&pingback_response("ok")));
+    }
+
+    #[test]
+    fn test_is_public_url() {
+        assert!(is_public_url(&Url::parse("https://example.com/a").unwrap()));
+        assert!(!is_public_url(&Url::parse("http://127.0.0.1:8000/").unwrap()));
+        assert!(!is_public_url(&Url::parse("http://10.1.2.3/").unwrap()));
+        assert!(!is_public_url(&Url::parse("http://localhost/").unwrap()));
+        assert!(!is_public_url(&Url::parse("ftp://example.com/").unwrap()));
+    }
+}